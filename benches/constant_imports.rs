@@ -0,0 +1,69 @@
+//! Imported constant folding benchmarks
+//!
+//! Reads nested frozen constants from a config module in a hot loop, as
+//! manifest generators read `limits.cpu.default` for every document. With
+//! `fold_constant_imports` the reads compile to constant loads; without it
+//! each one resolves the import binding and every property on the way.
+//!
+//! Run with: cargo bench --bench constant_imports
+
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use tsrun::{Interpreter, InterpreterConfig, ModulePath, StepResult};
+
+const CONFIG: &str = r#"
+export const limits = Object.freeze({
+    cpu: Object.freeze({ default: 250, max: 4000 }),
+    memory: Object.freeze({ default: 512, max: 8192 }),
+});
+export const replicas = 3;
+"#;
+
+/// Entry module building `documents` manifests from the config constants
+fn manifest_script(documents: usize) -> String {
+    format!(
+        r#"
+        import {{ limits, replicas }} from "./config.ts";
+        let total = 0;
+        for (let i = 0; i < {documents}; i++) {{
+            const cpu = Math.min(limits.cpu.default * replicas, limits.cpu.max);
+            const memory = Math.min(limits.memory.default * replicas, limits.memory.max);
+            total += cpu + memory;
+        }}
+        total
+        "#
+    )
+}
+
+fn run_script(source: &str, fold: bool) {
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        fold_constant_imports: fold,
+        ..Default::default()
+    });
+    let prepared = interp.prepare_with_modules(
+        source,
+        Some(ModulePath::new("/app/main.ts")),
+        [(ModulePath::new("/app/config.ts"), CONFIG.to_string())],
+    );
+    if prepared.is_err() {
+        return;
+    }
+    while let Ok(StepResult::Continue) = black_box(interp.step()) {}
+}
+
+fn bench_constant_imports(c: &mut Criterion) {
+    let mut group = c.benchmark_group("constant_imports");
+    group.sample_size(10);
+
+    let documents = 20_000;
+    let source = manifest_script(documents);
+    for (name, fold) in [("folded", true), ("unfolded", false)] {
+        group.bench_with_input(BenchmarkId::new(name, documents), &source, |b, s| {
+            b.iter(|| run_script(black_box(s), fold))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_constant_imports);
+criterion_main!(benches);
@@ -0,0 +1,62 @@
+//! Property enumeration benchmarks
+//!
+//! Stringifies and spreads 10k objects of the same shape several times over;
+//! once an object has been enumerated a few times its own key list is cached,
+//! so later passes skip collecting and ordering keys. Automatic GC is off so
+//! tracing the 10k live documents doesn't drown out the enumeration cost.
+//!
+//! Run with: cargo bench --bench enumeration
+
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use tsrun::{Interpreter, StepResult};
+
+/// Script building 10k manifest-like documents and enumerating them `passes` times
+fn enumeration_script(operation: &str, passes: usize) -> String {
+    format!(
+        r#"
+        const docs = [];
+        for (let i = 0; i < 10000; i++) {{
+            docs.push({{ apiVersion: "v1", kind: "ConfigMap", name: "cm-" + i, namespace: "default", replicas: i % 5, enabled: true }});
+        }}
+        let total = 0;
+        for (let pass = 0; pass < {passes}; pass++) {{
+            for (let i = 0; i < docs.length; i++) {{
+                const doc = docs[i];
+                total += {operation};
+            }}
+        }}
+        total
+        "#
+    )
+}
+
+fn run_script(source: &str) {
+    let mut interp = Interpreter::new();
+    interp.set_gc_threshold(0);
+    if interp.prepare(source, None).is_err() {
+        return;
+    }
+    while let Ok(StepResult::Continue) = black_box(interp.step()) {}
+}
+
+fn bench_enumeration(c: &mut Criterion) {
+    let mut group = c.benchmark_group("enumeration");
+    group.sample_size(10);
+
+    let operations = [
+        ("json_stringify", "JSON.stringify(doc).length"),
+        ("object_keys", "Object.keys(doc).length"),
+        ("spread", "Object.keys({ ...doc }).length"),
+    ];
+    for (name, operation) in operations {
+        let source = enumeration_script(operation, 8);
+        group.bench_with_input(BenchmarkId::new(name, 8), &source, |b, s| {
+            b.iter(|| run_script(black_box(s)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_enumeration);
+criterion_main!(benches);
@@ -0,0 +1,49 @@
+//! Function call benchmarks
+//!
+//! Recursive fibonacci, called directly (trampolined script-to-script calls) and
+//! through `Array.prototype.map` (nested calls from a builtin). Before timing,
+//! each script reports how many heap objects one run allocates; pooled call
+//! frames keep that near constant instead of one environment per call.
+//!
+//! Run with: cargo bench --bench recursion
+
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use tsrun::{Interpreter, StepResult};
+
+const DIRECT: &str = r#"
+    function fib(n) { return n < 2 ? n : fib(n - 1) + fib(n - 2); }
+    fib(20)
+"#;
+
+const VIA_MAP: &str = r#"
+    function fib(n) { return n < 2 ? n : fib(n - 1) + fib(n - 2); }
+    [10, 12, 14, 16, 18, 19].map(fib).length
+"#;
+
+/// Run a script to completion, returning the number of heap allocations it made
+fn run_script(source: &str) -> usize {
+    let mut interp = Interpreter::new();
+    let before = interp.gc_stats().allocations;
+    if interp.prepare(source, None).is_err() {
+        return 0;
+    }
+    while let Ok(StepResult::Continue) = black_box(interp.step()) {}
+    interp.gc_stats().allocations - before
+}
+
+fn bench_fibonacci(c: &mut Criterion) {
+    let mut group = c.benchmark_group("recursion");
+    group.sample_size(20);
+
+    for (name, source) in [("fib_direct", DIRECT), ("fib_via_map", VIA_MAP)] {
+        eprintln!("{name}: {} heap allocations per run", run_script(source));
+        group.bench_with_input(BenchmarkId::new(name, 20), &source, |b, s| {
+            b.iter(|| run_script(black_box(s)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_fibonacci);
+criterion_main!(benches);
@@ -0,0 +1,49 @@
+//! String concatenation benchmarks
+//!
+//! Builds strings with `+=` in a loop at doubling sizes up to 1MB; with rope
+//! concatenation the time per byte stays flat instead of growing linearly.
+//!
+//! Run with: cargo bench --bench string_concat
+
+use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use tsrun::{Interpreter, StepResult};
+
+/// Script appending ~16-byte lines until the output reaches `target` bytes
+fn concat_script(target: usize) -> String {
+    format!(
+        r#"
+        let out = "";
+        let i = 0;
+        while (out.length < {target}) {{
+            out += "line " + (i++ % 1000) + " ok\n";
+        }}
+        out.length
+        "#
+    )
+}
+
+fn run_script(source: &str) {
+    let mut interp = Interpreter::new();
+    if interp.prepare(source, None).is_err() {
+        return;
+    }
+    while let Ok(StepResult::Continue) = black_box(interp.step()) {}
+}
+
+fn bench_concat_loop(c: &mut Criterion) {
+    let mut group = c.benchmark_group("string_concat");
+    group.sample_size(10);
+
+    for target in [128 * 1024, 256 * 1024, 512 * 1024, 1024 * 1024] {
+        let source = concat_script(target);
+        group.throughput(Throughput::Bytes(target as u64));
+        group.bench_with_input(BenchmarkId::new("plus_assign", target), &source, |b, s| {
+            b.iter(|| run_script(black_box(s)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_concat_loop);
+criterion_main!(benches);
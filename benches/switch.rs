@@ -0,0 +1,69 @@
+//! String switch benchmarks
+//!
+//! Dispatches 30-case switches over manifest kinds in a hot loop. With only
+//! string literal cases the switch compiles to a single table lookup; making
+//! one case a computed expression forces the case-by-case comparison chain
+//! for comparison.
+//!
+//! Run with: cargo bench --bench switch
+
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use tsrun::{Interpreter, StepResult};
+
+const CASES: usize = 30;
+
+/// Script switching over every case kind `rounds` times; `last_case` is the
+/// source text of the final case test
+fn switch_script(last_case: &str, rounds: usize) -> String {
+    let mut cases = String::new();
+    for i in 0..CASES - 1 {
+        cases.push_str(&format!("case \"Kind{i}\": total += {i}; break;\n"));
+    }
+    cases.push_str(&format!("case {last_case}: total += 1000; break;\n"));
+    format!(
+        r#"
+        const kinds = [];
+        for (let i = 0; i < {CASES}; i++) kinds.push("Kind" + i);
+        let total = 0;
+        for (let round = 0; round < {rounds}; round++) {{
+            for (let i = 0; i < kinds.length; i++) {{
+                switch (kinds[i]) {{
+                    {cases}
+                    default: total -= 1;
+                }}
+            }}
+        }}
+        total
+        "#
+    )
+}
+
+fn run_script(source: &str) {
+    let mut interp = Interpreter::new();
+    if interp.prepare(source, None).is_err() {
+        return;
+    }
+    while let Ok(StepResult::Continue) = black_box(interp.step()) {}
+}
+
+fn bench_switch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("switch");
+    group.sample_size(10);
+
+    let last = CASES - 1;
+    let variants = [
+        ("string_table", format!("\"Kind{last}\"")),
+        ("comparison_chain", format!("\"Kind\" + {last}")),
+    ];
+    for (name, last_case) in variants {
+        let source = switch_script(&last_case, 1000);
+        group.bench_with_input(BenchmarkId::new(name, CASES), &source, |b, s| {
+            b.iter(|| run_script(black_box(s)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_switch);
+criterion_main!(benches);
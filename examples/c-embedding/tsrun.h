@@ -46,6 +46,13 @@ typedef struct {
     const char* error;    // NULL on success
 } TsRunResult;
 
+// Result for operations returning bytes
+typedef struct {
+    uint8_t* data;        // NULL on error, free with tsrun_free_bytes
+    size_t len;
+    const char* error;    // NULL on success, valid until next tsrun_* call
+} TsRunBytes;
+
 // ============================================================================
 // Console Levels
 // ============================================================================
@@ -83,6 +90,24 @@ typedef enum {
     TSRUN_TYPE_SYMBOL,
 } TsRunType;
 
+// Well-known symbols (for tsrun_well_known_symbol)
+typedef enum {
+    TSRUN_SYMBOL_ITERATOR = 0,
+    TSRUN_SYMBOL_ASYNC_ITERATOR,
+    TSRUN_SYMBOL_HAS_INSTANCE,
+    TSRUN_SYMBOL_IS_CONCAT_SPREADABLE,
+    TSRUN_SYMBOL_MATCH,
+    TSRUN_SYMBOL_REPLACE,
+    TSRUN_SYMBOL_SEARCH,
+    TSRUN_SYMBOL_SPECIES,
+    TSRUN_SYMBOL_SPLIT,
+    TSRUN_SYMBOL_TO_PRIMITIVE,
+    TSRUN_SYMBOL_TO_STRING_TAG,
+    TSRUN_SYMBOL_UNSCOPABLES,
+    TSRUN_SYMBOL_DISPOSE,
+    TSRUN_SYMBOL_ASYNC_DISPOSE,
+} TsRunWellKnownSymbol;
+
 // ============================================================================
 // Step Result (mirrors Rust StepResult)
 // ============================================================================
@@ -94,6 +119,7 @@ typedef enum {
     TSRUN_STEP_SUSPENDED,       // Waiting for order fulfillment
     TSRUN_STEP_DONE,            // No active execution
     TSRUN_STEP_ERROR,           // Execution error
+    TSRUN_STEP_ORDERS,          // New orders, execution continues (eager delivery)
 } TsRunStepStatus;
 
 // Import request
@@ -107,6 +133,10 @@ typedef struct {
 typedef struct {
     TsRunOrderId id;
     TsRunValue* payload;        // The order payload (owned by context)
+    const char* origin_module;  // Module that created the order (NULL for unnamed entry)
+    const char* origin_function; // Enclosing function (NULL at top level or anonymous)
+    uint32_t origin_line;       // 1-based line of the creating call (0 if unknown)
+    uint32_t origin_column;     // 1-based column of the creating call (0 if unknown)
 } TsRunOrder;
 
 // Step result with all possible data
@@ -120,7 +150,7 @@ typedef struct {
     TsRunImportRequest* imports;
     size_t import_count;
 
-    // For TSRUN_STEP_SUSPENDED
+    // For TSRUN_STEP_SUSPENDED (pending orders also for TSRUN_STEP_ORDERS)
     TsRunOrder* pending_orders;
     size_t pending_count;
     TsRunOrderId* cancelled_orders;
@@ -137,7 +167,8 @@ typedef struct {
 // Create a new interpreter context
 TsRunContext* tsrun_new(void);
 
-// Free an interpreter context (also frees all associated values)
+// Free an interpreter context. Values obtained from it must still be freed
+// with tsrun_value_free; until then objects among them read as undefined.
 void tsrun_free(TsRunContext* ctx);
 
 // Set a custom console provider callback.
@@ -146,6 +177,14 @@ void tsrun_free(TsRunContext* ctx);
 // For a default stdout/stderr implementation, see tsrun_console.h.
 TsRunResult tsrun_set_console(TsRunContext* ctx, TsRunConsoleFn func, void* userdata);
 
+// Limit console output. Messages longer than max_message_len bytes are truncated.
+// After max_output bytes in total (0 = unlimited), messages are dropped after a
+// single warning. Resets the dropped-message count.
+TsRunResult tsrun_set_console_limits(TsRunContext* ctx, size_t max_message_len, size_t max_output);
+
+// Number of console messages dropped because the output limit was reached
+size_t tsrun_console_dropped_count(const TsRunContext* ctx);
+
 // ============================================================================
 // Execution - Step-based API
 // ============================================================================
@@ -154,6 +193,38 @@ TsRunResult tsrun_set_console(TsRunContext* ctx, TsRunConsoleFn func, void* user
 // path is optional (NULL for anonymous scripts, or "/path/to/module.ts" for modules)
 TsRunResult tsrun_prepare(TsRunContext* ctx, const char* code, const char* path);
 
+// Incremental prepare for large sources: begin, feed chunks, then call finish
+// and continue until the status is READY, yielding to the host in between.
+// Each finish/continue call parses about `budget` source bytes; the call after
+// parsing completes compiles. Needed imports are reported by the next step.
+typedef enum {
+    TSRUN_PREPARE_PENDING = 0,  // Out of budget, call tsrun_prepare_continue
+    TSRUN_PREPARE_READY,        // Ready for tsrun_step / tsrun_run
+    TSRUN_PREPARE_ERROR,        // Failed; the incremental prepare is abandoned
+} TsRunPrepareStatus;
+
+typedef struct {
+    TsRunPrepareStatus status;
+    size_t parsed_bytes;        // Source bytes parsed so far (PENDING only)
+    size_t total_bytes;         // Total source bytes (PENDING only)
+    const char* error;          // Error message (ERROR only)
+} TsRunPrepareProgress;
+
+TsRunResult tsrun_prepare_begin(TsRunContext* ctx, const char* path);
+
+// Chunks need not be NUL-terminated; UTF-8 sequences may span chunks
+TsRunResult tsrun_prepare_chunk(TsRunContext* ctx, const uint8_t* data, size_t len);
+
+TsRunPrepareProgress tsrun_prepare_finish(TsRunContext* ctx, size_t budget);
+TsRunPrepareProgress tsrun_prepare_continue(TsRunContext* ctx, size_t budget);
+
+// Write out a prepared run before its first step, to set it up in another
+// context with tsrun_import_prepared. Fails once the run has started or
+// when it has imports. The bytes only load into the same tsrun build.
+TsRunBytes tsrun_export_prepared(TsRunContext* ctx);
+TsRunResult tsrun_import_prepared(TsRunContext* ctx, const uint8_t* data, size_t len);
+void tsrun_free_bytes(uint8_t* data, size_t len);
+
 // Execute one step
 // Returns step result - caller must call tsrun_step_result_free when done
 TsRunStepResult tsrun_step(TsRunContext* ctx);
@@ -165,6 +236,38 @@ TsRunStepResult tsrun_run(TsRunContext* ctx);
 // Free a step result (frees internal arrays, NOT the value)
 void tsrun_step_result_free(TsRunStepResult* result);
 
+// Order handler for tsrun_eval_json, called synchronously once per order.
+// - order_payload_json: the order payload as JSON, valid only during the call
+// - *out_response_json: set to a string from tsrun_alloc_string (tsrun takes
+//   ownership and frees it), or leave NULL to resolve the order with undefined
+// - *out_is_error: set to true to reject the order; the response string is
+//   then used as the error message instead of being parsed as JSON
+typedef void (*TsRunOrderHandlerFn)(
+    TsRunContext* ctx,
+    const char* order_payload_json,
+    void* userdata,
+    char** out_response_json,
+    bool* out_is_error
+);
+
+// Prepare and run code to completion, answering orders via order_handler.
+// - order_handler may be NULL; scripts that issue orders then fail
+// - imports are not loaded: TSRUN_STEP_NEED_IMPORTS fails with an error
+//   listing the missing paths (seed them with tsrun_provide_module_unrequested)
+// On success returns true and sets *out_json to the completion value as JSON.
+// On failure returns false and sets *out_err to the error message.
+// Both strings are owned by the caller: free with tsrun_free_string.
+// out_json / out_err may be NULL to discard that string.
+bool tsrun_eval_json(
+    TsRunContext* ctx,
+    const char* source,
+    const char* path,
+    TsRunOrderHandlerFn order_handler,
+    void* userdata,
+    char** out_json,
+    char** out_err
+);
+
 // ============================================================================
 // Module System
 // ============================================================================
@@ -172,6 +275,9 @@ void tsrun_step_result_free(TsRunStepResult* result);
 // Provide module source code in response to TSRUN_STEP_NEED_IMPORTS
 TsRunResult tsrun_provide_module(TsRunContext* ctx, const char* path, const char* code);
 
+// Pre-seed module source code before it is requested
+TsRunResult tsrun_provide_module_unrequested(TsRunContext* ctx, const char* path, const char* code);
+
 // ============================================================================
 // Order System (for async operations)
 // ============================================================================
@@ -188,6 +294,11 @@ TsRunResult tsrun_fulfill_orders(TsRunContext* ctx,
                                   const TsRunOrderResponse* responses,
                                   size_t count);
 
+// Choose when orders are handed to the host.
+// With eager set, order() returns a Promise and new orders are reported via
+// TSRUN_STEP_ORDERS while the script keeps running.
+TsRunResult tsrun_set_order_delivery(TsRunContext* ctx, bool eager);
+
 // Create a pending order that will suspend the interpreter.
 // Use in native callbacks to perform async operations.
 // The payload is accessible via order.payload in the step result.
@@ -222,6 +333,10 @@ bool tsrun_is_object(const TsRunValue* val);
 bool tsrun_is_array(const TsRunValue* val);
 bool tsrun_is_function(const TsRunValue* val);
 
+// Identity of the object a value refers to: equal for values of the same
+// object, never reused for another object. 0 for primitives.
+uint64_t tsrun_value_object_id(const TsRunValue* val);
+
 // Extract primitive values (undefined behavior if wrong type - check first!)
 bool tsrun_get_bool(const TsRunValue* val);
 double tsrun_get_number(const TsRunValue* val);
@@ -251,7 +366,8 @@ TsRunValueResult tsrun_array_new(TsRunContext* ctx);
 // ============================================================================
 
 // Values returned by the API are "guarded" - they won't be garbage collected
-// until you explicitly free them. Always free values when done.
+// until you explicitly free them. Always free values when done, before or
+// after freeing their context.
 void tsrun_value_free(TsRunValue* val);
 
 // Duplicate a value handle (both handles must be freed separately)
@@ -271,6 +387,17 @@ TsRunResult tsrun_delete(TsRunContext* ctx, TsRunValue* obj, const char* key);
 char** tsrun_keys(TsRunContext* ctx, TsRunValue* obj, size_t* count_out);
 void tsrun_free_strings(char** strings, size_t count);
 
+// Symbols
+TsRunValue* tsrun_well_known_symbol(TsRunContext* ctx, TsRunWellKnownSymbol symbol);
+TsRunValue* tsrun_symbol_new(TsRunContext* ctx, const char* description);  // description may be NULL
+char* tsrun_symbol_description(const TsRunValue* val);  // Caller frees with tsrun_free_string
+TsRunValueResult tsrun_get_symbol(TsRunContext* ctx, TsRunValue* obj, TsRunValue* symbol);
+TsRunResult tsrun_set_symbol(TsRunContext* ctx, TsRunValue* obj, TsRunValue* symbol, TsRunValue* val);
+
+// Get own symbol keys (caller must free returned array with tsrun_free_values)
+TsRunValue** tsrun_symbol_keys(TsRunContext* ctx, TsRunValue* obj, size_t* count_out);
+void tsrun_free_values(TsRunValue** values, size_t count);
+
 // Array operations
 size_t tsrun_array_len(const TsRunValue* arr);
 TsRunValueResult tsrun_array_get(TsRunContext* ctx, TsRunValue* arr, size_t index);
@@ -295,6 +422,22 @@ TsRunValueResult tsrun_call_method(TsRunContext* ctx,
                                     TsRunValue** args,
                                     size_t argc);
 
+// Where a script function was defined
+typedef struct {
+    char* text;             // Source text, exactly as written
+    char* path;             // File it was defined in, or NULL if unknown
+    uint32_t start_line;    // 1-based position of the first character
+    uint32_t start_column;
+    uint32_t end_line;      // 1-based position just past the last character
+    uint32_t end_column;
+} TsRunFunctionSource;
+
+// Get a script function's source and location. Returns false for native and
+// bound functions, non-functions, or when function source retention is off.
+// On success, release the strings with tsrun_function_source_free.
+bool tsrun_value_function_source(const TsRunValue* val, TsRunFunctionSource* out);
+void tsrun_function_source_free(TsRunFunctionSource* source);
+
 // ============================================================================
 // Global Access
 // ============================================================================
@@ -344,6 +487,10 @@ TsRunValueResult tsrun_native_function(
 char* tsrun_json_stringify(TsRunContext* ctx, TsRunValue* val);
 void tsrun_free_string(char* s);
 
+// Copy a string into a tsrun-owned allocation (for strings handed back to
+// tsrun, e.g. order handler responses). Returns NULL for NULL/invalid UTF-8.
+char* tsrun_alloc_string(const char* s);
+
 // ============================================================================
 // Internal Modules (for extending the interpreter)
 // ============================================================================
@@ -456,6 +603,36 @@ typedef struct {
 
 TsRunGcStats tsrun_gc_stats(TsRunContext* ctx);
 
+// Reachable objects grouped by kind (walks the whole heap)
+typedef struct {
+    size_t ordinary;
+    size_t arrays;
+    size_t functions;
+    size_t promises;
+    size_t maps;
+    size_t sets;
+    size_t environments;
+    size_t generators;
+    size_t proxies;
+    size_t other;
+    size_t properties;      // Own properties, array elements and bindings
+    size_t string_bytes;    // UTF-8 bytes of strings held in those slots
+} TsRunGcCensus;
+
+TsRunGcCensus tsrun_gc_census(TsRunContext* ctx);
+
+#define TSRUN_RETAINER_LABEL_LEN 32
+
+// A guard keeping objects alive
+typedef struct {
+    char label[TSRUN_RETAINER_LABEL_LEN];  // e.g. "root", "order payload"; empty if unlabeled
+    size_t roots;           // Objects rooted directly
+    size_t retained;        // Objects freed if the guard were released
+} TsRunGcRetainer;
+
+// Write up to `capacity` top retainers to `out`, largest first; returns count written
+size_t tsrun_gc_retainers(TsRunContext* ctx, TsRunGcRetainer* out, size_t capacity);
+
 #ifdef __cplusplus
 }
 #endif
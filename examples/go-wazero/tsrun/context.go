@@ -230,7 +230,11 @@ func (c *Context) parsePendingOrders(ptr uint32, count uint32) []Order {
 	// TsRunOrder layout (wasm32):
 	// offset 0: id (u64)
 	// offset 8: payload (i32 pointer to TsRunValue)
-	const structSize = 12 // 8 + 4 on wasm32
+	// offset 12: origin_module (i32 pointer to C string, 0 if unnamed)
+	// offset 16: origin_function (i32 pointer to C string, 0 if none)
+	// offset 20: origin_line (u32)
+	// offset 24: origin_column (u32)
+	const structSize = 32 // 28 padded to the u64 alignment
 
 	orders := make([]Order, count)
 	for i := uint32(0); i < count; i++ {
@@ -243,9 +247,20 @@ func (c *Context) parsePendingOrders(ptr uint32, count uint32) []Order {
 			payload = &Value{ctx: c, handle: payloadPtr}
 		}
 
+		modulePtr, _ := c.rt.memory.ReadUint32Le(offset + 12)
+		functionPtr, _ := c.rt.memory.ReadUint32Le(offset + 16)
+		line, _ := c.rt.memory.ReadUint32Le(offset + 20)
+		column, _ := c.rt.memory.ReadUint32Le(offset + 24)
+
 		orders[i] = Order{
 			ID:      id,
 			Payload: payload,
+			Origin: OrderOrigin{
+				Module:       c.rt.readString(modulePtr),
+				FunctionName: c.rt.readString(functionPtr),
+				Line:         line,
+				Column:       column,
+			},
 		}
 	}
 	return orders
@@ -90,6 +90,20 @@ type Order struct {
 	ID uint64
 	// Payload is the order payload value.
 	Payload *Value
+	// Origin is where in the script the order was created.
+	Origin OrderOrigin
+}
+
+// OrderOrigin describes the script location that created an order.
+type OrderOrigin struct {
+	// Module is the module containing the call (empty for an unnamed entry).
+	Module string
+	// FunctionName is the enclosing function (empty at top level or anonymous).
+	FunctionName string
+	// Line is the 1-based line of the call (0 if unknown).
+	Line uint32
+	// Column is the 1-based column of the call (0 if unknown).
+	Column uint32
 }
 
 // OrderResponse represents a response to an order.
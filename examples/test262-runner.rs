@@ -13,19 +13,56 @@
 //!   --list                List matching tests without running
 //!   --strict-only         Only run strict mode variants
 //!   --non-strict-only     Only run non-strict mode variants
+//!   --list-file <PATH>    Run the tests listed in PATH (one per line)
+//!   --known-failures <PATH> Tests expected to fail; only other failures fail the run
+//!   --write-passing <PATH> Write the tests that passed to PATH
+//!   --write-failing <PATH> Write the tests that failed to PATH
 //!
 //! Examples:
 //!   test262-runner test/language/expressions/addition
 //!   test262-runner --filter "array" test/built-ins/Array
 //!   test262-runner --skip-features "BigInt,WeakRef" test/language
+//!   test262-runner --list-file tests/test262/curated.txt \
+//!       --known-failures tests/test262/known-failures.txt
 
 use rustc_hash::{FxHashMap, FxHashSet};
+use std::cell::RefCell;
 use std::env;
 use std::fs;
 use std::io::{self, Write as IoWrite};
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::time::{Duration, Instant};
-use tsrun::{Interpreter, JsError, StepResult};
+use tsrun::platform::{ConsoleLevel, ConsoleProvider};
+use tsrun::{Interpreter, InterpreterConfig, JsError, ModulePath, StepResult};
+
+/// Harness files baked into the runner, so results don't depend on the
+/// checkout's copies. Other `includes` are still read from `<test262>/harness`.
+const BAKED_HARNESS: &[(&str, &str)] = &[
+    ("sta.js", include_str!("../tests/test262/harness/sta.js")),
+    (
+        "assert.js",
+        include_str!("../tests/test262/harness/assert.js"),
+    ),
+    (
+        "doneprintHandle.js",
+        include_str!("../tests/test262/harness/doneprintHandle.js"),
+    ),
+];
+
+/// Console output lines printed by `$DONE` (see doneprintHandle.js)
+const ASYNC_COMPLETE: &str = "Test262:AsyncTestComplete";
+const ASYNC_FAILURE: &str = "Test262:AsyncTestFailure:";
+
+/// Console provider that records `console.log` lines for async tests
+#[derive(Clone, Default)]
+struct CapturedConsole(Rc<RefCell<Vec<String>>>);
+
+impl ConsoleProvider for CapturedConsole {
+    fn write(&self, _level: ConsoleLevel, message: &str) {
+        self.0.borrow_mut().push(message.to_string());
+    }
+}
 
 /// Test metadata parsed from YAML frontmatter
 #[derive(Debug, Default)]
@@ -89,6 +126,9 @@ impl TestRunner {
 
     /// Load harness file content, caching results
     fn load_harness(&mut self, name: &str) -> Result<String, String> {
+        if let Some((_, content)) = BAKED_HARNESS.iter().find(|(baked, _)| *baked == name) {
+            return Ok(content.to_string());
+        }
         if let Some(content) = self.harness_cache.get(name) {
             return Ok(content.clone());
         }
@@ -243,16 +283,6 @@ impl TestRunner {
             }
         }
 
-        // Skip module tests for now (need module support)
-        if meta.flags.contains("module") {
-            return Some("module tests not yet supported".to_string());
-        }
-
-        // Skip async tests for now
-        if meta.flags.contains("async") {
-            return Some("async tests not yet supported".to_string());
-        }
-
         // Skip tests requiring agents/atomics
         if meta.flags.contains("CanBlockIsTrue") || meta.flags.contains("CanBlockIsFalse") {
             return Some("atomics/agents not supported".to_string());
@@ -276,6 +306,12 @@ impl TestRunner {
         preamble.push_str(&self.load_harness("assert.js")?);
         preamble.push('\n');
 
+        // Async tests report through $DONE
+        if meta.flags.contains("async") {
+            preamble.push_str(&self.load_harness("doneprintHandle.js")?);
+            preamble.push('\n');
+        }
+
         // Include additional harness files
         for include in &meta.includes {
             preamble.push_str(&self.load_harness(include)?);
@@ -288,7 +324,7 @@ impl TestRunner {
     /// Run a single test in a specific mode
     fn run_test_mode(
         &mut self,
-        _test_path: &Path,
+        test_path: &Path,
         source: &str,
         meta: &TestMetadata,
         strict: bool,
@@ -309,8 +345,9 @@ impl TestRunner {
             }
         };
 
-        // Add strict mode directive if needed
-        let test_source = if strict && !meta.flags.contains("raw") {
+        // Add strict mode directive if needed (modules are always strict)
+        let is_module = meta.flags.contains("module");
+        let test_source = if strict && !meta.flags.contains("raw") && !is_module {
             format!("\"use strict\";\n{}", source)
         } else {
             source.to_string()
@@ -318,35 +355,68 @@ impl TestRunner {
 
         let full_source = format!("{}\n{}", preamble, test_source);
 
-        // Create interpreter
-        let mut interp = Interpreter::new();
+        // Create interpreter; the suite exercises Function and indirect eval
+        let mut interp = Interpreter::with_config(InterpreterConfig {
+            allow_dynamic_code: true,
+            ..Default::default()
+        });
         interp.set_gc_threshold(100);
+        let console = CapturedConsole::default();
+        interp.set_console(Box::new(console.clone()));
+
+        // Modules are keyed by their absolute path so fixtures resolve next to the test
+        let module_path = is_module.then(|| {
+            let abs = fs::canonicalize(test_path).unwrap_or_else(|_| test_path.to_path_buf());
+            ModulePath::new(abs.to_string_lossy().into_owned())
+        });
 
         // Execute test using step-based API
-        let result = interp.prepare(&full_source, None);
+        let result = interp.prepare(&full_source, module_path);
         let result = match result {
-            Ok(_) => {
-                // Run to completion
-                loop {
-                    match interp.step() {
-                        Ok(StepResult::Continue) => continue,
-                        Ok(step_result) => break Ok(step_result),
-                        Err(e) => break Err(e),
-                    }
-                }
-            }
+            Ok(_) => run_to_completion(&mut interp),
             Err(e) => Err(e),
         };
         let duration = start.elapsed();
 
+        // Async tests only pass once $DONE reports completion
+        let result = match result {
+            Ok(StepResult::Complete(_) | StepResult::Done) if meta.flags.contains("async") => {
+                let lines = console.0.borrow();
+                if let Some(failure) = lines.iter().find_map(|l| l.strip_prefix(ASYNC_FAILURE)) {
+                    return TestOutcome {
+                        result: TestResult::Fail,
+                        mode,
+                        error: Some(format!("Async test failed: {}", failure)),
+                        duration,
+                    };
+                }
+                if !lines.iter().any(|l| l == ASYNC_COMPLETE) {
+                    return TestOutcome {
+                        result: TestResult::Fail,
+                        mode,
+                        error: Some("Async test finished without calling $DONE".to_string()),
+                        duration,
+                    };
+                }
+                result
+            }
+            other => other,
+        };
+
         // Check result against expectations
         match (&meta.negative, result) {
             // Expected to throw, and it did
             (Some(neg), Err(ref err)) => {
                 let error_matches = check_error_type(err, &neg.error_type);
                 let phase_matches = match neg.phase.as_str() {
-                    "parse" => matches!(err, JsError::SyntaxError { .. }),
-                    "runtime" => !matches!(err, JsError::SyntaxError { .. }),
+                    "parse" => matches!(
+                        err,
+                        JsError::SyntaxError { .. } | JsError::UnsupportedSyntax { .. }
+                    ),
+                    "runtime" => !matches!(
+                        err,
+                        JsError::SyntaxError { .. } | JsError::UnsupportedSyntax { .. }
+                    ),
                     "resolution" => true, // Module resolution - we'd need module support
                     _ => true,
                 };
@@ -401,7 +471,7 @@ impl TestRunner {
             },
 
             // Expected to pass, but suspended (async test that slipped through)
-            (None, Ok(StepResult::Suspended { .. })) => TestOutcome {
+            (None, Ok(StepResult::Suspended { .. } | StepResult::Orders(_))) => TestOutcome {
                 result: TestResult::Skip,
                 mode,
                 error: Some("Test requires async support".to_string()),
@@ -505,19 +575,26 @@ fn parse_yaml_array(value: &str) -> Vec<String> {
 /// Check if error matches expected type
 fn check_error_type(err: &JsError, expected: &str) -> bool {
     match err {
-        JsError::SyntaxError { .. } => expected == "SyntaxError",
+        JsError::SyntaxError { .. } | JsError::UnsupportedSyntax { .. } => {
+            expected == "SyntaxError"
+        }
         JsError::TypeError { .. } => expected == "TypeError",
         JsError::ReferenceError { .. } => expected == "ReferenceError",
-        JsError::RangeError { .. } => expected == "RangeError",
+        JsError::RangeError { .. } | JsError::CompileLimit { .. } => expected == "RangeError",
         JsError::RuntimeError { kind, message, .. } => {
             expected == "Error" || kind == expected || message.contains(expected)
         }
         JsError::ModuleError { .. } => expected == "SyntaxError" || expected == "Error",
+        JsError::IntegrityMismatch { .. } => expected == "Error",
+        JsError::TransformError { .. } => expected == "Error",
         JsError::Internal(_) => false,
         JsError::Thrown => expected == "Error",
         JsError::ThrownValue { .. } => expected == "Error",
         JsError::GeneratorYield { .. } => false,
         JsError::OptionalChainShortCircuit => false,
+        JsError::Terminated { .. } | JsError::Stalled { .. } | JsError::ReplayMismatch { .. } => {
+            false
+        }
     }
 }
 
@@ -545,9 +622,60 @@ fn format_error(err: &JsError) -> String {
         JsError::OptionalChainShortCircuit => {
             "OptionalChainShortCircuit (internal error - should not reach here)".to_string()
         }
+        JsError::UnsupportedSyntax { .. }
+        | JsError::IntegrityMismatch { .. }
+        | JsError::TransformError { .. }
+        | JsError::CompileLimit { .. }
+        | JsError::Stalled { .. }
+        | JsError::ReplayMismatch { .. }
+        | JsError::Terminated { .. } => err.to_string(),
     }
 }
 
+/// Step until the test finishes, loading module imports from disk
+fn run_to_completion(interp: &mut Interpreter) -> Result<StepResult, JsError> {
+    loop {
+        match interp.step()? {
+            StepResult::Continue => continue,
+            StepResult::NeedImports(imports) => {
+                for import in imports {
+                    let path = import.resolved_path;
+                    let source =
+                        fs::read_to_string(path.as_str()).map_err(|e| JsError::ModuleError {
+                            message: format!("Cannot load module '{}': {}", path, e),
+                        })?;
+                    interp.provide_module(path, &source)?;
+                }
+            }
+            step_result => return Ok(step_result),
+        }
+    }
+}
+
+/// Read a test list file: one path per line (relative to `test/`), `#` comments
+fn read_list_file(path: &Path) -> Result<Vec<String>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Ok(content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect())
+}
+
+/// Write a sorted test list file with a header comment
+fn write_list_file(path: &Path, header: &str, tests: &[String]) -> Result<(), String> {
+    let mut sorted = tests.to_vec();
+    sorted.sort();
+    let mut content = format!("# {}\n", header);
+    for test in sorted {
+        content.push_str(&test);
+        content.push('\n');
+    }
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
 /// Collect test files from a directory
 fn collect_tests(dir: &Path, pattern: Option<&str>) -> Vec<PathBuf> {
     let mut tests = Vec::new();
@@ -613,14 +741,19 @@ Options:
   --list                   List matching tests without running
   --strict-only            Only run strict mode variants
   --non-strict-only        Only run non-strict mode variants
+  --list-file <PATH>       Run the tests listed in PATH (one per line, # comments)
+  --known-failures <PATH>  Tests expected to fail; only other failures fail the run
+  --write-passing <PATH>   Write the tests that passed to PATH
+  --write-failing <PATH>   Write the tests that failed to PATH
   --help                   Show this help message
 
 Examples:
   {} language/expressions/addition
   {} --filter array built-ins/Array
   {} --skip-features BigInt,WeakRef language
-  {} --verbose --stop-on-fail language/statements",
-        program, program, program, program, program
+  {} --verbose --stop-on-fail language/statements
+  {} --list-file tests/test262/curated.txt --known-failures tests/test262/known-failures.txt",
+        program, program, program, program, program, program
     );
 }
 
@@ -646,6 +779,10 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
     let mut strict_only = false;
     let mut non_strict_only = false;
     let mut test_path: Option<String> = None;
+    let mut list_file: Option<PathBuf> = None;
+    let mut known_failures_file: Option<PathBuf> = None;
+    let mut write_passing: Option<PathBuf> = None;
+    let mut write_failing: Option<PathBuf> = None;
 
     // Default skip features (things we don't support yet)
     let default_skip = [
@@ -720,6 +857,30 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
             "--list" => list_only = true,
             "--strict-only" => strict_only = true,
             "--non-strict-only" => non_strict_only = true,
+            "--list-file" => {
+                i += 1;
+                list_file = Some(PathBuf::from(
+                    args.get(i).ok_or("Missing value for --list-file")?,
+                ));
+            }
+            "--known-failures" => {
+                i += 1;
+                known_failures_file = Some(PathBuf::from(
+                    args.get(i).ok_or("Missing value for --known-failures")?,
+                ));
+            }
+            "--write-passing" => {
+                i += 1;
+                write_passing = Some(PathBuf::from(
+                    args.get(i).ok_or("Missing value for --write-passing")?,
+                ));
+            }
+            "--write-failing" => {
+                i += 1;
+                write_failing = Some(PathBuf::from(
+                    args.get(i).ok_or("Missing value for --write-failing")?,
+                ));
+            }
             _ if !arg.starts_with('-') => {
                 test_path = Some(arg.clone());
             }
@@ -730,22 +891,45 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         i += 1;
     }
 
-    // Require test path
-    let test_path = test_path.ok_or("Missing test path argument. Use --help for usage.")?;
+    // Require a test path or a list file
+    if test_path.is_none() && list_file.is_none() {
+        return Err("Missing test path argument. Use --help for usage.".into());
+    }
 
-    // Resolve test path
-    let full_test_path = if Path::new(&test_path).is_absolute() {
-        PathBuf::from(&test_path)
-    } else {
-        test262_dir.join("test").join(&test_path)
+    let test_root = test262_dir.join("test");
+    let resolve = |path: &str| {
+        if Path::new(path).is_absolute() {
+            PathBuf::from(path)
+        } else {
+            test_root.join(path)
+        }
     };
 
-    if !full_test_path.exists() {
-        return Err(format!("Test path not found: {}", full_test_path.display()).into());
+    // Collect tests
+    let mut tests = Vec::new();
+    if let Some(ref test_path) = test_path {
+        let full_test_path = resolve(test_path);
+        if !full_test_path.exists() {
+            return Err(format!("Test path not found: {}", full_test_path.display()).into());
+        }
+        tests.extend(collect_tests(&full_test_path, filter.as_deref()));
+    }
+    if let Some(ref list_file) = list_file {
+        for entry in read_list_file(list_file)? {
+            let full = resolve(&entry);
+            if !full.exists() {
+                return Err(format!("Listed test not found: {}", full.display()).into());
+            }
+            tests.extend(collect_tests(&full, filter.as_deref()));
+        }
+        tests.sort();
+        tests.dedup();
     }
 
-    // Collect tests
-    let tests = collect_tests(&full_test_path, filter.as_deref());
+    let known_failures: FxHashSet<String> = match known_failures_file {
+        Some(ref path) => read_list_file(path)?.into_iter().collect(),
+        None => FxHashSet::default(),
+    };
 
     if tests.is_empty() {
         println!("No tests found matching criteria");
@@ -775,6 +959,8 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
     let mut fail_count = 0;
     let mut skip_count = 0;
     let mut timeout_count = 0;
+    let mut passing_tests: Vec<String> = Vec::new();
+    let mut failing_tests: Vec<String> = Vec::new();
     let total_start = Instant::now();
 
     for (idx, test_path) in tests.iter().enumerate() {
@@ -792,6 +978,12 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
             io::stdout().flush().ok();
         }
 
+        let list_key = test_path
+            .strip_prefix(&test_root)
+            .unwrap_or(test_path)
+            .to_string_lossy()
+            .replace('\\', "/");
+
         let outcomes = runner.run_test(test_path);
 
         let mut test_passed = true;
@@ -810,11 +1002,23 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
             }
         }
 
+        let all_skipped = outcomes.iter().all(|o| o.result == TestResult::Skip);
+        let known_failure = known_failures.contains(&list_key);
+        if !all_skipped {
+            if test_passed {
+                passing_tests.push(list_key.clone());
+            } else {
+                failing_tests.push(list_key.clone());
+            }
+        }
+
         if verbose {
-            let status = if outcomes.iter().all(|o| o.result == TestResult::Skip) {
+            let status = if all_skipped {
                 "SKIP"
             } else if test_passed {
                 "PASS"
+            } else if known_failure {
+                "FAIL (known)"
             } else {
                 "FAIL"
             };
@@ -832,10 +1036,12 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
             }
         } else {
             // Progress indicator
-            let c = if outcomes.iter().all(|o| o.result == TestResult::Skip) {
+            let c = if all_skipped {
                 'S'
             } else if test_passed {
                 '.'
+            } else if known_failure {
+                'K'
             } else {
                 'F'
             };
@@ -846,7 +1052,7 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
             io::stdout().flush().ok();
         }
 
-        if stop_on_fail && !test_passed {
+        if stop_on_fail && !test_passed && !known_failure {
             println!("\n\nStopping on first failure.");
             println!("Failed test: {}", relative_path.display());
             for outcome in &outcomes {
@@ -883,9 +1089,60 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         }
     );
     println!("  Duration: {:>5.2}s", total_duration.as_secs_f64());
+
+    // Compare against the known-failure baseline
+    let regressions: Vec<&String> = failing_tests
+        .iter()
+        .filter(|t| !known_failures.contains(*t))
+        .collect();
+    let newly_passing: Vec<&String> = passing_tests
+        .iter()
+        .filter(|t| known_failures.contains(*t))
+        .collect();
+    if known_failures_file.is_some() {
+        println!("───────────────────────────────────────────────────────────────");
+        println!(
+            "  Known failures:  {:>6}",
+            failing_tests.len() - regressions.len()
+        );
+        println!("  Regressions:     {:>6}", regressions.len());
+        println!("  Newly passing:   {:>6}", newly_passing.len());
+    }
     println!("═══════════════════════════════════════════════════════════════");
 
-    if fail_count > 0 || timeout_count > 0 {
+    if known_failures_file.is_some() {
+        for test in &regressions {
+            println!("  REGRESSION: {}", test);
+        }
+        for test in &newly_passing {
+            println!("  NOW PASSING (remove from known failures): {}", test);
+        }
+    }
+
+    if let Some(ref path) = write_passing {
+        write_list_file(path, "test262 tests passing under tsrun", &passing_tests)?;
+        println!(
+            "Wrote {} passing tests to {}",
+            passing_tests.len(),
+            path.display()
+        );
+    }
+    if let Some(ref path) = write_failing {
+        write_list_file(path, "test262 tests failing under tsrun", &failing_tests)?;
+        println!(
+            "Wrote {} failing tests to {}",
+            failing_tests.len(),
+            path.display()
+        );
+    }
+
+    // With a baseline, only regressions of previously passing tests fail the run
+    let failed = if known_failures_file.is_some() {
+        !regressions.is_empty()
+    } else {
+        fail_count > 0 || timeout_count > 0
+    };
+    if failed {
         std::process::exit(1);
     }
 
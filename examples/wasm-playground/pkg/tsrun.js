@@ -11,6 +11,14 @@ export const STEP_SUSPENDED = 3;
 export const STEP_DONE = 4;
 export const STEP_ERROR = 5;
 
+// Incremental prepare status constants (matching TsRunPrepareStatus in Rust)
+export const PREPARE_PENDING = 0;
+export const PREPARE_READY = 1;
+export const PREPARE_ERROR = 2;
+
+// Default parse budget per prepare_finish / prepare_continue call, in source bytes
+export const DEFAULT_PREPARE_BUDGET = 256 * 1024;
+
 // Value type constants (matching TsRunType in Rust)
 export const TYPE_UNDEFINED = 0;
 export const TYPE_NULL = 1;
@@ -20,6 +28,11 @@ export const TYPE_STRING = 4;
 export const TYPE_OBJECT = 5;
 export const TYPE_SYMBOL = 6;
 
+// maybe_collect results other than a cycle's duration in milliseconds
+export const COLLECT_NOTHING_TO_DO = -1;
+export const COLLECT_OVER_BUDGET = -2;
+export const COLLECT_INVALID_CONTEXT = -3;
+
 // Console level constants
 export const CONSOLE_LOG = 0;
 export const CONSOLE_INFO = 1;
@@ -244,6 +257,172 @@ export async function init(wasmPath = 'tsrun.wasm') {
             }
         }
 
+        /**
+         * Start an incremental prepare for a large source.
+         *
+         * Feed the source with prepare_chunk(), then call prepare_finish() and
+         * prepare_continue() until the status is PREPARE_READY, yielding to the
+         * event loop in between. Needed imports are reported by the next step().
+         * @param {string} [filename] - Optional filename for error messages
+         */
+        prepare_begin(filename = 'script.ts') {
+            this[_consoleBuffer] = [];
+            this[_pendingOrders] = [];
+            this[_importRequests] = [];
+
+            const filenameAlloc = filename ? allocString(filename) : { ptr: 0, len: 0 };
+            const resultPtr = this[_wasm].exports.tsrun_alloc(8);
+            if (resultPtr === 0) throw new Error('Failed to allocate result memory');
+
+            try {
+                this[_wasm].exports.tsrun_prepare_begin(resultPtr, this[_context], filenameAlloc.ptr);
+            } finally {
+                this[_wasm].exports.tsrun_dealloc(resultPtr, 8);
+                if (filenameAlloc.ptr) deallocString(filenameAlloc.ptr, filenameAlloc.len + 1);
+            }
+        }
+
+        /**
+         * Append a fragment of the source to an incremental prepare.
+         * @param {string} fragment
+         * @returns {string|null} Error message, or null on success
+         */
+        prepare_chunk(fragment) {
+            const bytes = textEncoder.encode(fragment);
+            const size = Math.max(bytes.length, 1);
+            const dataPtr = this[_wasm].exports.tsrun_alloc(size);
+            const resultPtr = this[_wasm].exports.tsrun_alloc(8);
+            if (dataPtr === 0 || resultPtr === 0) throw new Error('Failed to allocate chunk memory');
+
+            try {
+                new Uint8Array(this[_memory].buffer).set(bytes, dataPtr);
+                this[_wasm].exports.tsrun_prepare_chunk(resultPtr, this[_context], dataPtr, bytes.length);
+
+                const view = getDataView(resultPtr, 8);
+                return view.getUint32(0, true) === 0 ? readString(view.getUint32(4, true)) : null;
+            } finally {
+                this[_wasm].exports.tsrun_dealloc(resultPtr, 8);
+                this[_wasm].exports.tsrun_dealloc(dataPtr, size);
+            }
+        }
+
+        /**
+         * Stop accepting chunks and parse up to about `budget` source bytes.
+         * @param {number} [budget]
+         * @returns {{status: number, parsed_bytes: number, total_bytes: number, error?: string, console_output: Array}}
+         */
+        prepare_finish(budget = DEFAULT_PREPARE_BUDGET) {
+            return this._prepareProgress('tsrun_prepare_finish', budget);
+        }
+
+        /**
+         * Resume an incremental prepare after PREPARE_PENDING.
+         * @param {number} [budget]
+         * @returns {{status: number, parsed_bytes: number, total_bytes: number, error?: string, console_output: Array}}
+         */
+        prepare_continue(budget = DEFAULT_PREPARE_BUDGET) {
+            return this._prepareProgress('tsrun_prepare_continue', budget);
+        }
+
+        /**
+         * Call tsrun_prepare_finish / tsrun_prepare_continue and parse the progress.
+         * @private
+         */
+        _prepareProgress(exportName, budget) {
+            activeConsoleBuffer = this[_consoleBuffer];
+
+            try {
+                // TsRunPrepareProgress (wasm32): status, parsed_bytes, total_bytes, error = 16 bytes
+                const resultPtr = this[_wasm].exports.tsrun_alloc(16);
+                if (resultPtr === 0) throw new Error('Failed to allocate progress memory');
+
+                try {
+                    this[_wasm].exports[exportName](resultPtr, this[_context], budget);
+
+                    const view = getDataView(resultPtr, 16);
+                    const status = view.getUint32(0, true);
+                    const progress = {
+                        status,
+                        parsed_bytes: view.getUint32(4, true),
+                        total_bytes: view.getUint32(8, true),
+                        console_output: this[_consoleBuffer].splice(0)
+                    };
+                    if (status === PREPARE_ERROR) {
+                        progress.error = `Parse error: ${readString(view.getUint32(12, true))}`;
+                    }
+                    return progress;
+                } finally {
+                    this[_wasm].exports.tsrun_dealloc(resultPtr, 16);
+                }
+            } finally {
+                activeConsoleBuffer = null;
+            }
+        }
+
+        /**
+         * Write out the prepared run, before its first step, to set it up
+         * again with import_prepared() in this or another runner.
+         * @returns {{bytes?: Uint8Array, error?: string}}
+         */
+        export_prepared() {
+            // TsRunBytes (wasm32): data, len, error = 12 bytes
+            const resultPtr = this[_wasm].exports.tsrun_alloc(12);
+            if (resultPtr === 0) throw new Error('Failed to allocate result memory');
+
+            try {
+                this[_wasm].exports.tsrun_export_prepared(resultPtr, this[_context]);
+
+                const view = getDataView(resultPtr, 12);
+                const dataPtr = view.getUint32(0, true);
+                const len = view.getUint32(4, true);
+                if (dataPtr === 0) {
+                    return { error: readString(view.getUint32(8, true)) };
+                }
+                const bytes = new Uint8Array(this[_memory].buffer, dataPtr, len).slice();
+                this[_wasm].exports.tsrun_free_bytes(dataPtr, len);
+                return { bytes };
+            } finally {
+                this[_wasm].exports.tsrun_dealloc(resultPtr, 12);
+            }
+        }
+
+        /**
+         * Set up a run written by export_prepared(), in place of prepare().
+         * @param {Uint8Array} bytes
+         * @returns {{status: number, error?: string, console_output: Array}}
+         */
+        import_prepared(bytes) {
+            this[_consoleBuffer] = [];
+            this[_pendingOrders] = [];
+            this[_importRequests] = [];
+
+            const size = Math.max(bytes.length, 1);
+            const dataPtr = this[_wasm].exports.tsrun_alloc(size);
+            const resultPtr = this[_wasm].exports.tsrun_alloc(8);
+            if (dataPtr === 0 || resultPtr === 0) throw new Error('Failed to allocate prepared memory');
+
+            try {
+                new Uint8Array(this[_memory].buffer).set(bytes, dataPtr);
+                this[_wasm].exports.tsrun_import_prepared(resultPtr, this[_context], dataPtr, bytes.length);
+
+                const view = getDataView(resultPtr, 8);
+                if (view.getUint32(0, true) === 0) {
+                    return {
+                        status: STEP_ERROR,
+                        error: readString(view.getUint32(4, true)),
+                        console_output: this[_consoleBuffer].splice(0)
+                    };
+                }
+                return {
+                    status: STEP_CONTINUE,
+                    console_output: this[_consoleBuffer].splice(0)
+                };
+            } finally {
+                this[_wasm].exports.tsrun_dealloc(resultPtr, 8);
+                this[_wasm].exports.tsrun_dealloc(dataPtr, size);
+            }
+        }
+
         /**
          * Execute one step.
          * @returns {{status: number, value_handle?: number, error?: string, console_output: Array}}
@@ -377,16 +556,27 @@ export async function init(wasmPath = 'tsrun.wasm') {
         _parsePendingOrders(ptr, count) {
             if (ptr === 0 || count === 0) return [];
 
-            // TsRunOrder: { id: u64, payload: i32 } = 12 bytes (8 + 4 on wasm32)
+            // TsRunOrder: { id: u64, payload: i32, origin_module: i32,
+            //   origin_function: i32, origin_line: u32, origin_column: u32 }
+            //   = 28 bytes, padded to 32 for the u64 alignment
             const orders = [];
             for (let i = 0; i < count; i++) {
-                const view = getDataView(ptr + i * 12, 12);
+                const view = getDataView(ptr + i * 32, 32);
                 const id = view.getBigUint64(0, true);
                 const payloadPtr = view.getUint32(8, true);
+                const modulePtr = view.getUint32(12, true);
+                const functionPtr = view.getUint32(16, true);
 
                 orders.push({
                     id: Number(id),
-                    payload_handle: payloadPtr
+                    payload_handle: payloadPtr,
+                    // Strings are freed with the step result, so copy them now
+                    origin: {
+                        module: modulePtr ? readString(modulePtr) : null,
+                        functionName: functionPtr ? readString(functionPtr) : null,
+                        line: view.getUint32(20, true),
+                        column: view.getUint32(24, true)
+                    }
                 });
             }
             return orders;
@@ -414,6 +604,16 @@ export async function init(wasmPath = 'tsrun.wasm') {
             return order ? order.payload_handle : 0;
         }
 
+        /**
+         * Get where in the script a pending order was created.
+         * @param {number} orderId
+         * @returns {{module: string|null, functionName: string|null, line: number, column: number}|null}
+         */
+        get_order_origin(orderId) {
+            const order = this[_pendingOrders].find(o => o.id === orderId);
+            return order ? { ...order.origin } : null;
+        }
+
         /**
          * Get import request specifiers (after STEP_NEED_IMPORTS).
          * @returns {string[]}
@@ -791,6 +991,54 @@ export async function init(wasmPath = 'tsrun.wasm') {
             return handle !== 0 && this[_wasm].exports.tsrun_is_function(handle) !== 0;
         }
 
+        /**
+         * Get the identity of the object a value refers to.
+         * Handles to the same object give the same id; primitives give undefined.
+         * @param {number} handle
+         * @returns {bigint|undefined}
+         */
+        value_object_id(handle) {
+            if (handle === 0) return undefined;
+            const id = this[_wasm].exports.tsrun_value_object_id(handle);
+            return id === 0n ? undefined : BigInt.asUintN(64, id);
+        }
+
+        /**
+         * Get a script function's source text and where it was defined.
+         * Returns undefined for native and bound functions and non-functions.
+         * Lines and columns are 1-based; the end is just past the last character.
+         * @param {number} handle
+         * @returns {{text: string, path: string|undefined, startLine: number, startColumn: number, endLine: number, endColumn: number}|undefined}
+         */
+        value_function_source(handle) {
+            if (handle === 0) return undefined;
+
+            // TsRunFunctionSource = { text: i32, path: i32, 4 x u32 } = 24 bytes
+            const sourcePtr = this[_wasm].exports.tsrun_alloc(24);
+            if (sourcePtr === 0) throw new Error('Failed to allocate result memory');
+
+            try {
+                if (!this[_wasm].exports.tsrun_value_function_source(handle, sourcePtr)) {
+                    return undefined;
+                }
+                const view = getDataView(sourcePtr, 24);
+                const textPtr = view.getUint32(0, true);
+                const pathPtr = view.getUint32(4, true);
+                const source = {
+                    text: readString(textPtr),
+                    path: pathPtr === 0 ? undefined : readString(pathPtr),
+                    startLine: view.getUint32(8, true),
+                    startColumn: view.getUint32(12, true),
+                    endLine: view.getUint32(16, true),
+                    endColumn: view.getUint32(20, true),
+                };
+                this[_wasm].exports.tsrun_function_source_free(sourcePtr);
+                return source;
+            } finally {
+                this[_wasm].exports.tsrun_dealloc(sourcePtr, 24);
+            }
+        }
+
         // ═══════════════════════════════════════════════════════════════════════════════
         // Object Operations
         // ═══════════════════════════════════════════════════════════════════════════════
@@ -940,6 +1188,21 @@ export async function init(wasmPath = 'tsrun.wasm') {
             return result;
         }
 
+        // ═══════════════════════════════════════════════════════════════════════════════
+        // Idle-Time GC
+        // ═══════════════════════════════════════════════════════════════════════════════
+
+        /**
+         * Collect garbage if worthwhile and likely to fit in the budget.
+         * Call it in idle gaps, e.g. from requestIdleCallback.
+         * @param {number} budgetMs - Time the cycle may take, in milliseconds
+         * @returns {number} The cycle's duration in milliseconds, or
+         *   COLLECT_NOTHING_TO_DO, COLLECT_OVER_BUDGET or COLLECT_INVALID_CONTEXT
+         */
+        maybe_collect(budgetMs) {
+            return this[_wasm].exports.tsrun_maybe_collect(this[_context], budgetMs);
+        }
+
         // ═══════════════════════════════════════════════════════════════════════════════
         // Value Memory Management
         // ═══════════════════════════════════════════════════════════════════════════════
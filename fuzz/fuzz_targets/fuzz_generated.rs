@@ -0,0 +1,22 @@
+#![no_main]
+
+use fuzz_smoke::capabilities::Capabilities;
+use fuzz_smoke::generator::{Entropy, generate};
+use fuzz_smoke::{Outcome, RunOptions, run_source};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // The bytes steer the generator, so every input is a well-formed program
+    let program = generate(&mut Entropy::from_bytes(data), &Capabilities::all());
+    let script = program.script();
+
+    let outcome = run_source(&script, RunOptions::default());
+    assert!(
+        matches!(outcome, Outcome::Completed(Some(_))),
+        "generated program gave {:?}:\n{}",
+        outcome,
+        script
+    );
+    let stressed = run_source(&script, RunOptions { gc_stress: true });
+    assert_eq!(outcome, stressed, "GC stress changed the result of:\n{}", script);
+});
@@ -45,10 +45,14 @@ case "$TARGET" in
     interpreter|fuzz_interpreter)
         run_target fuzz_interpreter
         ;;
+    generated|fuzz_generated)
+        run_target fuzz_generated
+        ;;
     all)
         run_target fuzz_lexer
         run_target fuzz_parser
         run_target fuzz_interpreter
+        run_target fuzz_generated
         ;;
     list)
         cargo +nightly fuzz list
@@ -62,6 +66,7 @@ case "$TARGET" in
         echo "  lexer|fuzz_lexer       - Fuzz the lexer"
         echo "  parser|fuzz_parser     - Fuzz the parser"
         echo "  interpreter|fuzz_interpreter - Fuzz the full interpreter"
+        echo "  generated|fuzz_generated - Fuzz with generated well-formed programs"
         echo "  all                    - Run all targets (default)"
         echo "  list                   - List available fuzz targets"
         echo ""
@@ -0,0 +1,112 @@
+//! Language features an engine supports, found by running a probe for each.
+//!
+//! The generator only uses features both engines in a comparison support, so
+//! a missing feature shows up once as an absent capability rather than as a
+//! mismatch in every program using it.
+
+/// A feature the generator can do without
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    ArrowFunctions,
+    TemplateLiterals,
+    ExponentOperator,
+    NumericSeparators,
+    IdentifierEscapes,
+    AstralStrings,
+    Spread,
+    ObjectSpread,
+    ObjectFromEntries,
+    ArrayFrom,
+    StringPadding,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 11] = [
+        Feature::ArrowFunctions,
+        Feature::TemplateLiterals,
+        Feature::ExponentOperator,
+        Feature::NumericSeparators,
+        Feature::IdentifierEscapes,
+        Feature::AstralStrings,
+        Feature::Spread,
+        Feature::ObjectSpread,
+        Feature::ObjectFromEntries,
+        Feature::ArrayFrom,
+        Feature::StringPadding,
+    ];
+
+    /// A script completing with `true` where the feature works
+    pub fn probe(self) -> &'static str {
+        match self {
+            Feature::ArrowFunctions => "((a, b) => a + b)(1, 2) === 3",
+            Feature::TemplateLiterals => "`a${1 + 1}b` === 'a2b'",
+            Feature::ExponentOperator => "2 ** 10 === 1024",
+            Feature::NumericSeparators => "1_000 === 1000",
+            Feature::IdentifierEscapes => "const \\u0076x = 1; vx === 1",
+            // Characters past U+FFFF count as two UTF-16 code units
+            Feature::AstralStrings => "'\\u{1F600}'.length === 2",
+            Feature::Spread => "[...[1, 2], 3].length === 3",
+            Feature::ObjectSpread => "({ ...{ a: 1 }, b: 2 }).a === 1",
+            Feature::ObjectFromEntries => "Object.fromEntries([['a', 1]]).a === 1",
+            Feature::ArrayFrom => "Array.from({ length: 2 }, (_, i) => i)[1] === 1",
+            Feature::StringPadding => "'a'.padStart(3, 'b') === 'bba'",
+        }
+    }
+}
+
+/// The features an engine was found to support
+#[derive(Debug, Clone, Default)]
+pub struct Capabilities {
+    features: Vec<Feature>,
+}
+
+impl Capabilities {
+    /// Every feature, for generating without probing
+    pub fn all() -> Self {
+        Self {
+            features: Feature::ALL.to_vec(),
+        }
+    }
+
+    /// The features whose probe `passes`
+    pub fn probe(mut passes: impl FnMut(&str) -> bool) -> Self {
+        Self {
+            features: Feature::ALL
+                .into_iter()
+                .filter(|feature| passes(feature.probe()))
+                .collect(),
+        }
+    }
+
+    /// The features tsrun supports
+    pub fn tsrun() -> Self {
+        Self::probe(|probe| {
+            crate::run_source(probe, crate::RunOptions::default())
+                == crate::Outcome::Completed(Some("true".to_string()))
+        })
+    }
+
+    pub fn has(&self, feature: Feature) -> bool {
+        self.features.contains(&feature)
+    }
+
+    /// The features both `self` and `other` support
+    pub fn intersect(&self, other: &Capabilities) -> Self {
+        Self {
+            features: self
+                .features
+                .iter()
+                .copied()
+                .filter(|feature| other.has(*feature))
+                .collect(),
+        }
+    }
+
+    /// The features not supported
+    pub fn missing(&self) -> Vec<Feature> {
+        Feature::ALL
+            .into_iter()
+            .filter(|feature| !self.has(*feature))
+            .collect()
+    }
+}
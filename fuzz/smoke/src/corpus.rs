@@ -0,0 +1,100 @@
+//! Snippets from the interpreter's test suite.
+//!
+//! The first argument of every `eval`, `eval_result` and `throws_error` call
+//! in `tests/interpreter/*.rs`, as `fuzz/scripts/extract_corpus.py` collects
+//! them to seed the fuzzers. The smoke tests replay them through the
+//! harness, where they run without the test's assertions but with a step
+//! cap and GC stress.
+
+use std::path::{Path, PathBuf};
+
+/// Calls whose first argument is a script
+const CALLS: [&str; 3] = ["eval(", "eval_result(", "throws_error("];
+
+/// The interpreter test directory of this checkout
+pub fn test_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../../tests/interpreter")
+}
+
+/// Every snippet in the `.rs` files of `dir`, in file order, without
+/// duplicates
+pub fn snippets(dir: &Path) -> std::io::Result<Vec<String>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .collect();
+    files.sort();
+
+    let mut seen = std::collections::HashSet::new();
+    let mut snippets = Vec::new();
+    for file in files {
+        let content = std::fs::read_to_string(&file)?;
+        for snippet in extract_snippets(&content) {
+            if !snippet.trim().is_empty() && seen.insert(snippet.clone()) {
+                snippets.push(snippet);
+            }
+        }
+    }
+    Ok(snippets)
+}
+
+/// The string literal arguments opening the calls in [`CALLS`] in `content`
+pub fn extract_snippets(content: &str) -> Vec<String> {
+    let mut snippets = Vec::new();
+    for call in CALLS {
+        let mut rest = content;
+        while let Some(pos) = rest.find(call) {
+            let preceded_by_ident = rest
+                .get(..pos)
+                .and_then(|before| before.chars().next_back())
+                .is_some_and(|c| c.is_alphanumeric() || c == '_');
+            rest = rest.get(pos + call.len()..).unwrap_or_default();
+            if preceded_by_ident {
+                continue;
+            }
+            if let Some(snippet) = string_literal(rest) {
+                snippets.push(snippet);
+            }
+        }
+    }
+    snippets
+}
+
+/// The value of the Rust string literal `text` starts with, if it does
+fn string_literal(text: &str) -> Option<String> {
+    if let Some(raw) = text.strip_prefix('r') {
+        let hashes = raw.chars().take_while(|&c| c == '#').count();
+        let body = raw.get(hashes..)?.strip_prefix('"')?;
+        let end = format!("\"{}", "#".repeat(hashes));
+        return body
+            .find(&end)
+            .and_then(|len| body.get(..len))
+            .map(String::from);
+    }
+
+    let mut chars = text.strip_prefix('"')?.chars().peekable();
+    let mut value = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(value),
+            '\\' => match chars.next()? {
+                'n' => value.push('\n'),
+                't' => value.push('\t'),
+                'r' => value.push('\r'),
+                '0' => value.push('\0'),
+                'x' => {
+                    let hex: String = chars.by_ref().take(2).collect();
+                    value.push(char::from(u8::from_str_radix(&hex, 16).ok()?));
+                }
+                'u' => {
+                    let code: String = chars.by_ref().skip(1).take_while(|&c| c != '}').collect();
+                    value.push(char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+                }
+                // A line continuation skips the newline and leading whitespace
+                '\n' => while chars.next_if(|c| c.is_whitespace()).is_some() {},
+                other => value.push(other),
+            },
+            c => value.push(c),
+        }
+    }
+}
@@ -0,0 +1,867 @@
+//! Small well-formed programs built from a stream of bytes.
+//!
+//! Every choice the generator makes reads the next byte of [`Entropy`], so
+//! the fuzzer's mutations of the input become different programs, and a
+//! seeded [`Entropy`] gives the smoke tests a reproducible stream.
+//!
+//! The programs are typed as they are built: an expression of each type
+//! only uses operations valid for it, calls only functions already declared
+//! and loops a bounded number of times, so a program always completes
+//! without throwing (apart from the `try` blocks that throw on purpose).
+//! Results are pushed onto `out`, which both engines turn into canonical
+//! JSON to compare. Operations whose results engines may legitimately
+//! round differently, such as `Math.sin`, are left out.
+
+use crate::capabilities::{Capabilities, Feature};
+
+/// Where the generator's choices come from
+pub struct Entropy<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    /// xorshift state once `bytes` is used up; 0 gives zeros, so that
+    /// generation from fuzzer input winds down to the simplest choices
+    state: u64,
+}
+
+impl<'a> Entropy<'a> {
+    /// Choices read from fuzzer input, then all zeros
+    pub fn from_bytes(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            pos: 0,
+            state: 0,
+        }
+    }
+
+    /// An endless pseudo-random stream for `seed`
+    pub fn from_seed(seed: u64) -> Entropy<'static> {
+        Entropy {
+            bytes: &[],
+            pos: 0,
+            // Scramble so nearby seeds give unrelated programs
+            state: seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1,
+        }
+    }
+
+    fn byte(&mut self) -> u8 {
+        if let Some(&byte) = self.bytes.get(self.pos) {
+            self.pos += 1;
+            return byte;
+        }
+        if self.state == 0 {
+            return 0;
+        }
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        (self.state >> 24) as u8
+    }
+
+    /// A choice in `0..n`
+    pub fn below(&mut self, n: usize) -> usize {
+        if n <= 1 {
+            return 0;
+        }
+        let value = if n <= 256 {
+            usize::from(self.byte())
+        } else {
+            usize::from(self.byte()) << 8 | usize::from(self.byte())
+        };
+        value % n
+    }
+
+    /// True about `percent` times in a hundred
+    pub fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+
+    /// One of `items`
+    pub fn pick<'t, T>(&mut self, items: &'t [T]) -> Option<&'t T> {
+        items.get(self.below(items.len()))
+    }
+}
+
+/// A generated program whose results are left in `out`
+#[derive(Debug, Clone)]
+pub struct Program {
+    /// Statements declaring and filling `out`
+    pub body: String,
+}
+
+impl Program {
+    /// The program as a script completing with `out`
+    pub fn script(&self) -> String {
+        format!("{}out;\n", self.body)
+    }
+}
+
+/// Types the generator keeps track of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Ty {
+    Num,
+    Str,
+    Bool,
+    /// Array of numbers
+    Arr,
+    /// Object with string keys and number values
+    Obj,
+}
+
+const TYPES: [Ty; 5] = [Ty::Num, Ty::Str, Ty::Bool, Ty::Arr, Ty::Obj];
+
+struct Binding {
+    name: String,
+    ty: Ty,
+    mutable: bool,
+}
+
+/// Generate a program from `entropy`, using only features in `caps`
+pub fn generate(entropy: &mut Entropy<'_>, caps: &Capabilities) -> Program {
+    let mut generator = Generator {
+        entropy,
+        caps,
+        body: String::new(),
+        scope: Vec::new(),
+        functions: Vec::new(),
+        next_id: 0,
+        indent: 0,
+    };
+    generator.line("const out = [];");
+    let statements = 1 + generator.entropy.below(12);
+    for _ in 0..statements {
+        generator.statement(0);
+    }
+    Program {
+        body: generator.body,
+    }
+}
+
+/// Deepest nesting of expressions
+const MAX_DEPTH: usize = 4;
+
+/// Deepest nesting of blocks
+const MAX_BLOCK_DEPTH: usize = 2;
+
+struct Generator<'e, 'a, 'c> {
+    entropy: &'e mut Entropy<'a>,
+    caps: &'c Capabilities,
+    body: String,
+    scope: Vec<Binding>,
+    /// Declared functions taking and returning numbers: name and arity
+    functions: Vec<(String, usize)>,
+    next_id: usize,
+    indent: usize,
+}
+
+impl Generator<'_, '_, '_> {
+    fn has(&self, feature: Feature) -> bool {
+        self.caps.has(feature)
+    }
+
+    fn fresh(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{}{}", prefix, self.next_id)
+    }
+
+    fn line(&mut self, text: &str) {
+        for _ in 0..self.indent {
+            self.body.push_str("  ");
+        }
+        self.body.push_str(text);
+        // Comments and blank lines between statements exercise the lexer
+        match self.entropy.below(12) {
+            0 => self.body.push_str(" // note"),
+            1 => self.body.push_str(" /* block\n   comment */"),
+            2 => self.body.push('\n'),
+            _ => {}
+        }
+        self.body.push('\n');
+    }
+
+    fn variable(&mut self, ty: Ty, mutable_only: bool) -> Option<String> {
+        let candidates: Vec<&Binding> = self
+            .scope
+            .iter()
+            .filter(|binding| binding.ty == ty && (binding.mutable || !mutable_only))
+            .collect();
+        let index = self.entropy.below(candidates.len());
+        candidates.get(index).map(|binding| binding.name.clone())
+    }
+
+    fn block(&mut self, depth: usize, body: impl FnOnce(&mut Self)) {
+        let scope = self.scope.len();
+        self.indent += 1;
+        body(self);
+        let statements = self.entropy.below(3);
+        for _ in 0..statements {
+            self.statement(depth + 1);
+        }
+        self.indent -= 1;
+        self.scope.truncate(scope);
+    }
+
+    fn statement(&mut self, depth: usize) {
+        let nested = depth < MAX_BLOCK_DEPTH;
+        match self.entropy.below(11) {
+            0 | 1 => self.declaration(),
+            2 => self.assignment(),
+            3 if nested => self.for_loop(depth),
+            4 if nested => self.for_of(depth),
+            5 if nested => self.if_else(depth),
+            6 if nested => self.switch(depth),
+            7 if nested => self.try_catch(depth),
+            8 if depth == 0 => self.function(),
+            _ => self.push(),
+        }
+    }
+
+    fn declaration(&mut self) {
+        let ty = *self.entropy.pick(&TYPES).unwrap_or(&Ty::Num);
+        let mutable = self.entropy.chance(50);
+        let escaped = self.has(Feature::IdentifierEscapes) && self.entropy.chance(10);
+        let name = self.fresh(if escaped { "\\u0076" } else { "v" });
+        let value = self.expr(ty, 0);
+        let keyword = if mutable { "let" } else { "const" };
+        self.line(&format!("{} {} = {};", keyword, name, value));
+        // `\u0076` spells `v`
+        let name = name.replace("\\u0076", "v");
+        self.scope.push(Binding { name, ty, mutable });
+    }
+
+    fn assignment(&mut self) {
+        let ty = if self.entropy.chance(60) {
+            Ty::Num
+        } else {
+            Ty::Str
+        };
+        let Some(name) = self.variable(ty, true) else {
+            return self.push();
+        };
+        let text = match ty {
+            Ty::Num => {
+                let op = self.entropy.pick(&["=", "+=", "-=", "*=", "|="]).copied();
+                format!("{} {} {};", name, op.unwrap_or("="), self.expr(Ty::Num, 1))
+            }
+            _ => format!("{} += {};", name, self.expr(Ty::Str, 1)),
+        };
+        self.line(&text);
+    }
+
+    fn push(&mut self) {
+        let ty = *self.entropy.pick(&TYPES).unwrap_or(&Ty::Num);
+        let value = self.expr(ty, 0);
+        self.line(&format!("out.push({});", value));
+    }
+
+    fn for_loop(&mut self, depth: usize) {
+        let index = self.fresh("i");
+        let count = self.entropy.below(5);
+        self.line(&format!(
+            "for (let {i} = 0; {i} < {}; {i}++) {{",
+            count,
+            i = index
+        ));
+        self.block(depth, |g| {
+            g.scope.push(Binding {
+                name: index,
+                ty: Ty::Num,
+                mutable: false,
+            });
+            g.push();
+        });
+        self.line("}");
+    }
+
+    fn for_of(&mut self, depth: usize) {
+        let item = self.fresh("x");
+        let array = self.expr(Ty::Arr, 1);
+        self.line(&format!("for (const {} of {}) {{", item, array));
+        self.block(depth, |g| {
+            g.scope.push(Binding {
+                name: item,
+                ty: Ty::Num,
+                mutable: false,
+            });
+            g.push();
+        });
+        self.line("}");
+    }
+
+    fn if_else(&mut self, depth: usize) {
+        let condition = self.expr(Ty::Bool, 1);
+        self.line(&format!("if ({}) {{", condition));
+        self.block(depth, Self::push);
+        if self.entropy.chance(50) {
+            self.line("} else {");
+            self.block(depth, Self::push);
+        }
+        self.line("}");
+    }
+
+    fn switch(&mut self, depth: usize) {
+        let value = self.expr(Ty::Num, 1);
+        self.line(&format!("switch (Math.abs({}) % 3) {{", value));
+        for case in 0..3 {
+            if case == 2 {
+                self.line("default: {");
+            } else {
+                self.line(&format!("case {}: {{", case));
+            }
+            self.block(depth, Self::push);
+            let end = if self.entropy.chance(70) {
+                "break;"
+            } else {
+                ""
+            };
+            self.line(&format!("}} {}", end));
+        }
+        self.line("}");
+    }
+
+    fn try_catch(&mut self, depth: usize) {
+        let message = self.expr(Ty::Str, 1);
+        let error = self
+            .entropy
+            .pick(&["Error", "TypeError", "RangeError"])
+            .copied();
+        self.line("try {");
+        self.block(depth, |g| g.push());
+        self.indent += 1;
+        self.line(&format!(
+            "throw new {}({});",
+            error.unwrap_or("Error"),
+            message
+        ));
+        self.indent -= 1;
+        self.line("} catch (e) {");
+        self.indent += 1;
+        self.line("out.push(e.name, e.message, e instanceof Error);");
+        self.indent -= 1;
+        self.line("}");
+    }
+
+    fn function(&mut self) {
+        let name = self.fresh("f");
+        let arity = self.entropy.below(3);
+        let params: Vec<String> = (0..arity).map(|i| format!("p{}", i)).collect();
+        let scope = self.scope.len();
+        self.scope.extend(params.iter().map(|param| Binding {
+            name: param.clone(),
+            ty: Ty::Num,
+            mutable: false,
+        }));
+        let result = self.expr(Ty::Num, 1);
+        self.scope.truncate(scope);
+        let arrow = self.has(Feature::ArrowFunctions) && self.entropy.chance(40);
+        if arrow {
+            self.line(&format!(
+                "const {} = ({}) => {};",
+                name,
+                params.join(", "),
+                result
+            ));
+        } else {
+            self.line(&format!(
+                "function {}({}) {{ return {}; }}",
+                name,
+                params.join(", "),
+                result
+            ));
+        }
+        self.functions.push((name, arity));
+    }
+
+    fn expr(&mut self, ty: Ty, depth: usize) -> String {
+        // Past the depth limit, only leaves
+        if depth >= MAX_DEPTH || self.entropy.chance(20) {
+            if self.entropy.chance(50)
+                && let Some(name) = self.variable(ty, false)
+            {
+                return name;
+            }
+            return self.literal(ty);
+        }
+        match ty {
+            Ty::Num => self.num(depth + 1),
+            Ty::Str => self.str(depth + 1),
+            Ty::Bool => self.bool(depth + 1),
+            Ty::Arr => self.arr(depth + 1),
+            Ty::Obj => self.obj(depth + 1),
+        }
+    }
+
+    fn literal(&mut self, ty: Ty) -> String {
+        match ty {
+            Ty::Num => self.number_literal(),
+            Ty::Str => self.string_literal(),
+            Ty::Bool => self
+                .entropy
+                .pick(&["true", "false"])
+                .copied()
+                .unwrap_or("true")
+                .into(),
+            Ty::Arr => {
+                let items: Vec<String> = (0..self.entropy.below(4))
+                    .map(|_| self.number_literal())
+                    .collect();
+                format!("[{}]", items.join(", "))
+            }
+            Ty::Obj => {
+                let value = self.number_literal();
+                format!("{{ k: {} }}", value)
+            }
+        }
+    }
+
+    fn number_literal(&mut self) -> String {
+        let literal = match self.entropy.below(16) {
+            0 => "0".to_string(),
+            1 => format!("{}", self.entropy.below(10)),
+            2 => format!("{}", self.entropy.below(1000)),
+            3 => format!("{}.{}", self.entropy.below(100), self.entropy.below(1000)),
+            4 => format!(".{}", self.entropy.below(100)),
+            5 => format!("{}e{}", 1 + self.entropy.below(9), self.entropy.below(30)),
+            6 => format!("{}e-{}", 1 + self.entropy.below(9), self.entropy.below(12)),
+            7 => format!("0x{:X}", self.entropy.below(65536)),
+            8 => format!("0o{:o}", self.entropy.below(512)),
+            9 => format!("0b{:b}", self.entropy.below(64)),
+            10 if self.has(Feature::NumericSeparators) => {
+                format!(
+                    "{}_{:03}",
+                    1 + self.entropy.below(99),
+                    self.entropy.below(1000)
+                )
+            }
+            11 => self
+                .entropy
+                .pick(&[
+                    "0.1",
+                    "0.2",
+                    "0.3",
+                    "1.5",
+                    "2.5",
+                    "1e21",
+                    "1e-7",
+                    "123456789012345680000",
+                    "9007199254740993",
+                    "4294967296",
+                    "2147483648",
+                    "5e-324",
+                    "1.7976931348623157e308",
+                    "0.000001",
+                    "1e300",
+                ])
+                .copied()
+                .unwrap_or("0.1")
+                .to_string(),
+            12 => format!("{}.", self.entropy.below(50)),
+            _ => format!("{}", self.entropy.below(20)),
+        };
+        // Negative values, parenthesized so member access and `**` parse
+        if self.entropy.chance(20) {
+            format!("(-{})", literal)
+        } else {
+            literal
+        }
+    }
+
+    fn string_literal(&mut self) -> String {
+        const PIECES: [&str; 16] = [
+            "a", "b", "abc", " ", "x1", "10", "é", "Ω", "日本", "-", "_", "A", "Z", "0", "3.5", "",
+        ];
+        const ESCAPES: [&str; 9] = [
+            "\\n",
+            "\\t",
+            "\\\\",
+            "\\\"",
+            "\\'",
+            "\\x41",
+            "\\u00e9",
+            "\\0-",
+            "\\u{1F600}",
+        ];
+        let mut text = String::new();
+        for _ in 0..self.entropy.below(4) {
+            if self.entropy.chance(20) {
+                let escape = *self.entropy.pick(&ESCAPES).unwrap_or(&"\\n");
+                if escape != "\\u{1F600}" || self.has(Feature::AstralStrings) {
+                    text.push_str(escape);
+                }
+            } else {
+                text.push_str(self.entropy.pick(&PIECES).copied().unwrap_or("a"));
+            }
+        }
+        if self.entropy.chance(50) {
+            format!("\"{}\"", text)
+        } else {
+            format!("'{}'", text)
+        }
+    }
+
+    fn num(&mut self, depth: usize) -> String {
+        match self.entropy.below(14) {
+            0 | 1 => {
+                let op = *self
+                    .entropy
+                    .pick(&["+", "-", "*", "/", "%", "&", "|", "^", "<<", ">>", ">>>"])
+                    .unwrap_or(&"+");
+                format!(
+                    "({} {} {})",
+                    self.expr(Ty::Num, depth),
+                    op,
+                    self.expr(Ty::Num, depth)
+                )
+            }
+            2 if self.has(Feature::ExponentOperator) => {
+                // Small integer powers are exact in every engine
+                format!(
+                    "((({} | 0) % 1000) ** {})",
+                    self.expr(Ty::Num, depth),
+                    self.entropy.below(4)
+                )
+            }
+            3 => {
+                let op = *self.entropy.pick(&["-", "+", "~", "!"]).unwrap_or(&"-");
+                let value = self.expr(Ty::Num, depth);
+                if op == "!" {
+                    format!("(+!{})", value)
+                } else {
+                    format!("({}{})", op, value)
+                }
+            }
+            4 => {
+                let func = *self
+                    .entropy
+                    .pick(&[
+                        "floor", "ceil", "round", "trunc", "abs", "sign", "sqrt", "fround",
+                    ])
+                    .unwrap_or(&"floor");
+                format!("Math.{}({})", func, self.expr(Ty::Num, depth))
+            }
+            5 => {
+                let func = *self.entropy.pick(&["max", "min"]).unwrap_or(&"max");
+                format!(
+                    "Math.{}({}, {})",
+                    func,
+                    self.expr(Ty::Num, depth),
+                    self.expr(Ty::Num, depth)
+                )
+            }
+            6 => format!("{}.length", self.expr(Ty::Str, depth)),
+            7 => format!("{}.length", self.expr(Ty::Arr, depth)),
+            8 => {
+                let text = self.number_text();
+                match self.entropy.below(4) {
+                    0 => format!("Number({})", text),
+                    1 => format!("parseFloat({})", text),
+                    2 => format!("parseInt({}, {})", text, 2 + self.entropy.below(35)),
+                    _ => format!("(+{})", text),
+                }
+            }
+            9 => format!(
+                "{}.indexOf({})",
+                self.expr(Ty::Str, depth),
+                self.expr(Ty::Str, depth)
+            ),
+            10 => format!(
+                "{}.charCodeAt({})",
+                self.expr(Ty::Str, depth),
+                self.entropy.below(4)
+            ),
+            11 => format!(
+                "({} ? {} : {})",
+                self.expr(Ty::Bool, depth),
+                self.expr(Ty::Num, depth),
+                self.expr(Ty::Num, depth)
+            ),
+            12 if !self.functions.is_empty() => {
+                let index = self.entropy.below(self.functions.len());
+                let (name, arity) = self.functions.get(index).cloned().unwrap_or_default();
+                let args: Vec<String> = (0..arity).map(|_| self.expr(Ty::Num, depth)).collect();
+                format!("{}({})", name, args.join(", "))
+            }
+            13 => format!("{}.reduce((a, b) => a + b, 0)", self.expr(Ty::Arr, depth)),
+            _ => self.number_literal(),
+        }
+    }
+
+    /// A string holding number-like text, for the string-to-number conversions
+    fn number_text(&mut self) -> String {
+        let text = self
+            .entropy
+            .pick(&[
+                "  42  ",
+                "0x1f",
+                "0b101",
+                "0o17",
+                "1e3",
+                "-1.5e-3",
+                ".5",
+                "5.",
+                "1_000",
+                "Infinity",
+                "-Infinity",
+                "12px",
+                "",
+                " ",
+                "+7",
+                "--1",
+                "0.1e1",
+                "1e1000",
+                "00012",
+                "0.0000001",
+                "9007199254740993",
+                "\\n3\\t",
+            ])
+            .copied()
+            .unwrap_or("42");
+        format!("\"{}\"", text)
+    }
+
+    fn str(&mut self, depth: usize) -> String {
+        match self.entropy.below(14) {
+            0 => format!("String({})", self.expr(Ty::Num, depth)),
+            1 => {
+                let value = self.expr(Ty::Num, depth);
+                match self.entropy.below(5) {
+                    0 => format!("({}).toFixed({})", value, self.entropy.below(21)),
+                    1 => format!("({}).toPrecision({})", value, 1 + self.entropy.below(21)),
+                    2 => format!("({}).toExponential({})", value, self.entropy.below(21)),
+                    3 => format!("({}).toString({})", value, 2 + self.entropy.below(35)),
+                    _ => format!("({}).toString()", value),
+                }
+            }
+            2 if self.has(Feature::TemplateLiterals) => format!(
+                "`{}${{{}}}-${{{}}}`",
+                self.entropy.pick(&["", "n=", "é "]).copied().unwrap_or(""),
+                self.expr(Ty::Num, depth),
+                self.expr(Ty::Str, depth)
+            ),
+            3 => format!(
+                "({} + {})",
+                self.expr(Ty::Str, depth),
+                self.expr(Ty::Str, depth)
+            ),
+            4 => format!(
+                "{}.slice({}, {})",
+                self.expr(Ty::Str, depth),
+                self.entropy.below(5) as isize - 2,
+                self.entropy.below(6)
+            ),
+            5 => {
+                let method = *self
+                    .entropy
+                    .pick(&["toUpperCase", "toLowerCase", "trim", "trimStart", "trimEnd"])
+                    .unwrap_or(&"trim");
+                format!("{}.{}()", self.expr(Ty::Str, depth), method)
+            }
+            6 if self.has(Feature::StringPadding) => {
+                let method = *self
+                    .entropy
+                    .pick(&["padStart", "padEnd"])
+                    .unwrap_or(&"padStart");
+                format!(
+                    "{}.{}({}, {})",
+                    self.expr(Ty::Str, depth),
+                    method,
+                    self.entropy.below(8),
+                    self.string_literal()
+                )
+            }
+            7 => format!(
+                "{}.repeat({})",
+                self.expr(Ty::Str, depth),
+                self.entropy.below(3)
+            ),
+            8 => format!(
+                "{}.join({})",
+                self.expr(Ty::Arr, depth),
+                self.string_literal()
+            ),
+            9 => format!("JSON.stringify({})", self.json_value(depth)),
+            10 => format!(
+                "{}.split({}).join({})",
+                self.expr(Ty::Str, depth),
+                self.string_literal(),
+                self.string_literal()
+            ),
+            11 => format!("(typeof {})", self.any(depth)),
+            12 => format!("Object.keys({}).join()", self.expr(Ty::Obj, depth)),
+            13 => format!(
+                "{}.charAt({})",
+                self.expr(Ty::Str, depth),
+                self.entropy.below(4)
+            ),
+            _ => self.string_literal(),
+        }
+    }
+
+    fn json_value(&mut self, depth: usize) -> String {
+        let ty = *self.entropy.pick(&TYPES).unwrap_or(&Ty::Obj);
+        self.expr(ty, depth)
+    }
+
+    fn any(&mut self, depth: usize) -> String {
+        let ty = *self.entropy.pick(&TYPES).unwrap_or(&Ty::Num);
+        self.expr(ty, depth)
+    }
+
+    fn bool(&mut self, depth: usize) -> String {
+        match self.entropy.below(8) {
+            0 | 1 => {
+                let op = *self
+                    .entropy
+                    .pick(&["<", "<=", ">", ">=", "===", "!==", "==", "!="])
+                    .unwrap_or(&"<");
+                format!(
+                    "({} {} {})",
+                    self.expr(Ty::Num, depth),
+                    op,
+                    self.expr(Ty::Num, depth)
+                )
+            }
+            2 => {
+                let op = *self.entropy.pick(&["<", "===", ">="]).unwrap_or(&"<");
+                format!(
+                    "({} {} {})",
+                    self.expr(Ty::Str, depth),
+                    op,
+                    self.expr(Ty::Str, depth)
+                )
+            }
+            3 => {
+                let method = *self
+                    .entropy
+                    .pick(&["includes", "startsWith", "endsWith"])
+                    .unwrap_or(&"includes");
+                format!(
+                    "{}.{}({})",
+                    self.expr(Ty::Str, depth),
+                    method,
+                    self.expr(Ty::Str, depth)
+                )
+            }
+            4 => {
+                let func = *self
+                    .entropy
+                    .pick(&[
+                        "Number.isInteger",
+                        "Number.isSafeInteger",
+                        "Number.isFinite",
+                        "isNaN",
+                    ])
+                    .unwrap_or(&"isNaN");
+                format!("{}({})", func, self.expr(Ty::Num, depth))
+            }
+            5 => {
+                let op = *self.entropy.pick(&["&&", "||"]).unwrap_or(&"&&");
+                format!(
+                    "({} {} {})",
+                    self.expr(Ty::Bool, depth),
+                    op,
+                    self.expr(Ty::Bool, depth)
+                )
+            }
+            6 => format!("(!{})", self.expr(Ty::Bool, depth)),
+            _ => format!(
+                "{}.includes({})",
+                self.expr(Ty::Arr, depth),
+                self.expr(Ty::Num, depth)
+            ),
+        }
+    }
+
+    fn arr(&mut self, depth: usize) -> String {
+        match self.entropy.below(8) {
+            0 => {
+                let items: Vec<String> = (0..self.entropy.below(4))
+                    .map(|_| self.expr(Ty::Num, depth))
+                    .collect();
+                format!("[{}]", items.join(", "))
+            }
+            1 => {
+                let item = self.fresh("e");
+                let scope = self.scope.len();
+                self.scope.push(Binding {
+                    name: item.clone(),
+                    ty: Ty::Num,
+                    mutable: false,
+                });
+                let mapped = self.expr(Ty::Num, depth);
+                self.scope.truncate(scope);
+                format!(
+                    "{}.map(({}) => {})",
+                    self.expr(Ty::Arr, depth),
+                    item,
+                    mapped
+                )
+            }
+            2 => {
+                let item = self.fresh("e");
+                let scope = self.scope.len();
+                self.scope.push(Binding {
+                    name: item.clone(),
+                    ty: Ty::Num,
+                    mutable: false,
+                });
+                let kept = self.expr(Ty::Bool, depth);
+                self.scope.truncate(scope);
+                format!(
+                    "{}.filter(({}) => {})",
+                    self.expr(Ty::Arr, depth),
+                    item,
+                    kept
+                )
+            }
+            3 => format!(
+                "{}.concat({})",
+                self.expr(Ty::Arr, depth),
+                self.expr(Ty::Arr, depth)
+            ),
+            4 if self.has(Feature::Spread) => format!(
+                "[...{}, {}]",
+                self.expr(Ty::Arr, depth),
+                self.expr(Ty::Num, depth)
+            ),
+            // The default sort compares strings, so NaN can't make it inconsistent
+            5 => format!("{}.slice().sort()", self.expr(Ty::Arr, depth)),
+            6 => format!("{}.slice().reverse()", self.expr(Ty::Arr, depth)),
+            7 if self.has(Feature::ArrayFrom) => format!(
+                "Array.from({{ length: {} }}, (_, i) => i * {})",
+                self.entropy.below(5),
+                self.expr(Ty::Num, depth)
+            ),
+            _ => self.literal(Ty::Arr),
+        }
+    }
+
+    fn obj(&mut self, depth: usize) -> String {
+        match self.entropy.below(4) {
+            0 => {
+                let keys = ["a", "b", "1", "10", "2", "z", "é", "\"x y\"", "0"];
+                let entries: Vec<String> = (0..self.entropy.below(4))
+                    .map(|_| {
+                        let key = *self.entropy.pick(&keys).unwrap_or(&"a");
+                        format!("{}: {}", key, self.expr(Ty::Num, depth))
+                    })
+                    .collect();
+                format!("{{ {} }}", entries.join(", "))
+            }
+            1 if self.has(Feature::ObjectSpread) => format!(
+                "{{ ...{}, c: {} }}",
+                self.expr(Ty::Obj, depth),
+                self.expr(Ty::Num, depth)
+            ),
+            2 if self.has(Feature::ObjectFromEntries) => format!(
+                "Object.fromEntries({}.map((x, i) => [\"k\" + i, x]))",
+                self.expr(Ty::Arr, depth)
+            ),
+            3 => format!(
+                "{{ [{}]: {} }}",
+                self.expr(Ty::Str, depth),
+                self.expr(Ty::Num, depth)
+            ),
+            _ => self.literal(Ty::Obj),
+        }
+    }
+}
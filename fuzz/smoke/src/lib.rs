@@ -0,0 +1,88 @@
+//! Harness shared by the fuzz targets in `fuzz/` and the smoke tests here.
+//!
+//! - [`run_source`] runs any source through `prepare` and `step` with a step
+//!   cap; it must never panic, whatever the input.
+//! - [`generator`] builds small well-formed programs from a stream of bytes,
+//!   so the fuzzer's mutations turn into different programs rather than
+//!   syntax errors.
+//! - [`capabilities`] probes which language features an engine supports, so
+//!   generated programs only use those.
+//! - [`corpus`] extracts the snippets in `tests/interpreter` to replay them
+//!   and to seed fuzzing.
+//! - With the `node-reference` feature, `reference` runs generated programs
+//!   in Node.js to compare their results with tsrun's.
+//!
+//! `cargo test -p fuzz-smoke` runs a fixed number of each so CI catches
+//! regressions without a fuzzing toolchain.
+
+pub mod capabilities;
+pub mod corpus;
+pub mod generator;
+#[cfg(feature = "node-reference")]
+pub mod reference;
+
+use tsrun::{Interpreter, StepResult};
+
+/// Most `step()` calls before a run is stopped
+pub const MAX_STEPS: usize = 100_000;
+
+/// How a run ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// Ran to the end; the canonical JSON of the completion value, or `None`
+    /// when it has no JSON form
+    Completed(Option<String>),
+    /// Failed to parse or threw; the error name, e.g. `TypeError`
+    Failed(String),
+    /// Still running after [`MAX_STEPS`] steps
+    StepLimit,
+    /// Stopped on imports or orders, which the harness doesn't provide
+    Unsupported,
+}
+
+/// How [`run_source`] sets up the interpreter
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunOptions {
+    /// Collect garbage after every allocation, so an object freed while
+    /// still in use fails fast instead of being read after it is reused
+    pub gc_stress: bool,
+}
+
+/// Run `source` as a script in a fresh interpreter
+pub fn run_source(source: &str, options: RunOptions) -> Outcome {
+    let mut interp = Interpreter::new();
+    if options.gc_stress {
+        interp.set_gc_threshold(1);
+    }
+
+    let mut result = interp.prepare(source, None);
+    let mut steps = 0;
+    loop {
+        match result {
+            Ok(StepResult::Continue) => {
+                steps += 1;
+                if steps > MAX_STEPS {
+                    return Outcome::StepLimit;
+                }
+            }
+            Ok(StepResult::Complete(value)) => {
+                return Outcome::Completed(interp.to_canonical_json(&value).ok());
+            }
+            Ok(StepResult::Done) => return Outcome::Completed(None),
+            Ok(_) => return Outcome::Unsupported,
+            Err(err) => return Outcome::Failed(error_name(&err)),
+        }
+        result = interp.step();
+    }
+}
+
+/// The name an error was thrown with, e.g. `RangeError`
+fn error_name(err: &tsrun::JsError) -> String {
+    let message = err.to_string();
+    message
+        .split(':')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
@@ -0,0 +1,143 @@
+//! Node.js as the reference engine for differential testing.
+//!
+//! Scripts are sent in one batch to a single `node` process, which runs
+//! each with a strict indirect `eval` so it gets its own lexical scope and
+//! yields its completion value, as a tsrun script does. Values are written
+//! back as canonical JSON (RFC 8785), the form `Interpreter::to_canonical_json`
+//! gives, so results compare as text.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::Outcome;
+use crate::capabilities::Capabilities;
+
+/// Runs the scripts read as a JSON array from stdin, one result per line:
+/// `=` and canonical JSON, or `!` and the name of the error thrown
+const RUNNER: &str = r#"
+"use strict";
+function canonical(value) {
+  if (value === undefined || typeof value === "function" || typeof value === "symbol") {
+    return undefined;
+  }
+  if (value === null || typeof value !== "object") {
+    return JSON.stringify(value);
+  }
+  if (Array.isArray(value)) {
+    return "[" + value.map((item) => canonical(item) ?? "null").join(",") + "]";
+  }
+  const members = [];
+  for (const key of Object.keys(value).sort()) {
+    const member = canonical(value[key]);
+    if (member !== undefined) {
+      members.push(JSON.stringify(key) + ":" + member);
+    }
+  }
+  return "{" + members.join(",") + "}";
+}
+const scripts = JSON.parse(require("fs").readFileSync(0, "utf8"));
+for (const script of scripts) {
+  let line;
+  try {
+    line = "=" + (canonical((0, eval)('"use strict";\n' + script)) ?? "null");
+  } catch (e) {
+    line = "!" + (e && e.name);
+  }
+  process.stdout.write(line + "\n");
+}
+"#;
+
+/// Whether `node` can be started
+pub fn available() -> bool {
+    Command::new("node")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Run each of `scripts` in Node.js
+pub fn run(scripts: &[String]) -> Result<Vec<Outcome>, String> {
+    let mut child = Command::new("node")
+        .arg("-e")
+        .arg(RUNNER)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("failed to start node: {}", err))?;
+
+    let input = format!(
+        "[{}]",
+        scripts
+            .iter()
+            .map(|script| json_string(script))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    child
+        .stdin
+        .take()
+        .ok_or("node has no stdin")?
+        .write_all(input.as_bytes())
+        .map_err(|err| format!("failed to write to node: {}", err))?;
+
+    let output = child
+        .wait_with_output()
+        .map_err(|err| format!("node failed: {}", err))?;
+    if !output.status.success() {
+        return Err(format!(
+            "node exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let outcomes: Vec<Outcome> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| match line.strip_prefix('!') {
+            Some(name) => Outcome::Failed(name.to_string()),
+            None => Outcome::Completed(line.strip_prefix('=').map(String::from)),
+        })
+        .collect();
+    if outcomes.len() != scripts.len() {
+        return Err(format!(
+            "node gave {} results for {} scripts",
+            outcomes.len(),
+            scripts.len()
+        ));
+    }
+    Ok(outcomes)
+}
+
+/// The features Node.js supports
+pub fn capabilities() -> Result<Capabilities, String> {
+    let probes: Vec<String> = crate::capabilities::Feature::ALL
+        .iter()
+        .map(|feature| feature.probe().to_string())
+        .collect();
+    let outcomes = run(&probes)?;
+    let mut results = outcomes.into_iter();
+    Ok(Capabilities::probe(|_| {
+        results.next() == Some(Outcome::Completed(Some("true".to_string())))
+    }))
+}
+
+/// `text` as a JSON string literal
+fn json_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 => out.push_str(&format!("\\u{:04x}", u32::from(c))),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
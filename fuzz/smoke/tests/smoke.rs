@@ -0,0 +1,153 @@
+//! Fixed-size runs of the fuzz harness, for CI
+
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+use fuzz_smoke::capabilities::Capabilities;
+use fuzz_smoke::generator::{Entropy, Program, generate};
+use fuzz_smoke::{Outcome, RunOptions, corpus, run_source};
+
+/// Generated programs run by each test
+const PROGRAMS: u64 = 400;
+
+#[allow(clippy::panic)]
+fn run_program(program: &Program, options: RunOptions) -> Outcome {
+    let script = program.script();
+    match catch_unwind(AssertUnwindSafe(|| run_source(&script, options))) {
+        Ok(outcome) => outcome,
+        Err(_) => panic!("tsrun panicked on generated program:\n{}", script),
+    }
+}
+
+#[test]
+fn generated_programs_complete() {
+    let caps = Capabilities::tsrun();
+    for seed in 0..PROGRAMS {
+        let program = generate(&mut Entropy::from_seed(seed), &caps);
+        let outcome = run_program(&program, RunOptions::default());
+        assert!(
+            matches!(outcome, Outcome::Completed(Some(_))),
+            "seed {} gave {:?}:\n{}",
+            seed,
+            outcome,
+            program.script()
+        );
+    }
+}
+
+#[test]
+fn generated_programs_under_gc_stress() {
+    let caps = Capabilities::tsrun();
+    for seed in 0..PROGRAMS / 10 {
+        let program = generate(&mut Entropy::from_seed(seed), &caps);
+        let plain = run_program(&program, RunOptions::default());
+        let stressed = run_program(&program, RunOptions { gc_stress: true });
+        assert_eq!(plain, stressed, "seed {}:\n{}", seed, program.script());
+    }
+}
+
+#[test]
+fn fuzzer_bytes_generate_programs() {
+    let caps = Capabilities::all();
+    let inputs: [Vec<u8>; 4] = [
+        Vec::new(),
+        vec![0xff; 256],
+        (0..=255).collect(),
+        b"tsrun fuzzing seed".repeat(8),
+    ];
+    for input in inputs {
+        let program = generate(&mut Entropy::from_bytes(&input), &caps);
+        let outcome = run_program(&program, RunOptions::default());
+        assert!(
+            matches!(outcome, Outcome::Completed(Some(_))),
+            "{:?}:\n{}",
+            outcome,
+            program.script()
+        );
+    }
+}
+
+#[test]
+fn test_suite_snippets_never_panic() {
+    let snippets = corpus::snippets(&corpus::test_dir()).unwrap();
+    assert!(snippets.len() > 1000, "found {} snippets", snippets.len());
+    for (index, snippet) in snippets.iter().enumerate() {
+        // Stressing the GC on every snippet would take minutes
+        let options = RunOptions {
+            gc_stress: index % 16 == 0,
+        };
+        if catch_unwind(AssertUnwindSafe(|| run_source(snippet, options))).is_err() {
+            panic!("tsrun panicked on test suite snippet:\n{}", snippet);
+        }
+    }
+}
+
+#[test]
+fn extract_snippets_reads_rust_literals() {
+    let source = r##"
+        eval("1 + \"2\"\n");
+        eval_result(r#"let a = "x";"#);
+        my_eval("skipped");
+        throws_error("null.x", "TypeError");
+        eval("one \
+              two");
+    "##;
+    assert_eq!(
+        corpus::extract_snippets(source),
+        vec![
+            "1 + \"2\"\n".to_string(),
+            "one two".to_string(),
+            "let a = \"x\";".to_string(),
+            "null.x".to_string(),
+        ]
+    );
+}
+
+#[cfg(feature = "node-reference")]
+#[test]
+fn generated_programs_match_node() {
+    use fuzz_smoke::reference;
+
+    assert!(reference::available(), "node-reference needs node on PATH");
+    let caps = Capabilities::tsrun().intersect(&reference::capabilities().unwrap());
+    let programs: Vec<Program> = (0..PROGRAMS)
+        .map(|seed| generate(&mut Entropy::from_seed(seed), &caps))
+        .collect();
+    let scripts: Vec<String> = programs.iter().map(Program::script).collect();
+    let expected = reference::run(&scripts).unwrap();
+
+    // Set FUZZ_SMOKE_DUMP to a directory to keep the programs that differ
+    let dump = std::env::var_os("FUZZ_SMOKE_DUMP").map(std::path::PathBuf::from);
+    if let Some(dir) = &dump {
+        std::fs::create_dir_all(dir).unwrap();
+    }
+    let mismatches: Vec<String> = programs
+        .iter()
+        .zip(expected)
+        .enumerate()
+        .filter_map(|(seed, (program, expected))| {
+            let actual = run_program(program, RunOptions::default());
+            if actual != expected
+                && let Some(dir) = &dump
+            {
+                let path = dir.join(format!("seed-{}.js", seed));
+                std::fs::write(path, program.script()).unwrap();
+            }
+            (actual != expected).then(|| {
+                format!(
+                    "seed {}: tsrun {:?}, node {:?}\n{}",
+                    seed,
+                    actual,
+                    expected,
+                    program.script()
+                )
+            })
+        })
+        .collect();
+    assert!(
+        mismatches.is_empty(),
+        "{} of {} programs differ from node; first:\n{}",
+        mismatches.len(),
+        PROGRAMS,
+        mismatches.first().cloned().unwrap_or_default()
+    );
+}
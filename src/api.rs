@@ -53,7 +53,21 @@ use crate::value::{self, CheapClone, JsObject, JsValue};
 /// // All objects created with this guard stay alive until guard is dropped
 /// ```
 pub fn create_guard(interp: &Interpreter) -> Guard<JsObject> {
-    interp.heap.create_guard()
+    let guard = interp.heap.create_guard();
+    guard.mark_host();
+    guard
+}
+
+/// The object `value` refers to, failing with a TypeError of `not_object`
+/// for other values and for objects whose interpreter has been dropped
+fn live_object<'a>(value: &'a JsValue, not_object: &str) -> Result<&'a Gc<JsObject>, JsError> {
+    let object = value
+        .as_object()
+        .ok_or_else(|| JsError::type_error(not_object))?;
+    if !object.is_heap_alive() {
+        return Err(JsError::detached_value());
+    }
+    Ok(object)
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -121,9 +135,42 @@ pub fn create_from_json(
     guard: &Guard<JsObject>,
     json: &serde_json::Value,
 ) -> Result<JsValue, JsError> {
+    if interp.detach_host_json {
+        return create_from_json_detached(interp, guard, json);
+    }
     interpreter::builtins::json::json_to_js_value_with_guard(interp, json, guard)
 }
 
+/// Create a JsValue from a JSON value whose objects and arrays all have a
+/// null prototype, so a script handed it reaches no intrinsics through it:
+/// no `constructor`, no `Object.prototype`, no `Function`.
+///
+/// Property access, `for...in`, `for...of` and spread over arrays,
+/// `Object.keys`, object spread and `JSON.stringify` work as usual. Arrays
+/// keep their indices and `length` but have none of the `Array.prototype`
+/// methods; a script that needs them can call `Array.from(list)` first.
+/// [`crate::InterpreterConfig::detach_host_json`] makes
+/// [`create_from_json`] and [`create_response_object`] build detached values.
+///
+/// # Example
+/// ```
+/// use tsrun::{Interpreter, api, JsValue};
+/// use serde_json::json;
+///
+/// let mut interp = Interpreter::new();
+/// let guard = api::create_guard(&interp);
+/// let ctx = api::create_from_json_detached(&mut interp, &guard, &json!({"user": {"id": 7}})).unwrap();
+/// let user = api::get_property(&ctx, "user").unwrap();
+/// assert_eq!(api::get_property(&user, "constructor").unwrap(), JsValue::Undefined);
+/// ```
+pub fn create_from_json_detached(
+    interp: &mut Interpreter,
+    guard: &Guard<JsObject>,
+    json: &serde_json::Value,
+) -> Result<JsValue, JsError> {
+    interpreter::builtins::json::json_to_js_value_detached(interp, json, guard)
+}
+
 /// Create an empty object.
 ///
 /// # Example
@@ -179,9 +226,7 @@ pub fn create_array(interp: &mut Interpreter, guard: &Guard<JsObject>) -> Result
 /// assert_eq!(name.as_str(), Some("Alice"));
 /// ```
 pub fn get_property(obj: &JsValue, key: &str) -> Result<JsValue, JsError> {
-    let object = obj
-        .as_object()
-        .ok_or_else(|| JsError::type_error("Cannot get property of non-object"))?;
+    let object = live_object(obj, "Cannot get property of non-object")?;
 
     let prop_key = value::PropertyKey::String(JsString::from(key));
     let value = {
@@ -208,9 +253,7 @@ pub fn get_property(obj: &JsValue, key: &str) -> Result<JsValue, JsError> {
 /// assert_eq!(first.as_number(), Some(10.0));
 /// ```
 pub fn get_index(arr: &JsValue, index: usize) -> Result<JsValue, JsError> {
-    let object = arr
-        .as_object()
-        .ok_or_else(|| JsError::type_error("Cannot get index of non-object"))?;
+    let object = live_object(arr, "Cannot get index of non-object")?;
 
     let value = {
         let borrowed = object.borrow();
@@ -237,9 +280,7 @@ pub fn get_index(arr: &JsValue, index: usize) -> Result<JsValue, JsError> {
 /// assert_eq!(elements[0].as_number(), Some(1.0));
 /// ```
 pub fn get_elements(arr: &JsValue) -> Result<Vec<JsValue>, JsError> {
-    let object = arr
-        .as_object()
-        .ok_or_else(|| JsError::type_error("Cannot get elements of non-object"))?;
+    let object = live_object(arr, "Cannot get elements of non-object")?;
 
     let elements = {
         let borrowed = object.borrow();
@@ -271,9 +312,7 @@ pub fn get_elements(arr: &JsValue) -> Result<Vec<JsValue>, JsError> {
 /// assert_eq!(api::get_property(&obj, "name").unwrap().as_str(), Some("Alice"));
 /// ```
 pub fn set_property(obj: &JsValue, key: &str, value: JsValue) -> Result<(), JsError> {
-    let object = obj
-        .as_object()
-        .ok_or_else(|| JsError::type_error("Cannot set property on non-object"))?;
+    let object = live_object(obj, "Cannot set property on non-object")?;
 
     let prop_key = value::PropertyKey::String(JsString::from(key));
     object.borrow_mut().set_property(prop_key, value);
@@ -297,9 +336,7 @@ pub fn set_property(obj: &JsValue, key: &str, value: JsValue) -> Result<(), JsEr
 /// assert_eq!(api::get_index(&arr, 1).unwrap().as_number(), Some(20.0));
 /// ```
 pub fn set_index(arr: &JsValue, index: usize, value: JsValue) -> Result<(), JsError> {
-    let object = arr
-        .as_object()
-        .ok_or_else(|| JsError::type_error("Cannot set index on non-object"))?;
+    let object = live_object(arr, "Cannot set index on non-object")?;
 
     let mut borrowed = object.borrow_mut();
 
@@ -339,9 +376,7 @@ pub fn set_index(arr: &JsValue, index: usize, value: JsValue) -> Result<(), JsEr
 /// assert_eq!(api::len(&arr), Some(2));
 /// ```
 pub fn push(arr: &JsValue, value: JsValue) -> Result<(), JsError> {
-    let object = arr
-        .as_object()
-        .ok_or_else(|| JsError::type_error("Cannot push to non-object"))?;
+    let object = live_object(arr, "Cannot push to non-object")?;
 
     let mut borrowed = object.borrow_mut();
 
@@ -389,9 +424,7 @@ pub fn call_method(
     method_name: &str,
     args: &[JsValue],
 ) -> Result<JsValue, JsError> {
-    let object = obj
-        .as_object()
-        .ok_or_else(|| JsError::type_error("Cannot call method on non-object"))?;
+    let object = live_object(obj, "Cannot call method on non-object")?;
 
     // Look up the method from the object's properties and prototype chain
     let prop_key = value::PropertyKey::String(JsString::from(method_name));
@@ -515,7 +548,7 @@ pub fn guard_value(guard: &Guard<JsObject>, value: &JsValue) {
 /// assert_eq!(api::len(&arr), Some(5));
 /// ```
 pub fn len(arr: &JsValue) -> Option<usize> {
-    let obj = arr.as_object()?;
+    let obj = arr.as_object().filter(|obj| obj.is_heap_alive())?;
     let borrowed = obj.borrow();
     borrowed.array_length().map(|l| l as usize)
 }
@@ -542,7 +575,7 @@ pub fn is_empty(arr: &JsValue) -> Option<bool> {
 /// assert!(!api::is_array(&obj));
 /// ```
 pub fn is_array(value: &JsValue) -> bool {
-    if let Some(obj) = value.as_object() {
+    if let Some(obj) = value.as_object().filter(|obj| obj.is_heap_alive()) {
         let borrowed = obj.borrow();
         borrowed.array_length().is_some()
     } else {
@@ -567,7 +600,7 @@ pub fn is_array(value: &JsValue) -> bool {
 /// assert!(keys.contains(&"b".to_string()));
 /// ```
 pub fn keys(obj: &JsValue) -> Vec<String> {
-    if let Some(object) = obj.as_object() {
+    if let Some(object) = obj.as_object().filter(|obj| obj.is_heap_alive()) {
         let borrowed = object.borrow();
         borrowed
             .properties
@@ -684,7 +717,18 @@ pub fn create_response_object(
     json: &serde_json::Value,
 ) -> Result<RuntimeValue, JsError> {
     let guard = interp.heap.create_guard();
-    let value = interpreter::builtins::json::json_to_js_value_with_guard(interp, json, &guard)?;
+    let value = create_from_json(interp, &guard, json)?;
+    Ok(RuntimeValue::with_guard(value, guard))
+}
+
+/// Like [`create_response_object`], with null prototypes all the way down
+/// as by [`create_from_json_detached`]
+pub fn create_response_object_detached(
+    interp: &mut Interpreter,
+    json: &serde_json::Value,
+) -> Result<RuntimeValue, JsError> {
+    let guard = interp.heap.create_guard();
+    let value = create_from_json_detached(interp, &guard, json)?;
     Ok(RuntimeValue::with_guard(value, guard))
 }
 
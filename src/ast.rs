@@ -1,5 +1,7 @@
 //! Abstract Syntax Tree types for TypeScript
 
+pub mod visit;
+
 use crate::lexer::Span;
 use crate::prelude::*;
 use crate::value::JsString;
@@ -9,6 +11,9 @@ use crate::value::JsString;
 pub struct Program {
     pub body: Rc<[Statement]>,
     pub source_type: SourceType,
+    /// Original source text, used to recover function source for `toString()`.
+    /// `None` when function source retention is disabled.
+    pub source: Option<Rc<str>>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -143,6 +148,8 @@ pub enum ClassMember {
     Property(Box<ClassProperty>),
     Constructor(Box<ClassConstructor>),
     StaticBlock(BlockStatement),
+    /// TypeScript index signature `[key: string]: T`, erased at runtime
+    IndexSignature(IndexSignature),
 }
 
 #[derive(Debug, Clone)]
@@ -180,6 +187,8 @@ pub struct ClassProperty {
     pub static_: bool,
     pub readonly: bool,
     pub optional: bool,
+    /// TypeScript definite assignment assertion: `value!: number`
+    pub definite: bool,
     pub accessor: bool,
     pub accessibility: Option<Accessibility>,
     pub decorators: Vec<Decorator>,
@@ -367,10 +376,19 @@ pub struct NamespaceDeclaration {
 pub struct ImportDeclaration {
     pub specifiers: Vec<ImportSpecifier>,
     pub source: StringLiteral,
+    /// `with { ... }` (or `assert { ... }`) after the source
+    pub attributes: Vec<ImportAttribute>,
     pub type_only: bool,
     pub span: Span,
 }
 
+/// One entry of `with { type: "json" }`
+#[derive(Debug, Clone)]
+pub struct ImportAttribute {
+    pub key: JsString,
+    pub value: StringLiteral,
+}
+
 #[derive(Debug, Clone)]
 pub enum ImportSpecifier {
     Named {
@@ -393,6 +411,8 @@ pub struct ExportDeclaration {
     pub declaration: Option<Box<Statement>>,
     pub specifiers: Vec<ExportSpecifier>,
     pub source: Option<StringLiteral>,
+    /// Import attributes after `source`; empty without one
+    pub attributes: Vec<ImportAttribute>,
     /// For `export * as ns from "module"` - the namespace identifier
     pub namespace_export: Option<Identifier>,
     pub default: bool,
@@ -0,0 +1,1327 @@
+//! AST traversal for host-side static analysis
+//!
+//! Implement [`Visitor`] and override the hooks you care about; every default
+//! method calls the matching `walk_*` function, which recurses into all
+//! children. Overrides that still want the children visited call the `walk_*`
+//! function themselves.
+//!
+//! ```
+//! use tsrun::ast::visit::Visitor;
+//! use tsrun::ast::{Expression, Identifier};
+//! use tsrun::parser::Parser;
+//! use tsrun::string_dict::StringDict;
+//!
+//! struct Globals(Vec<String>);
+//!
+//! impl Visitor for Globals {
+//!     fn visit_identifier(&mut self, id: &Identifier) {
+//!         self.0.push(id.name.to_string());
+//!     }
+//! }
+//!
+//! let mut dict = StringDict::new();
+//! let program = Parser::new("eval(x + y.z);", &mut dict).parse_program().unwrap();
+//! let mut globals = Globals(Vec::new());
+//! globals.visit_program(&program);
+//! assert_eq!(globals.0, ["eval", "x", "y"]);
+//! ```
+//!
+//! The walkers destructure every node exhaustively, so adding a node kind or
+//! field to the AST fails to compile here until traversal covers it.
+
+use super::*;
+use crate::lexer::Span;
+
+/// Hooks for each kind of AST node, all defaulting to plain recursion
+pub trait Visitor {
+    fn visit_program(&mut self, program: &Program) {
+        walk_program(self, program);
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        walk_statement(self, statement);
+    }
+
+    fn visit_block_statement(&mut self, block: &BlockStatement) {
+        walk_block_statement(self, block);
+    }
+
+    fn visit_variable_declaration(&mut self, declaration: &VariableDeclaration) {
+        walk_variable_declaration(self, declaration);
+    }
+
+    fn visit_function_declaration(&mut self, function: &FunctionDeclaration) {
+        walk_function_declaration(self, function);
+    }
+
+    fn visit_class_declaration(&mut self, class: &ClassDeclaration) {
+        walk_class_declaration(self, class);
+    }
+
+    fn visit_class_member(&mut self, member: &ClassMember) {
+        walk_class_member(self, member);
+    }
+
+    fn visit_import_declaration(&mut self, import: &ImportDeclaration) {
+        walk_import_declaration(self, import);
+    }
+
+    fn visit_export_declaration(&mut self, export: &ExportDeclaration) {
+        walk_export_declaration(self, export);
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        walk_expression(self, expression);
+    }
+
+    fn visit_call_expression(&mut self, call: &CallExpression) {
+        walk_call_expression(self, call);
+    }
+
+    fn visit_new_expression(&mut self, new: &NewExpression) {
+        walk_new_expression(self, new);
+    }
+
+    fn visit_member_expression(&mut self, member: &MemberExpression) {
+        walk_member_expression(self, member);
+    }
+
+    fn visit_function_expression(&mut self, function: &FunctionExpression) {
+        walk_function_expression(self, function);
+    }
+
+    fn visit_arrow_function(&mut self, arrow: &ArrowFunctionExpression) {
+        walk_arrow_function(self, arrow);
+    }
+
+    fn visit_class_expression(&mut self, class: &ClassExpression) {
+        walk_class_expression(self, class);
+    }
+
+    fn visit_template_literal(&mut self, template: &TemplateLiteral) {
+        walk_template_literal(self, template);
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        walk_pattern(self, pattern);
+    }
+
+    fn visit_function_param(&mut self, param: &FunctionParam) {
+        walk_function_param(self, param);
+    }
+
+    fn visit_object_property_key(&mut self, key: &ObjectPropertyKey) {
+        walk_object_property_key(self, key);
+    }
+
+    fn visit_decorator(&mut self, decorator: &Decorator) {
+        walk_decorator(self, decorator);
+    }
+
+    fn visit_type_annotation(&mut self, ty: &TypeAnnotation) {
+        walk_type_annotation(self, ty);
+    }
+
+    /// An identifier used as a value, e.g. `x` in `f(x)`. Property names,
+    /// labels and binding names are not references.
+    fn visit_identifier(&mut self, _id: &Identifier) {}
+
+    /// A name introduced by a declaration, parameter or destructuring pattern
+    fn visit_binding_identifier(&mut self, _id: &Identifier) {}
+
+    fn visit_literal(&mut self, _literal: &Literal) {}
+
+    /// A string literal outside expressions: module specifiers and property keys
+    fn visit_string_literal(&mut self, _literal: &StringLiteral) {}
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Statements
+// ═══════════════════════════════════════════════════════════════════════════════
+
+pub fn walk_program<V: Visitor + ?Sized>(v: &mut V, program: &Program) {
+    let Program {
+        body,
+        source_type: _,
+        source: _,
+    } = program;
+    for statement in body.iter() {
+        v.visit_statement(statement);
+    }
+}
+
+pub fn walk_statements<V: Visitor + ?Sized>(v: &mut V, statements: &[Statement]) {
+    for statement in statements {
+        v.visit_statement(statement);
+    }
+}
+
+pub fn walk_statement<V: Visitor + ?Sized>(v: &mut V, statement: &Statement) {
+    match statement {
+        Statement::VariableDeclaration(declaration) => v.visit_variable_declaration(declaration),
+        Statement::FunctionDeclaration(function) => v.visit_function_declaration(function),
+        Statement::ClassDeclaration(class) => v.visit_class_declaration(class),
+        Statement::TypeAlias(alias) => {
+            let TypeAliasDeclaration {
+                id,
+                type_parameters,
+                type_annotation,
+                span: _,
+            } = alias.as_ref();
+            v.visit_binding_identifier(id);
+            walk_type_parameters(v, type_parameters.as_ref());
+            v.visit_type_annotation(type_annotation);
+        }
+        Statement::InterfaceDeclaration(interface) => {
+            let InterfaceDeclaration {
+                id,
+                type_parameters,
+                extends,
+                body,
+                span: _,
+            } = interface.as_ref();
+            v.visit_binding_identifier(id);
+            walk_type_parameters(v, type_parameters.as_ref());
+            for reference in extends {
+                walk_type_reference(v, reference);
+            }
+            for member in body {
+                walk_type_member(v, member);
+            }
+        }
+        Statement::EnumDeclaration(declaration) => {
+            let EnumDeclaration {
+                id,
+                members,
+                const_: _,
+                span: _,
+            } = declaration.as_ref();
+            v.visit_binding_identifier(id);
+            for EnumMember {
+                id: _,
+                initializer,
+                span: _,
+            } in members
+            {
+                if let Some(initializer) = initializer {
+                    v.visit_expression(initializer);
+                }
+            }
+        }
+        Statement::NamespaceDeclaration(namespace) => {
+            let NamespaceDeclaration { id, body, span: _ } = namespace.as_ref();
+            v.visit_binding_identifier(id);
+            walk_statements(v, body);
+        }
+        Statement::Block(block) => v.visit_block_statement(block),
+        Statement::If(IfStatement {
+            test,
+            consequent,
+            alternate,
+            span: _,
+        }) => {
+            v.visit_expression(test);
+            v.visit_statement(consequent);
+            if let Some(alternate) = alternate {
+                v.visit_statement(alternate);
+            }
+        }
+        Statement::Switch(SwitchStatement {
+            discriminant,
+            cases,
+            span: _,
+        }) => {
+            v.visit_expression(discriminant);
+            for SwitchCase {
+                test,
+                consequent,
+                span: _,
+            } in cases.iter()
+            {
+                if let Some(test) = test {
+                    v.visit_expression(test);
+                }
+                walk_statements(v, consequent);
+            }
+        }
+        Statement::For(for_statement) => {
+            let ForStatement {
+                init,
+                test,
+                update,
+                body,
+                span: _,
+            } = for_statement.as_ref();
+            match init {
+                Some(ForInit::Variable(declaration)) => v.visit_variable_declaration(declaration),
+                Some(ForInit::Expression(expression)) => v.visit_expression(expression),
+                None => {}
+            }
+            if let Some(test) = test {
+                v.visit_expression(test);
+            }
+            if let Some(update) = update {
+                v.visit_expression(update);
+            }
+            v.visit_statement(body);
+        }
+        Statement::ForIn(for_in) => {
+            let ForInStatement {
+                left,
+                right,
+                body,
+                span: _,
+            } = for_in.as_ref();
+            walk_for_in_of_left(v, left);
+            v.visit_expression(right);
+            v.visit_statement(body);
+        }
+        Statement::ForOf(for_of) => {
+            let ForOfStatement {
+                left,
+                right,
+                body,
+                await_: _,
+                span: _,
+            } = for_of.as_ref();
+            walk_for_in_of_left(v, left);
+            v.visit_expression(right);
+            v.visit_statement(body);
+        }
+        Statement::While(WhileStatement {
+            test,
+            body,
+            span: _,
+        }) => {
+            v.visit_expression(test);
+            v.visit_statement(body);
+        }
+        Statement::DoWhile(DoWhileStatement {
+            body,
+            test,
+            span: _,
+        }) => {
+            v.visit_statement(body);
+            v.visit_expression(test);
+        }
+        Statement::Try(try_statement) => {
+            let TryStatement {
+                block,
+                handler,
+                finalizer,
+                span: _,
+            } = try_statement.as_ref();
+            v.visit_block_statement(block);
+            if let Some(CatchClause {
+                param,
+                body,
+                span: _,
+            }) = handler
+            {
+                if let Some(param) = param {
+                    v.visit_pattern(param);
+                }
+                v.visit_block_statement(body);
+            }
+            if let Some(finalizer) = finalizer {
+                v.visit_block_statement(finalizer);
+            }
+        }
+        Statement::Return(ReturnStatement { argument, span: _ }) => {
+            if let Some(argument) = argument {
+                v.visit_expression(argument);
+            }
+        }
+        Statement::Break(BreakStatement { label: _, span: _ })
+        | Statement::Continue(ContinueStatement { label: _, span: _ }) => {}
+        Statement::Throw(ThrowStatement { argument, span: _ }) => v.visit_expression(argument),
+        Statement::Import(import) => v.visit_import_declaration(import),
+        Statement::Export(export) => v.visit_export_declaration(export),
+        Statement::Expression(ExpressionStatement {
+            expression,
+            span: _,
+        }) => v.visit_expression(expression),
+        Statement::Empty | Statement::Debugger => {}
+        Statement::Labeled(LabeledStatement {
+            label: _,
+            body,
+            span: _,
+        }) => v.visit_statement(body),
+    }
+}
+
+pub fn walk_block_statement<V: Visitor + ?Sized>(v: &mut V, block: &BlockStatement) {
+    let BlockStatement { body, span: _ } = block;
+    walk_statements(v, body);
+}
+
+pub fn walk_variable_declaration<V: Visitor + ?Sized>(
+    v: &mut V,
+    declaration: &VariableDeclaration,
+) {
+    let VariableDeclaration {
+        kind: _,
+        declarations,
+        span: _,
+    } = declaration;
+    for VariableDeclarator {
+        id,
+        type_annotation,
+        init,
+        span: _,
+    } in declarations.iter()
+    {
+        v.visit_pattern(id);
+        if let Some(ty) = type_annotation {
+            v.visit_type_annotation(ty);
+        }
+        if let Some(init) = init {
+            v.visit_expression(init);
+        }
+    }
+}
+
+fn walk_for_in_of_left<V: Visitor + ?Sized>(v: &mut V, left: &ForInOfLeft) {
+    match left {
+        ForInOfLeft::Variable(declaration) => v.visit_variable_declaration(declaration),
+        ForInOfLeft::Pattern(pattern) => v.visit_pattern(pattern),
+    }
+}
+
+pub fn walk_function_declaration<V: Visitor + ?Sized>(v: &mut V, function: &FunctionDeclaration) {
+    let FunctionDeclaration {
+        id,
+        params,
+        return_type,
+        type_parameters,
+        body,
+        generator: _,
+        async_: _,
+        span: _,
+    } = function;
+    if let Some(id) = id {
+        v.visit_binding_identifier(id);
+    }
+    walk_type_parameters(v, type_parameters.as_ref());
+    for param in params.iter() {
+        v.visit_function_param(param);
+    }
+    if let Some(ty) = return_type {
+        v.visit_type_annotation(ty);
+    }
+    v.visit_block_statement(body);
+}
+
+pub fn walk_function_param<V: Visitor + ?Sized>(v: &mut V, param: &FunctionParam) {
+    let FunctionParam {
+        pattern,
+        type_annotation,
+        optional: _,
+        decorators,
+        accessibility: _,
+        readonly: _,
+        span: _,
+    } = param;
+    for decorator in decorators {
+        v.visit_decorator(decorator);
+    }
+    v.visit_pattern(pattern);
+    if let Some(ty) = type_annotation {
+        v.visit_type_annotation(ty);
+    }
+}
+
+pub fn walk_class_declaration<V: Visitor + ?Sized>(v: &mut V, class: &ClassDeclaration) {
+    let ClassDeclaration {
+        id,
+        type_parameters,
+        super_class,
+        implements,
+        body,
+        decorators,
+        abstract_: _,
+        span: _,
+    } = class;
+    for decorator in decorators {
+        v.visit_decorator(decorator);
+    }
+    if let Some(id) = id {
+        v.visit_binding_identifier(id);
+    }
+    walk_class_parts(
+        v,
+        type_parameters.as_ref(),
+        super_class.as_deref(),
+        implements,
+        body,
+    );
+}
+
+pub fn walk_class_expression<V: Visitor + ?Sized>(v: &mut V, class: &ClassExpression) {
+    let ClassExpression {
+        id,
+        type_parameters,
+        super_class,
+        implements,
+        body,
+        decorators,
+        span: _,
+    } = class;
+    for decorator in decorators {
+        v.visit_decorator(decorator);
+    }
+    if let Some(id) = id {
+        v.visit_binding_identifier(id);
+    }
+    walk_class_parts(
+        v,
+        type_parameters.as_ref(),
+        super_class.as_deref(),
+        implements,
+        body,
+    );
+}
+
+fn walk_class_parts<V: Visitor + ?Sized>(
+    v: &mut V,
+    type_parameters: Option<&TypeParameters>,
+    super_class: Option<&Expression>,
+    implements: &[TypeReference],
+    body: &ClassBody,
+) {
+    walk_type_parameters(v, type_parameters);
+    if let Some(super_class) = super_class {
+        v.visit_expression(super_class);
+    }
+    for reference in implements {
+        walk_type_reference(v, reference);
+    }
+    let ClassBody { members, span: _ } = body;
+    for member in members {
+        v.visit_class_member(member);
+    }
+}
+
+pub fn walk_class_member<V: Visitor + ?Sized>(v: &mut V, member: &ClassMember) {
+    match member {
+        ClassMember::Method(method) => {
+            let ClassMethod {
+                key,
+                value,
+                kind: _,
+                computed: _,
+                static_: _,
+                accessibility: _,
+                decorators,
+                span: _,
+            } = method.as_ref();
+            for decorator in decorators {
+                v.visit_decorator(decorator);
+            }
+            v.visit_object_property_key(key);
+            v.visit_function_expression(value);
+        }
+        ClassMember::Property(property) => {
+            let ClassProperty {
+                key,
+                value,
+                type_annotation,
+                computed: _,
+                static_: _,
+                readonly: _,
+                optional: _,
+                definite: _,
+                accessor: _,
+                accessibility: _,
+                decorators,
+                span: _,
+            } = property.as_ref();
+            for decorator in decorators {
+                v.visit_decorator(decorator);
+            }
+            v.visit_object_property_key(key);
+            if let Some(ty) = type_annotation {
+                v.visit_type_annotation(ty);
+            }
+            if let Some(value) = value {
+                v.visit_expression(value);
+            }
+        }
+        ClassMember::Constructor(constructor) => {
+            let ClassConstructor {
+                params,
+                body,
+                accessibility: _,
+                span: _,
+            } = constructor.as_ref();
+            for param in params {
+                v.visit_function_param(param);
+            }
+            v.visit_block_statement(body);
+        }
+        ClassMember::StaticBlock(block) => v.visit_block_statement(block),
+        ClassMember::IndexSignature(IndexSignature {
+            key: _,
+            key_type,
+            value_type,
+            readonly: _,
+            span: _,
+        }) => {
+            v.visit_type_annotation(key_type);
+            v.visit_type_annotation(value_type);
+        }
+    }
+}
+
+pub fn walk_decorator<V: Visitor + ?Sized>(v: &mut V, decorator: &Decorator) {
+    let Decorator {
+        expression,
+        span: _,
+    } = decorator;
+    v.visit_expression(expression);
+}
+
+pub fn walk_import_declaration<V: Visitor + ?Sized>(v: &mut V, import: &ImportDeclaration) {
+    let ImportDeclaration {
+        specifiers,
+        source,
+        attributes: _,
+        type_only: _,
+        span: _,
+    } = import;
+    for specifier in specifiers {
+        match specifier {
+            ImportSpecifier::Named {
+                local,
+                imported: _,
+                span: _,
+            }
+            | ImportSpecifier::Default { local, span: _ }
+            | ImportSpecifier::Namespace { local, span: _ } => v.visit_binding_identifier(local),
+        }
+    }
+    v.visit_string_literal(source);
+}
+
+pub fn walk_export_declaration<V: Visitor + ?Sized>(v: &mut V, export: &ExportDeclaration) {
+    let ExportDeclaration {
+        declaration,
+        specifiers,
+        source,
+        attributes: _,
+        namespace_export: _,
+        default: _,
+        type_only: _,
+        span: _,
+    } = export;
+    if let Some(declaration) = declaration {
+        v.visit_statement(declaration);
+    }
+    // `export { a }` references a local binding; with a source it names the
+    // other module's export instead
+    if source.is_none() {
+        for ExportSpecifier {
+            local,
+            exported: _,
+            span: _,
+        } in specifiers
+        {
+            v.visit_identifier(local);
+        }
+    }
+    if let Some(source) = source {
+        v.visit_string_literal(source);
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Expressions
+// ═══════════════════════════════════════════════════════════════════════════════
+
+pub fn walk_expression<V: Visitor + ?Sized>(v: &mut V, expression: &Expression) {
+    match expression {
+        Expression::Literal(literal) => v.visit_literal(literal),
+        Expression::Array(ArrayExpression { elements, span: _ }) => {
+            for element in elements.iter().flatten() {
+                match element {
+                    ArrayElement::Expression(expression) => v.visit_expression(expression),
+                    ArrayElement::Spread(spread) => walk_spread(v, spread),
+                }
+            }
+        }
+        Expression::Object(ObjectExpression {
+            properties,
+            span: _,
+        }) => {
+            for property in properties {
+                match property {
+                    ObjectProperty::Property(property) => {
+                        let Property {
+                            key,
+                            value,
+                            kind: _,
+                            computed: _,
+                            shorthand: _,
+                            method: _,
+                            span: _,
+                        } = property.as_ref();
+                        v.visit_object_property_key(key);
+                        v.visit_expression(value);
+                    }
+                    ObjectProperty::Spread(spread) => walk_spread(v, spread),
+                }
+            }
+        }
+        Expression::Function(function) => v.visit_function_expression(function),
+        Expression::ArrowFunction(arrow) => v.visit_arrow_function(arrow),
+        Expression::Class(class) => v.visit_class_expression(class),
+        Expression::Template(template) => v.visit_template_literal(template),
+        Expression::TaggedTemplate(tagged) => {
+            let TaggedTemplateExpression {
+                tag,
+                quasi,
+                span: _,
+            } = tagged.as_ref();
+            v.visit_expression(tag);
+            v.visit_template_literal(quasi);
+        }
+        Expression::Identifier(id) => v.visit_identifier(id),
+        Expression::This(_) | Expression::Super(_) => {}
+        Expression::Unary(UnaryExpression {
+            operator: _,
+            argument,
+            prefix: _,
+            span: _,
+        }) => v.visit_expression(argument),
+        Expression::Binary(BinaryExpression {
+            operator: _,
+            left,
+            right,
+            span: _,
+        }) => {
+            v.visit_expression(left);
+            v.visit_expression(right);
+        }
+        Expression::Logical(LogicalExpression {
+            operator: _,
+            left,
+            right,
+            span: _,
+        }) => {
+            v.visit_expression(left);
+            v.visit_expression(right);
+        }
+        Expression::Conditional(ConditionalExpression {
+            test,
+            consequent,
+            alternate,
+            span: _,
+        }) => {
+            v.visit_expression(test);
+            v.visit_expression(consequent);
+            v.visit_expression(alternate);
+        }
+        Expression::Assignment(assignment) => {
+            let AssignmentExpression {
+                operator: _,
+                left,
+                right,
+                span: _,
+            } = assignment.as_ref();
+            match left {
+                AssignmentTarget::Identifier(id) => v.visit_identifier(id),
+                AssignmentTarget::Member(member) => v.visit_member_expression(member),
+                AssignmentTarget::Pattern(pattern) => v.visit_pattern(pattern),
+            }
+            v.visit_expression(right);
+        }
+        Expression::Update(UpdateExpression {
+            operator: _,
+            argument,
+            prefix: _,
+            span: _,
+        }) => v.visit_expression(argument),
+        Expression::Sequence(SequenceExpression {
+            expressions,
+            span: _,
+        }) => {
+            for expression in expressions {
+                v.visit_expression(expression);
+            }
+        }
+        Expression::Member(member) => v.visit_member_expression(member),
+        Expression::OptionalChain(OptionalChainExpression { base, span: _ }) => {
+            v.visit_expression(base)
+        }
+        Expression::Call(call) => v.visit_call_expression(call),
+        Expression::New(new) => v.visit_new_expression(new),
+        Expression::TypeAssertion(TypeAssertionExpression {
+            expression,
+            type_annotation,
+            span: _,
+        }) => {
+            v.visit_expression(expression);
+            v.visit_type_annotation(type_annotation);
+        }
+        Expression::NonNull(NonNullExpression {
+            expression,
+            span: _,
+        }) => v.visit_expression(expression),
+        Expression::Spread(spread) => walk_spread(v, spread),
+        Expression::Yield(YieldExpression {
+            argument,
+            delegate: _,
+            span: _,
+        }) => {
+            if let Some(argument) = argument {
+                v.visit_expression(argument);
+            }
+        }
+        Expression::Await(AwaitExpression { argument, span: _ }) => v.visit_expression(argument),
+        Expression::Parenthesized(expression, _) => v.visit_expression(expression),
+    }
+}
+
+fn walk_spread<V: Visitor + ?Sized>(v: &mut V, spread: &SpreadElement) {
+    let SpreadElement { argument, span: _ } = spread;
+    v.visit_expression(argument);
+}
+
+fn walk_arguments<V: Visitor + ?Sized>(v: &mut V, arguments: &[Argument]) {
+    for argument in arguments {
+        match argument {
+            Argument::Expression(expression) => v.visit_expression(expression),
+            Argument::Spread(spread) => walk_spread(v, spread),
+        }
+    }
+}
+
+pub fn walk_call_expression<V: Visitor + ?Sized>(v: &mut V, call: &CallExpression) {
+    let CallExpression {
+        callee,
+        arguments,
+        type_arguments,
+        optional: _,
+        span: _,
+    } = call;
+    v.visit_expression(callee);
+    walk_type_arguments(v, type_arguments.as_ref());
+    walk_arguments(v, arguments);
+}
+
+pub fn walk_new_expression<V: Visitor + ?Sized>(v: &mut V, new: &NewExpression) {
+    let NewExpression {
+        callee,
+        arguments,
+        type_arguments,
+        span: _,
+    } = new;
+    v.visit_expression(callee);
+    walk_type_arguments(v, type_arguments.as_ref());
+    walk_arguments(v, arguments);
+}
+
+pub fn walk_member_expression<V: Visitor + ?Sized>(v: &mut V, member: &MemberExpression) {
+    let MemberExpression {
+        object,
+        property,
+        computed: _,
+        optional: _,
+        span: _,
+    } = member;
+    v.visit_expression(object);
+    match property {
+        MemberProperty::Expression(expression) => v.visit_expression(expression),
+        MemberProperty::Identifier(_) | MemberProperty::PrivateIdentifier(_) => {}
+    }
+}
+
+pub fn walk_function_expression<V: Visitor + ?Sized>(v: &mut V, function: &FunctionExpression) {
+    let FunctionExpression {
+        id,
+        params,
+        return_type,
+        type_parameters,
+        body,
+        generator: _,
+        async_: _,
+        span: _,
+    } = function;
+    if let Some(id) = id {
+        v.visit_binding_identifier(id);
+    }
+    walk_type_parameters(v, type_parameters.as_ref());
+    for param in params.iter() {
+        v.visit_function_param(param);
+    }
+    if let Some(ty) = return_type {
+        v.visit_type_annotation(ty);
+    }
+    v.visit_block_statement(body);
+}
+
+pub fn walk_arrow_function<V: Visitor + ?Sized>(v: &mut V, arrow: &ArrowFunctionExpression) {
+    let ArrowFunctionExpression {
+        params,
+        return_type,
+        type_parameters,
+        body,
+        async_: _,
+        span: _,
+    } = arrow;
+    walk_type_parameters(v, type_parameters.as_ref());
+    for param in params.iter() {
+        v.visit_function_param(param);
+    }
+    if let Some(ty) = return_type {
+        v.visit_type_annotation(ty);
+    }
+    match body.as_ref() {
+        ArrowFunctionBody::Expression(expression) => v.visit_expression(expression),
+        ArrowFunctionBody::Block(block) => v.visit_block_statement(block),
+    }
+}
+
+pub fn walk_template_literal<V: Visitor + ?Sized>(v: &mut V, template: &TemplateLiteral) {
+    let TemplateLiteral {
+        quasis: _,
+        expressions,
+        span: _,
+    } = template;
+    for expression in expressions {
+        v.visit_expression(expression);
+    }
+}
+
+pub fn walk_object_property_key<V: Visitor + ?Sized>(v: &mut V, key: &ObjectPropertyKey) {
+    match key {
+        ObjectPropertyKey::Identifier(_) | ObjectPropertyKey::PrivateIdentifier(_) => {}
+        ObjectPropertyKey::String(literal) => v.visit_string_literal(literal),
+        ObjectPropertyKey::Number(literal) => v.visit_literal(literal),
+        ObjectPropertyKey::Computed(expression) => v.visit_expression(expression),
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Patterns
+// ═══════════════════════════════════════════════════════════════════════════════
+
+pub fn walk_pattern<V: Visitor + ?Sized>(v: &mut V, pattern: &Pattern) {
+    match pattern {
+        Pattern::Identifier(id) => v.visit_binding_identifier(id),
+        Pattern::Object(ObjectPattern {
+            properties,
+            type_annotation,
+            span: _,
+        }) => {
+            for property in properties {
+                match property {
+                    ObjectPatternProperty::KeyValue {
+                        key,
+                        value,
+                        shorthand: _,
+                        span: _,
+                    } => {
+                        v.visit_object_property_key(key);
+                        v.visit_pattern(value);
+                    }
+                    ObjectPatternProperty::Rest(rest) => walk_rest(v, rest),
+                }
+            }
+            if let Some(ty) = type_annotation {
+                v.visit_type_annotation(ty);
+            }
+        }
+        Pattern::Array(ArrayPattern {
+            elements,
+            type_annotation,
+            span: _,
+        }) => {
+            for element in elements.iter().flatten() {
+                v.visit_pattern(element);
+            }
+            if let Some(ty) = type_annotation {
+                v.visit_type_annotation(ty);
+            }
+        }
+        Pattern::Rest(rest) => walk_rest(v, rest),
+        Pattern::Assignment(AssignmentPattern {
+            left,
+            right,
+            span: _,
+        }) => {
+            v.visit_pattern(left);
+            v.visit_expression(right);
+        }
+    }
+}
+
+fn walk_rest<V: Visitor + ?Sized>(v: &mut V, rest: &RestElement) {
+    let RestElement {
+        argument,
+        type_annotation,
+        span: _,
+    } = rest;
+    v.visit_pattern(argument);
+    if let Some(ty) = type_annotation {
+        v.visit_type_annotation(ty);
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Types
+// ═══════════════════════════════════════════════════════════════════════════════
+
+pub fn walk_type_annotation<V: Visitor + ?Sized>(v: &mut V, ty: &TypeAnnotation) {
+    match ty {
+        TypeAnnotation::Keyword(TypeKeyword {
+            keyword: _,
+            span: _,
+        })
+        | TypeAnnotation::Literal(TypeLiteral { value: _, span: _ })
+        | TypeAnnotation::This => {}
+        TypeAnnotation::Reference(reference) => walk_type_reference(v, reference),
+        TypeAnnotation::Object(ObjectType { members, span: _ }) => {
+            for member in members {
+                walk_type_member(v, member);
+            }
+        }
+        TypeAnnotation::Array(ArrayType {
+            element_type,
+            span: _,
+        }) => v.visit_type_annotation(element_type),
+        TypeAnnotation::Tuple(TupleType {
+            element_types: types,
+            span: _,
+        })
+        | TypeAnnotation::Union(UnionType { types, span: _ })
+        | TypeAnnotation::Intersection(IntersectionType { types, span: _ })
+        | TypeAnnotation::TemplateLiteral(TemplateLiteralType {
+            quasis: _,
+            types,
+            span: _,
+        }) => {
+            for ty in types {
+                v.visit_type_annotation(ty);
+            }
+        }
+        TypeAnnotation::Function(FunctionType {
+            params,
+            return_type,
+            type_parameters,
+            span: _,
+        }) => {
+            walk_type_parameters(v, type_parameters.as_ref());
+            for param in params {
+                v.visit_function_param(param);
+            }
+            v.visit_type_annotation(return_type);
+        }
+        TypeAnnotation::Conditional(ConditionalType {
+            check_type,
+            extends_type,
+            true_type,
+            false_type,
+            span: _,
+        }) => {
+            v.visit_type_annotation(check_type);
+            v.visit_type_annotation(extends_type);
+            v.visit_type_annotation(true_type);
+            v.visit_type_annotation(false_type);
+        }
+        TypeAnnotation::Infer(InferType {
+            type_parameter,
+            span: _,
+        }) => walk_type_parameter(v, type_parameter),
+        TypeAnnotation::Mapped(MappedType {
+            type_parameter,
+            name_type,
+            type_annotation,
+            readonly: _,
+            optional: _,
+            span: _,
+        }) => {
+            walk_type_parameter(v, type_parameter);
+            if let Some(ty) = name_type {
+                v.visit_type_annotation(ty);
+            }
+            if let Some(ty) = type_annotation {
+                v.visit_type_annotation(ty);
+            }
+        }
+        TypeAnnotation::Indexed(IndexedAccessType {
+            object_type,
+            index_type,
+            span: _,
+        }) => {
+            v.visit_type_annotation(object_type);
+            v.visit_type_annotation(index_type);
+        }
+        // `typeof x` in a type position reads the value binding `x`
+        TypeAnnotation::Typeof(TypeofType {
+            expression,
+            span: _,
+        }) => v.visit_identifier(expression),
+        TypeAnnotation::Keyof(KeyofType {
+            type_annotation,
+            span: _,
+        }) => v.visit_type_annotation(type_annotation),
+        TypeAnnotation::TypePredicate(TypePredicateType {
+            parameter_name: _,
+            type_annotation,
+            asserts: _,
+            span: _,
+        }) => {
+            if let Some(ty) = type_annotation {
+                v.visit_type_annotation(ty);
+            }
+        }
+        TypeAnnotation::Parenthesized(ty) => v.visit_type_annotation(ty),
+    }
+}
+
+fn walk_type_reference<V: Visitor + ?Sized>(v: &mut V, reference: &TypeReference) {
+    let TypeReference {
+        name: _,
+        type_arguments,
+        span: _,
+    } = reference;
+    walk_type_arguments(v, type_arguments.as_ref());
+}
+
+fn walk_type_arguments<V: Visitor + ?Sized>(v: &mut V, arguments: Option<&TypeArguments>) {
+    if let Some(TypeArguments { params, span: _ }) = arguments {
+        for ty in params {
+            v.visit_type_annotation(ty);
+        }
+    }
+}
+
+fn walk_type_parameters<V: Visitor + ?Sized>(v: &mut V, parameters: Option<&TypeParameters>) {
+    if let Some(TypeParameters { params, span: _ }) = parameters {
+        for parameter in params {
+            walk_type_parameter(v, parameter);
+        }
+    }
+}
+
+fn walk_type_parameter<V: Visitor + ?Sized>(v: &mut V, parameter: &TypeParameter) {
+    let TypeParameter {
+        name: _,
+        constraint,
+        default,
+        span: _,
+    } = parameter;
+    if let Some(ty) = constraint {
+        v.visit_type_annotation(ty);
+    }
+    if let Some(ty) = default {
+        v.visit_type_annotation(ty);
+    }
+}
+
+fn walk_type_member<V: Visitor + ?Sized>(v: &mut V, member: &TypeMember) {
+    match member {
+        TypeMember::Property(PropertySignature {
+            key,
+            type_annotation,
+            optional: _,
+            readonly: _,
+            span: _,
+        }) => {
+            v.visit_object_property_key(key);
+            if let Some(ty) = type_annotation {
+                v.visit_type_annotation(ty);
+            }
+        }
+        TypeMember::Method(MethodSignature {
+            key,
+            params,
+            return_type,
+            type_parameters,
+            optional: _,
+            span: _,
+        }) => {
+            v.visit_object_property_key(key);
+            walk_signature(v, params, return_type.as_deref(), type_parameters.as_ref());
+        }
+        TypeMember::Index(IndexSignature {
+            key: _,
+            key_type,
+            value_type,
+            readonly: _,
+            span: _,
+        }) => {
+            v.visit_type_annotation(key_type);
+            v.visit_type_annotation(value_type);
+        }
+        TypeMember::Call(CallSignature {
+            params,
+            return_type,
+            type_parameters,
+            span: _,
+        })
+        | TypeMember::Construct(ConstructSignature {
+            params,
+            return_type,
+            type_parameters,
+            span: _,
+        }) => walk_signature(v, params, return_type.as_deref(), type_parameters.as_ref()),
+    }
+}
+
+fn walk_signature<V: Visitor + ?Sized>(
+    v: &mut V,
+    params: &[FunctionParam],
+    return_type: Option<&TypeAnnotation>,
+    type_parameters: Option<&TypeParameters>,
+) {
+    walk_type_parameters(v, type_parameters);
+    for param in params {
+        v.visit_function_param(param);
+    }
+    if let Some(ty) = return_type {
+        v.visit_type_annotation(ty);
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Built-in analyses
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// How a module specifier is referenced
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportKind {
+    /// `import ... from "x"` or `import "x"`
+    Static,
+    /// `import("x")` with a string literal (or substitution-free template)
+    Dynamic,
+    /// `export ... from "x"`
+    ReExport,
+}
+
+/// A module specifier found by [`collect_imports`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportInfo {
+    pub specifier: String,
+    pub kind: ImportKind,
+    /// `import type` / `export type` - erased at runtime
+    pub type_only: bool,
+    /// Span of the import/export statement or `import()` call
+    pub span: Span,
+}
+
+/// Every module specifier in `program`, in source order, without executing it.
+///
+/// Dynamic imports whose argument isn't a literal are skipped.
+pub fn collect_imports(program: &Program) -> Vec<ImportInfo> {
+    struct Imports(Vec<ImportInfo>);
+
+    impl Visitor for Imports {
+        fn visit_import_declaration(&mut self, import: &ImportDeclaration) {
+            self.0.push(ImportInfo {
+                specifier: import.source.value.to_string(),
+                kind: ImportKind::Static,
+                type_only: import.type_only,
+                span: import.span,
+            });
+        }
+
+        fn visit_export_declaration(&mut self, export: &ExportDeclaration) {
+            if let Some(source) = &export.source {
+                self.0.push(ImportInfo {
+                    specifier: source.value.to_string(),
+                    kind: ImportKind::ReExport,
+                    type_only: export.type_only,
+                    span: export.span,
+                });
+            }
+            walk_export_declaration(self, export);
+        }
+
+        fn visit_call_expression(&mut self, call: &CallExpression) {
+            if matches!(call.callee.as_ref(), Expression::Identifier(id) if id.name.as_str() == "import")
+                && let Some(specifier) = call.arguments.first().and_then(literal_specifier)
+            {
+                self.0.push(ImportInfo {
+                    specifier,
+                    kind: ImportKind::Dynamic,
+                    type_only: false,
+                    span: call.span,
+                });
+            }
+            walk_call_expression(self, call);
+        }
+    }
+
+    let mut imports = Imports(Vec::new());
+    imports.visit_program(program);
+    imports.0
+}
+
+fn literal_specifier(argument: &Argument) -> Option<String> {
+    match argument {
+        Argument::Expression(Expression::Literal(literal)) => match &literal.value {
+            LiteralValue::String(s) => Some(s.to_string()),
+            _ => None,
+        },
+        Argument::Expression(Expression::Template(template)) if template.expressions.is_empty() => {
+            Some(
+                template
+                    .quasis
+                    .iter()
+                    .map(|quasi| quasi.value.as_str())
+                    .collect(),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// Spans of every call whose callee is `name` - a plain identifier such as
+/// `"fetch"` or a dotted member path such as `"console.log"`.
+///
+/// Calls through optional chaining (`a?.b()`) match too; computed members
+/// (`a["b"]()`) and aliases don't.
+pub fn collect_call_targets(program: &Program, name: &str) -> Vec<Span> {
+    struct CallTargets<'a> {
+        name: &'a str,
+        spans: Vec<Span>,
+    }
+
+    impl Visitor for CallTargets<'_> {
+        fn visit_call_expression(&mut self, call: &CallExpression) {
+            if callee_path(&call.callee).is_some_and(|path| path == self.name) {
+                self.spans.push(call.span);
+            }
+            walk_call_expression(self, call);
+        }
+    }
+
+    let mut targets = CallTargets {
+        name,
+        spans: Vec::new(),
+    };
+    targets.visit_program(program);
+    targets.spans
+}
+
+/// `a.b.c` for a chain of non-computed member accesses on an identifier
+fn callee_path(expression: &Expression) -> Option<String> {
+    match expression {
+        Expression::Identifier(id) => Some(id.name.to_string()),
+        Expression::Member(member) if !member.computed => match &member.property {
+            MemberProperty::Identifier(property) => {
+                let object = callee_path(&member.object)?;
+                Some(format!("{}.{}", object, property.name))
+            }
+            MemberProperty::Expression(_) | MemberProperty::PrivateIdentifier(_) => None,
+        },
+        Expression::OptionalChain(chain) => callee_path(&chain.base),
+        Expression::Parenthesized(inner, _)
+        | Expression::NonNull(NonNullExpression {
+            expression: inner, ..
+        }) => callee_path(inner),
+        _ => None,
+    }
+}
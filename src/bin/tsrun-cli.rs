@@ -0,0 +1,438 @@
+//! Run a TypeScript file and print its completion value
+//!
+//! Usage: tsrun-cli [options] <entry-point.ts>
+//!
+//! Options:
+//!   --yaml                     Print the completion value as YAML instead of JSON
+//!   --context <file.json>      Expose the parsed file as `context` from "tsrun:env"
+//!   --order-handler <type>=exec:<command>
+//!                              Answer orders whose payload has this `type` by
+//!                              running `command` with `sh -c`: the payload is
+//!                              written to its stdin as JSON and its stdout is
+//!                              parsed as the JSON response. Repeatable.
+//!   --timeout <ms>             Stop the script after this much wall-clock time
+//!   --max-memory <bytes>       Heap budget (see `InterpreterConfig::heap_budget`)
+//!   --fuel <units>             Instruction budget (see `Interpreter::set_fuel`)
+//!
+//! Relative and absolute imports are read from disk, trying the same
+//! candidates as the in-memory module source map: the path as written, then
+//! `.ts`, then `/index.ts`.
+//!
+//! Exit status: 0 on success, 1 when the script fails (the error is printed
+//! with the source line it points at), 2 for usage errors.
+
+use rustc_hash::FxHashMap;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, Stdio};
+use std::time::Duration;
+use tsrun::{
+    HeapBudget, ImportRequest, InternalModule, Interpreter, InterpreterConfig, JsError, JsValue,
+    ModuleCandidate, ModulePath, Order, OrderResponse, RuntimeValue, SourceText, StepResult,
+    TerminationReason, api,
+    error::{SourceLocation, StackFrame},
+};
+
+/// Exit status for a script that failed to parse or threw
+const EXIT_SCRIPT_ERROR: u8 = 1;
+/// Exit status for bad arguments or unreadable inputs
+const EXIT_USAGE: u8 = 2;
+
+const USAGE: &str = "Usage: tsrun-cli [--yaml] [--context <file.json>] \
+[--order-handler <type>=exec:<command>]... [--timeout <ms>] [--max-memory <bytes>] \
+[--fuel <units>] <entry-point.ts>";
+
+/// CLI configuration
+struct Config {
+    entry_path: PathBuf,
+    yaml: bool,
+    context: Option<PathBuf>,
+    /// Shell command answering each order type
+    order_handlers: FxHashMap<String, String>,
+    timeout_ms: Option<u64>,
+    max_memory: Option<usize>,
+    fuel: Option<u64>,
+}
+
+fn main() -> ExitCode {
+    let config = match parse_args(env::args().skip(1)) {
+        Ok(config) => config,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+    let mut runner = match Runner::new(&config) {
+        Ok(runner) => runner,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+    match runner.run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::from(EXIT_SCRIPT_ERROR)
+        }
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{} requires a value", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("{} must be a non-negative integer, got '{}'", flag, value))
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Config, String> {
+    let mut args = args.into_iter();
+    let mut entry_arg = None;
+    let mut yaml = false;
+    let mut context = None;
+    let mut order_handlers = FxHashMap::default();
+    let mut timeout_ms = None;
+    let mut max_memory = None;
+    let mut fuel = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--yaml" => yaml = true,
+            "--context" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| "--context requires a file".to_string())?;
+                context = Some(PathBuf::from(path));
+            }
+            "--order-handler" => {
+                let spec = args
+                    .next()
+                    .ok_or_else(|| "--order-handler requires <type>=exec:<command>".to_string())?;
+                let (order_type, command) = spec
+                    .split_once("=exec:")
+                    .filter(|(order_type, command)| !order_type.is_empty() && !command.is_empty())
+                    .ok_or_else(|| {
+                        format!(
+                            "--order-handler must look like <type>=exec:<command>, got '{}'",
+                            spec
+                        )
+                    })?;
+                order_handlers.insert(order_type.to_string(), command.to_string());
+            }
+            "--timeout" => timeout_ms = Some(parse_number("--timeout", args.next())?),
+            "--max-memory" => max_memory = Some(parse_number("--max-memory", args.next())?),
+            "--fuel" => fuel = Some(parse_number("--fuel", args.next())?),
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            flag if flag.starts_with('-') => {
+                return Err(format!("Unknown option: {}\n{}", flag, USAGE));
+            }
+            _ if entry_arg.is_some() => {
+                return Err(format!("Unexpected argument: {}\n{}", arg, USAGE));
+            }
+            _ => entry_arg = Some(arg),
+        }
+    }
+
+    let entry_arg = entry_arg.ok_or_else(|| USAGE.to_string())?;
+    Ok(Config {
+        entry_path: absolute(Path::new(&entry_arg)),
+        yaml,
+        context,
+        order_handlers,
+        timeout_ms,
+        max_memory,
+        fuel,
+    })
+}
+
+/// `path` made absolute against the working directory
+fn absolute(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    env::current_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join(path)
+}
+
+/// One script run and the state the host side keeps for it
+struct Runner<'a> {
+    config: &'a Config,
+    /// Keeps the deep-frozen context exported by "tsrun:env" alive; declared
+    /// before `interp` so it is released first
+    _context: RuntimeValue,
+    interp: Interpreter,
+    /// Module path of the entry point
+    entry: String,
+    entry_source: String,
+    /// Source of every file loaded, by module path, for error snippets
+    sources: FxHashMap<String, String>,
+}
+
+impl<'a> Runner<'a> {
+    fn new(config: &'a Config) -> Result<Self, String> {
+        let entry_source = fs::read_to_string(&config.entry_path)
+            .map_err(|e| format!("Cannot read {}: {}", config.entry_path.display(), e))?;
+        let context_json = match &config.context {
+            Some(path) => {
+                let text = fs::read_to_string(path)
+                    .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+                serde_json::from_str(&text)
+                    .map_err(|e| format!("Invalid JSON in {}: {}", path.display(), e))?
+            }
+            None => serde_json::json!({}),
+        };
+
+        let mut interp = Interpreter::with_config(InterpreterConfig {
+            heap_budget: config.max_memory.map(HeapBudget::new),
+            ..Default::default()
+        });
+        interp.set_fuel(config.fuel);
+        if let Some(timeout_ms) = config.timeout_ms {
+            let handle = interp.interrupt_handle();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(timeout_ms));
+                handle.interrupt();
+            });
+        }
+
+        let context = interp
+            .create_from_json_cached(&context_json)
+            .map_err(|e| format!("Cannot use context: {}", e))?;
+        interp.register_internal_module(
+            InternalModule::native("tsrun:env")
+                .with_value("context", context.value().clone())
+                .build(),
+        );
+
+        Ok(Self {
+            config,
+            interp,
+            entry: config.entry_path.display().to_string(),
+            entry_source,
+            sources: FxHashMap::default(),
+            _context: context,
+        })
+    }
+
+    fn run(&mut self) -> Result<(), String> {
+        self.sources
+            .insert(self.entry.clone(), self.entry_source.clone());
+        let prepared = self.interp.prepare(
+            &self.entry_source,
+            Some(ModulePath::new(self.entry.as_str())),
+        );
+        let mut result = prepared.map_err(|e| self.render_error(&e))?;
+        loop {
+            result = match result {
+                StepResult::Continue => self.step()?,
+                StepResult::Complete(value) => return self.print_value(&value),
+                StepResult::Done => return Ok(()),
+                StepResult::NeedImports(requests) => {
+                    for req in &requests {
+                        self.provide_import(req)?;
+                    }
+                    self.step()?
+                }
+                StepResult::Suspended { pending, .. } | StepResult::Orders(pending) => {
+                    if pending.is_empty() && self.interp.pending_order_count() == 0 {
+                        return Err("Script is waiting on a promise nothing will settle".into());
+                    }
+                    let responses = pending
+                        .iter()
+                        .map(|order| self.handle_order(order))
+                        .collect();
+                    self.interp.fulfill_orders(responses);
+                    self.step()?
+                }
+            };
+        }
+    }
+
+    fn step(&mut self) -> Result<StepResult, String> {
+        self.interp.step().map_err(|e| self.render_error(&e))
+    }
+
+    /// Read the file an import refers to, trying each module candidate
+    fn provide_import(&mut self, req: &ImportRequest) -> Result<(), String> {
+        if ModulePath::is_bare(&req.specifier) {
+            return Err(format!(
+                "Cannot import '{}': package imports are not supported",
+                req.specifier
+            ));
+        }
+        let requested = req.resolved_path.as_str();
+        let mut found = None;
+        for candidate in ModuleCandidate::defaults() {
+            let (path, hint) = match candidate {
+                ModuleCandidate::Exact => (requested.to_string(), None),
+                ModuleCandidate::Suffix(suffix) => (format!("{}{}", requested, suffix), None),
+                ModuleCandidate::Unsupported { suffix, hint } => {
+                    (format!("{}{}", requested, suffix), Some(hint))
+                }
+            };
+            if !Path::new(&path).is_file() {
+                continue;
+            }
+            if let Some(hint) = hint {
+                return Err(format!(
+                    "Cannot import '{}': found '{}', but {}",
+                    req.specifier, path, hint
+                ));
+            }
+            found = Some(path);
+            break;
+        }
+        let importer = req
+            .importer
+            .as_ref()
+            .map_or("the entry point", |path| path.as_str());
+        let file = found.ok_or_else(|| {
+            format!(
+                "Cannot find module '{}' imported by {}",
+                req.specifier, importer
+            )
+        })?;
+        let source =
+            fs::read_to_string(&file).map_err(|e| format!("Cannot read {}: {}", file, e))?;
+        let provided = self.interp.provide_module_file(
+            req.resolved_path.clone(),
+            ModulePath::new(file.as_str()),
+            &source,
+        );
+        self.sources.insert(file, source);
+        provided.map_err(|e| self.render_error(&e))
+    }
+
+    /// Answer an order with its type's handler, rejecting it if there is none
+    /// or the handler fails
+    fn handle_order(&mut self, order: &Order) -> OrderResponse {
+        let result = self.run_order_handler(order).and_then(|json| {
+            api::create_response_object(&mut self.interp, &json)
+                .map_err(|e| handler_error(e.to_string()))
+        });
+        OrderResponse {
+            id: order.id,
+            result,
+        }
+    }
+
+    fn run_order_handler(&self, order: &Order) -> Result<serde_json::Value, JsError> {
+        let payload = tsrun::js_value_to_json(order.payload.value())
+            .map_err(|e| handler_error(format!("Cannot send order payload: {}", e)))?;
+        let order_type = payload
+            .get("type")
+            .and_then(|t| t.as_str())
+            .unwrap_or_default();
+        let command =
+            self.config.order_handlers.get(order_type).ok_or_else(|| {
+                handler_error(format!("No handler for order type '{}'", order_type))
+            })?;
+
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| handler_error(format!("Cannot run '{}': {}", command, e)))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // A handler that exits without reading its input is not an error
+            let _ = stdin.write_all(payload.to_string().as_bytes());
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|e| handler_error(format!("Cannot run '{}': {}", command, e)))?;
+        if !output.status.success() {
+            return Err(handler_error(format!(
+                "Order handler '{}' failed ({}): {}",
+                command,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        serde_json::from_slice(&output.stdout).map_err(|e| {
+            handler_error(format!(
+                "Order handler '{}' printed invalid JSON: {}",
+                command, e
+            ))
+        })
+    }
+
+    fn print_value(&self, value: &RuntimeValue) -> Result<(), String> {
+        if matches!(value.value(), JsValue::Undefined) {
+            return Ok(());
+        }
+        let text = if self.config.yaml {
+            self.interp
+                .to_yaml(value)
+                .map_err(|e| format!("Cannot print the result as YAML: {}", e))?
+        } else {
+            let json = tsrun::js_value_to_json(value.value())
+                .map_err(|e| format!("Cannot print the result as JSON: {}", e))?;
+            serde_json::to_string_pretty(&json)
+                .map_err(|e| format!("Cannot print the result as JSON: {}", e))?
+        };
+        println!("{}", text.trim_end());
+        Ok(())
+    }
+
+    /// The error with the source line it points at, when that file was loaded
+    fn render_error(&self, err: &JsError) -> String {
+        if let JsError::Terminated {
+            reason: TerminationReason::Interrupted,
+        } = err
+            && let Some(timeout_ms) = self.config.timeout_ms
+        {
+            return format!("Execution timed out after {}ms", timeout_ms);
+        }
+        let mut message = err.to_string().trim_end().to_string();
+        if message.contains("\n -->") {
+            // The snippet was attached when the error was raised
+            return message;
+        }
+        let location = err.location().cloned().or_else(|| match err {
+            JsError::RuntimeError { stack, .. } => stack.iter().find_map(frame_location),
+            _ => None,
+        });
+        let snippet = location.and_then(|location| {
+            let file = location.file.as_deref().unwrap_or(&self.entry);
+            let source = self.sources.get(file)?;
+            SourceText::new(source).snippet(&location)
+        });
+        if let Some(snippet) = snippet {
+            message.push('\n');
+            message.push_str(&snippet);
+        }
+        message
+    }
+}
+
+/// Where a stack frame points, if it names a file and line
+fn frame_location(frame: &StackFrame) -> Option<SourceLocation> {
+    if frame.line == 0 {
+        return None;
+    }
+    Some(SourceLocation {
+        file: Some(frame.file.clone()?),
+        line: frame.line,
+        column: frame.column,
+        length: 1,
+        offset: None,
+        snippet: None,
+    })
+}
+
+/// An `Error` rejecting an order the CLI could not answer
+fn handler_error(message: String) -> JsError {
+    JsError::RuntimeError {
+        kind: "Error".to_string(),
+        message,
+        stack: Vec::new(),
+        properties: None,
+        flight_log: None,
+    }
+}
@@ -5,16 +5,19 @@
 //! Options:
 //!   --timeout <ms>     Maximum execution time in milliseconds (default: unlimited)
 //!   --max-depth <n>    Maximum call stack depth (default: unlimited)
+//!   --allow-dynamic-code  Allow `new Function(...)` and indirect eval
 //!
 //! Supports static imports - modules are resolved relative to the importing file.
 //! Nested imports are supported.
+//!
+//! Set `TSRUN_DUMP_BYTECODE=1` to print the bytecode of every compiled chunk.
 
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::Instant;
-use tsrun::{Interpreter, JsValue, ModulePath, StepResult};
+use tsrun::{Interpreter, InterpreterConfig, JsValue, ModulePath, StepResult};
 
 /// Minimal package.json representation for module resolution
 #[derive(serde::Deserialize)]
@@ -60,6 +63,7 @@ struct Config {
     entry_path: PathBuf,
     timeout_ms: Option<u64>,
     max_depth: Option<usize>,
+    allow_dynamic_code: bool,
 }
 
 fn parse_args() -> Result<Config, String> {
@@ -68,6 +72,7 @@ fn parse_args() -> Result<Config, String> {
 
     let mut timeout_ms: Option<u64> = None;
     let mut max_depth: Option<usize> = None;
+    let mut allow_dynamic_code = false;
     let mut entry_arg: Option<&str> = None;
 
     let mut i = 1;
@@ -91,6 +96,8 @@ fn parse_args() -> Result<Config, String> {
                     .parse::<usize>()
                     .map_err(|_| "--max-depth must be a positive integer".to_string())?,
             );
+        } else if arg == "--allow-dynamic-code" {
+            allow_dynamic_code = true;
         } else if arg.starts_with('-') {
             return Err(format!("Unknown option: {}", arg));
         } else {
@@ -101,7 +108,7 @@ fn parse_args() -> Result<Config, String> {
 
     let entry_arg = entry_arg.ok_or_else(|| {
         format!(
-            "Usage: {} [--timeout <ms>] [--max-depth <n>] <entry-point.ts>",
+            "Usage: {} [--timeout <ms>] [--max-depth <n>] [--allow-dynamic-code] <entry-point.ts>",
             program_name
         )
     })?;
@@ -120,6 +127,7 @@ fn parse_args() -> Result<Config, String> {
         entry_path,
         timeout_ms,
         max_depth,
+        allow_dynamic_code,
     })
 }
 
@@ -138,7 +146,11 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
     let source = fs::read_to_string(&entry_path)
         .map_err(|e| format!("Cannot read {}: {}", entry_path.display(), e))?;
 
-    let mut interp = Interpreter::new();
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        allow_dynamic_code: config.allow_dynamic_code,
+        dump_bytecode: env::var_os("TSRUN_DUMP_BYTECODE").is_some(),
+        ..Default::default()
+    });
     // Allow overriding GC threshold via environment variable for stress testing
     if let Ok(threshold_str) = std::env::var("GC_THRESHOLD") {
         if let Ok(threshold) = threshold_str.parse::<usize>() {
@@ -286,7 +298,7 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
                     }
                     step_result = interp.step().map_err(|e| format!("{}", e))?;
                 }
-                StepResult::Suspended { pending, .. } => {
+                StepResult::Suspended { pending, .. } | StepResult::Orders(pending) => {
                     return Err(format!(
                         "Async operations not supported in CLI (pending orders: {})",
                         pending.len()
@@ -321,7 +333,7 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
                         )?;
                     }
                 }
-                StepResult::Suspended { pending, .. } => {
+                StepResult::Suspended { pending, .. } | StepResult::Orders(pending) => {
                     return Err(format!(
                         "Async operations not supported in CLI (pending orders: {})",
                         pending.len()
@@ -551,7 +563,7 @@ fn print_value(value: &JsValue) {
 
 fn value_to_json(value: &JsValue) -> Result<serde_json::Value, &'static str> {
     use tsrun::Gc;
-    use tsrun::value::JsObject;
+    use tsrun::value::{ExoticObject, JsObject};
 
     fn to_json_inner(
         value: &JsValue,
@@ -584,6 +596,18 @@ fn value_to_json(value: &JsValue) -> Result<serde_json::Value, &'static str> {
                     return Err("Cannot convert function to JSON");
                 }
 
+                // Primitive wrappers print as the value they box
+                let boxed = match &borrowed.exotic {
+                    ExoticObject::Boolean(b) => Some(JsValue::Boolean(*b)),
+                    ExoticObject::Number(n) => Some(JsValue::Number(*n)),
+                    ExoticObject::StringObj(s) => Some(JsValue::String(s.clone())),
+                    _ => None,
+                };
+                if let Some(boxed) = boxed {
+                    visited.remove(obj);
+                    return to_json_inner(&boxed, visited);
+                }
+
                 // Check if it's an array
                 if let Some(elements) = borrowed.array_elements() {
                     let mut arr = Vec::with_capacity(elements.len());
@@ -3,10 +3,13 @@
 //! Provides a convenient API for building bytecode chunks with
 //! register allocation and jump patching support.
 
+use super::CompileLimits;
 use super::bytecode::{
-    BytecodeChunk, Constant, ConstantIndex, FunctionInfo, JumpTarget, Op, Register, SourceMapEntry,
+    BytecodeChunk, Constant, ConstantIndex, FunctionInfo, JumpTarget, Op, OperandName, Register,
+    SourceMapEntry,
 };
-use crate::error::JsError;
+use crate::CompileLimitKind;
+use crate::error::{JsError, SourceLocation};
 use crate::lexer::Span;
 use crate::prelude::*;
 use crate::value::JsString;
@@ -71,6 +74,12 @@ impl RegisterAllocator {
         // This keeps register usage contiguous
         if r == self.next.saturating_sub(1) {
             self.next = r;
+            // Registers freed earlier that are now on top are released too,
+            // so the free list only holds registers below `next`
+            while let Some(pos) = self.free_list.iter().position(|&f| f + 1 == self.next) {
+                self.free_list.swap_remove(pos);
+                self.next -= 1;
+            }
         } else {
             self.free_list.push(r);
         }
@@ -139,6 +148,9 @@ pub struct BytecodeBuilder {
     /// Source map entries
     source_map: Vec<SourceMapEntry>,
 
+    /// Operand names for error messages
+    operand_names: Vec<OperandName>,
+
     /// Register allocator
     registers: RegisterAllocator,
 
@@ -150,6 +162,13 @@ pub struct BytecodeBuilder {
 
     /// Source file path (for stack traces)
     source_file: Option<String>,
+
+    /// Size limits the chunk is checked against
+    limits: CompileLimits,
+
+    /// Name reported when the chunk passes a limit: `None` for top-level
+    /// code, empty for an anonymous function
+    limit_name: Option<JsString>,
 }
 
 impl BytecodeBuilder {
@@ -161,10 +180,13 @@ impl BytecodeBuilder {
             string_map: FxHashMap::default(),
             number_map: FxHashMap::default(),
             source_map: Vec::new(),
+            operand_names: Vec::new(),
             registers: RegisterAllocator::new(),
             current_span: None,
             function_info: None,
             source_file: None,
+            limits: CompileLimits::default(),
+            limit_name: None,
         }
     }
 
@@ -185,6 +207,38 @@ impl BytecodeBuilder {
         self.source_file.as_ref()
     }
 
+    /// Set the limits the chunk is checked against and the function name
+    /// errors report, `None` for top-level code
+    pub fn set_limits(&mut self, limits: CompileLimits, name: Option<JsString>) {
+        self.limits = limits;
+        self.limit_name = name;
+    }
+
+    /// The error for this chunk passing `limit`, located at the current span
+    pub fn limit_error(&self, limit: CompileLimitKind) -> JsError {
+        JsError::CompileLimit {
+            limit,
+            max: self.limits.max(limit),
+            function: self.limit_name.as_ref().map(|name| name.to_string()),
+            location: self.current_span.map(|span| SourceLocation {
+                file: self.source_file.clone(),
+                line: span.line,
+                column: span.column,
+                length: u32::try_from(span.end.saturating_sub(span.start)).unwrap_or(u32::MAX),
+                offset: Some(span.start),
+                snippet: None,
+            }),
+        }
+    }
+
+    /// Fail once the chunk has more instructions than its limit allows
+    pub fn check_size(&self) -> Result<(), JsError> {
+        if self.code.len() > self.limits.max_chunk_instructions {
+            return Err(self.limit_error(CompileLimitKind::ChunkInstructions));
+        }
+        Ok(())
+    }
+
     /// Get access to the register allocator
     pub fn registers(&mut self) -> &mut RegisterAllocator {
         &mut self.registers
@@ -195,6 +249,11 @@ impl BytecodeBuilder {
         self.current_span = Some(span);
     }
 
+    /// The current source span, if any
+    pub fn current_span(&self) -> Option<Span> {
+        self.current_span
+    }
+
     /// Clear the current source span
     pub fn clear_span(&mut self) {
         self.current_span = None;
@@ -224,6 +283,22 @@ impl BytecodeBuilder {
         index
     }
 
+    /// Record the source-level name of an instruction's operand.
+    ///
+    /// Instructions are named in emission order, so the table stays sorted.
+    pub fn name_operand(&mut self, index: usize, name: JsString) {
+        if self
+            .operand_names
+            .last()
+            .is_none_or(|e| e.bytecode_offset < index)
+        {
+            self.operand_names.push(OperandName {
+                bytecode_offset: index,
+                name,
+            });
+        }
+    }
+
     /// Emit a jump instruction with a placeholder target
     pub fn emit_jump(&mut self) -> JumpPlaceholder {
         let index = self.emit(Op::Jump { target: 0 });
@@ -293,6 +368,7 @@ impl BytecodeBuilder {
                 Op::IteratorDone { target: t, .. } => *t = target,
                 Op::Break { target: t, .. } => *t = target,
                 Op::Continue { target: t, .. } => *t = target,
+                Op::SwitchString { default: t, .. } => *t = target,
 
                 // PushTry has targets but is patched via patch_try_targets()
                 Op::PushTry { .. } => {}
@@ -337,8 +413,10 @@ impl BytecodeBuilder {
                 | Op::GetVar { .. }
                 | Op::TryGetVar { .. }
                 | Op::SetVar { .. }
+                | Op::SetFunctionVar { .. }
                 | Op::DeclareVar { .. }
                 | Op::DeclareVarHoisted { .. }
+                | Op::DeclareUninitialized { .. }
                 | Op::GetGlobal { .. }
                 | Op::SetGlobal { .. }
                 | Op::CreateObject { .. }
@@ -423,8 +501,10 @@ impl BytecodeBuilder {
                 | Op::ExportNamespace { .. }
                 | Op::ReExport { .. }
                 | Op::SetFunctionName { .. }
+                | Op::SetFunctionNameFromKey { .. }
                 | Op::PopIterTry
-                | Op::IteratorClose { .. } => {}
+                | Op::IteratorClose { .. }
+                | Op::IteratorCloseOnThrow { .. } => {}
             }
         }
     }
@@ -483,8 +563,8 @@ impl BytecodeBuilder {
 
     /// Add a constant to the pool
     pub fn add_constant(&mut self, constant: Constant) -> Result<ConstantIndex, JsError> {
-        if self.constants.len() >= u16::MAX as usize {
-            return Err(JsError::internal_error("Too many constants (max 65535)"));
+        if self.constants.len() >= self.limits.max(CompileLimitKind::ChunkConstants) {
+            return Err(self.limit_error(CompileLimitKind::ChunkConstants));
         }
 
         let idx = self.constants.len() as ConstantIndex;
@@ -497,6 +577,17 @@ impl BytecodeBuilder {
         self.add_constant(Constant::Chunk(Rc::new(chunk)))
     }
 
+    /// Fill in the case targets of a switch table added with an empty map
+    pub fn patch_switch_table(
+        &mut self,
+        idx: ConstantIndex,
+        targets: FxHashMap<JsString, JumpTarget>,
+    ) {
+        if let Some(Constant::SwitchTable(table)) = self.constants.get_mut(idx as usize) {
+            *table = targets;
+        }
+    }
+
     /// Add excluded keys for object rest destructuring
     pub fn add_excluded_keys(&mut self, keys: Vec<JsString>) -> Result<ConstantIndex, JsError> {
         self.add_constant(Constant::ExcludedKeys(keys))
@@ -511,8 +602,12 @@ impl BytecodeBuilder {
 
     /// Emit LoadConst for a number
     pub fn emit_load_number(&mut self, dst: Register, n: f64) -> Result<(), JsError> {
-        // Optimize small integers
-        if math::fract(n) == 0.0 && n >= i32::MIN as f64 && n <= i32::MAX as f64 {
+        // Optimize small integers (but not -0, which LoadInt can't hold)
+        if math::fract(n) == 0.0
+            && n >= i32::MIN as f64
+            && n <= i32::MAX as f64
+            && !(n == 0.0 && n.is_sign_negative())
+        {
             let i = n as i32;
             // Use LoadInt for small integers
             if (-128..=127).contains(&i) {
@@ -534,9 +629,11 @@ impl BytecodeBuilder {
     /// Finish building and return the bytecode chunk
     pub fn finish(self) -> BytecodeChunk {
         BytecodeChunk {
-            code: self.code,
+            back_edges: BytecodeChunk::find_back_edges(&self.code),
+            code: Rc::from(self.code),
             constants: self.constants,
             source_map: self.source_map,
+            operand_names: self.operand_names,
             register_count: self.registers.max_used(),
             function_info: self.function_info,
             source_file: self.source_file,
@@ -553,10 +650,22 @@ impl BytecodeBuilder {
         self.registers.free(r);
     }
 
+    /// Number of registers currently allocated
+    pub fn registers_in_use(&self) -> u8 {
+        self.registers.current()
+    }
+
     /// Reserve a range of consecutive registers
     pub fn reserve_registers(&mut self, count: u8) -> Result<Register, JsError> {
         self.registers.reserve_range(count)
     }
+
+    /// Free `count` registers from `start`, such as a reserved range
+    pub fn free_registers(&mut self, start: Register, count: u8) {
+        for i in (0..count).rev() {
+            self.registers.free(start + i);
+        }
+    }
 }
 
 impl Default for BytecodeBuilder {
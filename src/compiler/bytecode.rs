@@ -3,9 +3,10 @@
 //! This module defines the bytecode format used by the VM.
 //! We use a register-based design with up to 256 virtual registers.
 
-use crate::lexer::Span;
+use crate::lexer::{SourceSpan, Span};
 use crate::prelude::*;
-use crate::value::JsString;
+use crate::string_dict::StringDict;
+use crate::value::{CheapClone, JsString};
 
 /// Virtual register index (0-255)
 pub type Register = u8;
@@ -23,7 +24,7 @@ pub type JumpTarget = u32;
 ///
 /// Op is Copy because all variants contain only primitive types (u8, u16, u32, bool).
 /// This allows efficient pass-by-value without heap allocation or reference counting.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Op {
     // ═══════════════════════════════════════════════════════════════════════════════
     // Constants & Register Operations
@@ -259,6 +260,14 @@ pub enum Op {
     /// try_depth is the try stack depth at the target loop
     Continue { target: JumpTarget, try_depth: u8 },
 
+    /// Switch dispatch: jump to the target constants[table] maps r[value] to,
+    /// or to `default` if r[value] is not a string in the table
+    SwitchString {
+        value: Register,
+        table: ConstantIndex,
+        default: JumpTarget,
+    },
+
     // ═══════════════════════════════════════════════════════════════════════════════
     // Variable Access
     // ═══════════════════════════════════════════════════════════════════════════════
@@ -271,6 +280,10 @@ pub enum Op {
     /// Store variable: env[name] = r[src]
     SetVar { name: ConstantIndex, src: Register },
 
+    /// Store variable in the function's var scope, past any block scopes
+    /// shadowing it: varEnv[name] = r[src] (sloppy-mode block functions)
+    SetFunctionVar { name: ConstantIndex, src: Register },
+
     /// Declare variable with let/const: env.define(name, r[init], mutable)
     DeclareVar {
         name: ConstantIndex,
@@ -281,6 +294,10 @@ pub enum Op {
     /// Declare variable with var (hoisted): env.define_var(name, r[init])
     DeclareVarHoisted { name: ConstantIndex, init: Register },
 
+    /// Declare variable in its temporal dead zone: reads and writes throw
+    /// until a DeclareVar for the same name initializes it
+    DeclareUninitialized { name: ConstantIndex },
+
     /// Get global variable (optimized path for globals)
     GetGlobal { dst: Register, name: ConstantIndex },
 
@@ -519,9 +536,13 @@ pub enum Op {
     IteratorValue { dst: Register, result: Register },
 
     /// Close iterator: call r[iterator].return() if it exists
-    /// Used for early loop exit (break, return, throw) in for-of loops
+    /// Used for early loop exit (break, continue, return) in for-of loops
     IteratorClose { iterator: Register },
 
+    /// Close iterator while an exception propagates: like IteratorClose, but
+    /// errors thrown by return() are discarded so the original exception wins
+    IteratorCloseOnThrow { iterator: Register },
+
     // ═══════════════════════════════════════════════════════════════════════════════
     // Class Operations
     // ═══════════════════════════════════════════════════════════════════════════════
@@ -540,13 +561,14 @@ pub enum Op {
         is_static: bool,
     },
 
-    /// Define getter/setter
+    /// Define getter/setter; enumerable for object literals, not for classes
     DefineAccessor {
         class: Register,
         name: ConstantIndex,
         getter: Register,
         setter: Register,
         is_static: bool,
+        enumerable: bool,
     },
 
     /// Define class method with computed key
@@ -564,6 +586,7 @@ pub enum Op {
         getter: Register,
         setter: Register,
         is_static: bool,
+        enumerable: bool,
     },
 
     /// Super call: r[dst] = super(args...)
@@ -790,6 +813,10 @@ pub enum Op {
     /// If it already has a name or is not a function, this is a no-op.
     SetFunctionName { func: Register, name: ConstantIndex },
 
+    /// SetFunctionName with a computed key: name the anonymous function in
+    /// r[func] after the property key in r[key] (`[desc]` for symbols)
+    SetFunctionNameFromKey { func: Register, key: Register },
+
     // ═══════════════════════════════════════════════════════════════════════════════
     // Miscellaneous
     // ═══════════════════════════════════════════════════════════════════════════════
@@ -847,8 +874,9 @@ pub enum Op {
 /// A compiled chunk of bytecode
 #[derive(Debug, Clone)]
 pub struct BytecodeChunk {
-    /// The bytecode instructions
-    pub code: Vec<Op>,
+    /// The bytecode instructions, shared between chunks that compile to the
+    /// same code (see `Interpreter::chunk_cache_stats`)
+    pub code: Rc<[Op]>,
 
     /// Constant pool (strings, numbers, nested chunks)
     pub constants: Vec<Constant>,
@@ -856,6 +884,9 @@ pub struct BytecodeChunk {
     /// Source map: instruction index -> source span
     pub source_map: Vec<SourceMapEntry>,
 
+    /// Source-level names of operands, for error messages, sorted by offset
+    pub operand_names: Vec<OperandName>,
+
     /// Number of registers needed for this chunk
     pub register_count: u8,
 
@@ -864,10 +895,15 @@ pub struct BytecodeChunk {
 
     /// Source file path (for stack traces)
     pub source_file: Option<String>,
+
+    /// Offsets of jumps to an earlier instruction, sorted. These are the
+    /// loop back-edges where `StepGranularity::Checkpoint` hands control
+    /// back to the host.
+    pub back_edges: Vec<u32>,
 }
 
 /// Source map entry for debugging
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SourceMapEntry {
     /// Bytecode instruction index
     pub bytecode_offset: usize,
@@ -875,6 +911,17 @@ pub struct SourceMapEntry {
     pub span: Span,
 }
 
+/// The source text of an operand, e.g. `config.spec` for `config.spec.replicas`.
+///
+/// Property accesses record the object expression, calls record the callee.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperandName {
+    /// Bytecode instruction index
+    pub bytecode_offset: usize,
+    /// Identifier or member chain as written
+    pub name: JsString,
+}
+
 /// Constants that can be stored in the pool
 #[derive(Debug, Clone)]
 pub enum Constant {
@@ -898,6 +945,55 @@ pub enum Constant {
 
     /// List of keys to exclude in object rest destructuring
     ExcludedKeys(Vec<JsString>),
+
+    /// Case strings of a switch and the offsets of their bodies (for SwitchString)
+    SwitchTable(FxHashMap<JsString, JumpTarget>),
+}
+
+impl Constant {
+    /// Whether `other` holds the same constant. Numbers compare by bits, so
+    /// `NaN` matches itself and `0` differs from `-0`; nested chunks compare
+    /// by identity.
+    pub(crate) fn same_as(&self, other: &Constant) -> bool {
+        match (self, other) {
+            (Constant::String(a), Constant::String(b)) => a == b,
+            (Constant::Number(a), Constant::Number(b)) => a.to_bits() == b.to_bits(),
+            (Constant::Chunk(a), Constant::Chunk(b)) => Rc::ptr_eq(a, b),
+            (
+                Constant::RegExp { pattern, flags },
+                Constant::RegExp {
+                    pattern: other_pattern,
+                    flags: other_flags,
+                },
+            ) => pattern == other_pattern && flags == other_flags,
+            (
+                Constant::TemplateStrings { cooked, raw },
+                Constant::TemplateStrings {
+                    cooked: other_cooked,
+                    raw: other_raw,
+                },
+            ) => cooked == other_cooked && raw == other_raw,
+            (Constant::ExcludedKeys(a), Constant::ExcludedKeys(b)) => a == b,
+            (Constant::SwitchTable(a), Constant::SwitchTable(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Feed the parts of this constant [`Constant::same_as`] compares cheaply
+    /// into `state`, so equal constants hash alike
+    pub(crate) fn hash_content<H: core::hash::Hasher>(&self, state: &mut H) {
+        use core::hash::Hash;
+        core::mem::discriminant(self).hash(state);
+        match self {
+            Constant::String(s) => s.hash(state),
+            Constant::Number(n) => n.to_bits().hash(state),
+            Constant::Chunk(chunk) => Rc::as_ptr(chunk).hash(state),
+            Constant::RegExp { pattern, .. } => pattern.hash(state),
+            Constant::TemplateStrings { .. }
+            | Constant::ExcludedKeys(_)
+            | Constant::SwitchTable(_) => {}
+        }
+    }
 }
 
 /// Function metadata
@@ -906,7 +1002,8 @@ pub struct FunctionInfo {
     /// Function name (if any)
     pub name: Option<JsString>,
 
-    /// Number of parameters
+    /// Number of parameters before the first default or rest parameter,
+    /// reported as the function's `length`
     pub param_count: usize,
 
     /// Whether this is a generator function
@@ -924,30 +1021,263 @@ pub struct FunctionInfo {
     /// Whether function uses `this`
     pub uses_this: bool,
 
+    /// Whether the body creates closures or runs direct eval, so its
+    /// environment may outlive the call (see `BytecodeChunk::creates_closures`)
+    pub creates_closures: bool,
+
     /// Parameter names (for creating environment)
     pub param_names: Vec<JsString>,
 
     /// Rest parameter index (if any)
     pub rest_param: Option<usize>,
 
+    /// Names of the parameters and variables the body declares, in order of
+    /// declaration (see `BytecodeChunk::declared_names`). Locals live in the
+    /// function's environments, so this is what the host uses to read them
+    /// back while the function is suspended.
+    pub local_names: Vec<JsString>,
+
     /// Expected number of bindings in the function's environment
     /// Used to pre-size the HashMap to avoid resizing during execution
     pub binding_count: usize,
+
+    /// Original source text of the function (for Function.prototype.toString)
+    pub source_text: Option<FunctionSource>,
+}
+
+/// A function's source text, stored as a range into the shared program source.
+///
+/// All functions compiled from one program share the same `Rc<str>`, so retaining
+/// the source costs one copy of the program text rather than one per function.
+#[derive(Debug, Clone)]
+pub struct FunctionSource {
+    source: Rc<str>,
+    start: usize,
+    end: usize,
+    path: Option<Rc<str>>,
+}
+
+impl FunctionSource {
+    /// Create a function source from the program text and a byte range within it.
+    /// Returns `None` if the range is not valid for the text.
+    pub fn new(source: Rc<str>, start: usize, end: usize) -> Option<Self> {
+        source.get(start..end)?;
+        Some(Self {
+            source,
+            start,
+            end,
+            path: None,
+        })
+    }
+
+    /// Attach the path of the file the function was defined in
+    pub(crate) fn with_path(mut self, path: Option<&str>) -> Self {
+        self.path = path.map(Rc::from);
+        self
+    }
+
+    /// The function's source text
+    pub fn text(&self) -> &str {
+        self.source.get(self.start..self.end).unwrap_or_default()
+    }
+
+    /// Path of the module or script the function was defined in, if known
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    /// The program text the function's range points into
+    pub(crate) fn program_source(&self) -> &Rc<str> {
+        &self.source
+    }
+
+    /// Byte range of the function's text in the program text
+    pub(crate) fn range(&self) -> (usize, usize) {
+        (self.start, self.end)
+    }
+
+    /// Whether `other` has the same text at the same position of the same file.
+    /// The text before it is compared too, since it decides the line numbers.
+    pub(crate) fn same_as(&self, other: &FunctionSource) -> bool {
+        self.start == other.start
+            && self.end == other.end
+            && self.path == other.path
+            && (Rc::ptr_eq(&self.source, &other.source)
+                || self.source.get(..self.end) == other.source.get(..other.end))
+    }
+
+    /// Where the text sits in its file
+    pub fn span(&self) -> SourceSpan {
+        let lines = crate::error::SourceText::new(&self.source);
+        let (start_line, start_column) = lines.offset_to_line_col(self.start);
+        let (end_line, end_column) = lines.offset_to_line_col(self.end);
+        SourceSpan {
+            start: self.start,
+            end: self.end,
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+        }
+    }
 }
 
 impl BytecodeChunk {
     /// Create a new empty bytecode chunk
     pub fn new() -> Self {
         Self {
-            code: Vec::new(),
+            code: Rc::from([]),
             constants: Vec::new(),
             source_map: Vec::new(),
+            operand_names: Vec::new(),
             register_count: 0,
             function_info: None,
             source_file: None,
+            back_edges: Vec::new(),
         }
     }
 
+    /// Whether the instruction at `offset` jumps backwards
+    pub fn is_back_edge(&self, offset: usize) -> bool {
+        u32::try_from(offset).is_ok_and(|offset| self.back_edges.binary_search(&offset).is_ok())
+    }
+
+    /// Whether executing this chunk can capture its environment.
+    ///
+    /// Every function, arrow, method or class body is a nested chunk constant,
+    /// and direct eval can define any of those at runtime. Without either,
+    /// nothing can reference the environment once the call returns.
+    pub fn creates_closures(&self) -> bool {
+        self.constants
+            .iter()
+            .any(|c| matches!(c, Constant::Chunk(_)))
+            || self
+                .code
+                .iter()
+                .any(|op| matches!(op, Op::DirectEval { .. }))
+    }
+
+    /// Offsets of the jumps in `code` whose target is at or before the jump
+    pub fn find_back_edges(code: &[Op]) -> Vec<u32> {
+        code.iter()
+            .enumerate()
+            .filter_map(|(offset, op)| {
+                let target = match op {
+                    Op::Jump { target }
+                    | Op::JumpIfTrue { target, .. }
+                    | Op::JumpIfFalse { target, .. }
+                    | Op::JumpIfNullish { target, .. }
+                    | Op::JumpIfNotNullish { target, .. }
+                    | Op::Break { target, .. }
+                    | Op::Continue { target, .. } => *target,
+                    _ => return None,
+                };
+                let offset = u32::try_from(offset).ok()?;
+                (target <= offset).then_some(offset)
+            })
+            .collect()
+    }
+
+    /// Names this chunk declares with `DeclareVar`, `DeclareVarHoisted` or
+    /// `DeclareUninitialized`, after `params`, each listed once
+    pub fn declared_names(&self, params: &[JsString]) -> Vec<JsString> {
+        let mut names: Vec<JsString> = Vec::new();
+        let mut seen: FxHashSet<JsString> = FxHashSet::default();
+        for name in params {
+            if seen.insert(name.cheap_clone()) {
+                names.push(name.cheap_clone());
+            }
+        }
+        for op in self.code.iter() {
+            let (Op::DeclareVar { name, .. }
+            | Op::DeclareVarHoisted { name, .. }
+            | Op::DeclareUninitialized { name }) = op
+            else {
+                continue;
+            };
+            if let Some(Constant::String(name)) = self.get_constant(*name)
+                && seen.insert(name.cheap_clone())
+            {
+                names.push(name.cheap_clone());
+            }
+        }
+        names
+    }
+
+    /// Replace every string of this chunk and its nested chunks (constants,
+    /// function metadata and operand names) with the copy held by `dict`,
+    /// so strict equality against strings interned in the same dictionary
+    /// succeeds on pointer identity and equal strings are stored once.
+    ///
+    /// Nested chunks that are already shared are left as they are.
+    pub fn intern_strings(&mut self, dict: &mut StringDict) {
+        for constant in &mut self.constants {
+            match constant {
+                Constant::String(s) => *s = dict.get_or_insert(s.as_str()),
+                Constant::RegExp { pattern, flags } => {
+                    *pattern = dict.get_or_insert(pattern.as_str());
+                    *flags = dict.get_or_insert(flags.as_str());
+                }
+                Constant::TemplateStrings { cooked, raw } => {
+                    for s in cooked.iter_mut().chain(raw.iter_mut()) {
+                        *s = dict.get_or_insert(s.as_str());
+                    }
+                }
+                Constant::ExcludedKeys(keys) => {
+                    for s in keys {
+                        *s = dict.get_or_insert(s.as_str());
+                    }
+                }
+                Constant::SwitchTable(table) => {
+                    *table = table
+                        .drain()
+                        .map(|(key, target)| (dict.get_or_insert(key.as_str()), target))
+                        .collect();
+                }
+                Constant::Chunk(child) => {
+                    if let Some(child) = Rc::get_mut(child) {
+                        child.intern_strings(dict);
+                    }
+                }
+                Constant::Number(_) => {}
+            }
+        }
+        if let Some(info) = &mut self.function_info {
+            if let Some(name) = &mut info.name {
+                *name = dict.get_or_insert(name.as_str());
+            }
+            for s in info.param_names.iter_mut().chain(&mut info.local_names) {
+                *s = dict.get_or_insert(s.as_str());
+            }
+        }
+        for operand in &mut self.operand_names {
+            operand.name = dict.get_or_insert(operand.name.as_str());
+        }
+    }
+
+    /// Whether `other` is the same compiled chunk: same instructions,
+    /// constants, metadata and source positions. Nested chunks compare by
+    /// identity, so they must be shared before their parents are compared.
+    pub(crate) fn same_content(&self, other: &BytecodeChunk) -> bool {
+        self.register_count == other.register_count
+            && self.source_file == other.source_file
+            && self.code == other.code
+            && self.back_edges == other.back_edges
+            && self.source_map == other.source_map
+            && self.operand_names == other.operand_names
+            && self.constants.len() == other.constants.len()
+            && self
+                .constants
+                .iter()
+                .zip(&other.constants)
+                .all(|(a, b)| a.same_as(b))
+            && match (&self.function_info, &other.function_info) {
+                (Some(a), Some(b)) => a.same_as(b),
+                (None, None) => true,
+                _ => false,
+            }
+    }
+
     /// Get the instruction at the given offset
     pub fn get(&self, offset: usize) -> Option<&Op> {
         self.code.get(offset)
@@ -967,6 +1297,15 @@ impl BytecodeChunk {
         }
     }
 
+    /// Get the recorded operand name for the instruction at `offset`
+    pub fn get_operand_name(&self, offset: usize) -> Option<&JsString> {
+        self.operand_names
+            .binary_search_by_key(&offset, |e| e.bytecode_offset)
+            .ok()
+            .and_then(|i| self.operand_names.get(i))
+            .map(|e| &e.name)
+    }
+
     /// Get a constant from the pool
     pub fn get_constant(&self, idx: ConstantIndex) -> Option<&Constant> {
         self.constants.get(idx as usize)
@@ -980,6 +1319,35 @@ impl Default for BytecodeChunk {
 }
 
 impl FunctionInfo {
+    /// Whether a call's environment can be handed back to the call pool on return.
+    ///
+    /// Generators and async functions keep their environment across suspensions,
+    /// so only plain functions that create no closures qualify.
+    pub fn env_is_recyclable(&self) -> bool {
+        !self.creates_closures && !self.is_generator && !self.is_async
+    }
+
+    /// Whether every field matches `other`, source text compared by content
+    pub(crate) fn same_as(&self, other: &FunctionInfo) -> bool {
+        self.name == other.name
+            && self.param_count == other.param_count
+            && self.is_generator == other.is_generator
+            && self.is_async == other.is_async
+            && self.is_arrow == other.is_arrow
+            && self.uses_arguments == other.uses_arguments
+            && self.uses_this == other.uses_this
+            && self.creates_closures == other.creates_closures
+            && self.param_names == other.param_names
+            && self.rest_param == other.rest_param
+            && self.local_names == other.local_names
+            && self.binding_count == other.binding_count
+            && match (&self.source_text, &other.source_text) {
+                (Some(a), Some(b)) => a.same_as(b),
+                (None, None) => true,
+                _ => false,
+            }
+    }
+
     /// Create info for a regular function
     pub fn regular(name: Option<JsString>, param_count: usize) -> Self {
         Self {
@@ -990,9 +1358,12 @@ impl FunctionInfo {
             is_arrow: false,
             uses_arguments: false,
             uses_this: false,
+            creates_closures: true,
             param_names: Vec::new(),
             rest_param: None,
+            local_names: Vec::new(),
             binding_count: 0,
+            source_text: None,
         }
     }
 
@@ -1006,9 +1377,12 @@ impl FunctionInfo {
             is_arrow: true,
             uses_arguments: false,
             uses_this: false,
+            creates_closures: true,
             param_names: Vec::new(),
             rest_param: None,
+            local_names: Vec::new(),
             binding_count: 0,
+            source_text: None,
         }
     }
 
@@ -1022,9 +1396,12 @@ impl FunctionInfo {
             is_arrow: false,
             uses_arguments: false,
             uses_this: false,
+            creates_closures: true,
             param_names: Vec::new(),
             rest_param: None,
+            local_names: Vec::new(),
             binding_count: 0,
+            source_text: None,
         }
     }
 
@@ -1038,9 +1415,12 @@ impl FunctionInfo {
             is_arrow: false,
             uses_arguments: false,
             uses_this: false,
+            creates_closures: true,
             param_names: Vec::new(),
             rest_param: None,
+            local_names: Vec::new(),
             binding_count: 0,
+            source_text: None,
         }
     }
 }
@@ -12,6 +12,42 @@ use crate::error::JsError;
 use crate::prelude::*;
 use crate::value::{CheapClone, JsString};
 
+/// Registers in use past which a binary expression spills its left operand
+/// instead of holding it in a register while the right one is evaluated
+const SPILL_THRESHOLD: Register = 200;
+
+/// Name of the binding a spilled left operand is kept in; no script can
+/// refer to it
+const SPILL_SLOT: &str = "%spill";
+
+/// The instruction for `left <operator> right`, stored in `dst`
+fn binary_op(operator: BinaryOp, dst: Register, left: Register, right: Register) -> Op {
+    match operator {
+        BinaryOp::Add => Op::Add { dst, left, right },
+        BinaryOp::Sub => Op::Sub { dst, left, right },
+        BinaryOp::Mul => Op::Mul { dst, left, right },
+        BinaryOp::Div => Op::Div { dst, left, right },
+        BinaryOp::Mod => Op::Mod { dst, left, right },
+        BinaryOp::Exp => Op::Exp { dst, left, right },
+        BinaryOp::Eq => Op::Eq { dst, left, right },
+        BinaryOp::NotEq => Op::NotEq { dst, left, right },
+        BinaryOp::StrictEq => Op::StrictEq { dst, left, right },
+        BinaryOp::StrictNotEq => Op::StrictNotEq { dst, left, right },
+        BinaryOp::Lt => Op::Lt { dst, left, right },
+        BinaryOp::LtEq => Op::LtEq { dst, left, right },
+        BinaryOp::Gt => Op::Gt { dst, left, right },
+        BinaryOp::GtEq => Op::GtEq { dst, left, right },
+        BinaryOp::BitAnd => Op::BitAnd { dst, left, right },
+        BinaryOp::BitOr => Op::BitOr { dst, left, right },
+        BinaryOp::BitXor => Op::BitXor { dst, left, right },
+        BinaryOp::LShift => Op::LShift { dst, left, right },
+        BinaryOp::RShift => Op::RShift { dst, left, right },
+        BinaryOp::URShift => Op::URShift { dst, left, right },
+        BinaryOp::In => Op::In { dst, left, right },
+        BinaryOp::Instanceof => Op::Instanceof { dst, left, right },
+    }
+}
+
 /// Information about a member key (const or computed)
 enum MemberKeyInfo {
     Const(ConstantIndex),
@@ -38,6 +74,14 @@ impl Compiler {
                     return Ok(());
                 }
 
+                if let Some(value) = self
+                    .constant_imports
+                    .as_ref()
+                    .and_then(|imports| imports.resolve_identifier(id))
+                {
+                    return self.compile_literal(&value, dst);
+                }
+
                 let name_idx = self.builder.add_string(id.name.cheap_clone())?;
                 self.builder.emit(Op::GetVar {
                     dst,
@@ -236,11 +280,14 @@ impl Compiler {
                 }
             }
 
+            // The elements left the span at the last one
+            self.builder.set_span(arr.span);
             self.builder.emit(Op::CreateArray {
                 dst,
                 start,
                 count: count as u16,
             });
+            self.builder.free_registers(start, count as u8);
         } else {
             // Slow path: array has spreads, build incrementally
             // Start with an empty array
@@ -339,9 +386,13 @@ impl Compiler {
             return self.compile_accessor_property(obj, prop);
         }
 
-        // Compile the value for regular properties
+        // Compile the value for regular properties; `{ f: () => {} }` names the function "f"
         let value_reg = self.builder.alloc_register()?;
-        self.compile_expression(&prop.value, value_reg)?;
+        self.compile_expression_with_inferred_name(
+            &prop.value,
+            value_reg,
+            static_key_name(&prop.key),
+        )?;
 
         // Set the property based on key type
         match &prop.key {
@@ -375,6 +426,12 @@ impl Compiler {
             ObjectPropertyKey::Computed(expr) => {
                 let key_reg = self.builder.alloc_register()?;
                 self.compile_expression(expr, key_reg)?;
+                if is_anonymous_function_definition(&prop.value) {
+                    self.builder.emit(Op::SetFunctionNameFromKey {
+                        func: value_reg,
+                        key: key_reg,
+                    });
+                }
                 self.builder.emit(Op::SetProperty {
                     obj,
                     key: key_reg,
@@ -423,6 +480,7 @@ impl Compiler {
                         getter: accessor_reg,
                         setter: undefined_reg,
                         is_static: true, // Define on the object itself
+                        enumerable: true,
                     });
                 }
                 PropertyKind::Set => {
@@ -432,6 +490,7 @@ impl Compiler {
                         getter: undefined_reg,
                         setter: accessor_reg,
                         is_static: true, // Define on the object itself
+                        enumerable: true,
                     });
                 }
                 PropertyKind::Init => {
@@ -452,10 +511,10 @@ impl Compiler {
             ObjectPropertyKey::Number(lit) => {
                 // Number keys need to be converted to string
                 let num_str = match &lit.value {
-                    LiteralValue::Number(n) => crate::value::JsString::from(n.to_string()),
-                    _ => crate::value::JsString::from("0"),
+                    LiteralValue::Number(n) => crate::value::number_to_string(*n),
+                    _ => "0".to_string(),
                 };
-                self.builder.add_string(num_str)?
+                self.builder.add_string(JsString::from(num_str))?
             }
             ObjectPropertyKey::PrivateIdentifier(_) => {
                 return Err(JsError::syntax_error_simple(
@@ -468,9 +527,16 @@ impl Compiler {
             }
         };
 
-        // Compile the accessor function
+        // Compile the accessor function, named `get <key>` or `set <key>`
+        let prefix = if prop.kind == PropertyKind::Get {
+            "get "
+        } else {
+            "set "
+        };
+        let accessor_name = static_key_name(&prop.key)
+            .map(|name| JsString::from(format!("{}{}", prefix, name.as_str())));
         let accessor_reg = self.builder.alloc_register()?;
-        self.compile_expression(&prop.value, accessor_reg)?;
+        self.compile_expression_with_inferred_name(&prop.value, accessor_reg, accessor_name)?;
 
         // Create undefined for the other accessor slot
         let undefined_reg = self.builder.alloc_register()?;
@@ -485,6 +551,7 @@ impl Compiler {
                     getter: accessor_reg,
                     setter: undefined_reg,
                     is_static: true, // Define on the object itself
+                    enumerable: true,
                 });
             }
             PropertyKind::Set => {
@@ -494,6 +561,7 @@ impl Compiler {
                     getter: undefined_reg,
                     setter: accessor_reg,
                     is_static: true, // Define on the object itself
+                    enumerable: true,
                 });
             }
             PropertyKind::Init => {
@@ -521,7 +589,11 @@ impl Compiler {
                 // typeof needs special handling for identifiers:
                 // typeof undeclaredVar should return "undefined", not throw ReferenceError
                 let src = self.builder.alloc_register()?;
-                if let Expression::Identifier(id) = &*unary.argument {
+                let mut argument = unary.argument.as_ref();
+                while let Expression::Parenthesized(inner, _) = argument {
+                    argument = inner.as_ref();
+                }
+                if let Expression::Identifier(id) = argument {
                     // Use TryGetVar to get undefined for undeclared variables
                     let name_idx = self.builder.add_string(id.name.cheap_clone())?;
                     self.builder.emit(Op::TryGetVar {
@@ -530,7 +602,7 @@ impl Compiler {
                     });
                 } else {
                     // For other expressions, evaluate normally
-                    self.compile_expression(&unary.argument, src)?;
+                    self.compile_expression(argument, src)?;
                 }
                 self.builder.emit(Op::Typeof { dst, src });
                 self.builder.free_register(src);
@@ -573,32 +645,42 @@ impl Compiler {
             Expression::Member(member) => {
                 let obj_reg = self.builder.alloc_register()?;
                 self.compile_expression(&member.object, obj_reg)?;
+                self.emit_delete_member(member, obj_reg, dst)?;
+                self.builder.free_register(obj_reg);
+            }
+            Expression::OptionalChain(opt)
+                if matches!(opt.base.as_ref(), Expression::Member(_)) =>
+            {
+                // delete a?.b: deletes like a.b unless the chain short-circuits,
+                // in which case the result is true
+                let Expression::Member(member) = opt.base.as_ref() else {
+                    return Ok(());
+                };
+                let obj_reg = self.builder.alloc_register()?;
+                let mut short_circuit_jumps =
+                    self.compile_optional_chain_inner(&member.object, obj_reg)?;
+                if member.optional {
+                    short_circuit_jumps.push(self.builder.emit_jump_if_nullish(obj_reg));
+                }
+                self.emit_delete_member(member, obj_reg, dst)?;
+                self.builder.free_register(obj_reg);
 
-                match &member.property {
-                    MemberProperty::Identifier(id) => {
-                        let key_idx = self.builder.add_string(id.name.cheap_clone())?;
-                        self.builder.emit(Op::DeletePropertyConst {
-                            dst,
-                            obj: obj_reg,
-                            key: key_idx,
-                        });
-                    }
-                    MemberProperty::Expression(expr) => {
-                        let key_reg = self.builder.alloc_register()?;
-                        self.compile_expression(expr, key_reg)?;
-                        self.builder.emit(Op::DeleteProperty {
-                            dst,
-                            obj: obj_reg,
-                            key: key_reg,
-                        });
-                        self.builder.free_register(key_reg);
-                    }
-                    MemberProperty::PrivateIdentifier(_) => {
-                        return Err(JsError::syntax_error_simple("Cannot delete private field"));
+                if !short_circuit_jumps.is_empty() {
+                    let skip_true = self.builder.emit_jump();
+                    let short_circuit_target = self.builder.current_offset();
+                    for jump in short_circuit_jumps {
+                        self.builder.patch_jump_to(
+                            jump,
+                            short_circuit_target as super::bytecode::JumpTarget,
+                        );
                     }
+                    self.builder.emit(Op::LoadBool { dst, value: true });
+                    self.builder.patch_jump(skip_true);
                 }
-
-                self.builder.free_register(obj_reg);
+            }
+            Expression::Parenthesized(inner, _) => {
+                // delete (a.b) deletes a.b
+                return self.compile_delete_expression(inner, dst);
             }
             Expression::Identifier(_) => {
                 // delete identifier - in strict mode this is an error
@@ -617,48 +699,104 @@ impl Compiler {
         Ok(())
     }
 
+    /// Delete the property `member` names from the object in `obj_reg`
+    fn emit_delete_member(
+        &mut self,
+        member: &crate::ast::MemberExpression,
+        obj_reg: Register,
+        dst: Register,
+    ) -> Result<(), JsError> {
+        match &member.property {
+            MemberProperty::Identifier(id) => {
+                let key_idx = self.builder.add_string(id.name.cheap_clone())?;
+                self.builder.emit(Op::DeletePropertyConst {
+                    dst,
+                    obj: obj_reg,
+                    key: key_idx,
+                });
+            }
+            MemberProperty::Expression(expr) => {
+                let key_reg = self.builder.alloc_register()?;
+                self.compile_expression(expr, key_reg)?;
+                self.builder.emit(Op::DeleteProperty {
+                    dst,
+                    obj: obj_reg,
+                    key: key_reg,
+                });
+                self.builder.free_register(key_reg);
+            }
+            MemberProperty::PrivateIdentifier(_) => {
+                return Err(JsError::syntax_error_simple("Cannot delete private field"));
+            }
+        }
+        Ok(())
+    }
+
     /// Compile a binary expression
     fn compile_binary_expression(
         &mut self,
         binary: &crate::ast::BinaryExpression,
         dst: Register,
     ) -> Result<(), JsError> {
-        // Compile left operand
-        let left = self.builder.alloc_register()?;
-        self.compile_expression(&binary.left, left)?;
+        // Walk down the left operands first, so a long left-associative chain
+        // like `a + b + c + ...` compiles without recursion and keeps its
+        // running result in `dst` instead of holding a register per term
+        let mut chain = vec![binary];
+        let mut leftmost = binary.left.as_ref();
+        loop {
+            match leftmost {
+                Expression::Binary(inner) => {
+                    chain.push(inner);
+                    leftmost = inner.left.as_ref();
+                }
+                Expression::Parenthesized(inner, _) => leftmost = inner.as_ref(),
+                _ => break,
+            }
+        }
 
-        // Compile right operand
-        let right = self.builder.alloc_register()?;
-        self.compile_expression(&binary.right, right)?;
+        self.compile_expression(leftmost, dst)?;
+        for binary in chain.into_iter().rev() {
+            self.compile_binary_step(binary, dst)?;
+        }
+        Ok(())
+    }
 
-        // Emit the binary operation
-        let op = match binary.operator {
-            BinaryOp::Add => Op::Add { dst, left, right },
-            BinaryOp::Sub => Op::Sub { dst, left, right },
-            BinaryOp::Mul => Op::Mul { dst, left, right },
-            BinaryOp::Div => Op::Div { dst, left, right },
-            BinaryOp::Mod => Op::Mod { dst, left, right },
-            BinaryOp::Exp => Op::Exp { dst, left, right },
-            BinaryOp::Eq => Op::Eq { dst, left, right },
-            BinaryOp::NotEq => Op::NotEq { dst, left, right },
-            BinaryOp::StrictEq => Op::StrictEq { dst, left, right },
-            BinaryOp::StrictNotEq => Op::StrictNotEq { dst, left, right },
-            BinaryOp::Lt => Op::Lt { dst, left, right },
-            BinaryOp::LtEq => Op::LtEq { dst, left, right },
-            BinaryOp::Gt => Op::Gt { dst, left, right },
-            BinaryOp::GtEq => Op::GtEq { dst, left, right },
-            BinaryOp::BitAnd => Op::BitAnd { dst, left, right },
-            BinaryOp::BitOr => Op::BitOr { dst, left, right },
-            BinaryOp::BitXor => Op::BitXor { dst, left, right },
-            BinaryOp::LShift => Op::LShift { dst, left, right },
-            BinaryOp::RShift => Op::RShift { dst, left, right },
-            BinaryOp::URShift => Op::URShift { dst, left, right },
-            BinaryOp::In => Op::In { dst, left, right },
-            BinaryOp::Instanceof => Op::Instanceof { dst, left, right },
-        };
-        self.builder.emit(op);
+    /// Apply `binary` to its left operand, already in `dst`, and its right
+    /// operand
+    fn compile_binary_step(
+        &mut self,
+        binary: &crate::ast::BinaryExpression,
+        dst: Register,
+    ) -> Result<(), JsError> {
+        if self.builder.registers_in_use() < SPILL_THRESHOLD {
+            let right = self.builder.alloc_register()?;
+            self.compile_expression(&binary.right, right)?;
+            self.builder.set_span(binary.span);
+            self.builder
+                .emit(binary_op(binary.operator, dst, dst, right));
+            self.builder.free_register(right);
+            return Ok(());
+        }
 
-        self.builder.free_register(right);
+        // Too few registers left to hold the left operand while the right
+        // one is evaluated: keep it in a scope of its own meanwhile
+        let slot = self.builder.add_string(JsString::from(SPILL_SLOT))?;
+        self.builder.emit(Op::PushScope);
+        self.builder.emit(Op::DeclareVar {
+            name: slot,
+            init: dst,
+            mutable: false,
+        });
+        self.compile_expression(&binary.right, dst)?;
+        let left = self.builder.alloc_register()?;
+        self.builder.emit(Op::GetVar {
+            dst: left,
+            name: slot,
+        });
+        self.builder.emit(Op::PopScope);
+        self.builder.set_span(binary.span);
+        self.builder
+            .emit(binary_op(binary.operator, dst, left, dst));
         self.builder.free_register(left);
         Ok(())
     }
@@ -777,8 +915,8 @@ impl Compiler {
         let redirect_reg = self.get_loop_var_redirect(&id.name);
 
         if *op == AssignmentOp::Assign {
-            // Simple assignment
-            self.compile_expression(right, dst)?;
+            // Simple assignment; `f = () => {}` names the function "f"
+            self.compile_expression_with_inferred_name(right, dst, Some(id.name.cheap_clone()))?;
 
             if let Some(reg) = redirect_reg {
                 // Redirect: write to register instead of environment
@@ -805,12 +943,20 @@ impl Compiler {
             match op {
                 AssignmentOp::AndAssign => {
                     let skip = self.builder.emit_jump_if_false(dst);
-                    self.compile_expression(right, dst)?;
+                    self.compile_expression_with_inferred_name(
+                        right,
+                        dst,
+                        Some(id.name.cheap_clone()),
+                    )?;
                     self.builder.patch_jump(skip);
                 }
                 AssignmentOp::OrAssign => {
                     let skip = self.builder.emit_jump_if_true(dst);
-                    self.compile_expression(right, dst)?;
+                    self.compile_expression_with_inferred_name(
+                        right,
+                        dst,
+                        Some(id.name.cheap_clone()),
+                    )?;
                     self.builder.patch_jump(skip);
                 }
                 AssignmentOp::NullishAssign => {
@@ -818,7 +964,11 @@ impl Compiler {
                         cond: dst,
                         target: 0,
                     });
-                    self.compile_expression(right, dst)?;
+                    self.compile_expression_with_inferred_name(
+                        right,
+                        dst,
+                        Some(id.name.cheap_clone()),
+                    )?;
                     self.builder.patch_jump(super::JumpPlaceholder {
                         instruction_index: not_nullish,
                     });
@@ -872,10 +1022,12 @@ impl Compiler {
         if *op == AssignmentOp::Assign {
             // Simple assignment
             self.compile_expression(right, dst)?;
-            self.emit_set_property(obj_reg, &key_info, dst)?;
+            let index = self.emit_set_property(obj_reg, &key_info, dst)?;
+            self.name_operand(index, &member.object);
         } else {
             // Compound assignment - load current value first
-            self.emit_get_property(dst, obj_reg, &key_info)?;
+            let index = self.emit_get_property(dst, obj_reg, &key_info)?;
+            self.name_operand(index, &member.object);
 
             // Handle short-circuit operators
             match op {
@@ -1158,7 +1310,8 @@ impl Compiler {
                 let key_info = self.get_member_key_info(&member.property)?;
 
                 // Load current value
-                self.emit_get_property(dst, obj_reg, &key_info)?;
+                let index = self.emit_get_property(dst, obj_reg, &key_info)?;
+                self.name_operand(index, &member.object);
 
                 let one = self.builder.alloc_register()?;
                 self.builder.emit(Op::LoadInt { dst: one, value: 1 });
@@ -1273,6 +1426,15 @@ impl Compiler {
             return Ok(());
         }
 
+        // A constant read through imports loads the value directly
+        if let Some(value) = self
+            .constant_imports
+            .as_ref()
+            .and_then(|imports| imports.resolve_member(member))
+        {
+            return self.compile_literal(&value, dst);
+        }
+
         // Compile object
         let obj_reg = self.builder.alloc_register()?;
         self.compile_expression(&member.object, obj_reg)?;
@@ -1281,20 +1443,22 @@ impl Compiler {
         match &member.property {
             MemberProperty::Identifier(id) => {
                 let key_idx = self.builder.add_string(id.name.cheap_clone())?;
-                self.builder.emit(Op::GetPropertyConst {
+                let index = self.builder.emit(Op::GetPropertyConst {
                     dst,
                     obj: obj_reg,
                     key: key_idx,
                 });
+                self.name_operand(index, &member.object);
             }
             MemberProperty::Expression(expr) => {
                 let key_reg = self.builder.alloc_register()?;
                 self.compile_expression(expr, key_reg)?;
-                self.builder.emit(Op::GetProperty {
+                let index = self.builder.emit(Op::GetProperty {
                     dst,
                     obj: obj_reg,
                     key: key_reg,
                 });
+                self.name_operand(index, &member.object);
                 self.builder.free_register(key_reg);
             }
             MemberProperty::PrivateIdentifier(id) => {
@@ -1554,6 +1718,7 @@ impl Compiler {
 
             // Call the method
             self.emit_call(dst, method_reg, obj_reg, args_start, argc, has_spread);
+            self.builder.free_registers(args_start, argc);
 
             self.builder.free_register(method_reg);
             self.builder.free_register(obj_reg);
@@ -1640,6 +1805,7 @@ impl Compiler {
 
             // Call the method with obj as this
             self.emit_call(dst, method_reg, obj_reg, args_start, argc, has_spread);
+            self.builder.free_registers(args_start, argc);
 
             self.builder.free_register(method_reg);
             self.builder.free_register(obj_reg);
@@ -1680,6 +1846,7 @@ impl Compiler {
 
             // Call
             self.emit_call(dst, callee_reg, this_reg, args_start, argc, has_spread);
+            self.builder.free_registers(args_start, argc);
 
             self.builder.free_register(this_reg);
             self.builder.free_register(callee_reg);
@@ -1723,14 +1890,26 @@ impl Compiler {
     ) -> Result<(), JsError> {
         // Handle super() call
         if matches!(call.callee.as_ref(), Expression::Super(_)) {
-            // Compile arguments (spread not supported for super calls yet)
-            let (args_start, argc, _has_spread) = self.compile_arguments(&call.arguments)?;
+            let (args_start, argc, has_spread) = self.compile_arguments(&call.arguments)?;
 
-            self.builder.emit(Op::SuperCall {
-                dst,
-                args_start,
-                argc,
-            });
+            if has_spread {
+                self.builder.emit(Op::SuperCallSpread {
+                    dst,
+                    args_array: args_start,
+                });
+            } else {
+                self.builder.emit(Op::SuperCall {
+                    dst,
+                    args_start,
+                    argc,
+                });
+            }
+            self.builder.free_registers(args_start, argc);
+
+            // `this` is initialized now, so the derived class's fields can be set
+            if let Some(init) = self.instance_init.clone() {
+                self.emit_instance_init(&init)?;
+            }
             return Ok(());
         }
 
@@ -1758,6 +1937,7 @@ impl Compiler {
                     self.builder.emit(Op::LoadThis { dst: this_reg });
 
                     self.emit_call(dst, method_reg, this_reg, args_start, argc, has_spread);
+                    self.builder.free_registers(args_start, argc);
 
                     self.builder.free_register(this_reg);
                     self.builder.free_register(method_reg);
@@ -1783,6 +1963,7 @@ impl Compiler {
                     self.builder.emit(Op::LoadThis { dst: this_reg });
 
                     self.emit_call(dst, method_reg, this_reg, args_start, argc, has_spread);
+                    self.builder.free_registers(args_start, argc);
 
                     self.builder.free_register(this_reg);
                     self.builder.free_register(method_reg);
@@ -1810,17 +1991,21 @@ impl Compiler {
                     // Get method from object (may throw if obj is undefined/null)
                     let method_key = self.builder.add_string(method_name.name.cheap_clone())?;
                     let method_reg = self.builder.alloc_register()?;
-                    self.builder.emit(Op::GetPropertyConst {
+                    let index = self.builder.emit(Op::GetPropertyConst {
                         dst: method_reg,
                         obj: obj_reg,
                         key: method_key,
                     });
+                    self.name_operand(index, &member.object);
 
                     // Now compile arguments (only after callee is evaluated)
                     let (args_start, argc, has_spread) = self.compile_arguments(&call.arguments)?;
 
                     // Call with obj as this
-                    self.emit_call(dst, method_reg, obj_reg, args_start, argc, has_spread);
+                    let index =
+                        self.emit_call(dst, method_reg, obj_reg, args_start, argc, has_spread);
+                    self.builder.free_registers(args_start, argc);
+                    self.name_operand(index, &call.callee);
 
                     self.builder.free_register(method_reg);
                     self.builder.free_register(obj_reg);
@@ -1836,17 +2021,19 @@ impl Compiler {
                     self.compile_expression(key_expr, key_reg)?;
 
                     let method_reg = self.builder.alloc_register()?;
-                    self.builder.emit(Op::GetProperty {
+                    let index = self.builder.emit(Op::GetProperty {
                         dst: method_reg,
                         obj: obj_reg,
                         key: key_reg,
                     });
+                    self.name_operand(index, &member.object);
 
                     // Compile arguments (after callee is evaluated)
                     let (args_start, argc, has_spread) = self.compile_arguments(&call.arguments)?;
 
                     // Call with obj as this
                     self.emit_call(dst, method_reg, obj_reg, args_start, argc, has_spread);
+                    self.builder.free_registers(args_start, argc);
 
                     self.builder.free_register(method_reg);
                     self.builder.free_register(key_reg);
@@ -1881,6 +2068,7 @@ impl Compiler {
 
                     // Call with obj as this
                     self.emit_call(dst, method_reg, obj_reg, args_start, argc, has_spread);
+                    self.builder.free_registers(args_start, argc);
 
                     self.builder.free_register(method_reg);
                     self.builder.free_register(obj_reg);
@@ -1983,6 +2171,7 @@ impl Compiler {
 
                 // Call the method with obj as this
                 self.emit_call(dst, method_reg, obj_reg, args_start, argc, has_spread);
+                self.builder.free_registers(args_start, argc);
 
                 let skip_undefined = self.builder.emit_jump();
                 self.builder.patch_jump(end_label);
@@ -2036,6 +2225,7 @@ impl Compiler {
 
                 // Call the method with obj as this
                 self.emit_call(dst, method_reg, obj_reg, args_start, argc, has_spread);
+                self.builder.free_registers(args_start, argc);
 
                 self.builder.free_register(method_reg);
             }
@@ -2055,7 +2245,9 @@ impl Compiler {
         // Compile arguments
         let (args_start, argc, has_spread) = self.compile_arguments(&call.arguments)?;
 
-        self.emit_call(dst, callee_reg, this_reg, args_start, argc, has_spread);
+        let index = self.emit_call(dst, callee_reg, this_reg, args_start, argc, has_spread);
+        self.builder.free_registers(args_start, argc);
+        self.name_operand(index, &call.callee);
 
         self.builder.free_register(this_reg);
         self.builder.free_register(callee_reg);
@@ -2072,6 +2264,19 @@ impl Compiler {
     /// Returns (args_start, argc, has_spread)
     /// If has_spread is true, args_start points to a single register containing the args array
     fn compile_arguments(&mut self, args: &[Argument]) -> Result<(Register, u8, bool), JsError> {
+        // Map the call instruction back to the callee, not the last argument
+        let call_span = self.builder.current_span();
+        let compiled = self.compile_argument_values(args)?;
+        if let Some(span) = call_span {
+            self.builder.set_span(span);
+        }
+        Ok(compiled)
+    }
+
+    fn compile_argument_values(
+        &mut self,
+        args: &[Argument],
+    ) -> Result<(Register, u8, bool), JsError> {
         let argc = args.len();
         if argc > 255 {
             return Err(JsError::syntax_error_simple("Too many arguments"));
@@ -2152,7 +2357,7 @@ impl Compiler {
         args_start: Register,
         argc: u8,
         has_spread: bool,
-    ) {
+    ) -> usize {
         if has_spread {
             self.builder.emit(Op::CallSpread {
                 dst,
@@ -2160,7 +2365,7 @@ impl Compiler {
                 this,
                 args_start,
                 argc,
-            });
+            })
         } else {
             self.builder.emit(Op::Call {
                 dst,
@@ -2168,7 +2373,35 @@ impl Compiler {
                 this,
                 args_start,
                 argc,
-            });
+            })
+        }
+    }
+
+    /// Source-level name of an expression for error messages: an identifier,
+    /// `this`, or a non-optional member chain of identifiers like `config.spec`.
+    /// Identifiers keep their interned name so the VM can look up the binding.
+    fn operand_name(expr: &Expression) -> Option<JsString> {
+        match expr {
+            Expression::Identifier(id) => Some(id.name.cheap_clone()),
+            Expression::This(_) => Some(JsString::from("this")),
+            Expression::Parenthesized(inner, _) => Self::operand_name(inner),
+            Expression::TypeAssertion(ta) => Self::operand_name(&ta.expression),
+            Expression::NonNull(nn) => Self::operand_name(&nn.expression),
+            Expression::Member(member) if !member.optional => match &member.property {
+                MemberProperty::Identifier(prop) => {
+                    let object = Self::operand_name(&member.object)?;
+                    Some(JsString::from(format!("{}.{}", object, prop.name)))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Record `expr`'s source-level name for the instruction at `index`, if it has one
+    fn name_operand(&mut self, index: usize, expr: &Expression) {
+        if let Some(name) = Self::operand_name(expr) {
+            self.builder.name_operand(index, name);
         }
     }
 
@@ -2199,6 +2432,7 @@ impl Compiler {
             });
         }
 
+        self.builder.free_registers(args_start, argc);
         self.builder.free_register(callee_reg);
         Ok(())
     }
@@ -2303,6 +2537,7 @@ impl Compiler {
             start,
             count: reg_idx,
         });
+        self.builder.free_registers(start, total_parts as u8);
 
         Ok(())
     }
@@ -2420,6 +2655,7 @@ impl Compiler {
 
         // Clean up
         self.builder.free_register(final_this_reg);
+        self.builder.free_registers(exprs_start, exprs_count as u8);
         self.builder.free_register(tag_reg);
 
         Ok(())
@@ -2437,7 +2673,7 @@ impl Compiler {
     }
 
     /// Compile function expression with an optional inferred name
-    fn compile_function_expression_with_name(
+    pub(crate) fn compile_function_expression_with_name(
         &mut self,
         func: &crate::ast::FunctionExpression,
         dst: Register,
@@ -2447,7 +2683,7 @@ impl Compiler {
         let func_name = name.or_else(|| func.id.as_ref().map(|id| id.name.cheap_clone()));
 
         // Use the existing compile_function_body from compile_stmt
-        let chunk = self.compile_function_body(
+        let mut chunk = self.compile_function_body(
             &func.params,
             &func.body.body,
             func_name,
@@ -2455,6 +2691,7 @@ impl Compiler {
             func.async_,
             false, // is_arrow = false
         )?;
+        self.attach_function_source(&mut chunk, func.span);
 
         // Add the chunk to constants
         let chunk_idx = self
@@ -2493,7 +2730,7 @@ impl Compiler {
         name: Option<JsString>,
     ) -> Result<(), JsError> {
         // Compile the arrow function body
-        let chunk = match arrow.body.as_ref() {
+        let mut chunk = match arrow.body.as_ref() {
             crate::ast::ArrowFunctionBody::Block(block) => self.compile_function_body(
                 &arrow.params,
                 &block.body,
@@ -2505,6 +2742,7 @@ impl Compiler {
             crate::ast::ArrowFunctionBody::Expression(expr) => self
                 .compile_arrow_expression_body_with_name(&arrow.params, expr, arrow.async_, name)?,
         };
+        self.attach_function_source(&mut chunk, arrow.span);
 
         // Add chunk to constants
         let chunk_idx = self
@@ -2527,62 +2765,11 @@ impl Compiler {
         use super::FunctionInfo;
 
         // Create a new compiler for the function body
-        let mut func_compiler = super::Compiler::new();
-
-        // Reserve registers for parameters - they are passed in registers 0, 1, 2...
-        // We must reserve these before any other register allocation
-        if !params.is_empty() {
-            func_compiler
-                .builder
-                .reserve_registers(params.len() as u8)?;
-        }
+        let mut func_compiler = self.nested_compiler(None)?;
+        func_compiler.instance_init = self.instance_init.clone();
+        func_compiler.strict = self.strict || !self.class_context_stack.is_empty();
 
-        // Compile parameter declarations
-        let mut param_names = Vec::with_capacity(params.len());
-        let mut rest_param = None;
-
-        for (idx, param) in params.iter().enumerate() {
-            match &param.pattern {
-                crate::ast::Pattern::Identifier(id) => {
-                    param_names.push(id.name.cheap_clone());
-
-                    // Load argument from register and declare variable
-                    let arg_reg = idx as u8;
-                    let name_idx = func_compiler.builder.add_string(id.name.cheap_clone())?;
-                    func_compiler.builder.emit(Op::DeclareVar {
-                        name: name_idx,
-                        init: arg_reg,
-                        mutable: true,
-                    });
-                }
-                crate::ast::Pattern::Rest(rest) => {
-                    rest_param = Some(idx);
-                    if let crate::ast::Pattern::Identifier(id) = &*rest.argument {
-                        param_names.push(id.name.cheap_clone());
-
-                        let arg_reg = idx as u8;
-                        let name_idx = func_compiler.builder.add_string(id.name.cheap_clone())?;
-                        func_compiler.builder.emit(Op::DeclareVar {
-                            name: name_idx,
-                            init: arg_reg,
-                            mutable: true,
-                        });
-                    }
-                }
-                crate::ast::Pattern::Object(_) | crate::ast::Pattern::Array(_) => {
-                    // Handle destructuring patterns in function parameters
-                    // The argument is in arg_reg, compile the pattern binding
-                    let arg_reg = idx as u8;
-                    param_names.push(JsString::from(format!("__param{}__", idx)));
-                    func_compiler.compile_pattern_binding(&param.pattern, arg_reg, true, false)?;
-                }
-                crate::ast::Pattern::Assignment(_) => {
-                    // Parameter with default value - not fully supported in expression-bodied arrows
-                    // For now, just use a placeholder name
-                    param_names.push(JsString::from(format!("__param{}__", idx)));
-                }
-            }
-        }
+        let (param_names, rest_param) = func_compiler.compile_parameters(params)?;
 
         // Compile the expression and return it
         let result_reg = func_compiler.builder.alloc_register()?;
@@ -2594,18 +2781,23 @@ impl Compiler {
         let binding_count = super::hoist::count_function_bindings(params, &[], true);
 
         // Build the chunk with function info
-        let mut chunk = func_compiler.builder.finish();
+        let mut chunk = func_compiler.finish_chunk()?;
+        let creates_closures = chunk.creates_closures();
+        let local_names = chunk.declared_names(&param_names);
         chunk.function_info = Some(FunctionInfo {
             name: None,
-            param_count: params.len(),
+            param_count: super::expected_argument_count(params),
             is_generator: false,
             is_async,
             is_arrow: true,
             uses_arguments: false,
             uses_this: false,
+            creates_closures,
             param_names,
             rest_param,
+            local_names,
             binding_count,
+            source_text: None,
         });
 
         Ok(chunk)
@@ -2639,7 +2831,7 @@ impl Compiler {
     }
 
     /// Compile class expression with an optional inferred name
-    fn compile_class_expression_with_name(
+    pub(crate) fn compile_class_expression_with_name(
         &mut self,
         class: &crate::ast::ClassExpression,
         dst: Register,
@@ -2711,35 +2903,29 @@ impl Compiler {
         dst: Register,
         obj: Register,
         key_info: &MemberKeyInfo,
-    ) -> Result<(), JsError> {
-        match key_info {
-            MemberKeyInfo::Const(idx) => {
-                self.builder.emit(Op::GetPropertyConst {
-                    dst,
-                    obj,
-                    key: *idx,
-                });
-            }
-            MemberKeyInfo::Computed(reg) => {
-                self.builder.emit(Op::GetProperty {
-                    dst,
-                    obj,
-                    key: *reg,
-                });
-            }
+    ) -> Result<usize, JsError> {
+        let index = match key_info {
+            MemberKeyInfo::Const(idx) => self.builder.emit(Op::GetPropertyConst {
+                dst,
+                obj,
+                key: *idx,
+            }),
+            MemberKeyInfo::Computed(reg) => self.builder.emit(Op::GetProperty {
+                dst,
+                obj,
+                key: *reg,
+            }),
             MemberKeyInfo::Private {
                 class_brand,
                 field_name,
-            } => {
-                self.builder.emit(Op::GetPrivateField {
-                    dst,
-                    obj,
-                    class_brand: *class_brand,
-                    field_name: *field_name,
-                });
-            }
-        }
-        Ok(())
+            } => self.builder.emit(Op::GetPrivateField {
+                dst,
+                obj,
+                class_brand: *class_brand,
+                field_name: *field_name,
+            }),
+        };
+        Ok(index)
     }
 
     /// Emit set property based on key info
@@ -2748,34 +2934,53 @@ impl Compiler {
         obj: Register,
         key_info: &MemberKeyInfo,
         value: Register,
-    ) -> Result<(), JsError> {
-        match key_info {
-            MemberKeyInfo::Const(idx) => {
-                self.builder.emit(Op::SetPropertyConst {
-                    obj,
-                    key: *idx,
-                    value,
-                });
-            }
-            MemberKeyInfo::Computed(reg) => {
-                self.builder.emit(Op::SetProperty {
-                    obj,
-                    key: *reg,
-                    value,
-                });
-            }
+    ) -> Result<usize, JsError> {
+        let index = match key_info {
+            MemberKeyInfo::Const(idx) => self.builder.emit(Op::SetPropertyConst {
+                obj,
+                key: *idx,
+                value,
+            }),
+            MemberKeyInfo::Computed(reg) => self.builder.emit(Op::SetProperty {
+                obj,
+                key: *reg,
+                value,
+            }),
             MemberKeyInfo::Private {
                 class_brand,
                 field_name,
-            } => {
-                self.builder.emit(Op::SetPrivateField {
-                    obj,
-                    class_brand: *class_brand,
-                    field_name: *field_name,
-                    value,
-                });
-            }
-        }
-        Ok(())
+            } => self.builder.emit(Op::SetPrivateField {
+                obj,
+                class_brand: *class_brand,
+                field_name: *field_name,
+                value,
+            }),
+        };
+        Ok(index)
+    }
+}
+
+/// The name a function defined under a non-computed property key gets
+pub(super) fn static_key_name(key: &ObjectPropertyKey) -> Option<JsString> {
+    match key {
+        ObjectPropertyKey::Identifier(id) => Some(id.name.cheap_clone()),
+        ObjectPropertyKey::String(s) => Some(s.value.cheap_clone()),
+        ObjectPropertyKey::Number(lit) => match &lit.value {
+            LiteralValue::Number(n) => Some(JsString::from(crate::value::number_to_string(*n))),
+            _ => None,
+        },
+        ObjectPropertyKey::Computed(_) | ObjectPropertyKey::PrivateIdentifier(_) => None,
+    }
+}
+
+/// IsAnonymousFunctionDefinition: whether evaluating `expr` creates a
+/// function or class that takes its name from where it is defined
+fn is_anonymous_function_definition(expr: &Expression) -> bool {
+    match expr {
+        Expression::Function(func) => func.id.is_none(),
+        Expression::ArrowFunction(_) => true,
+        Expression::Class(class) => class.id.is_none(),
+        Expression::Parenthesized(inner, _) => is_anonymous_function_definition(inner),
+        _ => false,
     }
 }
@@ -4,6 +4,7 @@
 
 use super::Compiler;
 use super::bytecode::{Op, Register};
+use super::compile_expr::static_key_name;
 use crate::ast::{ObjectPatternProperty, ObjectPropertyKey, Pattern};
 use crate::error::JsError;
 use crate::prelude::*;
@@ -128,18 +129,11 @@ impl Compiler {
         for prop in &obj_pat.properties {
             match prop {
                 ObjectPatternProperty::KeyValue { key, .. } => {
-                    // Collect the key name for rest handling
-                    match key {
-                        ObjectPropertyKey::Identifier(id) => {
-                            extracted_keys.push(id.name.cheap_clone());
-                        }
-                        ObjectPropertyKey::String(s) => {
-                            extracted_keys.push(s.value.cheap_clone());
-                        }
-                        // Computed keys can't be statically known, skip them
-                        // (rest will still work but may include some extra props)
-                        ObjectPropertyKey::Computed(_) | ObjectPropertyKey::Number(_) => {}
-                        ObjectPropertyKey::PrivateIdentifier(_) => {}
+                    // Collect the key name for rest handling. Computed keys
+                    // can't be statically known, so they are skipped (rest
+                    // will still work but may include some extra props)
+                    if let Some(name) = static_key_name(key) {
+                        extracted_keys.push(name);
                     }
                 }
                 ObjectPatternProperty::Rest(_) => {
@@ -399,16 +393,11 @@ impl Compiler {
 
         for prop in &obj_pat.properties {
             match prop {
-                ObjectPatternProperty::KeyValue { key, .. } => match key {
-                    ObjectPropertyKey::Identifier(id) => {
-                        extracted_keys.push(id.name.cheap_clone());
-                    }
-                    ObjectPropertyKey::String(s) => {
-                        extracted_keys.push(s.value.cheap_clone());
+                ObjectPatternProperty::KeyValue { key, .. } => {
+                    if let Some(name) = static_key_name(key) {
+                        extracted_keys.push(name);
                     }
-                    ObjectPropertyKey::Computed(_) | ObjectPropertyKey::Number(_) => {}
-                    ObjectPropertyKey::PrivateIdentifier(_) => {}
-                },
+                }
                 ObjectPatternProperty::Rest(_) => {
                     has_rest = true;
                 }
@@ -3,22 +3,27 @@
 //! Compiles AST statements to bytecode instructions.
 
 use super::Compiler;
-use super::bytecode::{ConstantIndex, Op, Register};
+use super::bytecode::{Constant, ConstantIndex, JumpTarget, Op, Register};
 use crate::ast::{
     BlockStatement, BreakStatement, ClassConstructor, ClassDeclaration, ClassMember, ClassMethod,
-    ClassProperty, ContinueStatement, DoWhileStatement, ExportDeclaration, ForInOfLeft,
+    ClassProperty, ContinueStatement, DoWhileStatement, ExportDeclaration, Expression, ForInOfLeft,
     ForInStatement, ForInit, ForOfStatement, ForStatement, IfStatement, LabeledStatement,
-    MethodKind, ObjectPatternProperty, ObjectPropertyKey, Pattern, ReturnStatement, Statement,
-    SwitchStatement, ThrowStatement, TryStatement, VariableDeclaration, VariableKind,
+    LiteralValue, MethodKind, ObjectPatternProperty, ObjectPropertyKey, Pattern, ReturnStatement,
+    Statement, SwitchStatement, ThrowStatement, TryStatement, VariableDeclaration, VariableKind,
     WhileStatement,
 };
 use crate::error::JsError;
 use crate::prelude::*;
 use crate::value::{CheapClone, JsString};
 
+/// A switch needs at least this many string literal cases to be compiled
+/// to a SwitchString table lookup; shorter ones use a comparison chain
+const SWITCH_TABLE_MIN_CASES: usize = 4;
+
 impl Compiler {
     /// Compile a statement
     pub fn compile_statement_impl(&mut self, stmt: &Statement) -> Result<(), JsError> {
+        self.check_compile_limits()?;
         match stmt {
             Statement::Expression(expr_stmt) => {
                 self.builder.set_span(expr_stmt.span);
@@ -115,6 +120,21 @@ impl Compiler {
                 _ => None,
             };
 
+            // `var x;` without an initializer leaves the hoisted binding alone
+            if let Pattern::Identifier(id) = &declarator.id
+                && is_var
+                && declarator.init.is_none()
+                && self.is_hoisted(&id.name)
+            {
+                continue;
+            }
+
+            // Destructured let/const names are in their temporal dead zone while
+            // the initializer and pattern defaults run
+            if !is_var && !matches!(declarator.id, Pattern::Identifier(_)) {
+                self.emit_uninitialized_bindings(&declarator.id)?;
+            }
+
             // Compile initializer (or undefined)
             let init_reg = self.builder.alloc_register()?;
             if let Some(init) = &declarator.init {
@@ -140,6 +160,7 @@ impl Compiler {
 
         // Push a new scope
         self.builder.emit(Op::PushScope);
+        self.hoist_function_declarations(&block.body)?;
 
         if block.body.is_empty() && self.track_completion {
             // Empty block has completion value undefined
@@ -618,9 +639,9 @@ impl Compiler {
         // Loop start
         let loop_start = self.builder.current_offset();
 
-        // Push loop context with iterator register (for iterator close protocol)
+        // Push loop context with iterator register (for iterator close protocol).
+        // The continue target is set after the body, where PopIterTry runs.
         self.push_loop_with_iterator(None, Some(iter_reg));
-        self.set_continue_target(loop_start);
 
         // Get next value
         let result_reg = self.builder.alloc_register()?;
@@ -671,10 +692,18 @@ impl Compiler {
             catch_target: 0, // Will be patched
         });
 
+        // The handler counts as a try level, so break/continue/return know
+        // to close this iterator when they leave the body
+        self.try_depth += 1;
+
         // Compile body
         self.compile_statement_impl(&for_of.body)?;
 
-        // Pop iterator try handler (normal completion, no exception)
+        self.try_depth -= 1;
+
+        // Pop iterator try handler (normal completion or continue)
+        let continue_target = self.builder.current_offset();
+        self.set_continue_target(continue_target);
         self.builder.emit(Op::PopIterTry);
 
         // Jump back to start
@@ -684,8 +713,9 @@ impl Compiler {
         // This is where PushIterTry will jump on exception
         let catch_target = self.builder.current_offset();
 
-        // Close the iterator
-        self.builder.emit(Op::IteratorClose { iterator: iter_reg });
+        // Close the iterator, keeping the original exception
+        self.builder
+            .emit(Op::IteratorCloseOnThrow { iterator: iter_reg });
 
         // Rethrow the exception
         self.builder.emit(Op::Rethrow);
@@ -743,6 +773,11 @@ impl Compiler {
         let disc_reg = self.builder.alloc_register()?;
         self.compile_expression(&switch_stmt.discriminant, disc_reg)?;
 
+        // Functions declared in any case exist before the matching case runs
+        for case in switch_stmt.cases.iter() {
+            self.hoist_function_declarations(&case.consequent)?;
+        }
+
         // Push loop context for break (switch uses the same break mechanism)
         self.push_loop(None);
 
@@ -750,6 +785,26 @@ impl Compiler {
         let mut case_jumps: Vec<super::JumpPlaceholder> = Vec::new();
         let mut default_jump: Option<super::JumpPlaceholder> = None;
 
+        // With enough string literal cases, dispatch through a hash table
+        // instead of comparing against each case in turn
+        let string_cases: Option<Vec<&JsString>> = switch_stmt
+            .cases
+            .iter()
+            .filter_map(|case| case.test.as_ref())
+            .map(|test| match test.as_ref() {
+                Expression::Literal(lit) => match &lit.value {
+                    LiteralValue::String(s) => Some(s),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+        if let Some(string_cases) = string_cases
+            && string_cases.len() >= SWITCH_TABLE_MIN_CASES
+        {
+            return self.compile_string_switch(switch_stmt, disc_reg, string_cases);
+        }
+
         // First pass: emit comparison and jumps
         for case in switch_stmt.cases.iter() {
             if let Some(test) = &case.test {
@@ -817,17 +872,64 @@ impl Compiler {
         Ok(())
     }
 
-    /// Compile a return statement
-    fn compile_return(&mut self, return_stmt: &ReturnStatement) -> Result<(), JsError> {
-        self.builder.set_span(return_stmt.span);
+    /// Compile the dispatch and bodies of a switch whose cases are all string
+    /// literals as one SwitchString lookup. Called with the switch's loop
+    /// context pushed; pops it and frees `disc_reg`.
+    fn compile_string_switch(
+        &mut self,
+        switch_stmt: &SwitchStatement,
+        disc_reg: Register,
+        string_cases: Vec<&JsString>,
+    ) -> Result<(), JsError> {
+        let table = self
+            .builder
+            .add_constant(Constant::SwitchTable(FxHashMap::default()))?;
+        let dispatch = super::JumpPlaceholder {
+            instruction_index: self.builder.emit(Op::SwitchString {
+                value: disc_reg,
+                table,
+                default: 0,
+            }),
+        };
 
-        // Emit IteratorClose for all enclosing for-of loops before returning
-        // (iterate from innermost to outermost)
-        for i in (0..self.loop_stack.len()).rev() {
-            if let Some(iter_reg) = self.loop_stack.get(i).and_then(|ctx| ctx.iterator_reg) {
-                self.builder.emit(Op::IteratorClose { iterator: iter_reg });
+        // Emit case bodies, recording where each one starts. A repeated case
+        // string keeps its first body, as the comparison chain would.
+        let mut targets: FxHashMap<JsString, JumpTarget> = FxHashMap::default();
+        let mut string_cases = string_cases.into_iter();
+        let mut has_default = false;
+        for case in switch_stmt.cases.iter() {
+            let offset = self.builder.current_offset() as JumpTarget;
+            if case.test.is_some() {
+                if let Some(s) = string_cases.next() {
+                    targets.entry(s.cheap_clone()).or_insert(offset);
+                }
+            } else {
+                self.builder.patch_jump_to(dispatch, offset);
+                has_default = true;
+            }
+
+            for stmt in case.consequent.iter() {
+                self.compile_statement_impl(stmt)?;
             }
         }
+        self.builder.patch_switch_table(table, targets);
+
+        // Without a default clause, unmatched values skip every body
+        if !has_default {
+            self.builder.patch_jump(dispatch);
+        }
+
+        // Pop loop context (patches break jumps)
+        self.pop_loop();
+
+        self.builder.free_register(disc_reg);
+
+        Ok(())
+    }
+
+    /// Compile a return statement
+    fn compile_return(&mut self, return_stmt: &ReturnStatement) -> Result<(), JsError> {
+        self.builder.set_span(return_stmt.span);
 
         if let Some(argument) = &return_stmt.argument {
             let reg = self.builder.alloc_register()?;
@@ -921,6 +1023,7 @@ impl Compiler {
             }
 
             // Compile catch body
+            self.hoist_function_declarations(&handler.body.body)?;
             if handler.body.body.is_empty() && self.track_completion {
                 // Empty catch block has completion value undefined
                 self.builder.emit(Op::LoadUndefined { dst: 0 });
@@ -943,6 +1046,7 @@ impl Compiler {
             self.builder.set_span(finalizer.span);
 
             // Compile finally block
+            self.hoist_function_declarations(&finalizer.body)?;
             for stmt in finalizer.body.iter() {
                 self.compile_statement_impl(stmt)?;
             }
@@ -1010,13 +1114,63 @@ impl Compiler {
         &mut self,
         func: &crate::ast::FunctionDeclaration,
     ) -> Result<(), JsError> {
+        let ptr = core::ptr::from_ref(func);
+        if self.hoisted_functions.contains(&ptr) {
+            // Created on entering its scope; a sloppy-mode block function
+            // also stores its value in the var scope now (Annex B.3.3)
+            if self.var_scoped_functions.contains(&ptr)
+                && let Some(ref id) = func.id
+            {
+                let value_reg = self.builder.alloc_register()?;
+                let name_idx = self.builder.add_string(id.name.cheap_clone())?;
+                self.builder.emit(Op::GetVar {
+                    dst: value_reg,
+                    name: name_idx,
+                });
+                if self.global_var_scope {
+                    self.builder.emit(Op::SetGlobal {
+                        name: name_idx,
+                        src: value_reg,
+                    });
+                } else {
+                    self.builder.emit(Op::SetFunctionVar {
+                        name: name_idx,
+                        src: value_reg,
+                    });
+                }
+                self.builder.free_register(value_reg);
+            }
+            return Ok(());
+        }
+
+        let dst = self.compile_function_declaration_closure(func)?;
+
+        // If the function has a name, declare it as a variable
+        if let Some(ref id) = func.id {
+            let name_idx = self.builder.add_string(id.name.cheap_clone())?;
+            self.builder.emit(Op::DeclareVarHoisted {
+                name: name_idx,
+                init: dst,
+            });
+        }
+
+        self.builder.free_register(dst);
+
+        Ok(())
+    }
+
+    /// Create the function object of a declaration in a fresh register
+    pub(super) fn compile_function_declaration_closure(
+        &mut self,
+        func: &crate::ast::FunctionDeclaration,
+    ) -> Result<Register, JsError> {
         self.builder.set_span(func.span);
 
         // Get function name
         let name = func.id.as_ref().map(|id| id.name.cheap_clone());
 
         // Compile the function body to a nested chunk
-        let chunk = self.compile_function_body(
+        let mut chunk = self.compile_function_body(
             &func.params,
             &func.body.body,
             name.clone(),
@@ -1024,6 +1178,7 @@ impl Compiler {
             func.async_,
             false, // not an arrow function
         )?;
+        self.attach_function_source(&mut chunk, func.span);
 
         // Add the chunk to constants
         let chunk_idx = self.builder.add_chunk(chunk)?;
@@ -1043,144 +1198,130 @@ impl Compiler {
             self.builder.emit(Op::CreateClosure { dst, chunk_idx });
         }
 
-        // If the function has a name, declare it as a variable
-        if let Some(ref fn_name) = name {
-            let name_idx = self.builder.add_string(fn_name.cheap_clone())?;
-            self.builder.emit(Op::DeclareVarHoisted {
-                name: name_idx,
-                init: dst,
-            });
-        }
-
-        self.builder.free_register(dst);
-
-        Ok(())
+        Ok(dst)
     }
 
-    /// Compile function body to a nested BytecodeChunk
-    pub fn compile_function_body(
+    /// Bind parameters, passed in registers 0, 1, 2..., in the function environment.
+    ///
+    /// Parameters initialize left to right, so a default sees the parameters
+    /// before it. When any parameter has a default or a pattern, every
+    /// parameter name starts uninitialized and referencing a later one throws.
+    /// Returns the parameter names and the index of the rest parameter.
+    pub(crate) fn compile_parameters(
         &mut self,
         params: &[crate::ast::FunctionParam],
-        body: &[Statement],
-        name: Option<JsString>,
-        is_generator: bool,
-        is_async: bool,
-        is_arrow: bool,
-    ) -> Result<super::BytecodeChunk, JsError> {
-        use super::FunctionInfo;
-
-        // Create a new compiler for the function body
-        let mut func_compiler = Compiler::new();
-
-        // Propagate source file for stack traces
-        func_compiler.source_file = self.source_file.clone();
-        if let Some(ref path) = self.source_file {
-            func_compiler.builder.set_source_file(path.clone());
-        }
-
-        // Copy class context so private members can be accessed inside nested functions
-        func_compiler.class_context_stack = self.class_context_stack.clone();
-
+    ) -> Result<(Vec<JsString>, Option<usize>), JsError> {
         // Reserve registers for parameters - they are passed in registers 0, 1, 2...
         // We must reserve these before any other register allocation
         if !params.is_empty() {
-            func_compiler
-                .builder
-                .reserve_registers(params.len() as u8)?;
+            self.builder.reserve_registers(params.len() as u8)?;
+        }
+
+        let is_simple = params.iter().all(|param| match &param.pattern {
+            Pattern::Identifier(_) => true,
+            Pattern::Rest(rest) => matches!(*rest.argument, Pattern::Identifier(_)),
+            _ => false,
+        });
+        if !is_simple {
+            for param in params {
+                self.emit_uninitialized_bindings(&param.pattern)?;
+            }
         }
 
-        // Compile parameter declarations
-        // Parameters will be passed via registers and need to be bound to the environment
         let mut param_names = Vec::with_capacity(params.len());
         let mut rest_param = None;
 
         for (idx, param) in params.iter().enumerate() {
             let arg_reg = idx as u8;
 
-            match &param.pattern {
-                crate::ast::Pattern::Identifier(id) => {
+            let pattern = match &param.pattern {
+                Pattern::Rest(rest) => {
+                    rest_param = Some(idx);
+                    &*rest.argument
+                }
+                pattern => pattern,
+            };
+
+            match pattern {
+                Pattern::Identifier(id) => {
                     param_names.push(id.name.cheap_clone());
 
                     // Load argument from register and declare variable
-                    let name_idx = func_compiler.builder.add_string(id.name.cheap_clone())?;
-                    func_compiler.builder.emit(Op::DeclareVar {
+                    let name_idx = self.builder.add_string(id.name.cheap_clone())?;
+                    self.builder.emit(Op::DeclareVar {
                         name: name_idx,
                         init: arg_reg,
                         mutable: true,
                     });
                 }
-                crate::ast::Pattern::Rest(rest) => {
-                    rest_param = Some(idx);
-                    if let crate::ast::Pattern::Identifier(id) = &*rest.argument {
-                        param_names.push(id.name.cheap_clone());
-
-                        // For rest params, we'll need special handling (not fully implemented)
-                        let name_idx = func_compiler.builder.add_string(id.name.cheap_clone())?;
-                        func_compiler.builder.emit(Op::DeclareVar {
-                            name: name_idx,
-                            init: arg_reg,
-                            mutable: true,
-                        });
+                _ => {
+                    // Destructuring and defaults (`x = value` only evaluates `value`
+                    // when the argument is undefined)
+                    match pattern {
+                        Pattern::Assignment(assign_pat) => match assign_pat.left.as_ref() {
+                            Pattern::Identifier(id) => param_names.push(id.name.cheap_clone()),
+                            _ => param_names.push(JsString::from(format!("__param{}__", idx))),
+                        },
+                        _ => param_names.push(JsString::from(format!("__param{}__", idx))),
                     }
+                    self.compile_pattern_binding(pattern, arg_reg, true, false)?;
                 }
-                crate::ast::Pattern::Object(_) | crate::ast::Pattern::Array(_) => {
-                    // Handle destructuring patterns in function parameters
-                    // The argument is in arg_reg, compile the pattern binding
-                    param_names.push(JsString::from(format!("__param{}__", idx)));
-                    func_compiler.compile_pattern_binding(&param.pattern, arg_reg, true, false)?;
-                }
-                crate::ast::Pattern::Assignment(assign_pat) => {
-                    // Parameter with default value: param = defaultValue
-                    // If argument is undefined, use default value
-                    let actual_value = func_compiler.builder.alloc_register()?;
-
-                    // Check if arg is undefined
-                    let is_undefined = func_compiler.builder.alloc_register()?;
-                    func_compiler
-                        .builder
-                        .emit(Op::LoadUndefined { dst: is_undefined });
-                    func_compiler.builder.emit(Op::StrictEq {
-                        dst: is_undefined,
-                        left: arg_reg,
-                        right: is_undefined,
-                    });
+            }
+        }
 
-                    let skip_default = func_compiler.builder.emit_jump_if_false(is_undefined);
-                    func_compiler.builder.free_register(is_undefined);
+        // A `var` in the body with a parameter's name reuses the parameter binding
+        let mut bound_names = Vec::new();
+        for param in params {
+            super::hoist::collect_pattern_var_names(&param.pattern, &mut bound_names);
+        }
+        self.hoisted_vars.extend(bound_names);
 
-                    // Use default value
-                    func_compiler.compile_expression(&assign_pat.right, actual_value)?;
-                    let skip_arg = func_compiler.builder.emit_jump();
+        Ok((param_names, rest_param))
+    }
 
-                    // Use provided argument
-                    func_compiler.builder.patch_jump(skip_default);
-                    func_compiler.builder.emit(Op::Move {
-                        dst: actual_value,
-                        src: arg_reg,
-                    });
+    /// Declare every name bound by `pattern` as uninitialized (temporal dead zone)
+    pub(crate) fn emit_uninitialized_bindings(&mut self, pattern: &Pattern) -> Result<(), JsError> {
+        let mut names = Vec::new();
+        super::hoist::collect_pattern_var_names(pattern, &mut names);
+        for name in names {
+            let name_idx = self.builder.add_string(name)?;
+            self.builder
+                .emit(Op::DeclareUninitialized { name: name_idx });
+        }
+        Ok(())
+    }
+
+    /// Compile function body to a nested BytecodeChunk
+    pub fn compile_function_body(
+        &mut self,
+        params: &[crate::ast::FunctionParam],
+        body: &[Statement],
+        name: Option<JsString>,
+        is_generator: bool,
+        is_async: bool,
+        is_arrow: bool,
+    ) -> Result<super::BytecodeChunk, JsError> {
+        use super::FunctionInfo;
 
-                    func_compiler.builder.patch_jump(skip_arg);
+        // Create a new compiler for the function body, with the source file
+        // for stack traces and source text for toString
+        let mut func_compiler = self.nested_compiler(name.as_ref())?;
 
-                    // Bind the inner pattern
-                    func_compiler.compile_pattern_binding(
-                        &assign_pat.left,
-                        actual_value,
-                        true,
-                        false,
-                    )?;
+        // Copy class context so private members can be accessed inside nested functions
+        func_compiler.class_context_stack = self.class_context_stack.clone();
 
-                    // Extract name for param_names
-                    if let crate::ast::Pattern::Identifier(id) = assign_pat.left.as_ref() {
-                        param_names.push(id.name.cheap_clone());
-                    } else {
-                        param_names.push(JsString::from(format!("__param{}__", idx)));
-                    }
+        // Class bodies are strict mode code
+        func_compiler.strict = self.strict
+            || !self.class_context_stack.is_empty()
+            || super::hoist::is_strict_body(body);
 
-                    func_compiler.builder.free_register(actual_value);
-                }
-            }
+        // Arrows share the constructor's `this`, so super() in one runs the instance setup
+        if is_arrow {
+            func_compiler.instance_init = self.instance_init.clone();
         }
 
+        let (param_names, rest_param) = func_compiler.compile_parameters(params)?;
+
         // Hoist var declarations in the function body
         func_compiler.emit_hoisted_declarations(body)?;
 
@@ -1202,18 +1343,23 @@ impl Compiler {
         let binding_count = super::hoist::count_function_bindings(params, body, is_arrow);
 
         // Build the chunk with function info
-        let mut chunk = func_compiler.builder.finish();
+        let mut chunk = func_compiler.finish_chunk()?;
+        let creates_closures = chunk.creates_closures();
+        let local_names = chunk.declared_names(&param_names);
         chunk.function_info = Some(FunctionInfo {
             name,
-            param_count: params.len(),
+            param_count: super::expected_argument_count(params),
             is_generator,
             is_async,
             is_arrow,
             uses_arguments: false, // TODO: analyze function body
             uses_this: !is_arrow,
+            creates_closures,
             param_names,
             rest_param,
+            local_names,
             binding_count,
+            source_text: None,
         });
 
         // Make sure we have enough registers for parameters
@@ -1359,6 +1505,8 @@ impl Compiler {
                 ClassMember::StaticBlock(block) => {
                     static_blocks.push(block);
                 }
+                // Type-only
+                ClassMember::IndexSignature(_) => {}
             }
         }
 
@@ -1370,26 +1518,28 @@ impl Compiler {
 
         // Compile constructor (or create default one)
         let has_super = class.super_class.is_some();
-        let ctor_chunk = if let Some(ctor) = constructor {
-            self.compile_constructor_body(
-                ctor,
-                &instance_fields,
-                &instance_private_fields,
-                &instance_private_methods,
-                class_brand,
-                class_name.clone(),
-            )?
+        let instance_init = super::InstanceInit {
+            param_properties: Vec::new(),
+            fields: instance_fields.iter().map(|&field| field.clone()).collect(),
+            private_fields: instance_private_fields
+                .iter()
+                .map(|&field| field.clone())
+                .collect(),
+            private_methods: instance_private_methods
+                .iter()
+                .map(|&method| method.clone())
+                .collect(),
+            class_brand,
+        };
+        let mut ctor_chunk = if let Some(ctor) = constructor {
+            self.compile_constructor_body(ctor, instance_init, class_name.clone(), has_super)?
         } else {
-            self.compile_default_constructor(
-                &instance_fields,
-                &instance_private_fields,
-                &instance_private_methods,
-                class_brand,
-                class_name.clone(),
-                has_super,
-            )?
+            self.compile_default_constructor(&instance_init, class_name.clone(), has_super)?
         };
 
+        // The class constructor reports the whole class as its source
+        self.attach_function_source(&mut ctor_chunk, class.span);
+
         let ctor_chunk_idx = self.builder.add_chunk(ctor_chunk)?;
 
         // Create constructor register and emit CreateClosure
@@ -1641,15 +1791,23 @@ impl Compiler {
             }
         };
 
-        // Get method name for function naming (use None for computed)
-        let method_name = match &key {
-            MethodKey::Const(_, name) | MethodKey::Private(_, name) => Some(name.cheap_clone()),
-            MethodKey::Computed(_) => None,
+        // Get method name for function naming; computed keys are named at runtime
+        let method_name = match (&key, &method.kind) {
+            (MethodKey::Computed(_), _) => None,
+            (MethodKey::Const(_, name) | MethodKey::Private(_, name), MethodKind::Method) => {
+                Some(name.cheap_clone())
+            }
+            (MethodKey::Const(_, name) | MethodKey::Private(_, name), MethodKind::Get) => {
+                Some(JsString::from(format!("get {}", name.as_str())))
+            }
+            (MethodKey::Const(_, name) | MethodKey::Private(_, name), MethodKind::Set) => {
+                Some(JsString::from(format!("set {}", name.as_str())))
+            }
         };
 
         // Compile method body
         let func = &method.value;
-        let method_chunk = self.compile_function_body(
+        let mut method_chunk = self.compile_function_body(
             &func.params,
             &func.body.body,
             method_name,
@@ -1657,6 +1815,7 @@ impl Compiler {
             func.async_,
             false,
         )?;
+        self.attach_function_source(&mut method_chunk, func.span);
 
         let chunk_idx = self.builder.add_chunk(method_chunk)?;
 
@@ -1797,6 +1956,7 @@ impl Compiler {
                     getter: method_reg,
                     setter: undefined_reg,
                     is_static,
+                    enumerable: false,
                 });
 
                 self.builder.free_register(undefined_reg);
@@ -1812,6 +1972,7 @@ impl Compiler {
                     getter: method_reg,
                     setter: undefined_reg,
                     is_static,
+                    enumerable: false,
                 });
 
                 self.builder.free_register(undefined_reg);
@@ -1828,6 +1989,7 @@ impl Compiler {
                     getter: undefined_reg,
                     setter: method_reg,
                     is_static,
+                    enumerable: false,
                 });
 
                 self.builder.free_register(undefined_reg);
@@ -1843,6 +2005,7 @@ impl Compiler {
                     getter: undefined_reg,
                     setter: method_reg,
                     is_static,
+                    enumerable: false,
                 });
 
                 self.builder.free_register(undefined_reg);
@@ -2002,149 +2165,39 @@ impl Compiler {
     fn compile_constructor_body(
         &mut self,
         ctor: &ClassConstructor,
-        instance_fields: &[&ClassProperty],
-        instance_private_fields: &[&ClassProperty],
-        instance_private_methods: &[&ClassMethod],
-        class_brand: u32,
+        mut init: super::InstanceInit,
         name: Option<JsString>,
+        has_super: bool,
     ) -> Result<super::BytecodeChunk, JsError> {
         use super::FunctionInfo;
 
-        let mut func_compiler = Compiler::new();
+        let mut func_compiler = self.nested_compiler(name.as_ref())?;
 
         // Copy the class context so private field access works inside the constructor
         func_compiler.class_context_stack = self.class_context_stack.clone();
+        func_compiler.strict = true;
 
-        // Reserve registers for parameters
-        if !ctor.params.is_empty() {
-            func_compiler
-                .builder
-                .reserve_registers(ctor.params.len() as u8)?;
-        }
-
-        // Compile parameter declarations inline (same as compile_function_body)
-        // Also collect parameter properties (public/private/protected) to assign to this
-        let mut param_names = Vec::with_capacity(ctor.params.len());
-        // Param properties: (name, value_reg, needs_free)
-        // needs_free is true for registers allocated for default values
-        let mut param_properties: Vec<(JsString, u8, bool)> = Vec::new();
-
-        for (idx, param) in ctor.params.iter().enumerate() {
-            let arg_reg = idx as u8;
-
-            match &param.pattern {
-                crate::ast::Pattern::Identifier(id) => {
-                    param_names.push(id.name.cheap_clone());
-                    let name_idx = func_compiler.builder.add_string(id.name.cheap_clone())?;
-                    func_compiler.builder.emit(Op::DeclareVar {
-                        name: name_idx,
-                        init: arg_reg,
-                        mutable: true,
-                    });
-
-                    // If this is a parameter property (has accessibility or readonly), record it
-                    // arg_reg is a fixed argument register, doesn't need freeing
-                    if param.accessibility.is_some() || param.readonly {
-                        param_properties.push((id.name.cheap_clone(), arg_reg, false));
-                    }
-                }
-                crate::ast::Pattern::Rest(rest) => {
-                    if let crate::ast::Pattern::Identifier(id) = &*rest.argument {
-                        param_names.push(id.name.cheap_clone());
-                        let name_idx = func_compiler.builder.add_string(id.name.cheap_clone())?;
-                        func_compiler.builder.emit(Op::DeclareVar {
-                            name: name_idx,
-                            init: arg_reg,
-                            mutable: true,
-                        });
-                    }
-                }
-                crate::ast::Pattern::Object(_) | crate::ast::Pattern::Array(_) => {
-                    param_names.push(JsString::from(format!("__param{}__", idx)));
-                    func_compiler.compile_pattern_binding(&param.pattern, arg_reg, true, false)?;
-                }
-                crate::ast::Pattern::Assignment(assign_pat) => {
-                    let actual_value = func_compiler.builder.alloc_register()?;
-                    let is_undefined = func_compiler.builder.alloc_register()?;
-                    func_compiler
-                        .builder
-                        .emit(Op::LoadUndefined { dst: is_undefined });
-                    func_compiler.builder.emit(Op::StrictEq {
-                        dst: is_undefined,
-                        left: arg_reg,
-                        right: is_undefined,
-                    });
-
-                    let skip_default = func_compiler.builder.emit_jump_if_false(is_undefined);
-                    func_compiler.builder.free_register(is_undefined);
-
-                    func_compiler.compile_expression(&assign_pat.right, actual_value)?;
-                    let skip_arg = func_compiler.builder.emit_jump();
+        let (param_names, rest_param) = func_compiler.compile_parameters(&ctor.params)?;
 
-                    func_compiler.builder.patch_jump(skip_default);
-                    func_compiler.builder.emit(Op::Move {
-                        dst: actual_value,
-                        src: arg_reg,
-                    });
-
-                    func_compiler.builder.patch_jump(skip_arg);
-                    func_compiler.compile_pattern_binding(
-                        &assign_pat.left,
-                        actual_value,
-                        true,
-                        false,
-                    )?;
-
-                    if let crate::ast::Pattern::Identifier(id) = assign_pat.left.as_ref() {
-                        param_names.push(id.name.cheap_clone());
-                        // If this is a parameter property, record it
-                        // Note: we DON'T free actual_value here if it's a param property,
-                        // because it's needed later in the param_properties loop
-                        if param.accessibility.is_some() || param.readonly {
-                            param_properties.push((id.name.cheap_clone(), actual_value, true));
-                        } else {
-                            func_compiler.builder.free_register(actual_value);
-                        }
-                    } else {
-                        param_names.push(JsString::from(format!("__param{}__", idx)));
-                        func_compiler.builder.free_register(actual_value);
-                    }
-                }
-            }
-        }
-
-        // Emit parameter property assignments: this.x = x
-        // These happen before instance field initializers
-        for (prop_name, value_reg, needs_free) in &param_properties {
-            let this_reg = func_compiler.builder.alloc_register()?;
-            func_compiler.builder.emit(Op::LoadThis { dst: this_reg });
-            let prop_idx = func_compiler.builder.add_string(prop_name.cheap_clone())?;
-            func_compiler.builder.emit(Op::SetPropertyConst {
-                obj: this_reg,
-                key: prop_idx,
-                value: *value_reg,
-            });
-            func_compiler.builder.free_register(this_reg);
-            // Free registers allocated for default values after they've been used
-            if *needs_free {
-                func_compiler.builder.free_register(*value_reg);
-            }
-        }
-
-        // Compile instance field initializers at the start of constructor
-        // These run before the user's constructor body (after super() call if extending)
-        for field in instance_fields {
-            func_compiler.compile_instance_field_initializer(field)?;
-        }
-
-        // Initialize instance private fields
-        for field in instance_private_fields {
-            func_compiler.compile_instance_private_field_initializer(field, class_brand)?;
-        }
-
-        // Install instance private methods on 'this'
-        for method in instance_private_methods {
-            func_compiler.compile_instance_private_method_initializer(method, class_brand)?;
+        // Parameter properties (this.x = x), then instance fields. A derived
+        // constructor runs them after super() instead of on entry.
+        init.param_properties = ctor
+            .params
+            .iter()
+            .filter(|param| param.accessibility.is_some() || param.readonly)
+            .filter_map(|param| match &param.pattern {
+                Pattern::Identifier(id) => Some(id.name.cheap_clone()),
+                Pattern::Assignment(assign_pat) => match assign_pat.left.as_ref() {
+                    Pattern::Identifier(id) => Some(id.name.cheap_clone()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+        if has_super {
+            func_compiler.instance_init = Some(Rc::new(init));
+        } else {
+            func_compiler.emit_instance_init(&init)?;
         }
 
         // Hoist var declarations in constructor body
@@ -2164,18 +2217,23 @@ impl Compiler {
         let binding_count =
             super::hoist::count_function_bindings(&ctor.params, &ctor.body.body, false);
 
-        let mut chunk = func_compiler.builder.finish();
+        let mut chunk = func_compiler.finish_chunk()?;
+        let creates_closures = chunk.creates_closures();
+        let local_names = chunk.declared_names(&param_names);
         chunk.function_info = Some(FunctionInfo {
             name,
-            param_count: ctor.params.len(),
+            param_count: super::expected_argument_count(&ctor.params),
             param_names,
-            rest_param: None,
+            rest_param,
+            local_names,
             is_generator: false,
             is_async: false,
             is_arrow: false,
             uses_arguments: false,
             uses_this: true, // constructors use this
+            creates_closures,
             binding_count,
+            source_text: None,
         });
 
         Ok(chunk)
@@ -2185,19 +2243,17 @@ impl Compiler {
     /// For derived classes, this generates: constructor(...args) { super(...args); }
     fn compile_default_constructor(
         &mut self,
-        instance_fields: &[&ClassProperty],
-        instance_private_fields: &[&ClassProperty],
-        instance_private_methods: &[&ClassMethod],
-        class_brand: u32,
+        init: &super::InstanceInit,
         name: Option<JsString>,
         has_super: bool,
     ) -> Result<super::BytecodeChunk, JsError> {
         use super::FunctionInfo;
 
-        let mut func_compiler = Compiler::new();
+        let mut func_compiler = self.nested_compiler(name.as_ref())?;
 
         // Copy the class context so private field access works inside the constructor
         func_compiler.class_context_stack = self.class_context_stack.clone();
+        func_compiler.strict = true;
 
         // For derived classes, call super(...args) first to forward all arguments
         if has_super {
@@ -2217,43 +2273,73 @@ impl Compiler {
             func_compiler.builder.free_register(args_reg);
         }
 
-        // Compile instance field initializers (these run AFTER super() call)
-        for field in instance_fields {
-            func_compiler.compile_instance_field_initializer(field)?;
-        }
-
-        // Initialize instance private fields
-        for field in instance_private_fields {
-            func_compiler.compile_instance_private_field_initializer(field, class_brand)?;
-        }
-
-        // Install instance private methods on 'this'
-        for method in instance_private_methods {
-            func_compiler.compile_instance_private_method_initializer(method, class_brand)?;
-        }
+        // Instance fields run AFTER the super() call
+        func_compiler.emit_instance_init(init)?;
 
         // Return this
         let this_reg = func_compiler.builder.alloc_register()?;
         func_compiler.builder.emit(Op::LoadThis { dst: this_reg });
         func_compiler.builder.emit(Op::Return { value: this_reg });
 
-        let mut chunk = func_compiler.builder.finish();
+        let mut chunk = func_compiler.finish_chunk()?;
+        let creates_closures = chunk.creates_closures();
+        let local_names = chunk.declared_names(&[]);
         chunk.function_info = Some(FunctionInfo {
             name,
             param_count: 0,
             param_names: vec![],
+            local_names,
             rest_param: Some(0), // Rest parameter at index 0 to collect all args
             is_generator: false,
             is_async: false,
             is_arrow: false,
             uses_arguments: has_super, // Uses arguments if we have a super call
             uses_this: true,           // constructors use this
-            binding_count: 3,          // this + slack
+            creates_closures,
+            binding_count: 3, // this + slack
+            source_text: None,
         });
 
         Ok(chunk)
     }
 
+    /// Emit a constructor's instance setup on `this`
+    pub(super) fn emit_instance_init(&mut self, init: &super::InstanceInit) -> Result<(), JsError> {
+        // Parameter properties come before instance field initializers
+        for prop_name in &init.param_properties {
+            let name_idx = self.builder.add_string(prop_name.cheap_clone())?;
+            let value_reg = self.builder.alloc_register()?;
+            self.builder.emit(Op::GetVar {
+                dst: value_reg,
+                name: name_idx,
+            });
+            let this_reg = self.builder.alloc_register()?;
+            self.builder.emit(Op::LoadThis { dst: this_reg });
+            self.builder.emit(Op::SetPropertyConst {
+                obj: this_reg,
+                key: name_idx,
+                value: value_reg,
+            });
+            self.builder.free_register(this_reg);
+            self.builder.free_register(value_reg);
+        }
+
+        for field in &init.fields {
+            self.compile_instance_field_initializer(field)?;
+        }
+
+        // Initialize instance private fields
+        for field in &init.private_fields {
+            self.compile_instance_private_field_initializer(field, init.class_brand)?;
+        }
+
+        // Install instance private methods on 'this'
+        for method in &init.private_methods {
+            self.compile_instance_private_method_initializer(method, init.class_brand)?;
+        }
+        Ok(())
+    }
+
     /// Compile instance field initializer (this.field = value)
     fn compile_instance_field_initializer(&mut self, field: &ClassProperty) -> Result<(), JsError> {
         // Get field name
@@ -2263,7 +2349,7 @@ impl Compiler {
             _ => return Ok(()), // Skip computed/private for now
         };
 
-        let name_idx = self.builder.add_string(field_name)?;
+        let name_idx = self.builder.add_string(field_name.cheap_clone())?;
 
         // Get this
         let this_reg = self.builder.alloc_register()?;
@@ -2272,7 +2358,11 @@ impl Compiler {
         // Compile field initializer or use undefined
         let value_reg = self.builder.alloc_register()?;
         if let Some(init) = &field.value {
-            self.compile_expression(init, value_reg)?;
+            self.compile_expression_with_inferred_name(
+                init,
+                value_reg,
+                Some(field_name.cheap_clone()),
+            )?;
         } else {
             self.builder.emit(Op::LoadUndefined { dst: value_reg });
         }
@@ -2326,12 +2416,16 @@ impl Compiler {
             _ => return Ok(()), // Skip computed/private for now
         };
 
-        let name_idx = self.builder.add_string(field_name)?;
+        let name_idx = self.builder.add_string(field_name.cheap_clone())?;
 
         // Compile field initializer or use undefined
         let value_reg = self.builder.alloc_register()?;
         if let Some(init) = &field.value {
-            self.compile_expression(init, value_reg)?;
+            self.compile_expression_with_inferred_name(
+                init,
+                value_reg,
+                Some(field_name.cheap_clone()),
+            )?;
         } else {
             self.builder.emit(Op::LoadUndefined { dst: value_reg });
         }
@@ -2376,7 +2470,7 @@ impl Compiler {
             _ => return Ok(()), // Should only be called for private fields
         };
 
-        let name_idx = self.builder.add_string(field_name)?;
+        let name_idx = self.builder.add_string(field_name.cheap_clone())?;
 
         // Get this
         let this_reg = self.builder.alloc_register()?;
@@ -2385,7 +2479,11 @@ impl Compiler {
         // Compile field initializer or use undefined
         let value_reg = self.builder.alloc_register()?;
         if let Some(init) = &field.value {
-            self.compile_expression(init, value_reg)?;
+            self.compile_expression_with_inferred_name(
+                init,
+                value_reg,
+                Some(field_name.cheap_clone()),
+            )?;
         } else {
             self.builder.emit(Op::LoadUndefined { dst: value_reg });
         }
@@ -2464,12 +2562,16 @@ impl Compiler {
             _ => return Ok(()), // Should only be called for private fields
         };
 
-        let name_idx = self.builder.add_string(field_name)?;
+        let name_idx = self.builder.add_string(field_name.cheap_clone())?;
 
         // Compile field initializer or use undefined
         let value_reg = self.builder.alloc_register()?;
         if let Some(init) = &field.value {
-            self.compile_expression(init, value_reg)?;
+            self.compile_expression_with_inferred_name(
+                init,
+                value_reg,
+                Some(field_name.cheap_clone()),
+            )?;
         } else {
             self.builder.emit(Op::LoadUndefined { dst: value_reg });
         }
@@ -2504,7 +2606,7 @@ impl Compiler {
 
         // Compile method body
         let func = &method.value;
-        let method_chunk = self.compile_function_body(
+        let mut method_chunk = self.compile_function_body(
             &func.params,
             &func.body.body,
             Some(method_name),
@@ -2512,6 +2614,7 @@ impl Compiler {
             func.async_,
             false,
         )?;
+        self.attach_function_source(&mut method_chunk, func.span);
 
         let chunk_idx = self.builder.add_chunk(method_chunk)?;
 
@@ -2784,6 +2887,7 @@ impl Compiler {
 
         // Push a new scope for the namespace body
         self.builder.emit(Op::PushScope);
+        self.hoist_function_declarations(&decl.body)?;
 
         // Compile the namespace body statements
         for stmt in decl.body.iter() {
@@ -3072,8 +3176,12 @@ impl Compiler {
                     self.compile_class_expression_for_export(class, value_reg)?;
                 }
                 Statement::Expression(expr_stmt) => {
-                    // Compile expression
-                    self.compile_expression(&expr_stmt.expression, value_reg)?;
+                    // `export default () => {}` names the function "default"
+                    self.compile_expression_with_inferred_name(
+                        &expr_stmt.expression,
+                        value_reg,
+                        Some(JsString::from("default")),
+                    )?;
                 }
                 _ => {
                     // Shouldn't happen - other statements can't be default exported
@@ -3266,7 +3374,9 @@ impl Compiler {
             type_parameters: None,
         };
 
-        self.compile_function_expression(&func_expr, dst)?;
+        // An anonymous `export default function () {}` is named "default"
+        let name = func.id.is_none().then(|| JsString::from("default"));
+        self.compile_function_expression_with_name(&func_expr, dst, name)?;
         Ok(())
     }
 
@@ -3289,7 +3399,8 @@ impl Compiler {
             span: class.span,
         };
 
-        self.compile_class_expression(&class_expr, dst)?;
+        let name = class.id.is_none().then(|| JsString::from("default"));
+        self.compile_class_expression_with_name(&class_expr, dst, name)?;
         Ok(())
     }
 }
@@ -0,0 +1,208 @@
+//! Imported constants folded into the importing module's bytecode
+//!
+//! With [`crate::InterpreterConfig::fold_constant_imports`], a module is
+//! compiled after the modules it imports have run, so the values its
+//! imports resolve to are known. A read of an imported `const` holding a
+//! primitive, or a chain of static property names from an import through
+//! frozen plain objects and arrays to a primitive (`limits.cpu.default`),
+//! then loads the constant directly instead of resolving the import binding
+//! and looking up each property at run time.
+//!
+//! Folding is conservative and leaves everything else to the usual code:
+//! `let` and `var` exports, getters other than the live-binding getters of
+//! module namespaces, objects that aren't frozen, computed keys, inherited
+//! properties, and imports whose name the module declares anywhere else,
+//! since the compiler has no scope tracking to tell which binding a
+//! shadowed name refers to.
+
+use crate::prelude::*;
+
+use crate::ast::visit::Visitor;
+use crate::ast::{Expression, Identifier, LiteralValue, MemberExpression, MemberProperty, Program};
+use crate::gc::Gc;
+use crate::value::{
+    CheapClone, ExoticObject, JsFunction, JsObject, JsString, JsValue, PropertyKey, VarKey,
+};
+
+/// Longest chain of re-exports followed to the exporting module
+const MAX_REEXPORT_DEPTH: usize = 32;
+
+/// The imports of one module whose reads may fold, by local name
+#[derive(Default)]
+pub struct ConstantImports {
+    imports: FxHashMap<JsString, ImportedValue>,
+}
+
+/// Where an import binding reads from
+struct ImportedValue {
+    /// Namespace of the imported module
+    namespace: Gc<JsObject>,
+    /// Export read by a named or default import, `None` for `import * as`
+    export: Option<PropertyKey>,
+}
+
+impl ConstantImports {
+    /// Let reads of `local` fold to export `export` of `namespace`
+    pub fn add_named(&mut self, local: JsString, namespace: Gc<JsObject>, export: PropertyKey) {
+        self.imports.insert(
+            local,
+            ImportedValue {
+                namespace,
+                export: Some(export),
+            },
+        );
+    }
+
+    /// Let property chains from `local`, bound to `namespace`, fold
+    pub fn add_namespace(&mut self, local: JsString, namespace: Gc<JsObject>) {
+        self.imports.insert(
+            local,
+            ImportedValue {
+                namespace,
+                export: None,
+            },
+        );
+    }
+
+    /// Whether no import may fold
+    pub fn is_empty(&self) -> bool {
+        self.imports.is_empty()
+    }
+
+    /// The primitive `name` followed by the properties `path` reads, if
+    /// it can't change
+    fn resolve(&self, name: &JsString, path: &[&JsString]) -> Option<LiteralValue> {
+        let import = self.imports.get(name)?;
+        let mut value = JsValue::Object(import.namespace.cheap_clone());
+        if let Some(export) = &import.export {
+            value = constant_property(&value, export, 0)?;
+        }
+        for key in path {
+            value = constant_property(&value, &PropertyKey::String((*key).cheap_clone()), 0)?;
+        }
+        match value {
+            JsValue::Undefined => Some(LiteralValue::Undefined),
+            JsValue::Null => Some(LiteralValue::Null),
+            JsValue::Boolean(b) => Some(LiteralValue::Boolean(b)),
+            JsValue::Number(n) => Some(LiteralValue::Number(n)),
+            JsValue::String(s) => Some(LiteralValue::String(s)),
+            JsValue::Symbol(_) | JsValue::Object(_) => None,
+        }
+    }
+
+    /// The constant a read of `id` folds to
+    pub(super) fn resolve_identifier(&self, id: &Identifier) -> Option<LiteralValue> {
+        self.resolve(&id.name, &[])
+    }
+
+    /// The constant `member` folds to, if it is a chain of static property
+    /// names from an import
+    pub(super) fn resolve_member(&self, member: &MemberExpression) -> Option<LiteralValue> {
+        let mut path = Vec::new();
+        let mut current = member;
+        loop {
+            let MemberProperty::Identifier(key) = &current.property else {
+                return None;
+            };
+            if current.computed || current.optional {
+                return None;
+            }
+            path.push(&key.name);
+            match current.object.as_ref() {
+                Expression::Member(object) => current = object,
+                Expression::Identifier(root) => {
+                    path.reverse();
+                    return self.resolve(&root.name, &path);
+                }
+                _ => return None,
+            }
+        }
+    }
+}
+
+/// Own property `key` of `value`, if it can never read differently: a
+/// read-only data property of a plain object or array, as in frozen ones,
+/// or an export of a module namespace bound with `const`
+fn constant_property(value: &JsValue, key: &PropertyKey, depth: usize) -> Option<JsValue> {
+    let JsValue::Object(obj) = value else {
+        return None;
+    };
+    let obj = obj.borrow();
+    let prop = match (&obj.exotic, key) {
+        (ExoticObject::Array { elements }, PropertyKey::String(name))
+            if obj.frozen && name.as_str() == "length" =>
+        {
+            return Some(JsValue::Number(elements.len() as f64));
+        }
+        (ExoticObject::Ordinary | ExoticObject::Array { .. }, _) => obj.get_own_property(key)?,
+        _ => return None,
+    };
+    let Some(getter) = prop.getter() else {
+        // A data property that can be neither written nor redefined
+        let fixed = !prop.is_accessor() && !prop.writable() && !prop.configurable();
+        return fixed.then(|| prop.value.clone());
+    };
+    // Namespaces read their exports through getters, which only they hold
+    let getter = getter.borrow();
+    let ExoticObject::Function(function) = &getter.exotic else {
+        return None;
+    };
+    match function {
+        JsFunction::ModuleExportGetter {
+            module_env,
+            binding_name,
+        } => const_binding(module_env, binding_name, depth),
+        JsFunction::ModuleReExportGetter {
+            source_module,
+            source_key,
+        } if depth < MAX_REEXPORT_DEPTH => constant_property(
+            &JsValue::Object(source_module.cheap_clone()),
+            source_key,
+            depth + 1,
+        ),
+        _ => None,
+    }
+}
+
+/// The value of `const` binding `name` of module environment `env`
+fn const_binding(env: &Gc<JsObject>, name: &JsString, depth: usize) -> Option<JsValue> {
+    let env = env.borrow();
+    let binding = env
+        .as_environment()?
+        .bindings
+        .get(&VarKey(name.cheap_clone()))?;
+    if binding.mutable || !binding.initialized {
+        return None;
+    }
+    match &binding.import_binding {
+        // An import the module exports again
+        Some(import) if depth < MAX_REEXPORT_DEPTH => constant_property(
+            &JsValue::Object(import.module_obj.cheap_clone()),
+            &import.property_key,
+            depth + 1,
+        ),
+        Some(_) => None,
+        None => Some(binding.value.clone()),
+    }
+}
+
+/// Whether `program` mentions `eval` or `Function`, which turns folding off
+/// for the whole run
+pub fn uses_dynamic_code(program: &Program) -> bool {
+    let mut scan = DynamicCodeScan::default();
+    scan.visit_program(program);
+    scan.found
+}
+
+#[derive(Default)]
+struct DynamicCodeScan {
+    found: bool,
+}
+
+impl Visitor for DynamicCodeScan {
+    fn visit_identifier(&mut self, id: &Identifier) {
+        if matches!(id.name.as_str(), "eval" | "Function") {
+            self.found = true;
+        }
+    }
+}
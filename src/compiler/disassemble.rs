@@ -0,0 +1,1068 @@
+//! Human-readable listings of compiled bytecode
+//!
+//! Used to inspect what the compiler emitted when execution goes wrong. The
+//! format is kept stable so tests can snapshot it:
+//!
+//! ```text
+//! == <main> (registers: 2) ==
+//!    0  LoadInt                 dst=r0, value=1
+//!    1  JumpIfFalse             cond=r0, target=L3
+//! L3:
+//!    3  Halt
+//! ```
+//!
+//! Constant operands are shown inline, jump targets as labels, and nested
+//! function chunks follow their parent, indented one level further.
+
+use core::fmt::Write;
+
+use crate::prelude::*;
+
+use super::bytecode::{BytecodeChunk, Constant, ConstantIndex, JumpTarget, Op, Register};
+
+/// One instruction operand, classified by how it should be printed
+#[derive(Clone, Copy)]
+enum Operand {
+    Reg(Register),
+    Const(ConstantIndex),
+    Label(JumpTarget),
+    /// Exception handler target, where 0 means the handler has no such block
+    Handler(JumpTarget),
+    Imm(i64),
+    Flag(bool),
+}
+
+use Operand::{Const, Flag, Handler, Imm, Label, Reg};
+
+impl BytecodeChunk {
+    /// Render the chunk and every function chunk nested in it as text
+    pub fn disassemble(&self) -> String {
+        let mut out = String::new();
+        self.disassemble_into(&mut out, 0);
+        out
+    }
+
+    fn disassemble_into(&self, out: &mut String, depth: usize) {
+        let indent = "  ".repeat(depth);
+
+        let _ = writeln!(out, "{}== {} ==", indent, self.header());
+
+        let mut labels: Vec<JumpTarget> = self
+            .code
+            .iter()
+            .flat_map(|op| operands(*op).1)
+            .filter_map(|(_, operand)| match operand {
+                Label(target) => Some(target),
+                Handler(target) if target != 0 => Some(target),
+                _ => None,
+            })
+            .collect();
+        // Switch tables jump to case bodies too
+        for constant in &self.constants {
+            if let Constant::SwitchTable(table) = constant {
+                labels.extend(table.values().copied());
+            }
+        }
+        labels.sort_unstable();
+        labels.dedup();
+
+        for (index, op) in self.code.iter().enumerate() {
+            if labels.binary_search(&(index as JumpTarget)).is_ok() {
+                let _ = writeln!(out, "{}L{}:", indent, index);
+            }
+            let (name, fields) = operands(*op);
+            let rendered: Vec<String> = fields
+                .iter()
+                .map(|(field, operand)| format!("{}={}", field, self.render(*operand)))
+                .collect();
+            let line = format!("{:>4}  {:<24}{}", index, name, rendered.join(", "));
+            let _ = writeln!(out, "{}{}", indent, line.trim_end());
+        }
+        // A jump past the last instruction targets the end of the chunk
+        if labels
+            .last()
+            .is_some_and(|&t| t as usize >= self.code.len())
+        {
+            let _ = writeln!(out, "{}L{}:", indent, self.code.len());
+        }
+
+        for constant in &self.constants {
+            if let Constant::Chunk(chunk) = constant {
+                out.push('\n');
+                chunk.disassemble_into(out, depth + 1);
+            }
+        }
+    }
+
+    /// `<main>` or the function's signature, plus the register count
+    fn header(&self) -> String {
+        let Some(info) = &self.function_info else {
+            return format!("<main> (registers: {})", self.register_count);
+        };
+        let mut kind = String::new();
+        if info.is_async {
+            kind.push_str("async ");
+        }
+        kind.push_str(if info.is_arrow { "arrow" } else { "function" });
+        if info.is_generator {
+            kind.push('*');
+        }
+        if let Some(name) = &info.name {
+            kind.push(' ');
+            kind.push_str(name.as_str());
+        }
+        let params: Vec<&str> = info.param_names.iter().map(|p| p.as_str()).collect();
+        format!(
+            "{}({}) (registers: {})",
+            kind,
+            params.join(", "),
+            self.register_count
+        )
+    }
+
+    fn render(&self, operand: Operand) -> String {
+        match operand {
+            Reg(r) => format!("r{}", r),
+            Label(target) => format!("L{}", target),
+            Handler(0) => "none".to_string(),
+            Handler(target) => format!("L{}", target),
+            Imm(n) => n.to_string(),
+            Flag(b) => b.to_string(),
+            Const(idx) => match self.constants.get(idx as usize) {
+                Some(constant) => render_constant(constant),
+                None => format!("#{}?", idx),
+            },
+        }
+    }
+}
+
+fn render_constant(constant: &Constant) -> String {
+    let quoted = |strings: &[crate::value::JsString]| {
+        strings
+            .iter()
+            .map(|s| format!("{:?}", s.as_str()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match constant {
+        Constant::String(s) => format!("{:?}", s.as_str()),
+        Constant::Number(n) => crate::value::number_to_string(*n),
+        Constant::Chunk(chunk) => {
+            match chunk
+                .function_info
+                .as_ref()
+                .and_then(|info| info.name.as_ref())
+            {
+                Some(name) => format!("<function {}>", name.as_str()),
+                None => "<function>".to_string(),
+            }
+        }
+        Constant::RegExp { pattern, flags } => format!("/{}/{}", pattern.as_str(), flags.as_str()),
+        Constant::TemplateStrings { cooked, .. } => format!("template({})", quoted(cooked)),
+        Constant::ExcludedKeys(keys) => format!("keys({})", quoted(keys)),
+        Constant::SwitchTable(table) => {
+            let mut cases: Vec<_> = table.iter().collect();
+            cases.sort_by_key(|(s, target)| (**target, s.as_str()));
+            let cases: Vec<_> = cases
+                .into_iter()
+                .map(|(s, target)| format!("{:?} => L{}", s.as_str(), target))
+                .collect();
+            format!("switch({})", cases.join(", "))
+        }
+    }
+}
+
+/// The opcode name and named operands of an instruction.
+///
+/// Lists every variant so new instructions fail to compile until they are
+/// given a listing.
+fn operands(op: Op) -> (&'static str, Vec<(&'static str, Operand)>) {
+    match op {
+        Op::LoadConst { dst, idx } => ("LoadConst", vec![("dst", Reg(dst)), ("idx", Const(idx))]),
+        Op::LoadUndefined { dst } => ("LoadUndefined", vec![("dst", Reg(dst))]),
+        Op::LoadNull { dst } => ("LoadNull", vec![("dst", Reg(dst))]),
+        Op::LoadBool { dst, value } => {
+            ("LoadBool", vec![("dst", Reg(dst)), ("value", Flag(value))])
+        }
+        Op::LoadInt { dst, value } => (
+            "LoadInt",
+            vec![("dst", Reg(dst)), ("value", Imm(value.into()))],
+        ),
+        Op::Move { dst, src } => ("Move", vec![("dst", Reg(dst)), ("src", Reg(src))]),
+        Op::Add { dst, left, right } => (
+            "Add",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::Sub { dst, left, right } => (
+            "Sub",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::Mul { dst, left, right } => (
+            "Mul",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::Div { dst, left, right } => (
+            "Div",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::Mod { dst, left, right } => (
+            "Mod",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::Exp { dst, left, right } => (
+            "Exp",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::Eq { dst, left, right } => (
+            "Eq",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::NotEq { dst, left, right } => (
+            "NotEq",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::StrictEq { dst, left, right } => (
+            "StrictEq",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::StrictNotEq { dst, left, right } => (
+            "StrictNotEq",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::Lt { dst, left, right } => (
+            "Lt",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::LtEq { dst, left, right } => (
+            "LtEq",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::Gt { dst, left, right } => (
+            "Gt",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::GtEq { dst, left, right } => (
+            "GtEq",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::BitAnd { dst, left, right } => (
+            "BitAnd",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::BitOr { dst, left, right } => (
+            "BitOr",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::BitXor { dst, left, right } => (
+            "BitXor",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::LShift { dst, left, right } => (
+            "LShift",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::RShift { dst, left, right } => (
+            "RShift",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::URShift { dst, left, right } => (
+            "URShift",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::In { dst, left, right } => (
+            "In",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::Instanceof { dst, left, right } => (
+            "Instanceof",
+            vec![
+                ("dst", Reg(dst)),
+                ("left", Reg(left)),
+                ("right", Reg(right)),
+            ],
+        ),
+        Op::Neg { dst, src } => ("Neg", vec![("dst", Reg(dst)), ("src", Reg(src))]),
+        Op::Plus { dst, src } => ("Plus", vec![("dst", Reg(dst)), ("src", Reg(src))]),
+        Op::Not { dst, src } => ("Not", vec![("dst", Reg(dst)), ("src", Reg(src))]),
+        Op::BitNot { dst, src } => ("BitNot", vec![("dst", Reg(dst)), ("src", Reg(src))]),
+        Op::Typeof { dst, src } => ("Typeof", vec![("dst", Reg(dst)), ("src", Reg(src))]),
+        Op::Void { dst, src } => ("Void", vec![("dst", Reg(dst)), ("src", Reg(src))]),
+        Op::Jump { target } => ("Jump", vec![("target", Label(target))]),
+        Op::JumpIfTrue { cond, target } => (
+            "JumpIfTrue",
+            vec![("cond", Reg(cond)), ("target", Label(target))],
+        ),
+        Op::JumpIfFalse { cond, target } => (
+            "JumpIfFalse",
+            vec![("cond", Reg(cond)), ("target", Label(target))],
+        ),
+        Op::JumpIfNullish { cond, target } => (
+            "JumpIfNullish",
+            vec![("cond", Reg(cond)), ("target", Label(target))],
+        ),
+        Op::JumpIfNotNullish { cond, target } => (
+            "JumpIfNotNullish",
+            vec![("cond", Reg(cond)), ("target", Label(target))],
+        ),
+        Op::SwitchString {
+            value,
+            table,
+            default,
+        } => (
+            "SwitchString",
+            vec![
+                ("value", Reg(value)),
+                ("table", Const(table)),
+                ("default", Label(default)),
+            ],
+        ),
+        Op::Break { target, try_depth } => (
+            "Break",
+            vec![
+                ("target", Label(target)),
+                ("try_depth", Imm(try_depth.into())),
+            ],
+        ),
+        Op::Continue { target, try_depth } => (
+            "Continue",
+            vec![
+                ("target", Label(target)),
+                ("try_depth", Imm(try_depth.into())),
+            ],
+        ),
+        Op::GetVar { dst, name } => ("GetVar", vec![("dst", Reg(dst)), ("name", Const(name))]),
+        Op::TryGetVar { dst, name } => {
+            ("TryGetVar", vec![("dst", Reg(dst)), ("name", Const(name))])
+        }
+        Op::SetVar { name, src } => ("SetVar", vec![("name", Const(name)), ("src", Reg(src))]),
+        Op::SetFunctionVar { name, src } => (
+            "SetFunctionVar",
+            vec![("name", Const(name)), ("src", Reg(src))],
+        ),
+        Op::DeclareVar {
+            name,
+            init,
+            mutable,
+        } => (
+            "DeclareVar",
+            vec![
+                ("name", Const(name)),
+                ("init", Reg(init)),
+                ("mutable", Flag(mutable)),
+            ],
+        ),
+        Op::DeclareVarHoisted { name, init } => (
+            "DeclareVarHoisted",
+            vec![("name", Const(name)), ("init", Reg(init))],
+        ),
+        Op::DeclareUninitialized { name } => ("DeclareUninitialized", vec![("name", Const(name))]),
+        Op::GetGlobal { dst, name } => {
+            ("GetGlobal", vec![("dst", Reg(dst)), ("name", Const(name))])
+        }
+        Op::SetGlobal { name, src } => {
+            ("SetGlobal", vec![("name", Const(name)), ("src", Reg(src))])
+        }
+        Op::CreateObject { dst } => ("CreateObject", vec![("dst", Reg(dst))]),
+        Op::CreateArray { dst, start, count } => (
+            "CreateArray",
+            vec![
+                ("dst", Reg(dst)),
+                ("start", Reg(start)),
+                ("count", Imm(count.into())),
+            ],
+        ),
+        Op::GetProperty { dst, obj, key } => (
+            "GetProperty",
+            vec![("dst", Reg(dst)), ("obj", Reg(obj)), ("key", Reg(key))],
+        ),
+        Op::GetPropertyConst { dst, obj, key } => (
+            "GetPropertyConst",
+            vec![("dst", Reg(dst)), ("obj", Reg(obj)), ("key", Const(key))],
+        ),
+        Op::SetProperty { obj, key, value } => (
+            "SetProperty",
+            vec![("obj", Reg(obj)), ("key", Reg(key)), ("value", Reg(value))],
+        ),
+        Op::SetPropertyConst { obj, key, value } => (
+            "SetPropertyConst",
+            vec![
+                ("obj", Reg(obj)),
+                ("key", Const(key)),
+                ("value", Reg(value)),
+            ],
+        ),
+        Op::DeleteProperty { dst, obj, key } => (
+            "DeleteProperty",
+            vec![("dst", Reg(dst)), ("obj", Reg(obj)), ("key", Reg(key))],
+        ),
+        Op::DeletePropertyConst { dst, obj, key } => (
+            "DeletePropertyConst",
+            vec![("dst", Reg(dst)), ("obj", Reg(obj)), ("key", Const(key))],
+        ),
+        Op::DefineProperty {
+            obj,
+            key,
+            value,
+            flags,
+        } => (
+            "DefineProperty",
+            vec![
+                ("obj", Reg(obj)),
+                ("key", Reg(key)),
+                ("value", Reg(value)),
+                ("flags", Imm(flags.into())),
+            ],
+        ),
+        Op::Call {
+            dst,
+            callee,
+            this,
+            args_start,
+            argc,
+        } => (
+            "Call",
+            vec![
+                ("dst", Reg(dst)),
+                ("callee", Reg(callee)),
+                ("this", Reg(this)),
+                ("args_start", Reg(args_start)),
+                ("argc", Imm(argc.into())),
+            ],
+        ),
+        Op::CallSpread {
+            dst,
+            callee,
+            this,
+            args_start,
+            argc,
+        } => (
+            "CallSpread",
+            vec![
+                ("dst", Reg(dst)),
+                ("callee", Reg(callee)),
+                ("this", Reg(this)),
+                ("args_start", Reg(args_start)),
+                ("argc", Imm(argc.into())),
+            ],
+        ),
+        Op::DirectEval { dst, arg } => ("DirectEval", vec![("dst", Reg(dst)), ("arg", Reg(arg))]),
+        Op::CallMethod {
+            dst,
+            obj,
+            method,
+            args_start,
+            argc,
+        } => (
+            "CallMethod",
+            vec![
+                ("dst", Reg(dst)),
+                ("obj", Reg(obj)),
+                ("method", Const(method)),
+                ("args_start", Reg(args_start)),
+                ("argc", Imm(argc.into())),
+            ],
+        ),
+        Op::Construct {
+            dst,
+            callee,
+            args_start,
+            argc,
+        } => (
+            "Construct",
+            vec![
+                ("dst", Reg(dst)),
+                ("callee", Reg(callee)),
+                ("args_start", Reg(args_start)),
+                ("argc", Imm(argc.into())),
+            ],
+        ),
+        Op::ConstructSpread {
+            dst,
+            callee,
+            args_start,
+            argc,
+        } => (
+            "ConstructSpread",
+            vec![
+                ("dst", Reg(dst)),
+                ("callee", Reg(callee)),
+                ("args_start", Reg(args_start)),
+                ("argc", Imm(argc.into())),
+            ],
+        ),
+        Op::Return { value } => ("Return", vec![("value", Reg(value))]),
+        Op::ReturnUndefined => ("ReturnUndefined", vec![]),
+        Op::CreateClosure { dst, chunk_idx } => (
+            "CreateClosure",
+            vec![("dst", Reg(dst)), ("chunk_idx", Const(chunk_idx))],
+        ),
+        Op::CreateArrow { dst, chunk_idx } => (
+            "CreateArrow",
+            vec![("dst", Reg(dst)), ("chunk_idx", Const(chunk_idx))],
+        ),
+        Op::CreateGenerator { dst, chunk_idx } => (
+            "CreateGenerator",
+            vec![("dst", Reg(dst)), ("chunk_idx", Const(chunk_idx))],
+        ),
+        Op::CreateAsync { dst, chunk_idx } => (
+            "CreateAsync",
+            vec![("dst", Reg(dst)), ("chunk_idx", Const(chunk_idx))],
+        ),
+        Op::CreateAsyncGenerator { dst, chunk_idx } => (
+            "CreateAsyncGenerator",
+            vec![("dst", Reg(dst)), ("chunk_idx", Const(chunk_idx))],
+        ),
+        Op::Throw { value } => ("Throw", vec![("value", Reg(value))]),
+        Op::PushTry {
+            catch_target,
+            finally_target,
+        } => (
+            "PushTry",
+            vec![
+                ("catch_target", Handler(catch_target)),
+                ("finally_target", Handler(finally_target)),
+            ],
+        ),
+        Op::PopTry => ("PopTry", vec![]),
+        Op::FinallyEnd => ("FinallyEnd", vec![]),
+        Op::GetException { dst } => ("GetException", vec![("dst", Reg(dst))]),
+        Op::PushIterTry {
+            iterator,
+            catch_target,
+        } => (
+            "PushIterTry",
+            vec![
+                ("iterator", Reg(iterator)),
+                ("catch_target", Handler(catch_target)),
+            ],
+        ),
+        Op::PopIterTry => ("PopIterTry", vec![]),
+        Op::Rethrow => ("Rethrow", vec![]),
+        Op::Await { dst, promise } => ("Await", vec![("dst", Reg(dst)), ("promise", Reg(promise))]),
+        Op::Yield { dst, value } => ("Yield", vec![("dst", Reg(dst)), ("value", Reg(value))]),
+        Op::YieldStar { dst, iterable } => (
+            "YieldStar",
+            vec![("dst", Reg(dst)), ("iterable", Reg(iterable))],
+        ),
+        Op::PushScope => ("PushScope", vec![]),
+        Op::PopScope => ("PopScope", vec![]),
+        Op::GetIterator { dst, obj } => ("GetIterator", vec![("dst", Reg(dst)), ("obj", Reg(obj))]),
+        Op::GetKeysIterator { dst, obj } => (
+            "GetKeysIterator",
+            vec![("dst", Reg(dst)), ("obj", Reg(obj))],
+        ),
+        Op::GetAsyncIterator { dst, obj } => (
+            "GetAsyncIterator",
+            vec![("dst", Reg(dst)), ("obj", Reg(obj))],
+        ),
+        Op::IteratorNext { dst, iterator } => (
+            "IteratorNext",
+            vec![("dst", Reg(dst)), ("iterator", Reg(iterator))],
+        ),
+        Op::IteratorDone { result, target } => (
+            "IteratorDone",
+            vec![("result", Reg(result)), ("target", Label(target))],
+        ),
+        Op::IteratorValue { dst, result } => (
+            "IteratorValue",
+            vec![("dst", Reg(dst)), ("result", Reg(result))],
+        ),
+        Op::IteratorClose { iterator } => ("IteratorClose", vec![("iterator", Reg(iterator))]),
+        Op::IteratorCloseOnThrow { iterator } => {
+            ("IteratorCloseOnThrow", vec![("iterator", Reg(iterator))])
+        }
+        Op::CreateClass {
+            dst,
+            constructor,
+            super_class,
+        } => (
+            "CreateClass",
+            vec![
+                ("dst", Reg(dst)),
+                ("constructor", Reg(constructor)),
+                ("super_class", Reg(super_class)),
+            ],
+        ),
+        Op::DefineMethod {
+            class,
+            name,
+            method,
+            is_static,
+        } => (
+            "DefineMethod",
+            vec![
+                ("class", Reg(class)),
+                ("name", Const(name)),
+                ("method", Reg(method)),
+                ("is_static", Flag(is_static)),
+            ],
+        ),
+        Op::DefineAccessor {
+            class,
+            name,
+            getter,
+            setter,
+            is_static,
+            enumerable,
+        } => (
+            "DefineAccessor",
+            vec![
+                ("class", Reg(class)),
+                ("name", Const(name)),
+                ("getter", Reg(getter)),
+                ("setter", Reg(setter)),
+                ("is_static", Flag(is_static)),
+                ("enumerable", Flag(enumerable)),
+            ],
+        ),
+        Op::DefineMethodComputed {
+            class,
+            key,
+            method,
+            is_static,
+        } => (
+            "DefineMethodComputed",
+            vec![
+                ("class", Reg(class)),
+                ("key", Reg(key)),
+                ("method", Reg(method)),
+                ("is_static", Flag(is_static)),
+            ],
+        ),
+        Op::DefineAccessorComputed {
+            class,
+            key,
+            getter,
+            setter,
+            is_static,
+            enumerable,
+        } => (
+            "DefineAccessorComputed",
+            vec![
+                ("class", Reg(class)),
+                ("key", Reg(key)),
+                ("getter", Reg(getter)),
+                ("setter", Reg(setter)),
+                ("is_static", Flag(is_static)),
+                ("enumerable", Flag(enumerable)),
+            ],
+        ),
+        Op::SuperCall {
+            dst,
+            args_start,
+            argc,
+        } => (
+            "SuperCall",
+            vec![
+                ("dst", Reg(dst)),
+                ("args_start", Reg(args_start)),
+                ("argc", Imm(argc.into())),
+            ],
+        ),
+        Op::SuperCallSpread { dst, args_array } => (
+            "SuperCallSpread",
+            vec![("dst", Reg(dst)), ("args_array", Reg(args_array))],
+        ),
+        Op::SuperGet { dst, key } => ("SuperGet", vec![("dst", Reg(dst)), ("key", Reg(key))]),
+        Op::SuperGetConst { dst, key } => (
+            "SuperGetConst",
+            vec![("dst", Reg(dst)), ("key", Const(key))],
+        ),
+        Op::SuperSet { key, value } => ("SuperSet", vec![("key", Reg(key)), ("value", Reg(value))]),
+        Op::SuperSetConst { key, value } => (
+            "SuperSetConst",
+            vec![("key", Const(key)), ("value", Reg(value))],
+        ),
+        Op::ApplyClassDecorator {
+            class,
+            decorator,
+            class_name,
+            initializers,
+        } => (
+            "ApplyClassDecorator",
+            vec![
+                ("class", Reg(class)),
+                ("decorator", Reg(decorator)),
+                ("class_name", Const(class_name)),
+                ("initializers", Reg(initializers)),
+            ],
+        ),
+        Op::RunClassInitializers {
+            class,
+            initializers,
+        } => (
+            "RunClassInitializers",
+            vec![("class", Reg(class)), ("initializers", Reg(initializers))],
+        ),
+        Op::ApplyMethodDecorator {
+            method,
+            decorator,
+            name,
+            kind,
+            is_static,
+            is_private,
+        } => (
+            "ApplyMethodDecorator",
+            vec![
+                ("method", Reg(method)),
+                ("decorator", Reg(decorator)),
+                ("name", Const(name)),
+                ("kind", Imm(kind.into())),
+                ("is_static", Flag(is_static)),
+                ("is_private", Flag(is_private)),
+            ],
+        ),
+        Op::ApplyParameterDecorator {
+            target,
+            decorator,
+            method_name,
+            param_name,
+            param_index,
+            is_static,
+        } => (
+            "ApplyParameterDecorator",
+            vec![
+                ("target", Reg(target)),
+                ("decorator", Reg(decorator)),
+                ("method_name", Const(method_name)),
+                ("param_name", Const(param_name)),
+                ("param_index", Imm(param_index.into())),
+                ("is_static", Flag(is_static)),
+            ],
+        ),
+        Op::ApplyFieldDecorator {
+            dst,
+            decorator,
+            name,
+            is_static,
+            is_private,
+            is_accessor,
+        } => (
+            "ApplyFieldDecorator",
+            vec![
+                ("dst", Reg(dst)),
+                ("decorator", Reg(decorator)),
+                ("name", Const(name)),
+                ("is_static", Flag(is_static)),
+                ("is_private", Flag(is_private)),
+                ("is_accessor", Flag(is_accessor)),
+            ],
+        ),
+        Op::StoreFieldInitializer {
+            class,
+            name,
+            initializer,
+        } => (
+            "StoreFieldInitializer",
+            vec![
+                ("class", Reg(class)),
+                ("name", Const(name)),
+                ("initializer", Reg(initializer)),
+            ],
+        ),
+        Op::GetFieldInitializer { dst, class, name } => (
+            "GetFieldInitializer",
+            vec![
+                ("dst", Reg(dst)),
+                ("class", Reg(class)),
+                ("name", Const(name)),
+            ],
+        ),
+        Op::ApplyFieldInitializer { value, initializer } => (
+            "ApplyFieldInitializer",
+            vec![("value", Reg(value)), ("initializer", Reg(initializer))],
+        ),
+        Op::DefineAutoAccessor {
+            class,
+            name,
+            init_value,
+            target_dst,
+            is_static,
+        } => (
+            "DefineAutoAccessor",
+            vec![
+                ("class", Reg(class)),
+                ("name", Const(name)),
+                ("init_value", Reg(init_value)),
+                ("target_dst", Reg(target_dst)),
+                ("is_static", Flag(is_static)),
+            ],
+        ),
+        Op::StoreAutoAccessor {
+            class,
+            name,
+            accessor_obj,
+            is_static,
+        } => (
+            "StoreAutoAccessor",
+            vec![
+                ("class", Reg(class)),
+                ("name", Const(name)),
+                ("accessor_obj", Reg(accessor_obj)),
+                ("is_static", Flag(is_static)),
+            ],
+        ),
+        Op::ApplyAutoAccessorDecorator {
+            target,
+            decorator,
+            name,
+            is_static,
+        } => (
+            "ApplyAutoAccessorDecorator",
+            vec![
+                ("target", Reg(target)),
+                ("decorator", Reg(decorator)),
+                ("name", Const(name)),
+                ("is_static", Flag(is_static)),
+            ],
+        ),
+        Op::SpreadArray { dst, src } => ("SpreadArray", vec![("dst", Reg(dst)), ("src", Reg(src))]),
+        Op::CreateRestArray { dst, start_index } => (
+            "CreateRestArray",
+            vec![("dst", Reg(dst)), ("start_index", Imm(start_index.into()))],
+        ),
+        Op::CreateObjectRest {
+            dst,
+            src,
+            excluded_keys,
+        } => (
+            "CreateObjectRest",
+            vec![
+                ("dst", Reg(dst)),
+                ("src", Reg(src)),
+                ("excluded_keys", Const(excluded_keys)),
+            ],
+        ),
+        Op::SpreadObject { dst, src } => {
+            ("SpreadObject", vec![("dst", Reg(dst)), ("src", Reg(src))])
+        }
+        Op::TemplateConcat { dst, start, count } => (
+            "TemplateConcat",
+            vec![
+                ("dst", Reg(dst)),
+                ("start", Reg(start)),
+                ("count", Imm(count.into())),
+            ],
+        ),
+        Op::TaggedTemplate {
+            dst,
+            tag,
+            this,
+            template,
+            exprs_start,
+            exprs_count,
+        } => (
+            "TaggedTemplate",
+            vec![
+                ("dst", Reg(dst)),
+                ("tag", Reg(tag)),
+                ("this", Reg(this)),
+                ("template", Const(template)),
+                ("exprs_start", Reg(exprs_start)),
+                ("exprs_count", Imm(exprs_count.into())),
+            ],
+        ),
+        Op::GetPrivateField {
+            dst,
+            obj,
+            class_brand,
+            field_name,
+        } => (
+            "GetPrivateField",
+            vec![
+                ("dst", Reg(dst)),
+                ("obj", Reg(obj)),
+                ("class_brand", Imm(class_brand.into())),
+                ("field_name", Const(field_name)),
+            ],
+        ),
+        Op::SetPrivateField {
+            obj,
+            class_brand,
+            field_name,
+            value,
+        } => (
+            "SetPrivateField",
+            vec![
+                ("obj", Reg(obj)),
+                ("class_brand", Imm(class_brand.into())),
+                ("field_name", Const(field_name)),
+                ("value", Reg(value)),
+            ],
+        ),
+        Op::DefinePrivateField {
+            obj,
+            class_brand,
+            field_name,
+            value,
+        } => (
+            "DefinePrivateField",
+            vec![
+                ("obj", Reg(obj)),
+                ("class_brand", Imm(class_brand.into())),
+                ("field_name", Const(field_name)),
+                ("value", Reg(value)),
+            ],
+        ),
+        Op::DefinePrivateMethod {
+            class,
+            class_brand,
+            method_name,
+            method,
+            is_static,
+        } => (
+            "DefinePrivateMethod",
+            vec![
+                ("class", Reg(class)),
+                ("class_brand", Imm(class_brand.into())),
+                ("method_name", Const(method_name)),
+                ("method", Reg(method)),
+                ("is_static", Flag(is_static)),
+            ],
+        ),
+        Op::InstallPrivateMethod {
+            class_brand,
+            method_name,
+        } => (
+            "InstallPrivateMethod",
+            vec![
+                ("class_brand", Imm(class_brand.into())),
+                ("method_name", Const(method_name)),
+            ],
+        ),
+        Op::SetFunctionName { func, name } => (
+            "SetFunctionName",
+            vec![("func", Reg(func)), ("name", Const(name))],
+        ),
+        Op::SetFunctionNameFromKey { func, key } => (
+            "SetFunctionNameFromKey",
+            vec![("func", Reg(func)), ("key", Reg(key))],
+        ),
+        Op::Nop => ("Nop", vec![]),
+        Op::Halt => ("Halt", vec![]),
+        Op::Debugger => ("Debugger", vec![]),
+        Op::Pop => ("Pop", vec![]),
+        Op::Dup { dst, src } => ("Dup", vec![("dst", Reg(dst)), ("src", Reg(src))]),
+        Op::LoadThis { dst } => ("LoadThis", vec![("dst", Reg(dst))]),
+        Op::LoadArguments { dst } => ("LoadArguments", vec![("dst", Reg(dst))]),
+        Op::LoadNewTarget { dst } => ("LoadNewTarget", vec![("dst", Reg(dst))]),
+        Op::ExportBinding {
+            export_name,
+            binding_name,
+            value,
+        } => (
+            "ExportBinding",
+            vec![
+                ("export_name", Const(export_name)),
+                ("binding_name", Const(binding_name)),
+                ("value", Reg(value)),
+            ],
+        ),
+        Op::ExportNamespace {
+            export_name,
+            module_specifier,
+        } => (
+            "ExportNamespace",
+            vec![
+                ("export_name", Const(export_name)),
+                ("module_specifier", Const(module_specifier)),
+            ],
+        ),
+        Op::ReExport {
+            export_name,
+            source_module,
+            source_key,
+        } => (
+            "ReExport",
+            vec![
+                ("export_name", Const(export_name)),
+                ("source_module", Const(source_module)),
+                ("source_key", Const(source_key)),
+            ],
+        ),
+    }
+}
@@ -1,23 +1,42 @@
-//! Variable hoisting for var declarations
+//! Hoisting of var and function declarations
 //!
 //! JavaScript `var` declarations are hoisted to the top of their function scope.
-//! This module provides functions to collect var declarations and emit them at
-//! the beginning of the scope.
+//! Function declarations are created when the block, function body or program
+//! directly containing them is entered, so they can be called before the
+//! statement declaring them, and a later declaration of the same name wins.
+//! In strict and module code a function declared in a block is only visible
+//! inside it; in sloppy code it also gets a `var` binding in the enclosing
+//! function, assigned when the declaration is evaluated (Annex B.3.3).
 
 use super::Compiler;
 use super::bytecode::Op;
-use crate::ast::{ForInOfLeft, ForInit, Pattern, Statement, VariableDeclaration, VariableKind};
+use crate::ast::{
+    Expression, ForInOfLeft, ForInit, FunctionDeclaration, LiteralValue, Pattern, Statement,
+    VariableDeclaration, VariableKind,
+};
 use crate::error::JsError;
 use crate::prelude::*;
 use crate::value::{CheapClone, JsString};
 
 impl Compiler {
-    /// Emit hoisted var declarations for a list of statements
+    /// Emit hoisted var and function declarations for a list of statements
     /// This should be called at the beginning of a scope (program or function body)
     pub fn emit_hoisted_declarations(&mut self, statements: &[Statement]) -> Result<(), JsError> {
+        // Parameters are the only names hoisted so far
+        let param_names = self.hoisted_vars.clone();
+
         // Collect all var names that need to be hoisted
         let mut var_names: Vec<JsString> = Vec::new();
         collect_hoisted_vars(statements, &mut var_names);
+        if !self.strict {
+            for func in self.collect_var_scoped_block_functions(statements, &param_names) {
+                if let Some(id) = &func.id
+                    && id.name.as_str() != "arguments"
+                {
+                    var_names.push(id.name.cheap_clone());
+                }
+            }
+        }
 
         // Emit DeclareVarHoisted for each unique var (with undefined as initial value)
         let undefined_reg = self.builder.alloc_register()?;
@@ -36,6 +55,85 @@ impl Compiler {
         }
 
         self.builder.free_register(undefined_reg);
+
+        self.hoist_function_declarations(statements)
+    }
+
+    /// Create the functions declared directly in `statements` in the current
+    /// scope, in order, so later declarations of a name replace earlier ones.
+    /// Called on entering the program, function body or block they belong to;
+    /// the declarations then compile to nothing where they appear.
+    pub fn hoist_function_declarations(&mut self, statements: &[Statement]) -> Result<(), JsError> {
+        for func in statements.iter().filter_map(hoistable_function) {
+            let Some(id) = &func.id else {
+                continue;
+            };
+            self.hoisted_functions.insert(core::ptr::from_ref(func));
+            let dst = self.compile_function_declaration_closure(func)?;
+            let name_idx = self.builder.add_string(id.name.cheap_clone())?;
+            self.builder.emit(Op::DeclareVarHoisted {
+                name: name_idx,
+                init: dst,
+            });
+            self.builder.free_register(dst);
+        }
+        Ok(())
+    }
+
+    /// Record and return the functions declared in blocks of a sloppy-mode
+    /// body that also get a `var` binding in its scope: those whose name is
+    /// not a parameter and would not clash with a `let`, `const` or `class`
+    /// of the body or of a block around them (Annex B.3.3)
+    fn collect_var_scoped_block_functions<'a>(
+        &mut self,
+        statements: &'a [Statement],
+        param_names: &FxHashSet<JsString>,
+    ) -> Vec<&'a FunctionDeclaration> {
+        let mut lexical = Vec::new();
+        collect_lexical_names(statements, &mut lexical);
+        let mut found = Vec::new();
+        for stmt in statements {
+            collect_block_functions_stmt(stmt, &mut lexical, &mut found);
+        }
+        found.retain(|func| {
+            func.id
+                .as_ref()
+                .is_some_and(|id| !param_names.contains(&id.name))
+        });
+        self.var_scoped_functions
+            .extend(found.iter().map(|func| core::ptr::from_ref(*func)));
+        found
+    }
+
+    /// Hoist the top-level declarations of a script whose vars and functions
+    /// live on the global object.
+    ///
+    /// The interpreter has already created the global properties, so var
+    /// names are only recorded as hoisted; function declarations are created
+    /// here, before any statement runs, and stored on the global object.
+    pub fn emit_global_declarations(&mut self, statements: &[Statement]) -> Result<(), JsError> {
+        let mut var_names: Vec<JsString> = Vec::new();
+        collect_hoisted_vars(statements, &mut var_names);
+        self.hoisted_vars.extend(var_names);
+        self.global_var_scope = true;
+        if !self.strict {
+            self.collect_var_scoped_block_functions(statements, &FxHashSet::default());
+        }
+
+        for stmt in statements {
+            if let Statement::FunctionDeclaration(func) = stmt
+                && let Some(id) = &func.id
+            {
+                self.hoisted_vars.insert(id.name.cheap_clone());
+                let dst = self.compile_function_declaration_closure(func)?;
+                let name_idx = self.builder.add_string(id.name.cheap_clone())?;
+                self.builder.emit(Op::SetGlobal {
+                    name: name_idx,
+                    src: dst,
+                });
+                self.builder.free_register(dst);
+            }
+        }
         Ok(())
     }
 
@@ -45,6 +143,222 @@ impl Compiler {
     }
 }
 
+/// Names a script declares at its top level
+#[derive(Debug, Default)]
+pub struct ScriptDeclarations {
+    /// `var` names, including those nested in blocks and loops
+    pub var_names: Vec<JsString>,
+    /// Names of functions declared in blocks of a sloppy-mode script, which
+    /// become global vars unless a lexical binding has the name
+    pub block_function_names: Vec<JsString>,
+    /// Names of top-level function declarations
+    pub function_names: Vec<JsString>,
+    /// Top-level `let`, `const` and `class` names
+    pub lexical_names: Vec<JsString>,
+}
+
+/// Collect the declarations global declaration instantiation needs for a script
+pub fn script_declarations(statements: &[Statement]) -> ScriptDeclarations {
+    let mut decls = ScriptDeclarations::default();
+    collect_hoisted_vars(statements, &mut decls.var_names);
+    for stmt in statements {
+        match stmt {
+            Statement::FunctionDeclaration(func) => {
+                if let Some(id) = &func.id {
+                    decls.function_names.push(id.name.cheap_clone());
+                }
+            }
+            Statement::VariableDeclaration(decl) if decl.kind != VariableKind::Var => {
+                collect_hoisted_vars_decl(decl, &mut decls.lexical_names);
+            }
+            Statement::ClassDeclaration(class) => {
+                if let Some(id) = &class.id {
+                    decls.lexical_names.push(id.name.cheap_clone());
+                }
+            }
+            _ => {}
+        }
+    }
+    if !is_strict_body(statements) {
+        let mut lexical = decls.lexical_names.clone();
+        let mut found = Vec::new();
+        for stmt in statements {
+            collect_block_functions_stmt(stmt, &mut lexical, &mut found);
+        }
+        decls.block_function_names = found
+            .iter()
+            .filter_map(|func| func.id.as_ref().map(|id| id.name.cheap_clone()))
+            .collect();
+    }
+    decls
+}
+
+/// Whether `statements` start with a `"use strict"` directive
+pub fn is_strict_body(statements: &[Statement]) -> bool {
+    for stmt in statements {
+        let Statement::Expression(expr_stmt) = stmt else {
+            break;
+        };
+        let Expression::Literal(lit) = expr_stmt.expression.as_ref() else {
+            break;
+        };
+        let LiteralValue::String(directive) = &lit.value else {
+            break;
+        };
+        if directive.as_str() == "use strict" {
+            return true;
+        }
+    }
+    false
+}
+
+/// The function a statement of a statement list declares in that list's
+/// scope: plain, labeled or exported
+fn hoistable_function(stmt: &Statement) -> Option<&FunctionDeclaration> {
+    match stmt {
+        Statement::FunctionDeclaration(func) => Some(func.as_ref()),
+        Statement::Labeled(labeled) => hoistable_function(&labeled.body),
+        Statement::Export(export) if !export.default => match export.declaration.as_deref() {
+            Some(Statement::FunctionDeclaration(func)) => Some(func.as_ref()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Collect the `let`, `const` and `class` names declared directly in `statements`
+fn collect_lexical_names(statements: &[Statement], names: &mut Vec<JsString>) {
+    for stmt in statements {
+        match stmt {
+            Statement::VariableDeclaration(decl) if decl.kind != VariableKind::Var => {
+                collect_hoisted_vars_decl(decl, names);
+            }
+            Statement::ClassDeclaration(class) => {
+                if let Some(id) = &class.id {
+                    names.push(id.name.cheap_clone());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Collect the functions declared in blocks nested in `stmt` whose name no
+/// lexical declaration in `lexical` or around them shadows
+fn collect_block_functions_stmt<'a>(
+    stmt: &'a Statement,
+    lexical: &mut Vec<JsString>,
+    found: &mut Vec<&'a FunctionDeclaration>,
+) {
+    match stmt {
+        Statement::Block(block) => collect_block_functions_list(&block.body, lexical, found),
+        Statement::If(if_stmt) => {
+            collect_block_functions_stmt(&if_stmt.consequent, lexical, found);
+            if let Some(alt) = &if_stmt.alternate {
+                collect_block_functions_stmt(alt, lexical, found);
+            }
+        }
+        Statement::While(while_stmt) => {
+            collect_block_functions_stmt(&while_stmt.body, lexical, found);
+        }
+        Statement::DoWhile(do_while) => {
+            collect_block_functions_stmt(&do_while.body, lexical, found);
+        }
+        Statement::For(for_stmt) => {
+            let depth = lexical.len();
+            if let Some(ForInit::Variable(decl)) = &for_stmt.init
+                && decl.kind != VariableKind::Var
+            {
+                collect_hoisted_vars_decl(decl, lexical);
+            }
+            collect_block_functions_stmt(&for_stmt.body, lexical, found);
+            lexical.truncate(depth);
+        }
+        Statement::ForIn(for_in) => {
+            let depth = lexical.len();
+            if let ForInOfLeft::Variable(decl) = &for_in.left
+                && decl.kind != VariableKind::Var
+            {
+                collect_hoisted_vars_decl(decl, lexical);
+            }
+            collect_block_functions_stmt(&for_in.body, lexical, found);
+            lexical.truncate(depth);
+        }
+        Statement::ForOf(for_of) => {
+            let depth = lexical.len();
+            if let ForInOfLeft::Variable(decl) = &for_of.left
+                && decl.kind != VariableKind::Var
+            {
+                collect_hoisted_vars_decl(decl, lexical);
+            }
+            collect_block_functions_stmt(&for_of.body, lexical, found);
+            lexical.truncate(depth);
+        }
+        Statement::Switch(switch_stmt) => {
+            // The cases share one scope
+            let depth = lexical.len();
+            for case in switch_stmt.cases.iter() {
+                collect_lexical_names(&case.consequent, lexical);
+            }
+            for stmt in switch_stmt
+                .cases
+                .iter()
+                .flat_map(|case| case.consequent.iter())
+            {
+                collect_block_function(stmt, lexical, found);
+                collect_block_functions_stmt(stmt, lexical, found);
+            }
+            lexical.truncate(depth);
+        }
+        Statement::Try(try_stmt) => {
+            collect_block_functions_list(&try_stmt.block.body, lexical, found);
+            if let Some(handler) = &try_stmt.handler {
+                collect_block_functions_list(&handler.body.body, lexical, found);
+            }
+            if let Some(finalizer) = &try_stmt.finalizer {
+                collect_block_functions_list(&finalizer.body, lexical, found);
+            }
+        }
+        Statement::Labeled(labeled) => {
+            collect_block_functions_stmt(&labeled.body, lexical, found);
+        }
+        _ => {}
+    }
+}
+
+/// Collect the block functions of a block's statements, see
+/// [`collect_block_functions_stmt`]
+fn collect_block_functions_list<'a>(
+    statements: &'a [Statement],
+    lexical: &mut Vec<JsString>,
+    found: &mut Vec<&'a FunctionDeclaration>,
+) {
+    let depth = lexical.len();
+    collect_lexical_names(statements, lexical);
+    for stmt in statements {
+        collect_block_function(stmt, lexical, found);
+        collect_block_functions_stmt(stmt, lexical, found);
+    }
+    lexical.truncate(depth);
+}
+
+/// Add the function `stmt` declares directly in a block unless a lexical
+/// declaration shadows its name
+fn collect_block_function<'a>(
+    stmt: &'a Statement,
+    lexical: &[JsString],
+    found: &mut Vec<&'a FunctionDeclaration>,
+) {
+    if let Some(func) = hoistable_function(stmt)
+        && !func.generator
+        && !func.async_
+        && let Some(id) = &func.id
+        && !lexical.contains(&id.name)
+    {
+        found.push(func);
+    }
+}
+
 /// Recursively collect all var declaration names from statements
 /// This only collects top-level vars within the current scope - it doesn't
 /// descend into function bodies (which create new scopes)
@@ -165,8 +479,13 @@ fn collect_hoisted_vars_stmt(stmt: &Statement, names: &mut Vec<JsString>) {
         | Statement::InterfaceDeclaration(_)
         | Statement::EnumDeclaration(_)
         | Statement::NamespaceDeclaration(_)
-        | Statement::Import(_)
-        | Statement::Export(_) => {}
+        | Statement::Import(_) => {}
+
+        Statement::Export(export) => {
+            if let Some(decl) = &export.declaration {
+                collect_hoisted_vars_stmt(decl, names);
+            }
+        }
     }
 }
 
@@ -336,7 +655,7 @@ fn collect_function_decl_names_stmt(
 }
 
 /// Collect var names from a pattern (for destructuring)
-fn collect_pattern_var_names(pattern: &Pattern, names: &mut Vec<JsString>) {
+pub(super) fn collect_pattern_var_names(pattern: &Pattern, names: &mut Vec<JsString>) {
     match pattern {
         Pattern::Identifier(id) => {
             names.push(id.name.cheap_clone());
@@ -0,0 +1,149 @@
+//! Early error for assignments to imported bindings
+//!
+//! Imports are immutable views of another module's exports, so `x = 1` or
+//! `x++` on an imported `x` is a SyntaxError before the module runs. The
+//! compiler has no scope tracking, so the check is conservative: a name that
+//! is also declared anywhere in the module (a parameter or local shadowing
+//! the import) is left to the runtime check, which throws a TypeError on the
+//! write instead.
+
+use crate::prelude::*;
+
+use crate::ast::visit::{self, Visitor};
+use crate::ast::{
+    AssignmentExpression, AssignmentTarget, Expression, ForInOfLeft, ForInStatement,
+    ForOfStatement, Identifier, ImportDeclaration, ImportSpecifier, Program, Statement,
+    UpdateExpression,
+};
+use crate::error::JsError;
+use crate::value::JsString;
+
+/// Fail with a SyntaxError if `program` assigns to one of its imports
+pub(super) fn check_import_assignments(program: &Program) -> Result<(), JsError> {
+    if !program
+        .body
+        .iter()
+        .any(|stmt| matches!(stmt, Statement::Import(_)))
+    {
+        return Ok(());
+    }
+
+    let mut check = ImportAssignments::default();
+    check.visit_program(program);
+    match check
+        .assigned
+        .iter()
+        .find(|id| check.imports.contains(&id.name) && !check.declared.contains(&id.name))
+    {
+        Some(id) => Err(JsError::syntax_error_at(
+            format!("Cannot assign to imported binding '{}'", id.name),
+            &id.span,
+        )),
+        None => Ok(()),
+    }
+}
+
+/// The local names bound by `program`'s imports that it declares nowhere
+/// else, so that every read of them reads the import
+pub(crate) fn unshadowed_imports(program: &Program) -> FxHashSet<JsString> {
+    let mut check = ImportAssignments::default();
+    check.visit_program(program);
+    let ImportAssignments {
+        imports, declared, ..
+    } = check;
+    imports
+        .into_iter()
+        .filter(|name| !declared.contains(name))
+        .collect()
+}
+
+#[derive(Default)]
+struct ImportAssignments {
+    /// Local names bound by import declarations
+    imports: FxHashSet<JsString>,
+    /// Every other name the module declares, at any depth
+    declared: FxHashSet<JsString>,
+    /// Identifiers written by assignments, updates and for-in/of heads
+    assigned: Vec<Identifier>,
+    /// Set while visiting a destructuring assignment target, where binding
+    /// identifiers are writes rather than declarations
+    in_assignment_target: bool,
+}
+
+impl ImportAssignments {
+    fn visit_assignment_target(&mut self, pattern: &crate::ast::Pattern) {
+        let outer = core::mem::replace(&mut self.in_assignment_target, true);
+        self.visit_pattern(pattern);
+        self.in_assignment_target = outer;
+    }
+}
+
+impl Visitor for ImportAssignments {
+    fn visit_import_declaration(&mut self, import: &ImportDeclaration) {
+        if import.type_only {
+            return;
+        }
+        for specifier in &import.specifiers {
+            let (ImportSpecifier::Named { local, .. }
+            | ImportSpecifier::Default { local, .. }
+            | ImportSpecifier::Namespace { local, .. }) = specifier;
+            self.imports.insert(local.name.clone());
+        }
+    }
+
+    fn visit_binding_identifier(&mut self, id: &Identifier) {
+        if self.in_assignment_target {
+            self.assigned.push(id.clone());
+        } else {
+            self.declared.insert(id.name.clone());
+        }
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        let (left, right, body) = match statement {
+            Statement::ForIn(for_in) => {
+                let ForInStatement {
+                    left, right, body, ..
+                } = for_in.as_ref();
+                (left, right, body)
+            }
+            Statement::ForOf(for_of) => {
+                let ForOfStatement {
+                    left, right, body, ..
+                } = for_of.as_ref();
+                (left, right, body)
+            }
+            _ => return visit::walk_statement(self, statement),
+        };
+        match left {
+            ForInOfLeft::Variable(declaration) => self.visit_variable_declaration(declaration),
+            ForInOfLeft::Pattern(pattern) => self.visit_assignment_target(pattern),
+        }
+        self.visit_expression(right);
+        self.visit_statement(body);
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        // Defaults and computed keys inside a target are ordinary expressions
+        let outer = core::mem::replace(&mut self.in_assignment_target, false);
+        match expression {
+            Expression::Assignment(assignment) => {
+                let AssignmentExpression { left, right, .. } = assignment.as_ref();
+                match left {
+                    AssignmentTarget::Identifier(id) => self.assigned.push(id.clone()),
+                    AssignmentTarget::Member(member) => self.visit_member_expression(member),
+                    AssignmentTarget::Pattern(pattern) => self.visit_assignment_target(pattern),
+                }
+                self.visit_expression(right);
+            }
+            Expression::Update(UpdateExpression { argument, .. }) => {
+                if let Expression::Identifier(id) = argument.as_ref() {
+                    self.assigned.push(id.clone());
+                }
+                self.visit_expression(argument);
+            }
+            _ => visit::walk_expression(self, expression),
+        }
+        self.in_assignment_target = outer;
+    }
+}
@@ -8,18 +8,66 @@ mod bytecode;
 mod compile_expr;
 mod compile_pattern;
 mod compile_stmt;
+mod constant_imports;
+mod disassemble;
 mod hoist;
+mod import_check;
 
 pub use builder::{BytecodeBuilder, JumpPlaceholder};
-pub use bytecode::{BytecodeChunk, Constant, FunctionInfo, JumpTarget, Op, Register};
+pub use bytecode::{
+    BytecodeChunk, Constant, FunctionInfo, FunctionSource, JumpTarget, Op, Register,
+};
+pub(crate) use bytecode::{OperandName, SourceMapEntry};
+pub(crate) use constant_imports::{ConstantImports, uses_dynamic_code};
+pub use hoist::{ScriptDeclarations, script_declarations};
+pub(crate) use import_check::unshadowed_imports;
 
 use crate::prelude::*;
 
-use crate::ast::Program;
+use crate::CompileLimitKind;
+use crate::ast::{Pattern, Program};
 use crate::error::JsError;
 use crate::value::JsString;
 use builder::RegisterAllocator;
 
+/// Size limits of compiled code, see
+/// [`crate::InterpreterConfig::max_chunk_instructions`] and the settings
+/// after it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompileLimits {
+    /// Most instructions of one chunk
+    pub max_chunk_instructions: usize,
+    /// Most constants of one chunk, at most 65535
+    pub max_chunk_constants: usize,
+    /// Deepest nesting of function chunks
+    pub max_function_depth: usize,
+    /// Most instructions of all chunks of one compile
+    pub max_program_instructions: usize,
+}
+
+impl CompileLimits {
+    /// The maximum `limit` sets
+    pub fn max(&self, limit: CompileLimitKind) -> usize {
+        match limit {
+            CompileLimitKind::ChunkInstructions => self.max_chunk_instructions,
+            CompileLimitKind::ChunkConstants => self.max_chunk_constants.min(u16::MAX as usize),
+            CompileLimitKind::FunctionDepth => self.max_function_depth,
+            CompileLimitKind::ProgramInstructions => self.max_program_instructions,
+        }
+    }
+}
+
+impl Default for CompileLimits {
+    fn default() -> Self {
+        Self {
+            max_chunk_instructions: crate::DEFAULT_MAX_CHUNK_INSTRUCTIONS,
+            max_chunk_constants: crate::DEFAULT_MAX_CHUNK_CONSTANTS,
+            max_function_depth: crate::DEFAULT_MAX_FUNCTION_DEPTH,
+            max_program_instructions: crate::DEFAULT_MAX_PROGRAM_INSTRUCTIONS,
+        }
+    }
+}
+
 /// Compiler state for converting AST to bytecode
 pub struct Compiler {
     /// Current bytecode builder
@@ -38,6 +86,19 @@ pub struct Compiler {
     /// Used to determine if we should emit DeclareVarHoisted or SetVar
     hoisted_vars: FxHashSet<JsString>,
 
+    /// Function declarations already created on entering their scope
+    hoisted_functions: FxHashSet<*const crate::ast::FunctionDeclaration>,
+
+    /// Sloppy-mode block functions whose value is also stored in the var
+    /// scope when their declaration is evaluated (Annex B.3.3)
+    var_scoped_functions: FxHashSet<*const crate::ast::FunctionDeclaration>,
+
+    /// Whether the code being compiled is strict mode or module code
+    strict: bool,
+
+    /// Whether the var scope is the global object (scripts with `script_globals`)
+    global_var_scope: bool,
+
     /// Loop variable redirects: when compiling for-loop updates, assignments to
     /// these variables should write to the register instead of the environment.
     /// This ensures closures capture pre-update values.
@@ -56,6 +117,26 @@ pub struct Compiler {
 
     /// Source file path for stack traces (propagated to all nested chunks)
     source_file: Option<String>,
+
+    /// Program source text for recording function source (propagated to all nested chunks)
+    source: Option<Rc<str>>,
+
+    /// Instance setup of the derived class constructor being compiled, emitted
+    /// after each `super()` call (propagated to arrow functions)
+    instance_init: Option<Rc<InstanceInit>>,
+
+    /// Size limits of the compiled code (propagated to all nested chunks)
+    limits: CompileLimits,
+
+    /// Number of functions enclosing the one being compiled
+    function_depth: usize,
+
+    /// Instructions of the chunks this compile has finished so far, shared
+    /// with the compilers of nested functions
+    program_instructions: Rc<Cell<usize>>,
+
+    /// Imports whose reads fold to constants (propagated to all nested chunks)
+    constant_imports: Option<Rc<ConstantImports>>,
 }
 
 /// Context for a class being compiled (for private field handling)
@@ -67,6 +148,20 @@ struct ClassContext {
     private_members: FxHashMap<JsString, PrivateMemberInfo>,
 }
 
+/// What a class constructor does to a new instance before its own body:
+/// parameter properties, then field initializers and private members.
+/// Derived classes run it when `super()` returns, since `this` is not
+/// initialized before then.
+#[derive(Clone)]
+struct InstanceInit {
+    /// Parameters declared with an accessibility modifier or `readonly`
+    param_properties: Vec<JsString>,
+    fields: Vec<crate::ast::ClassProperty>,
+    private_fields: Vec<crate::ast::ClassProperty>,
+    private_methods: Vec<crate::ast::ClassMethod>,
+    class_brand: u32,
+}
+
 /// Information about a private class member
 #[derive(Clone)]
 #[allow(dead_code)]
@@ -94,6 +189,11 @@ struct LoopContext {
     iterator_reg: Option<Register>,
 }
 
+/// Whether a program is module code or starts with a `"use strict"` directive
+fn is_strict_program(program: &Program) -> bool {
+    program.source_type == crate::ast::SourceType::Module || hoist::is_strict_body(&program.body)
+}
+
 impl Compiler {
     /// Create a new compiler
     pub fn new() -> Self {
@@ -103,11 +203,21 @@ impl Compiler {
             labels: FxHashMap::default(),
             try_depth: 0,
             hoisted_vars: FxHashSet::default(),
+            hoisted_functions: FxHashSet::default(),
+            var_scoped_functions: FxHashSet::default(),
+            strict: false,
+            global_var_scope: false,
             loop_var_redirects: FxHashMap::default(),
             class_context_stack: Vec::new(),
             next_class_brand: 0,
             track_completion: false,
             source_file: None,
+            source: None,
+            instance_init: None,
+            limits: CompileLimits::default(),
+            function_depth: 0,
+            program_instructions: Rc::new(Cell::new(0)),
+            constant_imports: None,
         }
     }
 
@@ -129,6 +239,64 @@ impl Compiler {
         compiler
     }
 
+    /// Set the size limits of the code compiled, reported under `name` for a
+    /// function body compiled on its own
+    fn set_limits(&mut self, limits: CompileLimits, name: Option<JsString>) {
+        self.limits = limits;
+        self.builder.set_limits(limits, name);
+    }
+
+    /// A compiler for the body of function `name` nested in the code being
+    /// compiled, sharing its source, file and limits
+    fn nested_compiler(&self, name: Option<&JsString>) -> Result<Compiler, JsError> {
+        if self.function_depth >= self.limits.max_function_depth {
+            return Err(self.builder.limit_error(CompileLimitKind::FunctionDepth));
+        }
+        let mut compiler = Compiler::new();
+        compiler.source_file = self.source_file.clone();
+        compiler.source = self.source.clone();
+        if let Some(ref path) = self.source_file {
+            compiler.builder.set_source_file(path.clone());
+        }
+        compiler.set_limits(
+            self.limits,
+            Some(name.cloned().unwrap_or_else(|| JsString::from(""))),
+        );
+        compiler.function_depth = self.function_depth + 1;
+        compiler.program_instructions = self.program_instructions.clone();
+        compiler.constant_imports = self.constant_imports.clone();
+        Ok(compiler)
+    }
+
+    /// Fail once the chunk being compiled, or the compile as a whole, has
+    /// more instructions than the limits allow
+    fn check_compile_limits(&self) -> Result<(), JsError> {
+        self.builder.check_size()?;
+        let total = self.program_instructions.get() + self.builder.current_offset();
+        if total > self.limits.max_program_instructions {
+            return Err(self
+                .builder
+                .limit_error(CompileLimitKind::ProgramInstructions));
+        }
+        Ok(())
+    }
+
+    /// Finish the chunk being compiled, counting it towards the instructions
+    /// of the whole compile
+    fn finish_chunk(self) -> Result<BytecodeChunk, JsError> {
+        self.check_compile_limits()?;
+        self.program_instructions
+            .set(self.program_instructions.get() + self.builder.current_offset());
+        Ok(self.builder.finish())
+    }
+
+    /// Record the source text of a compiled function chunk from its span
+    fn attach_function_source(&self, chunk: &mut BytecodeChunk, span: crate::lexer::Span) {
+        if let (Some(source), Some(info)) = (&self.source, chunk.function_info.as_mut()) {
+            info.source_text = FunctionSource::new(source.clone(), span.start, span.end);
+        }
+    }
+
     /// Generate a new unique class brand ID
     fn new_class_brand(&mut self) -> u32 {
         let brand = self.next_class_brand;
@@ -154,9 +322,17 @@ impl Compiler {
         None
     }
 
-    /// Compile a program to bytecode
-    pub fn compile_program(program: &Program) -> Result<Rc<BytecodeChunk>, JsError> {
+    /// Compile a program to bytecode, failing with [`JsError::CompileLimit`]
+    /// once the code passes `limits`
+    pub fn compile_program(
+        program: &Program,
+        limits: CompileLimits,
+    ) -> Result<Rc<BytecodeChunk>, JsError> {
         let mut compiler = Compiler::new();
+        compiler.set_limits(limits, None);
+        compiler.source = program.source.clone();
+        compiler.strict = is_strict_program(program);
+        import_check::check_import_assignments(program)?;
 
         // First, hoist all var declarations and function declarations to the top
         compiler.emit_hoisted_declarations(&program.body)?;
@@ -164,15 +340,20 @@ impl Compiler {
         // Then compile the statements
         compiler.compile_statements(&program.body)?;
         compiler.builder.emit_halt();
-        Ok(Rc::new(compiler.builder.finish()))
+        Ok(Rc::new(compiler.finish_chunk()?))
     }
 
     /// Compile a program to bytecode with source file path for stack traces
     pub fn compile_program_with_source(
         program: &Program,
         source_file: String,
+        limits: CompileLimits,
     ) -> Result<Rc<BytecodeChunk>, JsError> {
         let mut compiler = Compiler::with_source_file(source_file);
+        compiler.set_limits(limits, None);
+        compiler.source = program.source.clone();
+        compiler.strict = is_strict_program(program);
+        import_check::check_import_assignments(program)?;
 
         // First, hoist all var declarations and function declarations to the top
         compiler.emit_hoisted_declarations(&program.body)?;
@@ -180,13 +361,78 @@ impl Compiler {
         // Then compile the statements
         compiler.compile_statements(&program.body)?;
         compiler.builder.emit_halt();
-        Ok(Rc::new(compiler.builder.finish()))
+        Ok(Rc::new(compiler.finish_chunk()?))
+    }
+
+    /// Compile a script whose top-level vars and functions are properties of
+    /// the global object.
+    ///
+    /// Expects the interpreter to have run global declaration instantiation
+    /// for the names in [`script_declarations`]: function declarations are
+    /// hoisted onto the global object and skipped where they appear.
+    pub fn compile_global_script(
+        program: &Program,
+        limits: CompileLimits,
+    ) -> Result<Rc<BytecodeChunk>, JsError> {
+        let mut compiler = Compiler::new();
+        compiler.set_limits(limits, None);
+        compiler.source = program.source.clone();
+        compiler.strict = is_strict_program(program);
+        import_check::check_import_assignments(program)?;
+
+        compiler.emit_global_declarations(&program.body)?;
+        for stmt in program.body.iter() {
+            if !matches!(stmt, crate::ast::Statement::FunctionDeclaration(_)) {
+                compiler.compile_statement_impl(stmt)?;
+            }
+        }
+        compiler.builder.emit_halt();
+        Ok(Rc::new(compiler.finish_chunk()?))
     }
 
     /// Compile a program for eval with completion value tracking
     /// Register 0 will contain the completion value when Halt is reached.
-    pub fn compile_program_for_eval(program: &Program) -> Result<Rc<BytecodeChunk>, JsError> {
-        let mut compiler = Compiler::new_with_completion_tracking();
+    pub fn compile_program_for_eval(
+        program: &Program,
+        limits: CompileLimits,
+    ) -> Result<Rc<BytecodeChunk>, JsError> {
+        Self::compile_entry_program(program, None, limits)
+    }
+
+    /// Compile the entry program of a run, script or module, with completion
+    /// value tracking like [`Compiler::compile_program_for_eval`] and
+    /// `source_file` recorded for stack traces
+    pub fn compile_entry_program(
+        program: &Program,
+        source_file: Option<String>,
+        limits: CompileLimits,
+    ) -> Result<Rc<BytecodeChunk>, JsError> {
+        Self::compile_module_program(program, source_file, limits, true, None)
+    }
+
+    /// Compile a module whose imports have run, as the entry program when
+    /// `entry` is set, folding reads of `constant_imports` to constants
+    pub(crate) fn compile_module_program(
+        program: &Program,
+        source_file: Option<String>,
+        limits: CompileLimits,
+        entry: bool,
+        constant_imports: Option<Rc<ConstantImports>>,
+    ) -> Result<Rc<BytecodeChunk>, JsError> {
+        let mut compiler = if entry {
+            Compiler::new_with_completion_tracking()
+        } else {
+            Compiler::new()
+        };
+        if let Some(path) = source_file {
+            compiler.source_file = Some(path.clone());
+            compiler.builder.set_source_file(path);
+        }
+        compiler.constant_imports = constant_imports;
+        compiler.set_limits(limits, None);
+        compiler.source = program.source.clone();
+        compiler.strict = is_strict_program(program);
+        import_check::check_import_assignments(program)?;
 
         // First, hoist all var declarations and function declarations to the top
         compiler.emit_hoisted_declarations(&program.body)?;
@@ -194,7 +440,7 @@ impl Compiler {
         // Then compile the statements
         compiler.compile_statements(&program.body)?;
         compiler.builder.emit_halt();
-        Ok(Rc::new(compiler.builder.finish()))
+        Ok(Rc::new(compiler.finish_chunk()?))
     }
 
     /// Compile a single statement to bytecode
@@ -202,20 +448,27 @@ impl Compiler {
         let mut compiler = Compiler::new();
         compiler.compile_statement_impl(stmt)?;
         compiler.builder.emit_halt();
-        Ok(Rc::new(compiler.builder.finish()))
+        Ok(Rc::new(compiler.finish_chunk()?))
     }
 
     /// Compile a function body directly (for JIT compilation of interpreted functions)
     /// This is a static entry point that compiles a function body to bytecode,
-    /// handling parameter binding and function info metadata.
+    /// handling parameter binding and function info metadata. Stack traces
+    /// report `source_file` as the function's location.
     pub fn compile_function_body_direct(
         params: &Rc<[crate::ast::FunctionParam]>,
         body: &[crate::ast::Statement],
         name: Option<JsString>,
         is_generator: bool,
         is_async: bool,
+        source_file: String,
+        limits: CompileLimits,
     ) -> Result<BytecodeChunk, JsError> {
-        let mut compiler = Compiler::new();
+        let mut compiler = Compiler::with_source_file(source_file);
+        compiler.set_limits(
+            limits,
+            Some(name.clone().unwrap_or_else(|| JsString::from(""))),
+        );
         let chunk =
             compiler.compile_function_body(params, body, name, is_generator, is_async, false)?;
         Ok(chunk)
@@ -350,14 +603,8 @@ impl Compiler {
             .map(|ctx| ctx.try_depth as u8)
             .unwrap_or(0);
 
-        // Emit IteratorClose before break if this is a for-of loop
-        // Also need to close iterators for any enclosing for-of loops we're breaking out of
-        for i in (loop_idx..self.loop_stack.len()).rev() {
-            if let Some(iter_reg) = self.loop_stack.get(i).and_then(|ctx| ctx.iterator_reg) {
-                self.builder.emit(Op::IteratorClose { iterator: iter_reg });
-            }
-        }
-
+        // Iterators of for-of loops being left are closed by the VM as it
+        // pops their try handlers down to the target depth
         // Emit Break opcode with placeholder target
         let idx = self.builder.emit(Op::Break {
             target: 0,
@@ -390,11 +637,16 @@ impl Compiler {
                 .ok_or_else(|| JsError::syntax_error_simple("Illegal continue statement"))?
         };
 
-        // Get the target loop's try_depth
-        let target_try_depth = self
+        // Get the target loop's try_depth. A for-of loop's own iterator
+        // handler stays on the stack (its continue target pops it), so only
+        // loops nested inside it get closed.
+        let target_loop = self
             .loop_stack
-            .get(loop_idx)
-            .map(|ctx| ctx.try_depth)
+            .iter()
+            .skip(loop_idx)
+            .find(|ctx| ctx.label.is_none());
+        let target_try_depth = target_loop
+            .map(|ctx| ctx.try_depth + usize::from(ctx.iterator_reg.is_some()))
             .unwrap_or(0) as u8;
 
         if let Some(ctx) = self.loop_stack.get_mut(loop_idx) {
@@ -427,5 +679,13 @@ impl Default for Compiler {
     }
 }
 
+/// A function's `length`: the parameters before the first default or rest
+fn expected_argument_count(params: &[crate::ast::FunctionParam]) -> usize {
+    params
+        .iter()
+        .take_while(|param| !matches!(param.pattern, Pattern::Rest(_) | Pattern::Assignment(_)))
+        .count()
+}
+
 // Import CheapClone for JsString
 use crate::value::CheapClone;
@@ -0,0 +1,106 @@
+//! The stable embedding API.
+//!
+//! This module re-exports what a host needs to run scripts: create an
+//! interpreter, prepare and step code, serve imports, answer orders and read
+//! results. These items follow semver: a breaking change to any of them
+//! waits for a major release, and `tests/public_api.rs` fails on changes to
+//! this module that nobody meant to make.
+//!
+//! Everything else the crate exports (the `value`, `gc`, `ast` and `parser`
+//! modules, the FFI, and most crate-root items) is public for tools, tests
+//! and advanced hosts, and can change in any release.
+//!
+//! ```
+//! use tsrun::embed::*;
+//!
+//! let mut interp = Interpreter::with_config(InterpreterConfig::default());
+//! interp.prepare("1 + 2", None).unwrap();
+//! let value = loop {
+//!     match interp.step().unwrap() {
+//!         StepResult::Continue => continue,
+//!         StepResult::Complete(value) => break value,
+//!         other => panic!("unexpected {:?}", other),
+//!     }
+//! };
+//! assert_eq!(value.as_number(), Some(3.0));
+//! ```
+
+/// The interpreter. Stable: existing methods keep their signatures; new
+/// methods may arrive in minor releases.
+pub use crate::Interpreter;
+
+/// Interpreter settings. Stable: fields may be added in minor releases, so
+/// build it with `..Default::default()`.
+pub use crate::InterpreterConfig;
+
+/// A value handed to the host, kept alive while held. Stable.
+pub use crate::RuntimeValue;
+
+/// What a call to `step()` produced. Stable: variants are only added in
+/// major releases.
+pub use crate::StepResult;
+
+/// An async operation the script asks the host to perform. Stable.
+pub use crate::Order;
+
+/// Identifies an order across `step()` and `fulfill_orders`. Stable.
+pub use crate::OrderId;
+
+/// The host's answer to an order. Stable.
+pub use crate::OrderResponse;
+
+/// Whether orders reach the host only at suspensions or as soon as they
+/// are created. Stable.
+pub use crate::OrderDelivery;
+
+/// Build the value of an [`OrderResponse`] from JSON. Stable.
+pub use crate::api::create_response_object;
+
+/// The simplest way to run a script: implement this trait and call
+/// [`Interpreter::run`]. Stable: new methods come with default bodies.
+pub use crate::Host;
+
+/// What [`Interpreter::run`] does with an order. Stable: variants are only
+/// added in major releases.
+pub use crate::HostAction;
+
+/// Builds order responses for a [`Host`]. Stable.
+pub use crate::ResponseFactory;
+
+/// The stage of [`Interpreter::run`] reported to a [`Host`]. Stable:
+/// variants are only added in major releases.
+pub use crate::RunPhase;
+
+/// Console levels passed to [`Host::on_console`]. Stable.
+pub use crate::platform::ConsoleLevel;
+
+/// A normalized module path. Stable.
+pub use crate::ModulePath;
+
+/// A module the script imports and the host must provide. Stable.
+pub use crate::ImportRequest;
+
+/// Whether an import is static or dynamic. Stable.
+pub use crate::ImportKind;
+
+/// A module implemented by the host, served without a file. Stable.
+pub use crate::InternalModule;
+
+/// Builder for native internal modules, from [`InternalModule::native`].
+/// Stable.
+pub use crate::NativeModuleBuilder;
+
+/// The signature of a native module function. Stable.
+pub use crate::InternalFn;
+
+/// A JavaScript value, as native functions receive it. Stable: variants are
+/// only added in major releases.
+pub use crate::JsValue;
+
+/// A value a native function returns, with the guard keeping it alive.
+/// Stable.
+pub use crate::Guarded;
+
+/// Errors from preparing and running scripts. Stable: variants may be added
+/// in minor releases, so match it with a wildcard arm.
+pub use crate::JsError;
@@ -7,10 +7,13 @@
 //! | Error | When |
 //! |-------|------|
 //! | `SyntaxError` | Parsing failures |
+//! | `UnsupportedSyntax` | Recognized syntax tsrun does not implement (JSX, `do` expressions, ...) |
 //! | `TypeError` | Type mismatches at runtime |
 //! | `ReferenceError` | Undefined variable access |
 //! | `RangeError` | Value out of valid range |
 //! | `ModuleError` | Module loading failures |
+//! | `IntegrityMismatch` | A provided module whose source doesn't match its expected hash |
+//! | `TransformError` | The host's source transform rejected a module |
 //!
 //! # Creating Errors
 //!
@@ -29,6 +32,22 @@
 //! let err = JsError::range_error("Invalid array length");
 //! assert!(err.to_string().contains("RangeError"));
 //! ```
+//!
+//! # Source Snippets
+//!
+//! [`SourceText`] maps byte offsets to lines and columns, and
+//! [`JsError::render`] uses it to show the offending line with a caret:
+//!
+//! ```
+//! use tsrun::{Interpreter, SourceText};
+//!
+//! let source = "let x = 1;\nlet y = ;";
+//! let err = Interpreter::new().prepare(source, None).unwrap_err();
+//! assert_eq!(
+//!     err.render(&SourceText::new(source)),
+//!     "SyntaxError: Unexpected Semicolon, expected expression\n --> 2:9\n  |\n2 | let y = ;\n  |         ^"
+//! );
+//! ```
 
 use crate::prelude::*;
 use crate::value::Guarded;
@@ -39,7 +58,12 @@ pub struct SourceLocation {
     pub file: Option<String>,
     pub line: u32,
     pub column: u32,
+    /// Length of the span in bytes
     pub length: u32,
+    /// Byte offset of the span start, when known
+    pub offset: Option<usize>,
+    /// Rendered source excerpt, attached when the source was at hand
+    pub snippet: Option<String>,
 }
 
 impl fmt::Display for SourceLocation {
@@ -53,7 +77,7 @@ impl fmt::Display for SourceLocation {
 }
 
 /// Stack frame for error traces
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StackFrame {
     pub function_name: Option<String>,
     pub file: Option<String>,
@@ -103,6 +127,16 @@ pub enum JsError {
         location: SourceLocation,
     },
 
+    /// Valid syntax of a proposal or dialect that tsrun does not implement.
+    /// Scripts see it as a `SyntaxError`.
+    UnsupportedSyntax {
+        /// What was found, e.g. "JSX" or "the pipeline operator"
+        construct: &'static str,
+        /// What to do instead
+        hint: &'static str,
+        location: SourceLocation,
+    },
+
     TypeError {
         message: String,
         location: Option<SourceLocation>,
@@ -120,12 +154,35 @@ pub enum JsError {
         kind: String,
         message: String,
         stack: Vec<StackFrame>,
+        /// The thrown object's other own enumerable properties, such as a
+        /// `code`, as JSON; properties JSON can't hold are left out.
+        /// [`crate::Interpreter::error_to_value`] puts them back.
+        properties: Option<Box<serde_json::Map<String, serde_json::Value>>>,
+        /// The last events of the flight recorder when the error left the
+        /// interpreter, see [`crate::InterpreterConfig::flight_recorder`]
+        flight_log: Option<Box<[crate::FlightEvent]>>,
     },
 
     ModuleError {
         message: String,
     },
 
+    /// A module source did not match the integrity it was expected to have
+    IntegrityMismatch {
+        path: crate::ModulePath,
+        /// The expected SRI string, e.g. `sha256-<base64>`
+        expected: String,
+        /// The SRI string of the source that was provided
+        actual: String,
+    },
+
+    /// The host's [`crate::InterpreterConfig::source_transform`] failed
+    TransformError {
+        path: crate::ModulePath,
+        /// The message the transform returned
+        message: String,
+    },
+
     Internal(String),
 
     /// Marker error indicating a value was thrown (actual value stored in interpreter)
@@ -145,6 +202,42 @@ pub enum JsError {
     /// When a?.b evaluates with a being null/undefined, we need to short-circuit
     /// the entire optional chain (a?.b.c.d should all return undefined)
     OptionalChainShortCircuit,
+
+    /// Execution stopped by a host limit. Scripts can't catch this: `catch`
+    /// and `finally` blocks are skipped on the way out.
+    Terminated {
+        reason: crate::TerminationReason,
+    },
+
+    /// The host kept stepping a script that can make no more progress, see
+    /// [`crate::InterpreterConfig::stall_threshold`]
+    Stalled {
+        report: Box<crate::StallReport>,
+    },
+
+    /// Compiling produced more code than a limit of
+    /// [`crate::InterpreterConfig`] allows, such as
+    /// [`crate::InterpreterConfig::max_chunk_instructions`]. Scripts see it
+    /// as a `RangeError` from `eval` and `Function`.
+    CompileLimit {
+        limit: crate::CompileLimitKind,
+        /// The maximum the limit was configured with
+        max: usize,
+        /// The function being compiled when the limit was passed: `None`
+        /// for top-level code, empty for an anonymous function
+        function: Option<String>,
+        /// The statement or expression being compiled then
+        location: Option<SourceLocation>,
+    },
+
+    /// A replayed run created an order the log doesn't have, see
+    /// [`crate::Interpreter::set_order_replay`]
+    ReplayMismatch {
+        /// Index of the log entry the order was matched against
+        position: usize,
+        /// The first difference, e.g. `payload.sku: logged "A-1", got "B-2"`
+        diff: String,
+    },
 }
 
 fn format_stack(stack: &[StackFrame]) -> String {
@@ -155,6 +248,10 @@ fn format_stack(stack: &[StackFrame]) -> String {
         .join("\n")
 }
 
+fn unsupported_message(construct: &str, hint: &str) -> String {
+    format!("{} is not supported; {}", construct, hint)
+}
+
 fn format_location(location: &Option<SourceLocation>) -> String {
     match location {
         Some(loc) => format!(" at {}", loc),
@@ -166,7 +263,23 @@ impl fmt::Display for JsError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             JsError::SyntaxError { message, location } => {
-                write!(f, "SyntaxError: {} at {}", message, location)
+                write!(f, "SyntaxError: {} at {}", message, location)?;
+                if let Some(snippet) = &location.snippet {
+                    write!(f, "\n{}", snippet)?;
+                }
+                Ok(())
+            }
+            JsError::UnsupportedSyntax {
+                construct,
+                hint,
+                location,
+            } => {
+                let message = unsupported_message(construct, hint);
+                write!(f, "SyntaxError: {} at {}", message, location)?;
+                if let Some(snippet) = &location.snippet {
+                    write!(f, "\n{}", snippet)?;
+                }
+                Ok(())
             }
             JsError::TypeError { message, location } => {
                 write!(f, "TypeError: {}{}", message, format_location(location))
@@ -181,12 +294,27 @@ impl fmt::Display for JsError {
                 kind,
                 message,
                 stack,
+                ..
             } => {
                 write!(f, "{}: {}\n{}", kind, message, format_stack(stack))
             }
             JsError::ModuleError { message } => {
                 write!(f, "ModuleError: {}", message)
             }
+            JsError::IntegrityMismatch { .. } => {
+                write!(
+                    f,
+                    "ModuleError: {}",
+                    self.integrity_message().unwrap_or_default()
+                )
+            }
+            JsError::TransformError { .. } => {
+                write!(
+                    f,
+                    "ModuleError: {}",
+                    self.transform_message().unwrap_or_default()
+                )
+            }
             JsError::Internal(msg) => {
                 write!(f, "Internal error: {}", msg)
             }
@@ -202,6 +330,37 @@ impl fmt::Display for JsError {
             JsError::OptionalChainShortCircuit => {
                 write!(f, "OptionalChainShortCircuit")
             }
+            JsError::Terminated { reason } => match reason {
+                crate::TerminationReason::FuelExhausted => {
+                    write!(f, "Execution terminated: fuel exhausted")
+                }
+                crate::TerminationReason::Interrupted => {
+                    write!(f, "Execution terminated: interrupted")
+                }
+                crate::TerminationReason::PurityViolation => {
+                    write!(f, "Execution terminated: purity violation")
+                }
+                crate::TerminationReason::HeapBudgetExceeded => {
+                    write!(f, "Execution terminated: heap budget exceeded")
+                }
+            },
+            JsError::Stalled { report } => {
+                write!(f, "Execution stalled: {}", report)
+            }
+            JsError::CompileLimit { .. } => {
+                write!(
+                    f,
+                    "RangeError: {}",
+                    self.compile_limit_message().unwrap_or_default()
+                )
+            }
+            JsError::ReplayMismatch { position, diff } => {
+                write!(
+                    f,
+                    "Order replay mismatch at log entry {}: {}",
+                    position, diff
+                )
+            }
         }
     }
 }
@@ -218,6 +377,23 @@ impl JsError {
                 line,
                 column,
                 length: 1,
+                offset: None,
+                snippet: None,
+            },
+        }
+    }
+
+    /// Create a syntax error covering a lexer span, recording its byte offset
+    pub fn syntax_error_at(message: impl Into<String>, span: &crate::lexer::Span) -> Self {
+        JsError::SyntaxError {
+            message: message.into(),
+            location: SourceLocation {
+                file: None,
+                line: span.line,
+                column: span.column,
+                length: u32::try_from(span.end.saturating_sub(span.start)).unwrap_or(u32::MAX),
+                offset: Some(span.start),
+                snippet: None,
             },
         }
     }
@@ -231,10 +407,108 @@ impl JsError {
                 line: 0,
                 column: 0,
                 length: 0,
+                offset: None,
+                snippet: None,
+            },
+        }
+    }
+
+    /// Create an error for a recognized construct tsrun does not implement
+    pub fn unsupported_syntax(
+        construct: &'static str,
+        hint: &'static str,
+        span: &crate::lexer::Span,
+    ) -> Self {
+        JsError::UnsupportedSyntax {
+            construct,
+            hint,
+            location: SourceLocation {
+                file: None,
+                line: span.line,
+                column: span.column,
+                length: u32::try_from(span.end.saturating_sub(span.start)).unwrap_or(u32::MAX),
+                offset: Some(span.start),
+                snippet: None,
             },
         }
     }
 
+    /// Message of a syntax error, as scripts see it in `error.message`
+    pub(crate) fn syntax_message(&self) -> Option<String> {
+        match self {
+            JsError::SyntaxError { message, .. } => Some(message.clone()),
+            JsError::UnsupportedSyntax {
+                construct, hint, ..
+            } => Some(unsupported_message(construct, hint)),
+            _ => None,
+        }
+    }
+
+    /// The message of an `IntegrityMismatch`
+    pub(crate) fn integrity_message(&self) -> Option<String> {
+        match self {
+            JsError::IntegrityMismatch {
+                path,
+                expected,
+                actual,
+            } => Some(format!(
+                "Integrity check failed for '{}': expected {}, got {}",
+                path, expected, actual
+            )),
+            _ => None,
+        }
+    }
+
+    /// The message of a `TransformError`
+    pub(crate) fn transform_message(&self) -> Option<String> {
+        match self {
+            JsError::TransformError { path, message } => Some(format!(
+                "Source transform failed for '{}': {}",
+                path, message
+            )),
+            _ => None,
+        }
+    }
+
+    /// The message of a `CompileLimit`
+    pub(crate) fn compile_limit_message(&self) -> Option<String> {
+        let JsError::CompileLimit {
+            limit,
+            max,
+            function,
+            location,
+        } = self
+        else {
+            return None;
+        };
+        let exceeded = match limit {
+            crate::CompileLimitKind::ChunkInstructions => format!("more than {} instructions", max),
+            crate::CompileLimitKind::ChunkConstants => format!("more than {} constants", max),
+            crate::CompileLimitKind::FunctionDepth => {
+                format!("functions nested more than {} deep", max)
+            }
+            crate::CompileLimitKind::ProgramInstructions => {
+                format!("more than {} instructions in total", max)
+            }
+        };
+        let function = match function.as_deref() {
+            None => "top-level code".to_string(),
+            Some("") => "anonymous function".to_string(),
+            Some(name) => format!("function {}", name),
+        };
+        Some(format!(
+            "Compiled code too large: {} in {}{}",
+            exceeded,
+            function,
+            format_location(location)
+        ))
+    }
+
+    /// The error for using an object after its interpreter was dropped
+    pub(crate) fn detached_value() -> Self {
+        JsError::type_error("Value used after its Interpreter was dropped")
+    }
+
     pub fn type_error(message: impl Into<String>) -> Self {
         JsError::TypeError {
             message: message.into(),
@@ -250,6 +524,8 @@ impl JsError {
                 line,
                 column,
                 length: 1,
+                offset: None,
+                snippet: None,
             }),
         }
     }
@@ -267,6 +543,40 @@ impl JsError {
         }
     }
 
+    /// ReferenceError for a binding read or written in its temporal dead zone
+    pub fn uninitialized_binding(name: impl core::fmt::Display) -> Self {
+        JsError::RuntimeError {
+            kind: "ReferenceError".to_string(),
+            message: format!("Cannot access '{}' before initialization", name),
+            stack: Vec::new(),
+            properties: None,
+            flight_log: None,
+        }
+    }
+
+    /// ReferenceError for adding to or moving a disposable stack that was
+    /// already disposed
+    pub fn disposed_stack(class: &str) -> Self {
+        JsError::RuntimeError {
+            kind: "ReferenceError".to_string(),
+            message: format!("{} has already been disposed", class),
+            stack: Vec::new(),
+            properties: None,
+            flight_log: None,
+        }
+    }
+
+    /// EvalError, raised when dynamic code generation is disabled
+    pub fn eval_error(message: impl Into<String>) -> Self {
+        JsError::RuntimeError {
+            kind: "EvalError".to_string(),
+            message: message.into(),
+            stack: Vec::new(),
+            properties: None,
+            flight_log: None,
+        }
+    }
+
     pub fn range_error(message: impl Into<String>) -> Self {
         JsError::RangeError {
             message: message.into(),
@@ -288,6 +598,108 @@ impl JsError {
         }
     }
 
+    /// The source location this error points at, if any
+    pub fn location(&self) -> Option<&SourceLocation> {
+        match self {
+            JsError::SyntaxError { location, .. } | JsError::UnsupportedSyntax { location, .. }
+                if location.line > 0 =>
+            {
+                Some(location)
+            }
+            JsError::TypeError {
+                location: Some(location),
+                ..
+            } if location.line > 0 => Some(location),
+            _ => None,
+        }
+    }
+
+    /// Render the error rustc-style, with the offending line of `source` and
+    /// a caret under the error span.
+    ///
+    /// Errors without a location render as their first display line only.
+    pub fn render(&self, source: &SourceText<'_>) -> String {
+        let headline = match self {
+            JsError::SyntaxError { message, .. } => format!("SyntaxError: {}", message),
+            JsError::UnsupportedSyntax {
+                construct, hint, ..
+            } => format!("SyntaxError: {}", unsupported_message(construct, hint)),
+            JsError::TypeError { message, .. } => format!("TypeError: {}", message),
+            other => other.to_string().lines().next().unwrap_or("").to_string(),
+        };
+        match self.location().and_then(|loc| source.snippet(loc)) {
+            Some(snippet) => format!("{}\n{}", headline, snippet),
+            None => headline,
+        }
+    }
+
+    /// Attach a rendered excerpt of `source` to this error's location, so the
+    /// snippet shows up in its `Display` output
+    pub(crate) fn with_snippet(mut self, source: &SourceText<'_>) -> Self {
+        if let JsError::SyntaxError { location, .. } | JsError::UnsupportedSyntax { location, .. } =
+            &mut self
+            && location.line > 0
+        {
+            location.snippet = source.snippet(location);
+        }
+        self
+    }
+
+    /// Fields of a host-side error that its message doesn't carry, as JSON
+    pub(crate) fn host_data(&self) -> Option<serde_json::Value> {
+        match self {
+            JsError::IntegrityMismatch {
+                path,
+                expected,
+                actual,
+            } => Some(serde_json::json!({
+                "path": path.as_str(),
+                "expected": expected,
+                "actual": actual,
+            })),
+            JsError::TransformError { path, message } => Some(serde_json::json!({
+                "path": path.as_str(),
+                "message": message,
+            })),
+            JsError::ReplayMismatch { position, diff } => Some(serde_json::json!({
+                "position": position,
+                "diff": diff,
+            })),
+            _ => None,
+        }
+    }
+
+    /// The flight recorder events attached to this error, oldest first.
+    ///
+    /// Only errors a script raised carry them, and only when
+    /// [`crate::InterpreterConfig::flight_recorder`] is on.
+    pub fn flight_events(&self) -> Option<&[crate::FlightEvent]> {
+        match self {
+            JsError::RuntimeError {
+                flight_log: Some(events),
+                ..
+            } => Some(events),
+            _ => None,
+        }
+    }
+
+    /// [`JsError::flight_events`] rendered one event per line
+    pub fn flight_log(&self) -> Option<String> {
+        let events = self.flight_events()?;
+        Some(
+            events
+                .iter()
+                .map(|event| event.to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    }
+
+    /// Whether this error is a host-imposed stop that scripts must not observe
+    pub fn is_termination(&self) -> bool {
+        matches!(self, JsError::Terminated { .. })
+    }
+
     /// Create an error that wraps a thrown JsValue with its guard
     pub fn thrown(guarded: Guarded) -> Self {
         JsError::ThrownValue { guarded }
@@ -310,18 +722,138 @@ impl JsError {
             JsError::SyntaxError { message, .. } => crate::value::JsValue::String(
                 crate::value::JsString::from(format!("SyntaxError: {}", message)),
             ),
+            JsError::UnsupportedSyntax {
+                construct, hint, ..
+            } => crate::value::JsValue::String(crate::value::JsString::from(format!(
+                "SyntaxError: {}",
+                unsupported_message(construct, hint)
+            ))),
             JsError::RuntimeError { kind, message, .. } => crate::value::JsValue::String(
                 crate::value::JsString::from(format!("{}: {}", kind, message)),
             ),
             JsError::ModuleError { message } => crate::value::JsValue::String(
                 crate::value::JsString::from(format!("ModuleError: {}", message)),
             ),
+            JsError::IntegrityMismatch { .. }
+            | JsError::TransformError { .. }
+            | JsError::CompileLimit { .. } => {
+                crate::value::JsValue::String(crate::value::JsString::from(self.to_string()))
+            }
             JsError::Internal(msg) => crate::value::JsValue::String(crate::value::JsString::from(
                 format!("InternalError: {}", msg),
             )),
             JsError::Thrown => crate::value::JsValue::Undefined,
             // OptionalChainShortCircuit should never escape to user code - it's an internal marker
             JsError::OptionalChainShortCircuit => crate::value::JsValue::Undefined,
+            JsError::Terminated { .. }
+            | JsError::Stalled { .. }
+            | JsError::ReplayMismatch { .. } => {
+                crate::value::JsValue::String(crate::value::JsString::from(self.to_string()))
+            }
         }
     }
 }
+
+/// Source text with precomputed line starts, for mapping byte offsets to
+/// 1-based line and column numbers.
+///
+/// Line terminators match the lexer: `\n`, U+2028 and U+2029. Columns count
+/// characters, so multi-byte characters occupy one column each.
+pub struct SourceText<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> SourceText<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        for (pos, ch) in source.char_indices() {
+            if ch == '\n' || ch == '\u{2028}' || ch == '\u{2029}' {
+                line_starts.push(pos + ch.len_utf8());
+            }
+        }
+        Self {
+            source,
+            line_starts,
+        }
+    }
+
+    /// Number of lines in the source (at least 1)
+    pub fn line_count(&self) -> u32 {
+        u32::try_from(self.line_starts.len()).unwrap_or(u32::MAX)
+    }
+
+    /// Map a byte offset to a 1-based `(line, column)` pair.
+    ///
+    /// Offsets past the end clamp to the end of the source; offsets inside a
+    /// multi-byte character map to that character's column.
+    pub fn offset_to_line_col(&self, offset: usize) -> (u32, u32) {
+        let offset = offset.min(self.source.len());
+        let line_index = self
+            .line_starts
+            .partition_point(|&start| start <= offset)
+            .saturating_sub(1);
+        let line_start = self.line_starts.get(line_index).copied().unwrap_or(0);
+        let column = self
+            .source
+            .get(line_start..)
+            .unwrap_or("")
+            .char_indices()
+            .take_while(|(pos, _)| line_start + pos < offset)
+            .count();
+        let line = u32::try_from(line_index + 1).unwrap_or(u32::MAX);
+        (line, u32::try_from(column + 1).unwrap_or(u32::MAX))
+    }
+
+    /// Text of a 1-based line, without its terminator
+    pub fn line_text(&self, line: u32) -> Option<&'a str> {
+        let index = usize::try_from(line).ok()?.checked_sub(1)?;
+        let start = *self.line_starts.get(index)?;
+        let text = match self.line_starts.get(index + 1) {
+            Some(&next) => {
+                let raw = self.source.get(start..next)?;
+                raw.strip_suffix(['\n', '\u{2028}', '\u{2029}'])
+                    .unwrap_or(raw)
+            }
+            None => self.source.get(start..)?,
+        };
+        Some(text.strip_suffix('\r').unwrap_or(text))
+    }
+
+    /// Rustc-style excerpt for a location: a `-->` header, the source line
+    /// and a caret line. Tabs before the span are kept so the caret lines up.
+    pub fn snippet(&self, location: &SourceLocation) -> Option<String> {
+        let (line, column) = match location.offset {
+            Some(offset) => self.offset_to_line_col(offset),
+            None => (location.line, location.column),
+        };
+        let text = self.line_text(line)?;
+
+        let start_col = usize::try_from(column.max(1) - 1).unwrap_or(0);
+        let padding: String = text
+            .chars()
+            .take(start_col)
+            .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+            .collect();
+        let span_chars = match location.offset {
+            Some(offset) => {
+                let end = offset.saturating_add(location.length as usize);
+                self.source
+                    .get(offset.min(self.source.len())..end.min(self.source.len()))
+                    .map_or(0, |s| s.chars().take_while(|c| *c != '\n').count())
+            }
+            None => location.length as usize,
+        };
+        let remaining = text.chars().count().saturating_sub(start_col);
+        let carets = "^".repeat(span_chars.min(remaining).max(1));
+
+        let gutter = " ".repeat(line.to_string().len());
+        let place = match &location.file {
+            Some(file) => format!("{}:{}:{}", file, line, column),
+            None => format!("{}:{}", line, column),
+        };
+        Some(format!(
+            "{gutter}--> {place}\n{gutter} |\n{line} | {text}\n{gutter} | {padding}{carets}"
+        ))
+    }
+}
@@ -104,3 +104,42 @@ pub extern "C" fn tsrun_set_console(
 
     TsRunResult::success()
 }
+
+/// Limit console output.
+///
+/// Messages longer than `max_message_len` bytes are truncated. Once
+/// `max_output` bytes have been written in total, further messages are
+/// dropped after a single warning; pass 0 for no total limit. Resets the
+/// dropped-message count.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_set_console_limits(
+    ctx: *mut TsRunContext,
+    max_message_len: usize,
+    max_output: usize,
+) -> TsRunResult {
+    let ctx = match unsafe { ctx.as_mut() } {
+        Some(c) => c,
+        None => {
+            return TsRunResult {
+                ok: false,
+                error: c"NULL context".as_ptr(),
+            };
+        }
+    };
+
+    ctx.clear_error();
+    ctx.interp
+        .set_console_limits(max_message_len, (max_output > 0).then_some(max_output));
+    TsRunResult::success()
+}
+
+/// Number of console messages dropped because the output limit was reached.
+///
+/// Returns 0 for a NULL context.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_console_dropped_count(ctx: *const TsRunContext) -> usize {
+    match unsafe { ctx.as_ref() } {
+        Some(c) => c.interp.console_dropped_messages(),
+        None => 0,
+    }
+}
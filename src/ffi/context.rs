@@ -4,15 +4,20 @@ extern crate alloc;
 
 use alloc::boxed::Box;
 use alloc::ffi::CString;
-use alloc::string::ToString;
+use alloc::format;
+use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::ffi::{c_char, c_void};
 use core::ptr;
 
-use crate::{ModulePath, StepResult};
+use crate::value::CheapClone;
+use crate::{
+    JsError, JsValue, ModulePath, OrderResponse, PrepareProgress, RuntimeValue, StepResult,
+};
 
 use super::{
-    TsRunContext, TsRunImportRequest, TsRunOrder, TsRunResult, TsRunStepResult, TsRunStepStatus,
+    TsRunBytes, TsRunContext, TsRunImportRequest, TsRunOrder, TsRunOrderHandlerFn,
+    TsRunPrepareProgress, TsRunPrepareStatus, TsRunResult, TsRunStepResult, TsRunStepStatus,
     TsRunValue, c_str_to_str, console::FfiConsoleProvider, str_to_c_string,
 };
 
@@ -40,7 +45,8 @@ pub extern "C" fn tsrun_new() -> *mut TsRunContext {
 
 /// Free an interpreter context.
 ///
-/// Also frees all associated values that haven't been explicitly freed.
+/// Values obtained from it must still be freed with `tsrun_value_free`;
+/// until then objects among them read as undefined.
 #[unsafe(no_mangle)]
 pub extern "C" fn tsrun_free(ctx: *mut TsRunContext) {
     if !ctx.is_null() {
@@ -88,6 +94,180 @@ pub extern "C" fn tsrun_prepare(
     }
 }
 
+/// Start an incremental prepare of a source delivered in chunks.
+///
+/// `path` is optional, as for `tsrun_prepare`. Feed the source with
+/// `tsrun_prepare_chunk`, then call `tsrun_prepare_finish` and
+/// `tsrun_prepare_continue` until the status is Ready.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_prepare_begin(ctx: *mut TsRunContext, path: *const c_char) -> TsRunResult {
+    let ctx = match unsafe { ctx.as_mut() } {
+        Some(c) => c,
+        None => {
+            return TsRunResult {
+                ok: false,
+                error: c"NULL context".as_ptr(),
+            };
+        }
+    };
+
+    ctx.clear_error();
+
+    let module_path = unsafe { c_str_to_str(path) }.map(|p| ModulePath::new(p.to_string()));
+    ctx.interp.prepare_begin(module_path);
+    TsRunResult::success()
+}
+
+/// Append `len` bytes of source to an incremental prepare.
+///
+/// The data need not be NUL-terminated, and a UTF-8 sequence may be split
+/// between two chunks.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_prepare_chunk(
+    ctx: *mut TsRunContext,
+    data: *const u8,
+    len: usize,
+) -> TsRunResult {
+    let ctx = match unsafe { ctx.as_mut() } {
+        Some(c) => c,
+        None => {
+            return TsRunResult {
+                ok: false,
+                error: c"NULL context".as_ptr(),
+            };
+        }
+    };
+
+    ctx.clear_error();
+
+    let bytes = if len == 0 {
+        &[][..]
+    } else if data.is_null() {
+        return TsRunResult::err(ctx, "NULL chunk data".to_string());
+    } else {
+        unsafe { core::slice::from_raw_parts(data, len) }
+    };
+
+    match ctx.interp.prepare_chunk_bytes(bytes) {
+        Ok(()) => TsRunResult::success(),
+        Err(e) => TsRunResult::err(ctx, e.to_string()),
+    }
+}
+
+/// Finish receiving chunks and parse up to about `budget` source bytes.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_prepare_finish(
+    ctx: *mut TsRunContext,
+    budget: usize,
+) -> TsRunPrepareProgress {
+    prepare_progress(ctx, |interp| interp.prepare_finish(budget))
+}
+
+/// Resume an incremental prepare after a Pending status.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_prepare_continue(
+    ctx: *mut TsRunContext,
+    budget: usize,
+) -> TsRunPrepareProgress {
+    prepare_progress(ctx, |interp| interp.prepare_continue(budget))
+}
+
+/// Write out the run set up by `tsrun_prepare`, before its first step, for
+/// `tsrun_import_prepared` in another context.
+///
+/// Free the bytes with `tsrun_free_bytes`.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_export_prepared(ctx: *mut TsRunContext) -> TsRunBytes {
+    let ctx = match unsafe { ctx.as_mut() } {
+        Some(c) => c,
+        None => {
+            return TsRunBytes {
+                data: ptr::null_mut(),
+                len: 0,
+                error: c"NULL context".as_ptr(),
+            };
+        }
+    };
+
+    ctx.clear_error();
+
+    match ctx.interp.export_prepared() {
+        Ok(bytes) => TsRunBytes::ok(bytes),
+        Err(e) => TsRunBytes::err(ctx, e.to_string()),
+    }
+}
+
+/// Set up a run written by `tsrun_export_prepared`, in place of `tsrun_prepare`.
+///
+/// Fails for bytes written by another version or build of tsrun.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_import_prepared(
+    ctx: *mut TsRunContext,
+    data: *const u8,
+    len: usize,
+) -> TsRunResult {
+    let ctx = match unsafe { ctx.as_mut() } {
+        Some(c) => c,
+        None => {
+            return TsRunResult {
+                ok: false,
+                error: c"NULL context".as_ptr(),
+            };
+        }
+    };
+
+    ctx.clear_error();
+
+    if data.is_null() {
+        return TsRunResult::err(ctx, "NULL prepared data".to_string());
+    }
+    let bytes = unsafe { core::slice::from_raw_parts(data, len) };
+
+    match ctx.interp.import_prepared(bytes) {
+        Ok(_) => TsRunResult::success(),
+        Err(e) => TsRunResult::err(ctx, e.to_string()),
+    }
+}
+
+fn prepare_progress(
+    ctx: *mut TsRunContext,
+    advance: impl FnOnce(&mut crate::Interpreter) -> Result<PrepareProgress, JsError>,
+) -> TsRunPrepareProgress {
+    let ctx = match unsafe { ctx.as_mut() } {
+        Some(c) => c,
+        None => {
+            return TsRunPrepareProgress {
+                status: TsRunPrepareStatus::Error,
+                parsed_bytes: 0,
+                total_bytes: 0,
+                error: c"NULL context".as_ptr(),
+            };
+        }
+    };
+
+    ctx.clear_error();
+
+    match advance(&mut ctx.interp) {
+        Ok(PrepareProgress::Pending {
+            parsed_bytes,
+            total_bytes,
+        }) => TsRunPrepareProgress {
+            status: TsRunPrepareStatus::Pending,
+            parsed_bytes,
+            total_bytes,
+            error: ptr::null(),
+        },
+        // As with tsrun_prepare, needed imports are reported by the next step
+        Ok(PrepareProgress::Ready(_)) => TsRunPrepareProgress {
+            status: TsRunPrepareStatus::Ready,
+            parsed_bytes: 0,
+            total_bytes: 0,
+            error: ptr::null(),
+        },
+        Err(e) => TsRunPrepareProgress::err(ctx, e.to_string()),
+    }
+}
+
 /// Execute one step.
 ///
 /// The result is written to `out` which must point to valid memory for TsRunStepResult.
@@ -191,6 +371,49 @@ pub extern "C" fn tsrun_run(out: *mut TsRunStepResult, ctx: *mut TsRunContext) {
     }
 }
 
+/// Prepare and run code to completion, answering orders through a callback.
+///
+/// `order_handler` (optional) is invoked synchronously for every order with the
+/// payload serialized as JSON; see `TsRunOrderHandlerFn` for the response
+/// contract. Scripts that issue orders without a handler fail. Imports are not
+/// resolved: a script that needs modules fails with an error listing the
+/// missing paths (seed them with `tsrun_provide_module_unrequested` first).
+///
+/// On success returns true and stores the completion value as JSON in
+/// `*out_json`. On failure returns false and stores the message in `*out_err`.
+/// Both strings are owned by the caller and must be freed with
+/// tsrun_free_string. Either out pointer may be NULL to discard that string.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_eval_json(
+    ctx: *mut TsRunContext,
+    source: *const c_char,
+    path: *const c_char,
+    order_handler: Option<TsRunOrderHandlerFn>,
+    userdata: *mut c_void,
+    out_json: *mut *mut c_char,
+    out_err: *mut *mut c_char,
+) -> bool {
+    write_out_string(out_json, None);
+    write_out_string(out_err, None);
+
+    let result = if ctx.is_null() {
+        Err("NULL context".to_string())
+    } else {
+        eval_to_json(ctx, source, path, order_handler, userdata)
+    };
+
+    match result {
+        Ok(json) => {
+            write_out_string(out_json, Some(&json));
+            true
+        }
+        Err(message) => {
+            write_out_string(out_err, Some(&message));
+            false
+        }
+    }
+}
+
 /// Free a step result's internal arrays.
 ///
 /// Does NOT free the value - caller must free that separately with tsrun_value_free.
@@ -226,12 +449,21 @@ pub extern "C" fn tsrun_step_result_free(result: *mut TsRunStepResult) {
         result.imports = ptr::null_mut();
         result.import_count = 0;
 
-        // Free pending orders array (but NOT the payload values - they're owned by context)
+        // Free pending orders array and origin strings (but NOT the payload
+        // values - they're owned by context)
         // Use Box::from_raw with slice to match how we allocated (via into_boxed_slice)
         if !result.pending_orders.is_null() && result.pending_count > 0 {
             let slice_ptr =
                 core::ptr::slice_from_raw_parts_mut(result.pending_orders, result.pending_count);
-            drop(Box::from_raw(slice_ptr));
+            let orders = Box::from_raw(slice_ptr);
+            for order in orders.iter() {
+                if !order.origin_module.is_null() {
+                    drop(CString::from_raw(order.origin_module as *mut c_char));
+                }
+                if !order.origin_function.is_null() {
+                    drop(CString::from_raw(order.origin_function as *mut c_char));
+                }
+            }
         }
         result.pending_orders = ptr::null_mut();
         result.pending_count = 0;
@@ -300,23 +532,7 @@ fn convert_step_result(_ctx: &mut TsRunContext, result: StepResult) -> TsRunStep
         }
 
         StepResult::Suspended { pending, cancelled } => {
-            // Convert pending orders - use null pointer if empty
-            // Use into_boxed_slice to ensure capacity == length for correct deallocation
-            let (orders_ptr, pending_count) = if pending.is_empty() {
-                (ptr::null_mut(), 0)
-            } else {
-                let c_orders: Vec<TsRunOrder> = pending
-                    .into_iter()
-                    .map(|order| TsRunOrder {
-                        id: order.id.0,
-                        payload: Box::into_raw(TsRunValue::from_runtime_value(order.payload)),
-                    })
-                    .collect();
-                let count = c_orders.len();
-                let boxed = c_orders.into_boxed_slice();
-                let ptr = Box::into_raw(boxed) as *mut TsRunOrder;
-                (ptr, count)
-            };
+            let (orders_ptr, pending_count) = orders_to_c(pending);
 
             // Convert cancelled order IDs - use null pointer if empty
             // Use into_boxed_slice to ensure capacity == length for correct deallocation
@@ -339,5 +555,175 @@ fn convert_step_result(_ctx: &mut TsRunContext, result: StepResult) -> TsRunStep
                 ..Default::default()
             }
         }
+
+        StepResult::Orders(pending) => {
+            let (orders_ptr, pending_count) = orders_to_c(pending);
+            TsRunStepResult {
+                status: TsRunStepStatus::Orders,
+                pending_orders: orders_ptr,
+                pending_count,
+                ..Default::default()
+            }
+        }
+    }
+}
+
+/// Store an allocated copy of `s` (or NULL) into an optional out pointer.
+fn write_out_string(out: *mut *mut c_char, s: Option<&str>) {
+    if !out.is_null() {
+        let value = s.map_or(ptr::null_mut(), str_to_c_string);
+        unsafe { ptr::write(out, value) };
+    }
+}
+
+fn eval_to_json(
+    ctx: *mut TsRunContext,
+    source: *const c_char,
+    path: *const c_char,
+    order_handler: Option<TsRunOrderHandlerFn>,
+    userdata: *mut c_void,
+) -> Result<String, String> {
+    {
+        let ctx_ref = unsafe { &mut *ctx };
+        ctx_ref.clear_error();
+        let code_str = unsafe { c_str_to_str(source) }
+            .ok_or_else(|| "Invalid or NULL code string".to_string())?;
+        let module_path = unsafe { c_str_to_str(path) }.map(|p| ModulePath::new(p.to_string()));
+        ctx_ref
+            .interp
+            .prepare(code_str, module_path)
+            .map_err(|e| e.to_string())?;
+    }
+
+    loop {
+        // Re-borrow every iteration: the order handler receives `ctx` and may
+        // call back into tsrun, so no reference is held across it.
+        let ctx_ref = unsafe { &mut *ctx };
+        ctx_ref.interp.ffi_context = ctx as *mut c_void;
+        let step = ctx_ref.interp.step();
+        ctx_ref.interp.ffi_context = ptr::null_mut();
+
+        match step {
+            Ok(StepResult::Continue) => continue,
+            Ok(StepResult::Complete(rv)) => return value_to_json_string(rv.value()),
+            Ok(StepResult::Done) => return Ok("null".to_string()),
+            Ok(StepResult::NeedImports(imports)) => {
+                let paths: Vec<&str> = imports.iter().map(|i| i.resolved_path.as_str()).collect();
+                return Err(format!("Missing modules: {}", paths.join(", ")));
+            }
+            Ok(StepResult::Suspended { pending, .. }) if pending.is_empty() => {
+                return Err("Execution suspended with no pending orders".to_string());
+            }
+            Ok(StepResult::Suspended { pending, .. }) | Ok(StepResult::Orders(pending)) => {
+                answer_orders(ctx, order_handler, userdata, pending)?;
+            }
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+}
+
+/// Serialize a value with `js_value_to_json` into a compact JSON string.
+fn value_to_json_string(value: &JsValue) -> Result<String, String> {
+    let json = crate::js_value_to_json(value).map_err(|e| e.to_string())?;
+    serde_json::to_string(&json).map_err(|e| format!("JSON stringify error: {}", e))
+}
+
+/// Run each order through the host handler and fulfill them in one batch.
+fn answer_orders(
+    ctx: *mut TsRunContext,
+    order_handler: Option<TsRunOrderHandlerFn>,
+    userdata: *mut c_void,
+    pending: Vec<crate::Order>,
+) -> Result<(), String> {
+    let handler = order_handler
+        .ok_or_else(|| "Script issued an order but no order handler was provided".to_string())?;
+
+    let mut responses = Vec::with_capacity(pending.len());
+    for order in pending {
+        let payload = value_to_json_string(order.payload.value())?;
+        let payload = CString::new(payload).map_err(|_| "Order payload contains a null byte")?;
+        drop(order.payload);
+
+        let mut response_ptr: *mut c_char = ptr::null_mut();
+        let mut is_error = false;
+        handler(
+            ctx,
+            payload.as_ptr(),
+            userdata,
+            &mut response_ptr,
+            &mut is_error,
+        );
+
+        // SAFETY: the handler contract requires tsrun_alloc_string allocations
+        let response = if response_ptr.is_null() {
+            None
+        } else {
+            Some(unsafe { CString::from_raw(response_ptr) })
+        };
+        let response = response.map(|s| s.to_string_lossy().into_owned());
+
+        let result = if is_error {
+            Err(JsError::type_error(
+                response.unwrap_or_else(|| "Unknown error".to_string()),
+            ))
+        } else {
+            match response {
+                Some(json) => Ok(json_to_runtime_value(ctx, &json)?),
+                None => Ok(RuntimeValue::unguarded(JsValue::Undefined)),
+            }
+        };
+        responses.push(OrderResponse {
+            id: order.id,
+            result,
+        });
+    }
+
+    unsafe { &mut *ctx }.interp.fulfill_orders(responses);
+    Ok(())
+}
+
+/// Parse a handler response into a guarded runtime value.
+fn json_to_runtime_value(ctx: *mut TsRunContext, json: &str) -> Result<RuntimeValue, String> {
+    let json_value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| format!("Invalid order response JSON: {}", e))?;
+    let ctx_ref = unsafe { &mut *ctx };
+    let guard = ctx_ref.interp.heap.create_guard();
+    let value = crate::json_to_js_value_with_guard(&mut ctx_ref.interp, &json_value, &guard)
+        .map_err(|e| e.to_string())?;
+    if let JsValue::Object(ref obj) = value {
+        guard.guard(obj.cheap_clone());
+    }
+    Ok(RuntimeValue::with_guard(value, guard))
+}
+
+/// Convert pending orders - use null pointer if empty
+fn orders_to_c(pending: Vec<crate::Order>) -> (*mut TsRunOrder, usize) {
+    if pending.is_empty() {
+        return (ptr::null_mut(), 0);
     }
+    // Use into_boxed_slice to ensure capacity == length for correct deallocation
+    let c_orders: Vec<TsRunOrder> = pending
+        .into_iter()
+        .map(|order| TsRunOrder {
+            id: order.id.0,
+            payload: Box::into_raw(TsRunValue::from_runtime_value(order.payload)),
+            origin_module: order
+                .origin
+                .module
+                .as_ref()
+                .map(|p| str_to_c_string(p.as_str()))
+                .unwrap_or(ptr::null_mut()),
+            origin_function: order
+                .origin
+                .function_name
+                .as_deref()
+                .map(str_to_c_string)
+                .unwrap_or(ptr::null_mut()),
+            origin_line: order.origin.line,
+            origin_column: order.origin.column,
+        })
+        .collect();
+    let count = c_orders.len();
+    let boxed = c_orders.into_boxed_slice();
+    (Box::into_raw(boxed) as *mut TsRunOrder, count)
 }
@@ -12,7 +12,8 @@
 //! - `TsRunContext`: Created by `tsrun_new()`, freed by `tsrun_free()`
 //! - `TsRunValue`: Created by various functions, freed by `tsrun_value_free()`
 //! - Error strings: Valid until the next tsrun_* call on the same context
-//! - Allocated strings (from `tsrun_json_stringify`): Freed by `tsrun_free_string()`
+//! - Allocated strings (from `tsrun_json_stringify`, `tsrun_eval_json`,
+//!   `tsrun_alloc_string`): Freed by `tsrun_free_string()`
 
 extern crate alloc;
 
@@ -131,8 +132,12 @@ impl TsRunValue {
         }
     }
 
+    /// The value; an object whose context has been freed reads as undefined
     pub(crate) fn value(&self) -> &JsValue {
-        self.inner.value()
+        match self.inner.try_value() {
+            Ok(value) => value,
+            Err(_) => &JsValue::Undefined,
+        }
     }
 }
 
@@ -196,6 +201,36 @@ impl TsRunResult {
     }
 }
 
+/// Result for operations returning bytes.
+#[repr(C)]
+pub struct TsRunBytes {
+    /// The bytes, or NULL on error. Free with tsrun_free_bytes.
+    pub data: *mut u8,
+    /// Number of bytes at `data`.
+    pub len: usize,
+    /// Error message, or NULL on success. Valid until next tsrun_* call.
+    pub error: *const c_char,
+}
+
+impl TsRunBytes {
+    pub(crate) fn ok(bytes: Vec<u8>) -> Self {
+        let len = bytes.len();
+        Self {
+            data: Box::into_raw(bytes.into_boxed_slice()).cast::<u8>(),
+            len,
+            error: ptr::null(),
+        }
+    }
+
+    pub(crate) fn err(ctx: &mut TsRunContext, error: String) -> Self {
+        Self {
+            data: ptr::null_mut(),
+            len: 0,
+            error: ctx.set_error(error),
+        }
+    }
+}
+
 // ============================================================================
 // Value Types
 // ============================================================================
@@ -213,6 +248,65 @@ pub enum TsRunType {
     Symbol = 6,
 }
 
+/// Well-known symbols, for `tsrun_well_known_symbol`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TsRunWellKnownSymbol {
+    Iterator = 0,
+    AsyncIterator = 1,
+    HasInstance = 2,
+    IsConcatSpreadable = 3,
+    Match = 4,
+    Replace = 5,
+    Search = 6,
+    Species = 7,
+    Split = 8,
+    ToPrimitive = 9,
+    ToStringTag = 10,
+    Unscopables = 11,
+    Dispose = 12,
+    AsyncDispose = 13,
+}
+
+impl From<TsRunWellKnownSymbol> for crate::WellKnownSymbol {
+    fn from(symbol: TsRunWellKnownSymbol) -> Self {
+        use crate::WellKnownSymbol as W;
+        match symbol {
+            TsRunWellKnownSymbol::Iterator => W::Iterator,
+            TsRunWellKnownSymbol::AsyncIterator => W::AsyncIterator,
+            TsRunWellKnownSymbol::HasInstance => W::HasInstance,
+            TsRunWellKnownSymbol::IsConcatSpreadable => W::IsConcatSpreadable,
+            TsRunWellKnownSymbol::Match => W::Match,
+            TsRunWellKnownSymbol::Replace => W::Replace,
+            TsRunWellKnownSymbol::Search => W::Search,
+            TsRunWellKnownSymbol::Species => W::Species,
+            TsRunWellKnownSymbol::Split => W::Split,
+            TsRunWellKnownSymbol::ToPrimitive => W::ToPrimitive,
+            TsRunWellKnownSymbol::ToStringTag => W::ToStringTag,
+            TsRunWellKnownSymbol::Unscopables => W::Unscopables,
+            TsRunWellKnownSymbol::Dispose => W::Dispose,
+            TsRunWellKnownSymbol::AsyncDispose => W::AsyncDispose,
+        }
+    }
+}
+
+/// Where a script function was defined, filled by `tsrun_value_function_source`.
+///
+/// Release the strings with `tsrun_function_source_free`.
+#[repr(C)]
+pub struct TsRunFunctionSource {
+    /// The function's source text, exactly as written.
+    pub text: *mut c_char,
+    /// Path of the file the function was defined in, or NULL if unknown.
+    pub path: *mut c_char,
+    /// 1-based line and column of the first character.
+    pub start_line: u32,
+    pub start_column: u32,
+    /// 1-based line and column just past the last character.
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
 // ============================================================================
 // Step Status
 // ============================================================================
@@ -233,6 +327,43 @@ pub enum TsRunStepStatus {
     Done = 4,
     /// Execution error.
     Error = 5,
+    /// New orders while execution continues (eager order delivery).
+    Orders = 6,
+}
+
+/// Status of an incremental prepare.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TsRunPrepareStatus {
+    /// Out of budget; call tsrun_prepare_continue again.
+    Pending = 0,
+    /// Parsed and compiled; continue with tsrun_step or tsrun_run.
+    Ready = 1,
+    /// Prepare failed and was abandoned.
+    Error = 2,
+}
+
+/// Progress of an incremental prepare.
+#[repr(C)]
+pub struct TsRunPrepareProgress {
+    pub status: TsRunPrepareStatus,
+    /// Source bytes parsed so far, for Pending status.
+    pub parsed_bytes: usize,
+    /// Total source bytes, for Pending status.
+    pub total_bytes: usize,
+    /// Error message for Error status, NULL otherwise. Valid until next tsrun_* call.
+    pub error: *const c_char,
+}
+
+impl TsRunPrepareProgress {
+    pub(crate) fn err(ctx: &mut TsRunContext, error: String) -> Self {
+        Self {
+            status: TsRunPrepareStatus::Error,
+            parsed_bytes: 0,
+            total_bytes: 0,
+            error: ctx.set_error(error),
+        }
+    }
 }
 
 // ============================================================================
@@ -257,6 +388,14 @@ pub struct TsRunOrder {
     pub id: u64,
     /// Order payload value (owned by context).
     pub payload: *mut TsRunValue,
+    /// Module that created the order (NULL for an unnamed entry program).
+    pub origin_module: *const c_char,
+    /// Function the order was created in (NULL at top level or when anonymous).
+    pub origin_function: *const c_char,
+    /// 1-based line of the creating call (0 if unknown).
+    pub origin_line: u32,
+    /// 1-based column of the creating call (0 if unknown).
+    pub origin_column: u32,
 }
 
 /// Step result with all possible data.
@@ -273,7 +412,7 @@ pub struct TsRunStepResult {
     /// Number of import requests.
     pub import_count: usize,
 
-    /// For TSRUN_STEP_SUSPENDED: pending orders.
+    /// For TSRUN_STEP_SUSPENDED and TSRUN_STEP_ORDERS: pending orders.
     pub pending_orders: *mut TsRunOrder,
     /// Number of pending orders.
     pub pending_count: usize,
@@ -367,6 +506,25 @@ pub type TsRunNativeFn = extern "C" fn(
     error_out: *mut *const c_char,
 ) -> *mut TsRunValue;
 
+// ============================================================================
+// Order Handler Callback
+// ============================================================================
+
+/// Order handler signature for `tsrun_eval_json`.
+///
+/// Called synchronously once per order. `order_payload_json` is only valid for
+/// the duration of the call. Store the response in `*out_response_json` using a
+/// string from `tsrun_alloc_string` (tsrun takes ownership), or leave it NULL
+/// to resolve with undefined. Set `*out_is_error` to reject the order; the
+/// response string is then used as the error message.
+pub type TsRunOrderHandlerFn = extern "C" fn(
+    ctx: *mut TsRunContext,
+    order_payload_json: *const c_char,
+    userdata: *mut c_void,
+    out_response_json: *mut *mut c_char,
+    out_is_error: *mut bool,
+);
+
 // ============================================================================
 // GC Statistics
 // ============================================================================
@@ -382,6 +540,39 @@ pub struct TsRunGcStats {
     pub live_objects: usize,
 }
 
+/// Reachable objects grouped by kind, see `tsrun_gc_census`.
+#[repr(C)]
+pub struct TsRunGcCensus {
+    pub ordinary: usize,
+    pub arrays: usize,
+    pub functions: usize,
+    pub promises: usize,
+    pub maps: usize,
+    pub sets: usize,
+    pub environments: usize,
+    pub generators: usize,
+    pub proxies: usize,
+    pub other: usize,
+    /// Own properties, array elements and environment bindings.
+    pub properties: usize,
+    /// UTF-8 bytes of strings held in those slots, counting property keys.
+    pub string_bytes: usize,
+}
+
+/// Length of `TsRunGcRetainer::label`, including the NUL terminator.
+pub const TSRUN_RETAINER_LABEL_LEN: usize = 32;
+
+/// A guard keeping objects alive, see `tsrun_gc_retainers`.
+#[repr(C)]
+pub struct TsRunGcRetainer {
+    /// NUL-terminated guard label, e.g. "root" or "order payload"; empty if unlabeled.
+    pub label: [c_char; TSRUN_RETAINER_LABEL_LEN],
+    /// Objects the guard roots directly.
+    pub roots: usize,
+    /// Objects that become garbage if the guard is released.
+    pub retained: usize,
+}
+
 // ============================================================================
 // Utility Functions
 // ============================================================================
@@ -398,6 +589,34 @@ pub unsafe extern "C" fn tsrun_free_string(s: *mut c_char) {
     }
 }
 
+/// Free bytes returned by tsrun (e.g., from tsrun_export_prepared).
+///
+/// # Safety
+/// `data` must be a pointer returned by a tsrun function (or NULL), and
+/// `len` must be the length returned with it.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tsrun_free_bytes(data: *mut u8, len: usize) {
+    if !data.is_null() {
+        // SAFETY: data and len come from a boxed slice leaked by TsRunBytes::ok
+        unsafe { drop(Box::from_raw(ptr::slice_from_raw_parts_mut(data, len))) };
+    }
+}
+
+/// Copy a C string into a tsrun-owned allocation.
+///
+/// Use this for strings handed back to tsrun (e.g., order handler responses).
+/// Returns NULL if `s` is NULL or not valid UTF-8.
+///
+/// # Safety
+/// `s` must be a valid NUL-terminated string (or NULL).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tsrun_alloc_string(s: *const c_char) -> *mut c_char {
+    match unsafe { c_str_to_str(s) } {
+        Some(s) => str_to_c_string(s),
+        None => ptr::null_mut(),
+    }
+}
+
 /// Free a string array allocated by tsrun (e.g., from tsrun_keys).
 ///
 /// # Safety
@@ -421,6 +640,27 @@ pub unsafe extern "C" fn tsrun_free_strings(strings: *mut *mut c_char, count: us
     }
 }
 
+/// Free an array of values returned by a tsrun function (e.g. tsrun_symbol_keys).
+///
+/// # Safety
+/// `values` must be NULL or an array of `count` values returned by tsrun.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tsrun_free_values(values: *mut *mut TsRunValue, count: usize) {
+    if values.is_null() {
+        return;
+    }
+    // SAFETY: values was allocated by a tsrun function, count matches original allocation
+    unsafe {
+        for i in 0..count {
+            let v = *values.add(i);
+            if !v.is_null() {
+                drop(Box::from_raw(v));
+            }
+        }
+        drop(Vec::from_raw_parts(values, count, count));
+    }
+}
+
 /// Helper to convert C string to Rust &str.
 pub(crate) unsafe fn c_str_to_str<'a>(s: *const c_char) -> Option<&'a str> {
     if s.is_null() {
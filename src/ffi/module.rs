@@ -16,11 +16,32 @@ use super::{TsRunContext, TsRunResult, TsRunValueResult, c_str_to_str, str_to_c_
 // ============================================================================
 
 /// Provide module source code in response to TSRUN_STEP_NEED_IMPORTS.
+///
+/// Fails if `path` is not one of the requested imports.
 #[unsafe(no_mangle)]
 pub extern "C" fn tsrun_provide_module(
     ctx: *mut TsRunContext,
     path: *const c_char,
     code: *const c_char,
+) -> TsRunResult {
+    provide_module_source(ctx, path, code, false)
+}
+
+/// Pre-seed module source code that has not been requested (yet).
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_provide_module_unrequested(
+    ctx: *mut TsRunContext,
+    path: *const c_char,
+    code: *const c_char,
+) -> TsRunResult {
+    provide_module_source(ctx, path, code, true)
+}
+
+fn provide_module_source(
+    ctx: *mut TsRunContext,
+    path: *const c_char,
+    code: *const c_char,
+    unrequested: bool,
 ) -> TsRunResult {
     let ctx = match unsafe { ctx.as_mut() } {
         Some(c) => c,
@@ -43,7 +64,12 @@ pub extern "C" fn tsrun_provide_module(
     };
 
     let module_path = ModulePath::new(path_str.to_string());
-    match ctx.interp.provide_module(module_path, code_str) {
+    let result = if unrequested {
+        ctx.interp.provide_module_unrequested(module_path, code_str)
+    } else {
+        ctx.interp.provide_module(module_path, code_str)
+    };
+    match result {
         Ok(()) => TsRunResult::success(),
         Err(e) => TsRunResult::err(ctx, e.to_string()),
     }
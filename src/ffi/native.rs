@@ -172,12 +172,12 @@ fn native_callback_trampoline(
     } else {
         let result_val = unsafe { Box::from_raw(result) };
         // Create a guard for the result if it's an object
-        if let JsValue::Object(obj) = result_val.inner.value() {
+        if let JsValue::Object(obj) = result_val.value() {
             let guard = interp.heap.create_guard();
             guard.guard(obj.cheap_clone());
-            Ok(Guarded::with_guard(result_val.inner.value().clone(), guard))
+            Ok(Guarded::with_guard(result_val.value().clone(), guard))
         } else {
-            Ok(Guarded::unguarded(result_val.inner.value().clone()))
+            Ok(Guarded::unguarded(result_val.value().clone()))
         }
     }
 }
@@ -9,7 +9,7 @@ use core::ffi::c_char;
 use core::ptr;
 
 use crate::value::{CheapClone, PropertyKey};
-use crate::{JsError, JsString, JsValue, OrderId, OrderResponse, RuntimeValue};
+use crate::{JsError, JsString, JsValue, OrderDelivery, OrderId, OrderResponse, RuntimeValue};
 
 use super::{
     TsRunContext, TsRunOrderResponse, TsRunResult, TsRunValue, TsRunValueResult, c_str_to_str,
@@ -68,6 +68,30 @@ pub extern "C" fn tsrun_fulfill_orders(
     TsRunResult::success()
 }
 
+/// Choose when orders are handed to the host.
+///
+/// With `eager` set, `order()` returns a Promise instead of suspending and new
+/// orders are reported via TSRUN_STEP_ORDERS while the script keeps running.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_set_order_delivery(ctx: *mut TsRunContext, eager: bool) -> TsRunResult {
+    let ctx = match unsafe { ctx.as_mut() } {
+        Some(c) => c,
+        None => {
+            return TsRunResult {
+                ok: false,
+                error: c"NULL context".as_ptr(),
+            };
+        }
+    };
+
+    ctx.interp.set_order_delivery(if eager {
+        OrderDelivery::Eager
+    } else {
+        OrderDelivery::Batched
+    });
+    TsRunResult::success()
+}
+
 // ============================================================================
 // Pending Order Creation
 // ============================================================================
@@ -76,7 +100,8 @@ pub extern "C" fn tsrun_fulfill_orders(
 ///
 /// Use this in native C callbacks when you need to perform async operations.
 /// When a native function returns a pending order value, the interpreter suspends
-/// and reports the order to the host via TSRUN_STEP_SUSPENDED.
+/// and reports the order to the host via TSRUN_STEP_SUSPENDED. With eager order
+/// delivery the value is a Promise and the order is reported via TSRUN_STEP_ORDERS.
 ///
 /// The payload is the value that will be reported to the host (accessible via order.payload).
 /// The returned order_id_out is the ID the host will use to fulfill the order.
@@ -104,37 +129,15 @@ pub extern "C" fn tsrun_create_pending_order(
         None => JsValue::Undefined,
     };
 
-    // Generate unique order ID
-    let id = crate::OrderId(ctx.interp.next_order_id);
-    ctx.interp.next_order_id += 1;
+    let (id, result) = ctx.interp.create_order(payload_value, None, None);
 
     // Write order ID to output parameter
     if !order_id_out.is_null() {
         unsafe { *order_id_out = id.0 };
     }
 
-    // Create payload RuntimeValue with guard if it's an object
-    let payload_rv = if let JsValue::Object(ref obj) = payload_value {
-        let payload_guard = ctx.interp.heap.create_guard();
-        payload_guard.guard(obj.clone());
-        RuntimeValue::with_guard(payload_value.clone(), payload_guard)
-    } else {
-        RuntimeValue::unguarded(payload_value)
-    };
-
-    // Record the pending order
-    ctx.interp.pending_orders.push(crate::Order {
-        id,
-        payload: payload_rv,
-    });
-
-    // Create PendingOrder marker - VM will suspend when this is returned
-    let marker_guard = ctx.interp.heap.create_guard();
-    let marker = marker_guard.alloc();
-    marker.borrow_mut().exotic = crate::value::ExoticObject::PendingOrder { id: id.0 };
-
     TsRunValueResult::ok(Box::new(TsRunValue {
-        inner: RuntimeValue::with_guard(JsValue::Object(marker), marker_guard),
+        inner: RuntimeValue::from_guarded(result),
     }))
 }
 
@@ -14,8 +14,8 @@ use crate::value::{CheapClone, ExoticObject, PropertyKey};
 use crate::{JsString, JsValue};
 
 use super::{
-    TsRunContext, TsRunResult, TsRunType, TsRunValue, TsRunValueResult, c_str_to_str,
-    str_to_c_string,
+    TsRunContext, TsRunResult, TsRunType, TsRunValue, TsRunValueResult, TsRunWellKnownSymbol,
+    c_str_to_str, str_to_c_string,
 };
 
 // ============================================================================
@@ -125,6 +125,18 @@ pub extern "C" fn tsrun_is_function(val: *const TsRunValue) -> bool {
         .unwrap_or(false)
 }
 
+/// Get the identity of the object a value refers to.
+///
+/// Values of the same object return the same id, which no other object of
+/// the context gets while it lives. Returns 0 for primitives.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_value_object_id(val: *const TsRunValue) -> u64 {
+    unsafe { val.as_ref() }
+        .and_then(|v| v.inner.object_id())
+        .map(|id| id.as_u64())
+        .unwrap_or(0)
+}
+
 // ============================================================================
 // Value Extraction
 // ============================================================================
@@ -294,6 +306,8 @@ pub extern "C" fn tsrun_string_len(
 // ============================================================================
 
 /// Free a value.
+///
+/// Safe before or after `tsrun_free` of its context.
 #[unsafe(no_mangle)]
 pub extern "C" fn tsrun_value_free(val: *mut TsRunValue) {
     if !val.is_null() {
@@ -546,6 +560,177 @@ pub extern "C" fn tsrun_keys(
     ptr
 }
 
+// ============================================================================
+// Symbol Operations
+// ============================================================================
+
+/// Get a well-known symbol such as `Symbol.iterator`.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_well_known_symbol(
+    ctx: *mut TsRunContext,
+    symbol: TsRunWellKnownSymbol,
+) -> *mut TsRunValue {
+    let ctx = match unsafe { ctx.as_mut() } {
+        Some(c) => c,
+        None => return ptr::null_mut(),
+    };
+    let value = ctx.interp.get_well_known(symbol.into());
+    Box::into_raw(TsRunValue::from_runtime_value(value))
+}
+
+/// Create a new unique symbol. `description` may be NULL.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_symbol_new(
+    ctx: *mut TsRunContext,
+    description: *const c_char,
+) -> *mut TsRunValue {
+    let ctx = match unsafe { ctx.as_mut() } {
+        Some(c) => c,
+        None => return ptr::null_mut(),
+    };
+    let description = unsafe { c_str_to_str(description) };
+    let value = ctx.interp.create_symbol(description);
+    Box::into_raw(TsRunValue::from_runtime_value(value))
+}
+
+/// Get a symbol's description. Returns NULL if not a symbol or it has none.
+///
+/// The returned string must be freed with tsrun_free_string.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_symbol_description(val: *const TsRunValue) -> *mut c_char {
+    unsafe { val.as_ref() }
+        .and_then(|v| match v.value() {
+            JsValue::Symbol(sym) => sym
+                .description
+                .as_ref()
+                .map(|d| str_to_c_string(d.as_str())),
+            _ => None,
+        })
+        .unwrap_or(ptr::null_mut())
+}
+
+/// Get `obj[symbol]`.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_get_symbol(
+    ctx: *mut TsRunContext,
+    obj: *mut TsRunValue,
+    symbol: *mut TsRunValue,
+) -> TsRunValueResult {
+    let ctx = match unsafe { ctx.as_mut() } {
+        Some(c) => c,
+        None => {
+            return TsRunValueResult {
+                value: ptr::null_mut(),
+                error: c"NULL context".as_ptr(),
+            };
+        }
+    };
+
+    let obj_val = match unsafe { obj.as_ref() } {
+        Some(v) => v,
+        None => return TsRunValueResult::err(ctx, "NULL object".to_string()),
+    };
+
+    let symbol_val = match unsafe { symbol.as_ref() } {
+        Some(v) => v,
+        None => return TsRunValueResult::err(ctx, "NULL symbol".to_string()),
+    };
+
+    match ctx
+        .interp
+        .get_property_by_symbol(&obj_val.inner, &symbol_val.inner)
+    {
+        Ok(value) => TsRunValueResult::ok(TsRunValue::from_runtime_value(value)),
+        Err(e) => TsRunValueResult::err(ctx, e.to_string()),
+    }
+}
+
+/// Set `obj[symbol] = val`.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_set_symbol(
+    ctx: *mut TsRunContext,
+    obj: *mut TsRunValue,
+    symbol: *mut TsRunValue,
+    val: *mut TsRunValue,
+) -> TsRunResult {
+    let ctx = match unsafe { ctx.as_mut() } {
+        Some(c) => c,
+        None => {
+            return TsRunResult {
+                ok: false,
+                error: c"NULL context".as_ptr(),
+            };
+        }
+    };
+
+    let obj_val = match unsafe { obj.as_ref() } {
+        Some(v) => v,
+        None => return TsRunResult::err(ctx, "NULL object".to_string()),
+    };
+
+    let symbol_val = match unsafe { symbol.as_ref() } {
+        Some(v) => v,
+        None => return TsRunResult::err(ctx, "NULL symbol".to_string()),
+    };
+
+    let val_ref = match unsafe { val.as_ref() } {
+        Some(v) => v,
+        None => return TsRunResult::err(ctx, "NULL value".to_string()),
+    };
+
+    match ctx
+        .interp
+        .set_property_by_symbol(&obj_val.inner, &symbol_val.inner, &val_ref.inner)
+    {
+        Ok(()) => TsRunResult::success(),
+        Err(e) => TsRunResult::err(ctx, e.to_string()),
+    }
+}
+
+/// Get the symbol keys of an object's own properties.
+///
+/// Caller must free the returned array with tsrun_free_values.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_symbol_keys(
+    ctx: *mut TsRunContext,
+    obj: *mut TsRunValue,
+    count_out: *mut usize,
+) -> *mut *mut TsRunValue {
+    if !count_out.is_null() {
+        unsafe { *count_out = 0 };
+    }
+    if unsafe { ctx.as_mut() }.is_none() {
+        return ptr::null_mut();
+    }
+    let Some(obj_val) = (unsafe { obj.as_ref() }).filter(|v| !v.inner.is_detached()) else {
+        return ptr::null_mut();
+    };
+
+    let keys: Vec<*mut TsRunValue> = obj_val
+        .inner
+        .symbol_keys()
+        .into_iter()
+        .map(|sym| {
+            let value = crate::RuntimeValue::unguarded(JsValue::Symbol(Box::new(sym)));
+            Box::into_raw(TsRunValue::from_runtime_value(value))
+        })
+        .collect();
+
+    let count = keys.len();
+    if !count_out.is_null() {
+        unsafe { *count_out = count };
+    }
+
+    if count == 0 {
+        return ptr::null_mut();
+    }
+
+    let mut boxed = keys.into_boxed_slice();
+    let ptr = boxed.as_mut_ptr();
+    core::mem::forget(boxed);
+    ptr
+}
+
 // ============================================================================
 // Array Operations
 // ============================================================================
@@ -733,7 +918,7 @@ pub extern "C" fn tsrun_json_parse(
         None => return TsRunValueResult::err(ctx, "Invalid or NULL JSON string".to_string()),
     };
 
-    let json_value: serde_json::Value = match serde_json::from_str(json_str) {
+    let json_value = match crate::interpreter::builtins::json::parse_json_text(json_str) {
         Ok(v) => v,
         Err(e) => return TsRunValueResult::err(ctx, format!("JSON parse error: {}", e)),
     };
@@ -952,6 +1137,54 @@ pub extern "C" fn tsrun_call_method(
     }
 }
 
+/// Get the source text and location of a script function.
+///
+/// Returns false, leaving `out` untouched, for non-functions, native and bound
+/// functions, or when function source retention is disabled. On success the
+/// strings in `out` must be released with `tsrun_function_source_free`.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_value_function_source(
+    val: *const TsRunValue,
+    out: *mut super::TsRunFunctionSource,
+) -> bool {
+    let Some(source) = unsafe { val.as_ref() }
+        .filter(|v| !v.inner.is_detached())
+        .and_then(|v| v.inner.function_source())
+    else {
+        return false;
+    };
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return false;
+    };
+    let span = source.span();
+    *out = super::TsRunFunctionSource {
+        text: str_to_c_string(source.text()),
+        path: source
+            .path()
+            .map(str_to_c_string)
+            .unwrap_or(ptr::null_mut()),
+        start_line: span.start_line,
+        start_column: span.start_column,
+        end_line: span.end_line,
+        end_column: span.end_column,
+    };
+    true
+}
+
+/// Free the strings of a `TsRunFunctionSource` and reset them to NULL.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_function_source_free(source: *mut super::TsRunFunctionSource) {
+    let Some(source) = (unsafe { source.as_mut() }) else {
+        return;
+    };
+    unsafe {
+        super::tsrun_free_string(source.text);
+        super::tsrun_free_string(source.path);
+    }
+    source.text = ptr::null_mut();
+    source.path = ptr::null_mut();
+}
+
 // ============================================================================
 // Global Access
 // ============================================================================
@@ -977,12 +1210,9 @@ pub extern "C" fn tsrun_get_global(
         None => return TsRunValueResult::err(ctx, "Invalid or NULL name".to_string()),
     };
 
-    let prop_key = PropertyKey::String(JsString::from(name_str));
     let value = ctx
         .interp
-        .global
-        .borrow()
-        .get_property(&prop_key)
+        .read_global(&JsString::from(name_str))
         .unwrap_or(JsValue::Undefined);
 
     TsRunValueResult::ok(TsRunValue::from_js_value(&mut ctx.interp, value))
@@ -1016,6 +1246,7 @@ pub extern "C" fn tsrun_set_global(
     };
 
     let prop_key = PropertyKey::String(JsString::from(name_str));
+    ctx.interp.install_builtin(name_str);
     ctx.interp
         .global
         .borrow_mut()
@@ -1049,3 +1280,64 @@ pub extern "C" fn tsrun_gc_stats(ctx: *mut TsRunContext) -> super::TsRunGcStats
         live_objects: stats.live_objects,
     }
 }
+
+/// Count reachable objects by kind.
+///
+/// Walks the whole heap; intended for diagnosing memory growth.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_gc_census(ctx: *mut TsRunContext) -> super::TsRunGcCensus {
+    let census = match unsafe { ctx.as_ref() } {
+        Some(ctx) => ctx.interp.gc_census(),
+        None => crate::GcCensus::default(),
+    };
+    super::TsRunGcCensus {
+        ordinary: census.ordinary,
+        arrays: census.arrays,
+        functions: census.functions,
+        promises: census.promises,
+        maps: census.maps,
+        sets: census.sets,
+        environments: census.environments,
+        generators: census.generators,
+        proxies: census.proxies,
+        other: census.other,
+        properties: census.properties,
+        string_bytes: census.string_bytes,
+    }
+}
+
+/// Write up to `capacity` of the guards retaining the most objects to `out`,
+/// largest first. Returns the number written.
+#[unsafe(no_mangle)]
+pub extern "C" fn tsrun_gc_retainers(
+    ctx: *mut TsRunContext,
+    out: *mut super::TsRunGcRetainer,
+    capacity: usize,
+) -> usize {
+    let Some(ctx) = (unsafe { ctx.as_ref() }) else {
+        return 0;
+    };
+    if out.is_null() || capacity == 0 {
+        return 0;
+    }
+
+    let retainers = ctx.interp.gc_retainers(capacity);
+    for (i, retainer) in retainers.iter().enumerate() {
+        let mut label = [0 as c_char; super::TSRUN_RETAINER_LABEL_LEN];
+        let bytes = retainer.label.unwrap_or("").as_bytes();
+        for (dst, &src) in label
+            .iter_mut()
+            .zip(bytes.iter().take(super::TSRUN_RETAINER_LABEL_LEN - 1))
+        {
+            *dst = src as c_char;
+        }
+        unsafe {
+            out.add(i).write(super::TsRunGcRetainer {
+                label,
+                roots: retainer.roots,
+                retained: retainer.retained,
+            });
+        }
+    }
+    retainers.len()
+}
@@ -33,7 +33,9 @@
 //! # GC Triggering
 //!
 //! Collection runs automatically when `net_allocs >= gc_threshold`.
-//! Set threshold to 0 to disable automatic collection.
+//! Set threshold to 0 to disable automatic collection. Hosts with idle time
+//! can also call `Interpreter::maybe_collect`, which collects only when enough
+//! garbage has built up and past cycle durations say it fits a time budget.
 //!
 //! ```
 //! use tsrun::Interpreter;
@@ -196,20 +198,51 @@ impl<T: Default + Reset + Traceable> Eq for Gc<T> {}
 
 impl<T: Default + Reset + Traceable> Gc<T> {
     /// Borrow the inner data immutably
+    ///
+    /// # Panics
+    /// Panics if the Heap has been dropped; see [`Gc::is_heap_alive`].
     pub fn borrow(&self) -> Ref<'_, T> {
+        if !self.is_heap_alive() {
+            heap_dropped();
+        }
         unsafe { self.ptr.as_ref().data.borrow() }
     }
 
     /// Borrow the inner data mutably
+    ///
+    /// # Panics
+    /// Panics if the Heap has been dropped; see [`Gc::is_heap_alive`].
     pub fn borrow_mut(&self) -> RefMut<'_, T> {
+        if !self.is_heap_alive() {
+            heap_dropped();
+        }
         unsafe { self.ptr.as_ref().data.borrow_mut() }
     }
 
+    /// Whether the Heap owning the object still exists.
+    ///
+    /// Once it is dropped the object's memory is gone and borrowing it
+    /// panics; dropping or cloning the `Gc` stays safe.
+    pub fn is_heap_alive(&self) -> bool {
+        self.space.strong_count() > 0
+    }
+
     /// Get the object's unique ID (pointer address)
     pub fn id(&self) -> usize {
         self.ptr.as_ptr() as usize
     }
 
+    /// The object's slot index and how many times the slot has been reused.
+    /// Unlike [`Gc::id`] this tells a new object in a reused slot apart from
+    /// the old one. `None` once the Heap is dropped.
+    pub fn slot_generation(&self) -> Option<(usize, u32)> {
+        if !self.is_heap_alive() {
+            return None;
+        }
+        let gc_box = unsafe { self.ptr.as_ref() };
+        Some((gc_box.index, gc_box.generation.get()))
+    }
+
     /// Check if two Gc pointers point to the same object
     pub fn ptr_eq(a: &Gc<T>, b: &Gc<T>) -> bool {
         a.ptr == b.ptr
@@ -224,6 +257,108 @@ impl<T: Default + Reset + Traceable> Gc<T> {
     pub fn copy_ref(&self) -> GcPtr<T> {
         GcPtr { ptr: self.ptr }
     }
+
+    /// Create a weak reference that does not keep the object alive.
+    ///
+    /// The reference dies when the object is collected or pooled, so it
+    /// never resolves to a slot that has been reused for another object.
+    pub fn downgrade(&self) -> WeakGc<T> {
+        let alive = match self.space.upgrade() {
+            Some(space) => {
+                let gc_box = unsafe { self.ptr.as_ref() };
+                if gc_box.pooled.get() {
+                    Rc::new(Cell::new(false))
+                } else {
+                    space
+                        .borrow_mut()
+                        .weak_slots
+                        .entry(gc_box.index)
+                        .or_insert_with(|| Rc::new(Cell::new(true)))
+                        .clone()
+                }
+            }
+            None => Rc::new(Cell::new(false)),
+        };
+        WeakGc {
+            ptr: self.ptr,
+            alive,
+            space: self.space.clone(),
+        }
+    }
+}
+
+/// Fail a borrow of an object whose Heap has been dropped
+#[cold]
+#[inline(never)]
+fn heap_dropped() -> ! {
+    #[allow(clippy::panic)]
+    {
+        panic!("GC error: object used after its Heap was dropped")
+    }
+}
+
+// ============================================================================
+// WeakGc - a reference that does not keep its target alive
+// ============================================================================
+
+/// A weak reference to a GC-managed object, created by [`Gc::downgrade`].
+///
+/// It is not traced, so it does not keep the target reachable. All weak
+/// references to one object share a liveness flag that the space clears when
+/// the object is collected.
+pub struct WeakGc<T: Default + Reset + Traceable> {
+    ptr: NonNull<GcBox<T>>,
+    /// Shared with the space's `weak_slots` entry for the target
+    alive: Rc<Cell<bool>>,
+    space: Weak<RefCell<Space<T>>>,
+}
+
+impl<T: Default + Reset + Traceable> WeakGc<T> {
+    /// Get the target if it has not been collected.
+    ///
+    /// The returned `Gc` must be guarded or stored in a traced object before
+    /// the next allocation, like any other `Gc`.
+    pub fn upgrade(&self) -> Option<Gc<T>> {
+        if !self.alive.get() {
+            return None;
+        }
+        let _space = self.space.upgrade()?;
+        let gc_box = unsafe { self.ptr.as_ref() };
+        gc_box.ref_count.set(gc_box.ref_count.get() + 1);
+        Some(Gc {
+            ptr: self.ptr,
+            space: self.space.clone(),
+        })
+    }
+
+    /// Whether the target has been collected
+    pub fn is_dead(&self) -> bool {
+        !self.alive.get() || self.space.strong_count() == 0
+    }
+
+    /// Whether this is a live reference to `target`
+    pub fn refers_to(&self, target: &Gc<T>) -> bool {
+        !self.is_dead() && self.ptr == target.ptr
+    }
+}
+
+impl<T: Default + Reset + Traceable> Clone for WeakGc<T> {
+    fn clone(&self) -> Self {
+        Self {
+            ptr: self.ptr,
+            alive: self.alive.clone(),
+            space: self.space.clone(),
+        }
+    }
+}
+
+impl<T: Default + Reset + Traceable> fmt::Debug for WeakGc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeakGc")
+            .field("ptr", &self.ptr)
+            .field("dead", &self.is_dead())
+            .finish()
+    }
 }
 
 // ============================================================================
@@ -352,9 +487,10 @@ pub struct GcBox<T: Default + Reset + Traceable> {
 
     /// Whether this object is in the pool (dead)
     pooled: Cell<bool>,
-    // Generation counter - incremented each time slot is reused from pool.
-    // Old Gc pointers with different generations don't affect ref_count.
-    // generation: Cell<u32>,
+
+    /// Times the slot has been reused from the pool, so a new object in it
+    /// doesn't share the old one's identity
+    generation: Cell<u32>,
 }
 
 impl<T: Default + Reset + Traceable> GcBox<T> {
@@ -364,7 +500,7 @@ impl<T: Default + Reset + Traceable> GcBox<T> {
             data: RefCell::new(data),
             ref_count: Cell::new(0),
             pooled: Cell::new(false),
-            // generation: Cell::new(0),
+            generation: Cell::new(0),
         }
     }
 }
@@ -408,9 +544,35 @@ struct Space<T: Default + Reset + Traceable> {
     /// GC triggers when this exceeds threshold
     net_allocs: isize,
 
+    /// Total allocations over the space's lifetime (never reset)
+    total_allocs: usize,
+
     /// Threshold for triggering collection (0 = never auto-collect)
     gc_threshold: isize,
 
+    /// Liveness flags of objects that have weak references, by object index.
+    /// Pooling an object clears its flag and removes the entry.
+    weak_slots: FxHashMap<usize, Rc<Cell<bool>>>,
+
+    /// Weakly referenced objects that have died over the space's lifetime
+    weak_deaths: usize,
+
+    /// Collection cycles run, automatic or forced
+    collections: usize,
+
+    /// Duration of the last timed collection, see [`Heap::record_collect_duration`]
+    last_collect_ms: f64,
+
+    /// Sum of all timed collection durations
+    total_collect_ms: f64,
+
+    /// Collections whose duration was recorded
+    timed_collections: usize,
+
+    /// Smoothed cost of a timed collection per heap slot, used to predict
+    /// how long the next one will take
+    collect_ms_per_object: f64,
+
     /// Weak self-reference for Gc pointers
     self_weak: Weak<RefCell<Space<T>>>,
 }
@@ -419,6 +581,14 @@ struct Space<T: Default + Reset + Traceable> {
 /// Higher threshold = less frequent GC = better throughput but more memory
 const DEFAULT_GC_THRESHOLD: usize = 100;
 
+/// Idle collection skips a cycle unless at least this many objects were
+/// allocated since the last one
+pub(crate) const IDLE_COLLECT_MIN_ALLOCS: usize = 64;
+
+/// ... and unless they amount to at least `1 / IDLE_COLLECT_GARBAGE_RATIO`
+/// of the live heap
+pub(crate) const IDLE_COLLECT_GARBAGE_RATIO: usize = 8;
+
 /// Chunk capacity: objects per chunk (hardcoded for bitmask optimization)
 /// 256 = 4 × 64 bits, matching ChunkBitmask size
 const CHUNK_CAPACITY: usize = 256;
@@ -434,7 +604,15 @@ impl<T: Default + Reset + Traceable> Space<T> {
             guard_pool: Vec::new(),
             active_guards: Vec::new(),
             net_allocs: 0,
+            total_allocs: 0,
             gc_threshold: DEFAULT_GC_THRESHOLD as isize,
+            weak_slots: FxHashMap::default(),
+            weak_deaths: 0,
+            collections: 0,
+            last_collect_ms: 0.0,
+            total_collect_ms: 0.0,
+            timed_collections: 0,
+            collect_ms_per_object: 0.0,
             self_weak: Weak::new(),
         }
     }
@@ -473,6 +651,7 @@ impl<T: Default + Reset + Traceable> Space<T> {
         // This ensures the newly allocated object won't be swept before
         // it's added to a guard's roots
         self.net_allocs += 1;
+        self.total_allocs += 1;
         if self.gc_threshold > 0 && self.net_allocs >= self.gc_threshold {
             self.collect();
         }
@@ -493,6 +672,9 @@ impl<T: Default + Reset + Traceable> Space<T> {
             gc_box.data.borrow_mut().reset();
             gc_box.ref_count.set(1); // Start with ref_count = 1 for the returned Gc
             gc_box.pooled.set(false);
+            gc_box
+                .generation
+                .set(gc_box.generation.get().wrapping_add(1));
             ptr
         } else {
             // Need to allocate new - check if current chunk has space
@@ -563,6 +745,14 @@ impl<T: Default + Reset + Traceable> Space<T> {
         // Decrement net allocations (object is being deallocated)
         self.net_allocs -= 1;
 
+        // Kill weak references so they can't resolve to the reused slot
+        if !self.weak_slots.is_empty()
+            && let Some(alive) = self.weak_slots.remove(&gc_box.index)
+        {
+            alive.set(false);
+            self.weak_deaths += 1;
+        }
+
         // Mark as pooled (reset already called in sweep or will be called on reuse)
         gc_box.pooled.set(true);
 
@@ -699,8 +889,12 @@ impl<T: Default + Reset + Traceable> Space<T> {
     /// Run mark-and-sweep collection
     fn collect(&mut self) {
         self.mark();
+        // Forget slots whose weak references have all been dropped
+        self.weak_slots
+            .retain(|_, alive| Rc::strong_count(alive) > 1);
         self.sweep();
         self.net_allocs = 0;
+        self.collections += 1;
     }
 
     /// Force a collection (for testing or explicit cleanup)
@@ -716,13 +910,111 @@ impl<T: Default + Reset + Traceable> Space<T> {
             total_objects,
             pooled_objects: self.free_list.len(),
             live_objects: total_objects - self.free_list.len(),
+            allocations: self.total_allocs,
+            allocations_since_collect: self.net_allocs.max(0) as usize,
+            collections: self.collections,
+            last_collect_ms: self.last_collect_ms,
+            total_collect_ms: self.total_collect_ms,
         }
     }
 
+    fn record_collect_duration(&mut self, duration_ms: f64, objects: usize) {
+        self.last_collect_ms = duration_ms;
+        self.total_collect_ms += duration_ms;
+        if objects > 0 {
+            let sample = duration_ms / objects as f64;
+            self.collect_ms_per_object = if self.timed_collections == 0 {
+                sample
+            } else {
+                (self.collect_ms_per_object + sample) / 2.0
+            };
+        }
+        self.timed_collections += 1;
+    }
+
     /// Set the GC threshold (0 = disable automatic collection)
     fn set_gc_threshold(&mut self, threshold: usize) {
         self.gc_threshold = threshold as isize;
     }
+
+    /// Rank live guards by the number of objects only they keep reachable.
+    ///
+    /// Each guard's roots are traced in turn, tagging every object with the
+    /// guard that reached it. An object reached from a second guard becomes
+    /// shared, and so does everything below it, so no object is traced more
+    /// than twice.
+    fn dominators(&mut self, top_n: usize) -> Vec<Retainer> {
+        let mut owners = vec![Owner::Unreached; self.chunks.len() * CHUNK_CAPACITY];
+        let guards: Vec<Rc<GuardInner<T>>> = self
+            .active_guards
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect();
+
+        let mut stack: Vec<(NonNull<GcBox<T>>, Owner)> = Vec::new();
+        for (g, inner) in guards.iter().enumerate() {
+            stack.extend(
+                inner
+                    .roots
+                    .borrow()
+                    .iter()
+                    .map(|&ptr| (ptr, Owner::Guard(g))),
+            );
+            while let Some((ptr, tag)) = stack.pop() {
+                let gc_box = unsafe { ptr.as_ref() };
+                if gc_box.pooled.get() {
+                    continue;
+                }
+                let Some(slot) = owners.get_mut(gc_box.index) else {
+                    continue;
+                };
+                let owner = match (*slot, tag) {
+                    (Owner::Shared, _) => continue,
+                    (Owner::Guard(h), Owner::Guard(g)) if h == g => continue,
+                    (Owner::Unreached, tag) => tag,
+                    _ => Owner::Shared,
+                };
+                *slot = owner;
+                gc_box
+                    .data
+                    .borrow()
+                    .trace(|child: GcPtr<T>| stack.push((child.ptr, owner)));
+            }
+        }
+
+        let mut retained = vec![0usize; guards.len()];
+        for owner in &owners {
+            if let Owner::Guard(g) = owner
+                && let Some(count) = retained.get_mut(*g)
+            {
+                *count += 1;
+            }
+        }
+
+        let mut retainers: Vec<Retainer> = guards
+            .iter()
+            .zip(retained)
+            .filter(|(inner, _)| !inner.roots.borrow().is_empty())
+            .map(|(inner, retained)| Retainer {
+                label: inner.label.get(),
+                roots: inner.roots.borrow().len(),
+                retained,
+            })
+            .collect();
+        retainers.sort_by_key(|r| core::cmp::Reverse(r.retained));
+        retainers.truncate(top_n);
+        retainers
+    }
+}
+
+/// Which guards reach an object, during [`Space::dominators`]
+#[derive(Clone, Copy)]
+enum Owner {
+    Unreached,
+    /// Reached only from the guard at this index
+    Guard(usize),
+    /// Reached from more than one guard
+    Shared,
 }
 
 impl<T: Default + Reset + Traceable> Drop for Space<T> {
@@ -777,6 +1069,77 @@ impl<T: Default + Reset + Traceable> Heap<T> {
     pub fn set_gc_threshold(&self, threshold: usize) {
         self.inner.borrow_mut().set_gc_threshold(threshold);
     }
+
+    /// Record how long a collection over `objects` heap slots took.
+    ///
+    /// The heap has no clock of its own, so only callers that time a
+    /// [`Heap::collect`] report it; automatic collections are counted but
+    /// not timed.
+    pub fn record_collect_duration(&self, duration_ms: f64, objects: usize) {
+        self.inner
+            .borrow_mut()
+            .record_collect_duration(duration_ms, objects);
+    }
+
+    /// Predicted duration of a collection at the current heap size, from
+    /// past timed collections. `None` until one has been recorded.
+    pub fn estimated_collect_ms(&self) -> Option<f64> {
+        let space = self.inner.borrow();
+        if space.timed_collections == 0 {
+            return None;
+        }
+        let total_objects: usize = space.chunks.iter().map(|c| c.len()).sum();
+        Some(space.collect_ms_per_object * total_objects as f64)
+    }
+
+    /// Number of weakly referenced objects that have died so far.
+    ///
+    /// The count only grows, so comparing it with an earlier reading tells
+    /// whether any [`WeakGc`] has died since.
+    pub fn weak_deaths(&self) -> usize {
+        self.inner.borrow().weak_deaths
+    }
+
+    /// Visit every object reachable from a guard.
+    ///
+    /// Runs a mark phase first, so garbage awaiting collection is skipped.
+    /// `f` must not allocate or drop objects.
+    pub fn for_each_reachable(&self, mut f: impl FnMut(&T)) {
+        let mut space = self.inner.borrow_mut();
+        space.mark();
+        for (chunk, bitmask) in space.chunks.iter().zip(space.marked_chunks.iter()) {
+            for (index_in_chunk, gc_box) in chunk.iter().enumerate() {
+                if bitmask.get(index_in_chunk) && !gc_box.pooled.get() {
+                    f(&gc_box.data.borrow());
+                }
+            }
+        }
+    }
+
+    /// Number of live guards that were handed to the host, directly or
+    /// inside a `RuntimeValue`.
+    ///
+    /// Values they keep alive can no longer be read once the heap is
+    /// dropped, so this should be 0 by then.
+    pub fn host_guard_count(&self) -> usize {
+        self.inner
+            .borrow()
+            .active_guards
+            .iter()
+            .filter_map(Weak::upgrade)
+            .filter(|guard| guard.host.get())
+            .count()
+    }
+
+    /// Report the `top_n` guards retaining the most objects.
+    ///
+    /// An object counts towards a guard when that guard is the only one it is
+    /// reachable from, i.e. dropping the guard would free it. Objects shared by
+    /// several guards count towards none. This walks the whole heap, so it is
+    /// meant for diagnosing leaks rather than routine monitoring.
+    pub fn dominators(&self, top_n: usize) -> Vec<Retainer> {
+        self.inner.borrow_mut().dominators(top_n)
+    }
 }
 
 impl<T: Default + Reset + Traceable> Default for Heap<T> {
@@ -802,18 +1165,22 @@ impl<T: Default + Reset + Traceable> Clone for Heap<T> {
 struct GuardInner<T: Default + Reset + Traceable> {
     /// Objects guarded by this guard (these are the GC roots)
     roots: RefCell<Vec<NonNull<GcBox<T>>>>,
+    /// What the guard is for, reported by [`Heap::dominators`]
+    label: Cell<Option<&'static str>>,
+    /// Whether the guard was handed to the host, see [`Heap::host_guard_count`]
+    host: Cell<bool>,
 }
 
 impl<T: Default + Reset + Traceable> GuardInner<T> {
     fn new() -> Self {
-        Self {
-            roots: RefCell::new(Vec::new()),
-        }
+        Self::with_storage(Vec::new())
     }
 
     fn with_storage(storage: Vec<NonNull<GcBox<T>>>) -> Self {
         Self {
             roots: RefCell::new(storage),
+            label: Cell::new(None),
+            host: Cell::new(false),
         }
     }
 }
@@ -915,6 +1282,16 @@ impl<T: Default + Reset + Traceable> Guard<T> {
         self.inner.roots.borrow().is_empty()
     }
 
+    /// Name what this guard roots, so [`Heap::dominators`] can report it
+    pub fn set_label(&self, label: &'static str) {
+        self.inner.label.set(Some(label));
+    }
+
+    /// Count this guard in [`Heap::host_guard_count`] while it is alive
+    pub(crate) fn mark_host(&self) {
+        self.inner.host.set(true);
+    }
+
     /// Get the strong reference count of this guard's inner (for debugging)
     pub fn strong_count(&self) -> usize {
         Rc::strong_count(&self.inner)
@@ -950,6 +1327,46 @@ pub struct GcStats {
     pub pooled_objects: usize,
     /// Number of live objects
     pub live_objects: usize,
+    /// Total number of allocations since the heap was created
+    pub allocations: usize,
+    /// Net allocations since the last collection
+    pub allocations_since_collect: usize,
+    /// Collection cycles run so far, automatic or forced
+    pub collections: usize,
+    /// Duration of the last timed collection in milliseconds
+    pub last_collect_ms: f64,
+    /// Sum of all timed collection durations in milliseconds
+    pub total_collect_ms: f64,
+}
+
+/// Result of [`crate::Interpreter::maybe_collect`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CollectOutcome {
+    /// A full cycle ran
+    Collected {
+        /// How long the cycle took, at the time provider's resolution
+        duration_ms: f64,
+        /// Objects returned to the pool
+        freed: usize,
+    },
+    /// Too little was allocated since the last collection to be worth a cycle
+    NothingToDo,
+    /// Past cycles suggest a collection would not fit in the budget
+    OverBudget {
+        /// Predicted duration of a cycle at the current heap size
+        estimated_ms: f64,
+    },
+}
+
+/// A guard ranked by [`Heap::dominators`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Retainer {
+    /// The guard's label, if it was given one with [`Guard::set_label`]
+    pub label: Option<&'static str>,
+    /// Number of objects the guard roots directly
+    pub roots: usize,
+    /// Number of objects that become garbage if the guard is dropped
+    pub retained: usize,
 }
 
 // ============================================================================
@@ -1294,4 +1711,50 @@ mod tests {
 
         assert_eq!(heap.stats().live_objects, 0);
     }
+
+    #[test]
+    fn test_dominators_count_exclusively_retained_objects() {
+        let heap: Heap<TestObj> = Heap::new();
+        heap.set_gc_threshold(0);
+
+        let a = heap.create_guard();
+        a.set_label("a");
+        let b = heap.create_guard();
+        b.set_label("b");
+
+        let a_root = a.alloc();
+        let b_root = b.alloc();
+        {
+            let temp = heap.create_guard();
+            for _ in 0..3 {
+                a_root.borrow_mut().refs.push(temp.alloc());
+            }
+            let shared = temp.alloc();
+            shared.borrow_mut().refs.push(temp.alloc());
+            a_root.borrow_mut().refs.push(shared.clone());
+            b_root.borrow_mut().refs.push(shared);
+        }
+
+        let retainers = heap.dominators(10);
+        assert_eq!(
+            retainers,
+            vec![
+                Retainer {
+                    label: Some("a"),
+                    roots: 1,
+                    retained: 4,
+                },
+                Retainer {
+                    label: Some("b"),
+                    roots: 1,
+                    retained: 1,
+                },
+            ]
+        );
+        assert_eq!(heap.dominators(1).len(), 1);
+
+        let mut reachable = 0;
+        heap.for_each_reachable(|_| reachable += 1);
+        assert_eq!(reachable, 7);
+    }
 }
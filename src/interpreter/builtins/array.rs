@@ -1,7 +1,7 @@
 //! Array built-in methods
 
 use crate::error::JsError;
-use crate::gc::Gc;
+use crate::gc::{Gc, Guard};
 use crate::interpreter::Interpreter;
 use crate::prelude::*;
 use crate::value::{
@@ -20,6 +20,23 @@ fn to_length(n: f64) -> u32 {
     }
 }
 
+/// Validate a value assigned to an array's `length` (ArraySetLength).
+///
+/// The value must convert to a number that is already a uint32; anything
+/// else, such as -1, 1.5 or 2^32, is a RangeError rather than a truncation.
+pub(crate) fn to_array_length(interp: &mut Interpreter, value: &JsValue) -> Result<u32, JsError> {
+    let number = match value {
+        JsValue::Number(n) => *n,
+        _ => interp.coerce_to_number(value)?,
+    };
+    let len = number as u32;
+    if len as f64 != number || number < 0.0 {
+        return Err(JsError::range_error("Invalid array length"));
+    }
+    interp.check_array_len(len as usize)?;
+    Ok(len)
+}
+
 /// Parse a string to a number for ToNumber coercion.
 fn string_to_number(s: &str) -> f64 {
     let s = s.trim();
@@ -37,6 +54,10 @@ fn get_array_like_length(interp: &mut Interpreter, obj: &Gc<JsObject>) -> Result
     if let ExoticObject::Array { ref elements } = borrowed.exotic {
         return Ok(elements.len() as u32);
     }
+    // String wrappers have one element per character
+    if let ExoticObject::StringObj(ref s) = borrowed.exotic {
+        return Ok(s.char_count() as u32);
+    }
     // Otherwise, get the length property and coerce it
     let length_key = PropertyKey::String(JsString::from("length"));
     let length_val = borrowed
@@ -72,12 +93,38 @@ fn get_array_like_element(obj: &Gc<JsObject>, index: u32) -> JsValue {
             .cloned()
             .unwrap_or(JsValue::Undefined);
     }
+    if let ExoticObject::StringObj(ref s) = borrowed.exotic
+        && let Some(c) = s.as_str().chars().nth(index as usize)
+    {
+        return JsValue::String(JsString::from(c.to_string()));
+    }
     // Otherwise, get by property index
     borrowed
         .get_property(&PropertyKey::Index(index))
         .unwrap_or(JsValue::Undefined)
 }
 
+/// CreateListFromArrayLike: the elements of array-like `value`, as the
+/// arguments of `apply`, `Reflect.apply` and `Reflect.construct`. `what`
+/// names the argument in the TypeError for a non-object.
+pub(crate) fn create_list_from_array_like(
+    interp: &mut Interpreter,
+    value: &JsValue,
+    what: &str,
+) -> Result<Vec<JsValue>, JsError> {
+    let JsValue::Object(obj) = value else {
+        return Err(JsError::type_error(format!(
+            "{} must be an array-like object",
+            what
+        )));
+    };
+    let length = get_array_like_length(interp, obj)?;
+    interp.check_argument_count(length as usize)?;
+    Ok((0..length)
+        .map(|index| get_array_like_element(obj, index))
+        .collect())
+}
+
 /// Check if an array-like object has an element at the given index.
 /// Works on both real arrays and array-like objects.
 fn has_array_like_element(obj: &Gc<JsObject>, index: u32) -> bool {
@@ -86,10 +133,34 @@ fn has_array_like_element(obj: &Gc<JsObject>, index: u32) -> bool {
     if let ExoticObject::Array { ref elements } = borrowed.exotic {
         return index < elements.len() as u32;
     }
+    if let ExoticObject::StringObj(ref s) = borrowed.exotic
+        && (index as usize) < s.char_count()
+    {
+        return true;
+    }
     // Otherwise, check property existence
     borrowed.has_own_property(&PropertyKey::Index(index))
 }
 
+/// Root `value` in `guard` in place of whatever the guard held before.
+/// Used for values like a reduce accumulator that are carried across callbacks.
+fn reguard(guard: &Guard<JsObject>, value: &JsValue) {
+    guard.clear();
+    if let JsValue::Object(obj) = value {
+        guard.guard(obj.cheap_clone());
+    }
+}
+
+/// Root every object in a working copy of an array's elements.
+/// The copy must survive callbacks that truncate or overwrite the array itself.
+fn guard_elements(guard: &Guard<JsObject>, elements: &[JsValue]) {
+    for elem in elements {
+        if let JsValue::Object(obj) = elem {
+            guard.guard(obj.cheap_clone());
+        }
+    }
+}
+
 /// Initialize Array.prototype with all array methods.
 /// The prototype object must already exist in `interp.array_prototype`.
 pub fn init_array_prototype(interp: &mut Interpreter) {
@@ -189,6 +260,10 @@ pub fn array_constructor_fn(
         && let Some(JsValue::Number(n)) = args.first()
     {
         let len = *n as u32;
+        if len as f64 != *n {
+            return Err(JsError::range_error("Invalid array length"));
+        }
+        interp.check_array_len(len as usize)?;
         let mut elements = Vec::with_capacity(len as usize);
         for _ in 0..len {
             elements.push(JsValue::Undefined);
@@ -215,8 +290,19 @@ pub fn array_is_array(
     Ok(Guarded::unguarded(JsValue::Boolean(is_array)))
 }
 
+/// Mutating methods throw on a frozen array, as their element writes would
+fn check_not_frozen(arr: &JsObjectRef, method: &str) -> Result<(), JsError> {
+    if arr.borrow().frozen {
+        return Err(JsError::type_error(format!(
+            "Cannot modify frozen array in Array.prototype.{}",
+            method
+        )));
+    }
+    Ok(())
+}
+
 pub fn array_push(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
@@ -225,6 +311,8 @@ pub fn array_push(
             "Array.prototype.push called on non-object",
         ));
     };
+    interp.check_write(&arr, None)?;
+    check_not_frozen(&arr, "push")?;
 
     let mut arr_ref = arr.borrow_mut();
 
@@ -241,7 +329,7 @@ pub fn array_push(
 }
 
 pub fn array_pop(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     this: JsValue,
     _args: &[JsValue],
 ) -> Result<Guarded, JsError> {
@@ -250,6 +338,8 @@ pub fn array_pop(
             "Array.prototype.pop called on non-object",
         ));
     };
+    interp.check_write(&arr, None)?;
+    check_not_frozen(&arr, "pop")?;
 
     let mut arr_ref = arr.borrow_mut();
 
@@ -466,15 +556,20 @@ pub fn array_reduce(
     let (mut accumulator, start_index) = if let Some(initial) = args.get(1) {
         (initial.clone(), 0)
     } else {
-        if length == 0 {
+        // Start from the first element that is present, skipping holes
+        let Some(first) = (0..length).find(|&i| has_array_like_element(&arr, i)) else {
             return Err(JsError::type_error(
                 "Reduce of empty array with no initial value",
             ));
-        }
-        let first = get_array_like_element(&arr, 0);
-        (first, 1)
+        };
+        (get_array_like_element(&arr, first), first + 1)
     };
 
+    // The accumulator may be referenced by nothing but this loop, e.g. when
+    // the callback returns a fresh object or removes the initial element
+    let acc_guard = interp.heap.create_guard();
+    reguard(&acc_guard, &accumulator);
+
     for i in start_index..length {
         if has_array_like_element(&arr, i) {
             let elem = get_array_like_element(&arr, i);
@@ -487,13 +582,12 @@ pub fn array_reduce(
                 JsValue::Undefined,
                 &[accumulator, elem, JsValue::Number(i as f64), this.clone()],
             )?;
+            reguard(&acc_guard, &acc);
             accumulator = acc;
         }
     }
 
-    // Accumulator is a derived value - no guard needed as it's either a primitive
-    // or an object from the array/callback which is already owned
-    Ok(Guarded::unguarded(accumulator))
+    Ok(Guarded::with_guard(accumulator, acc_guard))
 }
 
 pub fn array_find(
@@ -537,8 +631,9 @@ pub fn array_find(
         )?;
 
         if result.to_boolean() {
-            // Element came from array which is owned by caller - no guard needed
-            return Ok(Guarded::unguarded(elem));
+            // The callback may have removed the element from the array
+            let guard = interp.guard_value(&elem);
+            return Ok(Guarded { value: elem, guard });
         }
     }
 
@@ -593,36 +688,64 @@ pub fn array_find_index(
     Ok(Guarded::unguarded(JsValue::Number(-1.0)))
 }
 
+/// ToObject for a search method's receiver, keeping the TypeError that names the method
+fn search_receiver(
+    interp: &mut Interpreter,
+    this: JsValue,
+    method: &str,
+) -> Result<(Gc<JsObject>, Guarded), JsError> {
+    if matches!(this, JsValue::Undefined | JsValue::Null) {
+        return Err(JsError::type_error(format!(
+            "Array.prototype.{} called on null or undefined",
+            method
+        )));
+    }
+    let guarded = interp.to_object(this)?;
+    let JsValue::Object(obj) = &guarded.value else {
+        return Err(JsError::internal_error("ToObject returned a primitive"));
+    };
+    Ok((obj.cheap_clone(), guarded))
+}
+
+/// ToIntegerOrInfinity of a fromIndex argument (missing counts as 0)
+fn from_index_integer(interp: &mut Interpreter, arg: Option<&JsValue>) -> Result<f64, JsError> {
+    let n = match arg {
+        Some(value) => interp.coerce_to_number(value)?,
+        None => 0.0,
+    };
+    Ok(if n.is_nan() { 0.0 } else { math::trunc(n) })
+}
+
+/// First index a forward search of `length` elements starts at, from fromIndex
+fn forward_search_start(from_index: f64, length: u32) -> u32 {
+    if from_index >= 0.0 {
+        from_index.min(length as f64) as u32
+    } else {
+        (length as f64 + from_index).max(0.0) as u32
+    }
+}
+
 pub fn array_index_of(
     interp: &mut Interpreter,
     this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let JsValue::Object(arr) = this else {
-        return Err(JsError::type_error(
-            "Array.prototype.indexOf called on non-object",
-        ));
-    };
-
+    let (arr, _receiver) = search_receiver(interp, this, "indexOf")?;
     let search_element = args.first().cloned().unwrap_or(JsValue::Undefined);
-    let from_index = args.get(1).map(|v| v.to_number() as i64).unwrap_or(0);
 
     // Use array-like length with full ToLength coercion (works on both arrays and array-like objects)
-    let length = get_array_like_length(interp, &arr)? as i64;
-
-    let start = if from_index < 0 {
-        (length + from_index).max(0) as u32
-    } else {
-        from_index.min(length) as u32
-    };
-
-    for i in start..(length as u32) {
-        if has_array_like_element(&arr, i) {
-            let elem = get_array_like_element(&arr, i);
+    let length = get_array_like_length(interp, &arr)?;
+    if length == 0 {
+        return Ok(Guarded::unguarded(JsValue::Number(-1.0)));
+    }
+    let start = forward_search_start(from_index_integer(interp, args.get(1))?, length);
 
-            if elem.strict_equals(&search_element) {
-                return Ok(Guarded::unguarded(JsValue::Number(i as f64)));
-            }
+    // Strict equality, skipping holes: NaN is never found
+    for i in start..length {
+        if has_array_like_element(&arr, i)
+            && get_array_like_element(&arr, i).strict_equals(&search_element)
+        {
+            return Ok(Guarded::unguarded(JsValue::Number(i as f64)));
         }
     }
 
@@ -634,36 +757,20 @@ pub fn array_includes(
     this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let JsValue::Object(arr) = this else {
-        return Err(JsError::type_error(
-            "Array.prototype.includes called on non-object",
-        ));
-    };
-
+    let (arr, _receiver) = search_receiver(interp, this, "includes")?;
     let search_element = args.first().cloned().unwrap_or(JsValue::Undefined);
-    let from_index = args.get(1).map(|v| v.to_number() as i64).unwrap_or(0);
 
     // Use array-like length with full ToLength coercion (works on both arrays and array-like objects)
-    let length = get_array_like_length(interp, &arr)? as i64;
-
-    let start = if from_index < 0 {
-        (length + from_index).max(0) as u32
-    } else {
-        from_index.min(length) as u32
-    };
-
-    for i in start..(length as u32) {
-        if has_array_like_element(&arr, i) {
-            let elem = get_array_like_element(&arr, i);
-
-            let found = match (&elem, &search_element) {
-                (JsValue::Number(a), JsValue::Number(b)) if a.is_nan() && b.is_nan() => true,
-                _ => elem.strict_equals(&search_element),
-            };
+    let length = get_array_like_length(interp, &arr)?;
+    if length == 0 {
+        return Ok(Guarded::unguarded(JsValue::Boolean(false)));
+    }
+    let start = forward_search_start(from_index_integer(interp, args.get(1))?, length);
 
-            if found {
-                return Ok(Guarded::unguarded(JsValue::Boolean(true)));
-            }
+    // SameValueZero, reading holes as undefined
+    for i in start..length {
+        if get_array_like_element(&arr, i).same_value_zero(&search_element) {
+            return Ok(Guarded::unguarded(JsValue::Boolean(true)));
         }
     }
 
@@ -804,50 +911,79 @@ pub fn array_join(
     this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let JsValue::Object(arr) = this else {
+    let JsValue::Object(arr) = this.clone() else {
         return Err(JsError::type_error(
             "Array.prototype.join called on non-object",
         ));
     };
 
     let separator = match args.first() {
-        Some(v) if !matches!(v, JsValue::Undefined) => interp.to_js_string(v).to_string(),
+        Some(v) if !matches!(v, JsValue::Undefined) => interp.coerce_to_string(v)?.to_string(),
         _ => ",".to_string(),
     };
 
-    let length = arr
-        .borrow()
-        .array_length()
-        .ok_or_else(|| JsError::type_error("Not an array"))?;
+    // An array that contains itself, directly or not, joins to "" at the cycle
+    if interp.join_stack.contains(&arr.id()) {
+        return Ok(Guarded::unguarded(JsValue::String(interp.intern(""))));
+    }
+    let _arr_guard = interp.guard_value(&this);
+    let length = get_array_like_length(interp, &arr)?;
+
+    interp.join_stack.push(arr.id());
+    let joined = join_elements(interp, &arr, length, &separator);
+    interp.join_stack.pop();
 
-    let mut parts = Vec::with_capacity(length as usize);
+    Ok(Guarded::unguarded(JsValue::String(JsString::from(joined?))))
+}
+
+/// Stringify elements `0..length` with their own `toString`, reading each one
+/// fresh since an element's `toString` may change the rest of the array
+fn join_elements(
+    interp: &mut Interpreter,
+    arr: &Gc<JsObject>,
+    length: u32,
+    separator: &str,
+) -> Result<String, JsError> {
+    let mut joined = String::new();
     for i in 0..length {
-        let elem = arr
-            .borrow()
-            .get_property(&PropertyKey::Index(i))
-            .unwrap_or(JsValue::Undefined);
+        if i > 0 {
+            joined.push_str(separator);
+        }
+        let elem = get_array_like_element(arr, i);
 
-        let part = match elem {
-            JsValue::Undefined | JsValue::Null => String::new(),
-            _ => interp.to_js_string(&elem).to_string(),
-        };
-        parts.push(part);
+        match elem {
+            JsValue::Undefined | JsValue::Null => {}
+            _ => joined.push_str(interp.coerce_to_string(&elem)?.as_str()),
+        }
+        interp.check_string_len(joined.len())?;
     }
-
-    Ok(Guarded::unguarded(JsValue::String(JsString::from(
-        parts.join(&separator),
-    ))))
+    Ok(joined)
 }
 
 /// Array.prototype.toString()
-/// Returns a string of comma-separated elements (equivalent to join()).
+/// Calls `this.join()`, falling back to Object.prototype.toString when
+/// `join` isn't callable.
 pub fn array_to_string(
     interp: &mut Interpreter,
     this: JsValue,
     _args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    // toString() is equivalent to join() with default separator
-    array_join(interp, this, &[])
+    let JsValue::Object(arr) = &this else {
+        return Err(JsError::type_error(
+            "Array.prototype.toString called on non-object",
+        ));
+    };
+
+    let join_key = PropertyKey::String(interp.intern("join"));
+    let join = arr
+        .borrow()
+        .get_property(&join_key)
+        .unwrap_or(JsValue::Undefined);
+    if join.is_callable() {
+        let _this_guard = interp.guard_value(&this);
+        return interp.call_function(join, this.clone(), &[]);
+    }
+    super::object::object_to_string(interp, this, &[])
 }
 
 pub fn array_every(
@@ -951,7 +1087,7 @@ pub fn array_some(
 }
 
 pub fn array_shift(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     this: JsValue,
     _args: &[JsValue],
 ) -> Result<Guarded, JsError> {
@@ -960,6 +1096,8 @@ pub fn array_shift(
             "Array.prototype.shift called on non-object",
         ));
     };
+    interp.check_write(&arr, None)?;
+    check_not_frozen(&arr, "shift")?;
 
     let mut arr_ref = arr.borrow_mut();
     let elements = arr_ref
@@ -975,7 +1113,7 @@ pub fn array_shift(
 }
 
 pub fn array_unshift(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
@@ -984,6 +1122,8 @@ pub fn array_unshift(
             "Array.prototype.unshift called on non-object",
         ));
     };
+    interp.check_write(&arr, None)?;
+    check_not_frozen(&arr, "unshift")?;
 
     let mut arr_ref = arr.borrow_mut();
     let elements = arr_ref
@@ -1003,7 +1143,7 @@ pub fn array_unshift(
 }
 
 pub fn array_reverse(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     this: JsValue,
     _args: &[JsValue],
 ) -> Result<Guarded, JsError> {
@@ -1012,6 +1152,8 @@ pub fn array_reverse(
             "Array.prototype.reverse called on non-object",
         ));
     };
+    interp.check_write(&arr, None)?;
+    check_not_frozen(&arr, "reverse")?;
 
     let mut arr_ref = arr.borrow_mut();
     let elements = arr_ref
@@ -1035,6 +1177,8 @@ pub fn array_sort(
             "Array.prototype.sort called on non-object",
         ));
     };
+    interp.check_write(&arr, None)?;
+    check_not_frozen(&arr, "sort")?;
 
     let compare_fn = args.first().cloned();
 
@@ -1057,6 +1201,9 @@ pub fn array_sort(
             })
             .collect()
     };
+    // The comparator may shrink or overwrite the array while we sort the copy
+    let elements_guard = interp.heap.create_guard();
+    guard_elements(&elements_guard, &elements);
 
     if let Some(cmp) = compare_fn {
         if cmp.is_callable() {
@@ -1064,6 +1211,7 @@ pub fn array_sort(
                 let limit = elements.len().saturating_sub(1 + i);
                 for j in 0..limit {
                     // j and j+1 are guaranteed in bounds due to limit calculation
+                    interp.check_budget()?;
                     let (left, right) = match (elements.get(j), elements.get(j + 1)) {
                         (Some(l), Some(r)) => (l.clone(), r.clone()),
                         _ => continue,
@@ -1080,14 +1228,15 @@ pub fn array_sort(
         }
     } else {
         // Pre-compute string representations for sorting
-        let mut pairs: Vec<(JsString, JsValue)> = elements
-            .into_iter()
-            .map(|v| {
-                let s = interp.to_js_string(&v);
-                (s, v)
-            })
-            .collect();
-        pairs.sort_by(|(a_str, _), (b_str, _)| a_str.as_str().cmp(b_str.as_str()));
+        let mut pairs: Vec<(JsString, JsValue)> = Vec::with_capacity(elements.len());
+        for v in elements {
+            interp.check_budget()?;
+            let s = interp.to_js_string(&v);
+            pairs.push((s, v));
+        }
+        pairs.sort_by(|(a_str, _), (b_str, _)| {
+            crate::value::compare_code_units(a_str.as_str(), b_str.as_str())
+        });
         elements = pairs.into_iter().map(|(_, v)| v).collect();
     }
 
@@ -1105,7 +1254,7 @@ pub fn array_sort(
 }
 
 pub fn array_fill(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
@@ -1114,6 +1263,8 @@ pub fn array_fill(
             "Array.prototype.fill called on non-object",
         ));
     };
+    interp.check_write(&arr, None)?;
+    check_not_frozen(&arr, "fill")?;
 
     let value = args.first().cloned().unwrap_or(JsValue::Undefined);
 
@@ -1121,6 +1272,7 @@ pub fn array_fill(
     let elements = arr_ref
         .array_elements_mut()
         .ok_or_else(|| JsError::type_error("Array.prototype.fill called on non-array"))?;
+    interp.check_array_len(elements.len())?;
     let length = elements.len() as i64;
 
     let start = args
@@ -1159,7 +1311,7 @@ pub fn array_fill(
 }
 
 pub fn array_copy_within(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
@@ -1168,6 +1320,8 @@ pub fn array_copy_within(
             "Array.prototype.copyWithin called on non-object",
         ));
     };
+    interp.check_write(&arr, None)?;
+    check_not_frozen(&arr, "copyWithin")?;
 
     let mut arr_ref = arr.borrow_mut();
     let elements = arr_ref
@@ -1236,6 +1390,8 @@ pub fn array_splice(
             "Array.prototype.splice called on non-object",
         ));
     };
+    interp.check_write(&arr, None)?;
+    check_not_frozen(&arr, "splice")?;
 
     let mut arr_ref = arr.borrow_mut();
     let elements = arr_ref
@@ -1295,12 +1451,17 @@ pub fn array_from(
 ) -> Result<Guarded, JsError> {
     let source = args.first().cloned().unwrap_or(JsValue::Undefined);
     let map_fn = args.get(1).cloned();
+    let this_arg = args.get(2).cloned().unwrap_or(JsValue::Undefined);
 
     // Guard the source and map_fn to prevent GC from collecting them
     // during the loop iterations which may trigger allocations.
     let _source_guard = interp.guard_value(&source);
     let _map_fn_guard = map_fn.as_ref().and_then(|m| interp.guard_value(m));
+    let _this_arg_guard = interp.guard_value(&this_arg);
 
+    // Collected values are referenced by nothing else until the result array
+    // exists, so each one is rooted here as it is pushed
+    let guard = interp.heap.create_guard();
     let mut elements = Vec::new();
 
     match source {
@@ -1308,12 +1469,17 @@ pub fn array_from(
             // First check if object is an array (fast path)
             let is_array = obj.borrow().array_elements().is_some();
             if is_array {
-                let source_elements: Vec<JsValue> = obj
-                    .borrow()
-                    .array_elements()
-                    .map(|e| e.to_vec())
-                    .unwrap_or_default();
-                for (i, elem) in source_elements.into_iter().enumerate() {
+                // Read each element fresh, as the array iterator would, so
+                // changes made by the map function are observed
+                let mut i = 0usize;
+                loop {
+                    let next = obj
+                        .borrow()
+                        .array_elements()
+                        .and_then(|e| e.get(i).cloned());
+                    let Some(elem) = next else {
+                        break;
+                    };
                     let mapped = if let Some(ref map) = map_fn {
                         if map.is_callable() {
                             let Guarded {
@@ -1321,7 +1487,7 @@ pub fn array_from(
                                 guard: _mapped_guard,
                             } = interp.call_function(
                                 map.clone(),
-                                JsValue::Undefined,
+                                this_arg.clone(),
                                 &[elem, JsValue::Number(i as f64)],
                             )?;
                             mapped_val
@@ -1331,7 +1497,11 @@ pub fn array_from(
                     } else {
                         elem
                     };
+                    if let JsValue::Object(obj) = &mapped {
+                        guard.guard(obj.cheap_clone());
+                    }
                     elements.push(mapped);
+                    i += 1;
                 }
             } else {
                 // Check for Symbol.iterator (handles Map, Set, and other iterables)
@@ -1398,7 +1568,7 @@ pub fn array_from(
                                                 guard: _mapped_guard,
                                             } = interp.call_function(
                                                 map.clone(),
-                                                JsValue::Undefined,
+                                                this_arg.clone(),
                                                 &[elem, JsValue::Number(i as f64)],
                                             )?;
                                             mapped_val
@@ -1408,6 +1578,9 @@ pub fn array_from(
                                     } else {
                                         elem
                                     };
+                                    if let JsValue::Object(obj) = &mapped {
+                                        guard.guard(obj.cheap_clone());
+                                    }
                                     elements.push(mapped);
                                     i += 1;
                                 } else {
@@ -1416,8 +1589,40 @@ pub fn array_from(
                             }
                         }
                     }
+                } else {
+                    // Array-like: read indices up to `length`
+                    let length_key = interp.property_key("length");
+                    let length = obj
+                        .borrow()
+                        .get_property(&length_key)
+                        .map_or(0, |v| to_length(v.to_number()));
+                    interp.check_array_len(length as usize)?;
+                    for i in 0..length {
+                        interp.check_budget()?;
+                        let elem = obj
+                            .borrow()
+                            .get_property(&PropertyKey::Index(i))
+                            .unwrap_or(JsValue::Undefined);
+                        let mapped = match &map_fn {
+                            Some(map) if map.is_callable() => {
+                                let Guarded {
+                                    value: mapped_val,
+                                    guard: _mapped_guard,
+                                } = interp.call_function(
+                                    map.clone(),
+                                    this_arg.clone(),
+                                    &[elem, JsValue::Number(i as f64)],
+                                )?;
+                                mapped_val
+                            }
+                            _ => elem,
+                        };
+                        if let JsValue::Object(obj) = &mapped {
+                            guard.guard(obj.cheap_clone());
+                        }
+                        elements.push(mapped);
+                    }
                 }
-                // If no iterator, elements remains empty (array-like objects would need length property)
             }
         }
         JsValue::String(s) => {
@@ -1430,7 +1635,7 @@ pub fn array_from(
                             guard: _mapped_guard,
                         } = interp.call_function(
                             map.clone(),
-                            JsValue::Undefined,
+                            this_arg.clone(),
                             &[elem, JsValue::Number(i as f64)],
                         )?;
                         mapped_val
@@ -1440,13 +1645,15 @@ pub fn array_from(
                 } else {
                     elem
                 };
+                if let JsValue::Object(obj) = &mapped {
+                    guard.guard(obj.cheap_clone());
+                }
                 elements.push(mapped);
             }
         }
         _ => {}
     }
 
-    let guard = interp.heap.create_guard();
     let arr = interp.create_array_from(&guard, elements);
     Ok(Guarded::with_guard(JsValue::Object(arr), guard))
 }
@@ -1488,44 +1695,36 @@ pub fn array_at(
 }
 
 pub fn array_last_index_of(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let JsValue::Object(arr) = this else {
-        return Err(JsError::type_error(
-            "Array.prototype.lastIndexOf called on non-object",
-        ));
-    };
-
+    let (arr, _receiver) = search_receiver(interp, this, "lastIndexOf")?;
     let search_elem = args.first().cloned().unwrap_or(JsValue::Undefined);
 
-    let arr_ref = arr.borrow();
-    let length = arr_ref
-        .array_length()
-        .ok_or_else(|| JsError::type_error("Not an array"))?;
-
-    let from_index = args
-        .get(1)
-        .map(|v| {
-            let n = v.to_number() as i64;
-            if n < 0 {
-                (length as i64 + n).max(-1)
-            } else {
-                n.min(length as i64 - 1)
-            }
-        })
-        .unwrap_or(length as i64 - 1);
+    let length = get_array_like_length(interp, &arr)?;
+    if length == 0 {
+        return Ok(Guarded::unguarded(JsValue::Number(-1.0)));
+    }
 
-    if from_index < 0 {
+    // The search runs backwards from fromIndex, which defaults to the last element
+    let from_index = match args.get(1) {
+        Some(_) => from_index_integer(interp, args.get(1))?,
+        None => f64::from(length - 1),
+    };
+    let start = if from_index >= 0.0 {
+        from_index.min(f64::from(length - 1))
+    } else {
+        f64::from(length) + from_index
+    };
+    if start < 0.0 {
         return Ok(Guarded::unguarded(JsValue::Number(-1.0)));
     }
 
-    for i in (0..=from_index as u32).rev() {
-        let elem = arr_ref
-            .get_property(&PropertyKey::Index(i))
-            .unwrap_or(JsValue::Undefined);
-        if elem.strict_equals(&search_elem) {
+    for i in (0..=start as u32).rev() {
+        if has_array_like_element(&arr, i)
+            && get_array_like_element(&arr, i).strict_equals(&search_elem)
+        {
             return Ok(Guarded::unguarded(JsValue::Number(i as f64)));
         }
     }
@@ -1551,50 +1750,45 @@ pub fn array_reduce_right(
         ));
     }
 
-    let length = arr
-        .borrow()
-        .array_length()
-        .ok_or_else(|| JsError::type_error("Not an array"))?;
+    // Guard values to prevent GC from collecting them during iterations
+    let _callback_guard = interp.guard_value(&callback);
+    let _arr_guard = interp.guard_value(&this);
 
-    if length == 0 && args.get(1).is_none() {
-        return Err(JsError::type_error(
-            "Reduce of empty array with no initial value",
-        ));
-    }
+    let length = get_array_like_length(interp, &arr)?;
 
-    let (mut accumulator, start_index) = if let Some(initial) = args.get(1) {
-        (initial.clone(), length as i64 - 1)
+    let (mut accumulator, end) = if let Some(initial) = args.get(1) {
+        (initial.clone(), length)
     } else {
-        let elem = arr
-            .borrow()
-            .get_property(&PropertyKey::Index(length - 1))
-            .unwrap_or(JsValue::Undefined);
-        (elem, length as i64 - 2)
+        // Start from the last element that is present, skipping holes
+        let Some(last) = (0..length).rev().find(|&i| has_array_like_element(&arr, i)) else {
+            return Err(JsError::type_error(
+                "Reduce of empty array with no initial value",
+            ));
+        };
+        (get_array_like_element(&arr, last), last)
     };
 
-    for i in (0..=start_index).rev() {
-        let elem = arr
-            .borrow()
-            .get_property(&PropertyKey::Index(i as u32))
-            .unwrap_or(JsValue::Undefined);
-        let Guarded {
-            value: result,
-            guard: _result_guard,
-        } = interp.call_function(
-            callback.clone(),
-            JsValue::Undefined,
-            &[
-                accumulator.clone(),
-                elem,
-                JsValue::Number(i as f64),
-                this.clone(),
-            ],
-        )?;
-        accumulator = result;
+    let acc_guard = interp.heap.create_guard();
+    reguard(&acc_guard, &accumulator);
+
+    for i in (0..end).rev() {
+        if has_array_like_element(&arr, i) {
+            let elem = get_array_like_element(&arr, i);
+
+            let Guarded {
+                value: acc,
+                guard: _acc_guard,
+            } = interp.call_function(
+                callback.clone(),
+                JsValue::Undefined,
+                &[accumulator, elem, JsValue::Number(i as f64), this.clone()],
+            )?;
+            reguard(&acc_guard, &acc);
+            accumulator = acc;
+        }
     }
 
-    // Accumulator is a derived value - no guard needed
-    Ok(Guarded::unguarded(accumulator))
+    Ok(Guarded::with_guard(accumulator, acc_guard))
 }
 
 pub fn array_flat(
@@ -1610,13 +1804,19 @@ pub fn array_flat(
 
     let depth = args.first().map(|v| v.to_number() as i32).unwrap_or(1);
 
-    fn flatten(arr: &JsObjectRef, depth: i32) -> Vec<JsValue> {
+    fn flatten(
+        interp: &Interpreter,
+        arr: &JsObjectRef,
+        depth: i32,
+    ) -> Result<Vec<JsValue>, JsError> {
+        // Each nesting level recurses on the Rust stack
+        let _depth = interp.enter_native()?;
         let elements: Vec<JsValue> = {
             let arr_ref = arr.borrow();
             if let Some(elements) = arr_ref.array_elements() {
                 elements.to_vec()
             } else {
-                return vec![];
+                return Ok(vec![]);
             }
         };
 
@@ -1626,15 +1826,15 @@ pub fn array_flat(
                 && let JsValue::Object(ref inner) = elem
                 && inner.borrow().is_array()
             {
-                result.extend(flatten(inner, depth - 1));
+                result.extend(flatten(interp, inner, depth - 1)?);
                 continue;
             }
             result.push(elem);
         }
-        result
+        Ok(result)
     }
 
-    let elements = flatten(&arr, depth);
+    let elements = flatten(interp, &arr, depth)?;
     let guard = interp.heap.create_guard();
     let arr = interp.create_array_from(&guard, elements);
     Ok(Guarded::with_guard(JsValue::Object(arr), guard))
@@ -1680,10 +1880,10 @@ pub fn array_flat_map(
     let mut result = Vec::new();
 
     for i in 0..length {
-        let elem = arr
-            .borrow()
-            .get_property(&PropertyKey::Index(i))
-            .unwrap_or(JsValue::Undefined);
+        if !has_array_like_element(&arr, i) {
+            continue;
+        }
+        let elem = get_array_like_element(&arr, i);
 
         let Guarded {
             value: mapped,
@@ -1746,16 +1946,15 @@ pub fn array_find_last(
 
     let this_arg = args.get(1).cloned().unwrap_or(JsValue::Undefined);
 
-    let length = arr
-        .borrow()
-        .array_length()
-        .ok_or_else(|| JsError::type_error("Not an array"))?;
+    // Guard values to prevent GC from collecting them during iterations
+    let _callback_guard = interp.guard_value(&callback);
+    let _this_arg_guard = interp.guard_value(&this_arg);
+    let _arr_guard = interp.guard_value(&this);
+
+    let length = get_array_like_length(interp, &arr)?;
 
     for i in (0..length).rev() {
-        let elem = arr
-            .borrow()
-            .get_property(&PropertyKey::Index(i))
-            .unwrap_or(JsValue::Undefined);
+        let elem = get_array_like_element(&arr, i);
 
         let Guarded {
             value: result,
@@ -1767,8 +1966,9 @@ pub fn array_find_last(
         )?;
 
         if result.to_boolean() {
-            // Element came from array which is owned by caller - no guard needed
-            return Ok(Guarded::unguarded(elem));
+            // The callback may have removed the element from the array
+            let guard = interp.guard_value(&elem);
+            return Ok(Guarded { value: elem, guard });
         }
     }
 
@@ -1795,16 +1995,15 @@ pub fn array_find_last_index(
 
     let this_arg = args.get(1).cloned().unwrap_or(JsValue::Undefined);
 
-    let length = arr
-        .borrow()
-        .array_length()
-        .ok_or_else(|| JsError::type_error("Not an array"))?;
+    // Guard values to prevent GC from collecting them during iterations
+    let _callback_guard = interp.guard_value(&callback);
+    let _this_arg_guard = interp.guard_value(&this_arg);
+    let _arr_guard = interp.guard_value(&this);
+
+    let length = get_array_like_length(interp, &arr)?;
 
     for i in (0..length).rev() {
-        let elem = arr
-            .borrow()
-            .get_property(&PropertyKey::Index(i))
-            .unwrap_or(JsValue::Undefined);
+        let elem = get_array_like_element(&arr, i);
 
         let Guarded {
             value: result,
@@ -1866,6 +2065,10 @@ pub fn array_to_sorted(
 
     let comparator = args.first().cloned();
 
+    // Guard the comparator and array to prevent GC from collecting them
+    let _cmp_guard = comparator.as_ref().and_then(|c| interp.guard_value(c));
+    let _arr_guard = interp.guard_value(&this);
+
     let length = arr
         .borrow()
         .array_length()
@@ -1878,6 +2081,9 @@ pub fn array_to_sorted(
                 .unwrap_or(JsValue::Undefined)
         })
         .collect();
+    // The comparator may shrink or overwrite the source array while we sort
+    let elements_guard = interp.heap.create_guard();
+    guard_elements(&elements_guard, &elements);
 
     if let Some(ref cmp_fn) = comparator {
         if cmp_fn.is_callable() {
@@ -1887,6 +2093,7 @@ pub fn array_to_sorted(
                 let mut j = i;
                 while j > 0 {
                     // j > 0 guarantees j-1 is valid, and j < elements.len() from outer loop
+                    interp.check_budget()?;
                     let (left, right) = match (elements.get(j - 1), elements.get(j)) {
                         (Some(l), Some(r)) => (l.clone(), r.clone()),
                         _ => break,
@@ -1908,14 +2115,15 @@ pub fn array_to_sorted(
         }
     } else {
         // Pre-compute string representations for sorting
-        let mut pairs: Vec<(JsString, JsValue)> = elements
-            .into_iter()
-            .map(|v| {
-                let s = interp.to_js_string(&v);
-                (s, v)
-            })
-            .collect();
-        pairs.sort_by(|(a_str, _), (b_str, _)| a_str.as_str().cmp(b_str.as_str()));
+        let mut pairs: Vec<(JsString, JsValue)> = Vec::with_capacity(elements.len());
+        for v in elements {
+            interp.check_budget()?;
+            let s = interp.to_js_string(&v);
+            pairs.push((s, v));
+        }
+        pairs.sort_by(|(a_str, _), (b_str, _)| {
+            crate::value::compare_code_units(a_str.as_str(), b_str.as_str())
+        });
         elements = pairs.into_iter().map(|(_, v)| v).collect();
     }
 
@@ -2061,6 +2269,7 @@ pub fn array_values(
     let guard = interp.heap.create_guard();
     guard.guard(arr.cheap_clone());
     let iter_obj = interp.create_object_raw(&guard);
+    iter_obj.borrow_mut().prototype = Some(interp.iterator_prototype.cheap_clone());
 
     // Store the array and current index on the iterator
     let array_key = interp.property_key("__array__");
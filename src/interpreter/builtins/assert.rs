@@ -0,0 +1,430 @@
+//! tsrun:assert built-in module
+//!
+//! Provides `assert(cond, message)` and declarative shape validation:
+//!
+//! ```typescript
+//! import { assert, assertShape, checkShape } from "tsrun:assert";
+//!
+//! assertShape(config, {
+//!     type: "object",
+//!     required: ["name"],
+//!     properties: {
+//!         name: { type: "string", pattern: "^[a-z-]+$" },
+//!         replicas: { type: "number", min: 1 },
+//!     },
+//! });
+//! ```
+//!
+//! Supported shape keywords: `type` (`"string"`, `"number"`, `"integer"`,
+//! `"boolean"`, `"object"`, `"array"`, `"null"`, `"any"`), `required`,
+//! `properties`, `additionalProperties: false`, `items`, `enum`, `pattern`,
+//! `minLength`/`maxLength`, `min`/`max` and `minItems`/`maxItems`.
+//!
+//! The walker uses an explicit work list, so deeply nested documents don't
+//! recurse on the Rust stack. Patterns are compiled through the interpreter's
+//! `RegExpProvider`.
+
+use crate::InternalModule;
+use crate::error::JsError;
+use crate::gc::{Gc, Guard};
+use crate::interpreter::Interpreter;
+use crate::interpreter::builtins::error::create_error_object;
+use crate::platform::CompiledRegex;
+use crate::prelude::{FxHashMap, Rc, String, ToString, Vec, format, math};
+use crate::value::{ExoticObject, Guarded, JsObject, JsString, JsValue, PropertyKey};
+
+/// Create the tsrun:assert module
+pub fn create_assert_internal_module() -> InternalModule {
+    InternalModule::native("tsrun:assert")
+        .with_function("assert", assert_fn, 2)
+        .with_function("assertShape", assert_shape_fn, 2)
+        .with_function("checkShape", check_shape_fn, 2)
+        .build()
+}
+
+/// A single failed check, e.g. `$.replicas: expected at least 1, got 0`
+struct Violation {
+    path: String,
+    message: String,
+}
+
+/// assert(cond, message?) - throws an Error with `message` when `cond` is falsy
+fn assert_fn(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let cond = args.first().cloned().unwrap_or(JsValue::Undefined);
+    if cond.to_boolean() {
+        return Ok(Guarded::unguarded(JsValue::Undefined));
+    }
+    let message = match args.get(1) {
+        Some(JsValue::Undefined) | None => "Assertion failed".to_string(),
+        Some(v) => interp.to_js_string(v).to_string(),
+    };
+    Err(plain_error(message))
+}
+
+/// assertShape(value, shape) - throws one Error listing every violation
+fn assert_shape_fn(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let value = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let shape = args.get(1).cloned().unwrap_or(JsValue::Undefined);
+    let violations = validate_shape(interp, value, &shape)?;
+    if violations.is_empty() {
+        return Ok(Guarded::unguarded(JsValue::Undefined));
+    }
+
+    let lines: Vec<String> = violations
+        .iter()
+        .map(|v| format!("  {}: {}", v.path, v.message))
+        .collect();
+    let message = format!(
+        "Shape validation failed with {} violation(s):\n{}",
+        violations.len(),
+        lines.join("\n")
+    );
+
+    // Attach the structured list so callers can inspect it from a catch block
+    let (error, guard) = create_error_object(interp, &plain_error(message));
+    let guard = guard.unwrap_or_else(|| interp.heap.create_guard());
+    if let JsValue::Object(error_obj) = &error {
+        let list = violations_to_array(interp, &guard, violations);
+        let key = PropertyKey::String(interp.intern("violations"));
+        error_obj
+            .borrow_mut()
+            .set_property(key, JsValue::Object(list));
+    }
+    Err(JsError::thrown(Guarded::with_guard(error, guard)))
+}
+
+/// checkShape(value, shape) - returns `{ path, message }[]` (empty when valid)
+fn check_shape_fn(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let value = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let shape = args.get(1).cloned().unwrap_or(JsValue::Undefined);
+    let violations = validate_shape(interp, value, &shape)?;
+    let guard = interp.heap.create_guard();
+    let list = violations_to_array(interp, &guard, violations);
+    Ok(Guarded::with_guard(JsValue::Object(list), guard))
+}
+
+fn plain_error(message: String) -> JsError {
+    JsError::RuntimeError {
+        kind: "Error".to_string(),
+        message,
+        stack: Vec::new(),
+        properties: None,
+        flight_log: None,
+    }
+}
+
+fn violations_to_array(
+    interp: &mut Interpreter,
+    guard: &Guard<JsObject>,
+    violations: Vec<Violation>,
+) -> Gc<JsObject> {
+    let path_key = PropertyKey::String(interp.intern("path"));
+    let message_key = PropertyKey::String(interp.intern("message"));
+    let mut elements = Vec::with_capacity(violations.len());
+    for violation in violations {
+        let entry = interp.create_object(guard);
+        {
+            let mut entry_ref = entry.borrow_mut();
+            entry_ref.set_property(
+                path_key.clone(),
+                JsValue::String(JsString::from(violation.path)),
+            );
+            entry_ref.set_property(
+                message_key.clone(),
+                JsValue::String(JsString::from(violation.message)),
+            );
+        }
+        elements.push(JsValue::Object(entry));
+    }
+    interp.create_array_from(guard, elements)
+}
+
+/// Walk `value` against `shape`, collecting every violation
+fn validate_shape(
+    interp: &mut Interpreter,
+    value: JsValue,
+    shape: &JsValue,
+) -> Result<Vec<Violation>, JsError> {
+    let JsValue::Object(shape) = shape else {
+        return Err(JsError::type_error("assertShape: shape must be an object"));
+    };
+
+    let mut violations = Vec::new();
+    let mut patterns: FxHashMap<String, Rc<dyn CompiledRegex>> = FxHashMap::default();
+    let mut work: Vec<(JsValue, Gc<JsObject>, String)> = Vec::new();
+    work.push((value, shape.clone(), "$".to_string()));
+
+    while let Some((value, shape, path)) = work.pop() {
+        let mut fail = |message: String| {
+            violations.push(Violation {
+                path: path.clone(),
+                message,
+            });
+        };
+
+        if let Some(expected) = shape_string(interp, &shape, "type")?
+            && !matches_type(&value, &expected)?
+        {
+            fail(format!(
+                "expected {}, got {}",
+                expected,
+                describe_type(&value)
+            ));
+            // Nested checks would only repeat the type mismatch
+            continue;
+        }
+
+        if let Some(allowed) = shape_get(interp, &shape, "enum") {
+            let options = array_values(&allowed)
+                .ok_or_else(|| JsError::type_error("assertShape: enum must be an array"))?;
+            if !options.iter().any(|option| option.strict_equals(&value)) {
+                let listed: Vec<String> = options.iter().map(describe_value).collect();
+                fail(format!(
+                    "expected one of [{}], got {}",
+                    listed.join(", "),
+                    describe_value(&value)
+                ));
+            }
+        }
+
+        match &value {
+            JsValue::String(s) => {
+                let len = s.as_str().chars().count();
+                if let Some(min) = shape_number(interp, &shape, "minLength")?
+                    && (len as f64) < min
+                {
+                    fail(format!("expected length at least {}, got {}", min, len));
+                }
+                if let Some(max) = shape_number(interp, &shape, "maxLength")?
+                    && (len as f64) > max
+                {
+                    fail(format!("expected length at most {}, got {}", max, len));
+                }
+                if let Some(pattern) = shape_string(interp, &shape, "pattern")? {
+                    let regex = match patterns.get(&pattern) {
+                        Some(regex) => regex.clone(),
+                        None => {
+                            let regex = interp.compile_regexp(&pattern, "")?;
+                            patterns.insert(pattern.clone(), regex.clone());
+                            regex
+                        }
+                    };
+                    let matched = regex
+                        .is_match(s.as_str())
+                        .map_err(|e| JsError::type_error(format!("assertShape: {}", e)))?;
+                    if !matched {
+                        fail(format!("expected string matching /{}/", pattern));
+                    }
+                }
+            }
+            JsValue::Number(n) => {
+                if let Some(min) = shape_number(interp, &shape, "min")?
+                    && *n < min
+                {
+                    fail(format!("expected at least {}, got {}", min, n));
+                }
+                if let Some(max) = shape_number(interp, &shape, "max")?
+                    && *n > max
+                {
+                    fail(format!("expected at most {}, got {}", max, n));
+                }
+            }
+            JsValue::Object(obj) => {
+                if let Some(elements) = array_values(&value) {
+                    if let Some(min) = shape_number(interp, &shape, "minItems")?
+                        && (elements.len() as f64) < min
+                    {
+                        fail(format!(
+                            "expected at least {} items, got {}",
+                            min,
+                            elements.len()
+                        ));
+                    }
+                    if let Some(max) = shape_number(interp, &shape, "maxItems")?
+                        && (elements.len() as f64) > max
+                    {
+                        fail(format!(
+                            "expected at most {} items, got {}",
+                            max,
+                            elements.len()
+                        ));
+                    }
+                    if let Some(items) = shape_object(interp, &shape, "items")? {
+                        // Pushed in reverse so violations come out in document order
+                        for (i, element) in elements.into_iter().enumerate().rev() {
+                            work.push((element, items.clone(), format!("{}[{}]", path, i)));
+                        }
+                    }
+                    continue;
+                }
+
+                let mut nested = Vec::new();
+                if let Some(required) = shape_get(interp, &shape, "required") {
+                    let names = array_values(&required).ok_or_else(|| {
+                        JsError::type_error("assertShape: required must be an array")
+                    })?;
+                    for name in names {
+                        let name = interp.to_js_string(&name);
+                        let key = PropertyKey::from_value(&JsValue::String(name.clone()));
+                        if obj.borrow().get_property(&key).is_none() {
+                            fail(format!("missing required property '{}'", name));
+                        }
+                    }
+                }
+
+                let properties = shape_object(interp, &shape, "properties")?;
+                if let Some(properties) = &properties {
+                    let keys = properties.borrow().own_keys();
+                    for key in keys {
+                        let Some(JsValue::Object(prop_shape)) =
+                            properties.borrow().get_property(&key)
+                        else {
+                            continue;
+                        };
+                        // Absent properties are only an error when listed in `required`
+                        if let Some(prop_value) = obj.borrow().get_property(&key) {
+                            nested.push((prop_value, prop_shape, format!("{}.{}", path, key)));
+                        }
+                    }
+                }
+
+                if let Some(JsValue::Boolean(false)) =
+                    shape_get(interp, &shape, "additionalProperties")
+                {
+                    let keys = obj.borrow().own_keys();
+                    for key in keys {
+                        let declared = properties
+                            .as_ref()
+                            .is_some_and(|p| p.borrow().properties.contains_key(&key));
+                        if !declared && !matches!(key, PropertyKey::Symbol(_)) {
+                            fail(format!("unexpected property '{}'", key));
+                        }
+                    }
+                }
+
+                work.extend(nested.into_iter().rev());
+            }
+            _ => {}
+        }
+    }
+
+    Ok(violations)
+}
+
+fn shape_get(interp: &mut Interpreter, shape: &Gc<JsObject>, name: &str) -> Option<JsValue> {
+    let key = PropertyKey::String(interp.intern(name));
+    shape
+        .borrow()
+        .get_property(&key)
+        .filter(|v| !matches!(v, JsValue::Undefined))
+}
+
+fn shape_string(
+    interp: &mut Interpreter,
+    shape: &Gc<JsObject>,
+    name: &str,
+) -> Result<Option<String>, JsError> {
+    match shape_get(interp, shape, name) {
+        None => Ok(None),
+        Some(JsValue::String(s)) => Ok(Some(s.to_string())),
+        Some(_) => Err(JsError::type_error(format!(
+            "assertShape: '{}' must be a string",
+            name
+        ))),
+    }
+}
+
+fn shape_number(
+    interp: &mut Interpreter,
+    shape: &Gc<JsObject>,
+    name: &str,
+) -> Result<Option<f64>, JsError> {
+    match shape_get(interp, shape, name) {
+        None => Ok(None),
+        Some(JsValue::Number(n)) => Ok(Some(n)),
+        Some(_) => Err(JsError::type_error(format!(
+            "assertShape: '{}' must be a number",
+            name
+        ))),
+    }
+}
+
+fn shape_object(
+    interp: &mut Interpreter,
+    shape: &Gc<JsObject>,
+    name: &str,
+) -> Result<Option<Gc<JsObject>>, JsError> {
+    match shape_get(interp, shape, name) {
+        None => Ok(None),
+        Some(JsValue::Object(obj)) => Ok(Some(obj)),
+        Some(_) => Err(JsError::type_error(format!(
+            "assertShape: '{}' must be an object",
+            name
+        ))),
+    }
+}
+
+/// Elements of `value` if it is an array
+fn array_values(value: &JsValue) -> Option<Vec<JsValue>> {
+    match value {
+        JsValue::Object(obj) => obj.borrow().array_elements().map(|e| e.to_vec()),
+        _ => None,
+    }
+}
+
+fn matches_type(value: &JsValue, expected: &str) -> Result<bool, JsError> {
+    Ok(match expected {
+        "any" => true,
+        "string" => matches!(value, JsValue::String(_)),
+        "number" => matches!(value, JsValue::Number(n) if !n.is_nan()),
+        "integer" => matches!(value, JsValue::Number(n) if n.is_finite() && math::trunc(*n) == *n),
+        "boolean" => matches!(value, JsValue::Boolean(_)),
+        "null" => matches!(value, JsValue::Null),
+        "array" => array_values(value).is_some(),
+        "object" => match value {
+            JsValue::Object(obj) => !matches!(
+                obj.borrow().exotic,
+                ExoticObject::Array { .. } | ExoticObject::Function(_)
+            ),
+            _ => false,
+        },
+        other => {
+            return Err(JsError::type_error(format!(
+                "assertShape: unknown type '{}'",
+                other
+            )));
+        }
+    })
+}
+
+fn describe_type(value: &JsValue) -> &'static str {
+    match value {
+        JsValue::Object(obj) => match obj.borrow().exotic {
+            ExoticObject::Array { .. } => "array",
+            ExoticObject::Function(_) => "function",
+            _ => "object",
+        },
+        JsValue::Number(n) if n.is_nan() => "NaN",
+        other => other.type_name(),
+    }
+}
+
+fn describe_value(value: &JsValue) -> String {
+    match value {
+        JsValue::String(s) => format!("\"{}\"", s),
+        JsValue::Object(_) => describe_type(value).to_string(),
+        other => other.to_js_string().to_string(),
+    }
+}
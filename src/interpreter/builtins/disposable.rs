@@ -0,0 +1,515 @@
+//! DisposableStack and AsyncDisposableStack built-ins
+//!
+//! A stack holds resources registered with `use`, `adopt` and `defer` and
+//! disposes them last-in first-out. Every resource is disposed even when an
+//! earlier one throws: each later error wraps the one before it in a
+//! SuppressedError, and the outermost error is thrown once the stack is
+//! empty.
+//!
+//! Resources are kept as functions disposing them, bound to their receiver
+//! and argument. `disposeAsync` is a script function built from
+//! [`DISPOSE_ASYNC_SOURCE`] around native helpers: the VM can only suspend
+//! calls made from bytecode, and a dispose method awaiting an order has to
+//! suspend the run like any other await.
+
+use crate::WellKnownSymbol;
+use crate::compiler::Compiler;
+use crate::error::JsError;
+use crate::gc::{Gc, Guard};
+use crate::interpreter::Interpreter;
+use crate::interpreter::env_scope::EnvScope;
+use crate::prelude::{Box, Rc, RefCell, Vec, format};
+use crate::value::{
+    BoundFunctionData, CheapClone, ExoticObject, Guarded, JsFunction, JsObject, JsValue,
+    PropertyKey,
+};
+
+use super::error::{create_error_object, create_suppressed_error};
+
+/// Builds `AsyncDisposableStack.prototype.disposeAsync` from the helpers
+/// `start`, `next`, `fail` and `finish` defined below
+const DISPOSE_ASYNC_SOURCE: &str = r#"
+(function (start, next, fail, finish) {
+    return async function disposeAsync() {
+        if (!start(this)) return;
+        for (let dispose = next(this); dispose !== undefined; dispose = next(this)) {
+            try {
+                await dispose();
+            } catch (error) {
+                fail(this, error);
+            }
+        }
+        finish(this);
+    };
+})
+"#;
+
+/// State of a DisposableStack or AsyncDisposableStack
+#[derive(Debug)]
+pub struct DisposableStackData {
+    /// Whether this is an AsyncDisposableStack
+    pub is_async: bool,
+    pub disposed: bool,
+    /// Functions disposing the resources left, in registration order
+    pub resources: Vec<JsValue>,
+    /// The error the disposal in progress will throw
+    pub pending_error: Option<JsValue>,
+}
+
+/// Create DisposableStack and AsyncDisposableStack and register them globally
+pub fn init_disposable(interp: &mut Interpreter) {
+    let dispose_key = well_known_key(interp, WellKnownSymbol::Dispose);
+    let async_dispose_key = well_known_key(interp, WellKnownSymbol::AsyncDispose);
+
+    let proto = interp.disposable_stack_prototype.clone();
+    interp.register_accessor(&proto, "disposed", disposable_stack_disposed, None);
+    interp.register_method(&proto, "use", disposable_stack_use, 1);
+    interp.register_method(&proto, "adopt", disposable_stack_adopt, 2);
+    interp.register_method(&proto, "defer", disposable_stack_defer, 1);
+    interp.register_method(&proto, "move", disposable_stack_move, 0);
+    interp.register_method(&proto, "dispose", disposable_stack_dispose, 0);
+    // DisposableStack.prototype[Symbol.dispose] is the same function as dispose
+    let dispose_fn = proto
+        .borrow()
+        .get_property(&PropertyKey::String(interp.intern("dispose")));
+    if let Some(dispose_fn) = dispose_fn {
+        proto.borrow_mut().set_property(dispose_key, dispose_fn);
+    }
+    super::weakref::set_to_string_tag(interp, &proto, "DisposableStack");
+    let constructor =
+        interp.create_native_function("DisposableStack", disposable_stack_constructor, 0);
+    interp.root_guard.guard(constructor.clone());
+    super::weakref::install_constructor(interp, "DisposableStack", constructor, proto);
+
+    let proto = interp.async_disposable_stack_prototype.clone();
+    interp.register_accessor(&proto, "disposed", disposable_stack_disposed, None);
+    interp.register_method(&proto, "use", disposable_stack_use, 1);
+    interp.register_method(&proto, "adopt", disposable_stack_adopt, 2);
+    interp.register_method(&proto, "defer", disposable_stack_defer, 1);
+    interp.register_method(&proto, "move", disposable_stack_move, 0);
+    // The source is fixed, so this only fails if the compiler is broken
+    if let Ok(dispose_async) = create_dispose_async(interp) {
+        interp.root_guard.guard(dispose_async.cheap_clone());
+        let dispose_async = JsValue::Object(dispose_async);
+        let key = PropertyKey::String(interp.intern("disposeAsync"));
+        let mut proto_ref = proto.borrow_mut();
+        proto_ref.set_property(key, dispose_async.clone());
+        proto_ref.set_property(async_dispose_key, dispose_async);
+    }
+    super::weakref::set_to_string_tag(interp, &proto, "AsyncDisposableStack");
+    let constructor = interp.create_native_function(
+        "AsyncDisposableStack",
+        async_disposable_stack_constructor,
+        0,
+    );
+    interp.root_guard.guard(constructor.clone());
+    super::weakref::install_constructor(interp, "AsyncDisposableStack", constructor, proto);
+}
+
+/// Run [`DISPOSE_ASYNC_SOURCE`] and call the factory it evaluates to
+fn create_dispose_async(interp: &mut Interpreter) -> Result<Gc<JsObject>, JsError> {
+    let program = interp.parse_program(DISPOSE_ASYNC_SOURCE)?;
+    let mut chunk = Compiler::compile_program_for_eval(&program, interp.compile_limits)?;
+    interp.intern_chunk(&mut chunk);
+    let guard = interp.heap.create_guard();
+    let global_env = interp.global_env.cheap_clone();
+    let factory = {
+        let mut env = EnvScope::enter(interp, global_env, guard);
+        env.run_bytecode_with_this(chunk, JsValue::Undefined)?
+    };
+    let helpers = [
+        interp.create_native_function("start", dispose_async_start, 1),
+        interp.create_native_function("next", dispose_async_next, 1),
+        interp.create_native_function("fail", dispose_async_fail, 2),
+        interp.create_native_function("finish", dispose_async_finish, 1),
+    ]
+    .map(JsValue::Object);
+    let dispose_async = interp.call_function(factory.value, JsValue::Undefined, &helpers)?;
+    match dispose_async.value {
+        JsValue::Object(dispose_async) => Ok(dispose_async),
+        _ => Err(JsError::internal_error("disposeAsync is not a function")),
+    }
+}
+
+fn well_known_key(interp: &mut Interpreter, symbol: WellKnownSymbol) -> PropertyKey {
+    match interp.get_well_known(symbol).value() {
+        JsValue::Symbol(symbol) => PropertyKey::Symbol(symbol.clone()),
+        _ => PropertyKey::String(interp.intern(symbol.description())),
+    }
+}
+
+fn class_name(is_async: bool) -> &'static str {
+    if is_async {
+        "AsyncDisposableStack"
+    } else {
+        "DisposableStack"
+    }
+}
+
+fn new_stack_data(is_async: bool, resources: Vec<JsValue>) -> ExoticObject {
+    ExoticObject::DisposableStack(Rc::new(RefCell::new(DisposableStackData {
+        is_async,
+        disposed: false,
+        resources,
+        pending_error: None,
+    })))
+}
+
+pub fn disposable_stack_constructor(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let JsValue::Object(stack) = this else {
+        return Err(JsError::type_error(
+            "Constructor DisposableStack requires 'new'",
+        ));
+    };
+    stack.borrow_mut().exotic = new_stack_data(false, Vec::new());
+    Ok(Guarded::unguarded(JsValue::Object(stack)))
+}
+
+pub fn async_disposable_stack_constructor(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let JsValue::Object(stack) = this else {
+        return Err(JsError::type_error(
+            "Constructor AsyncDisposableStack requires 'new'",
+        ));
+    };
+    stack.borrow_mut().exotic = new_stack_data(true, Vec::new());
+    Ok(Guarded::unguarded(JsValue::Object(stack)))
+}
+
+fn this_stack(this: &JsValue, method: &str) -> Result<Rc<RefCell<DisposableStackData>>, JsError> {
+    if let JsValue::Object(obj) = this
+        && let ExoticObject::DisposableStack(data) = &obj.borrow().exotic
+    {
+        return Ok(data.clone());
+    }
+    Err(JsError::type_error(format!(
+        "DisposableStack.prototype.{} called on incompatible receiver",
+        method
+    )))
+}
+
+/// The stack of `this`, failing if it was already disposed
+fn live_stack(this: &JsValue, method: &str) -> Result<Rc<RefCell<DisposableStackData>>, JsError> {
+    let data = this_stack(this, method)?;
+    let (disposed, is_async) = {
+        let data = data.borrow();
+        (data.disposed, data.is_async)
+    };
+    if disposed {
+        return Err(JsError::disposed_stack(class_name(is_async)));
+    }
+    Ok(data)
+}
+
+pub fn disposable_stack_disposed(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_stack(&this, "disposed")?;
+    let disposed = data.borrow().disposed;
+    Ok(Guarded::unguarded(JsValue::Boolean(disposed)))
+}
+
+/// `stack.use(value)` - dispose `value` through its `[Symbol.dispose]`, or
+/// for an async stack its `[Symbol.asyncDispose]`, when the stack is disposed
+pub fn disposable_stack_use(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = live_stack(&this, "use")?;
+    let value = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let obj = match &value {
+        JsValue::Undefined | JsValue::Null => return Ok(Guarded::unguarded(value)),
+        JsValue::Object(obj) => obj.cheap_clone(),
+        _ => {
+            return Err(JsError::type_error(
+                "Only objects, null and undefined can be disposed",
+            ));
+        }
+    };
+    let is_async = data.borrow().is_async;
+    let mut method = None;
+    if is_async {
+        let key = well_known_key(interp, WellKnownSymbol::AsyncDispose);
+        method = obj.borrow().get_property(&key);
+    }
+    if method
+        .as_ref()
+        .is_none_or(|m| matches!(m, JsValue::Undefined))
+    {
+        let key = well_known_key(interp, WellKnownSymbol::Dispose);
+        method = obj.borrow().get_property(&key);
+    }
+    let method = method.unwrap_or(JsValue::Undefined);
+    if !method.is_callable() {
+        return Err(JsError::type_error(format!(
+            "{} is not disposable",
+            interp.to_js_string(&value)
+        )));
+    }
+    let guard = interp.heap.create_guard();
+    let dispose = bind_disposal(interp, &guard, &method, value.clone(), Vec::new());
+    data.borrow_mut().resources.push(dispose);
+    Ok(Guarded::unguarded(value))
+}
+
+/// `stack.adopt(value, onDispose)` - call `onDispose(value)` when the stack
+/// is disposed
+pub fn disposable_stack_adopt(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = live_stack(&this, "adopt")?;
+    let value = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let on_dispose = args.get(1).cloned().unwrap_or(JsValue::Undefined);
+    if !on_dispose.is_callable() {
+        return Err(JsError::type_error(
+            "DisposableStack.prototype.adopt: onDispose must be callable",
+        ));
+    }
+    let guard = interp.heap.create_guard();
+    let dispose = bind_disposal(
+        interp,
+        &guard,
+        &on_dispose,
+        JsValue::Undefined,
+        Vec::from([value.clone()]),
+    );
+    data.borrow_mut().resources.push(dispose);
+    Ok(Guarded::unguarded(value))
+}
+
+/// `stack.defer(onDispose)` - call `onDispose()` when the stack is disposed
+pub fn disposable_stack_defer(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = live_stack(&this, "defer")?;
+    let on_dispose = args.first().cloned().unwrap_or(JsValue::Undefined);
+    if !on_dispose.is_callable() {
+        return Err(JsError::type_error(
+            "DisposableStack.prototype.defer: onDispose must be callable",
+        ));
+    }
+    data.borrow_mut().resources.push(on_dispose);
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+/// `stack.move()` - a new stack taking over the resources of this one,
+/// which is left disposed without disposing anything
+pub fn disposable_stack_move(
+    interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = live_stack(&this, "move")?;
+    let is_async = data.borrow().is_async;
+    let guard = interp.heap.create_guard();
+    let stack = interp.create_object(&guard);
+    // Taken only once the new stack exists, so no allocation happens while
+    // nothing traces them
+    let resources = {
+        let mut data = data.borrow_mut();
+        data.disposed = true;
+        core::mem::take(&mut data.resources)
+    };
+    {
+        let mut stack_ref = stack.borrow_mut();
+        stack_ref.prototype = Some(if is_async {
+            interp.async_disposable_stack_prototype.clone()
+        } else {
+            interp.disposable_stack_prototype.clone()
+        });
+        stack_ref.exotic = new_stack_data(is_async, resources);
+    }
+    Ok(Guarded::with_guard(JsValue::Object(stack), guard))
+}
+
+/// `stack.dispose()` - dispose every resource, last registered first
+pub fn disposable_stack_dispose(
+    interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_stack(&this, "dispose")?;
+    if data.borrow().disposed {
+        return Ok(Guarded::unguarded(JsValue::Undefined));
+    }
+    data.borrow_mut().disposed = true;
+
+    let guard = interp.heap.create_guard();
+    guard_stack(&guard, &this);
+    while let Some(dispose) = pop_resource(&data, &guard) {
+        if let Err(error) = interp.call_function(dispose, JsValue::Undefined, &[]) {
+            record_error(interp, &data, &guard, error)?;
+        }
+    }
+    match data.borrow_mut().pending_error.take() {
+        Some(error) => Err(JsError::thrown(Guarded::with_guard(error, guard))),
+        None => Ok(Guarded::unguarded(JsValue::Undefined)),
+    }
+}
+
+/// A function calling `method` with `receiver` as `this` and `args`
+fn bind_disposal(
+    interp: &mut Interpreter,
+    guard: &Guard<JsObject>,
+    method: &JsValue,
+    receiver: JsValue,
+    args: Vec<JsValue>,
+) -> JsValue {
+    let JsValue::Object(target) = method else {
+        return method.clone();
+    };
+    let bound = interp.create_js_function(
+        guard,
+        JsFunction::Bound(Box::new(BoundFunctionData {
+            target: target.cheap_clone(),
+            this_arg: receiver,
+            bound_args: args,
+        })),
+    );
+    JsValue::Object(bound)
+}
+
+/// The function disposing the last resource of the stack, rooted by `guard`
+/// now that the stack no longer holds it
+fn pop_resource(
+    data: &Rc<RefCell<DisposableStackData>>,
+    guard: &Guard<JsObject>,
+) -> Option<JsValue> {
+    let dispose = data.borrow_mut().resources.pop()?;
+    if let JsValue::Object(dispose) = &dispose {
+        guard.guard(dispose.cheap_clone());
+    }
+    Some(dispose)
+}
+
+/// Note `error`, raised disposing a resource, as the error the disposal
+/// throws, suppressing the one noted before it. Terminations pass through.
+fn record_error(
+    interp: &mut Interpreter,
+    data: &Rc<RefCell<DisposableStackData>>,
+    guard: &Guard<JsObject>,
+    error: JsError,
+) -> Result<(), JsError> {
+    if error.is_termination() {
+        return Err(error);
+    }
+    let (value, _error_guard) = match error {
+        JsError::ThrownValue { guarded } => (guarded.value, None),
+        other => create_error_object(interp, &other),
+    };
+    if let JsValue::Object(obj) = &value {
+        guard.guard(obj.cheap_clone());
+    }
+    let previous = data.borrow_mut().pending_error.take();
+    let error = match previous {
+        Some(suppressed) => {
+            if let JsValue::Object(obj) = &suppressed {
+                guard.guard(obj.cheap_clone());
+            }
+            JsValue::Object(create_suppressed_error(interp, guard, value, suppressed))
+        }
+        None => value,
+    };
+    data.borrow_mut().pending_error = Some(error);
+    Ok(())
+}
+
+fn guard_stack(guard: &Guard<JsObject>, stack: &JsValue) {
+    if let JsValue::Object(stack) = stack {
+        guard.guard(stack.cheap_clone());
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// disposeAsync helpers
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// `start(stack)` - mark an async stack disposed, returning whether it
+/// wasn't already
+fn dispose_async_start(
+    _interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let stack = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let data = match this_stack(&stack, "disposeAsync") {
+        Ok(data) if data.borrow().is_async => data,
+        _ => {
+            return Err(JsError::type_error(
+                "AsyncDisposableStack.prototype.disposeAsync called on incompatible receiver",
+            ));
+        }
+    };
+    let first = !core::mem::replace(&mut data.borrow_mut().disposed, true);
+    Ok(Guarded::unguarded(JsValue::Boolean(first)))
+}
+
+/// `next(stack)` - the function disposing the last resource left, or
+/// undefined once there are none
+fn dispose_async_next(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let stack = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let data = this_stack(&stack, "disposeAsync")?;
+    let guard = interp.heap.create_guard();
+    match pop_resource(&data, &guard) {
+        Some(dispose) => Ok(Guarded::with_guard(dispose, guard)),
+        None => Ok(Guarded::unguarded(JsValue::Undefined)),
+    }
+}
+
+/// `fail(stack, error)` - note an error disposing a resource
+fn dispose_async_fail(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let stack = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let data = this_stack(&stack, "disposeAsync")?;
+    let error = args.get(1).cloned().unwrap_or(JsValue::Undefined);
+    let guard = interp.heap.create_guard();
+    record_error(
+        interp,
+        &data,
+        &guard,
+        JsError::thrown(Guarded::unguarded(error)),
+    )?;
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+/// `finish(stack)` - throw the error noted while disposing, if any
+fn dispose_async_finish(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let stack = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let data = this_stack(&stack, "disposeAsync")?;
+    let error = data.borrow_mut().pending_error.take();
+    match error {
+        Some(error) => {
+            let guard = interp.heap.create_guard();
+            if let JsValue::Object(obj) = &error {
+                guard.guard(obj.cheap_clone());
+            }
+            Err(JsError::thrown(Guarded::with_guard(error, guard)))
+        }
+        None => Ok(Guarded::unguarded(JsValue::Undefined)),
+    }
+}
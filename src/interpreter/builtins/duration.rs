@@ -0,0 +1,237 @@
+//! Duration helpers exported by tsrun:host
+//!
+//! Parses and formats durations in milliseconds:
+//!
+//! ```typescript
+//! import { parseDuration, formatDuration } from "tsrun:host";
+//!
+//! parseDuration("1h30m");                     // 5400000
+//! parseDuration("1.5h");                      // 5400000
+//! formatDuration(5400000);                    // "1h 30m"
+//! formatDuration(5400000, { compact: true }); // "1h30m"
+//! formatDuration(5430000, { maxUnits: 2 });   // "1h 30m"
+//! ```
+//!
+//! Units are `ms`, `s`, `m`, `h` and `d`, written largest first and each at
+//! most once. Negative durations, fractions of a millisecond and results
+//! beyond `Number.MAX_SAFE_INTEGER` milliseconds are rejected.
+
+use crate::error::JsError;
+use crate::interpreter::Interpreter;
+use crate::prelude::{String, ToString, Vec, format, math};
+use crate::value::{Guarded, JsString, JsValue, PropertyKey};
+
+/// 2^53 - 1, the largest integer every f64 neighbour of which is exact
+const MAX_SAFE_INTEGER: u64 = 9_007_199_254_740_991;
+
+/// Units and their length in milliseconds, largest first
+const UNITS: &[(&str, u64)] = &[
+    ("d", 86_400_000),
+    ("h", 3_600_000),
+    ("m", 60_000),
+    ("s", 1_000),
+    ("ms", 1),
+];
+
+/// parseDuration(str) - returns the duration in milliseconds
+pub(crate) fn parse_duration_fn(
+    _interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let Some(JsValue::String(input)) = args.first() else {
+        return Err(JsError::type_error("parseDuration requires a string"));
+    };
+    let ms = parse_duration(input.as_str())?;
+    Ok(Guarded::unguarded(JsValue::Number(ms as f64)))
+}
+
+/// formatDuration(ms, { compact?, maxUnits? }) - returns e.g. "1h 30m"
+pub(crate) fn format_duration_fn(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let ms = match args.first() {
+        Some(JsValue::Number(n)) => *n,
+        Some(other) => interp.coerce_to_number(other)?,
+        None => f64::NAN,
+    };
+
+    let mut compact = false;
+    let mut max_units = None;
+    if let Some(JsValue::Object(opts)) = args.get(1) {
+        let compact_key = PropertyKey::String(interp.intern("compact"));
+        let max_units_key = PropertyKey::String(interp.intern("maxUnits"));
+        let opts = opts.borrow();
+        if let Some(v) = opts.get_property(&compact_key) {
+            compact = v.to_boolean();
+        }
+        match opts.get_property(&max_units_key) {
+            Some(JsValue::Number(n)) if n >= 1.0 && math::fract(n) == 0.0 => {
+                max_units = Some(n.min(UNITS.len() as f64) as usize);
+            }
+            Some(JsValue::Undefined) | None => {}
+            Some(_) => {
+                return Err(JsError::range_error(
+                    "formatDuration maxUnits must be a positive integer",
+                ));
+            }
+        }
+    }
+
+    let text = format_duration(ms, compact, max_units)?;
+    Ok(Guarded::unguarded(JsValue::String(JsString::from(text))))
+}
+
+/// Parse a duration such as `"90s"`, `"1h30m"` or `"1.5h"` into milliseconds
+pub fn parse_duration(input: &str) -> Result<u64, JsError> {
+    let invalid = |rest: &str, expected: &str| {
+        JsError::range_error(format!(
+            "Invalid duration '{}': {} at '{}'",
+            input, expected, rest
+        ))
+    };
+    let too_large = || {
+        JsError::range_error(format!(
+            "Duration '{}' exceeds Number.MAX_SAFE_INTEGER milliseconds",
+            input
+        ))
+    };
+
+    let s = input.trim();
+    if s.starts_with('-') {
+        return Err(JsError::range_error(format!(
+            "Invalid duration '{}': durations cannot be negative",
+            input
+        )));
+    }
+    let mut rest = s.strip_prefix('+').unwrap_or(s);
+    if rest == "0" {
+        return Ok(0);
+    }
+    if rest.is_empty() {
+        return Err(invalid(rest, "expected a number"));
+    }
+
+    let mut total: u64 = 0;
+    // Index into UNITS the next segment's unit must come after
+    let mut next_unit = 0;
+    while !rest.is_empty() {
+        // Mantissa digits, remembering how many came after the decimal point
+        let mut mantissa: u64 = 0;
+        let mut scale: u32 = 0;
+        let mut digit_count = 0;
+        let mut seen_point = false;
+        let mut end = 0;
+        for (i, c) in rest.char_indices() {
+            match c {
+                '0'..='9' => {
+                    mantissa = mantissa
+                        .checked_mul(10)
+                        .and_then(|m| m.checked_add(u64::from(c as u8 - b'0')))
+                        .ok_or_else(too_large)?;
+                    digit_count += 1;
+                    if seen_point {
+                        scale += 1;
+                    }
+                }
+                '.' if !seen_point => seen_point = true,
+                _ => break,
+            }
+            end = i + c.len_utf8();
+        }
+        if digit_count == 0 {
+            return Err(invalid(rest, "expected a number"));
+        }
+        let after_number = rest.get(end..).unwrap_or("");
+
+        let unit_len = after_number
+            .bytes()
+            .take_while(|b| b.is_ascii_alphabetic())
+            .count();
+        let unit = after_number.get(..unit_len).unwrap_or("");
+        if unit.is_empty() {
+            return Err(invalid(after_number, "expected a unit"));
+        }
+        let Some(position) = UNITS.iter().position(|(name, _)| *name == unit) else {
+            return Err(invalid(after_number, "unknown unit"));
+        };
+        if position < next_unit {
+            return Err(invalid(
+                after_number,
+                "units must go from largest to smallest",
+            ));
+        }
+        next_unit = position + 1;
+        let unit_ms = UNITS.get(position).map_or(1, |&(_, ms)| ms);
+
+        // mantissa * unit / 10^scale, exact or rejected
+        let scaled = mantissa.checked_mul(unit_ms).ok_or_else(too_large)?;
+        let divisor = 10u64.checked_pow(scale).ok_or_else(too_large)?;
+        if scaled % divisor != 0 {
+            return Err(JsError::range_error(format!(
+                "Invalid duration '{}': durations are whole milliseconds",
+                input
+            )));
+        }
+        total = total
+            .checked_add(scaled / divisor)
+            .filter(|&t| t <= MAX_SAFE_INTEGER)
+            .ok_or_else(too_large)?;
+
+        rest = after_number.get(unit_len..).unwrap_or("");
+    }
+    Ok(total)
+}
+
+/// Format a duration in milliseconds as `"1h 30m"`, or `"1h30m"` when
+/// `compact`.
+///
+/// Zero units are left out. With `max_units`, only that many units starting
+/// from the largest present are considered and anything smaller is dropped.
+pub fn format_duration(
+    ms: f64,
+    compact: bool,
+    max_units: Option<usize>,
+) -> Result<String, JsError> {
+    let cannot_format = |why: &str| {
+        JsError::range_error(format!(
+            "Cannot format {} as a duration: {}",
+            JsValue::Number(ms).to_js_string(),
+            why
+        ))
+    };
+    if !ms.is_finite() {
+        return Err(cannot_format("not a finite number"));
+    }
+    if ms < 0.0 {
+        return Err(cannot_format("durations cannot be negative"));
+    }
+    if math::fract(ms) != 0.0 {
+        return Err(cannot_format("durations are whole milliseconds"));
+    }
+    if ms > MAX_SAFE_INTEGER as f64 {
+        return Err(cannot_format("exceeds Number.MAX_SAFE_INTEGER"));
+    }
+    let mut remaining = ms as u64;
+    if remaining == 0 {
+        return Ok("0ms".to_string());
+    }
+
+    let mut parts: Vec<String> = Vec::new();
+    let mut first_unit = None;
+    for (i, &(name, unit_ms)) in UNITS.iter().enumerate() {
+        let count = remaining / unit_ms;
+        remaining %= unit_ms;
+        if count == 0 {
+            continue;
+        }
+        let first = *first_unit.get_or_insert(i);
+        if max_units.is_some_and(|max| i >= first + max) {
+            break;
+        }
+        parts.push(format!("{}{}", count, name));
+    }
+    Ok(parts.join(if compact { "" } else { " " }))
+}
@@ -4,7 +4,7 @@ use crate::error::JsError;
 use crate::gc::{Gc, Guard};
 use crate::interpreter::Interpreter;
 use crate::prelude::{String, ToString, format};
-use crate::value::{Guarded, JsObject, JsString, JsValue, PropertyKey};
+use crate::value::{Guarded, JsObject, JsString, JsValue, Property, PropertyKey};
 
 /// Initialize Error and all derived error constructors and add them to globals
 pub fn init_error(interp: &mut Interpreter) {
@@ -27,6 +27,8 @@ pub fn init_error(interp: &mut Interpreter) {
     let syntax_error_key = PropertyKey::String(interp.intern("SyntaxError"));
     let uri_error_key = PropertyKey::String(interp.intern("URIError"));
     let eval_error_key = PropertyKey::String(interp.intern("EvalError"));
+    let aggregate_error_key = PropertyKey::String(interp.intern("AggregateError"));
+    let suppressed_error_key = PropertyKey::String(interp.intern("SuppressedError"));
     {
         let mut p = error_proto.borrow_mut();
         p.set_property(name_key.clone(), JsValue::String(JsString::from("Error")));
@@ -153,9 +155,8 @@ pub fn init_error(interp: &mut Interpreter) {
         .borrow_mut()
         .set_property(syntax_error_key, JsValue::Object(syntax_error_fn));
 
-    // URIError (uses error_prototype since we don't have a dedicated prototype)
-    let uri_error_proto = interp.root_guard.alloc();
-    uri_error_proto.borrow_mut().prototype = Some(interp.error_prototype.clone());
+    // URIError
+    let uri_error_proto = interp.uri_error_prototype.clone();
     {
         let mut p = uri_error_proto.borrow_mut();
         p.set_property(
@@ -178,26 +179,77 @@ pub fn init_error(interp: &mut Interpreter) {
         .borrow_mut()
         .set_property(uri_error_key, JsValue::Object(uri_error_fn));
 
-    // EvalError (uses error_prototype since we don't have a dedicated prototype)
-    let eval_error_proto = interp.root_guard.alloc();
-    eval_error_proto.borrow_mut().prototype = Some(interp.error_prototype.clone());
+    // EvalError
+    let eval_error_proto = interp.eval_error_prototype.clone();
     {
         let mut p = eval_error_proto.borrow_mut();
-        p.set_property(name_key, JsValue::String(JsString::from("EvalError")));
-        p.set_property(message_key, JsValue::String(JsString::from("")));
+        p.set_property(
+            name_key.clone(),
+            JsValue::String(JsString::from("EvalError")),
+        );
+        p.set_property(message_key.clone(), JsValue::String(JsString::from("")));
     }
     let eval_error_fn = interp.create_native_function("EvalError", eval_error_constructor, 1);
     interp.root_guard.guard(eval_error_fn.clone());
     eval_error_fn
         .borrow_mut()
-        .set_property(proto_key, JsValue::Object(eval_error_proto.clone()));
-    eval_error_proto
-        .borrow_mut()
-        .set_property(constructor_key, JsValue::Object(eval_error_fn.clone()));
+        .set_property(proto_key.clone(), JsValue::Object(eval_error_proto.clone()));
+    eval_error_proto.borrow_mut().set_property(
+        constructor_key.clone(),
+        JsValue::Object(eval_error_fn.clone()),
+    );
     interp
         .global
         .borrow_mut()
         .set_property(eval_error_key, JsValue::Object(eval_error_fn));
+
+    // AggregateError
+    let aggregate_error_proto = interp.aggregate_error_prototype.clone();
+    {
+        let mut p = aggregate_error_proto.borrow_mut();
+        p.set_property(
+            name_key.clone(),
+            JsValue::String(JsString::from("AggregateError")),
+        );
+        p.set_property(message_key.clone(), JsValue::String(JsString::from("")));
+    }
+    let aggregate_error_fn =
+        interp.create_native_function("AggregateError", aggregate_error_constructor, 2);
+    interp.root_guard.guard(aggregate_error_fn.clone());
+    aggregate_error_fn.borrow_mut().set_property(
+        proto_key.clone(),
+        JsValue::Object(aggregate_error_proto.clone()),
+    );
+    aggregate_error_proto.borrow_mut().set_property(
+        constructor_key.clone(),
+        JsValue::Object(aggregate_error_fn.clone()),
+    );
+    interp
+        .global
+        .borrow_mut()
+        .set_property(aggregate_error_key, JsValue::Object(aggregate_error_fn));
+
+    // SuppressedError
+    let suppressed_error_proto = interp.suppressed_error_prototype.clone();
+    {
+        let mut p = suppressed_error_proto.borrow_mut();
+        p.set_property(name_key, JsValue::String(JsString::from("SuppressedError")));
+        p.set_property(message_key, JsValue::String(JsString::from("")));
+    }
+    let suppressed_error_fn =
+        interp.create_native_function("SuppressedError", suppressed_error_constructor, 3);
+    interp.root_guard.guard(suppressed_error_fn.clone());
+    suppressed_error_fn
+        .borrow_mut()
+        .set_property(proto_key, JsValue::Object(suppressed_error_proto.clone()));
+    suppressed_error_proto.borrow_mut().set_property(
+        constructor_key,
+        JsValue::Object(suppressed_error_fn.clone()),
+    );
+    interp
+        .global
+        .borrow_mut()
+        .set_property(suppressed_error_key, JsValue::Object(suppressed_error_fn));
 }
 
 /// Error.prototype.toString()
@@ -247,6 +299,7 @@ fn initialize_error_on_this(
     obj: &Gc<JsObject>,
     name: &str,
     message: JsValue,
+    options: Option<&JsValue>,
 ) {
     let msg_str = match &message {
         JsValue::Undefined => interp.intern(""),
@@ -268,6 +321,19 @@ fn initialize_error_on_this(
     obj_ref.set_property(name_key, JsValue::String(JsString::from(name)));
     obj_ref.set_property(message_key, JsValue::String(msg_str.clone()));
     obj_ref.set_property(stack_key, JsValue::String(stack));
+    drop(obj_ref);
+
+    // `new Error(message, { cause })` keeps the cause as an own, non-enumerable property
+    if let Some(JsValue::Object(options)) = options {
+        let cause_key = interp.property_key("cause");
+        let cause = options.borrow().get_property(&cause_key);
+        if let Some(cause) = cause {
+            obj.borrow_mut().define_property(
+                cause_key,
+                Property::with_attributes(cause, true, false, true),
+            );
+        }
+    }
 }
 
 /// Error constructor - sets name and message on `this`
@@ -280,7 +346,7 @@ pub fn error_constructor(
 
     // When called via `new Error()`, this is the newly created object
     if let JsValue::Object(ref this_obj) = this {
-        initialize_error_on_this(interp, this_obj, "Error", message);
+        initialize_error_on_this(interp, this_obj, "Error", message, args.get(1));
     }
 
     // Return undefined - new handler will return the created object
@@ -295,7 +361,7 @@ pub fn type_error_constructor(
 ) -> Result<Guarded, JsError> {
     let message = args.first().cloned().unwrap_or(JsValue::Undefined);
     if let JsValue::Object(ref this_obj) = this {
-        initialize_error_on_this(interp, this_obj, "TypeError", message);
+        initialize_error_on_this(interp, this_obj, "TypeError", message, args.get(1));
     }
     Ok(Guarded::unguarded(JsValue::Undefined))
 }
@@ -308,7 +374,7 @@ pub fn range_error_constructor(
 ) -> Result<Guarded, JsError> {
     let message = args.first().cloned().unwrap_or(JsValue::Undefined);
     if let JsValue::Object(ref this_obj) = this {
-        initialize_error_on_this(interp, this_obj, "RangeError", message);
+        initialize_error_on_this(interp, this_obj, "RangeError", message, args.get(1));
     }
     Ok(Guarded::unguarded(JsValue::Undefined))
 }
@@ -321,7 +387,7 @@ pub fn reference_error_constructor(
 ) -> Result<Guarded, JsError> {
     let message = args.first().cloned().unwrap_or(JsValue::Undefined);
     if let JsValue::Object(ref this_obj) = this {
-        initialize_error_on_this(interp, this_obj, "ReferenceError", message);
+        initialize_error_on_this(interp, this_obj, "ReferenceError", message, args.get(1));
     }
     Ok(Guarded::unguarded(JsValue::Undefined))
 }
@@ -334,7 +400,7 @@ pub fn syntax_error_constructor(
 ) -> Result<Guarded, JsError> {
     let message = args.first().cloned().unwrap_or(JsValue::Undefined);
     if let JsValue::Object(ref this_obj) = this {
-        initialize_error_on_this(interp, this_obj, "SyntaxError", message);
+        initialize_error_on_this(interp, this_obj, "SyntaxError", message, args.get(1));
     }
     Ok(Guarded::unguarded(JsValue::Undefined))
 }
@@ -347,7 +413,7 @@ pub fn uri_error_constructor(
 ) -> Result<Guarded, JsError> {
     let message = args.first().cloned().unwrap_or(JsValue::Undefined);
     if let JsValue::Object(ref this_obj) = this {
-        initialize_error_on_this(interp, this_obj, "URIError", message);
+        initialize_error_on_this(interp, this_obj, "URIError", message, args.get(1));
     }
     Ok(Guarded::unguarded(JsValue::Undefined))
 }
@@ -360,11 +426,122 @@ pub fn eval_error_constructor(
 ) -> Result<Guarded, JsError> {
     let message = args.first().cloned().unwrap_or(JsValue::Undefined);
     if let JsValue::Object(ref this_obj) = this {
-        initialize_error_on_this(interp, this_obj, "EvalError", message);
+        initialize_error_on_this(interp, this_obj, "EvalError", message, args.get(1));
+    }
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+/// AggregateError constructor - `new AggregateError(errors, message, options)`
+pub fn aggregate_error_constructor(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let errors = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let message = args.get(1).cloned().unwrap_or(JsValue::Undefined);
+    let Some(errors) = interp.collect_iterator_values(&errors)? else {
+        return Err(JsError::type_error(
+            "AggregateError errors must be iterable",
+        ));
+    };
+    if let JsValue::Object(ref this_obj) = this {
+        initialize_error_on_this(interp, this_obj, "AggregateError", message, args.get(2));
+
+        let guard = interp.heap.create_guard();
+        let errors = interp.create_array_from(&guard, errors);
+        let errors_key = interp.property_key("errors");
+        this_obj.borrow_mut().define_property(
+            errors_key,
+            Property::with_attributes(JsValue::Object(errors), true, false, true),
+        );
+    }
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+/// SuppressedError constructor - `new SuppressedError(error, suppressed, message)`
+pub fn suppressed_error_constructor(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let error = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let suppressed = args.get(1).cloned().unwrap_or(JsValue::Undefined);
+    let message = args.get(2).cloned().unwrap_or(JsValue::Undefined);
+    if let JsValue::Object(ref this_obj) = this {
+        initialize_error_on_this(interp, this_obj, "SuppressedError", message, None);
+        set_suppressed_fields(interp, this_obj, error, suppressed);
     }
     Ok(Guarded::unguarded(JsValue::Undefined))
 }
 
+/// A SuppressedError recording that `error` was raised while `suppressed`
+/// was already being thrown, as resource disposal creates them
+pub(crate) fn create_suppressed_error(
+    interp: &mut Interpreter,
+    guard: &Guard<JsObject>,
+    error: JsValue,
+    suppressed: JsValue,
+) -> Gc<JsObject> {
+    let obj = interp.create_object(guard);
+    obj.borrow_mut().prototype = Some(interp.suppressed_error_prototype.clone());
+    initialize_error_on_this(
+        interp,
+        &obj,
+        "SuppressedError",
+        JsValue::String(JsString::from("An error was suppressed during disposal")),
+        None,
+    );
+    set_suppressed_fields(interp, &obj, error, suppressed);
+    obj
+}
+
+fn set_suppressed_fields(
+    interp: &mut Interpreter,
+    obj: &Gc<JsObject>,
+    error: JsValue,
+    suppressed: JsValue,
+) {
+    let error_key = interp.property_key("error");
+    let suppressed_key = interp.property_key("suppressed");
+    let mut obj_ref = obj.borrow_mut();
+    obj_ref.define_property(
+        error_key,
+        Property::with_attributes(error, true, false, true),
+    );
+    obj_ref.define_property(
+        suppressed_key,
+        Property::with_attributes(suppressed, true, false, true),
+    );
+}
+
+/// The prototype of the built-in error type called `name`, or
+/// Error.prototype for any other name
+pub(crate) fn error_prototype_for(interp: &Interpreter, name: &str) -> Gc<JsObject> {
+    match name {
+        "TypeError" => interp.type_error_prototype.clone(),
+        "ReferenceError" => interp.reference_error_prototype.clone(),
+        "RangeError" => interp.range_error_prototype.clone(),
+        "SyntaxError" => interp.syntax_error_prototype.clone(),
+        "EvalError" => interp.eval_error_prototype.clone(),
+        "URIError" => interp.uri_error_prototype.clone(),
+        "AggregateError" => interp.aggregate_error_prototype.clone(),
+        "SuppressedError" => interp.suppressed_error_prototype.clone(),
+        _ => interp.error_prototype.clone(),
+    }
+}
+
+/// Whether `obj` inherits from `error_prototype`, the Error.prototype of its interpreter
+pub(crate) fn is_error_object(error_prototype: &Gc<JsObject>, obj: &Gc<JsObject>) -> bool {
+    let mut proto = obj.borrow().prototype.clone();
+    while let Some(current) = proto {
+        if current.id() == error_prototype.id() {
+            return true;
+        }
+        proto = current.borrow().prototype.clone();
+    }
+    false
+}
+
 /// Create an error object from a JsError
 /// Returns the error object and a guard to keep it alive
 pub fn create_error_object(
@@ -394,26 +571,50 @@ pub fn create_error_object(
             "SyntaxError",
             format!("{} at {}", message, location),
         ),
+        JsError::UnsupportedSyntax { location, .. } => (
+            interp.syntax_error_prototype.clone(),
+            "SyntaxError",
+            format!(
+                "{} at {}",
+                error.syntax_message().unwrap_or_default(),
+                location
+            ),
+        ),
         JsError::RuntimeError { kind, message, .. } => {
             // Map to appropriate prototype based on kind
-            let proto = match kind.as_str() {
-                "TypeError" => interp.type_error_prototype.clone(),
-                "ReferenceError" => interp.reference_error_prototype.clone(),
-                "RangeError" => interp.range_error_prototype.clone(),
-                "SyntaxError" => interp.syntax_error_prototype.clone(),
-                _ => interp.error_prototype.clone(),
-            };
-            (proto, kind.as_str(), message.clone())
+            (
+                error_prototype_for(interp, kind),
+                kind.as_str(),
+                message.clone(),
+            )
         }
         JsError::ModuleError { message } => {
             (interp.error_prototype.clone(), "Error", message.clone())
         }
+        JsError::IntegrityMismatch { .. } => (
+            interp.error_prototype.clone(),
+            "Error",
+            error.integrity_message().unwrap_or_default(),
+        ),
+        JsError::TransformError { .. } => (
+            interp.error_prototype.clone(),
+            "Error",
+            error.transform_message().unwrap_or_default(),
+        ),
+        JsError::CompileLimit { .. } => (
+            interp.range_error_prototype.clone(),
+            "RangeError",
+            error.compile_limit_message().unwrap_or_default(),
+        ),
         JsError::Internal(msg) => (interp.error_prototype.clone(), "Error", msg.clone()),
         // These should not reach here, but handle them anyway
         JsError::Thrown
         | JsError::ThrownValue { .. }
         | JsError::GeneratorYield { .. }
-        | JsError::OptionalChainShortCircuit => {
+        | JsError::OptionalChainShortCircuit
+        | JsError::Terminated { .. }
+        | JsError::Stalled { .. }
+        | JsError::ReplayMismatch { .. } => {
             return (JsValue::Undefined, None);
         }
     };
@@ -424,11 +625,17 @@ pub fn create_error_object(
 
     // Set name, message, and stack properties
     let msg_str = JsString::from(message.as_str());
-    let stack_str = if msg_str.is_empty() {
-        JsString::from(name)
+    let mut stack_str = if msg_str.is_empty() {
+        name.to_string()
     } else {
-        JsString::from(format!("{}: {}", name, msg_str))
+        format!("{}: {}", name, msg_str)
     };
+    if let JsError::RuntimeError { stack, .. } = error {
+        for frame in stack {
+            stack_str.push('\n');
+            stack_str.push_str(&frame.to_string());
+        }
+    }
 
     let name_key = interp.property_key("name");
     let message_key = interp.property_key("message");
@@ -438,7 +645,21 @@ pub fn create_error_object(
         let mut obj = error_obj.borrow_mut();
         obj.set_property(name_key, JsValue::String(JsString::from(name)));
         obj.set_property(message_key, JsValue::String(msg_str));
-        obj.set_property(stack_key, JsValue::String(stack_str));
+        obj.set_property(stack_key, JsValue::String(JsString::from(stack_str)));
+    }
+
+    // Properties an error thrown earlier had, such as a `code`
+    if let JsError::RuntimeError {
+        properties: Some(properties),
+        ..
+    } = error
+    {
+        for (key, json) in properties.iter() {
+            if let Ok(value) = super::json::json_to_js_value_with_guard(interp, json, &guard) {
+                let key = interp.property_key(key);
+                error_obj.borrow_mut().set_property(key, value);
+            }
+        }
     }
 
     (JsValue::Object(error_obj), Some(guard))
@@ -3,11 +3,10 @@
 use crate::prelude::{Box, Rc, String, ToString, Vec, format, vec};
 
 use crate::ast::{Expression, Statement};
-use crate::compiler::Compiler;
+use crate::compiler::{Compiler, FunctionSource};
 use crate::error::JsError;
 use crate::gc::Gc;
 use crate::interpreter::Interpreter;
-use crate::parser::Parser;
 use crate::value::{
     BoundFunctionData, BytecodeFunction, CheapClone, ExoticObject, Guarded, JsFunction, JsObject,
     JsString, JsSymbol, JsValue, NativeFunction, PropertyKey,
@@ -49,6 +48,7 @@ pub fn init_function_prototype(interp: &mut Interpreter) {
     interp.register_method(&proto, "call", function_call, 1);
     interp.register_method(&proto, "apply", function_apply, 2);
     interp.register_method(&proto, "bind", function_bind, 1);
+    interp.register_method(&proto, "toString", function_to_string, 0);
 
     // Add Symbol.hasInstance method
     let well_known = interp.well_known_symbols;
@@ -67,6 +67,86 @@ pub fn init_function_prototype(interp: &mut Interpreter) {
     // after it's created in create_function_constructor
 }
 
+/// Function.prototype.toString()
+/// Returns the source text of user-defined functions and classes, or the
+/// `function name() { [native code] }` form for builtins, bound functions and
+/// functions compiled without retained source.
+fn function_to_string(
+    interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let JsValue::Object(func_obj) = &this else {
+        return Err(JsError::type_error(
+            "Function.prototype.toString requires that 'this' be a Function",
+        ));
+    };
+
+    let source_text = {
+        let obj_ref = func_obj.borrow();
+        match &obj_ref.exotic {
+            ExoticObject::Function(
+                JsFunction::Bytecode(bc)
+                | JsFunction::BytecodeGenerator(bc)
+                | JsFunction::BytecodeAsync(bc)
+                | JsFunction::BytecodeAsyncGenerator(bc),
+            ) => bc
+                .chunk
+                .function_info
+                .as_ref()
+                .and_then(|info| info.source_text.as_ref())
+                .map(|src| src.text().to_string()),
+            ExoticObject::Function(_) => None,
+            ExoticObject::Proxy(_) if this.is_callable() => None,
+            _ => {
+                return Err(JsError::type_error(
+                    "Function.prototype.toString requires that 'this' be a Function",
+                ));
+            }
+        }
+    };
+
+    let text = match source_text {
+        Some(text) => text,
+        None => {
+            let name_key = PropertyKey::String(interp.intern("name"));
+            let name_value = func_obj
+                .borrow()
+                .get_property_descriptor(&name_key)
+                .map(|(prop, _)| prop.value);
+            let name = match name_value {
+                Some(JsValue::String(name)) => name.to_string(),
+                _ => String::new(),
+            };
+            format!("function {}() {{ [native code] }}", name)
+        }
+    };
+
+    Ok(Guarded::unguarded(JsValue::String(JsString::from(text))))
+}
+
+/// The retained source of a script function, with the file it came from.
+///
+/// `None` for native, bound and other built-in functions, and for script
+/// functions when source retention is disabled.
+pub(crate) fn function_source(value: &JsValue) -> Option<FunctionSource> {
+    let JsValue::Object(obj) = value else {
+        return None;
+    };
+    let obj_ref = obj.borrow();
+    let ExoticObject::Function(
+        JsFunction::Bytecode(bc)
+        | JsFunction::BytecodeGenerator(bc)
+        | JsFunction::BytecodeAsync(bc)
+        | JsFunction::BytecodeAsyncGenerator(bc),
+    ) = &obj_ref.exotic
+    else {
+        return None;
+    };
+    let source = bc.chunk.function_info.as_ref()?.source_text.clone()?;
+    Some(source.with_path(bc.chunk.source_file.as_deref()))
+}
+
 /// Function.prototype[Symbol.hasInstance](V)
 /// Returns true if V is an instance of this function (i.e., V's prototype chain includes this.prototype)
 fn function_has_instance(
@@ -145,6 +225,8 @@ fn function_constructor_fn(
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
+    interp.check_dynamic_code("The Function constructor")?;
+
     // Collect parameter strings and body string
     let (param_strings, body_string) = if args.is_empty() {
         // new Function() - empty function
@@ -176,9 +258,8 @@ fn function_constructor_fn(
     );
 
     // Parse the function
-    let mut parser = Parser::new(&source, &mut interp.string_dict);
-    let program = parser
-        .parse_program()
+    let program = interp
+        .parse_program(&source)
         .map_err(|e| JsError::syntax_error_simple(format!("Invalid function body: {}", e)))?;
 
     // Extract the function expression from the parsed program
@@ -186,14 +267,30 @@ fn function_constructor_fn(
     let func_expr = extract_function_expression(&program)?;
 
     // Compile the function body to bytecode
-    let chunk = Compiler::compile_function_body_direct(
+    let mut chunk = Compiler::compile_function_body_direct(
         &func_expr.params,
         &func_expr.body.body,
         Some(JsString::from("anonymous")),
         false, // not a generator
         false, // not async
+        DYNAMIC_SOURCE_FILE.to_string(),
+        interp.compile_limits,
     )
-    .map_err(|e| JsError::syntax_error_simple(format!("Failed to compile function: {}", e)))?;
+    .map_err(|e| match e {
+        JsError::CompileLimit { .. } => e,
+        e => JsError::syntax_error_simple(format!("Failed to compile function: {}", e)),
+    })?;
+
+    // Report the synthesized `function anonymous(...) { ... }` text from toString
+    if let Some(info) = chunk.function_info.as_mut() {
+        info.source_text = program
+            .source
+            .clone()
+            .and_then(|src| FunctionSource::new(src, func_expr.span.start, func_expr.span.end));
+    }
+
+    chunk.intern_strings(&mut interp.string_dict);
+    interp.dump_chunk(&chunk);
 
     // Create bytecode function with global scope as closure
     // This is what makes Function() different from regular function declarations
@@ -210,6 +307,9 @@ fn function_constructor_fn(
     Ok(Guarded::with_guard(JsValue::Object(func_obj), guard))
 }
 
+/// Source file reported in stack traces for functions built from strings
+const DYNAMIC_SOURCE_FILE: &str = "<dynamic>";
+
 /// Convert a JsValue to a string for parameter/body parsing
 fn js_value_to_string(value: &JsValue) -> String {
     match value {
@@ -307,21 +407,13 @@ pub fn function_apply(
     let this_arg = args.first().cloned().unwrap_or(JsValue::Undefined);
     let args_array = args.get(1).cloned().unwrap_or(JsValue::Undefined);
 
-    let call_args: Vec<JsValue> = match args_array {
-        JsValue::Object(arr_ref) => {
-            let arr = arr_ref.borrow();
-            if let Some(elements) = arr.array_elements() {
-                elements.to_vec()
-            } else {
-                vec![]
-            }
-        }
+    let call_args = match args_array {
         JsValue::Undefined | JsValue::Null => vec![],
-        _ => {
-            return Err(JsError::type_error(
-                "Second argument to apply must be an array",
-            ));
-        }
+        _ => super::array::create_list_from_array_like(
+            interp,
+            &args_array,
+            "Second argument to apply",
+        )?,
     };
 
     // Propagate the Guarded from call_function
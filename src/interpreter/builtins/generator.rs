@@ -1,13 +1,14 @@
 //! Generator built-in methods
 
-use crate::prelude::{Box, Rc, RefCell};
+use crate::prelude::{Box, Rc, RefCell, format, mem};
 
 use crate::error::JsError;
 use crate::gc::{Gc, Guard};
 use crate::interpreter::Interpreter;
 use crate::value::{
-    BytecodeGeneratorState, CheapClone, ExoticObject, GeneratorStatus, Guarded, JsObject, JsString,
-    JsSymbol, JsValue, PropertyKey,
+    AsyncGeneratorRequest, AsyncGeneratorRequestKind, BytecodeGeneratorState, CheapClone,
+    ExoticObject, GeneratorStatus, Guarded, JsFunction, JsObject, JsString, JsSymbol, JsValue,
+    PropertyKey,
 };
 
 /// Initialize Generator.prototype
@@ -91,152 +92,53 @@ pub fn create_generator_result(interp: &mut Interpreter, value: JsValue, done: b
     Guarded::with_guard(JsValue::Object(obj), guard)
 }
 
+/// The generator object and state behind `this` of a generator method
+fn this_generator(
+    this: &JsValue,
+    method: &str,
+) -> Result<(Gc<JsObject>, Rc<RefCell<BytecodeGeneratorState>>), JsError> {
+    let JsValue::Object(obj) = this else {
+        return Err(JsError::type_error(format!(
+            "Generator.prototype.{} called on non-object",
+            method
+        )));
+    };
+    let state = match &obj.borrow().exotic {
+        ExoticObject::BytecodeGenerator(state) => state.clone(),
+        _ => {
+            return Err(JsError::type_error(format!(
+                "Generator.prototype.{} called on non-generator",
+                method
+            )));
+        }
+    };
+    Ok((obj.cheap_clone(), state))
+}
+
+/// Result of a sync generator step; only async generators can park on an await
+fn sync_result(result: Option<Guarded>) -> Result<Guarded, JsError> {
+    result.ok_or_else(|| JsError::internal_error("Unexpected suspension in generator"))
+}
+
 /// Generator.prototype.next(value)
 pub fn generator_next(
     interp: &mut Interpreter,
     this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let JsValue::Object(obj) = this else {
-        return Err(JsError::type_error(
-            "Generator.prototype.next called on non-object",
-        ));
-    };
-
-    // Get the bytecode generator state
-    let obj_ref = obj.borrow();
-    match &obj_ref.exotic {
-        ExoticObject::BytecodeGenerator(state) => {
-            let gen_state = state.clone();
-            let is_async = gen_state.borrow().is_async;
-            drop(obj_ref); // Release borrow before calling resume_bytecode_generator
-
-            // Check if generator is already completed
-            {
-                let state = gen_state.borrow();
-                if state.status == GeneratorStatus::Completed {
-                    let result = create_generator_result(interp, JsValue::Undefined, true);
-                    return if is_async {
-                        wrap_in_fulfilled_promise(interp, result)
-                    } else {
-                        Ok(result)
-                    };
-                }
-            }
-
-            // Check if we're delegating to another iterator (yield*)
-            let delegated = {
-                let state = gen_state.borrow();
-                state.delegated_iterator.clone()
-            };
-
-            if let Some((iter_obj, next_method)) = delegated {
-                // Guard the iterator object during the call to prevent GC collection
-                let iter_guard = interp.heap.create_guard();
-                iter_guard.guard(iter_obj.cheap_clone());
-
-                // Forward next() to the delegated iterator
-                let sent_value = args.first().cloned().unwrap_or(JsValue::Undefined);
-                let result = interp.call_function(
-                    next_method.clone(),
-                    JsValue::Object(iter_obj.cheap_clone()),
-                    &[sent_value],
-                )?;
-                // Keep guard alive until after call_function returns
-                let _ = iter_guard;
-
-                // For async generators, the delegated iterator might also be async.
-                // In that case, result.value is a Promise. We need to check for this
-                // and return a Promise that resolves to the proper iterator result.
-                if is_async {
-                    if let JsValue::Object(result_obj) = &result.value {
-                        if matches!(result_obj.borrow().exotic, ExoticObject::Promise(_)) {
-                            // The delegated iterator returned a Promise.
-                            // We need to wrap this in handling that:
-                            // 1. Awaits the promise
-                            // 2. Checks if done
-                            // 3. Either yields the value or resumes the outer generator
-                            //
-                            // For now, we return a Promise that resolves to the inner result
-                            // and handle the done/value logic in continuation.
-                            // This is complex, so we use a simpler approach:
-                            // Store that we need to await and handle on next .next() call
-                            //
-                            // Actually, the cleanest fix is to return the inner promise
-                            // wrapped with .then() to handle the done check.
-                            // But that requires promise chaining infrastructure.
-                            //
-                            // Simplest fix: await the promise now using resolve_promise_sync
-                            let promise_result =
-                                super::promise::resolve_promise_sync(interp, result_obj)?;
-                            let (value, done) = interp.extract_iterator_result(&promise_result);
-
-                            if done {
-                                // Clear delegation and resume outer generator with the return value
-                                gen_state.borrow_mut().delegated_iterator = None;
-                                gen_state.borrow_mut().sent_value = value;
-                                let result = interp.resume_bytecode_generator(&gen_state)?;
-                                wrap_in_fulfilled_promise(interp, result)
-                            } else {
-                                // Yield the delegated value
-                                let result = create_generator_result(interp, value, false);
-                                wrap_in_fulfilled_promise(interp, result)
-                            }
-                        } else {
-                            // Result is not a Promise, extract value/done directly
-                            let (value, done) = interp.extract_iterator_result(&result.value);
-
-                            if done {
-                                gen_state.borrow_mut().delegated_iterator = None;
-                                gen_state.borrow_mut().sent_value = value;
-                                let result = interp.resume_bytecode_generator(&gen_state)?;
-                                wrap_in_fulfilled_promise(interp, result)
-                            } else {
-                                let result = create_generator_result(interp, value, false);
-                                wrap_in_fulfilled_promise(interp, result)
-                            }
-                        }
-                    } else {
-                        // Result is not an object, treat as done
-                        gen_state.borrow_mut().delegated_iterator = None;
-                        gen_state.borrow_mut().sent_value = JsValue::Undefined;
-                        let result = interp.resume_bytecode_generator(&gen_state)?;
-                        wrap_in_fulfilled_promise(interp, result)
-                    }
-                } else {
-                    // Sync generator - extract value/done directly
-                    let (value, done) = interp.extract_iterator_result(&result.value);
-
-                    if done {
-                        // Clear delegation and resume outer generator with the return value
-                        gen_state.borrow_mut().delegated_iterator = None;
-                        gen_state.borrow_mut().sent_value = value;
-                        interp.resume_bytecode_generator(&gen_state)
-                    } else {
-                        // Yield the delegated value
-                        Ok(create_generator_result(interp, value, false))
-                    }
-                }
-            } else {
-                // Set the sent value
-                {
-                    let mut state = gen_state.borrow_mut();
-                    state.sent_value = args.first().cloned().unwrap_or(JsValue::Undefined);
-                }
-
-                // Resume the bytecode generator
-                let result = interp.resume_bytecode_generator(&gen_state)?;
-                if is_async {
-                    wrap_in_fulfilled_promise(interp, result)
-                } else {
-                    Ok(result)
-                }
-            }
-        }
-        _ => Err(JsError::type_error(
-            "Generator.prototype.next called on non-generator",
-        )),
+    let (obj, gen_state) = this_generator(&this, "next")?;
+    let sent_value = args.first().cloned().unwrap_or(JsValue::Undefined);
+
+    if gen_state.borrow().is_async {
+        return async_generator_enqueue(
+            interp,
+            &obj,
+            &gen_state,
+            AsyncGeneratorRequestKind::Next,
+            sent_value,
+        );
     }
+    sync_result(generator_resume_next(interp, &gen_state, sent_value)?)
 }
 
 /// Generator.prototype.return(value)
@@ -245,32 +147,19 @@ pub fn generator_return(
     this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let JsValue::Object(obj) = this else {
-        return Err(JsError::type_error(
-            "Generator.prototype.return called on non-object",
-        ));
-    };
-
+    let (obj, gen_state) = this_generator(&this, "return")?;
     let value = args.first().cloned().unwrap_or(JsValue::Undefined);
 
-    // Get the bytecode generator state
-    let obj_ref = obj.borrow();
-    match &obj_ref.exotic {
-        ExoticObject::BytecodeGenerator(state) => {
-            let is_async = state.borrow().is_async;
-            state.borrow_mut().status = GeneratorStatus::Completed;
-            drop(obj_ref);
-            let result = create_generator_result(interp, value, true);
-            if is_async {
-                wrap_in_fulfilled_promise(interp, result)
-            } else {
-                Ok(result)
-            }
-        }
-        _ => Err(JsError::type_error(
-            "Generator.prototype.return called on non-generator",
-        )),
+    if gen_state.borrow().is_async {
+        return async_generator_enqueue(
+            interp,
+            &obj,
+            &gen_state,
+            AsyncGeneratorRequestKind::Return,
+            value,
+        );
     }
+    sync_result(generator_resume_return(interp, &gen_state, value)?)
 }
 
 /// Generator.prototype.throw(exception)
@@ -279,56 +168,311 @@ pub fn generator_throw(
     this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let JsValue::Object(obj) = this else {
-        return Err(JsError::type_error(
-            "Generator.prototype.throw called on non-object",
-        ));
+    let (obj, gen_state) = this_generator(&this, "throw")?;
+    let exception = args.first().cloned().unwrap_or(JsValue::Undefined);
+
+    if gen_state.borrow().is_async {
+        return async_generator_enqueue(
+            interp,
+            &obj,
+            &gen_state,
+            AsyncGeneratorRequestKind::Throw,
+            exception,
+        );
+    }
+    sync_result(generator_resume_throw(interp, &gen_state, exception)?)
+}
+
+/// Resume a generator for next(value), forwarding to a yield* delegate if there is one
+fn generator_resume_next(
+    interp: &mut Interpreter,
+    gen_state: &Rc<RefCell<BytecodeGeneratorState>>,
+    sent_value: JsValue,
+) -> Result<Option<Guarded>, JsError> {
+    let is_async = gen_state.borrow().is_async;
+
+    // Check if generator is already completed
+    if gen_state.borrow().status == GeneratorStatus::Completed {
+        return Ok(Some(create_generator_result(
+            interp,
+            JsValue::Undefined,
+            true,
+        )));
+    }
+
+    // Check if we're delegating to another iterator (yield*)
+    let delegated = gen_state.borrow().delegated_iterator.clone();
+
+    let Some((iter_obj, next_method)) = delegated else {
+        // Set the sent value and resume the bytecode generator
+        gen_state.borrow_mut().sent_value = sent_value;
+        return interp.resume_bytecode_generator(gen_state);
     };
 
-    let exception = args.first().cloned().unwrap_or(JsValue::Undefined);
+    // Guard the iterator object during the call to prevent GC collection
+    let iter_guard = interp.heap.create_guard();
+    iter_guard.guard(iter_obj.cheap_clone());
+
+    // Forward next() to the delegated iterator
+    let result = interp.call_function(
+        next_method.clone(),
+        JsValue::Object(iter_obj.cheap_clone()),
+        &[sent_value],
+    )?;
+    // Keep guard alive until after call_function returns
+    let _ = iter_guard;
+
+    // For async generators, the delegated iterator might also be async, in
+    // which case the result is a Promise of the iterator result.
+    let result_value = match &result.value {
+        JsValue::Object(result_obj)
+            if is_async && matches!(result_obj.borrow().exotic, ExoticObject::Promise(_)) =>
+        {
+            super::promise::resolve_promise_sync(interp, result_obj)?
+        }
+        // A non-object result reads as done
+        _ => result.value.clone(),
+    };
+    let (value, done) = interp.extract_iterator_result(&result_value);
+
+    if done {
+        // Clear delegation and resume outer generator with the return value
+        gen_state.borrow_mut().delegated_iterator = None;
+        gen_state.borrow_mut().sent_value = value;
+        interp.resume_bytecode_generator(gen_state)
+    } else {
+        // Yield the delegated value
+        Ok(Some(create_generator_result(interp, value, false)))
+    }
+}
 
-    // Get the bytecode generator state
-    let obj_ref = obj.borrow();
-    match &obj_ref.exotic {
-        ExoticObject::BytecodeGenerator(state) => {
-            let gen_state = state.clone();
-            let is_async = gen_state.borrow().is_async;
-            drop(obj_ref); // Release borrow before resuming
-
-            // Check if generator is completed or not started
-            {
-                let state_ref = gen_state.borrow();
-                if state_ref.status == GeneratorStatus::Completed {
-                    let guarded = Guarded::from_value(exception, &interp.heap);
-                    return Err(JsError::ThrownValue { guarded });
-                }
-                if !state_ref.started {
-                    // Generator hasn't started, just throw
-                    drop(state_ref);
-                    gen_state.borrow_mut().status = GeneratorStatus::Completed;
-                    let guarded = Guarded::from_value(exception, &interp.heap);
-                    return Err(JsError::ThrownValue { guarded });
-                }
-            }
+/// Resume a generator for return(value), running finally blocks around the
+/// current yield before it completes
+fn generator_resume_return(
+    interp: &mut Interpreter,
+    gen_state: &Rc<RefCell<BytecodeGeneratorState>>,
+    value: JsValue,
+) -> Result<Option<Guarded>, JsError> {
+    let not_running = {
+        let state = gen_state.borrow();
+        state.status == GeneratorStatus::Completed || !state.started
+    };
+    if not_running {
+        gen_state.borrow_mut().complete();
+        return Ok(Some(create_generator_result(interp, value, true)));
+    }
+
+    // Close a yield* delegate first, as a `return` through the yield* would
+    let delegated = gen_state.borrow_mut().delegated_iterator.take();
+    if let Some((iter_obj, _)) = delegated {
+        let iter_guard = interp.heap.create_guard();
+        iter_guard.guard(iter_obj.cheap_clone());
+        let return_key = interp.property_key("return");
+        let return_method = iter_obj.borrow().get_property(&return_key);
+        if let Some(method) = return_method.filter(|m| m.is_callable()) {
+            interp.call_function(
+                method,
+                JsValue::Object(iter_obj.cheap_clone()),
+                core::slice::from_ref(&value),
+            )?;
+        }
+    }
+
+    gen_state.borrow_mut().return_value = Some(value);
+    interp.resume_bytecode_generator(gen_state)
+}
+
+/// Resume a generator for throw(exception), raising it at the current yield
+fn generator_resume_throw(
+    interp: &mut Interpreter,
+    gen_state: &Rc<RefCell<BytecodeGeneratorState>>,
+    exception: JsValue,
+) -> Result<Option<Guarded>, JsError> {
+    // Check if generator is completed or not started
+    {
+        let state_ref = gen_state.borrow();
+        if state_ref.status == GeneratorStatus::Completed {
+            let guarded = Guarded::from_value(exception, &interp.heap);
+            return Err(JsError::ThrownValue { guarded });
+        }
+        if !state_ref.started {
+            // Generator hasn't started, just throw
+            drop(state_ref);
+            gen_state.borrow_mut().complete();
+            let guarded = Guarded::from_value(exception, &interp.heap);
+            return Err(JsError::ThrownValue { guarded });
+        }
+    }
 
-            // Set the throw value and resume the generator
-            // The generator will throw this exception at the current yield point
-            gen_state.borrow_mut().throw_value = Some(exception);
+    // Set the throw value and resume the generator
+    // The generator will throw this exception at the current yield point
+    gen_state.borrow_mut().throw_value = Some(exception);
+    interp.resume_bytecode_generator(gen_state)
+}
+
+/// Queue a next/throw/return call on an async generator and return the
+/// promise for its result.
+///
+/// Requests run one at a time in call order, so a second next() issued while
+/// the body awaits waits its turn instead of resuming the body again.
+fn async_generator_enqueue(
+    interp: &mut Interpreter,
+    generator: &Gc<JsObject>,
+    gen_state: &Rc<RefCell<BytecodeGeneratorState>>,
+    kind: AsyncGeneratorRequestKind,
+    value: JsValue,
+) -> Result<Guarded, JsError> {
+    let guard = interp.heap.create_guard();
+    let promise = super::promise::create_promise(interp, &guard);
+    gen_state
+        .borrow_mut()
+        .queue
+        .push_back(AsyncGeneratorRequest {
+            kind,
+            value,
+            promise: promise.cheap_clone(),
+        });
+
+    async_generator_drain(interp, generator, gen_state)?;
+    Ok(Guarded::with_guard(JsValue::Object(promise), guard))
+}
 
-            // Resume the generator - it will throw the exception inside
-            let result = interp.resume_bytecode_generator(&gen_state)?;
-            if is_async {
-                wrap_in_fulfilled_promise(interp, result)
-            } else {
-                Ok(result)
+/// Run queued requests until the queue is empty or the body awaits
+fn async_generator_drain(
+    interp: &mut Interpreter,
+    generator: &Gc<JsObject>,
+    gen_state: &Rc<RefCell<BytecodeGeneratorState>>,
+) -> Result<(), JsError> {
+    loop {
+        let (kind, value) = {
+            let state = gen_state.borrow();
+            if state.running {
+                return Ok(());
             }
+            match state.queue.front() {
+                Some(request) => (request.kind, request.value.clone()),
+                None => return Ok(()),
+            }
+        };
+
+        gen_state.borrow_mut().running = true;
+        let outcome = match kind {
+            AsyncGeneratorRequestKind::Next => generator_resume_next(interp, gen_state, value),
+            AsyncGeneratorRequestKind::Throw => generator_resume_throw(interp, gen_state, value),
+            AsyncGeneratorRequestKind::Return => generator_resume_return(interp, gen_state, value),
+        };
+        if !async_generator_settle(interp, generator, gen_state, outcome)? {
+            return Ok(());
         }
-        _ => Err(JsError::type_error(
-            "Generator.prototype.throw called on non-generator",
-        )),
     }
 }
 
+/// Settle the oldest request with the outcome of running the body.
+///
+/// Returns false if the body is awaiting instead, after subscribing the
+/// generator to the awaited promise.
+fn async_generator_settle(
+    interp: &mut Interpreter,
+    generator: &Gc<JsObject>,
+    gen_state: &Rc<RefCell<BytecodeGeneratorState>>,
+    outcome: Result<Option<Guarded>, JsError>,
+) -> Result<bool, JsError> {
+    let outcome = match outcome {
+        Ok(Some(result)) => Ok(result),
+        Ok(None) => {
+            async_generator_await(interp, generator, gen_state)?;
+            return Ok(false);
+        }
+        Err(e) if e.is_termination() => return Err(e),
+        Err(e) => Err(e.to_value()),
+    };
+
+    let request = {
+        let mut state = gen_state.borrow_mut();
+        state.running = false;
+        state.queue.pop_front()
+    };
+    let Some(request) = request else {
+        return Ok(true);
+    };
+
+    // The request left the traced queue; keep its promise alive while settling
+    let guard = interp.heap.create_guard();
+    guard.guard(request.promise.cheap_clone());
+    match outcome {
+        Ok(result) => {
+            super::promise::resolve_promise_value(interp, &request.promise, result.value)?;
+        }
+        Err(reason) => {
+            super::promise::reject_promise_value(interp, &request.promise, reason)?;
+        }
+    }
+    Ok(true)
+}
+
+/// Subscribe a parked async generator to the promise its body awaits
+fn async_generator_await(
+    interp: &mut Interpreter,
+    generator: &Gc<JsObject>,
+    gen_state: &Rc<RefCell<BytecodeGeneratorState>>,
+) -> Result<(), JsError> {
+    let waiting_on = gen_state
+        .borrow()
+        .suspended_await
+        .as_ref()
+        .map(|awaiting| awaiting.waiting_on.cheap_clone());
+    let Some(promise) = waiting_on else {
+        return Err(JsError::internal_error("Async generator is not awaiting"));
+    };
+
+    let guard = interp.heap.create_guard();
+    let on_fulfilled = interp.create_js_function(
+        &guard,
+        JsFunction::AsyncGeneratorResume {
+            generator: generator.cheap_clone(),
+            is_fulfill: true,
+        },
+    );
+    let on_rejected = interp.create_js_function(
+        &guard,
+        JsFunction::AsyncGeneratorResume {
+            generator: generator.cheap_clone(),
+            is_fulfill: false,
+        },
+    );
+    super::promise::promise_then(
+        interp,
+        JsValue::Object(promise),
+        &[JsValue::Object(on_fulfilled), JsValue::Object(on_rejected)],
+    )?;
+    Ok(())
+}
+
+/// Continue an async generator once the promise its body awaits settles,
+/// then run any requests queued behind the current one
+pub fn async_generator_resume(
+    interp: &mut Interpreter,
+    generator: &Gc<JsObject>,
+    value: JsValue,
+    is_fulfill: bool,
+) -> Result<(), JsError> {
+    let gen_state = match &generator.borrow().exotic {
+        ExoticObject::BytecodeGenerator(state) => state.clone(),
+        _ => return Err(JsError::internal_error("Not an async generator")),
+    };
+
+    // A body whose results nothing observes any more, such as the losing
+    // branch of a settled race, gets its new orders cancelled up front
+    let unobserved = super::promise::is_generator_result_ignored(interp, generator);
+    let outer = mem::replace(&mut interp.orders_unobserved, unobserved);
+    let outcome = interp.resume_async_generator_await(&gen_state, value, is_fulfill);
+    interp.orders_unobserved = outer;
+    if async_generator_settle(interp, generator, &gen_state, outcome)? {
+        async_generator_drain(interp, generator, &gen_state)?;
+    }
+    Ok(())
+}
+
 /// Create a new bytecode generator object
 pub fn create_bytecode_generator_object(
     interp: &mut Interpreter,
@@ -343,21 +487,3 @@ pub fn create_bytecode_generator_object(
     }
     obj
 }
-
-/// Wrap a generator result (Guarded) in a fulfilled Promise.
-/// Used by async generators to return Promise<{value, done}>.
-fn wrap_in_fulfilled_promise(
-    interp: &mut Interpreter,
-    result: Guarded,
-) -> Result<Guarded, JsError> {
-    use super::promise::create_fulfilled_promise;
-
-    let guard = interp.heap.create_guard();
-    // Guard the result value before allocating the promise
-    if let JsValue::Object(ref obj) = result.value {
-        guard.guard(obj.cheap_clone());
-    }
-
-    let promise = create_fulfilled_promise(interp, &guard, result.value);
-    Ok(Guarded::with_guard(JsValue::Object(promise), guard))
-}
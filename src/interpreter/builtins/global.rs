@@ -3,12 +3,13 @@
 use crate::error::JsError;
 use crate::gc::{Gc, Guard};
 use crate::interpreter::Interpreter;
-use crate::parser::Parser;
+use crate::interpreter::env_scope::EnvScope;
 use crate::prelude::{
-    String, ToString, Vec, format, index_map_with_capacity, index_set_with_capacity,
+    String, ToString, Vec, format, index_map_with_capacity, index_set_with_capacity, math,
 };
 use crate::value::{
-    CheapClone, ExoticObject, Guarded, JsMapKey, JsObject, JsString, JsValue, Property, PropertyKey,
+    CheapClone, ExoticObject, Guarded, JsMapKey, JsObject, JsString, JsValue, Property,
+    PropertyKey, trim_js_whitespace,
 };
 
 /// Register global functions (parseInt, parseFloat, isNaN, isFinite, URI functions)
@@ -195,6 +196,8 @@ pub fn global_eval(
         _ => return Ok(Guarded::unguarded(arg.clone())),
     };
 
+    interp.check_dynamic_code("Indirect eval")?;
+
     // Execute the code in global scope (indirect eval behavior)
     eval_code_in_scope(interp, &code, true)
 }
@@ -227,9 +230,8 @@ pub fn eval_code_in_scope_with_this(
     }
 
     // Parse the code
-    let mut parser = Parser::new(code, &mut interp.string_dict);
-    let program = parser
-        .parse_program()
+    let program = interp
+        .parse_program(code)
         .map_err(|e| JsError::syntax_error_simple(format!("eval: {}", e)))?;
 
     // Save current environment
@@ -243,14 +245,12 @@ pub fn eval_code_in_scope_with_this(
     // In strict mode, eval code runs in its own lexical environment
     // This ensures let/const declarations don't leak to the outer scope
     // but can still read from it via the scope chain
-    let eval_scope = interp.push_scope();
-
-    // Execute the program with completion value tracking for proper eval semantics
-    // Note: Var hoisting is handled by the bytecode compiler in compile_program_for_eval
-    let result = interp.execute_program_for_eval_with_this(&program, this_value);
-
-    // Pop the eval scope
-    interp.pop_scope(eval_scope);
+    let result = {
+        let mut eval_scope = EnvScope::nested(interp);
+        // Execute the program with completion value tracking for proper eval semantics
+        // Note: Var hoisting is handled by the bytecode compiler in compile_program_for_eval
+        eval_scope.execute_program_for_eval_with_this(&program, this_value)
+    };
 
     // Restore original environment (needed for indirect eval case)
     interp.env = saved_env;
@@ -275,20 +275,13 @@ pub fn global_parse_int(
         None => interp.intern(""),
     };
     let string = string.as_str().to_string();
-    let radix = args.get(1).map(|v| v.to_number() as i32).unwrap_or(10);
-
-    // Trim whitespace
-    let s = string.trim();
+    let radix = args
+        .get(1)
+        .map(|v| crate::value::to_int32(v.to_number()))
+        .unwrap_or(0);
 
-    if s.is_empty() {
-        return Ok(Guarded::unguarded(JsValue::Number(f64::NAN)));
-    }
-
-    // Handle radix
-    let radix = if radix == 0 { 10 } else { radix };
-    if !(2..=36).contains(&radix) {
-        return Ok(Guarded::unguarded(JsValue::Number(f64::NAN)));
-    }
+    // Only the whitespace JS recognizes: U+FEFF is skipped, U+0085 is not
+    let s = trim_js_whitespace(&string);
 
     // Handle sign
     let (negative, s) = if let Some(rest) = s.strip_prefix('-') {
@@ -299,34 +292,96 @@ pub fn global_parse_int(
         (false, s)
     };
 
-    // Handle hex prefix for radix 16
-    let s = if radix == 16 {
-        s.strip_prefix("0x")
-            .or_else(|| s.strip_prefix("0X"))
-            .unwrap_or(s)
-    } else {
-        s
+    // A 0x prefix means hex unless another radix is given
+    let hex = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X"));
+    let (radix, s) = match (radix, hex) {
+        (0 | 16, Some(rest)) => (16, rest),
+        (0, None) => (10, s),
+        _ => (radix, s),
     };
+    if !(2..=36).contains(&radix) {
+        return Ok(Guarded::unguarded(JsValue::Number(f64::NAN)));
+    }
+    let radix = radix as u32;
+
+    // Digits up to the first character that isn't one
+    let end = s
+        .char_indices()
+        .find(|(_, c)| !c.is_digit(radix))
+        .map_or(s.len(), |(pos, _)| pos);
+    let digits = s.get(..end).unwrap_or_default();
+    if digits.is_empty() {
+        return Ok(Guarded::unguarded(JsValue::Number(f64::NAN)));
+    }
 
-    // Parse digits until invalid character
-    let mut result: i64 = 0;
-    let mut found_digit = false;
+    let result = parse_int_digits(digits, radix);
+    Ok(Guarded::unguarded(JsValue::Number(if negative {
+        -result
+    } else {
+        result
+    })))
+}
 
-    for c in s.chars() {
-        let digit = match c.to_digit(radix as u32) {
-            Some(d) => d as i64,
-            None => break,
-        };
-        found_digit = true;
-        result = result * (radix as i64) + digit;
+/// The value of a non-empty run of ASCII `digits` in `radix`.
+///
+/// Exact or correctly rounded for radix 10 and powers of two; other radices
+/// accumulate in 32-bit chunks as V8 does, which the spec allows past 20
+/// significant digits.
+fn parse_int_digits(digits: &str, radix: u32) -> f64 {
+    if radix == 10 {
+        return digits.parse().unwrap_or(f64::NAN);
+    }
+    let values = digits.chars().filter_map(|c| c.to_digit(radix));
+    if radix.is_power_of_two() {
+        return parse_power_of_two_digits(values, radix.trailing_zeros());
     }
 
-    if !found_digit {
-        return Ok(Guarded::unguarded(JsValue::Number(f64::NAN)));
+    const MAX_MULTIPLIER: u32 = u32::MAX / 36;
+    let mut result = 0.0;
+    let (mut part, mut multiplier) = (0u32, 1u32);
+    for digit in values {
+        let next = multiplier * radix;
+        if next > MAX_MULTIPLIER {
+            result = result * f64::from(multiplier) + f64::from(part);
+            (part, multiplier) = (0, 1);
+        }
+        part = part * radix + digit;
+        multiplier *= radix;
     }
+    result * f64::from(multiplier) + f64::from(part)
+}
+
+/// Digits in radix `2^bits`, rounded to the nearest double, ties to even
+fn parse_power_of_two_digits(mut digits: impl Iterator<Item = u32>, bits: u32) -> f64 {
+    let mut number: u64 = 0;
+    for digit in digits.by_ref() {
+        number = number << bits | u64::from(digit);
+        let overflow = number >> 53;
+        if overflow == 0 {
+            continue;
+        }
 
-    let result = if negative { -result } else { result };
-    Ok(Guarded::unguarded(JsValue::Number(result as f64)))
+        // Past 53 bits: round off the extra bits and count the rest
+        let dropped_count = 64 - overflow.leading_zeros();
+        let dropped = number & ((1 << dropped_count) - 1);
+        number >>= dropped_count;
+        let mut exponent = dropped_count as i32;
+        let mut zero_tail = true;
+        for digit in digits {
+            zero_tail &= digit == 0;
+            exponent += bits as i32;
+        }
+        let half = 1 << (dropped_count - 1);
+        if dropped > half || (dropped == half && (number & 1 != 0 || !zero_tail)) {
+            number += 1;
+        }
+        if number >> 53 != 0 {
+            number >>= 1;
+            exponent += 1;
+        }
+        return number as f64 * math::powf(2.0, f64::from(exponent));
+    }
+    number as f64
 }
 
 pub fn global_parse_float(
@@ -339,74 +394,85 @@ pub fn global_parse_float(
         None => interp.intern(""),
     };
     let string = string.as_str().to_string();
-    let s = string.trim();
+    let s = trim_js_whitespace(&string);
 
     if s.is_empty() {
         return Ok(Guarded::unguarded(JsValue::Number(f64::NAN)));
     }
 
-    // Find the longest valid float prefix
-    let mut num_str = String::new();
-    let mut has_dot = false;
-    let mut has_exp = false;
-    let mut chars = s.chars().peekable();
-
-    // Handle sign
-    if matches!(chars.peek(), Some('-') | Some('+'))
-        && let Some(c) = chars.next()
-    {
-        num_str.push(c);
+    let (sign, unsigned) = match s.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, s.strip_prefix('+').unwrap_or(s)),
+    };
+    if unsigned.starts_with("Infinity") {
+        return Ok(Guarded::unguarded(JsValue::Number(sign * f64::INFINITY)));
     }
 
-    // Parse digits and decimal point
-    while let Some(&c) = chars.peek() {
+    // Find the longest valid float prefix; an exponent without digits
+    // (`1e`, `1e+`) is not part of it
+    let mut end = 0;
+    let mut has_digits = false;
+    let mut has_dot = false;
+    let mut chars = unsigned.char_indices().peekable();
+    while let Some(&(pos, c)) = chars.peek() {
         match c {
             '0'..='9' => {
-                num_str.push(c);
+                has_digits = true;
+                end = pos + 1;
                 chars.next();
             }
-            '.' if !has_dot && !has_exp => {
+            '.' if !has_dot => {
                 has_dot = true;
-                num_str.push(c);
                 chars.next();
             }
-            'e' | 'E' if !has_exp => {
-                has_exp = true;
-                num_str.push(c);
+            'e' | 'E' if has_digits => {
                 chars.next();
-                // Optional sign after exponent
-                if matches!(chars.peek(), Some('-') | Some('+'))
-                    && let Some(sign) = chars.next()
-                {
-                    num_str.push(sign);
+                if let Some(&(_, '-' | '+')) = chars.peek() {
+                    chars.next();
+                }
+                while let Some(&(pos, '0'..='9')) = chars.peek() {
+                    end = pos + 1;
+                    chars.next();
                 }
+                break;
             }
             _ => break,
         }
     }
-    match num_str.parse::<f64>() {
-        Ok(n) => Ok(Guarded::unguarded(JsValue::Number(n))),
-        Err(_) => Ok(Guarded::unguarded(JsValue::Number(f64::NAN))),
+
+    if !has_digits {
+        return Ok(Guarded::unguarded(JsValue::Number(f64::NAN)));
     }
+    let n = unsigned
+        .get(..end)
+        .and_then(|prefix| prefix.parse::<f64>().ok())
+        .unwrap_or(f64::NAN);
+    Ok(Guarded::unguarded(JsValue::Number(sign * n)))
 }
 
 // Global isNaN - converts argument to number first
 pub fn global_is_nan(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Boolean(n.is_nan())))
 }
 
 // Global isFinite - converts argument to number first
 pub fn global_is_finite(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Boolean(n.is_finite())))
 }
 
@@ -448,7 +514,7 @@ pub fn global_decode_uri(
         Some(v) => interp.to_js_string(v),
         None => interp.intern(""),
     };
-    let result = percent_decode(s.as_str(), true);
+    let result = percent_decode(s.as_str(), true)?;
     Ok(Guarded::unguarded(JsValue::String(JsString::from(result))))
 }
 
@@ -485,40 +551,77 @@ pub fn global_decode_uri_component(
         Some(v) => interp.to_js_string(v),
         None => interp.intern(""),
     };
-    let result = percent_decode(s.as_str(), false);
+    let result = percent_decode(s.as_str(), false)?;
     Ok(Guarded::unguarded(JsValue::String(JsString::from(result))))
 }
 
-fn percent_decode(s: &str, preserve_reserved: bool) -> String {
-    let mut result = String::new();
-    let mut chars = s.chars().peekable();
-    while let Some(c) = chars.next() {
-        if c == '%' {
-            // Try to read two hex digits
-            let hex: String = chars.by_ref().take(2).collect();
-            if hex.len() == 2 {
-                if let Ok(byte) = u8::from_str_radix(&hex, 16) {
-                    let decoded = byte as char;
-                    // For decodeURI, don't decode reserved characters
-                    if preserve_reserved && URI_RESERVED.contains(decoded) {
-                        result.push('%');
-                        result.push_str(&hex);
-                    } else {
-                        result.push(decoded);
-                    }
-                } else {
-                    result.push('%');
-                    result.push_str(&hex);
-                }
+fn uri_error(message: &str) -> JsError {
+    JsError::RuntimeError {
+        kind: "URIError".to_string(),
+        message: message.to_string(),
+        stack: Vec::new(),
+        properties: None,
+        flight_log: None,
+    }
+}
+
+/// Read the two hex digits following a '%' at `pos`
+fn hex_byte_at(bytes: &[u8], pos: usize) -> Option<u8> {
+    let hi = (*bytes.get(pos + 1)? as char).to_digit(16)?;
+    let lo = (*bytes.get(pos + 2)? as char).to_digit(16)?;
+    Some((hi * 16 + lo) as u8)
+}
+
+/// Decode percent-escapes as UTF-8 byte sequences (ECMAScript Decode).
+/// With `preserve_reserved`, escapes of reserved characters and '#' are kept verbatim.
+fn percent_decode(s: &str, preserve_reserved: bool) -> Result<String, JsError> {
+    let bytes = s.as_bytes();
+    let mut result = String::with_capacity(s.len());
+    let mut pos = 0;
+    let mut literal_start = 0;
+    while pos < bytes.len() {
+        if bytes.get(pos) != Some(&b'%') {
+            pos += 1;
+            continue;
+        }
+        result.push_str(s.get(literal_start..pos).unwrap_or_default());
+        let lead = hex_byte_at(bytes, pos).ok_or_else(|| uri_error("URI malformed"))?;
+        if lead < 0x80 {
+            let decoded = lead as char;
+            if preserve_reserved && URI_RESERVED.contains(decoded) {
+                result.push_str(s.get(pos..pos + 3).unwrap_or_default());
             } else {
-                result.push('%');
-                result.push_str(&hex);
+                result.push(decoded);
             }
-        } else {
-            result.push(c);
+            pos += 3;
+            literal_start = pos;
+            continue;
+        }
+
+        // Multi-byte sequence: the lead byte determines how many continuation escapes follow
+        let len = match lead {
+            0xC2..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF4 => 4,
+            _ => return Err(uri_error("URI malformed")),
+        };
+        let mut buf = Vec::with_capacity(len);
+        buf.push(lead);
+        for i in 1..len {
+            let at = pos + i * 3;
+            if bytes.get(at) != Some(&b'%') {
+                return Err(uri_error("URI malformed"));
+            }
+            let byte = hex_byte_at(bytes, at).ok_or_else(|| uri_error("URI malformed"))?;
+            buf.push(byte);
         }
+        let decoded = core::str::from_utf8(&buf).map_err(|_| uri_error("URI malformed"))?;
+        result.push_str(decoded);
+        pos += len * 3;
+        literal_start = pos;
     }
-    result
+    result.push_str(s.get(literal_start..).unwrap_or_default());
+    Ok(result)
 }
 
 // Base64 encoding alphabet
@@ -579,7 +682,7 @@ pub fn global_atob(
     }
 }
 
-fn base64_encode(data: &[u8]) -> String {
+pub(crate) fn base64_encode(data: &[u8]) -> String {
     let mut result = String::new();
     let mut i = 0;
 
@@ -716,12 +819,60 @@ fn structured_clone_internal(
     }
 }
 
+/// Clone an error: the copy gets the prototype of the built-in error type its
+/// `name` names, its own enumerable properties such as `name`, `message`,
+/// `stack` or a `code`, and the non-enumerable `cause` and AggregateError
+/// `errors`, each cloned in turn
+fn clone_error(
+    interp: &mut Interpreter,
+    guard: &Guard<JsObject>,
+    obj: &Gc<JsObject>,
+) -> Result<JsValue, JsError> {
+    let name_key = interp.property_key("name");
+    let cause_key = interp.property_key("cause");
+    let errors_key = interp.property_key("errors");
+
+    let (name, props_to_clone) = {
+        let obj_ref = obj.borrow();
+        let name = match obj_ref.get_property(&name_key) {
+            Some(JsValue::String(name)) => name.to_string(),
+            _ => String::from("Error"),
+        };
+        let props: Vec<(PropertyKey, JsValue, bool)> = obj_ref
+            .properties
+            .iter()
+            .filter(|(k, prop)| {
+                !prop.is_accessor()
+                    && !matches!(k, PropertyKey::Symbol(_))
+                    && (prop.enumerable() || **k == cause_key || **k == errors_key)
+            })
+            .map(|(k, prop)| (k.clone(), prop.value.clone(), prop.enumerable()))
+            .collect();
+        (name, props)
+    };
+
+    let cloned_obj = interp.create_object(guard);
+    cloned_obj.borrow_mut().prototype = Some(super::error::error_prototype_for(interp, &name));
+
+    for (key, value, enumerable) in props_to_clone {
+        let cloned_value = structured_clone_internal(interp, guard, &value)?;
+        cloned_obj.borrow_mut().define_property(
+            key,
+            Property::with_attributes(cloned_value, true, enumerable, true),
+        );
+    }
+
+    Ok(JsValue::Object(cloned_obj))
+}
+
 /// Clone an object (handles arrays, maps, sets, dates, regexps, errors, and plain objects)
 fn clone_object(
     interp: &mut Interpreter,
     guard: &Guard<JsObject>,
     obj: &Gc<JsObject>,
 ) -> Result<JsValue, JsError> {
+    // Each nesting level recurses on the Rust stack
+    let _depth = interp.enter_native()?;
     let obj_ref = obj.borrow();
 
     // Check the exotic type
@@ -933,23 +1084,19 @@ fn clone_object(
 
         // Ordinary objects - clone properties recursively
         ExoticObject::Ordinary => {
+            drop(obj_ref);
+            if super::error::is_error_object(&interp.error_prototype, obj) {
+                return clone_error(interp, guard, obj);
+            }
+
             // Collect properties to clone (extract values from Property wrapper)
-            let props_to_clone: Vec<(PropertyKey, JsValue)> = obj_ref
+            let props_to_clone: Vec<(PropertyKey, JsValue)> = obj
+                .borrow()
                 .properties
                 .iter()
                 .map(|(k, prop)| (k.clone(), prop.value.clone()))
                 .collect();
 
-            // Check if this is an Error object by looking at prototype chain
-            let key_name = interp.property_key("name");
-            let key_message = interp.property_key("message");
-            let key_stack = interp.property_key("stack");
-            let is_error = obj_ref.properties.contains_key(&key_name)
-                && obj_ref.properties.contains_key(&key_message)
-                && obj_ref.properties.contains_key(&key_stack);
-
-            drop(obj_ref);
-
             let cloned_obj = interp.create_object(guard);
 
             // Clone each property
@@ -960,10 +1107,6 @@ fn clone_object(
                     .set_property(key.clone(), cloned_value);
             }
 
-            // For error objects, the properties are already cloned so we don't need
-            // to set a special prototype - object_prototype is sufficient
-            let _ = is_error; // silence unused warning
-
             Ok(JsValue::Object(cloned_obj))
         }
 
@@ -972,6 +1115,21 @@ fn clone_object(
             "Proxy cannot be cloned with structuredClone",
         )),
 
+        // URL objects are platform objects and not serializable
+        ExoticObject::Url(_) | ExoticObject::UrlSearchParams(_) => Err(JsError::type_error(
+            "URL objects cannot be cloned with structuredClone",
+        )),
+
+        // Weak references depend on object identity
+        ExoticObject::WeakRef(_) | ExoticObject::FinalizationRegistry(_) => Err(
+            JsError::type_error("Weak references cannot be cloned with structuredClone"),
+        ),
+
+        // Disposal state belongs to the stack that registered it
+        ExoticObject::DisposableStack(_) => Err(JsError::type_error(
+            "DisposableStack cannot be cloned with structuredClone",
+        )),
+
         // RawJSON - clone the raw JSON string
         ExoticObject::RawJSON(raw) => {
             let raw_clone = raw.cheap_clone();
@@ -1005,5 +1163,10 @@ fn clone_object(
         ExoticObject::PendingOrder { .. } => Err(JsError::type_error(
             "PendingOrder cannot be cloned with structuredClone",
         )),
+
+        // Host data is opaque to the interpreter
+        ExoticObject::Host(_) => Err(JsError::type_error(
+            "Host object cannot be cloned with structuredClone",
+        )),
     }
 }
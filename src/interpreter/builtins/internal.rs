@@ -1,18 +1,262 @@
 //! tsrun:host built-in module
 //!
-//! Provides the core order system functions for async host operations.
+//! The order primitives are native functions in `tsrun:host/primitives`.
+//! `tsrun:host` re-exports them and adds request helpers written in TypeScript
+//! on top: `request`, `requestAll`, `batch`, `defineClient` and their error
+//! classes.
+//! It also exports the duration helpers `parseDuration` and `formatDuration`,
+//! the lenient config parsers `parseJSONC` and `parseJSON5`, and
+//! `evalInSandbox` for running untrusted snippets in isolation, and
+//! `checkpoint` for long loops the host may ask to wind down.
 
+use super::duration::{format_duration_fn, parse_duration_fn};
+use super::jsonc::{parse_json5_fn, parse_jsonc_fn};
 use crate::error::JsError;
 use crate::interpreter::Interpreter;
-use crate::value::{CheapClone, ExoticObject, Guarded, JsValue};
-use crate::{InternalModule, Order, OrderId, RuntimeValue};
+use crate::interpreter::sandbox::SandboxLimits;
+use crate::prelude::{ToString, format};
+use crate::value::{Guarded, JsValue};
+use crate::{InternalModule, OrderId};
+
+/// Specifier of the native module `tsrun:host` is built on
+pub const HOST_PRIMITIVES_SPECIFIER: &str = "tsrun:host/primitives";
+
+/// Request helpers exported by tsrun:host.
+///
+/// `request(type, params, { timeoutMs, signal })` sends `{ ...params, type }`
+/// as an order and returns a Promise. With `timeoutMs`, both the order and a
+/// companion `{ type: "timeout", orderId, delayMs }` order carry the same
+/// `deadline_ms`; the host fulfills the timeout order once the deadline has
+/// passed, which rejects the request with a `TimeoutError` and cancels the
+/// original order. Whichever order loses is cancelled. `signal` is any object
+/// with `aborted`, `reason` and `addEventListener("abort", ...)`.
+const HOST_SOURCE: &str = r#"
+import {
+    order, __cancelOrder__, __getOrderId__, __beginBatch__, __endBatch__,
+    __parseDuration__, __formatDuration__, __parseJSONC__, __parseJSON5__, __evalInSandbox__,
+    __cancellationRequested__, __cancellationReason__,
+} from "tsrun:host/primitives";
+export { order, __cancelOrder__, __getOrderId__ } from "tsrun:host/primitives";
+
+export interface AbortSignalLike {
+    readonly aborted: boolean;
+    readonly reason?: any;
+    addEventListener?(type: "abort", listener: () => void): void;
+}
+
+export interface RequestOptions {
+    timeoutMs?: number;
+    signal?: AbortSignalLike;
+}
+
+export interface RequestSpec extends RequestOptions {
+    type: string;
+    params?: object;
+}
+
+export class TimeoutError extends Error {
+    orderId: number;
+    timeoutMs: number;
+    constructor(orderId: number, timeoutMs: number) {
+        super(`Order ${orderId} timed out after ${timeoutMs}ms`);
+        this.name = "TimeoutError";
+        this.orderId = orderId;
+        this.timeoutMs = timeoutMs;
+    }
+}
+
+export class AbortError extends Error {
+    reason: any;
+    constructor(reason: any) {
+        super(reason === undefined ? "The request was aborted" : `The request was aborted: ${reason}`);
+        this.name = "AbortError";
+        this.reason = reason;
+    }
+}
+
+function isThenable(value: any): boolean {
+    return value !== null
+        && (typeof value === "object" || typeof value === "function")
+        && typeof value.then === "function";
+}
+
+export function request(type: string, params?: object, options: RequestOptions = {}): Promise<any> {
+    const { timeoutMs, signal } = options;
+    if (signal && signal.aborted) {
+        return Promise.reject(new AbortError(signal.reason));
+    }
+
+    const id = __getOrderId__();
+    const deadlineMs = timeoutMs === undefined ? undefined : Date.now() + timeoutMs;
+    let result: any;
+    try {
+        result = order({ ...params, type }, { id, deadlineMs });
+    } catch (e) {
+        return Promise.reject(e);
+    }
+    if (!isThenable(result) || (timeoutMs === undefined && !signal)) {
+        return Promise.resolve(result);
+    }
+
+    const racers: Promise<any>[] = [result];
+    let timerId: number | undefined = undefined;
+    if (timeoutMs !== undefined) {
+        timerId = __getOrderId__();
+        const timer = order({ type: "timeout", orderId: id, delayMs: timeoutMs }, { id: timerId, deadlineMs });
+        racers.push(Promise.resolve(timer).then(() => {
+            throw new TimeoutError(id, timeoutMs);
+        }));
+    }
+    if (signal && typeof signal.addEventListener === "function") {
+        racers.push(new Promise((_, reject) => {
+            signal.addEventListener!("abort", () => reject(new AbortError(signal.reason)));
+        }));
+    }
+
+    return Promise.race(racers).then(
+        (value) => {
+            if (timerId !== undefined) __cancelOrder__(timerId);
+            return value;
+        },
+        (error) => {
+            if (error instanceof TimeoutError || error instanceof AbortError) __cancelOrder__(id);
+            if (timerId !== undefined) __cancelOrder__(timerId);
+            throw error;
+        },
+    );
+}
+
+export function requestAll(specs: RequestSpec[], options: RequestOptions = {}): Promise<any[]> {
+    // A plain loop rather than `map`, since batched orders suspend the script
+    // and native callbacks can't be suspended
+    const pending: Promise<any>[] = [];
+    for (const spec of specs) {
+        pending.push(request(spec.type, spec.params, {
+            timeoutMs: spec.timeoutMs ?? options.timeoutMs,
+            signal: spec.signal ?? options.signal,
+        }));
+    }
+    return Promise.all(pending);
+}
+
+/**
+ * Run `body` with `order()` returning Promises instead of suspending, until
+ * the Promise `body` returns settles. The orders it creates, including those
+ * created after awaiting already-settled Promises, reach the host in one
+ * `Suspended` batch.
+ */
+export async function batch<T>(body: () => T | Promise<T>): Promise<T> {
+    __beginBatch__();
+    try {
+        return await body();
+    } finally {
+        __endBatch__();
+    }
+}
+
+export interface FormatDurationOptions {
+    /** Leave out the spaces between units: "1h30m" rather than "1h 30m" */
+    compact?: boolean;
+    /** Show at most this many units, starting from the largest */
+    maxUnits?: number;
+}
+
+/** Milliseconds in a duration such as "250ms", "90s", "1h30m" or "1.5h" */
+export function parseDuration(text: string): number {
+    return __parseDuration__(text);
+}
+
+/** Format milliseconds as a duration such as "1h 30m" */
+export function formatDuration(ms: number, options: FormatDurationOptions = {}): string {
+    return __formatDuration__(ms, options);
+}
+
+/** Parse JSON with comments and trailing commas */
+export function parseJSONC(text: string): any {
+    return __parseJSONC__(text);
+}
+
+/** Parse JSON5: JSONC plus unquoted keys, single quotes, hex numbers and more */
+export function parseJSON5(text: string): any {
+    return __parseJSON5__(text);
+}
+
+export interface SandboxOptions {
+    /** Instructions the snippet may run; never more than the caller has left */
+    fuel?: number;
+    /** Wall-clock limit for the snippet */
+    timeoutMs?: number;
+}
+
+/**
+ * Evaluate `code` in an isolated scope that sees only copies of `bindings`,
+ * and return a copy of its completion value
+ */
+export function evalInSandbox(code: string, bindings?: Record<string, unknown>, options: SandboxOptions = {}): any {
+    return __evalInSandbox__(code, bindings, options);
+}
+
+export class CancellationError extends Error {
+    reason: any;
+    constructor(reason: any) {
+        super(reason === undefined ? "The run was cancelled" : `The run was cancelled: ${reason}`);
+        this.name = "CancellationError";
+        this.reason = reason;
+    }
+}
+
+/** Whether the host has asked the run to wind down */
+export function isCancellationRequested(): boolean {
+    return __cancellationRequested__();
+}
+
+/**
+ * Throw a `CancellationError` if the host has asked the run to wind down.
+ * Cheap enough to call on every iteration of a long loop.
+ */
+export function checkpoint(): void {
+    if (__cancellationRequested__()) {
+        throw new CancellationError(__cancellationReason__());
+    }
+}
+
+export function defineClient(schema: Record<string, string | { type: string; timeoutMs?: number }>): any {
+    const client: any = {};
+    for (const name of Object.keys(schema)) {
+        const entry = schema[name];
+        const type = typeof entry === "string" ? entry : entry.type;
+        const timeoutMs = typeof entry === "string" ? undefined : entry.timeoutMs;
+        client[name] = (params?: object, options: RequestOptions = {}) =>
+            request(type, params, { timeoutMs, ...options });
+    }
+    return client;
+}
+"#;
 
 /// Create the tsrun:host module
 pub fn create_eval_internal_module() -> InternalModule {
-    InternalModule::native("tsrun:host")
-        .with_function("order", order_syscall, 1)
+    InternalModule::source("tsrun:host", HOST_SOURCE)
+}
+
+/// Create the native order primitives tsrun:host builds on
+pub fn create_host_primitives_module() -> InternalModule {
+    InternalModule::native(HOST_PRIMITIVES_SPECIFIER)
+        .with_function("order", order_syscall, 2)
         .with_function("__cancelOrder__", cancel_order_syscall, 1)
         .with_function("__getOrderId__", get_order_id_syscall, 0)
+        .with_function("__beginBatch__", begin_batch_syscall, 0)
+        .with_function("__endBatch__", end_batch_syscall, 0)
+        .with_function("__parseDuration__", parse_duration_fn, 1)
+        .with_function("__formatDuration__", format_duration_fn, 2)
+        .with_function("__parseJSONC__", parse_jsonc_fn, 1)
+        .with_function("__parseJSON5__", parse_json5_fn, 1)
+        .with_function("__evalInSandbox__", eval_in_sandbox_syscall, 3)
+        .with_function(
+            "__cancellationRequested__",
+            cancellation_requested_syscall,
+            0,
+        )
+        .with_function("__cancellationReason__", cancellation_reason_syscall, 0)
         .build()
 }
 
@@ -23,7 +267,8 @@ pub fn create_eval_internal_module() -> InternalModule {
 /// to provide any value (plain objects, Promises, primitives).
 ///
 /// This is a blocking syscall - each call suspends execution until
-/// the host fulfills the order.
+/// the host fulfills the order. With `OrderDelivery::Eager` it returns a
+/// Promise instead and execution continues.
 ///
 /// Usage:
 ///   const result = await order({ type: "readFile", path: "/foo" });
@@ -32,38 +277,44 @@ pub fn create_eval_internal_module() -> InternalModule {
 ///   const p1 = order({ type: "fetch", url: "/a" });  // suspends, host returns Promise
 ///   const p2 = order({ type: "fetch", url: "/b" });  // suspends, host returns Promise
 ///   const [a, b] = await Promise.all([p1, p2]);          // awaits both
+///
+/// An optional second argument `{ id, deadlineMs }` creates the order under an
+/// ID reserved with `__getOrderId__` and sets `Order::deadline_ms`.
 fn order_syscall(
     interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
+    interp.check_order()?;
+    interp.check_pending_order_limit()?;
     let payload = args.first().cloned().unwrap_or(JsValue::Undefined);
 
-    // Generate unique order ID
-    let id = OrderId(interp.next_order_id);
-    interp.next_order_id += 1;
+    let mut id = None;
+    let mut deadline_ms = None;
+    if let Some(JsValue::Object(options)) = args.get(1) {
+        let id_key = interp.property_key("id");
+        let deadline_key = interp.property_key("deadlineMs");
+        let options = options.borrow();
+        match options.get_property(&id_key) {
+            Some(JsValue::Number(n)) if n >= 0.0 && n < interp.next_order_id as f64 => {
+                id = Some(OrderId(n as u64));
+            }
+            Some(JsValue::Undefined) | None => {}
+            Some(_) => {
+                return Err(JsError::type_error(
+                    "order() id must come from __getOrderId__",
+                ));
+            }
+        }
+        if let Some(JsValue::Number(n)) = options.get_property(&deadline_key)
+            && n.is_finite()
+        {
+            deadline_ms = Some(n as i64);
+        }
+    }
 
-    // Create payload RuntimeValue with guard if it's an object
-    let payload_rv = if let JsValue::Object(ref obj) = payload {
-        let payload_guard = interp.heap.create_guard();
-        payload_guard.guard(obj.cheap_clone());
-        RuntimeValue::with_guard(payload, payload_guard)
-    } else {
-        RuntimeValue::unguarded(payload)
-    };
-
-    // Record the pending order
-    interp.pending_orders.push(Order {
-        id,
-        payload: payload_rv,
-    });
-
-    // Create PendingOrder marker - VM will suspend immediately when this returns
-    let marker_guard = interp.heap.create_guard();
-    let marker = marker_guard.alloc();
-    marker.borrow_mut().exotic = ExoticObject::PendingOrder { id: id.0 };
-
-    Ok(Guarded::with_guard(JsValue::Object(marker), marker_guard))
+    let (_, result) = interp.create_order(payload, id, deadline_ms);
+    Ok(result)
 }
 
 /// Native implementation of __cancelOrder__
@@ -81,14 +332,8 @@ fn cancel_order_syscall(
         _ => return Err(JsError::type_error("__cancelOrder__ requires order ID")),
     };
 
-    // Mark as cancelled
-    interp.cancelled_orders.push(id);
-
-    // Remove from pending
-    interp.pending_orders.retain(|o| o.id != id);
-
-    // Remove any pending response (in case host already provided one)
-    interp.order_responses.remove(&id);
+    // Mark as cancelled and drop any pending state or response
+    interp.cancel_order(id);
 
     Ok(Guarded::unguarded(JsValue::Undefined))
 }
@@ -108,3 +353,104 @@ fn get_order_id_syscall(
     interp.next_order_id += 1;
     Ok(Guarded::unguarded(JsValue::Number(id as f64)))
 }
+
+/// Native implementation of __beginBatch__
+///
+/// Opens a `batch()` scope: until the matching `__endBatch__`, `order()`
+/// returns a Promise as it does with `OrderDelivery::Eager`.
+fn begin_batch_syscall(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    interp.order_batch_depth += 1;
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+/// Native implementation of __evalInSandbox__
+///
+/// Runs a code string in a fresh interpreter; see the `sandbox` module.
+///
+/// Usage: __evalInSandbox__(code, bindings, { fuel, timeoutMs });
+fn eval_in_sandbox_syscall(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let Some(JsValue::String(code)) = args.first() else {
+        return Err(JsError::type_error("evalInSandbox requires a code string"));
+    };
+    let bindings = match args.get(1) {
+        Some(JsValue::Object(obj)) => Some(obj.clone()),
+        Some(JsValue::Undefined) | None => None,
+        Some(_) => {
+            return Err(JsError::type_error(
+                "evalInSandbox bindings must be an object",
+            ));
+        }
+    };
+
+    let mut limits = SandboxLimits::default();
+    if let Some(JsValue::Object(options)) = args.get(2) {
+        let fuel_key = interp.property_key("fuel");
+        let timeout_key = interp.property_key("timeoutMs");
+        let options = options.borrow();
+        limits.fuel = sandbox_limit(options.get_property(&fuel_key), "fuel")?;
+        limits.timeout_ms = sandbox_limit(options.get_property(&timeout_key), "timeoutMs")?;
+    }
+
+    let code = code.to_string();
+    interp.eval_in_sandbox(&code, bindings.as_ref(), limits)
+}
+
+/// A `fuel` or `timeoutMs` option: absent, or a non-negative number
+fn sandbox_limit(value: Option<JsValue>, name: &str) -> Result<Option<u64>, JsError> {
+    match value {
+        Some(JsValue::Number(n)) if n >= 0.0 && !n.is_nan() => Ok(Some(n as u64)),
+        Some(JsValue::Undefined) | None => Ok(None),
+        Some(_) => Err(JsError::type_error(format!(
+            "evalInSandbox {} must be a non-negative number",
+            name
+        ))),
+    }
+}
+
+/// Native implementation of __endBatch__
+///
+/// Closes the innermost `batch()` scope.
+fn end_batch_syscall(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    interp.order_batch_depth = interp.order_batch_depth.saturating_sub(1);
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+/// Native implementation of __cancellationRequested__
+///
+/// Whether `Interpreter::request_cancellation` or the interrupt handle has
+/// asked the run to wind down.
+fn cancellation_requested_syscall(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let requested = interp.cancellation().is_some();
+    Ok(Guarded::unguarded(JsValue::Boolean(requested)))
+}
+
+/// Native implementation of __cancellationReason__
+///
+/// The reason given to `Interpreter::request_cancellation`, or undefined.
+fn cancellation_reason_syscall(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let reason = match interp.cancellation().flatten() {
+        Some(reason) => JsValue::from(reason),
+        None => JsValue::Undefined,
+    };
+    Ok(Guarded::unguarded(reason))
+}
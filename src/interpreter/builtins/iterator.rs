@@ -0,0 +1,694 @@
+//! Iterator.prototype and the ES2025 iterator helpers
+//!
+//! Generator objects and the built-in Array/Map/Set iterators inherit from
+//! `Iterator.prototype`, which provides lazy helpers (`map`, `filter`, `take`,
+//! `drop`, `flatMap`) and eager consumers (`reduce`, `toArray`, `forEach`,
+//! `some`, `every`, `find`).
+//!
+//! Lazy helpers return helper iterator objects whose state lives in hidden
+//! properties, like the other built-in iterators. A helper's `return()` closes
+//! its source, and a callback that throws closes the source before the error
+//! propagates.
+
+use core::ops::ControlFlow;
+
+use crate::error::JsError;
+use crate::gc::Gc;
+use crate::interpreter::Interpreter;
+use crate::interpreter::builtins::generator::create_generator_result;
+use crate::prelude::{Box, Vec, format, math};
+use crate::value::{CheapClone, Guarded, JsObject, JsString, JsSymbol, JsValue, PropertyKey};
+
+const SOURCE_SLOT: &str = "__source__";
+const NEXT_SLOT: &str = "__next__";
+const KIND_SLOT: &str = "__kind__";
+const CALLBACK_SLOT: &str = "__callback__";
+const COUNTER_SLOT: &str = "__counter__";
+const INNER_SLOT: &str = "__inner__";
+const INNER_NEXT_SLOT: &str = "__inner_next__";
+const DONE_SLOT: &str = "__done__";
+
+/// Initialize Iterator.prototype, the iterator helper prototype and the
+/// global `Iterator` constructor
+pub fn init_iterator(interp: &mut Interpreter) {
+    let proto = interp.iterator_prototype.clone();
+
+    interp.register_method(&proto, "map", iterator_map, 1);
+    interp.register_method(&proto, "filter", iterator_filter, 1);
+    interp.register_method(&proto, "take", iterator_take, 1);
+    interp.register_method(&proto, "drop", iterator_drop, 1);
+    interp.register_method(&proto, "flatMap", iterator_flat_map, 1);
+    interp.register_method(&proto, "reduce", iterator_reduce, 1);
+    interp.register_method(&proto, "toArray", iterator_to_array, 0);
+    interp.register_method(&proto, "forEach", iterator_for_each, 1);
+    interp.register_method(&proto, "some", iterator_some, 1);
+    interp.register_method(&proto, "every", iterator_every, 1);
+    interp.register_method(&proto, "find", iterator_find, 1);
+
+    // Iterator.prototype[Symbol.iterator]() returns the iterator itself
+    let well_known = interp.well_known_symbols;
+    let iterator_symbol =
+        JsSymbol::new(well_known.iterator, Some(interp.intern("Symbol.iterator")));
+    let iterator_key = PropertyKey::Symbol(Box::new(iterator_symbol));
+    let self_fn = interp.create_native_function("[Symbol.iterator]", iterator_self, 0);
+    proto
+        .borrow_mut()
+        .set_property(iterator_key, JsValue::Object(self_fn));
+
+    let tag_key = PropertyKey::String(interp.intern("@@toStringTag"));
+    proto
+        .borrow_mut()
+        .set_property(tag_key, JsValue::String(JsString::from("Iterator")));
+
+    let helper_proto = interp.iterator_helper_prototype.clone();
+    interp.register_method(&helper_proto, "next", iterator_helper_next, 0);
+    interp.register_method(&helper_proto, "return", iterator_helper_return, 0);
+    let tag_key = PropertyKey::String(interp.intern("@@toStringTag"));
+    helper_proto
+        .borrow_mut()
+        .set_property(tag_key, JsValue::String(JsString::from("Iterator Helper")));
+
+    let constructor = interp.create_native_function("Iterator", iterator_constructor, 0);
+    interp.root_guard.guard(constructor.clone());
+
+    let proto_key = PropertyKey::String(interp.intern("prototype"));
+    constructor
+        .borrow_mut()
+        .set_property(proto_key, JsValue::Object(proto.clone()));
+    let constructor_key = PropertyKey::String(interp.intern("constructor"));
+    proto
+        .borrow_mut()
+        .set_property(constructor_key, JsValue::Object(constructor.clone()));
+
+    let iterator_name = PropertyKey::String(interp.intern("Iterator"));
+    interp
+        .global
+        .borrow_mut()
+        .set_property(iterator_name, JsValue::Object(constructor));
+}
+
+/// Iterator() - abstract, only usable as a base class
+fn iterator_constructor(
+    _interp: &mut Interpreter,
+    _this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    Err(JsError::type_error(
+        "Abstract class Iterator not directly constructable",
+    ))
+}
+
+fn iterator_self(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    Ok(Guarded::unguarded(this))
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Iterator record helpers
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// GetIteratorDirect: the iterator object and its `next` method, read once
+fn iterator_direct(
+    interp: &mut Interpreter,
+    this: &JsValue,
+    method: &str,
+) -> Result<(Gc<JsObject>, JsValue), JsError> {
+    let JsValue::Object(iterator) = this else {
+        return Err(JsError::type_error(format!(
+            "Iterator.prototype.{} called on non-object",
+            method
+        )));
+    };
+    let next_key = PropertyKey::String(interp.intern("next"));
+    let next = iterator
+        .borrow()
+        .get_property(&next_key)
+        .unwrap_or(JsValue::Undefined);
+    Ok((iterator.cheap_clone(), next))
+}
+
+/// Call `next` on an iterator; `None` once it reports done.
+/// The returned value is kept alive through the result object's guard.
+fn iterator_step(
+    interp: &mut Interpreter,
+    iterator: &Gc<JsObject>,
+    next: &JsValue,
+) -> Result<Option<Guarded>, JsError> {
+    let Guarded { value, guard } =
+        interp.call_function(next.clone(), JsValue::Object(iterator.cheap_clone()), &[])?;
+    let JsValue::Object(result) = value else {
+        return Err(JsError::type_error("Iterator result is not an object"));
+    };
+    let done_key = PropertyKey::String(interp.intern("done"));
+    let value_key = PropertyKey::String(interp.intern("value"));
+    let result_ref = result.borrow();
+    if result_ref
+        .get_property(&done_key)
+        .is_some_and(|v| v.to_boolean())
+    {
+        return Ok(None);
+    }
+    let value = result_ref
+        .get_property(&value_key)
+        .unwrap_or(JsValue::Undefined);
+    drop(result_ref);
+
+    let guard = guard.unwrap_or_else(|| interp.heap.create_guard());
+    guard.guard(result);
+    Ok(Some(Guarded {
+        value,
+        guard: Some(guard),
+    }))
+}
+
+/// Call a helper callback, closing `source` if it throws
+fn call_callback(
+    interp: &mut Interpreter,
+    source: &Gc<JsObject>,
+    callback: &JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    interp
+        .call_function(callback.clone(), JsValue::Undefined, args)
+        .inspect_err(|_| interp.close_iterator(source))
+}
+
+/// Validate a callback argument, closing the iterator if it isn't callable
+fn require_callable(
+    interp: &mut Interpreter,
+    iterator: &Gc<JsObject>,
+    callback: Option<&JsValue>,
+) -> Result<JsValue, JsError> {
+    let callback = callback.cloned().unwrap_or(JsValue::Undefined);
+    if callback.is_callable() {
+        return Ok(callback);
+    }
+    interp.close_iterator(iterator);
+    let name = interp.to_js_string(&callback);
+    Err(JsError::type_error(format!("{} is not a function", name)))
+}
+
+/// Validate a take/drop count: a non-negative integer or +Infinity
+fn require_count(
+    interp: &mut Interpreter,
+    iterator: &Gc<JsObject>,
+    limit: Option<&JsValue>,
+    method: &str,
+) -> Result<f64, JsError> {
+    let limit = limit.cloned().unwrap_or(JsValue::Undefined);
+    let number = match interp.coerce_to_number(&limit) {
+        Ok(n) => n,
+        Err(e) => {
+            interp.close_iterator(iterator);
+            return Err(e);
+        }
+    };
+    if number.is_nan() || number < 0.0 {
+        interp.close_iterator(iterator);
+        return Err(JsError::range_error(format!(
+            "Iterator.prototype.{} argument must be a non-negative number",
+            method
+        )));
+    }
+    Ok(if number.is_infinite() {
+        number
+    } else {
+        math::trunc(number)
+    })
+}
+
+/// Drive an iterator, calling `f` with each value and its index. A `Break`
+/// from `f` closes the iterator and returns the value; an error from `f` closes
+/// it before propagating. Errors from the iterator itself don't close it.
+fn drive<F>(
+    interp: &mut Interpreter,
+    iterator: &Gc<JsObject>,
+    next: &JsValue,
+    mut index: usize,
+    mut f: F,
+) -> Result<Option<Guarded>, JsError>
+where
+    F: FnMut(&mut Interpreter, Guarded, usize) -> Result<ControlFlow<Guarded>, JsError>,
+{
+    loop {
+        let Some(item) = iterator_step(interp, iterator, next)? else {
+            return Ok(None);
+        };
+        match f(interp, item, index) {
+            Ok(ControlFlow::Continue(())) => {}
+            Ok(ControlFlow::Break(result)) => {
+                interp.close_iterator(iterator);
+                return Ok(Some(result));
+            }
+            Err(e) => {
+                interp.close_iterator(iterator);
+                return Err(e);
+            }
+        }
+        index += 1;
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Lazy helpers
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Create a helper iterator over `source`
+fn create_helper(
+    interp: &mut Interpreter,
+    source: Gc<JsObject>,
+    next: JsValue,
+    kind: &str,
+    callback: JsValue,
+    counter: f64,
+) -> Guarded {
+    let guard = interp.heap.create_guard();
+    let helper = interp.create_object_raw(&guard);
+    helper.borrow_mut().prototype = Some(interp.iterator_helper_prototype.cheap_clone());
+
+    set_slot(interp, &helper, SOURCE_SLOT, JsValue::Object(source));
+    set_slot(interp, &helper, NEXT_SLOT, next);
+    set_slot(
+        interp,
+        &helper,
+        KIND_SLOT,
+        JsValue::String(JsString::from(kind)),
+    );
+    set_slot(interp, &helper, CALLBACK_SLOT, callback);
+    set_slot(interp, &helper, COUNTER_SLOT, JsValue::Number(counter));
+    Guarded::with_guard(JsValue::Object(helper), guard)
+}
+
+fn slot(interp: &mut Interpreter, obj: &Gc<JsObject>, name: &str) -> JsValue {
+    let key = PropertyKey::String(interp.intern(name));
+    obj.borrow()
+        .get_property(&key)
+        .unwrap_or(JsValue::Undefined)
+}
+
+fn set_slot(interp: &mut Interpreter, obj: &Gc<JsObject>, name: &str, value: JsValue) {
+    let key = PropertyKey::String(interp.intern(name));
+    obj.borrow_mut().set_property(key, value);
+}
+
+fn counter(interp: &mut Interpreter, helper: &Gc<JsObject>) -> f64 {
+    match slot(interp, helper, COUNTER_SLOT) {
+        JsValue::Number(n) => n,
+        _ => 0.0,
+    }
+}
+
+fn iterator_map(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let (source, next) = iterator_direct(interp, &this, "map")?;
+    let callback = require_callable(interp, &source, args.first())?;
+    Ok(create_helper(interp, source, next, "map", callback, 0.0))
+}
+
+fn iterator_filter(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let (source, next) = iterator_direct(interp, &this, "filter")?;
+    let callback = require_callable(interp, &source, args.first())?;
+    Ok(create_helper(interp, source, next, "filter", callback, 0.0))
+}
+
+fn iterator_take(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let (source, next) = iterator_direct(interp, &this, "take")?;
+    let limit = require_count(interp, &source, args.first(), "take")?;
+    Ok(create_helper(
+        interp,
+        source,
+        next,
+        "take",
+        JsValue::Undefined,
+        limit,
+    ))
+}
+
+fn iterator_drop(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let (source, next) = iterator_direct(interp, &this, "drop")?;
+    let limit = require_count(interp, &source, args.first(), "drop")?;
+    Ok(create_helper(
+        interp,
+        source,
+        next,
+        "drop",
+        JsValue::Undefined,
+        limit,
+    ))
+}
+
+fn iterator_flat_map(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let (source, next) = iterator_direct(interp, &this, "flatMap")?;
+    let callback = require_callable(interp, &source, args.first())?;
+    Ok(create_helper(
+        interp, source, next, "flatMap", callback, 0.0,
+    ))
+}
+
+/// Helper next() - pulls from the source according to the helper's kind
+fn iterator_helper_next(
+    interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let JsValue::Object(helper) = this else {
+        return Err(JsError::type_error(
+            "Iterator helper next called on non-object",
+        ));
+    };
+    if slot(interp, &helper, DONE_SLOT).to_boolean() {
+        return Ok(create_generator_result(interp, JsValue::Undefined, true));
+    }
+
+    match helper_next_value(interp, &helper) {
+        Ok(Some(Guarded {
+            value,
+            guard: _guard,
+        })) => Ok(create_generator_result(interp, value, false)),
+        Ok(None) => {
+            set_slot(interp, &helper, DONE_SLOT, JsValue::Boolean(true));
+            Ok(create_generator_result(interp, JsValue::Undefined, true))
+        }
+        Err(e) => {
+            set_slot(interp, &helper, DONE_SLOT, JsValue::Boolean(true));
+            Err(e)
+        }
+    }
+}
+
+fn helper_next_value(
+    interp: &mut Interpreter,
+    helper: &Gc<JsObject>,
+) -> Result<Option<Guarded>, JsError> {
+    let JsValue::Object(source) = slot(interp, helper, SOURCE_SLOT) else {
+        return Err(JsError::type_error("Invalid iterator helper"));
+    };
+    let next = slot(interp, helper, NEXT_SLOT);
+    let callback = slot(interp, helper, CALLBACK_SLOT);
+    let kind = match slot(interp, helper, KIND_SLOT) {
+        JsValue::String(s) => s,
+        _ => return Err(JsError::type_error("Invalid iterator helper")),
+    };
+
+    match kind.as_str() {
+        "map" => {
+            let Some(item) = iterator_step(interp, &source, &next)? else {
+                return Ok(None);
+            };
+            let index = counter(interp, helper);
+            set_slot(interp, helper, COUNTER_SLOT, JsValue::Number(index + 1.0));
+            let mapped = call_callback(
+                interp,
+                &source,
+                &callback,
+                &[item.value.clone(), JsValue::Number(index)],
+            )?;
+            Ok(Some(mapped))
+        }
+        "filter" => loop {
+            let Some(item) = iterator_step(interp, &source, &next)? else {
+                return Ok(None);
+            };
+            let index = counter(interp, helper);
+            set_slot(interp, helper, COUNTER_SLOT, JsValue::Number(index + 1.0));
+            let keep = call_callback(
+                interp,
+                &source,
+                &callback,
+                &[item.value.clone(), JsValue::Number(index)],
+            )?;
+            if keep.value.to_boolean() {
+                return Ok(Some(item));
+            }
+        },
+        "take" => {
+            let remaining = counter(interp, helper);
+            if remaining <= 0.0 {
+                interp.close_iterator(&source);
+                return Ok(None);
+            }
+            set_slot(
+                interp,
+                helper,
+                COUNTER_SLOT,
+                JsValue::Number(remaining - 1.0),
+            );
+            iterator_step(interp, &source, &next)
+        }
+        "drop" => {
+            let mut remaining = counter(interp, helper);
+            while remaining > 0.0 {
+                remaining -= 1.0;
+                set_slot(interp, helper, COUNTER_SLOT, JsValue::Number(remaining));
+                if iterator_step(interp, &source, &next)?.is_none() {
+                    return Ok(None);
+                }
+            }
+            iterator_step(interp, &source, &next)
+        }
+        "flatMap" => loop {
+            if let JsValue::Object(inner) = slot(interp, helper, INNER_SLOT) {
+                let inner_next = slot(interp, helper, INNER_NEXT_SLOT);
+                match iterator_step(interp, &inner, &inner_next) {
+                    Ok(Some(item)) => return Ok(Some(item)),
+                    Ok(None) => set_slot(interp, helper, INNER_SLOT, JsValue::Undefined),
+                    Err(e) => {
+                        interp.close_iterator(&source);
+                        return Err(e);
+                    }
+                }
+                continue;
+            }
+
+            let Some(item) = iterator_step(interp, &source, &next)? else {
+                return Ok(None);
+            };
+            let index = counter(interp, helper);
+            set_slot(interp, helper, COUNTER_SLOT, JsValue::Number(index + 1.0));
+            let mapped = call_callback(
+                interp,
+                &source,
+                &callback,
+                &[item.value.clone(), JsValue::Number(index)],
+            )?;
+            let (inner, inner_next) = match flattenable_iterator(interp, &mapped.value) {
+                Ok(pair) => pair,
+                Err(e) => {
+                    interp.close_iterator(&source);
+                    return Err(e);
+                }
+            };
+            set_slot(interp, helper, INNER_SLOT, JsValue::Object(inner));
+            set_slot(interp, helper, INNER_NEXT_SLOT, inner_next);
+        },
+        _ => Err(JsError::type_error("Invalid iterator helper")),
+    }
+}
+
+/// GetIteratorFlattenable: objects with Symbol.iterator, or iterator objects
+/// themselves. Strings and other primitives are rejected.
+fn flattenable_iterator(
+    interp: &mut Interpreter,
+    value: &JsValue,
+) -> Result<(Gc<JsObject>, JsValue), JsError> {
+    let JsValue::Object(obj) = value else {
+        return Err(JsError::type_error(
+            "flatMap mapper must return an iterable object",
+        ));
+    };
+    let well_known = interp.well_known_symbols;
+    let iterator_symbol =
+        JsSymbol::new(well_known.iterator, Some(interp.intern("Symbol.iterator")));
+    let iterator_key = PropertyKey::Symbol(Box::new(iterator_symbol));
+    let method = obj.borrow().get_property(&iterator_key);
+
+    let iterator = match method.filter(|m| m.is_callable()) {
+        Some(method) => {
+            let result = interp.call_function(method, value.clone(), &[])?;
+            match result.value {
+                JsValue::Object(iterator) => iterator,
+                _ => {
+                    return Err(JsError::type_error("Symbol.iterator must return an object"));
+                }
+            }
+        }
+        None => obj.cheap_clone(),
+    };
+    let next_key = PropertyKey::String(interp.intern("next"));
+    let next = iterator
+        .borrow()
+        .get_property(&next_key)
+        .unwrap_or(JsValue::Undefined);
+    Ok((iterator, next))
+}
+
+/// Helper return() - closes the current inner iterator and the source
+fn iterator_helper_return(
+    interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let JsValue::Object(helper) = this else {
+        return Err(JsError::type_error(
+            "Iterator helper return called on non-object",
+        ));
+    };
+    if !slot(interp, &helper, DONE_SLOT).to_boolean() {
+        set_slot(interp, &helper, DONE_SLOT, JsValue::Boolean(true));
+        if let JsValue::Object(inner) = slot(interp, &helper, INNER_SLOT) {
+            interp.close_iterator(&inner);
+        }
+        if let JsValue::Object(source) = slot(interp, &helper, SOURCE_SLOT) {
+            interp.close_iterator(&source);
+        }
+    }
+    Ok(create_generator_result(interp, JsValue::Undefined, true))
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Eager consumers
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn iterator_reduce(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let (iterator, next) = iterator_direct(interp, &this, "reduce")?;
+    let callback = require_callable(interp, &iterator, args.first())?;
+
+    let (mut accumulator, start) = match args.get(1) {
+        Some(initial) => (Guarded::unguarded(initial.clone()), 0),
+        None => match iterator_step(interp, &iterator, &next)? {
+            Some(first) => (first, 1),
+            None => {
+                return Err(JsError::type_error(
+                    "Reduce of empty iterator with no initial value",
+                ));
+            }
+        },
+    };
+
+    drive(interp, &iterator, &next, start, |interp, item, index| {
+        accumulator = interp.call_function(
+            callback.clone(),
+            JsValue::Undefined,
+            &[
+                accumulator.value.clone(),
+                item.value,
+                JsValue::Number(index as f64),
+            ],
+        )?;
+        Ok(ControlFlow::Continue(()))
+    })?;
+    Ok(accumulator)
+}
+
+fn iterator_to_array(
+    interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let (iterator, next) = iterator_direct(interp, &this, "toArray")?;
+    let guard = interp.heap.create_guard();
+    let mut values = Vec::new();
+    drive(interp, &iterator, &next, 0, |_, item, _| {
+        item.value.guard_by(&guard);
+        values.push(item.value);
+        Ok(ControlFlow::Continue(()))
+    })?;
+    let arr = interp.create_array_from(&guard, values);
+    Ok(Guarded::with_guard(JsValue::Object(arr), guard))
+}
+
+fn iterator_for_each(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let (iterator, next) = iterator_direct(interp, &this, "forEach")?;
+    let callback = require_callable(interp, &iterator, args.first())?;
+    drive(interp, &iterator, &next, 0, |interp, item, index| {
+        interp.call_function(
+            callback.clone(),
+            JsValue::Undefined,
+            &[item.value, JsValue::Number(index as f64)],
+        )?;
+        Ok(ControlFlow::Continue(()))
+    })?;
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+/// Shared body of some/every/find: stop at the first item whose predicate
+/// result equals `stop_on`
+fn find_first(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+    method: &str,
+    stop_on: bool,
+) -> Result<Option<Guarded>, JsError> {
+    let (iterator, next) = iterator_direct(interp, &this, method)?;
+    let callback = require_callable(interp, &iterator, args.first())?;
+    drive(interp, &iterator, &next, 0, |interp, item, index| {
+        let result = interp.call_function(
+            callback.clone(),
+            JsValue::Undefined,
+            &[item.value.clone(), JsValue::Number(index as f64)],
+        )?;
+        if result.value.to_boolean() == stop_on {
+            Ok(ControlFlow::Break(item))
+        } else {
+            Ok(ControlFlow::Continue(()))
+        }
+    })
+}
+
+fn iterator_some(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let found = find_first(interp, this, args, "some", true)?;
+    Ok(Guarded::unguarded(JsValue::Boolean(found.is_some())))
+}
+
+fn iterator_every(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let found = find_first(interp, this, args, "every", false)?;
+    Ok(Guarded::unguarded(JsValue::Boolean(found.is_none())))
+}
+
+fn iterator_find(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let found = find_first(interp, this, args, "find", true)?;
+    Ok(found.unwrap_or_else(|| Guarded::unguarded(JsValue::Undefined)))
+}
@@ -1,9 +1,9 @@
 //! JSON built-in methods
 
 use crate::error::JsError;
-use crate::gc::Guard;
+use crate::gc::{Gc, Guard};
 use crate::interpreter::Interpreter;
-use crate::prelude::{FxHashSet, String, ToString, Vec, format, math};
+use crate::prelude::{FxHashSet, String, ToString, Vec, format, math, vec};
 use crate::value::{ExoticObject, Guarded, JsObject, JsString, JsValue, PropertyKey};
 
 const MS_PER_SECOND: i64 = 1000;
@@ -27,7 +27,7 @@ fn days_to_ymd(days: i64) -> (i32, u32, u32) {
 }
 
 /// Format timestamp (ms since epoch) as ISO 8601 string
-fn format_timestamp_iso(ts: f64) -> String {
+pub(crate) fn format_timestamp_iso(ts: f64) -> String {
     if ts.is_nan() || ts.is_infinite() {
         return "Invalid Date".to_string();
     }
@@ -57,6 +57,7 @@ pub fn init_json(interp: &mut Interpreter) {
     interp.register_method(&json, "parse", json_parse, 2);
     interp.register_method(&json, "rawJSON", json_raw_json, 1);
     interp.register_method(&json, "isRawJSON", json_is_raw_json, 1);
+    interp.register_method(&json, "canonicalize", json_canonicalize, 2);
 
     let json_key = PropertyKey::String(interp.intern("JSON"));
     interp
@@ -66,7 +67,7 @@ pub fn init_json(interp: &mut Interpreter) {
 }
 
 pub fn json_stringify(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
@@ -76,65 +77,151 @@ pub fn json_stringify(
     let indent = args.get(2).cloned().unwrap_or(JsValue::Undefined);
 
     // Track visited objects for circular reference detection
-    let mut visited = FxHashSet::default();
-    let json = js_value_to_json_with_visited(&value, &mut visited)?;
-
-    let output = match indent {
-        JsValue::Number(n) if n > 0.0 => {
-            // Use pretty printing with indentation
-            let indent_size = n.min(10.0) as usize;
-            serde_json::to_string_pretty(&json)
-                .map(|s| {
-                    // serde_json uses 2 spaces by default, adjust if needed
-                    if indent_size == 2 {
-                        s
-                    } else {
-                        // Re-indent with the requested size
-                        let indent_str = " ".repeat(indent_size);
-                        s.lines()
-                            .map(|line| {
-                                let stripped = line.trim_start();
-                                let leading_spaces = line.len() - stripped.len();
-                                let indent_level = leading_spaces / 2;
-                                format!("{}{}", indent_str.repeat(indent_level), stripped)
-                            })
-                            .collect::<Vec<_>>()
-                            .join("\n")
-                    }
-                })
-                .unwrap_or_else(|_| json.to_string())
-        }
-        JsValue::String(s) if !s.is_empty() => {
-            // Use string as indent
-            let indent_str = s.as_str();
-            serde_json::to_string_pretty(&json)
-                .map(|s| {
-                    s.lines()
-                        .map(|line| {
-                            let stripped = line.trim_start();
-                            let leading_spaces = line.len() - stripped.len();
-                            let indent_level = leading_spaces / 2;
-                            format!(
-                                "{}{}",
-                                indent_str
-                                    .chars()
-                                    .take(10)
-                                    .collect::<String>()
-                                    .repeat(indent_level),
-                                stripped
-                            )
-                        })
-                        .collect::<Vec<_>>()
-                        .join("\n")
-                })
-                .unwrap_or_else(|_| json.to_string())
+    let mut budget = interp.max_string_len;
+    let json = stringify_to_json(&value, &mut budget, &mut || interp.check_budget())?;
+
+    let gap: String = match indent {
+        JsValue::Number(n) if n > 0.0 => " ".repeat(n.min(10.0) as usize),
+        JsValue::String(s) => s.as_str().chars().take(10).collect(),
+        _ => String::new(),
+    };
+    let mut output = String::new();
+    write_json(&mut output, &json, &gap, 0, false);
+    // Indentation is not covered by the budget
+    interp.check_string_len(output.len())?;
+
+    Ok(Guarded::unguarded(JsValue::String(JsString::from(output))))
+}
+
+/// JSON.canonicalize(value, { pretty, errors }) - RFC 8785 canonical JSON text
+///
+/// Values convert as in `JSON.stringify`; see [`value_to_canonical_json`].
+/// `errors` is `"properties"`, `"withStack"` or `"withoutStack"`; see [`ErrorsAs`].
+pub fn json_canonicalize(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let value = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let (pretty, errors) = match args.get(1) {
+        Some(JsValue::Object(options)) => {
+            let key = interp.property_key("pretty");
+            let pretty = options
+                .borrow()
+                .get_property(&key)
+                .is_some_and(|pretty| pretty.to_boolean());
+            (pretty, errors_option(interp, options)?)
         }
-        _ => json.to_string(),
+        _ => (false, ErrorsAs::Properties),
     };
 
+    let mut budget = interp.max_string_len;
+    let error_prototype = interp.error_prototype.clone();
+    let options = ToJsonOptions::default();
+    let json = JsonConverter::new(&options, &mut budget, &mut || interp.check_budget())
+        .with_errors(errors, error_prototype)
+        .run(&value)?;
+    let output = canonical_json_text(&json, pretty);
+    interp.check_string_len(output.len())?;
+
     Ok(Guarded::unguarded(JsValue::String(JsString::from(output))))
 }
 
+/// Serialize `value` as canonical JSON in the JSON Canonicalization Scheme
+/// (RFC 8785): object members sorted by the UTF-16 code units of their keys,
+/// no whitespace, and numbers and strings written as `JSON.stringify` writes
+/// them. Values convert as in `JSON.stringify`, so a cycle is a TypeError.
+///
+/// With `pretty`, members go on their own lines indented by two spaces per
+/// level, for readable diffs; keys stay sorted.
+pub fn value_to_canonical_json(value: &JsValue, pretty: bool) -> Result<String, JsError> {
+    Ok(canonical_json_text(&js_value_to_json(value)?, pretty))
+}
+
+/// [`value_to_canonical_json`] with errors converted as `errors` says
+pub fn value_to_canonical_json_with_errors(
+    interp: &Interpreter,
+    value: &JsValue,
+    pretty: bool,
+    errors: ErrorsAs,
+) -> Result<String, JsError> {
+    let mut budget = usize::MAX;
+    let json = JsonConverter::new(&ToJsonOptions::default(), &mut budget, &mut || Ok(()))
+        .with_errors(errors, interp.error_prototype.clone())
+        .run(value)?;
+    Ok(canonical_json_text(&json, pretty))
+}
+
+fn canonical_json_text(json: &serde_json::Value, pretty: bool) -> String {
+    let mut output = String::new();
+    write_json(&mut output, json, if pretty { "  " } else { "" }, 0, true);
+    output
+}
+
+/// Parse JSON text the way `JSON.parse` does. serde_json rejects `\u`
+/// escapes of lone surrogates, so text that fails is retried with those
+/// escapes turned into U+FFFD, as strings built any other way get them.
+pub(crate) fn parse_json_text(text: &str) -> Result<serde_json::Value, serde_json::Error> {
+    serde_json::from_str(text).or_else(|error| match replace_lone_surrogate_escapes(text) {
+        Some(fixed) => serde_json::from_str(&fixed),
+        None => Err(error),
+    })
+}
+
+/// `text` with `\u` escapes of lone surrogates replaced by `\uFFFD`, or
+/// `None` if it has none
+fn replace_lone_surrogate_escapes(text: &str) -> Option<String> {
+    fn unit_at(text: &str, at: usize) -> Option<u32> {
+        let hex = text.get(at..at + 6)?.strip_prefix("\\u")?;
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        u32::from_str_radix(hex, 16).ok()
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut changed = false;
+    let mut rest_start = 0;
+    let mut chars = text.char_indices();
+    while let Some((at, c)) = chars.next() {
+        if c != '\\' {
+            continue;
+        }
+        let Some(unit) = unit_at(text, at) else {
+            // Skip the escaped char, so `\\u` is not read as an escape
+            chars.next();
+            continue;
+        };
+        let lone = match unit {
+            0xD800..=0xDBFF => {
+                !unit_at(text, at + 6).is_some_and(|low| (0xDC00..0xE000).contains(&low))
+            }
+            0xDC00..=0xDFFF => true,
+            _ => false,
+        };
+        let escape_len = if (0xD800..0xDC00).contains(&unit) && !lone {
+            12
+        } else {
+            6
+        };
+        if lone {
+            out.push_str(text.get(rest_start..at).unwrap_or(""));
+            out.push_str("\\uFFFD");
+            rest_start = at + 6;
+            changed = true;
+        }
+        // Step over the rest of the escape
+        for _ in 1..escape_len {
+            chars.next();
+        }
+    }
+    if !changed {
+        return None;
+    }
+    out.push_str(text.get(rest_start..).unwrap_or(""));
+    Some(out)
+}
+
 pub fn json_parse(
     interp: &mut Interpreter,
     _this: JsValue,
@@ -143,12 +230,12 @@ pub fn json_parse(
     let text = args.first().cloned().unwrap_or(JsValue::Undefined);
     let text_str = interp.to_js_string(&text);
 
-    let json: serde_json::Value = serde_json::from_str(text_str.as_str())
+    let json = parse_json_text(text_str.as_str())
         .map_err(|e| JsError::syntax_error(format!("JSON parse error: {}", e), 0, 0))?;
 
     // Use a single guard for all objects created during parsing
     let guard = interp.heap.create_guard();
-    let value = json_to_js_value_with_guard(interp, &json, &guard)?;
+    let value = json_to_js_value_metered(interp, &json, &guard, true, false)?;
 
     // Return the result with the guard if it's an object
     if matches!(value, JsValue::Object(_)) {
@@ -157,165 +244,738 @@ pub fn json_parse(
     Ok(Guarded::unguarded(value))
 }
 
+/// Write `json` as `JSON.stringify` text, putting each member on its own
+/// line indented by `gap` per level unless `gap` is empty.
+///
+/// Numbers are written with [`crate::value::number_to_string`], so `1e21`
+/// stringifies as `1e+21` and `-0` as `0`, like every other number-to-string
+/// path. With `sort_keys`, object members are ordered by the UTF-16 code
+/// units of their keys, as RFC 8785 requires.
+fn write_json(
+    out: &mut String,
+    json: &serde_json::Value,
+    gap: &str,
+    level: usize,
+    sort_keys: bool,
+) {
+    let mut members: Vec<(Option<&str>, &serde_json::Value)> = match json {
+        serde_json::Value::Null => return out.push_str("null"),
+        serde_json::Value::Bool(b) => return out.push_str(if *b { "true" } else { "false" }),
+        serde_json::Value::Number(n) => {
+            let n = n.as_f64().unwrap_or(0.0);
+            return out.push_str(&crate::value::number_to_string(n));
+        }
+        serde_json::Value::String(s) => return write_json_string(out, s),
+        serde_json::Value::Array(items) => items.iter().map(|item| (None, item)).collect(),
+        serde_json::Value::Object(map) => map
+            .iter()
+            .map(|(key, value)| (Some(key.as_str()), value))
+            .collect(),
+    };
+
+    if sort_keys && json.is_object() {
+        members.sort_by(|(a, _), (b, _)| {
+            let (a, b) = (a.unwrap_or_default(), b.unwrap_or_default());
+            a.encode_utf16().cmp(b.encode_utf16())
+        });
+    }
+
+    let (open, close) = if json.is_array() {
+        ('[', ']')
+    } else {
+        ('{', '}')
+    };
+    out.push(open);
+    for (i, (key, value)) in members.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        if !gap.is_empty() {
+            out.push('\n');
+            out.push_str(&gap.repeat(level + 1));
+        }
+        if let Some(key) = key {
+            write_json_string(out, key);
+            out.push(':');
+            if !gap.is_empty() {
+                out.push(' ');
+            }
+        }
+        write_json(out, value, gap, level + 1, sort_keys);
+    }
+    if !members.is_empty() && !gap.is_empty() {
+        out.push('\n');
+        out.push_str(&gap.repeat(level));
+    }
+    out.push(close);
+}
+
+/// Write `s` as a quoted JSON string literal
+fn write_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
 /// Convert a JsValue to JSON, with public API for external callers (without circular detection)
+///
+/// Maps and Sets become `null`, as they do in `JSON.stringify`; use
+/// [`js_value_to_json_with`] to keep their contents.
 pub fn js_value_to_json(value: &JsValue) -> Result<serde_json::Value, JsError> {
-    let mut visited = FxHashSet::default();
-    js_value_to_json_with_visited(value, &mut visited)
+    js_value_to_json_opts(value, &ToJsonOptions::default())
+}
+
+/// How [`js_value_to_json_with`] converts Map and Set objects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapsAs {
+    /// A Map becomes an object keyed by its keys converted to strings, and a
+    /// Set an array of its values. Symbol and object keys are a TypeError.
+    Object,
+    /// A Map becomes an array of `[key, value]` pairs, and a Set an array of
+    /// its values
+    EntriesArray,
+    /// Converting a Map or Set is a TypeError
+    Error,
+}
+
+/// Convert a JsValue to JSON like [`js_value_to_json`], converting Maps and
+/// Sets as `maps` says instead of dropping their contents
+pub fn js_value_to_json_with(value: &JsValue, maps: MapsAs) -> Result<serde_json::Value, JsError> {
+    let options = ToJsonOptions {
+        maps: Some(maps),
+        ..ToJsonOptions::default()
+    };
+    js_value_to_json_opts(value, &options)
+}
+
+/// How [`js_value_to_json_opts`] converts NaN, `Infinity` and `-Infinity`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonFinite {
+    /// `null`, as in `JSON.stringify`
+    Null,
+    /// The strings `"NaN"`, `"Infinity"` and `"-Infinity"`
+    String,
+    /// A TypeError naming the path to the number
+    Error,
+}
+
+/// How [`js_value_to_json_opts`] converts `undefined` array elements
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UndefinedInArrays {
+    /// `null`, as in `JSON.stringify`
+    Null,
+    /// Left out, so later elements move up
+    Skip,
+    /// A TypeError naming the path to the element
+    Error,
+}
+
+/// How [`js_value_to_json_opts`] converts Boolean, Number and String
+/// wrapper objects such as `new String("ab")`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrappersAs {
+    /// Their primitive value, as in `JSON.stringify`
+    Unwrap,
+    /// A TypeError naming the path to the wrapper, for output where a boxed
+    /// primitive is a mistake
+    Error,
+}
+
+/// How canonical JSON and YAML output convert Error objects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorsAs {
+    /// Their own enumerable properties, as `JSON.stringify` converts them
+    #[default]
+    Properties,
+    /// `{ name, message, stack }` objects, followed by their other own
+    /// enumerable properties such as a `code`, then `cause` and
+    /// AggregateError `errors`, converted in turn
+    WithStack,
+    /// As [`ErrorsAs::WithStack`] without `stack`, for output that should
+    /// not depend on where the errors were thrown
+    WithoutStack,
+}
+
+/// The `errors` option of `JSON.canonicalize` and `YAML.stringify`
+pub(crate) fn errors_option(
+    interp: &mut Interpreter,
+    options: &Gc<JsObject>,
+) -> Result<ErrorsAs, JsError> {
+    let key = interp.property_key("errors");
+    let value = options.borrow().get_property(&key);
+    match value {
+        None | Some(JsValue::Undefined) => Ok(ErrorsAs::Properties),
+        Some(JsValue::String(s)) if s.as_str() == "properties" => Ok(ErrorsAs::Properties),
+        Some(JsValue::String(s)) if s.as_str() == "withStack" => Ok(ErrorsAs::WithStack),
+        Some(JsValue::String(s)) if s.as_str() == "withoutStack" => Ok(ErrorsAs::WithoutStack),
+        Some(other) => Err(JsError::type_error(format!(
+            "Invalid errors option '{}': expected 'properties', 'withStack' or 'withoutStack'",
+            interp.to_js_string(&other)
+        ))),
+    }
+}
+
+/// The members `errors` gives error `obj` in place of its own enumerable
+/// properties, or `None` when it keeps them
+pub(crate) fn error_members(
+    obj: &Gc<JsObject>,
+    errors: ErrorsAs,
+    error_prototype: Option<&Gc<JsObject>>,
+) -> Option<Vec<(PropertyKey, JsValue)>> {
+    let with_stack = match errors {
+        ErrorsAs::Properties => return None,
+        ErrorsAs::WithStack => true,
+        ErrorsAs::WithoutStack => false,
+    };
+    if !error_prototype.is_some_and(|proto| super::error::is_error_object(proto, obj)) {
+        return None;
+    }
+
+    let key = |name: &str| PropertyKey::String(JsString::from(name));
+    let (name_key, message_key, stack_key) = (key("name"), key("message"), key("stack"));
+    let obj_ref = obj.borrow();
+    let mut members = Vec::new();
+    for field in [&name_key, &message_key] {
+        let value = obj_ref.get_property(field).unwrap_or(JsValue::Undefined);
+        members.push((field.clone(), value));
+    }
+    if with_stack && let Some(stack) = obj_ref.get_property(&stack_key) {
+        members.push((stack_key.clone(), stack));
+    }
+    for k in obj_ref.ordered_own_keys(true) {
+        if !k.is_symbol() && k != name_key && k != message_key && k != stack_key {
+            let value = obj_ref.get_property(&k).unwrap_or(JsValue::Undefined);
+            members.push((k, value));
+        }
+    }
+    for field in [key("cause"), key("errors")] {
+        if let Some(prop) = obj_ref.properties.get(&field)
+            && !prop.enumerable()
+        {
+            members.push((field, prop.value.clone()));
+        }
+    }
+    Some(members)
+}
+
+/// Options for [`js_value_to_json_opts`] and [`crate::RuntimeValue::to_json`].
+///
+/// The default converts as [`js_value_to_json`] and `JSON.stringify` do.
+/// Errors name the offending value by its JSON Pointer path, e.g.
+/// `/spec/replicas`.
+///
+/// ```
+/// use tsrun::{Interpreter, NonFinite, StepResult, ToJsonOptions};
+///
+/// let mut interp = Interpreter::new();
+/// let StepResult::Complete(value) = interp.eval("({ spec: { replicas: 0 / 0 } })", None)? else {
+///     panic!("expected a value");
+/// };
+/// let strict = ToJsonOptions {
+///     non_finite: NonFinite::Error,
+///     ..ToJsonOptions::default()
+/// };
+/// let err = value.to_json(&strict).unwrap_err();
+/// assert!(err.to_string().contains("at /spec/replicas"));
+/// # Ok::<(), tsrun::JsError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToJsonOptions {
+    /// How NaN and the infinities convert
+    pub non_finite: NonFinite,
+    /// Deepest nesting of objects and arrays converted; deeper is a
+    /// RangeError. The outermost object is at depth 1.
+    pub max_depth: Option<usize>,
+    /// How `undefined` array elements convert
+    pub undefined_in_arrays: UndefinedInArrays,
+    /// How Maps and Sets convert; `None` turns them into `null`
+    pub maps: Option<MapsAs>,
+    /// How primitive wrapper objects convert
+    pub wrappers: WrappersAs,
+}
+
+impl Default for ToJsonOptions {
+    fn default() -> Self {
+        Self {
+            non_finite: NonFinite::Null,
+            max_depth: None,
+            undefined_in_arrays: UndefinedInArrays::Null,
+            maps: None,
+            wrappers: WrappersAs::Unwrap,
+        }
+    }
 }
 
-/// Convert a JsValue to JSON, tracking visited objects for circular reference detection
-fn js_value_to_json_with_visited(
+/// Convert a JsValue to JSON as `options` says
+pub fn js_value_to_json_opts(
     value: &JsValue,
-    visited: &mut FxHashSet<usize>,
+    options: &ToJsonOptions,
 ) -> Result<serde_json::Value, JsError> {
-    Ok(match value {
-        JsValue::Undefined => serde_json::Value::Null,
-        JsValue::Null => serde_json::Value::Null,
-        JsValue::Boolean(b) => serde_json::Value::Bool(*b),
-        JsValue::Number(n) => {
-            if n.is_finite() {
-                // Check if the number is a whole integer that fits in i64
-                if math::fract(*n) == 0.0 && *n >= i64::MIN as f64 && *n <= i64::MAX as f64 {
-                    serde_json::Value::Number(serde_json::Number::from(*n as i64))
-                } else {
-                    serde_json::Value::Number(
-                        serde_json::Number::from_f64(*n).unwrap_or(serde_json::Number::from(0)),
-                    )
+    let mut budget = usize::MAX;
+    JsonConverter::new(options, &mut budget, &mut || Ok(())).run(value)
+}
+
+/// Convert a JsValue to JSON as `JSON.stringify` does.
+///
+/// `budget` is the number of output bytes allowed, and `check` runs once per
+/// value so interrupts and fuel limits apply mid-walk.
+fn stringify_to_json(
+    value: &JsValue,
+    budget: &mut usize,
+    check: &mut dyn FnMut() -> Result<(), JsError>,
+) -> Result<serde_json::Value, JsError> {
+    JsonConverter::new(&ToJsonOptions::default(), budget, check).run(value)
+}
+
+/// Object key for a Map key under [`MapsAs::Object`]
+fn map_key_to_json_key(key: &JsValue) -> Result<String, JsError> {
+    match key {
+        JsValue::String(s) => Ok(s.to_string()),
+        JsValue::Number(n) => Ok(crate::value::number_to_string(*n)),
+        JsValue::Boolean(b) => Ok(b.to_string()),
+        JsValue::Null => Ok("null".to_string()),
+        JsValue::Undefined => Ok("undefined".to_string()),
+        JsValue::Symbol(_) | JsValue::Object(_) => Err(JsError::type_error(
+            "Cannot convert a Map with symbol or object keys to a JSON object",
+        )),
+    }
+}
+
+/// Deduct `len` bytes of output from the stringify budget
+fn charge_json_output(budget: &mut usize, len: usize) -> Result<(), JsError> {
+    *budget = budget
+        .checked_sub(len)
+        .ok_or_else(|| JsError::range_error("Invalid string length"))?;
+    Ok(())
+}
+
+/// A value the options refuse to convert
+enum Refused {
+    NonFinite(f64),
+    Undefined,
+    TooDeep(usize),
+    Wrapper(&'static str),
+}
+
+/// Walks a value converting it to JSON, tracking visited objects for circular
+/// reference detection.
+///
+/// Each value deducts a lower bound of its compact serialized size from
+/// `budget`, so huge (or exponentially shared) structures fail with a
+/// RangeError before being fully materialized.
+///
+/// The path to a refused value is only built once it is refused: each
+/// container adds its key to `refused` as the error passes back up.
+struct JsonConverter<'a> {
+    options: &'a ToJsonOptions,
+    visited: FxHashSet<usize>,
+    budget: &'a mut usize,
+    check: &'a mut dyn FnMut() -> Result<(), JsError>,
+    /// The refused value and its path, innermost key first
+    refused: Option<(Refused, Vec<String>)>,
+    errors: ErrorsAs,
+    /// Error.prototype, to tell errors apart when `errors` isn't `Properties`
+    error_prototype: Option<Gc<JsObject>>,
+}
+
+impl<'a> JsonConverter<'a> {
+    fn new(
+        options: &'a ToJsonOptions,
+        budget: &'a mut usize,
+        check: &'a mut dyn FnMut() -> Result<(), JsError>,
+    ) -> Self {
+        Self {
+            options,
+            visited: FxHashSet::default(),
+            budget,
+            check,
+            refused: None,
+            errors: ErrorsAs::Properties,
+            error_prototype: None,
+        }
+    }
+
+    /// Convert errors inheriting from `error_prototype` as `errors` says
+    fn with_errors(mut self, errors: ErrorsAs, error_prototype: Gc<JsObject>) -> Self {
+        self.errors = errors;
+        self.error_prototype = Some(error_prototype);
+        self
+    }
+
+    fn run(mut self, value: &JsValue) -> Result<serde_json::Value, JsError> {
+        self.convert(value, 0)
+            .map_err(|err| match self.refused.take() {
+                Some((refused, path)) => refused_error(refused, &path),
+                None => err,
+            })
+    }
+
+    /// Note a refused value; `run` replaces the returned error with one
+    /// naming its path
+    fn refuse(&mut self, refused: Refused) -> JsError {
+        self.refused = Some((refused, Vec::new()));
+        JsError::type_error("Cannot convert value to JSON")
+    }
+
+    /// Add `key` to the path of a refused value inside the current container
+    fn at(&mut self, key: impl ToString, err: JsError) -> JsError {
+        if let Some((_, path)) = &mut self.refused {
+            path.push(key.to_string());
+        }
+        err
+    }
+
+    fn number(&mut self, n: f64) -> Result<serde_json::Value, JsError> {
+        if !n.is_finite() {
+            return match self.options.non_finite {
+                NonFinite::Null => Ok(serde_json::Value::Null),
+                NonFinite::String => {
+                    Ok(serde_json::Value::String(crate::value::number_to_string(n)))
                 }
+                NonFinite::Error => Err(self.refuse(Refused::NonFinite(n))),
+            };
+        }
+        // Whole integers that fit in i64 stay integers
+        Ok(
+            if math::fract(n) == 0.0 && n >= i64::MIN as f64 && n <= i64::MAX as f64 {
+                serde_json::Value::Number(serde_json::Number::from(n as i64))
             } else {
-                serde_json::Value::Null
+                serde_json::Value::Number(
+                    serde_json::Number::from_f64(n).unwrap_or(serde_json::Number::from(0)),
+                )
+            },
+        )
+    }
+
+    /// Convert `value`, nested `depth` objects or arrays deep
+    fn convert(&mut self, value: &JsValue, depth: usize) -> Result<serde_json::Value, JsError> {
+        (self.check)()?;
+        let approx_len = match value {
+            JsValue::String(s) => s.len() + 2,
+            JsValue::Object(_) => 2,
+            _ => 4,
+        };
+        charge_json_output(self.budget, approx_len)?;
+
+        Ok(match value {
+            JsValue::Undefined => serde_json::Value::Null,
+            JsValue::Null => serde_json::Value::Null,
+            JsValue::Boolean(b) => serde_json::Value::Bool(*b),
+            JsValue::Number(n) => self.number(*n)?,
+            JsValue::String(s) => serde_json::Value::String(s.to_string()),
+            JsValue::Symbol(_) => serde_json::Value::Null, // Symbols are ignored in JSON
+            JsValue::Object(obj) => {
+                // Check for circular reference using object's unique ID
+                let obj_id = obj.id();
+                if self.visited.contains(&obj_id) {
+                    return Err(JsError::type_error(
+                        "Converting circular structure to JSON".to_string(),
+                    ));
+                }
+                if self.options.max_depth.is_some_and(|max| depth >= max) && is_json_container(obj)
+                {
+                    return Err(self.refuse(Refused::TooDeep(depth)));
+                }
+                self.visited.insert(obj_id);
+                let result = self.object(obj, depth + 1);
+                // Remove from visited set after processing
+                self.visited.remove(&obj_id);
+                result?
+            }
+        })
+    }
+
+    /// Convert an object whose members are `depth` deep
+    fn object(&mut self, obj: &Gc<JsObject>, depth: usize) -> Result<serde_json::Value, JsError> {
+        let obj_ref = obj.borrow();
+        if self.options.wrappers == WrappersAs::Error {
+            let kind = match obj_ref.exotic {
+                ExoticObject::Boolean(_) => Some("Boolean"),
+                ExoticObject::Number(_) => Some("Number"),
+                ExoticObject::StringObj(_) => Some("String"),
+                _ => None,
+            };
+            if let Some(kind) = kind {
+                return Err(self.refuse(Refused::Wrapper(kind)));
+            }
+        }
+        if let Some(elements) = obj_ref.array_elements() {
+            let mut arr = Vec::with_capacity(elements.len());
+            for (i, val) in elements.iter().enumerate() {
+                charge_json_output(self.budget, 1)?;
+                if matches!(val, JsValue::Undefined) {
+                    match self.options.undefined_in_arrays {
+                        UndefinedInArrays::Null => {}
+                        UndefinedInArrays::Skip => continue,
+                        UndefinedInArrays::Error => {
+                            let err = self.refuse(Refused::Undefined);
+                            return Err(self.at(i, err));
+                        }
+                    }
+                }
+                match self.convert(val, depth) {
+                    Ok(json) => arr.push(json),
+                    Err(err) => return Err(self.at(i, err)),
+                }
             }
+            return Ok(serde_json::Value::Array(arr));
         }
-        JsValue::String(s) => serde_json::Value::String(s.to_string()),
-        JsValue::Symbol(_) => serde_json::Value::Null, // Symbols are ignored in JSON
-        JsValue::Object(obj) => {
-            // Check for circular reference using object's unique ID
-            let obj_id = obj.id();
-            if visited.contains(&obj_id) {
-                return Err(JsError::type_error(
-                    "Converting circular structure to JSON".to_string(),
-                ));
+
+        Ok(match &obj_ref.exotic {
+            // Array is handled above by array_elements() check
+            ExoticObject::Array { .. } | ExoticObject::Function(_) => serde_json::Value::Null,
+            ExoticObject::Map { entries } => {
+                let entries: Vec<(JsValue, JsValue)> = entries
+                    .iter()
+                    .map(|(key, value)| (key.0.clone(), value.clone()))
+                    .collect();
+                drop(obj_ref);
+                self.map(&entries, depth)?
+            }
+            ExoticObject::Set { entries } => {
+                let values: Vec<JsValue> = entries.iter().map(|value| value.0.clone()).collect();
+                drop(obj_ref);
+                self.set(&values, depth)?
             }
-            visited.insert(obj_id);
-
-            let result = {
-                let obj_ref = obj.borrow();
-                if let Some(elements) = obj_ref.array_elements() {
-                    let mut arr = Vec::with_capacity(elements.len());
-                    for val in elements {
-                        arr.push(js_value_to_json_with_visited(val, visited)?);
+            ExoticObject::Date { timestamp } => {
+                // Dates serialize as their ISO string
+                serde_json::Value::String(format_timestamp_iso(*timestamp))
+            }
+            ExoticObject::RegExp { .. } => serde_json::Value::Object(serde_json::Map::new()),
+            ExoticObject::Generator(_) | ExoticObject::BytecodeGenerator(_) => {
+                serde_json::Value::Null
+            }
+            ExoticObject::Promise(_) => serde_json::Value::Null,
+            ExoticObject::Environment(_) => serde_json::Value::Null, // Internal type
+            ExoticObject::Enum(data) => {
+                // Enums serialize with forward and reverse mappings
+                let members: Vec<(String, JsValue)> = data
+                    .members
+                    .iter()
+                    .map(|member| (member.name.to_string(), member.value.clone()))
+                    .collect();
+                drop(obj_ref);
+                let mut map = serde_json::Map::new();
+                // Add forward mappings (name -> value)
+                for (name, value) in &members {
+                    match self.convert(value, depth) {
+                        Ok(json) => map.insert(name.clone(), json),
+                        Err(err) => return Err(self.at(name, err)),
+                    };
+                }
+                // Add reverse mappings (numeric value -> name)
+                for (name, value) in members {
+                    if let JsValue::Number(n) = value {
+                        map.insert(n.to_string(), serde_json::Value::String(name));
                     }
-                    serde_json::Value::Array(arr)
+                }
+                serde_json::Value::Object(map)
+            }
+            ExoticObject::Ordinary => {
+                // Ordinary objects serialize with their properties
+                let mut map = serde_json::Map::new();
+                // First collect keys to avoid borrowing issues
+                drop(obj_ref);
+                let members = error_members(obj, self.errors, self.error_prototype.as_ref());
+                let props: Vec<_> = if let Some(members) = members {
+                    members
+                        .into_iter()
+                        .map(|(k, value)| (k.to_string(), value))
+                        .collect()
                 } else {
-                    match &obj_ref.exotic {
-                        // Array is handled above by array_elements() check
-                        ExoticObject::Array { .. } | ExoticObject::Function(_) => {
-                            serde_json::Value::Null
-                        }
-                        ExoticObject::Map { .. } => serde_json::Value::Null,
-                        ExoticObject::Set { .. } => serde_json::Value::Null,
-                        ExoticObject::Date { timestamp } => {
-                            // Dates serialize as their ISO string
-                            serde_json::Value::String(format_timestamp_iso(*timestamp))
-                        }
-                        ExoticObject::RegExp { .. } => {
-                            serde_json::Value::Object(serde_json::Map::new())
-                        }
-                        ExoticObject::Generator(_) | ExoticObject::BytecodeGenerator(_) => {
-                            serde_json::Value::Null
-                        }
-                        ExoticObject::Promise(_) => serde_json::Value::Null,
-                        ExoticObject::Environment(_) => serde_json::Value::Null, // Internal type
-                        ExoticObject::Enum(data) => {
-                            // Enums serialize with forward and reverse mappings
-                            let mut map = serde_json::Map::new();
-                            // Add forward mappings (name -> value)
-                            for member in &data.members {
-                                let json_val =
-                                    js_value_to_json_with_visited(&member.value, visited)?;
-                                map.insert(member.name.to_string(), json_val);
-                            }
-                            // Add reverse mappings (numeric value -> name)
-                            for member in &data.members {
-                                if let JsValue::Number(n) = &member.value {
-                                    map.insert(
-                                        n.to_string(),
-                                        serde_json::Value::String(member.name.to_string()),
-                                    );
-                                }
-                            }
-                            serde_json::Value::Object(map)
-                        }
-                        ExoticObject::Ordinary => {
-                            // Ordinary objects serialize with their properties
-                            let mut map = serde_json::Map::new();
-                            // First collect keys to avoid borrowing issues
-                            let props: Vec<_> = obj_ref
-                                .properties
-                                .iter()
-                                .filter(|(_, prop)| prop.enumerable())
-                                .map(|(k, p)| (k.to_string(), p.value.clone()))
-                                .collect();
-                            drop(obj_ref); // Release borrow before recursive calls
-
-                            for (key, val) in props {
-                                let json_val = js_value_to_json_with_visited(&val, visited)?;
-                                // Skip undefined values in objects
-                                if json_val != serde_json::Value::Null
-                                    || !matches!(val, JsValue::Undefined)
-                                {
-                                    map.insert(key, json_val);
-                                }
-                            }
-                            serde_json::Value::Object(map)
-                        }
-                        ExoticObject::Proxy(_) => {
-                            // Proxies are serialized as their target (or could trap toJSON)
-                            // For now, serialize as null to match JSON.stringify behavior
-                            serde_json::Value::Null
-                        }
-                        ExoticObject::Boolean(b) => {
-                            // Boolean wrapper objects serialize as their primitive value
-                            serde_json::Value::Bool(*b)
-                        }
-                        ExoticObject::Number(n) => {
-                            // Number wrapper objects serialize as their primitive value
-                            if n.is_finite() {
-                                serde_json::Value::Number(
-                                    serde_json::Number::from_f64(*n)
-                                        .unwrap_or(serde_json::Number::from(0)),
-                                )
-                            } else {
-                                serde_json::Value::Null
-                            }
-                        }
-                        ExoticObject::StringObj(s) => {
-                            // String wrapper objects serialize as their primitive value
-                            serde_json::Value::String(s.to_string())
-                        }
-                        ExoticObject::RawJSON(raw) => {
-                            // RawJSON objects are serialized as their raw JSON value
-                            // We already validated the JSON when creating the RawJSON object,
-                            // so this parse should never fail
-                            serde_json::from_str(raw.as_str()).unwrap_or(serde_json::Value::Null)
-                        }
-                        ExoticObject::Symbol(_) => {
-                            // Symbol wrapper objects serialize to undefined (null in JSON)
-                            serde_json::Value::Null
-                        }
-                        ExoticObject::PendingOrder { .. } => {
-                            // PendingOrder markers serialize to null
-                            serde_json::Value::Null
-                        }
+                    let mut obj_mut = obj.borrow_mut();
+                    let keys = obj_mut.enumerable_own_keys();
+                    keys.into_iter()
+                        .filter(|k| !k.is_symbol())
+                        .filter_map(|k| {
+                            let value = obj_mut.properties.get(&k)?.value.clone();
+                            Some((k.to_string(), value))
+                        })
+                        .collect()
+                }; // Release borrow before recursive calls
+
+                for (key, val) in props {
+                    charge_json_output(self.budget, key.len() + 3)?;
+                    let json_val = match self.convert(&val, depth) {
+                        Ok(json) => json,
+                        Err(err) => return Err(self.at(key, err)),
+                    };
+                    // Skip undefined values in objects
+                    if json_val != serde_json::Value::Null || !matches!(val, JsValue::Undefined) {
+                        map.insert(key, json_val);
                     }
                 }
-            };
+                serde_json::Value::Object(map)
+            }
+            ExoticObject::Proxy(_) => {
+                // Proxies are serialized as their target (or could trap toJSON)
+                // For now, serialize as null to match JSON.stringify behavior
+                serde_json::Value::Null
+            }
+            ExoticObject::Boolean(b) => {
+                // Boolean wrapper objects serialize as their primitive value
+                serde_json::Value::Bool(*b)
+            }
+            ExoticObject::Number(n) => {
+                // Number wrapper objects serialize as their primitive value
+                let n = *n;
+                drop(obj_ref);
+                self.number(n)?
+            }
+            ExoticObject::StringObj(s) => {
+                // String wrapper objects serialize as their primitive value
+                serde_json::Value::String(s.to_string())
+            }
+            ExoticObject::RawJSON(raw) => {
+                // RawJSON objects are serialized as their raw JSON value
+                // We already validated the JSON when creating the RawJSON object,
+                // so this parse should never fail
+                serde_json::from_str(raw.as_str()).unwrap_or(serde_json::Value::Null)
+            }
+            ExoticObject::Symbol(_) => {
+                // Symbol wrapper objects serialize to undefined (null in JSON)
+                serde_json::Value::Null
+            }
+            ExoticObject::PendingOrder { .. } | ExoticObject::Host(_) => {
+                // PendingOrder markers and host objects serialize to null
+                serde_json::Value::Null
+            }
+            ExoticObject::Url(data) => {
+                // URLs serialize as their href (URL.prototype.toJSON)
+                serde_json::Value::String(data.record.borrow().href())
+            }
+            ExoticObject::UrlSearchParams(_)
+            | ExoticObject::WeakRef(_)
+            | ExoticObject::FinalizationRegistry(_)
+            | ExoticObject::DisposableStack(_) => serde_json::Value::Object(serde_json::Map::new()),
+        })
+    }
 
-            // Remove from visited set after processing
-            visited.remove(&obj_id);
-            result
-        }
-    })
+    /// Convert a Map's entries as the options say
+    fn map(
+        &mut self,
+        entries: &[(JsValue, JsValue)],
+        depth: usize,
+    ) -> Result<serde_json::Value, JsError> {
+        Ok(match self.options.maps {
+            None => serde_json::Value::Null,
+            Some(MapsAs::Error) => return Err(JsError::type_error("Cannot convert a Map to JSON")),
+            Some(MapsAs::Object) => {
+                let mut map = serde_json::Map::new();
+                for (key, value) in entries {
+                    let key = map_key_to_json_key(key)?;
+                    charge_json_output(self.budget, key.len() + 3)?;
+                    match self.convert(value, depth) {
+                        Ok(json) => map.insert(key, json),
+                        Err(err) => return Err(self.at(key, err)),
+                    };
+                }
+                serde_json::Value::Object(map)
+            }
+            Some(MapsAs::EntriesArray) => {
+                let mut arr = Vec::with_capacity(entries.len());
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    charge_json_output(self.budget, 3)?;
+                    // Each `[key, value]` pair is one level deeper
+                    let pair = self
+                        .convert(key, depth + 1)
+                        .map_err(|err| self.at(0, err))
+                        .and_then(|key| {
+                            let value = self
+                                .convert(value, depth + 1)
+                                .map_err(|err| self.at(1, err))?;
+                            Ok(serde_json::Value::Array(vec![key, value]))
+                        })
+                        .map_err(|err| self.at(i, err))?;
+                    arr.push(pair);
+                }
+                serde_json::Value::Array(arr)
+            }
+        })
+    }
+
+    /// Convert a Set's values as the options say
+    fn set(&mut self, values: &[JsValue], depth: usize) -> Result<serde_json::Value, JsError> {
+        Ok(match self.options.maps {
+            None => serde_json::Value::Null,
+            Some(MapsAs::Error) => return Err(JsError::type_error("Cannot convert a Set to JSON")),
+            Some(MapsAs::Object | MapsAs::EntriesArray) => {
+                let mut arr = Vec::with_capacity(values.len());
+                for (i, value) in values.iter().enumerate() {
+                    charge_json_output(self.budget, 1)?;
+                    match self.convert(value, depth) {
+                        Ok(json) => arr.push(json),
+                        Err(err) => return Err(self.at(i, err)),
+                    }
+                }
+                serde_json::Value::Array(arr)
+            }
+        })
+    }
+}
+
+/// Whether `obj` converts to a JSON object or array, and so counts toward
+/// the depth limit
+fn is_json_container(obj: &Gc<JsObject>) -> bool {
+    let obj_ref = obj.borrow();
+    obj_ref.array_elements().is_some()
+        || matches!(
+            obj_ref.exotic,
+            ExoticObject::Ordinary
+                | ExoticObject::Enum(_)
+                | ExoticObject::Map { .. }
+                | ExoticObject::Set { .. }
+        )
+}
+
+/// The error for a value the options refuse, at `path` (innermost key first)
+fn refused_error(refused: Refused, path: &[String]) -> JsError {
+    let pointer = json_pointer(path);
+    match refused {
+        Refused::NonFinite(n) => JsError::type_error(format!(
+            "Cannot convert non-finite number {} to JSON at {}",
+            crate::value::number_to_string(n),
+            pointer
+        )),
+        Refused::Undefined => JsError::type_error(format!(
+            "Cannot convert undefined array element to JSON at {}",
+            pointer
+        )),
+        Refused::TooDeep(depth) => JsError::range_error(format!(
+            "JSON nesting exceeds the maximum depth of {} at {}",
+            depth, pointer
+        )),
+        Refused::Wrapper(kind) => JsError::type_error(format!(
+            "Cannot convert {} wrapper object to JSON at {}",
+            kind, pointer
+        )),
+    }
+}
+
+/// The JSON Pointer (RFC 6901) for `path`, innermost key first; `/` for
+/// the root
+fn json_pointer(path: &[String]) -> String {
+    if path.is_empty() {
+        return String::from("/");
+    }
+    let mut pointer = String::new();
+    for key in path.iter().rev() {
+        pointer.push('/');
+        pointer.push_str(&key.replace('~', "~0").replace('/', "~1"));
+    }
+    pointer
 }
 
 /// Convert a serde_json value to a JsValue using a provided guard.
@@ -326,6 +986,31 @@ pub fn json_to_js_value_with_guard(
     json: &serde_json::Value,
     guard: &Guard<JsObject>,
 ) -> Result<JsValue, JsError> {
+    json_to_js_value_metered(interp, json, guard, false, false)
+}
+
+/// Like [`json_to_js_value_with_guard`], with every object and array of the
+/// result given a null prototype, so the graph reaches no intrinsics
+pub fn json_to_js_value_detached(
+    interp: &mut Interpreter,
+    json: &serde_json::Value,
+    guard: &Guard<JsObject>,
+) -> Result<JsValue, JsError> {
+    json_to_js_value_metered(interp, json, guard, false, true)
+}
+
+/// Shared conversion; `metered` charges fuel per value for script-driven
+/// parses, `detached` leaves objects and arrays without a prototype
+fn json_to_js_value_metered(
+    interp: &mut Interpreter,
+    json: &serde_json::Value,
+    guard: &Guard<JsObject>,
+    metered: bool,
+    detached: bool,
+) -> Result<JsValue, JsError> {
+    if metered {
+        interp.check_budget()?;
+    }
     Ok(match json {
         serde_json::Value::Null => JsValue::Null,
         serde_json::Value::Bool(b) => JsValue::Boolean(*b),
@@ -335,24 +1020,38 @@ pub fn json_to_js_value_with_guard(
             // First build all elements
             let mut elements = Vec::with_capacity(arr.len());
             for item in arr {
-                let val = json_to_js_value_with_guard(interp, item, guard)?;
+                let val = json_to_js_value_metered(interp, item, guard, metered, detached)?;
                 elements.push(val);
             }
             let result = interp.create_array_from(guard, elements);
+            if detached {
+                detach(&result);
+            }
             JsValue::Object(result)
         }
         serde_json::Value::Object(map) => {
             let obj = interp.create_object(guard);
+            if detached {
+                detach(&obj);
+            }
             for (key, value) in map {
-                let js_value = json_to_js_value_with_guard(interp, value, guard)?;
-                let interned_key = PropertyKey::String(interp.intern(key));
-                obj.borrow_mut().set_property(interned_key, js_value);
+                let js_value = json_to_js_value_metered(interp, value, guard, metered, detached)?;
+                // Array-index keys such as "2" become index keys, as in literals
+                let key = interp.property_key(key);
+                obj.borrow_mut().set_property(key, js_value);
             }
             JsValue::Object(obj)
         }
     })
 }
 
+/// Give `obj` a null prototype
+fn detach(obj: &Gc<JsObject>) {
+    let mut obj_ref = obj.borrow_mut();
+    obj_ref.prototype = None;
+    obj_ref.null_prototype = true;
+}
+
 /// Convert a serde_json value to a JsValue using the interpreter's GC space
 pub fn json_to_js_value_with_interp(
     interp: &mut Interpreter,
@@ -384,7 +1083,7 @@ pub fn json_raw_json(
 
     // Per spec: must be valid JSON (parse it to verify)
     let json_str = json_string.as_str();
-    serde_json::from_str::<serde_json::Value>(json_str)
+    parse_json_text(json_str)
         .map_err(|e| JsError::syntax_error(format!("JSON.rawJSON: invalid JSON: {}", e), 0, 0))?;
 
     // Create a RawJSON exotic object
@@ -0,0 +1,534 @@
+//! Lenient JSON dialects for configuration files: JSONC and JSON5
+//!
+//! `parseJSONC` and `parseJSON5` from tsrun:host build the same values
+//! `JSON.parse` would, and each is strict about its own grammar:
+//!
+//! - JSONC is JSON plus `//` and `/* */` comments and trailing commas.
+//! - JSON5 also allows unquoted keys, single-quoted and multi-line strings,
+//!   hexadecimal numbers, leading or trailing decimal points, an explicit
+//!   `+` sign, `Infinity` and `NaN`.
+//!
+//! Errors are SyntaxErrors located at the first character that breaks the
+//! grammar. The host converts JSONC with [`Interpreter::create_from_jsonc`].
+
+use crate::RuntimeValue;
+use crate::error::JsError;
+use crate::gc::Guard;
+use crate::interpreter::Interpreter;
+use crate::prelude::{String, Vec, format};
+use crate::value::{Guarded, JsObject, JsString, JsValue};
+
+/// Deepest nesting of arrays and objects accepted
+const MAX_DEPTH: usize = 128;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dialect {
+    Jsonc,
+    Json5,
+}
+
+impl Dialect {
+    fn name(self) -> &'static str {
+        match self {
+            Dialect::Jsonc => "JSONC",
+            Dialect::Json5 => "JSON5",
+        }
+    }
+}
+
+impl Interpreter {
+    /// Parse JSONC text (JSON with comments and trailing commas) into a value
+    pub fn create_from_jsonc(&mut self, text: &str) -> Result<RuntimeValue, JsError> {
+        let guard = self.heap.create_guard();
+        let value = LenientParser::new(text, Dialect::Jsonc, false).parse(self, &guard)?;
+        Ok(RuntimeValue::with_guard(value, guard))
+    }
+}
+
+/// parseJSONC(text) - parse JSON with comments and trailing commas
+pub(crate) fn parse_jsonc_fn(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    parse_script_arg(interp, args, Dialect::Jsonc)
+}
+
+/// parseJSON5(text) - parse JSON5
+pub(crate) fn parse_json5_fn(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    parse_script_arg(interp, args, Dialect::Json5)
+}
+
+fn parse_script_arg(
+    interp: &mut Interpreter,
+    args: &[JsValue],
+    dialect: Dialect,
+) -> Result<Guarded, JsError> {
+    let Some(JsValue::String(text)) = args.first() else {
+        return Err(JsError::type_error(format!(
+            "parse{} requires a string",
+            dialect.name()
+        )));
+    };
+    let guard = interp.heap.create_guard();
+    let value = LenientParser::new(text.as_str(), dialect, true).parse(interp, &guard)?;
+    if matches!(value, JsValue::Object(_)) {
+        return Ok(Guarded::with_guard(value, guard));
+    }
+    Ok(Guarded::unguarded(value))
+}
+
+struct LenientParser<'a> {
+    text: &'a str,
+    /// Byte offset of the next character
+    pos: usize,
+    dialect: Dialect,
+    depth: usize,
+    /// Charge fuel per value, for script-driven parses
+    metered: bool,
+}
+
+impl<'a> LenientParser<'a> {
+    fn new(text: &'a str, dialect: Dialect, metered: bool) -> Self {
+        Self {
+            text,
+            pos: 0,
+            dialect,
+            depth: 0,
+            metered,
+        }
+    }
+
+    fn json5(&self) -> bool {
+        self.dialect == Dialect::Json5
+    }
+
+    fn rest(&self) -> &'a str {
+        self.text.get(self.pos..).unwrap_or("")
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        self.rest().chars().nth(1)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        if self.rest().starts_with(word) {
+            self.pos += word.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// SyntaxError at byte offset `pos`. The 1-based line and column are
+    /// part of the message, as in `JSON.parse` errors, and of the location.
+    fn error_at(&self, pos: usize, message: &str) -> JsError {
+        let before = self.text.get(..pos).unwrap_or(self.text);
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+        JsError::syntax_error(
+            format!(
+                "{} parse error: {} at line {} column {}",
+                self.dialect.name(),
+                message,
+                line,
+                column
+            ),
+            u32::try_from(line).unwrap_or(u32::MAX),
+            u32::try_from(column).unwrap_or(u32::MAX),
+        )
+    }
+
+    fn unexpected(&self) -> JsError {
+        match self.peek() {
+            Some(c) => self.error_at(self.pos, &format!("unexpected character {:?}", c)),
+            None => self.error_at(self.pos, "unexpected end of input"),
+        }
+    }
+
+    fn parse(
+        mut self,
+        interp: &mut Interpreter,
+        guard: &Guard<JsObject>,
+    ) -> Result<JsValue, JsError> {
+        self.skip_trivia()?;
+        let value = self.parse_value(interp, guard)?;
+        self.skip_trivia()?;
+        if self.peek().is_some() {
+            return Err(self.unexpected());
+        }
+        Ok(value)
+    }
+
+    fn is_whitespace(&self, c: char) -> bool {
+        match c {
+            ' ' | '\t' | '\n' | '\r' => true,
+            // JSON5 takes ECMAScript whitespace and line terminators
+            '\u{FEFF}' => self.json5(),
+            c => self.json5() && c.is_whitespace(),
+        }
+    }
+
+    /// Skip whitespace and comments
+    fn skip_trivia(&mut self) -> Result<(), JsError> {
+        loop {
+            match self.peek() {
+                Some(c) if self.is_whitespace(c) => {
+                    self.bump();
+                }
+                Some('/') if self.peek_second() == Some('/') => {
+                    while let Some(c) = self.peek() {
+                        if matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}') {
+                            break;
+                        }
+                        self.bump();
+                    }
+                }
+                Some('/') if self.peek_second() == Some('*') => {
+                    let start = self.pos;
+                    match self.rest().get(2..).and_then(|rest| rest.find("*/")) {
+                        Some(end) => self.pos += end + 4,
+                        None => return Err(self.error_at(start, "unterminated comment")),
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn parse_value(
+        &mut self,
+        interp: &mut Interpreter,
+        guard: &Guard<JsObject>,
+    ) -> Result<JsValue, JsError> {
+        if self.metered {
+            interp.check_budget()?;
+        }
+        match self.peek() {
+            Some('{') => self.parse_object(interp, guard),
+            Some('[') => self.parse_array(interp, guard),
+            Some('"') => Ok(JsValue::String(JsString::from(self.parse_string()?))),
+            Some('\'') if self.json5() => Ok(JsValue::String(JsString::from(self.parse_string()?))),
+            Some('t') if self.eat_word("true") => Ok(JsValue::Boolean(true)),
+            Some('f') if self.eat_word("false") => Ok(JsValue::Boolean(false)),
+            Some('n') if self.eat_word("null") => Ok(JsValue::Null),
+            Some('-' | '0'..='9') => self.parse_number().map(JsValue::Number),
+            Some('+' | '.' | 'I' | 'N') if self.json5() => self.parse_number().map(JsValue::Number),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn enter(&mut self) -> Result<(), JsError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error_at(self.pos, "nesting too deep"));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn parse_array(
+        &mut self,
+        interp: &mut Interpreter,
+        guard: &Guard<JsObject>,
+    ) -> Result<JsValue, JsError> {
+        self.enter()?;
+        self.bump(); // [
+        let mut elements = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            // Empty array, or a trailing comma
+            if self.eat(']') {
+                break;
+            }
+            elements.push(self.parse_value(interp, guard)?);
+            self.skip_trivia()?;
+            if self.eat(']') {
+                break;
+            }
+            if !self.eat(',') {
+                return Err(match self.peek() {
+                    Some(_) => self.error_at(self.pos, "expected ',' or ']' after array element"),
+                    None => self.unexpected(),
+                });
+            }
+        }
+        self.depth -= 1;
+        Ok(JsValue::Object(interp.create_array_from(guard, elements)))
+    }
+
+    fn parse_object(
+        &mut self,
+        interp: &mut Interpreter,
+        guard: &Guard<JsObject>,
+    ) -> Result<JsValue, JsError> {
+        self.enter()?;
+        self.bump(); // {
+        let obj = interp.create_object(guard);
+        loop {
+            self.skip_trivia()?;
+            // Empty object, or a trailing comma
+            if self.eat('}') {
+                break;
+            }
+            let key = self.parse_key()?;
+            self.skip_trivia()?;
+            if !self.eat(':') {
+                return Err(self.error_at(self.pos, "expected ':' after property name"));
+            }
+            self.skip_trivia()?;
+            let value = self.parse_value(interp, guard)?;
+            let key = interp.property_key(&key);
+            obj.borrow_mut().set_property(key, value);
+            self.skip_trivia()?;
+            if self.eat('}') {
+                break;
+            }
+            if !self.eat(',') {
+                return Err(match self.peek() {
+                    Some(_) => self.error_at(self.pos, "expected ',' or '}' after property value"),
+                    None => self.unexpected(),
+                });
+            }
+        }
+        self.depth -= 1;
+        Ok(JsValue::Object(obj))
+    }
+
+    fn parse_key(&mut self) -> Result<String, JsError> {
+        match self.peek() {
+            Some('"') => self.parse_string(),
+            Some('\'') if self.json5() => self.parse_string(),
+            Some(c) if self.json5() && (is_identifier_start(c) || c == '\\') => {
+                self.parse_identifier()
+            }
+            Some(_) => Err(self.error_at(self.pos, "expected property name")),
+            None => Err(self.unexpected()),
+        }
+    }
+
+    /// JSON5 unquoted key: an ECMAScript IdentifierName
+    fn parse_identifier(&mut self) -> Result<String, JsError> {
+        let mut name = String::new();
+        while let Some(c) = self.peek() {
+            let valid = |c: char| {
+                if name.is_empty() {
+                    is_identifier_start(c)
+                } else {
+                    is_identifier_part(c)
+                }
+            };
+            if c == '\\' {
+                let start = self.pos;
+                self.bump();
+                if !self.eat('u') {
+                    return Err(self.error_at(start, "invalid escape in property name"));
+                }
+                let c = self.parse_unicode_escape(start)?;
+                if !valid(c) {
+                    return Err(self.error_at(start, "invalid character in property name"));
+                }
+                name.push(c);
+            } else if valid(c) {
+                self.bump();
+                name.push(c);
+            } else {
+                break;
+            }
+        }
+        Ok(name)
+    }
+
+    fn parse_string(&mut self) -> Result<String, JsError> {
+        let start = self.pos;
+        let Some(quote) = self.bump() else {
+            return Err(self.unexpected());
+        };
+        let mut out = String::new();
+        loop {
+            let char_pos = self.pos;
+            let Some(c) = self.bump() else {
+                return Err(self.error_at(start, "unterminated string"));
+            };
+            match c {
+                c if c == quote => return Ok(out),
+                '\\' => self.parse_escape(char_pos, &mut out)?,
+                '\n' | '\r' => {
+                    return Err(self.error_at(char_pos, "unescaped line break in string"));
+                }
+                c if !self.json5() && c < ' ' => {
+                    return Err(self.error_at(char_pos, "unescaped control character in string"));
+                }
+                c => out.push(c),
+            }
+        }
+    }
+
+    /// Escape sequence after a backslash at `start`, appended to `out`
+    fn parse_escape(&mut self, start: usize, out: &mut String) -> Result<(), JsError> {
+        let Some(c) = self.bump() else {
+            return Err(self.error_at(start, "unterminated string"));
+        };
+        let decoded = match c {
+            '"' | '\\' | '/' => c,
+            'b' => '\u{08}',
+            'f' => '\u{0C}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => self.parse_unicode_escape(start)?,
+            _ if !self.json5() => return Err(self.error_at(start, "invalid escape sequence")),
+            'v' => '\u{0B}',
+            '0' if !self.peek().is_some_and(|c| c.is_ascii_digit()) => '\0',
+            '1'..='9' | '0' => return Err(self.error_at(start, "invalid escape sequence")),
+            'x' => {
+                let high = self.parse_hex_digits(2, start)?;
+                char::from_u32(high).unwrap_or('\u{FFFD}')
+            }
+            // Line continuation
+            '\r' => {
+                self.eat('\n');
+                return Ok(());
+            }
+            '\n' | '\u{2028}' | '\u{2029}' => return Ok(()),
+            c => c,
+        };
+        out.push(decoded);
+        Ok(())
+    }
+
+    /// `\uXXXX` after the `u`, joining surrogate pairs. Lone surrogates
+    /// become U+FFFD since strings are UTF-8.
+    fn parse_unicode_escape(&mut self, start: usize) -> Result<char, JsError> {
+        let unit = self.parse_hex_digits(4, start)?;
+        if (0xD800..0xDC00).contains(&unit) && self.rest().starts_with("\\u") {
+            let checkpoint = self.pos;
+            self.pos += 2;
+            let low = self.parse_hex_digits(4, start)?;
+            if (0xDC00..0xE000).contains(&low) {
+                let code = 0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00);
+                return Ok(char::from_u32(code).unwrap_or('\u{FFFD}'));
+            }
+            self.pos = checkpoint;
+        }
+        Ok(char::from_u32(unit).unwrap_or('\u{FFFD}'))
+    }
+
+    fn parse_hex_digits(&mut self, count: usize, start: usize) -> Result<u32, JsError> {
+        let mut value = 0;
+        for _ in 0..count {
+            match self.peek().and_then(|c| c.to_digit(16)) {
+                Some(digit) => {
+                    self.bump();
+                    value = value * 16 + digit;
+                }
+                None => return Err(self.error_at(start, "invalid escape sequence")),
+            }
+        }
+        Ok(value)
+    }
+
+    fn skip_digits(&mut self) -> usize {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.bump();
+        }
+        self.pos - start
+    }
+
+    fn parse_number(&mut self) -> Result<f64, JsError> {
+        let start = self.pos;
+        let negative = self.eat('-');
+        if !negative && self.json5() {
+            self.eat('+');
+        }
+        let sign = if negative { -1.0 } else { 1.0 };
+
+        if self.json5() {
+            if self.eat_word("Infinity") {
+                return Ok(sign * f64::INFINITY);
+            }
+            if self.eat_word("NaN") {
+                return Ok(f64::NAN);
+            }
+            if self.peek() == Some('0') && matches!(self.peek_second(), Some('x' | 'X')) {
+                self.pos += 2;
+                let mut value = 0.0;
+                let mut digits = 0;
+                while let Some(digit) = self.peek().and_then(|c| c.to_digit(16)) {
+                    self.bump();
+                    value = value * 16.0 + f64::from(digit);
+                    digits += 1;
+                }
+                if digits == 0 {
+                    return Err(self.error_at(start, "invalid hexadecimal number"));
+                }
+                return Ok(sign * value);
+            }
+        }
+
+        let digits_start = self.pos;
+        let int_digits = if self.eat('0') {
+            if self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                return Err(self.error_at(start, "leading zeros are not allowed"));
+            }
+            1
+        } else {
+            self.skip_digits()
+        };
+        if int_digits == 0 && !(self.json5() && self.peek() == Some('.')) {
+            return Err(self.error_at(start, "invalid number"));
+        }
+        if self.eat('.') {
+            let frac_digits = self.skip_digits();
+            // JSON needs digits after the point; JSON5 needs them on one side
+            if frac_digits == 0 && (!self.json5() || int_digits == 0) {
+                return Err(self.error_at(start, "invalid number"));
+            }
+        }
+        if self.eat('e') || self.eat('E') {
+            if !self.eat('+') {
+                self.eat('-');
+            }
+            if self.skip_digits() == 0 {
+                return Err(self.error_at(start, "invalid number"));
+            }
+        }
+
+        let literal = self.text.get(digits_start..self.pos).unwrap_or("");
+        let value: f64 = literal
+            .parse()
+            .map_err(|_| self.error_at(start, "invalid number"))?;
+        Ok(sign * value)
+    }
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c == '$' || c == '_' || c.is_alphabetic()
+}
+
+fn is_identifier_part(c: char) -> bool {
+    is_identifier_start(c) || c.is_alphanumeric() || c == '\u{200C}' || c == '\u{200D}'
+}
@@ -2,8 +2,11 @@
 
 use crate::error::JsError;
 use crate::interpreter::Interpreter;
-use crate::prelude::{Box, Vec, index_map_new, index_map_with_capacity, vec};
-use crate::value::{CheapClone, ExoticObject, Guarded, JsMapKey, JsValue, PropertyKey};
+use crate::interpreter::builtins::object::check_entries_mutable;
+use crate::prelude::{Box, IndexMap, Vec, format, index_map_new, index_map_with_capacity, vec};
+use crate::value::{
+    CheapClone, ExoticObject, Guarded, JsMapKey, JsObjectRef, JsValue, PropertyKey,
+};
 
 /// Initialize Map.prototype with get, set, has, delete, clear, forEach methods
 pub fn init_map_prototype(interp: &mut Interpreter) {
@@ -18,6 +21,8 @@ pub fn init_map_prototype(interp: &mut Interpreter) {
     interp.register_method(&proto, "keys", map_keys, 0);
     interp.register_method(&proto, "values", map_values, 0);
     interp.register_method(&proto, "entries", map_entries, 0);
+    interp.register_method(&proto, "getOrInsert", map_get_or_insert, 2);
+    interp.register_method(&proto, "getOrInsertComputed", map_get_or_insert_computed, 2);
 
     // Symbol.iterator = Map.prototype.entries (Map iterates over [key, value] pairs by default)
     let well_known = interp.well_known_symbols;
@@ -82,37 +87,35 @@ pub fn map_constructor(
         obj.set_property(size_key, JsValue::Number(0.0));
     }
 
-    // If an iterable is passed, add its entries
-    // First collect all pairs from the array, then add them to the map
-    if let Some(JsValue::Object(arr)) = args.first() {
-        let pairs: Vec<(JsValue, JsValue)> = {
-            let arr_ref = arr.borrow();
-            let mut result = Vec::new();
-            if let Some(elements) = arr_ref.array_elements() {
-                for elem in elements {
-                    if let JsValue::Object(pair_arr) = elem {
-                        let pair_ref = pair_arr.borrow();
-                        if pair_ref.is_array() {
-                            let key = pair_ref
-                                .get_property(&PropertyKey::Index(0))
-                                .unwrap_or(JsValue::Undefined);
-                            let value = pair_ref
-                                .get_property(&PropertyKey::Index(1))
-                                .unwrap_or(JsValue::Undefined);
-                            result.push((key, value));
-                        }
-                    }
-                }
-            }
-            result
+    // If an iterable is passed (an array of pairs, another Map, ...), add its entries.
+    // First collect all pairs, then add them to the map
+    if let Some(iterable) = args.first().filter(|v| !v.is_null_or_undefined()) {
+        let Some(items) = interp.collect_iterator_values(iterable)? else {
+            return Err(JsError::type_error(
+                "Map constructor argument is not iterable",
+            ));
         };
+        let mut pairs: Vec<(JsValue, JsValue)> = Vec::with_capacity(items.len());
+        for item in items {
+            let JsValue::Object(pair_arr) = item else {
+                return Err(JsError::type_error("Iterator value is not an entry object"));
+            };
+            let pair_ref = pair_arr.borrow();
+            let key = pair_ref
+                .get_property(&PropertyKey::Index(0))
+                .unwrap_or(JsValue::Undefined);
+            let value = pair_ref
+                .get_property(&PropertyKey::Index(1))
+                .unwrap_or(JsValue::Undefined);
+            pairs.push((key, value));
+        }
 
         // Now add all pairs to the map
         let size_key = PropertyKey::String(interp.intern("size"));
         let mut map = map_obj.borrow_mut();
         if let ExoticObject::Map { ref mut entries } = map.exotic {
             for (key, value) in pairs {
-                entries.insert(JsMapKey(key), value);
+                entries.insert(JsMapKey::canonical(key), value);
             }
             let len = entries.len();
             map.set_property(size_key, JsValue::Number(len as f64));
@@ -122,6 +125,25 @@ pub fn map_constructor(
     Ok(Guarded::with_guard(JsValue::Object(map_obj), guard))
 }
 
+/// Wrap computed entries in a new Map
+pub(crate) fn create_map_from(
+    interp: &mut Interpreter,
+    entries: IndexMap<JsMapKey, JsValue>,
+) -> Guarded {
+    interp.install_builtin("Map");
+    let size_key = PropertyKey::String(interp.intern("size"));
+    let guard = interp.heap.create_guard();
+    let map_obj = interp.create_object(&guard);
+    {
+        let mut obj = map_obj.borrow_mut();
+        let len = entries.len();
+        obj.exotic = ExoticObject::Map { entries };
+        obj.prototype = Some(interp.map_prototype.clone());
+        obj.set_property(size_key, JsValue::Number(len as f64));
+    }
+    Guarded::with_guard(JsValue::Object(map_obj), guard)
+}
+
 pub fn map_get(
     _interp: &mut Interpreter,
     this: JsValue,
@@ -155,6 +177,8 @@ pub fn map_set(
             "Map.prototype.set called on non-object",
         ));
     };
+    interp.check_write(&map_obj, None)?;
+    check_entries_mutable(&map_obj, "Map.prototype.set")?;
 
     let size_key = PropertyKey::String(interp.intern("size"));
 
@@ -164,7 +188,7 @@ pub fn map_set(
     let mut map = map_obj.borrow_mut();
 
     if let ExoticObject::Map { ref mut entries } = map.exotic {
-        entries.insert(JsMapKey(key), value);
+        entries.insert(JsMapKey::canonical(key), value);
         let len = entries.len();
         map.set_property(size_key, JsValue::Number(len as f64));
     }
@@ -173,6 +197,106 @@ pub fn map_set(
     Ok(Guarded::unguarded(this)) // Return the map for chaining
 }
 
+/// Insert `key` -> `value` into a Map and update its size
+fn map_insert(
+    interp: &mut Interpreter,
+    map_obj: &JsObjectRef,
+    key: JsMapKey,
+    value: JsValue,
+    method: &str,
+) -> Result<(), JsError> {
+    interp.check_write(map_obj, None)?;
+    check_entries_mutable(map_obj, method)?;
+    let size_key = PropertyKey::String(interp.intern("size"));
+    let mut map = map_obj.borrow_mut();
+    if let ExoticObject::Map { ref mut entries } = map.exotic {
+        entries.insert(key, value);
+        let len = entries.len();
+        map.set_property(size_key, JsValue::Number(len as f64));
+    }
+    Ok(())
+}
+
+/// The Map `this` and its current value for `key`, if it has one
+fn map_lookup(
+    this: &JsValue,
+    key: &JsMapKey,
+    method: &str,
+) -> Result<(JsObjectRef, Option<JsValue>), JsError> {
+    let JsValue::Object(map_obj) = this else {
+        return Err(JsError::type_error(format!(
+            "Map.prototype.{} called on non-object",
+            method
+        )));
+    };
+    let existing = match &map_obj.borrow().exotic {
+        ExoticObject::Map { entries } => entries.get(key).cloned(),
+        _ => {
+            return Err(JsError::type_error(format!(
+                "Map.prototype.{} called on non-Map",
+                method
+            )));
+        }
+    };
+    Ok((map_obj.cheap_clone(), existing))
+}
+
+/// Map.prototype.getOrInsert(key, value) - return the existing value, or insert `value`
+pub fn map_get_or_insert(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let key = JsMapKey::canonical(args.first().cloned().unwrap_or(JsValue::Undefined));
+    let value = args.get(1).cloned().unwrap_or(JsValue::Undefined);
+
+    let (map_obj, existing) = map_lookup(&this, &key, "getOrInsert")?;
+    if let Some(existing) = existing {
+        return Ok(Guarded::unguarded(existing));
+    }
+    map_insert(
+        interp,
+        &map_obj,
+        key,
+        value.clone(),
+        "Map.prototype.getOrInsert",
+    )?;
+    Ok(Guarded::unguarded(value))
+}
+
+/// Map.prototype.getOrInsertComputed(key, callback) - return the existing value,
+/// or insert and return `callback(key)`
+pub fn map_get_or_insert_computed(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let key = JsMapKey::canonical(args.first().cloned().unwrap_or(JsValue::Undefined));
+    let callback = args.get(1).cloned().unwrap_or(JsValue::Undefined);
+
+    let (map_obj, existing) = map_lookup(&this, &key, "getOrInsertComputed")?;
+    if !callback.is_callable() {
+        return Err(JsError::type_error(
+            "Map.prototype.getOrInsertComputed callback must be a function",
+        ));
+    }
+    if let Some(existing) = existing {
+        return Ok(Guarded::unguarded(existing));
+    }
+
+    // The callback may itself insert `key`; its result overwrites that entry
+    let Guarded { value, guard } =
+        interp.call_function(callback, JsValue::Undefined, core::slice::from_ref(&key.0))?;
+    map_insert(
+        interp,
+        &map_obj,
+        key,
+        value.clone(),
+        "Map.prototype.getOrInsertComputed",
+    )?;
+    Ok(Guarded { value, guard })
+}
+
 pub fn map_has(
     _interp: &mut Interpreter,
     this: JsValue,
@@ -206,6 +330,8 @@ pub fn map_delete(
             "Map.prototype.delete called on non-object",
         ));
     };
+    interp.check_write(&map_obj, None)?;
+    check_entries_mutable(&map_obj, "Map.prototype.delete")?;
 
     let size_key = PropertyKey::String(interp.intern("size"));
 
@@ -233,6 +359,8 @@ pub fn map_clear(
             "Map.prototype.clear called on non-object",
         ));
     };
+    interp.check_write(&map_obj, None)?;
+    check_entries_mutable(&map_obj, "Map.prototype.clear")?;
 
     let size_key = PropertyKey::String(interp.intern("size"));
 
@@ -305,9 +433,7 @@ pub fn map_keys(
         }
     }
 
-    let guard = interp.heap.create_guard();
-    let arr = interp.create_array_from(&guard, keys);
-    Ok(Guarded::with_guard(JsValue::Object(arr), guard))
+    Ok(create_map_iterator(interp, keys))
 }
 
 pub fn map_values(
@@ -333,9 +459,7 @@ pub fn map_values(
         }
     }
 
-    let guard = interp.heap.create_guard();
-    let arr = interp.create_array_from(&guard, values);
-    Ok(Guarded::with_guard(JsValue::Object(arr), guard))
+    Ok(create_map_iterator(interp, values))
 }
 
 pub fn map_entries(
@@ -349,10 +473,6 @@ pub fn map_entries(
         ));
     };
 
-    // Create an iterator object
-    let guard = interp.heap.create_guard();
-    guard.guard(map_obj.clone());
-
     // Collect entries upfront to avoid borrow issues during iteration
     let raw_entries: Vec<(JsValue, JsValue)>;
     {
@@ -366,16 +486,25 @@ pub fn map_entries(
         }
     }
 
-    // Store entries as an array of [key, value] pairs in the iterator
+    // Build [key, value] pairs, rooted until the iterator holds them
+    let guard = interp.heap.create_guard();
     let mut entry_arrays = Vec::with_capacity(raw_entries.len());
     for (k, v) in raw_entries {
         let arr = interp.create_array_from(&guard, vec![k, v]);
         entry_arrays.push(JsValue::Object(arr));
     }
-    let entries_arr = interp.create_array_from(&guard, entry_arrays);
+
+    Ok(create_map_iterator(interp, entry_arrays))
+}
+
+/// Create an iterator over a snapshot of a Map's keys, values or entries
+fn create_map_iterator(interp: &mut Interpreter, items: Vec<JsValue>) -> Guarded {
+    let guard = interp.heap.create_guard();
+    let entries_arr = interp.create_array_from(&guard, items);
 
     // Create iterator object
     let iter_obj = interp.create_object_raw(&guard);
+    iter_obj.borrow_mut().prototype = Some(interp.iterator_prototype.cheap_clone());
     let entries_key = interp.property_key("__entries__");
     let index_key = interp.property_key("__index__");
     let next_key = interp.property_key("next");
@@ -405,7 +534,7 @@ pub fn map_entries(
         .borrow_mut()
         .set_property(iterator_key, JsValue::Object(self_iterator_fn));
 
-    Ok(Guarded::with_guard(JsValue::Object(iter_obj), guard))
+    Guarded::with_guard(JsValue::Object(iter_obj), guard)
 }
 
 /// Iterator Symbol.iterator function - returns the iterator itself
@@ -497,81 +626,57 @@ pub fn map_group_by(
     let items = args.first().cloned().unwrap_or(JsValue::Undefined);
     let callback = args.get(1).cloned().unwrap_or(JsValue::Undefined);
 
-    // Items must be iterable - for now we support arrays
-    let JsValue::Object(items_ref) = items else {
-        return Err(JsError::type_error("Map.groupBy requires an iterable"));
-    };
-
-    // Guard the inputs
-    let guard = interp.heap.create_guard();
-    guard.guard(items_ref.clone());
-    if let JsValue::Object(cb_obj) = &callback {
-        guard.guard(cb_obj.clone());
+    if items.is_null_or_undefined() {
+        return Err(JsError::type_error(
+            "Map.groupBy called on null or undefined",
+        ));
     }
-
-    // Get array elements
-    let elements: Vec<JsValue> = {
-        let items_borrowed = items_ref.borrow();
-        if let Some(elems) = items_borrowed.array_elements() {
-            elems.to_vec()
-        } else {
-            return Err(JsError::type_error(
-                "Map.groupBy requires an array-like object",
-            ));
-        }
-    };
-
-    // Create a new Map for the result
-    let size_key = PropertyKey::String(interp.intern("size"));
-    let map_obj = interp.create_object(&guard);
-    {
-        let mut obj = map_obj.borrow_mut();
-        obj.exotic = ExoticObject::Map {
-            entries: index_map_new(),
-        };
-        obj.prototype = Some(interp.map_prototype.clone());
-        obj.set_property(size_key.clone(), JsValue::Number(0.0));
+    if !callback.is_callable() {
+        return Err(JsError::type_error(
+            "Map.groupBy callback is not a function",
+        ));
     }
 
+    // Guards the inputs and every grouped key and element until the Map is built
+    let guard = interp.heap.create_guard();
+    items.guard_by(&guard);
+    callback.guard_by(&guard);
+
     // Track groups using IndexMap for O(1) lookup with SameValueZero semantics
     let mut groups = index_map_new::<JsMapKey, Vec<JsValue>>();
-
-    // Iterate and group
-    for (index, item) in elements.into_iter().enumerate() {
-        // Guard the item in case callback triggers GC
-        if let JsValue::Object(item_obj) = &item {
-            guard.guard(item_obj.clone());
-        }
-
-        // Call the callback with (item, index)
+    interp.for_each_iterator_value(&items, |interp, item, index| {
         let key_result = interp.call_function(
             callback.clone(),
             JsValue::Undefined,
             &[item.clone(), JsValue::Number(index as f64)],
         )?;
-
-        let key = key_result.value;
-
-        // Add to existing group or create new one
+        // -0 is normalized to +0, as Map.prototype.set does
+        let key = match key_result.value {
+            JsValue::Number(n) => JsValue::Number(n + 0.0),
+            key => key,
+        };
+        key.guard_by(&guard);
+        item.guard_by(&guard);
         groups.entry(JsMapKey(key)).or_default().push(item);
-    }
+        Ok(())
+    })?;
 
-    // Now build the Map from the groups
-    // First, create all the arrays (which may trigger GC)
+    // Create all the arrays first (which may trigger GC), then the Map
     let mut built_entries = index_map_with_capacity::<JsMapKey, JsValue>(groups.len());
     for (key, items) in groups {
         let arr = interp.create_array_from(&guard, items);
         built_entries.insert(key, JsValue::Object(arr));
     }
 
-    // Then add entries to the map
+    let size_key = PropertyKey::String(interp.intern("size"));
+    let map_obj = interp.create_object(&guard);
     {
-        let mut map = map_obj.borrow_mut();
-        if let ExoticObject::Map { ref mut entries } = map.exotic {
-            *entries = built_entries;
-            let len = entries.len();
-            map.set_property(size_key, JsValue::Number(len as f64));
-        }
+        let mut obj = map_obj.borrow_mut();
+        obj.prototype = Some(interp.map_prototype.clone());
+        obj.set_property(size_key, JsValue::Number(built_entries.len() as f64));
+        obj.exotic = ExoticObject::Map {
+            entries: built_entries,
+        };
     }
 
     Ok(Guarded::with_guard(JsValue::Object(map_obj), guard))
@@ -3,8 +3,9 @@
 use crate::error::JsError;
 use crate::gc::Gc;
 use crate::interpreter::Interpreter;
+use crate::prelude::Vec;
 use crate::prelude::math as prelude_math;
-use crate::value::{Guarded, JsObject, JsValue, Property, PropertyKey};
+use crate::value::{Guarded, JsObject, JsValue, Property, PropertyKey, to_int32, to_uint32};
 
 /// Initialize Math object and bind it to global scope.
 /// Returns the Math object for rooting.
@@ -52,11 +53,17 @@ pub fn init_math(interp: &mut Interpreter) -> Gc<JsObject> {
     interp.register_method(&math_obj, "min", math_min, 2);
     interp.register_method(&math_obj, "max", math_max, 2);
 
+    // Summation
+    interp.register_method(&math_obj, "sumPrecise", math_sum_precise, 1);
+
     // Power and root functions
     interp.register_method(&math_obj, "pow", math_pow, 2);
     interp.register_method(&math_obj, "sqrt", math_sqrt, 1);
     interp.register_method(&math_obj, "cbrt", math_cbrt, 1);
     interp.register_method(&math_obj, "hypot", math_hypot, 2);
+    interp.register_method(&math_obj, "clz32", math_clz32, 1);
+    interp.register_method(&math_obj, "imul", math_imul, 2);
+    interp.register_method(&math_obj, "fround", math_fround, 1);
 
     // Logarithmic and exponential
     interp.register_method(&math_obj, "log", math_log, 1);
@@ -99,83 +106,116 @@ pub fn init_math(interp: &mut Interpreter) -> Gc<JsObject> {
 }
 
 pub fn math_abs(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(n.abs())))
 }
 
 pub fn math_floor(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::floor(n))))
 }
 
 pub fn math_ceil(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::ceil(n))))
 }
 
 pub fn math_round(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
-    Ok(Guarded::unguarded(JsValue::Number(prelude_math::round(n))))
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
+    Ok(Guarded::unguarded(JsValue::Number(js_round(n))))
+}
+
+/// Round half toward +Infinity, keeping -0 for inputs in [-0.5, -0]
+fn js_round(n: f64) -> f64 {
+    if !n.is_finite() || n == 0.0 {
+        return n;
+    }
+    if n > 0.0 && n < 0.5 {
+        return 0.0;
+    }
+    if (-0.5..0.0).contains(&n) {
+        return -0.0;
+    }
+    let floor = prelude_math::floor(n);
+    if n - floor >= 0.5 { floor + 1.0 } else { floor }
 }
 
 pub fn math_trunc(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::trunc(n))))
 }
 
 pub fn math_sign(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
-    let result = if n.is_nan() {
-        f64::NAN
-    } else if n > 0.0 {
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
+    // NaN, +0 and -0 are returned as they are
+    let result = if n > 0.0 {
         1.0
     } else if n < 0.0 {
         -1.0
     } else {
-        0.0
+        n
     };
     Ok(Guarded::unguarded(JsValue::Number(result)))
 }
 
 pub fn math_min(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
     if args.is_empty() {
         return Ok(Guarded::unguarded(JsValue::Number(f64::INFINITY)));
     }
+    // Every argument is coerced, even after a NaN
     let mut min = f64::INFINITY;
     for arg in args {
-        let n = arg.to_number();
-        if n.is_nan() {
-            return Ok(Guarded::unguarded(JsValue::Number(f64::NAN)));
-        }
-        if n < min {
+        let n = interp.coerce_to_number(arg)?;
+        // -0 is smaller than +0
+        if n.is_nan() || min.is_nan() {
+            min = f64::NAN;
+        } else if n < min || (n == 0.0 && min == 0.0 && n.is_sign_negative()) {
             min = n;
         }
     }
@@ -183,20 +223,21 @@ pub fn math_min(
 }
 
 pub fn math_max(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
     if args.is_empty() {
         return Ok(Guarded::unguarded(JsValue::Number(f64::NEG_INFINITY)));
     }
+    // Every argument is coerced, even after a NaN
     let mut max = f64::NEG_INFINITY;
     for arg in args {
-        let n = arg.to_number();
-        if n.is_nan() {
-            return Ok(Guarded::unguarded(JsValue::Number(f64::NAN)));
-        }
-        if n > max {
+        let n = interp.coerce_to_number(arg)?;
+        // +0 is larger than -0
+        if n.is_nan() || max.is_nan() {
+            max = f64::NAN;
+        } else if n > max || (n == 0.0 && max == 0.0 && n.is_sign_positive()) {
             max = n;
         }
     }
@@ -204,41 +245,56 @@ pub fn math_max(
 }
 
 pub fn math_pow(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let base = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
-    let exp = args.get(1).map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let base = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
+    let exp = match args.get(1) {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::powf(
         base, exp,
     ))))
 }
 
 pub fn math_sqrt(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::sqrt(n))))
 }
 
 pub fn math_log(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::ln(n))))
 }
 
 pub fn math_exp(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::exp(n))))
 }
 
@@ -252,186 +308,489 @@ pub fn math_random(
 }
 
 pub fn math_sin(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::sin(n))))
 }
 
 pub fn math_cos(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::cos(n))))
 }
 
 pub fn math_tan(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::tan(n))))
 }
 
 pub fn math_asin(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::asin(n))))
 }
 
 pub fn math_acos(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::acos(n))))
 }
 
 pub fn math_atan(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::atan(n))))
 }
 
 pub fn math_atan2(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let y = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
-    let x = args.get(1).map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let y = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
+    let x = match args.get(1) {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::atan2(
         y, x,
     ))))
 }
 
 pub fn math_sinh(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::sinh(n))))
 }
 
 pub fn math_cosh(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::cosh(n))))
 }
 
 pub fn math_tanh(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::tanh(n))))
 }
 
 pub fn math_asinh(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::asinh(n))))
 }
 
 pub fn math_acosh(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::acosh(n))))
 }
 
 pub fn math_atanh(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::atanh(n))))
 }
 
 pub fn math_cbrt(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::cbrt(n))))
 }
 
 pub fn math_hypot(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    if args.is_empty() {
+    let mut nums = Vec::with_capacity(args.len());
+    for arg in args {
+        nums.push(interp.coerce_to_number(arg)?);
+    }
+    // An infinite component wins over NaN
+    if nums.iter().any(|n| n.is_infinite()) {
+        return Ok(Guarded::unguarded(JsValue::Number(f64::INFINITY)));
+    }
+    if nums.iter().any(|n| n.is_nan()) {
+        return Ok(Guarded::unguarded(JsValue::Number(f64::NAN)));
+    }
+    let largest = nums.iter().fold(0.0_f64, |acc, n| acc.max(n.abs()));
+    if largest == 0.0 {
         return Ok(Guarded::unguarded(JsValue::Number(0.0)));
     }
-    let sum_sq: f64 = args
-        .iter()
-        .map(|v| {
-            let n = v.to_number();
-            n * n
-        })
-        .sum();
-    Ok(Guarded::unguarded(JsValue::Number(prelude_math::sqrt(
-        sum_sq,
-    ))))
+
+    // Scale by the largest component so squaring can't overflow or underflow,
+    // and sum with Kahan compensation
+    let mut sum = 0.0;
+    let mut compensation = 0.0;
+    for n in &nums {
+        let scaled = n / largest;
+        let term = scaled * scaled - compensation;
+        let next = sum + term;
+        compensation = (next - sum) - term;
+        sum = next;
+    }
+    Ok(Guarded::unguarded(JsValue::Number(
+        prelude_math::sqrt(sum) * largest,
+    )))
+}
+
+pub fn math_clz32(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
+    Ok(Guarded::unguarded(JsValue::Number(
+        to_uint32(n).leading_zeros() as f64,
+    )))
+}
+
+/// Math.imul(a, b) - 32-bit integer multiplication, wrapping like C
+pub fn math_imul(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let a = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
+    let b = match args.get(1) {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
+    Ok(Guarded::unguarded(JsValue::Number(
+        to_int32(a).wrapping_mul(to_int32(b)) as f64,
+    )))
+}
+
+pub fn math_fround(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
+    Ok(Guarded::unguarded(JsValue::Number(n as f32 as f64)))
 }
 
 pub fn math_log10(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::log10(n))))
 }
 
 pub fn math_log2(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::log2(n))))
 }
 
 pub fn math_log1p(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::log1p(n))))
 }
 
 pub fn math_expm1(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = args.first().map(|v| v.to_number()).unwrap_or(f64::NAN);
+    let n = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => f64::NAN,
+    };
     Ok(Guarded::unguarded(JsValue::Number(prelude_math::expm1(n))))
 }
+
+/// Math.sumPrecise(iterable): the exact sum of the numbers, rounded once.
+///
+/// Elements that are not numbers throw a `TypeError`, closing the iterator.
+/// An empty iterable sums to -0, as does one of only -0s.
+pub fn math_sum_precise(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let items = args.first().cloned().unwrap_or(JsValue::Undefined);
+    if !matches!(items, JsValue::Object(_)) {
+        return Err(JsError::type_error("Math.sumPrecise requires an iterable"));
+    }
+    let mut sum = PreciseSum::default();
+    interp.for_each_iterator_value(&items, |_, value, _| match value {
+        JsValue::Number(n) => {
+            sum.add(n);
+            Ok(())
+        }
+        _ => Err(JsError::type_error(
+            "Math.sumPrecise requires an iterable of numbers",
+        )),
+    })?;
+    Ok(Guarded::unguarded(JsValue::Number(sum.finish())))
+}
+
+/// 2^1023, half the overflow threshold
+const TWO_1023: f64 = f64::from_bits(0x7FE0_0000_0000_0000);
+
+/// 2^971, the distance between the largest double and the one below it
+const MAX_ULP: f64 = f64::from_bits(0x7CA0_0000_0000_0000);
+
+/// Exact running sum of doubles, kept as non-overlapping partials
+/// (Shewchuk's algorithm) plus a count of 2^1024 overflows, so that
+/// intermediate sums past `f64::MAX` don't lose the result.
+#[derive(Default)]
+struct PreciseSum {
+    /// Partials, smallest magnitude first
+    partials: Vec<f64>,
+    /// Multiples of 2^1024 carried out of the partials
+    overflow: i32,
+    /// Whether a finite non-(-0) value was added
+    nonzero: bool,
+    positive_infinity: bool,
+    negative_infinity: bool,
+    nan: bool,
+}
+
+/// `a + b` and its rounding error, for `|a| >= |b|`
+fn fast_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let hi = a + b;
+    (hi, b - (hi - a))
+}
+
+impl PreciseSum {
+    fn add(&mut self, value: f64) {
+        if value.is_nan() {
+            self.nan = true;
+            return;
+        }
+        if value == f64::INFINITY {
+            self.positive_infinity = true;
+            return;
+        }
+        if value == f64::NEG_INFINITY {
+            self.negative_infinity = true;
+            return;
+        }
+        if value != 0.0 || value.is_sign_positive() {
+            self.nonzero = true;
+        }
+
+        let mut x = value;
+        let mut used = 0;
+        for i in 0..self.partials.len() {
+            let mut y = self.partials.get(i).copied().unwrap_or_default();
+            if x.abs() < y.abs() {
+                core::mem::swap(&mut x, &mut y);
+            }
+            let (mut hi, mut lo) = fast_two_sum(x, y);
+            if hi.is_infinite() {
+                // Carry 2^1024 out of x and redo the sum without overflowing
+                let sign = if hi > 0.0 { 1.0 } else { -1.0 };
+                self.overflow += sign as i32;
+                x = (x - sign * TWO_1023) - sign * TWO_1023;
+                if x.abs() < y.abs() {
+                    core::mem::swap(&mut x, &mut y);
+                }
+                (hi, lo) = fast_two_sum(x, y);
+            }
+            if lo != 0.0 {
+                if let Some(slot) = self.partials.get_mut(used) {
+                    *slot = lo;
+                }
+                used += 1;
+            }
+            x = hi;
+        }
+        self.partials.truncate(used);
+        if x != 0.0 {
+            self.partials.push(x);
+        }
+    }
+
+    fn finish(mut self) -> f64 {
+        if self.nan || (self.positive_infinity && self.negative_infinity) {
+            return f64::NAN;
+        }
+        if self.positive_infinity {
+            return f64::INFINITY;
+        }
+        if self.negative_infinity {
+            return f64::NEG_INFINITY;
+        }
+        if !self.nonzero {
+            return -0.0;
+        }
+
+        // Add the partials from the largest down, stopping once the
+        // remaining ones can't change the rounded result
+        let partials = &mut self.partials;
+        let mut n = partials.len();
+        let (mut hi, mut lo) = (0.0, 0.0);
+
+        if self.overflow != 0 {
+            let next = match n.checked_sub(1) {
+                Some(last) => {
+                    n = last;
+                    partials.get(last).copied().unwrap_or_default()
+                }
+                None => 0.0,
+            };
+            let overflow = f64::from(self.overflow);
+            if self.overflow.abs() > 1 || overflow * next > 0.0 {
+                return overflow * f64::INFINITY;
+            }
+            // Halve everything so 2^1024 - |next| can be computed
+            (hi, lo) = fast_two_sum(overflow * TWO_1023, next / 2.0);
+            lo *= 2.0;
+            if (2.0 * hi).is_infinite() {
+                // Rounds to the largest double only when a smaller partial
+                // of the opposite sign pulls it below the halfway point
+                let below = n
+                    .checked_sub(1)
+                    .and_then(|i| partials.get(i))
+                    .is_some_and(|p| p * hi < 0.0);
+                if hi.abs() == TWO_1023 && lo == -hi.signum() * MAX_ULP / 2.0 && below {
+                    return hi.signum() * f64::MAX;
+                }
+                return hi.signum() * f64::INFINITY;
+            }
+            if lo != 0.0 {
+                partials.truncate(n);
+                partials.push(lo);
+                n += 1;
+                lo = 0.0;
+            }
+            hi *= 2.0;
+        }
+
+        while n > 0 {
+            n -= 1;
+            let y = partials.get(n).copied().unwrap_or_default();
+            (hi, lo) = fast_two_sum(hi, y);
+            if lo != 0.0 {
+                break;
+            }
+        }
+
+        // A rounding error of exactly half an ulp rounds away from zero when
+        // the next partial has the same sign
+        if let Some(next) = n.checked_sub(1).and_then(|i| partials.get(i))
+            && lo * next > 0.0
+        {
+            let y = lo * 2.0;
+            let x = hi + y;
+            if x - hi == y {
+                hi = x;
+            }
+        }
+        hi
+    }
+}
@@ -1,48 +1,68 @@
 //! Built-in function implementations for JavaScript standard library
 
 pub mod array;
+pub mod assert;
 pub mod boolean;
 #[cfg(feature = "console")]
 pub mod console;
 pub mod date;
+pub mod disposable;
+pub mod duration;
 pub mod error;
 pub mod function;
 pub mod generator;
 pub mod global;
 pub mod internal;
+pub mod iterator;
 pub mod json;
+pub mod jsonc;
 pub mod map;
 pub mod math;
 pub mod number;
 pub mod object;
+pub mod performance;
 pub mod promise;
 pub mod proxy;
+pub mod quantity;
 #[cfg(feature = "regex")]
 pub mod regexp;
 pub mod set;
 pub mod string;
 pub mod symbol;
+pub mod url;
+pub mod weakref;
+#[cfg(feature = "yaml")]
+pub mod yaml;
 
 // Re-export public functions from enabled modules
 pub use array::*;
+pub use assert::*;
 pub use boolean::*;
 #[cfg(feature = "console")]
 #[allow(unused_imports)]
 pub use console::*;
 pub use date::*;
+pub use disposable::*;
 pub use error::*;
 pub use function::*;
 pub use generator::*;
 pub use global::*;
 pub use internal::*;
+pub use iterator::*;
 pub use json::*;
 pub use map::*;
 pub use math::*;
 pub use number::*;
 pub use object::*;
+pub use quantity::*;
 #[cfg(feature = "regex")]
 #[allow(unused_imports)]
 pub use regexp::*;
 pub use set::*;
 pub use string::*;
 pub use symbol::*;
+pub use url::*;
+pub use weakref::*;
+#[cfg(feature = "yaml")]
+#[allow(unused_imports)]
+pub use yaml::*;
@@ -4,7 +4,7 @@ use crate::error::JsError;
 use crate::gc::Gc;
 use crate::interpreter::Interpreter;
 use crate::prelude::{String, ToString, Vec, format, math};
-use crate::value::{ExoticObject, Guarded, JsObject, JsString, JsValue, PropertyKey};
+use crate::value::{ExoticObject, Guarded, JsObject, JsString, JsValue, Property, PropertyKey};
 
 /// Initialize Number.prototype with toFixed, toString, toPrecision, toExponential, valueOf
 pub fn init_number_prototype(interp: &mut Interpreter) {
@@ -26,11 +26,10 @@ pub fn number_constructor_fn(
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
     // Get the number value from argument
-    let num_val = args
-        .first()
-        .cloned()
-        .unwrap_or(JsValue::Number(0.0))
-        .to_number();
+    let num_val = match args.first() {
+        Some(v) => interp.coerce_to_number(v)?,
+        None => 0.0,
+    };
 
     // Check if called with `new` (this will be a fresh object with Number.prototype)
     if let JsValue::Object(obj) = &this {
@@ -114,8 +113,16 @@ pub fn create_number_constructor(interp: &mut Interpreter) -> Gc<JsObject> {
     interp.register_method(&constructor, "isFinite", number_is_finite, 1);
     interp.register_method(&constructor, "isInteger", number_is_integer, 1);
     interp.register_method(&constructor, "isSafeInteger", number_is_safe_integer, 1);
-    interp.register_method(&constructor, "parseFloat", number_parse_float, 1);
-    interp.register_method(&constructor, "parseInt", number_parse_int, 2);
+    // Number.parseFloat and Number.parseInt are the global functions themselves
+    for name in ["parseFloat", "parseInt"] {
+        let key = PropertyKey::String(interp.intern(name));
+        let global_fn = interp.global.borrow().get_property(&key);
+        if let Some(func) = global_fn {
+            constructor
+                .borrow_mut()
+                .define_property(key, Property::with_attributes(func, true, false, true));
+        }
+    }
 
     // Constants
     let max_value_key = PropertyKey::String(interp.intern("MAX_VALUE"));
@@ -130,7 +137,8 @@ pub fn create_number_constructor(interp: &mut Interpreter) -> Gc<JsObject> {
     {
         let mut c = constructor.borrow_mut();
         c.set_property(max_value_key, JsValue::Number(f64::MAX));
-        c.set_property(min_value_key, JsValue::Number(f64::MIN_POSITIVE));
+        // Smallest positive subnormal, not f64::MIN_POSITIVE (the smallest normal)
+        c.set_property(min_value_key, JsValue::Number(f64::from_bits(1)));
         c.set_property(max_safe_key, JsValue::Number(9007199254740991.0));
         c.set_property(min_safe_key, JsValue::Number(-9007199254740991.0));
         c.set_property(nan_key, JsValue::Number(f64::NAN));
@@ -155,48 +163,6 @@ pub fn create_number_constructor(interp: &mut Interpreter) -> Gc<JsObject> {
     constructor
 }
 
-/// Number.parseFloat - same as global parseFloat
-pub fn number_parse_float(
-    interp: &mut Interpreter,
-    _this: JsValue,
-    args: &[JsValue],
-) -> Result<Guarded, JsError> {
-    let arg = args.first().cloned().unwrap_or(JsValue::Undefined);
-    let s = interp.to_js_string(&arg).to_string();
-
-    let trimmed = s.trim_start();
-    let result = trimmed.parse::<f64>().unwrap_or(f64::NAN);
-    Ok(Guarded::unguarded(JsValue::Number(result)))
-}
-
-/// Number.parseInt - same as global parseInt
-pub fn number_parse_int(
-    interp: &mut Interpreter,
-    _this: JsValue,
-    args: &[JsValue],
-) -> Result<Guarded, JsError> {
-    let arg = args.first().cloned().unwrap_or(JsValue::Undefined);
-    let s = interp.to_js_string(&arg).to_string();
-    let radix = args.get(1).map(|v| v.to_number() as i32).unwrap_or(10);
-
-    let trimmed = s.trim_start();
-
-    // Handle radix
-    let radix = if radix == 0 {
-        10
-    } else if !(2..=36).contains(&radix) {
-        return Ok(Guarded::unguarded(JsValue::Number(f64::NAN)));
-    } else {
-        radix
-    };
-
-    let result = i64::from_str_radix(trimmed, radix as u32)
-        .map(|n| n as f64)
-        .unwrap_or(f64::NAN);
-
-    Ok(Guarded::unguarded(JsValue::Number(result)))
-}
-
 // Number.isNaN - stricter, no type coercion
 pub fn number_is_nan(
     _interp: &mut Interpreter,
@@ -258,7 +224,8 @@ pub fn number_to_fixed(
     this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = get_number_value(interp, &this)?;
+    // -0 formats without its sign, as in Number::toString
+    let n = get_number_value(interp, &this)? + 0.0;
     let digits = args.first().map(|v| v.to_number() as i32).unwrap_or(0);
 
     if !(0..=100).contains(&digits) {
@@ -267,24 +234,29 @@ pub fn number_to_fixed(
         ));
     }
 
-    let result = format!("{:.prec$}", n, prec = digits as usize);
-    Ok(Guarded::unguarded(JsValue::String(JsString::from(result))))
-}
+    // From 1e21 up the spec falls back to ToString, exponent and all
+    if !n.is_finite() || n.abs() >= 1e21 {
+        return Ok(Guarded::unguarded(JsValue::String(JsString::from(
+            crate::value::number_to_string(n),
+        ))));
+    }
 
-/// Format a number as a string in JavaScript format
-/// (handles Infinity, -Infinity, NaN properly)
-fn format_number_js(n: f64) -> String {
-    if n.is_nan() {
-        "NaN".to_string()
-    } else if n.is_infinite() {
-        if n.is_sign_positive() {
-            "Infinity".to_string()
-        } else {
-            "-Infinity".to_string()
-        }
-    } else {
-        format!("{}", n)
+    // The digits of n * 10^digits rounded to an integer
+    let (exact, exp) = exact_digits(n);
+    let (mut int_digits, _) = round_digits(&exact, exp + 1 + digits);
+    let frac_len = digits as usize;
+    if int_digits.len() <= frac_len {
+        let zeros = frac_len + 1 - int_digits.len();
+        int_digits.insert_str(0, &"0".repeat(zeros));
     }
+    if frac_len > 0 {
+        int_digits.insert(int_digits.len() - frac_len, '.');
+    }
+
+    let sign = if n < 0.0 { "-" } else { "" };
+    Ok(Guarded::unguarded(JsValue::String(JsString::from(
+        format!("{}{}", sign, int_digits),
+    ))))
 }
 
 // Number.prototype.toString
@@ -302,51 +274,15 @@ pub fn number_to_string(
         ));
     }
 
-    if radix == 10 {
-        return Ok(Guarded::unguarded(JsValue::String(JsString::from(
-            format_number_js(n),
-        ))));
-    }
-
-    // For other radixes, we need integer conversion
-    if !n.is_finite() || math::fract(n) != 0.0 {
+    if radix == 10 || !n.is_finite() {
         return Ok(Guarded::unguarded(JsValue::String(JsString::from(
-            format_number_js(n),
+            crate::value::number_to_string(n),
         ))));
     }
 
-    let int_val = n as i64;
-    let result = match radix {
-        2 => format!("{:b}", int_val.abs()),
-        8 => format!("{:o}", int_val.abs()),
-        16 => format!("{:x}", int_val.abs()),
-        _ => {
-            // Generic radix conversion
-            const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
-            let mut num = int_val.abs();
-            let mut result = String::new();
-            while num > 0 {
-                let digit_idx = (num % radix as i64) as usize;
-                // radix is validated to be 2-36, so digit_idx is always 0-35
-                if let Some(&ch) = DIGITS.get(digit_idx) {
-                    result.insert(0, ch as char);
-                }
-                num /= radix as i64;
-            }
-            if result.is_empty() {
-                result = "0".to_string();
-            }
-            result
-        }
-    };
-
-    let result = if int_val < 0 {
-        format!("-{}", result)
-    } else {
-        result
-    };
-
-    Ok(Guarded::unguarded(JsValue::String(JsString::from(result))))
+    Ok(Guarded::unguarded(JsValue::String(JsString::from(
+        radix_string(n, f64::from(radix)),
+    ))))
 }
 
 // Number.prototype.toPrecision
@@ -355,62 +291,48 @@ pub fn number_to_precision(
     this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = get_number_value(interp, &this)?;
+    let n = get_number_value(interp, &this)? + 0.0;
 
     if args.is_empty() || matches!(args.first(), Some(JsValue::Undefined)) {
         return Ok(Guarded::unguarded(JsValue::String(JsString::from(
-            format_number_js(n),
+            crate::value::number_to_string(n),
         ))));
     }
 
     let precision = args.first().map(|v| v.to_number() as i32).unwrap_or(1);
 
-    if !(1..=100).contains(&precision) {
-        return Err(JsError::range_error(
-            "toPrecision() argument must be between 1 and 100",
-        ));
-    }
-
     if !n.is_finite() {
         return Ok(Guarded::unguarded(JsValue::String(JsString::from(
-            format_number_js(n),
+            crate::value::number_to_string(n),
         ))));
     }
 
-    let result = format!("{:.prec$e}", n, prec = (precision - 1) as usize);
-    // Parse and reformat to match JS behavior
-    let parts: Vec<&str> = result.split('e').collect();
-    if let [mantissa_str, exp_str] = parts.as_slice() {
-        let mantissa = mantissa_str.parse::<f64>().unwrap_or(0.0);
-        let exp: i32 = exp_str.parse().unwrap_or(0);
-
-        // If exponent is small enough, use fixed notation
-        if exp >= 0 && exp < precision {
-            let decimals = precision - 1 - exp;
-            if decimals >= 0 {
-                return Ok(Guarded::unguarded(JsValue::String(JsString::from(
-                    format!("{:.prec$}", n, prec = decimals as usize),
-                ))));
-            }
-        } else if (-4..0).contains(&exp) {
-            // For small numbers, use fixed notation
-            let decimals = precision - 1 - exp;
-            if (0..=100).contains(&decimals) {
-                return Ok(Guarded::unguarded(JsValue::String(JsString::from(
-                    format!("{:.prec$}", n, prec = decimals as usize),
-                ))));
-            }
-        }
-
-        // Use exponential notation
-        let exp_sign = if exp >= 0 { "+" } else { "" };
-        return Ok(Guarded::unguarded(JsValue::String(JsString::from(
-            format!("{}e{}{}", mantissa, exp_sign, exp),
-        ))));
+    if !(1..=100).contains(&precision) {
+        return Err(JsError::range_error(
+            "toPrecision() argument must be between 1 and 100",
+        ));
     }
 
+    let (exact, exp) = exact_digits(n);
+    let (mut digits, carry) = round_digits(&exact, precision);
+    digits.truncate(precision as usize);
+    let exp = exp + carry;
+
+    let sign = if n < 0.0 { "-" } else { "" };
+    let result = if exp < -6 || exp >= precision {
+        exponential_notation(&digits, exp)
+    } else if exp >= 0 {
+        let (int_part, frac_part) = digits.split_at(exp as usize + 1);
+        if frac_part.is_empty() {
+            int_part.to_string()
+        } else {
+            format!("{}.{}", int_part, frac_part)
+        }
+    } else {
+        format!("0.{}{}", "0".repeat((-exp - 1) as usize), digits)
+    };
     Ok(Guarded::unguarded(JsValue::String(JsString::from(
-        format!("{}", n),
+        format!("{}{}", sign, result),
     ))))
 }
 
@@ -420,24 +342,169 @@ pub fn number_to_exponential(
     this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let n = get_number_value(interp, &this)?;
+    let n = get_number_value(interp, &this)? + 0.0;
 
     if !n.is_finite() {
         return Ok(Guarded::unguarded(JsValue::String(JsString::from(
-            format_number_js(n),
+            crate::value::number_to_string(n),
         ))));
     }
 
-    let digits = args.first().map(|v| v.to_number() as i32).unwrap_or(6);
+    let (digits, exp) = match args.first() {
+        // Without an argument, as many digits as it takes to tell n apart
+        None | Some(JsValue::Undefined) => shortest_digits(n),
+        Some(arg) => {
+            let fraction_digits = arg.to_number() as i32;
+            if !(0..=100).contains(&fraction_digits) {
+                return Err(JsError::range_error(
+                    "toExponential() argument must be between 0 and 100",
+                ));
+            }
+            let (exact, exp) = exact_digits(n);
+            let (mut digits, carry) = round_digits(&exact, fraction_digits + 1);
+            digits.truncate(fraction_digits as usize + 1);
+            (digits, exp + carry)
+        }
+    };
 
-    if !(0..=100).contains(&digits) {
-        return Err(JsError::range_error(
-            "toExponential() argument must be between 0 and 100",
-        ));
+    let sign = if n < 0.0 { "-" } else { "" };
+    Ok(Guarded::unguarded(JsValue::String(JsString::from(
+        format!("{}{}", sign, exponential_notation(&digits, exp)),
+    ))))
+}
+
+/// All the decimal digits of `|n|` and the power of ten of the first, so
+/// `|n| = d.ddd… × 10^exp`. 800 digits hold any f64 exactly.
+fn exact_digits(n: f64) -> (String, i32) {
+    split_scientific(&format!("{:.800e}", n.abs()))
+}
+
+/// The shortest digits that round-trip to `|n|`, and the power of ten of
+/// the first
+fn shortest_digits(n: f64) -> (String, i32) {
+    split_scientific(&format!("{:e}", n.abs()))
+}
+
+/// Split Rust's `d.ddde-7` form into its digits and exponent
+fn split_scientific(scientific: &str) -> (String, i32) {
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((scientific, "0"));
+    let digits = mantissa.chars().filter(|c| *c != '.').collect();
+    (digits, exponent.parse().unwrap_or(0))
+}
+
+/// The first `keep` of `digits` (padded with zeros), rounded on the next
+/// one. An exact half rounds up, as the spec picks the larger of two equally
+/// close results. Returns the digits and 1 if rounding added a leading digit
+/// (`999` to `1000`), else 0.
+fn round_digits(digits: &str, keep: i32) -> (String, i32) {
+    let Ok(keep) = usize::try_from(keep) else {
+        return (String::new(), 0);
+    };
+    let mut out: Vec<u8> = digits.bytes().take(keep).collect();
+    out.resize(keep, b'0');
+
+    let mut carry = 0;
+    if digits.as_bytes().get(keep).is_some_and(|&d| d >= b'5') {
+        let mut pos = out.len();
+        loop {
+            if pos == 0 {
+                out.insert(0, b'1');
+                carry = 1;
+                break;
+            }
+            pos -= 1;
+            match out.get_mut(pos) {
+                Some(d) if *d == b'9' => *d = b'0',
+                Some(d) => {
+                    *d += 1;
+                    break;
+                }
+                None => break,
+            }
+        }
     }
+    (out.into_iter().map(char::from).collect(), carry)
+}
+
+/// `digits` as `d.ddde+exp`
+fn exponential_notation(digits: &str, exp: i32) -> String {
+    let (first, rest) = digits.split_at(digits.len().min(1));
+    let exp_sign = if exp >= 0 { "+" } else { "-" };
+    if rest.is_empty() {
+        format!("{}e{}{}", first, exp_sign, exp.abs())
+    } else {
+        format!("{}.{}e{}{}", first, rest, exp_sign, exp.abs())
+    }
+}
 
-    let result = format!("{:.prec$e}", n, prec = digits as usize);
-    // Convert Rust's "e" notation to JS format (e.g., "1.23e2" -> "1.23e+2")
-    let result = result.replace("e", "e+").replace("e+-", "e-");
-    Ok(Guarded::unguarded(JsValue::String(JsString::from(result))))
+/// Finite `n` in base `radix`, with as many fraction digits as its
+/// precision carries. This is the algorithm V8 uses, so results match it
+/// digit for digit.
+fn radix_string(n: f64, radix: f64) -> String {
+    const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let digit_char = |digit: f64| char::from(DIGITS.get(digit as usize).copied().unwrap_or(b'0'));
+    let digit_value = |c: u8| DIGITS.iter().position(|&d| d == c).unwrap_or(0);
+
+    let value = n.abs();
+    let mut integer = math::floor(value);
+    let mut fraction = value - integer;
+
+    // Half the gap to the next double: digits finer than that are noise
+    let next = f64::from_bits(value.to_bits() + 1);
+    let mut delta = (0.5 * (next - value)).max(f64::from_bits(1));
+    let mut fraction_digits: Vec<u8> = Vec::new();
+    if fraction >= delta {
+        loop {
+            fraction *= radix;
+            delta *= radix;
+            let digit = math::floor(fraction);
+            fraction_digits.push(DIGITS.get(digit as usize).copied().unwrap_or(b'0'));
+            fraction -= digit;
+            // Round half to even, carrying into written digits as needed
+            if (fraction > 0.5 || (fraction == 0.5 && digit % 2.0 == 1.0)) && fraction + delta > 1.0
+            {
+                loop {
+                    let Some(last) = fraction_digits.pop() else {
+                        integer += 1.0;
+                        break;
+                    };
+                    let bumped = digit_value(last) + 1;
+                    if (bumped as f64) < radix {
+                        fraction_digits.push(DIGITS.get(bumped).copied().unwrap_or(b'0'));
+                        break;
+                    }
+                }
+                break;
+            }
+            if fraction < delta {
+                break;
+            }
+        }
+    }
+
+    // Digits below the double's precision are written as zeros
+    let mut int_digits = String::new();
+    while integer / radix >= 9007199254740992.0 {
+        integer /= radix;
+        int_digits.push('0');
+    }
+    loop {
+        let remainder = integer % radix;
+        int_digits.push(digit_char(remainder));
+        integer = (integer - remainder) / radix;
+        if integer <= 0.0 {
+            break;
+        }
+    }
+
+    let mut out = String::new();
+    if n < 0.0 {
+        out.push('-');
+    }
+    out.extend(int_digits.chars().rev());
+    if !fraction_digits.is_empty() {
+        out.push('.');
+        out.extend(fraction_digits.into_iter().map(char::from));
+    }
+    out
 }
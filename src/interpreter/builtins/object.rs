@@ -1,26 +1,48 @@
 //! Object built-in methods
 
 use crate::error::JsError;
+use crate::gc::Guard;
 use crate::interpreter::Interpreter;
 use crate::interpreter::builtins::proxy::{
-    is_proxy, proxy_define_property, proxy_get_own_property_descriptor, proxy_get_prototype_of,
-    proxy_is_extensible, proxy_own_keys, proxy_prevent_extensions, proxy_set_prototype_of,
+    has_hooks, is_proxy, proxy_define_property, proxy_get, proxy_get_own_property_descriptor,
+    proxy_get_prototype_of, proxy_is_extensible, proxy_own_keys, proxy_prevent_extensions,
+    proxy_set, proxy_set_prototype_of,
 };
-use crate::prelude::{String, ToString, Vec, format, vec};
+use crate::prelude::{Box, ToString, Vec, format, index_map_new, vec};
 use crate::value::{
-    CheapClone, ExoticObject, Guarded, JsObjectRef, JsString, JsValue, Property, PropertyKey,
+    CheapClone, ExoticObject, Guarded, JsObject, JsObjectRef, JsString, JsSymbol, JsValue,
+    Property, PropertyKey,
 };
 
-/// Initialize Object.prototype with hasOwnProperty, toString, valueOf, isPrototypeOf methods.
+/// Initialize Object.prototype with hasOwnProperty, isPrototypeOf, propertyIsEnumerable,
+/// toString and valueOf methods.
 /// The prototype object must already exist in `interp.object_prototype`.
 pub fn init_object_prototype(interp: &mut Interpreter) {
     let proto = interp.object_prototype.clone();
 
     interp.register_method(&proto, "hasOwnProperty", object_has_own_property, 1);
     interp.register_method(&proto, "isPrototypeOf", object_is_prototype_of, 1);
+    interp.register_method(
+        &proto,
+        "propertyIsEnumerable",
+        object_property_is_enumerable,
+        1,
+    );
     interp.register_method(&proto, "toString", object_to_string, 0);
     interp.register_method(&proto, "toLocaleString", object_to_locale_string, 0);
     interp.register_method(&proto, "valueOf", object_value_of, 0);
+
+    // Annex B `__proto__` accessor, non-enumerable like the methods above
+    interp.register_accessor(
+        &proto,
+        "__proto__",
+        object_proto_getter,
+        Some(object_proto_setter),
+    );
+    let proto_key = PropertyKey::String(interp.intern("__proto__"));
+    if let Some(prop) = proto.borrow_mut().properties.get_mut(&proto_key) {
+        prop.set_enumerable(false);
+    }
 }
 
 /// Create Object constructor with static methods (keys, values, entries, assign, etc.)
@@ -44,6 +66,7 @@ pub fn create_object_constructor(interp: &mut Interpreter) -> JsObjectRef {
     // Freezing/sealing/extensibility
     interp.register_method(&constructor, "freeze", object_freeze, 1);
     interp.register_method(&constructor, "isFrozen", object_is_frozen, 1);
+    interp.register_method(&constructor, "deepFreeze", object_deep_freeze, 1);
     interp.register_method(&constructor, "seal", object_seal, 1);
     interp.register_method(&constructor, "isSealed", object_is_sealed, 1);
     interp.register_method(
@@ -150,148 +173,142 @@ pub fn object_constructor(
             let obj = interp.create_object(&guard);
             obj.borrow_mut().prototype = Some(interp.string_prototype.clone());
             obj.borrow_mut().exotic = ExoticObject::StringObj(s.clone());
-            // Also set length property for string wrappers
-            let len = s.len();
+            // String wrappers have a read-only, non-enumerable length
+            let len = s.char_count();
             let length_key = PropertyKey::String(interp.intern("length"));
-            obj.borrow_mut()
-                .set_property(length_key, JsValue::Number(len as f64));
+            obj.borrow_mut().properties.insert(
+                length_key,
+                Property::with_attributes(JsValue::Number(len as f64), false, false, false),
+            );
             Ok(Guarded::with_guard(JsValue::Object(obj), guard))
         }
-        JsValue::Symbol(_) => {
-            // Symbols cannot be wrapped with Object() - this should throw TypeError in strict mode
-            // but for now we return an ordinary object
+        JsValue::Symbol(sym) => {
+            // Create Symbol wrapper object
             let guard = interp.heap.create_guard();
             let obj = interp.create_object(&guard);
+            obj.borrow_mut().prototype = Some(interp.symbol_prototype.clone());
+            obj.borrow_mut().exotic = ExoticObject::Symbol(sym);
             Ok(Guarded::with_guard(JsValue::Object(obj), guard))
         }
     }
 }
 
-pub fn object_keys(
+/// Collect an object's own enumerable keys in spec order.
+///
+/// Proxies report keys from their ownKeys trap and enums from their member list.
+/// Symbol keys are only included when `include_symbols` is set.
+fn enumerable_own_keys(
     interp: &mut Interpreter,
-    _this: JsValue,
-    args: &[JsValue],
-) -> Result<Guarded, JsError> {
-    let arg = args.first().cloned().unwrap_or(JsValue::Undefined);
+    obj_ref: &JsObjectRef,
+    include_symbols: bool,
+) -> Result<Vec<PropertyKey>, JsError> {
+    if has_hooks(obj_ref) {
+        let Guarded {
+            value: keys_result,
+            guard: _keys_guard,
+        } = proxy_own_keys(interp, obj_ref.cheap_clone())?;
+        let key_values: Vec<JsValue> = match &keys_result {
+            JsValue::Object(keys_arr) => keys_arr
+                .borrow()
+                .array_elements()
+                .map(|elements| elements.to_vec())
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        return Ok(key_values
+            .iter()
+            .filter(|k| match k {
+                JsValue::String(_) => true,
+                JsValue::Symbol(_) => include_symbols,
+                _ => false,
+            })
+            .map(|k| interp.property_key_from_value(k))
+            .collect());
+    }
 
-    // ES2015+: Convert to object (primitives get boxed, null/undefined throw)
-    let to_obj_guarded = interp.to_object(arg)?;
-    let obj_ref = match &to_obj_guarded.value {
-        JsValue::Object(obj) => obj.cheap_clone(),
-        _ => return Err(JsError::internal_error("to_object returned non-object")),
+    let mut obj = obj_ref.borrow_mut();
+    let keys = if let ExoticObject::Enum(ref data) = obj.exotic {
+        data.keys()
+    } else {
+        obj.enumerable_own_keys()
     };
-    // Keep to_obj_guarded alive while we use obj_ref
-    let _guard = to_obj_guarded;
+    Ok(keys
+        .into_iter()
+        .filter(|k| include_symbols || !k.is_symbol())
+        .collect())
+}
 
-    // Use proxy trap if it's a proxy - ownKeys trap returns all keys
-    if is_proxy(&obj_ref) {
-        // Call ownKeys trap and filter for enumerable string keys
-        let Guarded {
-            value: keys_result, ..
-        } = proxy_own_keys(interp, obj_ref)?;
-        // Filter for enumerable string keys only (not symbols)
-        if let JsValue::Object(keys_arr) = keys_result {
-            let keys_ref = keys_arr.borrow();
-            if let Some(elements) = keys_ref.array_elements() {
-                let string_keys: Vec<JsValue> = elements
-                    .iter()
-                    .filter(|k| matches!(k, JsValue::String(_)))
-                    .cloned()
-                    .collect();
-                drop(keys_ref);
-                let guard = interp.heap.create_guard();
-                let arr = interp.create_array_from(&guard, string_keys);
-                return Ok(Guarded::with_guard(JsValue::Object(arr), guard));
-            }
-        }
-        // Fallback to empty array
-        let guard = interp.heap.create_guard();
-        let arr = interp.create_array_from(&guard, vec![]);
-        return Ok(Guarded::with_guard(JsValue::Object(arr), guard));
+/// Convert the first argument to an object, as Object.keys/values/entries do.
+fn to_object_arg(interp: &mut Interpreter, args: &[JsValue]) -> Result<Guarded, JsError> {
+    let arg = args.first().cloned().unwrap_or(JsValue::Undefined);
+    // ES2015+: Convert to object (primitives get boxed, null/undefined throw)
+    interp.to_object(arg)
+}
+
+fn key_to_string_value(key: &PropertyKey) -> JsValue {
+    match key {
+        PropertyKey::String(s) => JsValue::String(s.cheap_clone()),
+        other => JsValue::String(JsString::from(other.to_string())),
     }
+}
 
-    let keys: Vec<JsValue> = {
-        let obj = obj_ref.borrow();
-
-        // For enums, get keys from EnumData
-        if let ExoticObject::Enum(ref data) = obj.exotic {
-            data.keys()
-                .into_iter()
-                .filter(|k| !k.is_symbol())
-                .map(|k| JsValue::String(JsString::from(k.to_string())))
-                .collect()
-        } else if let ExoticObject::Array { ref elements } = obj.exotic {
-            // For arrays, include numeric indices first (0, 1, 2, ...), then other enumerable properties
-            let len = elements.len();
-            let mut result: Vec<JsValue> = (0..len)
-                .map(|i| JsValue::String(JsString::from(i.to_string())))
-                .collect();
-            // Add any other enumerable string properties (like "length" is not enumerable)
-            // Skip numeric index keys since they're already covered above
-            for (key, prop) in obj.properties.iter() {
-                if prop.enumerable() && !key.is_symbol() {
-                    // Skip if this is a numeric index that's already covered by elements
-                    let is_covered_index = match key {
-                        PropertyKey::Index(idx) => (*idx as usize) < len,
-                        PropertyKey::String(s) => {
-                            if let Ok(idx) = s.as_str().parse::<usize>() {
-                                idx < len
-                            } else {
-                                false
-                            }
-                        }
-                        PropertyKey::Symbol(_) => false,
-                    };
-                    if !is_covered_index {
-                        result.push(JsValue::String(JsString::from(key.to_string())));
-                    }
-                }
-            }
-            result
-        } else {
-            // Standard object - get from properties
-            // Only include enumerable string keys, not symbols
-            obj.properties
-                .iter()
-                .filter(|(key, prop)| prop.enumerable() && !key.is_symbol())
-                .map(|(key, _)| JsValue::String(JsString::from(key.to_string())))
-                .collect()
-        }
+pub fn object_keys(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let to_obj_guarded = to_object_arg(interp, args)?;
+    let JsValue::Object(obj_ref) = &to_obj_guarded.value else {
+        return Err(JsError::internal_error("to_object returned non-object"));
     };
 
+    let keys: Vec<JsValue> = enumerable_own_keys(interp, obj_ref, false)?
+        .iter()
+        .map(key_to_string_value)
+        .collect();
+
     let guard = interp.heap.create_guard();
     let arr = interp.create_array_from(&guard, keys);
     Ok(Guarded::with_guard(JsValue::Object(arr), guard))
 }
 
+/// Read each own enumerable string-keyed property through [[Get]], so getters run.
+/// Returned objects are kept alive by `guard`.
+fn enumerable_own_values(
+    interp: &mut Interpreter,
+    obj_ref: &JsObjectRef,
+    guard: &Guard<JsObject>,
+) -> Result<Vec<(PropertyKey, JsValue)>, JsError> {
+    let keys = enumerable_own_keys(interp, obj_ref, false)?;
+    let receiver = JsValue::Object(obj_ref.cheap_clone());
+    let mut pairs = Vec::with_capacity(keys.len());
+    for key in keys {
+        let Guarded { value, guard: _g } =
+            proxy_get(interp, obj_ref.cheap_clone(), key.clone(), receiver.clone())?;
+        if let JsValue::Object(obj) = &value {
+            guard.guard(obj.cheap_clone());
+        }
+        pairs.push((key, value));
+    }
+    Ok(pairs)
+}
+
 pub fn object_values(
     interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let obj = args.first().cloned().unwrap_or(JsValue::Undefined);
-    let JsValue::Object(obj_ref) = obj else {
-        return Err(JsError::type_error("Object.values requires an object"));
-    };
-
-    let values: Vec<JsValue> = {
-        let obj = obj_ref.borrow();
-
-        // For enums, get values from EnumData
-        if let ExoticObject::Enum(ref data) = obj.exotic {
-            data.values()
-        } else {
-            // Standard object - get from properties
-            // Only include enumerable string keys, not symbols
-            obj.properties
-                .iter()
-                .filter(|(key, prop)| prop.enumerable() && !key.is_symbol())
-                .map(|(_, prop)| prop.value.clone())
-                .collect()
-        }
+    let to_obj_guarded = to_object_arg(interp, args)?;
+    let JsValue::Object(obj_ref) = &to_obj_guarded.value else {
+        return Err(JsError::internal_error("to_object returned non-object"));
     };
 
     let guard = interp.heap.create_guard();
+    let values: Vec<JsValue> = enumerable_own_values(interp, obj_ref, &guard)?
+        .into_iter()
+        .map(|(_, value)| value)
+        .collect();
+
     let arr = interp.create_array_from(&guard, values);
     Ok(Guarded::with_guard(JsValue::Object(arr), guard))
 }
@@ -301,35 +318,17 @@ pub fn object_entries(
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let obj = args.first().cloned().unwrap_or(JsValue::Undefined);
-    let JsValue::Object(obj_ref) = obj else {
-        return Err(JsError::type_error("Object.entries requires an object"));
+    let to_obj_guarded = to_object_arg(interp, args)?;
+    let JsValue::Object(obj_ref) = &to_obj_guarded.value else {
+        return Err(JsError::internal_error("to_object returned non-object"));
     };
 
-    // Collect key-value pairs first to release the borrow
-    let pairs: Vec<(String, JsValue)> = {
-        let obj = obj_ref.borrow();
-
-        // For enums, get entries from EnumData
-        if let ExoticObject::Enum(ref data) = obj.exotic {
-            data.entries()
-        } else {
-            // Standard object - get from properties
-            // Only include enumerable string keys, not symbols
-            obj.properties
-                .iter()
-                .filter(|(key, prop)| prop.enumerable() && !key.is_symbol())
-                .map(|(key, prop)| (key.to_string(), prop.value.clone()))
-                .collect()
-        }
-    };
-
-    // Use single guard for all entry arrays
+    // Use single guard for the values and all entry arrays
     let guard = interp.heap.create_guard();
+    let pairs = enumerable_own_values(interp, obj_ref, &guard)?;
     let mut entries: Vec<JsValue> = Vec::with_capacity(pairs.len());
     for (key, value) in pairs {
-        let arr =
-            interp.create_array_from(&guard, vec![JsValue::String(JsString::from(key)), value]);
+        let arr = interp.create_array_from(&guard, vec![key_to_string_value(&key), value]);
         entries.push(JsValue::Object(arr));
     }
 
@@ -337,35 +336,62 @@ pub fn object_entries(
     Ok(Guarded::with_guard(JsValue::Object(result), guard))
 }
 
+/// Object.assign(target, ...sources)
+/// Reads each enumerable own property (strings then symbols) of every source with
+/// [[Get]] and writes it to the target with [[Set]], so accessors on either side run.
 pub fn object_assign(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let target = args.first().cloned().unwrap_or(JsValue::Undefined);
-    let JsValue::Object(target_ref) = target.clone() else {
-        return Err(JsError::type_error(
-            "Object.assign requires an object target",
-        ));
+    let target_guarded = to_object_arg(interp, args)?;
+    let JsValue::Object(target_ref) = target_guarded.value.clone() else {
+        return Err(JsError::internal_error("to_object returned non-object"));
     };
+    let target = JsValue::Object(target_ref.cheap_clone());
 
     for source in args.iter().skip(1) {
-        if let JsValue::Object(src_ref) = source {
-            let src = src_ref.borrow();
-            for (key, prop) in src.properties.iter() {
-                if prop.enumerable() {
-                    target_ref
-                        .borrow_mut()
-                        .set_property(key.clone(), prop.value.clone());
-                }
+        // null and undefined sources are skipped; other primitives are boxed
+        if matches!(source, JsValue::Null | JsValue::Undefined) {
+            continue;
+        }
+        let src_guarded = interp.to_object(source.clone())?;
+        let JsValue::Object(src_ref) = &src_guarded.value else {
+            return Err(JsError::internal_error("to_object returned non-object"));
+        };
+
+        let keys = enumerable_own_keys(interp, src_ref, true)?;
+        for key in keys {
+            let Guarded {
+                value,
+                guard: _value_guard,
+            } = proxy_get(
+                interp,
+                src_ref.cheap_clone(),
+                key.clone(),
+                src_guarded.value.clone(),
+            )?;
+            let ok = proxy_set(
+                interp,
+                target_ref.cheap_clone(),
+                key.clone(),
+                value,
+                target.clone(),
+            )?;
+            if !ok {
+                return Err(JsError::type_error(format!(
+                    "Cannot assign to read only property '{}'",
+                    key
+                )));
             }
         }
     }
 
-    // Target was passed in by caller, so it's already owned - no guard needed
-    Ok(Guarded::unguarded(target))
+    Ok(target_guarded)
 }
 
+/// Object.fromEntries(iterable)
+/// Accepts any iterable (arrays, Maps, generators, ...) of [key, value] entries.
 pub fn object_from_entries(
     interp: &mut Interpreter,
     _this: JsValue,
@@ -373,44 +399,62 @@ pub fn object_from_entries(
 ) -> Result<Guarded, JsError> {
     let iterable = args.first().cloned().unwrap_or(JsValue::Undefined);
 
-    let JsValue::Object(arr) = iterable else {
+    if matches!(iterable, JsValue::Null | JsValue::Undefined) {
         return Err(JsError::type_error(
             "Object.fromEntries requires an iterable",
         ));
-    };
+    }
 
-    // Guard the input array to prevent GC from collecting it during iteration
-    let _arr_guard = interp.guard_value(&JsValue::Object(arr.clone()));
+    // Guard the input to prevent GC from collecting it during iteration
+    let _iterable_guard = interp.guard_value(&iterable);
 
-    // Create result object with guard - key interning may trigger GC
+    let entries = interp
+        .collect_iterator_values(&iterable)?
+        .ok_or_else(|| JsError::type_error("Object.fromEntries requires an iterable"))?;
+
+    // Create result object with guard - entries and values stay reachable through it
     let result_guard = interp.heap.create_guard();
+    for entry in &entries {
+        if let JsValue::Object(entry_obj) = entry {
+            result_guard.guard(entry_obj.cheap_clone());
+        }
+    }
     let result = interp.create_object(&result_guard);
 
-    let length = arr
-        .borrow()
-        .array_length()
-        .ok_or_else(|| JsError::type_error("Object.fromEntries requires an array-like"))?;
-
-    for i in 0..length {
-        let entry = arr
-            .borrow()
-            .get_property(&PropertyKey::Index(i))
-            .unwrap_or(JsValue::Undefined);
-        if let JsValue::Object(entry_ref) = entry {
-            let entry_borrow = entry_ref.borrow();
-            if entry_borrow.is_array() {
-                let key = entry_borrow
-                    .get_property(&PropertyKey::Index(0))
-                    .unwrap_or(JsValue::Undefined);
-                let value = entry_borrow
-                    .get_property(&PropertyKey::Index(1))
-                    .unwrap_or(JsValue::Undefined);
-                drop(entry_borrow);
-                let key_str = interp.to_js_string(&key).to_string();
-                let interned_key = interp.property_key(&key_str);
-                result.borrow_mut().set_property(interned_key, value);
+    for entry in entries {
+        let JsValue::Object(entry_ref) = entry else {
+            return Err(JsError::type_error(format!(
+                "Iterator value {} is not an entry object",
+                interp.to_js_string(&entry)
+            )));
+        };
+        let receiver = JsValue::Object(entry_ref.cheap_clone());
+        let Guarded {
+            value: key,
+            guard: _key_guard,
+        } = proxy_get(
+            interp,
+            entry_ref.cheap_clone(),
+            PropertyKey::Index(0),
+            receiver.clone(),
+        )?;
+        let Guarded {
+            value,
+            guard: _value_guard,
+        } = proxy_get(
+            interp,
+            entry_ref.cheap_clone(),
+            PropertyKey::Index(1),
+            receiver,
+        )?;
+        let prop_key = match key {
+            JsValue::Symbol(_) => interp.property_key_from_value(&key),
+            _ => {
+                let key_str = interp.coerce_to_string(&key)?;
+                interp.property_key_from_js_string(key_str)
             }
-        }
+        };
+        result.borrow_mut().set_property(prop_key, value);
     }
 
     Ok(Guarded::with_guard(JsValue::Object(result), result_guard))
@@ -424,21 +468,8 @@ pub fn object_has_own(
     let obj = args.first().cloned().unwrap_or(JsValue::Undefined);
     let key = args.get(1).cloned().unwrap_or(JsValue::Undefined);
 
-    let JsValue::Object(obj_ref) = obj else {
-        return Ok(Guarded::unguarded(JsValue::Boolean(false)));
-    };
-
-    let key_str = interp.to_js_string(&key).to_string();
-    let interned_key = interp.property_key(&key_str);
-
-    let borrowed = obj_ref.borrow();
-    let has = if let ExoticObject::Enum(ref data) = borrowed.exotic {
-        // For enums, check EnumData
-        data.has_property(&interned_key)
-    } else {
-        // Standard object - check properties
-        borrowed.properties.contains_key(&interned_key)
-    };
+    let key = interp.to_property_key(&key)?;
+    let has = own_property_enumerable(interp, obj, &key)?.is_some();
     Ok(Guarded::unguarded(JsValue::Boolean(has)))
 }
 
@@ -584,6 +615,107 @@ pub fn object_freeze(
     Ok(Guarded { value: obj, guard })
 }
 
+/// Object.deepFreeze(value) - freeze `value` and everything reachable from it.
+/// See [`deep_freeze`] for what is followed.
+pub fn object_deep_freeze(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let obj = args.first().cloned().unwrap_or(JsValue::Undefined);
+    deep_freeze(&obj, false)?;
+    let guard = interp.guard_value(&obj);
+    Ok(Guarded { value: obj, guard })
+}
+
+/// Freeze `value` and every object reachable from it through own property
+/// values and accessors, array elements and Map/Set entries, returning how
+/// many objects were frozen.
+///
+/// Prototypes and function closures are not followed, and objects already
+/// deep-frozen are skipped together with what they reach, which also ends
+/// cycles. Frozen Maps and Sets reject `set`, `add`, `delete` and `clear`.
+///
+/// The walk uses an explicit worklist, so deep graphs can't overflow the
+/// stack, and it never allocates on the GC heap, so no collection can run
+/// while it holds unguarded references. A Proxy in the graph is an error,
+/// since its target can't be frozen without running traps; objects visited
+/// before it stay frozen.
+///
+/// `strict_reads` marks the objects frozen as host context, whose missing
+/// properties throw when read under `InterpreterConfig::strict_property_access`.
+pub(crate) fn deep_freeze(value: &JsValue, strict_reads: bool) -> Result<usize, JsError> {
+    let mut pending: Vec<JsObjectRef> = Vec::new();
+    if let JsValue::Object(obj) = value {
+        pending.push(obj.cheap_clone());
+    }
+    // Checked for deep_frozen when popped: a child can be the object whose
+    // properties are being walked, which is still mutably borrowed
+    let push = |pending: &mut Vec<JsObjectRef>, value: &JsValue| {
+        if let JsValue::Object(obj) = value {
+            pending.push(obj.cheap_clone());
+        }
+    };
+
+    let mut frozen = 0;
+    while let Some(obj) = pending.pop() {
+        let mut obj_mut = obj.borrow_mut();
+        if obj_mut.deep_frozen {
+            continue;
+        }
+        if matches!(obj_mut.exotic, ExoticObject::Proxy(_)) {
+            return Err(JsError::type_error("Cannot deep-freeze a Proxy"));
+        }
+        obj_mut.deep_frozen = true;
+        obj_mut.frozen = true;
+        obj_mut.extensible = false;
+        obj_mut.strict_reads |= strict_reads;
+        frozen += 1;
+
+        for (_, prop) in obj_mut.properties.iter_mut() {
+            prop.set_writable(false);
+            prop.set_configurable(false);
+        }
+        for (_, prop) in obj_mut.properties.iter() {
+            push(&mut pending, &prop.value);
+            for accessor in [prop.getter(), prop.setter()].into_iter().flatten() {
+                push(&mut pending, &JsValue::Object(accessor.cheap_clone()));
+            }
+        }
+        match &obj_mut.exotic {
+            ExoticObject::Array { elements } => {
+                for element in elements {
+                    push(&mut pending, element);
+                }
+            }
+            ExoticObject::Map { entries } => {
+                for (key, value) in entries {
+                    push(&mut pending, &key.0);
+                    push(&mut pending, value);
+                }
+            }
+            ExoticObject::Set { entries } => {
+                for key in entries {
+                    push(&mut pending, &key.0);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(frozen)
+}
+
+/// Reject a mutating Map/Set method on a collection frozen by [`deep_freeze`]
+pub(crate) fn check_entries_mutable(obj: &JsObjectRef, method: &str) -> Result<(), JsError> {
+    if obj.borrow().deep_frozen {
+        return Err(JsError::type_error(format!(
+            "{} called on a frozen collection",
+            method
+        )));
+    }
+    Ok(())
+}
+
 pub fn object_is_frozen(
     _interp: &mut Interpreter,
     _this: JsValue,
@@ -643,51 +775,75 @@ pub fn object_has_own_property(
     this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let JsValue::Object(obj) = this else {
-        return Ok(Guarded::unguarded(JsValue::Boolean(false)));
-    };
+    let arg = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let key = interp.to_property_key(&arg)?;
+    let has_prop = own_property_enumerable(interp, this, &key)?.is_some();
+    Ok(Guarded::unguarded(JsValue::Boolean(has_prop)))
+}
 
+/// Object.prototype.propertyIsEnumerable
+/// Returns true if the key is an own, enumerable property of this object.
+pub fn object_property_is_enumerable(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
     let arg = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let key = interp.to_property_key(&arg)?;
+    let enumerable = own_property_enumerable(interp, this, &key)?.unwrap_or(false);
+    Ok(Guarded::unguarded(JsValue::Boolean(enumerable)))
+}
 
-    // Handle symbol arguments directly
-    let key = if let JsValue::Symbol(ref sym) = arg {
-        PropertyKey::Symbol(sym.clone())
-    } else {
-        let prop_name = interp.to_js_string(&arg).to_string();
-        interp.property_key(&prop_name)
+/// Look up an own property of `target` (boxed via ToObject) without consulting
+/// the prototype chain. Returns whether it is enumerable, or None if absent.
+fn own_property_enumerable(
+    interp: &mut Interpreter,
+    target: JsValue,
+    key: &PropertyKey,
+) -> Result<Option<bool>, JsError> {
+    let to_obj_guarded = interp.to_object(target)?;
+    let JsValue::Object(obj) = to_obj_guarded.value.clone() else {
+        return Err(JsError::internal_error("to_object returned non-object"));
     };
 
+    if is_proxy(&obj) {
+        let desc = proxy_get_own_property_descriptor(interp, obj, key)?;
+        let JsValue::Object(desc_obj) = &desc.value else {
+            return Ok(None);
+        };
+        let enumerable_key = PropertyKey::String(interp.intern("enumerable"));
+        let enumerable = desc_obj
+            .borrow()
+            .get_property(&enumerable_key)
+            .is_some_and(|v| v.to_boolean());
+        return Ok(Some(enumerable));
+    }
+
     let obj_ref = obj.borrow();
-    let has_prop = if let ExoticObject::Enum(ref data) = obj_ref.exotic {
-        // For enums, check EnumData
-        data.has_property(&key)
-    } else if let ExoticObject::Array { ref elements } = obj_ref.exotic {
-        // For arrays, check if key is a valid array index
-        match &key {
-            PropertyKey::Index(index) => {
-                // Direct numeric index - check if within bounds
-                (*index as usize) < elements.len()
-            }
-            PropertyKey::String(key_str) => {
-                // Try to parse as integer index
-                if let Ok(index) = key_str.as_str().parse::<usize>() {
-                    // Check if index is within bounds
-                    index < elements.len()
-                } else {
-                    // Non-numeric key - check regular properties
-                    obj_ref.properties.contains_key(&key)
-                }
-            }
-            PropertyKey::Symbol(_) => {
-                // Symbol key - check regular properties
-                obj_ref.properties.contains_key(&key)
-            }
-        }
-    } else {
-        // Standard object - check properties
-        obj_ref.properties.contains_key(&key)
+    if let Some(prop) = obj_ref.get_own_property(key) {
+        return Ok(Some(prop.enumerable()));
+    }
+    let is_length = matches!(key, PropertyKey::String(s) if s.as_str() == "length");
+    let found = match &obj_ref.exotic {
+        // Enum members are enumerable data properties
+        ExoticObject::Enum(data) => data.has_property(key).then_some(true),
+        // Array elements are enumerable; length is not
+        ExoticObject::Array { elements } => match key {
+            PropertyKey::Index(index) => ((*index as usize) < elements.len()).then_some(true),
+            _ => is_length.then_some(false),
+        },
+        // String wrappers expose one enumerable property per character
+        ExoticObject::StringObj(s) => match key {
+            PropertyKey::Index(index) => ((*index as usize) < s.char_count()).then_some(true),
+            _ => is_length.then_some(false),
+        },
+        // Function name/length are synthesized, non-enumerable own properties
+        ExoticObject::Function(_) => obj_ref
+            .get_property_descriptor(key)
+            .and_then(|(prop, in_prototype)| (!in_prototype).then(|| prop.enumerable())),
+        _ => None,
     };
-    Ok(Guarded::unguarded(JsValue::Boolean(has_prop)))
+    Ok(found)
 }
 
 /// Object.prototype.isPrototypeOf
@@ -697,12 +853,6 @@ pub fn object_is_prototype_of(
     this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    // Get the this object
-    let JsValue::Object(this_obj) = this else {
-        // this is not an object - can't be in any prototype chain
-        return Ok(Guarded::unguarded(JsValue::Boolean(false)));
-    };
-
     // Get the argument - if not an object, return false
     let arg = args.first().cloned().unwrap_or(JsValue::Undefined);
     let JsValue::Object(mut check_obj) = arg else {
@@ -710,6 +860,18 @@ pub fn object_is_prototype_of(
         return Ok(Guarded::unguarded(JsValue::Boolean(false)));
     };
 
+    // Get the this object
+    let this_obj = match this {
+        JsValue::Object(obj) => obj,
+        JsValue::Undefined | JsValue::Null => {
+            return Err(JsError::type_error(
+                "Object.prototype.isPrototypeOf called on null or undefined",
+            ));
+        }
+        // A freshly boxed primitive can't be in any prototype chain
+        _ => return Ok(Guarded::unguarded(JsValue::Boolean(false))),
+    };
+
     // Walk up the prototype chain of check_obj, looking for this_obj
     loop {
         let proto = check_obj.borrow().prototype.clone();
@@ -733,10 +895,17 @@ pub fn object_is_prototype_of(
 /// Returns "[object Type]" based on the internal [[Class]] of the value.
 /// Per ES spec, this checks for Symbol.toStringTag on objects first.
 pub fn object_to_string(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     this: JsValue,
     _args: &[JsValue],
 ) -> Result<Guarded, JsError> {
+    if let JsValue::Object(obj) = &this
+        && let Some(name) = interp.host_class_name(obj)
+    {
+        return Ok(Guarded::unguarded(JsValue::String(JsString::from(
+            format!("[object {}]", name),
+        ))));
+    }
     let tag = match &this {
         JsValue::Undefined => "Undefined",
         JsValue::Null => "Null",
@@ -745,8 +914,16 @@ pub fn object_to_string(
         JsValue::String(_) => "String",
         JsValue::Symbol(_) => "Symbol",
         JsValue::Object(obj) => {
+            let tag_key = PropertyKey::Symbol(Box::new(JsSymbol::new(
+                interp.well_known_symbols.to_string_tag,
+                None,
+            )));
             let obj_ref = obj.borrow();
-            // TODO: Check for Symbol.toStringTag property first
+            if let Some(JsValue::String(tag)) = obj_ref.get_property(&tag_key) {
+                return Ok(Guarded::unguarded(JsValue::String(JsString::from(
+                    format!("[object {}]", tag),
+                ))));
+            }
             match &obj_ref.exotic {
                 ExoticObject::Array { .. } => "Array",
                 ExoticObject::Function(_) => "Function",
@@ -760,11 +937,20 @@ pub fn object_to_string(
                 ExoticObject::Environment(_) => "Object",
                 ExoticObject::Enum(_) => "Object",
                 ExoticObject::Proxy(_) => "Object",
+                ExoticObject::Host(_) => "Object",
                 ExoticObject::Boolean(_) => "Boolean",
                 ExoticObject::Number(_) => "Number",
                 ExoticObject::StringObj(_) => "String",
                 ExoticObject::Symbol(_) => "Symbol",
                 ExoticObject::RawJSON(_) => "Object", // RawJSON objects are ordinary objects
+                ExoticObject::Url(_) => "URL",
+                ExoticObject::UrlSearchParams(_) => "URLSearchParams",
+                ExoticObject::WeakRef(_) => "WeakRef",
+                ExoticObject::FinalizationRegistry(_) => "FinalizationRegistry",
+                ExoticObject::DisposableStack(data) if data.borrow().is_async => {
+                    "AsyncDisposableStack"
+                }
+                ExoticObject::DisposableStack(_) => "DisposableStack",
                 ExoticObject::PendingOrder { .. } => "Object", // PendingOrder markers are objects
             }
         }
@@ -822,14 +1008,14 @@ pub fn object_get_own_property_descriptor(
         return proxy_get_own_property_descriptor(interp, obj_ref, &key);
     }
 
-    let obj_borrowed = obj_ref.borrow();
-
     // Use get_property_descriptor which handles exotic properties (function name/length, array elements, etc.)
-    if let Some((property, in_prototype)) = obj_borrowed.get_property_descriptor(&key) {
+    let descriptor = obj_ref.borrow().get_property_descriptor(&key);
+    if let Some((property, in_prototype)) = descriptor {
         // Only return descriptor if it's an own property (not from prototype)
         if in_prototype {
             return Ok(Guarded::unguarded(JsValue::Undefined));
         }
+        let property = interp.reported_own_property(&obj_ref, &key, property)?;
 
         // Check if the key is "name" or "length" on a function - those are own properties
         // Pre-intern all descriptor property keys
@@ -879,29 +1065,7 @@ pub fn object_get_own_property_names(
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let obj = args.first().cloned().unwrap_or(JsValue::Undefined);
-
-    // ES2015+: Convert to object (primitives get boxed, null/undefined throw)
-    let to_obj_guarded = interp.to_object(obj)?;
-    let obj_ref = match &to_obj_guarded.value {
-        JsValue::Object(obj) => obj.cheap_clone(),
-        _ => return Err(JsError::internal_error("to_object returned non-object")),
-    };
-    // Keep to_obj_guarded alive while we use obj_ref
-    let _guard = to_obj_guarded;
-
-    // Filter out symbol keys - getOwnPropertyNames only returns string keys
-    let names: Vec<JsValue> = obj_ref
-        .borrow()
-        .properties
-        .keys()
-        .filter(|key| !key.is_symbol())
-        .map(|key| JsValue::String(JsString::from(key.to_string())))
-        .collect();
-
-    let guard = interp.heap.create_guard();
-    let arr = interp.create_array_from(&guard, names);
-    Ok(Guarded::with_guard(JsValue::Object(arr), guard))
+    own_keys_of_kind(interp, args, false)
 }
 
 /// Object.getOwnPropertySymbols(obj)
@@ -910,33 +1074,43 @@ pub fn object_get_own_property_symbols(
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let obj = args.first().cloned().unwrap_or(JsValue::Undefined);
+    own_keys_of_kind(interp, args, true)
+}
 
-    // ES2015+: Convert to object (primitives get boxed, null/undefined throw)
-    let to_obj_guarded = interp.to_object(obj)?;
-    let obj_ref = match &to_obj_guarded.value {
-        JsValue::Object(obj) => obj.cheap_clone(),
-        _ => return Err(JsError::internal_error("to_object returned non-object")),
+/// The string or symbol keys of [[OwnPropertyKeys]], in spec order and
+/// including non-enumerable ones. Proxies answer through their ownKeys trap.
+fn own_keys_of_kind(
+    interp: &mut Interpreter,
+    args: &[JsValue],
+    symbols: bool,
+) -> Result<Guarded, JsError> {
+    let to_obj_guarded = to_object_arg(interp, args)?;
+    let JsValue::Object(obj_ref) = &to_obj_guarded.value else {
+        return Err(JsError::internal_error("to_object returned non-object"));
     };
-    // Keep to_obj_guarded alive while we use obj_ref
-    let _guard = to_obj_guarded;
 
-    // Return only symbol keys
-    let symbols: Vec<JsValue> = obj_ref
-        .borrow()
-        .properties
-        .keys()
-        .filter_map(|key| {
-            if let PropertyKey::Symbol(s) = key {
-                Some(JsValue::Symbol(s.clone()))
-            } else {
-                None
-            }
-        })
-        .collect();
+    let Guarded {
+        value: keys_result,
+        guard: _keys_guard,
+    } = proxy_own_keys(interp, obj_ref.cheap_clone())?;
+    let keys: Vec<JsValue> = match &keys_result {
+        JsValue::Object(keys_arr) => keys_arr
+            .borrow()
+            .array_elements()
+            .unwrap_or_default()
+            .iter()
+            .filter(|key| match key {
+                JsValue::String(_) => !symbols,
+                JsValue::Symbol(_) => symbols,
+                _ => false,
+            })
+            .cloned()
+            .collect(),
+        _ => Vec::new(),
+    };
 
     let guard = interp.heap.create_guard();
-    let arr = interp.create_array_from(&guard, symbols);
+    let arr = interp.create_array_from(&guard, keys);
     Ok(Guarded::with_guard(JsValue::Object(arr), guard))
 }
 
@@ -967,6 +1141,7 @@ pub fn object_define_property(
         proxy_define_property(interp, obj_ref, key, descriptor)?;
         return Ok(Guarded::unguarded(obj));
     }
+    interp.check_write(&obj_ref, Some(&key))?;
 
     // Pre-intern descriptor property keys
     let value_key = PropertyKey::String(interp.intern("value"));
@@ -978,9 +1153,9 @@ pub fn object_define_property(
 
     // Get descriptor properties
     let desc_borrowed = desc_ref.borrow();
-    let value = desc_borrowed
-        .get_property(&value_key)
-        .unwrap_or(JsValue::Undefined);
+    let value_field = desc_borrowed.get_property(&value_key);
+    let has_value = value_field.is_some();
+    let value = value_field.unwrap_or(JsValue::Undefined);
     let writable = desc_borrowed
         .get_property(&writable_key)
         .map(|v| v.to_boolean())
@@ -1017,26 +1192,38 @@ pub fn object_define_property(
         obj_ref.borrow_mut().define_property(key, prop);
     } else {
         // Data descriptor
-        // For arrays with numeric index keys, also update the elements storage
-        let mut obj = obj_ref.borrow_mut();
-
-        // Check if this is an array and the key is a numeric index
-        if let ExoticObject::Array { ref mut elements } = obj.exotic {
-            let maybe_index = match &key {
-                PropertyKey::Index(idx) => Some(*idx as usize),
-                PropertyKey::String(key_str) => key_str.as_str().parse::<usize>().ok(),
-                PropertyKey::Symbol(_) => None,
-            };
-
-            if let Some(index) = maybe_index {
-                // Extend array if needed
-                while elements.len() <= index {
-                    elements.push(JsValue::Undefined);
+        let array_len = obj_ref.borrow().array_length();
+        if let Some(len) = array_len {
+            match &key {
+                // An array's length is its element count, so defining it
+                // truncates or extends the elements
+                PropertyKey::String(key_str) if key_str.as_str() == "length" => {
+                    if has_value {
+                        let new_len = super::array::to_array_length(interp, &value)?;
+                        if let Some(elements) = obj_ref.borrow_mut().array_elements_mut() {
+                            elements.resize(new_len as usize, JsValue::Undefined);
+                        }
+                    }
+                    return Ok(Guarded::unguarded(obj));
                 }
-                // Set the value at this index
-                if let Some(elem) = elements.get_mut(index) {
-                    *elem = value.clone();
+                PropertyKey::Index(idx) if *idx >= len => {
+                    interp.check_array_len(*idx as usize + 1)?;
                 }
+                _ => {}
+            }
+        }
+
+        // For arrays with index keys, also update the elements storage
+        let mut obj = obj_ref.borrow_mut();
+        if let PropertyKey::Index(idx) = &key
+            && let Some(elements) = obj.array_elements_mut()
+        {
+            let index = *idx as usize;
+            if elements.len() <= index {
+                elements.resize(index + 1, JsValue::Undefined);
+            }
+            if let Some(elem) = elements.get_mut(index) {
+                *elem = value.clone();
             }
         }
 
@@ -1096,6 +1283,7 @@ pub fn object_define_properties(
         let JsValue::Object(desc_ref) = descriptor else {
             continue; // Skip non-object descriptors
         };
+        interp.check_write(&obj_ref, Some(&key))?;
 
         // Get descriptor properties
         let desc_borrowed = desc_ref.borrow();
@@ -1148,32 +1336,18 @@ pub fn object_define_properties(
 }
 
 /// Object.getPrototypeOf(obj)
+/// Primitives are boxed first, so `Object.getPrototypeOf(1)` is `Number.prototype`.
 pub fn object_get_prototype_of(
     interp: &mut Interpreter,
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
     let obj = args.first().cloned().unwrap_or(JsValue::Undefined);
-
-    let JsValue::Object(obj_ref) = obj else {
-        return Err(JsError::type_error(
-            "Object.getPrototypeOf requires an object",
-        ));
-    };
-
-    // Use proxy trap if it's a proxy
-    if is_proxy(&obj_ref) {
-        return proxy_get_prototype_of(interp, obj_ref);
-    }
-
-    let obj_borrowed = obj_ref.borrow();
-    match &obj_borrowed.prototype {
-        Some(proto) => Ok(Guarded::unguarded(JsValue::Object(proto.clone()))),
-        None => Ok(Guarded::unguarded(JsValue::Null)),
-    }
+    get_prototype_of(interp, obj)
 }
 
 /// Object.setPrototypeOf(obj, proto)
+/// Primitives are returned unchanged; cycles and non-extensible objects throw.
 pub fn object_set_prototype_of(
     interp: &mut Interpreter,
     _this: JsValue,
@@ -1182,31 +1356,151 @@ pub fn object_set_prototype_of(
     let obj = args.first().cloned().unwrap_or(JsValue::Undefined);
     let proto = args.get(1).cloned().unwrap_or(JsValue::Undefined);
 
-    let JsValue::Object(obj_ref) = obj.clone() else {
+    if matches!(obj, JsValue::Undefined | JsValue::Null) {
         return Err(JsError::type_error(
-            "Object.setPrototypeOf requires an object",
+            "Object.setPrototypeOf called on null or undefined",
         ));
+    }
+    let new_proto = match &proto {
+        JsValue::Object(p) => Some(p.cheap_clone()),
+        JsValue::Null => None,
+        _ => {
+            return Err(JsError::type_error(
+                "Object prototype may only be an Object or null",
+            ));
+        }
+    };
+    let JsValue::Object(obj_ref) = obj.clone() else {
+        return Ok(Guarded::unguarded(obj));
     };
 
     // Use proxy trap if it's a proxy
     if is_proxy(&obj_ref) {
-        proxy_set_prototype_of(interp, obj_ref, proto)?;
+        if !proxy_set_prototype_of(interp, obj_ref, proto)? {
+            return Err(JsError::type_error(
+                "'setPrototypeOf' on proxy: trap returned falsish",
+            ));
+        }
         return Ok(Guarded::unguarded(obj));
     }
 
-    let new_proto = match proto {
-        JsValue::Object(p) => Some(p),
+    interp.check_write(&obj_ref, None)?;
+    if !ordinary_set_prototype_of(&obj_ref, new_proto) {
+        return Err(set_prototype_error(&obj_ref));
+    }
+    // Object was passed in by caller, already owned - no guard needed
+    Ok(Guarded::unguarded(obj))
+}
+
+/// get Object.prototype.__proto__
+pub fn object_proto_getter(
+    interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    get_prototype_of(interp, this)
+}
+
+/// set Object.prototype.__proto__
+/// Per Annex B, non-object prototypes and primitive receivers are ignored.
+pub fn object_proto_setter(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let proto = args.first().cloned().unwrap_or(JsValue::Undefined);
+
+    if matches!(this, JsValue::Undefined | JsValue::Null) {
+        return Err(JsError::type_error(
+            "Object.prototype.__proto__ called on null or undefined",
+        ));
+    }
+    let new_proto = match &proto {
+        JsValue::Object(p) => Some(p.cheap_clone()),
         JsValue::Null => None,
-        _ => {
+        _ => return Ok(Guarded::unguarded(JsValue::Undefined)),
+    };
+    let JsValue::Object(obj_ref) = this else {
+        return Ok(Guarded::unguarded(JsValue::Undefined));
+    };
+
+    if is_proxy(&obj_ref) {
+        if !proxy_set_prototype_of(interp, obj_ref, proto)? {
             return Err(JsError::type_error(
-                "Object prototype may only be an Object or null",
+                "'setPrototypeOf' on proxy: trap returned falsish",
             ));
         }
+    } else {
+        interp.check_write(&obj_ref, None)?;
+        if !ordinary_set_prototype_of(&obj_ref, new_proto) {
+            return Err(set_prototype_error(&obj_ref));
+        }
+    }
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+/// [[GetPrototypeOf]] of `value` after boxing primitives
+fn get_prototype_of(interp: &mut Interpreter, value: JsValue) -> Result<Guarded, JsError> {
+    let Guarded { value: obj, .. } = interp.to_object(value)?;
+    let JsValue::Object(obj_ref) = obj else {
+        return Err(JsError::internal_error("to_object returned non-object"));
     };
 
-    obj_ref.borrow_mut().prototype = new_proto;
-    // Object was passed in by caller, already owned - no guard needed
-    Ok(Guarded::unguarded(obj))
+    // Use proxy trap if it's a proxy
+    if is_proxy(&obj_ref) {
+        return proxy_get_prototype_of(interp, obj_ref);
+    }
+
+    // Prototypes are reachable from the object, so no guard is needed
+    match &obj_ref.borrow().prototype {
+        Some(proto) => Ok(Guarded::unguarded(JsValue::Object(proto.clone()))),
+        None => Ok(Guarded::unguarded(JsValue::Null)),
+    }
+}
+
+/// OrdinarySetPrototypeOf: replace `obj`'s prototype, returning false when
+/// `obj` is not extensible or `proto` has `obj` in its chain.
+///
+/// Setting the current prototype again always succeeds. The cycle walk stops
+/// at a proxy, whose own [[GetPrototypeOf]] could run arbitrary code.
+pub(crate) fn ordinary_set_prototype_of(obj: &JsObjectRef, proto: Option<JsObjectRef>) -> bool {
+    {
+        let obj_ref = obj.borrow();
+        let unchanged = match (&obj_ref.prototype, &proto) {
+            (Some(current), Some(new)) => current.id() == new.id(),
+            (None, None) => true,
+            _ => false,
+        };
+        if unchanged {
+            return true;
+        }
+        if !obj_ref.extensible {
+            return false;
+        }
+    }
+
+    let mut next = proto.clone();
+    while let Some(p) = next {
+        if p.id() == obj.id() {
+            return false;
+        }
+        if is_proxy(&p) {
+            break;
+        }
+        next = p.borrow().prototype.clone();
+    }
+
+    obj.borrow_mut().prototype = proto;
+    true
+}
+
+/// The TypeError for a failed `ordinary_set_prototype_of`
+fn set_prototype_error(obj: &JsObjectRef) -> JsError {
+    if obj.borrow().extensible {
+        JsError::type_error("Cyclic __proto__ value")
+    } else {
+        JsError::type_error("Object is not extensible")
+    }
 }
 
 /// Object.is(value1, value2)
@@ -1219,38 +1513,10 @@ pub fn object_is(
     let value1 = args.first().cloned().unwrap_or(JsValue::Undefined);
     let value2 = args.get(1).cloned().unwrap_or(JsValue::Undefined);
 
-    let result = same_value(&value1, &value2);
+    let result = value1.same_value(&value2);
     Ok(Guarded::unguarded(JsValue::Boolean(result)))
 }
 
-/// SameValue algorithm (used by Object.is)
-/// Different from strict equality (===) in that:
-/// - NaN is equal to NaN
-/// - +0 is NOT equal to -0
-fn same_value(x: &JsValue, y: &JsValue) -> bool {
-    match (x, y) {
-        (JsValue::Undefined, JsValue::Undefined) => true,
-        (JsValue::Null, JsValue::Null) => true,
-        (JsValue::Boolean(a), JsValue::Boolean(b)) => a == b,
-        (JsValue::Number(a), JsValue::Number(b)) => {
-            // Handle NaN: NaN is equal to NaN
-            if a.is_nan() && b.is_nan() {
-                return true;
-            }
-            // Handle -0 vs +0: they are NOT equal
-            if *a == 0.0 && *b == 0.0 {
-                // Check sign bit: 1/0.0 = Infinity, 1/-0.0 = -Infinity
-                return a.signum() == b.signum() || (a.is_nan() && b.is_nan());
-            }
-            a == b
-        }
-        (JsValue::String(a), JsValue::String(b)) => a == b,
-        (JsValue::Symbol(a), JsValue::Symbol(b)) => a == b,
-        (JsValue::Object(a), JsValue::Object(b)) => a == b,
-        _ => false,
-    }
-}
-
 /// Object.preventExtensions(obj)
 /// Prevents new properties from being added to an object
 pub fn object_prevent_extensions(
@@ -1337,6 +1603,7 @@ pub fn object_get_own_property_descriptors(
         };
 
         if let Some(property) = property {
+            let property = interp.reported_own_property(&obj_ref, &key, property)?;
             // Create descriptor object for this property
             let desc = interp.create_object(&result_guard);
             {
@@ -1389,82 +1656,47 @@ pub fn object_group_by(
     let items = args.first().cloned().unwrap_or(JsValue::Undefined);
     let callback = args.get(1).cloned().unwrap_or(JsValue::Undefined);
 
-    // Items must be iterable - for now we support arrays
-    let JsValue::Object(items_ref) = items else {
-        return Err(JsError::type_error("Object.groupBy requires an iterable"));
-    };
-
-    // Guard the inputs
-    let guard = interp.heap.create_guard();
-    guard.guard(items_ref.clone());
-    if let JsValue::Object(cb_obj) = &callback {
-        guard.guard(cb_obj.clone());
+    if items.is_null_or_undefined() {
+        return Err(JsError::type_error(
+            "Object.groupBy called on null or undefined",
+        ));
     }
-
-    // Get array elements
-    let elements: Vec<JsValue> = {
-        let items_borrowed = items_ref.borrow();
-        if let Some(elems) = items_borrowed.array_elements() {
-            elems.to_vec()
-        } else {
-            return Err(JsError::type_error(
-                "Object.groupBy requires an array-like object",
-            ));
-        }
-    };
-
-    // Create result object with null prototype
-    let result = interp.create_object(&guard);
-    {
-        let mut result_ref = result.borrow_mut();
-        result_ref.prototype = None;
-        result_ref.null_prototype = true;
+    if !callback.is_callable() {
+        return Err(JsError::type_error(
+            "Object.groupBy callback is not a function",
+        ));
     }
 
-    // Track groups as we build them - use Vec to preserve insertion order
-    let mut group_keys: Vec<String> = Vec::new();
-    let mut group_items: Vec<Vec<JsValue>> = Vec::new();
-
-    // Iterate and group
-    for (index, item) in elements.into_iter().enumerate() {
-        // Guard the item in case callback triggers GC
-        if let JsValue::Object(item_obj) = &item {
-            guard.guard(item_obj.clone());
-        }
+    // Guards the inputs and every grouped element until the arrays exist
+    let guard = interp.heap.create_guard();
+    items.guard_by(&guard);
+    callback.guard_by(&guard);
 
-        // Call the callback with (item, index)
+    // Groups keyed by ToPropertyKey(callback result), in first-seen order
+    let mut groups = index_map_new::<PropertyKey, Vec<JsValue>>();
+    interp.for_each_iterator_value(&items, |interp, item, index| {
         let key_result = interp.call_function(
             callback.clone(),
             JsValue::Undefined,
             &[item.clone(), JsValue::Number(index as f64)],
         )?;
+        let key = interp.to_property_key(&key_result.value)?;
+        item.guard_by(&guard);
+        groups.entry(key).or_default().push(item);
+        Ok(())
+    })?;
 
-        // Coerce key to string (property key)
-        let key_str = interp.to_js_string(&key_result.value).to_string();
-
-        // Find existing group or create new one
-        let found_idx = group_keys.iter().position(|k| k == &key_str);
-
-        match found_idx {
-            Some(idx) => {
-                if let Some(items_vec) = group_items.get_mut(idx) {
-                    items_vec.push(item);
-                }
-            }
-            None => {
-                group_keys.push(key_str);
-                group_items.push(vec![item]);
-            }
-        }
+    // Create result object with null prototype
+    let result = interp.create_object(&guard);
+    {
+        let mut result_ref = result.borrow_mut();
+        result_ref.prototype = None;
+        result_ref.null_prototype = true;
     }
 
-    // Now create the arrays and set them on the result object
-    for (key, items) in group_keys.into_iter().zip(group_items.into_iter()) {
+    for (key, items) in groups {
         let arr = interp.create_array_from(&guard, items);
-        let prop_key = PropertyKey::String(interp.intern(&key));
-        result
-            .borrow_mut()
-            .set_property(prop_key, JsValue::Object(arr));
+        result.borrow_mut().set_property(key, JsValue::Object(arr));
     }
 
     Ok(Guarded::with_guard(JsValue::Object(result), guard))
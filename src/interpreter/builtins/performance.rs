@@ -0,0 +1,351 @@
+//! performance built-in: now(), mark() and measure()
+//!
+//! Times come from the interpreter's [`TimeProvider`](crate::platform::TimeProvider),
+//! measured from a timer started when the interpreter was created (or its
+//! time provider replaced), so they are independent of `Date`. Marks and
+//! measures stay in the interpreter until a script clears them, and the host
+//! can read them with [`Interpreter::performance_entries`].
+
+use crate::error::JsError;
+use crate::gc::Guard;
+use crate::interpreter::Interpreter;
+use crate::prelude::*;
+use crate::value::{Guarded, JsObject, JsValue, PropertyKey};
+
+/// Kind of a [`PerfEntry`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PerfEntryType {
+    /// Recorded by `performance.mark()`
+    Mark,
+    /// Recorded by `performance.measure()`
+    Measure,
+}
+
+impl PerfEntryType {
+    /// The `entryType` string scripts see
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PerfEntryType::Mark => "mark",
+            PerfEntryType::Measure => "measure",
+        }
+    }
+}
+
+/// A mark or measure recorded by a script
+#[derive(Debug, Clone, PartialEq)]
+pub struct PerfEntry {
+    pub name: String,
+    pub entry_type: PerfEntryType,
+    /// Milliseconds since the time origin
+    pub start: f64,
+    /// Milliseconds; always 0 for marks
+    pub duration: f64,
+}
+
+/// Clock state and recorded entries of the `performance` global
+pub(crate) struct PerformanceTimeline {
+    /// Timer handle that `performance.now()` measures from
+    pub(crate) origin: u64,
+    /// Last value returned by `now()`, so time never runs backwards
+    last_now: f64,
+    pub(crate) entries: Vec<PerfEntry>,
+}
+
+impl PerformanceTimeline {
+    pub(crate) fn new(origin: u64) -> Self {
+        Self {
+            origin,
+            last_now: 0.0,
+            entries: Vec::new(),
+        }
+    }
+
+    fn find_mark(&self, name: &str) -> Option<f64> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| entry.entry_type == PerfEntryType::Mark && entry.name == name)
+            .map(|entry| entry.start)
+    }
+}
+
+impl Interpreter {
+    /// Milliseconds since the time origin, as `performance.now()` returns them
+    pub fn performance_now(&mut self) -> f64 {
+        let elapsed = self
+            .time_provider
+            .elapsed_precise_millis(self.performance.origin);
+        let now = elapsed.max(self.performance.last_now);
+        self.performance.last_now = now;
+        now
+    }
+
+    /// Marks and measures recorded by scripts, in the order they were made
+    pub fn performance_entries(&self) -> Vec<PerfEntry> {
+        self.performance.entries.clone()
+    }
+}
+
+/// Create the performance object and register it globally
+pub fn init_performance(interp: &mut Interpreter) {
+    let performance = interp.root_guard.alloc();
+    performance.borrow_mut().prototype = Some(interp.object_prototype.clone());
+
+    interp.register_method(&performance, "now", performance_now, 0);
+    interp.register_method(&performance, "mark", performance_mark, 1);
+    interp.register_method(&performance, "measure", performance_measure, 1);
+    interp.register_method(&performance, "getEntries", performance_get_entries, 0);
+    interp.register_method(
+        &performance,
+        "getEntriesByType",
+        performance_get_entries_by_type,
+        1,
+    );
+    interp.register_method(
+        &performance,
+        "getEntriesByName",
+        performance_get_entries_by_name,
+        1,
+    );
+    interp.register_method(&performance, "clearMarks", performance_clear_marks, 0);
+    interp.register_method(&performance, "clearMeasures", performance_clear_measures, 0);
+
+    let key = PropertyKey::String(interp.intern("performance"));
+    interp
+        .global
+        .borrow_mut()
+        .set_property(key, JsValue::Object(performance));
+}
+
+/// Build the object scripts see for an entry
+fn entry_object(interp: &mut Interpreter, guard: &Guard<JsObject>, entry: &PerfEntry) -> JsValue {
+    let obj = interp.create_object(guard);
+    let fields = [
+        ("name", JsValue::String(interp.intern(&entry.name))),
+        (
+            "entryType",
+            JsValue::String(interp.intern(entry.entry_type.as_str())),
+        ),
+        ("startTime", JsValue::Number(entry.start)),
+        ("duration", JsValue::Number(entry.duration)),
+    ];
+    for (name, value) in fields {
+        let key = PropertyKey::String(interp.intern(name));
+        obj.borrow_mut().set_property(key, value);
+    }
+    JsValue::Object(obj)
+}
+
+/// Return `entries` sorted by start time as an array of entry objects
+fn entries_array(interp: &mut Interpreter, mut entries: Vec<PerfEntry>) -> Guarded {
+    entries.sort_by(|a, b| a.start.total_cmp(&b.start));
+    let guard = interp.heap.create_guard();
+    let items: Vec<JsValue> = entries
+        .iter()
+        .map(|entry| entry_object(interp, &guard, entry))
+        .collect();
+    let array = interp.create_array_from(&guard, items);
+    Guarded::with_guard(JsValue::Object(array), guard)
+}
+
+fn record(interp: &mut Interpreter, entry: PerfEntry) -> Guarded {
+    let guard = interp.heap.create_guard();
+    let value = entry_object(interp, &guard, &entry);
+    interp.performance.entries.push(entry);
+    Guarded::with_guard(value, guard)
+}
+
+/// Read a property of an options object, treating undefined as absent
+fn get_option(interp: &mut Interpreter, options: &JsValue, name: &str) -> Option<JsValue> {
+    let JsValue::Object(obj) = options else {
+        return None;
+    };
+    let key = PropertyKey::String(interp.intern(name));
+    obj.borrow()
+        .get_property(&key)
+        .filter(|value| !matches!(value, JsValue::Undefined))
+}
+
+/// Resolve a measure endpoint given as a mark name or a timestamp
+fn resolve_time(interp: &mut Interpreter, value: &JsValue) -> Result<f64, JsError> {
+    match value {
+        JsValue::Number(time) => Ok(*time),
+        JsValue::String(name) => interp.performance.find_mark(name.as_str()).ok_or_else(|| {
+            JsError::syntax_error_simple(format!("The mark '{}' does not exist", name))
+        }),
+        other => {
+            let type_name = interp.type_of(other);
+            Err(JsError::type_error(format!(
+                "performance.measure: expected a mark name or timestamp, got {}",
+                type_name
+            )))
+        }
+    }
+}
+
+pub fn performance_now(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let now = interp.performance_now();
+    Ok(Guarded::unguarded(JsValue::Number(now)))
+}
+
+pub fn performance_mark(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let name = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let name = interp.to_js_string(&name).to_string();
+    let options = args.get(1).cloned().unwrap_or(JsValue::Undefined);
+    let start = match get_option(interp, &options, "startTime") {
+        Some(time) => {
+            let time = time.to_number();
+            if !time.is_finite() || time < 0.0 {
+                return Err(JsError::type_error(
+                    "performance.mark: startTime must be a non-negative number",
+                ));
+            }
+            time
+        }
+        None => interp.performance_now(),
+    };
+    Ok(record(
+        interp,
+        PerfEntry {
+            name,
+            entry_type: PerfEntryType::Mark,
+            start,
+            duration: 0.0,
+        },
+    ))
+}
+
+pub fn performance_measure(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let name = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let name = interp.to_js_string(&name).to_string();
+    let start_or_options = args.get(1).cloned().unwrap_or(JsValue::Undefined);
+
+    // measure(name, { start, end }) or measure(name, startMark, endMark)
+    let (start, end) = if matches!(start_or_options, JsValue::Object(_)) {
+        (
+            get_option(interp, &start_or_options, "start"),
+            get_option(interp, &start_or_options, "end"),
+        )
+    } else {
+        let end = args
+            .get(2)
+            .filter(|value| !matches!(value, JsValue::Undefined))
+            .cloned();
+        let start = Some(start_or_options).filter(|value| !matches!(value, JsValue::Undefined));
+        (start, end)
+    };
+
+    let start = match start {
+        Some(value) => resolve_time(interp, &value)?,
+        None => 0.0,
+    };
+    let end = match end {
+        Some(value) => resolve_time(interp, &value)?,
+        None => interp.performance_now(),
+    };
+    Ok(record(
+        interp,
+        PerfEntry {
+            name,
+            entry_type: PerfEntryType::Measure,
+            start,
+            duration: end - start,
+        },
+    ))
+}
+
+pub fn performance_get_entries(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let entries = interp.performance.entries.clone();
+    Ok(entries_array(interp, entries))
+}
+
+pub fn performance_get_entries_by_type(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let entry_type = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let entry_type = interp.to_js_string(&entry_type);
+    let entries = interp
+        .performance
+        .entries
+        .iter()
+        .filter(|entry| entry.entry_type.as_str() == entry_type.as_str())
+        .cloned()
+        .collect();
+    Ok(entries_array(interp, entries))
+}
+
+pub fn performance_get_entries_by_name(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let name = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let name = interp.to_js_string(&name);
+    let entry_type = match args.get(1) {
+        None | Some(JsValue::Undefined) => None,
+        Some(value) => Some(interp.to_js_string(value)),
+    };
+    let entries = interp
+        .performance
+        .entries
+        .iter()
+        .filter(|entry| entry.name == name.as_str())
+        .filter(|entry| {
+            entry_type
+                .as_ref()
+                .is_none_or(|t| entry.entry_type.as_str() == t.as_str())
+        })
+        .cloned()
+        .collect();
+    Ok(entries_array(interp, entries))
+}
+
+/// Remove entries of `entry_type`, only those named by the first argument if given
+fn clear_entries(interp: &mut Interpreter, args: &[JsValue], entry_type: PerfEntryType) {
+    let name = match args.first() {
+        None | Some(JsValue::Undefined) => None,
+        Some(value) => Some(interp.to_js_string(value)),
+    };
+    interp.performance.entries.retain(|entry| {
+        entry.entry_type != entry_type
+            || name
+                .as_ref()
+                .is_some_and(|name| entry.name != name.as_str())
+    });
+}
+
+pub fn performance_clear_marks(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    clear_entries(interp, args, PerfEntryType::Mark);
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+pub fn performance_clear_measures(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    clear_entries(interp, args, PerfEntryType::Measure);
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
@@ -4,6 +4,7 @@
 
 use crate::prelude::{Cell, Rc, RefCell, Vec, format, mem, vec};
 
+use super::error::create_error_object;
 use crate::error::JsError;
 use crate::gc::{Gc, Guard};
 use crate::interpreter::Interpreter;
@@ -66,6 +67,7 @@ pub fn create_promise(interp: &mut Interpreter, guard: &Guard<JsObject>) -> Gc<J
         o.prototype = Some(interp.promise_prototype.cheap_clone());
         o.exotic = ExoticObject::Promise(state);
     }
+    interp.track_promise(&obj);
     obj
 }
 
@@ -88,6 +90,7 @@ pub fn create_order_promise(
         o.prototype = Some(interp.promise_prototype.cheap_clone());
         o.exotic = ExoticObject::Promise(state);
     }
+    interp.track_promise(&obj);
     obj
 }
 
@@ -143,17 +146,25 @@ pub fn create_rejected_promise(
     obj
 }
 
-/// Resolve a promise (fulfill or reject based on value)
+/// Resolve a promise (fulfill or reject based on value), following the
+/// ResolvePromise steps of the spec
 fn resolve_promise(
     interp: &mut Interpreter,
     promise: &Gc<JsObject>,
     value: JsValue,
 ) -> Result<(), JsError> {
-    // Check if value is a thenable (another promise)
-    if let JsValue::Object(obj) = &value
-        && let ExoticObject::Promise(state) = &obj.borrow().exotic
-    {
-        // If the value is a promise, adopt its state
+    let JsValue::Object(obj) = &value else {
+        return fulfill_promise(interp, promise, value);
+    };
+
+    if Gc::ptr_eq(obj, promise) {
+        let error = JsError::type_error("Chaining cycle detected for promise #<Promise>");
+        let (reason, _guard) = create_error_object(interp, &error);
+        return reject_promise(interp, promise, reason);
+    }
+
+    // Native promises adopt the state directly, like every other reaction here
+    if let ExoticObject::Promise(state) = &obj.borrow().exotic {
         let state_ref = state.borrow();
         match state_ref.status {
             PromiseStatus::Pending => {
@@ -181,8 +192,148 @@ fn resolve_promise(
         }
     }
 
-    // Not a promise, fulfill with the value
-    fulfill_promise(interp, promise, value)
+    // Any other object with a callable `then` is a thenable: its `then` runs
+    // as a job, with fresh resolving functions for `promise`
+    let then = match get_then(interp, obj) {
+        Ok(then) => then,
+        Err(e) if e.is_termination() => return Err(e),
+        Err(e) => {
+            let (reason, _guard) = rejection_reason(interp, &e);
+            return reject_promise(interp, promise, reason);
+        }
+    };
+    if !then.value.is_callable() {
+        return fulfill_promise(interp, promise, value);
+    }
+
+    let guard = interp.heap.create_guard();
+    guard.guard(promise.cheap_clone());
+    guard.guard(obj.cheap_clone());
+    if let JsValue::Object(then_fn) = &then.value {
+        guard.guard(then_fn.cheap_clone());
+    }
+    interp.thenable_jobs.push_back(ThenableJob {
+        promise: promise.cheap_clone(),
+        thenable: value.clone(),
+        then: then.value,
+        _guard: guard,
+    });
+    Ok(())
+}
+
+/// The `then` property of `obj`, running getters and proxy traps
+fn get_then(interp: &mut Interpreter, obj: &Gc<JsObject>) -> Result<Guarded, JsError> {
+    let key = PropertyKey::String(interp.intern("then"));
+    if matches!(obj.borrow().exotic, ExoticObject::Proxy(_)) {
+        return super::proxy::proxy_get(
+            interp,
+            obj.cheap_clone(),
+            key,
+            JsValue::Object(obj.cheap_clone()),
+        );
+    }
+    let prop = obj.borrow().get_property_descriptor(&key);
+    match prop {
+        Some((prop, _)) if prop.is_accessor() => match prop.getter() {
+            Some(getter) => interp.call_function(
+                JsValue::Object(getter.cheap_clone()),
+                JsValue::Object(obj.cheap_clone()),
+                &[],
+            ),
+            None => Ok(Guarded::unguarded(JsValue::Undefined)),
+        },
+        Some((prop, _)) => Ok(Guarded::unguarded(prop.value.clone())),
+        None => Ok(Guarded::unguarded(JsValue::Undefined)),
+    }
+}
+
+/// What a promise is rejected with when `error` is raised: thrown values as
+/// they are, errors raised natively as error objects
+fn rejection_reason(
+    interp: &mut Interpreter,
+    error: &JsError,
+) -> (JsValue, Option<Guard<JsObject>>) {
+    match error {
+        JsError::ThrownValue { guarded } => (guarded.value.clone(), None),
+        other => create_error_object(interp, other),
+    }
+}
+
+/// A thenable's `then`, waiting to be called to settle `promise`
+/// (NewPromiseResolveThenableJob)
+pub(crate) struct ThenableJob {
+    promise: Gc<JsObject>,
+    thenable: JsValue,
+    then: JsValue,
+    /// Keeps the promise, thenable and `then` alive while queued
+    _guard: Guard<JsObject>,
+}
+
+/// The resolve and reject functions of `promise`, which settle it at most once
+/// between them
+fn create_resolving_functions(
+    interp: &mut Interpreter,
+    guard: &Guard<JsObject>,
+    promise: &Gc<JsObject>,
+) -> (Gc<JsObject>, Gc<JsObject>) {
+    let already_resolved = Rc::new(Cell::new(false));
+    let resolve_fn = interp.create_js_function(
+        guard,
+        JsFunction::PromiseResolve {
+            promise: promise.cheap_clone(),
+            already_resolved: already_resolved.clone(),
+        },
+    );
+    let reject_fn = interp.create_js_function(
+        guard,
+        JsFunction::PromiseReject {
+            promise: promise.cheap_clone(),
+            already_resolved,
+        },
+    );
+    (resolve_fn, reject_fn)
+}
+
+impl Interpreter {
+    /// Run queued thenable jobs, and the jobs they queue, until none are left.
+    ///
+    /// Called whenever script execution returns to the host, so thenables
+    /// settle before it sees the result. Returns whether any job ran.
+    pub(crate) fn run_thenable_jobs(&mut self) -> Result<bool, JsError> {
+        let mut ran = false;
+        while let Some(job) = self.thenable_jobs.pop_front() {
+            ran = true;
+            let guard = self.heap.create_guard();
+            let (resolve_fn, reject_fn) = create_resolving_functions(self, &guard, &job.promise);
+            let outcome = self.call_function(
+                job.then,
+                job.thenable,
+                &[
+                    JsValue::Object(resolve_fn.cheap_clone()),
+                    JsValue::Object(reject_fn),
+                ],
+            );
+            match outcome {
+                Ok(_) => {}
+                Err(e) if e.is_termination() => return Err(e),
+                Err(e) => {
+                    // A throw after resolve or reject was called is ignored
+                    let already_resolved = match &resolve_fn.borrow().exotic {
+                        ExoticObject::Function(JsFunction::PromiseResolve {
+                            already_resolved,
+                            ..
+                        }) => already_resolved.replace(true),
+                        _ => true,
+                    };
+                    if !already_resolved {
+                        let (reason, _reason_guard) = rejection_reason(self, &e);
+                        reject_promise(self, &job.promise, reason)?;
+                    }
+                }
+            }
+        }
+        Ok(ran)
+    }
 }
 
 /// Fulfill a promise with a value
@@ -208,6 +359,7 @@ fn fulfill_promise(
     };
 
     // Trigger handlers synchronously
+    let _handlers_guard = guard_handlers(interp, &handlers);
     for handler in handlers {
         trigger_handler(interp, handler, &value, true)?;
     }
@@ -240,10 +392,11 @@ fn reject_promise(
 
     // Signal cancelled order if this was a host Promise
     if let Some(id) = order_id {
-        interp.cancelled_orders.push(id);
+        interp.cancel_order(id);
     }
 
     // Trigger handlers synchronously
+    let _handlers_guard = guard_handlers(interp, &handlers);
     for handler in handlers {
         trigger_handler(interp, handler, &reason, false)?;
     }
@@ -251,6 +404,120 @@ fn reject_promise(
     Ok(())
 }
 
+/// Root `handlers` taken out of a settled promise, so those still waiting
+/// survive collections while earlier ones run
+fn guard_handlers(interp: &Interpreter, handlers: &[PromiseHandler]) -> Guard<JsObject> {
+    let guard = interp.heap.create_guard();
+    for handler in handlers {
+        guard.guard(handler.result_promise.cheap_clone());
+        for callback in [&handler.on_fulfilled, &handler.on_rejected] {
+            if let Some(JsValue::Object(obj)) = callback {
+                guard.guard(obj.cheap_clone());
+            }
+        }
+    }
+    guard
+}
+
+/// Whether the outcome of a pending promise can no longer be observed: no async
+/// context awaits it, and every reaction feeds an already-settled promise (such as
+/// a decided `Promise.race`) or another promise whose outcome is ignored.
+///
+/// A promise without reactions may still be held by a variable, so it is never ignored.
+pub(crate) fn is_result_ignored(interp: &Interpreter, promise: &Gc<JsObject>) -> bool {
+    let mut visited = Vec::new();
+    result_ignored(interp, promise, &mut visited)
+}
+
+fn result_ignored(
+    interp: &Interpreter,
+    promise: &Gc<JsObject>,
+    visited: &mut Vec<Gc<JsObject>>,
+) -> bool {
+    if visited.iter().any(|p| Gc::ptr_eq(p, promise)) {
+        return true;
+    }
+    visited.push(promise.cheap_clone());
+
+    let targets: Vec<Gc<JsObject>> = {
+        let obj = promise.borrow();
+        let ExoticObject::Promise(ref state) = obj.exotic else {
+            return false;
+        };
+        let state = state.borrow();
+        if state.status != PromiseStatus::Pending || state.handlers.is_empty() {
+            return false;
+        }
+        state.handlers.iter().flat_map(reaction_targets).collect()
+    };
+
+    let awaited = interp
+        .wait_graph
+        .contexts
+        .values()
+        .any(|ctx| Gc::ptr_eq(&ctx.waiting_on, promise));
+    if awaited {
+        return false;
+    }
+
+    targets
+        .iter()
+        .all(|target| is_settled(target) || result_ignored(interp, target, visited))
+}
+
+/// Whether nothing observes what an async generator produces next: every
+/// request queued on it feeds only promises whose outcome is ignored
+pub(crate) fn is_generator_result_ignored(interp: &Interpreter, generator: &Gc<JsObject>) -> bool {
+    let requests = generator_request_promises(generator);
+    let mut visited = Vec::new();
+    !requests.is_empty()
+        && requests
+            .iter()
+            .all(|request| is_settled(request) || result_ignored(interp, request, &mut visited))
+}
+
+/// The promises observing a reaction's outcome. An async generator resumed
+/// by it reports to the requests queued on it, not to the reaction's own
+/// result promise.
+fn reaction_targets(handler: &PromiseHandler) -> Vec<Gc<JsObject>> {
+    if let Some(JsValue::Object(callback)) = &handler.on_fulfilled
+        && let ExoticObject::Function(JsFunction::AsyncGeneratorResume { generator, .. }) =
+            &callback.borrow().exotic
+    {
+        let requests = generator_request_promises(generator);
+        if !requests.is_empty() {
+            return requests;
+        }
+    }
+    vec![handler.result_promise.cheap_clone()]
+}
+
+fn generator_request_promises(generator: &Gc<JsObject>) -> Vec<Gc<JsObject>> {
+    match &generator.borrow().exotic {
+        ExoticObject::BytecodeGenerator(state) => state
+            .borrow()
+            .queue
+            .iter()
+            .map(|request| request.promise.cheap_clone())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+pub(crate) fn is_settled(promise: &Gc<JsObject>) -> bool {
+    match &promise.borrow().exotic {
+        ExoticObject::Promise(state) => state.borrow().status != PromiseStatus::Pending,
+        _ => true,
+    }
+}
+
+/// Drop a pending promise's reactions so settling it later has no effect
+pub(crate) fn abandon_promise(promise: &Gc<JsObject>) {
+    if let ExoticObject::Promise(state) = &promise.borrow().exotic {
+        state.borrow_mut().handlers.clear();
+    }
+}
+
 /// Public function to resolve a promise
 // NOTE: review
 pub fn resolve_promise_value(
@@ -318,6 +585,7 @@ fn trigger_handler(
                         resolve_promise(interp, &handler.result_promise, result)?;
                     }
                 }
+                Err(e) if e.is_termination() => return Err(e),
                 Err(e) => {
                     // If callback throws, reject the result promise
                     let error_value = e.to_value();
@@ -360,13 +628,7 @@ pub fn promise_constructor(
     let guard = interp.heap.create_guard();
     let promise = create_promise(interp, &guard);
 
-    // Create resolve function using the PromiseResolve variant
-    let resolve_fn =
-        interp.create_js_function(&guard, JsFunction::PromiseResolve(promise.cheap_clone()));
-
-    // Create reject function using the PromiseReject variant
-    let reject_fn =
-        interp.create_js_function(&guard, JsFunction::PromiseReject(promise.cheap_clone()));
+    let (resolve_fn, reject_fn) = create_resolving_functions(interp, &guard, &promise);
 
     // Call executor(resolve, reject)
     match interp.call_function(
@@ -375,6 +637,7 @@ pub fn promise_constructor(
         &[JsValue::Object(resolve_fn), JsValue::Object(reject_fn)],
     ) {
         Ok(_) => {}
+        Err(e) if e.is_termination() => return Err(e),
         Err(e) => {
             // If executor throws, reject the promise
             let error_value = e.to_value();
@@ -835,6 +1098,9 @@ pub fn handle_promise_all_reject(
 
     reject_promise(interp, &result_promise, reason)?;
 
+    // Orders feeding the remaining inputs are no longer needed
+    interp.cancel_ignored_orders();
+
     Ok(())
 }
 
@@ -859,7 +1125,7 @@ pub fn handle_promise_race_settle(
         if i != winner_index
             && let Some(id) = order_id
         {
-            interp.cancelled_orders.push(*id);
+            interp.cancel_order(*id);
         }
     }
 
@@ -871,6 +1137,9 @@ pub fn handle_promise_race_settle(
         reject_promise(interp, &result_promise, value)?;
     }
 
+    // Orders that only fed the losers are no longer needed
+    interp.cancel_ignored_orders();
+
     Ok(())
 }
 
@@ -4,8 +4,8 @@
 //! Reflect provides methods that mirror the proxy trap operations.
 
 use crate::error::JsError;
-use crate::interpreter::Interpreter;
-use crate::prelude::{ToString, Vec, vec};
+use crate::interpreter::{Interpreter, host_class};
+use crate::prelude::{ToString, Vec};
 use crate::value::{
     ExoticObject, Guarded, JsFunction, JsObject, JsObjectRef, JsValue, Property, PropertyKey,
     ProxyData,
@@ -288,17 +288,11 @@ fn reflect_apply(
         return Err(JsError::type_error("Reflect.apply: target is not callable"));
     }
 
-    // Convert arguments list to array
-    let call_args = if let JsValue::Object(arr) = arguments_list {
-        let arr_ref = arr.borrow();
-        if let Some(elements) = arr_ref.array_elements() {
-            elements.to_vec()
-        } else {
-            vec![]
-        }
-    } else {
-        vec![]
-    };
+    let call_args = super::array::create_list_from_array_like(
+        interp,
+        &arguments_list,
+        "Reflect.apply: argumentsList",
+    )?;
 
     interp.call_function(target, this_arg, &call_args)
 }
@@ -319,17 +313,11 @@ fn reflect_construct(
         ));
     }
 
-    // Convert arguments list to array
-    let call_args: Vec<JsValue> = if let JsValue::Object(arr) = arguments_list {
-        let arr_ref = arr.borrow();
-        if let Some(elements) = arr_ref.array_elements() {
-            elements.to_vec()
-        } else {
-            vec![]
-        }
-    } else {
-        vec![]
-    };
+    let call_args = super::array::create_list_from_array_like(
+        interp,
+        &arguments_list,
+        "Reflect.construct: argumentsList",
+    )?;
 
     // Get the target's prototype from newTarget if different
     let prototype = if let JsValue::Object(new_target_obj) = &new_target {
@@ -530,6 +518,9 @@ pub fn proxy_get(
     key: PropertyKey,
     receiver: JsValue,
 ) -> Result<Guarded, JsError> {
+    // Traps and forwarding to proxy targets recurse on the Rust stack
+    let _depth = interp.enter_native()?;
+
     // Check if this is actually a proxy
     let (target, handler) = {
         let obj_ref = obj.borrow();
@@ -542,6 +533,10 @@ pub fn proxy_get(
                 }
                 (data.target.clone(), data.handler.clone())
             }
+            ExoticObject::Host(_) => {
+                drop(obj_ref);
+                return host_class::host_get(interp, &obj, &key, receiver);
+            }
             _ => {
                 // Not a proxy, do normal property access
                 drop(obj_ref);
@@ -586,6 +581,8 @@ pub fn proxy_set(
     value: JsValue,
     receiver: JsValue,
 ) -> Result<bool, JsError> {
+    let _depth = interp.enter_native()?;
+
     // Check if this is actually a proxy
     let (target, handler) = {
         let obj_ref = obj.borrow();
@@ -598,6 +595,10 @@ pub fn proxy_set(
                 }
                 (data.target.clone(), data.handler.clone())
             }
+            ExoticObject::Host(_) => {
+                drop(obj_ref);
+                return host_class::host_set(interp, &obj, key, value);
+            }
             _ => {
                 // Not a proxy, do normal property set
                 drop(obj_ref);
@@ -615,6 +616,7 @@ pub fn proxy_set(
                     }
                     return Ok(false);
                 }
+                interp.check_write(&obj, Some(&key))?;
                 obj.borrow_mut().set_property(key, value);
                 return Ok(true);
             }
@@ -642,6 +644,8 @@ pub fn proxy_has(
     obj: JsObjectRef,
     key: &PropertyKey,
 ) -> Result<bool, JsError> {
+    let _depth = interp.enter_native()?;
+
     // Check if this is actually a proxy
     let (target, handler) = {
         let obj_ref = obj.borrow();
@@ -654,6 +658,10 @@ pub fn proxy_has(
                 }
                 (data.target.clone(), data.handler.clone())
             }
+            ExoticObject::Host(_) => {
+                drop(obj_ref);
+                return host_class::host_has(interp, &obj, key);
+            }
             _ => {
                 // Not a proxy, do normal has check
                 drop(obj_ref);
@@ -683,6 +691,8 @@ pub fn proxy_delete_property(
     obj: JsObjectRef,
     key: &PropertyKey,
 ) -> Result<bool, JsError> {
+    let _depth = interp.enter_native()?;
+
     // Check if this is actually a proxy
     let (target, handler) = {
         let obj_ref = obj.borrow();
@@ -698,6 +708,7 @@ pub fn proxy_delete_property(
             _ => {
                 // Not a proxy, do normal delete
                 drop(obj_ref);
+                interp.check_write(&obj, Some(key))?;
                 obj.borrow_mut().properties.remove(key);
                 return Ok(true);
             }
@@ -725,6 +736,8 @@ pub fn proxy_get_own_property_descriptor(
     obj: JsObjectRef,
     key: &PropertyKey,
 ) -> Result<Guarded, JsError> {
+    let _depth = interp.enter_native()?;
+
     // Check if this is actually a proxy
     let (target, handler) = {
         let obj_ref = obj.borrow();
@@ -766,6 +779,8 @@ pub fn proxy_define_property(
     key: PropertyKey,
     descriptor: JsValue,
 ) -> Result<bool, JsError> {
+    let _depth = interp.enter_native()?;
+
     // Check if this is actually a proxy
     let (target, handler) = {
         let obj_ref = obj.borrow();
@@ -781,6 +796,7 @@ pub fn proxy_define_property(
             _ => {
                 // Not a proxy, do normal define
                 drop(obj_ref);
+                interp.check_write(&obj, Some(&key))?;
                 define_property_from_descriptor(interp, &obj, key, descriptor)?;
                 return Ok(true);
             }
@@ -807,6 +823,8 @@ pub fn proxy_get_prototype_of(
     interp: &mut Interpreter,
     obj: JsObjectRef,
 ) -> Result<Guarded, JsError> {
+    let _depth = interp.enter_native()?;
+
     // Check if this is actually a proxy
     let (target, handler) = {
         let obj_ref = obj.borrow();
@@ -846,6 +864,8 @@ pub fn proxy_set_prototype_of(
     obj: JsObjectRef,
     proto: JsValue,
 ) -> Result<bool, JsError> {
+    let _depth = interp.enter_native()?;
+
     // Check if this is actually a proxy
     let (target, handler) = {
         let obj_ref = obj.borrow();
@@ -866,8 +886,8 @@ pub fn proxy_set_prototype_of(
                     JsValue::Object(p) => Some(p),
                     _ => return Err(JsError::type_error("Prototype must be object or null")),
                 };
-                obj.borrow_mut().prototype = new_proto;
-                return Ok(true);
+                interp.check_write(&obj, None)?;
+                return Ok(super::object::ordinary_set_prototype_of(&obj, new_proto));
             }
         }
     };
@@ -888,6 +908,8 @@ pub fn proxy_set_prototype_of(
 
 /// Proxy [[IsExtensible]] internal method
 pub fn proxy_is_extensible(interp: &mut Interpreter, obj: JsObjectRef) -> Result<bool, JsError> {
+    let _depth = interp.enter_native()?;
+
     // Check if this is actually a proxy
     let (target, handler) = {
         let obj_ref = obj.borrow();
@@ -924,6 +946,8 @@ pub fn proxy_prevent_extensions(
     interp: &mut Interpreter,
     obj: JsObjectRef,
 ) -> Result<bool, JsError> {
+    let _depth = interp.enter_native()?;
+
     // Check if this is actually a proxy
     let (target, handler) = {
         let obj_ref = obj.borrow();
@@ -958,6 +982,8 @@ pub fn proxy_prevent_extensions(
 
 /// Proxy [[OwnPropertyKeys]] internal method
 pub fn proxy_own_keys(interp: &mut Interpreter, obj: JsObjectRef) -> Result<Guarded, JsError> {
+    let _depth = interp.enter_native()?;
+
     // Check if this is actually a proxy
     let (target, handler) = {
         let obj_ref = obj.borrow();
@@ -973,6 +999,12 @@ pub fn proxy_own_keys(interp: &mut Interpreter, obj: JsObjectRef) -> Result<Guar
             _ => {
                 // Not a proxy, return normal keys
                 drop(obj_ref);
+                if let Some(keys) = host_class::host_own_keys(interp, &obj)? {
+                    let guard = interp.heap.create_guard();
+                    let keys = keys.into_iter().map(host_class::key_to_value).collect();
+                    let arr = interp.create_array_from(&guard, keys);
+                    return Ok(Guarded::with_guard(JsValue::Object(arr), guard));
+                }
                 return get_own_keys(interp, &obj);
             }
         }
@@ -997,6 +1029,8 @@ pub fn proxy_apply(
     this_arg: JsValue,
     args: Vec<JsValue>,
 ) -> Result<Guarded, JsError> {
+    let _depth = interp.enter_native()?;
+
     // Check if this is actually a proxy
     let (target, handler) = {
         let obj_ref = obj.borrow();
@@ -1009,6 +1043,10 @@ pub fn proxy_apply(
                 }
                 (data.target.clone(), data.handler.clone())
             }
+            ExoticObject::Host(_) => {
+                drop(obj_ref);
+                return host_class::host_call(interp, &obj, this_arg, &args);
+            }
             _ => {
                 // Not a proxy, should not be called
                 return Err(JsError::type_error("proxy_apply called on non-proxy"));
@@ -1044,6 +1082,8 @@ pub fn proxy_construct(
     args: Vec<JsValue>,
     new_target: JsValue,
 ) -> Result<Guarded, JsError> {
+    let _depth = interp.enter_native()?;
+
     // Check if this is actually a proxy
     let (target, handler) = {
         let obj_ref = obj.borrow();
@@ -1140,6 +1180,7 @@ fn create_property_descriptor(
 
     match prop {
         Some(p) => {
+            let p = interp.reported_own_property(obj, key, p)?;
             let guard = interp.heap.create_guard();
             let desc = interp.create_object(&guard);
 
@@ -1203,33 +1244,14 @@ fn define_property_from_descriptor(
     Ok(())
 }
 
-/// Get own property keys as an array
+/// Get own property keys as an array, in spec order
 fn get_own_keys(interp: &mut Interpreter, obj: &JsObjectRef) -> Result<Guarded, JsError> {
-    let keys: Vec<JsValue> = {
-        let obj_ref = obj.borrow();
-
-        // For arrays, include indices
-        if let ExoticObject::Array { ref elements } = obj_ref.exotic {
-            let mut keys: Vec<JsValue> = (0..elements.len())
-                .map(|i| JsValue::String(crate::value::JsString::from(i.to_string())))
-                .collect();
-
-            // Add other string keys
-            for key in obj_ref.properties.keys() {
-                if let PropertyKey::String(s) = key {
-                    keys.push(JsValue::String(s.clone()));
-                }
-            }
-
-            keys
-        } else {
-            obj_ref
-                .properties
-                .keys()
-                .map(property_key_to_value)
-                .collect()
-        }
-    };
+    let keys: Vec<JsValue> = obj
+        .borrow()
+        .own_property_keys()
+        .iter()
+        .map(property_key_to_value)
+        .collect();
 
     let guard = interp.heap.create_guard();
     let arr = interp.create_array_from(&guard, keys);
@@ -1240,3 +1262,12 @@ fn get_own_keys(interp: &mut Interpreter, obj: &JsObjectRef) -> Result<Guarded,
 pub fn is_proxy(obj: &JsObjectRef) -> bool {
     matches!(obj.borrow().exotic, ExoticObject::Proxy(_))
 }
+
+/// Check if an object's [[Get]], [[Set]], [[HasProperty]], [[OwnPropertyKeys]]
+/// and [[Call]] go through the `proxy_*` functions: a proxy or a host object
+pub fn has_hooks(obj: &JsObjectRef) -> bool {
+    matches!(
+        obj.borrow().exotic,
+        ExoticObject::Proxy(_) | ExoticObject::Host(_)
+    )
+}
@@ -0,0 +1,283 @@
+//! tsrun:quantity built-in module
+//!
+//! Parses and formats Kubernetes-style resource quantities:
+//!
+//! ```typescript
+//! import { parseQuantity, formatQuantity } from "tsrun:quantity";
+//!
+//! parseQuantity("500m");                       // 0.5
+//! parseQuantity("1.5Gi");                      // 1610612736
+//! formatQuantity(1610612736, { binary: true }); // "1536Mi"
+//! formatQuantity(1610612736, { binary: true, precision: 1 }); // "1.5Gi"
+//! ```
+//!
+//! Decimal suffixes are `n`, `u`, `m`, `k`, `M`, `G`, `T`, `P`, `E`; binary
+//! suffixes are `Ki` through `Ei`. A decimal exponent (`1e3`) is accepted too.
+//! Results beyond `Number.MAX_SAFE_INTEGER` are rejected rather than rounded.
+
+use crate::InternalModule;
+use crate::error::JsError;
+use crate::interpreter::Interpreter;
+use crate::prelude::{String, ToString, format, math};
+use crate::value::{Guarded, JsString, JsValue, PropertyKey};
+
+/// Create the tsrun:quantity module
+pub fn create_quantity_internal_module() -> InternalModule {
+    InternalModule::native("tsrun:quantity")
+        .with_function("parseQuantity", parse_quantity_fn, 1)
+        .with_function("formatQuantity", format_quantity_fn, 2)
+        .build()
+}
+
+/// 2^53 - 1, the largest integer every f64 neighbour of which is exact
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+/// Suffix multipliers: (suffix, power of 10, power of 1024)
+const SUFFIXES: &[(&str, i32, u32)] = &[
+    ("n", -9, 0),
+    ("u", -6, 0),
+    ("m", -3, 0),
+    ("", 0, 0),
+    ("k", 3, 0),
+    ("M", 6, 0),
+    ("G", 9, 0),
+    ("T", 12, 0),
+    ("P", 15, 0),
+    ("E", 18, 0),
+    ("Ki", 0, 1),
+    ("Mi", 0, 2),
+    ("Gi", 0, 3),
+    ("Ti", 0, 4),
+    ("Pi", 0, 5),
+    ("Ei", 0, 6),
+];
+
+/// Formatting candidates, largest first
+const DECIMAL_ORDER: &[&str] = &["E", "P", "T", "G", "M", "k", "", "m", "u", "n"];
+const BINARY_ORDER: &[&str] = &["Ei", "Pi", "Ti", "Gi", "Mi", "Ki", "", "m", "u", "n"];
+
+/// parseQuantity(str) - returns the quantity in base units
+fn parse_quantity_fn(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let input = match args.first() {
+        Some(JsValue::String(s)) => s.as_str().to_string(),
+        Some(JsValue::Number(n)) => return Ok(Guarded::unguarded(JsValue::Number(*n))),
+        Some(other) => interp.to_js_string(other).to_string(),
+        None => return Err(JsError::type_error("parseQuantity requires a string")),
+    };
+    let value = parse_quantity(&input)?;
+    Ok(Guarded::unguarded(JsValue::Number(value)))
+}
+
+/// formatQuantity(number, { binary?, precision? }) - returns the shortest clean form
+fn format_quantity_fn(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let value = match args.first() {
+        Some(JsValue::Number(n)) => *n,
+        Some(other) => interp.coerce_to_number(other)?,
+        None => f64::NAN,
+    };
+
+    let mut binary = false;
+    let mut precision = None;
+    if let Some(JsValue::Object(opts)) = args.get(1) {
+        let binary_key = PropertyKey::String(interp.intern("binary"));
+        let precision_key = PropertyKey::String(interp.intern("precision"));
+        let opts = opts.borrow();
+        if let Some(v) = opts.get_property(&binary_key) {
+            binary = v.to_boolean();
+        }
+        match opts.get_property(&precision_key) {
+            Some(JsValue::Number(p)) if (0.0..=20.0).contains(&p) => precision = Some(p as u32),
+            Some(JsValue::Undefined) | None => {}
+            Some(_) => {
+                return Err(JsError::range_error(
+                    "formatQuantity precision must be a number between 0 and 20",
+                ));
+            }
+        }
+    }
+
+    let text = format_quantity(value, binary, precision)?;
+    Ok(Guarded::unguarded(JsValue::String(JsString::from(text))))
+}
+
+/// Parse a quantity string such as `"500m"`, `"2Gi"` or `"1.5e3k"`
+pub fn parse_quantity(input: &str) -> Result<f64, JsError> {
+    let invalid = |rest: &str, expected: &str| {
+        JsError::range_error(format!(
+            "Invalid quantity '{}': {} at '{}'",
+            input, expected, rest
+        ))
+    };
+
+    let s = input.trim();
+    let (negative, rest) = match s.as_bytes().first() {
+        Some(b'-') => (true, s.get(1..).unwrap_or("")),
+        Some(b'+') => (false, s.get(1..).unwrap_or("")),
+        _ => (false, s),
+    };
+
+    // Mantissa digits, remembering how many came after the decimal point
+    let mut digits = String::new();
+    let mut scale: i32 = 0;
+    let mut seen_point = false;
+    let mut end = 0;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '0'..='9' => {
+                digits.push(c);
+                if seen_point {
+                    scale += 1;
+                }
+            }
+            '.' if !seen_point => seen_point = true,
+            _ => break,
+        }
+        end = i + c.len_utf8();
+    }
+    if digits.is_empty() {
+        return Err(invalid(rest, "expected a number"));
+    }
+    let mut rest = rest.get(end..).unwrap_or("");
+
+    // Optional decimal exponent; a lone `E` is the exa suffix
+    let mut exponent: i32 = 0;
+    if let Some(after) = rest.strip_prefix(['e', 'E'])
+        && let Some(len) = exponent_len(after)
+    {
+        let text = after.get(..len).unwrap_or("");
+        exponent = text
+            .parse()
+            .map_err(|_| invalid(rest, "exponent out of range"))?;
+        rest = after.get(len..).unwrap_or("");
+    }
+
+    let Some(&(_, pow10, pow1024)) = SUFFIXES.iter().find(|(suffix, _, _)| *suffix == rest) else {
+        return Err(invalid(rest, "unknown suffix"));
+    };
+
+    let mantissa: i128 = digits.trim_start_matches('0').parse().or_else(|_| {
+        if digits.bytes().all(|b| b == b'0') {
+            Ok(0)
+        } else {
+            Err(invalid(&digits, "too many digits"))
+        }
+    })?;
+    let too_large = || {
+        JsError::range_error(format!(
+            "Quantity '{}' exceeds Number.MAX_SAFE_INTEGER",
+            input
+        ))
+    };
+
+    // mantissa * 1024^pow1024 * 10^(pow10 + exponent - scale), kept exact in integers
+    // as long as possible so representable inputs don't pick up rounding error
+    let mut numerator = mantissa
+        .checked_mul(1i128.checked_shl(10 * pow1024).ok_or_else(too_large)?)
+        .ok_or_else(too_large)?;
+    let power = pow10 + exponent - scale;
+    let value = if power >= 0 {
+        for _ in 0..power {
+            numerator = numerator.checked_mul(10).ok_or_else(too_large)?;
+            if numerator as f64 > MAX_SAFE_INTEGER {
+                return Err(too_large());
+            }
+        }
+        numerator as f64
+    } else if numerator == 0 {
+        0.0
+    } else {
+        let divisor = math::powi(10.0, -power);
+        numerator as f64 / divisor
+    };
+
+    if value.abs() > MAX_SAFE_INTEGER {
+        return Err(too_large());
+    }
+    Ok(if negative { -value } else { value })
+}
+
+/// Length of a signed integer exponent at the start of `s`, if there is one
+fn exponent_len(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    let sign = usize::from(matches!(bytes.first(), Some(b'+' | b'-')));
+    let digit_count = bytes
+        .iter()
+        .skip(sign)
+        .take_while(|b| b.is_ascii_digit())
+        .count();
+    (digit_count > 0).then_some(sign + digit_count)
+}
+
+/// Format `value` with the largest suffix that keeps the mantissa clean.
+///
+/// Without `precision` the mantissa must be an integer that parses back to
+/// exactly `value`; if no suffix qualifies the plain number is returned. With
+/// `precision` the largest suffix with a mantissa of at least 1 is used and
+/// the mantissa is rounded to that many fractional digits.
+pub fn format_quantity(
+    value: f64,
+    binary: bool,
+    precision: Option<u32>,
+) -> Result<String, JsError> {
+    if !value.is_finite() {
+        return Err(JsError::range_error(format!(
+            "Cannot format {} as a quantity",
+            JsValue::Number(value).to_js_string()
+        )));
+    }
+    if value == 0.0 {
+        return Ok("0".to_string());
+    }
+
+    let order = if binary { BINARY_ORDER } else { DECIMAL_ORDER };
+    let magnitude = value.abs();
+    let sign = if value < 0.0 { "-" } else { "" };
+
+    if let Some(precision) = precision {
+        let suffix = order
+            .iter()
+            .find(|suffix| magnitude / multiplier(suffix) >= 1.0)
+            .or(order.last())
+            .copied()
+            .unwrap_or("");
+        let factor = math::powi(10.0, precision as i32);
+        let mantissa = math::round(magnitude / multiplier(suffix) * factor) / factor;
+        return Ok(format!("{}{}{}", sign, number_text(mantissa), suffix));
+    }
+
+    for suffix in order {
+        // Division by a decimal multiplier can land a hair off the integer;
+        // the parse-back check below is what guarantees exactness
+        let mantissa = math::round(magnitude / multiplier(suffix));
+        if mantissa < 1.0 {
+            continue;
+        }
+        let text = number_text(mantissa);
+        if parse_quantity(&format!("{}{}", text, suffix)).ok() == Some(magnitude) {
+            return Ok(format!("{}{}{}", sign, text, suffix));
+        }
+    }
+
+    // Nothing is exact: fall back to the plain number
+    Ok(number_text(value))
+}
+
+fn multiplier(suffix: &str) -> f64 {
+    SUFFIXES
+        .iter()
+        .find(|(s, _, _)| *s == suffix)
+        .map(|&(_, pow10, pow1024)| math::powi(10.0, pow10) * math::powi(1024.0, pow1024 as i32))
+        .unwrap_or(1.0)
+}
+
+fn number_text(n: f64) -> String {
+    JsValue::Number(n).to_js_string().to_string()
+}
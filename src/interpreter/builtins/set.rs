@@ -1,11 +1,14 @@
 //! Set built-in methods
 
 use crate::error::JsError;
+use crate::gc::{Gc, Guard};
 use crate::interpreter::Interpreter;
-use crate::prelude::{Box, Vec, index_set_new, vec};
-use crate::value::{CheapClone, ExoticObject, Guarded, JsMapKey, JsValue, PropertyKey};
+use crate::interpreter::builtins::object::check_entries_mutable;
+use crate::prelude::{Box, IndexSet, Vec, format, index_set_new, math, vec};
+use crate::value::{CheapClone, ExoticObject, Guarded, JsMapKey, JsObject, JsValue, PropertyKey};
 
-/// Initialize Set.prototype with add, has, delete, clear, forEach methods
+/// Initialize Set.prototype with add, has, delete, clear, forEach methods and
+/// the set algebra methods (union, intersection, isSubsetOf, ...)
 pub fn init_set_prototype(interp: &mut Interpreter) {
     let proto = interp.set_prototype.clone();
 
@@ -17,6 +20,13 @@ pub fn init_set_prototype(interp: &mut Interpreter) {
     interp.register_method(&proto, "keys", set_keys, 0);
     interp.register_method(&proto, "values", set_values, 0);
     interp.register_method(&proto, "entries", set_entries, 0);
+    interp.register_method(&proto, "union", set_union, 1);
+    interp.register_method(&proto, "intersection", set_intersection, 1);
+    interp.register_method(&proto, "difference", set_difference, 1);
+    interp.register_method(&proto, "symmetricDifference", set_symmetric_difference, 1);
+    interp.register_method(&proto, "isSubsetOf", set_is_subset_of, 1);
+    interp.register_method(&proto, "isSupersetOf", set_is_superset_of, 1);
+    interp.register_method(&proto, "isDisjointFrom", set_is_disjoint_from, 1);
 
     // Symbol.iterator = Set.prototype.values (Set iterates over values by default)
     let well_known = interp.well_known_symbols;
@@ -78,22 +88,22 @@ pub fn set_constructor(
         obj.set_property(size_key, JsValue::Number(0.0));
     }
 
-    // If an iterable (array) is passed, add its elements
-    if let Some(JsValue::Object(arr)) = args.first() {
-        let arr_ref = arr.borrow();
-        if let Some(elements) = arr_ref.array_elements() {
-            let items: Vec<JsValue> = elements.to_vec();
-            drop(arr_ref);
-
-            let size_key = PropertyKey::String(interp.intern("size"));
-            let mut set = set_obj.borrow_mut();
-            if let ExoticObject::Set { ref mut entries } = set.exotic {
-                for value in items {
-                    entries.insert(JsMapKey(value));
-                }
-                let len = entries.len();
-                set.set_property(size_key, JsValue::Number(len as f64));
+    // If an iterable is passed, add its elements
+    if let Some(iterable) = args.first().filter(|v| !v.is_null_or_undefined()) {
+        let Some(items) = interp.collect_iterator_values(iterable)? else {
+            return Err(JsError::type_error(
+                "Set constructor argument is not iterable",
+            ));
+        };
+
+        let size_key = PropertyKey::String(interp.intern("size"));
+        let mut set = set_obj.borrow_mut();
+        if let ExoticObject::Set { ref mut entries } = set.exotic {
+            for value in items {
+                entries.insert(JsMapKey::canonical(value));
             }
+            let len = entries.len();
+            set.set_property(size_key, JsValue::Number(len as f64));
         }
     }
 
@@ -110,6 +120,8 @@ pub fn set_add(
             "Set.prototype.add called on non-object",
         ));
     };
+    interp.check_write(&set_obj, None)?;
+    check_entries_mutable(&set_obj, "Set.prototype.add")?;
 
     let size_key = PropertyKey::String(interp.intern("size"));
 
@@ -118,7 +130,7 @@ pub fn set_add(
     let mut set = set_obj.borrow_mut();
 
     if let ExoticObject::Set { ref mut entries } = set.exotic {
-        entries.insert(JsMapKey(value));
+        entries.insert(JsMapKey::canonical(value));
         let len = entries.len();
         set.set_property(size_key, JsValue::Number(len as f64));
     }
@@ -160,6 +172,8 @@ pub fn set_delete(
             "Set.prototype.delete called on non-object",
         ));
     };
+    interp.check_write(&set_obj, None)?;
+    check_entries_mutable(&set_obj, "Set.prototype.delete")?;
 
     let size_key = PropertyKey::String(interp.intern("size"));
 
@@ -187,6 +201,8 @@ pub fn set_clear(
             "Set.prototype.clear called on non-object",
         ));
     };
+    interp.check_write(&set_obj, None)?;
+    check_entries_mutable(&set_obj, "Set.prototype.clear")?;
 
     let size_key = PropertyKey::String(interp.intern("size"));
 
@@ -281,6 +297,7 @@ pub fn set_values(
 
     // Create iterator object
     let iter_obj = interp.create_object_raw(&guard);
+    iter_obj.borrow_mut().prototype = Some(interp.iterator_prototype.cheap_clone());
     let entries_key = interp.property_key("__entries__");
     let index_key = interp.property_key("__index__");
     let next_key = interp.property_key("next");
@@ -431,6 +448,7 @@ pub fn set_entries(
 
     // Create iterator object
     let iter_obj = interp.create_object_raw(&guard);
+    iter_obj.borrow_mut().prototype = Some(interp.iterator_prototype.cheap_clone());
     let entries_key = interp.property_key("__entries__");
     let index_key = interp.property_key("__index__");
     let next_key = interp.property_key("next");
@@ -462,3 +480,400 @@ pub fn set_entries(
 
     Ok(Guarded::with_guard(JsValue::Object(iter_obj), guard))
 }
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Set algebra (union, intersection, difference, ...)
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// The parts of a set-like argument the set methods use (GetSetRecord)
+struct SetRecord {
+    object: Gc<JsObject>,
+    size: f64,
+    has: JsValue,
+    keys: JsValue,
+}
+
+/// Snapshot the elements of `this`, which must be a Set.
+///
+/// Object elements are rooted in `guard`, since callbacks into the other
+/// argument may delete them from the set while they're still being visited.
+fn this_set_elements(
+    this: &JsValue,
+    method: &str,
+    guard: &Guard<JsObject>,
+) -> Result<Vec<JsValue>, JsError> {
+    let elements: Option<Vec<JsValue>> = match this {
+        JsValue::Object(obj) => match &obj.borrow().exotic {
+            ExoticObject::Set { entries } => Some(entries.iter().map(|k| k.0.clone()).collect()),
+            _ => None,
+        },
+        _ => None,
+    };
+    let Some(elements) = elements else {
+        return Err(JsError::type_error(format!(
+            "Set.prototype.{} called on non-Set",
+            method
+        )));
+    };
+    root_values(guard, &elements);
+    Ok(elements)
+}
+
+fn root_values(guard: &Guard<JsObject>, values: &[JsValue]) {
+    for value in values {
+        if let JsValue::Object(obj) = value {
+            guard.guard(obj.cheap_clone());
+        }
+    }
+}
+
+/// Whether the Set `this` currently contains `value`
+fn set_data_has(this: &JsValue, value: &JsValue) -> bool {
+    let JsValue::Object(obj) = this else {
+        return false;
+    };
+    match &obj.borrow().exotic {
+        ExoticObject::Set { entries } => entries.contains(&JsMapKey(value.clone())),
+        _ => false,
+    }
+}
+
+/// Read `size`, `has` and `keys` from a set-like `other`, validating each
+fn get_set_record(
+    interp: &mut Interpreter,
+    other: &JsValue,
+    guard: &Guard<JsObject>,
+) -> Result<SetRecord, JsError> {
+    let JsValue::Object(object) = other else {
+        return Err(JsError::type_error(
+            "Set operation argument must be a set-like object",
+        ));
+    };
+
+    let get = |interp: &mut Interpreter, name: &str| -> Result<JsValue, JsError> {
+        let key = PropertyKey::String(interp.intern(name));
+        let Guarded { value, guard: _g } = super::proxy::proxy_get(
+            interp,
+            object.cheap_clone(),
+            key,
+            JsValue::Object(object.cheap_clone()),
+        )?;
+        if let JsValue::Object(obj) = &value {
+            guard.guard(obj.cheap_clone());
+        }
+        Ok(value)
+    };
+
+    let raw_size = get(interp, "size")?;
+    let size = interp.coerce_to_number(&raw_size)?;
+    if size.is_nan() {
+        return Err(JsError::type_error(
+            "The 'size' of a set-like must be a number",
+        ));
+    }
+    let size = math::trunc(size);
+    if size < 0.0 {
+        return Err(JsError::range_error(
+            "The 'size' of a set-like must not be negative",
+        ));
+    }
+
+    let has = get(interp, "has")?;
+    if !has.is_callable() {
+        return Err(JsError::type_error(
+            "The 'has' of a set-like must be a function",
+        ));
+    }
+    let keys = get(interp, "keys")?;
+    if !keys.is_callable() {
+        return Err(JsError::type_error(
+            "The 'keys' of a set-like must be a function",
+        ));
+    }
+
+    Ok(SetRecord {
+        object: object.cheap_clone(),
+        size,
+        has,
+        keys,
+    })
+}
+
+/// Call `other.has(value)` and coerce the result to a boolean
+fn set_record_has(
+    interp: &mut Interpreter,
+    record: &SetRecord,
+    value: &JsValue,
+) -> Result<bool, JsError> {
+    let result = interp.call_function(
+        record.has.clone(),
+        JsValue::Object(record.object.cheap_clone()),
+        core::slice::from_ref(value),
+    )?;
+    Ok(result.value.to_boolean())
+}
+
+/// Iterate `other.keys()`, passing each key (with -0 normalized to +0) to `f`.
+///
+/// Returns early with `false`, closing the iterator, as soon as `f` does;
+/// otherwise returns `true` once the iterator is exhausted. Object keys are
+/// rooted in `guard` so `f` can keep them.
+fn for_each_set_record_key(
+    interp: &mut Interpreter,
+    record: &SetRecord,
+    guard: &Guard<JsObject>,
+    mut f: impl FnMut(&mut Interpreter, JsValue) -> Result<bool, JsError>,
+) -> Result<bool, JsError> {
+    let Guarded {
+        value: iterator,
+        guard: _iterator_guard,
+    } = interp.call_function(
+        record.keys.clone(),
+        JsValue::Object(record.object.cheap_clone()),
+        &[],
+    )?;
+    let JsValue::Object(iterator) = iterator else {
+        return Err(JsError::type_error(
+            "keys() of a set-like must return an object",
+        ));
+    };
+
+    let next_key = PropertyKey::String(interp.intern("next"));
+    let Guarded {
+        value: next,
+        guard: _next_guard,
+    } = super::proxy::proxy_get(
+        interp,
+        iterator.cheap_clone(),
+        next_key,
+        JsValue::Object(iterator.cheap_clone()),
+    )?;
+    let done_key = PropertyKey::String(interp.intern("done"));
+    let value_key = PropertyKey::String(interp.intern("value"));
+
+    loop {
+        interp.check_budget()?;
+
+        let Guarded {
+            value: result,
+            guard: _result_guard,
+        } = interp.call_function(next.clone(), JsValue::Object(iterator.cheap_clone()), &[])?;
+        let JsValue::Object(result) = result else {
+            return Err(JsError::type_error("Iterator result is not an object"));
+        };
+        let receiver = JsValue::Object(result.cheap_clone());
+        let done = super::proxy::proxy_get(
+            interp,
+            result.cheap_clone(),
+            done_key.clone(),
+            receiver.clone(),
+        )?
+        .value
+        .to_boolean();
+        if done {
+            return Ok(true);
+        }
+
+        let Guarded { value, guard: _g } =
+            super::proxy::proxy_get(interp, result, value_key.clone(), receiver)?;
+        let value = JsMapKey::canonical(value).0;
+        if let JsValue::Object(obj) = &value {
+            guard.guard(obj.cheap_clone());
+        }
+        if !f(interp, value)? {
+            interp.close_iterator(&iterator);
+            return Ok(false);
+        }
+    }
+}
+
+/// Wrap computed entries in a new Set
+pub(crate) fn create_set_from(interp: &mut Interpreter, entries: IndexSet<JsMapKey>) -> Guarded {
+    interp.install_builtin("Set");
+    let size_key = PropertyKey::String(interp.intern("size"));
+    let guard = interp.heap.create_guard();
+    let set_obj = interp.create_object(&guard);
+    {
+        let mut obj = set_obj.borrow_mut();
+        let len = entries.len();
+        obj.exotic = ExoticObject::Set { entries };
+        obj.prototype = Some(interp.set_prototype.clone());
+        obj.set_property(size_key, JsValue::Number(len as f64));
+    }
+    Guarded::with_guard(JsValue::Object(set_obj), guard)
+}
+
+/// The elements of `this`, as the starting point of a new Set
+fn to_entries(elements: Vec<JsValue>) -> IndexSet<JsMapKey> {
+    let mut entries = index_set_new();
+    for value in elements {
+        entries.insert(JsMapKey(value));
+    }
+    entries
+}
+
+/// Set.prototype.union(other)
+pub fn set_union(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let guard = interp.heap.create_guard();
+    let elements = this_set_elements(&this, "union", &guard)?;
+    let other = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let record = get_set_record(interp, &other, &guard)?;
+
+    let mut result = to_entries(elements);
+    for_each_set_record_key(interp, &record, &guard, |_, key| {
+        result.insert(JsMapKey(key));
+        Ok(true)
+    })?;
+    Ok(create_set_from(interp, result))
+}
+
+/// Set.prototype.intersection(other)
+pub fn set_intersection(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let guard = interp.heap.create_guard();
+    let elements = this_set_elements(&this, "intersection", &guard)?;
+    let other = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let record = get_set_record(interp, &other, &guard)?;
+
+    let mut result = index_set_new();
+    if elements.len() as f64 <= record.size {
+        // Probe the other side once per element of the smaller `this`
+        for value in elements {
+            if set_record_has(interp, &record, &value)? {
+                result.insert(JsMapKey(value));
+            }
+        }
+    } else {
+        for_each_set_record_key(interp, &record, &guard, |_, key| {
+            if set_data_has(&this, &key) {
+                result.insert(JsMapKey(key));
+            }
+            Ok(true)
+        })?;
+    }
+    Ok(create_set_from(interp, result))
+}
+
+/// Set.prototype.difference(other)
+pub fn set_difference(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let guard = interp.heap.create_guard();
+    let elements = this_set_elements(&this, "difference", &guard)?;
+    let other = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let record = get_set_record(interp, &other, &guard)?;
+
+    let mut result = to_entries(elements.clone());
+    if elements.len() as f64 <= record.size {
+        for value in elements {
+            if set_record_has(interp, &record, &value)? {
+                result.shift_remove(&JsMapKey(value));
+            }
+        }
+    } else {
+        for_each_set_record_key(interp, &record, &guard, |_, key| {
+            result.shift_remove(&JsMapKey(key));
+            Ok(true)
+        })?;
+    }
+    Ok(create_set_from(interp, result))
+}
+
+/// Set.prototype.symmetricDifference(other)
+pub fn set_symmetric_difference(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let guard = interp.heap.create_guard();
+    let elements = this_set_elements(&this, "symmetricDifference", &guard)?;
+    let other = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let record = get_set_record(interp, &other, &guard)?;
+
+    let mut result = to_entries(elements);
+    for_each_set_record_key(interp, &record, &guard, |_, key| {
+        if set_data_has(&this, &key) {
+            result.shift_remove(&JsMapKey(key));
+        } else {
+            result.insert(JsMapKey(key));
+        }
+        Ok(true)
+    })?;
+    Ok(create_set_from(interp, result))
+}
+
+/// Set.prototype.isSubsetOf(other)
+pub fn set_is_subset_of(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let guard = interp.heap.create_guard();
+    let elements = this_set_elements(&this, "isSubsetOf", &guard)?;
+    let other = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let record = get_set_record(interp, &other, &guard)?;
+
+    if elements.len() as f64 > record.size {
+        return Ok(Guarded::unguarded(JsValue::Boolean(false)));
+    }
+    for value in elements {
+        if !set_record_has(interp, &record, &value)? {
+            return Ok(Guarded::unguarded(JsValue::Boolean(false)));
+        }
+    }
+    Ok(Guarded::unguarded(JsValue::Boolean(true)))
+}
+
+/// Set.prototype.isSupersetOf(other)
+pub fn set_is_superset_of(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let guard = interp.heap.create_guard();
+    let elements = this_set_elements(&this, "isSupersetOf", &guard)?;
+    let other = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let record = get_set_record(interp, &other, &guard)?;
+
+    if (elements.len() as f64) < record.size {
+        return Ok(Guarded::unguarded(JsValue::Boolean(false)));
+    }
+    let all_present = for_each_set_record_key(interp, &record, &guard, |_, key| {
+        Ok(set_data_has(&this, &key))
+    })?;
+    Ok(Guarded::unguarded(JsValue::Boolean(all_present)))
+}
+
+/// Set.prototype.isDisjointFrom(other)
+pub fn set_is_disjoint_from(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let guard = interp.heap.create_guard();
+    let elements = this_set_elements(&this, "isDisjointFrom", &guard)?;
+    let other = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let record = get_set_record(interp, &other, &guard)?;
+
+    if elements.len() as f64 <= record.size {
+        for value in elements {
+            if set_record_has(interp, &record, &value)? {
+                return Ok(Guarded::unguarded(JsValue::Boolean(false)));
+            }
+        }
+        return Ok(Guarded::unguarded(JsValue::Boolean(true)));
+    }
+    let disjoint = for_each_set_record_key(interp, &record, &guard, |_, key| {
+        Ok(!set_data_has(&this, &key))
+    })?;
+    Ok(Guarded::unguarded(JsValue::Boolean(disjoint)))
+}
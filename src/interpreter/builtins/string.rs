@@ -2,8 +2,11 @@
 
 use crate::error::JsError;
 use crate::interpreter::Interpreter;
+use crate::interpreter::collation::{self, CollatorOptions, Sensitivity};
 use crate::prelude::{String, ToString, Vec, format, math, vec};
-use crate::value::{CheapClone, Guarded, JsObjectRef, JsString, JsValue, PropertyKey};
+use crate::value::{
+    CheapClone, Guarded, JsObjectRef, JsString, JsValue, Property, PropertyKey, Utf16Collector,
+};
 
 /// Initialize String.prototype with all string methods.
 /// The prototype object must already exist in `interp.string_prototype`.
@@ -32,6 +35,8 @@ pub fn init_string_prototype(interp: &mut Interpreter) {
     // Case conversion
     interp.register_method(&proto, "toLowerCase", string_to_lower_case, 0);
     interp.register_method(&proto, "toUpperCase", string_to_upper_case, 0);
+    interp.register_method(&proto, "toLocaleLowerCase", string_to_lower_case, 0);
+    interp.register_method(&proto, "toLocaleUpperCase", string_to_upper_case, 0);
 
     // Whitespace handling
     interp.register_method(&proto, "trim", string_trim, 0);
@@ -47,6 +52,8 @@ pub fn init_string_prototype(interp: &mut Interpreter) {
     interp.register_method(&proto, "padEnd", string_pad_end, 2);
     interp.register_method(&proto, "concat", string_concat, 1);
     interp.register_method(&proto, "normalize", string_normalize, 1);
+    interp.register_method(&proto, "isWellFormed", string_is_well_formed, 0);
+    interp.register_method(&proto, "toWellFormed", string_to_well_formed, 0);
     interp.register_method(&proto, "match", string_match, 1);
     interp.register_method(&proto, "matchAll", string_match_all, 1);
 
@@ -97,9 +104,14 @@ pub fn string_constructor_fn(
             obj.borrow_mut().exotic = ExoticObject::StringObj(str_val.cheap_clone());
             // Also set the length property (String objects have a read-only length)
             let length_key = PropertyKey::String(interp.intern("length"));
-            obj.borrow_mut().set_property(
+            obj.borrow_mut().properties.insert(
                 length_key,
-                JsValue::Number(str_val.as_str().chars().count() as f64),
+                Property::with_attributes(
+                    JsValue::Number(str_val.char_count() as f64),
+                    false,
+                    false,
+                    false,
+                ),
             );
             return Ok(Guarded::unguarded(this));
         }
@@ -221,19 +233,16 @@ pub fn string_index_of(
         None => interp.intern(""),
     };
     let from_index = args.get(1).map(|v| v.to_number() as usize).unwrap_or(0);
+    let from_index = from_index.min(s.char_count());
 
-    if from_index >= s.len() {
-        return Ok(Guarded::unguarded(JsValue::Number(-1.0)));
-    }
-
-    // Use get() for safe slicing - from_index is validated above to be < len
-    match s
-        .as_str()
-        .get(from_index..)
+    let text = s.as_str();
+    let start = byte_offset(text, from_index);
+    match text
+        .get(start..)
         .and_then(|slice| slice.find(search.as_str()))
     {
         Some(pos) => Ok(Guarded::unguarded(JsValue::Number(
-            (from_index + pos) as f64,
+            char_offset(text, start + pos) as f64,
         ))),
         None => Ok(Guarded::unguarded(JsValue::Number(-1.0))),
     }
@@ -249,7 +258,7 @@ pub fn string_last_index_of(
         Some(v) => interp.to_js_string(v),
         None => interp.intern(""),
     };
-    let len = s.len();
+    let len = s.char_count();
 
     // Default from_index is length of string
     let from_index = if let Some(arg) = args.get(1) {
@@ -271,13 +280,15 @@ pub fn string_last_index_of(
     }
 
     // Search backwards from from_index
-    let search_end = (from_index + search.len()).min(len);
-    match s
-        .as_str()
+    let text = s.as_str();
+    let search_end = byte_offset(text, from_index.saturating_add(search.char_count()));
+    match text
         .get(..search_end)
         .and_then(|slice| slice.rfind(search.as_str()))
     {
-        Some(pos) => Ok(Guarded::unguarded(JsValue::Number(pos as f64))),
+        Some(pos) => Ok(Guarded::unguarded(JsValue::Number(
+            char_offset(text, pos) as f64
+        ))),
         None => Ok(Guarded::unguarded(JsValue::Number(-1.0))),
     }
 }
@@ -288,7 +299,7 @@ pub fn string_at(
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
     let s = interp.to_js_string(&this);
-    let len = s.len() as isize;
+    let len = s.char_count() as isize;
     let index = if let Some(v) = args.first() {
         interp.coerce_to_number(v)? as isize
     } else {
@@ -323,13 +334,13 @@ pub fn string_includes(
     };
     let from_index = args.get(1).map(|v| v.to_number() as usize).unwrap_or(0);
 
-    if from_index >= s.len() {
+    if from_index >= s.char_count() {
         return Ok(Guarded::unguarded(JsValue::Boolean(search.is_empty())));
     }
 
+    let text = s.as_str();
     Ok(Guarded::unguarded(JsValue::Boolean(
-        s.as_str()
-            .get(from_index..)
+        text.get(byte_offset(text, from_index)..)
             .map(|slice| slice.contains(search.as_str()))
             .unwrap_or(false),
     )))
@@ -347,13 +358,13 @@ pub fn string_starts_with(
     };
     let position = args.get(1).map(|v| v.to_number() as usize).unwrap_or(0);
 
-    if position >= s.len() {
+    if position >= s.char_count() {
         return Ok(Guarded::unguarded(JsValue::Boolean(search.is_empty())));
     }
 
+    let text = s.as_str();
     Ok(Guarded::unguarded(JsValue::Boolean(
-        s.as_str()
-            .get(position..)
+        text.get(byte_offset(text, position)..)
             .map(|slice| slice.starts_with(search.as_str()))
             .unwrap_or(false),
     )))
@@ -372,12 +383,11 @@ pub fn string_ends_with(
     let end_position = args
         .get(1)
         .map(|v| v.to_number() as usize)
-        .unwrap_or(s.len());
+        .unwrap_or(usize::MAX);
 
-    let end = end_position.min(s.len());
+    let text = s.as_str();
     Ok(Guarded::unguarded(JsValue::Boolean(
-        s.as_str()
-            .get(..end)
+        text.get(..byte_offset(text, end_position))
             .map(|slice| slice.ends_with(search.as_str()))
             .unwrap_or(false),
     )))
@@ -389,7 +399,7 @@ pub fn string_slice(
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
     let s = interp.to_js_string(&this);
-    let len = s.len() as i64;
+    let len = s.char_count() as i64;
 
     let start_arg = args.first().map(|v| v.to_number() as i64).unwrap_or(0);
     let end_arg = args.get(1).map(|v| v.to_number() as i64).unwrap_or(len);
@@ -426,7 +436,7 @@ pub fn string_substring(
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
     let s = interp.to_js_string(&this);
-    let len = s.len();
+    let len = s.char_count();
 
     let start = args
         .first()
@@ -507,6 +517,9 @@ pub fn string_substr(
     Ok(Guarded::unguarded(JsValue::String(JsString::from(result))))
 }
 
+/// String.prototype.toLowerCase() and toLocaleLowerCase()
+/// Full Unicode default case mapping, including SpecialCasing (`İ` → `i̇`,
+/// final sigma); the locale argument is ignored
 pub fn string_to_lower_case(
     interp: &mut Interpreter,
     this: JsValue,
@@ -518,6 +531,9 @@ pub fn string_to_lower_case(
     ))))
 }
 
+/// String.prototype.toUpperCase() and toLocaleUpperCase()
+/// Full Unicode default case mapping, including multi-character mappings
+/// such as `ß` → `SS`; the locale argument is ignored
 pub fn string_to_upper_case(
     interp: &mut Interpreter,
     this: JsValue,
@@ -664,9 +680,15 @@ pub fn string_repeat(
 ) -> Result<Guarded, JsError> {
     let s = interp.to_js_string(&this);
     let count = args.first().map(|v| v.to_number() as usize).unwrap_or(0);
-    Ok(Guarded::unguarded(JsValue::String(JsString::from(
-        s.as_str().repeat(count),
-    ))))
+    let total = s.len().saturating_mul(count);
+    interp.check_string_len(total)?;
+    let mut result = String::with_capacity(total);
+    // An empty string repeats to nothing however large the count
+    for _ in 0..if s.is_empty() { 0 } else { count } {
+        interp.check_budget()?;
+        result.push_str(s.as_str());
+    }
+    Ok(Guarded::unguarded(JsValue::String(JsString::from(result))))
 }
 
 pub fn string_replace(
@@ -861,11 +883,18 @@ pub fn string_pad_start(
     }
 
     let pad_len = target_length - current_len;
+    interp.check_string_len(s.len().saturating_add(pad_len))?;
+    // Counted in characters, like `current_len`, so a multi-byte filler is
+    // never cut inside a character
+    let filler_len = pad_string.char_count();
     let mut padding = String::new();
-    while padding.len() < pad_len {
+    let mut padded = 0;
+    while padded < pad_len {
+        interp.check_budget()?;
         padding.push_str(pad_string.as_str());
+        padded += filler_len;
     }
-    padding.truncate(pad_len);
+    padding.truncate(byte_offset(&padding, pad_len));
 
     Ok(Guarded::unguarded(JsValue::String(JsString::from(
         format!("{}{}", padding, s.as_str()),
@@ -890,11 +919,18 @@ pub fn string_pad_end(
     }
 
     let pad_len = target_length - current_len;
+    interp.check_string_len(s.len().saturating_add(pad_len))?;
+    // Counted in characters, like `current_len`, so a multi-byte filler is
+    // never cut inside a character
+    let filler_len = pad_string.char_count();
     let mut padding = String::new();
-    while padding.len() < pad_len {
+    let mut padded = 0;
+    while padded < pad_len {
+        interp.check_budget()?;
         padding.push_str(pad_string.as_str());
+        padded += filler_len;
     }
-    padding.truncate(pad_len);
+    padding.truncate(byte_offset(&padding, pad_len));
 
     Ok(Guarded::unguarded(JsValue::String(JsString::from(
         format!("{}{}", s.as_str(), padding),
@@ -908,7 +944,9 @@ pub fn string_concat(
 ) -> Result<Guarded, JsError> {
     let mut result = interp.to_js_string(&this).to_string();
     for arg in args {
-        result.push_str(interp.to_js_string(arg).as_ref());
+        let part = interp.to_js_string(arg);
+        interp.check_string_len(result.len() + part.len())?;
+        result.push_str(part.as_ref());
     }
     Ok(Guarded::unguarded(JsValue::String(JsString::from(result))))
 }
@@ -954,16 +992,11 @@ pub fn string_from_char_code(
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let chars: String = args
-        .iter()
-        .map(|v| {
-            // Apply ToUint16 conversion as per ECMAScript spec
-            let code = to_uint16(v.to_number());
-            // u16 is always a valid Unicode code unit (char is UTF-32)
-            char::from_u32(code as u32).unwrap_or('\u{FFFD}')
-        })
-        .collect();
-    Ok(Guarded::unguarded(JsValue::String(JsString::from(chars))))
+    // Apply ToUint16 conversion as per ECMAScript spec
+    let units: Vec<u16> = args.iter().map(|v| to_uint16(v.to_number())).collect();
+    Ok(Guarded::unguarded(JsValue::String(
+        JsString::from_utf16_lossy(&units),
+    )))
 }
 
 /// String.fromCodePoint(...codePoints)
@@ -973,7 +1006,7 @@ pub fn string_from_code_point(
     _this: JsValue,
     args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    let mut result = String::new();
+    let mut result = Utf16Collector::default();
     for arg in args {
         let code_point = arg.to_number();
 
@@ -989,18 +1022,12 @@ pub fn string_from_code_point(
             )));
         }
 
-        let code_point = code_point as u32;
-        match char::from_u32(code_point) {
-            Some(c) => result.push(c),
-            None => {
-                return Err(JsError::range_error(format!(
-                    "Invalid code point {}",
-                    code_point
-                )));
-            }
-        }
+        // Surrogate code points pair up with their neighbours
+        result.push_code_point(code_point as u32);
     }
-    Ok(Guarded::unguarded(JsValue::String(JsString::from(result))))
+    Ok(Guarded::unguarded(JsValue::String(JsString::from(
+        result.finish(),
+    ))))
 }
 
 /// String.prototype.codePointAt(index)
@@ -1273,6 +1300,28 @@ pub fn string_search(
     }
 }
 
+/// String.prototype.isWellFormed()
+/// Always true: strings never hold lone surrogates, see [`JsString`]
+pub fn string_is_well_formed(
+    interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    interp.to_js_string(&this);
+    Ok(Guarded::unguarded(JsValue::Boolean(true)))
+}
+
+/// String.prototype.toWellFormed()
+/// The string unchanged: lone surrogates were replaced when it was built
+pub fn string_to_well_formed(
+    interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let s = interp.to_js_string(&this);
+    Ok(Guarded::unguarded(JsValue::String(s)))
+}
+
 /// String.prototype.normalize(form?)
 /// Returns the Unicode Normalization Form of the string
 /// Forms: "NFC" (default), "NFD", "NFKC", "NFKD"
@@ -1457,8 +1506,10 @@ fn expand_replacement_pattern(
     result
 }
 
-/// String.prototype.localeCompare(compareString)
-/// Compares two strings in the current locale
+/// String.prototype.localeCompare(compareString, locales?, options?)
+/// Compares the strings without locale data, see [`crate::interpreter::collation`].
+/// Of the `Intl.Collator` options, `numeric` and `sensitivity` are supported;
+/// the locale is ignored.
 /// Returns: -1 if string comes before, 0 if equal, 1 if string comes after
 pub fn string_locale_compare(
     interp: &mut Interpreter,
@@ -1470,9 +1521,12 @@ pub fn string_locale_compare(
         Some(v) => interp.to_js_string(v),
         None => interp.intern(""),
     };
+    let options = match args.get(2) {
+        Some(JsValue::Object(options)) => collator_options(interp, options)?,
+        _ => CollatorOptions::default(),
+    };
 
-    // Simple lexicographic comparison (locale-insensitive for now)
-    let result = match s.as_str().cmp(compare_string.as_str()) {
+    let result = match collation::compare(s.as_str(), compare_string.as_str(), &options) {
         core::cmp::Ordering::Less => -1.0,
         core::cmp::Ordering::Equal => 0.0,
         core::cmp::Ordering::Greater => 1.0,
@@ -1480,3 +1534,44 @@ pub fn string_locale_compare(
 
     Ok(Guarded::unguarded(JsValue::Number(result)))
 }
+
+/// Read the `numeric` and `sensitivity` options of `localeCompare`
+fn collator_options(
+    interp: &mut Interpreter,
+    options: &JsObjectRef,
+) -> Result<CollatorOptions, JsError> {
+    let numeric_key = interp.property_key("numeric");
+    let sensitivity_key = interp.property_key("sensitivity");
+    let (numeric, sensitivity) = {
+        let options = options.borrow();
+        (
+            options.get_property(&numeric_key),
+            options.get_property(&sensitivity_key),
+        )
+    };
+
+    let mut collator = CollatorOptions {
+        numeric: numeric.is_some_and(|numeric| numeric.to_boolean()),
+        ..CollatorOptions::default()
+    };
+    if let Some(value) = sensitivity.filter(|value| !matches!(value, JsValue::Undefined)) {
+        let value = interp.to_js_string(&value);
+        collator.sensitivity = Sensitivity::parse(value.as_str()).ok_or_else(|| {
+            JsError::range_error(format!(
+                "Value {} out of range for Intl.Collator options property sensitivity",
+                value.as_str()
+            ))
+        })?;
+    }
+    Ok(collator)
+}
+
+/// Byte offset in `s` of character `index`, or `s.len()` past the end
+fn byte_offset(s: &str, index: usize) -> usize {
+    s.char_indices().nth(index).map_or(s.len(), |(pos, _)| pos)
+}
+
+/// Character index of byte offset `pos`, which must be on a char boundary
+fn char_offset(s: &str, pos: usize) -> usize {
+    s.get(..pos).map_or(0, |prefix| prefix.chars().count())
+}
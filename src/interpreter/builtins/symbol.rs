@@ -3,7 +3,7 @@
 use crate::error::JsError;
 use crate::interpreter::Interpreter;
 use crate::prelude::*;
-use crate::value::{CheapClone, Guarded, JsString, JsSymbol, JsValue, PropertyKey};
+use crate::value::{CheapClone, ExoticObject, Guarded, JsString, JsSymbol, JsValue, PropertyKey};
 
 /// Well-known symbol IDs (reserved during initialization)
 #[derive(Clone, Copy)]
@@ -20,6 +20,8 @@ pub struct WellKnownSymbols {
     pub search: u64,
     pub split: u64,
     pub async_iterator: u64,
+    pub dispose: u64,
+    pub async_dispose: u64,
 }
 
 impl Default for WellKnownSymbols {
@@ -51,6 +53,29 @@ impl WellKnownSymbols {
             search: alloc(),
             split: alloc(),
             async_iterator: alloc(),
+            dispose: alloc(),
+            async_dispose: alloc(),
+        }
+    }
+
+    /// The id reserved for a well-known symbol
+    pub fn id(&self, symbol: crate::WellKnownSymbol) -> u64 {
+        use crate::WellKnownSymbol;
+        match symbol {
+            WellKnownSymbol::Iterator => self.iterator,
+            WellKnownSymbol::AsyncIterator => self.async_iterator,
+            WellKnownSymbol::Dispose => self.dispose,
+            WellKnownSymbol::AsyncDispose => self.async_dispose,
+            WellKnownSymbol::HasInstance => self.has_instance,
+            WellKnownSymbol::IsConcatSpreadable => self.is_concat_spreadable,
+            WellKnownSymbol::Match => self.match_symbol,
+            WellKnownSymbol::Replace => self.replace,
+            WellKnownSymbol::Search => self.search,
+            WellKnownSymbol::Species => self.species,
+            WellKnownSymbol::Split => self.split,
+            WellKnownSymbol::ToPrimitive => self.to_primitive,
+            WellKnownSymbol::ToStringTag => self.to_string_tag,
+            WellKnownSymbol::Unscopables => self.unscopables,
         }
     }
 }
@@ -97,6 +122,8 @@ pub fn init_symbol(interp: &mut Interpreter) {
     let search_key = PropertyKey::String(interp.intern("search"));
     let split_key = PropertyKey::String(interp.intern("split"));
     let async_iterator_key = PropertyKey::String(interp.intern("asyncIterator"));
+    let dispose_key = PropertyKey::String(interp.intern("dispose"));
+    let async_dispose_key = PropertyKey::String(interp.intern("asyncDispose"));
 
     // Intern well-known symbol descriptions
     let sym_iterator = interp.intern("Symbol.iterator");
@@ -111,6 +138,8 @@ pub fn init_symbol(interp: &mut Interpreter) {
     let sym_search = interp.intern("Symbol.search");
     let sym_split = interp.intern("Symbol.split");
     let sym_async_iterator = interp.intern("Symbol.asyncIterator");
+    let sym_dispose = interp.intern("Symbol.dispose");
+    let sym_async_dispose = interp.intern("Symbol.asyncDispose");
 
     {
         let mut sym = symbol_fn.borrow_mut();
@@ -193,8 +222,28 @@ pub fn init_symbol(interp: &mut Interpreter) {
                 Some(sym_async_iterator),
             ))),
         );
+        sym.set_property(
+            dispose_key,
+            JsValue::Symbol(Box::new(JsSymbol::new(
+                well_known.dispose,
+                Some(sym_dispose),
+            ))),
+        );
+        sym.set_property(
+            async_dispose_key,
+            JsValue::Symbol(Box::new(JsSymbol::new(
+                well_known.async_dispose,
+                Some(sym_async_dispose),
+            ))),
+        );
     }
 
+    // Set Symbol.prototype, so wrapper objects and their methods are reachable
+    let proto_key = PropertyKey::String(interp.intern("prototype"));
+    symbol_fn
+        .borrow_mut()
+        .set_property(proto_key, JsValue::Object(interp.symbol_prototype.clone()));
+
     // Set Symbol.prototype.constructor = Symbol
     let constructor_key = PropertyKey::String(interp.intern("constructor"));
     interp
@@ -277,15 +326,15 @@ fn symbol_to_string(
     this: JsValue,
     _args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    match this {
-        JsValue::Symbol(s) => {
+    match this_symbol_value(&this) {
+        Some(s) => {
             let result = match &s.description {
                 Some(desc) => format!("Symbol({})", desc.as_str()),
                 None => "Symbol()".to_string(),
             };
             Ok(Guarded::unguarded(JsValue::String(JsString::from(result))))
         }
-        _ => Err(JsError::type_error(
+        None => Err(JsError::type_error(
             "Symbol.prototype.toString requires that 'this' be a Symbol",
         )),
     }
@@ -297,10 +346,22 @@ fn symbol_value_of(
     this: JsValue,
     _args: &[JsValue],
 ) -> Result<Guarded, JsError> {
-    match this {
-        JsValue::Symbol(_) => Ok(Guarded::unguarded(this)),
-        _ => Err(JsError::type_error(
+    match this_symbol_value(&this) {
+        Some(sym) => Ok(Guarded::unguarded(JsValue::Symbol(sym))),
+        None => Err(JsError::type_error(
             "Symbol.prototype.valueOf requires that 'this' be a Symbol",
         )),
     }
 }
+
+/// The symbol `this` is or wraps, as from `Object(symbol)`
+fn this_symbol_value(this: &JsValue) -> Option<Box<JsSymbol>> {
+    match this {
+        JsValue::Symbol(sym) => Some(sym.clone()),
+        JsValue::Object(obj) => match &obj.borrow().exotic {
+            ExoticObject::Symbol(sym) => Some(sym.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,1472 @@
+//! URL and URLSearchParams built-ins
+//!
+//! Implements the commonly used subset of the WHATWG URL Standard: special
+//! schemes (http, https, ws, wss, ftp, file) with an authority, opaque paths
+//! such as `mailto:`, relative resolution against a base URL, and
+//! `application/x-www-form-urlencoded` query strings. Hosts are not IDNA or
+//! IPv4 normalized beyond lowercasing.
+
+use crate::error::JsError;
+use crate::gc::{Gc, Guard};
+use crate::interpreter::Interpreter;
+use crate::interpreter::builtins::array::array_values;
+use crate::interpreter::builtins::proxy::proxy_get;
+use crate::prelude::{Box, Rc, RefCell, String, ToString, Vec, format, vec};
+use crate::value::{CheapClone, ExoticObject, Guarded, JsObject, JsString, JsValue, PropertyKey};
+
+/// Path of a URL record
+#[derive(Debug, Clone, PartialEq)]
+pub enum UrlPath {
+    /// Hierarchical path, serialized as `/` followed by the segments joined with `/`
+    Segments(Vec<String>),
+    /// Opaque path of a URL without authority, e.g. `user@example.com` in `mailto:user@example.com`
+    Opaque(String),
+}
+
+/// A parsed URL. All components are stored already percent-encoded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UrlRecord {
+    pub scheme: String,
+    pub username: String,
+    pub password: String,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub path: UrlPath,
+    pub query: Option<String>,
+    pub fragment: Option<String>,
+}
+
+/// Internal state of a URL object
+#[derive(Debug, Clone)]
+pub struct UrlData {
+    /// URL record, shared with the linked URLSearchParams object
+    pub record: Rc<RefCell<UrlRecord>>,
+    /// The object returned by `url.searchParams`
+    pub search_params: Gc<JsObject>,
+}
+
+/// Internal state of a URLSearchParams object
+#[derive(Debug, Clone, Default)]
+pub struct SearchParamsData {
+    /// Name/value pairs in insertion order
+    pub list: Vec<(String, String)>,
+    /// URL whose query is rewritten whenever the list changes
+    pub url: Option<Rc<RefCell<UrlRecord>>>,
+}
+
+impl SearchParamsData {
+    /// Write the serialized list back into the owning URL's query
+    fn update_url(&self) {
+        if let Some(url) = &self.url {
+            let serialized = serialize_form_urlencoded(&self.list);
+            url.borrow_mut().query = if serialized.is_empty() {
+                None
+            } else {
+                Some(serialized)
+            };
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Parsing and serialization
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn is_special_scheme(scheme: &str) -> bool {
+    matches!(scheme, "http" | "https" | "ws" | "wss" | "ftp" | "file")
+}
+
+fn default_port(scheme: &str) -> Option<u16> {
+    match scheme {
+        "http" | "ws" => Some(80),
+        "https" | "wss" => Some(443),
+        "ftp" => Some(21),
+        _ => None,
+    }
+}
+
+// Percent-encode sets from the URL Standard
+
+fn in_c0_control_set(b: u8) -> bool {
+    !(0x20..=0x7E).contains(&b)
+}
+
+fn in_fragment_set(b: u8) -> bool {
+    in_c0_control_set(b) || matches!(b, b' ' | b'"' | b'<' | b'>' | b'`')
+}
+
+fn in_query_set(b: u8) -> bool {
+    in_c0_control_set(b) || matches!(b, b' ' | b'"' | b'#' | b'<' | b'>')
+}
+
+fn in_special_query_set(b: u8) -> bool {
+    in_query_set(b) || b == b'\''
+}
+
+fn in_path_set(b: u8) -> bool {
+    in_query_set(b) || matches!(b, b'?' | b'`' | b'{' | b'}')
+}
+
+fn in_userinfo_set(b: u8) -> bool {
+    in_path_set(b) || matches!(b, b'/' | b':' | b';' | b'=' | b'@' | b'['..=b'^' | b'|')
+}
+
+fn push_percent_byte(out: &mut String, b: u8) {
+    out.push_str(&format!("%{:02X}", b));
+}
+
+fn percent_encode(input: &str, in_set: fn(u8) -> bool) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut buf = [0u8; 4];
+    for c in input.chars() {
+        if c.is_ascii() && !in_set(c as u8) {
+            out.push(c);
+        } else {
+            for b in c.encode_utf8(&mut buf).bytes() {
+                push_percent_byte(&mut out, b);
+            }
+        }
+    }
+    out
+}
+
+fn hex_value(b: &u8) -> Option<u8> {
+    (*b as char).to_digit(16).map(|d| d as u8)
+}
+
+/// Decode `%XX` escapes, leaving malformed escapes untouched
+fn percent_decode_bytes(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while let Some(&b) = input.get(i) {
+        if b == b'%'
+            && let Some(hi) = input.get(i + 1).and_then(hex_value)
+            && let Some(lo) = input.get(i + 2).and_then(hex_value)
+        {
+            out.push(hi * 16 + lo);
+            i += 3;
+            continue;
+        }
+        out.push(b);
+        i += 1;
+    }
+    out
+}
+
+/// Split `scheme:rest` if the input starts with a valid scheme
+fn split_scheme(input: &str) -> Option<(&str, &str)> {
+    let (scheme, rest) = input.split_once(':')?;
+    let mut chars = scheme.chars();
+    let first = chars.next()?;
+    let valid = first.is_ascii_alphabetic()
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    valid.then_some((scheme, rest))
+}
+
+/// Split the path, query and fragment parts of a URL tail
+fn split_components(input: &str) -> (&str, Option<&str>, Option<&str>) {
+    let (before_fragment, fragment) = match input.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment)),
+        None => (input, None),
+    };
+    match before_fragment.split_once('?') {
+        Some((path, query)) => (path, Some(query), fragment),
+        None => (before_fragment, None, fragment),
+    }
+}
+
+fn is_single_dot_segment(segment: &str) -> bool {
+    segment == "." || segment.eq_ignore_ascii_case("%2e")
+}
+
+fn is_double_dot_segment(segment: &str) -> bool {
+    matches!(
+        segment.to_ascii_lowercase().as_str(),
+        ".." | ".%2e" | "%2e." | "%2e%2e"
+    )
+}
+
+/// Append the segments of `path` to `segments`, resolving `.` and `..`
+fn parse_path_segments(path: &str, special: bool, mut segments: Vec<String>) -> Vec<String> {
+    if path.is_empty() {
+        if special && segments.is_empty() {
+            segments.push(String::new());
+        }
+        return segments;
+    }
+    let path = path
+        .strip_prefix(|c| c == '/' || (special && c == '\\'))
+        .unwrap_or(path);
+    let parts: Vec<&str> = if special {
+        path.split(['/', '\\']).collect()
+    } else {
+        path.split('/').collect()
+    };
+    let last = parts.len().saturating_sub(1);
+    for (i, part) in parts.iter().enumerate() {
+        let is_last = i == last;
+        if is_double_dot_segment(part) {
+            segments.pop();
+            if is_last {
+                segments.push(String::new());
+            }
+        } else if is_single_dot_segment(part) {
+            if is_last {
+                segments.push(String::new());
+            }
+        } else {
+            segments.push(percent_encode(part, in_path_set));
+        }
+    }
+    segments
+}
+
+/// Split `host[:port]`, keeping IPv6 literals in brackets intact
+fn split_host_port(input: &str) -> Option<(&str, Option<&str>)> {
+    if input.starts_with('[') {
+        let close = input.find(']')?;
+        let (host, after) = input.split_at_checked(close + 1)?;
+        if after.is_empty() {
+            return Some((host, None));
+        }
+        return after.strip_prefix(':').map(|port| (host, Some(port)));
+    }
+    Some(match input.split_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (input, None),
+    })
+}
+
+fn is_forbidden_host_char(c: char) -> bool {
+    matches!(
+        c,
+        '\0' | '\t'
+            | '\n'
+            | '\r'
+            | ' '
+            | '#'
+            | '/'
+            | ':'
+            | '<'
+            | '>'
+            | '?'
+            | '@'
+            | '['
+            | '\\'
+            | ']'
+            | '^'
+            | '|'
+    )
+}
+
+fn parse_host(input: &str, special: bool) -> Option<String> {
+    if let Some(inner) = input.strip_prefix('[') {
+        let inner = inner.strip_suffix(']')?;
+        let valid = !inner.is_empty()
+            && inner
+                .chars()
+                .all(|c| c.is_ascii_hexdigit() || c == ':' || c == '.');
+        return valid.then(|| format!("[{}]", inner.to_ascii_lowercase()));
+    }
+    if !special {
+        if input.chars().any(is_forbidden_host_char) {
+            return None;
+        }
+        return Some(percent_encode(input, in_c0_control_set));
+    }
+    let decoded = String::from_utf8(percent_decode_bytes(input.as_bytes())).ok()?;
+    let host = decoded.to_lowercase();
+    let forbidden = host
+        .chars()
+        .any(|c| is_forbidden_host_char(c) || c.is_ascii_control() || c == '%');
+    if host.is_empty() || forbidden {
+        return None;
+    }
+    Some(host)
+}
+
+/// Parse a port string. `None` means invalid; `Some(None)` means no (or the default) port.
+fn parse_port(port: Option<&str>, scheme: &str) -> Option<Option<u16>> {
+    let Some(port) = port.filter(|p| !p.is_empty()) else {
+        return Some(None);
+    };
+    if !port.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let value = port.parse::<u16>().ok()?;
+    Some(if default_port(scheme) == Some(value) {
+        None
+    } else {
+        Some(value)
+    })
+}
+
+/// Index of the end of the authority (or host) part of a URL tail
+fn authority_end(input: &str, special: bool) -> usize {
+    input
+        .find(|c| matches!(c, '/' | '?' | '#') || (special && c == '\\'))
+        .unwrap_or(input.len())
+}
+
+impl UrlRecord {
+    fn new(scheme: String) -> Self {
+        UrlRecord {
+            scheme,
+            username: String::new(),
+            password: String::new(),
+            host: None,
+            port: None,
+            path: UrlPath::Segments(Vec::new()),
+            query: None,
+            fragment: None,
+        }
+    }
+
+    /// Parse `input`, resolving it against `base` if it is relative.
+    /// Returns `None` for invalid URLs.
+    pub fn parse(input: &str, base: Option<&UrlRecord>) -> Option<UrlRecord> {
+        let cleaned: String = input
+            .trim_matches(|c: char| c <= ' ')
+            .chars()
+            .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+            .collect();
+
+        let Some((scheme, rest)) = split_scheme(&cleaned) else {
+            return Self::parse_relative(&cleaned, base?);
+        };
+        let scheme = scheme.to_ascii_lowercase();
+
+        if !is_special_scheme(&scheme) {
+            if let Some(after) = rest.strip_prefix("//") {
+                return Self::parse_authority(scheme, after);
+            }
+            let mut record = UrlRecord::new(scheme);
+            if rest.starts_with('/') {
+                record.apply_components(rest, Vec::new());
+            } else {
+                let (path, query, fragment) = split_components(rest);
+                record.path = UrlPath::Opaque(percent_encode(path, in_c0_control_set));
+                record.query = query.map(|q| percent_encode(q, in_query_set));
+                record.fragment = fragment.map(|f| percent_encode(f, in_fragment_set));
+            }
+            return Some(record);
+        }
+
+        // "http:path" with a same-scheme base is relative to that base
+        if let Some(base) = base
+            && base.scheme == scheme
+            && !rest.starts_with(['/', '\\'])
+        {
+            return Self::parse_relative(rest, base);
+        }
+
+        if scheme == "file" {
+            let mut record = UrlRecord::new(scheme);
+            let remainder = match rest
+                .strip_prefix("//")
+                .or_else(|| rest.strip_prefix("\\\\"))
+            {
+                Some(after) => {
+                    let (host, remainder) = after.split_at_checked(authority_end(after, true))?;
+                    let host = if host.is_empty() {
+                        String::new()
+                    } else {
+                        parse_host(host, true)?
+                    };
+                    record.host = Some(if host == "localhost" {
+                        String::new()
+                    } else {
+                        host
+                    });
+                    remainder
+                }
+                None => {
+                    record.host = Some(String::new());
+                    rest
+                }
+            };
+            record.apply_components(remainder, Vec::new());
+            return Some(record);
+        }
+
+        Self::parse_authority(scheme, rest.trim_start_matches(['/', '\\']))
+    }
+
+    /// Parse `[userinfo@]host[:port][path][?query][#fragment]`
+    fn parse_authority(scheme: String, input: &str) -> Option<UrlRecord> {
+        let special = is_special_scheme(&scheme);
+        let (authority, remainder) = input.split_at_checked(authority_end(input, special))?;
+        let mut record = UrlRecord::new(scheme);
+
+        let host_port = match authority.rsplit_once('@') {
+            Some((userinfo, host_port)) => {
+                let (username, password) = userinfo.split_once(':').unwrap_or((userinfo, ""));
+                record.username = percent_encode(username, in_userinfo_set);
+                record.password = percent_encode(password, in_userinfo_set);
+                host_port
+            }
+            None => authority,
+        };
+        let (host, port) = split_host_port(host_port)?;
+        record.host = Some(parse_host(host, special)?);
+        record.port = parse_port(port, &record.scheme)?;
+        record.apply_components(remainder, Vec::new());
+        Some(record)
+    }
+
+    /// Resolve a scheme-less input against `base`
+    fn parse_relative(input: &str, base: &UrlRecord) -> Option<UrlRecord> {
+        if matches!(base.path, UrlPath::Opaque(_)) {
+            let fragment = input.strip_prefix('#')?;
+            let mut record = base.clone();
+            record.fragment = Some(percent_encode(fragment, in_fragment_set));
+            return Some(record);
+        }
+
+        let special = base.is_special();
+        let is_slash = |c: Option<char>| c == Some('/') || (special && c == Some('\\'));
+        let mut chars = input.chars();
+        let first = chars.next();
+        let second = chars.next();
+
+        // Protocol-relative: "//host/path" takes only the scheme from the base
+        if is_slash(first) && is_slash(second) {
+            return Self::parse(&format!("{}:{}", base.scheme, input), None);
+        }
+
+        let mut record = UrlRecord {
+            fragment: None,
+            ..base.clone()
+        };
+        match first {
+            None => {}
+            Some('?') => {
+                let (_, query, fragment) = split_components(input);
+                record.query = query.map(|q| record.encode_query(q));
+                record.fragment = fragment.map(|f| percent_encode(f, in_fragment_set));
+            }
+            Some('#') => {
+                let fragment = input.get(1..).unwrap_or_default();
+                record.query = base.query.clone();
+                record.fragment = Some(percent_encode(fragment, in_fragment_set));
+            }
+            _ if is_slash(first) => record.apply_components(input, Vec::new()),
+            _ => {
+                let mut segments = match &base.path {
+                    UrlPath::Segments(segments) => segments.clone(),
+                    UrlPath::Opaque(_) => Vec::new(),
+                };
+                segments.pop();
+                record.apply_components(input, segments);
+            }
+        }
+        Some(record)
+    }
+
+    /// Set path, query and fragment from a URL tail, appending the path to `segments`
+    fn apply_components(&mut self, input: &str, segments: Vec<String>) {
+        let (path, query, fragment) = split_components(input);
+        self.path = UrlPath::Segments(parse_path_segments(path, self.is_special(), segments));
+        self.query = query.map(|q| self.encode_query(q));
+        self.fragment = fragment.map(|f| percent_encode(f, in_fragment_set));
+    }
+
+    fn encode_query(&self, query: &str) -> String {
+        if self.is_special() {
+            percent_encode(query, in_special_query_set)
+        } else {
+            percent_encode(query, in_query_set)
+        }
+    }
+
+    fn is_special(&self) -> bool {
+        is_special_scheme(&self.scheme)
+    }
+
+    /// Whether username, password and port may be set
+    fn can_have_credentials(&self) -> bool {
+        self.host.as_deref().is_some_and(|h| !h.is_empty()) && self.scheme != "file"
+    }
+
+    pub fn pathname(&self) -> String {
+        match &self.path {
+            UrlPath::Opaque(path) => path.clone(),
+            UrlPath::Segments(segments) => segments.iter().map(|s| format!("/{}", s)).collect(),
+        }
+    }
+
+    pub fn host_with_port(&self) -> String {
+        match (&self.host, self.port) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.clone(),
+            (None, _) => String::new(),
+        }
+    }
+
+    pub fn origin(&self) -> String {
+        match self.scheme.as_str() {
+            "http" | "https" | "ws" | "wss" | "ftp" => {
+                format!("{}://{}", self.scheme, self.host_with_port())
+            }
+            _ => "null".to_string(),
+        }
+    }
+
+    pub fn href(&self) -> String {
+        let mut out = format!("{}:", self.scheme);
+        if let Some(host) = &self.host {
+            out.push_str("//");
+            if !self.username.is_empty() || !self.password.is_empty() {
+                out.push_str(&self.username);
+                if !self.password.is_empty() {
+                    out.push(':');
+                    out.push_str(&self.password);
+                }
+                out.push('@');
+            }
+            out.push_str(host);
+            if let Some(port) = self.port {
+                out.push_str(&format!(":{}", port));
+            }
+        } else if let UrlPath::Segments(segments) = &self.path
+            && segments.len() > 1
+            && segments.first().is_some_and(|s| s.is_empty())
+        {
+            // Keep "foo:/.//bar" from being read back as an authority
+            out.push_str("/.");
+        }
+        out.push_str(&self.pathname());
+        if let Some(query) = &self.query {
+            out.push('?');
+            out.push_str(query);
+        }
+        if let Some(fragment) = &self.fragment {
+            out.push('#');
+            out.push_str(fragment);
+        }
+        out
+    }
+}
+
+/// Parse an `application/x-www-form-urlencoded` string into name/value pairs
+pub fn parse_form_urlencoded(input: &str) -> Vec<(String, String)> {
+    input
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode_form_component(name), decode_form_component(value))
+        })
+        .collect()
+}
+
+fn decode_form_component(component: &str) -> String {
+    let bytes: Vec<u8> = component
+        .bytes()
+        .map(|b| if b == b'+' { b' ' } else { b })
+        .collect();
+    String::from_utf8_lossy(&percent_decode_bytes(&bytes)).into_owned()
+}
+
+/// Serialize name/value pairs as `application/x-www-form-urlencoded`
+pub fn serialize_form_urlencoded(list: &[(String, String)]) -> String {
+    let mut out = String::new();
+    for (i, (name, value)) in list.iter().enumerate() {
+        if i > 0 {
+            out.push('&');
+        }
+        encode_form_component(&mut out, name);
+        out.push('=');
+        encode_form_component(&mut out, value);
+    }
+    out
+}
+
+fn encode_form_component(out: &mut String, component: &str) {
+    for b in component.bytes() {
+        match b {
+            b' ' => out.push('+'),
+            b'*' | b'-' | b'.' | b'_' => out.push(b as char),
+            _ if b.is_ascii_alphanumeric() => out.push(b as char),
+            _ => push_percent_byte(out, b),
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Initialization
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Link a constructor with its prototype and register it globally
+fn install_constructor(
+    interp: &mut Interpreter,
+    name: &str,
+    constructor: Gc<JsObject>,
+    proto: Gc<JsObject>,
+) {
+    let proto_key = PropertyKey::String(interp.intern("prototype"));
+    constructor
+        .borrow_mut()
+        .set_property(proto_key, JsValue::Object(proto.clone()));
+    let constructor_key = PropertyKey::String(interp.intern("constructor"));
+    proto
+        .borrow_mut()
+        .set_property(constructor_key, JsValue::Object(constructor.clone()));
+    let key = PropertyKey::String(interp.intern(name));
+    interp
+        .global
+        .borrow_mut()
+        .set_property(key, JsValue::Object(constructor));
+}
+
+/// Create URL and URLSearchParams constructors and register them globally
+pub fn init_url(interp: &mut Interpreter) {
+    let proto = interp.url_prototype.clone();
+    interp.register_accessor(&proto, "href", url_get_href, Some(url_set_href));
+    interp.register_accessor(&proto, "origin", url_get_origin, None);
+    interp.register_accessor(&proto, "protocol", url_get_protocol, Some(url_set_protocol));
+    interp.register_accessor(&proto, "username", url_get_username, Some(url_set_username));
+    interp.register_accessor(&proto, "password", url_get_password, Some(url_set_password));
+    interp.register_accessor(&proto, "host", url_get_host, Some(url_set_host));
+    interp.register_accessor(&proto, "hostname", url_get_hostname, Some(url_set_hostname));
+    interp.register_accessor(&proto, "port", url_get_port, Some(url_set_port));
+    interp.register_accessor(&proto, "pathname", url_get_pathname, Some(url_set_pathname));
+    interp.register_accessor(&proto, "search", url_get_search, Some(url_set_search));
+    interp.register_accessor(&proto, "searchParams", url_get_search_params, None);
+    interp.register_accessor(&proto, "hash", url_get_hash, Some(url_set_hash));
+    interp.register_method(&proto, "toString", url_get_href, 0);
+    interp.register_method(&proto, "toJSON", url_get_href, 0);
+
+    let url_constructor_fn = interp.create_native_function("URL", url_constructor, 1);
+    interp.root_guard.guard(url_constructor_fn.clone());
+    interp.register_method(&url_constructor_fn, "canParse", url_can_parse, 1);
+    install_constructor(interp, "URL", url_constructor_fn, proto);
+
+    let proto = interp.url_search_params_prototype.clone();
+    interp.register_method(&proto, "append", search_params_append, 2);
+    interp.register_method(&proto, "delete", search_params_delete, 1);
+    interp.register_method(&proto, "get", search_params_get, 1);
+    interp.register_method(&proto, "getAll", search_params_get_all, 1);
+    interp.register_method(&proto, "has", search_params_has, 1);
+    interp.register_method(&proto, "set", search_params_set, 2);
+    interp.register_method(&proto, "sort", search_params_sort, 0);
+    interp.register_method(&proto, "forEach", search_params_for_each, 1);
+    interp.register_method(&proto, "keys", search_params_keys, 0);
+    interp.register_method(&proto, "values", search_params_values, 0);
+    interp.register_method(&proto, "entries", search_params_entries, 0);
+    interp.register_method(&proto, "toString", search_params_to_string, 0);
+    interp.register_accessor(&proto, "size", search_params_size, None);
+
+    // URLSearchParams.prototype[Symbol.iterator] = entries
+    let well_known = interp.well_known_symbols;
+    let iterator_symbol =
+        crate::value::JsSymbol::new(well_known.iterator, Some(interp.intern("Symbol.iterator")));
+    let iterator_key = PropertyKey::Symbol(Box::new(iterator_symbol));
+    let entries_fn = interp.create_native_function("[Symbol.iterator]", search_params_entries, 0);
+    proto
+        .borrow_mut()
+        .set_property(iterator_key, JsValue::Object(entries_fn));
+
+    let params_constructor_fn =
+        interp.create_native_function("URLSearchParams", search_params_constructor, 0);
+    interp.root_guard.guard(params_constructor_fn.clone());
+    install_constructor(interp, "URLSearchParams", params_constructor_fn, proto);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// URL
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn string_value(s: String) -> Result<Guarded, JsError> {
+    Ok(Guarded::unguarded(JsValue::String(JsString::from(s))))
+}
+
+/// Convert the first argument to a string (setters and single-argument methods)
+fn first_arg_string(interp: &mut Interpreter, args: &[JsValue]) -> Result<String, JsError> {
+    let value = args.first().cloned().unwrap_or(JsValue::Undefined);
+    Ok(interp.coerce_to_string(&value)?.to_string())
+}
+
+fn this_url(this: &JsValue, name: &str) -> Result<UrlData, JsError> {
+    if let JsValue::Object(obj) = this
+        && let ExoticObject::Url(data) = &obj.borrow().exotic
+    {
+        return Ok((**data).clone());
+    }
+    Err(JsError::type_error(format!(
+        "URL.prototype.{} called on incompatible receiver",
+        name
+    )))
+}
+
+fn create_search_params_object(
+    interp: &mut Interpreter,
+    guard: &Guard<JsObject>,
+    data: SearchParamsData,
+) -> Gc<JsObject> {
+    let obj = interp.create_object(guard);
+    {
+        let mut obj_ref = obj.borrow_mut();
+        obj_ref.prototype = Some(interp.url_search_params_prototype.cheap_clone());
+        obj_ref.exotic = ExoticObject::UrlSearchParams(Rc::new(RefCell::new(data)));
+    }
+    obj
+}
+
+/// Reparse the linked URLSearchParams list after the URL's query changed
+fn sync_search_params(data: &UrlData) {
+    let query = data.record.borrow().query.clone().unwrap_or_default();
+    if let ExoticObject::UrlSearchParams(params) = &data.search_params.borrow().exotic {
+        params.borrow_mut().list = parse_form_urlencoded(&query);
+    }
+}
+
+/// Parse the optional base argument of `new URL(url, base)` and `URL.canParse`
+fn parse_base_arg(
+    interp: &mut Interpreter,
+    args: &[JsValue],
+) -> Result<Option<Result<UrlRecord, String>>, JsError> {
+    match args.get(1) {
+        None | Some(JsValue::Undefined) => Ok(None),
+        Some(value) => {
+            let base = interp.coerce_to_string(value)?.to_string();
+            Ok(Some(UrlRecord::parse(&base, None).ok_or(base)))
+        }
+    }
+}
+
+/// new URL(url, base?)
+pub fn url_constructor(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let JsValue::Object(url_obj) = this else {
+        return Err(JsError::type_error("Constructor URL requires 'new'"));
+    };
+    let input = first_arg_string(interp, args)?;
+    let base = match parse_base_arg(interp, args)? {
+        Some(Ok(base)) => Some(base),
+        Some(Err(base)) => {
+            return Err(JsError::type_error(format!("Invalid base URL: {}", base)));
+        }
+        None => None,
+    };
+    let record = UrlRecord::parse(&input, base.as_ref())
+        .ok_or_else(|| JsError::type_error(format!("Invalid URL: {}", input)))?;
+
+    let list = parse_form_urlencoded(record.query.as_deref().unwrap_or_default());
+    let record = Rc::new(RefCell::new(record));
+
+    let guard = interp.heap.create_guard();
+    guard.guard(url_obj.cheap_clone());
+    let search_params = create_search_params_object(
+        interp,
+        &guard,
+        SearchParamsData {
+            list,
+            url: Some(record.clone()),
+        },
+    );
+    url_obj.borrow_mut().exotic = ExoticObject::Url(Box::new(UrlData {
+        record,
+        search_params,
+    }));
+    Ok(Guarded::with_guard(JsValue::Object(url_obj), guard))
+}
+
+/// URL.canParse(url, base?)
+pub fn url_can_parse(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let input = first_arg_string(interp, args)?;
+    let parsed = match parse_base_arg(interp, args)? {
+        Some(Ok(base)) => UrlRecord::parse(&input, Some(&base)).is_some(),
+        Some(Err(_)) => false,
+        None => UrlRecord::parse(&input, None).is_some(),
+    };
+    Ok(Guarded::unguarded(JsValue::Boolean(parsed)))
+}
+
+pub fn url_get_href(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    string_value(this_url(&this, "href")?.record.borrow().href())
+}
+
+pub fn url_set_href(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_url(&this, "href")?;
+    let input = first_arg_string(interp, args)?;
+    let record = UrlRecord::parse(&input, None)
+        .ok_or_else(|| JsError::type_error(format!("Invalid URL: {}", input)))?;
+    *data.record.borrow_mut() = record;
+    sync_search_params(&data);
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+pub fn url_get_origin(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    string_value(this_url(&this, "origin")?.record.borrow().origin())
+}
+
+pub fn url_get_protocol(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    string_value(format!(
+        "{}:",
+        this_url(&this, "protocol")?.record.borrow().scheme
+    ))
+}
+
+pub fn url_set_protocol(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_url(&this, "protocol")?;
+    let input = first_arg_string(interp, args)?;
+    let scheme = input.split(':').next().unwrap_or_default();
+    if split_scheme(&format!("{}:", scheme)).is_some() {
+        let scheme = scheme.to_ascii_lowercase();
+        let mut record = data.record.borrow_mut();
+        // Switching between special and non-special schemes is not allowed
+        if is_special_scheme(&scheme) == record.is_special()
+            && (scheme != "file" || (!record.can_have_credentials() && record.port.is_none()))
+        {
+            if record.port == default_port(&scheme) {
+                record.port = None;
+            }
+            record.scheme = scheme;
+        }
+    }
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+pub fn url_get_username(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    string_value(
+        this_url(&this, "username")?
+            .record
+            .borrow()
+            .username
+            .clone(),
+    )
+}
+
+pub fn url_set_username(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_url(&this, "username")?;
+    let input = first_arg_string(interp, args)?;
+    let mut record = data.record.borrow_mut();
+    if record.can_have_credentials() {
+        record.username = percent_encode(&input, in_userinfo_set);
+    }
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+pub fn url_get_password(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    string_value(
+        this_url(&this, "password")?
+            .record
+            .borrow()
+            .password
+            .clone(),
+    )
+}
+
+pub fn url_set_password(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_url(&this, "password")?;
+    let input = first_arg_string(interp, args)?;
+    let mut record = data.record.borrow_mut();
+    if record.can_have_credentials() {
+        record.password = percent_encode(&input, in_userinfo_set);
+    }
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+pub fn url_get_host(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    string_value(this_url(&this, "host")?.record.borrow().host_with_port())
+}
+
+/// Shared implementation of the host and hostname setters
+fn set_host(record: &mut UrlRecord, input: &str, with_port: bool) {
+    if matches!(record.path, UrlPath::Opaque(_)) {
+        return;
+    }
+    let special = record.is_special();
+    let input = input.get(..authority_end(input, special)).unwrap_or(input);
+    let Some((host, port)) = split_host_port(input) else {
+        return;
+    };
+    if host.is_empty() && special && record.scheme != "file" {
+        return;
+    }
+    let Some(host) = parse_host(host, special).or_else(|| host.is_empty().then(String::new)) else {
+        return;
+    };
+    record.host = Some(host);
+    if with_port
+        && port.is_some()
+        && let Some(port) = parse_port(port, &record.scheme)
+    {
+        record.port = port;
+    }
+}
+
+pub fn url_set_host(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_url(&this, "host")?;
+    let input = first_arg_string(interp, args)?;
+    set_host(&mut data.record.borrow_mut(), &input, true);
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+pub fn url_get_hostname(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_url(&this, "hostname")?;
+    let host = data.record.borrow().host.clone().unwrap_or_default();
+    string_value(host)
+}
+
+pub fn url_set_hostname(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_url(&this, "hostname")?;
+    let input = first_arg_string(interp, args)?;
+    set_host(&mut data.record.borrow_mut(), &input, false);
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+pub fn url_get_port(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let port = this_url(&this, "port")?.record.borrow().port;
+    string_value(port.map(|p| p.to_string()).unwrap_or_default())
+}
+
+pub fn url_set_port(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_url(&this, "port")?;
+    let input = first_arg_string(interp, args)?;
+    let mut record = data.record.borrow_mut();
+    if !record.can_have_credentials() {
+        return Ok(Guarded::unguarded(JsValue::Undefined));
+    }
+    if input.is_empty() {
+        record.port = None;
+    } else {
+        // Leading digits are used; anything after them is ignored
+        let digits: String = input.chars().take_while(|c| c.is_ascii_digit()).collect();
+        if !digits.is_empty()
+            && let Some(port) = parse_port(Some(&digits), &record.scheme)
+        {
+            record.port = port;
+        }
+    }
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+pub fn url_get_pathname(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    string_value(this_url(&this, "pathname")?.record.borrow().pathname())
+}
+
+pub fn url_set_pathname(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_url(&this, "pathname")?;
+    let input = first_arg_string(interp, args)?;
+    let mut record = data.record.borrow_mut();
+    if !matches!(record.path, UrlPath::Opaque(_)) {
+        let special = record.is_special();
+        record.path = UrlPath::Segments(parse_path_segments(&input, special, Vec::new()));
+    }
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+pub fn url_get_search(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_url(&this, "search")?;
+    let search = match &data.record.borrow().query {
+        Some(query) if !query.is_empty() => format!("?{}", query),
+        _ => String::new(),
+    };
+    string_value(search)
+}
+
+pub fn url_set_search(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_url(&this, "search")?;
+    let input = first_arg_string(interp, args)?;
+    let input = input.strip_prefix('?').unwrap_or(&input);
+    {
+        let mut record = data.record.borrow_mut();
+        record.query = if input.is_empty() {
+            None
+        } else {
+            Some(record.encode_query(input))
+        };
+    }
+    sync_search_params(&data);
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+pub fn url_get_search_params(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_url(&this, "searchParams")?;
+    Ok(Guarded::unguarded(JsValue::Object(data.search_params)))
+}
+
+pub fn url_get_hash(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_url(&this, "hash")?;
+    let hash = match &data.record.borrow().fragment {
+        Some(fragment) if !fragment.is_empty() => format!("#{}", fragment),
+        _ => String::new(),
+    };
+    string_value(hash)
+}
+
+pub fn url_set_hash(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_url(&this, "hash")?;
+    let input = first_arg_string(interp, args)?;
+    let input = input.strip_prefix('#').unwrap_or(&input);
+    data.record.borrow_mut().fragment = if input.is_empty() {
+        None
+    } else {
+        Some(percent_encode(input, in_fragment_set))
+    };
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// URLSearchParams
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn this_search_params(
+    this: &JsValue,
+    name: &str,
+) -> Result<Rc<RefCell<SearchParamsData>>, JsError> {
+    if let JsValue::Object(obj) = this
+        && let ExoticObject::UrlSearchParams(data) = &obj.borrow().exotic
+    {
+        return Ok(data.clone());
+    }
+    Err(JsError::type_error(format!(
+        "URLSearchParams.prototype.{} called on incompatible receiver",
+        name
+    )))
+}
+
+/// Convert a required argument to a string
+fn required_string_arg(
+    interp: &mut Interpreter,
+    args: &[JsValue],
+    index: usize,
+    name: &str,
+) -> Result<String, JsError> {
+    let value = args.get(index).ok_or_else(|| {
+        JsError::type_error(format!(
+            "URLSearchParams.prototype.{} requires at least {} argument(s)",
+            name,
+            index + 1
+        ))
+    })?;
+    Ok(interp.coerce_to_string(value)?.to_string())
+}
+
+/// Convert an optional argument to a string, treating undefined as absent
+fn optional_string_arg(
+    interp: &mut Interpreter,
+    args: &[JsValue],
+    index: usize,
+) -> Result<Option<String>, JsError> {
+    match args.get(index) {
+        None | Some(JsValue::Undefined) => Ok(None),
+        Some(value) => Ok(Some(interp.coerce_to_string(value)?.to_string())),
+    }
+}
+
+/// Build the pair list for `new URLSearchParams(object)`: a URLSearchParams,
+/// an iterable of `[name, value]` pairs, or a record of own enumerable properties
+fn search_params_list_from_object(
+    interp: &mut Interpreter,
+    init: &Gc<JsObject>,
+) -> Result<Vec<(String, String)>, JsError> {
+    if let ExoticObject::UrlSearchParams(data) = &init.borrow().exotic {
+        return Ok(data.borrow().list.clone());
+    }
+
+    let guard = interp.heap.create_guard();
+    guard.guard(init.cheap_clone());
+    let init_value = JsValue::Object(init.cheap_clone());
+
+    if let Some(items) = interp.collect_iterator_values(&init_value)? {
+        for item in &items {
+            item.guard_by(&guard);
+        }
+        let mut list = Vec::with_capacity(items.len());
+        for item in &items {
+            let pair = match item {
+                JsValue::Object(_) => interp.collect_iterator_values(item)?,
+                _ => None,
+            }
+            .ok_or_else(|| {
+                JsError::type_error("URLSearchParams init sequence items must be iterable")
+            })?;
+            let [name, value] = pair.as_slice() else {
+                return Err(JsError::type_error(
+                    "URLSearchParams init sequence items must contain exactly two elements",
+                ));
+            };
+            name.guard_by(&guard);
+            value.guard_by(&guard);
+            let name = interp.coerce_to_string(name)?.to_string();
+            let value = interp.coerce_to_string(value)?.to_string();
+            list.push((name, value));
+        }
+        return Ok(list);
+    }
+
+    let keys = init.borrow().ordered_own_keys(true);
+    let mut list = Vec::with_capacity(keys.len());
+    for key in keys {
+        if matches!(key, PropertyKey::Symbol(_)) {
+            continue;
+        }
+        let name = key.to_string();
+        let Guarded {
+            value,
+            guard: _value_guard,
+        } = proxy_get(interp, init.cheap_clone(), key, init_value.clone())?;
+        let value = interp.coerce_to_string(&value)?.to_string();
+        list.push((name, value));
+    }
+    Ok(list)
+}
+
+/// new URLSearchParams(init?)
+pub fn search_params_constructor(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let JsValue::Object(params_obj) = this else {
+        return Err(JsError::type_error(
+            "Constructor URLSearchParams requires 'new'",
+        ));
+    };
+    let list = match args.first() {
+        None | Some(JsValue::Undefined) => Vec::new(),
+        Some(JsValue::Object(init)) => search_params_list_from_object(interp, init)?,
+        Some(value) => {
+            let init = interp.coerce_to_string(value)?;
+            let init = init.as_str();
+            parse_form_urlencoded(init.strip_prefix('?').unwrap_or(init))
+        }
+    };
+    params_obj.borrow_mut().exotic =
+        ExoticObject::UrlSearchParams(Rc::new(RefCell::new(SearchParamsData { list, url: None })));
+    Ok(Guarded::unguarded(JsValue::Object(params_obj)))
+}
+
+pub fn search_params_append(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_search_params(&this, "append")?;
+    let name = required_string_arg(interp, args, 0, "append")?;
+    let value = required_string_arg(interp, args, 1, "append")?;
+    let mut data = data.borrow_mut();
+    data.list.push((name, value));
+    data.update_url();
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+pub fn search_params_delete(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_search_params(&this, "delete")?;
+    let name = required_string_arg(interp, args, 0, "delete")?;
+    let value = optional_string_arg(interp, args, 1)?;
+    let mut data = data.borrow_mut();
+    data.list
+        .retain(|(n, v)| *n != name || value.as_ref().is_some_and(|value| v != value));
+    data.update_url();
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+pub fn search_params_get(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_search_params(&this, "get")?;
+    let name = required_string_arg(interp, args, 0, "get")?;
+    let value = data
+        .borrow()
+        .list
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, v)| JsValue::String(JsString::from(v.as_str())))
+        .unwrap_or(JsValue::Null);
+    Ok(Guarded::unguarded(value))
+}
+
+pub fn search_params_get_all(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_search_params(&this, "getAll")?;
+    let name = required_string_arg(interp, args, 0, "getAll")?;
+    let values: Vec<JsValue> = data
+        .borrow()
+        .list
+        .iter()
+        .filter(|(n, _)| *n == name)
+        .map(|(_, v)| JsValue::String(JsString::from(v.as_str())))
+        .collect();
+    let guard = interp.heap.create_guard();
+    let arr = interp.create_array_from(&guard, values);
+    Ok(Guarded::with_guard(JsValue::Object(arr), guard))
+}
+
+pub fn search_params_has(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_search_params(&this, "has")?;
+    let name = required_string_arg(interp, args, 0, "has")?;
+    let value = optional_string_arg(interp, args, 1)?;
+    let found = data
+        .borrow()
+        .list
+        .iter()
+        .any(|(n, v)| *n == name && value.as_ref().is_none_or(|value| v == value));
+    Ok(Guarded::unguarded(JsValue::Boolean(found)))
+}
+
+pub fn search_params_set(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_search_params(&this, "set")?;
+    let name = required_string_arg(interp, args, 0, "set")?;
+    let value = required_string_arg(interp, args, 1, "set")?;
+    let mut data = data.borrow_mut();
+    // Replace the first pair with this name and drop the rest
+    let mut found = false;
+    data.list.retain_mut(|(n, v)| {
+        if *n != name {
+            return true;
+        }
+        if found {
+            return false;
+        }
+        found = true;
+        v.clone_from(&value);
+        true
+    });
+    if !found {
+        data.list.push((name, value));
+    }
+    data.update_url();
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+pub fn search_params_sort(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_search_params(&this, "sort")?;
+    let mut data = data.borrow_mut();
+    // Stable sort by UTF-16 code units of the names
+    data.list
+        .sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+    data.update_url();
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+pub fn search_params_for_each(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_search_params(&this, "forEach")?;
+    let callback = args.first().cloned().unwrap_or(JsValue::Undefined);
+    if !callback.is_callable() {
+        return Err(JsError::type_error(
+            "URLSearchParams.prototype.forEach callback is not a function",
+        ));
+    }
+    let this_arg = args.get(1).cloned().unwrap_or(JsValue::Undefined);
+    let _this_arg_guard = interp.guard_value(&this_arg);
+
+    // Re-read the list on every step so callbacks observe their own mutations
+    let mut index = 0;
+    loop {
+        let entry = data.borrow().list.get(index).cloned();
+        let Some((name, value)) = entry else {
+            break;
+        };
+        interp.call_function(
+            callback.clone(),
+            this_arg.clone(),
+            &[
+                JsValue::String(JsString::from(value)),
+                JsValue::String(JsString::from(name)),
+                this.clone(),
+            ],
+        )?;
+        index += 1;
+    }
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+/// Which part of each pair an iterator yields
+#[derive(Clone, Copy)]
+enum SearchParamsIterKind {
+    Keys,
+    Values,
+    Entries,
+}
+
+fn search_params_iterator(
+    interp: &mut Interpreter,
+    this: &JsValue,
+    name: &str,
+    kind: SearchParamsIterKind,
+) -> Result<Guarded, JsError> {
+    let data = this_search_params(this, name)?;
+    let list = data.borrow().list.clone();
+    let guard = interp.heap.create_guard();
+    let mut items = Vec::with_capacity(list.len());
+    for (name, value) in list {
+        let name = JsValue::String(JsString::from(name));
+        let value = JsValue::String(JsString::from(value));
+        items.push(match kind {
+            SearchParamsIterKind::Keys => name,
+            SearchParamsIterKind::Values => value,
+            SearchParamsIterKind::Entries => {
+                JsValue::Object(interp.create_array_from(&guard, vec![name, value]))
+            }
+        });
+    }
+    let arr = interp.create_array_from(&guard, items);
+    array_values(interp, JsValue::Object(arr), &[])
+}
+
+pub fn search_params_keys(
+    interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    search_params_iterator(interp, &this, "keys", SearchParamsIterKind::Keys)
+}
+
+pub fn search_params_values(
+    interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    search_params_iterator(interp, &this, "values", SearchParamsIterKind::Values)
+}
+
+pub fn search_params_entries(
+    interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    search_params_iterator(interp, &this, "entries", SearchParamsIterKind::Entries)
+}
+
+pub fn search_params_to_string(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_search_params(&this, "toString")?;
+    string_value(serialize_form_urlencoded(&data.borrow().list))
+}
+
+pub fn search_params_size(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_search_params(&this, "size")?;
+    let len = data.borrow().list.len();
+    Ok(Guarded::unguarded(JsValue::Number(len as f64)))
+}
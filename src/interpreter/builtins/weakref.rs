@@ -0,0 +1,313 @@
+//! WeakRef and FinalizationRegistry built-ins
+//!
+//! Both hold their targets through [`WeakGc`], which the collector clears
+//! when the target is swept or pooled. FinalizationRegistry cleanup callbacks
+//! never run inside a collection: dead cells are turned into jobs at the
+//! start of the next `step()` that has no active VM, and one job runs per step.
+
+use crate::error::JsError;
+use crate::gc::{Gc, Guard, WeakGc};
+use crate::interpreter::Interpreter;
+use crate::prelude::{Rc, RefCell, Vec, format, mem};
+use crate::value::{CheapClone, ExoticObject, Guarded, JsObject, JsValue, PropertyKey};
+
+/// State of a FinalizationRegistry
+#[derive(Debug)]
+pub struct FinalizationRegistryData {
+    /// Called with the held value of each collected target
+    pub cleanup: JsValue,
+    /// Registrations whose targets have not been collected yet
+    pub cells: Vec<FinalizationCell>,
+}
+
+/// One `register()` call on a FinalizationRegistry
+#[derive(Debug)]
+pub struct FinalizationCell {
+    pub target: WeakGc<JsObject>,
+    pub held_value: JsValue,
+    /// Object that `unregister()` matches, held weakly like the target
+    pub unregister_token: Option<WeakGc<JsObject>>,
+}
+
+/// A cleanup callback waiting to run, rooted until it does
+pub(crate) struct FinalizationJob {
+    callback: JsValue,
+    held_value: JsValue,
+    _guard: Guard<JsObject>,
+}
+
+/// Create WeakRef and FinalizationRegistry constructors and register them globally
+pub fn init_weakref(interp: &mut Interpreter) {
+    let proto = interp.weakref_prototype.clone();
+    interp.register_method(&proto, "deref", weakref_deref, 0);
+    set_to_string_tag(interp, &proto, "WeakRef");
+    let constructor = interp.create_native_function("WeakRef", weakref_constructor, 1);
+    interp.root_guard.guard(constructor.clone());
+    install_constructor(interp, "WeakRef", constructor, proto);
+
+    let proto = interp.finalization_registry_prototype.clone();
+    interp.register_method(&proto, "register", finalization_registry_register, 2);
+    interp.register_method(&proto, "unregister", finalization_registry_unregister, 1);
+    set_to_string_tag(interp, &proto, "FinalizationRegistry");
+    let constructor =
+        interp.create_native_function("FinalizationRegistry", finalization_registry_constructor, 1);
+    interp.root_guard.guard(constructor.clone());
+    install_constructor(interp, "FinalizationRegistry", constructor, proto);
+}
+
+pub(super) fn set_to_string_tag(interp: &mut Interpreter, proto: &Gc<JsObject>, tag: &str) {
+    let tag_key = PropertyKey::String(interp.intern("@@toStringTag"));
+    let tag_value = JsValue::String(interp.intern(tag));
+    proto.borrow_mut().set_property(tag_key, tag_value);
+}
+
+/// Link a constructor with its prototype and register it globally
+pub(super) fn install_constructor(
+    interp: &mut Interpreter,
+    name: &str,
+    constructor: Gc<JsObject>,
+    proto: Gc<JsObject>,
+) {
+    let proto_key = PropertyKey::String(interp.intern("prototype"));
+    constructor
+        .borrow_mut()
+        .set_property(proto_key, JsValue::Object(proto.clone()));
+    let constructor_key = PropertyKey::String(interp.intern("constructor"));
+    proto
+        .borrow_mut()
+        .set_property(constructor_key, JsValue::Object(constructor.clone()));
+    let key = PropertyKey::String(interp.intern(name));
+    interp
+        .global
+        .borrow_mut()
+        .set_property(key, JsValue::Object(constructor));
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// WeakRef
+// ═══════════════════════════════════════════════════════════════════════════════
+
+pub fn weakref_constructor(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let JsValue::Object(weakref_obj) = this else {
+        return Err(JsError::type_error("Constructor WeakRef requires 'new'"));
+    };
+    let Some(JsValue::Object(target)) = args.first() else {
+        return Err(JsError::type_error("WeakRef: target must be an object"));
+    };
+    weakref_obj.borrow_mut().exotic = ExoticObject::WeakRef(target.downgrade());
+    Ok(Guarded::unguarded(JsValue::Object(weakref_obj)))
+}
+
+pub fn weakref_deref(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    if let JsValue::Object(obj) = &this
+        && let ExoticObject::WeakRef(target) = &obj.borrow().exotic
+    {
+        let value = target
+            .upgrade()
+            .map(JsValue::Object)
+            .unwrap_or(JsValue::Undefined);
+        return Ok(Guarded::unguarded(value));
+    }
+    Err(JsError::type_error(
+        "WeakRef.prototype.deref called on incompatible receiver",
+    ))
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// FinalizationRegistry
+// ═══════════════════════════════════════════════════════════════════════════════
+
+pub fn finalization_registry_constructor(
+    interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let JsValue::Object(registry_obj) = this else {
+        return Err(JsError::type_error(
+            "Constructor FinalizationRegistry requires 'new'",
+        ));
+    };
+    let cleanup = args.first().cloned().unwrap_or(JsValue::Undefined);
+    if !cleanup.is_callable() {
+        return Err(JsError::type_error(
+            "FinalizationRegistry: cleanup must be callable",
+        ));
+    }
+    registry_obj.borrow_mut().exotic =
+        ExoticObject::FinalizationRegistry(Rc::new(RefCell::new(FinalizationRegistryData {
+            cleanup,
+            cells: Vec::new(),
+        })));
+    interp
+        .finalization_registries
+        .push(registry_obj.downgrade());
+    Ok(Guarded::unguarded(JsValue::Object(registry_obj)))
+}
+
+fn this_registry(
+    this: &JsValue,
+    name: &str,
+) -> Result<Rc<RefCell<FinalizationRegistryData>>, JsError> {
+    if let JsValue::Object(obj) = this
+        && let ExoticObject::FinalizationRegistry(data) = &obj.borrow().exotic
+    {
+        return Ok(data.clone());
+    }
+    Err(JsError::type_error(format!(
+        "FinalizationRegistry.prototype.{} called on incompatible receiver",
+        name
+    )))
+}
+
+pub fn finalization_registry_register(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_registry(&this, "register")?;
+    let Some(JsValue::Object(target)) = args.first() else {
+        return Err(JsError::type_error(
+            "FinalizationRegistry.prototype.register: target must be an object",
+        ));
+    };
+    let held_value = args.get(1).cloned().unwrap_or(JsValue::Undefined);
+    if let JsValue::Object(held) = &held_value
+        && Gc::ptr_eq(held, target)
+    {
+        return Err(JsError::type_error(
+            "FinalizationRegistry.prototype.register: target and held value must not be the same",
+        ));
+    }
+    let unregister_token = match args.get(2) {
+        None | Some(JsValue::Undefined) => None,
+        Some(JsValue::Object(token)) => Some(token.downgrade()),
+        Some(_) => {
+            return Err(JsError::type_error(
+                "FinalizationRegistry.prototype.register: unregister token must be an object",
+            ));
+        }
+    };
+    data.borrow_mut().cells.push(FinalizationCell {
+        target: target.downgrade(),
+        held_value,
+        unregister_token,
+    });
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+pub fn finalization_registry_unregister(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = this_registry(&this, "unregister")?;
+    let Some(JsValue::Object(token)) = args.first() else {
+        return Err(JsError::type_error(
+            "FinalizationRegistry.prototype.unregister: unregister token must be an object",
+        ));
+    };
+    let mut data = data.borrow_mut();
+    let before = data.cells.len();
+    data.cells.retain(|cell| {
+        !cell
+            .unregister_token
+            .as_ref()
+            .is_some_and(|t| t.refers_to(token))
+    });
+    Ok(Guarded::unguarded(JsValue::Boolean(
+        data.cells.len() != before,
+    )))
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Cleanup jobs
+// ═══════════════════════════════════════════════════════════════════════════════
+
+impl Interpreter {
+    /// Turn registrations whose targets died into cleanup jobs.
+    ///
+    /// Only walks the registries when the heap reports a weak target died
+    /// since the last call.
+    pub(crate) fn queue_finalizations(&mut self) {
+        let deaths = self.heap.weak_deaths();
+        if deaths == self.weak_deaths_seen {
+            return;
+        }
+        self.weak_deaths_seen = deaths;
+
+        for registry in mem::take(&mut self.finalization_registries) {
+            let Some(registry_obj) = registry.upgrade() else {
+                continue;
+            };
+            let data = match &registry_obj.borrow().exotic {
+                ExoticObject::FinalizationRegistry(data) => data.clone(),
+                _ => continue,
+            };
+            let mut data = data.borrow_mut();
+            let cleanup = data.cleanup.clone();
+            let mut i = 0;
+            while let Some(cell) = data.cells.get(i) {
+                if !cell.target.is_dead() {
+                    i += 1;
+                    continue;
+                }
+                let cell = data.cells.remove(i);
+                let guard = self.heap.create_guard();
+                for value in [&cleanup, &cell.held_value] {
+                    if let JsValue::Object(obj) = value {
+                        guard.guard(obj.cheap_clone());
+                    }
+                }
+                self.finalization_jobs.push_back(FinalizationJob {
+                    callback: cleanup.clone(),
+                    held_value: cell.held_value,
+                    _guard: guard,
+                });
+            }
+            drop(data);
+            self.finalization_registries.push(registry);
+        }
+    }
+
+    /// Run the oldest queued cleanup callback, if any
+    pub(crate) fn run_finalization_job(&mut self) -> Result<bool, JsError> {
+        let Some(job) = self.finalization_jobs.pop_front() else {
+            return Ok(false);
+        };
+        self.call_function(
+            job.callback,
+            JsValue::Undefined,
+            core::slice::from_ref(&job.held_value),
+        )?;
+        Ok(true)
+    }
+
+    /// Count cleanup callbacks that are queued or will be on the next step
+    pub(crate) fn count_pending_finalizations(&self) -> usize {
+        let mut pending = self.finalization_jobs.len();
+        if self.heap.weak_deaths() != self.weak_deaths_seen {
+            for registry in &self.finalization_registries {
+                let Some(registry_obj) = registry.upgrade() else {
+                    continue;
+                };
+                if let ExoticObject::FinalizationRegistry(data) = &registry_obj.borrow().exotic {
+                    pending += data
+                        .borrow()
+                        .cells
+                        .iter()
+                        .filter(|cell| cell.target.is_dead())
+                        .count();
+                }
+            }
+        }
+        pending
+    }
+}
@@ -0,0 +1,443 @@
+//! YAML built-in methods
+//!
+//! Only `YAML.stringify` is provided. Values are converted with the same rules
+//! as `JSON.stringify` (undefined and functions are skipped in objects and
+//! become `null` in arrays, Dates become ISO strings), keys are emitted in
+//! property order, and a top-level array becomes a `---` separated stream of
+//! documents unless `{ documents: false }` is passed. Errors keep their own
+//! enumerable properties, or become `name`, `message` and `stack` mappings
+//! with their `cause` under `{ errors: "withStack" }` (or `"withoutStack"`).
+
+use crate::error::JsError;
+use crate::gc::Gc;
+use crate::interpreter::Interpreter;
+use crate::interpreter::builtins::json::{
+    ErrorsAs, error_members, errors_option, format_timestamp_iso,
+};
+use crate::prelude::{FxHashSet, String, ToString, Vec, format};
+use crate::value::{ExoticObject, Guarded, JsObject, JsString, JsValue, PropertyKey};
+
+/// Options accepted by `YAML.stringify`
+#[derive(Debug, Clone, Copy)]
+pub struct YamlOptions {
+    /// Spaces per nesting level (1-10, default 2)
+    pub indent: usize,
+    /// Emit a top-level array as one document per element (default true)
+    pub documents: bool,
+    /// How Error objects are emitted (default their own enumerable properties)
+    pub errors: ErrorsAs,
+}
+
+impl Default for YamlOptions {
+    fn default() -> Self {
+        Self {
+            indent: 2,
+            documents: true,
+            errors: ErrorsAs::Properties,
+        }
+    }
+}
+
+/// Initialize YAML object and add it to globals
+pub fn init_yaml(interp: &mut Interpreter) {
+    let yaml = interp.root_guard.alloc();
+    yaml.borrow_mut().prototype = Some(interp.object_prototype.clone());
+
+    interp.register_method(&yaml, "stringify", yaml_stringify, 2);
+
+    let yaml_key = PropertyKey::String(interp.intern("YAML"));
+    interp
+        .global
+        .borrow_mut()
+        .set_property(yaml_key, JsValue::Object(yaml));
+}
+
+/// YAML.stringify(value, options?)
+pub fn yaml_stringify(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let value = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let mut options = YamlOptions::default();
+    if let Some(JsValue::Object(opts)) = args.get(1) {
+        let indent_key = PropertyKey::String(interp.intern("indent"));
+        let documents_key = PropertyKey::String(interp.intern("documents"));
+        options.errors = errors_option(interp, opts)?;
+        let opts = opts.borrow();
+        if let Some(JsValue::Number(n)) = opts.get_property(&indent_key) {
+            options.indent = n.clamp(1.0, 10.0) as usize;
+        }
+        if let Some(documents) = opts.get_property(&documents_key) {
+            options.documents = documents.to_boolean();
+        }
+    }
+
+    let output = value_to_yaml(interp, &value, options)?;
+    Ok(Guarded::unguarded(JsValue::String(JsString::from(output))))
+}
+
+/// Serialize `value` as YAML text
+pub fn value_to_yaml(
+    interp: &Interpreter,
+    value: &JsValue,
+    options: YamlOptions,
+) -> Result<String, JsError> {
+    let mut writer = YamlWriter {
+        out: String::new(),
+        step: options.indent,
+        visited: FxHashSet::default(),
+        max_len: interp.max_string_len,
+        errors: options.errors,
+        error_prototype: interp.error_prototype.clone(),
+    };
+
+    let documents = match value {
+        JsValue::Object(obj) if options.documents => {
+            obj.borrow().array_elements().map(<[_]>::to_vec)
+        }
+        _ => None,
+    };
+    match documents {
+        Some(documents) => {
+            for (i, document) in documents.iter().enumerate() {
+                if i > 0 {
+                    writer.out.push_str("---\n");
+                }
+                writer.write_document(document)?;
+            }
+        }
+        None => writer.write_document(value)?,
+    }
+    Ok(writer.out)
+}
+
+/// A value reduced to what YAML can express
+enum Node {
+    Scalar(String),
+    /// Multi-line string emitted as a `|` block scalar
+    Literal(String),
+    Mapping(Gc<JsObject>),
+    Sequence(Vec<JsValue>),
+    EmptyMapping,
+    EmptySequence,
+    /// undefined, functions and symbols: skipped in mappings, `null` elsewhere
+    Skip,
+}
+
+struct YamlWriter {
+    out: String,
+    step: usize,
+    visited: FxHashSet<usize>,
+    max_len: usize,
+    errors: ErrorsAs,
+    error_prototype: Gc<JsObject>,
+}
+
+impl YamlWriter {
+    fn write_document(&mut self, value: &JsValue) -> Result<(), JsError> {
+        match self.classify(value) {
+            Node::Scalar(s) => {
+                self.out.push_str(&s);
+                self.out.push('\n');
+            }
+            Node::Skip => self.out.push_str("null\n"),
+            Node::EmptyMapping => self.out.push_str("{}\n"),
+            Node::EmptySequence => self.out.push_str("[]\n"),
+            Node::Literal(s) => self.write_literal(&s, 0),
+            Node::Mapping(obj) => self.write_mapping(&obj, 0, 0)?,
+            Node::Sequence(items) => self.write_sequence(value, &items, 0, 0)?,
+        }
+        self.check_len()
+    }
+
+    /// Write mapping entries at `indent`; the first line is prefixed by only
+    /// `first_indent` spaces so it can follow a `- ` sequence marker.
+    fn write_mapping(
+        &mut self,
+        obj: &Gc<JsObject>,
+        indent: usize,
+        first_indent: usize,
+    ) -> Result<(), JsError> {
+        self.enter(obj)?;
+        let members = error_members(obj, self.errors, Some(&self.error_prototype));
+        let entries: Vec<(PropertyKey, JsValue)> = if let Some(members) = members {
+            members
+        } else {
+            let obj_ref = obj.borrow();
+            obj_ref
+                .ordered_own_keys(true)
+                .into_iter()
+                .filter(|key| !key.is_symbol())
+                .map(|key| {
+                    let value = obj_ref.get_property(&key).unwrap_or(JsValue::Undefined);
+                    (key, value)
+                })
+                .collect()
+        };
+
+        let mut first = true;
+        for (key, value) in entries {
+            if is_skipped(&value) {
+                continue;
+            }
+            let node = self.classify(&value);
+            let pad = if first { first_indent } else { indent };
+            first = false;
+            push_spaces(&mut self.out, pad);
+            self.out.push_str(&format_string(&key.to_string()));
+            self.out.push(':');
+            self.write_nested(node, &value, indent + self.step, indent)?;
+        }
+        self.leave(obj);
+        Ok(())
+    }
+
+    fn write_sequence(
+        &mut self,
+        value: &JsValue,
+        items: &[JsValue],
+        indent: usize,
+        first_indent: usize,
+    ) -> Result<(), JsError> {
+        let JsValue::Object(obj) = value else {
+            return Ok(());
+        };
+        self.enter(obj)?;
+        for (i, item) in items.iter().enumerate() {
+            push_spaces(&mut self.out, if i == 0 { first_indent } else { indent });
+            self.out.push('-');
+            let node = match self.classify(item) {
+                Node::Skip => Node::Scalar("null".to_string()),
+                node => node,
+            };
+            match node {
+                // Collections start on the marker line: `- key: value`
+                Node::Mapping(child) => {
+                    self.out.push(' ');
+                    self.write_mapping(&child, indent + 2, 0)?;
+                }
+                Node::Sequence(child_items) => {
+                    self.out.push(' ');
+                    self.write_sequence(item, &child_items, indent + 2, 0)?;
+                }
+                node => self.write_nested(node, item, indent + 2, indent)?,
+            }
+        }
+        self.leave(obj);
+        Ok(())
+    }
+
+    /// Write the value part after `key:` or `-`, with nested lines at `child_indent`
+    fn write_nested(
+        &mut self,
+        node: Node,
+        value: &JsValue,
+        child_indent: usize,
+        indent: usize,
+    ) -> Result<(), JsError> {
+        match node {
+            Node::Scalar(s) => {
+                self.out.push(' ');
+                self.out.push_str(&s);
+                self.out.push('\n');
+            }
+            Node::Skip => self.out.push_str(" null\n"),
+            Node::EmptyMapping => self.out.push_str(" {}\n"),
+            Node::EmptySequence => self.out.push_str(" []\n"),
+            Node::Literal(s) => {
+                self.out.push(' ');
+                self.write_literal(&s, indent);
+            }
+            Node::Mapping(obj) => {
+                self.out.push('\n');
+                self.write_mapping(&obj, child_indent, child_indent)?;
+            }
+            Node::Sequence(items) => {
+                self.out.push('\n');
+                self.write_sequence(value, &items, child_indent, child_indent)?;
+            }
+        }
+        self.check_len()
+    }
+
+    /// Write a `|` block scalar whose content lines are indented one step past `indent`
+    fn write_literal(&mut self, s: &str, indent: usize) {
+        let body = s.trim_end_matches('\n');
+        let trailing = s.len() - body.len();
+        self.out.push('|');
+        match trailing {
+            0 => self.out.push('-'),
+            1 => {}
+            _ => self.out.push('+'),
+        }
+        self.out.push('\n');
+        let content = if trailing > 1 {
+            s.strip_suffix('\n').unwrap_or(s)
+        } else {
+            body
+        };
+        for line in content.split('\n') {
+            if !line.is_empty() {
+                push_spaces(&mut self.out, indent + self.step);
+                self.out.push_str(line);
+            }
+            self.out.push('\n');
+        }
+    }
+
+    fn classify(&self, value: &JsValue) -> Node {
+        if is_skipped(value) {
+            return Node::Skip;
+        }
+        match value {
+            JsValue::Undefined | JsValue::Symbol(_) => Node::Skip,
+            JsValue::Null => Node::Scalar("null".to_string()),
+            JsValue::Boolean(b) => Node::Scalar(b.to_string()),
+            JsValue::Number(n) => Node::Scalar(format_number(*n)),
+            JsValue::String(s) => classify_string(s.as_str()),
+            JsValue::Object(obj) => {
+                let obj_ref = obj.borrow();
+                if let Some(elements) = obj_ref.array_elements() {
+                    return if elements.is_empty() {
+                        Node::EmptySequence
+                    } else {
+                        Node::Sequence(elements.to_vec())
+                    };
+                }
+                match &obj_ref.exotic {
+                    ExoticObject::Date { timestamp } => {
+                        Node::Scalar(format_string(&format_timestamp_iso(*timestamp)))
+                    }
+                    ExoticObject::Boolean(b) => Node::Scalar(b.to_string()),
+                    ExoticObject::Number(n) => Node::Scalar(format_number(*n)),
+                    ExoticObject::StringObj(s) => classify_string(s.as_str()),
+                    ExoticObject::Url(data) => {
+                        Node::Scalar(format_string(&data.record.borrow().href()))
+                    }
+                    ExoticObject::Ordinary => {
+                        let has_entries = obj_ref.ordered_own_keys(true).iter().any(|key| {
+                            !key.is_symbol()
+                                && !is_skipped(
+                                    &obj_ref.get_property(key).unwrap_or(JsValue::Undefined),
+                                )
+                        });
+                        if has_entries {
+                            Node::Mapping(obj.clone())
+                        } else {
+                            Node::EmptyMapping
+                        }
+                    }
+                    // Mirrors JSON.stringify, which has no representation for these
+                    _ => Node::Scalar("null".to_string()),
+                }
+            }
+        }
+    }
+
+    fn enter(&mut self, obj: &Gc<JsObject>) -> Result<(), JsError> {
+        if !self.visited.insert(obj.id()) {
+            return Err(JsError::type_error("Converting circular structure to YAML"));
+        }
+        Ok(())
+    }
+
+    fn leave(&mut self, obj: &Gc<JsObject>) {
+        self.visited.remove(&obj.id());
+    }
+
+    fn check_len(&self) -> Result<(), JsError> {
+        if self.out.len() > self.max_len {
+            return Err(JsError::range_error("Invalid string length"));
+        }
+        Ok(())
+    }
+}
+
+/// Values omitted from mappings (and written as `null` in sequences)
+fn is_skipped(value: &JsValue) -> bool {
+    match value {
+        JsValue::Undefined | JsValue::Symbol(_) => true,
+        JsValue::Object(obj) => matches!(obj.borrow().exotic, ExoticObject::Function(_)),
+        _ => false,
+    }
+}
+
+fn push_spaces(out: &mut String, count: usize) {
+    for _ in 0..count {
+        out.push(' ');
+    }
+}
+
+fn format_number(n: f64) -> String {
+    if n.is_finite() {
+        JsValue::Number(n).to_js_string().to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+fn classify_string(s: &str) -> Node {
+    if s.contains('\n') && is_literal_safe(s) {
+        Node::Literal(s.to_string())
+    } else {
+        Node::Scalar(format_string(s))
+    }
+}
+
+/// Multi-line strings can be block scalars unless they contain control
+/// characters or start with a space (which would need an indentation indicator)
+fn is_literal_safe(s: &str) -> bool {
+    !s.starts_with(' ')
+        && !s.starts_with('\n')
+        && s.chars().all(|c| c == '\n' || c == '\t' || !c.is_control())
+}
+
+/// Emit a string plain when YAML reads it back as the same string, quoted otherwise
+fn format_string(s: &str) -> String {
+    if is_plain_safe(s) {
+        return s.to_string();
+    }
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            '\0' => quoted.push_str("\\0"),
+            c if c.is_control() => quoted.push_str(&format!("\\x{:02x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn is_plain_safe(s: &str) -> bool {
+    let Some(first) = s.chars().next() else {
+        return false;
+    };
+    // Values YAML would resolve to null, booleans or numbers
+    const RESERVED: &[&str] = &[
+        "null", "~", "true", "false", "yes", "no", "on", "off", "y", "n",
+    ];
+    if RESERVED.iter().any(|word| s.eq_ignore_ascii_case(word)) {
+        return false;
+    }
+    if first.is_ascii_digit() || matches!(first, '+' | '-' | '.') {
+        return false;
+    }
+    if first.is_whitespace() || s.ends_with(char::is_whitespace) {
+        return false;
+    }
+    if "?:,[]{}#&*!|>'\"%@`".contains(first) {
+        return false;
+    }
+    if s.contains(": ") || s.contains(" #") || s.ends_with(':') {
+        return false;
+    }
+    s.chars().all(|c| !c.is_control())
+}
@@ -9,10 +9,11 @@ use crate::gc::{Gc, Guard};
 use crate::prelude::{math, *};
 use crate::value::{
     BytecodeFunction, CheapClone, ExoticObject, Guarded, JsFunction, JsObject, JsString, JsValue,
-    Property, PropertyKey,
+    Property, PropertyKey, to_int32, to_uint32,
 };
 
 use super::Interpreter;
+use super::flight_recorder::{FlightEventKind, FlightSubject};
 
 /// Parameters for a trampoline function call
 struct CallParams {
@@ -110,6 +111,30 @@ pub struct SavedVmState {
     pub new_target: JsValue,
     /// Trampoline call stack (for nested function calls)
     pub trampoline_stack: Vec<SavedTrampolineFrame>,
+    /// `this` of the suspended frame, `None` to use the value given on restore
+    pub this_value: Option<JsValue>,
+    /// Class whose constructor was running when the frame suspended
+    pub current_constructor: Option<Gc<JsObject>>,
+    /// Environments saved by the suspended frame's open scopes
+    pub saved_env_stack: Vec<Gc<JsObject>>,
+    /// The VM's entries on the interpreter's environment guard stack, one per
+    /// open scope and trampolined call, held here while it is suspended
+    pub env_guards: Vec<Guard<JsObject>>,
+}
+
+/// Environment guards for `count` scopes resumed without their original
+/// guards; the innermost roots `env`, and with it the outer scopes' chain
+pub(crate) fn scope_guards(
+    heap: &crate::gc::Heap<JsObject>,
+    count: usize,
+    env: &Gc<JsObject>,
+) -> Vec<Guard<JsObject>> {
+    let mut guards: Vec<_> = (0..count).map(|_| heap.create_guard()).collect();
+    if let Some(innermost) = guards.last_mut() {
+        innermost.guard(env.cheap_clone());
+        innermost.set_label("environment");
+    }
+    guards
 }
 
 /// A call frame in the VM
@@ -230,6 +255,8 @@ pub struct TrampolineFrame {
     pub construct_new_obj: Option<Gc<JsObject>>,
     /// For async function calls: wrap result in a Promise when returning
     pub is_async: bool,
+    /// Caller's function environment to hand back to the call pool when it returns
+    pub pooled_env: Option<Gc<JsObject>>,
 }
 
 /// The bytecode virtual machine
@@ -257,6 +284,9 @@ pub struct BytecodeVM {
     exception_value: Option<Guarded>,
     /// Stack of saved environments for nested scope restoration
     saved_env_stack: Vec<Gc<JsObject>>,
+    /// Environment guards restored from a suspension, pushed back onto the
+    /// interpreter's stack before the VM touches it again
+    resumed_env_guards: Vec<Guard<JsObject>>,
     /// Original arguments array (for `arguments` object)
     pub arguments: Vec<JsValue>,
     /// `new.target` value (constructor if called with new, undefined otherwise)
@@ -267,10 +297,10 @@ pub struct BytecodeVM {
     pending_completion: Option<PendingCompletion>,
     /// Trampoline call stack - replaces Rust recursion with explicit stack
     trampoline_stack: Vec<TrampolineFrame>,
-    /// Pool of reusable register files to reduce allocation overhead
-    register_pool: Vec<Vec<JsValue>>,
-    /// Pool of reusable argument vectors to reduce allocation overhead
-    arguments_pool: Vec<Vec<JsValue>>,
+    /// Environment of the current trampolined call, handed back to the call pool
+    /// when it returns. Not saved on suspension, so a suspended frame's
+    /// environment is left to the GC.
+    pooled_env: Option<Gc<JsObject>>,
 }
 
 impl BytecodeVM {
@@ -295,21 +325,16 @@ impl BytecodeVM {
             this_value,
             exception_value: None,
             saved_env_stack: Vec::new(),
+            resumed_env_guards: Vec::new(),
             arguments: Vec::new(),
             new_target: JsValue::Undefined,
             current_constructor: None,
             pending_completion: None,
             trampoline_stack: Vec::new(),
-            register_pool: Vec::new(),
-            arguments_pool: Vec::new(),
+            pooled_env: None,
         }
     }
 
-    /// Get the current trampoline call depth (number of pending function calls)
-    pub fn trampoline_depth(&self) -> usize {
-        self.trampoline_stack.len()
-    }
-
     /// Create a new VM with a guard and pre-populated function arguments.
     /// Arguments are placed in registers 0, 1, 2, ... before execution starts.
     /// The bytecode's DeclareVar ops will read from these registers.
@@ -349,46 +374,50 @@ impl BytecodeVM {
             this_value,
             exception_value: None,
             saved_env_stack: Vec::new(),
+            resumed_env_guards: Vec::new(),
             arguments: args.to_vec(),
             new_target: JsValue::Undefined,
             current_constructor: None,
             pending_completion: None,
             trampoline_stack: Vec::new(),
-            register_pool: Vec::new(),
-            arguments_pool: Vec::new(),
+            pooled_env: None,
         }
     }
 
-    /// Create a new VM with a guard, arguments, and new.target value.
-    pub fn with_guard_args_and_new_target(
+    /// Whether the VM has not run an instruction of its entry chunk yet
+    pub(crate) fn is_unstarted(&self) -> bool {
+        self.ip == 0 && self.trampoline_stack.is_empty()
+    }
+
+    /// The `this` value of the code being executed
+    pub(crate) fn this_value(&self) -> &JsValue {
+        &self.this_value
+    }
+
+    /// Create a new VM for a function call with a prepared register file.
+    ///
+    /// `registers` already holds the call's parameters in registers 0, 1, 2, ...
+    /// and `arguments` is the original argument list for the `arguments` object.
+    /// Both usually come from the interpreter's call pool; [`Self::recycle`]
+    /// hands them back once the call finishes.
+    pub fn with_registers_and_new_target(
         chunk: Rc<BytecodeChunk>,
         this_value: JsValue,
         guard: Guard<JsObject>,
-        args: &[JsValue],
+        registers: Vec<JsValue>,
+        arguments: Vec<JsValue>,
         new_target: JsValue,
     ) -> Self {
-        let register_count = chunk.register_count as usize;
-        let mut registers = vec![JsValue::Undefined; register_count.max(1)];
-
-        // Guard this_value if it's an object
+        // Guard this_value and new_target if they're objects
         if let JsValue::Object(obj) = &this_value {
             guard.guard(obj.cheap_clone());
         }
-
-        // Guard new_target if it's an object
         if let JsValue::Object(obj) = &new_target {
             guard.guard(obj.cheap_clone());
         }
-
-        // Pre-populate registers with arguments
-        for (i, arg) in args.iter().enumerate() {
-            if i < registers.len()
-                && let Some(slot) = registers.get_mut(i)
-            {
-                if let JsValue::Object(obj) = arg {
-                    guard.guard(obj.cheap_clone());
-                }
-                *slot = arg.clone();
+        for value in &registers {
+            if let JsValue::Object(obj) = value {
+                guard.guard(obj.cheap_clone());
             }
         }
 
@@ -402,72 +431,71 @@ impl BytecodeVM {
             this_value,
             exception_value: None,
             saved_env_stack: Vec::new(),
-            arguments: args.to_vec(),
+            resumed_env_guards: Vec::new(),
+            arguments,
             new_target,
             current_constructor: None,
             pending_completion: None,
             trampoline_stack: Vec::new(),
-            register_pool: Vec::new(),
-            arguments_pool: Vec::new(),
+            pooled_env: None,
         }
     }
 
-    /// Acquire a register file from the pool, or allocate a new one
-    #[inline]
-    fn acquire_registers(&mut self, size: usize) -> Vec<JsValue> {
-        let size = size.max(1);
-        // Try to find an existing frame of sufficient size
-        if let Some(pos) = self.register_pool.iter().position(|f| f.capacity() >= size) {
-            let mut frame = self.register_pool.swap_remove(pos);
-            frame.clear();
-            frame.resize(size, JsValue::Undefined);
-            return frame;
-        }
-        // Allocate new frame
-        vec![JsValue::Undefined; size]
+    /// Tear down a finished VM, handing its register file, arguments and
+    /// register guard back to the interpreter's call pool.
+    ///
+    /// Scopes the body left open (e.g. by returning from inside a block) are
+    /// popped first so the interpreter's environment guard stack stays balanced.
+    pub(crate) fn recycle(mut self, interp: &mut Interpreter) {
+        self.close_scopes(interp);
+        self.release_frame_storage(interp);
+        interp.call_pool.release_guard(self.register_guard);
     }
 
-    /// Return a register file to the pool for reuse.
-    /// Also clears the register_guard to remove stale roots that could waste
-    /// GC cycles iterating over now-pooled objects.
-    #[inline]
-    fn release_registers(&mut self, mut registers: Vec<JsValue>) {
-        // Clear register_guard BEFORE clearing registers - this removes the guard's
-        // roots while the objects still have valid ref_counts from the registers Vec.
-        // If we cleared registers first, the guard would have stale roots pointing
-        // to potentially-pooled GcBoxes.
-        self.register_guard.clear();
-        // Clear the registers to drop any references
-        registers.clear();
-        // Keep pool size reasonable (e.g., max 16 frames)
-        if self.register_pool.len() < 16 {
-            self.register_pool.push(registers);
+    /// Pop every scope the current frame has open, restoring the environment
+    /// the frame started in
+    fn close_scopes(&mut self, interp: &mut Interpreter) {
+        while let Some(saved_env) = self.saved_env_stack.pop() {
+            interp.pop_scope(saved_env);
         }
     }
 
-    /// Acquire an empty arguments vector with given capacity from pool
+    /// Push the environment guards set aside at suspension back onto the
+    /// interpreter's stack
     #[inline]
-    fn acquire_arguments_vec(&mut self, capacity: usize) -> Vec<JsValue> {
-        if let Some(pos) = self
-            .arguments_pool
-            .iter()
-            .position(|v| v.capacity() >= capacity)
-        {
-            let mut vec = self.arguments_pool.swap_remove(pos);
-            vec.clear();
-            return vec;
+    fn resume_env_guards(&mut self, interp: &mut Interpreter) {
+        if !self.resumed_env_guards.is_empty() {
+            interp.env_guards.append(&mut self.resumed_env_guards);
         }
-        Vec::with_capacity(capacity)
     }
 
-    /// Return an arguments vector to the pool for reuse
+    /// Take this VM's entries off the interpreter's environment guard stack:
+    /// one per open scope in each frame and one per trampolined call
+    fn suspend_env_guards(&self, interp: &mut Interpreter) -> Vec<Guard<JsObject>> {
+        let owned = self.saved_env_stack.len()
+            + self
+                .trampoline_stack
+                .iter()
+                .map(|frame| frame.saved_env_stack.len() + 1)
+                .sum::<usize>();
+        let depth = interp.env_guards.len().saturating_sub(owned);
+        interp.env_guards.split_off(depth)
+    }
+
+    /// Hand the current frame's register file and arguments back to the call pool
     #[inline]
-    fn release_arguments(&mut self, mut args: Vec<JsValue>) {
-        args.clear();
-        // Keep pool size reasonable (max 16)
-        if self.arguments_pool.len() < 16 {
-            self.arguments_pool.push(args);
-        }
+    fn release_frame_storage(&mut self, interp: &mut Interpreter) {
+        // Clear register_guard BEFORE clearing registers - this removes the guard's
+        // roots while the objects still have valid ref_counts from the registers Vec.
+        // If we cleared registers first, the guard would have stale roots pointing
+        // to potentially-pooled GcBoxes.
+        self.register_guard.clear();
+        interp
+            .call_pool
+            .release_registers(mem::take(&mut self.registers));
+        interp
+            .call_pool
+            .release_arguments(mem::take(&mut self.arguments));
     }
 
     /// Build a stack trace from the current VM state.
@@ -476,20 +504,7 @@ impl BytecodeVM {
         let mut frames = Vec::new();
 
         // Current frame (where the error occurred)
-        let current_ip = if self.ip > 0 { self.ip - 1 } else { 0 };
-        if let Some(span) = self.chunk.get_source_location(current_ip) {
-            let function_name = self
-                .chunk
-                .function_info
-                .as_ref()
-                .and_then(|info| info.name.as_ref().map(|s| s.to_string()));
-            frames.push(StackFrame {
-                function_name,
-                file: self.chunk.source_file.clone(),
-                line: span.line,
-                column: span.column,
-            });
-        }
+        frames.extend(self.current_frame());
 
         // Frames from the trampoline stack (outer call frames)
         for tramp_frame in self.trampoline_stack.iter().rev() {
@@ -516,6 +531,147 @@ impl BytecodeVM {
         frames
     }
 
+    /// The innermost frame: the instruction being executed in the current chunk
+    fn current_frame(&self) -> Option<StackFrame> {
+        let current_ip = if self.ip > 0 { self.ip - 1 } else { 0 };
+        let span = self.chunk.get_source_location(current_ip)?;
+        let function_name = self
+            .chunk
+            .function_info
+            .as_ref()
+            .and_then(|info| info.name.as_ref().map(|s| s.to_string()));
+        Some(StackFrame {
+            function_name,
+            file: self.chunk.source_file.clone(),
+            line: span.line,
+            column: span.column,
+        })
+    }
+
+    /// Record the literal being executed as the creation site of `obj`
+    #[cold]
+    fn note_creation_site(&self, interp: &mut Interpreter, obj: &Gc<JsObject>) {
+        if let Some(frame) = self.current_frame()
+            && let Some(table) = interp.provenance.as_mut()
+        {
+            table.record(obj, frame.file, frame.line, frame.column);
+        }
+    }
+
+    /// Record the running function as the one returning `value`
+    #[cold]
+    fn note_returned_value(&self, interp: &mut Interpreter, value: &JsValue) {
+        let JsValue::Object(obj) = value else {
+            return;
+        };
+        // Script and module bodies complete rather than return
+        let Some(info) = self.chunk.function_info.as_ref() else {
+            return;
+        };
+        let name = info
+            .name
+            .as_ref()
+            .map_or("<anonymous>", |name| name.as_str());
+        if let Some(table) = interp.provenance.as_mut() {
+            table.note_returned(obj, name);
+        }
+    }
+
+    /// Remember the current frame as the location of purity violations
+    #[cold]
+    fn note_purity_location(&self, interp: &mut Interpreter) {
+        if let Some(check) = interp.purity.as_mut() {
+            check.location = self.current_frame();
+        }
+    }
+
+    /// Note an event at the current instruction in the flight recorder
+    #[cold]
+    fn note_flight(&self, interp: &mut Interpreter, kind: FlightEventKind, subject: FlightSubject) {
+        let site = (self.chunk.clone(), self.ip.saturating_sub(1));
+        interp.record_flight(kind, subject, Some(site));
+    }
+
+    /// Note the running function returning or throwing in the flight recorder
+    #[cold]
+    fn note_flight_return(&self, interp: &mut Interpreter) {
+        let name = self
+            .chunk
+            .function_info
+            .as_ref()
+            .and_then(|info| info.name.cheap_clone());
+        self.note_flight(interp, FlightEventKind::Return, FlightSubject::Name(name));
+    }
+
+    /// Attribute the orders a native call created, from index `first` of the
+    /// pending orders on, to the innermost frame outside tsrun's own modules
+    #[cold]
+    fn note_order_origins(&self, interp: &mut Interpreter, first: usize) {
+        let Some(frame) = self.script_frame() else {
+            return;
+        };
+        let origin = crate::OrderOrigin {
+            module: frame
+                .file
+                .as_ref()
+                .map(|file| crate::ModulePath::new(file.clone())),
+            line: frame.line,
+            column: frame.column,
+            function_name: frame.function_name.clone(),
+        };
+        // Orders a nested VM already attributed keep their origin
+        let recording = interp.flight_recorder.is_some();
+        let mut attributed = Vec::new();
+        for order in interp.pending_orders.iter_mut().skip(first) {
+            if order.origin == crate::OrderOrigin::default() {
+                order.origin = origin.clone();
+                if recording {
+                    attributed.push(order.id);
+                }
+            }
+        }
+        if recording {
+            for id in attributed {
+                self.note_flight(
+                    interp,
+                    FlightEventKind::OrderCreated,
+                    FlightSubject::Order(id),
+                );
+            }
+        }
+    }
+
+    /// Record the call being executed as the creation site of the promises
+    /// tracked since `first`
+    #[cold]
+    fn note_promise_sites(&self, interp: &mut Interpreter, first: u64) {
+        let Some(frame) = self.script_frame() else {
+            return;
+        };
+        let site = super::provenance::CreationSite {
+            file: frame.file,
+            line: frame.line,
+            column: frame.column,
+            returned_by: None,
+        };
+        interp.promise_tracker.record_sites(first, &site);
+    }
+
+    /// The innermost frame outside tsrun's own modules, or the innermost
+    /// frame if all of them are inside
+    fn script_frame(&self) -> Option<StackFrame> {
+        let mut frames = self.build_stack_trace();
+        let index = frames
+            .iter()
+            .position(|f| {
+                !f.file
+                    .as_deref()
+                    .is_some_and(|file| file.starts_with("tsrun:"))
+            })
+            .unwrap_or(0);
+        (index < frames.len()).then(|| frames.swap_remove(index))
+    }
+
     /// Wrap a JsError with stack trace information.
     /// Converts simple errors (TypeError, ReferenceError, etc.) into RuntimeError with backtrace.
     pub fn wrap_error_with_trace(&self, error: JsError) -> JsError {
@@ -525,6 +681,7 @@ impl BytecodeVM {
             JsError::Thrown | JsError::ThrownValue { .. } => error, // User-thrown, handled separately
             JsError::GeneratorYield { .. } => error,                // Not a real error
             JsError::OptionalChainShortCircuit => error,            // Not a real error
+            JsError::Terminated { .. } => error, // Host limit, not a script error
             _ => {
                 let stack = self.build_stack_trace();
                 let (kind, message) = match &error {
@@ -536,12 +693,25 @@ impl BytecodeVM {
                         format!("{} is not defined", name),
                     ),
                     JsError::RangeError { message } => ("RangeError".to_string(), message.clone()),
-                    JsError::SyntaxError { message, .. } => {
-                        ("SyntaxError".to_string(), message.clone())
-                    }
+                    JsError::SyntaxError { .. } | JsError::UnsupportedSyntax { .. } => (
+                        "SyntaxError".to_string(),
+                        error.syntax_message().unwrap_or_default(),
+                    ),
                     JsError::ModuleError { message } => {
                         ("ModuleError".to_string(), message.clone())
                     }
+                    JsError::IntegrityMismatch { .. } => (
+                        "ModuleError".to_string(),
+                        error.integrity_message().unwrap_or_default(),
+                    ),
+                    JsError::TransformError { .. } => (
+                        "ModuleError".to_string(),
+                        error.transform_message().unwrap_or_default(),
+                    ),
+                    JsError::CompileLimit { .. } => (
+                        "RangeError".to_string(),
+                        error.compile_limit_message().unwrap_or_default(),
+                    ),
                     JsError::Internal(msg) => ("InternalError".to_string(), msg.clone()),
                     // Already handled above
                     _ => return error,
@@ -550,6 +720,8 @@ impl BytecodeVM {
                     kind,
                     message,
                     stack,
+                    properties: None,
+                    flight_log: None,
                 }
             }
         }
@@ -609,6 +781,18 @@ impl BytecodeVM {
         }
     }
 
+    /// The arguments a spread call passes, from the array collected in
+    /// register `r`
+    fn spread_arguments(&self, interp: &Interpreter, r: Register) -> Result<Vec<JsValue>, JsError> {
+        let JsValue::Object(arr_ref) = self.get_reg(r) else {
+            return Ok(Vec::new());
+        };
+        let arr = arr_ref.borrow();
+        let elems = arr.array_elements().unwrap_or_default();
+        interp.check_argument_count(elems.len())?;
+        Ok(elems.to_vec())
+    }
+
     /// Get the super constructor from the current function's __super__ property
     fn get_super_constructor(&self, interp: &mut Interpreter) -> Result<JsValue, JsError> {
         // Look up __super__ in the current function's properties
@@ -685,6 +869,25 @@ impl BytecodeVM {
         ))
     }
 
+    /// Execute instructions up to the next checkpoint: a loop back-edge, a
+    /// call into or return from a bytecode function, or a new order. Returns
+    /// early on a terminal result, like [`Self::step`].
+    pub fn run_to_checkpoint(&mut self, interp: &mut Interpreter) -> VmStepResult {
+        loop {
+            let ip = self.ip;
+            let depth = self.trampoline_stack.len();
+            let orders = interp.pending_orders.len();
+            let result = self.step(interp);
+            if !matches!(result, VmStepResult::Continue)
+                || self.trampoline_stack.len() != depth
+                || interp.pending_orders.len() != orders
+                || self.chunk.is_back_edge(ip)
+            {
+                return result;
+            }
+        }
+    }
+
     /// Execute a single bytecode instruction.
     ///
     /// Returns `VmStepResult::Continue` if more instructions remain,
@@ -693,6 +896,15 @@ impl BytecodeVM {
     /// This method enables step-by-step execution for host-controlled interruption.
     #[inline]
     pub fn step(&mut self, interp: &mut Interpreter) -> VmStepResult {
+        self.resume_env_guards(interp);
+        if let Err(e) = interp.check_budget() {
+            // Terminations are never caught, so this always unwinds to the host
+            if let Err(e) = self.handle_error_with_trampoline_unwind(interp, e) {
+                return VmStepResult::Terminal(Box::new(VmResult::Error(e)));
+            }
+            return VmStepResult::Continue;
+        }
+
         let Some(op) = self.fetch() else {
             // End of bytecode - return last result or undefined
             let result = self
@@ -708,6 +920,7 @@ impl BytecodeVM {
                 return VmStepResult::Continue;
             }
 
+            self.close_scopes(interp);
             let guard = interp.heap.create_guard();
             if let JsValue::Object(obj) = &result {
                 guard.guard(obj.cheap_clone());
@@ -727,6 +940,7 @@ impl BytecodeVM {
                     self.restore_from_trampoline_frame(interp, frame, value.value);
                     return VmStepResult::Continue;
                 }
+                self.close_scopes(interp);
                 VmStepResult::Terminal(Box::new(VmResult::Complete(value)))
             }
             Ok(OpResult::Suspend {
@@ -735,6 +949,9 @@ impl BytecodeVM {
             }) => {
                 // Extract the object from the guarded value
                 if let JsValue::Object(obj) = promise.value {
+                    if let Some(index) = self.innermost_async_call() {
+                        return self.detach_async_call(interp, index, obj, resume_register);
+                    }
                     VmStepResult::Terminal(Box::new(VmResult::Suspend(VmSuspension {
                         waiting_on: obj,
                         state: self.save_state(interp),
@@ -749,14 +966,7 @@ impl BytecodeVM {
             Ok(OpResult::SuspendForOrder {
                 order_id,
                 resume_register,
-            }) => {
-                // Suspend waiting for host to provide order response
-                VmStepResult::Terminal(Box::new(VmResult::SuspendForOrder(VmOrderSuspension {
-                    order_id,
-                    state: self.save_state(interp),
-                    resume_register,
-                })))
-            }
+            }) => self.suspend_for_order(interp, order_id, resume_register),
             Ok(OpResult::Yield {
                 value,
                 resume_register,
@@ -783,6 +993,7 @@ impl BytecodeVM {
                 guard: _guard, // Guard keeps values alive until trampoline frame is pushed
             }) => {
                 // Trampoline: save current state and switch to called function
+                let resume_register = return_register;
                 match self.setup_trampoline_call(
                     interp,
                     CallParams {
@@ -795,7 +1006,7 @@ impl BytecodeVM {
                     },
                 ) {
                     Ok(None) => VmStepResult::Continue,
-                    Ok(Some(vm_result)) => VmStepResult::Terminal(Box::new(vm_result)),
+                    Ok(Some(order_id)) => self.suspend_for_order(interp, order_id, resume_register),
                     Err(e) => {
                         // Try to find an exception handler, unwinding trampoline if needed
                         if let Err(e) = self.handle_error_with_trampoline_unwind(interp, e) {
@@ -863,13 +1074,13 @@ impl BytecodeVM {
     ///
     /// Returns:
     /// - `Ok(None)` - continue execution (trampoline set up or native function completed)
-    /// - `Ok(Some(VmResult))` - return this VmResult immediately (e.g., for suspension)
+    /// - `Ok(Some(order_id))` - the call created an order to suspend for
     /// - `Err(e)` - error occurred
     fn setup_trampoline_call(
         &mut self,
         interp: &mut Interpreter,
         params: CallParams,
-    ) -> Result<Option<VmResult>, JsError> {
+    ) -> Result<Option<crate::OrderId>, JsError> {
         use crate::value::{ExoticObject, JsFunction};
 
         let CallParams {
@@ -886,9 +1097,9 @@ impl BytecodeVM {
         };
 
         // Check if this is a proxy
-        let is_proxy = matches!(func_obj.borrow().exotic, ExoticObject::Proxy(_));
+        let is_proxy = crate::interpreter::builtins::proxy::has_hooks(func_obj);
         if is_proxy {
-            // For proxies, fall back to recursive call (they're rare)
+            // For proxies and host objects, fall back to recursive call (they're rare)
             let result = crate::interpreter::builtins::proxy::proxy_apply(
                 interp,
                 func_obj.cheap_clone(),
@@ -924,12 +1135,28 @@ impl BytecodeVM {
                 Ok(None)
             }
             JsFunction::Native(native) => {
+                if interp.audit_log.is_some() {
+                    interp.audit_native_call(func_obj, &native.name);
+                }
+                if interp.purity.is_some() {
+                    self.note_purity_location(interp);
+                }
                 // Native functions are quick, call directly
                 // Set FFI callback ID if this is an FFI-registered function
                 let prev_ffi_id = interp.current_ffi_id;
                 interp.current_ffi_id = native.ffi_id;
+                let orders_before = interp.pending_orders.len();
+                let promises_before = interp.promise_tracker.created();
                 let result = (native.func)(interp, this_value, &args);
                 interp.current_ffi_id = prev_ffi_id;
+                interp.call_pool.release_arguments(args);
+                if interp.pending_orders.len() > orders_before {
+                    self.note_order_origins(interp, orders_before);
+                }
+                if interp.provenance.is_some() && interp.promise_tracker.created() > promises_before
+                {
+                    self.note_promise_sites(interp, promises_before);
+                }
                 let result = result?;
 
                 // Check if result is a PendingOrder - if so, suspend immediately.
@@ -940,13 +1167,7 @@ impl BytecodeVM {
                 if let JsValue::Object(ref obj) = result.value
                     && let ExoticObject::PendingOrder { id, .. } = &obj.borrow().exotic
                 {
-                    let order_id = crate::OrderId(*id);
-                    let state = self.save_state(interp);
-                    return Ok(Some(VmResult::SuspendForOrder(VmOrderSuspension {
-                        order_id,
-                        state,
-                        resume_register: return_register,
-                    })));
+                    return Ok(Some(crate::OrderId(*id)));
                 }
 
                 self.set_reg(return_register, result.value);
@@ -980,7 +1201,7 @@ impl BytecodeVM {
 
                 let state = BytecodeGeneratorState {
                     chunk: bc_func.chunk,
-                    closure: bc_func.closure,
+                    closure: Some(bc_func.closure),
                     args: args.to_vec(),
                     this_value,
                     status: GeneratorStatus::Suspended,
@@ -991,12 +1212,17 @@ impl BytecodeVM {
                     saved_registers: Vec::new(),
                     saved_call_stack: Vec::new(),
                     saved_try_stack: Vec::new(),
+                    saved_env_stack: Vec::new(),
                     yield_result_register: None,
                     func_env: None,
                     current_env: None,
                     delegated_iterator: None,
                     is_async: false,
                     throw_value: None,
+                    return_value: None,
+                    queue: VecDeque::new(),
+                    running: false,
+                    suspended_await: None,
                 };
 
                 let gen_obj = super::builtins::generator::create_bytecode_generator_object(
@@ -1017,7 +1243,7 @@ impl BytecodeVM {
 
                 let state = BytecodeGeneratorState {
                     chunk: bc_func.chunk,
-                    closure: bc_func.closure,
+                    closure: Some(bc_func.closure),
                     args: args.to_vec(),
                     this_value,
                     status: GeneratorStatus::Suspended,
@@ -1028,12 +1254,17 @@ impl BytecodeVM {
                     saved_registers: Vec::new(),
                     saved_call_stack: Vec::new(),
                     saved_try_stack: Vec::new(),
+                    saved_env_stack: Vec::new(),
                     yield_result_register: None,
                     func_env: None,
                     current_env: None,
                     delegated_iterator: None,
                     is_async: true, // Async generator
                     throw_value: None,
+                    return_value: None,
+                    queue: VecDeque::new(),
+                    running: false,
+                    suspended_await: None,
                 };
 
                 let gen_obj = super::builtins::generator::create_bytecode_generator_object(
@@ -1064,8 +1295,14 @@ impl BytecodeVM {
             // For all other function types, fall back to the interpreter's call_function
             // This includes PromiseResolve, PromiseReject, PromiseAllFulfill, AccessorGetter, etc.
             _ => {
+                let promises_before = interp.promise_tracker.created();
                 let result =
-                    interp.call_function_with_new_target(callee, this_value, &args, new_target)?;
+                    interp.call_function_with_new_target(callee, this_value, &args, new_target);
+                if interp.provenance.is_some() && interp.promise_tracker.created() > promises_before
+                {
+                    self.note_promise_sites(interp, promises_before);
+                }
+                let result = result?;
                 self.set_reg(return_register, result.value);
                 Ok(None)
             }
@@ -1133,8 +1370,14 @@ impl BytecodeVM {
             // For all other function types, fall back to the interpreter's call_function
             // and handle the object/non-object return value
             _ => {
+                let promises_before = interp.promise_tracker.created();
                 let result =
-                    interp.call_function_with_new_target(callee, this_value, &args, new_target)?;
+                    interp.call_function_with_new_target(callee, this_value, &args, new_target);
+                if interp.provenance.is_some() && interp.promise_tracker.created() > promises_before
+                {
+                    self.note_promise_sites(interp, promises_before);
+                }
+                let result = result?;
                 // If constructor returned an object, use that; otherwise use the new_obj
                 let final_val = match result.value {
                     JsValue::Object(obj) => JsValue::Object(obj),
@@ -1161,7 +1404,9 @@ impl BytecodeVM {
         is_async: bool,
         is_super_call: bool,
     ) -> Result<(), JsError> {
-        use crate::interpreter::{Binding, VarKey, create_environment_unrooted_with_capacity};
+        use crate::interpreter::{Binding, VarKey};
+
+        interp.check_call_depth()?;
 
         // Get function info from the chunk
         let func_info = bc_func.chunk.function_info.as_ref();
@@ -1176,6 +1421,10 @@ impl BytecodeVM {
             function_name: func_name,
             location: None,
         });
+        if interp.flight_recorder.is_some() {
+            let name = func_info.and_then(|info| info.name.cheap_clone());
+            self.note_flight(interp, FlightEventKind::Call, FlightSubject::Name(name));
+        }
 
         // Calculate environment capacity: params + this + potential arguments + some slack
         // Use binding_count if available, otherwise estimate from param_count
@@ -1190,12 +1439,12 @@ impl BytecodeVM {
             })
             .unwrap_or(8);
 
-        // Create new environment for the function, with closure as parent
-        let (func_env, func_guard) = create_environment_unrooted_with_capacity(
-            &interp.heap,
-            Some(bc_func.closure.cheap_clone()),
-            env_capacity,
-        );
+        // Create new environment for the function, with closure as parent.
+        // Functions that can't capture it reuse one from the call pool.
+        let recyclable_env = func_info.is_some_and(|info| info.env_is_recyclable());
+        let (func_env, func_guard) =
+            interp.create_function_env(bc_func.closure.cheap_clone(), env_capacity, recyclable_env);
+        let pooled_env = recyclable_env.then(|| func_env.cheap_clone());
 
         // Bind `this` in the function environment
         let effective_this = if let Some(captured) = bc_func.captured_this {
@@ -1263,7 +1512,7 @@ impl BytecodeVM {
         interp.push_env_guard(func_guard);
 
         // Handle rest parameters - separate args for registers vs arguments object
-        let new_guard = interp.heap.create_guard();
+        let new_guard = interp.call_pool.acquire_guard(&interp.heap);
         let (processed_args, new_arguments): (Option<Vec<JsValue>>, Vec<JsValue>) =
             if let Some(rest_idx) = func_info.and_then(|info| info.rest_param) {
                 // Rest param case: create processed version for registers
@@ -1297,7 +1546,7 @@ impl BytecodeVM {
 
         // Create the new register file for the called function (from pool if available)
         let register_count = bc_func.chunk.register_count as usize;
-        let mut new_registers = self.acquire_registers(register_count);
+        let mut new_registers = interp.call_pool.acquire_registers(register_count);
         for (i, arg) in register_args.iter().enumerate() {
             if i < new_registers.len()
                 && let Some(slot) = new_registers.get_mut(i)
@@ -1327,6 +1576,7 @@ impl BytecodeVM {
             register_guard: old_guard,
             construct_new_obj: None,
             is_async,
+            pooled_env: mem::replace(&mut self.pooled_env, pooled_env),
         };
         self.trampoline_stack.push(frame);
 
@@ -1357,7 +1607,9 @@ impl BytecodeVM {
         new_target: JsValue,
         construct_new_obj: Gc<JsObject>,
     ) -> Result<(), JsError> {
-        use crate::interpreter::{Binding, VarKey, create_environment_unrooted_with_capacity};
+        use crate::interpreter::{Binding, VarKey};
+
+        interp.check_call_depth()?;
 
         // Get function info from the chunk
         let func_info = bc_func.chunk.function_info.as_ref();
@@ -1372,6 +1624,10 @@ impl BytecodeVM {
             function_name: func_name,
             location: None,
         });
+        if interp.flight_recorder.is_some() {
+            let name = func_info.and_then(|info| info.name.cheap_clone());
+            self.note_flight(interp, FlightEventKind::Call, FlightSubject::Name(name));
+        }
 
         // Calculate environment capacity: params + this + potential arguments + some slack
         // Use binding_count if available, otherwise estimate from param_count
@@ -1386,12 +1642,12 @@ impl BytecodeVM {
             })
             .unwrap_or(8);
 
-        // Create new environment for the function, with closure as parent
-        let (func_env, func_guard) = create_environment_unrooted_with_capacity(
-            &interp.heap,
-            Some(bc_func.closure.cheap_clone()),
-            env_capacity,
-        );
+        // Create new environment for the function, with closure as parent.
+        // Functions that can't capture it reuse one from the call pool.
+        let recyclable_env = func_info.is_some_and(|info| info.env_is_recyclable());
+        let (func_env, func_guard) =
+            interp.create_function_env(bc_func.closure.cheap_clone(), env_capacity, recyclable_env);
+        let pooled_env = recyclable_env.then(|| func_env.cheap_clone());
 
         // Bind `this` in the function environment
         let effective_this = if let Some(captured) = bc_func.captured_this {
@@ -1459,7 +1715,7 @@ impl BytecodeVM {
         interp.push_env_guard(func_guard);
 
         // Handle rest parameters - separate args for registers vs arguments object
-        let new_guard = interp.heap.create_guard();
+        let new_guard = interp.call_pool.acquire_guard(&interp.heap);
         let (processed_args, new_arguments): (Option<Vec<JsValue>>, Vec<JsValue>) =
             if let Some(rest_idx) = func_info.and_then(|info| info.rest_param) {
                 // Rest param case: create processed version for registers
@@ -1495,7 +1751,7 @@ impl BytecodeVM {
 
         // Create the new register file for the called function (from pool if available)
         let register_count = bc_func.chunk.register_count as usize;
-        let mut new_registers = self.acquire_registers(register_count);
+        let mut new_registers = interp.call_pool.acquire_registers(register_count);
         for (i, arg) in register_args.iter().enumerate() {
             if i < new_registers.len()
                 && let Some(slot) = new_registers.get_mut(i)
@@ -1525,6 +1781,7 @@ impl BytecodeVM {
             register_guard: old_guard,
             construct_new_obj: Some(construct_new_obj),
             is_async: false, // Construct calls are never async
+            pooled_env: mem::replace(&mut self.pooled_env, pooled_env),
         };
         self.trampoline_stack.push(frame);
 
@@ -1545,19 +1802,22 @@ impl BytecodeVM {
         frame: TrampolineFrame,
         return_value: JsValue,
     ) {
-        // Release current registers back to pool before restoring
-        let current_registers = mem::take(&mut self.registers);
-        self.release_registers(current_registers);
+        if interp.flight_recorder.is_some() {
+            self.note_flight_return(interp);
+        }
+        // Release current registers and arguments back to pool before restoring
+        self.release_frame_storage(interp);
 
-        // Release current arguments back to pool before restoring
-        let current_arguments = mem::take(&mut self.arguments);
-        self.release_arguments(current_arguments);
+        // Pop scopes left open by a return from inside a block, so the
+        // environment guard popped below is the function's own
+        self.close_scopes(interp);
 
         // Restore VM state
         self.ip = frame.ip;
         self.chunk = frame.chunk;
         self.registers = frame.registers;
-        self.register_guard = frame.register_guard;
+        let callee_guard = mem::replace(&mut self.register_guard, frame.register_guard);
+        interp.call_pool.release_guard(callee_guard);
         self.this_value = frame.this_value;
         self.call_stack = frame.vm_call_stack;
         self.try_stack = frame.try_stack;
@@ -1572,6 +1832,9 @@ impl BytecodeVM {
         interp.pop_env_guard();
         interp.env = frame.saved_interp_env;
         interp.call_stack.pop();
+        if let Some(env) = mem::replace(&mut self.pooled_env, frame.pooled_env) {
+            interp.call_pool.release_env(env);
+        }
 
         // For construct calls: if constructor didn't return an object, use the new object
         let intermediate_value = if let Some(new_obj) = frame.construct_new_obj {
@@ -1620,6 +1883,100 @@ impl BytecodeVM {
         self.set_reg(frame.return_register, final_value);
     }
 
+    /// Suspend until the host answers order `order_id`, storing the answer
+    /// in `resume_register`.
+    ///
+    /// A running async call waits on the order's promise instead, so that it
+    /// doesn't hold up its caller.
+    fn suspend_for_order(
+        &mut self,
+        interp: &mut Interpreter,
+        order_id: crate::OrderId,
+        resume_register: Register,
+    ) -> VmStepResult {
+        if let Some(index) = self.innermost_async_call() {
+            let guard = interp.heap.create_guard();
+            let promise = interp.create_tracked_order_promise(&guard, order_id);
+            return self.detach_async_call(interp, index, promise, resume_register);
+        }
+        VmStepResult::Terminal(Box::new(VmResult::SuspendForOrder(VmOrderSuspension {
+            order_id,
+            state: self.save_state(interp),
+            resume_register,
+        })))
+    }
+
+    /// Index in the trampoline stack of the frame that called the innermost
+    /// running async function its caller doesn't await right away, if one is
+    /// running above the VM's own frame.
+    ///
+    /// A caller awaiting the call would suspend on it at once, so the two
+    /// stay together and the search goes on below the caller.
+    fn innermost_async_call(&self) -> Option<usize> {
+        self.trampoline_stack.iter().rposition(|frame| {
+            frame.is_async
+                && !matches!(
+                    frame.chunk.get(frame.ip),
+                    Some(Op::Await { promise, .. }) if *promise == frame.return_register
+                )
+        })
+    }
+
+    /// Set the async call made from trampoline frame `index` aside while it
+    /// waits on `waiting_on`.
+    ///
+    /// The call and everything it has called become a task in the
+    /// interpreter's wait graph, resumed once the promise settles, and the
+    /// caller carries on with a pending promise for the call's result.
+    fn detach_async_call(
+        &mut self,
+        interp: &mut Interpreter,
+        index: usize,
+        waiting_on: Gc<JsObject>,
+        resume_register: Register,
+    ) -> VmStepResult {
+        let above = self.trampoline_stack.split_off(index + 1);
+        let Some(caller) = self.trampoline_stack.pop() else {
+            return VmStepResult::Terminal(Box::new(VmResult::Error(JsError::internal_error(
+                "No caller to detach an async call from",
+            ))));
+        };
+        // Save the call as if it were the whole VM: its scopes and the calls
+        // above it go with the state
+        let callers = mem::replace(&mut self.trampoline_stack, above);
+        // The call's function environment: what its first scope saved, or
+        // where it is running
+        let frame_env = match self.trampoline_stack.first() {
+            Some(frame) => frame
+                .saved_env_stack
+                .first()
+                .unwrap_or(&frame.saved_interp_env),
+            None => self.saved_env_stack.first().unwrap_or(&interp.env),
+        }
+        .cheap_clone();
+        let state = self.save_state(interp);
+        self.trampoline_stack = callers;
+        self.saved_env_stack.clear();
+        self.pooled_env = None;
+
+        // The call's entries on the interpreter's call stack go with it too,
+        // except its own, popped below as the call returns to its caller
+        let depth = interp
+            .call_stack
+            .len()
+            .saturating_sub(state.trampoline_stack.len() + 1);
+        let calls = interp.call_stack.split_off(depth);
+        if let Some(own) = calls.first() {
+            interp.call_stack.push(own.clone());
+        }
+
+        let env = interp.env.cheap_clone();
+        let completion =
+            interp.detach_async_task(state, waiting_on, resume_register, env, frame_env, calls);
+        self.restore_from_trampoline_frame(interp, caller, JsValue::Object(completion));
+        VmStepResult::Continue
+    }
+
     /// Convert an error to a guarded JS value (takes ownership to avoid re-guarding)
     fn error_to_guarded(&self, interp: &mut Interpreter, error: JsError) -> Guarded {
         match error {
@@ -1690,9 +2047,11 @@ impl BytecodeVM {
         // Capture stack trace BEFORE unwinding the trampoline stack
         // This gives us the full call stack at the point of error
         let wrapped_error = self.wrap_error_with_trace(e);
+        // Host-imposed stops skip catch/finally blocks and async rejection
+        let catchable = !wrapped_error.is_termination();
 
         // First check for handler in current frame
-        if let Some((handler_ip, is_catch)) = self.find_exception_handler(interp) {
+        if catchable && let Some((handler_ip, is_catch)) = self.find_exception_handler(interp) {
             self.ip = handler_ip;
             let guarded = self.error_to_guarded(interp, wrapped_error);
             if is_catch {
@@ -1709,27 +2068,24 @@ impl BytecodeVM {
         while let Some(frame) = self.trampoline_stack.pop() {
             let is_async_frame = frame.is_async;
             let return_register = frame.return_register;
+            if interp.flight_recorder.is_some() {
+                self.note_flight_return(interp);
+            }
 
-            // Release current registers back to pool before restoring
-            let current_registers = mem::take(&mut self.registers);
-            self.release_registers(current_registers);
-
-            // Release current arguments back to pool before restoring
-            let current_arguments = mem::take(&mut self.arguments);
-            self.release_arguments(current_arguments);
+            // Release current registers and arguments back to pool before restoring
+            self.release_frame_storage(interp);
 
             // Unwind current frame's scopes before restoring - if the called function
             // had any scopes pushed (e.g., from PushScope in its body), we need to pop
             // them and their guards before switching to the caller's saved_env_stack
-            while let Some(saved_env) = self.saved_env_stack.pop() {
-                interp.pop_scope(saved_env);
-            }
+            self.close_scopes(interp);
 
             // Restore state from frame
             self.ip = frame.ip;
             self.chunk = frame.chunk;
             self.registers = frame.registers;
-            self.register_guard = frame.register_guard;
+            let callee_guard = mem::replace(&mut self.register_guard, frame.register_guard);
+            interp.call_pool.release_guard(callee_guard);
             self.this_value = frame.this_value;
             self.call_stack = frame.vm_call_stack;
             self.try_stack = frame.try_stack;
@@ -1743,9 +2099,12 @@ impl BytecodeVM {
             interp.pop_env_guard();
             interp.env = frame.saved_interp_env;
             interp.call_stack.pop();
+            if let Some(env) = mem::replace(&mut self.pooled_env, frame.pooled_env) {
+                interp.call_pool.release_env(env);
+            }
 
             // For async frames: convert error to rejected Promise instead of propagating
-            if is_async_frame {
+            if is_async_frame && catchable {
                 let error_guarded = self.error_to_guarded(interp, wrapped_error);
                 let promise = super::builtins::promise::create_rejected_promise(
                     interp,
@@ -1760,7 +2119,7 @@ impl BytecodeVM {
             }
 
             // Check for exception handler in this frame
-            if let Some((handler_ip, is_catch)) = self.find_exception_handler(interp) {
+            if catchable && let Some((handler_ip, is_catch)) = self.find_exception_handler(interp) {
                 self.ip = handler_ip;
                 let guarded = self.error_to_guarded(interp, wrapped_error);
                 if is_catch {
@@ -1772,13 +2131,15 @@ impl BytecodeVM {
             }
         }
 
-        // No handler found - return the error back to caller with stack trace
+        // No handler found - leave the scopes the bottom frame has open and
+        // return the error back to caller with stack trace
+        self.close_scopes(interp);
         Err(wrapped_error)
     }
 
     /// Save VM state for suspension
     /// Creates a guard to keep all objects in registers alive during suspension
-    fn save_state(&self, interp: &Interpreter) -> SavedVmState {
+    fn save_state(&self, interp: &mut Interpreter) -> SavedVmState {
         let guard = interp.heap.create_guard();
 
         // Guard all objects in registers
@@ -1799,6 +2160,9 @@ impl BytecodeVM {
         if let JsValue::Object(obj) = &self.this_value {
             guard.guard(obj.cheap_clone());
         }
+        if let Some(ctor) = &self.current_constructor {
+            guard.guard(ctor.cheap_clone());
+        }
 
         // Guard exception_value if it's an object
         // (exception_value is already Guarded, but we also add it to the saved state guard
@@ -1816,6 +2180,14 @@ impl BytecodeVM {
             guard.guard(env.cheap_clone());
         }
 
+        // Guard the arguments and new.target of the running frame, which may
+        // be a constructor whose instance nothing else reaches yet
+        for val in self.arguments.iter().chain([&self.new_target]) {
+            if let JsValue::Object(obj) = val {
+                guard.guard(obj.cheap_clone());
+            }
+        }
+
         // Guard all objects in trampoline stack and convert to SavedTrampolineFrame
         let saved_trampoline_stack: Vec<SavedTrampolineFrame> = self
             .trampoline_stack
@@ -1840,6 +2212,11 @@ impl BytecodeVM {
                 if let Some(ref obj) = frame.construct_new_obj {
                     guard.guard(obj.cheap_clone());
                 }
+                for val in frame.arguments.iter().chain([&frame.new_target]) {
+                    if let JsValue::Object(obj) = val {
+                        guard.guard(obj.cheap_clone());
+                    }
+                }
 
                 SavedTrampolineFrame {
                     ip: frame.ip,
@@ -1870,6 +2247,10 @@ impl BytecodeVM {
             arguments: self.arguments.clone(),
             new_target: self.new_target.clone(),
             trampoline_stack: saved_trampoline_stack,
+            this_value: Some(self.this_value.clone()),
+            current_constructor: self.current_constructor.clone(),
+            saved_env_stack: self.saved_env_stack.clone(),
+            env_guards: self.suspend_env_guards(interp),
         }
     }
 
@@ -1881,10 +2262,14 @@ impl BytecodeVM {
         guard: Guard<JsObject>,
         heap: &crate::gc::Heap<JsObject>,
     ) -> Self {
-        // Guard this_value if it's an object
+        // A frame suspended inside a call keeps its own receiver
+        let this_value = state.this_value.unwrap_or(this_value);
         if let JsValue::Object(obj) = &this_value {
             guard.guard(obj.cheap_clone());
         }
+        if let Some(ctor) = &state.current_constructor {
+            guard.guard(ctor.cheap_clone());
+        }
 
         // Guard all objects in the restored registers
         for val in &state.registers {
@@ -1899,6 +2284,14 @@ impl BytecodeVM {
                 guard.guard(env.cheap_clone());
             }
         }
+        for env in &state.saved_env_stack {
+            guard.guard(env.cheap_clone());
+        }
+        for val in state.arguments.iter().chain([&state.new_target]) {
+            if let JsValue::Object(obj) = val {
+                guard.guard(obj.cheap_clone());
+            }
+        }
 
         // Guard all objects in trampoline stack
         for frame in &state.trampoline_stack {
@@ -1917,6 +2310,15 @@ impl BytecodeVM {
             if let Some(ref ctor) = frame.current_constructor {
                 guard.guard(ctor.cheap_clone());
             }
+            // The instance under construction, before the constructor returns it
+            if let Some(ref obj) = frame.construct_new_obj {
+                guard.guard(obj.cheap_clone());
+            }
+            for val in frame.arguments.iter().chain([&frame.new_target]) {
+                if let JsValue::Object(obj) = val {
+                    guard.guard(obj.cheap_clone());
+                }
+            }
         }
 
         // Convert SavedTrampolineFrame back to TrampolineFrame with new guards
@@ -1949,6 +2351,7 @@ impl BytecodeVM {
                     register_guard: frame_guard,
                     construct_new_obj: saved.construct_new_obj,
                     is_async: saved.is_async,
+                    pooled_env: None,
                 }
             })
             .collect();
@@ -1962,14 +2365,14 @@ impl BytecodeVM {
             try_stack: state.try_stack,
             this_value,
             exception_value: None,
-            saved_env_stack: Vec::new(),
+            saved_env_stack: state.saved_env_stack,
+            resumed_env_guards: state.env_guards,
             arguments: state.arguments,
             new_target: state.new_target,
-            current_constructor: None,
+            current_constructor: state.current_constructor,
             pending_completion: None,
             trampoline_stack,
-            register_pool: Vec::new(),
-            arguments_pool: Vec::new(),
+            pooled_env: None,
         }
     }
 
@@ -1987,6 +2390,7 @@ impl BytecodeVM {
     /// This sets up the VM to handle the exception as if it was thrown at the current position.
     /// Returns true if an exception handler was found, false if the exception should propagate.
     pub fn inject_exception(&mut self, interp: &mut Interpreter, exception: JsValue) -> bool {
+        self.resume_env_guards(interp);
         // Create guarded exception value
         let guarded = Guarded::from_value(exception, &interp.heap);
 
@@ -2000,12 +2404,32 @@ impl BytecodeVM {
             }
             true
         } else {
-            // No handler found - store exception for propagation
+            // No handler found - store exception for propagation. The caller
+            // abandons the VM, so its scopes and calls come off the stack
             self.exception_value = Some(guarded);
+            drop(self.suspend_env_guards(interp));
             false
         }
     }
 
+    /// Inject a return completion into the VM for generator.return()
+    /// Enclosing finally blocks run first, as for a `return` at the current position.
+    /// Returns the completion value if the VM finished without running any code.
+    pub fn inject_return(
+        &mut self,
+        interp: &mut Interpreter,
+        value: JsValue,
+    ) -> Result<Option<Guarded>, JsError> {
+        self.resume_env_guards(interp);
+        match self.execute_return(value, interp)? {
+            OpResult::Halt(guarded) => {
+                self.close_scopes(interp);
+                Ok(Some(guarded))
+            }
+            _ => Ok(None),
+        }
+    }
+
     /// Execute a single opcode
     fn execute_op(&mut self, interp: &mut Interpreter, op: Op) -> Result<OpResult, JsError> {
         match op {
@@ -2044,6 +2468,9 @@ impl BytecodeVM {
                             "Cannot load excluded keys as value",
                         ));
                     }
+                    Some(Constant::SwitchTable(_)) => {
+                        return Err(JsError::internal_error("Cannot load switch table as value"));
+                    }
                     None => return Err(JsError::internal_error("Invalid constant index")),
                 };
                 self.set_reg(dst, value);
@@ -2090,11 +2517,13 @@ impl BytecodeVM {
                 let result = match (&left_prim, &right_prim) {
                     (JsValue::String(a), _) => {
                         let right_str = interp.to_js_string(&right_prim);
-                        JsValue::String(a.cheap_clone() + right_str.as_str())
+                        interp.check_string_len(a.len() + right_str.len())?;
+                        JsValue::String(a.cheap_clone() + &right_str)
                     }
                     (_, JsValue::String(b)) => {
                         let left_str = interp.to_js_string(&left_prim);
-                        JsValue::String(left_str + b.as_str())
+                        interp.check_string_len(left_str.len() + b.len())?;
+                        JsValue::String(left_str + b)
                     }
                     _ => JsValue::Number(left_prim.to_number() + right_prim.to_number()),
                 };
@@ -2143,7 +2572,7 @@ impl BytecodeVM {
             Op::Eq { dst, left, right } => {
                 let left_val = self.get_reg(left);
                 let right_val = self.get_reg(right);
-                let result = interp.abstract_equals(left_val, right_val);
+                let result = interp.abstract_equals(left_val, right_val)?;
                 self.set_reg(dst, JsValue::Boolean(result));
                 Ok(OpResult::Continue)
             }
@@ -2151,7 +2580,7 @@ impl BytecodeVM {
             Op::NotEq { dst, left, right } => {
                 let left_val = self.get_reg(left);
                 let right_val = self.get_reg(right);
-                let result = interp.abstract_equals(left_val, right_val);
+                let result = interp.abstract_equals(left_val, right_val)?;
                 self.set_reg(dst, JsValue::Boolean(!result));
                 Ok(OpResult::Continue)
             }
@@ -2173,30 +2602,35 @@ impl BytecodeVM {
             }
 
             Op::Lt { dst, left, right } => {
-                let left_val = self.get_reg(left).to_number();
-                let right_val = self.get_reg(right).to_number();
-                self.set_reg(dst, JsValue::Boolean(left_val < right_val));
+                let left_val = self.get_reg(left);
+                let right_val = self.get_reg(right);
+                let result = interp.abstract_relational_comparison(left_val, right_val, true)?;
+                self.set_reg(dst, JsValue::Boolean(result == Some(true)));
                 Ok(OpResult::Continue)
             }
 
             Op::LtEq { dst, left, right } => {
-                let left_val = self.get_reg(left).to_number();
-                let right_val = self.get_reg(right).to_number();
-                self.set_reg(dst, JsValue::Boolean(left_val <= right_val));
+                let left_val = self.get_reg(left);
+                let right_val = self.get_reg(right);
+                // a <= b is !(b < a), but undefined (NaN) stays false
+                let result = interp.abstract_relational_comparison(right_val, left_val, false)?;
+                self.set_reg(dst, JsValue::Boolean(result == Some(false)));
                 Ok(OpResult::Continue)
             }
 
             Op::Gt { dst, left, right } => {
-                let left_val = self.get_reg(left).to_number();
-                let right_val = self.get_reg(right).to_number();
-                self.set_reg(dst, JsValue::Boolean(left_val > right_val));
+                let left_val = self.get_reg(left);
+                let right_val = self.get_reg(right);
+                let result = interp.abstract_relational_comparison(right_val, left_val, false)?;
+                self.set_reg(dst, JsValue::Boolean(result == Some(true)));
                 Ok(OpResult::Continue)
             }
 
             Op::GtEq { dst, left, right } => {
-                let left_val = self.get_reg(left).to_number();
-                let right_val = self.get_reg(right).to_number();
-                self.set_reg(dst, JsValue::Boolean(left_val >= right_val));
+                let left_val = self.get_reg(left);
+                let right_val = self.get_reg(right);
+                let result = interp.abstract_relational_comparison(left_val, right_val, true)?;
+                self.set_reg(dst, JsValue::Boolean(result == Some(false)));
                 Ok(OpResult::Continue)
             }
 
@@ -2204,43 +2638,43 @@ impl BytecodeVM {
             // Bitwise Operations
             // ═══════════════════════════════════════════════════════════════════════════
             Op::BitAnd { dst, left, right } => {
-                let left_val = self.get_reg(left).to_number() as i32;
-                let right_val = self.get_reg(right).to_number() as i32;
+                let left_val = to_int32(interp.coerce_to_number(self.get_reg(left))?);
+                let right_val = to_int32(interp.coerce_to_number(self.get_reg(right))?);
                 self.set_reg(dst, JsValue::Number((left_val & right_val) as f64));
                 Ok(OpResult::Continue)
             }
 
             Op::BitOr { dst, left, right } => {
-                let left_val = self.get_reg(left).to_number() as i32;
-                let right_val = self.get_reg(right).to_number() as i32;
+                let left_val = to_int32(interp.coerce_to_number(self.get_reg(left))?);
+                let right_val = to_int32(interp.coerce_to_number(self.get_reg(right))?);
                 self.set_reg(dst, JsValue::Number((left_val | right_val) as f64));
                 Ok(OpResult::Continue)
             }
 
             Op::BitXor { dst, left, right } => {
-                let left_val = self.get_reg(left).to_number() as i32;
-                let right_val = self.get_reg(right).to_number() as i32;
+                let left_val = to_int32(interp.coerce_to_number(self.get_reg(left))?);
+                let right_val = to_int32(interp.coerce_to_number(self.get_reg(right))?);
                 self.set_reg(dst, JsValue::Number((left_val ^ right_val) as f64));
                 Ok(OpResult::Continue)
             }
 
             Op::LShift { dst, left, right } => {
-                let left_val = self.get_reg(left).to_number() as i32;
-                let right_val = (self.get_reg(right).to_number() as u32) & 0x1F;
+                let left_val = to_int32(interp.coerce_to_number(self.get_reg(left))?);
+                let right_val = to_uint32(interp.coerce_to_number(self.get_reg(right))?) & 0x1F;
                 self.set_reg(dst, JsValue::Number((left_val << right_val) as f64));
                 Ok(OpResult::Continue)
             }
 
             Op::RShift { dst, left, right } => {
-                let left_val = self.get_reg(left).to_number() as i32;
-                let right_val = (self.get_reg(right).to_number() as u32) & 0x1F;
+                let left_val = to_int32(interp.coerce_to_number(self.get_reg(left))?);
+                let right_val = to_uint32(interp.coerce_to_number(self.get_reg(right))?) & 0x1F;
                 self.set_reg(dst, JsValue::Number((left_val >> right_val) as f64));
                 Ok(OpResult::Continue)
             }
 
             Op::URShift { dst, left, right } => {
-                let left_val = (self.get_reg(left).to_number() as i32) as u32;
-                let right_val = ((self.get_reg(right).to_number() as i32) as u32) & 0x1F;
+                let left_val = to_uint32(interp.coerce_to_number(self.get_reg(left))?);
+                let right_val = to_uint32(interp.coerce_to_number(self.get_reg(right))?) & 0x1F;
                 self.set_reg(dst, JsValue::Number((left_val >> right_val) as f64));
                 Ok(OpResult::Continue)
             }
@@ -2261,14 +2695,18 @@ impl BytecodeVM {
                 let prop_key = interp.property_key_from_value(key);
 
                 // Check if this is a proxy - delegate to proxy_has if so
-                let has_prop = if matches!(obj_ref.borrow().exotic, ExoticObject::Proxy(_)) {
+                let has_prop = if crate::interpreter::builtins::proxy::has_hooks(obj_ref) {
                     crate::interpreter::builtins::proxy::proxy_has(
                         interp,
                         obj_ref.cheap_clone(),
                         &prop_key,
                     )?
                 } else {
-                    obj_ref.borrow().has_own_property(&prop_key)
+                    // Inherited properties count, so prototype swaps are observed
+                    obj_ref
+                        .borrow()
+                        .get_property_descriptor(&prop_key)
+                        .is_some()
                 };
 
                 self.set_reg(dst, JsValue::Boolean(has_prop));
@@ -2373,7 +2811,7 @@ impl BytecodeVM {
             }
 
             Op::BitNot { dst, src } => {
-                let val = self.get_reg(src).to_number() as i32;
+                let val = to_int32(interp.coerce_to_number(self.get_reg(src))?);
                 self.set_reg(dst, JsValue::Number((!val) as f64));
                 Ok(OpResult::Continue)
             }
@@ -2441,11 +2879,31 @@ impl BytecodeVM {
                 Ok(OpResult::Continue)
             }
 
+            Op::SwitchString {
+                value,
+                table,
+                default,
+            } => {
+                let target = match (self.get_reg(value), self.get_constant(table)) {
+                    (JsValue::String(s), Some(Constant::SwitchTable(targets))) => {
+                        targets.get(s).copied().unwrap_or(default)
+                    }
+                    (_, Some(Constant::SwitchTable(_))) => default,
+                    _ => return Err(JsError::internal_error("Invalid switch table constant")),
+                };
+                self.ip = target as usize;
+                Ok(OpResult::Continue)
+            }
+
             // NOTE: review
-            Op::Break { target, try_depth } => self.execute_break(target as usize, try_depth),
+            Op::Break { target, try_depth } => {
+                self.execute_break(interp, target as usize, try_depth)
+            }
 
             // NOTE: review
-            Op::Continue { target, try_depth } => self.execute_continue(target as usize, try_depth),
+            Op::Continue { target, try_depth } => {
+                self.execute_continue(interp, target as usize, try_depth)
+            }
 
             // ═══════════════════════════════════════════════════════════════════════════
             // Variable Access
@@ -2474,10 +2932,34 @@ impl BytecodeVM {
                     .get_string_constant(name)
                     .ok_or_else(|| JsError::internal_error("Invalid variable name constant"))?;
                 let value = self.get_reg(src).clone();
+                if interp.purity.is_some() {
+                    self.note_purity_location(interp);
+                }
                 interp.env_set(&name, value)?;
                 Ok(OpResult::Continue)
             }
 
+            Op::SetFunctionVar { name, src } => {
+                let name = self
+                    .get_string_constant(name)
+                    .ok_or_else(|| JsError::internal_error("Invalid variable name constant"))?;
+                let value = self.get_reg(src).clone();
+                if interp.purity.is_some() {
+                    self.note_purity_location(interp);
+                }
+                // The environment current before this frame's first block scope
+                match self.saved_env_stack.first() {
+                    Some(var_env) => {
+                        let block_env = mem::replace(&mut interp.env, var_env.cheap_clone());
+                        let result = interp.env_set(&name, value);
+                        interp.env = block_env;
+                        result?;
+                    }
+                    None => interp.env_set(&name, value)?,
+                }
+                Ok(OpResult::Continue)
+            }
+
             Op::DeclareVar {
                 name,
                 init,
@@ -2500,16 +2982,19 @@ impl BytecodeVM {
                 Ok(OpResult::Continue)
             }
 
+            Op::DeclareUninitialized { name } => {
+                let name = self
+                    .get_string_constant(name)
+                    .ok_or_else(|| JsError::internal_error("Invalid variable name constant"))?;
+                interp.env_define_uninitialized(name);
+                Ok(OpResult::Continue)
+            }
+
             Op::GetGlobal { dst, name } => {
                 let name = self
                     .get_string_constant(name)
                     .ok_or_else(|| JsError::internal_error("Invalid global name constant"))?;
-                let global = interp.global.cheap_clone();
-                let prop_key = PropertyKey::String(name);
-                let value = global
-                    .borrow()
-                    .get_property(&prop_key)
-                    .unwrap_or(JsValue::Undefined);
+                let value = interp.read_global(&name).unwrap_or(JsValue::Undefined);
                 self.set_reg(dst, value);
                 Ok(OpResult::Continue)
             }
@@ -2519,7 +3004,12 @@ impl BytecodeVM {
                     .get_string_constant(name)
                     .ok_or_else(|| JsError::internal_error("Invalid global name constant"))?;
                 let value = self.get_reg(src).clone();
+                interp.install_builtin(name.as_str());
                 let global = interp.global.clone();
+                if interp.purity.is_some() {
+                    self.note_purity_location(interp);
+                    interp.check_write(&global, Some(&PropertyKey::String(name.cheap_clone())))?;
+                }
                 global
                     .borrow_mut()
                     .set_property(PropertyKey::String(name), value);
@@ -2532,6 +3022,9 @@ impl BytecodeVM {
             Op::CreateObject { dst } => {
                 let guard = interp.heap.create_guard();
                 let obj = interp.create_object(&guard);
+                if interp.provenance.is_some() {
+                    self.note_creation_site(interp, &obj);
+                }
                 self.set_reg(dst, JsValue::Object(obj));
                 Ok(OpResult::Continue)
             }
@@ -2543,6 +3036,9 @@ impl BytecodeVM {
                 }
                 let guard = interp.heap.create_guard();
                 let arr = interp.create_array_from(&guard, elements);
+                if interp.provenance.is_some() {
+                    self.note_creation_site(interp, &arr);
+                }
                 self.set_reg(dst, JsValue::Object(arr));
                 Ok(OpResult::Continue)
             }
@@ -2550,6 +3046,9 @@ impl BytecodeVM {
             Op::GetProperty { dst, obj, key } => {
                 let obj_val = self.get_reg(obj);
                 let key_val = self.get_reg(key);
+                if obj_val.is_null_or_undefined() {
+                    return Err(self.nullish_access_error(interp, obj_val, key_val, false));
+                }
                 let Guarded { value, .. } = self.get_property_value(interp, obj_val, key_val)?;
                 self.set_reg(dst, value);
                 Ok(OpResult::Continue)
@@ -2561,6 +3060,9 @@ impl BytecodeVM {
                     .get_string_constant(key)
                     .ok_or_else(|| JsError::internal_error("Invalid property key constant"))?;
                 let key_val = JsValue::String(key);
+                if obj_val.is_null_or_undefined() {
+                    return Err(self.nullish_access_error(interp, obj_val, &key_val, false));
+                }
                 let Guarded { value, .. } = self.get_property_value(interp, obj_val, &key_val)?;
                 self.set_reg(dst, value);
                 Ok(OpResult::Continue)
@@ -2569,6 +3071,9 @@ impl BytecodeVM {
             Op::SetProperty { obj, key, value } => {
                 let obj_val = self.get_reg(obj);
                 let key_val = self.get_reg(key);
+                if obj_val.is_null_or_undefined() {
+                    return Err(self.nullish_access_error(interp, obj_val, key_val, true));
+                }
                 let val = self.get_reg(value).clone();
                 self.set_property_value(interp, obj_val, key_val, val)?;
                 Ok(OpResult::Continue)
@@ -2581,6 +3086,9 @@ impl BytecodeVM {
                     .ok_or_else(|| JsError::internal_error("Invalid property key constant"))?;
                 let val = self.get_reg(value).clone();
                 let key_val = JsValue::String(key);
+                if obj_val.is_null_or_undefined() {
+                    return Err(self.nullish_access_error(interp, obj_val, &key_val, true));
+                }
                 self.set_property_value(interp, obj_val, &key_val, val)?;
                 Ok(OpResult::Continue)
             }
@@ -2609,17 +3117,17 @@ impl BytecodeVM {
                                 )?;
                             self.set_reg(dst, JsValue::Boolean(result));
                         } else {
+                            if interp.purity.is_some() {
+                                self.note_purity_location(interp);
+                                interp.check_write(obj_ref, Some(&prop_key))?;
+                            }
+
                             // Check if property is configurable before deleting
-                            {
-                                let obj_borrowed = obj_ref.borrow();
-                                if let Some(prop) = obj_borrowed.properties.get(&prop_key)
-                                    && !prop.configurable()
-                                {
-                                    return Err(JsError::type_error(format!(
-                                        "Cannot delete property '{}' of object",
-                                        prop_key
-                                    )));
-                                }
+                            if obj_ref.borrow().is_non_configurable_own(&prop_key) {
+                                return Err(JsError::type_error(format!(
+                                    "Cannot delete property '{}' of object",
+                                    prop_key
+                                )));
                             }
 
                             // For arrays, handle index deletion specially
@@ -2679,17 +3187,17 @@ impl BytecodeVM {
                                 )?;
                             self.set_reg(dst, JsValue::Boolean(result));
                         } else {
+                            if interp.purity.is_some() {
+                                self.note_purity_location(interp);
+                                interp.check_write(obj_ref, Some(&prop_key))?;
+                            }
+
                             // Check if property is configurable before deleting
-                            {
-                                let obj_borrowed = obj_ref.borrow();
-                                if let Some(prop) = obj_borrowed.properties.get(&prop_key)
-                                    && !prop.configurable()
-                                {
-                                    return Err(JsError::type_error(format!(
-                                        "Cannot delete property '{}' of object",
-                                        prop_key
-                                    )));
-                                }
+                            if obj_ref.borrow().is_non_configurable_own(&prop_key) {
+                                return Err(JsError::type_error(format!(
+                                    "Cannot delete property '{}' of object",
+                                    prop_key
+                                )));
                             }
 
                             obj_ref.borrow_mut().properties.remove(&prop_key);
@@ -2734,8 +3242,11 @@ impl BytecodeVM {
                 args_start,
                 argc,
             } => {
+                if let Some(err) = self.named_call_error(interp, callee, this) {
+                    return Err(err);
+                }
                 // Acquire args vec first (mutable borrow), then get register values
-                let mut args = self.acquire_arguments_vec(argc as usize);
+                let mut args = interp.call_pool.acquire_arguments(argc as usize);
                 for i in 0..argc {
                     args.push(self.get_reg(args_start + i).clone());
                 }
@@ -2771,19 +3282,12 @@ impl BytecodeVM {
             } => {
                 // CallSpread: args_start points to an array of arguments
                 // We extract the array elements and call the function with them
+                if let Some(err) = self.named_call_error(interp, callee, this) {
+                    return Err(err);
+                }
                 let callee_val = self.get_reg(callee).clone();
                 let this_val = self.get_reg(this).clone();
-                let args_val = self.get_reg(args_start).clone();
-
-                let args: Vec<JsValue> = if let JsValue::Object(arr_ref) = &args_val {
-                    if let Some(elems) = arr_ref.borrow().array_elements() {
-                        elems.to_vec()
-                    } else {
-                        Vec::new()
-                    }
-                } else {
-                    Vec::new()
-                };
+                let args = self.spread_arguments(interp, args_start)?;
 
                 // Create guard and protect all object values
                 let guard = interp.heap.create_guard();
@@ -2840,7 +3344,7 @@ impl BytecodeVM {
                 argc,
             } => {
                 // Acquire args vec first (mutable borrow), then get register values
-                let mut args = self.acquire_arguments_vec(argc as usize);
+                let mut args = interp.call_pool.acquire_arguments(argc as usize);
                 for i in 0..argc {
                     args.push(self.get_reg(args_start + i).clone());
                 }
@@ -2889,7 +3393,7 @@ impl BytecodeVM {
                 argc,
             } => {
                 // Acquire args vec first (mutable borrow), then get register values
-                let mut args = self.acquire_arguments_vec(argc as usize);
+                let mut args = interp.call_pool.acquire_arguments(argc as usize);
                 for i in 0..argc {
                     args.push(self.get_reg(args_start + i).clone());
                 }
@@ -2960,18 +3464,8 @@ impl BytecodeVM {
                 argc: _,
             } => {
                 // ConstructSpread: args_start points to an array of arguments
+                let args = self.spread_arguments(interp, args_start)?;
                 let callee_val = self.get_reg(callee);
-                let args_val = self.get_reg(args_start);
-
-                let args: Vec<JsValue> = if let JsValue::Object(arr_ref) = &args_val {
-                    if let Some(elems) = arr_ref.borrow().array_elements() {
-                        elems.to_vec()
-                    } else {
-                        Vec::new()
-                    }
-                } else {
-                    Vec::new()
-                };
 
                 // Inline constructor call logic (same as Construct)
                 let JsValue::Object(ctor) = callee_val else {
@@ -3167,6 +3661,8 @@ impl BytecodeVM {
             // ═══════════════════════════════════════════════════════════════════════════
             Op::Throw { value } => {
                 let val = self.get_reg(value).clone();
+                let thrown = Guarded::from_value(val.clone(), &interp.heap);
+                interp.last_throw = Some((thrown, self.build_stack_trace()));
                 let guarded = Guarded::from_value(val, &interp.heap);
                 Err(JsError::ThrownValue { guarded })
             }
@@ -3226,11 +3722,11 @@ impl BytecodeVM {
                         }
                         PendingCompletion::Break { target, try_depth } => {
                             // Continue with the break (recursively handles nested finally blocks)
-                            return self.execute_break(target, try_depth);
+                            return self.execute_break(interp, target, try_depth);
                         }
                         PendingCompletion::Continue { target, try_depth } => {
                             // Continue with the continue (recursively handles nested finally blocks)
-                            return self.execute_continue(target, try_depth);
+                            return self.execute_continue(interp, target, try_depth);
                         }
                     }
                 }
@@ -3310,6 +3806,49 @@ impl BytecodeVM {
                     }
                 }
 
+                // Other objects go through a promise resolved with them, which
+                // adopts the state of a thenable and fulfills with anything else
+                if let JsValue::Object(obj) = promise_val {
+                    let obj = obj.cheap_clone();
+                    let guard = interp.heap.create_guard();
+                    let promises_before = interp.promise_tracker.created();
+                    let adopted = super::builtins::promise::create_promise(interp, &guard);
+                    if interp.provenance.is_some() {
+                        self.note_promise_sites(interp, promises_before);
+                    }
+                    super::builtins::promise::resolve_promise_value(
+                        interp,
+                        &adopted,
+                        JsValue::Object(obj),
+                    )?;
+                    let settled = match &adopted.borrow().exotic {
+                        ExoticObject::Promise(state) => {
+                            let state = state.borrow();
+                            let result = state.result.clone().unwrap_or(JsValue::Undefined);
+                            match state.status {
+                                PromiseStatus::Pending => None,
+                                PromiseStatus::Fulfilled => Some(Ok(result)),
+                                PromiseStatus::Rejected => Some(Err(result)),
+                            }
+                        }
+                        _ => None,
+                    };
+                    return match settled {
+                        Some(Ok(value)) => {
+                            self.set_reg(dst, value);
+                            Ok(OpResult::Continue)
+                        }
+                        Some(Err(reason)) => {
+                            let guarded = Guarded::from_value(reason, &interp.heap);
+                            Err(JsError::thrown(guarded))
+                        }
+                        None => Ok(OpResult::Suspend {
+                            promise: Guarded::with_guard(JsValue::Object(adopted), guard),
+                            resume_register: dst,
+                        }),
+                    };
+                }
+
                 // Not a promise or pending order - treat as resolved value (await 42 === 42)
                 self.set_reg(dst, promise_val.clone());
                 Ok(OpResult::Continue)
@@ -3355,7 +3894,12 @@ impl BytecodeVM {
             // Iteration
             // ═══════════════════════════════════════════════════════════════════════════
             Op::GetIterator { dst, obj } => {
-                let obj_val = self.get_reg(obj);
+                // String wrappers iterate the characters of the string they box
+                let obj_val = match self.get_reg(obj).string_wrapper_value() {
+                    Some(s) => JsValue::String(s),
+                    None => self.get_reg(obj).clone(),
+                };
+                let obj_val = &obj_val;
 
                 // For arrays and strings, create an internal array iterator
                 // The iterator is stored as an object with internal index state
@@ -3393,7 +3937,7 @@ impl BytecodeVM {
                                 )?;
                                 self.set_reg(dst, value);
                             } else {
-                                return Err(JsError::type_error("Object is not iterable"));
+                                return Err(not_iterable_error(interp, obj_val));
                             }
                             return Ok(OpResult::Continue);
                         }
@@ -3438,7 +3982,7 @@ impl BytecodeVM {
                             )?;
                             self.set_reg(dst, value);
                         } else {
-                            return Err(JsError::type_error("Object is not iterable"));
+                            return Err(not_iterable_error(interp, obj_val));
                         }
                     }
                     JsValue::String(s) => {
@@ -3455,8 +3999,8 @@ impl BytecodeVM {
                         );
                         self.set_reg(dst, JsValue::Object(iter));
                     }
-                    _ => {
-                        return Err(JsError::type_error("Object is not iterable"));
+                    other => {
+                        return Err(not_iterable_error(interp, other));
                     }
                 }
                 Ok(OpResult::Continue)
@@ -3469,7 +4013,7 @@ impl BytecodeVM {
                 let keys: Vec<JsValue> = match obj_val {
                     JsValue::Object(obj_ref) => {
                         // Check if this is a proxy - use proxy_own_keys if so
-                        let is_proxy = matches!(obj_ref.borrow().exotic, ExoticObject::Proxy(_));
+                        let is_proxy = crate::interpreter::builtins::proxy::has_hooks(obj_ref);
 
                         if is_proxy {
                             // Get keys through proxy trap
@@ -3491,7 +4035,7 @@ impl BytecodeVM {
                             } else {
                                 Vec::new()
                             }
-                        } else {
+                        } else if obj_ref.borrow().array_elements().is_some() {
                             let obj_borrowed = obj_ref.borrow();
                             let mut result = Vec::new();
 
@@ -3502,28 +4046,27 @@ impl BytecodeVM {
                                 }
                             }
 
-                            // Then add own enumerable property keys (excluding indices already added)
+                            // Then add own enumerable string keys (indices are already added)
                             for (k, prop) in obj_borrowed.properties.iter() {
-                                // Only include enumerable properties
-                                if !prop.enumerable() {
-                                    continue;
-                                }
-                                match k {
-                                    PropertyKey::String(s) => {
-                                        result.push(JsValue::String(s.cheap_clone()));
-                                    }
-                                    PropertyKey::Index(i) => {
-                                        // Only add if not an array (arrays already handled above)
-                                        if obj_borrowed.array_elements().is_none() {
-                                            result.push(JsValue::String(JsString::from(
-                                                i.to_string(),
-                                            )));
-                                        }
-                                    }
-                                    _ => {} // Skip symbols for for-in
+                                if let PropertyKey::String(s) = k
+                                    && prop.enumerable()
+                                {
+                                    result.push(JsValue::String(s.cheap_clone()));
                                 }
                             }
                             result
+                        } else {
+                            // Own enumerable keys, skipping symbols
+                            let keys = obj_ref.borrow_mut().enumerable_own_keys();
+                            keys.into_iter()
+                                .filter_map(|k| match k {
+                                    PropertyKey::String(s) => Some(JsValue::String(s)),
+                                    PropertyKey::Index(i) => {
+                                        Some(JsValue::String(JsString::from(i.to_string())))
+                                    }
+                                    PropertyKey::Symbol(_) => None,
+                                })
+                                .collect()
                         }
                     }
                     JsValue::String(s) => {
@@ -3913,35 +4456,13 @@ impl BytecodeVM {
             }
 
             Op::IteratorClose { iterator } => {
-                let iter_val = self.get_reg(iterator);
-
-                // Check if iterator has a return() method
-                if let JsValue::Object(iter_obj) = iter_val {
-                    // For internal array/string/keys iterators, there's no return method
-                    // Only check for custom iterators that have a 'return' property
-                    let return_key = PropertyKey::String(interp.intern("return"));
-                    let return_method = iter_obj.borrow().get_property(&return_key);
-
-                    if let Some(JsValue::Object(return_fn)) = return_method {
-                        // Call the return method
-                        // Per ES spec 7.4.6: If Type(innerResult.[[value]]) is not Object,
-                        // throw a TypeError exception.
-                        let Guarded {
-                            value,
-                            guard: _guard,
-                        } = interp.call_function(
-                            JsValue::Object(return_fn),
-                            JsValue::Object(iter_obj.clone()),
-                            &[],
-                        )?;
-
-                        // Check that result is an object
-                        if !matches!(value, JsValue::Object(_)) {
-                            return Err(JsError::type_error("Iterator result is not an object"));
-                        }
-                    }
-                }
+                self.close_iterator(interp, iterator)?;
+                Ok(OpResult::Continue)
+            }
 
+            Op::IteratorCloseOnThrow { iterator } => {
+                // The exception being rethrown wins over anything return() does
+                let _ = self.close_iterator(interp, iterator);
                 Ok(OpResult::Continue)
             }
 
@@ -3980,10 +4501,16 @@ impl BytecodeVM {
                         prototype.borrow_mut().prototype = Some(super_proto.cheap_clone());
                     }
 
-                    // Store __super__ on constructor for super() calls
-                    ctor_obj.borrow_mut().set_property(
+                    // Store __super__ on constructor for super() calls; the internal links
+                    // are non-enumerable so for-in and Object.keys never see them
+                    ctor_obj.borrow_mut().define_property(
                         PropertyKey::String(interp.intern("__super__")),
-                        JsValue::Object(super_ctor.cheap_clone()),
+                        Property::with_attributes(
+                            JsValue::Object(super_ctor.cheap_clone()),
+                            true,
+                            false,
+                            true,
+                        ),
                     );
 
                     // Store __super_target__ for super.x property access
@@ -3991,9 +4518,9 @@ impl BytecodeVM {
                         .borrow()
                         .get_property(&PropertyKey::String(interp.intern("prototype")))
                     {
-                        ctor_obj.borrow_mut().set_property(
+                        ctor_obj.borrow_mut().define_property(
                             PropertyKey::String(interp.intern("__super_target__")),
-                            sp,
+                            Property::with_attributes(sp, true, false, true),
                         );
                     }
                 }
@@ -4045,24 +4572,25 @@ impl BytecodeVM {
                     // Copy __super__ from class constructor
                     let super_key = PropertyKey::String(interp.intern("__super__"));
                     if let Some(super_val) = class_obj.borrow().get_property(&super_key) {
-                        method_obj
-                            .borrow_mut()
-                            .set_property(super_key.clone(), super_val.clone());
+                        method_obj.borrow_mut().define_property(
+                            super_key.clone(),
+                            Property::with_attributes(super_val.clone(), true, false, true),
+                        );
 
                         // For static methods, __super_target__ = parent constructor (__super__)
                         // For instance methods, __super_target__ = parent prototype (from class)
                         if is_static {
-                            method_obj.borrow_mut().set_property(
+                            method_obj.borrow_mut().define_property(
                                 PropertyKey::String(interp.intern("__super_target__")),
-                                super_val,
+                                Property::with_attributes(super_val, true, false, true),
                             );
                         } else if let Some(super_target) = class_obj
                             .borrow()
                             .get_property(&PropertyKey::String(interp.intern("__super_target__")))
                         {
-                            method_obj.borrow_mut().set_property(
+                            method_obj.borrow_mut().define_property(
                                 PropertyKey::String(interp.intern("__super_target__")),
-                                super_target,
+                                Property::with_attributes(super_target, true, false, true),
                             );
                         }
                     }
@@ -4102,6 +4630,7 @@ impl BytecodeVM {
                 getter,
                 setter,
                 is_static,
+                enumerable,
             } => {
                 let class_val = self.get_reg(class);
                 let JsValue::Object(class_obj) = class_val else {
@@ -4157,8 +4686,9 @@ impl BytecodeVM {
                 let final_getter = new_getter.cloned().or(existing_getter);
                 let final_setter = new_setter.cloned().or(existing_setter);
 
-                // Create accessor property
-                let property = Property::accessor(final_getter, final_setter);
+                // Class accessors are non-enumerable, object literal ones enumerable
+                let mut property = Property::accessor(final_getter, final_setter);
+                property.set_enumerable(enumerable);
                 target.borrow_mut().define_property(prop_key, property);
 
                 Ok(OpResult::Continue)
@@ -4178,40 +4708,39 @@ impl BytecodeVM {
                 let method_val = self.get_reg(method);
                 let key_val = self.get_reg(key);
 
-                // Convert key to string for property name
-                let method_name = interp.to_js_string(key_val);
+                // Symbol keys stay symbols; the method is named after the key
+                let prop_key = interp.property_key_from_value(key_val);
+                set_function_name(interp, method_val, function_name_for_key(&prop_key, ""));
 
                 // Store __super__ and __super_target__ on method for super access
                 if let JsValue::Object(method_obj) = &method_val {
                     // Copy __super__ from class constructor
                     let super_key = PropertyKey::String(interp.intern("__super__"));
                     if let Some(super_val) = class_obj.borrow().get_property(&super_key) {
-                        method_obj
-                            .borrow_mut()
-                            .set_property(super_key.clone(), super_val.clone());
+                        method_obj.borrow_mut().define_property(
+                            super_key.clone(),
+                            Property::with_attributes(super_val.clone(), true, false, true),
+                        );
 
                         // For static methods, __super_target__ = parent constructor (__super__)
                         // For instance methods, __super_target__ = parent prototype (from class)
                         if is_static {
-                            method_obj.borrow_mut().set_property(
+                            method_obj.borrow_mut().define_property(
                                 PropertyKey::String(interp.intern("__super_target__")),
-                                super_val,
+                                Property::with_attributes(super_val, true, false, true),
                             );
                         } else if let Some(super_target) = class_obj
                             .borrow()
                             .get_property(&PropertyKey::String(interp.intern("__super_target__")))
                         {
-                            method_obj.borrow_mut().set_property(
+                            method_obj.borrow_mut().define_property(
                                 PropertyKey::String(interp.intern("__super_target__")),
-                                super_target,
+                                Property::with_attributes(super_target, true, false, true),
                             );
                         }
                     }
                 }
 
-                // Use from_value to handle numeric string keys correctly (e.g., "2" -> Index(2))
-                let prop_key = interp.property_key_from_value(&JsValue::String(method_name));
-
                 if is_static {
                     // Add to class constructor directly
                     // Methods are non-enumerable, writable, configurable (per spec)
@@ -4242,6 +4771,7 @@ impl BytecodeVM {
                 getter,
                 setter,
                 is_static,
+                enumerable,
             } => {
                 let class_val = self.get_reg(class);
                 let JsValue::Object(class_obj) = class_val else {
@@ -4252,8 +4782,10 @@ impl BytecodeVM {
                 let setter_val = self.get_reg(setter);
                 let key_val = self.get_reg(key);
 
-                // Convert key to string for accessor name
-                let accessor_name = interp.to_js_string(key_val);
+                // Symbol keys stay symbols; accessors are named `get <key>` / `set <key>`
+                let prop_key = interp.property_key_from_value(key_val);
+                set_function_name(interp, getter_val, function_name_for_key(&prop_key, "get "));
+                set_function_name(interp, setter_val, function_name_for_key(&prop_key, "set "));
 
                 // Extract function objects (undefined means keep existing)
                 let new_getter = if let JsValue::Object(g) = getter_val {
@@ -4282,8 +4814,6 @@ impl BytecodeVM {
                 };
 
                 // Get existing accessor property if any
-                // Use from_value to handle numeric string keys correctly (e.g., "2" -> Index(2))
-                let prop_key = interp.property_key_from_value(&JsValue::String(accessor_name));
                 let (existing_getter, existing_setter) = {
                     let target_ref = target.borrow();
                     if let Some(prop) = target_ref.properties.get(&prop_key) {
@@ -4297,8 +4827,9 @@ impl BytecodeVM {
                 let final_getter = new_getter.cloned().or(existing_getter);
                 let final_setter = new_setter.cloned().or(existing_setter);
 
-                // Create accessor property
-                let property = Property::accessor(final_getter, final_setter);
+                // Class accessors are non-enumerable, object literal ones enumerable
+                let mut property = Property::accessor(final_getter, final_setter);
+                property.set_enumerable(enumerable);
                 target.borrow_mut().define_property(prop_key, property);
 
                 Ok(OpResult::Continue)
@@ -4312,7 +4843,7 @@ impl BytecodeVM {
                 // Get the current function's __super__ property (parent constructor)
                 let super_ctor = self.get_super_constructor(interp)?;
 
-                let mut args = self.acquire_arguments_vec(argc as usize);
+                let mut args = interp.call_pool.acquire_arguments(argc as usize);
                 for i in 0..argc {
                     args.push(self.get_reg(args_start + i).clone());
                 }
@@ -4343,16 +4874,7 @@ impl BytecodeVM {
                 let super_ctor = self.get_super_constructor(interp)?;
 
                 // Extract arguments from the array
-                let args_val = self.get_reg(args_array);
-                let args: Vec<JsValue> = if let JsValue::Object(arr_ref) = args_val {
-                    if let Some(elems) = arr_ref.borrow().array_elements() {
-                        elems.to_vec()
-                    } else {
-                        Vec::new()
-                    }
-                } else {
-                    Vec::new()
-                };
+                let args = self.spread_arguments(interp, args_array)?;
 
                 // Create guard and protect all object values
                 let guard = interp.heap.create_guard();
@@ -4898,8 +5420,9 @@ impl BytecodeVM {
 
                 // Define the accessor property on target
                 let prop_key = PropertyKey::String(accessor_name.cheap_clone());
-                let property =
+                let mut property =
                     Property::accessor(Some(getter.cheap_clone()), Some(setter.cheap_clone()));
+                property.set_enumerable(false);
                 target.borrow_mut().define_property(prop_key, property);
 
                 // Create target object { get, set } for decorators
@@ -5148,25 +5671,27 @@ impl BytecodeVM {
 
                 if let JsValue::Object(src_obj) = src_val {
                     // Copy all enumerable own properties except excluded ones
+                    let keys = src_obj.borrow_mut().enumerable_own_keys();
                     let src_borrowed = src_obj.borrow();
-                    for (key, prop) in src_borrowed.properties.iter() {
-                        // Skip non-enumerable properties
-                        if !prop.enumerable() {
+                    for key in &keys {
+                        let Some(value) = own_enumerable_value(&src_borrowed, key) else {
                             continue;
-                        }
+                        };
 
                         // Check if this key should be excluded
                         let should_exclude = match key {
                             PropertyKey::String(s) => {
                                 excluded.iter().any(|k| k.as_str() == s.as_str())
                             }
-                            PropertyKey::Symbol(_) | PropertyKey::Index(_) => false,
+                            PropertyKey::Index(idx) => {
+                                let idx = idx.to_string();
+                                excluded.iter().any(|k| k.as_str() == idx)
+                            }
+                            PropertyKey::Symbol(_) => false,
                         };
 
                         if !should_exclude {
-                            result
-                                .borrow_mut()
-                                .set_property(key.clone(), prop.value.clone());
+                            result.borrow_mut().set_property(key.clone(), value);
                         }
                     }
                 }
@@ -5180,15 +5705,27 @@ impl BytecodeVM {
                 let dst_val = self.get_reg(dst);
                 let src_val = self.get_reg(src);
 
-                if let (JsValue::Object(dst_obj), JsValue::Object(src_obj)) = (&dst_val, &src_val) {
+                if let (JsValue::Object(dst_obj), JsValue::String(s)) = (&dst_val, &src_val) {
+                    // A string spreads its characters, as its wrapper would
+                    let mut dst_borrowed = dst_obj.borrow_mut();
+                    for (i, c) in s.as_str().chars().enumerate() {
+                        dst_borrowed.set_property(
+                            PropertyKey::Index(i as u32),
+                            JsValue::String(JsString::from(c.to_string())),
+                        );
+                    }
+                } else if let (JsValue::Object(dst_obj), JsValue::Object(src_obj)) =
+                    (&dst_val, &src_val)
+                {
                     // Collect properties first to avoid borrow issues
                     let props_to_copy: Vec<_> = {
-                        let src_borrowed = src_obj.borrow();
-                        src_borrowed
-                            .properties
-                            .iter()
-                            .filter(|(_, prop)| prop.enumerable())
-                            .map(|(key, prop)| (key.clone(), prop.value.clone()))
+                        let mut src_borrowed = src_obj.borrow_mut();
+                        let keys = src_borrowed.enumerable_own_keys();
+                        keys.into_iter()
+                            .filter_map(|key| {
+                                let value = own_enumerable_value(&src_borrowed, &key)?;
+                                Some((key, value))
+                            })
                             .collect()
                     };
 
@@ -5206,34 +5743,16 @@ impl BytecodeVM {
             // ═══════════════════════════════════════════════════════════════════════════
             Op::TemplateConcat { dst, start, count } => {
                 let mut result = String::new();
-                let to_string_key = PropertyKey::String(interp.intern("toString"));
                 for i in 0..count {
                     let val = self.get_reg(start + i);
-                    // For objects, call toString method; for primitives, use to_js_string
-                    let str_val = if let JsValue::Object(obj) = &val {
-                        // Check if object has a custom toString method
-                        if let Some(JsValue::Object(func_obj)) =
-                            obj.borrow().get_property(&to_string_key)
-                        {
-                            if func_obj.borrow().is_callable() {
-                                // Call toString()
-                                match interp.call_function(
-                                    JsValue::Object(func_obj.clone()),
-                                    val.clone(),
-                                    &[],
-                                ) {
-                                    Ok(Guarded { value, guard: _ }) => interp.to_js_string(&value),
-                                    Err(_) => interp.to_js_string(val),
-                                }
-                            } else {
-                                interp.to_js_string(val)
-                            }
-                        } else {
-                            interp.to_js_string(val)
-                        }
+                    // Objects go through ToString, so a throwing toString or a
+                    // null-prototype object surfaces as an error
+                    let str_val = if let JsValue::Object(_) = &val {
+                        interp.coerce_to_string(val)?
                     } else {
                         interp.to_js_string(val)
                     };
+                    interp.check_string_len(result.len() + str_val.len())?;
                     result.push_str(str_val.as_str());
                 }
                 self.set_reg(dst, JsValue::String(JsString::from(result)));
@@ -5304,65 +5823,17 @@ impl BytecodeVM {
             // Function Name Inference
             // ═══════════════════════════════════════════════════════════════════════════
             Op::SetFunctionName { func, name } => {
-                let func_val = self.get_reg(func);
                 let name_str = self
                     .get_string_constant(name)
                     .ok_or_else(|| JsError::internal_error("Invalid function name constant"))?;
+                set_function_name(interp, self.get_reg(func), name_str);
+                Ok(OpResult::Continue)
+            }
 
-                // Only set name if value is a function object without a name
-                if let JsValue::Object(obj) = func_val {
-                    let mut obj_ref = obj.borrow_mut();
-
-                    // Check if this is a function and if it doesn't have a name already
-                    let should_set_name =
-                        if let crate::value::ExoticObject::Function(func) = &obj_ref.exotic {
-                            // Check if function already has a non-empty name
-                            let has_name = match func {
-                                JsFunction::Native(f) => !f.name.as_str().is_empty(),
-                                JsFunction::Bytecode(bc)
-                                | JsFunction::BytecodeGenerator(bc)
-                                | JsFunction::BytecodeAsync(bc)
-                                | JsFunction::BytecodeAsyncGenerator(bc) => bc
-                                    .chunk
-                                    .function_info
-                                    .as_ref()
-                                    .and_then(|info| info.name.as_ref())
-                                    .is_some_and(|n| !n.as_str().is_empty()),
-                                JsFunction::Bound(_) => true, // Bound functions already have names
-                                // Internal functions don't need names set
-                                JsFunction::PromiseResolve(_)
-                                | JsFunction::PromiseReject(_)
-                                | JsFunction::PromiseAllFulfill { .. }
-                                | JsFunction::PromiseAllReject(_)
-                                | JsFunction::PromiseRaceSettle { .. }
-                                | JsFunction::AccessorGetter
-                                | JsFunction::AccessorSetter
-                                | JsFunction::ModuleExportGetter { .. }
-                                | JsFunction::ModuleReExportGetter { .. }
-                                | JsFunction::ProxyRevoke(_) => true,
-                            };
-                            // Also check if there's already an own name property set
-                            let name_key = PropertyKey::String(interp.intern("name"));
-                            let has_own_name = obj_ref.get_own_property(&name_key).is_some();
-                            !has_name && !has_own_name
-                        } else {
-                            false // Not a function
-                        };
-
-                    if should_set_name {
-                        let name_key = PropertyKey::String(interp.intern("name"));
-                        obj_ref.define_property(
-                            name_key,
-                            Property::with_attributes(
-                                JsValue::String(name_str),
-                                false, // not writable
-                                false, // not enumerable
-                                true,  // configurable
-                            ),
-                        );
-                    }
-                }
-
+            Op::SetFunctionNameFromKey { func, key } => {
+                let key = interp.property_key_from_value(self.get_reg(key));
+                let name_str = function_name_for_key(&key, "");
+                set_function_name(interp, self.get_reg(func), name_str);
                 Ok(OpResult::Continue)
             }
 
@@ -5391,6 +5862,7 @@ impl BytecodeVM {
             }
 
             Op::LoadThis { dst } => {
+                interp.expose_global(&self.this_value);
                 self.set_reg(dst, self.this_value.clone());
                 Ok(OpResult::Continue)
             }
@@ -5715,6 +6187,82 @@ impl BytecodeVM {
         }
     }
 
+    /// TypeError for reading or setting a property of null or undefined, e.g.
+    /// `Cannot read properties of undefined (reading 'replicas' of 'config.spec')`.
+    /// The object expression is named when the compiler recorded it.
+    fn nullish_access_error(
+        &self,
+        interp: &mut Interpreter,
+        obj: &JsValue,
+        key: &JsValue,
+        is_set: bool,
+    ) -> JsError {
+        let (verb, gerund) = if is_set {
+            ("set", "setting")
+        } else {
+            ("read", "reading")
+        };
+        let target = if obj.is_null() { "null" } else { "undefined" };
+        let key = interp.property_key_from_value(key);
+        if interp.flight_recorder.is_some() {
+            let subject = FlightSubject::Key(key.clone());
+            self.note_flight(interp, FlightEventKind::MissingProperty, subject);
+        }
+        let message = match self.current_operand_name() {
+            Some(name) => format!(
+                "Cannot {} properties of {} ({} '{}' of '{}')",
+                verb, target, gerund, key, name
+            ),
+            None => format!(
+                "Cannot {} properties of {} ({} '{}')",
+                verb, target, gerund, key
+            ),
+        };
+        JsError::type_error(message)
+    }
+
+    /// TypeError for calling a non-callable value the compiler named, e.g.
+    /// `config.render is not a function`, or for calling an export the
+    /// imported module doesn't provide. Unnamed callees are left to the
+    /// generic check in the call path.
+    fn named_call_error(
+        &self,
+        interp: &Interpreter,
+        callee: Register,
+        this: Register,
+    ) -> Option<JsError> {
+        use crate::value::ExoticObject;
+
+        if let JsValue::Object(obj) = self.get_reg(callee)
+            && matches!(
+                obj.borrow().exotic,
+                ExoticObject::Function(_)
+                    | ExoticObject::Proxy(_)
+                    | ExoticObject::Host(crate::interpreter::host_class::HostObjectData {
+                        callable: true,
+                        ..
+                    })
+            )
+        {
+            return None;
+        }
+        let name = self.current_operand_name()?;
+        // A missing export reads as undefined
+        let missing_export = match self.get_reg(callee) {
+            JsValue::Undefined => interp.missing_export_message(&name, self.get_reg(this)),
+            _ => None,
+        };
+        let message = missing_export.unwrap_or_else(|| format!("{} is not a function", name));
+        Some(JsError::type_error(message))
+    }
+
+    /// Source-level name the compiler recorded for the current instruction's operand
+    fn current_operand_name(&self) -> Option<JsString> {
+        self.chunk
+            .get_operand_name(self.ip.saturating_sub(1))
+            .cloned()
+    }
+
     /// Get a property value from an object, invoking getters if present.
     /// Returns a Guarded to keep newly allocated objects alive (e.g., from getters or proxies).
     fn get_property_value(
@@ -5723,10 +6271,13 @@ impl BytecodeVM {
         obj: &JsValue,
         key: &JsValue,
     ) -> Result<Guarded, JsError> {
+        if interp.audit_log.is_some() {
+            interp.audit_property_read(obj, key);
+        }
         match obj {
             JsValue::Object(obj_ref) => {
                 // Check if this is a proxy - delegate to proxy_get if so
-                if matches!(obj_ref.borrow().exotic, ExoticObject::Proxy(_)) {
+                if crate::interpreter::builtins::proxy::has_hooks(obj_ref) {
                     let prop_key = interp.property_key_from_value(key);
                     // proxy_get already returns Guarded
                     return crate::interpreter::builtins::proxy::proxy_get(
@@ -5737,20 +6288,6 @@ impl BytecodeVM {
                     );
                 }
 
-                // Handle __proto__ special property - return prototype
-                if let JsValue::String(k) = key
-                    && k.as_str() == "__proto__"
-                {
-                    return Ok(Guarded::unguarded(
-                        obj_ref
-                            .borrow()
-                            .prototype
-                            .as_ref()
-                            .map(|p| JsValue::Object(p.clone()))
-                            .unwrap_or(JsValue::Null),
-                    ));
-                }
-
                 let prop_key = interp.property_key_from_value(key);
                 // Get property descriptor to check for accessor properties
                 let prop_desc = obj_ref.borrow().get_property_descriptor(&prop_key);
@@ -5765,46 +6302,51 @@ impl BytecodeVM {
                         }
                     }
                     Some((prop, _)) => Ok(Guarded::unguarded(prop.value.clone())),
-                    None => Ok(Guarded::unguarded(JsValue::Undefined)),
+                    None => match interp
+                        .failed_module_error(obj_ref)
+                        .or_else(|| interp.strict_read_error(obj_ref, &prop_key))
+                    {
+                        Some(error) => Err(error),
+                        None => {
+                            if interp.flight_recorder.is_some() {
+                                let subject = FlightSubject::Key(prop_key);
+                                self.note_flight(interp, FlightEventKind::MissingProperty, subject);
+                            }
+                            Ok(Guarded::unguarded(JsValue::Undefined))
+                        }
+                    },
                 }
             }
-            JsValue::String(s) => match key {
-                JsValue::String(k) if k.as_str() == "length" => Ok(Guarded::unguarded(
-                    JsValue::Number(s.as_str().chars().count() as f64),
-                )),
-                JsValue::Number(n) => {
-                    let idx = *n as usize;
-                    if let Some(c) = s.as_str().chars().nth(idx) {
-                        return Ok(Guarded::unguarded(JsValue::String(JsString::from(
-                            c.to_string(),
-                        ))));
-                    }
-                    Ok(Guarded::unguarded(JsValue::Undefined))
+            JsValue::String(s) => {
+                // Only canonical index strings read a character: "1" does, "01" doesn't
+                let index = match key {
+                    JsValue::Number(n) => PropertyKey::array_index_from_number(*n),
+                    JsValue::String(k) => PropertyKey::array_index(k.as_str()),
+                    _ => None,
+                };
+                if let Some(idx) = index {
+                    let c = s.as_str().chars().nth(idx as usize);
+                    return Ok(Guarded::unguarded(c.map_or(JsValue::Undefined, |c| {
+                        JsValue::String(JsString::from(c.to_string()))
+                    })));
                 }
-                _ => {
-                    let prop_key = interp.property_key_from_value(key);
-                    if let Some(val) = interp.string_prototype.borrow().get_property(&prop_key) {
-                        Ok(Guarded::unguarded(val.clone()))
-                    } else {
-                        Ok(Guarded::unguarded(JsValue::Undefined))
+                match key {
+                    JsValue::String(k) if k.as_str() == "length" => {
+                        Ok(Guarded::unguarded(JsValue::Number(s.char_count() as f64)))
+                    }
+                    _ => {
+                        let proto = interp.string_prototype.cheap_clone();
+                        self.get_primitive_property(interp, &proto, obj, key)
                     }
                 }
-            },
+            }
             JsValue::Number(_) => {
-                let prop_key = interp.property_key_from_value(key);
-                if let Some(val) = interp.number_prototype.borrow().get_property(&prop_key) {
-                    Ok(Guarded::unguarded(val.clone()))
-                } else {
-                    Ok(Guarded::unguarded(JsValue::Undefined))
-                }
+                let proto = interp.number_prototype.cheap_clone();
+                self.get_primitive_property(interp, &proto, obj, key)
             }
             JsValue::Boolean(_) => {
-                let prop_key = interp.property_key_from_value(key);
-                if let Some(val) = interp.boolean_prototype.borrow().get_property(&prop_key) {
-                    Ok(Guarded::unguarded(val.clone()))
-                } else {
-                    Ok(Guarded::unguarded(JsValue::Undefined))
-                }
+                let proto = interp.boolean_prototype.cheap_clone();
+                self.get_primitive_property(interp, &proto, obj, key)
             }
             JsValue::Null => Err(JsError::type_error("Cannot read properties of null")),
             JsValue::Undefined => Err(JsError::type_error("Cannot read properties of undefined")),
@@ -5821,16 +6363,37 @@ impl BytecodeVM {
                     ));
                 }
                 // Other symbol prototype methods
-                let prop_key = interp.property_key_from_value(key);
-                if let Some(val) = interp.symbol_prototype.borrow().get_property(&prop_key) {
-                    Ok(Guarded::unguarded(val.clone()))
-                } else {
-                    Ok(Guarded::unguarded(JsValue::Undefined))
-                }
+                let proto = interp.symbol_prototype.cheap_clone();
+                self.get_primitive_property(interp, &proto, obj, key)
             }
         }
     }
 
+    /// Look up a property of a primitive on its wrapper prototype.
+    ///
+    /// Getters run with the primitive itself as `this`, so accessors such as
+    /// `__proto__` behave as they would on the boxed value.
+    fn get_primitive_property(
+        &self,
+        interp: &mut Interpreter,
+        proto: &Gc<JsObject>,
+        receiver: &JsValue,
+        key: &JsValue,
+    ) -> Result<Guarded, JsError> {
+        let prop_key = interp.property_key_from_value(key);
+        let prop_desc = proto.borrow().get_property_descriptor(&prop_key);
+        match prop_desc {
+            Some((prop, _)) if prop.is_accessor() => match prop.getter() {
+                Some(getter) => {
+                    interp.call_function(JsValue::Object(getter.clone()), receiver.clone(), &[])
+                }
+                None => Ok(Guarded::unguarded(JsValue::Undefined)),
+            },
+            Some((prop, _)) => Ok(Guarded::unguarded(prop.value.clone())),
+            None => Ok(Guarded::unguarded(JsValue::Undefined)),
+        }
+    }
+
     /// Set a property value on an object, invoking setters if present
     fn set_property_value(
         &self,
@@ -5841,8 +6404,12 @@ impl BytecodeVM {
     ) -> Result<(), JsError> {
         match obj {
             JsValue::Object(obj_ref) => {
+                if interp.purity.is_some() {
+                    self.note_purity_location(interp);
+                }
+
                 // Check if this is a proxy - delegate to proxy_set if so
-                if matches!(obj_ref.borrow().exotic, ExoticObject::Proxy(_)) {
+                if crate::interpreter::builtins::proxy::has_hooks(obj_ref) {
                     let prop_key = interp.property_key_from_value(key);
                     crate::interpreter::builtins::proxy::proxy_set(
                         interp,
@@ -5854,25 +6421,8 @@ impl BytecodeVM {
                     return Ok(());
                 }
 
-                // Handle __proto__ special property - set prototype
-                if let JsValue::String(k) = key
-                    && k.as_str() == "__proto__"
-                {
-                    match &value {
-                        JsValue::Object(proto) => {
-                            obj_ref.borrow_mut().prototype = Some(proto.clone());
-                        }
-                        JsValue::Null => {
-                            obj_ref.borrow_mut().prototype = None;
-                        }
-                        _ => {
-                            // Non-object, non-null values are ignored for __proto__ set
-                        }
-                    }
-                    return Ok(());
-                }
-
                 let prop_key = interp.property_key_from_value(key);
+                interp.check_write(obj_ref, Some(&prop_key))?;
 
                 // Check if object is frozen/sealed or property is non-writable
                 // First, check for accessor or non-writable property (including prototype chain)
@@ -5918,6 +6468,25 @@ impl BytecodeVM {
                     return Ok(());
                 }
 
+                // Growing an array by writing past the end is capped, and `length`
+                // only accepts values that are already valid array lengths
+                let mut value = value;
+                let array_len = obj_ref.borrow().array_length();
+                if let Some(len) = array_len {
+                    match &prop_key {
+                        PropertyKey::Index(idx) if *idx >= len => {
+                            interp.check_array_len(*idx as usize + 1)?;
+                        }
+                        PropertyKey::String(k) if k.as_str() == "length" => {
+                            let new_len = crate::interpreter::builtins::array::to_array_length(
+                                interp, &value,
+                            )?;
+                            value = JsValue::Number(new_len as f64);
+                        }
+                        _ => {}
+                    }
+                }
+
                 // Regular data property
                 obj_ref.borrow_mut().set_property(prop_key, value);
                 Ok(())
@@ -5928,6 +6497,66 @@ impl BytecodeVM {
         }
     }
 
+    /// Call r[iterator].return() if it exists (ES IteratorClose)
+    fn close_iterator(&self, interp: &mut Interpreter, iterator: Register) -> Result<(), JsError> {
+        let JsValue::Object(iter_obj) = self.get_reg(iterator) else {
+            return Ok(());
+        };
+        // Internal array/string/keys iterators have no return method
+        let return_key = PropertyKey::String(interp.intern("return"));
+        let return_method = iter_obj.borrow().get_property(&return_key);
+
+        if let Some(JsValue::Object(return_fn)) = return_method {
+            // Per ES spec 7.4.6: If Type(innerResult.[[value]]) is not Object,
+            // throw a TypeError exception.
+            let Guarded {
+                value,
+                guard: _guard,
+            } = interp.call_function(
+                JsValue::Object(return_fn),
+                JsValue::Object(iter_obj.clone()),
+                &[],
+            )?;
+
+            if !matches!(value, JsValue::Object(_)) {
+                return Err(JsError::type_error("Iterator result is not an object"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Pop the handlers above `depth` that belong to for-of loops being left,
+    /// closing their iterators, until one with a finally block is on top.
+    ///
+    /// Returns the index of that finally handler, if one was reached.
+    fn unwind_iterators(
+        &mut self,
+        interp: &mut Interpreter,
+        depth: usize,
+        frame_depth: Option<usize>,
+    ) -> Result<Option<usize>, JsError> {
+        while self.try_stack.len() > depth {
+            let idx = self.try_stack.len() - 1;
+            let Some(handler) = self.try_stack.last() else {
+                break;
+            };
+            if frame_depth.is_some_and(|d| handler.frame_depth != d) {
+                break;
+            }
+            if handler.finally_ip != 0 {
+                return Ok(Some(idx));
+            }
+            let iterator = handler.iterator_reg;
+            // Popped before calling return(), so an error it throws
+            // propagates past this loop instead of closing it again
+            self.try_stack.pop();
+            if let Some(iterator) = iterator {
+                self.close_iterator(interp, iterator)?;
+            }
+        }
+        Ok(None)
+    }
+
     /// Execute a return, running any pending finally blocks first
     // NOTE: review
     fn execute_return(
@@ -5935,16 +6564,16 @@ impl BytecodeVM {
         return_val: JsValue,
         interp: &mut Interpreter,
     ) -> Result<OpResult, JsError> {
+        if interp.provenance.is_some() {
+            self.note_returned_value(interp, &return_val);
+        }
+
         // Check if there's a try handler with a finally block that needs to run
         // We need to find try handlers for the current function (same call frame depth)
         let current_frame_depth = self.call_stack.len();
 
-        // Find try handlers that belong to the current function
-        if let Some(handler_idx) = self
-            .try_stack
-            .iter()
-            .rposition(|h| h.frame_depth == current_frame_depth && h.finally_ip != 0)
-        {
+        // Close for-of iterators up to the innermost finally of the current function
+        if let Some(handler_idx) = self.unwind_iterators(interp, 0, Some(current_frame_depth))? {
             // There's a finally block that needs to run
             let handler = self
                 .try_stack
@@ -5987,19 +6616,17 @@ impl BytecodeVM {
 
     /// Execute a break, running any pending finally blocks first
     // NOTE: review
-    fn execute_break(&mut self, target: usize, try_depth: u8) -> Result<OpResult, JsError> {
-        // Check if there's a try handler with a finally block between us and the target
+    fn execute_break(
+        &mut self,
+        interp: &mut Interpreter,
+        target: usize,
+        try_depth: u8,
+    ) -> Result<OpResult, JsError> {
         let target_try_depth = try_depth as usize;
 
-        // Find the first try handler ABOVE target depth that has a finally block
-        if let Some(handler_idx) = self
-            .try_stack
-            .iter()
-            .enumerate()
-            .skip(target_try_depth)
-            .find(|(_, h)| h.finally_ip != 0)
-            .map(|(i, _)| i)
-        {
+        // Close for-of iterators being left, innermost first, up to the
+        // innermost try handler above the target depth that has a finally block
+        if let Some(handler_idx) = self.unwind_iterators(interp, target_try_depth, None)? {
             // There's a finally block that needs to run
             let handler = self
                 .try_stack
@@ -6028,19 +6655,17 @@ impl BytecodeVM {
 
     /// Execute a continue, running any pending finally blocks first
     // NOTE: review
-    fn execute_continue(&mut self, target: usize, try_depth: u8) -> Result<OpResult, JsError> {
-        // Check if there's a try handler with a finally block between us and the target
+    fn execute_continue(
+        &mut self,
+        interp: &mut Interpreter,
+        target: usize,
+        try_depth: u8,
+    ) -> Result<OpResult, JsError> {
         let target_try_depth = try_depth as usize;
 
-        // Find the first try handler ABOVE target depth that has a finally block
-        if let Some(handler_idx) = self
-            .try_stack
-            .iter()
-            .enumerate()
-            .skip(target_try_depth)
-            .find(|(_, h)| h.finally_ip != 0)
-            .map(|(i, _)| i)
-        {
+        // Close for-of iterators being left, innermost first, up to the
+        // innermost try handler above the target depth that has a finally block
+        if let Some(handler_idx) = self.unwind_iterators(interp, target_try_depth, None)? {
             // There's a finally block that needs to run
             let handler = self
                 .try_stack
@@ -6119,3 +6744,109 @@ enum OpResult {
         guard: Guard<JsObject>,
     },
 }
+
+/// The value of own enumerable key `key` of `obj`, including array elements
+/// and the characters of string wrappers, which aren't stored as properties
+fn own_enumerable_value(obj: &JsObject, key: &PropertyKey) -> Option<JsValue> {
+    match obj.properties.get(key) {
+        Some(prop) => Some(prop.value.clone()),
+        None if matches!(key, PropertyKey::Index(_)) => obj.get_property(key),
+        None => None,
+    }
+}
+
+/// TypeError for a for-of or spread over a value with no Symbol.iterator,
+/// e.g. "number 5 is not iterable"
+/// SetFunctionName: give an anonymous function `name`, leaving functions
+/// that already have a name, and non-functions, as they are
+fn set_function_name(interp: &mut Interpreter, func: &JsValue, name_str: JsString) {
+    // Only set name if value is a function object without a name
+    if let JsValue::Object(obj) = func {
+        let mut obj_ref = obj.borrow_mut();
+
+        // Check if this is a function and if it doesn't have a name already
+        let should_set_name = if let crate::value::ExoticObject::Function(func) = &obj_ref.exotic {
+            // Check if function already has a non-empty name
+            let has_name = match func {
+                JsFunction::Native(f) => !f.name.as_str().is_empty(),
+                JsFunction::Bytecode(bc)
+                | JsFunction::BytecodeGenerator(bc)
+                | JsFunction::BytecodeAsync(bc)
+                | JsFunction::BytecodeAsyncGenerator(bc) => bc
+                    .chunk
+                    .function_info
+                    .as_ref()
+                    .and_then(|info| info.name.as_ref())
+                    .is_some_and(|n| !n.as_str().is_empty()),
+                JsFunction::Bound(_) => true, // Bound functions already have names
+                // Internal functions don't need names set
+                JsFunction::PromiseResolve { .. }
+                | JsFunction::PromiseReject { .. }
+                | JsFunction::PromiseAllFulfill { .. }
+                | JsFunction::PromiseAllReject(_)
+                | JsFunction::PromiseRaceSettle { .. }
+                | JsFunction::AccessorGetter
+                | JsFunction::AccessorSetter
+                | JsFunction::ModuleExportGetter { .. }
+                | JsFunction::ModuleReExportGetter { .. }
+                | JsFunction::ProxyRevoke(_)
+                | JsFunction::AsyncGeneratorResume { .. } => true,
+            };
+            // An own name other than the empty one functions are created
+            // with also counts, e.g. a class's `static name()`
+            let name_key = PropertyKey::String(interp.intern("name"));
+            let has_own_name = obj_ref.get_own_property(&name_key).is_some_and(
+                |prop| !matches!(&prop.value, JsValue::String(s) if s.as_str().is_empty()),
+            );
+            !has_name && !has_own_name
+        } else {
+            false // Not a function
+        };
+
+        if should_set_name {
+            let name_key = PropertyKey::String(interp.intern("name"));
+            obj_ref.define_property(
+                name_key,
+                Property::with_attributes(
+                    JsValue::String(name_str),
+                    false, // not writable
+                    false, // not enumerable
+                    true,  // configurable
+                ),
+            );
+        }
+    }
+}
+
+/// The name a method or accessor defined under `key` gets, e.g. `get size`
+/// or `[Symbol.iterator]`
+fn function_name_for_key(key: &PropertyKey, prefix: &str) -> JsString {
+    let name = match key {
+        PropertyKey::String(s) => s.to_string(),
+        PropertyKey::Index(i) => i.to_string(),
+        PropertyKey::Symbol(sym) => match &sym.description {
+            Some(desc) => format!("[{}]", desc),
+            None => String::new(),
+        },
+    };
+    JsString::from(format!("{}{}", prefix, name))
+}
+
+fn not_iterable_error(interp: &mut Interpreter, value: &JsValue) -> JsError {
+    match value {
+        JsValue::Undefined | JsValue::Null | JsValue::Object(_) => {
+            let type_name = match value {
+                JsValue::Undefined => "undefined",
+                JsValue::Null => "null",
+                _ if value.is_callable() => "function",
+                _ => "object",
+            };
+            JsError::type_error(format!("{} is not iterable", type_name))
+        }
+        _ => {
+            let type_name = interp.type_of(value);
+            let shown = interp.to_js_string(value);
+            JsError::type_error(format!("{} {} is not iterable", type_name, shown))
+        }
+    }
+}
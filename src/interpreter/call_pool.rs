@@ -0,0 +1,145 @@
+//! Per-interpreter pools of call-frame storage.
+//!
+//! Every bytecode call needs a register file, an arguments vector, a register
+//! guard and a function environment. Allocating these fresh dominates the cost
+//! of small recursive functions, so returning frames hand them back here and
+//! the next call takes them out again, cleared but with their capacity intact.
+
+use crate::prelude::*;
+
+use crate::gc::{Guard, Heap};
+use crate::value::{CheapClone, EnvRef, JsObject, JsValue};
+
+/// Maximum number of entries kept in each pool
+const MAX_POOLED: usize = 16;
+
+/// Recycled storage shared by every VM running on one interpreter
+pub(crate) struct CallPool {
+    /// Register files, cleared
+    registers: Vec<Vec<JsValue>>,
+    /// Argument vectors, cleared
+    arguments: Vec<Vec<JsValue>>,
+    /// Register guards, cleared
+    guards: Vec<Guard<JsObject>>,
+    /// Function environments with no bindings and no outer scope
+    envs: Vec<EnvRef>,
+    /// Roots the idle environments in `envs` so the GC doesn't reclaim them
+    env_guard: Guard<JsObject>,
+}
+
+impl CallPool {
+    pub(crate) fn new(heap: &Heap<JsObject>) -> Self {
+        Self {
+            registers: Vec::new(),
+            arguments: Vec::new(),
+            guards: Vec::new(),
+            envs: Vec::new(),
+            env_guard: {
+                let guard = heap.create_guard();
+                guard.set_label("pooled environments");
+                guard
+            },
+        }
+    }
+
+    /// Take a register file of `size` slots, all undefined
+    #[inline]
+    pub(crate) fn acquire_registers(&mut self, size: usize) -> Vec<JsValue> {
+        let size = size.max(1);
+        if let Some(pos) = self.registers.iter().position(|f| f.capacity() >= size) {
+            let mut frame = self.registers.swap_remove(pos);
+            frame.resize(size, JsValue::Undefined);
+            return frame;
+        }
+        vec![JsValue::Undefined; size]
+    }
+
+    /// Return a register file to the pool.
+    ///
+    /// The guard protecting the registers must be cleared first so it doesn't
+    /// keep roots to objects that are no longer referenced.
+    #[inline]
+    pub(crate) fn release_registers(&mut self, mut registers: Vec<JsValue>) {
+        registers.clear();
+        if self.registers.len() < MAX_POOLED {
+            self.registers.push(registers);
+        }
+    }
+
+    /// Take an empty arguments vector with at least `capacity` slots
+    #[inline]
+    pub(crate) fn acquire_arguments(&mut self, capacity: usize) -> Vec<JsValue> {
+        if let Some(pos) = self.arguments.iter().position(|v| v.capacity() >= capacity) {
+            return self.arguments.swap_remove(pos);
+        }
+        Vec::with_capacity(capacity)
+    }
+
+    /// Return an arguments vector to the pool
+    #[inline]
+    pub(crate) fn release_arguments(&mut self, mut args: Vec<JsValue>) {
+        args.clear();
+        if self.arguments.len() < MAX_POOLED {
+            self.arguments.push(args);
+        }
+    }
+
+    /// Take an empty guard for a frame's registers
+    #[inline]
+    pub(crate) fn acquire_guard(&mut self, heap: &Heap<JsObject>) -> Guard<JsObject> {
+        self.guards.pop().unwrap_or_else(|| {
+            let guard = heap.create_guard();
+            guard.set_label("registers");
+            guard
+        })
+    }
+
+    /// Return a register guard to the pool, dropping its roots
+    #[inline]
+    pub(crate) fn release_guard(&mut self, guard: Guard<JsObject>) {
+        guard.clear();
+        if self.guards.len() < MAX_POOLED {
+            self.guards.push(guard);
+        }
+    }
+
+    /// Take a pooled environment for a function call, if there is one.
+    ///
+    /// The environment gets `outer` as its parent and is rooted by the returned
+    /// guard, like a freshly created one.
+    pub(crate) fn acquire_env(
+        &mut self,
+        heap: &Heap<JsObject>,
+        outer: EnvRef,
+    ) -> Option<(EnvRef, Guard<JsObject>)> {
+        let env = self.envs.pop()?;
+        let guard = heap.create_guard();
+        guard.guard(env.cheap_clone());
+        self.env_guard.unguard(&env);
+        if let Some(data) = env.borrow_mut().as_environment_mut() {
+            data.outer = Some(outer);
+        }
+        Some((env, guard))
+    }
+
+    /// Return a function environment once its call has finished.
+    ///
+    /// Only valid when nothing else can still reach the environment, i.e. the
+    /// function's [`FunctionInfo::env_is_recyclable`] holds and the frame never
+    /// suspended.
+    ///
+    /// [`FunctionInfo::env_is_recyclable`]: crate::compiler::FunctionInfo::env_is_recyclable
+    pub(crate) fn release_env(&mut self, env: EnvRef) {
+        if self.envs.len() >= MAX_POOLED {
+            return;
+        }
+        if let Some(data) = env.borrow_mut().as_environment_mut() {
+            data.bindings.clear();
+            data.outer = None;
+        } else {
+            return;
+        }
+        self.env_guard.guard(env.cheap_clone());
+        self.envs.push(env);
+    }
+}
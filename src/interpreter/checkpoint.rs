@@ -0,0 +1,2150 @@
+//! Checkpoints of runs suspended on an order.
+//!
+//! [`Interpreter::checkpoint`] writes out a run that `step()` left waiting for
+//! the host to fulfill an order, and [`Interpreter::restore`] rebuilds it in a
+//! fresh interpreter, possibly in another process hours later.
+//!
+//! Only what the run created is written in full. Builtins and internal module
+//! objects are referred to by their position in a deterministic walk of a
+//! fresh interpreter's object graph; one the run changed (the global object
+//! once a binding is added, say) is written as an overlay that replaces its
+//! fresh state. Bytecode is referred to by content hash: restore recompiles the
+//! module sources the host provides again and looks the chunks up by hash, so
+//! a changed source is detected rather than resumed at the wrong instruction.
+
+use crate::prelude::*;
+
+use super::bytecode_vm::{
+    CallFrame, SavedTrampolineFrame, SavedVmState, TryHandler, VmOrderSuspension, scope_guards,
+};
+use super::{Interpreter, StackFrame};
+use crate::compiler::{BytecodeChunk, Constant};
+use crate::error::JsError;
+use crate::gc::Gc;
+use crate::value::{
+    BoundFunctionData, BytecodeFunction, CheapClone, EnumData, EnumMember, EnvironmentData,
+    ExoticObject, ImportBinding, JsFunction, JsMapKey, JsObject, JsString, JsSymbol, JsValue,
+    ModuleExport, PrivateFieldKey, PromiseState, PromiseStatus, Property, PropertyKey,
+    PropertyStorage, VarKey,
+};
+use crate::{InterpreterConfig, ModulePath, OrderDelivery, OrderId};
+
+/// First bytes of every checkpoint
+const MAGIC: &[u8; 4] = b"TSCP";
+
+/// Format version, bumped whenever the encoding changes
+const VERSION: u64 = 4;
+
+/// Why a run could not be checkpointed or a checkpoint restored
+#[derive(Debug)]
+pub enum CheckpointError {
+    /// The interpreter is not suspended on an order in a way a checkpoint covers
+    InvalidState(String),
+    /// The run reaches something that cannot be written out, such as a proxy or
+    /// a native function created at runtime
+    Unserializable(String),
+    /// The checkpoint is truncated or malformed
+    Corrupt(String),
+    /// A module source given to restore is missing or differs from the one the
+    /// checkpointed run was compiled from
+    SourceMismatch { path: String },
+    /// The checkpoint was made by an interpreter with different builtins or
+    /// internal modules
+    IncompatibleRuntime(String),
+    /// Setting up the restored interpreter failed
+    Restore(JsError),
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckpointError::InvalidState(reason) => write!(f, "Cannot checkpoint: {}", reason),
+            CheckpointError::Unserializable(what) => write!(f, "Cannot checkpoint {}", what),
+            CheckpointError::Corrupt(reason) => write!(f, "Corrupt checkpoint: {}", reason),
+            CheckpointError::SourceMismatch { path } => {
+                write!(f, "Source of '{}' does not match the checkpoint", path)
+            }
+            CheckpointError::IncompatibleRuntime(reason) => {
+                write!(f, "Checkpoint is from an incompatible runtime: {}", reason)
+            }
+            CheckpointError::Restore(err) => write!(f, "Restoring checkpoint failed: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CheckpointError {}
+
+/// Sources of the modules a checkpointed run was compiled from, given again
+/// to [`Interpreter::restore`]
+#[derive(Debug, Clone, Default)]
+pub struct ModuleSources {
+    entry: Option<String>,
+    modules: FxHashMap<String, String>,
+}
+
+impl ModuleSources {
+    /// Sources with the entry program passed to `prepare`
+    pub fn new(entry: impl Into<String>) -> Self {
+        Self {
+            entry: Some(entry.into()),
+            modules: FxHashMap::default(),
+        }
+    }
+
+    /// Add the source of a module provided through `provide_module`
+    pub fn with_module(mut self, path: ModulePath, source: impl Into<String>) -> Self {
+        self.insert(path, source);
+        self
+    }
+
+    /// Add the source of a module provided through `provide_module`
+    pub fn insert(&mut self, path: ModulePath, source: impl Into<String>) {
+        self.modules
+            .insert(path.as_str().to_string(), source.into());
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Encoding
+// ═══════════════════════════════════════════════════════════════════════════════
+
+// Value tags
+const V_UNDEFINED: u8 = 0;
+const V_NULL: u8 = 1;
+const V_FALSE: u8 = 2;
+const V_TRUE: u8 = 3;
+const V_NUMBER: u8 = 4;
+const V_STRING: u8 = 5;
+const V_SYMBOL: u8 = 6;
+const V_INTRINSIC: u8 = 7;
+const V_HEAP: u8 = 8;
+/// An object a fresh interpreter's walk doesn't reach; only written when
+/// comparing intrinsics, never to a checkpoint
+const V_UNKNOWN: u8 = 9;
+
+// Property key tags
+const K_STRING: u8 = 0;
+const K_INDEX: u8 = 1;
+const K_SYMBOL: u8 = 2;
+
+// Exotic object tags
+const X_ORDINARY: u8 = 0;
+const X_ARRAY: u8 = 1;
+const X_BOOLEAN: u8 = 2;
+const X_NUMBER: u8 = 3;
+const X_STRING: u8 = 4;
+const X_SYMBOL: u8 = 5;
+const X_MAP: u8 = 6;
+const X_SET: u8 = 7;
+const X_DATE: u8 = 8;
+const X_REGEXP: u8 = 9;
+const X_PROMISE: u8 = 10;
+const X_ENVIRONMENT: u8 = 11;
+const X_ENUM: u8 = 12;
+const X_RAW_JSON: u8 = 13;
+const X_PENDING_ORDER: u8 = 14;
+const X_FUNCTION: u8 = 15;
+/// Overlays only: the intrinsic keeps its own exotic state
+const X_KEEP: u8 = 255;
+
+// Function tags
+const F_BYTECODE: u8 = 0;
+const F_GENERATOR: u8 = 1;
+const F_ASYNC: u8 = 2;
+const F_ASYNC_GENERATOR: u8 = 3;
+const F_BOUND: u8 = 4;
+const F_EXPORT_GETTER: u8 = 5;
+const F_REEXPORT_GETTER: u8 = 6;
+const F_ACCESSOR_GETTER: u8 = 7;
+const F_ACCESSOR_SETTER: u8 = 8;
+
+// Object flag bits
+const O_EXTENSIBLE: u8 = 1;
+const O_FROZEN: u8 = 2;
+const O_SEALED: u8 = 4;
+const O_NULL_PROTOTYPE: u8 = 8;
+const O_DEEP_FROZEN: u8 = 16;
+const O_STRICT_READS: u8 = 32;
+
+// Property flag bits
+const P_WRITABLE: u8 = 1;
+const P_ENUMERABLE: u8 = 2;
+const P_CONFIGURABLE: u8 = 4;
+const P_ACCESSOR: u8 = 8;
+
+/// Byte buffer with LEB128 integers and length-prefixed strings
+#[derive(Default)]
+pub(super) struct Writer {
+    pub(super) buf: Vec<u8>,
+}
+
+impl Writer {
+    pub(super) fn u8(&mut self, v: u8) {
+        self.buf.push(v);
+    }
+
+    pub(super) fn uint(&mut self, mut v: u64) {
+        loop {
+            let byte = (v & 0x7f) as u8;
+            v >>= 7;
+            if v == 0 {
+                self.buf.push(byte);
+                return;
+            }
+            self.buf.push(byte | 0x80);
+        }
+    }
+
+    pub(super) fn usize(&mut self, v: usize) {
+        self.uint(v as u64);
+    }
+
+    pub(super) fn u64(&mut self, v: u64) {
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    pub(super) fn f64(&mut self, v: f64) {
+        self.u64(v.to_bits());
+    }
+
+    pub(super) fn bool(&mut self, v: bool) {
+        self.u8(u8::from(v));
+    }
+
+    pub(super) fn str(&mut self, s: &str) {
+        self.usize(s.len());
+        self.buf.extend_from_slice(s.as_bytes());
+    }
+
+    pub(super) fn opt_str(&mut self, s: Option<&str>) {
+        match s {
+            Some(s) => {
+                self.u8(1);
+                self.str(s);
+            }
+            None => self.u8(0),
+        }
+    }
+
+    pub(super) fn bytes(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+}
+
+/// How object and chunk references are written
+trait Refs {
+    fn object(&mut self, w: &mut Writer, obj: &Gc<JsObject>);
+    fn chunk(&mut self, w: &mut Writer, chunk: &Rc<BytecodeChunk>);
+}
+
+fn write_value(refs: &mut impl Refs, w: &mut Writer, value: &JsValue) {
+    match value {
+        JsValue::Undefined => w.u8(V_UNDEFINED),
+        JsValue::Null => w.u8(V_NULL),
+        JsValue::Boolean(false) => w.u8(V_FALSE),
+        JsValue::Boolean(true) => w.u8(V_TRUE),
+        JsValue::Number(n) => {
+            w.u8(V_NUMBER);
+            w.f64(*n);
+        }
+        JsValue::String(s) => {
+            w.u8(V_STRING);
+            w.str(s.as_str());
+        }
+        JsValue::Symbol(symbol) => {
+            w.u8(V_SYMBOL);
+            write_symbol(w, symbol);
+        }
+        JsValue::Object(obj) => refs.object(w, obj),
+    }
+}
+
+fn write_values(refs: &mut impl Refs, w: &mut Writer, values: &[JsValue]) {
+    w.usize(values.len());
+    for value in values {
+        write_value(refs, w, value);
+    }
+}
+
+fn write_opt_object(refs: &mut impl Refs, w: &mut Writer, obj: Option<&Gc<JsObject>>) {
+    match obj {
+        Some(obj) => refs.object(w, obj),
+        None => w.u8(V_UNDEFINED),
+    }
+}
+
+fn write_symbol(w: &mut Writer, symbol: &JsSymbol) {
+    w.uint(symbol.id());
+    w.opt_str(symbol.description.as_ref().map(|d| d.as_str()));
+}
+
+fn write_key(w: &mut Writer, key: &PropertyKey) {
+    match key {
+        PropertyKey::String(s) => {
+            w.u8(K_STRING);
+            w.str(s.as_str());
+        }
+        PropertyKey::Index(i) => {
+            w.u8(K_INDEX);
+            w.uint(u64::from(*i));
+        }
+        PropertyKey::Symbol(symbol) => {
+            w.u8(K_SYMBOL);
+            write_symbol(w, symbol);
+        }
+    }
+}
+
+/// Write an object's prototype, flags, properties, private fields and exotic
+/// state. Overlays leave behavior-carrying exotic state (functions, promises and
+/// the like) to the fresh intrinsic, which the pairing walk already matched.
+fn write_record(
+    refs: &mut impl Refs,
+    w: &mut Writer,
+    obj: &Gc<JsObject>,
+    overlay: bool,
+) -> Result<(), CheckpointError> {
+    let obj_ref = obj.borrow();
+    write_opt_object(refs, w, obj_ref.prototype.as_ref());
+
+    let mut flags = 0;
+    for (set, bit) in [
+        (obj_ref.extensible, O_EXTENSIBLE),
+        (obj_ref.frozen, O_FROZEN),
+        (obj_ref.sealed, O_SEALED),
+        (obj_ref.null_prototype, O_NULL_PROTOTYPE),
+        (obj_ref.deep_frozen, O_DEEP_FROZEN),
+        (obj_ref.strict_reads, O_STRICT_READS),
+    ] {
+        if set {
+            flags |= bit;
+        }
+    }
+    w.u8(flags);
+
+    w.usize(obj_ref.properties.len());
+    for (key, prop) in obj_ref.properties.iter() {
+        write_key(w, key);
+        let mut flags = 0;
+        for (set, bit) in [
+            (prop.writable(), P_WRITABLE),
+            (prop.enumerable(), P_ENUMERABLE),
+            (prop.configurable(), P_CONFIGURABLE),
+            (prop.is_accessor(), P_ACCESSOR),
+        ] {
+            if set {
+                flags |= bit;
+            }
+        }
+        w.u8(flags);
+        if prop.is_accessor() {
+            write_opt_object(refs, w, prop.getter());
+            write_opt_object(refs, w, prop.setter());
+        } else {
+            write_value(refs, w, &prop.value);
+        }
+    }
+
+    // Sorted so equal objects encode equally
+    let mut private_fields: Vec<(&PrivateFieldKey, &JsValue)> = obj_ref
+        .private_fields
+        .iter()
+        .flat_map(|fields| fields.iter())
+        .collect();
+    private_fields.sort_by(|(a, _), (b, _)| {
+        (a.class_brand, a.field_name.as_str()).cmp(&(b.class_brand, b.field_name.as_str()))
+    });
+    w.usize(private_fields.len());
+    for (key, value) in private_fields {
+        w.uint(u64::from(key.class_brand));
+        w.str(key.field_name.as_str());
+        write_value(refs, w, value);
+    }
+
+    write_exotic(refs, w, &obj_ref.exotic, overlay)
+}
+
+fn write_exotic(
+    refs: &mut impl Refs,
+    w: &mut Writer,
+    exotic: &ExoticObject,
+    overlay: bool,
+) -> Result<(), CheckpointError> {
+    match exotic {
+        ExoticObject::Ordinary => w.u8(X_ORDINARY),
+        ExoticObject::Array { elements } => {
+            w.u8(X_ARRAY);
+            write_values(refs, w, elements);
+        }
+        ExoticObject::Boolean(b) => {
+            w.u8(X_BOOLEAN);
+            w.bool(*b);
+        }
+        ExoticObject::Number(n) => {
+            w.u8(X_NUMBER);
+            w.f64(*n);
+        }
+        ExoticObject::StringObj(s) => {
+            w.u8(X_STRING);
+            w.str(s.as_str());
+        }
+        ExoticObject::Symbol(symbol) => {
+            w.u8(X_SYMBOL);
+            write_symbol(w, symbol);
+        }
+        ExoticObject::Map { entries } => {
+            w.u8(X_MAP);
+            w.usize(entries.len());
+            for (key, value) in entries {
+                write_value(refs, w, &key.0);
+                write_value(refs, w, value);
+            }
+        }
+        ExoticObject::Set { entries } => {
+            w.u8(X_SET);
+            w.usize(entries.len());
+            for key in entries {
+                write_value(refs, w, &key.0);
+            }
+        }
+        ExoticObject::Date { timestamp } => {
+            w.u8(X_DATE);
+            w.f64(*timestamp);
+        }
+        ExoticObject::Environment(env) => {
+            w.u8(X_ENVIRONMENT);
+            let mut bindings: Vec<_> = env.bindings.iter().collect();
+            bindings.sort_by(|(a, _), (b, _)| a.0.as_str().cmp(b.0.as_str()));
+            w.usize(bindings.len());
+            for (name, binding) in bindings {
+                w.str(name.0.as_str());
+                w.u8(u8::from(binding.mutable) | (u8::from(binding.initialized) << 1));
+                write_value(refs, w, &binding.value);
+                match &binding.import_binding {
+                    Some(import) => {
+                        w.u8(1);
+                        refs.object(w, &import.module_obj);
+                        write_key(w, &import.property_key);
+                    }
+                    None => w.u8(0),
+                }
+            }
+            write_opt_object(refs, w, env.outer.as_ref());
+        }
+        ExoticObject::Enum(data) => {
+            w.u8(X_ENUM);
+            w.str(data.name.as_str());
+            w.bool(data.const_);
+            w.usize(data.members.len());
+            for member in &data.members {
+                w.str(member.name.as_str());
+                write_value(refs, w, &member.value);
+            }
+        }
+        ExoticObject::RawJSON(s) => {
+            w.u8(X_RAW_JSON);
+            w.str(s.as_str());
+        }
+        _ if overlay => w.u8(X_KEEP),
+        ExoticObject::RegExp { pattern, flags, .. } => {
+            w.u8(X_REGEXP);
+            w.str(pattern);
+            w.str(flags);
+        }
+        ExoticObject::Promise(state) => {
+            let state = state.borrow();
+            let status = match state.status {
+                PromiseStatus::Pending => {
+                    return Err(CheckpointError::Unserializable("a pending promise".into()));
+                }
+                PromiseStatus::Fulfilled => 0,
+                PromiseStatus::Rejected => 1,
+            };
+            if !state.handlers.is_empty() {
+                return Err(CheckpointError::Unserializable(
+                    "a promise with reactions".into(),
+                ));
+            }
+            w.u8(X_PROMISE);
+            w.u8(status);
+            write_value(
+                refs,
+                w,
+                state.result.as_ref().unwrap_or(&JsValue::Undefined),
+            );
+            match state.order_id {
+                Some(id) => {
+                    w.u8(1);
+                    w.uint(id.0);
+                }
+                None => w.u8(0),
+            }
+        }
+        ExoticObject::PendingOrder { id } => {
+            w.u8(X_PENDING_ORDER);
+            w.uint(*id);
+        }
+        ExoticObject::Function(func) => {
+            w.u8(X_FUNCTION);
+            write_function(refs, w, func)?;
+        }
+        ExoticObject::Generator(_) | ExoticObject::BytecodeGenerator(_) => {
+            return Err(CheckpointError::Unserializable(
+                "an open generator object".into(),
+            ));
+        }
+        ExoticObject::Proxy(_) => {
+            return Err(CheckpointError::Unserializable("a proxy".into()));
+        }
+        ExoticObject::Host(_) => {
+            return Err(CheckpointError::Unserializable("a host object".into()));
+        }
+        ExoticObject::Url(_) | ExoticObject::UrlSearchParams(_) => {
+            return Err(CheckpointError::Unserializable("a URL object".into()));
+        }
+        ExoticObject::WeakRef(_) | ExoticObject::FinalizationRegistry(_) => {
+            return Err(CheckpointError::Unserializable("a weak reference".into()));
+        }
+        ExoticObject::DisposableStack(_) => {
+            return Err(CheckpointError::Unserializable("a disposable stack".into()));
+        }
+    }
+    Ok(())
+}
+
+fn write_function(
+    refs: &mut impl Refs,
+    w: &mut Writer,
+    func: &JsFunction,
+) -> Result<(), CheckpointError> {
+    let mut bytecode = |w: &mut Writer, tag: u8, f: &BytecodeFunction| {
+        w.u8(tag);
+        refs.chunk(w, &f.chunk);
+        refs.object(w, &f.closure);
+        match &f.captured_this {
+            Some(this) => {
+                w.u8(1);
+                write_value(refs, w, this);
+            }
+            None => w.u8(0),
+        }
+    };
+    match func {
+        JsFunction::Bytecode(f) => bytecode(w, F_BYTECODE, f),
+        JsFunction::BytecodeGenerator(f) => bytecode(w, F_GENERATOR, f),
+        JsFunction::BytecodeAsync(f) => bytecode(w, F_ASYNC, f),
+        JsFunction::BytecodeAsyncGenerator(f) => bytecode(w, F_ASYNC_GENERATOR, f),
+        JsFunction::Bound(data) => {
+            w.u8(F_BOUND);
+            refs.object(w, &data.target);
+            write_value(refs, w, &data.this_arg);
+            write_values(refs, w, &data.bound_args);
+        }
+        JsFunction::ModuleExportGetter {
+            module_env,
+            binding_name,
+        } => {
+            w.u8(F_EXPORT_GETTER);
+            refs.object(w, module_env);
+            w.str(binding_name.as_str());
+        }
+        JsFunction::ModuleReExportGetter {
+            source_module,
+            source_key,
+        } => {
+            w.u8(F_REEXPORT_GETTER);
+            refs.object(w, source_module);
+            write_key(w, source_key);
+        }
+        JsFunction::AccessorGetter => w.u8(F_ACCESSOR_GETTER),
+        JsFunction::AccessorSetter => w.u8(F_ACCESSOR_SETTER),
+        JsFunction::Native(native) => {
+            return Err(CheckpointError::Unserializable(format!(
+                "native function '{}' created at runtime",
+                native.name
+            )));
+        }
+        _ => {
+            return Err(CheckpointError::Unserializable(format!(
+                "internal function '{}'",
+                func.name().unwrap_or("anonymous")
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn write_frames(refs: &mut impl Refs, w: &mut Writer, frames: &[CallFrame]) {
+    w.usize(frames.len());
+    for frame in frames {
+        w.usize(frame.return_ip);
+        refs.chunk(w, &frame.return_chunk);
+        w.usize(frame.registers_base);
+        w.u8(frame.return_register);
+        write_opt_object(refs, w, frame.saved_env.as_ref());
+    }
+}
+
+fn write_try_stack(w: &mut Writer, handlers: &[TryHandler]) {
+    w.usize(handlers.len());
+    for handler in handlers {
+        w.usize(handler.catch_ip);
+        w.usize(handler.finally_ip);
+        w.usize(handler.registers_snapshot);
+        w.usize(handler.frame_depth);
+        w.usize(handler.scope_depth);
+        match handler.iterator_reg {
+            Some(reg) => {
+                w.u8(1);
+                w.u8(reg);
+            }
+            None => w.u8(0),
+        }
+    }
+}
+
+fn write_saved_state(refs: &mut impl Refs, w: &mut Writer, state: &SavedVmState) {
+    write_frames(refs, w, &state.frames);
+    w.usize(state.ip);
+    refs.chunk(w, &state.chunk);
+    write_values(refs, w, &state.registers);
+    write_try_stack(w, &state.try_stack);
+    write_values(refs, w, &state.arguments);
+    write_value(refs, w, &state.new_target);
+    w.usize(state.trampoline_stack.len());
+    for frame in &state.trampoline_stack {
+        w.usize(frame.ip);
+        refs.chunk(w, &frame.chunk);
+        write_values(refs, w, &frame.registers);
+        write_value(refs, w, &frame.this_value);
+        write_frames(refs, w, &frame.vm_call_stack);
+        write_try_stack(w, &frame.try_stack);
+        w.usize(frame.saved_env_stack.len());
+        for env in &frame.saved_env_stack {
+            refs.object(w, env);
+        }
+        write_values(refs, w, &frame.arguments);
+        write_value(refs, w, &frame.new_target);
+        write_opt_object(refs, w, frame.current_constructor.as_ref());
+        w.u8(frame.return_register);
+        refs.object(w, &frame.saved_interp_env);
+        write_opt_object(refs, w, frame.construct_new_obj.as_ref());
+        w.bool(frame.is_async);
+    }
+    match &state.this_value {
+        Some(this) => {
+            w.u8(1);
+            write_value(refs, w, this);
+        }
+        None => w.u8(0),
+    }
+    write_opt_object(refs, w, state.current_constructor.as_ref());
+    w.usize(state.saved_env_stack.len());
+    for env in &state.saved_env_stack {
+        refs.object(w, env);
+    }
+    w.usize(state.env_guards.len());
+}
+
+/// References of the run being checkpointed: paired intrinsics by index, other
+/// objects queued to be written in full, chunks by hash
+struct Encoder<'a> {
+    intrinsics: &'a FxHashMap<usize, u32>,
+    heap: FxHashMap<usize, u64>,
+    queue: VecDeque<Gc<JsObject>>,
+    chunks: FxHashMap<usize, u64>,
+    chunk_table: Writer,
+    hashes: &'a mut ChunkHashes,
+}
+
+impl Refs for Encoder<'_> {
+    fn object(&mut self, w: &mut Writer, obj: &Gc<JsObject>) {
+        if let Some(&index) = self.intrinsics.get(&obj.id()) {
+            w.u8(V_INTRINSIC);
+            w.uint(u64::from(index));
+            return;
+        }
+        let next = self.heap.len() as u64;
+        let index = *self.heap.entry(obj.id()).or_insert_with(|| {
+            self.queue.push_back(obj.cheap_clone());
+            next
+        });
+        w.u8(V_HEAP);
+        w.uint(index);
+    }
+
+    fn chunk(&mut self, w: &mut Writer, chunk: &Rc<BytecodeChunk>) {
+        let key = Rc::as_ptr(chunk) as usize;
+        let next = self.chunks.len() as u64;
+        let index = match self.chunks.get(&key) {
+            Some(&index) => index,
+            None => {
+                self.chunks.insert(key, next);
+                self.chunk_table.u64(self.hashes.hash(chunk));
+                self.chunk_table.opt_str(chunk.source_file.as_deref());
+                next
+            }
+        };
+        w.uint(index);
+    }
+}
+
+/// References of a fresh interpreter, for comparing intrinsics with their
+/// counterparts in the run
+struct FreshRefs<'a> {
+    index: &'a FxHashMap<usize, u32>,
+}
+
+impl Refs for FreshRefs<'_> {
+    fn object(&mut self, w: &mut Writer, obj: &Gc<JsObject>) {
+        match self.index.get(&obj.id()) {
+            Some(&index) => {
+                w.u8(V_INTRINSIC);
+                w.uint(u64::from(index));
+            }
+            None => w.u8(V_UNKNOWN),
+        }
+    }
+
+    fn chunk(&mut self, w: &mut Writer, chunk: &Rc<BytecodeChunk>) {
+        w.uint(Rc::as_ptr(chunk) as usize as u64);
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Chunk hashes
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// FNV-1a, fed through `fmt::Write` so instructions can be hashed via `Debug`
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Self {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// Length-prefixed, so adjacent strings can't run together
+    fn str(&mut self, s: &str) {
+        self.bytes(&(s.len() as u64).to_le_bytes());
+        self.bytes(s.as_bytes());
+    }
+}
+
+impl fmt::Write for Fnv {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.bytes(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// Content hashes of chunks, memoized by address
+#[derive(Default)]
+struct ChunkHashes {
+    memo: FxHashMap<usize, u64>,
+}
+
+impl ChunkHashes {
+    fn hash(&mut self, chunk: &Rc<BytecodeChunk>) -> u64 {
+        use core::fmt::Write;
+
+        let key = Rc::as_ptr(chunk) as usize;
+        if let Some(&hash) = self.memo.get(&key) {
+            return hash;
+        }
+
+        let mut h = Fnv::new();
+        for constant in &chunk.constants {
+            match constant {
+                Constant::String(s) => {
+                    h.bytes(&[0]);
+                    h.str(s.as_str());
+                }
+                Constant::Number(n) => {
+                    h.bytes(&[1]);
+                    h.bytes(&n.to_bits().to_le_bytes());
+                }
+                Constant::Chunk(child) => {
+                    let child = self.hash(child);
+                    h.bytes(&[2]);
+                    h.bytes(&child.to_le_bytes());
+                }
+                Constant::RegExp { pattern, flags } => {
+                    h.bytes(&[3]);
+                    h.str(pattern.as_str());
+                    h.str(flags.as_str());
+                }
+                Constant::TemplateStrings { cooked, raw } => {
+                    h.bytes(&[4]);
+                    for s in cooked.iter().chain(raw) {
+                        h.str(s.as_str());
+                    }
+                }
+                Constant::ExcludedKeys(keys) => {
+                    h.bytes(&[5]);
+                    for s in keys {
+                        h.str(s.as_str());
+                    }
+                }
+                Constant::SwitchTable(table) => {
+                    h.bytes(&[6]);
+                    let mut cases: Vec<_> = table.iter().collect();
+                    cases.sort_by_key(|(s, target)| (**target, s.as_str()));
+                    for (s, target) in cases {
+                        h.str(s.as_str());
+                        h.bytes(&target.to_le_bytes());
+                    }
+                }
+            }
+        }
+        let _ = write!(h, "{:?}{:?}", chunk.code, chunk.source_map);
+        h.bytes(&[chunk.register_count]);
+        if let Some(info) = &chunk.function_info {
+            // Everything but the source text, which is only kept on request
+            let _ = write!(
+                h,
+                "{:?}|{}|{}{}{}{}{}{}|{:?}|{}",
+                info.name.as_ref().map(|n| n.as_str()),
+                info.param_count,
+                info.is_generator,
+                info.is_async,
+                info.is_arrow,
+                info.uses_arguments,
+                info.uses_this,
+                info.creates_closures,
+                info.rest_param,
+                info.binding_count,
+            );
+            for name in &info.param_names {
+                h.str(name.as_str());
+            }
+        }
+        if let Some(file) = &chunk.source_file {
+            h.str(file);
+        }
+
+        self.memo.insert(key, h.0);
+        h.0
+    }
+
+    /// Record `chunk` and every chunk nested in it by hash
+    fn index(
+        &mut self,
+        chunk: &Rc<BytecodeChunk>,
+        by_hash: &mut FxHashMap<u64, Rc<BytecodeChunk>>,
+    ) {
+        let hash = self.hash(chunk);
+        if by_hash.contains_key(&hash) {
+            return;
+        }
+        by_hash.insert(hash, chunk.clone());
+        for constant in &chunk.constants {
+            if let Constant::Chunk(child) = constant {
+                self.index(child, by_hash);
+            }
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Intrinsics
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Objects a fresh interpreter starts with, in walk order
+struct Intrinsics {
+    objects: Vec<Gc<JsObject>>,
+    /// Object id -> position in `objects`
+    index: FxHashMap<usize, u32>,
+    /// Hash of the walk's shape, equal for interpreters with the same builtins
+    fingerprint: u64,
+}
+
+/// Where the intrinsic walk starts
+fn walk_roots(interp: &Interpreter, specifiers: &[String]) -> Vec<Gc<JsObject>> {
+    let mut roots = vec![
+        interp.global_env.cheap_clone(),
+        interp.global.cheap_clone(),
+        interp.object_prototype.cheap_clone(),
+        interp.array_prototype.cheap_clone(),
+        interp.function_prototype.cheap_clone(),
+        interp.string_prototype.cheap_clone(),
+        interp.number_prototype.cheap_clone(),
+        interp.boolean_prototype.cheap_clone(),
+        interp.regexp_prototype.cheap_clone(),
+        interp.map_prototype.cheap_clone(),
+        interp.set_prototype.cheap_clone(),
+        interp.date_prototype.cheap_clone(),
+        interp.symbol_prototype.cheap_clone(),
+        interp.promise_prototype.cheap_clone(),
+        interp.iterator_prototype.cheap_clone(),
+        interp.iterator_helper_prototype.cheap_clone(),
+        interp.generator_prototype.cheap_clone(),
+        interp.url_prototype.cheap_clone(),
+        interp.url_search_params_prototype.cheap_clone(),
+        interp.error_prototype.cheap_clone(),
+        interp.type_error_prototype.cheap_clone(),
+        interp.reference_error_prototype.cheap_clone(),
+        interp.range_error_prototype.cheap_clone(),
+        interp.syntax_error_prototype.cheap_clone(),
+        interp.eval_error_prototype.cheap_clone(),
+        interp.uri_error_prototype.cheap_clone(),
+        interp.aggregate_error_prototype.cheap_clone(),
+        interp.suppressed_error_prototype.cheap_clone(),
+    ];
+    for specifier in specifiers {
+        if let Some(namespace) = interp.internal_module_cache.get(specifier) {
+            roots.push(namespace.cheap_clone());
+        }
+    }
+    roots
+}
+
+/// Objects an object refers to, labelled so two graphs can be walked in step
+fn children(obj: &Gc<JsObject>) -> Vec<(String, Gc<JsObject>)> {
+    let mut children = Vec::new();
+    let obj_ref = obj.borrow();
+    if let Some(proto) = &obj_ref.prototype {
+        children.push(("proto".to_string(), proto.cheap_clone()));
+    }
+    for (key, prop) in obj_ref.properties.iter() {
+        let label = key_label(key);
+        if let JsValue::Object(value) = &prop.value {
+            children.push((format!("value {}", label), value.cheap_clone()));
+        }
+        if let Some(getter) = prop.getter() {
+            children.push((format!("get {}", label), getter.cheap_clone()));
+        }
+        if let Some(setter) = prop.setter() {
+            children.push((format!("set {}", label), setter.cheap_clone()));
+        }
+    }
+    match &obj_ref.exotic {
+        ExoticObject::Environment(env) => {
+            let mut bindings: Vec<_> = env.bindings.iter().collect();
+            bindings.sort_by(|(a, _), (b, _)| a.0.as_str().cmp(b.0.as_str()));
+            for (name, binding) in bindings {
+                if let JsValue::Object(value) = &binding.value {
+                    children.push((format!("binding {}", name.0), value.cheap_clone()));
+                }
+                if let Some(import) = &binding.import_binding {
+                    children.push((
+                        format!("import {}", name.0),
+                        import.module_obj.cheap_clone(),
+                    ));
+                }
+            }
+            if let Some(outer) = &env.outer {
+                children.push(("outer".to_string(), outer.cheap_clone()));
+            }
+        }
+        ExoticObject::Array { elements } => {
+            for (i, element) in elements.iter().enumerate() {
+                if let JsValue::Object(value) = element {
+                    children.push((format!("element {}", i), value.cheap_clone()));
+                }
+            }
+        }
+        ExoticObject::Function(func) => match func {
+            JsFunction::Bytecode(f)
+            | JsFunction::BytecodeGenerator(f)
+            | JsFunction::BytecodeAsync(f)
+            | JsFunction::BytecodeAsyncGenerator(f) => {
+                children.push(("closure".to_string(), f.closure.cheap_clone()));
+                if let Some(this) = &f.captured_this
+                    && let JsValue::Object(this) = this.as_ref()
+                {
+                    children.push(("this".to_string(), this.cheap_clone()));
+                }
+            }
+            JsFunction::Bound(data) => {
+                children.push(("target".to_string(), data.target.cheap_clone()));
+                if let JsValue::Object(this) = &data.this_arg {
+                    children.push(("this".to_string(), this.cheap_clone()));
+                }
+                for (i, arg) in data.bound_args.iter().enumerate() {
+                    if let JsValue::Object(arg) = arg {
+                        children.push((format!("arg {}", i), arg.cheap_clone()));
+                    }
+                }
+            }
+            JsFunction::ModuleExportGetter { module_env, .. } => {
+                children.push(("module".to_string(), module_env.cheap_clone()));
+            }
+            JsFunction::ModuleReExportGetter { source_module, .. } => {
+                children.push(("module".to_string(), source_module.cheap_clone()));
+            }
+            _ => {}
+        },
+        _ => {}
+    }
+    children
+}
+
+fn key_label(key: &PropertyKey) -> String {
+    match key {
+        PropertyKey::String(s) => format!("'{}'", s),
+        PropertyKey::Index(i) => format!("{}", i),
+        PropertyKey::Symbol(symbol) => format!("@{}", symbol.id()),
+    }
+}
+
+/// Distinguishes exotic kinds (and function kinds) for pairing
+fn exotic_kind(exotic: &ExoticObject) -> u8 {
+    match exotic {
+        ExoticObject::Ordinary => 0,
+        ExoticObject::Array { .. } => 1,
+        ExoticObject::Boolean(_) => 2,
+        ExoticObject::Number(_) => 3,
+        ExoticObject::StringObj(_) => 4,
+        ExoticObject::Symbol(_) => 5,
+        ExoticObject::Map { .. } => 6,
+        ExoticObject::Set { .. } => 7,
+        ExoticObject::Date { .. } => 8,
+        ExoticObject::RegExp { .. } => 9,
+        ExoticObject::Generator(_) => 10,
+        ExoticObject::BytecodeGenerator(_) => 11,
+        ExoticObject::Promise(_) => 12,
+        ExoticObject::Environment(_) => 13,
+        ExoticObject::Enum(_) => 14,
+        ExoticObject::Proxy(_) => 15,
+        ExoticObject::RawJSON(_) => 16,
+        ExoticObject::Url(_) => 17,
+        ExoticObject::UrlSearchParams(_) => 18,
+        ExoticObject::PendingOrder { .. } => 19,
+        ExoticObject::WeakRef(_) => 20,
+        ExoticObject::FinalizationRegistry(_) => 21,
+        ExoticObject::Host(_) => 22,
+        ExoticObject::DisposableStack(_) => 23,
+        ExoticObject::Function(func) => match func {
+            JsFunction::Bytecode(_) => 32,
+            JsFunction::BytecodeGenerator(_) => 33,
+            JsFunction::BytecodeAsync(_) => 34,
+            JsFunction::BytecodeAsyncGenerator(_) => 35,
+            JsFunction::Native(_) => 36,
+            JsFunction::Bound(_) => 37,
+            JsFunction::PromiseResolve { .. } => 38,
+            JsFunction::PromiseReject { .. } => 39,
+            JsFunction::PromiseAllFulfill { .. } => 40,
+            JsFunction::PromiseAllReject(_) => 41,
+            JsFunction::PromiseRaceSettle { .. } => 42,
+            JsFunction::AccessorGetter => 43,
+            JsFunction::AccessorSetter => 44,
+            JsFunction::ModuleExportGetter { .. } => 45,
+            JsFunction::ModuleReExportGetter { .. } => 46,
+            JsFunction::ProxyRevoke(_) => 47,
+            JsFunction::AsyncGeneratorResume { .. } => 48,
+        },
+    }
+}
+
+/// Kind, native identity and bytecode hash of an object: what must agree for
+/// an object in the run to stand for an intrinsic
+fn identity(obj: &Gc<JsObject>, hashes: &mut ChunkHashes) -> (u8, String, u64) {
+    let obj_ref = obj.borrow();
+    let kind = exotic_kind(&obj_ref.exotic);
+    match &obj_ref.exotic {
+        ExoticObject::Function(JsFunction::Native(native)) => {
+            (kind, native.name.to_string(), native.arity as u64)
+        }
+        ExoticObject::Function(
+            JsFunction::Bytecode(f)
+            | JsFunction::BytecodeGenerator(f)
+            | JsFunction::BytecodeAsync(f)
+            | JsFunction::BytecodeAsyncGenerator(f),
+        ) => (kind, String::new(), hashes.hash(&f.chunk)),
+        _ => (kind, String::new(), 0),
+    }
+}
+
+impl Intrinsics {
+    /// Walk a fresh interpreter breadth-first from its roots
+    fn collect(interp: &Interpreter, specifiers: &[String], hashes: &mut ChunkHashes) -> Self {
+        use core::fmt::Write;
+
+        let mut objects = Vec::new();
+        let mut index = FxHashMap::default();
+        let mut fingerprint = Fnv::new();
+        let mut queue: VecDeque<Gc<JsObject>> = walk_roots(interp, specifiers).into();
+        while let Some(obj) = queue.pop_front() {
+            if index.contains_key(&obj.id()) {
+                continue;
+            }
+            index.insert(obj.id(), objects.len() as u32);
+            let (kind, name, hash) = identity(&obj, hashes);
+            let _ = write!(fingerprint, "{}:{}:{};", kind, name, hash);
+            for (label, child) in children(&obj) {
+                fingerprint.str(&label);
+                queue.push_back(child);
+            }
+            objects.push(obj);
+        }
+        Intrinsics {
+            objects,
+            index,
+            fingerprint: fingerprint.0,
+        }
+    }
+
+    /// Match objects of `interp` to intrinsics by walking both graphs in step
+    /// from corresponding roots. Returns the matched objects of `interp` with
+    /// their intrinsic index.
+    fn pair(
+        &self,
+        interp: &Interpreter,
+        fresh: &Interpreter,
+        specifiers: &[String],
+        hashes: &mut ChunkHashes,
+    ) -> Vec<(Gc<JsObject>, u32)> {
+        let mut paired: Vec<(Gc<JsObject>, u32)> = Vec::new();
+        let mut seen: FxHashSet<usize> = FxHashSet::default();
+        let mut used = vec![false; self.objects.len()];
+        let mut queue: VecDeque<(Gc<JsObject>, Gc<JsObject>)> = walk_roots(interp, specifiers)
+            .into_iter()
+            .zip(walk_roots(fresh, specifiers))
+            .collect();
+
+        while let Some((obj, intrinsic)) = queue.pop_front() {
+            if seen.contains(&obj.id()) {
+                continue;
+            }
+            let Some(&idx) = self.index.get(&intrinsic.id()) else {
+                continue;
+            };
+            if used.get(idx as usize).copied().unwrap_or(true)
+                || identity(&obj, hashes) != identity(&intrinsic, hashes)
+            {
+                continue;
+            }
+            seen.insert(obj.id());
+            if let Some(slot) = used.get_mut(idx as usize) {
+                *slot = true;
+            }
+
+            let fresh_children: FxHashMap<String, Gc<JsObject>> =
+                children(&intrinsic).into_iter().collect();
+            for (label, child) in children(&obj) {
+                if let Some(counterpart) = fresh_children.get(&label) {
+                    queue.push_back((child, counterpart.cheap_clone()));
+                }
+            }
+            paired.push((obj, idx));
+        }
+        paired
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Decoding
+// ═══════════════════════════════════════════════════════════════════════════════
+
+pub(super) fn corrupt(what: &str) -> CheckpointError {
+    CheckpointError::Corrupt(what.to_string())
+}
+
+pub(super) struct Reader<'a> {
+    pub(super) bytes: &'a [u8],
+    pub(super) pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(super) fn u8(&mut self) -> Result<u8, CheckpointError> {
+        let byte = *self
+            .bytes
+            .get(self.pos)
+            .ok_or_else(|| corrupt("unexpected end"))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    pub(super) fn uint(&mut self) -> Result<u64, CheckpointError> {
+        let mut value = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = self.u8()?;
+            if shift >= 64 {
+                return Err(corrupt("integer too long"));
+            }
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+
+    pub(super) fn usize(&mut self) -> Result<usize, CheckpointError> {
+        usize::try_from(self.uint()?).map_err(|_| corrupt("integer out of range"))
+    }
+
+    /// A count of entries that each take at least one byte
+    pub(super) fn len(&mut self) -> Result<usize, CheckpointError> {
+        let len = self.usize()?;
+        if len > self.bytes.len().saturating_sub(self.pos) {
+            return Err(corrupt("length past the end"));
+        }
+        Ok(len)
+    }
+
+    pub(super) fn u32(&mut self) -> Result<u32, CheckpointError> {
+        u32::try_from(self.uint()?).map_err(|_| corrupt("integer out of range"))
+    }
+
+    pub(super) fn u64(&mut self) -> Result<u64, CheckpointError> {
+        let end = self.pos + 8;
+        let bytes = self
+            .bytes
+            .get(self.pos..end)
+            .and_then(|bytes| <[u8; 8]>::try_from(bytes).ok())
+            .ok_or_else(|| corrupt("unexpected end"))?;
+        self.pos = end;
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    pub(super) fn f64(&mut self) -> Result<f64, CheckpointError> {
+        Ok(f64::from_bits(self.u64()?))
+    }
+
+    pub(super) fn bool(&mut self) -> Result<bool, CheckpointError> {
+        Ok(self.u8()? != 0)
+    }
+
+    pub(super) fn str(&mut self) -> Result<&'a str, CheckpointError> {
+        let len = self.len()?;
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| corrupt("unexpected end"))?;
+        self.pos += len;
+        core::str::from_utf8(bytes).map_err(|_| corrupt("invalid UTF-8"))
+    }
+
+    pub(super) fn opt_str(&mut self) -> Result<Option<&'a str>, CheckpointError> {
+        Ok(match self.u8()? {
+            0 => None,
+            _ => Some(self.str()?),
+        })
+    }
+}
+
+/// Rebuilds values into a restored interpreter
+struct Decoder<'a> {
+    interp: &'a mut Interpreter,
+    intrinsics: &'a [Gc<JsObject>],
+    heap: &'a [Gc<JsObject>],
+    chunks: &'a [Rc<BytecodeChunk>],
+}
+
+impl Decoder<'_> {
+    fn value(&mut self, r: &mut Reader) -> Result<JsValue, CheckpointError> {
+        let tag = r.u8()?;
+        self.value_with_tag(r, tag)
+    }
+
+    fn value_with_tag(&mut self, r: &mut Reader, tag: u8) -> Result<JsValue, CheckpointError> {
+        Ok(match tag {
+            V_UNDEFINED => JsValue::Undefined,
+            V_NULL => JsValue::Null,
+            V_FALSE => JsValue::Boolean(false),
+            V_TRUE => JsValue::Boolean(true),
+            V_NUMBER => JsValue::Number(r.f64()?),
+            V_STRING => JsValue::String(JsString::from(r.str()?)),
+            V_SYMBOL => JsValue::Symbol(Box::new(self.symbol(r)?)),
+            V_INTRINSIC | V_HEAP => JsValue::Object(self.object_with_tag(r, tag)?),
+            _ => return Err(corrupt("unknown value tag")),
+        })
+    }
+
+    fn values(&mut self, r: &mut Reader) -> Result<Vec<JsValue>, CheckpointError> {
+        let len = r.len()?;
+        let mut values = Vec::with_capacity(len);
+        for _ in 0..len {
+            values.push(self.value(r)?);
+        }
+        Ok(values)
+    }
+
+    fn object(&mut self, r: &mut Reader) -> Result<Gc<JsObject>, CheckpointError> {
+        let tag = r.u8()?;
+        self.object_with_tag(r, tag)
+    }
+
+    fn object_with_tag(
+        &mut self,
+        r: &mut Reader,
+        tag: u8,
+    ) -> Result<Gc<JsObject>, CheckpointError> {
+        let objects = match tag {
+            V_INTRINSIC => self.intrinsics,
+            V_HEAP => self.heap,
+            _ => return Err(corrupt("expected an object")),
+        };
+        objects
+            .get(r.usize()?)
+            .map(|obj| obj.cheap_clone())
+            .ok_or_else(|| corrupt("object index out of range"))
+    }
+
+    fn opt_object(&mut self, r: &mut Reader) -> Result<Option<Gc<JsObject>>, CheckpointError> {
+        match r.u8()? {
+            V_UNDEFINED => Ok(None),
+            tag => self.object_with_tag(r, tag).map(Some),
+        }
+    }
+
+    fn chunk(&mut self, r: &mut Reader) -> Result<Rc<BytecodeChunk>, CheckpointError> {
+        self.chunks
+            .get(r.usize()?)
+            .cloned()
+            .ok_or_else(|| corrupt("chunk index out of range"))
+    }
+
+    fn symbol(&mut self, r: &mut Reader) -> Result<JsSymbol, CheckpointError> {
+        let id = r.uint()?;
+        let description = r.opt_str()?.map(JsString::from);
+        Ok(JsSymbol::new(id, description))
+    }
+
+    fn key(&mut self, r: &mut Reader) -> Result<PropertyKey, CheckpointError> {
+        Ok(match r.u8()? {
+            K_STRING => PropertyKey::String(self.interp.intern(r.str()?)),
+            K_INDEX => PropertyKey::Index(r.u32()?),
+            K_SYMBOL => PropertyKey::Symbol(Box::new(self.symbol(r)?)),
+            _ => return Err(corrupt("unknown property key tag")),
+        })
+    }
+
+    /// Read an object record written by `write_record` into `obj`
+    fn record(&mut self, r: &mut Reader, obj: &Gc<JsObject>) -> Result<(), CheckpointError> {
+        let prototype = self.opt_object(r)?;
+        let flags = r.u8()?;
+
+        let len = r.len()?;
+        let mut properties = PropertyStorage::with_capacity(len);
+        for _ in 0..len {
+            let key = self.key(r)?;
+            let pflags = r.u8()?;
+            let mut prop = if pflags & P_ACCESSOR != 0 {
+                let getter = self.opt_object(r)?;
+                let setter = self.opt_object(r)?;
+                Property::accessor(getter, setter)
+            } else {
+                Property::data(self.value(r)?)
+            };
+            prop.set_writable(pflags & P_WRITABLE != 0);
+            prop.set_enumerable(pflags & P_ENUMERABLE != 0);
+            prop.set_configurable(pflags & P_CONFIGURABLE != 0);
+            properties.insert(key, prop);
+        }
+
+        let len = r.len()?;
+        let private_fields = if len == 0 {
+            None
+        } else {
+            let mut fields = FxHashMap::default();
+            for _ in 0..len {
+                let class_brand = r.u32()?;
+                let field_name = self.interp.intern(r.str()?);
+                let value = self.value(r)?;
+                fields.insert(PrivateFieldKey::new(class_brand, field_name), value);
+            }
+            Some(fields)
+        };
+
+        let exotic = self.exotic(r)?;
+
+        let mut obj_ref = obj.borrow_mut();
+        obj_ref.prototype = prototype;
+        obj_ref.extensible = flags & O_EXTENSIBLE != 0;
+        obj_ref.frozen = flags & O_FROZEN != 0;
+        obj_ref.sealed = flags & O_SEALED != 0;
+        obj_ref.null_prototype = flags & O_NULL_PROTOTYPE != 0;
+        obj_ref.deep_frozen = flags & O_DEEP_FROZEN != 0;
+        obj_ref.strict_reads = flags & O_STRICT_READS != 0;
+        obj_ref.properties = properties;
+        obj_ref.private_fields = private_fields;
+        if let Some(exotic) = exotic {
+            obj_ref.exotic = exotic;
+        }
+        Ok(())
+    }
+
+    /// Exotic state, `None` when an overlay keeps the intrinsic's own
+    fn exotic(&mut self, r: &mut Reader) -> Result<Option<ExoticObject>, CheckpointError> {
+        Ok(Some(match r.u8()? {
+            X_KEEP => return Ok(None),
+            X_ORDINARY => ExoticObject::Ordinary,
+            X_ARRAY => ExoticObject::Array {
+                elements: self.values(r)?,
+            },
+            X_BOOLEAN => ExoticObject::Boolean(r.bool()?),
+            X_NUMBER => ExoticObject::Number(r.f64()?),
+            X_STRING => ExoticObject::StringObj(JsString::from(r.str()?)),
+            X_SYMBOL => ExoticObject::Symbol(Box::new(self.symbol(r)?)),
+            X_MAP => {
+                let len = r.len()?;
+                let mut entries = index_map_with_capacity(len);
+                for _ in 0..len {
+                    let key = self.value(r)?;
+                    let value = self.value(r)?;
+                    entries.insert(JsMapKey(key), value);
+                }
+                ExoticObject::Map { entries }
+            }
+            X_SET => {
+                let len = r.len()?;
+                let mut entries = index_set_with_capacity(len);
+                for _ in 0..len {
+                    entries.insert(JsMapKey(self.value(r)?));
+                }
+                ExoticObject::Set { entries }
+            }
+            X_DATE => ExoticObject::Date {
+                timestamp: r.f64()?,
+            },
+            X_REGEXP => ExoticObject::RegExp {
+                pattern: r.str()?.to_string(),
+                flags: r.str()?.to_string(),
+                compiled: None,
+            },
+            X_PROMISE => {
+                let status = match r.u8()? {
+                    0 => PromiseStatus::Fulfilled,
+                    _ => PromiseStatus::Rejected,
+                };
+                let result = Some(self.value(r)?);
+                let order_id = match r.u8()? {
+                    0 => None,
+                    _ => Some(OrderId(r.uint()?)),
+                };
+                ExoticObject::Promise(Rc::new(RefCell::new(PromiseState {
+                    status,
+                    result,
+                    handlers: Vec::new(),
+                    order_id,
+                })))
+            }
+            X_ENVIRONMENT => {
+                let len = r.len()?;
+                let mut env = EnvironmentData::new();
+                env.bindings.reserve(len);
+                for _ in 0..len {
+                    // Bindings are looked up by interned name
+                    let name = self.interp.intern(r.str()?);
+                    let flags = r.u8()?;
+                    let value = self.value(r)?;
+                    let import_binding = match r.u8()? {
+                        0 => None,
+                        _ => Some(ImportBinding {
+                            module_obj: self.object(r)?,
+                            property_key: self.key(r)?,
+                        }),
+                    };
+                    env.bindings.insert(
+                        VarKey(name),
+                        crate::value::Binding {
+                            value,
+                            mutable: flags & 1 != 0,
+                            initialized: flags & 2 != 0,
+                            import_binding,
+                        },
+                    );
+                }
+                env.outer = self.opt_object(r)?;
+                ExoticObject::Environment(env)
+            }
+            X_ENUM => {
+                let name = JsString::from(r.str()?);
+                let const_ = r.bool()?;
+                let len = r.len()?;
+                let mut members = Vec::with_capacity(len);
+                for _ in 0..len {
+                    members.push(EnumMember {
+                        name: JsString::from(r.str()?),
+                        value: self.value(r)?,
+                    });
+                }
+                ExoticObject::Enum(EnumData {
+                    name,
+                    const_,
+                    members,
+                })
+            }
+            X_RAW_JSON => ExoticObject::RawJSON(JsString::from(r.str()?)),
+            X_PENDING_ORDER => ExoticObject::PendingOrder { id: r.uint()? },
+            X_FUNCTION => ExoticObject::Function(self.function(r)?),
+            _ => return Err(corrupt("unknown object tag")),
+        }))
+    }
+
+    fn function(&mut self, r: &mut Reader) -> Result<JsFunction, CheckpointError> {
+        let tag = r.u8()?;
+        let bytecode =
+            |this: &mut Self, r: &mut Reader| -> Result<BytecodeFunction, CheckpointError> {
+                let chunk = this.chunk(r)?;
+                let closure = this.object(r)?;
+                let captured_this = match r.u8()? {
+                    0 => None,
+                    _ => Some(Box::new(this.value(r)?)),
+                };
+                Ok(BytecodeFunction {
+                    chunk,
+                    closure,
+                    captured_this,
+                })
+            };
+        Ok(match tag {
+            F_BYTECODE => JsFunction::Bytecode(bytecode(self, r)?),
+            F_GENERATOR => JsFunction::BytecodeGenerator(bytecode(self, r)?),
+            F_ASYNC => JsFunction::BytecodeAsync(bytecode(self, r)?),
+            F_ASYNC_GENERATOR => JsFunction::BytecodeAsyncGenerator(bytecode(self, r)?),
+            F_BOUND => JsFunction::Bound(Box::new(BoundFunctionData {
+                target: self.object(r)?,
+                this_arg: self.value(r)?,
+                bound_args: self.values(r)?,
+            })),
+            F_EXPORT_GETTER => JsFunction::ModuleExportGetter {
+                module_env: self.object(r)?,
+                binding_name: self.interp.intern(r.str()?),
+            },
+            F_REEXPORT_GETTER => JsFunction::ModuleReExportGetter {
+                source_module: self.object(r)?,
+                source_key: self.key(r)?,
+            },
+            F_ACCESSOR_GETTER => JsFunction::AccessorGetter,
+            F_ACCESSOR_SETTER => JsFunction::AccessorSetter,
+            _ => return Err(corrupt("unknown function tag")),
+        })
+    }
+
+    fn frames(&mut self, r: &mut Reader) -> Result<Vec<CallFrame>, CheckpointError> {
+        let len = r.len()?;
+        let mut frames = Vec::with_capacity(len);
+        for _ in 0..len {
+            frames.push(CallFrame {
+                return_ip: r.usize()?,
+                return_chunk: self.chunk(r)?,
+                registers_base: r.usize()?,
+                return_register: r.u8()?,
+                saved_env: self.opt_object(r)?,
+            });
+        }
+        Ok(frames)
+    }
+
+    fn try_stack(&mut self, r: &mut Reader) -> Result<Vec<TryHandler>, CheckpointError> {
+        let len = r.len()?;
+        let mut handlers = Vec::with_capacity(len);
+        for _ in 0..len {
+            handlers.push(TryHandler {
+                catch_ip: r.usize()?,
+                finally_ip: r.usize()?,
+                registers_snapshot: r.usize()?,
+                frame_depth: r.usize()?,
+                scope_depth: r.usize()?,
+                iterator_reg: match r.u8()? {
+                    0 => None,
+                    _ => Some(r.u8()?),
+                },
+            });
+        }
+        Ok(handlers)
+    }
+
+    fn saved_state(
+        &mut self,
+        r: &mut Reader,
+        guard: crate::gc::Guard<JsObject>,
+    ) -> Result<SavedVmState, CheckpointError> {
+        let frames = self.frames(r)?;
+        let ip = r.usize()?;
+        let chunk = self.chunk(r)?;
+        let registers = self.values(r)?;
+        let try_stack = self.try_stack(r)?;
+        let arguments = self.values(r)?;
+        let new_target = self.value(r)?;
+
+        let len = r.len()?;
+        let mut trampoline_stack = Vec::with_capacity(len);
+        for _ in 0..len {
+            let ip = r.usize()?;
+            let chunk = self.chunk(r)?;
+            let registers = self.values(r)?;
+            let this_value = self.value(r)?;
+            let vm_call_stack = self.frames(r)?;
+            let try_stack = self.try_stack(r)?;
+            let env_count = r.len()?;
+            let mut saved_env_stack = Vec::with_capacity(env_count);
+            for _ in 0..env_count {
+                saved_env_stack.push(self.object(r)?);
+            }
+            trampoline_stack.push(SavedTrampolineFrame {
+                ip,
+                chunk,
+                registers,
+                this_value,
+                vm_call_stack,
+                try_stack,
+                saved_env_stack,
+                arguments: self.values(r)?,
+                new_target: self.value(r)?,
+                current_constructor: self.opt_object(r)?,
+                return_register: r.u8()?,
+                saved_interp_env: self.object(r)?,
+                construct_new_obj: self.opt_object(r)?,
+                is_async: r.bool()?,
+            });
+        }
+
+        let this_value = match r.u8()? {
+            0 => None,
+            _ => Some(self.value(r)?),
+        };
+        let current_constructor = self.opt_object(r)?;
+        let env_count = r.len()?;
+        let mut saved_env_stack = Vec::with_capacity(env_count);
+        for _ in 0..env_count {
+            saved_env_stack.push(self.object(r)?);
+        }
+        // Placeholders until the current environment is decoded
+        let env_guards = (0..r.len()?)
+            .map(|_| self.interp.heap.create_guard())
+            .collect();
+        Ok(SavedVmState {
+            frames,
+            ip,
+            chunk,
+            registers,
+            try_stack,
+            guard: Some(guard),
+            arguments,
+            new_target,
+            trampoline_stack,
+            this_value,
+            current_constructor,
+            saved_env_stack,
+            env_guards,
+        })
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Checkpoint and restore
+// ═══════════════════════════════════════════════════════════════════════════════
+
+impl Interpreter {
+    /// Serialize a run suspended on an order.
+    ///
+    /// Valid once `step()` has returned `StepResult::Suspended` for a batched
+    /// order the host has not fulfilled yet. The checkpoint holds the suspended
+    /// VM state (frames, registers and try handlers, with bytecode referenced by
+    /// content hash), every object the run reaches from it, the ids of the
+    /// outstanding orders, and which modules were loaded. Restore it with
+    /// [`Interpreter::restore`]. Order payloads are not included: the host
+    /// keeps what it needs to fulfill an order alongside the checkpoint.
+    ///
+    /// Fails with `CheckpointError::InvalidState` in any other state, and with
+    /// `CheckpointError::Unserializable` when the run reaches something that
+    /// can't be rebuilt from the checkpoint: proxies, open generators, pending
+    /// promises, or native functions that aren't builtins or internal module
+    /// exports.
+    pub fn checkpoint(&self) -> Result<Vec<u8>, CheckpointError> {
+        let suspension = self.checkpointable()?;
+
+        let mut specifiers: Vec<String> = self.internal_module_cache.keys().cloned().collect();
+        specifiers.sort();
+
+        // A fresh interpreter like this one, to tell builtins from what the run created
+        let mut fresh = Interpreter::with_config(InterpreterConfig {
+            internal_modules: self
+                .internal_modules
+                .values()
+                .map(|module| crate::InternalModule {
+                    specifier: module.specifier.clone(),
+                    kind: module.kind.clone(),
+                    retain_across_reset: module.retain_across_reset,
+                })
+                .collect(),
+            shared_modules: self.shared_modules.clone(),
+            ..Default::default()
+        });
+        load_internal_modules(&mut fresh, &specifiers)?;
+        fresh.match_builtins(self.pending_builtins);
+
+        let mut hashes = ChunkHashes::default();
+        let intrinsics = Intrinsics::collect(&fresh, &specifiers, &mut hashes);
+        let paired = intrinsics.pair(self, &fresh, &specifiers, &mut hashes);
+        let paired_index: FxHashMap<usize, u32> =
+            paired.iter().map(|(obj, idx)| (obj.id(), *idx)).collect();
+
+        let mut enc = Encoder {
+            intrinsics: &paired_index,
+            heap: FxHashMap::default(),
+            queue: VecDeque::new(),
+            chunks: FxHashMap::default(),
+            chunk_table: Writer::default(),
+            hashes: &mut hashes,
+        };
+
+        let mut module_paths: Vec<&ModulePath> = self.loaded_modules.keys().collect();
+        module_paths.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        let mut roots = Writer::default();
+        self.write_roots(&mut enc, &mut roots, suspension, &module_paths);
+
+        // Intrinsics the run changed
+        let mut overlays = Writer::default();
+        let mut overlay_count = 0;
+        let mut fresh_refs = FreshRefs {
+            index: &intrinsics.index,
+        };
+        for (obj, idx) in &paired {
+            let Some(intrinsic) = intrinsics.objects.get(*idx as usize) else {
+                continue;
+            };
+            let mut current = Writer::default();
+            write_record(&mut enc, &mut current, obj, true)?;
+            let mut original = Writer::default();
+            write_record(&mut fresh_refs, &mut original, intrinsic, true)?;
+            if current.buf != original.buf {
+                overlays.uint(u64::from(*idx));
+                overlays.bytes(&current.buf);
+                overlay_count += 1;
+            }
+        }
+
+        // Everything the run created, discovered as it is written
+        let mut heap = Writer::default();
+        while let Some(obj) = enc.queue.pop_front() {
+            write_record(&mut enc, &mut heap, &obj, false)?;
+        }
+
+        let mut out = Writer::default();
+        out.bytes(MAGIC);
+        out.uint(VERSION);
+        out.u64(intrinsics.fingerprint);
+        out.uint(u64::from(self.pending_builtins));
+        out.usize(intrinsics.objects.len());
+        out.usize(specifiers.len());
+        for specifier in &specifiers {
+            out.str(specifier);
+        }
+        out.opt_str(self.main_module_path.as_ref().map(|p| p.as_str()));
+        out.usize(module_paths.len());
+        for path in &module_paths {
+            out.str(path.as_str());
+        }
+        out.usize(enc.chunks.len());
+        out.bytes(&enc.chunk_table.buf);
+        out.usize(enc.heap.len());
+        out.bytes(&heap.buf);
+        out.usize(overlay_count);
+        out.bytes(&overlays.buf);
+        out.bytes(&roots.buf);
+        Ok(out.buf)
+    }
+
+    /// Rebuild a run from [`Interpreter::checkpoint`] in a new interpreter
+    /// with the default configuration.
+    ///
+    /// `sources` must hold the entry program and every module the run had
+    /// loaded, unchanged. The restored interpreter is suspended on the same
+    /// orders, listed by [`Interpreter::outstanding_orders`]; fulfill them
+    /// and call `step()` to carry on.
+    pub fn restore(bytes: &[u8], sources: &ModuleSources) -> Result<Self, CheckpointError> {
+        Self::restore_with_config(InterpreterConfig::default(), bytes, sources)
+    }
+
+    /// Rebuild a run from [`Interpreter::checkpoint`] in a new interpreter.
+    ///
+    /// `config` must register the same internal modules as the interpreter
+    /// that made the checkpoint.
+    pub fn restore_with_config(
+        config: InterpreterConfig,
+        bytes: &[u8],
+        sources: &ModuleSources,
+    ) -> Result<Self, CheckpointError> {
+        let mut r = Reader { bytes, pos: 0 };
+        if bytes.get(..MAGIC.len()) != Some(MAGIC.as_slice()) {
+            return Err(corrupt("not a checkpoint"));
+        }
+        r.pos = MAGIC.len();
+        let version = r.uint()?;
+        if version != VERSION {
+            return Err(CheckpointError::IncompatibleRuntime(format!(
+                "checkpoint format {} is not supported",
+                version
+            )));
+        }
+        let fingerprint = r.u64()?;
+        let pending_builtins =
+            u32::try_from(r.uint()?).map_err(|_| corrupt("lazy builtins out of range"))?;
+        let intrinsic_count = r.usize()?;
+        let mut specifiers = Vec::new();
+        for _ in 0..r.len()? {
+            specifiers.push(r.str()?.to_string());
+        }
+
+        let mut interp = Interpreter::with_config(config);
+        load_internal_modules(&mut interp, &specifiers)?;
+        interp.match_builtins(pending_builtins);
+        let mut hashes = ChunkHashes::default();
+        let intrinsics = Intrinsics::collect(&interp, &specifiers, &mut hashes);
+        if intrinsics.fingerprint != fingerprint || intrinsics.objects.len() != intrinsic_count {
+            return Err(CheckpointError::IncompatibleRuntime(
+                "builtins or internal modules differ".to_string(),
+            ));
+        }
+
+        // Recompile everything the run had compiled, to find its chunks by hash
+        let mut by_hash: FxHashMap<u64, Rc<BytecodeChunk>> = FxHashMap::default();
+        for obj in &intrinsics.objects {
+            if let ExoticObject::Function(
+                JsFunction::Bytecode(f)
+                | JsFunction::BytecodeGenerator(f)
+                | JsFunction::BytecodeAsync(f)
+                | JsFunction::BytecodeAsyncGenerator(f),
+            ) = &obj.borrow().exotic
+            {
+                hashes.index(&f.chunk, &mut by_hash);
+            }
+        }
+        let main_module_path = r.opt_str()?.map(ModulePath::new);
+        let entry_name = main_module_path
+            .as_ref()
+            .map_or("<entry>", |path| path.as_str())
+            .to_string();
+        let entry = sources
+            .entry
+            .as_deref()
+            .ok_or_else(|| CheckpointError::SourceMismatch {
+                path: entry_name.clone(),
+            })?;
+        let chunk = interp
+            .compile_for_restore(entry, main_module_path.as_ref(), true)
+            .ok_or(CheckpointError::SourceMismatch { path: entry_name })?;
+        hashes.index(&chunk, &mut by_hash);
+
+        let mut module_paths = Vec::new();
+        for _ in 0..r.len()? {
+            let path = ModulePath::new(r.str()?);
+            let mismatch = || CheckpointError::SourceMismatch {
+                path: path.as_str().to_string(),
+            };
+            let source = sources.modules.get(path.as_str()).ok_or_else(mismatch)?;
+            let chunk = interp
+                .compile_for_restore(source, Some(&path), false)
+                .ok_or_else(mismatch)?;
+            hashes.index(&chunk, &mut by_hash);
+            module_paths.push(path);
+        }
+
+        let mut chunks = Vec::new();
+        for _ in 0..r.len()? {
+            let hash = r.u64()?;
+            let file = r.opt_str()?;
+            let chunk = by_hash
+                .get(&hash)
+                .ok_or_else(|| CheckpointError::SourceMismatch {
+                    path: file.unwrap_or("<entry>").to_string(),
+                })?;
+            chunks.push(chunk.clone());
+        }
+
+        // Allocate every object first so records can refer to each other
+        let guard = interp.heap.create_guard();
+        guard.set_label("restored checkpoint");
+        let heap_count = r.len()?;
+        let heap: Vec<Gc<JsObject>> = (0..heap_count).map(|_| guard.alloc()).collect();
+
+        let mut dec = Decoder {
+            interp: &mut interp,
+            intrinsics: &intrinsics.objects,
+            heap: &heap,
+            chunks: &chunks,
+        };
+        for obj in &heap {
+            dec.record(&mut r, obj)?;
+        }
+        for _ in 0..r.len()? {
+            let idx = r.usize()?;
+            let obj = intrinsics
+                .objects
+                .get(idx)
+                .ok_or_else(|| corrupt("intrinsic index out of range"))?;
+            dec.record(&mut r, obj)?;
+        }
+        read_roots(&mut dec, &mut r, guard, main_module_path, &module_paths)?;
+
+        if r.pos != bytes.len() {
+            return Err(corrupt("trailing bytes"));
+        }
+        Ok(interp)
+    }
+
+    /// The order suspension a checkpoint would capture, if the state allows one
+    fn checkpointable(&self) -> Result<&VmOrderSuspension, CheckpointError> {
+        let invalid = |reason: &str| Err(CheckpointError::InvalidState(reason.to_string()));
+        let Some(suspension) = &self.suspended_for_order else {
+            return invalid("the interpreter is not suspended on an order");
+        };
+        if self.active_vm.is_some()
+            || self.deferred_completion.is_some()
+            || self.held_completion.is_some()
+            || self.active_context_eval.is_some()
+            || self.purity.is_some()
+            || self.incremental.is_some()
+        {
+            return invalid("execution is in progress");
+        }
+        if self.pending_program.is_some()
+            || !self.pending_module_sources.is_empty()
+            || !self.requested_imports.is_empty()
+        {
+            return invalid("imports are still being loaded");
+        }
+        if self.folded_constant_imports {
+            return invalid("imports were folded to constants");
+        }
+        if !self.pending_orders.is_empty() || !self.cancelled_orders.is_empty() {
+            return invalid("orders have not been reported by step() yet");
+        }
+        if !self.order_responses.is_empty() {
+            return invalid("fulfilled orders have not been resumed yet");
+        }
+        if !self.eager_order_promises.is_empty() || self.wait_graph.has_waiting_contexts() {
+            return invalid("async functions are waiting on promises");
+        }
+        Ok(suspension)
+    }
+
+    /// Everything besides the heap a restored interpreter needs
+    fn write_roots(
+        &self,
+        enc: &mut Encoder,
+        w: &mut Writer,
+        suspension: &VmOrderSuspension,
+        module_paths: &[&ModulePath],
+    ) {
+        w.uint(suspension.order_id.0);
+        w.u8(suspension.resume_register);
+        write_saved_state(enc, w, &suspension.state);
+
+        enc.object(w, &self.env);
+        w.usize(self.env_guards.len());
+        w.opt_str(self.active_module_path.as_ref().map(|p| p.as_str()));
+        write_opt_object(enc, w, self.active_saved_env.as_ref());
+        write_opt_object(enc, w, self.active_module_env.as_ref());
+        w.opt_str(self.current_module_path.as_ref().map(|p| p.as_str()));
+
+        for path in module_paths {
+            if let Some(namespace) = self.loaded_modules.get(*path) {
+                enc.object(w, namespace);
+            }
+        }
+
+        let mut exports: Vec<_> = self.exports.iter().collect();
+        exports.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+        w.usize(exports.len());
+        for (name, export) in exports {
+            w.str(name.as_str());
+            match export {
+                ModuleExport::Direct { name, value } => {
+                    w.u8(0);
+                    w.str(name.as_str());
+                    write_value(enc, w, value);
+                }
+                ModuleExport::ReExport {
+                    source_module,
+                    source_key,
+                } => {
+                    w.u8(1);
+                    enc.object(w, source_module);
+                    write_key(w, source_key);
+                }
+            }
+        }
+
+        w.usize(self.call_stack.len());
+        for frame in &self.call_stack {
+            w.str(&frame.function_name);
+            match frame.location {
+                Some((line, column)) => {
+                    w.u8(1);
+                    w.uint(u64::from(line));
+                    w.uint(u64::from(column));
+                }
+                None => w.u8(0),
+            }
+        }
+
+        w.uint(self.next_order_id);
+        w.usize(self.outstanding_orders.len());
+        for id in &self.outstanding_orders {
+            w.uint(id.0);
+        }
+        w.u8(match self.order_delivery {
+            OrderDelivery::Batched => 0,
+            OrderDelivery::Eager => 1,
+        });
+
+        w.uint(self.next_symbol_id);
+        w.uint(self.next_generator_id);
+        w.usize(self.symbol_registry.len());
+        for (key, symbol) in &self.symbol_registry {
+            w.str(key.as_str());
+            write_symbol(w, symbol);
+        }
+    }
+
+    /// Parse and compile a source again on restore, as the `entry` program or
+    /// an imported module; `None` if it no longer parses
+    fn compile_for_restore(
+        &mut self,
+        source: &str,
+        path: Option<&ModulePath>,
+        entry: bool,
+    ) -> Option<Rc<BytecodeChunk>> {
+        let program = self.parse_program(source).ok()?;
+        let mut chunk =
+            super::compile_program(&program, path, self.compile_limits, entry, None).ok()?;
+        self.intern_chunk(&mut chunk);
+        Some(chunk)
+    }
+}
+
+/// Import the internal modules a run had imported, in a fixed order
+fn load_internal_modules(
+    interp: &mut Interpreter,
+    specifiers: &[String],
+) -> Result<(), CheckpointError> {
+    for specifier in specifiers {
+        match interp.resolve_internal_module(specifier) {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return Err(CheckpointError::IncompatibleRuntime(format!(
+                    "internal module '{}' is not registered",
+                    specifier
+                )));
+            }
+            Err(err) => return Err(CheckpointError::Restore(err)),
+        }
+    }
+    Ok(())
+}
+
+/// Read what `write_roots` wrote and install it in the restored interpreter
+fn read_roots(
+    dec: &mut Decoder,
+    r: &mut Reader,
+    guard: crate::gc::Guard<JsObject>,
+    main_module_path: Option<ModulePath>,
+    module_paths: &[ModulePath],
+) -> Result<(), CheckpointError> {
+    let order_id = OrderId(r.uint()?);
+    let resume_register = r.u8()?;
+    let mut state = dec.saved_state(r, guard)?;
+
+    let env = dec.object(r)?;
+    // The suspended VM's own guards come back with it
+    state.env_guards = scope_guards(&dec.interp.heap, state.env_guards.len(), &env);
+    let env_depth = r.usize()?;
+    let active_module_path = r.opt_str()?.map(ModulePath::new);
+    let active_saved_env = dec.opt_object(r)?;
+    let active_module_env = dec.opt_object(r)?;
+    let current_module_path = r.opt_str()?.map(ModulePath::new);
+
+    let mut loaded_modules = FxHashMap::default();
+    for path in module_paths {
+        loaded_modules.insert(path.clone(), dec.object(r)?);
+    }
+
+    let mut exports = FxHashMap::default();
+    for _ in 0..r.len()? {
+        let key = dec.interp.intern(r.str()?);
+        let export = match r.u8()? {
+            0 => ModuleExport::Direct {
+                name: dec.interp.intern(r.str()?),
+                value: dec.value(r)?,
+            },
+            _ => ModuleExport::ReExport {
+                source_module: dec.object(r)?,
+                source_key: dec.key(r)?,
+            },
+        };
+        exports.insert(key, export);
+    }
+
+    let mut call_stack = Vec::new();
+    for _ in 0..r.len()? {
+        let function_name = r.str()?.to_string();
+        let location = match r.u8()? {
+            0 => None,
+            _ => Some((r.u32()?, r.u32()?)),
+        };
+        call_stack.push(StackFrame {
+            function_name,
+            location,
+        });
+    }
+
+    let next_order_id = r.uint()?;
+    let mut outstanding = Vec::new();
+    for _ in 0..r.len()? {
+        outstanding.push(OrderId(r.uint()?));
+    }
+    let order_delivery = match r.u8()? {
+        0 => OrderDelivery::Batched,
+        _ => OrderDelivery::Eager,
+    };
+
+    let next_symbol_id = r.uint()?;
+    let next_generator_id = r.uint()?;
+    let mut registry = FxHashMap::default();
+    for _ in 0..r.len()? {
+        let key = JsString::from(r.str()?);
+        registry.insert(key, dec.symbol(r)?);
+    }
+
+    let interp = &mut *dec.interp;
+    // Scopes pop their guards as they exit, so keep the depth; the innermost
+    // guard roots the current environment and its chain
+    let env_guards = scope_guards(&interp.heap, env_depth, &env);
+    interp.env_guards.extend(env_guards);
+    interp.env = env;
+
+    for namespace in loaded_modules.values() {
+        interp.module_guard.guard(namespace.cheap_clone());
+    }
+    if let Some(module_env) = &active_module_env {
+        interp.module_guard.guard(module_env.cheap_clone());
+    }
+    interp.loaded_modules = loaded_modules;
+    interp.main_module_path = main_module_path;
+    interp.current_module_path = current_module_path;
+    interp.active_module_path = active_module_path;
+    interp.active_saved_env = active_saved_env;
+    interp.active_module_env = active_module_env;
+    interp.exports = exports;
+    interp.call_stack = call_stack;
+    interp.next_order_id = next_order_id;
+    interp.outstanding_orders = outstanding;
+    interp.order_delivery = order_delivery;
+    interp.next_symbol_id = interp.next_symbol_id.max(next_symbol_id);
+    interp.next_generator_id = interp.next_generator_id.max(next_generator_id);
+    for (key, symbol) in registry {
+        interp.symbol_registry_insert(key, symbol);
+    }
+    interp.suspended_for_order = Some(VmOrderSuspension {
+        order_id,
+        state,
+        resume_register,
+    });
+    Ok(())
+}
@@ -0,0 +1,145 @@
+//! Sharing of identical compiled code between the chunks of one interpreter.
+//!
+//! Modules generated from one template compile to the same helper functions
+//! again and again. Every chunk the interpreter attaches first has its
+//! strings re-interned through the string dictionary, then goes through the
+//! [`ChunkCache`]: nested function chunks equal to one already attached are
+//! replaced by it, and so are instruction arrays, which are kept apart from
+//! the rest of the chunk so functions differing only in name, position or
+//! file still share their code.
+//!
+//! The cache holds weak references only, so code stops being cached once
+//! the last function or module using it is dropped.
+
+use crate::prelude::*;
+
+use core::hash::{Hash, Hasher};
+use rustc_hash::FxHasher;
+
+use super::Interpreter;
+use crate::compiler::{BytecodeChunk, Constant, Op};
+
+/// How much compiled code the interpreter shares, see [`Interpreter::chunk_cache_stats`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChunkCacheStats {
+    /// Chunks attached so far, nested function chunks included
+    pub chunks_attached: usize,
+    /// Nested function chunks replaced by an identical chunk attached before
+    pub chunks_shared: usize,
+    /// Chunks whose instruction array was replaced by an identical one
+    pub code_shared: usize,
+    /// Distinct nested function chunks alive in the cache
+    pub live_chunks: usize,
+    /// Distinct instruction arrays alive in the cache
+    pub live_code: usize,
+}
+
+/// Attached chunks and instruction arrays by content hash
+#[derive(Default)]
+pub(crate) struct ChunkCache {
+    chunks: FxHashMap<u64, Vec<Weak<BytecodeChunk>>>,
+    code: FxHashMap<u64, Vec<Weak<[Op]>>>,
+    stats: ChunkCacheStats,
+}
+
+impl ChunkCache {
+    /// Share the nested chunks and instruction arrays of a chunk being
+    /// attached. Chunks already shared were attached before and are skipped.
+    pub(crate) fn share(&mut self, chunk: &mut Rc<BytecodeChunk>) {
+        let Some(inner) = Rc::get_mut(chunk) else {
+            return;
+        };
+        self.stats.chunks_attached += 1;
+        for constant in &mut inner.constants {
+            if let Constant::Chunk(child) = constant {
+                // Children first, so parents compare them by identity
+                self.share(child);
+                if let Some(existing) = self.existing_chunk(child) {
+                    *child = existing;
+                    self.stats.chunks_shared += 1;
+                }
+            }
+        }
+        if let Some(existing) = self.existing_code(&inner.code) {
+            inner.code = existing;
+            self.stats.code_shared += 1;
+        }
+    }
+
+    /// A cached chunk equal to `chunk`, or `None` after caching `chunk` itself
+    fn existing_chunk(&mut self, chunk: &Rc<BytecodeChunk>) -> Option<Rc<BytecodeChunk>> {
+        let mut hasher = FxHasher::default();
+        chunk.code.hash(&mut hasher);
+        chunk.source_file.hash(&mut hasher);
+        chunk.constants.len().hash(&mut hasher);
+        // Generated code repeats one function body with different constants
+        // and positions; leaving them out would put every copy in one bucket
+        for constant in &chunk.constants {
+            constant.hash_content(&mut hasher);
+        }
+        for entry in &chunk.source_map {
+            entry.span.start.hash(&mut hasher);
+        }
+        let bucket = self.chunks.entry(hasher.finish()).or_default();
+        bucket.retain(|cached| cached.strong_count() > 0);
+        let existing = bucket
+            .iter()
+            .filter_map(Weak::upgrade)
+            .find(|cached| !Rc::ptr_eq(cached, chunk) && cached.same_content(chunk));
+        if existing.is_none() {
+            bucket.push(Rc::downgrade(chunk));
+        }
+        existing
+    }
+
+    /// A cached instruction array equal to `code`, or `None` after caching `code`
+    fn existing_code(&mut self, code: &Rc<[Op]>) -> Option<Rc<[Op]>> {
+        let mut hasher = FxHasher::default();
+        code.hash(&mut hasher);
+        let bucket = self.code.entry(hasher.finish()).or_default();
+        bucket.retain(|cached| cached.strong_count() > 0);
+        let existing = bucket
+            .iter()
+            .filter_map(Weak::upgrade)
+            .find(|cached| !Rc::ptr_eq(cached, code) && **cached == **code);
+        if existing.is_none() {
+            bucket.push(Rc::downgrade(code));
+        }
+        existing
+    }
+}
+
+impl Interpreter {
+    /// Re-intern the strings of a freshly compiled chunk through the string
+    /// dictionary, then share its nested chunks and instruction arrays with
+    /// identical ones attached before
+    pub(crate) fn intern_chunk(&mut self, chunk: &mut Rc<BytecodeChunk>) {
+        if let Some(chunk) = Rc::get_mut(chunk) {
+            chunk.intern_strings(&mut self.string_dict);
+        }
+        self.chunk_cache.share(chunk);
+        if let Some(budget) = &self.heap_budget {
+            budget.add_chunk(chunk);
+        }
+    }
+
+    /// Counters of the compiled code this interpreter has attached and shared
+    pub fn chunk_cache_stats(&self) -> ChunkCacheStats {
+        let cache = &self.chunk_cache;
+        ChunkCacheStats {
+            live_chunks: cache
+                .chunks
+                .values()
+                .flatten()
+                .filter(|cached| cached.strong_count() > 0)
+                .count(),
+            live_code: cache
+                .code
+                .values()
+                .flatten()
+                .filter(|cached| cached.strong_count() > 0)
+                .count(),
+            ..cache.stats
+        }
+    }
+}
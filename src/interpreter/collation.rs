@@ -0,0 +1,2147 @@
+//! Locale-independent string collation for `String.prototype.localeCompare`.
+//!
+//! There is no locale data, so the locale argument is ignored and every
+//! string compares the same way everywhere. Strings are compared in levels,
+//! as a collator would: first by their letters with case and diacritics
+//! folded away, then by diacritics, then by case (lowercase first). The
+//! `sensitivity` option picks which levels count, and `numeric` compares
+//! runs of digits by their value, so `"item2"` sorts before `"item10"`.
+//!
+//! Diacritics are folded with the table below, generated from the canonical
+//! decompositions in UnicodeData, and by dropping combining marks.
+
+use crate::prelude::*;
+
+use core::cmp::Ordering;
+
+/// Which differences make two strings unequal, as in `Intl.Collator`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum Sensitivity {
+    /// Only different letters: `a = á = A`
+    Base,
+    /// Letters and diacritics: `a ≠ á`, `a = A`
+    Accent,
+    /// Letters and case: `a = á`, `a ≠ A`
+    Case,
+    /// Every difference
+    #[default]
+    Variant,
+}
+
+impl Sensitivity {
+    /// Parse an `Intl.Collator` `sensitivity` option value
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value {
+            "base" => Some(Self::Base),
+            "accent" => Some(Self::Accent),
+            "case" => Some(Self::Case),
+            "variant" => Some(Self::Variant),
+            _ => None,
+        }
+    }
+}
+
+/// The subset of `Intl.Collator` options `localeCompare` understands
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct CollatorOptions {
+    /// Compare runs of ASCII digits by their numeric value
+    pub numeric: bool,
+    pub sensitivity: Sensitivity,
+}
+
+/// Compare `a` to `b` level by level as set by `options`
+pub(crate) fn compare(a: &str, b: &str, options: &CollatorOptions) -> Ordering {
+    let numeric = options.numeric;
+    let primary = compare_keys(
+        &collation_key(a, true, true),
+        &collation_key(b, true, true),
+        numeric,
+    );
+    if primary != Ordering::Equal {
+        return primary;
+    }
+    let (accents, case) = match options.sensitivity {
+        Sensitivity::Base => (false, false),
+        Sensitivity::Accent => (true, false),
+        Sensitivity::Case => (false, true),
+        Sensitivity::Variant => (true, true),
+    };
+    if accents {
+        let secondary = compare_keys(
+            &collation_key(a, false, true),
+            &collation_key(b, false, true),
+            numeric,
+        );
+        if secondary != Ordering::Equal {
+            return secondary;
+        }
+    }
+    if case {
+        return compare_keys(
+            &collation_key(a, true, false),
+            &collation_key(b, true, false),
+            numeric,
+        );
+    }
+    Ordering::Equal
+}
+
+/// The characters of `s`, with diacritics removed and case folded as asked.
+/// Each is paired with whether it is uppercase, so that lowercase sorts
+/// before uppercase as in the root collation.
+fn collation_key(s: &str, strip_accents: bool, fold_case: bool) -> Vec<(bool, char)> {
+    let mut key = Vec::with_capacity(s.len());
+    let mut push = |c: char| {
+        let c = if !strip_accents {
+            c
+        } else if !is_combining_mark(c) {
+            unaccented(c)
+        } else {
+            return;
+        };
+        key.push((c.is_uppercase(), c));
+    };
+    for c in s.chars() {
+        if fold_case {
+            c.to_lowercase().for_each(&mut push);
+        } else {
+            push(c);
+        }
+    }
+    key
+}
+
+/// Compare two keys character by character, or digit runs by value when
+/// `numeric` is set
+fn compare_keys(a: &[(bool, char)], b: &[(bool, char)], numeric: bool) -> Ordering {
+    let (mut i, mut j) = (0, 0);
+    loop {
+        let (Some(&x), Some(&y)) = (a.get(i), b.get(j)) else {
+            return (a.len() - i).cmp(&(b.len() - j));
+        };
+        if numeric && x.1.is_ascii_digit() && y.1.is_ascii_digit() {
+            let x_run = digit_run(a, i);
+            let y_run = digit_run(b, j);
+            let ordering = compare_digit_runs(
+                a.get(i..x_run).unwrap_or_default(),
+                b.get(j..y_run).unwrap_or_default(),
+            );
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+            (i, j) = (x_run, y_run);
+            continue;
+        }
+        if x != y {
+            return x.cmp(&y);
+        }
+        i += 1;
+        j += 1;
+    }
+}
+
+/// End of the run of ASCII digits starting at `start`
+fn digit_run(key: &[(bool, char)], start: usize) -> usize {
+    key.get(start..)
+        .unwrap_or_default()
+        .iter()
+        .position(|(_, c)| !c.is_ascii_digit())
+        .map_or(key.len(), |len| start + len)
+}
+
+/// Compare two runs of ASCII digits by value; leading zeros don't count
+fn compare_digit_runs(a: &[(bool, char)], b: &[(bool, char)]) -> Ordering {
+    let significant = |run: &[(bool, char)]| -> Vec<char> {
+        run.iter()
+            .map(|&(_, c)| c)
+            .skip_while(|&c| c == '0')
+            .collect()
+    };
+    let (a, b) = (significant(a), significant(b));
+    a.len().cmp(&b.len()).then_with(|| a.cmp(&b))
+}
+
+/// Combining diacritical marks, dropped when diacritics are folded
+fn is_combining_mark(c: char) -> bool {
+    matches!(
+        c,
+        '\u{300}'..='\u{36f}'
+            | '\u{1ab0}'..='\u{1aff}'
+            | '\u{1dc0}'..='\u{1dff}'
+            | '\u{20d0}'..='\u{20ff}'
+            | '\u{fe20}'..='\u{fe2f}'
+    )
+}
+
+/// `c` without its diacritics, e.g. `é` → `e`
+fn unaccented(c: char) -> char {
+    if c.is_ascii() {
+        return c;
+    }
+    ACCENTED
+        .binary_search(&c)
+        .ok()
+        .and_then(|index| UNACCENTED.get(index).copied())
+        .unwrap_or(c)
+}
+
+/// Precomposed characters whose canonical decomposition is a base character
+/// followed only by nonspacing marks, sorted (Unicode 14.0.0)
+const ACCENTED: [char; 976] = [
+    '\u{c0}',
+    '\u{c1}',
+    '\u{c2}',
+    '\u{c3}',
+    '\u{c4}',
+    '\u{c5}',
+    '\u{c7}',
+    '\u{c8}',
+    '\u{c9}',
+    '\u{ca}',
+    '\u{cb}',
+    '\u{cc}',
+    '\u{cd}',
+    '\u{ce}',
+    '\u{cf}',
+    '\u{d1}',
+    '\u{d2}',
+    '\u{d3}',
+    '\u{d4}',
+    '\u{d5}',
+    '\u{d6}',
+    '\u{d9}',
+    '\u{da}',
+    '\u{db}',
+    '\u{dc}',
+    '\u{dd}',
+    '\u{e0}',
+    '\u{e1}',
+    '\u{e2}',
+    '\u{e3}',
+    '\u{e4}',
+    '\u{e5}',
+    '\u{e7}',
+    '\u{e8}',
+    '\u{e9}',
+    '\u{ea}',
+    '\u{eb}',
+    '\u{ec}',
+    '\u{ed}',
+    '\u{ee}',
+    '\u{ef}',
+    '\u{f1}',
+    '\u{f2}',
+    '\u{f3}',
+    '\u{f4}',
+    '\u{f5}',
+    '\u{f6}',
+    '\u{f9}',
+    '\u{fa}',
+    '\u{fb}',
+    '\u{fc}',
+    '\u{fd}',
+    '\u{ff}',
+    '\u{100}',
+    '\u{101}',
+    '\u{102}',
+    '\u{103}',
+    '\u{104}',
+    '\u{105}',
+    '\u{106}',
+    '\u{107}',
+    '\u{108}',
+    '\u{109}',
+    '\u{10a}',
+    '\u{10b}',
+    '\u{10c}',
+    '\u{10d}',
+    '\u{10e}',
+    '\u{10f}',
+    '\u{112}',
+    '\u{113}',
+    '\u{114}',
+    '\u{115}',
+    '\u{116}',
+    '\u{117}',
+    '\u{118}',
+    '\u{119}',
+    '\u{11a}',
+    '\u{11b}',
+    '\u{11c}',
+    '\u{11d}',
+    '\u{11e}',
+    '\u{11f}',
+    '\u{120}',
+    '\u{121}',
+    '\u{122}',
+    '\u{123}',
+    '\u{124}',
+    '\u{125}',
+    '\u{128}',
+    '\u{129}',
+    '\u{12a}',
+    '\u{12b}',
+    '\u{12c}',
+    '\u{12d}',
+    '\u{12e}',
+    '\u{12f}',
+    '\u{130}',
+    '\u{134}',
+    '\u{135}',
+    '\u{136}',
+    '\u{137}',
+    '\u{139}',
+    '\u{13a}',
+    '\u{13b}',
+    '\u{13c}',
+    '\u{13d}',
+    '\u{13e}',
+    '\u{143}',
+    '\u{144}',
+    '\u{145}',
+    '\u{146}',
+    '\u{147}',
+    '\u{148}',
+    '\u{14c}',
+    '\u{14d}',
+    '\u{14e}',
+    '\u{14f}',
+    '\u{150}',
+    '\u{151}',
+    '\u{154}',
+    '\u{155}',
+    '\u{156}',
+    '\u{157}',
+    '\u{158}',
+    '\u{159}',
+    '\u{15a}',
+    '\u{15b}',
+    '\u{15c}',
+    '\u{15d}',
+    '\u{15e}',
+    '\u{15f}',
+    '\u{160}',
+    '\u{161}',
+    '\u{162}',
+    '\u{163}',
+    '\u{164}',
+    '\u{165}',
+    '\u{168}',
+    '\u{169}',
+    '\u{16a}',
+    '\u{16b}',
+    '\u{16c}',
+    '\u{16d}',
+    '\u{16e}',
+    '\u{16f}',
+    '\u{170}',
+    '\u{171}',
+    '\u{172}',
+    '\u{173}',
+    '\u{174}',
+    '\u{175}',
+    '\u{176}',
+    '\u{177}',
+    '\u{178}',
+    '\u{179}',
+    '\u{17a}',
+    '\u{17b}',
+    '\u{17c}',
+    '\u{17d}',
+    '\u{17e}',
+    '\u{1a0}',
+    '\u{1a1}',
+    '\u{1af}',
+    '\u{1b0}',
+    '\u{1cd}',
+    '\u{1ce}',
+    '\u{1cf}',
+    '\u{1d0}',
+    '\u{1d1}',
+    '\u{1d2}',
+    '\u{1d3}',
+    '\u{1d4}',
+    '\u{1d5}',
+    '\u{1d6}',
+    '\u{1d7}',
+    '\u{1d8}',
+    '\u{1d9}',
+    '\u{1da}',
+    '\u{1db}',
+    '\u{1dc}',
+    '\u{1de}',
+    '\u{1df}',
+    '\u{1e0}',
+    '\u{1e1}',
+    '\u{1e2}',
+    '\u{1e3}',
+    '\u{1e6}',
+    '\u{1e7}',
+    '\u{1e8}',
+    '\u{1e9}',
+    '\u{1ea}',
+    '\u{1eb}',
+    '\u{1ec}',
+    '\u{1ed}',
+    '\u{1ee}',
+    '\u{1ef}',
+    '\u{1f0}',
+    '\u{1f4}',
+    '\u{1f5}',
+    '\u{1f8}',
+    '\u{1f9}',
+    '\u{1fa}',
+    '\u{1fb}',
+    '\u{1fc}',
+    '\u{1fd}',
+    '\u{1fe}',
+    '\u{1ff}',
+    '\u{200}',
+    '\u{201}',
+    '\u{202}',
+    '\u{203}',
+    '\u{204}',
+    '\u{205}',
+    '\u{206}',
+    '\u{207}',
+    '\u{208}',
+    '\u{209}',
+    '\u{20a}',
+    '\u{20b}',
+    '\u{20c}',
+    '\u{20d}',
+    '\u{20e}',
+    '\u{20f}',
+    '\u{210}',
+    '\u{211}',
+    '\u{212}',
+    '\u{213}',
+    '\u{214}',
+    '\u{215}',
+    '\u{216}',
+    '\u{217}',
+    '\u{218}',
+    '\u{219}',
+    '\u{21a}',
+    '\u{21b}',
+    '\u{21e}',
+    '\u{21f}',
+    '\u{226}',
+    '\u{227}',
+    '\u{228}',
+    '\u{229}',
+    '\u{22a}',
+    '\u{22b}',
+    '\u{22c}',
+    '\u{22d}',
+    '\u{22e}',
+    '\u{22f}',
+    '\u{230}',
+    '\u{231}',
+    '\u{232}',
+    '\u{233}',
+    '\u{385}',
+    '\u{386}',
+    '\u{388}',
+    '\u{389}',
+    '\u{38a}',
+    '\u{38c}',
+    '\u{38e}',
+    '\u{38f}',
+    '\u{390}',
+    '\u{3aa}',
+    '\u{3ab}',
+    '\u{3ac}',
+    '\u{3ad}',
+    '\u{3ae}',
+    '\u{3af}',
+    '\u{3b0}',
+    '\u{3ca}',
+    '\u{3cb}',
+    '\u{3cc}',
+    '\u{3cd}',
+    '\u{3ce}',
+    '\u{3d3}',
+    '\u{3d4}',
+    '\u{400}',
+    '\u{401}',
+    '\u{403}',
+    '\u{407}',
+    '\u{40c}',
+    '\u{40d}',
+    '\u{40e}',
+    '\u{419}',
+    '\u{439}',
+    '\u{450}',
+    '\u{451}',
+    '\u{453}',
+    '\u{457}',
+    '\u{45c}',
+    '\u{45d}',
+    '\u{45e}',
+    '\u{476}',
+    '\u{477}',
+    '\u{4c1}',
+    '\u{4c2}',
+    '\u{4d0}',
+    '\u{4d1}',
+    '\u{4d2}',
+    '\u{4d3}',
+    '\u{4d6}',
+    '\u{4d7}',
+    '\u{4da}',
+    '\u{4db}',
+    '\u{4dc}',
+    '\u{4dd}',
+    '\u{4de}',
+    '\u{4df}',
+    '\u{4e2}',
+    '\u{4e3}',
+    '\u{4e4}',
+    '\u{4e5}',
+    '\u{4e6}',
+    '\u{4e7}',
+    '\u{4ea}',
+    '\u{4eb}',
+    '\u{4ec}',
+    '\u{4ed}',
+    '\u{4ee}',
+    '\u{4ef}',
+    '\u{4f0}',
+    '\u{4f1}',
+    '\u{4f2}',
+    '\u{4f3}',
+    '\u{4f4}',
+    '\u{4f5}',
+    '\u{4f8}',
+    '\u{4f9}',
+    '\u{622}',
+    '\u{623}',
+    '\u{624}',
+    '\u{625}',
+    '\u{626}',
+    '\u{6c0}',
+    '\u{6c2}',
+    '\u{6d3}',
+    '\u{929}',
+    '\u{931}',
+    '\u{934}',
+    '\u{958}',
+    '\u{959}',
+    '\u{95a}',
+    '\u{95b}',
+    '\u{95c}',
+    '\u{95d}',
+    '\u{95e}',
+    '\u{95f}',
+    '\u{9dc}',
+    '\u{9dd}',
+    '\u{9df}',
+    '\u{a33}',
+    '\u{a36}',
+    '\u{a59}',
+    '\u{a5a}',
+    '\u{a5b}',
+    '\u{a5e}',
+    '\u{b5c}',
+    '\u{b5d}',
+    '\u{f43}',
+    '\u{f4d}',
+    '\u{f52}',
+    '\u{f57}',
+    '\u{f5c}',
+    '\u{f69}',
+    '\u{1026}',
+    '\u{1e00}',
+    '\u{1e01}',
+    '\u{1e02}',
+    '\u{1e03}',
+    '\u{1e04}',
+    '\u{1e05}',
+    '\u{1e06}',
+    '\u{1e07}',
+    '\u{1e08}',
+    '\u{1e09}',
+    '\u{1e0a}',
+    '\u{1e0b}',
+    '\u{1e0c}',
+    '\u{1e0d}',
+    '\u{1e0e}',
+    '\u{1e0f}',
+    '\u{1e10}',
+    '\u{1e11}',
+    '\u{1e12}',
+    '\u{1e13}',
+    '\u{1e14}',
+    '\u{1e15}',
+    '\u{1e16}',
+    '\u{1e17}',
+    '\u{1e18}',
+    '\u{1e19}',
+    '\u{1e1a}',
+    '\u{1e1b}',
+    '\u{1e1c}',
+    '\u{1e1d}',
+    '\u{1e1e}',
+    '\u{1e1f}',
+    '\u{1e20}',
+    '\u{1e21}',
+    '\u{1e22}',
+    '\u{1e23}',
+    '\u{1e24}',
+    '\u{1e25}',
+    '\u{1e26}',
+    '\u{1e27}',
+    '\u{1e28}',
+    '\u{1e29}',
+    '\u{1e2a}',
+    '\u{1e2b}',
+    '\u{1e2c}',
+    '\u{1e2d}',
+    '\u{1e2e}',
+    '\u{1e2f}',
+    '\u{1e30}',
+    '\u{1e31}',
+    '\u{1e32}',
+    '\u{1e33}',
+    '\u{1e34}',
+    '\u{1e35}',
+    '\u{1e36}',
+    '\u{1e37}',
+    '\u{1e38}',
+    '\u{1e39}',
+    '\u{1e3a}',
+    '\u{1e3b}',
+    '\u{1e3c}',
+    '\u{1e3d}',
+    '\u{1e3e}',
+    '\u{1e3f}',
+    '\u{1e40}',
+    '\u{1e41}',
+    '\u{1e42}',
+    '\u{1e43}',
+    '\u{1e44}',
+    '\u{1e45}',
+    '\u{1e46}',
+    '\u{1e47}',
+    '\u{1e48}',
+    '\u{1e49}',
+    '\u{1e4a}',
+    '\u{1e4b}',
+    '\u{1e4c}',
+    '\u{1e4d}',
+    '\u{1e4e}',
+    '\u{1e4f}',
+    '\u{1e50}',
+    '\u{1e51}',
+    '\u{1e52}',
+    '\u{1e53}',
+    '\u{1e54}',
+    '\u{1e55}',
+    '\u{1e56}',
+    '\u{1e57}',
+    '\u{1e58}',
+    '\u{1e59}',
+    '\u{1e5a}',
+    '\u{1e5b}',
+    '\u{1e5c}',
+    '\u{1e5d}',
+    '\u{1e5e}',
+    '\u{1e5f}',
+    '\u{1e60}',
+    '\u{1e61}',
+    '\u{1e62}',
+    '\u{1e63}',
+    '\u{1e64}',
+    '\u{1e65}',
+    '\u{1e66}',
+    '\u{1e67}',
+    '\u{1e68}',
+    '\u{1e69}',
+    '\u{1e6a}',
+    '\u{1e6b}',
+    '\u{1e6c}',
+    '\u{1e6d}',
+    '\u{1e6e}',
+    '\u{1e6f}',
+    '\u{1e70}',
+    '\u{1e71}',
+    '\u{1e72}',
+    '\u{1e73}',
+    '\u{1e74}',
+    '\u{1e75}',
+    '\u{1e76}',
+    '\u{1e77}',
+    '\u{1e78}',
+    '\u{1e79}',
+    '\u{1e7a}',
+    '\u{1e7b}',
+    '\u{1e7c}',
+    '\u{1e7d}',
+    '\u{1e7e}',
+    '\u{1e7f}',
+    '\u{1e80}',
+    '\u{1e81}',
+    '\u{1e82}',
+    '\u{1e83}',
+    '\u{1e84}',
+    '\u{1e85}',
+    '\u{1e86}',
+    '\u{1e87}',
+    '\u{1e88}',
+    '\u{1e89}',
+    '\u{1e8a}',
+    '\u{1e8b}',
+    '\u{1e8c}',
+    '\u{1e8d}',
+    '\u{1e8e}',
+    '\u{1e8f}',
+    '\u{1e90}',
+    '\u{1e91}',
+    '\u{1e92}',
+    '\u{1e93}',
+    '\u{1e94}',
+    '\u{1e95}',
+    '\u{1e96}',
+    '\u{1e97}',
+    '\u{1e98}',
+    '\u{1e99}',
+    '\u{1e9b}',
+    '\u{1ea0}',
+    '\u{1ea1}',
+    '\u{1ea2}',
+    '\u{1ea3}',
+    '\u{1ea4}',
+    '\u{1ea5}',
+    '\u{1ea6}',
+    '\u{1ea7}',
+    '\u{1ea8}',
+    '\u{1ea9}',
+    '\u{1eaa}',
+    '\u{1eab}',
+    '\u{1eac}',
+    '\u{1ead}',
+    '\u{1eae}',
+    '\u{1eaf}',
+    '\u{1eb0}',
+    '\u{1eb1}',
+    '\u{1eb2}',
+    '\u{1eb3}',
+    '\u{1eb4}',
+    '\u{1eb5}',
+    '\u{1eb6}',
+    '\u{1eb7}',
+    '\u{1eb8}',
+    '\u{1eb9}',
+    '\u{1eba}',
+    '\u{1ebb}',
+    '\u{1ebc}',
+    '\u{1ebd}',
+    '\u{1ebe}',
+    '\u{1ebf}',
+    '\u{1ec0}',
+    '\u{1ec1}',
+    '\u{1ec2}',
+    '\u{1ec3}',
+    '\u{1ec4}',
+    '\u{1ec5}',
+    '\u{1ec6}',
+    '\u{1ec7}',
+    '\u{1ec8}',
+    '\u{1ec9}',
+    '\u{1eca}',
+    '\u{1ecb}',
+    '\u{1ecc}',
+    '\u{1ecd}',
+    '\u{1ece}',
+    '\u{1ecf}',
+    '\u{1ed0}',
+    '\u{1ed1}',
+    '\u{1ed2}',
+    '\u{1ed3}',
+    '\u{1ed4}',
+    '\u{1ed5}',
+    '\u{1ed6}',
+    '\u{1ed7}',
+    '\u{1ed8}',
+    '\u{1ed9}',
+    '\u{1eda}',
+    '\u{1edb}',
+    '\u{1edc}',
+    '\u{1edd}',
+    '\u{1ede}',
+    '\u{1edf}',
+    '\u{1ee0}',
+    '\u{1ee1}',
+    '\u{1ee2}',
+    '\u{1ee3}',
+    '\u{1ee4}',
+    '\u{1ee5}',
+    '\u{1ee6}',
+    '\u{1ee7}',
+    '\u{1ee8}',
+    '\u{1ee9}',
+    '\u{1eea}',
+    '\u{1eeb}',
+    '\u{1eec}',
+    '\u{1eed}',
+    '\u{1eee}',
+    '\u{1eef}',
+    '\u{1ef0}',
+    '\u{1ef1}',
+    '\u{1ef2}',
+    '\u{1ef3}',
+    '\u{1ef4}',
+    '\u{1ef5}',
+    '\u{1ef6}',
+    '\u{1ef7}',
+    '\u{1ef8}',
+    '\u{1ef9}',
+    '\u{1f00}',
+    '\u{1f01}',
+    '\u{1f02}',
+    '\u{1f03}',
+    '\u{1f04}',
+    '\u{1f05}',
+    '\u{1f06}',
+    '\u{1f07}',
+    '\u{1f08}',
+    '\u{1f09}',
+    '\u{1f0a}',
+    '\u{1f0b}',
+    '\u{1f0c}',
+    '\u{1f0d}',
+    '\u{1f0e}',
+    '\u{1f0f}',
+    '\u{1f10}',
+    '\u{1f11}',
+    '\u{1f12}',
+    '\u{1f13}',
+    '\u{1f14}',
+    '\u{1f15}',
+    '\u{1f18}',
+    '\u{1f19}',
+    '\u{1f1a}',
+    '\u{1f1b}',
+    '\u{1f1c}',
+    '\u{1f1d}',
+    '\u{1f20}',
+    '\u{1f21}',
+    '\u{1f22}',
+    '\u{1f23}',
+    '\u{1f24}',
+    '\u{1f25}',
+    '\u{1f26}',
+    '\u{1f27}',
+    '\u{1f28}',
+    '\u{1f29}',
+    '\u{1f2a}',
+    '\u{1f2b}',
+    '\u{1f2c}',
+    '\u{1f2d}',
+    '\u{1f2e}',
+    '\u{1f2f}',
+    '\u{1f30}',
+    '\u{1f31}',
+    '\u{1f32}',
+    '\u{1f33}',
+    '\u{1f34}',
+    '\u{1f35}',
+    '\u{1f36}',
+    '\u{1f37}',
+    '\u{1f38}',
+    '\u{1f39}',
+    '\u{1f3a}',
+    '\u{1f3b}',
+    '\u{1f3c}',
+    '\u{1f3d}',
+    '\u{1f3e}',
+    '\u{1f3f}',
+    '\u{1f40}',
+    '\u{1f41}',
+    '\u{1f42}',
+    '\u{1f43}',
+    '\u{1f44}',
+    '\u{1f45}',
+    '\u{1f48}',
+    '\u{1f49}',
+    '\u{1f4a}',
+    '\u{1f4b}',
+    '\u{1f4c}',
+    '\u{1f4d}',
+    '\u{1f50}',
+    '\u{1f51}',
+    '\u{1f52}',
+    '\u{1f53}',
+    '\u{1f54}',
+    '\u{1f55}',
+    '\u{1f56}',
+    '\u{1f57}',
+    '\u{1f59}',
+    '\u{1f5b}',
+    '\u{1f5d}',
+    '\u{1f5f}',
+    '\u{1f60}',
+    '\u{1f61}',
+    '\u{1f62}',
+    '\u{1f63}',
+    '\u{1f64}',
+    '\u{1f65}',
+    '\u{1f66}',
+    '\u{1f67}',
+    '\u{1f68}',
+    '\u{1f69}',
+    '\u{1f6a}',
+    '\u{1f6b}',
+    '\u{1f6c}',
+    '\u{1f6d}',
+    '\u{1f6e}',
+    '\u{1f6f}',
+    '\u{1f70}',
+    '\u{1f71}',
+    '\u{1f72}',
+    '\u{1f73}',
+    '\u{1f74}',
+    '\u{1f75}',
+    '\u{1f76}',
+    '\u{1f77}',
+    '\u{1f78}',
+    '\u{1f79}',
+    '\u{1f7a}',
+    '\u{1f7b}',
+    '\u{1f7c}',
+    '\u{1f7d}',
+    '\u{1f80}',
+    '\u{1f81}',
+    '\u{1f82}',
+    '\u{1f83}',
+    '\u{1f84}',
+    '\u{1f85}',
+    '\u{1f86}',
+    '\u{1f87}',
+    '\u{1f88}',
+    '\u{1f89}',
+    '\u{1f8a}',
+    '\u{1f8b}',
+    '\u{1f8c}',
+    '\u{1f8d}',
+    '\u{1f8e}',
+    '\u{1f8f}',
+    '\u{1f90}',
+    '\u{1f91}',
+    '\u{1f92}',
+    '\u{1f93}',
+    '\u{1f94}',
+    '\u{1f95}',
+    '\u{1f96}',
+    '\u{1f97}',
+    '\u{1f98}',
+    '\u{1f99}',
+    '\u{1f9a}',
+    '\u{1f9b}',
+    '\u{1f9c}',
+    '\u{1f9d}',
+    '\u{1f9e}',
+    '\u{1f9f}',
+    '\u{1fa0}',
+    '\u{1fa1}',
+    '\u{1fa2}',
+    '\u{1fa3}',
+    '\u{1fa4}',
+    '\u{1fa5}',
+    '\u{1fa6}',
+    '\u{1fa7}',
+    '\u{1fa8}',
+    '\u{1fa9}',
+    '\u{1faa}',
+    '\u{1fab}',
+    '\u{1fac}',
+    '\u{1fad}',
+    '\u{1fae}',
+    '\u{1faf}',
+    '\u{1fb0}',
+    '\u{1fb1}',
+    '\u{1fb2}',
+    '\u{1fb3}',
+    '\u{1fb4}',
+    '\u{1fb6}',
+    '\u{1fb7}',
+    '\u{1fb8}',
+    '\u{1fb9}',
+    '\u{1fba}',
+    '\u{1fbb}',
+    '\u{1fbc}',
+    '\u{1fc1}',
+    '\u{1fc2}',
+    '\u{1fc3}',
+    '\u{1fc4}',
+    '\u{1fc6}',
+    '\u{1fc7}',
+    '\u{1fc8}',
+    '\u{1fc9}',
+    '\u{1fca}',
+    '\u{1fcb}',
+    '\u{1fcc}',
+    '\u{1fcd}',
+    '\u{1fce}',
+    '\u{1fcf}',
+    '\u{1fd0}',
+    '\u{1fd1}',
+    '\u{1fd2}',
+    '\u{1fd3}',
+    '\u{1fd6}',
+    '\u{1fd7}',
+    '\u{1fd8}',
+    '\u{1fd9}',
+    '\u{1fda}',
+    '\u{1fdb}',
+    '\u{1fdd}',
+    '\u{1fde}',
+    '\u{1fdf}',
+    '\u{1fe0}',
+    '\u{1fe1}',
+    '\u{1fe2}',
+    '\u{1fe3}',
+    '\u{1fe4}',
+    '\u{1fe5}',
+    '\u{1fe6}',
+    '\u{1fe7}',
+    '\u{1fe8}',
+    '\u{1fe9}',
+    '\u{1fea}',
+    '\u{1feb}',
+    '\u{1fec}',
+    '\u{1fed}',
+    '\u{1fee}',
+    '\u{1ff2}',
+    '\u{1ff3}',
+    '\u{1ff4}',
+    '\u{1ff6}',
+    '\u{1ff7}',
+    '\u{1ff8}',
+    '\u{1ff9}',
+    '\u{1ffa}',
+    '\u{1ffb}',
+    '\u{1ffc}',
+    '\u{212b}',
+    '\u{219a}',
+    '\u{219b}',
+    '\u{21ae}',
+    '\u{21cd}',
+    '\u{21ce}',
+    '\u{21cf}',
+    '\u{2204}',
+    '\u{2209}',
+    '\u{220c}',
+    '\u{2224}',
+    '\u{2226}',
+    '\u{2241}',
+    '\u{2244}',
+    '\u{2247}',
+    '\u{2249}',
+    '\u{2260}',
+    '\u{2262}',
+    '\u{226d}',
+    '\u{226e}',
+    '\u{226f}',
+    '\u{2270}',
+    '\u{2271}',
+    '\u{2274}',
+    '\u{2275}',
+    '\u{2278}',
+    '\u{2279}',
+    '\u{2280}',
+    '\u{2281}',
+    '\u{2284}',
+    '\u{2285}',
+    '\u{2288}',
+    '\u{2289}',
+    '\u{22ac}',
+    '\u{22ad}',
+    '\u{22ae}',
+    '\u{22af}',
+    '\u{22e0}',
+    '\u{22e1}',
+    '\u{22e2}',
+    '\u{22e3}',
+    '\u{22ea}',
+    '\u{22eb}',
+    '\u{22ec}',
+    '\u{22ed}',
+    '\u{2adc}',
+    '\u{304c}',
+    '\u{304e}',
+    '\u{3050}',
+    '\u{3052}',
+    '\u{3054}',
+    '\u{3056}',
+    '\u{3058}',
+    '\u{305a}',
+    '\u{305c}',
+    '\u{305e}',
+    '\u{3060}',
+    '\u{3062}',
+    '\u{3065}',
+    '\u{3067}',
+    '\u{3069}',
+    '\u{3070}',
+    '\u{3071}',
+    '\u{3073}',
+    '\u{3074}',
+    '\u{3076}',
+    '\u{3077}',
+    '\u{3079}',
+    '\u{307a}',
+    '\u{307c}',
+    '\u{307d}',
+    '\u{3094}',
+    '\u{309e}',
+    '\u{30ac}',
+    '\u{30ae}',
+    '\u{30b0}',
+    '\u{30b2}',
+    '\u{30b4}',
+    '\u{30b6}',
+    '\u{30b8}',
+    '\u{30ba}',
+    '\u{30bc}',
+    '\u{30be}',
+    '\u{30c0}',
+    '\u{30c2}',
+    '\u{30c5}',
+    '\u{30c7}',
+    '\u{30c9}',
+    '\u{30d0}',
+    '\u{30d1}',
+    '\u{30d3}',
+    '\u{30d4}',
+    '\u{30d6}',
+    '\u{30d7}',
+    '\u{30d9}',
+    '\u{30da}',
+    '\u{30dc}',
+    '\u{30dd}',
+    '\u{30f4}',
+    '\u{30f7}',
+    '\u{30f8}',
+    '\u{30f9}',
+    '\u{30fa}',
+    '\u{30fe}',
+    '\u{fb1d}',
+    '\u{fb1f}',
+    '\u{fb2a}',
+    '\u{fb2b}',
+    '\u{fb2c}',
+    '\u{fb2d}',
+    '\u{fb2e}',
+    '\u{fb2f}',
+    '\u{fb30}',
+    '\u{fb31}',
+    '\u{fb32}',
+    '\u{fb33}',
+    '\u{fb34}',
+    '\u{fb35}',
+    '\u{fb36}',
+    '\u{fb38}',
+    '\u{fb39}',
+    '\u{fb3a}',
+    '\u{fb3b}',
+    '\u{fb3c}',
+    '\u{fb3e}',
+    '\u{fb40}',
+    '\u{fb41}',
+    '\u{fb43}',
+    '\u{fb44}',
+    '\u{fb46}',
+    '\u{fb47}',
+    '\u{fb48}',
+    '\u{fb49}',
+    '\u{fb4a}',
+    '\u{fb4b}',
+    '\u{fb4c}',
+    '\u{fb4d}',
+    '\u{fb4e}',
+    '\u{1109a}',
+    '\u{1109c}',
+    '\u{110ab}',
+];
+
+/// The base character of each of [`ACCENTED`]
+const UNACCENTED: [char; 976] = [
+    '\u{41}',
+    '\u{41}',
+    '\u{41}',
+    '\u{41}',
+    '\u{41}',
+    '\u{41}',
+    '\u{43}',
+    '\u{45}',
+    '\u{45}',
+    '\u{45}',
+    '\u{45}',
+    '\u{49}',
+    '\u{49}',
+    '\u{49}',
+    '\u{49}',
+    '\u{4e}',
+    '\u{4f}',
+    '\u{4f}',
+    '\u{4f}',
+    '\u{4f}',
+    '\u{4f}',
+    '\u{55}',
+    '\u{55}',
+    '\u{55}',
+    '\u{55}',
+    '\u{59}',
+    '\u{61}',
+    '\u{61}',
+    '\u{61}',
+    '\u{61}',
+    '\u{61}',
+    '\u{61}',
+    '\u{63}',
+    '\u{65}',
+    '\u{65}',
+    '\u{65}',
+    '\u{65}',
+    '\u{69}',
+    '\u{69}',
+    '\u{69}',
+    '\u{69}',
+    '\u{6e}',
+    '\u{6f}',
+    '\u{6f}',
+    '\u{6f}',
+    '\u{6f}',
+    '\u{6f}',
+    '\u{75}',
+    '\u{75}',
+    '\u{75}',
+    '\u{75}',
+    '\u{79}',
+    '\u{79}',
+    '\u{41}',
+    '\u{61}',
+    '\u{41}',
+    '\u{61}',
+    '\u{41}',
+    '\u{61}',
+    '\u{43}',
+    '\u{63}',
+    '\u{43}',
+    '\u{63}',
+    '\u{43}',
+    '\u{63}',
+    '\u{43}',
+    '\u{63}',
+    '\u{44}',
+    '\u{64}',
+    '\u{45}',
+    '\u{65}',
+    '\u{45}',
+    '\u{65}',
+    '\u{45}',
+    '\u{65}',
+    '\u{45}',
+    '\u{65}',
+    '\u{45}',
+    '\u{65}',
+    '\u{47}',
+    '\u{67}',
+    '\u{47}',
+    '\u{67}',
+    '\u{47}',
+    '\u{67}',
+    '\u{47}',
+    '\u{67}',
+    '\u{48}',
+    '\u{68}',
+    '\u{49}',
+    '\u{69}',
+    '\u{49}',
+    '\u{69}',
+    '\u{49}',
+    '\u{69}',
+    '\u{49}',
+    '\u{69}',
+    '\u{49}',
+    '\u{4a}',
+    '\u{6a}',
+    '\u{4b}',
+    '\u{6b}',
+    '\u{4c}',
+    '\u{6c}',
+    '\u{4c}',
+    '\u{6c}',
+    '\u{4c}',
+    '\u{6c}',
+    '\u{4e}',
+    '\u{6e}',
+    '\u{4e}',
+    '\u{6e}',
+    '\u{4e}',
+    '\u{6e}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{52}',
+    '\u{72}',
+    '\u{52}',
+    '\u{72}',
+    '\u{52}',
+    '\u{72}',
+    '\u{53}',
+    '\u{73}',
+    '\u{53}',
+    '\u{73}',
+    '\u{53}',
+    '\u{73}',
+    '\u{53}',
+    '\u{73}',
+    '\u{54}',
+    '\u{74}',
+    '\u{54}',
+    '\u{74}',
+    '\u{55}',
+    '\u{75}',
+    '\u{55}',
+    '\u{75}',
+    '\u{55}',
+    '\u{75}',
+    '\u{55}',
+    '\u{75}',
+    '\u{55}',
+    '\u{75}',
+    '\u{55}',
+    '\u{75}',
+    '\u{57}',
+    '\u{77}',
+    '\u{59}',
+    '\u{79}',
+    '\u{59}',
+    '\u{5a}',
+    '\u{7a}',
+    '\u{5a}',
+    '\u{7a}',
+    '\u{5a}',
+    '\u{7a}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{55}',
+    '\u{75}',
+    '\u{41}',
+    '\u{61}',
+    '\u{49}',
+    '\u{69}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{55}',
+    '\u{75}',
+    '\u{55}',
+    '\u{75}',
+    '\u{55}',
+    '\u{75}',
+    '\u{55}',
+    '\u{75}',
+    '\u{55}',
+    '\u{75}',
+    '\u{41}',
+    '\u{61}',
+    '\u{41}',
+    '\u{61}',
+    '\u{c6}',
+    '\u{e6}',
+    '\u{47}',
+    '\u{67}',
+    '\u{4b}',
+    '\u{6b}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{1b7}',
+    '\u{292}',
+    '\u{6a}',
+    '\u{47}',
+    '\u{67}',
+    '\u{4e}',
+    '\u{6e}',
+    '\u{41}',
+    '\u{61}',
+    '\u{c6}',
+    '\u{e6}',
+    '\u{d8}',
+    '\u{f8}',
+    '\u{41}',
+    '\u{61}',
+    '\u{41}',
+    '\u{61}',
+    '\u{45}',
+    '\u{65}',
+    '\u{45}',
+    '\u{65}',
+    '\u{49}',
+    '\u{69}',
+    '\u{49}',
+    '\u{69}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{52}',
+    '\u{72}',
+    '\u{52}',
+    '\u{72}',
+    '\u{55}',
+    '\u{75}',
+    '\u{55}',
+    '\u{75}',
+    '\u{53}',
+    '\u{73}',
+    '\u{54}',
+    '\u{74}',
+    '\u{48}',
+    '\u{68}',
+    '\u{41}',
+    '\u{61}',
+    '\u{45}',
+    '\u{65}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{59}',
+    '\u{79}',
+    '\u{a8}',
+    '\u{391}',
+    '\u{395}',
+    '\u{397}',
+    '\u{399}',
+    '\u{39f}',
+    '\u{3a5}',
+    '\u{3a9}',
+    '\u{3b9}',
+    '\u{399}',
+    '\u{3a5}',
+    '\u{3b1}',
+    '\u{3b5}',
+    '\u{3b7}',
+    '\u{3b9}',
+    '\u{3c5}',
+    '\u{3b9}',
+    '\u{3c5}',
+    '\u{3bf}',
+    '\u{3c5}',
+    '\u{3c9}',
+    '\u{3d2}',
+    '\u{3d2}',
+    '\u{415}',
+    '\u{415}',
+    '\u{413}',
+    '\u{406}',
+    '\u{41a}',
+    '\u{418}',
+    '\u{423}',
+    '\u{418}',
+    '\u{438}',
+    '\u{435}',
+    '\u{435}',
+    '\u{433}',
+    '\u{456}',
+    '\u{43a}',
+    '\u{438}',
+    '\u{443}',
+    '\u{474}',
+    '\u{475}',
+    '\u{416}',
+    '\u{436}',
+    '\u{410}',
+    '\u{430}',
+    '\u{410}',
+    '\u{430}',
+    '\u{415}',
+    '\u{435}',
+    '\u{4d8}',
+    '\u{4d9}',
+    '\u{416}',
+    '\u{436}',
+    '\u{417}',
+    '\u{437}',
+    '\u{418}',
+    '\u{438}',
+    '\u{418}',
+    '\u{438}',
+    '\u{41e}',
+    '\u{43e}',
+    '\u{4e8}',
+    '\u{4e9}',
+    '\u{42d}',
+    '\u{44d}',
+    '\u{423}',
+    '\u{443}',
+    '\u{423}',
+    '\u{443}',
+    '\u{423}',
+    '\u{443}',
+    '\u{427}',
+    '\u{447}',
+    '\u{42b}',
+    '\u{44b}',
+    '\u{627}',
+    '\u{627}',
+    '\u{648}',
+    '\u{627}',
+    '\u{64a}',
+    '\u{6d5}',
+    '\u{6c1}',
+    '\u{6d2}',
+    '\u{928}',
+    '\u{930}',
+    '\u{933}',
+    '\u{915}',
+    '\u{916}',
+    '\u{917}',
+    '\u{91c}',
+    '\u{921}',
+    '\u{922}',
+    '\u{92b}',
+    '\u{92f}',
+    '\u{9a1}',
+    '\u{9a2}',
+    '\u{9af}',
+    '\u{a32}',
+    '\u{a38}',
+    '\u{a16}',
+    '\u{a17}',
+    '\u{a1c}',
+    '\u{a2b}',
+    '\u{b21}',
+    '\u{b22}',
+    '\u{f42}',
+    '\u{f4c}',
+    '\u{f51}',
+    '\u{f56}',
+    '\u{f5b}',
+    '\u{f40}',
+    '\u{1025}',
+    '\u{41}',
+    '\u{61}',
+    '\u{42}',
+    '\u{62}',
+    '\u{42}',
+    '\u{62}',
+    '\u{42}',
+    '\u{62}',
+    '\u{43}',
+    '\u{63}',
+    '\u{44}',
+    '\u{64}',
+    '\u{44}',
+    '\u{64}',
+    '\u{44}',
+    '\u{64}',
+    '\u{44}',
+    '\u{64}',
+    '\u{44}',
+    '\u{64}',
+    '\u{45}',
+    '\u{65}',
+    '\u{45}',
+    '\u{65}',
+    '\u{45}',
+    '\u{65}',
+    '\u{45}',
+    '\u{65}',
+    '\u{45}',
+    '\u{65}',
+    '\u{46}',
+    '\u{66}',
+    '\u{47}',
+    '\u{67}',
+    '\u{48}',
+    '\u{68}',
+    '\u{48}',
+    '\u{68}',
+    '\u{48}',
+    '\u{68}',
+    '\u{48}',
+    '\u{68}',
+    '\u{48}',
+    '\u{68}',
+    '\u{49}',
+    '\u{69}',
+    '\u{49}',
+    '\u{69}',
+    '\u{4b}',
+    '\u{6b}',
+    '\u{4b}',
+    '\u{6b}',
+    '\u{4b}',
+    '\u{6b}',
+    '\u{4c}',
+    '\u{6c}',
+    '\u{4c}',
+    '\u{6c}',
+    '\u{4c}',
+    '\u{6c}',
+    '\u{4c}',
+    '\u{6c}',
+    '\u{4d}',
+    '\u{6d}',
+    '\u{4d}',
+    '\u{6d}',
+    '\u{4d}',
+    '\u{6d}',
+    '\u{4e}',
+    '\u{6e}',
+    '\u{4e}',
+    '\u{6e}',
+    '\u{4e}',
+    '\u{6e}',
+    '\u{4e}',
+    '\u{6e}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{50}',
+    '\u{70}',
+    '\u{50}',
+    '\u{70}',
+    '\u{52}',
+    '\u{72}',
+    '\u{52}',
+    '\u{72}',
+    '\u{52}',
+    '\u{72}',
+    '\u{52}',
+    '\u{72}',
+    '\u{53}',
+    '\u{73}',
+    '\u{53}',
+    '\u{73}',
+    '\u{53}',
+    '\u{73}',
+    '\u{53}',
+    '\u{73}',
+    '\u{53}',
+    '\u{73}',
+    '\u{54}',
+    '\u{74}',
+    '\u{54}',
+    '\u{74}',
+    '\u{54}',
+    '\u{74}',
+    '\u{54}',
+    '\u{74}',
+    '\u{55}',
+    '\u{75}',
+    '\u{55}',
+    '\u{75}',
+    '\u{55}',
+    '\u{75}',
+    '\u{55}',
+    '\u{75}',
+    '\u{55}',
+    '\u{75}',
+    '\u{56}',
+    '\u{76}',
+    '\u{56}',
+    '\u{76}',
+    '\u{57}',
+    '\u{77}',
+    '\u{57}',
+    '\u{77}',
+    '\u{57}',
+    '\u{77}',
+    '\u{57}',
+    '\u{77}',
+    '\u{57}',
+    '\u{77}',
+    '\u{58}',
+    '\u{78}',
+    '\u{58}',
+    '\u{78}',
+    '\u{59}',
+    '\u{79}',
+    '\u{5a}',
+    '\u{7a}',
+    '\u{5a}',
+    '\u{7a}',
+    '\u{5a}',
+    '\u{7a}',
+    '\u{68}',
+    '\u{74}',
+    '\u{77}',
+    '\u{79}',
+    '\u{17f}',
+    '\u{41}',
+    '\u{61}',
+    '\u{41}',
+    '\u{61}',
+    '\u{41}',
+    '\u{61}',
+    '\u{41}',
+    '\u{61}',
+    '\u{41}',
+    '\u{61}',
+    '\u{41}',
+    '\u{61}',
+    '\u{41}',
+    '\u{61}',
+    '\u{41}',
+    '\u{61}',
+    '\u{41}',
+    '\u{61}',
+    '\u{41}',
+    '\u{61}',
+    '\u{41}',
+    '\u{61}',
+    '\u{41}',
+    '\u{61}',
+    '\u{45}',
+    '\u{65}',
+    '\u{45}',
+    '\u{65}',
+    '\u{45}',
+    '\u{65}',
+    '\u{45}',
+    '\u{65}',
+    '\u{45}',
+    '\u{65}',
+    '\u{45}',
+    '\u{65}',
+    '\u{45}',
+    '\u{65}',
+    '\u{45}',
+    '\u{65}',
+    '\u{49}',
+    '\u{69}',
+    '\u{49}',
+    '\u{69}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{4f}',
+    '\u{6f}',
+    '\u{55}',
+    '\u{75}',
+    '\u{55}',
+    '\u{75}',
+    '\u{55}',
+    '\u{75}',
+    '\u{55}',
+    '\u{75}',
+    '\u{55}',
+    '\u{75}',
+    '\u{55}',
+    '\u{75}',
+    '\u{55}',
+    '\u{75}',
+    '\u{59}',
+    '\u{79}',
+    '\u{59}',
+    '\u{79}',
+    '\u{59}',
+    '\u{79}',
+    '\u{59}',
+    '\u{79}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{391}',
+    '\u{391}',
+    '\u{391}',
+    '\u{391}',
+    '\u{391}',
+    '\u{391}',
+    '\u{391}',
+    '\u{391}',
+    '\u{3b5}',
+    '\u{3b5}',
+    '\u{3b5}',
+    '\u{3b5}',
+    '\u{3b5}',
+    '\u{3b5}',
+    '\u{395}',
+    '\u{395}',
+    '\u{395}',
+    '\u{395}',
+    '\u{395}',
+    '\u{395}',
+    '\u{3b7}',
+    '\u{3b7}',
+    '\u{3b7}',
+    '\u{3b7}',
+    '\u{3b7}',
+    '\u{3b7}',
+    '\u{3b7}',
+    '\u{3b7}',
+    '\u{397}',
+    '\u{397}',
+    '\u{397}',
+    '\u{397}',
+    '\u{397}',
+    '\u{397}',
+    '\u{397}',
+    '\u{397}',
+    '\u{3b9}',
+    '\u{3b9}',
+    '\u{3b9}',
+    '\u{3b9}',
+    '\u{3b9}',
+    '\u{3b9}',
+    '\u{3b9}',
+    '\u{3b9}',
+    '\u{399}',
+    '\u{399}',
+    '\u{399}',
+    '\u{399}',
+    '\u{399}',
+    '\u{399}',
+    '\u{399}',
+    '\u{399}',
+    '\u{3bf}',
+    '\u{3bf}',
+    '\u{3bf}',
+    '\u{3bf}',
+    '\u{3bf}',
+    '\u{3bf}',
+    '\u{39f}',
+    '\u{39f}',
+    '\u{39f}',
+    '\u{39f}',
+    '\u{39f}',
+    '\u{39f}',
+    '\u{3c5}',
+    '\u{3c5}',
+    '\u{3c5}',
+    '\u{3c5}',
+    '\u{3c5}',
+    '\u{3c5}',
+    '\u{3c5}',
+    '\u{3c5}',
+    '\u{3a5}',
+    '\u{3a5}',
+    '\u{3a5}',
+    '\u{3a5}',
+    '\u{3c9}',
+    '\u{3c9}',
+    '\u{3c9}',
+    '\u{3c9}',
+    '\u{3c9}',
+    '\u{3c9}',
+    '\u{3c9}',
+    '\u{3c9}',
+    '\u{3a9}',
+    '\u{3a9}',
+    '\u{3a9}',
+    '\u{3a9}',
+    '\u{3a9}',
+    '\u{3a9}',
+    '\u{3a9}',
+    '\u{3a9}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{3b5}',
+    '\u{3b5}',
+    '\u{3b7}',
+    '\u{3b7}',
+    '\u{3b9}',
+    '\u{3b9}',
+    '\u{3bf}',
+    '\u{3bf}',
+    '\u{3c5}',
+    '\u{3c5}',
+    '\u{3c9}',
+    '\u{3c9}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{391}',
+    '\u{391}',
+    '\u{391}',
+    '\u{391}',
+    '\u{391}',
+    '\u{391}',
+    '\u{391}',
+    '\u{391}',
+    '\u{3b7}',
+    '\u{3b7}',
+    '\u{3b7}',
+    '\u{3b7}',
+    '\u{3b7}',
+    '\u{3b7}',
+    '\u{3b7}',
+    '\u{3b7}',
+    '\u{397}',
+    '\u{397}',
+    '\u{397}',
+    '\u{397}',
+    '\u{397}',
+    '\u{397}',
+    '\u{397}',
+    '\u{397}',
+    '\u{3c9}',
+    '\u{3c9}',
+    '\u{3c9}',
+    '\u{3c9}',
+    '\u{3c9}',
+    '\u{3c9}',
+    '\u{3c9}',
+    '\u{3c9}',
+    '\u{3a9}',
+    '\u{3a9}',
+    '\u{3a9}',
+    '\u{3a9}',
+    '\u{3a9}',
+    '\u{3a9}',
+    '\u{3a9}',
+    '\u{3a9}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{3b1}',
+    '\u{391}',
+    '\u{391}',
+    '\u{391}',
+    '\u{391}',
+    '\u{391}',
+    '\u{a8}',
+    '\u{3b7}',
+    '\u{3b7}',
+    '\u{3b7}',
+    '\u{3b7}',
+    '\u{3b7}',
+    '\u{395}',
+    '\u{395}',
+    '\u{397}',
+    '\u{397}',
+    '\u{397}',
+    '\u{1fbf}',
+    '\u{1fbf}',
+    '\u{1fbf}',
+    '\u{3b9}',
+    '\u{3b9}',
+    '\u{3b9}',
+    '\u{3b9}',
+    '\u{3b9}',
+    '\u{3b9}',
+    '\u{399}',
+    '\u{399}',
+    '\u{399}',
+    '\u{399}',
+    '\u{1ffe}',
+    '\u{1ffe}',
+    '\u{1ffe}',
+    '\u{3c5}',
+    '\u{3c5}',
+    '\u{3c5}',
+    '\u{3c5}',
+    '\u{3c1}',
+    '\u{3c1}',
+    '\u{3c5}',
+    '\u{3c5}',
+    '\u{3a5}',
+    '\u{3a5}',
+    '\u{3a5}',
+    '\u{3a5}',
+    '\u{3a1}',
+    '\u{a8}',
+    '\u{a8}',
+    '\u{3c9}',
+    '\u{3c9}',
+    '\u{3c9}',
+    '\u{3c9}',
+    '\u{3c9}',
+    '\u{39f}',
+    '\u{39f}',
+    '\u{3a9}',
+    '\u{3a9}',
+    '\u{3a9}',
+    '\u{41}',
+    '\u{2190}',
+    '\u{2192}',
+    '\u{2194}',
+    '\u{21d0}',
+    '\u{21d4}',
+    '\u{21d2}',
+    '\u{2203}',
+    '\u{2208}',
+    '\u{220b}',
+    '\u{2223}',
+    '\u{2225}',
+    '\u{223c}',
+    '\u{2243}',
+    '\u{2245}',
+    '\u{2248}',
+    '\u{3d}',
+    '\u{2261}',
+    '\u{224d}',
+    '\u{3c}',
+    '\u{3e}',
+    '\u{2264}',
+    '\u{2265}',
+    '\u{2272}',
+    '\u{2273}',
+    '\u{2276}',
+    '\u{2277}',
+    '\u{227a}',
+    '\u{227b}',
+    '\u{2282}',
+    '\u{2283}',
+    '\u{2286}',
+    '\u{2287}',
+    '\u{22a2}',
+    '\u{22a8}',
+    '\u{22a9}',
+    '\u{22ab}',
+    '\u{227c}',
+    '\u{227d}',
+    '\u{2291}',
+    '\u{2292}',
+    '\u{22b2}',
+    '\u{22b3}',
+    '\u{22b4}',
+    '\u{22b5}',
+    '\u{2add}',
+    '\u{304b}',
+    '\u{304d}',
+    '\u{304f}',
+    '\u{3051}',
+    '\u{3053}',
+    '\u{3055}',
+    '\u{3057}',
+    '\u{3059}',
+    '\u{305b}',
+    '\u{305d}',
+    '\u{305f}',
+    '\u{3061}',
+    '\u{3064}',
+    '\u{3066}',
+    '\u{3068}',
+    '\u{306f}',
+    '\u{306f}',
+    '\u{3072}',
+    '\u{3072}',
+    '\u{3075}',
+    '\u{3075}',
+    '\u{3078}',
+    '\u{3078}',
+    '\u{307b}',
+    '\u{307b}',
+    '\u{3046}',
+    '\u{309d}',
+    '\u{30ab}',
+    '\u{30ad}',
+    '\u{30af}',
+    '\u{30b1}',
+    '\u{30b3}',
+    '\u{30b5}',
+    '\u{30b7}',
+    '\u{30b9}',
+    '\u{30bb}',
+    '\u{30bd}',
+    '\u{30bf}',
+    '\u{30c1}',
+    '\u{30c4}',
+    '\u{30c6}',
+    '\u{30c8}',
+    '\u{30cf}',
+    '\u{30cf}',
+    '\u{30d2}',
+    '\u{30d2}',
+    '\u{30d5}',
+    '\u{30d5}',
+    '\u{30d8}',
+    '\u{30d8}',
+    '\u{30db}',
+    '\u{30db}',
+    '\u{30a6}',
+    '\u{30ef}',
+    '\u{30f0}',
+    '\u{30f1}',
+    '\u{30f2}',
+    '\u{30fd}',
+    '\u{5d9}',
+    '\u{5f2}',
+    '\u{5e9}',
+    '\u{5e9}',
+    '\u{5e9}',
+    '\u{5e9}',
+    '\u{5d0}',
+    '\u{5d0}',
+    '\u{5d0}',
+    '\u{5d1}',
+    '\u{5d2}',
+    '\u{5d3}',
+    '\u{5d4}',
+    '\u{5d5}',
+    '\u{5d6}',
+    '\u{5d8}',
+    '\u{5d9}',
+    '\u{5da}',
+    '\u{5db}',
+    '\u{5dc}',
+    '\u{5de}',
+    '\u{5e0}',
+    '\u{5e1}',
+    '\u{5e3}',
+    '\u{5e4}',
+    '\u{5e6}',
+    '\u{5e7}',
+    '\u{5e8}',
+    '\u{5e9}',
+    '\u{5ea}',
+    '\u{5d5}',
+    '\u{5d1}',
+    '\u{5db}',
+    '\u{5e4}',
+    '\u{11099}',
+    '\u{1109b}',
+    '\u{110a5}',
+];
@@ -0,0 +1,78 @@
+//! Exception-safe entry into an environment.
+//!
+//! An [`EnvScope`] makes an environment current and pushes the guard that
+//! roots it; dropping the scope restores the previous environment and cuts
+//! the environment guard stack back to where it was, so an early return or
+//! a `?` inside can't leave either behind. It dereferences to the
+//! interpreter, so code inside runs through it.
+//!
+//! Debug builds also check that the code inside left the guard stack as it
+//! found it: a construct that pushes without popping fails the tests that
+//! reach it instead of slowly keeping environments alive.
+
+use core::ops::{Deref, DerefMut};
+
+use super::Interpreter;
+use crate::gc::Guard;
+use crate::prelude::*;
+use crate::value::{CheapClone, EnvRef, JsObject};
+
+/// The interpreter running in an environment entered with [`EnvScope::enter`]
+/// or [`EnvScope::nested`], until dropped
+pub(crate) struct EnvScope<'a> {
+    interp: &'a mut Interpreter,
+    saved_env: EnvRef,
+    /// Depth of the guard stack before the scope's own guard was pushed
+    depth: usize,
+}
+
+impl<'a> EnvScope<'a> {
+    /// Make `env`, rooted by `guard`, the current environment
+    pub(crate) fn enter(interp: &'a mut Interpreter, env: EnvRef, guard: Guard<JsObject>) -> Self {
+        let depth = interp.env_guards.len();
+        let saved_env = mem::replace(&mut interp.env, env);
+        interp.push_env_guard(guard);
+        Self {
+            interp,
+            saved_env,
+            depth,
+        }
+    }
+
+    /// Open a fresh scope nested in the current environment
+    pub(crate) fn nested(interp: &'a mut Interpreter) -> Self {
+        let depth = interp.env_guards.len();
+        let saved_env = interp.push_scope();
+        Self {
+            interp,
+            saved_env,
+            depth,
+        }
+    }
+}
+
+impl Deref for EnvScope<'_> {
+    type Target = Interpreter;
+
+    fn deref(&self) -> &Interpreter {
+        self.interp
+    }
+}
+
+impl DerefMut for EnvScope<'_> {
+    fn deref_mut(&mut self) -> &mut Interpreter {
+        self.interp
+    }
+}
+
+impl Drop for EnvScope<'_> {
+    fn drop(&mut self) {
+        debug_assert_eq!(
+            self.interp.env_guards.len(),
+            self.depth + 1,
+            "environment guard stack unbalanced on leaving a scope"
+        );
+        self.interp.env_guards.truncate(self.depth);
+        self.interp.env = self.saved_env.cheap_clone();
+    }
+}
@@ -0,0 +1,189 @@
+//! A ring buffer of recent VM activity for post-mortem debugging.
+//!
+//! With [`crate::InterpreterConfig::flight_recorder`] set, the VM notes
+//! function calls and returns, property reads that found nothing, orders
+//! created and fulfilled, and modules starting and finishing. Entries hold
+//! interned strings and the chunk and offset they happened at, so recording
+//! allocates nothing but the slot; source positions are only resolved when
+//! an error leaves `step()` or `eval` and the last [`FLIGHT_LOG_TAIL`]
+//! events are attached to it (see [`JsError::flight_log`]). With the
+//! recorder off, each site costs a single `is_some()` branch.
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use crate::compiler::BytecodeChunk;
+use crate::error::{JsError, SourceLocation};
+use crate::value::{JsString, PropertyKey};
+use crate::{ModulePath, OrderId};
+
+/// Most recent events attached to an error
+pub const FLIGHT_LOG_TAIL: usize = 50;
+
+/// What a [`FlightEvent`] records
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlightEventKind {
+    /// A script function was called; `subject` is its name
+    Call,
+    /// A script function returned or threw; `subject` is its name
+    Return,
+    /// A property read found nothing; `subject` is the key
+    MissingProperty,
+    /// The script created an order; `subject` is its id
+    OrderCreated,
+    /// The host fulfilled an order; `subject` is its id
+    OrderFulfilled,
+    /// A module started running; `subject` is its path
+    ModuleStart,
+    /// A module finished running; `subject` is its path
+    ModuleEnd,
+}
+
+impl FlightEventKind {
+    fn label(self) -> &'static str {
+        match self {
+            FlightEventKind::Call => "call",
+            FlightEventKind::Return => "return",
+            FlightEventKind::MissingProperty => "missing property",
+            FlightEventKind::OrderCreated => "order created",
+            FlightEventKind::OrderFulfilled => "order fulfilled",
+            FlightEventKind::ModuleStart => "module start",
+            FlightEventKind::ModuleEnd => "module end",
+        }
+    }
+}
+
+/// An event of the flight recorder, as attached to an error
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlightEvent {
+    pub kind: FlightEventKind,
+    /// Function name, property key, order id or module path; empty for
+    /// anonymous functions
+    pub subject: String,
+    /// Where in the script it happened, for events the VM records
+    pub location: Option<SourceLocation>,
+}
+
+impl fmt::Display for FlightEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let subject = match self.subject.as_str() {
+            "" => "<anonymous>",
+            subject => subject,
+        };
+        write!(f, "{} {}", self.kind.label(), subject)?;
+        if let Some(location) = &self.location {
+            write!(f, " at {}", location)?;
+        }
+        Ok(())
+    }
+}
+
+/// The subject of an entry, kept in the form the VM had at hand
+pub(crate) enum FlightSubject {
+    Name(Option<JsString>),
+    Key(PropertyKey),
+    Order(OrderId),
+    Module(ModulePath),
+}
+
+struct FlightEntry {
+    kind: FlightEventKind,
+    subject: FlightSubject,
+    /// Chunk and bytecode offset the event happened at
+    site: Option<(Rc<BytecodeChunk>, usize)>,
+}
+
+/// The last `capacity` events, oldest first
+pub(crate) struct FlightRecorder {
+    entries: VecDeque<FlightEntry>,
+    capacity: usize,
+}
+
+impl FlightRecorder {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    fn push(&mut self, entry: FlightEntry) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// The last `count` events, rendered
+    fn tail(&self, count: usize) -> Vec<FlightEvent> {
+        let skip = self.entries.len().saturating_sub(count);
+        self.entries.iter().skip(skip).map(render).collect()
+    }
+}
+
+fn render(entry: &FlightEntry) -> FlightEvent {
+    let subject = match &entry.subject {
+        FlightSubject::Name(name) => name.as_ref().map(|n| n.to_string()).unwrap_or_default(),
+        FlightSubject::Key(key) => key.to_string(),
+        FlightSubject::Order(id) => id.0.to_string(),
+        FlightSubject::Module(path) => path.to_string(),
+    };
+    let location = entry.site.as_ref().and_then(|(chunk, offset)| {
+        let span = chunk.get_source_location(*offset)?;
+        Some(SourceLocation {
+            file: chunk.source_file.clone(),
+            line: span.line,
+            column: span.column,
+            length: span.end.saturating_sub(span.start) as u32,
+            offset: Some(span.start),
+            snippet: None,
+        })
+    });
+    FlightEvent {
+        kind: entry.kind,
+        subject,
+        location,
+    }
+}
+
+impl Interpreter {
+    /// Note an event in the flight recorder; callers check that it is on
+    #[cold]
+    pub(crate) fn record_flight(
+        &mut self,
+        kind: FlightEventKind,
+        subject: FlightSubject,
+        site: Option<(Rc<BytecodeChunk>, usize)>,
+    ) {
+        if let Some(recorder) = self.flight_recorder.as_mut() {
+            recorder.push(FlightEntry {
+                kind,
+                subject,
+                site,
+            });
+        }
+    }
+
+    /// Note module `path` starting or finishing, if the recorder is on
+    pub(crate) fn record_module_flight(&mut self, kind: FlightEventKind, path: &ModulePath) {
+        if self.flight_recorder.is_some() {
+            self.record_flight(kind, FlightSubject::Module(path.clone()), None);
+        }
+    }
+
+    /// Attach the tail of the flight recorder to a script error leaving the
+    /// interpreter, unless it already has one
+    pub(crate) fn with_flight_log(&self, mut error: JsError) -> JsError {
+        if let (
+            Some(recorder),
+            JsError::RuntimeError {
+                flight_log: flight_log @ None,
+                ..
+            },
+        ) = (self.flight_recorder.as_ref(), &mut error)
+        {
+            *flight_log = Some(recorder.tail(FLIGHT_LOG_TAIL).into_boxed_slice());
+        }
+        error
+    }
+}
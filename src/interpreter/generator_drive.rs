@@ -0,0 +1,207 @@
+//! Host driving of script generators.
+//!
+//! A script can hand the host a generator, say from an exported
+//! `function*`, and the host then pulls items from it one at a time with
+//! [`Interpreter::generator_next`], persisting progress between items.
+//! Each call runs `next`, `return` or `throw` as the active execution, so an
+//! async generator awaiting an order suspends like any other run: the host
+//! fulfills the order and calls `generator_next` again with no argument to
+//! carry on from where the generator stopped.
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use crate::error::JsError;
+use crate::value::{CheapClone, ExoticObject, Guarded, JsValue};
+use crate::{ObjectId, Order, OrderId, RuntimeValue, StepResult};
+
+/// What driving a generator one step produced, see [`Interpreter::generator_next`]
+#[derive(Debug)]
+pub enum GeneratorStep {
+    /// The generator yielded a value and is paused at the `yield`
+    Yielded(RuntimeValue),
+    /// The generator finished, with the value it returned
+    Done(RuntimeValue),
+    /// The generator waits on orders. Fulfill them, then call
+    /// `generator_next` with no argument to continue.
+    Suspended {
+        /// Orders created since the last suspension
+        pending: Vec<Order>,
+        /// Orders that were cancelled
+        cancelled: Vec<OrderId>,
+    },
+    /// Orders were created and the generator is still running (only with
+    /// eager order delivery). Start the work, fulfill the orders when ready
+    /// and call `generator_next` with no argument to continue.
+    Orders(Vec<Order>),
+}
+
+/// Which generator method a host call runs
+#[derive(Clone, Copy)]
+enum GeneratorMethod {
+    Next,
+    Return,
+    Throw,
+}
+
+impl GeneratorMethod {
+    fn name(self) -> &'static str {
+        match self {
+            GeneratorMethod::Next => "generator_next",
+            GeneratorMethod::Return => "generator_return",
+            GeneratorMethod::Throw => "generator_throw",
+        }
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            GeneratorMethod::Next => "await __host_gen__.next(__host_arg__)",
+            GeneratorMethod::Return => "await __host_gen__.return(__host_arg__)",
+            GeneratorMethod::Throw => "await __host_gen__.throw(__host_arg__)",
+        }
+    }
+}
+
+impl Interpreter {
+    /// Resume generator `generator`, as `generator.next(arg)`.
+    ///
+    /// Works for sync and async generators; the promise an async generator
+    /// returns is awaited. While the generator waits on orders the call
+    /// returns [`GeneratorStep::Suspended`]; after fulfilling them, call
+    /// `generator_next` on the same generator with `arg` `None` to continue.
+    /// Like [`Interpreter::call_method`], it needs no other execution to be
+    /// in progress.
+    pub fn generator_next(
+        &mut self,
+        generator: &RuntimeValue,
+        arg: Option<RuntimeValue>,
+    ) -> Result<GeneratorStep, JsError> {
+        let id = generator_id(generator)?;
+        if self.driven_generator.is_some() && self.execution_in_progress() {
+            if self.driven_generator != Some(id) {
+                return Err(JsError::internal_error(
+                    "generator_next: another generator is waiting on orders",
+                ));
+            }
+            if arg.is_some() {
+                return Err(JsError::internal_error(
+                    "generator_next: the generator is waiting on orders; continue it without an argument",
+                ));
+            }
+            let result = self.step_fresh_scope_run();
+            return self.generator_step(result);
+        }
+        self.drive_generator(GeneratorMethod::Next, generator, arg)
+    }
+
+    /// Finish generator `generator` early, as `generator.return(value)`.
+    ///
+    /// Its pending `finally` blocks run; one that yields again makes this
+    /// return [`GeneratorStep::Yielded`], one that waits on orders makes it
+    /// return [`GeneratorStep::Suspended`] (continue with `generator_next`).
+    pub fn generator_return(
+        &mut self,
+        generator: &RuntimeValue,
+        value: RuntimeValue,
+    ) -> Result<GeneratorStep, JsError> {
+        self.drive_generator(GeneratorMethod::Return, generator, Some(value))
+    }
+
+    /// Throw `error` into generator `generator` at its paused `yield`, as
+    /// `generator.throw(error)`.
+    ///
+    /// A generator that catches it steps on like with `generator_next`; one
+    /// that doesn't fails the call with the error.
+    pub fn generator_throw(
+        &mut self,
+        generator: &RuntimeValue,
+        error: RuntimeValue,
+    ) -> Result<GeneratorStep, JsError> {
+        self.drive_generator(GeneratorMethod::Throw, generator, Some(error))
+    }
+
+    /// Start running `method` of `generator` with `arg` as the active execution
+    fn drive_generator(
+        &mut self,
+        method: GeneratorMethod,
+        generator: &RuntimeValue,
+        arg: Option<RuntimeValue>,
+    ) -> Result<GeneratorStep, JsError> {
+        let id = generator_id(generator)?;
+        let arg = arg.map_or(JsValue::Undefined, |arg| arg.value().clone());
+        let global_env = self.env.cheap_clone();
+        let result = self.run_in_fresh_scope(
+            method.name(),
+            method.source(),
+            global_env,
+            vec![
+                ("__host_gen__", generator.value().clone()),
+                ("__host_arg__", arg),
+            ],
+        );
+        self.driven_generator = Some(id);
+        self.generator_step(result)
+    }
+
+    /// Map the result of stepping a generator call to a [`GeneratorStep`]
+    fn generator_step(
+        &mut self,
+        result: Result<StepResult, JsError>,
+    ) -> Result<GeneratorStep, JsError> {
+        let result = match result {
+            Ok(StepResult::Suspended { pending, cancelled }) => {
+                return Ok(GeneratorStep::Suspended { pending, cancelled });
+            }
+            Ok(StepResult::Orders(pending)) => return Ok(GeneratorStep::Orders(pending)),
+            Ok(StepResult::Complete(result)) => result,
+            Ok(other) => {
+                self.driven_generator = None;
+                return Err(JsError::internal_error(format!(
+                    "Unexpected step result driving a generator: {:?}",
+                    other
+                )));
+            }
+            Err(err) => {
+                self.driven_generator = None;
+                return Err(err);
+            }
+        };
+        self.driven_generator = None;
+
+        let JsValue::Object(iter_result) = result.value() else {
+            return Err(JsError::type_error("Iterator result is not an object"));
+        };
+        let value_key = self.property_key("value");
+        let done_key = self.property_key("done");
+        let (value, done) = {
+            let iter_result = iter_result.borrow();
+            let value = iter_result
+                .get_property(&value_key)
+                .unwrap_or(JsValue::Undefined);
+            let done = iter_result
+                .get_property(&done_key)
+                .is_some_and(|done| done.to_boolean());
+            (value, done)
+        };
+        let value = RuntimeValue::from_guarded(Guarded::from_value(value, &self.heap));
+        Ok(if done {
+            GeneratorStep::Done(value)
+        } else {
+            GeneratorStep::Yielded(value)
+        })
+    }
+}
+
+/// The identity of `generator`, or a TypeError if it isn't a generator
+fn generator_id(generator: &RuntimeValue) -> Result<ObjectId, JsError> {
+    let is_generator = generator.value().as_object().is_some_and(|obj| {
+        matches!(
+            obj.borrow().exotic,
+            ExoticObject::Generator(_) | ExoticObject::BytecodeGenerator(_)
+        )
+    });
+    match generator.object_id() {
+        Some(id) if is_generator => Ok(id),
+        _ => Err(JsError::type_error("Value is not a generator")),
+    }
+}
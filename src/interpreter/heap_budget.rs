@@ -0,0 +1,161 @@
+//! Caller-supplied memory budget.
+//!
+//! Usage is counted coarsely, per container rather than per allocation:
+//!
+//! - GC objects: live objects times the size of an object, plus the
+//!   property slots they held at the last collection
+//! - strings: bytes held by properties, elements and bindings at the last
+//!   collection; a single string being built is checked as it grows
+//! - bytecode: instructions and constants of every chunk compiled for
+//!   execution, never released
+//!
+//! The interpreter re-measures before each instruction and fails with
+//! `TerminationReason::HeapBudgetExceeded` once usage passes the limit.
+
+use crate::compiler::{BytecodeChunk, Constant, Op};
+use crate::prelude::*;
+use crate::value::{JsObject, Property, PropertyKey};
+
+/// Memory budget shared between the host and an interpreter.
+///
+/// Pass a clone in [`crate::InterpreterConfig::heap_budget`] and keep one to
+/// observe usage while the script runs. Available without `std`.
+#[derive(Debug, Clone)]
+pub struct HeapBudget(Rc<BudgetState>);
+
+#[derive(Debug)]
+struct BudgetState {
+    limit: usize,
+    live_objects: Cell<usize>,
+    property_slots: Cell<usize>,
+    string_bytes: Cell<usize>,
+    bytecode_bytes: Cell<usize>,
+    peak: Cell<usize>,
+}
+
+/// Bytes counted against a [`HeapBudget`], by kind
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HeapUsage {
+    /// GC objects and their property slots
+    pub object_bytes: usize,
+    /// UTF-8 bytes of the strings objects hold
+    pub string_bytes: usize,
+    /// Compiled bytecode chunks
+    pub bytecode_bytes: usize,
+}
+
+impl HeapUsage {
+    /// All bytes counted
+    pub fn total(&self) -> usize {
+        self.object_bytes
+            .saturating_add(self.string_bytes)
+            .saturating_add(self.bytecode_bytes)
+    }
+}
+
+impl HeapBudget {
+    /// A budget of `limit` bytes
+    pub fn new(limit: usize) -> Self {
+        Self(Rc::new(BudgetState {
+            limit,
+            live_objects: Cell::new(0),
+            property_slots: Cell::new(0),
+            string_bytes: Cell::new(0),
+            bytecode_bytes: Cell::new(0),
+            peak: Cell::new(0),
+        }))
+    }
+
+    /// Most bytes the interpreter may use
+    pub fn limit(&self) -> usize {
+        self.0.limit
+    }
+
+    /// Bytes in use at the last measurement
+    pub fn used(&self) -> usize {
+        self.usage().total()
+    }
+
+    /// Bytes left before the limit, 0 once it is passed
+    pub fn remaining(&self) -> usize {
+        self.0.limit.saturating_sub(self.used())
+    }
+
+    /// Highest [`Self::used`] seen so far
+    pub fn peak(&self) -> usize {
+        self.0.peak.get()
+    }
+
+    /// Usage at the last measurement, by kind
+    pub fn usage(&self) -> HeapUsage {
+        HeapUsage {
+            object_bytes: self
+                .0
+                .live_objects
+                .get()
+                .saturating_mul(mem::size_of::<JsObject>())
+                .saturating_add(self.0.property_slots.get().saturating_mul(SLOT_BYTES)),
+            string_bytes: self.0.string_bytes.get(),
+            bytecode_bytes: self.0.bytecode_bytes.get(),
+        }
+    }
+
+    /// Whether usage has passed the limit
+    pub fn is_exceeded(&self) -> bool {
+        self.used() > self.0.limit
+    }
+
+    pub(crate) fn set_live_objects(&self, live_objects: usize) {
+        self.0.live_objects.set(live_objects);
+        self.update_peak();
+    }
+
+    /// Record what a walk of the reachable objects found
+    pub(crate) fn set_contents(&self, property_slots: usize, string_bytes: usize) {
+        self.0.property_slots.set(property_slots);
+        self.0.string_bytes.set(string_bytes);
+        self.update_peak();
+    }
+
+    pub(crate) fn add_chunk(&self, chunk: &BytecodeChunk) {
+        let bytes = self
+            .0
+            .bytecode_bytes
+            .get()
+            .saturating_add(chunk_bytes(chunk));
+        self.0.bytecode_bytes.set(bytes);
+        self.update_peak();
+    }
+
+    fn update_peak(&self) {
+        let used = self.used();
+        if used > self.0.peak.get() {
+            self.0.peak.set(used);
+        }
+    }
+}
+
+/// Size of one property slot: a key and its value
+const SLOT_BYTES: usize = mem::size_of::<(PropertyKey, Property)>();
+
+/// Instructions and constants of `chunk` and the chunks nested in it
+fn chunk_bytes(chunk: &BytecodeChunk) -> usize {
+    let own = chunk
+        .code
+        .len()
+        .saturating_mul(mem::size_of::<Op>())
+        .saturating_add(
+            chunk
+                .constants
+                .len()
+                .saturating_mul(mem::size_of::<Constant>()),
+        );
+    chunk
+        .constants
+        .iter()
+        .fold(own, |total, constant| match constant {
+            Constant::Chunk(nested) => total.saturating_add(chunk_bytes(nested)),
+            Constant::String(s) => total.saturating_add(s.len()),
+            _ => total,
+        })
+}
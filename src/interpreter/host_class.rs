@@ -0,0 +1,306 @@
+//! Host-defined exotic objects.
+//!
+//! A host class is a set of Rust hooks registered with
+//! [`Interpreter::register_host_class`]; objects created from it carry
+//! opaque host data and route property reads, writes, `in`, key enumeration
+//! and calls through those hooks, the way a Proxy routes them through its
+//! handler's traps. A missing hook, or a `get` hook answering `None`, falls
+//! back to the object's ordinary properties and prototype chain.
+//!
+//! A class with a `dispose` hook makes its objects disposable: they get a
+//! `[Symbol.dispose]` method that runs the hook once, so a script can hand
+//! them to a DisposableStack and release the Rust-side resource early
+//! instead of waiting for the collector to drop the host data.
+
+use core::any::Any;
+
+use crate::RuntimeValue;
+use crate::error::JsError;
+use crate::gc::Guard;
+use crate::interpreter::Interpreter;
+use crate::prelude::*;
+use crate::value::{
+    ExoticObject, Guarded, JsFunction, JsObject, JsObjectRef, JsString, JsValue, NativeFunction,
+    Property, PropertyKey,
+};
+
+/// [`HostClassDef::get`]: the value of a property, or `None` to read it
+/// from the object's own properties and prototype chain
+pub type HostGetFn =
+    fn(&mut Interpreter, &dyn Any, &PropertyKey) -> Result<Option<Guarded>, JsError>;
+
+/// [`HostClassDef::set`]: whether the write was handled; `false` stores the
+/// value as an ordinary own property
+pub type HostSetFn = fn(&mut Interpreter, &dyn Any, &PropertyKey, JsValue) -> Result<bool, JsError>;
+
+/// [`HostClassDef::has`]: whether the property exists, for `in`
+pub type HostHasFn = fn(&mut Interpreter, &dyn Any, &PropertyKey) -> Result<bool, JsError>;
+
+/// [`HostClassDef::own_keys`]: the keys `for-in`, `Object.keys` and
+/// `Object.entries` enumerate, in order
+pub type HostOwnKeysFn = fn(&mut Interpreter, &dyn Any) -> Result<Vec<PropertyKey>, JsError>;
+
+/// [`HostClassDef::call`]: the result of calling the object with `this` and
+/// the arguments
+pub type HostCallFn =
+    fn(&mut Interpreter, &dyn Any, JsValue, &[JsValue]) -> Result<Guarded, JsError>;
+
+/// [`HostClassDef::dispose`]: release what the object holds, called the
+/// first time the script disposes it
+pub type HostDisposeFn = fn(&mut Interpreter, &dyn Any) -> Result<(), JsError>;
+
+/// Hooks of a host class, registered with [`Interpreter::register_host_class`]
+#[derive(Clone, Default)]
+pub struct HostClassDef {
+    /// Class name, shown by `Object.prototype.toString` as `[object Name]`
+    pub name: String,
+    pub get: Option<HostGetFn>,
+    pub set: Option<HostSetFn>,
+    pub has: Option<HostHasFn>,
+    pub own_keys: Option<HostOwnKeysFn>,
+    /// Makes objects of the class callable, with `typeof` giving `"function"`
+    pub call: Option<HostCallFn>,
+    /// Makes objects of the class disposable through `[Symbol.dispose]()`
+    pub dispose: Option<HostDisposeFn>,
+}
+
+/// A class registered with [`Interpreter::register_host_class`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HostClassId(usize);
+
+/// What an object created by [`Interpreter::create_host_object`] holds.
+///
+/// The host data is dropped when the garbage collector frees the object, so
+/// its `Drop` impl is the class's finalizer. Values kept in host data are
+/// not traced: objects it refers to must be kept alive by a guard.
+#[derive(Clone)]
+pub struct HostObjectData {
+    pub class: HostClassId,
+    pub data: Rc<dyn Any>,
+    /// Whether the class has a `call` hook
+    pub callable: bool,
+    /// Whether the script has disposed the object, so its `dispose` hook
+    /// doesn't run again
+    pub disposed: bool,
+}
+
+impl fmt::Debug for HostObjectData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HostObjectData")
+            .field("class", &self.class)
+            .field("callable", &self.callable)
+            .field("disposed", &self.disposed)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Interpreter {
+    /// Register a host class whose objects dispatch to `def`'s hooks
+    pub fn register_host_class(&mut self, def: HostClassDef) -> HostClassId {
+        self.host_classes.push(Rc::new(def));
+        HostClassId(self.host_classes.len() - 1)
+    }
+
+    /// Create an object of a registered host class holding `host_data`,
+    /// which every hook receives.
+    ///
+    /// The object inherits from `Object.prototype`, so a `get` hook that
+    /// answers `None` for `toString` keeps the usual methods. With a
+    /// `dispose` hook it also gets an own `[Symbol.dispose]` method.
+    pub fn create_host_object(
+        &mut self,
+        class: HostClassId,
+        host_data: Box<dyn Any>,
+    ) -> Result<RuntimeValue, JsError> {
+        let def = self
+            .host_classes
+            .get(class.0)
+            .ok_or_else(|| JsError::internal_error("Unknown host class"))?;
+        let callable = def.call.is_some();
+        let disposable = def.dispose.is_some();
+        let guard = self.heap.create_guard();
+        let obj = guard.alloc();
+        {
+            let mut obj_ref = obj.borrow_mut();
+            obj_ref.prototype = Some(self.object_prototype.clone());
+            obj_ref.exotic = ExoticObject::Host(HostObjectData {
+                class,
+                data: Rc::from(host_data),
+                callable,
+                disposed: false,
+            });
+        }
+        if disposable {
+            self.install_host_dispose(&guard, &obj);
+        }
+        Ok(RuntimeValue::with_guard(JsValue::Object(obj), guard))
+    }
+
+    /// Give host object `obj` a `[Symbol.dispose]` method running its
+    /// class's `dispose` hook
+    pub(crate) fn install_host_dispose(&mut self, guard: &Guard<JsObject>, obj: &JsObjectRef) {
+        let name = self.intern("[Symbol.dispose]");
+        let dispose = self.create_js_function(
+            guard,
+            JsFunction::Native(NativeFunction {
+                name,
+                func: host_dispose,
+                arity: 0,
+                ffi_id: 0,
+            }),
+        );
+        if let JsValue::Symbol(symbol) =
+            self.get_well_known(crate::WellKnownSymbol::Dispose).value()
+        {
+            obj.borrow_mut().define_property(
+                PropertyKey::Symbol(symbol.clone()),
+                Property::with_attributes(JsValue::Object(dispose), true, false, true),
+            );
+        }
+    }
+
+    /// Name of the host class `obj` belongs to, if it is a host object
+    pub(crate) fn host_class_name(&self, obj: &JsObjectRef) -> Option<String> {
+        let class = match &obj.borrow().exotic {
+            ExoticObject::Host(data) => data.class,
+            _ => return None,
+        };
+        self.host_classes.get(class.0).map(|def| def.name.clone())
+    }
+
+    /// The hooks of host object `obj` and its data, taken out so no borrow
+    /// of the object is held while a hook runs
+    fn host_hooks(&self, obj: &JsObjectRef) -> Option<(Rc<HostClassDef>, Rc<dyn Any>)> {
+        let (class, data) = match &obj.borrow().exotic {
+            ExoticObject::Host(data) => (data.class, data.data.clone()),
+            _ => return None,
+        };
+        let def = Rc::clone(self.host_classes.get(class.0)?);
+        Some((def, data))
+    }
+}
+
+/// Whether `obj` is a host object
+pub(crate) fn is_host(obj: &JsObjectRef) -> bool {
+    matches!(obj.borrow().exotic, ExoticObject::Host(_))
+}
+
+/// [[Get]] of a host object: its `get` hook, then ordinary lookup
+pub(crate) fn host_get(
+    interp: &mut Interpreter,
+    obj: &JsObjectRef,
+    key: &PropertyKey,
+    receiver: JsValue,
+) -> Result<Guarded, JsError> {
+    if let Some((def, data)) = interp.host_hooks(obj)
+        && let Some(get) = def.get
+        && let Some(value) = get(interp, &*data, key)?
+    {
+        return Ok(value);
+    }
+    let prop = obj.borrow().get_property_descriptor(key);
+    match prop {
+        Some((p, _)) if p.is_accessor() => match p.getter() {
+            Some(getter) => interp.call_function(JsValue::Object(getter.clone()), receiver, &[]),
+            None => Ok(Guarded::unguarded(JsValue::Undefined)),
+        },
+        Some((p, _)) => Ok(Guarded::unguarded(p.value)),
+        None => Ok(Guarded::unguarded(JsValue::Undefined)),
+    }
+}
+
+/// [[Set]] of a host object: its `set` hook, then an ordinary own property
+pub(crate) fn host_set(
+    interp: &mut Interpreter,
+    obj: &JsObjectRef,
+    key: PropertyKey,
+    value: JsValue,
+) -> Result<bool, JsError> {
+    if let Some((def, data)) = interp.host_hooks(obj)
+        && let Some(set) = def.set
+        && set(interp, &*data, &key, value.clone())?
+    {
+        return Ok(true);
+    }
+    interp.check_write(obj, Some(&key))?;
+    obj.borrow_mut().set_property(key, value);
+    Ok(true)
+}
+
+/// [[HasProperty]] of a host object: its `has` hook, then ordinary lookup
+pub(crate) fn host_has(
+    interp: &mut Interpreter,
+    obj: &JsObjectRef,
+    key: &PropertyKey,
+) -> Result<bool, JsError> {
+    if let Some((def, data)) = interp.host_hooks(obj)
+        && let Some(has) = def.has
+    {
+        return has(interp, &*data, key);
+    }
+    Ok(obj.borrow().get_property_descriptor(key).is_some())
+}
+
+/// Keys of a host object from its `own_keys` hook, `None` without one
+pub(crate) fn host_own_keys(
+    interp: &mut Interpreter,
+    obj: &JsObjectRef,
+) -> Result<Option<Vec<PropertyKey>>, JsError> {
+    match interp.host_hooks(obj) {
+        Some((def, data)) => match def.own_keys {
+            Some(own_keys) => own_keys(interp, &*data).map(Some),
+            None => Ok(None),
+        },
+        None => Ok(None),
+    }
+}
+
+/// [[Call]] of a host object through its `call` hook
+pub(crate) fn host_call(
+    interp: &mut Interpreter,
+    obj: &JsObjectRef,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let Some((def, data)) = interp.host_hooks(obj) else {
+        return Err(JsError::type_error("Not a function"));
+    };
+    let Some(call) = def.call else {
+        return Err(JsError::type_error(format!(
+            "{} is not a function",
+            def.name
+        )));
+    };
+    call(interp, &*data, this, args)
+}
+
+/// `[Symbol.dispose]()` of a host object: its `dispose` hook, the first time
+fn host_dispose(
+    interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let JsValue::Object(obj) = &this else {
+        return Err(JsError::type_error("Not a host object"));
+    };
+    let first = match &mut obj.borrow_mut().exotic {
+        ExoticObject::Host(data) => !mem::replace(&mut data.disposed, true),
+        _ => return Err(JsError::type_error("Not a host object")),
+    };
+    if first
+        && let Some((def, data)) = interp.host_hooks(obj)
+        && let Some(dispose) = def.dispose
+    {
+        dispose(interp, &*data)?;
+    }
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+/// A property key as the string or symbol script sees
+pub(crate) fn key_to_value(key: PropertyKey) -> JsValue {
+    match key {
+        PropertyKey::String(s) => JsValue::String(s),
+        PropertyKey::Index(i) => JsValue::String(JsString::from(i.to_string())),
+        PropertyKey::Symbol(s) => JsValue::Symbol(s),
+    }
+}
@@ -0,0 +1,265 @@
+//! A blocking driver for hosts that implement [`Host`].
+//!
+//! [`Interpreter::run`] prepares a script and steps it to completion,
+//! serving `NeedImports` from [`Host::load_module`], orders from
+//! [`Host::handle_order`] and console output from [`Host::on_console`]. It
+//! wraps `prepare`/`step`/`provide_module`/`fulfill_orders` without
+//! replacing them: hosts that interleave other work between steps keep
+//! using the step API.
+//!
+//! ```
+//! use tsrun::{
+//!     Host, HostAction, ImportRequest, Interpreter, Order, ResponseFactory, ToJsonOptions,
+//! };
+//!
+//! struct Echo;
+//!
+//! impl Host for Echo {
+//!     fn load_module(&mut self, request: &ImportRequest) -> Result<String, String> {
+//!         Err(format!("no module {}", request.specifier))
+//!     }
+//!
+//!     fn handle_order(&mut self, order: &Order, responses: &mut ResponseFactory<'_>) -> HostAction {
+//!         let echo = order
+//!             .payload
+//!             .to_json(&ToJsonOptions::default())
+//!             .and_then(|json| responses.json(&json));
+//!         HostAction::Fulfill(echo)
+//!     }
+//! }
+//!
+//! let mut interp = Interpreter::new();
+//! let source = r#"
+//!     import { order } from "tsrun:host";
+//!     const reply: any = await order({ n: 41 });
+//!     reply.n + 1
+//! "#;
+//! let value = interp.run(source, None, &mut Echo)?;
+//! assert_eq!(value.as_number(), Some(42.0));
+//! # Ok::<(), tsrun::JsError>(())
+//! ```
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use crate::error::JsError;
+use crate::platform::{ConsoleLevel, ConsoleProvider};
+use crate::value::JsValue;
+use crate::{ImportRequest, ModulePath, Order, OrderId, OrderResponse, RuntimeValue, StepResult};
+
+/// What [`Interpreter::run`] does with an order, from [`Host::handle_order`]
+#[derive(Debug)]
+pub enum HostAction {
+    /// Answer the order now
+    Fulfill(Result<RuntimeValue, JsError>),
+    /// Answer it later: once the script can make no other progress,
+    /// [`Host::resolve_deferred`] is called with the order's id
+    Defer,
+    /// Stop the run, which fails with this error
+    Abort(JsError),
+}
+
+/// The stage of [`Interpreter::run`], reported to [`Host::on_phase`] as it
+/// changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunPhase {
+    /// Serving imports with [`Host::load_module`]
+    LoadingModules,
+    /// Running script code and handing orders to [`Host::handle_order`]
+    Executing,
+    /// The script waits on deferred orders only; [`Host::resolve_deferred`]
+    /// answers them
+    AwaitingOrders,
+}
+
+/// Builds response values for [`Host::handle_order`] and
+/// [`Host::resolve_deferred`] in the interpreter being run
+pub struct ResponseFactory<'a> {
+    interp: &'a mut Interpreter,
+}
+
+impl ResponseFactory<'_> {
+    /// A response value built from JSON, as by [`crate::api::create_response_object`]
+    pub fn json(&mut self, value: &serde_json::Value) -> Result<RuntimeValue, JsError> {
+        crate::api::create_response_object(self.interp, value)
+    }
+
+    /// The interpreter, for building values the other methods don't cover
+    pub fn interpreter(&mut self) -> &mut Interpreter {
+        self.interp
+    }
+}
+
+/// A host that [`Interpreter::run`] drives a script for
+pub trait Host {
+    /// The source of the module `request` imports, or why it can't be loaded,
+    /// which fails the run
+    fn load_module(&mut self, request: &ImportRequest) -> Result<String, String>;
+
+    /// What to do with an order the script created
+    fn handle_order(&mut self, order: &Order, responses: &mut ResponseFactory<'_>) -> HostAction;
+
+    /// The answer to order `id`, deferred by [`Host::handle_order`]. Called
+    /// oldest first, one order at a time, while the script waits on nothing
+    /// else; orders the script cancels meanwhile are never asked for.
+    fn resolve_deferred(
+        &mut self,
+        id: OrderId,
+        responses: &mut ResponseFactory<'_>,
+    ) -> Result<RuntimeValue, JsError> {
+        let _ = responses;
+        Err(JsError::type_error(format!(
+            "Order {} was deferred but the host does not resolve deferred orders",
+            id.0
+        )))
+    }
+
+    /// A line the script wrote with `console.log` and friends
+    fn on_console(&mut self, level: ConsoleLevel, message: &str) {
+        let _ = (level, message);
+    }
+
+    /// The run entered `phase`
+    fn on_phase(&mut self, phase: RunPhase) {
+        let _ = phase;
+    }
+}
+
+/// Collects console output during a run until it is handed to the host
+struct BufferedConsole(Rc<RefCell<Vec<(ConsoleLevel, String)>>>);
+
+impl ConsoleProvider for BufferedConsole {
+    fn write(&self, level: ConsoleLevel, message: &str) {
+        self.0.borrow_mut().push((level, message.to_string()));
+    }
+}
+
+impl Interpreter {
+    /// Run `source` to completion, serving its imports, orders and console
+    /// output from `host`, and return its final value.
+    ///
+    /// `path` is the module path of `source`, as for [`Interpreter::prepare`].
+    /// The console provider is replaced for the duration of the run and
+    /// restored afterwards. A script left waiting with nothing to answer
+    /// fails with [`JsError::Stalled`].
+    pub fn run(
+        &mut self,
+        source: &str,
+        path: Option<ModulePath>,
+        host: &mut impl Host,
+    ) -> Result<RuntimeValue, JsError> {
+        let console = Rc::new(RefCell::new(Vec::new()));
+        let previous = mem::replace(
+            &mut self.console_provider,
+            Box::new(BufferedConsole(console.clone())),
+        );
+
+        let mut driver = HostDriver {
+            host,
+            console,
+            phase: None,
+            deferred: Vec::new(),
+        };
+        let result = driver.run(self, source, path);
+        driver.flush_console();
+
+        self.console_provider = previous;
+        result
+    }
+}
+
+struct HostDriver<'h, H: Host> {
+    host: &'h mut H,
+    console: Rc<RefCell<Vec<(ConsoleLevel, String)>>>,
+    phase: Option<RunPhase>,
+    /// Deferred orders not yet resolved or cancelled, oldest first
+    deferred: Vec<OrderId>,
+}
+
+impl<H: Host> HostDriver<'_, H> {
+    fn run(
+        &mut self,
+        interp: &mut Interpreter,
+        source: &str,
+        path: Option<ModulePath>,
+    ) -> Result<RuntimeValue, JsError> {
+        self.enter(RunPhase::Executing);
+        let mut result = interp.prepare(source, path)?;
+        loop {
+            self.flush_console();
+            match result {
+                StepResult::Continue => {}
+                StepResult::Complete(value) => return Ok(value),
+                StepResult::Done => return Ok(RuntimeValue::unguarded(JsValue::Undefined)),
+                StepResult::NeedImports(requests) => {
+                    self.enter(RunPhase::LoadingModules);
+                    for request in &requests {
+                        let source = self.host.load_module(request).map_err(|message| {
+                            JsError::module_error(format!(
+                                "Cannot load module '{}': {}",
+                                request.resolved_path, message
+                            ))
+                        })?;
+                        interp.provide_module(request.resolved_path.clone(), &source)?;
+                    }
+                }
+                StepResult::Orders(orders) => {
+                    let responses = self.handle_orders(interp, &orders)?;
+                    interp.fulfill_orders(responses);
+                }
+                StepResult::Suspended { pending, cancelled } => {
+                    self.deferred.retain(|id| !cancelled.contains(id));
+                    let mut responses = self.handle_orders(interp, &pending)?;
+                    if responses.is_empty() && !self.deferred.is_empty() {
+                        self.enter(RunPhase::AwaitingOrders);
+                        let id = self.deferred.remove(0);
+                        let result = self
+                            .host
+                            .resolve_deferred(id, &mut ResponseFactory { interp });
+                        responses.push(OrderResponse { id, result });
+                    }
+                    interp.fulfill_orders(responses);
+                }
+            }
+            self.enter(RunPhase::Executing);
+            result = interp.step()?;
+        }
+    }
+
+    /// Hand `orders` to the host; the responses of those it answered now
+    fn handle_orders(
+        &mut self,
+        interp: &mut Interpreter,
+        orders: &[Order],
+    ) -> Result<Vec<OrderResponse>, JsError> {
+        let mut responses = Vec::new();
+        for order in orders {
+            match self
+                .host
+                .handle_order(order, &mut ResponseFactory { interp })
+            {
+                HostAction::Fulfill(result) => responses.push(OrderResponse {
+                    id: order.id,
+                    result,
+                }),
+                HostAction::Defer => self.deferred.push(order.id),
+                HostAction::Abort(err) => return Err(err),
+            }
+        }
+        Ok(responses)
+    }
+
+    fn enter(&mut self, phase: RunPhase) {
+        if self.phase != Some(phase) {
+            self.phase = Some(phase);
+            self.host.on_phase(phase);
+        }
+    }
+
+    fn flush_console(&mut self) {
+        let lines = mem::take(&mut *self.console.borrow_mut());
+        for (level, message) in lines {
+            self.host.on_console(level, &message);
+        }
+    }
+}
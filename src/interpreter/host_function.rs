@@ -0,0 +1,174 @@
+//! Functions backed by Rust closures, from
+//! [`Interpreter::create_function_value`].
+//!
+//! Each function is an object of a callable host class whose data holds the
+//! closure, so the closure is dropped with the object: when the garbage
+//! collector frees it, or when the interpreter is dropped. `typeof` gives
+//! `"function"`, the object inherits from `Function.prototype`, and it has
+//! the usual `name` and `length` properties.
+//!
+//! [`Interpreter::create_disposable_function_value`] adds a hook run when
+//! the script disposes the function, for functions standing for a host
+//! resource the script should release once it is done with it.
+
+use core::any::Any;
+
+use super::Interpreter;
+use super::host_class::{HostClassDef, HostObjectData};
+use crate::RuntimeValue;
+use crate::error::JsError;
+use crate::prelude::*;
+use crate::value::{ExoticObject, Guarded, JsValue, PropertyKey};
+
+/// Closure behind a host function, called with `this` and the arguments
+type HostFunctionFn = dyn Fn(&mut Interpreter, JsValue, &[JsValue]) -> Result<Guarded, JsError>;
+
+/// Hook run the first time the script disposes a host function
+type HostFunctionDisposeFn = dyn FnOnce(&mut Interpreter) -> Result<(), JsError>;
+
+/// Host data of a host function
+struct HostFunction {
+    closure: Box<HostFunctionFn>,
+    on_dispose: RefCell<Option<Box<HostFunctionDisposeFn>>>,
+}
+
+fn host_function_call(
+    interp: &mut Interpreter,
+    data: &dyn Any,
+    this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let Some(function) = data.downcast_ref::<HostFunction>() else {
+        return Err(JsError::type_error("Not a function"));
+    };
+    (function.closure)(interp, this, args)
+}
+
+fn host_function_dispose(interp: &mut Interpreter, data: &dyn Any) -> Result<(), JsError> {
+    let hook = data
+        .downcast_ref::<HostFunction>()
+        .and_then(|function| function.on_dispose.borrow_mut().take());
+    match hook {
+        Some(hook) => hook(interp),
+        None => Ok(()),
+    }
+}
+
+impl Interpreter {
+    /// Create a function that calls `closure`, for the host to hand to
+    /// scripts, for example as a method of an order response.
+    ///
+    /// The closure receives `this` and the arguments. It can return any
+    /// value, including a promise from [`Interpreter::issue_order`] when the
+    /// call needs the host to do more work. It lives as long as the function
+    /// object, and at the latest until the interpreter is dropped.
+    ///
+    /// ```
+    /// use tsrun::{Guarded, Interpreter, JsValue, api};
+    ///
+    /// let mut interp = Interpreter::new();
+    /// let double = interp.create_function_value("double", 1, |_, _, args| {
+    ///     let n = args.first().and_then(JsValue::as_number).unwrap_or(0.0);
+    ///     Ok(Guarded::unguarded(JsValue::Number(n * 2.0)))
+    /// });
+    /// let guard = api::create_guard(&interp);
+    /// let result =
+    ///     api::call_function(&mut interp, &guard, double.value(), None, &[JsValue::from(21)]);
+    /// assert_eq!(result.unwrap().as_number(), Some(42.0));
+    /// ```
+    pub fn create_function_value(
+        &mut self,
+        name: &str,
+        arity: usize,
+        closure: impl Fn(&mut Interpreter, JsValue, &[JsValue]) -> Result<Guarded, JsError> + 'static,
+    ) -> RuntimeValue {
+        self.new_function_value(name, arity, Box::new(closure), None)
+    }
+
+    /// Like [`Interpreter::create_function_value`], for a function that
+    /// stands for a resource: it gets a `[Symbol.dispose]` method running
+    /// `on_dispose` the first time the script calls it, directly or through
+    /// a DisposableStack.
+    ///
+    /// ```
+    /// use std::cell::Cell;
+    /// use std::rc::Rc;
+    /// use tsrun::{Guarded, Interpreter, JsValue, api};
+    ///
+    /// let mut interp = Interpreter::new();
+    /// let closed = Rc::new(Cell::new(false));
+    /// let flag = closed.clone();
+    /// let read = interp.create_disposable_function_value(
+    ///     "read",
+    ///     0,
+    ///     |_, _, _| Ok(Guarded::unguarded(JsValue::from("line"))),
+    ///     move |_| {
+    ///         flag.set(true);
+    ///         Ok(())
+    ///     },
+    /// );
+    /// let global = JsValue::Object(interp.global.clone());
+    /// api::set_property(&global, "read", read.value().clone()).unwrap();
+    /// interp
+    ///     .eval("const stack = new DisposableStack(); stack.use(read); stack.dispose();", None)
+    ///     .unwrap();
+    /// assert!(closed.get());
+    /// ```
+    pub fn create_disposable_function_value(
+        &mut self,
+        name: &str,
+        arity: usize,
+        closure: impl Fn(&mut Interpreter, JsValue, &[JsValue]) -> Result<Guarded, JsError> + 'static,
+        on_dispose: impl FnOnce(&mut Interpreter) -> Result<(), JsError> + 'static,
+    ) -> RuntimeValue {
+        self.new_function_value(name, arity, Box::new(closure), Some(Box::new(on_dispose)))
+    }
+
+    fn new_function_value(
+        &mut self,
+        name: &str,
+        arity: usize,
+        closure: Box<HostFunctionFn>,
+        on_dispose: Option<Box<HostFunctionDisposeFn>>,
+    ) -> RuntimeValue {
+        let class = match self.host_function_class {
+            Some(class) => class,
+            None => {
+                let class = self.register_host_class(HostClassDef {
+                    name: "Function".to_string(),
+                    call: Some(host_function_call),
+                    dispose: Some(host_function_dispose),
+                    ..Default::default()
+                });
+                self.host_function_class = Some(class);
+                class
+            }
+        };
+        let name_key = PropertyKey::String(self.intern("name"));
+        let length_key = PropertyKey::String(self.intern("length"));
+        let name = self.intern(name);
+        let disposable = on_dispose.is_some();
+
+        let guard = self.heap.create_guard();
+        let func_obj = guard.alloc();
+        {
+            let mut f_ref = func_obj.borrow_mut();
+            f_ref.prototype = Some(self.function_prototype.clone());
+            f_ref.exotic = ExoticObject::Host(HostObjectData {
+                class,
+                data: Rc::new(HostFunction {
+                    closure,
+                    on_dispose: RefCell::new(on_dispose),
+                }),
+                callable: true,
+                disposed: false,
+            });
+            f_ref.set_property(length_key, JsValue::Number(arity as f64));
+            f_ref.set_property(name_key, JsValue::String(name));
+        }
+        if disposable {
+            self.install_host_dispose(&guard, &func_obj);
+        }
+        RuntimeValue::with_guard(JsValue::Object(func_obj), guard)
+    }
+}
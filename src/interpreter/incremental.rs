@@ -0,0 +1,183 @@
+//! Incremental preparation of large entry sources.
+//!
+//! [`Interpreter::prepare`] parses and compiles in one go, which can keep an
+//! embedding host (a browser main thread, say) busy for a long time on a big
+//! bundle. The incremental API splits that work: the source arrives in chunks
+//! that are only buffered, then the top-level statements are parsed a budget
+//! of source bytes at a time, resuming from a parser checkpoint, and a final
+//! call compiles the program and sets up execution exactly as `prepare` does.
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use crate::ast::{Program, Statement};
+use crate::error::{JsError, SourceText};
+use crate::parser::{ParseCheckpoint, Parser};
+use crate::{ModulePath, PrepareProgress};
+
+/// How far an incremental prepare has got
+enum Stage {
+    /// Receiving chunks
+    Buffering(Vec<u8>),
+    /// Parsing top-level statements; `checkpoint` is `None` before the first one
+    Parsing {
+        source: String,
+        body: Vec<Statement>,
+        checkpoint: Option<ParseCheckpoint>,
+    },
+    /// Parsed and waiting to be compiled
+    Parsed(Program),
+}
+
+/// State of an incremental prepare between calls
+pub(crate) struct IncrementalPrepare {
+    /// Normalized entry path, as `prepare` would set it
+    module_path: Option<ModulePath>,
+    stage: Stage,
+    /// Parse time summed over the calls so far, for the module metrics
+    parse_ms: u64,
+}
+
+impl Interpreter {
+    /// Start preparing an entry source that is delivered in chunks.
+    ///
+    /// Feed the source to [`Interpreter::prepare_chunk`], then call
+    /// [`Interpreter::prepare_finish`] and [`Interpreter::prepare_continue`]
+    /// until they return `PrepareProgress::Ready`. Discards any incremental
+    /// prepare that was still in progress.
+    pub fn prepare_begin(&mut self, module_path: Option<ModulePath>) {
+        let module_path = self.enter_prepare(module_path);
+        self.incremental = Some(Box::new(IncrementalPrepare {
+            module_path,
+            stage: Stage::Buffering(Vec::new()),
+            parse_ms: 0,
+        }));
+    }
+
+    /// Append a fragment of the source passed to an incremental prepare
+    pub fn prepare_chunk(&mut self, fragment: &str) -> Result<(), JsError> {
+        self.prepare_chunk_bytes(fragment.as_bytes())
+    }
+
+    /// Append raw source bytes; a UTF-8 sequence may be split across chunks
+    pub(crate) fn prepare_chunk_bytes(&mut self, bytes: &[u8]) -> Result<(), JsError> {
+        match self.incremental.as_deref_mut() {
+            Some(IncrementalPrepare {
+                stage: Stage::Buffering(buffer),
+                ..
+            }) => {
+                buffer.extend_from_slice(bytes);
+                Ok(())
+            }
+            Some(_) => Err(JsError::internal_error(
+                "prepare_chunk called after prepare_finish",
+            )),
+            None => Err(JsError::internal_error(
+                "prepare_chunk called without prepare_begin",
+            )),
+        }
+    }
+
+    /// Stop accepting chunks and start parsing, spending at most about
+    /// `budget` source bytes of parsing before returning.
+    pub fn prepare_finish(&mut self, budget: usize) -> Result<PrepareProgress, JsError> {
+        let Some(state) = self.incremental.as_deref_mut() else {
+            return Err(JsError::internal_error(
+                "prepare_finish called without prepare_begin",
+            ));
+        };
+        let Stage::Buffering(buffer) = &mut state.stage else {
+            return Err(JsError::internal_error("prepare_finish called twice"));
+        };
+        let Ok(source) = String::from_utf8(core::mem::take(buffer)) else {
+            self.incremental = None;
+            return Err(JsError::syntax_error_simple("Source is not valid UTF-8"));
+        };
+        let module_path = state.module_path.clone();
+        let source = match self.transform_source(module_path.as_ref(), &source) {
+            Ok(transformed) => transformed.unwrap_or(source),
+            Err(err) => {
+                self.incremental = None;
+                return Err(err);
+            }
+        };
+        let Some(state) = self.incremental.as_deref_mut() else {
+            return Err(JsError::internal_error("incremental prepare went missing"));
+        };
+        state.stage = Stage::Parsing {
+            source,
+            body: Vec::new(),
+            checkpoint: None,
+        };
+        self.prepare_continue(budget)
+    }
+
+    /// Resume an incremental prepare after `PrepareProgress::Pending`.
+    ///
+    /// Each call parses top-level statements until about `budget` source
+    /// bytes have been consumed, always making progress. Once parsing is done
+    /// the next call compiles the program and returns `PrepareProgress::Ready`
+    /// with the result `prepare` would have given. Errors end the prepare.
+    pub fn prepare_continue(&mut self, budget: usize) -> Result<PrepareProgress, JsError> {
+        let Some(mut state) = self.incremental.take() else {
+            return Err(JsError::internal_error(
+                "prepare_continue called without prepare_begin",
+            ));
+        };
+
+        match core::mem::replace(&mut state.stage, Stage::Buffering(Vec::new())) {
+            Stage::Buffering(buffer) => {
+                state.stage = Stage::Buffering(buffer);
+                self.incremental = Some(state);
+                Err(JsError::internal_error(
+                    "prepare_continue called before prepare_finish",
+                ))
+            }
+            Stage::Parsing {
+                source,
+                mut body,
+                checkpoint,
+            } => {
+                let total_bytes = source.len();
+                let timer = self.start_metrics_timer();
+                let lang = self.module_lang(state.module_path.as_ref());
+                let mut parser = match checkpoint {
+                    Some(checkpoint) => Parser::resume(&source, &mut self.string_dict, checkpoint),
+                    None => Parser::new(&source, &mut self.string_dict),
+                }
+                .with_lang(lang);
+                let done = parser
+                    .parse_statements(&mut body, budget.max(1))
+                    .map_err(|e| e.with_snippet(&SourceText::new(&source)))?;
+
+                let parsed_bytes = if done {
+                    let program = parser.finish_program(body);
+                    let program = self.finish_parse(program);
+                    let parse_ms = state.parse_ms + self.time_provider.elapsed_millis(timer);
+                    self.record_parse_ms(state.module_path.as_ref(), parse_ms);
+                    state.stage = Stage::Parsed(program);
+                    total_bytes
+                } else {
+                    let parsed_bytes = parser.position();
+                    let checkpoint = Some(parser.checkpoint());
+                    state.parse_ms += self.time_provider.elapsed_millis(timer);
+                    state.stage = Stage::Parsing {
+                        source,
+                        body,
+                        checkpoint,
+                    };
+                    parsed_bytes
+                };
+                self.incremental = Some(state);
+                Ok(PrepareProgress::Pending {
+                    parsed_bytes,
+                    total_bytes,
+                })
+            }
+            Stage::Parsed(program) => {
+                let result = self.prepare_program(program, state.module_path)?;
+                Ok(PrepareProgress::Ready(result))
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Read-only view of an order-suspended run for the host.
+//!
+//! [`Interpreter::suspension_info`] reports where the run is waiting and,
+//! for every frame on the suspended stack, the locals its function declares.
+//! Locals live in environment objects rather than registers, so they are
+//! found by walking each frame's environment chain for the names recorded in
+//! the function's `local_names` table.
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use crate::RuntimeValue;
+use crate::compiler::BytecodeChunk;
+use crate::gc::Gc;
+use crate::value::{CheapClone, ExoticObject, JsObject, JsString, JsValue};
+
+/// Most locals reported per frame; the rest are left out
+pub const MAX_SUSPENSION_LOCALS: usize = 64;
+
+/// A frame of the suspended stack and the locals visible in it
+#[derive(Debug)]
+pub struct SuspendedFrame {
+    /// Function name, `None` for anonymous functions and the script itself
+    pub function_name: Option<String>,
+    pub file: Option<String>,
+    pub line: u32,
+    pub column: u32,
+    pub is_async: bool,
+    /// Declared names and their current values, in order of declaration.
+    /// Bindings still in their temporal dead zone are left out.
+    pub locals: Vec<(String, RuntimeValue)>,
+}
+
+/// Where an order-suspended run is waiting, see [`Interpreter::suspension_info`]
+#[derive(Debug)]
+pub struct SuspensionInfo {
+    /// The order the run is blocked on
+    pub order_id: crate::OrderId,
+    /// Innermost first: `frames[0]` is the function at the `await`
+    pub frames: Vec<SuspendedFrame>,
+}
+
+impl SuspensionInfo {
+    /// The frame holding the `await` the run stopped at
+    pub fn await_frame(&self) -> Option<&SuspendedFrame> {
+        self.frames.first()
+    }
+}
+
+impl Interpreter {
+    /// Inspect the run while it is suspended waiting for an order.
+    ///
+    /// Returns `None` when no VM is blocked on an order, for example before
+    /// the first step or after the run completed. Values are guarded, so
+    /// they stay valid for as long as the returned struct is kept, even if
+    /// the run is resumed in the meantime.
+    pub fn suspension_info(&mut self) -> Option<SuspensionInfo> {
+        let suspension = self.suspended_for_order.as_ref()?;
+        let state = &suspension.state;
+
+        // The innermost frame runs in the interpreter's current environment;
+        // each trampoline frame saved its caller's environment when it called
+        let mut stack = vec![(state.chunk.clone(), state.ip, self.env.cheap_clone())];
+        for tramp in state.trampoline_stack.iter().rev() {
+            stack.push((
+                tramp.chunk.clone(),
+                tramp.ip,
+                tramp.saved_interp_env.cheap_clone(),
+            ));
+        }
+        let order_id = suspension.order_id;
+
+        let frames = stack
+            .into_iter()
+            .map(|(chunk, ip, env)| self.suspended_frame(&chunk, ip, &env))
+            .collect();
+        Some(SuspensionInfo { order_id, frames })
+    }
+
+    fn suspended_frame(
+        &mut self,
+        chunk: &BytecodeChunk,
+        ip: usize,
+        env: &Gc<JsObject>,
+    ) -> SuspendedFrame {
+        let span = chunk.get_source_location(ip.saturating_sub(1));
+        let info = chunk.function_info.as_ref();
+        let names = match info {
+            Some(info) => info.local_names.clone(),
+            // The script's own chunk has no function info
+            None => chunk.declared_names(&[]),
+        };
+        let locals = lookup_locals(env, &names)
+            .into_iter()
+            .map(|(name, value)| {
+                let value = match self.guard_value(&value) {
+                    Some(guard) => RuntimeValue::with_guard(value, guard),
+                    None => RuntimeValue::unguarded(value),
+                };
+                (name.to_string(), value)
+            })
+            .collect();
+        SuspendedFrame {
+            function_name: info.and_then(|info| info.name.as_ref().map(|s| s.to_string())),
+            file: chunk.source_file.clone(),
+            line: span.map_or(0, |span| span.line),
+            column: span.map_or(0, |span| span.column),
+            is_async: info.is_some_and(|info| info.is_async),
+            locals,
+        }
+    }
+}
+
+/// Nearest initialized binding of each of `names` along `env`'s chain,
+/// skipping names with none, up to [`MAX_SUSPENSION_LOCALS`]
+fn lookup_locals(env: &Gc<JsObject>, names: &[JsString]) -> Vec<(JsString, JsValue)> {
+    let mut found: Vec<Option<JsValue>> = vec![None; names.len()];
+    let mut current = Some(env.cheap_clone());
+    while let Some(scope) = current {
+        let scope = scope.borrow();
+        let ExoticObject::Environment(data) = &scope.exotic else {
+            break;
+        };
+        // Compared by content: names in the table need not be the interned
+        // copies the bindings are keyed by
+        for (key, binding) in &data.bindings {
+            if !binding.initialized || binding.import_binding.is_some() {
+                continue;
+            }
+            let slot = names
+                .iter()
+                .position(|name| name.as_str() == key.0.as_str())
+                .and_then(|index| found.get_mut(index));
+            if let Some(slot) = slot
+                && slot.is_none()
+            {
+                *slot = Some(binding.value.clone());
+            }
+        }
+        current = data.outer.as_ref().map(|outer| outer.cheap_clone());
+    }
+
+    names
+        .iter()
+        .zip(found)
+        .filter_map(|(name, value)| Some((name.cheap_clone(), value?)))
+        .take(MAX_SUSPENSION_LOCALS)
+        .collect()
+}
@@ -0,0 +1,177 @@
+//! Integrity checks of module sources provided by the host.
+//!
+//! A module can be expected to have a Subresource Integrity hash such as
+//! `sha256-<base64>`, given by a `with { integrity: "..." }` import
+//! attribute, by the manifest set with [`Interpreter::set_integrity_manifest`]
+//! or passed to [`Interpreter::provide_module_with_integrity`]. A source that
+//! doesn't match is refused with [`JsError::IntegrityMismatch`] before it is
+//! parsed, so a tampered module never runs.
+//!
+//! Only `sha256` hashes are checked; the digest is computed here so no
+//! dependency is needed.
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use super::builtins::global::base64_encode;
+use crate::error::JsError;
+use crate::{ImportRequest, ModulePath};
+
+impl Interpreter {
+    /// Expect the modules in `manifest`, by resolved path, to have the given
+    /// SRI integrity, e.g. `sha256-<base64>`.
+    ///
+    /// Applies to every later way of providing those modules, including
+    /// [`Interpreter::provide_module`] and the module source map. Imports
+    /// of listed modules carry the integrity in [`ImportRequest::integrity`].
+    /// Replaces any manifest set before.
+    pub fn set_integrity_manifest(
+        &mut self,
+        manifest: impl IntoIterator<Item = (ModulePath, String)>,
+    ) {
+        self.integrity_manifest = manifest.into_iter().collect();
+    }
+
+    /// Like [`Interpreter::provide_module`], refusing `source` with
+    /// [`JsError::IntegrityMismatch`] unless it matches `integrity`
+    pub fn provide_module_with_integrity(
+        &mut self,
+        resolved_path: ModulePath,
+        source: &str,
+        integrity: &str,
+    ) -> Result<(), JsError> {
+        verify_integrity(&resolved_path, source, integrity)?;
+        self.provide_module(resolved_path, source)
+    }
+
+    /// The integrity the manifest lists for `path`
+    pub(crate) fn manifest_integrity(&self, path: &ModulePath) -> Option<&str> {
+        self.integrity_manifest.get(path).map(String::as_str)
+    }
+
+    /// Check a source provided for `path` against the integrity its import
+    /// request carries, or else the manifest's
+    pub(crate) fn check_module_integrity(
+        &self,
+        path: &ModulePath,
+        source: &str,
+        request: Option<&ImportRequest>,
+    ) -> Result<(), JsError> {
+        let expected = request
+            .and_then(|req| req.integrity.as_deref())
+            .or_else(|| self.manifest_integrity(path));
+        match expected {
+            Some(integrity) => verify_integrity(path, source, integrity),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Check `source` against an SRI string of one or more space-separated
+/// hashes; it matches if any `sha256` hash does
+fn verify_integrity(path: &ModulePath, source: &str, integrity: &str) -> Result<(), JsError> {
+    let expected: Vec<&str> = integrity
+        .split_whitespace()
+        .filter_map(|hash| hash.strip_prefix("sha256-"))
+        // Options after `?` are reserved by the SRI spec
+        .map(|digest| digest.split('?').next().unwrap_or(digest))
+        .collect();
+    if expected.is_empty() {
+        return Err(JsError::module_error(format!(
+            "Unsupported integrity '{}' for '{}': expected a sha256-<base64> hash",
+            integrity, path
+        )));
+    }
+
+    let actual = base64_encode(&sha256(source.as_bytes()));
+    if expected.contains(&actual.as_str()) {
+        return Ok(());
+    }
+    Err(JsError::IntegrityMismatch {
+        path: path.clone(),
+        expected: integrity.to_string(),
+        actual: format!("sha256-{}", actual),
+    })
+}
+
+/// SHA-256 round constants
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// SHA-256 initial hash value
+const H0: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// SHA-256 digest of `data` (FIPS 180-4)
+fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut message = data.to_vec();
+    let bit_len = (data.len() as u64).wrapping_mul(8);
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&bit_len.to_be_bytes());
+
+    let mut state = H0;
+    for block in message.chunks_exact(64) {
+        compress(&mut state, block);
+    }
+
+    let mut digest = [0u8; 32];
+    for (out, word) in digest.chunks_exact_mut(4).zip(state) {
+        out.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+/// Mix one 64-byte block into `state`
+fn compress(state: &mut [u32; 8], block: &[u8]) {
+    let mut schedule = [0u32; 64];
+    for (word, bytes) in schedule.iter_mut().zip(block.chunks_exact(4)) {
+        *word = bytes.try_into().map(u32::from_be_bytes).unwrap_or(0);
+    }
+    for i in 16..64 {
+        let at = |offset: usize| schedule.get(i - offset).copied().unwrap_or(0);
+        let (w15, w2) = (at(15), at(2));
+        let s0 = w15.rotate_right(7) ^ w15.rotate_right(18) ^ (w15 >> 3);
+        let s1 = w2.rotate_right(17) ^ w2.rotate_right(19) ^ (w2 >> 10);
+        let word = at(16).wrapping_add(s0).wrapping_add(at(7)).wrapping_add(s1);
+        if let Some(slot) = schedule.get_mut(i) {
+            *slot = word;
+        }
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for (k, w) in K.iter().zip(schedule) {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(*k)
+            .wrapping_add(w);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+    for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *word = word.wrapping_add(value);
+    }
+}
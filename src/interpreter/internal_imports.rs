@@ -0,0 +1,130 @@
+//! Imports of internal source modules.
+//!
+//! An [`crate::InternalModuleKind::Source`] module is instantiated the first
+//! time something imports it, and may itself import other internal modules
+//! or paths the host provides. Before a program is linked, its imports are
+//! extended with those of the internal source modules it reaches, directly or
+//! through other internal modules, so host-provided dependencies are reported
+//! through `NeedImports` like the program's own and loaded before the
+//! internal module runs.
+//!
+//! Internal modules importing each other in a cycle fail with an error naming
+//! the cycle, both while collecting imports and while instantiating.
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use crate::error::JsError;
+use crate::{ImportRequest, InternalModuleKind, ModulePath};
+
+impl Interpreter {
+    /// `imports` followed by the imports of the internal source modules they
+    /// reach that are not internal modules themselves
+    pub(crate) fn with_internal_module_imports(
+        &mut self,
+        mut imports: Vec<ImportRequest>,
+    ) -> Result<Vec<ImportRequest>, JsError> {
+        let mut chain = Vec::new();
+        let mut visited = FxHashSet::default();
+        let mut found = Vec::new();
+        for req in &imports {
+            self.collect_internal_module_imports(
+                &req.specifier,
+                &mut chain,
+                &mut visited,
+                &mut found,
+            )?;
+        }
+        imports.extend(found);
+        Ok(imports)
+    }
+
+    /// Add the host imports of internal module `specifier` and of the
+    /// internal modules it imports to `found`. Modules already instantiated
+    /// have had their imports loaded and are skipped.
+    fn collect_internal_module_imports(
+        &mut self,
+        specifier: &str,
+        chain: &mut Vec<String>,
+        visited: &mut FxHashSet<String>,
+        found: &mut Vec<ImportRequest>,
+    ) -> Result<(), JsError> {
+        if self.internal_module_cache.contains_key(specifier) || visited.contains(specifier) {
+            return Ok(());
+        }
+        let Some(source) = self.internal_module_source(specifier) else {
+            return Ok(());
+        };
+        if chain.iter().any(|entry| entry == specifier) {
+            return Err(internal_import_cycle(chain, specifier));
+        }
+
+        let path = ModulePath::new(specifier);
+        if !self.internal_module_programs.contains_key(specifier) {
+            let timer = self.start_metrics_timer();
+            let program = self.parse_module_source(Some(&path), &source)?;
+            self.record_module_parse(Some(&path), timer);
+            self.internal_module_programs
+                .insert(specifier.to_string(), program);
+        }
+        let imports = match self.internal_module_programs.get(specifier) {
+            Some(program) => self.collect_import_requests(program, Some(&path))?,
+            None => Vec::new(),
+        };
+
+        chain.push(specifier.to_string());
+        for req in imports {
+            if self.is_internal_module(&req.specifier) {
+                self.collect_internal_module_imports(&req.specifier, chain, visited, found)?;
+            } else {
+                found.push(req);
+            }
+        }
+        chain.pop();
+        visited.insert(specifier.to_string());
+        Ok(())
+    }
+
+    /// Source text of a registered internal source module
+    fn internal_module_source(&self, specifier: &str) -> Option<String> {
+        match &self.internal_modules.get(specifier)?.kind {
+            InternalModuleKind::Source(source) => Some(source.clone()),
+            InternalModuleKind::Native(_) | InternalModuleKind::Lazy { .. } => None,
+        }
+    }
+
+    /// Fail if internal module `specifier` is already being instantiated
+    /// further up the import chain
+    pub(crate) fn check_internal_module_cycle(&self, specifier: &str) -> Result<(), JsError> {
+        if self
+            .internal_modules_loading
+            .iter()
+            .any(|entry| entry == specifier)
+        {
+            return Err(internal_import_cycle(
+                &self.internal_modules_loading,
+                specifier,
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The error for `chain` importing `specifier` again, e.g. `a → b → a`
+fn internal_import_cycle(chain: &[String], specifier: &str) -> JsError {
+    let start = chain
+        .iter()
+        .position(|entry| entry == specifier)
+        .unwrap_or(0);
+    let mut cycle: Vec<&str> = chain
+        .get(start..)
+        .unwrap_or_default()
+        .iter()
+        .map(String::as_str)
+        .collect();
+    cycle.push(specifier);
+    JsError::module_error(format!(
+        "Circular import between internal modules: {}",
+        cycle.join(" → ")
+    ))
+}
@@ -0,0 +1,273 @@
+//! Content-addressed cache for host JSON converted to deeply-frozen objects.
+//!
+//! [`Interpreter::create_from_json_cached`] hashes every array and object in
+//! the input, then reuses a previously built graph for any subtree whose hash
+//! and structure match one already converted. Shared graphs are deeply frozen
+//! so no script can observe the sharing by mutating one of them. Cached
+//! objects stay rooted by the cache's own guard until they are evicted or the
+//! cache is cleared.
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use super::builtins::object::deep_freeze;
+use crate::RuntimeValue;
+use crate::error::JsError;
+use crate::gc::{Gc, Guard, Heap};
+use crate::value::{CheapClone, ExoticObject, JsObject, JsString, JsValue, PropertyKey};
+use core::hash::{Hash, Hasher};
+
+/// A converted subtree and when it was last reused
+struct CacheEntry {
+    object: Gc<JsObject>,
+    last_used: u64,
+}
+
+/// Converted JSON subtrees by structural hash
+pub(crate) struct JsonCache {
+    entries: FxHashMap<u64, Vec<CacheEntry>>,
+    len: usize,
+    /// Most subtrees kept; the least recently used quarter is evicted past this
+    capacity: usize,
+    /// Incremented on every lookup and insert, for LRU order
+    tick: u64,
+    guard: Guard<JsObject>,
+}
+
+impl JsonCache {
+    pub(crate) fn new(heap: &Heap<JsObject>) -> Self {
+        let guard = heap.create_guard();
+        guard.set_label("json cache");
+        Self {
+            entries: FxHashMap::default(),
+            len: 0,
+            capacity: crate::DEFAULT_JSON_CACHE_CAPACITY,
+            tick: 0,
+            guard,
+        }
+    }
+
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+
+    fn lookup(&mut self, hash: u64, json: &serde_json::Value) -> Option<Gc<JsObject>> {
+        self.tick += 1;
+        let entry = self
+            .entries
+            .get_mut(&hash)?
+            .iter_mut()
+            .find(|entry| object_matches(&entry.object, json))?;
+        entry.last_used = self.tick;
+        Some(entry.object.cheap_clone())
+    }
+
+    fn insert(&mut self, hash: u64, object: &Gc<JsObject>) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        self.guard.guard(object.cheap_clone());
+        self.entries.entry(hash).or_default().push(CacheEntry {
+            object: object.cheap_clone(),
+            last_used: self.tick,
+        });
+        self.len += 1;
+        if self.len > self.capacity {
+            self.evict();
+        }
+    }
+
+    /// Drop the least recently used entries until at most three quarters of
+    /// the capacity is used, then re-root the survivors
+    fn evict(&mut self) {
+        if self.len <= self.capacity {
+            return;
+        }
+        let keep = self.capacity - self.capacity / 4;
+        let mut ages: Vec<u64> = self
+            .entries
+            .values()
+            .flatten()
+            .map(|entry| entry.last_used)
+            .collect();
+        ages.sort_unstable();
+        let cutoff = ages.get(self.len - keep).copied().unwrap_or(u64::MAX);
+
+        self.guard.clear();
+        self.len = 0;
+        self.entries.retain(|_, bucket| {
+            bucket.retain(|entry| entry.last_used >= cutoff);
+            !bucket.is_empty()
+        });
+        for entry in self.entries.values().flatten() {
+            self.guard.guard(entry.object.cheap_clone());
+            self.len += 1;
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.guard.clear();
+        self.len = 0;
+    }
+}
+
+impl Interpreter {
+    /// Convert host JSON to a deeply-frozen value, sharing identical subtrees.
+    ///
+    /// Every array and object in `json` is looked up by content in a cache
+    /// kept for the lifetime of the interpreter; a match is reused as is, so
+    /// converting the same document twice allocates nothing the second time
+    /// and documents that differ only in places share everything else. The
+    /// result is deeply frozen (see `Object.deepFreeze`) because it may be
+    /// shared. Use [`crate::api::create_from_json`] for a fresh, mutable copy.
+    pub fn create_from_json_cached(
+        &mut self,
+        json: &serde_json::Value,
+    ) -> Result<RuntimeValue, JsError> {
+        let mut hashes = FxHashMap::default();
+        hash_subtree(json, &mut hashes);
+        let guard = self.heap.create_guard();
+        let value = self.convert_cached(json, &hashes, &guard)?;
+        Ok(RuntimeValue::with_guard(value, guard))
+    }
+
+    /// Drop every cached subtree; values already handed out are unaffected
+    pub fn clear_json_cache(&mut self) {
+        self.json_cache.clear();
+    }
+
+    /// Number of subtrees in the JSON conversion cache
+    pub fn json_cache_len(&self) -> usize {
+        self.json_cache.len
+    }
+
+    fn convert_cached(
+        &mut self,
+        json: &serde_json::Value,
+        hashes: &FxHashMap<usize, u64>,
+        guard: &Guard<JsObject>,
+    ) -> Result<JsValue, JsError> {
+        let hash = match json {
+            serde_json::Value::Null => return Ok(JsValue::Null),
+            serde_json::Value::Bool(b) => return Ok(JsValue::Boolean(*b)),
+            serde_json::Value::Number(n) => return Ok(JsValue::Number(json_number(n))),
+            serde_json::Value::String(s) => return Ok(JsValue::String(JsString::from(s.as_str()))),
+            serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+                hashes.get(&node_id(json)).copied().unwrap_or_default()
+            }
+        };
+
+        if let Some(object) = self.json_cache.lookup(hash, json) {
+            // Rooted here too, as a later insert may evict it from the cache
+            guard.guard(object.cheap_clone());
+            return Ok(JsValue::Object(object));
+        }
+
+        let object = match json {
+            serde_json::Value::Array(items) => {
+                let mut elements = Vec::with_capacity(items.len());
+                for item in items {
+                    elements.push(self.convert_cached(item, hashes, guard)?);
+                }
+                self.create_array_from(guard, elements)
+            }
+            serde_json::Value::Object(map) => {
+                let object = self.create_object(guard);
+                for (key, value) in map {
+                    let value = self.convert_cached(value, hashes, guard)?;
+                    let key = PropertyKey::String(self.intern(key));
+                    object.borrow_mut().set_property(key, value);
+                }
+                object
+            }
+            _ => return Err(JsError::internal_error("JSON cache: expected a container")),
+        };
+        // Children are already deeply frozen, so this only freezes `object`
+        deep_freeze(&JsValue::Object(object.cheap_clone()), true)?;
+        self.json_cache.insert(hash, &object);
+        Ok(JsValue::Object(object))
+    }
+}
+
+/// Identity of a JSON node for the hash table built by [`hash_subtree`]
+fn node_id(json: &serde_json::Value) -> usize {
+    json as *const serde_json::Value as usize
+}
+
+fn json_number(n: &serde_json::Number) -> f64 {
+    n.as_f64().unwrap_or(0.0)
+}
+
+/// Hash `json` by content, recording the hash of every array and object in it
+fn hash_subtree(json: &serde_json::Value, hashes: &mut FxHashMap<usize, u64>) -> u64 {
+    let mut hasher = rustc_hash::FxHasher::default();
+    match json {
+        serde_json::Value::Null => 0u8.hash(&mut hasher),
+        serde_json::Value::Bool(b) => (1u8, b).hash(&mut hasher),
+        serde_json::Value::Number(n) => (2u8, json_number(n).to_bits()).hash(&mut hasher),
+        serde_json::Value::String(s) => (3u8, s.as_str()).hash(&mut hasher),
+        serde_json::Value::Array(items) => {
+            (4u8, items.len()).hash(&mut hasher);
+            for item in items {
+                hash_subtree(item, hashes).hash(&mut hasher);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            (5u8, map.len()).hash(&mut hasher);
+            for (key, value) in map {
+                key.as_str().hash(&mut hasher);
+                hash_subtree(value, hashes).hash(&mut hasher);
+            }
+        }
+    }
+    let hash = hasher.finish();
+    if matches!(
+        json,
+        serde_json::Value::Array(_) | serde_json::Value::Object(_)
+    ) {
+        hashes.insert(node_id(json), hash);
+    }
+    hash
+}
+
+/// Whether a cached object has exactly the structure of `json`, key order included
+fn object_matches(object: &Gc<JsObject>, json: &serde_json::Value) -> bool {
+    let object = object.borrow();
+    match (json, &object.exotic) {
+        (serde_json::Value::Array(items), ExoticObject::Array { elements }) => {
+            items.len() == elements.len()
+                && items
+                    .iter()
+                    .zip(elements)
+                    .all(|(item, element)| value_matches(element, item))
+        }
+        (serde_json::Value::Object(map), ExoticObject::Ordinary) => {
+            map.len() == object.properties.len()
+                && map.iter().zip(object.properties.iter()).all(
+                    |((key, value), (prop_key, prop))| {
+                        matches!(prop_key, PropertyKey::String(s) if s.as_str() == key)
+                            && value_matches(&prop.value, value)
+                    },
+                )
+        }
+        _ => false,
+    }
+}
+
+fn value_matches(value: &JsValue, json: &serde_json::Value) -> bool {
+    match (json, value) {
+        (serde_json::Value::Null, JsValue::Null) => true,
+        (serde_json::Value::Bool(a), JsValue::Boolean(b)) => a == b,
+        (serde_json::Value::Number(a), JsValue::Number(b)) => {
+            json_number(a).to_bits() == b.to_bits()
+        }
+        (serde_json::Value::String(a), JsValue::String(b)) => a.as_str() == b.as_str(),
+        (serde_json::Value::Array(_) | serde_json::Value::Object(_), JsValue::Object(obj)) => {
+            object_matches(obj, json)
+        }
+        _ => false,
+    }
+}
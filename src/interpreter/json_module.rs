@@ -0,0 +1,95 @@
+//! JSON modules: `import config from "./config.json" with { type: "json" }`.
+//!
+//! The host provides a JSON module's text like any other source, through
+//! [`Interpreter::provide_module`] or the module source map. An import
+//! carrying the `type: "json"` attribute has that text parsed as JSON rather
+//! than TypeScript, and the module's only export is `default`: the parsed
+//! value, deeply frozen so every importer sees the same immutable data.
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use super::builtins::json::{json_to_js_value_with_guard, parse_json_text};
+use super::builtins::object::deep_freeze;
+use crate::error::JsError;
+use crate::value::PropertyKey;
+use crate::{ImportRequest, ModulePath};
+
+impl ImportRequest {
+    /// Whether the import carries a `type: "json"` attribute
+    pub fn is_json(&self) -> bool {
+        self.attributes
+            .iter()
+            .any(|(key, value)| key == "type" && value == "json")
+    }
+}
+
+impl Interpreter {
+    /// Load the JSON module `path` requested at `requested_imports[pos]`
+    pub(crate) fn provide_json_module(
+        &mut self,
+        pos: usize,
+        path: ModulePath,
+        source: &str,
+    ) -> Result<(), JsError> {
+        match self.load_json_module(&path, source) {
+            Ok(()) => {
+                self.requested_imports.remove(pos);
+                Ok(())
+            }
+            Err(err) if self.module_error_policy == crate::ModuleErrorPolicy::Collect => {
+                self.requested_imports.remove(pos);
+                self.load_failed_module(path, err);
+                Ok(())
+            }
+            Err(err) => {
+                self.note_failed_import(&path);
+                Err(err)
+            }
+        }
+    }
+
+    /// Load `source` as the JSON module `path`, whose namespace has the
+    /// deeply-frozen parsed value as its `default` export
+    pub(crate) fn load_json_module(
+        &mut self,
+        path: &ModulePath,
+        source: &str,
+    ) -> Result<(), JsError> {
+        if let Some(hook) = &self.module_hooks.on_module_start {
+            hook(path);
+        }
+        let timer = self
+            .module_hooks
+            .on_module_loaded
+            .is_some()
+            .then(|| self.time_provider.start_timer());
+
+        let json = parse_json_text(source).map_err(|e| {
+            JsError::module_error(format!("Module '{}' is not valid JSON: {}", path, e))
+        })?;
+        let guard = self.heap.create_guard();
+        let value = json_to_js_value_with_guard(self, &json, &guard)?;
+        deep_freeze(&value, false)?;
+
+        let namespace = self.create_object(&guard);
+        let default_key = PropertyKey::String(self.intern("default"));
+        namespace.borrow_mut().set_property(default_key, value);
+        self.seal_module_namespace(&namespace);
+        self.module_guard.guard(namespace.clone());
+        self.loaded_modules.insert(path.clone(), namespace);
+
+        if let (Some(hook), Some(start)) = (&self.module_hooks.on_module_loaded, timer) {
+            hook(path, self.time_provider.elapsed_millis(start));
+        }
+        Ok(())
+    }
+}
+
+/// The error for a JSON-typed import of `path` provided as parsed code
+pub(crate) fn json_provided_as_code(path: &ModulePath) -> JsError {
+    JsError::module_error(format!(
+        "Module '{}' is imported with {{ type: \"json\" }}; provide its JSON text, not code",
+        path
+    ))
+}
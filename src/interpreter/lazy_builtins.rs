@@ -0,0 +1,199 @@
+//! Builtins installed on first use.
+//!
+//! A typical script touches a handful of builtins, so `Interpreter::new`
+//! leaves the ones scripts can only reach through their global name, such as
+//! `Map`, `Date` or `Math`, to be built the first time a script names them.
+//! Their global properties are reserved with `undefined` at startup, keeping
+//! the global object's key order, and anything that hands out the global
+//! object itself (`globalThis`, a top-level `this`) installs all of them
+//! first, so scripts can't tell the difference.
+
+use super::{Interpreter, builtins};
+use crate::gc::Gc;
+use crate::prelude::*;
+use crate::value::{CheapClone, JsString, JsValue, PropertyKey};
+
+/// A group of globals one initializer installs
+struct LazyBuiltin {
+    /// The global properties it defines, in the order it defines them
+    names: &'static [&'static str],
+    init: fn(&mut Interpreter),
+}
+
+const LAZY_BUILTINS: &[LazyBuiltin] = &[
+    LazyBuiltin {
+        names: &["Math"],
+        init: |interp| {
+            builtins::init_math(interp);
+        },
+    },
+    LazyBuiltin {
+        names: &["JSON"],
+        init: builtins::init_json,
+    },
+    #[cfg(feature = "yaml")]
+    LazyBuiltin {
+        names: &["YAML"],
+        init: builtins::yaml::init_yaml,
+    },
+    #[cfg(feature = "console")]
+    LazyBuiltin {
+        names: &["console"],
+        init: builtins::console::init_console,
+    },
+    LazyBuiltin {
+        names: &["Map"],
+        init: builtins::init_map,
+    },
+    LazyBuiltin {
+        names: &["Set"],
+        init: builtins::init_set,
+    },
+    LazyBuiltin {
+        names: &["URL", "URLSearchParams"],
+        init: builtins::init_url,
+    },
+    LazyBuiltin {
+        names: &["WeakRef", "FinalizationRegistry"],
+        init: builtins::init_weakref,
+    },
+    LazyBuiltin {
+        names: &["DisposableStack", "AsyncDisposableStack"],
+        init: builtins::init_disposable,
+    },
+    LazyBuiltin {
+        names: &["performance"],
+        init: builtins::performance::init_performance,
+    },
+    LazyBuiltin {
+        names: &["Date"],
+        init: builtins::init_date,
+    },
+    LazyBuiltin {
+        names: &["Proxy", "Reflect"],
+        init: builtins::proxy::init_proxy,
+    },
+];
+
+fn group_of(name: &str) -> Option<usize> {
+    LAZY_BUILTINS
+        .iter()
+        .position(|builtin| builtin.names.contains(&name))
+}
+
+impl Interpreter {
+    /// Reserve the global properties of the builtin group defining `name`,
+    /// to be built by `install_builtin`
+    pub(crate) fn defer_builtin(&mut self, name: &str) {
+        let Some(index) = group_of(name) else {
+            return;
+        };
+        for name in LAZY_BUILTINS.get(index).map_or(&[][..], |b| b.names) {
+            let key = PropertyKey::String(self.intern(name));
+            self.global
+                .borrow_mut()
+                .set_property(key, JsValue::Undefined);
+        }
+        self.pending_builtins |= 1 << index;
+    }
+
+    /// Build the builtin named `name` if it hasn't been yet
+    pub(crate) fn install_builtin(&mut self, name: &str) {
+        if self.pending_builtins == 0 {
+            return;
+        }
+        if let Some(index) = group_of(name) {
+            self.install_builtin_group(index);
+        }
+    }
+
+    /// Build every builtin not built yet. Scripts can't tell which builtins
+    /// were built lazily, but a host reading them straight off
+    /// [`Interpreter::global`] should call this first.
+    pub fn materialize_builtins(&mut self) {
+        for index in 0..LAZY_BUILTINS.len() {
+            self.install_builtin_group(index);
+        }
+    }
+
+    /// Build the builtins another interpreter, with `pending` still to
+    /// build, has built, so the two walk the same builtins
+    pub(crate) fn match_builtins(&mut self, pending: u32) {
+        for index in 0..LAZY_BUILTINS.len() {
+            if pending & (1 << index) == 0 {
+                self.install_builtin_group(index);
+            }
+        }
+    }
+
+    /// Read global property `name` for a script, building it first if it is
+    /// a builtin not built yet. Handing out the global object itself builds
+    /// them all.
+    pub(crate) fn read_global(&mut self, name: &JsString) -> Option<JsValue> {
+        let key = PropertyKey::String(name.cheap_clone());
+        let mut value = self.global.borrow().get_property(&key);
+        if self.pending_builtins == 0 {
+            return value;
+        }
+        match &value {
+            Some(JsValue::Undefined) => {
+                self.install_builtin(name.as_str());
+                value = self.global.borrow().get_property(&key);
+            }
+            Some(JsValue::Object(obj)) if Gc::ptr_eq(obj, &self.global) => {
+                self.materialize_builtins()
+            }
+            _ => {}
+        }
+        value
+    }
+
+    /// `value` as handed to a script, building all builtins first if it is
+    /// the global object
+    pub(crate) fn expose_global(&mut self, value: &JsValue) {
+        if self.pending_builtins != 0
+            && let JsValue::Object(obj) = value
+            && Gc::ptr_eq(obj, &self.global)
+        {
+            self.materialize_builtins();
+        }
+    }
+
+    fn install_builtin_group(&mut self, index: usize) {
+        let bit = 1 << index;
+        if self.pending_builtins & bit == 0 {
+            return;
+        }
+        self.pending_builtins &= !bit;
+        let Some(builtin) = LAZY_BUILTINS.get(index) else {
+            return;
+        };
+
+        // Keep what a host stored under a reserved name meanwhile
+        let keys: Vec<PropertyKey> = builtin
+            .names
+            .iter()
+            .map(|name| PropertyKey::String(self.intern(name)))
+            .collect();
+        let stored: Vec<Option<JsValue>> = keys
+            .iter()
+            .map(|key| {
+                self.global
+                    .borrow()
+                    .get_own_property(key)
+                    .map(|prop| prop.value.clone())
+                    .filter(|value| !value.is_undefined())
+            })
+            .collect();
+
+        (builtin.init)(self);
+        // A reset puts back the builtin, not the placeholder it replaced
+        self.update_global_baseline(&keys);
+
+        for (key, value) in keys.into_iter().zip(stored) {
+            if let Some(value) = value {
+                self.global.borrow_mut().set_property(key, value);
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! Host JSON arrays converted one element at a time.
+//!
+//! [`Interpreter::create_lazy_json_array`] wraps a host iterator of JSON
+//! values in a host object that is its own iterator: each `next()` converts
+//! the next element, and nothing else keeps it, so an element the script
+//! drops is collectable right away. The array is single-pass, like a
+//! generator. Reading `length` or an index throws a `TypeError` instead of
+//! converting everything up to it; scripts that need random access can
+//! collect the elements with `Array.from` or `.toArray()` first.
+
+use crate::RuntimeValue;
+use crate::error::JsError;
+use crate::gc::Gc;
+use crate::interpreter::Interpreter;
+use crate::interpreter::builtins::json::json_to_js_value_with_guard;
+use crate::interpreter::host_class::{HostClassDef, HostClassId};
+use crate::prelude::*;
+use crate::value::{ExoticObject, Guarded, JsObject, JsSymbol, JsValue, PropertyKey};
+
+/// Host data of a lazy JSON array: the elements not yet converted
+struct LazyJsonSource {
+    elements: RefCell<Box<dyn Iterator<Item = serde_json::Value>>>,
+}
+
+/// Class and shared prototype of lazy JSON arrays, created on first use
+#[derive(Clone)]
+pub(crate) struct LazyJsonArrayClass {
+    class: HostClassId,
+    prototype: Gc<JsObject>,
+}
+
+impl Interpreter {
+    /// Create an iterable that converts `elements` to script values on
+    /// demand, one per `next()` call.
+    ///
+    /// Peak heap stays proportional to what the script keeps rather than to
+    /// the whole array. The result is single-pass; `length` and index reads
+    /// throw a `TypeError`.
+    pub fn create_lazy_json_array<I>(&mut self, elements: I) -> Result<RuntimeValue, JsError>
+    where
+        I: IntoIterator<Item = serde_json::Value>,
+        I::IntoIter: 'static,
+    {
+        let LazyJsonArrayClass { class, prototype } = self.lazy_json_array_class();
+        let array = self.create_host_object(
+            class,
+            Box::new(LazyJsonSource {
+                elements: RefCell::new(Box::new(elements.into_iter())),
+            }),
+        )?;
+        if let JsValue::Object(obj) = array.value() {
+            obj.borrow_mut().prototype = Some(prototype);
+        }
+        Ok(array)
+    }
+
+    fn lazy_json_array_class(&mut self) -> LazyJsonArrayClass {
+        if let Some(class) = &self.lazy_json_array_class {
+            return class.clone();
+        }
+        let class = self.register_host_class(HostClassDef {
+            name: "LazyJsonArray".to_string(),
+            get: Some(lazy_json_get),
+            own_keys: Some(|_, _| Ok(Vec::new())),
+            ..Default::default()
+        });
+
+        let prototype = self.root_guard.alloc();
+        prototype.borrow_mut().prototype = Some(self.iterator_prototype.clone());
+        self.register_method(&prototype, "next", lazy_json_next, 0);
+        let iterator_key = PropertyKey::Symbol(Box::new(JsSymbol::new(
+            self.well_known_symbols.iterator,
+            Some(self.intern("Symbol.iterator")),
+        )));
+        let self_fn = self.create_native_function("[Symbol.iterator]", lazy_json_self, 0);
+        prototype
+            .borrow_mut()
+            .set_property(iterator_key, JsValue::Object(self_fn));
+
+        let class = LazyJsonArrayClass { class, prototype };
+        self.lazy_json_array_class = Some(class.clone());
+        class
+    }
+}
+
+/// `length` and index reads, which would force conversion, throw
+fn lazy_json_get(
+    _interp: &mut Interpreter,
+    _data: &dyn core::any::Any,
+    key: &PropertyKey,
+) -> Result<Option<Guarded>, JsError> {
+    let random_access = match key {
+        PropertyKey::Index(_) => true,
+        PropertyKey::String(s) => s.as_str() == "length",
+        PropertyKey::Symbol(_) => false,
+    };
+    if random_access {
+        return Err(JsError::type_error(format!(
+            "Cannot read '{}' of a lazy JSON array; it can only be iterated",
+            key
+        )));
+    }
+    Ok(None)
+}
+
+/// A lazy JSON array is its own iterator
+fn lazy_json_self(
+    _interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    Ok(Guarded::unguarded(this))
+}
+
+/// Convert the next element; the result object's guard keeps it alive
+fn lazy_json_next(
+    interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let data = match &this {
+        JsValue::Object(obj) => match &obj.borrow().exotic {
+            ExoticObject::Host(host) => Some(host.data.clone()),
+            _ => None,
+        },
+        _ => None,
+    };
+    let Some(source) = data
+        .as_ref()
+        .and_then(|data| data.downcast_ref::<LazyJsonSource>())
+    else {
+        return Err(JsError::type_error(
+            "next called on an object that is not a lazy JSON array",
+        ));
+    };
+    // Taken before converting so no borrow is held while allocating
+    let element = source.elements.borrow_mut().next();
+
+    let guard = interp.heap.create_guard();
+    let (value, done) = match element {
+        Some(json) => (json_to_js_value_with_guard(interp, &json, &guard)?, false),
+        None => (JsValue::Undefined, true),
+    };
+    let result = interp.create_object(&guard);
+    let value_key = interp.property_key("value");
+    let done_key = interp.property_key("done");
+    {
+        let mut result_ref = result.borrow_mut();
+        result_ref.set_property(value_key, value);
+        result_ref.set_property(done_key, JsValue::Boolean(done));
+    }
+    Ok(Guarded::with_guard(JsValue::Object(result), guard))
+}
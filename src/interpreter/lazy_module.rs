@@ -0,0 +1,198 @@
+//! Namespaces of [`crate::InternalModuleKind::Lazy`] modules.
+//!
+//! A lazy module's namespace is a host object whose `get` hook calls the
+//! module's resolver the first time an export is read and keeps the value
+//! for later reads. Its `own_keys` hook lists the module's `own_keys`
+//! without resolving anything, so `import * as ns` and `Object.keys(ns)`
+//! stay cheap however large the module is.
+//!
+//! Import bindings read exports through [`Interpreter::read_module_export`],
+//! which reaches the resolver the same way a property read does.
+
+use core::any::Any;
+use core::cell::RefCell;
+
+use super::Interpreter;
+use super::host_class::{self, HostClassDef};
+use crate::error::JsError;
+use crate::gc::{Gc, Guard};
+use crate::prelude::*;
+use crate::value::{CheapClone, ExoticObject, Guarded, JsFunction, JsObject, JsValue, PropertyKey};
+use crate::{LazyExportFn, LazyKeysFn};
+
+/// Host data of a lazy module's namespace
+struct LazyNamespace {
+    resolver: Rc<LazyExportFn>,
+    own_keys: Option<Rc<LazyKeysFn>>,
+    /// Exports resolved so far, in the order they were first read
+    resolved: RefCell<Vec<(String, JsValue)>>,
+    /// Whether the namespace is kept across `Interpreter::reset`
+    retained: bool,
+}
+
+impl LazyNamespace {
+    fn cached(&self, name: &str) -> Option<JsValue> {
+        self.resolved
+            .borrow()
+            .iter()
+            .find(|(export, _)| export == name)
+            .map(|(_, value)| value.clone())
+    }
+}
+
+/// The export name `key` reads, if it can name one
+fn export_name(key: &PropertyKey) -> Option<String> {
+    match key {
+        PropertyKey::Symbol(_) => None,
+        other => Some(other.to_string()),
+    }
+}
+
+fn lazy_get(
+    interp: &mut Interpreter,
+    data: &dyn Any,
+    key: &PropertyKey,
+) -> Result<Option<Guarded>, JsError> {
+    let (Some(namespace), Some(name)) = (data.downcast_ref::<LazyNamespace>(), export_name(key))
+    else {
+        return Ok(None);
+    };
+    if let Some(value) = namespace.cached(&name) {
+        return Ok(Some(Guarded::unguarded(value)));
+    }
+    let resolver = Rc::clone(&namespace.resolver);
+    let Some(resolved) = resolver(interp, &name)? else {
+        return Ok(None);
+    };
+    // The values the namespace hands out live as long as it does
+    if let JsValue::Object(obj) = &resolved.value {
+        if namespace.retained {
+            interp.retained_module_guard.guard(obj.cheap_clone());
+        } else {
+            interp.module_guard.guard(obj.cheap_clone());
+        }
+    }
+    namespace
+        .resolved
+        .borrow_mut()
+        .push((name, resolved.value.clone()));
+    Ok(Some(resolved))
+}
+
+fn lazy_has(interp: &mut Interpreter, data: &dyn Any, key: &PropertyKey) -> Result<bool, JsError> {
+    let (Some(namespace), Some(name)) = (data.downcast_ref::<LazyNamespace>(), export_name(key))
+    else {
+        return Ok(false);
+    };
+    if namespace.cached(&name).is_some() {
+        return Ok(true);
+    }
+    match &namespace.own_keys {
+        Some(own_keys) => Ok(own_keys().contains(&name)),
+        None => Ok(lazy_get(interp, data, key)?.is_some()),
+    }
+}
+
+fn lazy_own_keys(interp: &mut Interpreter, data: &dyn Any) -> Result<Vec<PropertyKey>, JsError> {
+    let Some(namespace) = data.downcast_ref::<LazyNamespace>() else {
+        return Ok(Vec::new());
+    };
+    let names = match &namespace.own_keys {
+        Some(own_keys) => own_keys(),
+        None => namespace
+            .resolved
+            .borrow()
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect(),
+    };
+    Ok(names.iter().map(|name| interp.property_key(name)).collect())
+}
+
+impl Interpreter {
+    /// Create the namespace object of a lazy module; `retained` namespaces
+    /// root what they resolve past `reset`
+    pub(crate) fn create_lazy_module_object(
+        &mut self,
+        guard: &Guard<JsObject>,
+        resolver: Rc<LazyExportFn>,
+        own_keys: Option<Rc<LazyKeysFn>>,
+        retained: bool,
+    ) -> Result<Gc<JsObject>, JsError> {
+        let class = match self.lazy_module_class {
+            Some(class) => class,
+            None => {
+                let class = self.register_host_class(HostClassDef {
+                    name: "Module".to_string(),
+                    get: Some(lazy_get),
+                    has: Some(lazy_has),
+                    own_keys: Some(lazy_own_keys),
+                    ..Default::default()
+                });
+                self.lazy_module_class = Some(class);
+                class
+            }
+        };
+        let namespace = LazyNamespace {
+            resolver,
+            own_keys,
+            resolved: RefCell::new(Vec::new()),
+            retained,
+        };
+        let created = self.create_host_object(class, Box::new(namespace))?;
+        let JsValue::Object(module_obj) = created.value() else {
+            return Err(JsError::internal_error("host object is not an object"));
+        };
+        guard.guard(module_obj.cheap_clone());
+        Ok(module_obj.cheap_clone())
+    }
+
+    /// Read export `key` of a module namespace for an import binding or a
+    /// re-export, resolving it if it comes from a lazy module
+    pub(crate) fn read_module_export(
+        &mut self,
+        module_obj: &Gc<JsObject>,
+        key: &PropertyKey,
+    ) -> Result<JsValue, JsError> {
+        let (module_obj, key) = self.export_origin(module_obj, key);
+        if self.is_lazy_namespace(&module_obj) {
+            let receiver = JsValue::Object(module_obj.cheap_clone());
+            return Ok(host_class::host_get(self, &module_obj, &key, receiver)?.value);
+        }
+        self.resolve_module_property(&module_obj, &key)
+    }
+
+    /// The module and key a chain of re-exports of `key` ends at
+    fn export_origin(
+        &self,
+        module_obj: &Gc<JsObject>,
+        key: &PropertyKey,
+    ) -> (Gc<JsObject>, PropertyKey) {
+        let mut module_obj = module_obj.cheap_clone();
+        let mut key = key.clone();
+        loop {
+            let getter = match module_obj.borrow().get_own_property(&key) {
+                Some(prop) if prop.is_accessor() => prop.getter().cloned(),
+                _ => None,
+            };
+            let Some(getter) = getter else {
+                return (module_obj, key);
+            };
+            let next = match &getter.borrow().exotic {
+                ExoticObject::Function(JsFunction::ModuleReExportGetter {
+                    source_module,
+                    source_key,
+                }) => (source_module.cheap_clone(), source_key.clone()),
+                _ => return (module_obj, key),
+            };
+            (module_obj, key) = next;
+        }
+    }
+
+    fn is_lazy_namespace(&self, obj: &Gc<JsObject>) -> bool {
+        match &obj.borrow().exotic {
+            ExoticObject::Host(data) => Some(data.class) == self.lazy_module_class,
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Per-module parse and compile metrics.
+//!
+//! Every module the interpreter parses and compiles (the entry program,
+//! modules provided by the host and internal source modules) gets a
+//! [`ModuleMetrics`] entry, timed with the configured `TimeProvider` so the
+//! numbers are available in WASM embeddings too.
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use crate::ModulePath;
+use crate::compiler::{BytecodeChunk, Constant};
+
+/// Label for an entry program prepared without a path
+pub(crate) const UNNAMED_MAIN: &str = "<main>";
+
+/// What parsing and compiling one module cost
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleMetrics {
+    /// Normalized module path, internal module specifier, or `<main>` for an
+    /// entry program without a path
+    pub path: String,
+    /// Milliseconds spent parsing the source
+    pub parse_ms: u64,
+    /// Milliseconds spent compiling the parsed module to bytecode
+    pub compile_ms: u64,
+    /// Instructions in the module's bytecode, nested functions included
+    pub instruction_count: usize,
+    /// Constant pool entries of the module and its nested functions
+    pub constant_count: usize,
+    /// Function chunks nested in the module
+    pub function_count: usize,
+}
+
+impl ModuleMetrics {
+    /// Parse plus compile time
+    pub fn total_ms(&self) -> u64 {
+        self.parse_ms + self.compile_ms
+    }
+
+    /// One line per module, slowest first
+    pub fn summary(metrics: &[ModuleMetrics]) -> String {
+        let mut sorted: Vec<&ModuleMetrics> = metrics.iter().collect();
+        sorted.sort_by(|a, b| {
+            b.total_ms()
+                .cmp(&a.total_ms())
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        let mut out = String::new();
+        for m in sorted {
+            out.push_str(&format!(
+                "{}: {}ms (parse {}ms, compile {}ms), {} instructions, {} constants, {} functions\n",
+                m.path,
+                m.total_ms(),
+                m.parse_ms,
+                m.compile_ms,
+                m.instruction_count,
+                m.constant_count,
+                m.function_count
+            ));
+        }
+        out
+    }
+
+    /// Add the sizes of `chunk` and the function chunks nested in it
+    fn count(&mut self, chunk: &BytecodeChunk) {
+        self.instruction_count += chunk.code.len();
+        self.constant_count += chunk.constants.len();
+        for constant in &chunk.constants {
+            if let Constant::Chunk(child) = constant {
+                self.function_count += 1;
+                self.count(child);
+            }
+        }
+    }
+}
+
+fn metrics_path(path: Option<&ModulePath>) -> String {
+    path.map_or_else(|| UNNAMED_MAIN.to_string(), |p| p.as_str().to_string())
+}
+
+impl Interpreter {
+    /// Parse and compile metrics of every module so far, in the order they
+    /// were parsed.
+    ///
+    /// Kept until [`Interpreter::reset_module_metrics`]; see
+    /// [`ModuleMetrics::summary`] for a printable breakdown.
+    pub fn module_metrics(&self) -> &[ModuleMetrics] {
+        &self.module_metrics
+    }
+
+    /// Forget the metrics collected so far
+    pub fn reset_module_metrics(&mut self) {
+        self.module_metrics.clear();
+    }
+
+    /// Start timing a parse or compile
+    pub(crate) fn start_metrics_timer(&self) -> u64 {
+        self.time_provider.start_timer()
+    }
+
+    /// Record that the module at `path` was parsed, timed from `start`
+    pub(crate) fn record_module_parse(&mut self, path: Option<&ModulePath>, start: u64) {
+        let parse_ms = self.time_provider.elapsed_millis(start);
+        self.record_parse_ms(path, parse_ms);
+    }
+
+    /// Record a parse that was timed elsewhere, such as across the calls of
+    /// an incremental prepare
+    pub(crate) fn record_parse_ms(&mut self, path: Option<&ModulePath>, parse_ms: u64) {
+        self.module_metrics.push(ModuleMetrics {
+            path: metrics_path(path),
+            parse_ms,
+            compile_ms: 0,
+            instruction_count: 0,
+            constant_count: 0,
+            function_count: 0,
+        });
+    }
+
+    /// Record that the module at `path` was compiled to `chunk`, timed from `start`
+    pub(crate) fn record_module_compile(
+        &mut self,
+        path: Option<&ModulePath>,
+        start: u64,
+        chunk: &BytecodeChunk,
+    ) {
+        let compile_ms = self.time_provider.elapsed_millis(start);
+        let path = metrics_path(path);
+        // The latest parse of the module, unless it was already compiled
+        let index = self
+            .module_metrics
+            .iter()
+            .rposition(|m| m.path == path && m.instruction_count == 0);
+        let entry = match index.and_then(|i| self.module_metrics.get_mut(i)) {
+            Some(entry) => entry,
+            None => {
+                self.record_parse_ms(Some(&ModulePath::new(path)), 0);
+                let Some(entry) = self.module_metrics.last_mut() else {
+                    return;
+                };
+                entry
+            }
+        };
+        entry.compile_ms += compile_ms;
+        entry.count(chunk);
+    }
+}
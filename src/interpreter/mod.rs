@@ -8,6 +8,76 @@ pub mod builtins;
 // Bytecode virtual machine
 pub mod bytecode_vm;
 
+// Recycled per-call storage
+mod call_pool;
+// Locale-independent comparison for localeCompare
+pub(crate) mod collation;
+// Sharing of identical compiled code between attached chunks
+pub(crate) mod chunk_cache;
+// Chunked, budgeted prepare of large sources
+mod incremental;
+// SRI checks of module sources provided by the host
+mod integrity;
+// Dependencies and import cycles of internal source modules
+mod internal_imports;
+// Host view of an order-suspended run
+pub mod inspect;
+// Shared, deeply-frozen conversions of host JSON
+mod json_cache;
+// Modules imported `with { type: "json" }`
+mod json_module;
+// Builtins built the first time a script names them
+mod lazy_builtins;
+// Host JSON arrays converted as they are iterated
+mod lazy_json;
+// Internal modules whose exports Rust resolves on first access
+pub(crate) mod lazy_module;
+// Side-effect detection for eval_pure
+mod purity;
+// Watch expressions sampled at suspensions
+pub(crate) mod probes;
+// Deep copies of values between interpreters
+mod transfer;
+// Serialized snapshots of order-suspended runs
+pub(crate) mod checkpoint;
+// Parse and compile metrics per module
+pub(crate) mod metrics;
+// Imports served from in-memory module sources
+pub(crate) mod module_map;
+// Running provided modules in dependency order
+mod module_graph;
+// Promises left pending when a script stops
+pub(crate) mod pending_promises;
+// Prepared runs written out and set up in another interpreter
+pub(crate) mod prepared;
+// Creation sites of objects for Interpreter::explain
+pub(crate) mod provenance;
+// Recent VM activity attached to script errors
+pub(crate) mod flight_recorder;
+pub(crate) mod shared_modules;
+// Caller-supplied memory budget
+pub(crate) mod heap_budget;
+// Host-defined exotic objects with Rust hooks
+pub(crate) mod host_class;
+// Running a script to completion against a Host implementation
+pub(crate) mod host_driver;
+// Functions backed by Rust closures
+mod host_function;
+// Did-you-mean suggestions for misspelled names
+pub(crate) mod suggest;
+// Hosts stepping a script that can no longer make progress
+pub(crate) mod stall;
+// Recorded order traffic and its replay
+pub(crate) mod order_log;
+// Isolated snippet evaluation for evalInSandbox
+pub(crate) mod sandbox;
+// Exception-safe entry into environments
+pub(crate) mod env_scope;
+// Hosts pulling values from script generators
+pub(crate) mod generator_drive;
+// Reusing an interpreter for unrelated runs
+mod reset;
+
 use crate::prelude::*;
 
 // Platform provider imports based on target/features
@@ -27,17 +97,20 @@ use crate::platform::RegExpProvider;
 use crate::StepResult;
 use crate::ast::{ImportSpecifier, Program, Statement};
 use crate::error::JsError;
-use crate::gc::{Gc, Guard, Heap};
+use crate::gc::{
+    CollectOutcome, Gc, Guard, Heap, IDLE_COLLECT_GARBAGE_RATIO, IDLE_COLLECT_MIN_ALLOCS,
+};
 use crate::parser::Parser;
 use crate::string_dict::StringDict;
 use crate::value::{
-    Binding, BytecodeFunction, BytecodeGeneratorState, CheapClone, EnvRef, EnvironmentData,
-    ExoticObject, GeneratorStatus, Guarded, ImportBinding, JsFunction, JsObject, JsString,
-    JsSymbol, JsValue, ModuleExport, NativeFn, NativeFunction, PromiseStatus, Property,
+    AsyncGeneratorAwait, Binding, BytecodeFunction, BytecodeGeneratorState, CheapClone, EnvRef,
+    EnvironmentData, ExoticObject, GeneratorStatus, Guarded, ImportBinding, JsFunction, JsObject,
+    JsString, JsSymbol, JsValue, ModuleExport, NativeFn, NativeFunction, PromiseStatus, Property,
     PropertyKey, VarKey, create_environment_unrooted, create_environment_unrooted_with_capacity,
 };
 
 use self::builtins::symbol::WellKnownSymbols;
+use self::env_scope::EnvScope;
 
 // Re-export Guarded from value module - see value.rs for documentation
 
@@ -50,6 +123,18 @@ pub struct StackFrame {
     pub location: Option<(u32, u32)>, // (line, column)
 }
 
+/// One level of native recursion counted by [`Interpreter::call_depth`],
+/// released when dropped so early returns cannot leak it
+pub(crate) struct NativeDepthGuard {
+    depth: Rc<Cell<usize>>,
+}
+
+impl Drop for NativeDepthGuard {
+    fn drop(&mut self) {
+        self.depth.set(self.depth.get().saturating_sub(1));
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // Async Context Management Types
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -74,6 +159,42 @@ pub struct SuspendedContext {
     pub waiting_on_id: PromiseId,
     /// Register to store the resolved value when resumed
     pub resume_register: crate::compiler::Register,
+    /// Set for an async call detached from its caller, `None` for the main
+    /// execution
+    pub task: Option<AsyncTask>,
+}
+
+/// An async call set aside while it waits, so that its caller and other
+/// work can run on
+pub struct AsyncTask {
+    /// Promise for the call's result, settled when its body finishes
+    pub completion: Gc<JsObject>,
+    /// Environment the body was running in
+    pub env: Gc<JsObject>,
+    /// Environment of the async function's own frame, which nothing else
+    /// roots while the task runs
+    pub frame_env: Gc<JsObject>,
+    /// The call's entries on the interpreter's call stack
+    pub calls: Vec<StackFrame>,
+    /// Roots the completion promise, the environments and the awaited promise
+    #[allow(dead_code)]
+    guard: Guard<JsObject>,
+}
+
+/// A detached async call resumed as the active VM
+struct ActiveTask {
+    completion: Gc<JsObject>,
+    /// Roots the completion promise while the task runs
+    #[allow(dead_code)]
+    guard: Guard<JsObject>,
+    /// Environment of the async function's own frame
+    frame_env: Gc<JsObject>,
+    /// Environment to restore when the task suspends again or finishes
+    outer_env: Gc<JsObject>,
+    /// Depth of the environment guard stack below the task's entries
+    env_depth: usize,
+    /// Depth of the call stack below the task's entries
+    call_depth: usize,
 }
 
 /// Tracks all suspended async contexts and their Promise dependencies
@@ -148,10 +269,25 @@ pub struct Interpreter {
     /// Root guard for permanent objects (prototypes, global, global_env)
     root_guard: Guard<JsObject>,
 
+    /// Roots every loaded module's namespace and environment until `reset`
+    module_guard: Guard<JsObject>,
+
+    /// Roots namespaces and environments of internal modules kept across `reset`
+    retained_module_guard: Guard<JsObject>,
+
+    /// Whether an internal module kept across `reset` is being created
+    instantiating_retained: bool,
+
+    /// Subtrees converted by `create_from_json_cached`, rooted by their own guard
+    json_cache: json_cache::JsonCache,
+
     // ═══════════════════════════════════════════════════════════════════════════
     // Global State
     // ═══════════════════════════════════════════════════════════════════════════
-    /// Global object
+    /// Global object.
+    ///
+    /// Some builtins are only built when a script first names them; call
+    /// [`Interpreter::materialize_builtins`] before reading them from here.
     pub global: Gc<JsObject>,
 
     /// Global environment (variable bindings for global scope)
@@ -207,9 +343,33 @@ pub struct Interpreter {
     /// Promise.prototype (for Promise methods)
     pub promise_prototype: Gc<JsObject>,
 
+    /// Iterator.prototype (iterator helpers; inherited by generators and built-in iterators)
+    pub iterator_prototype: Gc<JsObject>,
+
+    /// %IteratorHelperPrototype% (next/return for objects returned by map, filter, etc.)
+    pub iterator_helper_prototype: Gc<JsObject>,
+
     /// Generator.prototype (for generator methods)
     pub generator_prototype: Gc<JsObject>,
 
+    /// URL.prototype (for URL accessors and methods)
+    pub url_prototype: Gc<JsObject>,
+
+    /// URLSearchParams.prototype (for URLSearchParams methods)
+    pub url_search_params_prototype: Gc<JsObject>,
+
+    /// WeakRef.prototype (for deref)
+    pub weakref_prototype: Gc<JsObject>,
+
+    /// FinalizationRegistry.prototype (for register and unregister)
+    pub finalization_registry_prototype: Gc<JsObject>,
+
+    /// DisposableStack.prototype (for use, adopt, defer, move and dispose)
+    pub disposable_stack_prototype: Gc<JsObject>,
+
+    /// AsyncDisposableStack.prototype (for use, adopt, defer, move and disposeAsync)
+    pub async_disposable_stack_prototype: Gc<JsObject>,
+
     // ═══════════════════════════════════════════════════════════════════════════
     // Error Prototypes (for creating proper error objects from JsError)
     // ═══════════════════════════════════════════════════════════════════════════
@@ -228,6 +388,18 @@ pub struct Interpreter {
     /// SyntaxError.prototype
     pub syntax_error_prototype: Gc<JsObject>,
 
+    /// EvalError.prototype
+    pub eval_error_prototype: Gc<JsObject>,
+
+    /// URIError.prototype
+    pub uri_error_prototype: Gc<JsObject>,
+
+    /// AggregateError.prototype
+    pub aggregate_error_prototype: Gc<JsObject>,
+
+    /// SuppressedError.prototype
+    pub suppressed_error_prototype: Gc<JsObject>,
+
     // ═══════════════════════════════════════════════════════════════════════════
     // Execution State
     // ═══════════════════════════════════════════════════════════════════════════
@@ -238,14 +410,20 @@ pub struct Interpreter {
     /// Call stack for stack traces
     pub call_stack: Vec<StackFrame>,
 
+    /// Register files, argument vectors, guards and environments recycled across calls
+    pub(crate) call_pool: call_pool::CallPool,
+
     /// Counter for generating unique generator IDs
     next_generator_id: u64,
 
     /// Counter for generating unique symbol IDs
     next_symbol_id: u64,
 
-    /// Symbol registry for Symbol.for() / Symbol.keyFor()
-    symbol_registry: FxHashMap<JsString, JsSymbol>,
+    /// Symbol registry for Symbol.for() / Symbol.keyFor(), in registration
+    /// order so checkpoints list it the same way every run
+    symbol_registry: IndexMap<JsString, JsSymbol>,
+    /// Registry keys by symbol id, for Symbol.keyFor()
+    symbol_registry_keys: FxHashMap<u64, JsString>,
 
     /// Well-known symbols (Symbol.iterator, Symbol.toStringTag, etc.)
     pub well_known_symbols: WellKnownSymbols,
@@ -257,6 +435,24 @@ pub struct Interpreter {
     /// Console counters for console.count() / console.countReset()
     console_counters: FxHashMap<String, u64>,
 
+    /// Clock origin and entries of the `performance` global
+    performance: builtins::performance::PerformanceTimeline,
+
+    /// Ids of arrays with a `join` in progress, so cyclic arrays join to ""
+    pub(crate) join_stack: Vec<usize>,
+
+    /// Longest console message in UTF-8 bytes before it is truncated
+    pub(crate) max_console_message_len: usize,
+
+    /// Total console bytes that may be written, `None` for unlimited
+    max_console_output: Option<usize>,
+
+    /// Console bytes written so far, counted against `max_console_output`
+    console_bytes_written: usize,
+
+    /// Console messages dropped after `max_console_output` was reached
+    console_dropped: usize,
+
     /// Current FFI callback ID (set before calling native functions with ffi_id > 0)
     /// Used by the FFI layer to look up C callbacks
     pub current_ffi_id: usize,
@@ -291,6 +487,9 @@ pub struct Interpreter {
     /// Boxed to make take/put-back cheap (pointer move instead of struct move).
     pub(crate) active_vm: Option<Box<bytecode_vm::BytecodeVM>>,
 
+    /// Set while the active VM runs a detached async call
+    active_task: Option<ActiveTask>,
+
     /// Module path for active execution (needed for finalizing exports on completion)
     pub(crate) active_module_path: Option<crate::ModulePath>,
 
@@ -300,6 +499,19 @@ pub struct Interpreter {
     /// Module environment for active execution (needed for finalizing exports on completion)
     pub(crate) active_module_env: Option<Gc<JsObject>>,
 
+    /// Guard for the query scope while an `eval_in_context` execution is active
+    pub(crate) active_context_eval: Option<Guard<JsObject>>,
+
+    /// Keep the completed main module's top-level scope for `eval_in_context`
+    pub(crate) retain_main_scope: bool,
+
+    /// Throw on reads of missing properties of module namespaces and host
+    /// context objects (see `InterpreterConfig::strict_property_access`)
+    pub(crate) strict_property_access: bool,
+
+    /// Top-level scope of the last completed main execution, with the guard rooting it
+    pub(crate) retained_main_scope: Option<(Gc<JsObject>, Guard<JsObject>)>,
+
     // ═══════════════════════════════════════════════════════════════════════════
     // Module System
     // ═══════════════════════════════════════════════════════════════════════════
@@ -309,6 +521,34 @@ pub struct Interpreter {
     /// Instantiated internal module objects (cached after first import)
     internal_module_cache: FxHashMap<String, Gc<JsObject>>,
 
+    /// Internal source modules parsed to collect their imports, waiting to
+    /// be instantiated
+    internal_module_programs: FxHashMap<String, crate::ast::Program>,
+
+    /// Internal source modules being instantiated, innermost last
+    internal_modules_loading: Vec<String>,
+
+    /// Pre-evaluated modules shared with other interpreters
+    shared_modules: Option<Arc<shared_modules::SharedModuleSet>>,
+
+    /// Copies of shared module objects in this heap, by index in the set
+    shared_module_objects: Vec<Option<Gc<JsObject>>>,
+
+    /// Number of programs parsed so far
+    parsed_programs: usize,
+
+    /// Parse and compile metrics per module, until `reset_module_metrics`
+    module_metrics: Vec<metrics::ModuleMetrics>,
+
+    /// Host callbacks observing module loading
+    module_hooks: crate::ModuleHooks,
+
+    /// Host rewrite of module sources before parsing
+    source_transform: Option<crate::SourceTransform>,
+
+    /// Host choice of which ready modules run, and in what order
+    module_execution_order: Option<crate::ModuleExecutionOrder>,
+
     /// Loaded external modules (normalized path -> module namespace)
     loaded_modules: FxHashMap<crate::ModulePath, Gc<JsObject>>,
 
@@ -334,9 +574,93 @@ pub struct Interpreter {
     /// Cancelled order IDs
     pub(crate) cancelled_orders: Vec<crate::OrderId>,
 
+    /// Open `batch()` scopes from `tsrun:host`; while any is open, `order()`
+    /// returns a Promise instead of suspending the script
+    pub(crate) order_batch_depth: usize,
+
+    /// Orders created by the script that are neither fulfilled nor cancelled
+    pub(crate) outstanding_orders: Vec<crate::OrderId>,
+
+    /// Whether the running code feeds only promises whose outcome is ignored;
+    /// its orders are created cancelled and resolve to `undefined`
+    pub(crate) orders_unobserved: bool,
+
+    /// Most outstanding orders before creating another overflows
+    /// (see `InterpreterConfig::max_pending_orders`)
+    max_pending_orders: usize,
+    /// What creating an order past `max_pending_orders` does
+    pending_order_overflow: crate::PendingOrderOverflow,
+
+    /// Whether provided modules that fail to load fail the run
+    module_error_policy: crate::ModuleErrorPolicy,
+    /// Modules that failed to load under `ModuleErrorPolicy::Collect`, in order
+    module_errors: Vec<(crate::ModulePath, JsError)>,
+    /// What reading from the namespace of a module that failed to load
+    /// throws, by namespace object id
+    failed_module_namespaces: FxHashMap<usize, String>,
+
+    /// The value of the latest `throw` and the stack where it ran, so an
+    /// uncaught throw reaches the host with its origin
+    pub(crate) last_throw: Option<(Guarded, Vec<crate::error::StackFrame>)>,
+
+    /// Idle suspensions in a row before `step()` fails with `JsError::Stalled`
+    /// (see `InterpreterConfig::stall_threshold`)
+    stall_threshold: usize,
+    /// What import resolution does with the query of URL module paths
+    url_query: crate::UrlQuery,
+    /// The language of sources whose path doesn't tell
+    default_lang: crate::Lang,
+    /// The language the next parse uses whatever its path, set by
+    /// `prepare_with_lang` and `provide_module_with_lang`
+    lang_override: Option<crate::Lang>,
+    /// Whether host JSON is built with null prototypes (see
+    /// `InterpreterConfig::detach_host_json`)
+    pub(crate) detach_host_json: bool,
+    /// Consecutive `Suspended` results with nothing new and no progress
+    idle_suspensions: usize,
+    /// `step()` calls so far, the `step_index` of probe samples
+    steps_taken: u64,
+    /// Watch expressions added with `add_probe`, in order of their ids
+    probes: Vec<probes::Probe>,
+    /// Probe values not yet taken by the host
+    probe_samples: Vec<crate::ProbeSample>,
+    /// Whether an instruction ran or an order was fulfilled since the last
+    /// `Suspended` result
+    stall_progress: bool,
+    /// The ids passed to the latest `fulfill_orders` call
+    last_fulfilled: Vec<crate::OrderId>,
+
+    /// Whether orders handed to the host are logged (see `set_order_recording`)
+    order_recording: bool,
+    /// Orders recorded and not yet taken with `take_order_log`
+    order_log: Vec<crate::OrderLogEntry>,
+    /// `step()` results that logged orders, numbering `OrderLogEntry::batch_index`
+    order_log_batches: u32,
+    /// Log answering the script's orders (see `set_order_replay`)
+    order_replay: Option<order_log::OrderReplay>,
+
+    /// Completion value held back by `step()` while cancelled orders are reported
+    pub(crate) deferred_completion: Option<crate::RuntimeValue>,
+    /// Completion value of a finished main execution, held back by `step()`
+    /// until the async calls it left waiting have finished too
+    pub(crate) held_completion: Option<crate::RuntimeValue>,
+
     /// Suspended VM state waiting for order response from host
     pub(crate) suspended_for_order: Option<bytecode_vm::VmOrderSuspension>,
 
+    /// When orders are handed to the host (see `set_order_delivery`)
+    pub(crate) order_delivery: crate::OrderDelivery,
+    /// How much each `step()` executes (see `InterpreterConfig::step_granularity`)
+    step_granularity: crate::StepGranularity,
+    /// Whether entry scripts declare vars and functions on the global object
+    /// (see `InterpreterConfig::script_globals`)
+    script_globals: bool,
+
+    /// Promises returned by `order()` in eager mode, settled by `fulfill_orders`.
+    /// Batched orders awaited inside an async generator get one here too.
+    /// Each RuntimeValue keeps its promise alive until the order is fulfilled.
+    pub(crate) eager_order_promises: FxHashMap<crate::OrderId, crate::RuntimeValue>,
+
     // ═══════════════════════════════════════════════════════════════════════════
     // Async Context Management
     // ═══════════════════════════════════════════════════════════════════════════
@@ -353,6 +677,25 @@ pub struct Interpreter {
     /// Uses Gc identity (pointer comparison via Hash impl)
     pub(crate) promise_ids: FxHashMap<Gc<JsObject>, PromiseId>,
 
+    /// Thenables whose `then` is still to be called to settle the promise they
+    /// resolved, run before script execution returns to the host
+    pub(crate) thenable_jobs: VecDeque<builtins::promise::ThenableJob>,
+
+    /// Every pending promise created so far, for `pending_promises`
+    pub(crate) promise_tracker: pending_promises::PromiseTracker,
+
+    // ═══════════════════════════════════════════════════════════════════════════
+    // Finalization
+    // ═══════════════════════════════════════════════════════════════════════════
+    /// Every FinalizationRegistry created so far that is still alive
+    finalization_registries: Vec<crate::gc::WeakGc<JsObject>>,
+
+    /// `Heap::weak_deaths` when the registries were last checked for dead targets
+    weak_deaths_seen: usize,
+
+    /// Cleanup callbacks waiting for a `step()` without an active VM
+    finalization_jobs: VecDeque<builtins::weakref::FinalizationJob>,
+
     // ═══════════════════════════════════════════════════════════════════════════
     // Program State
     // ═══════════════════════════════════════════════════════════════════════════
@@ -362,13 +705,156 @@ pub struct Interpreter {
     /// Pending module sources waiting for their imports to be satisfied
     /// Maps normalized path -> parsed program
     pub(crate) pending_module_sources: FxHashMap<crate::ModulePath, crate::ast::Program>,
+
+    /// Module sources by path, served to imports without `NeedImports`
+    /// (see `set_module_source_map`)
+    module_source_map: FxHashMap<String, Rc<str>>,
+
+    /// Expected integrity of modules by path (see `set_integrity_manifest`)
+    integrity_manifest: FxHashMap<crate::ModulePath, String>,
+
+    /// How an import is looked up in `module_source_map`
+    module_candidates: Vec<module_map::ModuleCandidate>,
+
+    /// File each module served from `module_source_map` under another path
+    /// was read from, by requested path
+    module_files: FxHashMap<crate::ModulePath, crate::ModulePath>,
+
+    /// Imports reported through `NeedImports` that the host has not provided yet
+    pub(crate) requested_imports: Vec<crate::ImportRequest>,
+
+    /// Requested imports whose `provide_module` failed, to report again
+    failed_imports: Vec<crate::ModulePath>,
+
+    /// Order in which each path was first imported or reported through
+    /// `NeedImports`, which orders the pending modules
+    import_discovery: FxHashMap<crate::ModulePath, usize>,
+
+    /// Times the imports of a pending module were collected (see `module_import_scans`)
+    module_import_scans: usize,
+
+    /// Whether compiled functions keep their source text for `Function.prototype.toString`
+    pub(crate) retain_function_source: bool,
+
+    /// Whether the `Function` constructor and indirect eval may compile code
+    pub(crate) allow_dynamic_code: bool,
+
+    /// Whether compiled chunks are disassembled to the console
+    dump_bytecode: bool,
+
+    /// Whether runs fold reads of imported constants (see `fold_constant_imports`)
+    fold_constant_imports: bool,
+
+    /// Whether the current run folds imported constants: the config allows
+    /// it and no module seen so far uses dynamic code
+    constant_imports_active: bool,
+
+    /// Whether any chunk was compiled with imports folded to constants
+    folded_constant_imports: bool,
+
+    /// Largest string (in UTF-8 bytes) builtins and concatenation may produce
+    pub(crate) max_string_len: usize,
+
+    /// Largest array length builtins and element writes may produce
+    pub(crate) max_array_len: usize,
+
+    /// Most arguments spread calls and `apply` may pass
+    pub(crate) max_call_arguments: usize,
+
+    /// Deepest [`Interpreter::call_depth`] before calls fail with a RangeError
+    pub(crate) max_call_depth: usize,
+
+    /// Size limits of compiled code, from the `max_*` compile settings of
+    /// [`crate::InterpreterConfig`]
+    pub(crate) compile_limits: crate::compiler::CompileLimits,
+
+    /// Generator whose host-driven call is waiting on orders, see
+    /// [`Interpreter::generator_next`]
+    pub(crate) driven_generator: Option<crate::ObjectId>,
+
+    /// Levels of native recursion in progress, shared with the guards undoing them
+    native_depth: Rc<Cell<usize>>,
+
+    /// Work units left before execution is terminated, `None` for unlimited
+    pub(crate) fuel: Option<u64>,
+
+    /// Flag the host raises to stop execution at the next budget check
+    pub(crate) interrupt: crate::InterruptHandle,
+
+    /// Reason given to `request_cancellation`, for the `CancellationError`
+    cancellation_reason: Option<String>,
+
+    /// Memory budget from `InterpreterConfig::heap_budget`, `None` for unlimited
+    pub(crate) heap_budget: Option<heap_budget::HeapBudget>,
+
+    /// Classes registered with `register_host_class`, indexed by `HostClassId`
+    pub(crate) host_classes: Vec<Rc<host_class::HostClassDef>>,
+
+    /// Class behind `create_lazy_json_array`, registered on first use
+    lazy_json_array_class: Option<lazy_json::LazyJsonArrayClass>,
+
+    /// Class behind the namespaces of lazy internal modules, registered on first use
+    lazy_module_class: Option<host_class::HostClassId>,
+
+    /// Class behind `create_function_value`, registered on first use
+    host_function_class: Option<host_class::HostClassId>,
+
+    /// One bit per lazy builtin group whose global properties are reserved
+    /// but not built yet
+    pending_builtins: u32,
+
+    /// Clock `advance_time` moves, from `set_mock_clock`
+    #[cfg(feature = "testing")]
+    pub(crate) mock_clock: Option<crate::testing::MockClock>,
+
+    /// Deadlines of the timer orders `advance_time` answers, in creation order
+    #[cfg(feature = "testing")]
+    pub(crate) timer_orders: Vec<(i64, crate::OrderId)>,
+
+    /// Collections the heap had run when strings and property slots were
+    /// last measured for the budget, `None` before the first measurement
+    heap_budget_collections: Option<usize>,
+
+    /// Access audit counters in first-access order, `None` unless `enable_audit` was called
+    pub(crate) audit_log: Option<IndexMap<AuditKey, usize>>,
+
+    /// Write barrier of a running `eval_pure`, `None` otherwise
+    pub(crate) purity: Option<Box<purity::PurityCheck>>,
+
+    /// Nested chunks and instruction arrays shared between attached chunks
+    pub(crate) chunk_cache: chunk_cache::ChunkCache,
+
+    /// Creation sites of objects, `None` unless `track_provenance` is set
+    pub(crate) provenance: Option<Box<provenance::ProvenanceTable>>,
+
+    /// Recent VM activity, `None` unless `flight_recorder` is set
+    pub(crate) flight_recorder: Option<Box<flight_recorder::FlightRecorder>>,
+
+    /// Incremental prepare between `prepare_begin` and `PrepareProgress::Ready`
+    pub(crate) incremental: Option<Box<incremental::IncrementalPrepare>>,
+
+    /// Native functions exported by internal modules, mapped to their module specifier
+    pub(crate) internal_function_modules: FxHashMap<Gc<JsObject>, String>,
+
+    /// Globals as the first run since creation or the last `reset` found them
+    global_baseline: Option<reset::GlobalBaseline>,
 }
 
+/// Audit log key: (kind, module specifier, name)
+type AuditKey = (crate::AuditEventKind, Option<String>, String);
+
 impl Interpreter {
     /// Create a new interpreter instance
     pub fn new() -> Self {
         let heap: Heap<JsObject> = Heap::new();
         let root_guard = heap.create_guard();
+        root_guard.set_label("root");
+        let module_guard = heap.create_guard();
+        module_guard.set_label("module cache");
+        let retained_module_guard = heap.create_guard();
+        retained_module_guard.set_label("retained modules");
+        let json_cache = json_cache::JsonCache::new(&heap);
+        let call_pool = call_pool::CallPool::new(&heap);
 
         // Create prototypes (all rooted)
         let object_prototype = root_guard.alloc();
@@ -383,7 +869,15 @@ impl Interpreter {
         let date_prototype = root_guard.alloc();
         let symbol_prototype = root_guard.alloc();
         let promise_prototype = root_guard.alloc();
+        let iterator_prototype = root_guard.alloc();
+        let iterator_helper_prototype = root_guard.alloc();
         let generator_prototype = root_guard.alloc();
+        let url_prototype = root_guard.alloc();
+        let url_search_params_prototype = root_guard.alloc();
+        let weakref_prototype = root_guard.alloc();
+        let finalization_registry_prototype = root_guard.alloc();
+        let disposable_stack_prototype = root_guard.alloc();
+        let async_disposable_stack_prototype = root_guard.alloc();
 
         // Create error prototypes (all rooted)
         let error_prototype = root_guard.alloc();
@@ -391,6 +885,10 @@ impl Interpreter {
         let reference_error_prototype = root_guard.alloc();
         let range_error_prototype = root_guard.alloc();
         let syntax_error_prototype = root_guard.alloc();
+        let eval_error_prototype = root_guard.alloc();
+        let uri_error_prototype = root_guard.alloc();
+        let aggregate_error_prototype = root_guard.alloc();
+        let suppressed_error_prototype = root_guard.alloc();
 
         // Set up prototype chain - all prototypes inherit from object_prototype
         array_prototype.borrow_mut().prototype = Some(object_prototype.clone());
@@ -404,7 +902,15 @@ impl Interpreter {
         date_prototype.borrow_mut().prototype = Some(object_prototype.clone());
         symbol_prototype.borrow_mut().prototype = Some(object_prototype.clone());
         promise_prototype.borrow_mut().prototype = Some(object_prototype.clone());
-        generator_prototype.borrow_mut().prototype = Some(object_prototype.clone());
+        iterator_prototype.borrow_mut().prototype = Some(object_prototype.clone());
+        iterator_helper_prototype.borrow_mut().prototype = Some(iterator_prototype.clone());
+        generator_prototype.borrow_mut().prototype = Some(iterator_prototype.clone());
+        url_prototype.borrow_mut().prototype = Some(object_prototype.clone());
+        url_search_params_prototype.borrow_mut().prototype = Some(object_prototype.clone());
+        weakref_prototype.borrow_mut().prototype = Some(object_prototype.clone());
+        finalization_registry_prototype.borrow_mut().prototype = Some(object_prototype.clone());
+        disposable_stack_prototype.borrow_mut().prototype = Some(object_prototype.clone());
+        async_disposable_stack_prototype.borrow_mut().prototype = Some(object_prototype.clone());
 
         // Set up error prototype chain
         // Error.prototype inherits from Object.prototype
@@ -414,6 +920,10 @@ impl Interpreter {
         reference_error_prototype.borrow_mut().prototype = Some(error_prototype.clone());
         range_error_prototype.borrow_mut().prototype = Some(error_prototype.clone());
         syntax_error_prototype.borrow_mut().prototype = Some(error_prototype.clone());
+        eval_error_prototype.borrow_mut().prototype = Some(error_prototype.clone());
+        uri_error_prototype.borrow_mut().prototype = Some(error_prototype.clone());
+        aggregate_error_prototype.borrow_mut().prototype = Some(error_prototype.clone());
+        suppressed_error_prototype.borrow_mut().prototype = Some(error_prototype.clone());
 
         // Create global object (rooted)
         let global = root_guard.alloc();
@@ -430,13 +940,17 @@ impl Interpreter {
         let string_dict = StringDict::new();
 
         // Initialize symbol counter and well-known symbols
-        // Well-known symbols get IDs 1-12, next_symbol_id starts at 13
+        // Well-known symbols get IDs 1-14, next_symbol_id starts at 15
         let mut symbol_counter = 1u64;
         let well_known_symbols = WellKnownSymbols::new(&mut symbol_counter);
 
         let mut interp = Self {
             heap,
             root_guard,
+            module_guard,
+            retained_module_guard,
+            instantiating_retained: false,
+            json_cache,
             global,
             global_env: global_env.clone(),
             env: global_env,
@@ -454,20 +968,40 @@ impl Interpreter {
             date_prototype,
             symbol_prototype,
             promise_prototype,
+            iterator_prototype,
+            iterator_helper_prototype,
             generator_prototype,
+            url_prototype,
+            url_search_params_prototype,
+            weakref_prototype,
+            finalization_registry_prototype,
+            disposable_stack_prototype,
+            async_disposable_stack_prototype,
             error_prototype,
             type_error_prototype,
             reference_error_prototype,
             range_error_prototype,
             syntax_error_prototype,
+            eval_error_prototype,
+            uri_error_prototype,
+            aggregate_error_prototype,
+            suppressed_error_prototype,
             exports: FxHashMap::default(),
             call_stack: Vec::new(),
+            call_pool,
             next_generator_id: 1,
             next_symbol_id: symbol_counter,
-            symbol_registry: FxHashMap::default(),
+            symbol_registry: IndexMap::default(),
+            symbol_registry_keys: FxHashMap::default(),
             well_known_symbols,
             console_timers: FxHashMap::default(),
             console_counters: FxHashMap::default(),
+            performance: builtins::performance::PerformanceTimeline::new(0),
+            join_stack: Vec::new(),
+            max_console_message_len: crate::DEFAULT_MAX_CONSOLE_MESSAGE_LEN,
+            max_console_output: None,
+            console_bytes_written: 0,
+            console_dropped: 0,
             current_ffi_id: 0,
             #[cfg(feature = "c-api")]
             ffi_context: core::ptr::null_mut(),
@@ -491,12 +1025,26 @@ impl Interpreter {
             regexp_provider: Rc::new(NoOpRegExpProvider),
             // Step-based execution
             active_vm: None,
+            active_task: None,
             active_module_path: None,
             active_saved_env: None,
             active_module_env: None,
+            active_context_eval: None,
+            retain_main_scope: false,
+            strict_property_access: false,
+            retained_main_scope: None,
             // Module system
             internal_modules: FxHashMap::default(),
             internal_module_cache: FxHashMap::default(),
+            internal_module_programs: FxHashMap::default(),
+            internal_modules_loading: Vec::new(),
+            shared_modules: None,
+            shared_module_objects: Vec::new(),
+            parsed_programs: 0,
+            module_metrics: Vec::new(),
+            module_hooks: crate::ModuleHooks::default(),
+            source_transform: None,
+            module_execution_order: None,
             loaded_modules: FxHashMap::default(),
             main_module_path: None,
             current_module_path: None,
@@ -505,22 +1053,104 @@ impl Interpreter {
             pending_orders: Vec::new(),
             order_responses: FxHashMap::default(),
             cancelled_orders: Vec::new(),
+            order_batch_depth: 0,
+            outstanding_orders: Vec::new(),
+            orders_unobserved: false,
+            max_pending_orders: crate::DEFAULT_MAX_PENDING_ORDERS,
+            pending_order_overflow: crate::PendingOrderOverflow::Throw,
+            module_error_policy: crate::ModuleErrorPolicy::FailFast,
+            module_errors: Vec::new(),
+            failed_module_namespaces: FxHashMap::default(),
+            last_throw: None,
+            stall_threshold: crate::DEFAULT_STALL_THRESHOLD,
+            url_query: crate::UrlQuery::Keep,
+            default_lang: crate::Lang::TypeScript,
+            lang_override: None,
+            detach_host_json: false,
+            idle_suspensions: 0,
+            stall_progress: false,
+            steps_taken: 0,
+            probes: Vec::new(),
+            probe_samples: Vec::new(),
+            last_fulfilled: Vec::new(),
+            order_recording: false,
+            order_log: Vec::new(),
+            order_log_batches: 0,
+            order_replay: None,
+            deferred_completion: None,
+            held_completion: None,
             suspended_for_order: None,
+            order_delivery: crate::OrderDelivery::Batched,
+            step_granularity: crate::StepGranularity::Instruction,
+            script_globals: false,
+            eager_order_promises: FxHashMap::default(),
             // Async context management
             wait_graph: WaitGraph::new(),
             next_context_id: 1,
             next_promise_id: 1,
             promise_ids: FxHashMap::default(),
+            // Finalization
+            finalization_registries: Vec::new(),
+            weak_deaths_seen: 0,
+            finalization_jobs: VecDeque::new(),
+            thenable_jobs: VecDeque::new(),
+            promise_tracker: pending_promises::PromiseTracker::default(),
             // Program state
             pending_program: None,
             pending_module_sources: FxHashMap::default(),
+            module_source_map: FxHashMap::default(),
+            integrity_manifest: FxHashMap::default(),
+            module_candidates: module_map::ModuleCandidate::defaults(),
+            module_files: FxHashMap::default(),
+            requested_imports: Vec::new(),
+            failed_imports: Vec::new(),
+            import_discovery: FxHashMap::default(),
+            module_import_scans: 0,
+            retain_function_source: true,
+            allow_dynamic_code: false,
+            dump_bytecode: false,
+            fold_constant_imports: false,
+            constant_imports_active: false,
+            folded_constant_imports: false,
+            max_string_len: crate::DEFAULT_MAX_STRING_LEN,
+            max_array_len: crate::DEFAULT_MAX_ARRAY_LEN,
+            max_call_arguments: crate::DEFAULT_MAX_CALL_ARGUMENTS,
+            max_call_depth: crate::DEFAULT_MAX_CALL_DEPTH,
+            compile_limits: crate::compiler::CompileLimits::default(),
+            driven_generator: None,
+            native_depth: Rc::new(Cell::new(0)),
+            fuel: None,
+            interrupt: crate::InterruptHandle::default(),
+            cancellation_reason: None,
+            heap_budget: None,
+            heap_budget_collections: None,
+            host_classes: Vec::new(),
+            lazy_json_array_class: None,
+            lazy_module_class: None,
+            host_function_class: None,
+            pending_builtins: 0,
+            #[cfg(feature = "testing")]
+            mock_clock: None,
+            #[cfg(feature = "testing")]
+            timer_orders: Vec::new(),
+            audit_log: None,
+            purity: None,
+            chunk_cache: chunk_cache::ChunkCache::default(),
+            provenance: None,
+            flight_recorder: None,
+            incremental: None,
+            internal_function_modules: FxHashMap::default(),
+            global_baseline: None,
         };
 
         // Initialize built-in globals
         interp.init_globals();
 
         // Register built-in internal modules
+        interp.register_internal_module(builtins::create_host_primitives_module());
         interp.register_internal_module(builtins::create_eval_internal_module());
+        interp.register_internal_module(builtins::create_assert_internal_module());
+        interp.register_internal_module(builtins::create_quantity_internal_module());
 
         interp
     }
@@ -555,6 +1185,247 @@ impl Interpreter {
         self.regexp_provider = provider;
     }
 
+    /// Fail with `RangeError: Invalid string length` if a string of `len` bytes exceeds the limit
+    /// and `JsError::Terminated` if it would not fit in the heap budget
+    pub(crate) fn check_string_len(&self, len: usize) -> Result<(), JsError> {
+        if len > self.max_string_len {
+            return Err(JsError::range_error("Invalid string length"));
+        }
+        if self
+            .heap_budget
+            .as_ref()
+            .is_some_and(|budget| len > budget.remaining())
+        {
+            return Err(heap_budget_exceeded());
+        }
+        Ok(())
+    }
+
+    /// Fail with `RangeError: Invalid array length` if `len` exceeds the array limit
+    pub(crate) fn check_array_len(&self, len: usize) -> Result<(), JsError> {
+        if len > self.max_array_len {
+            return Err(JsError::range_error("Invalid array length"));
+        }
+        Ok(())
+    }
+
+    /// Fail with a RangeError if a call would pass more than `max_call_arguments`
+    pub(crate) fn check_argument_count(&self, count: usize) -> Result<(), JsError> {
+        if count > self.max_call_arguments {
+            return Err(JsError::range_error("Too many arguments in function call"));
+        }
+        Ok(())
+    }
+
+    /// Limit how much work the script may do, `None` for unlimited (the default).
+    ///
+    /// Every VM instruction costs one unit, as does every iteration of builtins
+    /// whose work grows with script-controlled input (sort, JSON, `repeat`,
+    /// `padStart`/`padEnd`, iterator collection). When the budget runs out,
+    /// execution fails with `JsError::Terminated`, which scripts cannot catch.
+    /// Calling this again refills the budget.
+    pub fn set_fuel(&mut self, fuel: Option<u64>) {
+        self.fuel = fuel;
+    }
+
+    /// Fuel left from the last `set_fuel`, `None` when unlimited
+    pub fn fuel_remaining(&self) -> Option<u64> {
+        self.fuel
+    }
+
+    /// Handle for stopping this interpreter from another thread or a callback
+    pub fn interrupt_handle(&self) -> crate::InterruptHandle {
+        self.interrupt.clone()
+    }
+
+    /// Ask the script to wind down gracefully.
+    ///
+    /// Unlike an interrupt, nothing stops by itself: the script's next call
+    /// to `checkpoint()` from `tsrun:host` throws a `CancellationError`
+    /// carrying `reason`, which it may catch to flush partial results, and
+    /// `isCancellationRequested()` returns true. Can be called between
+    /// `step()`s; [`crate::InterruptHandle::request_cancellation`] does the
+    /// same from another thread. The request stays until
+    /// [`Interpreter::clear_cancellation`].
+    pub fn request_cancellation(&mut self, reason: impl Into<String>) {
+        self.cancellation_reason = Some(reason.into());
+        self.interrupt.request_cancellation();
+    }
+
+    /// Withdraw a cancellation request, so `checkpoint()` passes again
+    pub fn clear_cancellation(&mut self) {
+        self.cancellation_reason = None;
+        self.interrupt.clear_cancellation();
+    }
+
+    /// Whether a cancellation is requested, and with what reason
+    pub(crate) fn cancellation(&self) -> Option<Option<&str>> {
+        self.interrupt
+            .is_cancellation_requested()
+            .then_some(self.cancellation_reason.as_deref())
+    }
+
+    /// Charge one unit of fuel, failing if the budget is spent or an interrupt
+    /// is pending.
+    ///
+    /// The VM calls this before each instruction. Builtins call it once per
+    /// loop iteration so a single native call can't outrun the host's limits.
+    pub(crate) fn check_budget(&mut self) -> Result<(), JsError> {
+        if self.interrupt.is_interrupted() {
+            return Err(JsError::Terminated {
+                reason: crate::TerminationReason::Interrupted,
+            });
+        }
+        if let Some(fuel) = &mut self.fuel {
+            if *fuel == 0 {
+                return Err(JsError::Terminated {
+                    reason: crate::TerminationReason::FuelExhausted,
+                });
+            }
+            *fuel -= 1;
+        }
+        if self.heap_budget.is_some() {
+            self.measure_heap_budget()?;
+        }
+        Ok(())
+    }
+
+    /// Bring the heap budget's usage up to date, failing once it is over.
+    ///
+    /// Live objects are counted every time; strings and property slots are
+    /// walked only after a collection has run since the last walk.
+    fn measure_heap_budget(&mut self) -> Result<(), JsError> {
+        let Some(budget) = self.heap_budget.clone() else {
+            return Ok(());
+        };
+        let stats = self.heap.stats();
+        budget.set_live_objects(stats.live_objects);
+        if self.heap_budget_collections != Some(stats.collections) {
+            let census = self.gc_census();
+            budget.set_contents(census.properties, census.string_bytes);
+            self.heap_budget_collections = Some(stats.collections);
+        }
+        if budget.is_exceeded() {
+            return Err(heap_budget_exceeded());
+        }
+        Ok(())
+    }
+
+    /// Memory counted against `InterpreterConfig::heap_budget`, `None`
+    /// without a budget
+    pub fn heap_usage(&self) -> Option<heap_budget::HeapUsage> {
+        self.heap_budget.as_ref().map(|budget| budget.usage())
+    }
+
+    /// Choose when orders created by the script are handed to the host.
+    ///
+    /// With `OrderDelivery::Eager`, `order()` returns a Promise instead of
+    /// suspending and `step()` reports new orders via `StepResult::Orders`.
+    pub fn set_order_delivery(&mut self, delivery: crate::OrderDelivery) {
+        self.order_delivery = delivery;
+    }
+
+    /// Start recording which globals, internal module exports, and native
+    /// functions the script touches.
+    ///
+    /// Retrieve the records with `take_audit_log`. When auditing is not enabled
+    /// the hooks cost a single branch.
+    pub fn enable_audit(&mut self) {
+        if self.audit_log.is_none() {
+            self.audit_log = Some(index_map_new());
+        }
+    }
+
+    /// Take the access records collected since auditing was enabled or the log
+    /// was last taken, in first-access order. Empty if auditing is disabled.
+    pub fn take_audit_log(&mut self) -> Vec<crate::AuditEvent> {
+        let Some(log) = self.audit_log.as_mut() else {
+            return Vec::new();
+        };
+        mem::take(log)
+            .into_iter()
+            .map(|((kind, module, name), count)| crate::AuditEvent {
+                kind,
+                name,
+                module,
+                count,
+            })
+            .collect()
+    }
+
+    /// Serialize a value as YAML text with the `YAML.stringify` defaults.
+    ///
+    /// A top-level array is emitted as a `---` separated stream of documents.
+    #[cfg(feature = "yaml")]
+    pub fn to_yaml(&self, value: &crate::RuntimeValue) -> Result<String, JsError> {
+        builtins::yaml::value_to_yaml(self, value.value(), builtins::yaml::YamlOptions::default())
+    }
+
+    /// [`Self::to_yaml`] with Error objects emitted as `errors` says
+    #[cfg(feature = "yaml")]
+    pub fn to_yaml_with_errors(
+        &self,
+        value: &crate::RuntimeValue,
+        errors: crate::ErrorsAs,
+    ) -> Result<String, JsError> {
+        let options = builtins::yaml::YamlOptions {
+            errors,
+            ..builtins::yaml::YamlOptions::default()
+        };
+        builtins::yaml::value_to_yaml(self, value.value(), options)
+    }
+
+    /// Serialize a value as canonical JSON (RFC 8785), like `JSON.canonicalize`:
+    /// keys sorted, no whitespace, so equal values give byte-identical text
+    /// whatever order their properties were created in.
+    pub fn to_canonical_json(&self, value: &crate::RuntimeValue) -> Result<String, JsError> {
+        builtins::json::value_to_canonical_json(value.value(), false)
+    }
+
+    /// [`Self::to_canonical_json`] with members on their own lines, indented
+    /// by two spaces, for human-readable diffs
+    pub fn to_canonical_json_pretty(&self, value: &crate::RuntimeValue) -> Result<String, JsError> {
+        builtins::json::value_to_canonical_json(value.value(), true)
+    }
+
+    /// [`Self::to_canonical_json`] with Error objects converted as `errors`
+    /// says, e.g. to keep the `cause` chain of errors collected in a result
+    pub fn to_canonical_json_with_errors(
+        &self,
+        value: &crate::RuntimeValue,
+        errors: crate::ErrorsAs,
+        pretty: bool,
+    ) -> Result<String, JsError> {
+        builtins::json::value_to_canonical_json_with_errors(self, value.value(), pretty, errors)
+    }
+
+    /// Count one access in the audit log (no-op when auditing is disabled)
+    fn record_audit(&mut self, kind: crate::AuditEventKind, module: Option<&str>, name: &str) {
+        if let Some(log) = self.audit_log.as_mut() {
+            *log.entry((kind, module.map(String::from), name.to_string()))
+                .or_insert(0) += 1;
+        }
+    }
+
+    /// Record a property read if it targets the global object
+    pub(crate) fn audit_property_read(&mut self, obj: &JsValue, key: &JsValue) {
+        if let (JsValue::Object(obj), JsValue::String(name)) = (obj, key)
+            && Gc::ptr_eq(obj, &self.global)
+        {
+            self.record_audit(crate::AuditEventKind::GlobalRead, None, name.as_str());
+        }
+    }
+
+    /// Record a native function call, attributed to its internal module if it has one
+    pub(crate) fn audit_native_call(&mut self, func_obj: &Gc<JsObject>, name: &JsString) {
+        let module = self.internal_function_modules.get(func_obj).cloned();
+        self.record_audit(
+            crate::AuditEventKind::NativeCall,
+            module.as_deref(),
+            name.as_str(),
+        );
+    }
+
     /// Set the console provider at runtime.
     pub fn set_console(&mut self, provider: Box<dyn ConsoleProvider>) {
         self.console_provider = provider;
@@ -565,6 +1436,8 @@ impl Interpreter {
     /// This affects `Date.now()`, `console.time()`, and other time-related operations.
     pub fn set_time_provider(&mut self, provider: Box<dyn TimeProvider>) {
         self.time_provider = provider;
+        // Timer handles belong to the provider that issued them
+        self.performance.origin = self.time_provider.start_timer();
     }
 
     /// Set the random provider at runtime.
@@ -619,14 +1492,18 @@ impl Interpreter {
         self.env_define(function_name, JsValue::Object(function_constructor), false);
 
         // Initialize Math global object
-        builtins::init_math(self);
+        self.defer_builtin("Math");
 
         // Initialize JSON global object
-        builtins::init_json(self);
+        self.defer_builtin("JSON");
+
+        // Initialize YAML object
+        #[cfg(feature = "yaml")]
+        self.defer_builtin("YAML");
 
         // Initialize console global object (only when console feature is enabled)
         #[cfg(feature = "console")]
-        builtins::console::init_console(self);
+        self.defer_builtin("console");
 
         // Initialize Number prototype methods
         builtins::init_number_prototype(self);
@@ -638,13 +1515,27 @@ impl Interpreter {
         builtins::init_global_functions(self);
 
         // Initialize Map constructor and prototype
-        builtins::init_map(self);
+        self.defer_builtin("Map");
 
         // Initialize Set constructor and prototype
-        builtins::init_set(self);
+        self.defer_builtin("Set");
+
+        // Initialize URL and URLSearchParams
+        self.defer_builtin("URL");
+
+        // Initialize WeakRef and FinalizationRegistry
+        self.defer_builtin("WeakRef");
+
+        // Initialize DisposableStack and AsyncDisposableStack
+        self.defer_builtin("DisposableStack");
+
+        // Initialize performance global object, timing from construction
+        // however late it is built
+        self.performance.origin = self.time_provider.start_timer();
+        self.defer_builtin("performance");
 
         // Initialize Date constructor and prototype
-        builtins::init_date(self);
+        self.defer_builtin("Date");
 
         // Initialize Symbol constructor and prototype
         builtins::init_symbol(self);
@@ -700,11 +1591,14 @@ impl Interpreter {
         let promise_name = self.intern("Promise");
         self.env_define(promise_name, JsValue::Object(promise_constructor), false);
 
+        // Initialize Iterator prototype, helpers and the abstract Iterator constructor
+        builtins::init_iterator(self);
+
         // Initialize Generator prototype
         builtins::init_generator_prototype(self);
 
         // Initialize Proxy constructor and Reflect object
-        builtins::proxy::init_proxy(self);
+        self.defer_builtin("Proxy");
     }
 
     /// Create an interpreter with configuration
@@ -716,6 +1610,49 @@ impl Interpreter {
             interp.regexp_provider = provider;
         }
 
+        interp.retain_function_source = config.retain_function_source;
+        interp.allow_dynamic_code = config.allow_dynamic_code;
+        interp.dump_bytecode = config.dump_bytecode;
+        interp.fold_constant_imports = config.fold_constant_imports;
+        interp.max_string_len = config.max_string_len;
+        interp.max_array_len = config.max_array_len;
+        interp.max_call_arguments = config.max_call_arguments;
+        interp.max_call_depth = config.max_call_depth;
+        interp.compile_limits = crate::compiler::CompileLimits {
+            max_chunk_instructions: config.max_chunk_instructions,
+            max_chunk_constants: config.max_chunk_constants,
+            max_function_depth: config.max_function_depth,
+            max_program_instructions: config.max_program_instructions,
+        };
+        interp.heap_budget = config.heap_budget;
+        interp.max_console_message_len = config.max_console_message_len;
+        interp.max_console_output = config.max_console_output;
+        interp.retain_main_scope = config.retain_main_scope;
+        interp.strict_property_access = config.strict_property_access;
+        interp.shared_modules = config.shared_modules;
+        interp.module_hooks = config.module_hooks;
+        interp.source_transform = config.source_transform;
+        interp.module_execution_order = config.module_execution_order;
+        interp.json_cache.set_capacity(config.json_cache_capacity);
+        interp.step_granularity = config.step_granularity;
+        interp.script_globals = config.script_globals;
+        interp.max_pending_orders = config.max_pending_orders;
+        interp.stall_threshold = config.stall_threshold;
+        interp.url_query = config.url_query;
+        interp.default_lang = config.default_lang;
+        interp.detach_host_json = config.detach_host_json;
+        interp.pending_order_overflow = config.pending_order_overflow;
+        interp.module_error_policy = config.module_error_policy;
+        if config.track_provenance {
+            let guard = interp.heap.create_guard();
+            interp.provenance = Some(Box::new(provenance::ProvenanceTable::new(guard)));
+        }
+        if config.flight_recorder {
+            interp.flight_recorder = Some(Box::new(flight_recorder::FlightRecorder::new(
+                config.flight_recorder_capacity,
+            )));
+        }
+
         // Register internal modules
         for module in config.internal_modules {
             interp.register_internal_module(module);
@@ -724,21 +1661,122 @@ impl Interpreter {
         interp
     }
 
+    /// Fail with `EvalError` unless the host allowed compiling code from strings
+    pub(crate) fn check_dynamic_code(&self, what: &str) -> Result<(), JsError> {
+        if self.allow_dynamic_code {
+            return Ok(());
+        }
+        Err(JsError::eval_error(format!(
+            "{} is not allowed: code generation from strings is disabled",
+            what
+        )))
+    }
+
+    /// Parse source text into a program.
+    ///
+    /// The program only keeps its source text (for `Function.prototype.toString`)
+    /// when function source retention is enabled.
+    pub(crate) fn parse_program(&mut self, source: &str) -> Result<crate::ast::Program, JsError> {
+        self.parse_program_as(source, self.default_lang)
+    }
+
+    /// Parse source text into a program in language `lang`
+    pub(crate) fn parse_program_as(
+        &mut self,
+        source: &str,
+        lang: crate::Lang,
+    ) -> Result<crate::ast::Program, JsError> {
+        let mut parser = Parser::new(source, &mut self.string_dict).with_lang(lang);
+        let program = parser
+            .parse_program()
+            .map_err(|e| e.with_snippet(&crate::error::SourceText::new(source)))?;
+        Ok(self.finish_parse(program))
+    }
+
+    /// Parse the source of module `path` (`None` for an entry without a
+    /// path), after running it through the host's source transform
+    pub(crate) fn parse_module_source(
+        &mut self,
+        path: Option<&crate::ModulePath>,
+        source: &str,
+    ) -> Result<crate::ast::Program, JsError> {
+        let lang = self.module_lang(path);
+        match self.transform_source(path, source)? {
+            Some(transformed) => self.parse_program_as(&transformed, lang),
+            None => self.parse_program_as(source, lang),
+        }
+    }
+
+    /// The language the source of module `path` is parsed as
+    pub(crate) fn module_lang(&self, path: Option<&crate::ModulePath>) -> crate::Lang {
+        self.lang_override
+            .unwrap_or_else(|| crate::Lang::infer(path, self.default_lang))
+    }
+
+    /// The source of module `path` as rewritten by the host's source
+    /// transform, or `None` without one
+    pub(crate) fn transform_source(
+        &self,
+        path: Option<&crate::ModulePath>,
+        source: &str,
+    ) -> Result<Option<String>, JsError> {
+        let Some(transform) = &self.source_transform else {
+            return Ok(None);
+        };
+        let path = path
+            .cloned()
+            .unwrap_or_else(|| crate::ModulePath::new(metrics::UNNAMED_MAIN));
+        match transform(&path, source) {
+            Ok(transformed) => Ok(Some(transformed)),
+            Err(message) => Err(JsError::TransformError { path, message }),
+        }
+    }
+
+    /// Count a freshly parsed program and drop its source unless it is retained
+    fn finish_parse(&mut self, mut program: crate::ast::Program) -> crate::ast::Program {
+        self.parsed_programs += 1;
+        if !self.retain_function_source {
+            program.source = None;
+        }
+        program
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // Call Stack Depth
     // ═══════════════════════════════════════════════════════════════════════════
 
     /// Get the current call stack depth.
     ///
-    /// Returns the total depth combining the interpreter's call stack and
-    /// the active VM's trampoline stack (if any).
+    /// Counts every function frame, whether run by the VM's trampoline or
+    /// by a nested VM, plus every level of native recursion in progress
+    /// (getters, proxy traps, callbacks run by builtins, builtins walking
+    /// nested values). This is the number checked against
+    /// [`crate::InterpreterConfig::max_call_depth`].
     pub fn call_depth(&self) -> usize {
-        let vm_depth = self
-            .active_vm
-            .as_ref()
-            .map(|vm| vm.trampoline_depth())
-            .unwrap_or(0);
-        self.call_stack.len() + vm_depth
+        self.call_stack.len() + self.native_depth.get()
+    }
+
+    /// Fail with `RangeError: Maximum call stack size exceeded` if pushing
+    /// another function frame would pass the depth limit
+    pub(crate) fn check_call_depth(&self) -> Result<(), JsError> {
+        if self.call_depth() >= self.max_call_depth {
+            return Err(JsError::range_error("Maximum call stack size exceeded"));
+        }
+        Ok(())
+    }
+
+    /// Count one level of native recursion until the returned guard drops,
+    /// failing like [`Self::check_call_depth`] once too many are in progress
+    pub(crate) fn enter_native(&self) -> Result<NativeDepthGuard, JsError> {
+        let depth = self.native_depth.get();
+        if depth >= crate::MAX_NATIVE_CALL_DEPTH {
+            return Err(JsError::range_error("Maximum call stack size exceeded"));
+        }
+        self.check_call_depth()?;
+        self.native_depth.set(depth + 1);
+        Ok(NativeDepthGuard {
+            depth: Rc::clone(&self.native_depth),
+        })
     }
 
     /// Set the GC threshold (0 = disable automatic collection)
@@ -754,11 +1792,84 @@ impl Interpreter {
         self.heap.collect();
     }
 
+    /// Collect garbage during host idle time, if it looks worthwhile.
+    ///
+    /// A full cycle runs only when the allocations since the last collection
+    /// are a sizeable share of the live heap, and past timed cycles predict it
+    /// fits in `budget_ms`. Before any timed cycle the first one is allowed, so
+    /// the heap has a duration to go on. The cycle is timed with `time` and
+    /// recorded in [`Interpreter::gc_stats`].
+    pub fn maybe_collect(&self, budget_ms: f64, time: &dyn TimeProvider) -> CollectOutcome {
+        let stats = self.heap.stats();
+        let worthwhile =
+            (stats.live_objects / IDLE_COLLECT_GARBAGE_RATIO).max(IDLE_COLLECT_MIN_ALLOCS);
+        if stats.allocations_since_collect < worthwhile {
+            return CollectOutcome::NothingToDo;
+        }
+        if let Some(estimated_ms) = self.heap.estimated_collect_ms()
+            && estimated_ms > budget_ms
+        {
+            return CollectOutcome::OverBudget { estimated_ms };
+        }
+
+        let start = time.start_timer();
+        self.heap.collect();
+        let duration_ms = time.elapsed_millis(start) as f64;
+        self.heap
+            .record_collect_duration(duration_ms, stats.total_objects);
+        let freed = stats
+            .live_objects
+            .saturating_sub(self.heap.stats().live_objects);
+        CollectOutcome::Collected { duration_ms, freed }
+    }
+
+    /// Number of environments the interpreter is keeping alive for scopes and
+    /// calls in progress; 0 whenever no script is running
+    pub fn env_depth(&self) -> usize {
+        self.env_guards.len()
+    }
+
     /// Get GC statistics
     pub fn gc_stats(&self) -> crate::gc::GcStats {
         self.heap.stats()
     }
 
+    /// Number of FinalizationRegistry cleanup callbacks waiting to run.
+    ///
+    /// Callbacks for targets found dead by a collection run one per
+    /// [`Interpreter::step`] call once no script is executing, so a host that
+    /// sees a non-zero count should keep stepping.
+    pub fn pending_finalizations(&self) -> usize {
+        self.count_pending_finalizations()
+    }
+
+    /// Count reachable objects by kind, with their property and string totals.
+    ///
+    /// Walks the whole heap, so call it when investigating memory growth
+    /// rather than on every step.
+    pub fn gc_census(&self) -> crate::value::GcCensus {
+        let mut census = crate::value::GcCensus::default();
+        self.heap.for_each_reachable(|obj| census.record(obj));
+        census
+    }
+
+    /// The `top_n` guards keeping the most objects alive. See [`Heap::dominators`].
+    ///
+    /// Interpreter guards are labeled `"root"` (prototypes and globals),
+    /// `"module cache"`, `"environment"` (active scopes), `"registers"` (call
+    /// frames), `"order payload"` and `"order promise"` (pending orders).
+    pub fn gc_retainers(&self, top_n: usize) -> Vec<crate::gc::Retainer> {
+        self.heap.dominators(top_n)
+    }
+
+    /// Number of programs parsed so far: entry points, modules and `eval` code.
+    ///
+    /// Each parsed program is also compiled, so this shows whether imports were
+    /// served from a [`SharedModuleSet`](crate::SharedModuleSet) instead.
+    pub fn parsed_programs(&self) -> usize {
+        self.parsed_programs
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // Symbol Management
     // ═══════════════════════════════════════════════════════════════════════════
@@ -777,17 +1888,106 @@ impl Interpreter {
 
     /// Insert a symbol into the registry (for Symbol.for)
     pub fn symbol_registry_insert(&mut self, key: JsString, symbol: JsSymbol) {
-        self.symbol_registry.insert(key, symbol);
+        let id = symbol.id();
+        if let Some(replaced) = self.symbol_registry.insert(key.cheap_clone(), symbol) {
+            self.symbol_registry_keys.remove(&replaced.id());
+        }
+        self.symbol_registry_keys.insert(id, key);
     }
 
     /// Find the key for a registered symbol (for Symbol.keyFor)
     pub fn symbol_registry_key_for(&self, symbol_id: u64) -> Option<JsString> {
-        for (key, sym) in &self.symbol_registry {
-            if sym.id() == symbol_id {
-                return Some(key.cheap_clone());
-            }
+        self.symbol_registry_keys.get(&symbol_id).cloned()
+    }
+
+    /// Get a well-known symbol, the same value scripts see as e.g. `Symbol.iterator`
+    pub fn get_well_known(&mut self, symbol: crate::WellKnownSymbol) -> crate::RuntimeValue {
+        let id = self.well_known_symbols.id(symbol);
+        let description = self.intern(symbol.description());
+        crate::RuntimeValue::unguarded(JsValue::Symbol(Box::new(JsSymbol::new(
+            id,
+            Some(description),
+        ))))
+    }
+
+    /// Create a new unique symbol, as `Symbol(description)` would
+    pub fn create_symbol(&mut self, description: Option<&str>) -> crate::RuntimeValue {
+        let id = self.next_symbol_id();
+        let description = description.map(JsString::from);
+        crate::RuntimeValue::unguarded(JsValue::Symbol(Box::new(JsSymbol::new(id, description))))
+    }
+
+    /// Create a Map holding `entries` in order, as `new Map(entries)` would.
+    ///
+    /// Keys keep their type, so number or object keys stay distinct from
+    /// strings; a repeated key keeps its first position and last value.
+    pub fn create_map(
+        &mut self,
+        entries: Vec<(crate::RuntimeValue, crate::RuntimeValue)>,
+    ) -> crate::RuntimeValue {
+        let mut map = index_map_with_capacity(entries.len());
+        for (key, value) in &entries {
+            map.insert(
+                crate::value::JsMapKey::canonical(key.value().clone()),
+                value.value().clone(),
+            );
         }
-        None
+        // `entries` keeps keys and values rooted until the Map holds them
+        crate::RuntimeValue::from_guarded(builtins::map::create_map_from(self, map))
+    }
+
+    /// Create a Set holding `values` in order, as `new Set(values)` would
+    pub fn create_set(&mut self, values: Vec<crate::RuntimeValue>) -> crate::RuntimeValue {
+        let mut set = index_set_new();
+        for value in &values {
+            set.insert(crate::value::JsMapKey::canonical(value.value().clone()));
+        }
+        // `values` keeps the elements rooted until the Set holds them
+        crate::RuntimeValue::from_guarded(builtins::set::create_set_from(self, set))
+    }
+
+    /// Whether `a` and `b` are the same value, as `Object.is` decides: the
+    /// same object, or equal primitives with `NaN` equal to itself and `0`
+    /// distinct from `-0`
+    pub fn values_same(&self, a: &crate::RuntimeValue, b: &crate::RuntimeValue) -> bool {
+        a.value().same_value(b.value())
+    }
+
+    /// Read `object[symbol]`, searching the prototype chain.
+    ///
+    /// Like [`Interpreter::call_method`], this reads data properties and
+    /// doesn't run getters. Fails if `object` isn't an object or `symbol`
+    /// isn't a symbol.
+    pub fn get_property_by_symbol(
+        &mut self,
+        object: &crate::RuntimeValue,
+        symbol: &crate::RuntimeValue,
+    ) -> Result<crate::RuntimeValue, JsError> {
+        let (obj, key) = symbol_property_target(object, symbol)?;
+        let value = obj
+            .borrow()
+            .get_property(&key)
+            .unwrap_or(JsValue::Undefined);
+        let guard = self.heap.create_guard();
+        if let JsValue::Object(o) = &value {
+            guard.guard(o.cheap_clone());
+        }
+        Ok(crate::RuntimeValue::with_guard(value, guard))
+    }
+
+    /// Set `object[symbol] = value` as an own data property.
+    ///
+    /// A host can install `Symbol.iterator` this way to make an object it
+    /// built iterable by scripts.
+    pub fn set_property_by_symbol(
+        &mut self,
+        object: &crate::RuntimeValue,
+        symbol: &crate::RuntimeValue,
+        value: &crate::RuntimeValue,
+    ) -> Result<(), JsError> {
+        let (obj, key) = symbol_property_target(object, symbol)?;
+        obj.borrow_mut().set_property(key, value.value().clone());
+        Ok(())
     }
 
     // ═══════════════════════════════════════════════════════════════════════════
@@ -825,10 +2025,54 @@ impl Interpreter {
 
     /// Write a message to the console at the specified level.
     /// Used by console.log(), console.error(), etc.
-    pub fn console_write(&self, level: ConsoleLevel, message: &str) {
+    ///
+    /// Messages longer than `max_console_message_len` are truncated, and once
+    /// `max_console_output` bytes have been written further messages are
+    /// dropped after a single notice.
+    pub fn console_write(&mut self, level: ConsoleLevel, message: &str) {
+        let truncated;
+        let message = if message.len() > self.max_console_message_len {
+            let mut end = self.max_console_message_len;
+            while !message.is_char_boundary(end) {
+                end -= 1;
+            }
+            let (kept, rest) = message.split_at(end);
+            truncated = format!("{}… ({} more characters)", kept, rest.chars().count());
+            truncated.as_str()
+        } else {
+            message
+        };
+
+        if let Some(limit) = self.max_console_output {
+            if self.console_dropped > 0 || self.console_bytes_written + message.len() > limit {
+                if self.console_dropped == 0 {
+                    self.console_provider
+                        .write(ConsoleLevel::Warn, "console output limit reached");
+                }
+                self.console_dropped += 1;
+                return;
+            }
+            self.console_bytes_written += message.len();
+        }
         self.console_provider.write(level, message);
     }
 
+    /// Change the console limits set by `InterpreterConfig::max_console_message_len`
+    /// and `InterpreterConfig::max_console_output`.
+    ///
+    /// Resets the output budget and the dropped-message count.
+    pub fn set_console_limits(&mut self, max_message_len: usize, max_output: Option<usize>) {
+        self.max_console_message_len = max_message_len;
+        self.max_console_output = max_output;
+        self.console_bytes_written = 0;
+        self.console_dropped = 0;
+    }
+
+    /// Number of console messages dropped because the output limit was reached
+    pub fn console_dropped_messages(&self) -> usize {
+        self.console_dropped
+    }
+
     /// Clear the console.
     /// Used by console.clear()
     pub fn console_clear(&self) {
@@ -862,6 +2106,10 @@ impl Interpreter {
         // Check both registered internal modules and FFI-registered modules
         self.internal_modules.contains_key(specifier)
             || self.internal_module_cache.contains_key(specifier)
+            || self
+                .shared_modules
+                .as_ref()
+                .is_some_and(|shared| shared.contains(specifier))
     }
 
     // ═══════════════════════════════════════════════════════════════════════════
@@ -875,7 +2123,12 @@ impl Interpreter {
     /// contains `import { foo } from "./utils"`, it will resolve to
     /// `/project/src/utils`.
     ///
-    /// If no path is provided, relative imports will be treated as bare specifiers.
+    /// A path without a leading `/` is rooted first (see [`ModulePath::entry`]),
+    /// so `main.ts` runs as `/main.ts`. If no path is provided, relative imports
+    /// resolve to rootless paths (`./utils` becomes `utils`); mixing them with a
+    /// bare import of the same name is rejected as ambiguous.
+    ///
+    /// [`ModulePath::entry`]: crate::ModulePath::entry
     ///
     /// Returns StepResult which may indicate:
     /// - Complete: execution finished with a value
@@ -886,22 +2139,20 @@ impl Interpreter {
         source: &str,
         module_path: Option<crate::ModulePath>,
     ) -> Result<StepResult, JsError> {
-        use crate::compiler::Compiler;
         use bytecode_vm::BytecodeVM;
 
-        // Set main module path if this is the entry point
-        if self.main_module_path.is_none() {
-            self.main_module_path = module_path.clone();
-        }
-        self.current_module_path = module_path.clone();
+        let module_path = self.enter_prepare(module_path);
 
         // Parse the source
-        let mut parser = Parser::new(source, &mut self.string_dict);
-        let program = parser.parse_program()?;
+        let timer = self.start_metrics_timer();
+        let program = self.parse_module_source(module_path.as_ref(), source)?;
+        self.record_module_parse(module_path.as_ref(), timer);
 
         // Collect all import requests with resolved paths
         // For main module, importer is None (we pass module_path for resolution but not as importer)
-        let imports = self.collect_import_requests_internal(&program, module_path.as_ref(), None);
+        let imports =
+            self.collect_import_requests_internal(&program, module_path.as_ref(), None)?;
+        let imports = self.with_internal_module_imports(imports)?;
 
         // Filter to only missing imports and deduplicate
         let missing = self.filter_missing_imports(imports);
@@ -910,7 +2161,20 @@ impl Interpreter {
         if !missing.is_empty() {
             // Save the program for later execution when imports are provided
             self.pending_program = Some(program);
-            return Ok(StepResult::NeedImports(missing));
+            // Imports all served from the module source map run right away
+            return match self.request_imports(missing)? {
+                StepResult::Continue => loop {
+                    match self.step()? {
+                        StepResult::Continue => continue,
+                        result => break Ok(result),
+                    }
+                },
+                result => Ok(result),
+            };
+        }
+
+        if let Some(path) = &module_path {
+            self.record_module_flight(flight_recorder::FlightEventKind::ModuleStart, path);
         }
 
         // Create module environment for main module (if module_path is provided)
@@ -919,7 +2183,7 @@ impl Interpreter {
             let saved = self.env.cheap_clone();
             let module_env = self.create_module_environment();
             // Root the module environment - it must persist for live bindings
-            self.root_guard.guard(module_env.clone());
+            self.module_guard.guard(module_env.clone());
             self.env = module_env.cheap_clone();
             (Some(saved), Some(module_env))
         } else {
@@ -930,17 +2194,22 @@ impl Interpreter {
         self.setup_import_bindings(&program)?;
 
         // Compile the program to bytecode
-        let chunk = if let Some(ref path) = module_path {
-            Compiler::compile_program_with_source(&program, path.as_str().to_string())?
-        } else {
-            Compiler::compile_program(&program)?
-        };
+        let timer = self.start_metrics_timer();
+        let chunk = self.compile_entry_chunk(&program, module_path.as_ref())?;
+        self.record_module_compile(module_path.as_ref(), timer, &chunk);
 
         // Run the bytecode VM
         let vm_guard = self.heap.create_guard();
-        let vm = BytecodeVM::with_guard(chunk, JsValue::Object(self.global.clone()), vm_guard);
+        let this_value = self.top_level_this(&program);
+        let vm = BytecodeVM::with_guard(chunk, this_value, vm_guard);
+
+        let result = self
+            .run_vm_to_completion(vm)
+            .map_err(|err| self.with_flight_log(err));
 
-        let result = self.run_vm_to_completion(vm);
+        if let Ok(StepResult::Complete(_)) = &result {
+            self.retain_completed_scope(module_env.as_ref());
+        }
 
         // Restore environment and finalize exports if we used a module environment
         if let (Some(saved), Some(module_env)) = (saved_env, module_env) {
@@ -1036,8 +2305,10 @@ impl Interpreter {
             }
         }
 
+        self.seal_module_namespace(&module_obj);
+
         // Root the module namespace object (lives forever)
-        self.root_guard.guard(module_obj.clone());
+        self.module_guard.guard(module_obj.clone());
 
         // Cache it by normalized path
         self.loaded_modules.insert(module_path, module_obj);
@@ -1051,17 +2322,24 @@ impl Interpreter {
         use bytecode_vm::VmResult;
 
         let result = vm.run(self);
+        if !matches!(result, VmResult::Error(_)) {
+            self.run_thenable_jobs()?;
+        }
 
         match result {
             VmResult::Complete(guarded) => {
-                // Check if there are pending orders to return
+                // Check if there are pending orders to return, once no job
+                // that could create more is left to run
                 if !self.pending_orders.is_empty() {
-                    let pending = mem::take(&mut self.pending_orders);
+                    if self.has_runnable_jobs() {
+                        return Ok(StepResult::Continue);
+                    }
+                    let pending = self.take_pending_orders();
                     let cancelled = mem::take(&mut self.cancelled_orders);
                     return Ok(StepResult::Suspended { pending, cancelled });
                 }
                 // Check for suspended order awaits or waiting async contexts
-                if self.suspended_for_order.is_some() || self.wait_graph.has_waiting_contexts() {
+                if self.has_outstanding_work() {
                     let cancelled = mem::take(&mut self.cancelled_orders);
                     return Ok(StepResult::Suspended {
                         pending: Vec::new(),
@@ -1075,36 +2353,26 @@ impl Interpreter {
             VmResult::Error(err) => Err(self.materialize_thrown_error(err)),
             VmResult::Suspend(suspension) => {
                 // Promise await suspension - add to wait graph
-                let ctx_id = ContextId(self.next_context_id);
-                self.next_context_id += 1;
-
-                // Get or create PromiseId for this promise
-                let promise_key = suspension.waiting_on.cheap_clone();
-                let promise_id = *self.promise_ids.entry(promise_key).or_insert_with(|| {
-                    let id = PromiseId(self.next_promise_id);
-                    self.next_promise_id += 1;
-                    id
-                });
-
-                // Create suspended context
-                let suspended_ctx = SuspendedContext {
-                    id: ctx_id,
-                    state: suspension.state,
-                    waiting_on: suspension.waiting_on,
-                    waiting_on_id: promise_id,
-                    resume_register: suspension.resume_register,
-                };
-
-                self.wait_graph.add_context(suspended_ctx);
+                self.park_context(
+                    suspension.state,
+                    suspension.waiting_on,
+                    suspension.resume_register,
+                    None,
+                );
 
-                let pending = mem::take(&mut self.pending_orders);
+                // A thenable job may have settled the awaited promise already,
+                // and contexts left to run may add orders to this batch
+                if self.has_runnable_jobs() && self.cancelled_orders.is_empty() {
+                    return Ok(StepResult::Continue);
+                }
+                let pending = self.take_pending_orders();
                 let cancelled = mem::take(&mut self.cancelled_orders);
                 Ok(StepResult::Suspended { pending, cancelled })
             }
             VmResult::SuspendForOrder(order_suspension) => {
                 // Order suspension - waiting for host to provide a value
                 self.suspended_for_order = Some(order_suspension);
-                let pending = mem::take(&mut self.pending_orders);
+                let pending = self.take_pending_orders();
                 let cancelled = mem::take(&mut self.cancelled_orders);
                 Ok(StepResult::Suspended { pending, cancelled })
             }
@@ -1119,13 +2387,59 @@ impl Interpreter {
     /// Returns `StepResult::Continue` if more instructions remain,
     /// or a terminal result if execution completed or needs input.
     ///
-    /// Call `prepare()` to set up execution before using `step()`.
+    /// Call `prepare()` to set up execution before using `step()`. Stepping
+    /// a script that can no longer make progress fails with
+    /// `JsError::Stalled`, see `InterpreterConfig::stall_threshold`.
     #[inline]
     pub fn step(&mut self) -> Result<StepResult, JsError> {
-        use bytecode_vm::{BytecodeVM, VmStepResult};
+        let probe_scope = (!self.probes.is_empty()).then(|| self.probe_scope());
+        let result = self.step_once();
+        // Between steps only the active VM may hold scopes and calls open;
+        // suspended ones set theirs aside
+        debug_assert!(
+            self.active_vm.is_some() || self.env_guards.is_empty(),
+            "{} environment guard(s) left after a step",
+            self.env_guards.len()
+        );
+        let mut result = result.map_err(|err| self.with_flight_log(err))?;
+        if self.order_recording {
+            self.record_orders(&result)?;
+        }
+        if self.order_replay.is_some() {
+            result = self.replay_orders(result)?;
+        }
+        if self.stall_threshold > 0 {
+            self.check_stall(&result)?;
+        }
+        if let Some(scope) = probe_scope {
+            self.sample_probes(&result, scope);
+        }
+        self.steps_taken += 1;
+        Ok(result)
+    }
+
+    /// One `step()`, without stall detection
+    fn step_once(&mut self) -> Result<StepResult, JsError> {
+        use bytecode_vm::{BytecodeVM, VmResult, VmStepResult};
+
+        // Completion held back while cancellations were reported
+        if let Some(value) = self.deferred_completion.take() {
+            self.finalize_active_execution();
+            return Ok(StepResult::Complete(value));
+        }
 
         // If there's no active VM, try to set one up from various sources
         if self.active_vm.is_none() {
+            // 0. Run a cleanup callback for a collected FinalizationRegistry target
+            self.queue_finalizations();
+            if self.run_finalization_job()? {
+                return Ok(StepResult::Continue);
+            }
+            // Thenables resolved by host calls made between steps
+            if self.run_thenable_jobs()? {
+                return Ok(StepResult::Continue);
+            }
+
             // 1. Check for order suspension with fulfilled response
             if let Some(order_suspension) = self.suspended_for_order.take() {
                 if let Some(result) = self.order_responses.remove(&order_suspension.order_id) {
@@ -1158,21 +2472,18 @@ impl Interpreter {
                                 &self.heap,
                             );
 
-                            let error_msg = JsValue::String(JsString::from(error.to_string()));
-                            if vm.inject_exception(self, error_msg.clone()) {
+                            let reason = self.order_rejection(error);
+                            if vm.inject_exception(self, reason.value.clone()) {
                                 self.active_vm = Some(Box::new(vm));
                             } else {
-                                let guarded = Guarded::from_value(error_msg, &self.heap);
-                                return Err(JsError::thrown(guarded));
+                                return Err(JsError::thrown(reason));
                             }
                         }
                     }
                 } else {
-                    // Order not yet fulfilled - re-suspend
+                    // Order not yet fulfilled - keep waiting while any
+                    // async call that can run does
                     self.suspended_for_order = Some(order_suspension);
-                    let pending = mem::take(&mut self.pending_orders);
-                    let cancelled = mem::take(&mut self.cancelled_orders);
-                    return Ok(StepResult::Suspended { pending, cancelled });
                 }
             }
 
@@ -1204,6 +2515,9 @@ impl Interpreter {
                                     vm_guard,
                                     &self.heap,
                                 );
+                                if let Some(task) = ctx.task {
+                                    self.enter_task(task);
+                                }
                                 vm.set_resume_value(ctx.resume_register, result_value);
                                 self.active_vm = Some(Box::new(vm));
                             }
@@ -1215,11 +2529,19 @@ impl Interpreter {
                                     vm_guard,
                                     &self.heap,
                                 );
+                                if let Some(task) = ctx.task {
+                                    self.enter_task(task);
+                                }
                                 if vm.inject_exception(self, result_value.clone()) {
                                     self.active_vm = Some(Box::new(vm));
                                 } else {
                                     let guarded = Guarded::from_value(result_value, &self.heap);
-                                    return Err(JsError::thrown(guarded));
+                                    let error = JsError::thrown(guarded);
+                                    if let Some(task) = self.active_task.take() {
+                                        return self
+                                            .process_task_result(task, VmResult::Error(error));
+                                    }
+                                    return Err(error);
                                 }
                             }
                             PromiseStatus::Pending => {
@@ -1241,28 +2563,66 @@ impl Interpreter {
                     // Still needs imports or other action
                     return Ok(setup_result);
                 }
+                if self.active_vm.is_none() {
+                    // Nested imports were served from the module source map;
+                    // the next step runs them
+                    return Ok(StepResult::Continue);
+                }
             }
         }
 
+        // Hold the script back until the host drains enough orders
+        if self.active_vm.is_some()
+            && self.pending_order_overflow == crate::PendingOrderOverflow::Suspend
+            && self.outstanding_orders.len() >= self.max_pending_orders
+        {
+            let pending = self.take_pending_orders();
+            let cancelled = mem::take(&mut self.cancelled_orders);
+            return Ok(StepResult::Suspended { pending, cancelled });
+        }
+
         // Take the VM out temporarily to work with it
         let Some(mut vm) = self.active_vm.take() else {
             // No VM and nothing to resume
             // Check if there are waiting contexts (unresolved promises) or suspended orders
-            if self.suspended_for_order.is_some() || self.wait_graph.has_waiting_contexts() {
-                let pending = mem::take(&mut self.pending_orders);
+            if self.has_outstanding_work() || !self.pending_orders.is_empty() {
+                let pending = self.take_pending_orders();
                 let cancelled = mem::take(&mut self.cancelled_orders);
                 return Ok(StepResult::Suspended { pending, cancelled });
             }
+            // The main execution finished before the async calls it started
+            if let Some(value) = self.held_completion.take() {
+                if !self.cancelled_orders.is_empty() {
+                    self.held_completion = Some(value);
+                    let cancelled = mem::take(&mut self.cancelled_orders);
+                    return Ok(StepResult::Suspended {
+                        pending: Vec::new(),
+                        cancelled,
+                    });
+                }
+                return Ok(StepResult::Complete(value));
+            }
             // Truly done
             return Ok(StepResult::Done);
         };
 
-        let step_result = vm.step(self);
+        self.stall_progress = true;
+        let step_result = match self.step_granularity {
+            crate::StepGranularity::Instruction => vm.step(self),
+            crate::StepGranularity::Checkpoint => vm.run_to_checkpoint(self),
+        };
 
         match step_result {
             VmStepResult::Continue => {
                 // Put VM back and return Continue
                 self.active_vm = Some(vm);
+
+                // In eager mode, hand new orders to the host while the script keeps running
+                if self.order_delivery == crate::OrderDelivery::Eager
+                    && !self.pending_orders.is_empty()
+                {
+                    return Ok(StepResult::Orders(self.take_pending_orders()));
+                }
                 Ok(StepResult::Continue)
             }
             VmStepResult::Terminal(vm_result) => {
@@ -1283,59 +2643,58 @@ impl Interpreter {
     fn process_vm_result(&mut self, result: bytecode_vm::VmResult) -> Result<StepResult, JsError> {
         use bytecode_vm::VmResult;
 
+        if !matches!(result, VmResult::Error(_)) {
+            self.run_thenable_jobs()?;
+        }
+
+        if let Some(task) = self.active_task.take() {
+            return self.process_task_result(task, result);
+        }
+
         match result {
             VmResult::Complete(guarded) => {
-                // Check if there are pending orders to return
-                if !self.pending_orders.is_empty() {
-                    let pending = mem::take(&mut self.pending_orders);
-                    let cancelled = mem::take(&mut self.cancelled_orders);
-                    return Ok(StepResult::Suspended { pending, cancelled });
+                let value = crate::RuntimeValue::from_guarded(guarded);
+                // Complete once the orders and async calls still outstanding
+                // are done with
+                if !self.pending_orders.is_empty() || self.has_outstanding_work() {
+                    self.finalize_active_execution();
+                    self.held_completion = Some(value);
+                    return Ok(StepResult::Continue);
                 }
-                // Check for suspended order awaits or waiting async contexts
-                if self.suspended_for_order.is_some() || self.wait_graph.has_waiting_contexts() {
+                // Report cancellations before completing so the host can stop that work
+                if !self.cancelled_orders.is_empty() {
+                    self.deferred_completion = Some(value);
                     let cancelled = mem::take(&mut self.cancelled_orders);
                     return Ok(StepResult::Suspended {
                         pending: Vec::new(),
                         cancelled,
                     });
                 }
-                Ok(StepResult::Complete(crate::RuntimeValue::from_guarded(
-                    guarded,
-                )))
+                Ok(StepResult::Complete(value))
             }
             VmResult::Error(err) => Err(self.materialize_thrown_error(err)),
             VmResult::Suspend(suspension) => {
                 // Promise await suspension - add to wait graph
-                let ctx_id = ContextId(self.next_context_id);
-                self.next_context_id += 1;
-
-                // Get or create PromiseId for this promise
-                let promise_key = suspension.waiting_on.cheap_clone();
-                let promise_id = *self.promise_ids.entry(promise_key).or_insert_with(|| {
-                    let id = PromiseId(self.next_promise_id);
-                    self.next_promise_id += 1;
-                    id
-                });
-
-                // Create suspended context
-                let suspended_ctx = SuspendedContext {
-                    id: ctx_id,
-                    state: suspension.state,
-                    waiting_on: suspension.waiting_on,
-                    waiting_on_id: promise_id,
-                    resume_register: suspension.resume_register,
-                };
-
-                self.wait_graph.add_context(suspended_ctx);
+                self.park_context(
+                    suspension.state,
+                    suspension.waiting_on,
+                    suspension.resume_register,
+                    None,
+                );
 
-                let pending = mem::take(&mut self.pending_orders);
+                // A thenable job may have settled the awaited promise already,
+                // and contexts left to run may add orders to this batch
+                if self.has_runnable_jobs() && self.cancelled_orders.is_empty() {
+                    return Ok(StepResult::Continue);
+                }
+                let pending = self.take_pending_orders();
                 let cancelled = mem::take(&mut self.cancelled_orders);
                 Ok(StepResult::Suspended { pending, cancelled })
             }
             VmResult::SuspendForOrder(order_suspension) => {
                 // Order suspension - waiting for host to provide a value
                 self.suspended_for_order = Some(order_suspension);
-                let pending = mem::take(&mut self.pending_orders);
+                let pending = self.take_pending_orders();
                 let cancelled = mem::take(&mut self.cancelled_orders);
                 Ok(StepResult::Suspended { pending, cancelled })
             }
@@ -1345,6 +2704,142 @@ impl Interpreter {
         }
     }
 
+    /// Add a suspended VM state waiting on `waiting_on` to the wait graph
+    fn park_context(
+        &mut self,
+        state: bytecode_vm::SavedVmState,
+        waiting_on: Gc<JsObject>,
+        resume_register: crate::compiler::Register,
+        task: Option<AsyncTask>,
+    ) {
+        let ctx_id = ContextId(self.next_context_id);
+        self.next_context_id += 1;
+
+        // Get or create PromiseId for this promise
+        let promise_key = waiting_on.cheap_clone();
+        let promise_id = *self.promise_ids.entry(promise_key).or_insert_with(|| {
+            let id = PromiseId(self.next_promise_id);
+            self.next_promise_id += 1;
+            id
+        });
+
+        self.wait_graph.add_context(SuspendedContext {
+            id: ctx_id,
+            state,
+            waiting_on,
+            waiting_on_id: promise_id,
+            resume_register,
+            task,
+        });
+    }
+
+    /// Park an async call that suspended in `env`, with `frame_env` as its
+    /// function's environment and `calls` as its call stack entries, as a
+    /// task of its own and return the promise for its result
+    pub(crate) fn detach_async_task(
+        &mut self,
+        state: bytecode_vm::SavedVmState,
+        waiting_on: Gc<JsObject>,
+        resume_register: crate::compiler::Register,
+        env: Gc<JsObject>,
+        frame_env: Gc<JsObject>,
+        calls: Vec<StackFrame>,
+    ) -> Gc<JsObject> {
+        let guard = self.heap.create_guard();
+        guard.set_label("async task");
+        guard.guard(env.cheap_clone());
+        guard.guard(frame_env.cheap_clone());
+        guard.guard(waiting_on.cheap_clone());
+        let completion = builtins::promise::create_promise(self, &guard);
+        let task = AsyncTask {
+            completion: completion.cheap_clone(),
+            env,
+            frame_env,
+            calls,
+            guard,
+        };
+        self.park_context(state, waiting_on, resume_register, Some(task));
+        completion
+    }
+
+    /// Make a detached async call's environment and call stack current, to
+    /// run it as the active VM
+    fn enter_task(&mut self, task: AsyncTask) {
+        // Stands in for the guard the call pushed for its environment
+        let env_depth = self.env_guards.len();
+        let env_guard = self.heap.create_guard();
+        env_guard.guard(task.frame_env.cheap_clone());
+        self.push_env_guard(env_guard);
+        let outer_env = mem::replace(&mut self.env, task.env);
+        let call_depth = self.call_stack.len();
+        self.call_stack.extend(task.calls);
+        self.active_task = Some(ActiveTask {
+            completion: task.completion,
+            guard: task.guard,
+            frame_env: task.frame_env,
+            outer_env,
+            env_depth,
+            call_depth,
+        });
+    }
+
+    /// Handle a detached async call stopping: settle its promise when it
+    /// finished, or park it again when it suspended
+    fn process_task_result(
+        &mut self,
+        task: ActiveTask,
+        result: bytecode_vm::VmResult,
+    ) -> Result<StepResult, JsError> {
+        use bytecode_vm::VmResult;
+
+        // What the task left on the guard stack is its own environment guard;
+        // a suspended VM has taken its other entries with its state
+        let env = mem::replace(&mut self.env, task.outer_env);
+        let guard = self.heap.create_guard();
+        guard.set_label("async task");
+        guard.guard(task.completion.cheap_clone());
+        guard.guard(env.cheap_clone());
+        guard.guard(task.frame_env.cheap_clone());
+        self.env_guards.truncate(task.env_depth);
+        let calls = self.call_stack.split_off(task.call_depth);
+
+        let (state, waiting_on, resume_register) = match result {
+            VmResult::Complete(guarded) => {
+                builtins::promise::resolve_promise_value(self, &task.completion, guarded.value)?;
+                return Ok(StepResult::Continue);
+            }
+            VmResult::Error(err) if err.is_termination() => return Err(err),
+            VmResult::Error(err) => {
+                builtins::promise::reject_promise_value(self, &task.completion, err.to_value())?;
+                return Ok(StepResult::Continue);
+            }
+            VmResult::Suspend(suspension) => (
+                suspension.state,
+                suspension.waiting_on,
+                suspension.resume_register,
+            ),
+            VmResult::SuspendForOrder(suspension) => {
+                let guard = self.heap.create_guard();
+                let promise = self.create_tracked_order_promise(&guard, suspension.order_id);
+                (suspension.state, promise, suspension.resume_register)
+            }
+            VmResult::Yield(_) | VmResult::YieldStar(_) => {
+                return Err(JsError::internal_error("An async call cannot yield"));
+            }
+        };
+
+        guard.guard(waiting_on.cheap_clone());
+        let task = AsyncTask {
+            completion: task.completion,
+            env,
+            frame_env: task.frame_env,
+            calls,
+            guard,
+        };
+        self.park_context(state, waiting_on, resume_register, Some(task));
+        Ok(StepResult::Continue)
+    }
+
     /// Finalize active execution (restore environment, finalize exports)
     fn finalize_active_execution(&mut self) {
         // Take state
@@ -1352,6 +2847,11 @@ impl Interpreter {
         let module_env = self.active_module_env.take();
         let module_path = self.active_module_path.take();
 
+        // Queries don't replace the scope they were evaluated against
+        if self.active_context_eval.take().is_none() {
+            self.retain_completed_scope(module_env.as_ref());
+        }
+
         // Restore environment and finalize exports if we used a module environment
         if let (Some(saved), Some(env)) = (saved_env, module_env) {
             self.env = saved;
@@ -1363,31 +2863,271 @@ impl Interpreter {
         }
     }
 
+    /// Remember the top-level scope of a completed run when `retain_main_scope` is set
+    fn retain_completed_scope(&mut self, module_env: Option<&Gc<JsObject>>) {
+        if !self.retain_main_scope {
+            return;
+        }
+        let env = module_env
+            .cloned()
+            .unwrap_or_else(|| self.env.cheap_clone());
+        let guard = self.heap.create_guard();
+        guard.guard(env.cheap_clone());
+        self.retained_main_scope = Some((env, guard));
+    }
+
+    /// Evaluate `source` against the top-level scope of the completed main run.
+    ///
+    /// The code runs in a fresh scope whose parent is the main module's
+    /// top-level environment, so its consts, lets, functions and imported
+    /// bindings are visible while new declarations stay local to the query.
+    /// Requires `InterpreterConfig::retain_main_scope`.
+    ///
+    /// Returns like `eval`: `Complete` with the value of the last expression,
+    /// or `Suspended` when the query awaits orders (continue with `step()`).
+    pub fn eval_in_context(&mut self, source: &str) -> Result<StepResult, JsError> {
+        let Some((main_env, _)) = &self.retained_main_scope else {
+            return Err(JsError::internal_error(
+                "eval_in_context requires a completed run with retain_main_scope enabled",
+            ));
+        };
+        let main_env = main_env.cheap_clone();
+        self.run_in_fresh_scope("eval_in_context", source, main_env, Vec::new())
+    }
+
+    /// Construct an instance of a script-defined class, as `new Class(...args)`.
+    ///
+    /// This is a full [[Construct]]: the instance gets the class prototype,
+    /// `new.target` is the class, field initializers and base class
+    /// constructors run in order. Returns like `eval_in_context`: `Complete`
+    /// with the instance, or `Suspended` when the constructor waits on orders
+    /// (continue with `step()`).
+    pub fn construct(
+        &mut self,
+        class: &crate::RuntimeValue,
+        args: &[crate::RuntimeValue],
+    ) -> Result<StepResult, JsError> {
+        if !class.value().is_callable() {
+            return Err(JsError::type_error("construct target is not a constructor"));
+        }
+        let guard = self.heap.create_guard();
+        let args = self.create_array_from(&guard, args.iter().map(|a| a.value().clone()).collect());
+        let global_env = self.env.cheap_clone();
+        self.run_in_fresh_scope(
+            "construct",
+            "new __host_callee__(...__host_args__)",
+            global_env,
+            vec![
+                ("__host_callee__", class.value().clone()),
+                ("__host_args__", JsValue::Object(args)),
+            ],
+        )
+    }
+
+    /// Call `object[name](...args)` with `this` bound to `object`.
+    ///
+    /// A returned promise is awaited, so async methods complete with their
+    /// settled value. Returns like `eval_in_context`: `Complete` with the
+    /// result, or `Suspended` when the method waits on orders.
+    pub fn call_method(
+        &mut self,
+        object: &crate::RuntimeValue,
+        name: &str,
+        args: &[crate::RuntimeValue],
+    ) -> Result<StepResult, JsError> {
+        let JsValue::Object(obj) = object.value() else {
+            return Err(JsError::type_error("Cannot call method on non-object"));
+        };
+        let key = self.property_key(name);
+        let method = obj.borrow().get_property(&key);
+        if !method.as_ref().is_some_and(JsValue::is_callable) {
+            return Err(JsError::type_error(format!("{} is not a function", name)));
+        }
+        let guard = self.heap.create_guard();
+        let args = self.create_array_from(&guard, args.iter().map(|a| a.value().clone()).collect());
+        let global_env = self.env.cheap_clone();
+        self.run_in_fresh_scope(
+            "call_method",
+            "await __host_this__[__host_name__](...__host_args__)",
+            global_env,
+            vec![
+                ("__host_this__", object.value().clone()),
+                ("__host_name__", JsValue::String(JsString::from(name))),
+                ("__host_args__", JsValue::Object(args)),
+            ],
+        )
+    }
+
+    /// Run `source` as the active execution in a new scope under `parent`
+    /// with `bindings` predeclared, stepping until it completes or suspends.
+    ///
+    /// Like a query, the run leaves any retained main scope untouched.
+    fn run_in_fresh_scope(
+        &mut self,
+        caller: &str,
+        source: &str,
+        parent: Gc<JsObject>,
+        bindings: Vec<(&str, JsValue)>,
+    ) -> Result<StepResult, JsError> {
+        use bytecode_vm::BytecodeVM;
+
+        if self.execution_in_progress() {
+            return Err(JsError::internal_error(format!(
+                "{} cannot run while an execution is in progress",
+                caller
+            )));
+        }
+
+        let program = self.parse_program(source)?;
+        let chunk = self.compile_program_chunk(&program, None, false)?;
+
+        let (scope, scope_guard) = create_environment_unrooted(&self.heap, Some(parent));
+        let saved_env = mem::replace(&mut self.env, scope.cheap_clone());
+        for (name, value) in bindings {
+            let name = self.intern(name);
+            self.env_define(name, value, false);
+        }
+
+        let vm_guard = self.heap.create_guard();
+        let this_value = self.top_level_this(&program);
+        let vm = BytecodeVM::with_guard(chunk, this_value, vm_guard);
+
+        self.active_vm = Some(Box::new(vm));
+        self.active_module_path = None;
+        self.active_saved_env = Some(saved_env);
+        self.active_module_env = Some(scope);
+        self.active_context_eval = Some(scope_guard);
+
+        self.step_fresh_scope_run()
+    }
+
+    /// Whether a run, its pending program or work it left behind is still
+    /// in progress
+    fn execution_in_progress(&self) -> bool {
+        self.active_vm.is_some() || self.pending_program.is_some() || self.has_outstanding_work()
+    }
+
+    /// Step the run started by `run_in_fresh_scope` until it completes or
+    /// suspends, tearing it down if it fails
+    fn step_fresh_scope_run(&mut self) -> Result<StepResult, JsError> {
+        loop {
+            match self.step() {
+                Ok(StepResult::Continue) => continue,
+                Ok(result) => return Ok(result),
+                Err(err) => {
+                    self.active_vm = None;
+                    self.finalize_active_execution();
+                    return Err(err);
+                }
+            }
+        }
+    }
+
     /// Prepare code for step-based execution without running it.
     ///
     /// After calling this, use `step()` to execute one instruction at a time.
     /// Returns `Ok(())` if setup succeeded, or an error if parsing/compilation failed
-    /// or if imports are needed.
+    /// or if imports are needed. `module_path` is handled as in [`Interpreter::eval`].
     pub fn prepare(
         &mut self,
         source: &str,
         module_path: Option<crate::ModulePath>,
     ) -> Result<StepResult, JsError> {
-        use crate::compiler::Compiler;
-        use bytecode_vm::BytecodeVM;
+        let module_path = self.enter_prepare(module_path);
+
+        // Parse the source
+        let timer = self.start_metrics_timer();
+        let program = self.parse_module_source(module_path.as_ref(), source)?;
+        self.record_module_parse(module_path.as_ref(), timer);
+
+        self.prepare_program(program, module_path)
+    }
+
+    /// Like [`Interpreter::prepare`], parsing `source` as `lang` whatever
+    /// the extension of `module_path`
+    pub fn prepare_with_lang(
+        &mut self,
+        source: &str,
+        module_path: Option<crate::ModulePath>,
+        lang: crate::Lang,
+    ) -> Result<StepResult, JsError> {
+        let previous = self.lang_override.replace(lang);
+        let result = self.prepare(source, module_path);
+        self.lang_override = previous;
+        result
+    }
+
+    /// Parse `source` without running it, for [`Interpreter::prepare_ast`]
+    /// and [`Interpreter::provide_module_ast`].
+    ///
+    /// The program may be handed to other interpreters too: each re-interns
+    /// the names it binds, so strings from a foreign [`StringDict`] never
+    /// reach its environments. `module_path` is only used for metrics.
+    pub fn parse(
+        &mut self,
+        source: &str,
+        module_path: Option<crate::ModulePath>,
+    ) -> Result<Program, JsError> {
+        let module_path = module_path.map(|path| crate::ModulePath::entry(path.as_str()));
+        let timer = self.start_metrics_timer();
+        let program = self.parse_module_source(module_path.as_ref(), source)?;
+        self.record_module_parse(module_path.as_ref(), timer);
+        Ok(program)
+    }
+
+    /// Like [`Interpreter::prepare`], for a program that is already parsed.
+    ///
+    /// The program may come from [`Interpreter::parse`] on any interpreter,
+    /// or from a host that builds the AST itself.
+    pub fn prepare_ast(
+        &mut self,
+        program: Program,
+        module_path: Option<crate::ModulePath>,
+    ) -> Result<StepResult, JsError> {
+        let module_path = self.enter_prepare(module_path);
+        let program = self.adopt_program(program);
+        self.prepare_program(program, module_path)
+    }
+
+    /// Drop the source of a program parsed elsewhere unless function source is retained
+    fn adopt_program(&self, mut program: Program) -> Program {
+        if !self.retain_function_source {
+            program.source = None;
+        }
+        program
+    }
+
+    /// Reset per-run state for a new entry module and return its normalized path
+    fn enter_prepare(
+        &mut self,
+        module_path: Option<crate::ModulePath>,
+    ) -> Option<crate::ModulePath> {
+        // Requests from an earlier execution no longer apply
+        self.clear_import_requests();
+        self.constant_imports_active = self.fold_constant_imports;
+        self.record_global_baseline();
+        let module_path = module_path.map(|path| crate::ModulePath::entry(path.as_str()));
 
         // Set main module path if this is the entry point
         if self.main_module_path.is_none() {
             self.main_module_path = module_path.clone();
         }
         self.current_module_path = module_path.clone();
+        module_path
+    }
 
-        // Parse the source
-        let mut parser = Parser::new(source, &mut self.string_dict);
-        let program = parser.parse_program()?;
+    /// Set up step-based execution of a parsed entry program, as `prepare` does
+    fn prepare_program(
+        &mut self,
+        program: Program,
+        module_path: Option<crate::ModulePath>,
+    ) -> Result<StepResult, JsError> {
+        use bytecode_vm::BytecodeVM;
 
         // Collect all import requests with resolved paths
-        let imports = self.collect_import_requests_internal(&program, module_path.as_ref(), None);
+        let imports =
+            self.collect_import_requests_internal(&program, module_path.as_ref(), None)?;
+        let imports = self.with_internal_module_imports(imports)?;
 
         // Filter to only missing imports and deduplicate
         let missing = self.filter_missing_imports(imports);
@@ -1396,14 +3136,18 @@ impl Interpreter {
         if !missing.is_empty() {
             // Save the program for later execution when imports are provided
             self.pending_program = Some(program);
-            return Ok(StepResult::NeedImports(missing));
+            return self.request_imports(missing);
+        }
+
+        if let Some(path) = &module_path {
+            self.record_module_flight(flight_recorder::FlightEventKind::ModuleStart, path);
         }
 
         // Create module environment for main module (if module_path is provided)
         let (saved_env, module_env) = if module_path.is_some() {
             let saved = self.env.cheap_clone();
             let module_env = self.create_module_environment();
-            self.root_guard.guard(module_env.clone());
+            self.module_guard.guard(module_env.clone());
             self.env = module_env.cheap_clone();
             (Some(saved), Some(module_env))
         } else {
@@ -1414,15 +3158,14 @@ impl Interpreter {
         self.setup_import_bindings(&program)?;
 
         // Compile the program to bytecode
-        let chunk = if let Some(ref path) = module_path {
-            Compiler::compile_program_with_source(&program, path.as_str().to_string())?
-        } else {
-            Compiler::compile_program(&program)?
-        };
+        let timer = self.start_metrics_timer();
+        let chunk = self.compile_entry_chunk(&program, module_path.as_ref())?;
+        self.record_module_compile(module_path.as_ref(), timer, &chunk);
 
         // Create VM but don't run it
         let vm_guard = self.heap.create_guard();
-        let vm = BytecodeVM::with_guard(chunk, JsValue::Object(self.global.clone()), vm_guard);
+        let this_value = self.top_level_this(&program);
+        let vm = BytecodeVM::with_guard(chunk, this_value, vm_guard);
 
         // Store VM and state for step-based execution
         self.active_vm = Some(Box::new(vm));
@@ -1433,65 +3176,44 @@ impl Interpreter {
         Ok(StepResult::Continue)
     }
 
-    /// Process any pending module sources that are ready to execute.
-    /// This executes modules in dependency order until all are executed
-    /// or some still have missing imports.
-    /// Process pending modules that have all their imports satisfied.
-    /// Returns a list of unprovided imports if some pending modules still need dependencies
-    /// that the host hasn't provided yet.
-    fn process_pending_modules(&mut self) -> Result<Vec<crate::ImportRequest>, JsError> {
-        loop {
-            // Collect all unprovided imports across all pending modules
-            // (imports that the host hasn't provided yet)
-            let mut all_unprovided: Vec<crate::ImportRequest> = Vec::new();
-            let mut ready_modules: Vec<crate::ModulePath> = Vec::new();
-
-            // Clone keys to avoid borrow issues
-            let pending_keys: Vec<crate::ModulePath> =
-                self.pending_module_sources.keys().cloned().collect();
-
-            for module_path in &pending_keys {
-                // Skip if already loaded
-                if self.loaded_modules.contains_key(module_path) {
-                    continue;
-                }
+    /// The pending modules whose imports are all loaded, and the imports of
+    /// the others that the host hasn't provided yet
+    fn scan_pending_modules(
+        &mut self,
+    ) -> Result<(Vec<crate::ModulePath>, Vec<crate::ImportRequest>), JsError> {
+        // Collect all unprovided imports across all pending modules
+        // (imports that the host hasn't provided yet)
+        let mut all_unprovided: Vec<crate::ImportRequest> = Vec::new();
+        let mut ready_modules: Vec<crate::ModulePath> = Vec::new();
+
+        for module_path in self.pending_modules_in_order() {
+            // Skip if already loaded
+            if self.loaded_modules.contains_key(&module_path) {
+                continue;
+            }
 
-                // Get the program to check its imports
-                if let Some(program) = self.pending_module_sources.get(module_path) {
-                    let imports = self.collect_import_requests(program, Some(module_path));
-                    // Check if all imports are LOADED (not just provided)
-                    let missing_from_loaded = self.filter_missing_imports(imports.clone());
+            let imports = self.pending_module_imports(&module_path)?;
+            // Check if all imports are LOADED (not just provided)
+            let missing_from_loaded = self.filter_missing_imports(imports.clone());
 
-                    if missing_from_loaded.is_empty() {
-                        // All imports are loaded - this module is ready to execute
-                        ready_modules.push(module_path.clone());
-                    } else {
-                        // Check which imports the HOST still needs to provide
-                        let unprovided = self.filter_unprovided_imports(imports);
-                        for req in unprovided {
-                            let already_in_list = all_unprovided
-                                .iter()
-                                .any(|r| r.resolved_path == req.resolved_path);
-                            if !already_in_list {
-                                all_unprovided.push(req);
-                            }
-                        }
+            if missing_from_loaded.is_empty() {
+                // All imports are loaded - this module is ready to execute
+                ready_modules.push(module_path);
+            } else {
+                // Check which imports the HOST still needs to provide
+                let unprovided = self.filter_unprovided_imports(imports);
+                for req in unprovided {
+                    let already_in_list = all_unprovided
+                        .iter()
+                        .any(|r| r.resolved_path == req.resolved_path);
+                    if !already_in_list {
+                        all_unprovided.push(req);
                     }
                 }
             }
-
-            // If we have modules ready to execute, execute them
-            if !ready_modules.is_empty() {
-                for module_path in ready_modules {
-                    self.execute_pending_module(&module_path)?;
-                }
-                // Continue the loop to check if more modules are now ready
-                continue;
-            }
-
-            // Return any imports the host still needs to provide
-            return Ok(all_unprovided);
         }
+
+        Ok((ready_modules, all_unprovided))
     }
 
     /// Set up VM from a pending program (called when imports have been provided)
@@ -1499,22 +3221,30 @@ impl Interpreter {
         &mut self,
         program: crate::ast::Program,
     ) -> Result<StepResult, JsError> {
-        use crate::compiler::Compiler;
         use bytecode_vm::BytecodeVM;
 
         let module_path = self.current_module_path.clone();
 
         // Collect all import requests with resolved paths
-        let imports = self.collect_import_requests_internal(&program, module_path.as_ref(), None);
+        let imports =
+            self.collect_import_requests_internal(&program, module_path.as_ref(), None)?;
+        let imports = self.with_internal_module_imports(imports)?;
+        self.note_import_discovery(&imports);
 
         // Check what the HOST still needs to provide (not in loaded_modules OR pending_module_sources)
-        let unprovided = self.filter_unprovided_imports(imports.clone());
+        let mut unprovided = self.filter_unprovided_imports(imports.clone());
+        if !unprovided.is_empty() {
+            // Ask for what the modules provided so far import in the same
+            // batch, so a host that provided only some is not stalled
+            let (_, pending_unprovided) = self.scan_pending_modules()?;
+            unprovided.extend(pending_unprovided);
+        }
         let unprovided = Self::dedupe_import_requests(unprovided);
 
         if !unprovided.is_empty() {
             // Host needs to provide these modules
             self.pending_program = Some(program);
-            return Ok(StepResult::NeedImports(unprovided));
+            return self.request_imports(unprovided);
         }
 
         // Execute any pending modules before setting up the main program
@@ -1523,7 +3253,7 @@ impl Interpreter {
         if !pending_module_unprovided.is_empty() {
             // Pending modules have dependencies the host hasn't provided yet
             self.pending_program = Some(program);
-            return Ok(StepResult::NeedImports(pending_module_unprovided));
+            return self.request_imports(pending_module_unprovided);
         }
 
         // After processing, verify all main program imports are now loaded
@@ -1533,14 +3263,18 @@ impl Interpreter {
             // But handle it gracefully
             self.pending_program = Some(program);
             let unprovided = self.filter_unprovided_imports(still_missing);
-            return Ok(StepResult::NeedImports(unprovided));
+            return self.request_imports(unprovided);
+        }
+
+        if let Some(path) = &module_path {
+            self.record_module_flight(flight_recorder::FlightEventKind::ModuleStart, path);
         }
 
         // Create module environment for main module (if module_path is provided)
         let (saved_env, module_env) = if module_path.is_some() {
             let saved = self.env.cheap_clone();
             let module_env = self.create_module_environment();
-            self.root_guard.guard(module_env.clone());
+            self.module_guard.guard(module_env.clone());
             self.env = module_env.cheap_clone();
             (Some(saved), Some(module_env))
         } else {
@@ -1551,15 +3285,14 @@ impl Interpreter {
         self.setup_import_bindings(&program)?;
 
         // Compile the program to bytecode
-        let chunk = if let Some(ref path) = module_path {
-            Compiler::compile_program_with_source(&program, path.as_str().to_string())?
-        } else {
-            Compiler::compile_program(&program)?
-        };
+        let timer = self.start_metrics_timer();
+        let chunk = self.compile_entry_chunk(&program, module_path.as_ref())?;
+        self.record_module_compile(module_path.as_ref(), timer, &chunk);
 
         // Create VM
         let vm_guard = self.heap.create_guard();
-        let vm = BytecodeVM::with_guard(chunk, JsValue::Object(self.global.clone()), vm_guard);
+        let this_value = self.top_level_this(&program);
+        let vm = BytecodeVM::with_guard(chunk, this_value, vm_guard);
 
         // Store VM and state for step-based execution
         self.active_vm = Some(Box::new(vm));
@@ -1574,10 +3307,15 @@ impl Interpreter {
     ///
     /// JsError::ThrownValue contains a JsValue that may have Gc pointers.
     /// These pointers become invalid when the interpreter/heap is dropped.
-    /// This function extracts the error information while it's still valid.
+    /// This function extracts the error information while it's still valid:
+    /// name, message, stack and a JSON snapshot of the other own properties.
     fn materialize_thrown_error(&mut self, error: JsError) -> JsError {
         match error {
             JsError::ThrownValue { guarded } => {
+                let stack = match self.last_throw.take() {
+                    Some((thrown, stack)) if thrown.value.same_value(&guarded.value) => stack,
+                    _ => Vec::new(),
+                };
                 // Extract error name and message from the thrown value
                 if let JsValue::Object(obj) = &guarded.value {
                     let name_key = self.property_key("name");
@@ -1596,7 +3334,9 @@ impl Interpreter {
                     JsError::RuntimeError {
                         kind: name,
                         message,
-                        stack: Vec::new(),
+                        stack,
+                        properties: thrown_properties(&guarded.value),
+                        flight_log: None,
                     }
                 } else {
                     // Non-object thrown value - convert to string
@@ -1604,7 +3344,9 @@ impl Interpreter {
                     JsError::RuntimeError {
                         kind: "Error".to_string(),
                         message,
-                        stack: Vec::new(),
+                        stack,
+                        properties: None,
+                        flight_log: None,
                     }
                 }
             }
@@ -1613,26 +3355,375 @@ impl Interpreter {
         }
     }
 
+    /// A value equivalent to `error`, for rejecting an order or throwing it
+    /// into another run.
+    ///
+    /// An error a script threw comes back as an error object with the
+    /// prototype its name selects, its message, a stack string with the
+    /// frames it was thrown through, and the other properties it had, such
+    /// as a `code`. Errors of the host side with more to them, like
+    /// [`JsError::IntegrityMismatch`], also carry their fields as JSON in a
+    /// `hostData` property. A value still held by `error` is returned as it
+    /// is.
+    pub fn error_to_value(&mut self, error: &JsError) -> crate::RuntimeValue {
+        if let JsError::ThrownValue { guarded } = error {
+            return crate::RuntimeValue::from_guarded(Guarded::from_value(
+                guarded.value.clone(),
+                &self.heap,
+            ));
+        }
+        let (value, guard) = match builtins::error::create_error_object(self, error) {
+            (JsValue::Undefined, _) => {
+                let generic = JsError::RuntimeError {
+                    kind: "Error".to_string(),
+                    message: error.to_string(),
+                    stack: Vec::new(),
+                    properties: None,
+                    flight_log: None,
+                };
+                builtins::error::create_error_object(self, &generic)
+            }
+            created => created,
+        };
+        if let (JsValue::Object(obj), Some(host_data)) = (&value, error.host_data()) {
+            let guard = self.heap.create_guard();
+            if let Ok(data) = builtins::json::json_to_js_value_with_guard(self, &host_data, &guard)
+            {
+                let key = self.property_key("hostData");
+                obj.borrow_mut().set_property(key, data);
+            }
+        }
+        match guard {
+            Some(guard) => crate::RuntimeValue::with_guard(value, guard),
+            None => crate::RuntimeValue::unguarded(value),
+        }
+    }
+
     /// Provide a module source for a pending import.
     ///
     /// The `resolved_path` should be the normalized path from `ImportRequest.resolved_path`.
     /// The module is parsed and stored, but not executed until `continue_eval` is called.
     /// This allows collecting all needed imports before execution.
+    ///
+    /// Returns an error if `resolved_path` is not one of the outstanding import
+    /// requests; use `provide_module_unrequested` to pre-seed modules.
     pub fn provide_module(
         &mut self,
         resolved_path: crate::ModulePath,
         source: &str,
     ) -> Result<(), JsError> {
-        // Parse the module
-        let mut parser = Parser::new(source, &mut self.string_dict);
-        let program = parser.parse_program()?;
+        let Some(pos) = self
+            .requested_imports
+            .iter()
+            .position(|req| req.resolved_path == resolved_path)
+        else {
+            return Err(self.unrequested_module_error(&resolved_path));
+        };
+
+        let checked =
+            self.check_module_integrity(&resolved_path, source, self.requested_imports.get(pos));
+        if let Err(err) = checked {
+            self.note_failed_import(&resolved_path);
+            return Err(err);
+        }
+        if self
+            .requested_imports
+            .get(pos)
+            .is_some_and(|req| req.is_json())
+        {
+            return self.provide_json_module(pos, resolved_path, source);
+        }
+        let program = match self.parse_module(&resolved_path, source) {
+            Ok(program) => program,
+            Err(err) if self.module_error_policy == crate::ModuleErrorPolicy::Collect => {
+                self.requested_imports.remove(pos);
+                self.load_failed_module(resolved_path, err);
+                return Ok(());
+            }
+            Err(err) => {
+                self.note_failed_import(&resolved_path);
+                return Err(err);
+            }
+        };
 
         // Store the parsed program for later execution
+        self.requested_imports.remove(pos);
         self.pending_module_sources.insert(resolved_path, program);
 
         Ok(())
     }
 
+    /// Like [`Interpreter::provide_module`], parsing `source` as `lang`
+    /// rather than [`crate::ImportRequest::lang`]
+    pub fn provide_module_with_lang(
+        &mut self,
+        resolved_path: crate::ModulePath,
+        source: &str,
+        lang: crate::Lang,
+    ) -> Result<(), JsError> {
+        let previous = self.lang_override.replace(lang);
+        let result = self.provide_module(resolved_path, source);
+        self.lang_override = previous;
+        result
+    }
+
+    /// Like [`Interpreter::provide_module`], for a module that is already parsed.
+    ///
+    /// See [`Interpreter::prepare_ast`] for where `program` may come from.
+    /// Module hooks report it as loaded with a parse time of 0 ms.
+    pub fn provide_module_ast(
+        &mut self,
+        resolved_path: crate::ModulePath,
+        program: Program,
+    ) -> Result<(), JsError> {
+        let Some(pos) = self
+            .requested_imports
+            .iter()
+            .position(|req| req.resolved_path == resolved_path)
+        else {
+            return Err(self.unrequested_module_error(&resolved_path));
+        };
+
+        if self
+            .requested_imports
+            .get(pos)
+            .is_some_and(|req| req.is_json())
+        {
+            self.note_failed_import(&resolved_path);
+            return Err(json_module::json_provided_as_code(&resolved_path));
+        }
+
+        // A parsed program has no source left to hash
+        let expected = self
+            .requested_imports
+            .get(pos)
+            .and_then(|req| req.integrity.as_deref())
+            .or_else(|| self.manifest_integrity(&resolved_path));
+        if let Some(integrity) = expected {
+            let err = JsError::module_error(format!(
+                "Module '{}' must have integrity {}; provide its source so it can be checked",
+                resolved_path, integrity
+            ));
+            self.note_failed_import(&resolved_path);
+            return Err(err);
+        }
+
+        if let Some(hook) = &self.module_hooks.on_module_start {
+            hook(&resolved_path);
+        }
+        let program = self.adopt_program(program);
+        if let Some(hook) = &self.module_hooks.on_module_loaded {
+            hook(&resolved_path, 0);
+        }
+
+        self.requested_imports.remove(pos);
+        self.pending_module_sources.insert(resolved_path, program);
+        Ok(())
+    }
+
+    /// Provide a module source that has not been requested (yet).
+    ///
+    /// Use this to pre-seed known libraries before `prepare()`; imports of
+    /// `resolved_path` are then satisfied without a `NeedImports` round trip.
+    pub fn provide_module_unrequested(
+        &mut self,
+        resolved_path: crate::ModulePath,
+        source: &str,
+    ) -> Result<(), JsError> {
+        let request = self
+            .requested_imports
+            .iter()
+            .find(|req| req.resolved_path == resolved_path);
+        let checked = self.check_module_integrity(&resolved_path, source, request);
+        if request.is_some_and(|req| req.is_json()) {
+            if let Err(err) = checked.and_then(|()| self.load_json_module(&resolved_path, source)) {
+                self.note_failed_import(&resolved_path);
+                return Err(err);
+            }
+            self.requested_imports
+                .retain(|req| req.resolved_path != resolved_path);
+            return Ok(());
+        }
+        let program = match checked.and_then(|()| self.parse_module(&resolved_path, source)) {
+            Ok(program) => program,
+            Err(err) => {
+                self.note_failed_import(&resolved_path);
+                return Err(err);
+            }
+        };
+        self.requested_imports
+            .retain(|req| req.resolved_path != resolved_path);
+        self.pending_module_sources.insert(resolved_path, program);
+        Ok(())
+    }
+
+    /// Parse a module provided by the host, reporting it to the module hooks
+    fn parse_module(&mut self, path: &crate::ModulePath, source: &str) -> Result<Program, JsError> {
+        if let Some(hook) = &self.module_hooks.on_module_start {
+            hook(path);
+        }
+        let timer = self
+            .module_hooks
+            .on_module_loaded
+            .is_some()
+            .then(|| self.time_provider.start_timer());
+
+        let metrics_timer = self.start_metrics_timer();
+        let program = self.parse_module_source(Some(path), source)?;
+        self.record_module_parse(Some(path), metrics_timer);
+
+        if let (Some(hook), Some(start)) = (&self.module_hooks.on_module_loaded, timer) {
+            hook(path, self.time_provider.elapsed_millis(start));
+        }
+        Ok(program)
+    }
+
+    /// Remember import requests handed to the host so `provide_module` can check paths
+    ///
+    /// Imports found in the module source map are queued instead; when that
+    /// covers all of them the result is `Continue`, and stepping runs them.
+    fn request_imports(
+        &mut self,
+        requests: Vec<crate::ImportRequest>,
+    ) -> Result<StepResult, JsError> {
+        let mut requests_left = Vec::with_capacity(requests.len());
+        for req in requests {
+            if !self.serve_from_module_map(&req)? {
+                requests_left.push(req);
+            }
+        }
+        if requests_left.is_empty() {
+            return Ok(StepResult::Continue);
+        }
+
+        // A path is reported once until it is provided, however many modules
+        // import it, unless providing it failed
+        let mut requests = Vec::with_capacity(requests_left.len());
+        let mut outstanding = Vec::new();
+        for mut req in requests_left {
+            let first = self
+                .requested_imports
+                .iter()
+                .find(|r| r.resolved_path == req.resolved_path);
+            if let Some(first) = first {
+                req.first_requested_by = first.first_requested_by.clone();
+                let failed = self
+                    .failed_imports
+                    .iter()
+                    .position(|path| *path == req.resolved_path);
+                match failed {
+                    Some(pos) => {
+                        self.failed_imports.swap_remove(pos);
+                        requests.push(req);
+                    }
+                    None => outstanding.push(req),
+                }
+                continue;
+            }
+            if let Some(hook) = &self.module_hooks.on_import_requested {
+                hook(&req);
+            }
+            let discovered = self.import_discovery.len();
+            self.import_discovery
+                .entry(req.resolved_path.clone())
+                .or_insert(discovered);
+            self.requested_imports.push(req.clone());
+            requests.push(req);
+        }
+        // Continued without providing anything new: report what is still awaited
+        if requests.is_empty() {
+            requests = outstanding;
+        }
+        Ok(StepResult::NeedImports(requests))
+    }
+
+    /// Report `path` again in the next `NeedImports` after providing it failed
+    fn note_failed_import(&mut self, path: &crate::ModulePath) {
+        let requested = self
+            .requested_imports
+            .iter()
+            .any(|req| req.resolved_path == *path);
+        if requested && !self.failed_imports.contains(path) {
+            self.failed_imports.push(path.clone());
+        }
+    }
+
+    /// Load `path`, which failed to parse or compile, as a namespace whose
+    /// reads throw, and record `err` for `module_errors`
+    fn load_failed_module(&mut self, path: crate::ModulePath, err: JsError) {
+        let guard = self.heap.create_guard();
+        let namespace = self.create_object(&guard);
+        self.seal_module_namespace(&namespace);
+        self.module_guard.guard(namespace.clone());
+        self.failed_module_namespaces.insert(
+            namespace.id(),
+            format!("Module '{}' failed to load: {}", path, err),
+        );
+        self.loaded_modules.insert(path.clone(), namespace);
+        self.module_errors.push((path, err));
+    }
+
+    /// The modules that failed to parse or compile under
+    /// `ModuleErrorPolicy::Collect`, with their errors, in the order they failed
+    pub fn module_errors(&self) -> &[(crate::ModulePath, JsError)] {
+        &self.module_errors
+    }
+
+    /// What reading from `obj` throws if it is the namespace of a module that
+    /// failed to load
+    pub(crate) fn failed_module_error(&self, obj: &Gc<JsObject>) -> Option<JsError> {
+        if self.failed_module_namespaces.is_empty() {
+            return None;
+        }
+        self.failed_module_namespaces
+            .get(&obj.id())
+            .map(|message| JsError::type_error(message.clone()))
+    }
+
+    /// Forget the imports reported to the host, for a new execution
+    pub(crate) fn clear_import_requests(&mut self) {
+        self.requested_imports.clear();
+        self.failed_imports.clear();
+        self.import_discovery.clear();
+    }
+
+    /// Explain why a module provided for `path` does not match any outstanding import
+    fn unrequested_module_error(&self, path: &crate::ModulePath) -> JsError {
+        if self.requested_imports.is_empty() {
+            return JsError::module_error(format!(
+                "Cannot provide module '{}': execution is not waiting for imports \
+                 (not started or already completed); use provide_module_unrequested to pre-seed modules",
+                path
+            ));
+        }
+
+        let awaited: Vec<String> = self
+            .requested_imports
+            .iter()
+            .map(|req| format!("'{}'", req.resolved_path))
+            .collect();
+        let mut message = format!(
+            "Module '{}' was not requested; awaiting {}",
+            path,
+            awaited.join(", ")
+        );
+
+        // Common mistake: passing the specifier instead of the resolved path
+        let provided = path.as_str().trim_start_matches("./");
+        let suggestion = self.requested_imports.iter().find(|req| {
+            req.specifier == path.as_str()
+                || req.specifier.trim_start_matches("./") == provided
+                || req.resolved_path.as_str().ends_with(provided)
+        });
+        if let Some(req) = suggestion {
+            message.push_str(&format!(
+                ". Did you mean '{}' (the resolved path for import '{}')?",
+                req.resolved_path, req.specifier
+            ));
+        }
+        JsError::module_error(message)
+    }
+
     /// Set up import bindings for a program before bytecode execution.
     /// This resolves all imports and creates bindings in the current environment
     /// so that the bytecode can reference imported values.
@@ -1648,6 +3739,24 @@ impl Interpreter {
 
                 // Resolve the module
                 let module_obj = self.resolve_module(&specifier)?;
+                if self.purity.is_some() {
+                    self.protect_import(&specifier, &module_obj);
+                }
+
+                if self.audit_log.is_some() && self.internal_modules.contains_key(&specifier) {
+                    for spec in &import.specifiers {
+                        let name = match spec {
+                            ImportSpecifier::Named { imported, .. } => imported.name.as_str(),
+                            ImportSpecifier::Default { .. } => "default",
+                            ImportSpecifier::Namespace { .. } => "*",
+                        };
+                        self.record_audit(
+                            crate::AuditEventKind::ModuleAccess,
+                            Some(&specifier),
+                            name,
+                        );
+                    }
+                }
 
                 // Set up bindings for each import specifier
                 for spec in &import.specifiers {
@@ -1657,8 +3766,9 @@ impl Interpreter {
                         } => {
                             // import { foo as bar } from "mod" -> bar binds to mod.foo
                             let property_key = PropertyKey::String(imported.name.cheap_clone());
+                            let local_name = self.intern(local.name.as_str());
                             self.env_define_import(
-                                local.name.cheap_clone(),
+                                local_name,
                                 module_obj.cheap_clone(),
                                 property_key,
                             );
@@ -1666,8 +3776,9 @@ impl Interpreter {
                         ImportSpecifier::Default { local, .. } => {
                             // import foo from "mod" -> foo binds to mod.default
                             let property_key = PropertyKey::String(self.intern("default"));
+                            let local_name = self.intern(local.name.as_str());
                             self.env_define_import(
-                                local.name.cheap_clone(),
+                                local_name,
                                 module_obj.cheap_clone(),
                                 property_key,
                             );
@@ -1675,8 +3786,9 @@ impl Interpreter {
                         ImportSpecifier::Namespace { local, .. } => {
                             // import * as foo from "mod" -> foo binds to the entire module object
                             // For namespace imports, we define a regular binding to the module object
+                            let local_name = self.intern(local.name.as_str());
                             self.env_define(
-                                local.name.cheap_clone(),
+                                local_name,
                                 JsValue::Object(module_obj.cheap_clone()),
                                 false, // immutable
                             );
@@ -1696,31 +3808,57 @@ impl Interpreter {
             .ok_or_else(|| {
                 JsError::internal_error(format!("Module '{}' not found", module_path))
             })?;
+        let timer = self
+            .module_hooks
+            .on_module_executed
+            .is_some()
+            .then(|| self.time_provider.start_timer());
 
         // Save current state
         let saved_env = self.env.cheap_clone();
         let saved_module_path = self.current_module_path.take();
 
         // Set current module path for resolving nested imports
-        self.current_module_path = Some(module_path.clone());
+        let module_file = self
+            .module_files
+            .remove(module_path)
+            .unwrap_or_else(|| module_path.clone());
+        self.current_module_path = Some(module_file.clone());
+        self.record_module_flight(flight_recorder::FlightEventKind::ModuleStart, module_path);
 
         // Create module environment (rooted so it persists for live bindings)
         let module_env = self.create_module_environment();
         // Root the module environment - it must persist for live bindings
-        self.root_guard.guard(module_env.clone());
+        self.module_guard.guard(module_env.clone());
         self.env = module_env.cheap_clone();
 
         // Set up import bindings before bytecode execution
         self.setup_import_bindings(&program)?;
 
         // Execute module using bytecode compilation
-        let result = self.execute_program_bytecode(&program);
+        let metrics_timer = self.start_metrics_timer();
+        let compiled = self.compile_program_chunk(&program, Some(&module_file), false);
+        let collect_error = self.module_error_policy == crate::ModuleErrorPolicy::Collect;
+        let result = match compiled {
+            Err(err) if collect_error => {
+                self.env = saved_env;
+                self.current_module_path = saved_module_path;
+                self.load_failed_module(module_path.clone(), err);
+                return Ok(());
+            }
+            compiled => compiled.and_then(|chunk| {
+                self.record_module_compile(Some(module_path), metrics_timer, &chunk);
+                let this_value = self.top_level_this(&program);
+                self.run_bytecode_with_this(chunk, this_value)
+            }),
+        };
 
         // Restore state
         self.env = saved_env;
         self.current_module_path = saved_module_path;
 
         result?;
+        self.record_module_flight(flight_recorder::FlightEventKind::ModuleEnd, module_path);
 
         // Create module namespace object from exports
         let guard = self.heap.create_guard();
@@ -1796,10 +3934,25 @@ impl Interpreter {
             }
         }
 
+        if let (Some(hook), Some(start)) = (&self.module_hooks.on_module_executed, timer) {
+            let export_count = module_obj.borrow().properties.len();
+            hook(
+                module_path,
+                self.time_provider.elapsed_millis(start),
+                export_count,
+            );
+        }
+
+        self.seal_module_namespace(&module_obj);
+
         // Root the module namespace object (lives forever)
-        self.root_guard.guard(module_obj.clone());
+        self.module_guard.guard(module_obj.clone());
 
-        // Cache it by normalized path
+        // Cache it by normalized path, and by the file it was read from
+        if module_file != *module_path {
+            self.loaded_modules
+                .insert(module_file, module_obj.cheap_clone());
+        }
         self.loaded_modules.insert(module_path.clone(), module_obj);
 
         Ok(())
@@ -1814,11 +3967,294 @@ impl Interpreter {
     ///
     /// For parallel async operations, the host can return unresolved Promises
     /// and resolve them later via api::resolve_promise.
+    ///
+    /// Orders created in eager mode may be fulfilled before the script awaits
+    /// them: their promises are settled immediately.
+    ///
+    /// Responses for cancelled or unknown orders are ignored.
     pub fn fulfill_orders(&mut self, responses: Vec<crate::OrderResponse>) {
+        self.last_fulfilled = responses.iter().map(|response| response.id).collect();
         for response in responses {
+            let Some(pos) = self
+                .outstanding_orders
+                .iter()
+                .position(|&id| id == response.id)
+            else {
+                continue;
+            };
+            self.outstanding_orders.remove(pos);
+            self.stall_progress = true;
+            self.record_order_response(&response);
+            if self.flight_recorder.is_some() {
+                let subject = flight_recorder::FlightSubject::Order(response.id);
+                self.record_flight(
+                    flight_recorder::FlightEventKind::OrderFulfilled,
+                    subject,
+                    None,
+                );
+            }
+
+            if let Some(promise) = self.eager_order_promises.remove(&response.id) {
+                self.settle_eager_order(response.id, &promise, response.result);
+                continue;
+            }
             // Store response - will be injected into VM when execution resumes
             self.order_responses.insert(response.id, response.result);
         }
+        self.check_resolved_promises();
+    }
+
+    /// Create an order for the host and return the value `order()` evaluates to.
+    ///
+    /// In batched mode this is a PendingOrder marker that suspends the VM;
+    /// in eager mode, or inside a `batch()` scope, it is a Promise settled by
+    /// `fulfill_orders`.
+    ///
+    /// `id` is an ID reserved with `__getOrderId__`; a fresh one is allocated
+    /// when it is `None`. `deadline_ms` is passed through to the host.
+    pub(crate) fn create_order(
+        &mut self,
+        payload: JsValue,
+        id: Option<crate::OrderId>,
+        deadline_ms: Option<i64>,
+    ) -> (crate::OrderId, Guarded) {
+        if self.orders_unobserved {
+            // Reported once as cancelled, never as pending
+            let id = self.order_id_or_next(id);
+            if !self.cancelled_orders.contains(&id) {
+                self.cancelled_orders.push(id);
+            }
+            let guard = self.heap.create_guard();
+            let promise =
+                builtins::promise::create_fulfilled_promise(self, &guard, JsValue::Undefined);
+            return (id, Guarded::with_guard(JsValue::Object(promise), guard));
+        }
+
+        let id = self.record_order(payload, id, deadline_ms);
+
+        let guard = self.heap.create_guard();
+        if self.order_delivery == crate::OrderDelivery::Eager || self.order_batch_depth > 0 {
+            let promise = self.create_tracked_order_promise(&guard, id);
+            return (id, Guarded::with_guard(JsValue::Object(promise), guard));
+        }
+
+        // Create PendingOrder marker - VM will suspend immediately when this returns
+        let marker = guard.alloc();
+        marker.borrow_mut().exotic = ExoticObject::PendingOrder { id: id.0 };
+        (id, Guarded::with_guard(JsValue::Object(marker), guard))
+    }
+
+    /// Issue an order from Rust, such as a function made with
+    /// [`Interpreter::create_function_value`], and return a promise for its
+    /// response.
+    ///
+    /// The order reaches the host like one the script created, and
+    /// `fulfill_orders` settles the promise. Unlike `order()` in batched
+    /// mode it never suspends the caller, so it works from any native code.
+    pub fn issue_order(&mut self, payload: JsValue) -> Result<crate::RuntimeValue, JsError> {
+        self.check_order()?;
+        self.check_pending_order_limit()?;
+        let id = self.record_order(payload, None, None);
+        let guard = self.heap.create_guard();
+        let promise = self.create_tracked_order_promise(&guard, id);
+        Ok(crate::RuntimeValue::with_guard(
+            JsValue::Object(promise),
+            guard,
+        ))
+    }
+
+    /// Record order `id`, or a fresh one, as pending for the host
+    fn record_order(
+        &mut self,
+        payload: JsValue,
+        id: Option<crate::OrderId>,
+        deadline_ms: Option<i64>,
+    ) -> crate::OrderId {
+        let id = self.order_id_or_next(id);
+        #[cfg(feature = "testing")]
+        if let Some(deadline_ms) = deadline_ms {
+            self.note_timer_order(&payload, id, deadline_ms);
+        }
+
+        // Create payload RuntimeValue with guard if it's an object
+        let payload_rv = if let JsValue::Object(ref obj) = payload {
+            let payload_guard = self.heap.create_guard();
+            payload_guard.set_label("order payload");
+            payload_guard.guard(obj.cheap_clone());
+            crate::RuntimeValue::with_guard(payload, payload_guard)
+        } else {
+            crate::RuntimeValue::unguarded(payload)
+        };
+
+        // Record the pending order
+        self.pending_orders.push(crate::Order {
+            id,
+            payload: payload_rv,
+            deadline_ms,
+            // Filled in by the VM once the native call creating it returns
+            origin: crate::OrderOrigin::default(),
+            // Numbered when the batch is handed to the host
+            batch_seq: 0,
+        });
+        self.outstanding_orders.push(id);
+        id
+    }
+
+    /// `id`, or a fresh order ID when it is `None`
+    fn order_id_or_next(&mut self, id: Option<crate::OrderId>) -> crate::OrderId {
+        id.unwrap_or_else(|| {
+            let id = crate::OrderId(self.next_order_id);
+            self.next_order_id += 1;
+            id
+        })
+    }
+
+    /// Create the promise for order `id` that `fulfill_orders` settles
+    pub(crate) fn create_tracked_order_promise(
+        &mut self,
+        guard: &Guard<JsObject>,
+        id: crate::OrderId,
+    ) -> Gc<JsObject> {
+        let promise = builtins::promise::create_order_promise(self, guard, id);
+        let promise_guard = self.heap.create_guard();
+        promise_guard.set_label("order promise");
+        promise_guard.guard(promise.cheap_clone());
+        self.eager_order_promises.insert(
+            id,
+            crate::RuntimeValue::with_guard(JsValue::Object(promise.cheap_clone()), promise_guard),
+        );
+        promise
+    }
+
+    /// Settle the promise of an eagerly delivered order with the host's response
+    fn settle_eager_order(
+        &mut self,
+        id: crate::OrderId,
+        promise: &crate::RuntimeValue,
+        result: Result<crate::RuntimeValue, JsError>,
+    ) {
+        let JsValue::Object(promise) = promise.value() else {
+            return;
+        };
+        // Settling only fails for non-promise objects, which the order map never holds
+        let _ = match result {
+            Ok(value) => {
+                builtins::promise::resolve_promise_value(self, promise, value.value().clone())
+            }
+            Err(error) => {
+                // Same rejection value the suspended path injects
+                let reason = self.order_rejection(error);
+                let settled =
+                    builtins::promise::reject_promise_value(self, promise, reason.value.clone());
+                // A host error is not a cancellation
+                self.cancelled_orders.retain(|&cancelled| cancelled != id);
+                settled
+            }
+        };
+    }
+
+    /// What the script receives for an order the host rejected with `error`:
+    /// a value from [`crate::OrderResponse::reject_with_value`] as it is, any
+    /// other error as an error object of the matching type
+    fn order_rejection(&mut self, error: JsError) -> Guarded {
+        match error {
+            JsError::ThrownValue { guarded } => guarded,
+            other => {
+                let (value, guard) = builtins::error::create_error_object(self, &other);
+                Guarded { value, guard }
+            }
+        }
+    }
+
+    /// Number of orders created by the script that have been neither
+    /// fulfilled nor cancelled, as checked against
+    /// [`crate::InterpreterConfig::max_pending_orders`]
+    pub fn pending_order_count(&self) -> usize {
+        self.outstanding_orders.len()
+    }
+
+    /// Fail a new order in [`crate::PendingOrderOverflow::Throw`] mode once
+    /// the script has `max_pending_orders` outstanding
+    pub(crate) fn check_pending_order_limit(&self) -> Result<(), JsError> {
+        if self.pending_order_overflow == crate::PendingOrderOverflow::Throw
+            && self.outstanding_orders.len() >= self.max_pending_orders
+        {
+            return Err(JsError::range_error("too many pending host requests"));
+        }
+        Ok(())
+    }
+
+    /// Orders created by the script that have been neither fulfilled nor cancelled.
+    ///
+    /// Hosts can use this to check that no orders leak once execution completes.
+    pub fn outstanding_orders(&self) -> Vec<crate::OrderId> {
+        self.outstanding_orders.clone()
+    }
+
+    /// Cancel an order: report it to the host and drop everything kept for it.
+    /// A later response for the order is ignored.
+    pub(crate) fn cancel_order(&mut self, id: crate::OrderId) {
+        if !self.cancelled_orders.contains(&id) {
+            self.cancelled_orders.push(id);
+        }
+        self.outstanding_orders.retain(|&o| o != id);
+        self.pending_orders.retain(|o| o.id != id);
+        self.order_responses.remove(&id);
+        self.eager_order_promises.remove(&id);
+    }
+
+    /// Cancel eager orders whose promise results can no longer be observed,
+    /// e.g. orders feeding only the losing side of a settled `Promise.race`.
+    ///
+    /// The order's reactions are dropped so they never create follow-up orders.
+    pub(crate) fn cancel_ignored_orders(&mut self) {
+        let mut ignored: Vec<(crate::OrderId, Gc<JsObject>)> = self
+            .eager_order_promises
+            .iter()
+            .filter_map(|(&id, promise)| match promise.value() {
+                JsValue::Object(obj) if builtins::promise::is_result_ignored(self, obj) => {
+                    Some((id, obj.cheap_clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        ignored.sort_by_key(|(id, _)| id.0);
+
+        for (id, promise) in ignored {
+            // Keep the promise guarded while it is being abandoned
+            let Some(_guarded) = self.eager_order_promises.remove(&id) else {
+                continue;
+            };
+            builtins::promise::abandon_promise(&promise);
+            self.cancel_order(id);
+        }
+    }
+
+    /// Whether anything still needs the host: an order-blocked VM, unsettled
+    /// eager orders, or async contexts waiting on promises
+    fn has_outstanding_work(&self) -> bool {
+        self.suspended_for_order.is_some()
+            || !self.eager_order_promises.is_empty()
+            || self.wait_graph.has_waiting_contexts()
+            || !self.thenable_jobs.is_empty()
+    }
+
+    /// Whether a job can run without host input: a context whose promise has
+    /// settled, or a queued thenable job. Pending orders are held back until
+    /// there is none, so every order the script can create on its own
+    /// reaches the host in the same `Suspended` batch.
+    fn has_runnable_jobs(&mut self) -> bool {
+        self.check_resolved_promises();
+        !self.wait_graph.ready_queue.is_empty() || !self.thenable_jobs.is_empty()
+    }
+
+    /// Take the orders to hand to the host, numbering them in creation order
+    fn take_pending_orders(&mut self) -> Vec<crate::Order> {
+        let mut orders = mem::take(&mut self.pending_orders);
+        for (seq, order) in orders.iter_mut().enumerate() {
+            order.batch_seq = seq as u32;
+        }
+        orders
     }
 
     /// Check all waiting contexts for resolved promises and move them to ready queue
@@ -1879,7 +4315,7 @@ impl Interpreter {
             .current_module_path
             .as_ref()
             .or(self.main_module_path.as_ref());
-        crate::ModulePath::resolve(specifier, base)
+        crate::ModulePath::resolve_with(specifier, base, self.url_query)
     }
 
     /// Collect all import requests from a program, resolving relative paths.
@@ -1890,7 +4326,7 @@ impl Interpreter {
         &self,
         program: &Program,
         module_path: Option<&crate::ModulePath>,
-    ) -> Vec<crate::ImportRequest> {
+    ) -> Result<Vec<crate::ImportRequest>, JsError> {
         self.collect_import_requests_internal(program, module_path, module_path)
     }
 
@@ -1903,32 +4339,70 @@ impl Interpreter {
         program: &Program,
         resolve_base: Option<&crate::ModulePath>,
         importer: Option<&crate::ModulePath>,
-    ) -> Vec<crate::ImportRequest> {
+    ) -> Result<Vec<crate::ImportRequest>, JsError> {
         use crate::ast::Statement;
 
         let mut imports = Vec::new();
 
         for stmt in program.body.iter() {
-            let specifier = match stmt {
-                Statement::Import(import) => Some(import.source.value.to_string()),
+            let (specifier, attributes) = match stmt {
+                Statement::Import(import) => {
+                    (Some(import.source.value.to_string()), &import.attributes)
+                }
                 Statement::Export(export) => {
                     // Re-export from another module: export { foo } from "./bar"
-                    export.source.as_ref().map(|s| s.value.to_string())
+                    (
+                        export.source.as_ref().map(|s| s.value.to_string()),
+                        &export.attributes,
+                    )
                 }
-                _ => None,
+                _ => continue,
             };
 
             if let Some(spec) = specifier {
-                let resolved = crate::ModulePath::resolve(&spec, resolve_base);
+                let resolved = crate::ModulePath::resolve_with(&spec, resolve_base, self.url_query);
+                let integrity = attributes
+                    .iter()
+                    .find(|attr| attr.key.as_str() == "integrity")
+                    .map(|attr| attr.value.value.to_string())
+                    .or_else(|| self.manifest_integrity(&resolved).map(String::from));
+                let attributes = attributes
+                    .iter()
+                    .map(|attr| (attr.key.to_string(), attr.value.value.to_string()))
+                    .collect();
                 imports.push(crate::ImportRequest {
+                    lang: crate::Lang::infer(Some(&resolved), self.default_lang),
+                    kind: crate::ModulePath::kind(&spec),
                     specifier: spec,
                     resolved_path: resolved,
                     importer: importer.cloned(),
+                    first_requested_by: importer.cloned(),
+                    integrity,
+                    attributes,
                 });
             }
         }
 
-        imports
+        // Without an absolute base, a relative import resolves to a rootless path
+        // that can't be told apart from a bare specifier of the same name
+        for req in &imports {
+            if req.kind != crate::ImportKind::Relative
+                || req.resolved_path.as_str().starts_with('/')
+            {
+                continue;
+            }
+            if let Some(bare) = imports.iter().find(|other| {
+                other.kind == crate::ImportKind::Bare && other.resolved_path == req.resolved_path
+            }) {
+                return Err(JsError::module_error(format!(
+                    "Ambiguous import: '{}' resolves to '{}', the same path as bare import '{}'; \
+                     give the importing module an absolute path",
+                    req.specifier, req.resolved_path, bare.specifier
+                )));
+            }
+        }
+
+        Ok(imports)
     }
 
     /// Filter import requests to only those that are missing (not internal, not already loaded).
@@ -2011,6 +4485,23 @@ impl Interpreter {
         }
     }
 
+    /// Define a variable in the current environment that is not yet initialized.
+    /// Accessing it throws a ReferenceError until `env_define` replaces it.
+    pub fn env_define_uninitialized(&mut self, name: JsString) {
+        let mut env_ref = self.env.borrow_mut();
+        if let Some(data) = env_ref.as_environment_mut() {
+            data.bindings.insert(
+                VarKey(name),
+                Binding {
+                    value: JsValue::Undefined,
+                    mutable: true,
+                    initialized: false,
+                    import_binding: None,
+                },
+            );
+        }
+    }
+
     /// Define an import binding in the current environment (for live bindings)
     pub fn env_define_import(
         &mut self,
@@ -2036,46 +4527,224 @@ impl Interpreter {
     }
 
     /// Get a variable from the environment chain
-    pub fn env_get(&self, name: &JsString) -> Result<JsValue, JsError> {
+    pub fn env_get(&mut self, name: &JsString) -> Result<JsValue, JsError> {
         let mut current = Some(self.env.cheap_clone());
         // Create VarKey once for pointer-based lookup
         let key = VarKey(name.cheap_clone());
 
         while let Some(env) = current {
             let env_ref = env.borrow();
-            if let Some(data) = env_ref.as_environment() {
-                if let Some(binding) = data.bindings.get(&key) {
-                    if !binding.initialized {
-                        return Err(JsError::reference_error(format!(
-                            "Cannot access '{}' before initialization",
-                            name
-                        )));
-                    }
-                    // Handle import bindings (for live bindings)
-                    if let Some(ref import_binding) = binding.import_binding {
-                        return self.resolve_import_binding(import_binding);
-                    }
-                    return Ok(binding.value.clone());
-                }
-                current = data.outer.cheap_clone();
-            } else {
-                break;
+            if let Some(data) = env_ref.as_environment() {
+                if let Some(binding) = data.bindings.get(&key) {
+                    if !binding.initialized {
+                        return Err(JsError::uninitialized_binding(name));
+                    }
+                    // Handle import bindings (for live bindings)
+                    if let Some(import_binding) = binding.import_binding.clone() {
+                        drop(env_ref);
+                        return self.resolve_import_binding(&import_binding);
+                    }
+                    return Ok(binding.value.clone());
+                }
+                current = data.outer.cheap_clone();
+            } else {
+                break;
+            }
+        }
+
+        // Check global object properties
+        if let Some(prop) = self.read_global(name) {
+            if self.audit_log.is_some() {
+                self.record_audit(crate::AuditEventKind::GlobalRead, None, name.as_str());
+            }
+            return Ok(prop);
+        }
+
+        Err(JsError::reference_error(name.to_string()))
+    }
+
+    /// Resolve an import binding by reading from the module's environment
+    /// This handles both direct exports (ModuleExportGetter) and re-exports (ModuleReExportGetter)
+    fn resolve_import_binding(
+        &mut self,
+        import_binding: &ImportBinding,
+    ) -> Result<JsValue, JsError> {
+        self.read_module_export(&import_binding.module_obj, &import_binding.property_key)
+    }
+
+    /// Describe a call to an export the module doesn't provide, if `callee` names one.
+    ///
+    /// `callee` is the callee as written: an imported binding such as `render`, or a
+    /// member of a namespace import such as `util.render`, whose namespace is `this_value`.
+    pub(crate) fn missing_export_message(
+        &self,
+        callee: &JsString,
+        this_value: &JsValue,
+    ) -> Option<String> {
+        let (module_obj, property_key) = match callee.as_str().rsplit_once('.') {
+            Some((_, export)) => {
+                let JsValue::Object(namespace) = this_value else {
+                    return None;
+                };
+                (
+                    namespace.cheap_clone(),
+                    PropertyKey::String(JsString::from(export)),
+                )
+            }
+            None => {
+                let binding = self.env_import_binding(callee)?;
+                (binding.module_obj, binding.property_key)
+            }
+        };
+        if module_obj
+            .borrow()
+            .get_own_property(&property_key)
+            .is_some()
+        {
+            return None;
+        }
+        let specifier = self.module_specifier(&module_obj)?;
+        Some(format!(
+            "Module '{}' has no export '{}'",
+            specifier, property_key
+        ))
+    }
+
+    /// The error for reading `key`, which `obj` doesn't have, if `obj` is a
+    /// module namespace or host context object and
+    /// `InterpreterConfig::strict_property_access` is on
+    pub(crate) fn strict_read_error(
+        &self,
+        obj: &Gc<JsObject>,
+        key: &PropertyKey,
+    ) -> Option<JsError> {
+        if !self.strict_property_access {
+            return None;
+        }
+        let PropertyKey::String(name) = key else {
+            return None;
+        };
+        let suggestion = {
+            let obj_ref = obj.borrow();
+            if !obj_ref.strict_reads {
+                return None;
+            }
+            let keys = obj_ref.properties.iter().filter_map(|(key, _)| match key {
+                PropertyKey::String(key) => Some(key.as_str()),
+                _ => None,
+            });
+            suggest::did_you_mean(name.as_str(), keys).map(String::from)
+        };
+        let target = match self.module_specifier(obj) {
+            Some(specifier) => format!("module '{}'", specifier),
+            None => "the host object".to_string(),
+        };
+        let mut message = format!("Property '{}' does not exist on {}", name, target);
+        if let Some(suggestion) = suggestion {
+            message.push_str(&format!("; did you mean '{}'?", suggestion));
+        }
+        Some(JsError::type_error(message))
+    }
+
+    /// The import binding `name` refers to, if its nearest binding is an import.
+    /// `name` must be interned, as for any environment lookup.
+    fn env_import_binding(&self, name: &JsString) -> Option<ImportBinding> {
+        let key = VarKey(name.cheap_clone());
+        let mut current = Some(self.env.cheap_clone());
+        while let Some(env) = current {
+            let env_ref = env.borrow();
+            let data = env_ref.as_environment()?;
+            if let Some(binding) = data.bindings.get(&key) {
+                return binding.import_binding.clone();
             }
+            current = data.outer.cheap_clone();
         }
+        None
+    }
 
-        // Check global object properties
-        let global = self.global.borrow();
-        if let Some(prop) = global.get_property(&PropertyKey::String(name.cheap_clone())) {
-            return Ok(prop);
+    /// Finish a freshly built module namespace: exports sorted by name, a
+    /// null prototype and `Symbol.toStringTag` of `"Module"`, and immutable
+    /// from script, with no new properties and exports that can be neither
+    /// redefined, deleted nor written. Live bindings still update through
+    /// their getters.
+    fn seal_module_namespace(&mut self, module_obj: &Gc<JsObject>) {
+        let tag_key = PropertyKey::Symbol(Box::new(JsSymbol::new(
+            self.well_known_symbols.to_string_tag,
+            Some(self.intern("Symbol.toStringTag")),
+        )));
+        let tag = JsValue::String(self.intern("Module"));
+        let mut namespace = module_obj.borrow_mut();
+
+        // Namespaces list their exports in code unit order, unlike ordinary
+        // objects
+        let mut exports: Vec<(PropertyKey, Property)> = namespace
+            .properties
+            .iter()
+            .map(|(key, prop)| (key.clone(), prop.clone()))
+            .collect();
+        exports.sort_by(|(a, _), (b, _)| match (a, b) {
+            (PropertyKey::String(a), PropertyKey::String(b)) => {
+                a.as_str().encode_utf16().cmp(b.as_str().encode_utf16())
+            }
+            _ => core::cmp::Ordering::Equal,
+        });
+        namespace.properties.clear();
+        for (key, prop) in exports {
+            namespace.properties.insert(key, prop);
+        }
+        namespace
+            .properties
+            .insert(tag_key, Property::with_attributes(tag, false, false, false));
+
+        namespace.prototype = None;
+        namespace.null_prototype = true;
+        namespace.extensible = false;
+        namespace.sealed = true;
+        namespace.strict_reads = true;
+        for (_, prop) in namespace.properties.iter_mut() {
+            prop.set_configurable(false);
+            prop.set_writable(false);
         }
+    }
 
-        Err(JsError::reference_error(name.to_string()))
+    /// Own property `prop` of `obj` under `key` as
+    /// `getOwnPropertyDescriptor` reports it. Module namespace exports are
+    /// read through getters to keep them live, but are described as the
+    /// writable data properties the spec makes them.
+    pub(crate) fn reported_own_property(
+        &self,
+        obj: &Gc<JsObject>,
+        key: &PropertyKey,
+        prop: Property,
+    ) -> Result<Property, JsError> {
+        let is_export = prop.getter().is_some_and(|getter| {
+            matches!(
+                getter.borrow().exotic,
+                ExoticObject::Function(
+                    JsFunction::ModuleExportGetter { .. } | JsFunction::ModuleReExportGetter { .. }
+                )
+            )
+        });
+        if !is_export {
+            return Ok(prop);
+        }
+        let value = self.resolve_module_property(obj, key)?;
+        Ok(Property::with_attributes(value, true, true, false))
     }
 
-    /// Resolve an import binding by reading from the module's environment
-    /// This handles both direct exports (ModuleExportGetter) and re-exports (ModuleReExportGetter)
-    fn resolve_import_binding(&self, import_binding: &ImportBinding) -> Result<JsValue, JsError> {
-        self.resolve_module_property(&import_binding.module_obj, &import_binding.property_key)
+    /// The path or specifier a module namespace object was loaded from
+    fn module_specifier(&self, namespace: &Gc<JsObject>) -> Option<String> {
+        let id = namespace.id();
+        self.loaded_modules
+            .iter()
+            .find(|(_, module)| module.id() == id)
+            .map(|(path, _)| path.to_string())
+            .or_else(|| {
+                self.internal_module_cache
+                    .iter()
+                    .find(|(_, module)| module.id() == id)
+                    .map(|(specifier, _)| specifier.clone())
+            })
     }
 
     /// Resolve a property from a module namespace object, handling live bindings
@@ -2086,6 +4755,9 @@ impl Interpreter {
         module_obj: &Gc<JsObject>,
         prop_key: &PropertyKey,
     ) -> Result<JsValue, JsError> {
+        if let Some(err) = self.failed_module_error(module_obj) {
+            return Err(err);
+        }
         // Get the property descriptor from the module namespace object
         let prop_desc = module_obj.borrow().get_property_descriptor(prop_key);
 
@@ -2184,12 +4856,18 @@ impl Interpreter {
             let mut env_ref = env.borrow_mut();
             if let Some(data) = env_ref.as_environment_mut() {
                 if let Some(binding) = data.bindings.get_mut(&key) {
+                    if !binding.initialized {
+                        return Err(JsError::uninitialized_binding(name));
+                    }
                     if !binding.mutable {
                         return Err(JsError::type_error(format!(
                             "Assignment to constant variable '{}'",
                             name
                         )));
                     }
+                    if self.purity.is_some() {
+                        self.check_write(&env, Some(&PropertyKey::String(name.cheap_clone())))?;
+                    }
                     // Update binding value - Gc clone/drop handles ref_count automatically
                     binding.value = value;
                     return Ok(());
@@ -2202,37 +4880,73 @@ impl Interpreter {
             }
         }
 
+        // Script globals assign to the global object, where vars and
+        // functions of earlier scripts live
+        let key = PropertyKey::String(name.cheap_clone());
+        self.install_builtin(name.as_str());
+        if self.script_globals && self.global.borrow().has_own_property(&key) {
+            let global = self.global.cheap_clone();
+            if self.purity.is_some() {
+                self.check_write(&global, Some(&key))?;
+            }
+            global.borrow_mut().set_property(key, value);
+            return Ok(());
+        }
+
         Err(JsError::reference_error(name.to_string()))
     }
 
     /// Push a new scope and return the saved environment
-    pub fn push_scope(&mut self) -> EnvRef {
+    pub(crate) fn push_scope(&mut self) -> EnvRef {
         let (new_env, new_guard) =
             create_environment_unrooted(&self.heap, Some(self.env.cheap_clone()));
 
         let old_env = self.env.cheap_clone();
         self.env = new_env;
+        new_guard.set_label("environment");
         self.env_guards.push(new_guard);
         old_env
     }
 
     /// Pop scope by restoring saved environment
-    pub fn pop_scope(&mut self, saved_env: EnvRef) {
+    pub(crate) fn pop_scope(&mut self, saved_env: EnvRef) {
         self.env = saved_env;
         // Pop the guard that was pushed when this scope was created
         self.env_guards.pop();
     }
 
     /// Push an environment guard (for env changes without push_scope)
-    pub fn push_env_guard(&mut self, guard: Guard<JsObject>) {
+    pub(crate) fn push_env_guard(&mut self, guard: Guard<JsObject>) {
+        guard.set_label("environment");
         self.env_guards.push(guard);
     }
 
     /// Pop an environment guard
-    pub fn pop_env_guard(&mut self) {
+    pub(crate) fn pop_env_guard(&mut self) {
         self.env_guards.pop();
     }
 
+    /// Create the environment for a bytecode function call, with `closure` as parent.
+    ///
+    /// When `recyclable` is set the environment comes from the call pool if one
+    /// is available; the caller must hand it back with `CallPool::release_env`
+    /// once the call returns.
+    pub(crate) fn create_function_env(
+        &mut self,
+        closure: EnvRef,
+        capacity: usize,
+        recyclable: bool,
+    ) -> (EnvRef, Guard<JsObject>) {
+        if recyclable
+            && let Some(pooled) = self
+                .call_pool
+                .acquire_env(&self.heap, closure.cheap_clone())
+        {
+            return pooled;
+        }
+        create_environment_unrooted_with_capacity(&self.heap, Some(closure), capacity)
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // Object/Array/Function Creation
     // ═══════════════════════════════════════════════════════════════════════════
@@ -2387,7 +5101,7 @@ impl Interpreter {
     #[cfg(feature = "c-api")]
     pub fn register_ffi_module(&mut self, specifier: &str, namespace: Gc<JsObject>) {
         // Root the module (lives forever)
-        self.root_guard.guard(namespace.clone());
+        self.module_guard.guard(namespace.clone());
         // Cache it directly
         self.internal_module_cache
             .insert(specifier.to_string(), namespace);
@@ -2426,9 +5140,15 @@ impl Interpreter {
             f_ref.prototype = Some(self.function_prototype.clone());
             f_ref.exotic = ExoticObject::Function(JsFunction::Bytecode(bc_func));
             // Set length property (number of formal parameters)
-            f_ref.set_property(length_key, JsValue::Number(param_count as f64));
+            f_ref.define_property(
+                length_key,
+                Property::with_attributes(JsValue::Number(param_count as f64), false, false, true),
+            );
             // Set name property
-            f_ref.set_property(name_key, JsValue::String(func_name));
+            f_ref.define_property(
+                name_key,
+                Property::with_attributes(JsValue::String(func_name), false, false, true),
+            );
         }
 
         // Regular functions (not arrow functions) need a .prototype property
@@ -2437,13 +5157,20 @@ impl Interpreter {
             let proto_obj = guard.alloc();
             proto_obj.borrow_mut().prototype = Some(self.object_prototype.clone());
             // Set prototype.constructor = function
-            proto_obj
-                .borrow_mut()
-                .set_property(ctor_key, JsValue::Object(func_obj.cheap_clone()));
+            proto_obj.borrow_mut().define_property(
+                ctor_key,
+                Property::with_attributes(
+                    JsValue::Object(func_obj.cheap_clone()),
+                    true,
+                    false,
+                    true,
+                ),
+            );
             // Set function.prototype = prototype object
-            func_obj
-                .borrow_mut()
-                .set_property(proto_key, JsValue::Object(proto_obj));
+            func_obj.borrow_mut().define_property(
+                proto_key,
+                Property::with_attributes(JsValue::Object(proto_obj), true, false, false),
+            );
         }
 
         func_obj
@@ -2480,8 +5207,14 @@ impl Interpreter {
             let mut f_ref = func_obj.borrow_mut();
             f_ref.prototype = Some(self.function_prototype.clone());
             f_ref.exotic = ExoticObject::Function(JsFunction::BytecodeGenerator(bc_func));
-            f_ref.set_property(length_key, JsValue::Number(param_count as f64));
-            f_ref.set_property(name_key, JsValue::String(func_name));
+            f_ref.define_property(
+                length_key,
+                Property::with_attributes(JsValue::Number(param_count as f64), false, false, true),
+            );
+            f_ref.define_property(
+                name_key,
+                Property::with_attributes(JsValue::String(func_name), false, false, true),
+            );
         }
         func_obj
     }
@@ -2517,8 +5250,14 @@ impl Interpreter {
             let mut f_ref = func_obj.borrow_mut();
             f_ref.prototype = Some(self.function_prototype.clone());
             f_ref.exotic = ExoticObject::Function(JsFunction::BytecodeAsync(bc_func));
-            f_ref.set_property(length_key, JsValue::Number(param_count as f64));
-            f_ref.set_property(name_key, JsValue::String(func_name));
+            f_ref.define_property(
+                length_key,
+                Property::with_attributes(JsValue::Number(param_count as f64), false, false, true),
+            );
+            f_ref.define_property(
+                name_key,
+                Property::with_attributes(JsValue::String(func_name), false, false, true),
+            );
         }
         func_obj
     }
@@ -2553,8 +5292,14 @@ impl Interpreter {
             let mut f_ref = func_obj.borrow_mut();
             f_ref.prototype = Some(self.function_prototype.clone());
             f_ref.exotic = ExoticObject::Function(JsFunction::BytecodeAsyncGenerator(bc_func));
-            f_ref.set_property(length_key, JsValue::Number(param_count as f64));
-            f_ref.set_property(name_key, JsValue::String(func_name));
+            f_ref.define_property(
+                length_key,
+                Property::with_attributes(JsValue::Number(param_count as f64), false, false, true),
+            );
+            f_ref.define_property(
+                name_key,
+                Property::with_attributes(JsValue::String(func_name), false, false, true),
+            );
         }
         func_obj
     }
@@ -2570,6 +5315,9 @@ impl Interpreter {
 
     /// Convert a JsValue to its string representation using interned strings
     /// for common values (undefined, null, true, false).
+    ///
+    /// Runs no script code, so objects don't get their own `toString`; use
+    /// `coerce_to_string` for the ToString of the spec.
     pub fn to_js_string(&mut self, value: &JsValue) -> JsString {
         match value {
             JsValue::Undefined => self.intern("undefined"),
@@ -2635,12 +5383,7 @@ impl Interpreter {
 
     /// Create a PropertyKey from a string, using interned strings.
     pub fn property_key(&mut self, s: &str) -> PropertyKey {
-        // Fast path: check if it's an array index
-        if let Some(first) = s.bytes().next()
-            && first.is_ascii_digit()
-            && let Ok(idx) = s.parse::<u32>()
-            && idx.to_string() == s
-        {
+        if let Some(idx) = PropertyKey::array_index(s) {
             return PropertyKey::Index(idx);
         }
         PropertyKey::String(self.intern(s))
@@ -2648,12 +5391,7 @@ impl Interpreter {
 
     /// Create a PropertyKey from an already-interned JsString.
     pub fn property_key_from_js_string(&mut self, s: JsString) -> PropertyKey {
-        // Fast path: check if it's an array index
-        if let Some(first) = s.as_str().bytes().next()
-            && first.is_ascii_digit()
-            && let Ok(idx) = s.parse::<u32>()
-            && idx.to_string() == s.as_str()
-        {
+        if let Some(idx) = PropertyKey::array_index(s.as_str()) {
             return PropertyKey::Index(idx);
         }
         PropertyKey::String(s)
@@ -2662,20 +5400,27 @@ impl Interpreter {
     /// Create a PropertyKey from a JsValue.
     pub fn property_key_from_value(&mut self, value: &JsValue) -> PropertyKey {
         match value {
-            JsValue::Number(n) => {
-                let idx = *n as u32;
-                if idx as f64 == *n && *n >= 0.0 {
-                    PropertyKey::Index(idx)
-                } else {
-                    PropertyKey::String(self.to_js_string(value))
-                }
-            }
+            JsValue::Number(n) => match PropertyKey::array_index_from_number(*n) {
+                Some(idx) => PropertyKey::Index(idx),
+                None => PropertyKey::String(self.to_js_string(value)),
+            },
             JsValue::String(s) => self.property_key_from_js_string(s.cheap_clone()),
             JsValue::Symbol(s) => PropertyKey::Symbol(s.clone()),
             _ => PropertyKey::String(self.to_js_string(value)),
         }
     }
 
+    /// ToPropertyKey: objects are converted with ToPrimitive (string hint) first
+    pub fn to_property_key(&mut self, value: &JsValue) -> Result<PropertyKey, JsError> {
+        match value {
+            JsValue::Object(_) => {
+                let prim = self.coerce_to_primitive(value, "string")?;
+                Ok(self.property_key_from_value(&prim))
+            }
+            _ => Ok(self.property_key_from_value(value)),
+        }
+    }
+
     /// Create a native function object, permanently rooted via `root_guard`.
     /// Use this for builtin constructors and methods during initialization.
     /// The function is permanently rooted and never collected.
@@ -2699,9 +5444,15 @@ impl Interpreter {
                 ffi_id: 0,
             }));
             // Set length property (number of formal parameters)
-            f_ref.set_property(length_key, JsValue::Number(arity as f64));
+            f_ref.define_property(
+                length_key,
+                Property::with_attributes(JsValue::Number(arity as f64), false, false, true),
+            );
             // Set name property
-            f_ref.set_property(name_key, JsValue::String(name_str));
+            f_ref.define_property(
+                name_key,
+                Property::with_attributes(JsValue::String(name_str), false, false, true),
+            );
         }
         func_obj
     }
@@ -2720,16 +5471,17 @@ impl Interpreter {
         let (func_name, arity) = match &func {
             JsFunction::Native(f) => (f.name.cheap_clone(), f.arity),
             JsFunction::Bound(b) => {
-                // Bound functions: compute name and length from target
-                let (target_name, target_length) =
-                    if let ExoticObject::Function(target_func) = &b.target.borrow().exotic {
-                        match target_func {
-                            JsFunction::Native(f) => (f.name.to_string(), f.arity),
-                            _ => (String::new(), 0),
-                        }
-                    } else {
-                        (String::new(), 0)
-                    };
+                // Bound functions take name and length from the target's properties
+                let target = b.target.borrow();
+                let target_name = match target.get_property(&name_key) {
+                    Some(JsValue::String(name)) => name,
+                    _ => JsString::from(""),
+                };
+                let target_length = match target.get_property(&length_key) {
+                    Some(JsValue::Number(n)) if n.is_finite() && n > 0.0 => n as usize,
+                    _ => 0,
+                };
+                drop(target);
                 let name = self.intern(&format!("bound {}", target_name));
                 let arity = target_length.saturating_sub(b.bound_args.len());
                 (name, arity)
@@ -2743,9 +5495,15 @@ impl Interpreter {
             f_ref.prototype = Some(self.function_prototype.clone());
             f_ref.exotic = ExoticObject::Function(func);
             // Set length property (number of formal parameters)
-            f_ref.set_property(length_key, JsValue::Number(arity as f64));
+            f_ref.define_property(
+                length_key,
+                Property::with_attributes(JsValue::Number(arity as f64), false, false, true),
+            );
             // Set name property
-            f_ref.set_property(name_key, JsValue::String(func_name));
+            f_ref.define_property(
+                name_key,
+                Property::with_attributes(JsValue::String(func_name), false, false, true),
+            );
         }
         func_obj
     }
@@ -2767,6 +5525,24 @@ impl Interpreter {
         obj.borrow_mut().define_property(key, prop);
     }
 
+    /// Register an accessor property on an object (for builtin initialization).
+    /// Uses root_guard internally - functions are permanently rooted.
+    /// Per WebIDL, builtin accessors are: enumerable, configurable
+    pub fn register_accessor(
+        &mut self,
+        obj: &Gc<JsObject>,
+        name: &str,
+        getter: NativeFn,
+        setter: Option<NativeFn>,
+    ) {
+        let getter_obj = self.create_native_function(&format!("get {}", name), getter, 0);
+        let setter_obj =
+            setter.map(|f| self.create_native_function(&format!("set {}", name), f, 1));
+        let key = PropertyKey::String(self.intern(name));
+        obj.borrow_mut()
+            .define_property(key, Property::accessor(Some(getter_obj), setter_obj));
+    }
+
     /// Register Symbol.species getter on a constructor.
     /// Per ECMAScript spec, Symbol.species is a getter that returns `this`.
     /// Uses root_guard internally - the getter is permanently rooted.
@@ -2803,26 +5579,40 @@ impl Interpreter {
         }
     }
 
+    /// Freeze `value` and everything reachable from it, as `Object.deepFreeze`
+    /// does, returning the number of objects frozen.
+    ///
+    /// Use this to hand scripts input they can't modify, or to make script
+    /// output safe to cache. Fails on a Proxy in the graph. With
+    /// [`crate::InterpreterConfig::strict_property_access`], scripts reading
+    /// a property the frozen objects don't have get a `TypeError`.
+    pub fn deep_freeze(&mut self, value: &crate::RuntimeValue) -> Result<usize, JsError> {
+        builtins::object::deep_freeze(value.value(), true)
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // Generator Support
     // ═══════════════════════════════════════════════════════════════════════════
 
     /// Resume a bytecode generator from a suspended state
+    ///
+    /// Returns the iterator result, or `None` when an async generator's body
+    /// is parked on an `await` (see `run_generator_vm`).
     pub fn resume_bytecode_generator(
         &mut self,
         gen_state: &Rc<RefCell<BytecodeGeneratorState>>,
-    ) -> Result<Guarded, JsError> {
-        use bytecode_vm::{BytecodeVM, VmResult};
+    ) -> Result<Option<Guarded>, JsError> {
+        use bytecode_vm::BytecodeVM;
 
         // Check if generator is already completed
         {
             let state = gen_state.borrow();
             if state.status == GeneratorStatus::Completed {
-                return Ok(builtins::create_generator_result(
+                return Ok(Some(builtins::create_generator_result(
                     self,
                     JsValue::Undefined,
                     true,
-                ));
+                )));
             }
         }
 
@@ -2834,6 +5624,7 @@ impl Interpreter {
             saved_registers,
             saved_call_stack,
             saved_try_stack,
+            saved_env_stack,
             chunk,
             yield_result_register,
             closure,
@@ -2850,6 +5641,7 @@ impl Interpreter {
                 state.saved_registers.clone(),
                 state.saved_call_stack.clone(),
                 state.saved_try_stack.clone(),
+                state.saved_env_stack.clone(),
                 state.chunk.clone(),
                 state.yield_result_register,
                 state.closure.clone(),
@@ -2867,8 +5659,7 @@ impl Interpreter {
         // For subsequent calls, use the saved current environment (which may include block scopes)
         let (gen_env, env_guard) = if !started {
             // Create a new environment with closure as parent
-            let (new_env, guard) =
-                create_environment_unrooted(&self.heap, Some(closure.cheap_clone()));
+            let (new_env, guard) = create_environment_unrooted(&self.heap, closure.clone());
             // Save it for future calls
             gen_state.borrow_mut().func_env = Some(new_env.cheap_clone());
             (new_env, Some(guard))
@@ -2880,17 +5671,16 @@ impl Interpreter {
             (env, None)
         } else {
             // Fallback: create new environment (shouldn't happen)
-            let (new_env, guard) =
-                create_environment_unrooted(&self.heap, Some(closure.cheap_clone()));
+            let (new_env, guard) = create_environment_unrooted(&self.heap, closure.clone());
             gen_state.borrow_mut().func_env = Some(new_env.cheap_clone());
             (new_env, Some(guard))
         };
 
-        // Set the generator's environment as the current environment
+        // Set the generator's environment as the current environment. The
+        // generator object traces it from here on; the guard only covers this
+        // call, so it stays off the environment guard stack
         self.env = gen_env;
-        if let Some(guard) = env_guard {
-            self.push_env_guard(guard);
-        }
+        let _env_guard = env_guard;
 
         let vm_guard = self.heap.create_guard();
 
@@ -2919,73 +5709,14 @@ impl Interpreter {
             };
 
             // Create VM with arguments and the original this value
-            let mut vm = BytecodeVM::with_guard_and_args(
+            let vm = BytecodeVM::with_guard_and_args(
                 chunk,
                 this_value.clone(),
                 vm_guard,
                 &processed_args,
             );
 
-            match vm.run(self) {
-                VmResult::Complete(guarded) => {
-                    // Generator completed normally
-                    gen_state.borrow_mut().status = GeneratorStatus::Completed;
-                    self.env = saved_env;
-                    Ok(builtins::create_generator_result(self, guarded.value, true))
-                }
-                VmResult::Yield(yield_result) => {
-                    // Save the VM state and current environment for resumption
-                    {
-                        let mut state = gen_state.borrow_mut();
-                        state.saved_ip = yield_result.state.ip;
-                        state.saved_registers = yield_result.state.registers;
-                        state.saved_call_stack = yield_result.state.frames;
-                        state.saved_try_stack = yield_result.state.try_stack;
-                        state.yield_result_register = Some(yield_result.resume_register);
-                        // Save current environment (may include block scopes)
-                        state.current_env = Some(self.env.cheap_clone());
-                    }
-                    self.env = saved_env;
-                    Ok(builtins::create_generator_result(
-                        self,
-                        yield_result.value.value, // Extract JsValue from Guarded
-                        false,
-                    ))
-                }
-                VmResult::YieldStar(yield_star_result) => {
-                    // For yield*, we need to iterate over the iterable
-                    // Save state and start delegating
-                    {
-                        let mut state = gen_state.borrow_mut();
-                        state.saved_ip = yield_star_result.state.ip;
-                        state.saved_registers = yield_star_result.state.registers;
-                        state.saved_call_stack = yield_star_result.state.frames;
-                        state.saved_try_stack = yield_star_result.state.try_stack;
-                        state.yield_result_register = Some(yield_star_result.resume_register);
-                        // Save current environment (may include block scopes)
-                        state.current_env = Some(self.env.cheap_clone());
-                    }
-                    // Delegate to the iterable - get its iterator and next value
-                    self.start_yield_star_delegation(
-                        gen_state,
-                        yield_star_result.iterable.value, // Extract JsValue from Guarded
-                        saved_env,
-                    )
-                }
-                VmResult::Suspend(_) | VmResult::SuspendForOrder(_) => {
-                    // Should not happen for generators
-                    gen_state.borrow_mut().status = GeneratorStatus::Completed;
-                    self.env = saved_env;
-                    Err(JsError::internal_error(
-                        "Unexpected suspension in generator",
-                    ))
-                }
-                VmResult::Error(e) => {
-                    gen_state.borrow_mut().status = GeneratorStatus::Completed;
-                    self.env = saved_env;
-                    Err(e)
-                }
-            }
+            self.run_generator_vm(gen_state, vm, saved_env)
         } else {
             // Resume from saved state
             // Create guard for the saved state
@@ -3015,6 +5746,10 @@ impl Interpreter {
                 arguments: args.clone(),
                 new_target: JsValue::Undefined,
                 trampoline_stack: Vec::new(), // Generators run at top level
+                this_value: None,
+                current_constructor: None,
+                env_guards: bytecode_vm::scope_guards(&self.heap, saved_env_stack.len(), &self.env),
+                saved_env_stack,
             };
 
             // Create guard for the VM registers
@@ -3024,17 +5759,29 @@ impl Interpreter {
 
             // Check if we need to throw an exception (generator.throw())
             let throw_value = gen_state.borrow_mut().throw_value.take();
+            let return_value = gen_state.borrow_mut().return_value.take();
             if let Some(exception) = throw_value {
                 // Inject the exception - if there's a handler, it will jump to catch
                 // If no handler, the exception will propagate
                 if !vm.inject_exception(self, exception.clone()) {
                     // No exception handler found, propagate the error
-                    gen_state.borrow_mut().status = GeneratorStatus::Completed;
+                    gen_state.borrow_mut().complete();
                     self.env = saved_env;
                     let guarded = Guarded::from_value(exception, &self.heap);
                     return Err(JsError::ThrownValue { guarded });
                 }
                 // Handler found - continue to run the VM which will execute the catch block
+            } else if let Some(value) = return_value {
+                // generator.return() - run enclosing finally blocks before completing
+                if let Some(guarded) = vm.inject_return(self, value)? {
+                    gen_state.borrow_mut().complete();
+                    self.env = saved_env;
+                    return Ok(Some(builtins::create_generator_result(
+                        self,
+                        guarded.value,
+                        true,
+                    )));
+                }
             } else {
                 // Normal resume - set the sent value in the yield result register
                 if let Some(resume_reg) = yield_result_register {
@@ -3042,72 +5789,177 @@ impl Interpreter {
                 }
             }
 
-            match vm.run(self) {
-                VmResult::Complete(guarded) => {
-                    gen_state.borrow_mut().status = GeneratorStatus::Completed;
-                    self.env = saved_env;
-                    Ok(builtins::create_generator_result(self, guarded.value, true))
-                }
-                VmResult::Yield(yield_result) => {
-                    // Save the VM state and current environment for next resumption
-                    {
-                        let mut state = gen_state.borrow_mut();
-                        state.saved_ip = yield_result.state.ip;
-                        state.saved_registers = yield_result.state.registers;
-                        state.saved_call_stack = yield_result.state.frames;
-                        state.saved_try_stack = yield_result.state.try_stack;
-                        state.yield_result_register = Some(yield_result.resume_register);
-                        // Save current environment (may include block scopes)
-                        state.current_env = Some(self.env.cheap_clone());
-                    }
-                    self.env = saved_env;
-                    Ok(builtins::create_generator_result(
-                        self,
-                        yield_result.value.value, // Extract JsValue from Guarded
-                        false,
-                    ))
-                }
-                VmResult::YieldStar(yield_star_result) => {
-                    // Save state for yield* delegation
-                    {
-                        let mut state = gen_state.borrow_mut();
-                        state.saved_ip = yield_star_result.state.ip;
-                        state.saved_registers = yield_star_result.state.registers;
-                        state.saved_call_stack = yield_star_result.state.frames;
-                        state.saved_try_stack = yield_star_result.state.try_stack;
-                        state.yield_result_register = Some(yield_star_result.resume_register);
-                        // Save current environment (may include block scopes)
-                        state.current_env = Some(self.env.cheap_clone());
-                    }
-                    self.start_yield_star_delegation(
-                        gen_state,
-                        yield_star_result.iterable.value, // Extract JsValue from Guarded
-                        saved_env,
-                    )
-                }
-                VmResult::Suspend(_) | VmResult::SuspendForOrder(_) => {
-                    gen_state.borrow_mut().status = GeneratorStatus::Completed;
-                    self.env = saved_env;
-                    Err(JsError::internal_error(
-                        "Unexpected suspension in generator",
-                    ))
+            self.run_generator_vm(gen_state, vm, saved_env)
+        }
+    }
+
+    /// Resume an async generator body parked on an `await` once the awaited
+    /// promise settles, with its value (`is_fulfill`) or rejection reason.
+    ///
+    /// Returns the iterator result for the request being processed, or `None`
+    /// when the body awaits again.
+    pub(crate) fn resume_async_generator_await(
+        &mut self,
+        gen_state: &Rc<RefCell<BytecodeGeneratorState>>,
+        value: JsValue,
+        is_fulfill: bool,
+    ) -> Result<Option<Guarded>, JsError> {
+        use bytecode_vm::BytecodeVM;
+
+        let (awaiting, this_value) = {
+            let mut state = gen_state.borrow_mut();
+            (state.suspended_await.take(), state.this_value.clone())
+        };
+        let Some(awaiting) = awaiting else {
+            return Err(JsError::internal_error(
+                "Async generator resumed without a pending await",
+            ));
+        };
+
+        let saved_env = mem::replace(&mut self.env, awaiting.env);
+        let vm_guard = self.heap.create_guard();
+        let mut vm = BytecodeVM::from_saved_state(awaiting.state, this_value, vm_guard, &self.heap);
+
+        if is_fulfill {
+            vm.set_resume_value(awaiting.resume_register, value);
+        } else if !vm.inject_exception(self, value.clone()) {
+            gen_state.borrow_mut().complete();
+            self.env = saved_env;
+            let guarded = Guarded::from_value(value, &self.heap);
+            return Err(JsError::ThrownValue { guarded });
+        }
+
+        self.run_generator_vm(gen_state, vm, saved_env)
+    }
+
+    /// Run a generator's VM until it yields, completes, throws or (for async
+    /// generators) awaits a pending promise or order.
+    ///
+    /// An async generator parked on an `await` keeps its VM state in
+    /// `suspended_await` and returns `None`; the caller subscribes to the
+    /// awaited promise and resumes it with `resume_async_generator_await`.
+    fn run_generator_vm(
+        &mut self,
+        gen_state: &Rc<RefCell<BytecodeGeneratorState>>,
+        mut vm: bytecode_vm::BytecodeVM,
+        saved_env: Gc<JsObject>,
+    ) -> Result<Option<Guarded>, JsError> {
+        use bytecode_vm::VmResult;
+
+        match vm.run(self) {
+            VmResult::Complete(guarded) => {
+                // Generator completed normally
+                gen_state.borrow_mut().complete();
+                self.env = saved_env;
+                Ok(Some(builtins::create_generator_result(
+                    self,
+                    guarded.value,
+                    true,
+                )))
+            }
+            VmResult::Yield(yield_result) => {
+                // Save the VM state and current environment for resumption
+                {
+                    let mut state = gen_state.borrow_mut();
+                    state.saved_ip = yield_result.state.ip;
+                    state.saved_registers = yield_result.state.registers;
+                    state.saved_call_stack = yield_result.state.frames;
+                    state.saved_try_stack = yield_result.state.try_stack;
+                    state.saved_env_stack = yield_result.state.saved_env_stack;
+                    state.yield_result_register = Some(yield_result.resume_register);
+                    // Save current environment (may include block scopes)
+                    state.current_env = Some(self.env.cheap_clone());
                 }
-                VmResult::Error(e) => {
-                    gen_state.borrow_mut().status = GeneratorStatus::Completed;
-                    self.env = saved_env;
-                    Err(e)
+                self.env = saved_env;
+                Ok(Some(builtins::create_generator_result(
+                    self,
+                    yield_result.value.value, // Extract JsValue from Guarded
+                    false,
+                )))
+            }
+            VmResult::YieldStar(yield_star_result) => {
+                // For yield*, we need to iterate over the iterable
+                // Save state and start delegating
+                {
+                    let mut state = gen_state.borrow_mut();
+                    state.saved_ip = yield_star_result.state.ip;
+                    state.saved_registers = yield_star_result.state.registers;
+                    state.saved_call_stack = yield_star_result.state.frames;
+                    state.saved_try_stack = yield_star_result.state.try_stack;
+                    state.saved_env_stack = yield_star_result.state.saved_env_stack;
+                    state.yield_result_register = Some(yield_star_result.resume_register);
+                    // Save current environment (may include block scopes)
+                    state.current_env = Some(self.env.cheap_clone());
                 }
+                // Delegate to the iterable - get its iterator and next value
+                self.start_yield_star_delegation(
+                    gen_state,
+                    yield_star_result.iterable.value, // Extract JsValue from Guarded
+                    saved_env,
+                )
+            }
+            VmResult::Suspend(suspension) if gen_state.borrow().is_async => {
+                self.park_async_generator(
+                    gen_state,
+                    suspension.state,
+                    suspension.resume_register,
+                    suspension.waiting_on,
+                );
+                self.env = saved_env;
+                Ok(None)
+            }
+            VmResult::SuspendForOrder(suspension) if gen_state.borrow().is_async => {
+                // Batched orders have no promise yet; make one that fulfill_orders settles
+                let guard = self.heap.create_guard();
+                let promise = self.create_tracked_order_promise(&guard, suspension.order_id);
+                self.park_async_generator(
+                    gen_state,
+                    suspension.state,
+                    suspension.resume_register,
+                    promise,
+                );
+                self.env = saved_env;
+                Ok(None)
+            }
+            VmResult::Suspend(_) | VmResult::SuspendForOrder(_) => {
+                // Sync generators cannot await
+                gen_state.borrow_mut().complete();
+                self.env = saved_env;
+                Err(JsError::internal_error(
+                    "Unexpected suspension in generator",
+                ))
+            }
+            VmResult::Error(e) => {
+                gen_state.borrow_mut().complete();
+                self.env = saved_env;
+                Err(e)
             }
         }
     }
 
+    /// Keep the VM state of an async generator body that awaits `waiting_on`
+    fn park_async_generator(
+        &mut self,
+        gen_state: &Rc<RefCell<BytecodeGeneratorState>>,
+        state: bytecode_vm::SavedVmState,
+        resume_register: crate::compiler::Register,
+        waiting_on: Gc<JsObject>,
+    ) {
+        gen_state.borrow_mut().suspended_await = Some(Box::new(AsyncGeneratorAwait {
+            state,
+            resume_register,
+            env: self.env.cheap_clone(),
+            waiting_on,
+        }));
+    }
+
     /// Start yield* delegation - get the first value from the iterable
     fn start_yield_star_delegation(
         &mut self,
         gen_state: &Rc<RefCell<BytecodeGeneratorState>>,
         iterable: JsValue,
         saved_env: Gc<JsObject>,
-    ) -> Result<Guarded, JsError> {
+    ) -> Result<Option<Guarded>, JsError> {
         // For yield*, we need to:
         // 1. Get the iterator from the iterable
         // 2. Store it in the generator state for delegation
@@ -3227,7 +6079,7 @@ impl Interpreter {
             gen_state.borrow_mut().delegated_iterator = Some((iter_obj, next_method));
             gen_state.borrow_mut().status = GeneratorStatus::Suspended;
             self.env = saved_env;
-            Ok(builtins::create_generator_result(self, value, false))
+            Ok(Some(builtins::create_generator_result(self, value, false)))
         }
     }
 
@@ -3250,73 +6102,278 @@ impl Interpreter {
             _ => false,
         };
 
-        (value, done)
+        (value, done)
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════════
+    // Evaluation Entry Point
+    // ═══════════════════════════════════════════════════════════════════════════
+
+    /// Run bytecode using the bytecode VM
+    ///
+    /// This is the bytecode execution entry point. It compiles the program
+    /// to bytecode and then executes it using the bytecode VM.
+    pub fn run_bytecode(
+        &mut self,
+        chunk: Rc<crate::compiler::BytecodeChunk>,
+    ) -> Result<Guarded, JsError> {
+        self.run_bytecode_with_this(chunk, JsValue::Object(self.global.clone()))
+    }
+
+    /// Run bytecode using the bytecode VM with a specific `this` value
+    fn run_bytecode_with_this(
+        &mut self,
+        chunk: Rc<crate::compiler::BytecodeChunk>,
+        this_value: JsValue,
+    ) -> Result<Guarded, JsError> {
+        use bytecode_vm::{BytecodeVM, VmResult};
+
+        let vm_guard = self.heap.create_guard();
+        let mut vm = BytecodeVM::with_guard(chunk, this_value, vm_guard);
+
+        match vm.run(self) {
+            VmResult::Complete(guarded) => Ok(guarded),
+            VmResult::Error(err) => Err(err),
+            VmResult::Suspend(_) | VmResult::SuspendForOrder(_) => Err(JsError::internal_error(
+                "Bytecode execution cannot suspend at top level",
+            )),
+            VmResult::Yield(_) | VmResult::YieldStar(_) => Err(JsError::internal_error(
+                "Bytecode execution cannot yield at top level",
+            )),
+        }
+    }
+
+    /// Compile source to bytecode without running it and return the listing.
+    ///
+    /// The source is compiled as an entry program, which keeps its
+    /// completion value in register 0. Imports are not resolved, so modules compile even before their
+    /// dependencies are provided. See [`crate::compiler::BytecodeChunk::disassemble`]
+    /// for the format.
+    pub fn disassemble(
+        &mut self,
+        source: &str,
+        module_path: Option<crate::ModulePath>,
+    ) -> Result<String, JsError> {
+        let program = self.parse_program(source)?;
+        let module_path = module_path.map(|path| crate::ModulePath::entry(path.as_str()));
+        let chunk = compile_program(
+            &program,
+            module_path.as_ref(),
+            self.compile_limits,
+            true,
+            None,
+        )?;
+        Ok(chunk.disassemble())
+    }
+
+    /// Compile the entry program, as a global script when `script_globals`
+    /// is set and it has no module path
+    fn compile_entry_chunk(
+        &mut self,
+        program: &crate::ast::Program,
+        module_path: Option<&crate::ModulePath>,
+    ) -> Result<Rc<crate::compiler::BytecodeChunk>, JsError> {
+        if !self.script_globals
+            || module_path.is_some()
+            || program.source_type == crate::ast::SourceType::Module
+        {
+            return self.compile_program_chunk(program, module_path, true);
+        }
+        self.instantiate_global_declarations(program)?;
+        let mut chunk =
+            crate::compiler::Compiler::compile_global_script(program, self.compile_limits)?;
+        self.intern_chunk(&mut chunk);
+        self.dump_chunk(&chunk);
+        Ok(chunk)
+    }
+
+    /// Global declaration instantiation for a script run with `script_globals`.
+    ///
+    /// Every top-level name is checked against the bindings earlier scripts
+    /// left behind before anything is created, so a clash leaves the global
+    /// scope untouched. Vars and functions then get their global object
+    /// properties; functions receive their values when the script starts.
+    fn instantiate_global_declarations(
+        &mut self,
+        program: &crate::ast::Program,
+    ) -> Result<(), JsError> {
+        let mut decls = crate::compiler::script_declarations(&program.body);
+        for name in decls
+            .var_names
+            .iter_mut()
+            .chain(&mut decls.function_names)
+            .chain(&mut decls.lexical_names)
+            .chain(&mut decls.block_function_names)
+        {
+            *name = self.intern(name.as_str());
+        }
+        let global = self.global.cheap_clone();
+        let env = self.env.cheap_clone();
+        let has_lexical = |name: &JsString| {
+            env.borrow()
+                .as_environment()
+                .is_some_and(|data| data.bindings.contains_key(&VarKey(name.cheap_clone())))
+        };
+        let already_declared = |name: &JsString| {
+            JsError::syntax_error(
+                format!("Identifier '{}' has already been declared", name),
+                0,
+                0,
+            )
+        };
+
+        for name in &decls.lexical_names {
+            let key = PropertyKey::String(name.cheap_clone());
+            if has_lexical(name)
+                || global.borrow().is_non_configurable_own(&key)
+                || decls.var_names.contains(name)
+                || decls.function_names.contains(name)
+            {
+                return Err(already_declared(name));
+            }
+        }
+        for name in decls.var_names.iter().chain(&decls.function_names) {
+            if has_lexical(name) {
+                return Err(already_declared(name));
+            }
+        }
+        for name in &decls.function_names {
+            let key = PropertyKey::String(name.cheap_clone());
+            let redefinable = global.borrow().properties.get(&key).is_none_or(|prop| {
+                prop.configurable() || (!prop.is_accessor() && prop.writable() && prop.enumerable())
+            });
+            if !redefinable {
+                return Err(JsError::type_error(format!(
+                    "Cannot redefine global function '{}'",
+                    name
+                )));
+            }
+        }
+
+        let mut global_ref = global.borrow_mut();
+        for name in &decls.function_names {
+            let key = PropertyKey::String(name.cheap_clone());
+            let configurable = global_ref
+                .properties
+                .get(&key)
+                .is_none_or(|prop| prop.configurable());
+            if configurable {
+                global_ref.define_property(
+                    key,
+                    Property::with_attributes(JsValue::Undefined, true, true, false),
+                );
+            }
+        }
+        // Functions declared in blocks of a sloppy script also become global
+        // vars, unless a lexical binding has their name (Annex B.3.3)
+        let block_functions = decls
+            .block_function_names
+            .iter()
+            .filter(|name| !has_lexical(name) && !decls.lexical_names.contains(name));
+        for name in decls.var_names.iter().chain(block_functions) {
+            let key = PropertyKey::String(name.cheap_clone());
+            if !global_ref.has_own_property(&key) && global_ref.extensible {
+                global_ref.define_property(
+                    key,
+                    Property::with_attributes(JsValue::Undefined, true, true, false),
+                );
+            }
+        }
+        Ok(())
     }
 
-    // ═══════════════════════════════════════════════════════════════════════════
-    // Evaluation Entry Point
-    // ═══════════════════════════════════════════════════════════════════════════
-
-    /// Run bytecode using the bytecode VM
-    ///
-    /// This is the bytecode execution entry point. It compiles the program
-    /// to bytecode and then executes it using the bytecode VM.
-    pub fn run_bytecode(
+    /// Compile a program for execution, dumping it if configured to
+    fn compile_program_chunk(
         &mut self,
-        chunk: Rc<crate::compiler::BytecodeChunk>,
-    ) -> Result<Guarded, JsError> {
-        self.run_bytecode_with_this(chunk, JsValue::Object(self.global.clone()))
+        program: &crate::ast::Program,
+        module_path: Option<&crate::ModulePath>,
+        entry: bool,
+    ) -> Result<Rc<crate::compiler::BytecodeChunk>, JsError> {
+        let constant_imports = self.constant_imports(program);
+        let mut chunk = compile_program(
+            program,
+            module_path,
+            self.compile_limits,
+            entry,
+            constant_imports,
+        )?;
+        self.intern_chunk(&mut chunk);
+        self.dump_chunk(&chunk);
+        Ok(chunk)
     }
 
-    /// Run bytecode using the bytecode VM with a specific `this` value
-    fn run_bytecode_with_this(
+    /// The imports of `program` whose reads fold to constants, when
+    /// `fold_constant_imports` is on for this run. Expects the import
+    /// bindings to be set up in the current environment.
+    fn constant_imports(
         &mut self,
-        chunk: Rc<crate::compiler::BytecodeChunk>,
-        this_value: JsValue,
-    ) -> Result<Guarded, JsError> {
-        use bytecode_vm::{BytecodeVM, VmResult};
+        program: &crate::ast::Program,
+    ) -> Option<Rc<crate::compiler::ConstantImports>> {
+        if !self.constant_imports_active {
+            return None;
+        }
+        // A module provided after the run started may still use eval
+        if crate::compiler::uses_dynamic_code(program) {
+            self.constant_imports_active = false;
+            return None;
+        }
 
-        let vm_guard = self.heap.create_guard();
-        let mut vm = BytecodeVM::with_guard(chunk, this_value, vm_guard);
+        let mut imports = crate::compiler::ConstantImports::default();
+        for name in crate::compiler::unshadowed_imports(program) {
+            let key = VarKey(self.intern(name.as_str()));
+            let env = self.env.borrow();
+            let Some(binding) = env
+                .as_environment()
+                .and_then(|data| data.bindings.get(&key))
+            else {
+                continue;
+            };
+            match (&binding.import_binding, &binding.value) {
+                (Some(import), _) => imports.add_named(
+                    name,
+                    import.module_obj.cheap_clone(),
+                    import.property_key.clone(),
+                ),
+                // `import * as name`
+                (None, JsValue::Object(namespace)) if !binding.mutable => {
+                    imports.add_namespace(name, namespace.cheap_clone())
+                }
+                (None, _) => {}
+            }
+        }
+        if imports.is_empty() {
+            return None;
+        }
+        // Restoring a checkpoint recompiles modules without the folding
+        self.folded_constant_imports = true;
+        Some(Rc::new(imports))
+    }
 
-        match vm.run(self) {
-            VmResult::Complete(guarded) => Ok(guarded),
-            VmResult::Error(err) => Err(err),
-            VmResult::Suspend(_) | VmResult::SuspendForOrder(_) => Err(JsError::internal_error(
-                "Bytecode execution cannot suspend at top level",
-            )),
-            VmResult::Yield(_) | VmResult::YieldStar(_) => Err(JsError::internal_error(
-                "Bytecode execution cannot yield at top level",
-            )),
+    /// Write a chunk's disassembly to the console when `dump_bytecode` is set.
+    ///
+    /// Goes straight to the provider: console limits are for script output.
+    pub(crate) fn dump_chunk(&self, chunk: &crate::compiler::BytecodeChunk) {
+        if self.dump_bytecode {
+            self.console_provider
+                .write(ConsoleLevel::Debug, &chunk.disassemble());
         }
     }
 
     /// Compile and run source code using bytecode VM
     pub fn eval_bytecode(&mut self, source: &str) -> Result<JsValue, JsError> {
-        use crate::compiler::Compiler;
-
-        let mut parser = crate::parser::Parser::new(source, &mut self.string_dict);
-        let program = parser.parse_program()?;
-        let chunk = Compiler::compile_program(&program)?;
+        let program = self.parse_program(source)?;
+        let chunk = self.compile_entry_chunk(&program, None)?;
         let result = self.run_bytecode(chunk)?;
         Ok(result.value)
     }
 
-    /// Execute a program (AST) using bytecode compilation
-    fn execute_program_bytecode(
-        &mut self,
-        program: &crate::ast::Program,
-    ) -> Result<JsValue, JsError> {
-        use crate::compiler::Compiler;
-
-        // Use current module path for source file in stack traces
-        let chunk = if let Some(ref path) = self.current_module_path {
-            Compiler::compile_program_with_source(program, path.as_str().to_string())?
+    /// Top-level `this` for a program: undefined in module code, the global object in scripts
+    fn top_level_this(&self, program: &crate::ast::Program) -> JsValue {
+        if program.source_type == crate::ast::SourceType::Module {
+            JsValue::Undefined
         } else {
-            Compiler::compile_program(program)?
-        };
-        let result = self.run_bytecode(chunk)?;
-        Ok(result.value)
+            JsValue::Object(self.global.cheap_clone())
+        }
     }
 
     /// Execute a program (AST) for eval with proper completion value tracking
@@ -3326,7 +6383,9 @@ impl Interpreter {
     ) -> Result<JsValue, JsError> {
         use crate::compiler::Compiler;
 
-        let chunk = Compiler::compile_program_for_eval(program)?;
+        let mut chunk = Compiler::compile_program_for_eval(program, self.compile_limits)?;
+        self.intern_chunk(&mut chunk);
+        self.dump_chunk(&chunk);
         let result = self.run_bytecode(chunk)?;
         Ok(result.value)
     }
@@ -3339,7 +6398,9 @@ impl Interpreter {
     ) -> Result<JsValue, JsError> {
         use crate::compiler::Compiler;
 
-        let chunk = Compiler::compile_program_for_eval(program)?;
+        let mut chunk = Compiler::compile_program_for_eval(program, self.compile_limits)?;
+        self.intern_chunk(&mut chunk);
+        self.dump_chunk(&chunk);
         let result = self.run_bytecode_with_this(chunk, this_value)?;
         Ok(result.value)
     }
@@ -3384,43 +6445,82 @@ impl Interpreter {
 
         // Check if it's a registered internal module
         if !self.internal_modules.contains_key(specifier) {
-            return Ok(None);
+            // Shared modules are already evaluated, so just copy them in
+            let Some(module_obj) = self.attach_shared_module(specifier) else {
+                return Ok(None);
+            };
+            self.internal_module_cache
+                .insert(specifier.to_string(), module_obj.cheap_clone());
+            return Ok(Some(module_obj));
         }
 
         // Get module definition - we need to clone to avoid borrow issues
-        let module_kind = {
+        let (module_kind, retained) = {
             let module = self
                 .internal_modules
                 .get(specifier)
                 .ok_or_else(|| JsError::internal_error("Module disappeared"))?;
-            module.kind.clone()
+            (module.kind.clone(), module.retain_across_reset)
         };
 
-        // Create module object based on kind
+        // Everything a retained module roots while it is created, including
+        // the modules it imports, outlives `reset`
+        let retaining = retained && !self.instantiating_retained;
+        if retaining {
+            mem::swap(&mut self.module_guard, &mut self.retained_module_guard);
+            self.instantiating_retained = true;
+        }
+        let module_obj = self.create_internal_module_object(specifier, module_kind);
+        if retaining {
+            mem::swap(&mut self.module_guard, &mut self.retained_module_guard);
+            self.instantiating_retained = false;
+        }
+        let module_obj = module_obj?;
+
+        // Cache it
+        self.internal_module_cache
+            .insert(specifier.to_string(), module_obj.clone());
+
+        Ok(Some(module_obj))
+    }
+
+    /// Create, seal and root the namespace of a registered internal module
+    fn create_internal_module_object(
+        &mut self,
+        specifier: &str,
+        module_kind: crate::InternalModuleKind,
+    ) -> Result<Gc<JsObject>, JsError> {
         let guard = self.heap.create_guard();
         let module_obj = match module_kind {
             crate::InternalModuleKind::Native(exports) => {
-                self.create_native_module_object(&guard, &exports)?
+                self.create_native_module_object(&guard, specifier, &exports)?
+            }
+            crate::InternalModuleKind::Lazy { resolver, own_keys } => {
+                let retained = self.instantiating_retained;
+                self.create_lazy_module_object(&guard, resolver, own_keys, retained)?
             }
             crate::InternalModuleKind::Source(source) => {
-                self.create_source_module_object(&guard, specifier, &source)?
+                self.check_internal_module_cycle(specifier)?;
+                self.internal_modules_loading.push(specifier.to_string());
+                let module_obj = self.create_source_module_object(&guard, specifier, &source);
+                self.internal_modules_loading.pop();
+                module_obj?
             }
         };
 
-        // Root the module (lives forever)
-        self.root_guard.guard(module_obj.clone());
+        self.seal_module_namespace(&module_obj);
 
-        // Cache it
-        self.internal_module_cache
-            .insert(specifier.to_string(), module_obj.clone());
+        // Root the module until `reset`, or for good if it is retained
+        self.module_guard.guard(module_obj.clone());
 
-        Ok(Some(module_obj))
+        Ok(module_obj)
     }
 
     /// Create module object from native exports
     fn create_native_module_object(
         &mut self,
         guard: &Guard<JsObject>,
+        specifier: &str,
         exports: &[(String, crate::InternalExport)],
     ) -> Result<Gc<JsObject>, JsError> {
         let module_obj = self.create_object(guard);
@@ -3434,6 +6534,8 @@ impl Interpreter {
                     arity,
                 } => {
                     let fn_obj = self.create_internal_function(fn_name, *func, *arity);
+                    self.internal_function_modules
+                        .insert(fn_obj.cheap_clone(), specifier.to_string());
                     JsValue::Object(fn_obj)
                 }
                 crate::InternalExport::Value(v) => v.clone(),
@@ -3448,31 +6550,46 @@ impl Interpreter {
     fn create_source_module_object(
         &mut self,
         guard: &Guard<JsObject>,
-        _specifier: &str,
+        specifier: &str,
         source: &str,
     ) -> Result<Gc<JsObject>, JsError> {
-        // Parse the source
-        let mut parser = Parser::new(source, &mut self.string_dict);
-        let program = parser.parse_program()?;
+        // Parse the source, unless its imports were collected already
+        let path = crate::ModulePath::new(specifier);
+        let program = match self.internal_module_programs.remove(specifier) {
+            Some(program) => program,
+            None => {
+                let timer = self.start_metrics_timer();
+                let program = self.parse_module_source(Some(&path), source)?;
+                self.record_module_parse(Some(&path), timer);
+                program
+            }
+        };
 
-        // Save current environment and exports
+        // Save current environment and exports; imports of the module
+        // resolve relative to the module itself
         let saved_env = self.env.cheap_clone();
         let saved_exports = mem::take(&mut self.exports);
+        let saved_module_path = self.current_module_path.replace(path.clone());
 
         // Create module environment (rooted so it persists for live bindings)
         let module_env = self.create_module_environment();
         // Root the module environment - it must persist for live bindings
-        self.root_guard.guard(module_env.clone());
+        self.module_guard.guard(module_env.clone());
         self.env = module_env.cheap_clone();
 
-        // Set up import bindings before bytecode execution
-        self.setup_import_bindings(&program)?;
-
-        // Execute the module body using bytecode
-        let result = self.execute_program_bytecode(&program);
+        // Set up import bindings, then execute the module body, attributed to
+        // the module itself rather than whichever module happened to import it first
+        let result = self.setup_import_bindings(&program).and_then(|()| {
+            let timer = self.start_metrics_timer();
+            let chunk = self.compile_program_chunk(&program, Some(&path), false)?;
+            self.record_module_compile(Some(&path), timer, &chunk);
+            let this_value = self.top_level_this(&program);
+            self.run_bytecode_with_this(chunk, this_value)
+        });
 
         // Restore environment
         self.env = saved_env;
+        self.current_module_path = saved_module_path;
 
         // Handle errors
         result?;
@@ -3572,20 +6689,21 @@ impl Interpreter {
     // ═══════════════════════════════════════════════════════════════════════════
 
     /// Abstract Equality Comparison Algorithm (ECMAScript spec 7.2.14)
-    /// Implements the == operator with type coercion
-    fn abstract_equals(&mut self, left: &JsValue, right: &JsValue) -> bool {
+    /// Implements the == operator with type coercion. Errors from ToPrimitive,
+    /// such as a throwing `valueOf`, propagate.
+    fn abstract_equals(&mut self, left: &JsValue, right: &JsValue) -> Result<bool, JsError> {
         // If types are the same, use strict equality
         if mem::discriminant(left) == mem::discriminant(right) {
-            return left.strict_equals(right);
+            return Ok(left.strict_equals(right));
         }
 
         match (left, right) {
             // 1. null == undefined and undefined == null
-            (JsValue::Undefined, JsValue::Null) | (JsValue::Null, JsValue::Undefined) => true,
+            (JsValue::Undefined, JsValue::Null) | (JsValue::Null, JsValue::Undefined) => Ok(true),
 
             // 2. Number == String: convert string to number
-            (JsValue::Number(n), JsValue::String(s)) => *n == s.parse().unwrap_or(f64::NAN),
-            (JsValue::String(s), JsValue::Number(n)) => s.parse().unwrap_or(f64::NAN) == *n,
+            (JsValue::Number(n), JsValue::String(_)) => Ok(*n == right.to_number()),
+            (JsValue::String(_), JsValue::Number(n)) => Ok(left.to_number() == *n),
 
             // 3. Boolean == anything: convert boolean to number and compare again
             (JsValue::Boolean(b), other) => {
@@ -3600,39 +6718,82 @@ impl Interpreter {
             // 4. Object == String/Number/Symbol: convert object to primitive
             (JsValue::Object(_), JsValue::Number(_) | JsValue::String(_)) => {
                 // ToPrimitive with default hint
-                match self.coerce_to_primitive(left, "default") {
-                    Ok(prim) => self.abstract_equals(&prim, right),
-                    Err(_) => false,
-                }
+                let prim = self.coerce_to_primitive(left, "default")?;
+                self.abstract_equals(&prim, right)
             }
             (JsValue::Number(_) | JsValue::String(_), JsValue::Object(_)) => {
-                match self.coerce_to_primitive(right, "default") {
-                    Ok(prim) => self.abstract_equals(left, &prim),
-                    Err(_) => false,
-                }
+                let prim = self.coerce_to_primitive(right, "default")?;
+                self.abstract_equals(left, &prim)
             }
 
             // 5. Object == Symbol: convert object to primitive
             (JsValue::Object(_), JsValue::Symbol(_)) => {
-                match self.coerce_to_primitive(left, "default") {
-                    Ok(prim) => self.abstract_equals(&prim, right),
-                    Err(_) => false,
-                }
+                let prim = self.coerce_to_primitive(left, "default")?;
+                self.abstract_equals(&prim, right)
             }
             (JsValue::Symbol(_), JsValue::Object(_)) => {
-                match self.coerce_to_primitive(right, "default") {
-                    Ok(prim) => self.abstract_equals(left, &prim),
-                    Err(_) => false,
-                }
+                let prim = self.coerce_to_primitive(right, "default")?;
+                self.abstract_equals(left, &prim)
             }
 
             // All other cases: not equal
-            _ => false,
+            _ => Ok(false),
+        }
+    }
+
+    /// Abstract Relational Comparison Algorithm (ECMAScript spec 7.2.13)
+    /// Computes `x < y`; `None` means undefined (a NaN operand), which makes
+    /// every relational operator false. `left_first` controls the order in which
+    /// the operands go through ToPrimitive, since `>` and `<=` swap them.
+    pub(crate) fn abstract_relational_comparison(
+        &mut self,
+        x: &JsValue,
+        y: &JsValue,
+        left_first: bool,
+    ) -> Result<Option<bool>, JsError> {
+        // Fast path: both numbers
+        if let (JsValue::Number(a), JsValue::Number(b)) = (x, y) {
+            return Ok(if a.is_nan() || b.is_nan() {
+                None
+            } else {
+                Some(a < b)
+            });
+        }
+
+        let (px, py) = if left_first {
+            let px = self.coerce_to_primitive(x, "number")?;
+            let py = self.coerce_to_primitive(y, "number")?;
+            (px, py)
+        } else {
+            let py = self.coerce_to_primitive(y, "number")?;
+            let px = self.coerce_to_primitive(x, "number")?;
+            (px, py)
+        };
+
+        if let (JsValue::String(a), JsValue::String(b)) = (&px, &py) {
+            return Ok(Some(
+                crate::value::compare_code_units(a.as_str(), b.as_str())
+                    == core::cmp::Ordering::Less,
+            ));
         }
+
+        if matches!(px, JsValue::Symbol(_)) || matches!(py, JsValue::Symbol(_)) {
+            return Err(JsError::type_error(
+                "Cannot convert a Symbol value to a number",
+            ));
+        }
+
+        let nx = px.to_number();
+        let ny = py.to_number();
+        if nx.is_nan() || ny.is_nan() {
+            return Ok(None);
+        }
+        Ok(Some(nx < ny))
     }
 
     /// ToPrimitive: Convert an object to a primitive value.
-    /// For wrapper objects (Number, String, Boolean), this calls valueOf/toString.
+    /// A `[Symbol.toPrimitive]` method is called with the hint and decides.
+    /// Otherwise, as for wrapper objects (Number, String, Boolean), this calls valueOf/toString.
     /// `hint` specifies preference: "number" tries valueOf first, "string" tries toString first.
     /// For Date objects with "default" hint, uses "string" per ES spec (Date.prototype[@@toPrimitive]).
     /// Throws TypeError if neither method returns a primitive value (ES2015+ spec).
@@ -3643,6 +6804,29 @@ impl Interpreter {
             _ => return Ok(value.clone()),
         };
 
+        // A [Symbol.toPrimitive] method takes over the conversion
+        let to_primitive_key = PropertyKey::Symbol(Box::new(JsSymbol::new(
+            self.well_known_symbols.to_primitive,
+            Some(self.intern("Symbol.toPrimitive")),
+        )));
+        let exotic_to_prim = obj.borrow().get_property(&to_primitive_key);
+        match exotic_to_prim {
+            None | Some(JsValue::Undefined | JsValue::Null) => {}
+            Some(method) if method.is_callable() => {
+                let hint = JsValue::String(self.intern(hint));
+                let result = self.call_function(method, value.clone(), &[hint])?;
+                if matches!(result.value, JsValue::Object(_)) {
+                    return Err(JsError::type_error(
+                        "Cannot convert object to primitive value",
+                    ));
+                }
+                return Ok(result.value);
+            }
+            Some(_) => {
+                return Err(JsError::type_error("Symbol.toPrimitive is not a function"));
+            }
+        }
+
         // Per ES spec: Date objects prefer "string" for "default" hint
         // This is what Date.prototype[@@toPrimitive] does
         let effective_hint = if hint == "default" {
@@ -3806,12 +6990,17 @@ impl Interpreter {
         let JsValue::Object(func_obj) = callee else {
             return Err(JsError::type_error("Not a function"));
         };
+        // Native code calling back into a function recurses on the Rust stack
+        let _depth = self.enter_native()?;
 
         // Check if this is a proxy - use apply trap
         let is_proxy = matches!(func_obj.borrow().exotic, ExoticObject::Proxy(_));
         if is_proxy {
             return builtins::proxy::proxy_apply(self, func_obj, this_value, args.to_vec());
         }
+        if host_class::is_host(&func_obj) {
+            return host_class::host_call(self, &func_obj, this_value, args);
+        }
 
         let func = {
             let obj_ref = func_obj.borrow();
@@ -3823,6 +7012,9 @@ impl Interpreter {
 
         match func {
             JsFunction::Native(native) => {
+                if self.audit_log.is_some() {
+                    self.audit_native_call(&func_obj, &native.name);
+                }
                 // Call native function - propagate the Guarded to preserve guard
                 (native.func)(self, this_value, args)
             }
@@ -3858,15 +7050,25 @@ impl Interpreter {
                 )
             }
 
-            JsFunction::PromiseResolve(promise) => {
-                let value = args.first().cloned().unwrap_or(JsValue::Undefined);
-                builtins::promise::resolve_promise_value(self, &promise, value)?;
+            JsFunction::PromiseResolve {
+                promise,
+                already_resolved,
+            } => {
+                if !already_resolved.replace(true) {
+                    let value = args.first().cloned().unwrap_or(JsValue::Undefined);
+                    builtins::promise::resolve_promise_value(self, &promise, value)?;
+                }
                 Ok(Guarded::unguarded(JsValue::Undefined))
             }
 
-            JsFunction::PromiseReject(promise) => {
-                let reason = args.first().cloned().unwrap_or(JsValue::Undefined);
-                builtins::promise::reject_promise_value(self, &promise, reason)?;
+            JsFunction::PromiseReject {
+                promise,
+                already_resolved,
+            } => {
+                if !already_resolved.replace(true) {
+                    let reason = args.first().cloned().unwrap_or(JsValue::Undefined);
+                    builtins::promise::reject_promise_value(self, &promise, reason)?;
+                }
                 Ok(Guarded::unguarded(JsValue::Undefined))
             }
 
@@ -3894,6 +7096,15 @@ impl Interpreter {
                 Ok(Guarded::unguarded(JsValue::Undefined))
             }
 
+            JsFunction::AsyncGeneratorResume {
+                generator,
+                is_fulfill,
+            } => {
+                let value = args.first().cloned().unwrap_or(JsValue::Undefined);
+                builtins::generator::async_generator_resume(self, &generator, value, is_fulfill)?;
+                Ok(Guarded::unguarded(JsValue::Undefined))
+            }
+
             JsFunction::AccessorGetter => {
                 // Auto-accessor getter - read from storage slot on `this`
                 let storage_key_prop = self.intern("__accessor_storage_key__");
@@ -3974,7 +7185,7 @@ impl Interpreter {
                 source_key,
             } => {
                 // Re-export getter - delegate to source module's property
-                let value = self.resolve_module_property(&source_module, &source_key)?;
+                let value = self.read_module_export(&source_module, &source_key)?;
                 Ok(Guarded::unguarded(value))
             }
 
@@ -3989,27 +7200,36 @@ impl Interpreter {
         }
     }
 
-    /// Call a bytecode-compiled function
-    fn call_bytecode_function(
+    /// Call a bytecode-compiled function with an explicit new.target value
+    fn call_bytecode_function_with_new_target(
         &mut self,
         bc_func: BytecodeFunction,
         this_value: JsValue,
         args: &[JsValue],
+        new_target: JsValue,
     ) -> Result<Guarded, JsError> {
-        self.call_bytecode_function_with_new_target(bc_func, this_value, args, JsValue::Undefined)
+        self.run_bytecode_function(bc_func, this_value, args, new_target, false)
     }
 
-    /// Call a bytecode-compiled function with an explicit new.target value
+    /// Run a bytecode-compiled function to its result.
+    ///
+    /// With `detach` set, a body that suspends is parked as an async task and
+    /// the result is the promise that task settles; otherwise suspending is
+    /// an error.
     // NOTE: review
-    fn call_bytecode_function_with_new_target(
+    fn run_bytecode_function(
         &mut self,
         bc_func: BytecodeFunction,
         this_value: JsValue,
         args: &[JsValue],
         new_target: JsValue,
+        detach: bool,
     ) -> Result<Guarded, JsError> {
         use crate::interpreter::bytecode_vm::{BytecodeVM, VmResult};
 
+        self.check_call_depth()?;
+        let call_depth = self.call_stack.len();
+
         // Get function info from the chunk
         let func_info = bc_func.chunk.function_info.as_ref();
 
@@ -4023,10 +7243,22 @@ impl Interpreter {
             function_name: func_name,
             location: None,
         });
+        let flight_name = self
+            .flight_recorder
+            .is_some()
+            .then(|| func_info.and_then(|info| info.name.cheap_clone()));
+        if let Some(name) = &flight_name {
+            let site = Some((bc_func.chunk.clone(), 0));
+            let subject = flight_recorder::FlightSubject::Name(name.clone());
+            self.record_flight(flight_recorder::FlightEventKind::Call, subject, site);
+        }
 
-        // Create new environment for the function, with closure as parent
+        // Create new environment for the function, with closure as parent.
+        // Functions that can't capture it reuse one from the call pool.
+        let recyclable_env = func_info.is_some_and(|info| info.env_is_recyclable());
+        let env_capacity = func_info.map_or(8, |info| info.binding_count.max(1));
         let (func_env, func_guard) =
-            create_environment_unrooted(&self.heap, Some(bc_func.closure.cheap_clone()));
+            self.create_function_env(bc_func.closure.cheap_clone(), env_capacity, recyclable_env);
 
         // Bind `this` in the function environment
         // For arrow functions, use captured_this; otherwise use provided this_value
@@ -4051,61 +7283,99 @@ impl Interpreter {
             }
         }
 
-        // Set up environment for execution
-        let saved_env = self.env.cheap_clone();
-        self.env = func_env;
-        self.push_env_guard(func_guard);
-
-        // Handle rest parameters: if the function has a rest parameter, we need to
-        // collect all extra arguments into an array at that parameter index
-        let vm_guard = self.heap.create_guard();
-        let processed_args: Vec<JsValue> =
-            if let Some(rest_idx) = func_info.and_then(|info| info.rest_param) {
-                let mut result_args = Vec::with_capacity(rest_idx + 1);
-
-                // Copy regular parameters
-                for i in 0..rest_idx {
-                    result_args.push(args.get(i).cloned().unwrap_or(JsValue::Undefined));
-                }
-
-                // Collect remaining args into an array for the rest parameter
-                let rest_elements: Vec<JsValue> = args.get(rest_idx..).unwrap_or_default().to_vec();
-                let rest_array = self.create_array_from(&vm_guard, rest_elements);
-                result_args.push(JsValue::Object(rest_array));
-
-                result_args
-            } else {
-                args.to_vec()
-            };
+        // Fill the register file from the call pool. Parameters are read straight
+        // from `args`, except that a rest parameter collects the extra arguments
+        // into an array at its index.
+        let vm_guard = self.call_pool.acquire_guard(&self.heap);
+        let mut registers = self
+            .call_pool
+            .acquire_registers(bc_func.chunk.register_count as usize);
+        let rest_param = func_info.and_then(|info| info.rest_param);
+        let direct_count = rest_param.unwrap_or(args.len());
+        for (slot, arg) in registers.iter_mut().zip(args.iter().take(direct_count)) {
+            *slot = arg.clone();
+        }
+        if let Some(rest_idx) = rest_param {
+            let rest_elements: Vec<JsValue> = args.get(rest_idx..).unwrap_or_default().to_vec();
+            let rest_array = self.create_array_from(&vm_guard, rest_elements);
+            if let Some(slot) = registers.get_mut(rest_idx) {
+                *slot = JsValue::Object(rest_array);
+            }
+        }
+        let mut arguments = self.call_pool.acquire_arguments(args.len());
+        arguments.extend_from_slice(args);
 
         // Create VM and run with args pre-populated in registers
         // Use new_target if provided (for constructor calls)
-        let mut vm = BytecodeVM::with_guard_args_and_new_target(
+        let mut vm = BytecodeVM::with_registers_and_new_target(
             bc_func.chunk.clone(),
             effective_this,
             vm_guard,
-            &processed_args,
+            registers,
+            arguments,
             new_target,
         );
 
-        let result = vm.run(self);
-
-        // Restore environment
-        self.pop_env_guard();
-        self.env = saved_env;
-        self.call_stack.pop();
+        // Run in the function's environment; leaving the scope restores the
+        // caller's, however the body exits
+        let (result, suspended_env) = {
+            let mut scope = EnvScope::enter(self, func_env.cheap_clone(), func_guard);
+            let result = vm.run(&mut scope);
+            // A suspended body took its scopes with its saved state; its
+            // environment stays rooted until it is parked
+            if matches!(result, VmResult::Suspend(_) | VmResult::SuspendForOrder(_)) {
+                let env_guard = scope.heap.create_guard();
+                env_guard.guard(scope.env.cheap_clone());
+                (result, Some((scope.env.cheap_clone(), env_guard)))
+            } else {
+                vm.recycle(&mut scope);
+                (result, None)
+            }
+        };
+        let calls = self.call_stack.split_off(call_depth);
+        if let Some(name) = flight_name {
+            let subject = flight_recorder::FlightSubject::Name(name);
+            self.record_flight(flight_recorder::FlightEventKind::Return, subject, None);
+        }
+        if recyclable_env {
+            self.call_pool.release_env(func_env.cheap_clone());
+        }
 
         // Convert VM result to Guarded
-        match result {
-            VmResult::Complete(guarded) => Ok(guarded),
-            VmResult::Error(e) => Err(e),
-            VmResult::Suspend(_) | VmResult::SuspendForOrder(_) => Err(JsError::internal_error(
-                "Bytecode function suspended unexpectedly",
-            )),
-            VmResult::Yield(_) | VmResult::YieldStar(_) => Err(JsError::internal_error(
-                "Bytecode function yielded unexpectedly",
-            )),
-        }
+        let (state, waiting_on, resume_register) = match result {
+            VmResult::Complete(guarded) => return Ok(guarded),
+            VmResult::Error(e) => return Err(e),
+            VmResult::Suspend(suspension) if detach => (
+                suspension.state,
+                suspension.waiting_on,
+                suspension.resume_register,
+            ),
+            VmResult::SuspendForOrder(suspension) if detach => {
+                let guard = self.heap.create_guard();
+                let promise = self.create_tracked_order_promise(&guard, suspension.order_id);
+                (suspension.state, promise, suspension.resume_register)
+            }
+            VmResult::Suspend(_) | VmResult::SuspendForOrder(_) => {
+                return Err(JsError::internal_error(
+                    "Bytecode function suspended unexpectedly",
+                ));
+            }
+            VmResult::Yield(_) | VmResult::YieldStar(_) => {
+                return Err(JsError::internal_error(
+                    "Bytecode function yielded unexpectedly",
+                ));
+            }
+        };
+        let Some((env, _env_guard)) = suspended_env else {
+            return Err(JsError::internal_error(
+                "Suspended function without environment",
+            ));
+        };
+        let completion =
+            self.detach_async_task(state, waiting_on, resume_register, env, func_env, calls);
+        let guard = self.heap.create_guard();
+        guard.guard(completion.cheap_clone());
+        Ok(Guarded::with_guard(JsValue::Object(completion), guard))
     }
 
     /// Call a bytecode async function - wraps result in Promise
@@ -4116,8 +7386,9 @@ impl Interpreter {
         this_value: JsValue,
         args: &[JsValue],
     ) -> Result<Guarded, JsError> {
-        // Execute the function body synchronously
-        let body_result = self.call_bytecode_function(bc_func, this_value, args);
+        // Execute the function body up to its first suspension
+        let body_result =
+            self.run_bytecode_function(bc_func, this_value, args, JsValue::Undefined, true);
 
         // Wrap result in Promise (fulfilled or rejected)
         match body_result {
@@ -4134,6 +7405,7 @@ impl Interpreter {
                 let promise = builtins::promise::create_fulfilled_promise(self, &guard, result);
                 Ok(Guarded::with_guard(JsValue::Object(promise), guard))
             }
+            Err(e) if e.is_termination() => Err(e),
             Err(e) => {
                 // Create rejected promise with the error
                 let guard = self.heap.create_guard();
@@ -4161,7 +7433,7 @@ impl Interpreter {
         // Create the generator state with arguments
         let state = BytecodeGeneratorState {
             chunk: bc_func.chunk,
-            closure: bc_func.closure,
+            closure: Some(bc_func.closure),
             args: args.to_vec(),
             this_value,
             status: GeneratorStatus::Suspended,
@@ -4172,12 +7444,17 @@ impl Interpreter {
             saved_registers: Vec::new(),
             saved_call_stack: Vec::new(),
             saved_try_stack: Vec::new(),
+            saved_env_stack: Vec::new(),
             yield_result_register: None,
             func_env: None,           // Will be created on first call to next()
             current_env: None,        // Will be saved at each yield point
             delegated_iterator: None, // For yield* delegation
             is_async: false,          // Regular generator, not async
             throw_value: None,        // For generator.throw()
+            return_value: None,       // For generator.return()
+            queue: VecDeque::new(),   // Requests queued on an async generator
+            running: false,           // No request in progress yet
+            suspended_await: None,    // Set while an async generator awaits
         };
 
         // Create the generator object with a guard
@@ -4204,7 +7481,7 @@ impl Interpreter {
         // Create the generator state with arguments (same as regular generator but with is_async=true)
         let state = BytecodeGeneratorState {
             chunk: bc_func.chunk,
-            closure: bc_func.closure,
+            closure: Some(bc_func.closure),
             args: args.to_vec(),
             this_value,
             status: GeneratorStatus::Suspended,
@@ -4215,12 +7492,17 @@ impl Interpreter {
             saved_registers: Vec::new(),
             saved_call_stack: Vec::new(),
             saved_try_stack: Vec::new(),
+            saved_env_stack: Vec::new(),
             yield_result_register: None,
             func_env: None,           // Will be created on first call to next()
             current_env: None,        // Will be saved at each yield point
             delegated_iterator: None, // For yield* delegation
             is_async: true,           // Async generator - next() returns Promise
             throw_value: None,        // For generator.throw()
+            return_value: None,       // For generator.return()
+            queue: VecDeque::new(),   // Requests queued on an async generator
+            running: false,           // No request in progress yet
+            suspended_await: None,    // Set while an async generator awaits
         };
 
         // Create the generator object with a guard
@@ -4237,108 +7519,148 @@ impl Interpreter {
         &mut self,
         value: &JsValue,
     ) -> Result<Option<Vec<JsValue>>, JsError> {
-        let JsValue::Object(obj) = value else {
-            // Strings are iterable but handled separately
-            if let JsValue::String(s) = value {
-                return Ok(Some(
-                    s.as_str()
-                        .chars()
-                        .map(|c| JsValue::String(JsString::from(c.to_string())))
-                        .collect(),
-                ));
+        // Collected objects stay guarded while later next() calls may trigger GC
+        let mut values = Vec::new();
+        let values_guard = self.heap.create_guard();
+        let iterable = self.try_for_each_iterator_value(value, |_, iter_value, _| {
+            if let JsValue::Object(value_obj) = &iter_value {
+                values_guard.guard(value_obj.cheap_clone());
             }
-            return Ok(None);
+            values.push(iter_value);
+            Ok(())
+        })?;
+        Ok(iterable.then_some(values))
+    }
+
+    /// Iterate `iterable` with the iterator protocol, calling `f` with each value and its index.
+    /// Plain arrays, strings and String wrappers take a fast path. If `f` fails, the iterator's `return`
+    /// method is called (its own errors are ignored) before the error is propagated.
+    pub fn for_each_iterator_value<F>(&mut self, iterable: &JsValue, f: F) -> Result<(), JsError>
+    where
+        F: FnMut(&mut Self, JsValue, usize) -> Result<(), JsError>,
+    {
+        if self.try_for_each_iterator_value(iterable, f)? {
+            return Ok(());
+        }
+        if let JsValue::Object(_) = iterable {
+            return Err(JsError::type_error("object is not iterable"));
+        }
+        let name = self.to_js_string(iterable);
+        Err(JsError::type_error(format!("{} is not iterable", name)))
+    }
+
+    /// Shared loop behind [`Self::for_each_iterator_value`] and [`Self::collect_iterator_values`].
+    /// Returns `Ok(false)` without calling `f` when `iterable` has no callable Symbol.iterator.
+    fn try_for_each_iterator_value<F>(
+        &mut self,
+        iterable: &JsValue,
+        mut f: F,
+    ) -> Result<bool, JsError>
+    where
+        F: FnMut(&mut Self, JsValue, usize) -> Result<(), JsError>,
+    {
+        if let Some(s) = iterable.string_wrapper_value() {
+            return self.try_for_each_iterator_value(&JsValue::String(s), f);
+        }
+        let obj = match iterable {
+            JsValue::String(s) => {
+                for (index, c) in s.as_str().chars().enumerate() {
+                    f(self, JsValue::String(JsString::from(c.to_string())), index)?;
+                }
+                return Ok(true);
+            }
+            JsValue::Object(obj) => obj.cheap_clone(),
+            _ => return Ok(false),
         };
 
-        // First check if it's a plain array - use fast path
-        {
-            let obj_ref = obj.borrow();
-            if let Some(elements) = obj_ref.array_elements() {
-                return Ok(Some(elements.to_vec()));
+        let guard = self.heap.create_guard();
+        guard.guard(obj.cheap_clone());
+
+        // Fast path for plain arrays: snapshot the elements, keeping them alive
+        let elements = obj.borrow().array_elements().map(|e| e.to_vec());
+        if let Some(elements) = elements {
+            for element in &elements {
+                element.guard_by(&guard);
+            }
+            for (index, element) in elements.into_iter().enumerate() {
+                f(self, element, index)?;
             }
+            return Ok(true);
         }
 
-        // Check for Symbol.iterator method
         let well_known = self.well_known_symbols;
         let iterator_symbol =
             JsSymbol::new(well_known.iterator, Some(self.intern("Symbol.iterator")));
         let iterator_key = PropertyKey::Symbol(Box::new(iterator_symbol));
-
-        let iterator_method = {
-            let obj_ref = obj.borrow();
-            obj_ref.get_property(&iterator_key)
-        };
-
-        let Some(JsValue::Object(method_obj)) = iterator_method else {
-            return Ok(None);
+        let iterator_method = obj.borrow().get_property(&iterator_key);
+        let Some(method) = iterator_method.filter(|m| m.is_callable()) else {
+            return Ok(false);
         };
 
-        // Call the iterator method to get the iterator object
         let Guarded {
-            value: iterator_obj,
+            value: iterator_value,
             guard: _iter_guard,
-        } = self.call_function(JsValue::Object(method_obj), value.clone(), &[])?;
-
-        let JsValue::Object(iterator) = iterator_obj else {
+        } = self.call_function(method, iterable.clone(), &[])?;
+        let JsValue::Object(iterator) = iterator_value else {
             return Err(JsError::type_error("Symbol.iterator must return an object"));
         };
+        guard.guard(iterator.cheap_clone());
 
-        // Iterate: call next() until done is true
-        let mut values = Vec::new();
+        // The next method is read once, per GetIterator
         let next_key = PropertyKey::String(self.intern("next"));
+        let next_method = iterator
+            .borrow()
+            .get_property(&next_key)
+            .unwrap_or(JsValue::Undefined);
+        let done_key = PropertyKey::String(self.intern("done"));
+        let value_key = PropertyKey::String(self.intern("value"));
 
+        let mut index = 0;
         loop {
-            // Get the next method
-            let next_method = {
-                let iter_ref = iterator.borrow();
-                iter_ref.get_property(&next_key)
-            };
-
-            let Some(JsValue::Object(next_fn)) = next_method else {
-                return Err(JsError::type_error("Iterator must have a next method"));
-            };
+            self.check_budget()?;
 
-            // Call next()
             let Guarded {
                 value: result,
                 guard: _result_guard,
             } = self.call_function(
-                JsValue::Object(next_fn),
-                JsValue::Object(iterator.clone()),
+                next_method.clone(),
+                JsValue::Object(iterator.cheap_clone()),
                 &[],
             )?;
-
             let JsValue::Object(result_obj) = result else {
                 return Err(JsError::type_error("Iterator next() must return an object"));
             };
-
-            // Check done property
-            let done = {
+            let (done, value) = {
                 let result_ref = result_obj.borrow();
-                let done_key = PropertyKey::String(self.intern("done"));
-                result_ref
-                    .get_property(&done_key)
-                    .map(|v| v.to_boolean())
-                    .unwrap_or(false)
+                (
+                    result_ref
+                        .get_property(&done_key)
+                        .is_some_and(|v| v.to_boolean()),
+                    result_ref
+                        .get_property(&value_key)
+                        .unwrap_or(JsValue::Undefined),
+                )
             };
-
             if done {
-                break;
+                return Ok(true);
             }
 
-            // Get value property
-            let iter_value = {
-                let result_ref = result_obj.borrow();
-                let value_key = PropertyKey::String(self.intern("value"));
-                result_ref
-                    .get_property(&value_key)
-                    .unwrap_or(JsValue::Undefined)
-            };
-
-            values.push(iter_value);
+            let _value_guard = self.guard_value(&value);
+            if let Err(error) = f(self, value, index) {
+                self.close_iterator(&iterator);
+                return Err(error);
+            }
+            index += 1;
         }
+    }
 
-        Ok(Some(values))
+    /// Call an iterator's `return` method, if any, discarding its result
+    pub(crate) fn close_iterator(&mut self, iterator: &Gc<JsObject>) {
+        let return_key = PropertyKey::String(self.intern("return"));
+        let return_method = iterator.borrow().get_property(&return_key);
+        if let Some(method) = return_method.filter(|m| m.is_callable()) {
+            let _ = self.call_function(method, JsValue::Object(iterator.cheap_clone()), &[]);
+        }
     }
 }
 
@@ -4348,6 +7670,31 @@ impl Default for Interpreter {
     }
 }
 
+impl Drop for Interpreter {
+    /// With the `guard-leak-warnings` feature, report host values that
+    /// outlive the interpreter: reading their objects afterwards panics, and
+    /// `RuntimeValue::try_value` and the `api` accessors fail.
+    fn drop(&mut self) {
+        // Values the interpreter holds for itself are not the host's
+        self.order_responses.clear();
+        self.eager_order_promises.clear();
+        self.deferred_completion = None;
+        self.held_completion = None;
+
+        #[cfg(feature = "guard-leak-warnings")]
+        {
+            let outstanding = self.heap.host_guard_count();
+            if outstanding > 0 {
+                std::eprintln!(
+                    "tsrun: Interpreter dropped with {} host value guard(s) outstanding; \
+                     their objects can no longer be read",
+                    outstanding
+                );
+            }
+        }
+    }
+}
+
 /// Native getter for Symbol.species that returns `this`.
 /// Per ECMAScript spec, Symbol.species getter returns the constructor itself.
 fn species_getter(
@@ -4359,6 +7706,71 @@ fn species_getter(
     Ok(Guarded::unguarded(this))
 }
 
+/// Split a host `object[symbol]` access into the object and its property key
+fn symbol_property_target(
+    object: &crate::RuntimeValue,
+    symbol: &crate::RuntimeValue,
+) -> Result<(Gc<JsObject>, PropertyKey), JsError> {
+    let JsValue::Object(obj) = object.try_value()? else {
+        return Err(JsError::type_error(
+            "Cannot access a symbol property of a non-object",
+        ));
+    };
+    let JsValue::Symbol(sym) = symbol.value() else {
+        return Err(JsError::type_error("Property key is not a symbol"));
+    };
+    Ok((obj.cheap_clone(), PropertyKey::Symbol(sym.clone())))
+}
+
+/// Own enumerable properties of thrown object `value` other than `name`,
+/// `message` and `stack`, as JSON, or `None` if it has none JSON can hold
+fn thrown_properties(value: &JsValue) -> Option<Box<serde_json::Map<String, serde_json::Value>>> {
+    let serde_json::Value::Object(mut properties) = builtins::json::js_value_to_json(value).ok()?
+    else {
+        return None;
+    };
+    for key in ["name", "message", "stack"] {
+        properties.remove(key);
+    }
+    (!properties.is_empty()).then(|| Box::new(properties))
+}
+
+/// The error for running out of `InterpreterConfig::heap_budget`
+fn heap_budget_exceeded() -> JsError {
+    JsError::Terminated {
+        reason: crate::TerminationReason::HeapBudgetExceeded,
+    }
+}
+
+/// Compile a program within `limits`, recording `module_path` as its source
+/// file for stack traces. The `entry` program of a run tracks its completion
+/// value; imported modules complete with nothing.
+fn compile_program(
+    program: &crate::ast::Program,
+    module_path: Option<&crate::ModulePath>,
+    limits: crate::compiler::CompileLimits,
+    entry: bool,
+    constant_imports: Option<Rc<crate::compiler::ConstantImports>>,
+) -> Result<Rc<crate::compiler::BytecodeChunk>, JsError> {
+    if entry || constant_imports.is_some() {
+        return crate::compiler::Compiler::compile_module_program(
+            program,
+            module_path.map(|path| path.as_str().to_string()),
+            limits,
+            entry,
+            constant_imports,
+        );
+    }
+    match module_path {
+        Some(path) => crate::compiler::Compiler::compile_program_with_source(
+            program,
+            path.as_str().to_string(),
+            limits,
+        ),
+        None => crate::compiler::Compiler::compile_program(program, limits),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,255 @@
+//! Running provided modules in dependency order.
+//!
+//! Modules provided by the host wait in `pending_module_sources` until every
+//! module they import is loaded. Each pending module's imports are collected
+//! once; a module then runs when the count of its imports not yet loaded
+//! drops to zero, so a graph of N modules is ordered in time linear in its
+//! imports rather than rescanned after every module that runs. Modules
+//! that become ready together run in the order they were first imported,
+//! unless the host picks the order with
+//! [`crate::InterpreterConfig::module_execution_order`].
+//!
+//! Hosts that know the whole file set up front hand it to
+//! [`Interpreter::prepare_with_modules`], which needs no `NeedImports` round
+//! trips for the modules it was given.
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use crate::error::JsError;
+use crate::{ImportRequest, ModulePath, StepResult};
+
+impl Interpreter {
+    /// Prepare `entry_source` like [`Interpreter::prepare`], with `modules`
+    /// provided up front as by [`Interpreter::provide_module_unrequested`].
+    ///
+    /// Imports must resolve to the given paths exactly. The modules run in
+    /// dependency order before the entry; `NeedImports` reports only imports
+    /// that none of them provide.
+    pub fn prepare_with_modules(
+        &mut self,
+        entry_source: &str,
+        entry_path: Option<ModulePath>,
+        modules: impl IntoIterator<Item = (ModulePath, String)>,
+    ) -> Result<StepResult, JsError> {
+        for (path, source) in modules {
+            self.provide_module_unrequested(path, &source)?;
+        }
+
+        let module_path = self.enter_prepare(entry_path);
+        let timer = self.start_metrics_timer();
+        let program = self.parse_module_source(module_path.as_ref(), entry_source)?;
+        self.record_module_parse(module_path.as_ref(), timer);
+
+        // Whole-graph check: one module running code from strings turns
+        // constant folding off for every module of the run
+        if self.constant_imports_active
+            && (crate::compiler::uses_dynamic_code(&program)
+                || self
+                    .pending_module_sources
+                    .values()
+                    .any(crate::compiler::uses_dynamic_code))
+        {
+            self.constant_imports_active = false;
+        }
+
+        self.setup_vm_from_program(program)
+    }
+
+    /// How many times the imports of a pending module have been collected
+    /// since the interpreter was created, for tests of module scheduling
+    #[doc(hidden)]
+    pub fn module_import_scans(&self) -> usize {
+        self.module_import_scans
+    }
+
+    /// Execute the pending modules whose imports are loaded, dependencies
+    /// first, until none is left or the rest wait on imports.
+    ///
+    /// Returns the imports of the waiting modules that the host hasn't
+    /// provided yet.
+    pub(super) fn process_pending_modules(&mut self) -> Result<Vec<ImportRequest>, JsError> {
+        let mut waiting = Vec::new();
+        for module_path in self.pending_modules_in_order() {
+            if !self.loaded_modules.contains_key(&module_path) {
+                let imports = self.pending_module_imports(&module_path)?;
+                waiting.push((module_path, imports));
+            }
+        }
+
+        // Imports are normally loaded only by running the pending module of
+        // the same path; count again once the queue drains in case one was
+        // loaded otherwise
+        while self.run_ready_modules(&mut waiting)? > 0 {}
+
+        let mut unprovided = Vec::new();
+        for (_, imports) in waiting {
+            unprovided.extend(self.filter_unprovided_imports(imports));
+        }
+        Ok(Self::dedupe_import_requests(unprovided))
+    }
+
+    /// Run the modules of `waiting` whose imports are loaded, then those
+    /// their running unblocks, in waves ordered by discovery. Returns how
+    /// many ran; they are removed from `waiting`.
+    fn run_ready_modules(
+        &mut self,
+        waiting: &mut Vec<(ModulePath, Vec<ImportRequest>)>,
+    ) -> Result<usize, JsError> {
+        let mut unloaded = vec![0usize; waiting.len()];
+        let mut dependents: FxHashMap<ModulePath, Vec<usize>> = FxHashMap::default();
+        let mut ready = Vec::new();
+        for (index, (_, imports)) in waiting.iter().enumerate() {
+            let missing =
+                Self::dedupe_import_requests(self.filter_missing_imports(imports.clone()));
+            if let Some(count) = unloaded.get_mut(index) {
+                *count = missing.len();
+            }
+            if missing.is_empty() {
+                ready.push(index);
+            }
+            for req in missing {
+                dependents.entry(req.resolved_path).or_default().push(index);
+            }
+        }
+
+        let mut ran = vec![false; waiting.len()];
+        let mut count = 0;
+        while !ready.is_empty() {
+            let (run, deferred) = self.order_ready_modules(waiting, ready)?;
+            let mut next = deferred;
+            for index in run {
+                let Some((module_path, _)) = waiting.get(index) else {
+                    continue;
+                };
+                let module_path = module_path.clone();
+                if !self.loaded_modules.contains_key(&module_path) {
+                    self.execute_pending_module(&module_path)?;
+                }
+                if let Some(flag) = ran.get_mut(index) {
+                    *flag = true;
+                }
+                count += 1;
+
+                for dependent in dependents.remove(&module_path).unwrap_or_default() {
+                    if let Some(left) = unloaded.get_mut(dependent) {
+                        *left -= 1;
+                        if *left == 0 {
+                            next.push(dependent);
+                        }
+                    }
+                }
+            }
+            // Modules unblocked by the same wave, and those the host deferred,
+            // run in the order they were requested
+            next.sort_by(|&a, &b| match (waiting.get(a), waiting.get(b)) {
+                (Some((a, _)), Some((b, _))) => self.discovery_order(a, b),
+                _ => a.cmp(&b),
+            });
+            ready = next;
+        }
+
+        let mut ran = ran.into_iter();
+        waiting.retain(|_| !ran.next().unwrap_or(false));
+        Ok(count)
+    }
+
+    /// Split the `ready` modules of `waiting` into those to run this wave,
+    /// in order, and those the host's `module_execution_order` defers
+    fn order_ready_modules(
+        &self,
+        waiting: &[(ModulePath, Vec<ImportRequest>)],
+        ready: Vec<usize>,
+    ) -> Result<(Vec<usize>, Vec<usize>), JsError> {
+        let Some(order) = &self.module_execution_order else {
+            return Ok((ready, Vec::new()));
+        };
+        let paths: Vec<ModulePath> = ready
+            .iter()
+            .filter_map(|&index| waiting.get(index).map(|(path, _)| path.clone()))
+            .collect();
+        let chosen = order(&paths);
+        if chosen.is_empty() {
+            return Err(JsError::module_error(format!(
+                "module_execution_order chose none of the ready modules {}",
+                path_list(&paths)
+            )));
+        }
+
+        let mut run = Vec::with_capacity(chosen.len());
+        for path in &chosen {
+            let index = paths
+                .iter()
+                .position(|ready_path| ready_path == path)
+                .and_then(|position| ready.get(position).copied());
+            let Some(index) = index else {
+                return Err(JsError::module_error(format!(
+                    "module_execution_order returned {}, which is not one of the ready modules {}",
+                    path,
+                    path_list(&paths)
+                )));
+            };
+            if run.contains(&index) {
+                return Err(JsError::module_error(format!(
+                    "module_execution_order returned {} more than once",
+                    path
+                )));
+            }
+            run.push(index);
+        }
+        let deferred = ready
+            .into_iter()
+            .filter(|index| !run.contains(index))
+            .collect();
+        Ok((run, deferred))
+    }
+
+    /// The pending modules, in the order they were requested, so their
+    /// imports are reported in the same order every run
+    pub(super) fn pending_modules_in_order(&self) -> Vec<ModulePath> {
+        let mut pending: Vec<ModulePath> = self.pending_module_sources.keys().cloned().collect();
+        pending.sort_by(|a, b| self.discovery_order(a, b));
+        pending
+    }
+
+    fn discovery_order(&self, a: &ModulePath, b: &ModulePath) -> core::cmp::Ordering {
+        let discovered = |path| self.import_discovery.get(path).copied();
+        discovered(a)
+            .unwrap_or(usize::MAX)
+            .cmp(&discovered(b).unwrap_or(usize::MAX))
+            .then_with(|| a.as_str().cmp(b.as_str()))
+    }
+
+    /// The imports of pending module `module_path`, with those of the
+    /// internal source modules it reaches
+    pub(super) fn pending_module_imports(
+        &mut self,
+        module_path: &ModulePath,
+    ) -> Result<Vec<ImportRequest>, JsError> {
+        let Some(program) = self.pending_module_sources.get(module_path) else {
+            return Ok(Vec::new());
+        };
+        self.module_import_scans += 1;
+        let imports = self.collect_import_requests(program, Some(self.module_file(module_path)))?;
+        let imports = self.with_internal_module_imports(imports)?;
+        self.note_import_discovery(&imports);
+        Ok(imports)
+    }
+
+    /// Note the paths of `imports` not seen before as discovered now, so
+    /// modules provided up front run in the order they are imported
+    pub(super) fn note_import_discovery(&mut self, imports: &[ImportRequest]) {
+        for req in imports {
+            let discovered = self.import_discovery.len();
+            self.import_discovery
+                .entry(req.resolved_path.clone())
+                .or_insert(discovered);
+        }
+    }
+}
+
+/// `paths` as `[a, b]`, for error messages
+fn path_list(paths: &[ModulePath]) -> String {
+    let paths: Vec<&str> = paths.iter().map(|path| path.as_str()).collect();
+    format!("[{}]", paths.join(", "))
+}
@@ -0,0 +1,157 @@
+//! Imports served from in-memory module sources.
+//!
+//! Hosts that bundle their scripts (embedded assets, archives) hand the whole
+//! file tree to [`Interpreter::set_module_source_map`]. Each import is then
+//! matched against the map with a list of [`ModuleCandidate`]s, so `./utils`
+//! finds `/utils.ts` or `/utils/index.ts`, and only imports with no matching
+//! file are returned to the host as `NeedImports`.
+//!
+//! A module found under another path than the one requested runs as the file
+//! that matched: its relative imports and stack traces use that path.
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use crate::error::JsError;
+use crate::value::CheapClone;
+use crate::{ImportRequest, ModulePath};
+
+/// One way to look up an import's resolved path in the module source map
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModuleCandidate {
+    /// The resolved path as written
+    Exact,
+    /// The resolved path followed by a suffix, such as `".ts"` or `"/index.ts"`
+    Suffix(String),
+    /// A suffix whose file must not be imported: finding it fails the import
+    /// with `hint` instead of trying later candidates
+    Unsupported {
+        /// Appended to the resolved path
+        suffix: String,
+        /// Why the file cannot be used
+        hint: String,
+    },
+}
+
+impl ModuleCandidate {
+    /// The candidates used until [`Interpreter::set_module_candidates`] is called:
+    /// exact path, `.ts`, `.tsx` (rejected) and `/index.ts`
+    pub fn defaults() -> Vec<ModuleCandidate> {
+        vec![
+            ModuleCandidate::Exact,
+            ModuleCandidate::Suffix(".ts".to_string()),
+            ModuleCandidate::Unsupported {
+                suffix: ".tsx".to_string(),
+                hint: "TSX modules are not supported".to_string(),
+            },
+            ModuleCandidate::Suffix("/index.ts".to_string()),
+        ]
+    }
+}
+
+impl Interpreter {
+    /// Serve imports from `sources`, a map of module path to source text.
+    ///
+    /// Paths are compared with [`ImportRequest::resolved_path`], so relative
+    /// imports need absolute keys such as `/src/utils.ts`. Replaces any map
+    /// set before; modules already loaded stay loaded.
+    pub fn set_module_source_map<K, V>(&mut self, sources: impl IntoIterator<Item = (K, V)>)
+    where
+        K: Into<String>,
+        V: AsRef<str>,
+    {
+        self.module_source_map = sources
+            .into_iter()
+            .map(|(path, source)| (path.into(), Rc::from(source.as_ref())))
+            .collect();
+    }
+
+    /// Set the candidates tried, in order, to find an import in the module
+    /// source map (default: [`ModuleCandidate::defaults`])
+    pub fn set_module_candidates(&mut self, candidates: Vec<ModuleCandidate>) {
+        self.module_candidates = candidates;
+    }
+
+    /// Satisfy `req` from the module source map, parsing the matching file.
+    ///
+    /// Returns `false` when no candidate matches, leaving the import to the host.
+    pub(crate) fn serve_from_module_map(&mut self, req: &ImportRequest) -> Result<bool, JsError> {
+        if self.module_source_map.is_empty() {
+            return Ok(false);
+        }
+
+        let requested = req.resolved_path.as_str();
+        let mut found = None;
+        for candidate in &self.module_candidates {
+            let (path, hint) = match candidate {
+                ModuleCandidate::Exact => (requested.to_string(), None),
+                ModuleCandidate::Suffix(suffix) => (format!("{}{}", requested, suffix), None),
+                ModuleCandidate::Unsupported { suffix, hint } => {
+                    (format!("{}{}", requested, suffix), Some(hint))
+                }
+            };
+            let Some(source) = self.module_source_map.get(&path) else {
+                continue;
+            };
+            if let Some(hint) = hint {
+                return Err(JsError::module_error(format!(
+                    "Cannot import '{}': found '{}', but {}",
+                    req.specifier, path, hint
+                )));
+            }
+            found = Some((ModulePath::new(path), Rc::clone(source)));
+            break;
+        }
+        let Some((file, source)) = found else {
+            return Ok(false);
+        };
+
+        // The same file imported under another path shares its instance
+        if let Some(module) = self.loaded_modules.get(&file) {
+            let module = module.cheap_clone();
+            self.loaded_modules
+                .insert(req.resolved_path.clone(), module);
+            return Ok(true);
+        }
+
+        self.check_module_integrity(&file, &source, Some(req))?;
+        if req.is_json() {
+            self.load_json_module(&file, &source)?;
+            if let Some(module) = self.loaded_modules.get(&file) {
+                let module = module.cheap_clone();
+                self.loaded_modules
+                    .insert(req.resolved_path.clone(), module);
+            }
+            return Ok(true);
+        }
+        let program = self.parse_module(&file, &source)?;
+        if file != req.resolved_path {
+            self.module_files.insert(req.resolved_path.clone(), file);
+        }
+        self.pending_module_sources
+            .insert(req.resolved_path.clone(), program);
+        Ok(true)
+    }
+
+    /// Like [`Interpreter::provide_module`], for a module the host read from
+    /// `file` rather than from `resolved_path`, such as `/lib/index.ts` for
+    /// an import of `./lib`. The module runs as `file`, so its relative
+    /// imports resolve from there.
+    pub fn provide_module_file(
+        &mut self,
+        resolved_path: ModulePath,
+        file: ModulePath,
+        source: &str,
+    ) -> Result<(), JsError> {
+        self.provide_module(resolved_path.clone(), source)?;
+        if file != resolved_path {
+            self.module_files.insert(resolved_path, file);
+        }
+        Ok(())
+    }
+
+    /// The file a pending or running module was read from, for resolving its imports
+    pub(crate) fn module_file<'a>(&'a self, module_path: &'a ModulePath) -> &'a ModulePath {
+        self.module_files.get(module_path).unwrap_or(module_path)
+    }
+}
@@ -0,0 +1,263 @@
+//! Recording and replaying the orders a script exchanges with its host.
+//!
+//! With [`Interpreter::set_order_recording`] every order handed to the host
+//! is logged with its payload as JSON, and the response the host gives it
+//! is filled in by `fulfill_orders`. The log, taken with
+//! [`Interpreter::take_order_log`], serializes with serde, so a production
+//! run can be saved and re-run later: [`Interpreter::set_order_replay`]
+//! makes `step()` answer each new order from the log instead of returning
+//! it to the host.
+//!
+//! Order ids differ between runs, so replay matches orders by payload
+//! content, in order: the n-th order the script creates must carry the
+//! payload of the n-th logged one. The first order that doesn't fails
+//! `step()` with [`JsError::ReplayMismatch`] naming the field that differs.
+
+use crate::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+use super::Interpreter;
+use super::builtins::json::{js_value_to_json, json_to_js_value_with_guard};
+use crate::error::JsError;
+use crate::{Order, OrderId, OrderResponse, RuntimeValue, StepResult};
+
+/// One order of a recorded run, see [`Interpreter::take_order_log`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderLogEntry {
+    /// The order's id in the recorded run
+    pub id: OrderId,
+    /// The order payload, converted to JSON
+    pub payload_json: serde_json::Value,
+    /// How the host answered; `None` if it never did, for example because
+    /// the order was cancelled
+    pub response: Option<OrderLogResponse>,
+    /// Which `step()` result handed the order to the host, counting from 0;
+    /// orders delivered together share it
+    pub batch_index: u32,
+}
+
+/// The host's answer to a recorded order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum OrderLogResponse {
+    /// Fulfilled with this value
+    Value(serde_json::Value),
+    /// Rejected with this value, as by [`OrderResponse::reject_with_value`]
+    Rejected(serde_json::Value),
+    /// Failed with a [`JsError`], which reaches the script as an error
+    /// object named `name`
+    Error { name: String, message: String },
+}
+
+/// A log being replayed and how far the run has got through it
+pub(crate) struct OrderReplay {
+    log: Vec<OrderLogEntry>,
+    next: usize,
+}
+
+impl Interpreter {
+    /// Start or stop recording order traffic.
+    ///
+    /// While recording, orders handed to the host by `step()` are logged with
+    /// their payloads, and the responses passed to `fulfill_orders` are added
+    /// to their entries. Responses JSON cannot represent are left out.
+    pub fn set_order_recording(&mut self, enabled: bool) {
+        self.order_recording = enabled;
+    }
+
+    /// Take the orders recorded so far, in the order the script created them.
+    ///
+    /// A response that arrives after its entry was taken is not recorded, so
+    /// take the log once the run is over.
+    pub fn take_order_log(&mut self) -> Vec<OrderLogEntry> {
+        mem::take(&mut self.order_log)
+    }
+
+    /// Answer the script's orders from a recorded log.
+    ///
+    /// `step()` fulfills each new order with the response logged for it and
+    /// carries on, so the host is never handed an order. An order with no
+    /// logged response stays outstanding. An order whose payload differs from
+    /// the log, or one past its end, fails `step()` with
+    /// [`JsError::ReplayMismatch`].
+    pub fn set_order_replay(&mut self, log: Vec<OrderLogEntry>) {
+        self.order_replay = Some(OrderReplay { log, next: 0 });
+    }
+
+    /// Log the orders in a `step()` result
+    pub(crate) fn record_orders(&mut self, result: &StepResult) -> Result<(), JsError> {
+        let orders = match result {
+            StepResult::Suspended { pending, .. } | StepResult::Orders(pending) => pending,
+            _ => return Ok(()),
+        };
+        if orders.is_empty() {
+            return Ok(());
+        }
+        for order in orders {
+            self.order_log.push(OrderLogEntry {
+                id: order.id,
+                payload_json: js_value_to_json(order.payload.value())?,
+                response: None,
+                batch_index: self.order_log_batches,
+            });
+        }
+        self.order_log_batches += 1;
+        Ok(())
+    }
+
+    /// Add a response passed to `fulfill_orders` to its order's entry
+    pub(crate) fn record_order_response(&mut self, response: &OrderResponse) {
+        let Some(entry) = self
+            .order_log
+            .iter_mut()
+            .rev()
+            .find(|entry| entry.id == response.id)
+        else {
+            return;
+        };
+        entry.response = match &response.result {
+            Ok(value) => js_value_to_json(value.value())
+                .ok()
+                .map(OrderLogResponse::Value),
+            Err(JsError::ThrownValue { guarded }) => js_value_to_json(&guarded.value)
+                .ok()
+                .map(OrderLogResponse::Rejected),
+            Err(error) => Some(error_response(error)),
+        };
+    }
+
+    /// Fulfill the orders in a `step()` result from the replayed log.
+    ///
+    /// Results that carry orders or cancellations become `Continue`; the
+    /// host has nothing to do for them.
+    pub(crate) fn replay_orders(&mut self, result: StepResult) -> Result<StepResult, JsError> {
+        let orders = match result {
+            StepResult::Orders(orders) => orders,
+            StepResult::Suspended { pending, cancelled }
+                if !pending.is_empty() || !cancelled.is_empty() =>
+            {
+                pending
+            }
+            other => return Ok(other),
+        };
+        let mut responses = Vec::new();
+        for order in &orders {
+            if let Some(response) = self.replayed_response(order)? {
+                responses.push(response);
+            }
+        }
+        self.fulfill_orders(responses);
+        Ok(StepResult::Continue)
+    }
+
+    /// Match `order` against the next logged order and rebuild its response
+    fn replayed_response(&mut self, order: &Order) -> Result<Option<OrderResponse>, JsError> {
+        let Some(replay) = self.order_replay.as_mut() else {
+            return Ok(None);
+        };
+        let position = replay.next;
+        let payload = js_value_to_json(order.payload.value())?;
+        let Some(entry) = replay.log.get(position) else {
+            return Err(JsError::ReplayMismatch {
+                position,
+                diff: format!("the log ends here; the script ordered {}", payload),
+            });
+        };
+        if let Some(diff) = json_diff("payload", &entry.payload_json, &payload) {
+            return Err(JsError::ReplayMismatch { position, diff });
+        }
+        replay.next += 1;
+        let Some(logged) = entry.response.clone() else {
+            return Ok(None);
+        };
+
+        let id = order.id;
+        let response = match logged {
+            OrderLogResponse::Value(json) => OrderResponse {
+                id,
+                result: Ok(self.json_runtime_value(&json)?),
+            },
+            OrderLogResponse::Rejected(json) => {
+                let value = self.json_runtime_value(&json)?;
+                OrderResponse::reject_with_value(id, value)
+            }
+            OrderLogResponse::Error { name, message } => OrderResponse {
+                id,
+                result: Err(JsError::RuntimeError {
+                    kind: name,
+                    message,
+                    stack: Vec::new(),
+                    properties: None,
+                    flight_log: None,
+                }),
+            },
+        };
+        Ok(Some(response))
+    }
+
+    fn json_runtime_value(&mut self, json: &serde_json::Value) -> Result<RuntimeValue, JsError> {
+        let guard = self.heap.create_guard();
+        let value = json_to_js_value_with_guard(self, json, &guard)?;
+        Ok(RuntimeValue::with_guard(value, guard))
+    }
+}
+
+/// The error name and message a script sees for `error`
+fn error_response(error: &JsError) -> OrderLogResponse {
+    let (name, message) = match error {
+        JsError::TypeError { message, .. } => ("TypeError", message.clone()),
+        JsError::RangeError { message } => ("RangeError", message.clone()),
+        JsError::ReferenceError { name } => ("ReferenceError", format!("{} is not defined", name)),
+        JsError::RuntimeError { kind, message, .. } => (kind.as_str(), message.clone()),
+        other => ("Error", other.to_string()),
+    };
+    OrderLogResponse::Error {
+        name: name.to_string(),
+        message,
+    }
+}
+
+/// The first difference between a logged and a new JSON value, as
+/// `path: logged X, got Y`
+fn json_diff(path: &str, logged: &serde_json::Value, actual: &serde_json::Value) -> Option<String> {
+    use serde_json::Value;
+    match (logged, actual) {
+        (Value::Object(logged), Value::Object(actual)) => {
+            for (key, logged_value) in logged {
+                let field = format!("{}.{}", path, key);
+                match actual.get(key) {
+                    Some(actual_value) => {
+                        if let Some(diff) = json_diff(&field, logged_value, actual_value) {
+                            return Some(diff);
+                        }
+                    }
+                    None => {
+                        return Some(format!("{}: logged {}, now missing", field, logged_value));
+                    }
+                }
+            }
+            actual
+                .iter()
+                .find(|(key, _)| !logged.contains_key(*key))
+                .map(|(key, value)| format!("{}.{}: not logged, got {}", path, key, value))
+        }
+        (Value::Array(logged), Value::Array(actual)) => {
+            for (index, (logged_value, actual_value)) in logged.iter().zip(actual).enumerate() {
+                let element = format!("{}[{}]", path, index);
+                if let Some(diff) = json_diff(&element, logged_value, actual_value) {
+                    return Some(diff);
+                }
+            }
+            (logged.len() != actual.len()).then(|| {
+                format!(
+                    "{}: logged {} elements, got {}",
+                    path,
+                    logged.len(),
+                    actual.len()
+                )
+            })
+        }
+        _ if logged == actual => None,
+        _ => Some(format!("{}: logged {}, got {}", path, logged, actual)),
+    }
+}
@@ -0,0 +1,140 @@
+//! Promises still pending when a script stops.
+//!
+//! A forgotten `await` lets a run complete while the work behind it never
+//! happens. Every pending promise the interpreter creates is remembered in a
+//! [`PromiseTracker`] through a weak reference, so tracking keeps nothing
+//! alive, and [`Interpreter::pending_promises`] lists those still unsettled.
+//! Entries for collected promises are dropped after each collection.
+//!
+//! With [`crate::InterpreterConfig::track_provenance`] on, each promise also
+//! records the script location of the call that created it.
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use super::provenance::CreationSite;
+use crate::gc::{Gc, WeakGc};
+use crate::value::{ExoticObject, JsObject, PromiseStatus};
+
+/// A promise not yet settled, see [`Interpreter::pending_promises`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingPromiseInfo {
+    /// The call that created the promise; `None` without
+    /// [`crate::InterpreterConfig::track_provenance`] or when it was created
+    /// outside script code
+    pub created_at: Option<CreationSite>,
+    /// Whether a `then`, `catch` or `finally` handler, or a promise adopting
+    /// this one, is waiting on it
+    pub has_reactions: bool,
+}
+
+struct TrackedPromise {
+    promise: WeakGc<JsObject>,
+    /// Position in creation order, for attributing promises to a call
+    serial: u64,
+    created_at: Option<CreationSite>,
+}
+
+/// Weak references to the promises created so far, oldest first
+#[derive(Default)]
+pub(crate) struct PromiseTracker {
+    promises: Vec<TrackedPromise>,
+    /// Promises tracked over the interpreter's lifetime
+    created: u64,
+    /// `Heap::weak_deaths` when dead entries were last dropped
+    deaths_seen: usize,
+}
+
+impl PromiseTracker {
+    /// Promises tracked so far; read before a call and pass to
+    /// [`Self::record_sites`] after it
+    pub(crate) fn created(&self) -> u64 {
+        self.created
+    }
+
+    /// Record `site` for the promises tracked since `first` that have none,
+    /// so promises a nested VM attributed keep their site
+    pub(crate) fn record_sites(&mut self, first: u64, site: &CreationSite) {
+        for tracked in self.promises.iter_mut().rev() {
+            if tracked.serial < first {
+                break;
+            }
+            if tracked.created_at.is_none() {
+                tracked.created_at = Some(site.clone());
+            }
+        }
+    }
+}
+
+impl Interpreter {
+    /// Remember a newly created pending promise
+    pub(crate) fn track_promise(&mut self, promise: &Gc<JsObject>) {
+        let deaths = self.heap.weak_deaths();
+        let tracker = &mut self.promise_tracker;
+        if deaths != tracker.deaths_seen {
+            tracker.deaths_seen = deaths;
+            tracker
+                .promises
+                .retain(|tracked| !tracked.promise.is_dead());
+        }
+        tracker.promises.push(TrackedPromise {
+            promise: promise.downgrade(),
+            serial: tracker.created,
+            created_at: None,
+        });
+        tracker.created += 1;
+    }
+
+    /// Number of promises created by the script that are still pending.
+    ///
+    /// Meant to be checked once [`Interpreter::step`] returns a terminal
+    /// result: a non-zero count usually means a missing `await`. Promises
+    /// that are unreachable but not yet collected are still counted; call
+    /// [`Interpreter::collect`] first to leave them out.
+    pub fn pending_promise_count(&self) -> usize {
+        self.pending_promises().len()
+    }
+
+    /// What is known about `promise`, for reports on what a script awaits
+    pub(crate) fn promise_info(&self, promise: &Gc<JsObject>) -> PendingPromiseInfo {
+        let created_at = self
+            .promise_tracker
+            .promises
+            .iter()
+            .find(|tracked| {
+                tracked
+                    .promise
+                    .upgrade()
+                    .is_some_and(|tracked| Gc::ptr_eq(&tracked, promise))
+            })
+            .and_then(|tracked| tracked.created_at.clone());
+        let has_reactions = match &promise.borrow().exotic {
+            ExoticObject::Promise(state) => !state.borrow().handlers.is_empty(),
+            _ => false,
+        };
+        PendingPromiseInfo {
+            created_at,
+            has_reactions,
+        }
+    }
+
+    /// The promises counted by [`Self::pending_promise_count`], oldest first
+    pub fn pending_promises(&self) -> Vec<PendingPromiseInfo> {
+        let mut pending = Vec::new();
+        for tracked in &self.promise_tracker.promises {
+            let Some(promise) = tracked.promise.upgrade() else {
+                continue;
+            };
+            if let ExoticObject::Promise(state) = &promise.borrow().exotic {
+                let state = state.borrow();
+                if state.status == PromiseStatus::Pending {
+                    pending.push(PendingPromiseInfo {
+                        created_at: tracked.created_at.clone(),
+                        has_reactions: !state.handlers.is_empty(),
+                    });
+                }
+            }
+        }
+        pending
+    }
+}
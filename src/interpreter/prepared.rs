@@ -0,0 +1,742 @@
+//! Prepared runs moved to another interpreter.
+//!
+//! [`Interpreter::export_prepared`] writes out a run that `prepare()` set up
+//! and that has not stepped yet: the compiled entry chunk with every function
+//! chunk nested in it, the module source map and the entry path.
+//! [`Interpreter::import_prepared`] sets the same run up in another
+//! interpreter without parsing or compiling anything, so a browser host can
+//! prepare in a Web Worker and run on the main thread.
+//!
+//! Bytecode is written instruction by instruction. The header carries the
+//! crate version and a fingerprint of the instruction set, so bytes written by
+//! a different build are refused rather than run.
+
+use crate::prelude::*;
+
+use core::hash::{Hash, Hasher};
+use rustc_hash::FxHasher;
+
+use super::Interpreter;
+use super::bytecode_vm::BytecodeVM;
+use super::checkpoint::{CheckpointError, Reader, Writer, corrupt};
+use crate::compiler::{
+    BytecodeChunk, Constant, FunctionInfo, FunctionSource, Op, OperandName, SourceMapEntry,
+};
+use crate::lexer::Span;
+use crate::value::{CheapClone, JsString, JsValue};
+use crate::{ModulePath, StepResult};
+
+/// First bytes of every exported run
+const MAGIC: &[u8; 4] = b"TSPR";
+
+/// Format version, bumped whenever the encoding changes
+const VERSION: u64 = 1;
+
+/// Chunks nested deeper than this are taken for a corrupt export
+const MAX_NESTING: usize = 512;
+
+// Constant tags
+const C_STRING: u8 = 0;
+const C_NUMBER: u8 = 1;
+const C_CHUNK: u8 = 2;
+const C_REGEXP: u8 = 3;
+const C_TEMPLATE_STRINGS: u8 = 4;
+const C_EXCLUDED_KEYS: u8 = 5;
+const C_SWITCH_TABLE: u8 = 6;
+
+impl Interpreter {
+    /// Write out the run set up by the last `prepare()`, for
+    /// [`Interpreter::import_prepared`] in another interpreter.
+    ///
+    /// Only a run that has not stepped yet can be exported, and only for an
+    /// entry program without static imports, since those modules are linked
+    /// and run by the first step. Sources given to `set_module_source_map`
+    /// are included; modules provided through `provide_module` are not.
+    /// Scripts prepared with `script_globals` are refused too, as their
+    /// globals are declared by `prepare()` itself.
+    pub fn export_prepared(&self) -> Result<Vec<u8>, CheckpointError> {
+        let Some(vm) = self.active_vm.as_ref() else {
+            let reason = if self.pending_program.is_some() {
+                "a program with imports cannot be exported, its modules are linked by the first step"
+            } else {
+                "export_prepared needs a run set up by prepare()"
+            };
+            return Err(CheckpointError::InvalidState(reason.to_string()));
+        };
+        if !vm.is_unstarted() {
+            return Err(CheckpointError::InvalidState(
+                "the prepared run has already started".to_string(),
+            ));
+        }
+        if !self.loaded_modules.is_empty() {
+            return Err(CheckpointError::InvalidState(
+                "a program with imports cannot be exported, its modules are already loaded"
+                    .to_string(),
+            ));
+        }
+        let is_script = matches!(vm.this_value(), JsValue::Object(_));
+        if is_script && self.script_globals && self.active_module_path.is_none() {
+            return Err(CheckpointError::InvalidState(
+                "a script prepared with script_globals cannot be exported".to_string(),
+            ));
+        }
+
+        let mut sources: Vec<(&String, &Rc<str>)> = self.module_source_map.iter().collect();
+        sources.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut out = Writer::default();
+        out.bytes(MAGIC);
+        out.uint(VERSION);
+        out.str(env!("CARGO_PKG_VERSION"));
+        out.u64(instruction_set_fingerprint());
+        out.opt_str(self.active_module_path.as_ref().map(|p| p.as_str()));
+        out.bool(is_script);
+        out.usize(sources.len());
+        for (path, source) in sources {
+            out.str(path);
+            out.str(source);
+        }
+        ChunkWriter::default().chunk(&mut out, &vm.chunk);
+        Ok(out.buf)
+    }
+
+    /// Set up a run written by [`Interpreter::export_prepared`], in place of
+    /// `prepare()`; `step()` then runs it as it would have run in the
+    /// exporting interpreter.
+    ///
+    /// Fails with `CheckpointError::IncompatibleRuntime` for bytes written
+    /// by another version or build of tsrun, and leaves the interpreter
+    /// untouched on any error.
+    pub fn import_prepared(&mut self, bytes: &[u8]) -> Result<StepResult, CheckpointError> {
+        if bytes.get(..MAGIC.len()) != Some(MAGIC.as_slice()) {
+            return Err(corrupt("not an exported prepared run"));
+        }
+        let mut r = Reader {
+            bytes,
+            pos: MAGIC.len(),
+        };
+        let version = r.uint()?;
+        if version != VERSION {
+            return Err(CheckpointError::IncompatibleRuntime(format!(
+                "prepared run format {} is not supported",
+                version
+            )));
+        }
+        let crate_version = r.str()?;
+        if crate_version != env!("CARGO_PKG_VERSION") {
+            return Err(CheckpointError::IncompatibleRuntime(format!(
+                "prepared by tsrun {}, this is tsrun {}",
+                crate_version,
+                env!("CARGO_PKG_VERSION")
+            )));
+        }
+        if r.u64()? != instruction_set_fingerprint() {
+            return Err(CheckpointError::IncompatibleRuntime(
+                "the bytecode instruction set differs".to_string(),
+            ));
+        }
+        let module_path = r.opt_str()?.map(ModulePath::new);
+        let is_script = r.bool()?;
+        let mut sources = Vec::new();
+        for _ in 0..r.len()? {
+            let path = r.str()?.to_string();
+            sources.push((path, Rc::from(r.str()?)));
+        }
+        let mut chunk = Rc::new(ChunkReader::default().chunk(&mut r, 0)?);
+        if r.pos != bytes.len() {
+            return Err(corrupt("trailing bytes"));
+        }
+
+        self.module_source_map.extend(sources);
+        let module_path = self.enter_prepare(module_path);
+
+        // Module environment for the main module, as `prepare()` creates it
+        let (saved_env, module_env) = if module_path.is_some() {
+            let saved = self.env.cheap_clone();
+            let module_env = self.create_module_environment();
+            self.module_guard.guard(module_env.clone());
+            self.env = module_env.cheap_clone();
+            (Some(saved), Some(module_env))
+        } else {
+            (None, None)
+        };
+
+        self.intern_chunk(&mut chunk);
+        let this_value = if is_script {
+            JsValue::Object(self.global.cheap_clone())
+        } else {
+            JsValue::Undefined
+        };
+        let vm = BytecodeVM::with_guard(chunk, this_value, self.heap.create_guard());
+
+        self.active_vm = Some(Box::new(vm));
+        self.active_module_path = module_path;
+        self.active_saved_env = saved_env;
+        self.active_module_env = module_env;
+        Ok(StepResult::Continue)
+    }
+}
+
+/// Instruction encoding: an op is written as its position in this list
+/// followed by its operands in order.
+///
+/// `write_op` matches every variant, so an op missing from the list fails
+/// to compile; reordering or changing the list changes the fingerprint.
+macro_rules! op_codec {
+    ($($name:ident $({ $($field:ident),* })?,)*) => {
+        #[repr(u16)]
+        enum OpTag {
+            $($name,)*
+        }
+
+        /// Every op with its operand names, for the instruction set fingerprint
+        const OP_LAYOUT: &[&str] = &[
+            $(concat!(stringify!($name) $(, $(" ", stringify!($field)),*)?),)*
+        ];
+
+        fn write_op(w: &mut Writer, op: Op) {
+            match op {
+                $(Op::$name $({ $($field),* })? => {
+                    w.uint(OpTag::$name as u64);
+                    $($(Operand::write($field, w);)*)?
+                })*
+            }
+        }
+
+        fn read_op(r: &mut Reader<'_>) -> Result<Op, CheckpointError> {
+            let tag = r.uint()?;
+            $(if tag == OpTag::$name as u64 {
+                return Ok(Op::$name $({ $($field: Operand::read(r)?),* })?);
+            })*
+            Err(corrupt("unknown instruction"))
+        }
+    };
+}
+
+op_codec! {
+    LoadConst { dst, idx },
+    LoadUndefined { dst },
+    LoadNull { dst },
+    LoadBool { dst, value },
+    LoadInt { dst, value },
+    Move { dst, src },
+    Add { dst, left, right },
+    Sub { dst, left, right },
+    Mul { dst, left, right },
+    Div { dst, left, right },
+    Mod { dst, left, right },
+    Exp { dst, left, right },
+    Eq { dst, left, right },
+    NotEq { dst, left, right },
+    StrictEq { dst, left, right },
+    StrictNotEq { dst, left, right },
+    Lt { dst, left, right },
+    LtEq { dst, left, right },
+    Gt { dst, left, right },
+    GtEq { dst, left, right },
+    BitAnd { dst, left, right },
+    BitOr { dst, left, right },
+    BitXor { dst, left, right },
+    LShift { dst, left, right },
+    RShift { dst, left, right },
+    URShift { dst, left, right },
+    In { dst, left, right },
+    Instanceof { dst, left, right },
+    Neg { dst, src },
+    Plus { dst, src },
+    Not { dst, src },
+    BitNot { dst, src },
+    Typeof { dst, src },
+    Void { dst, src },
+    Jump { target },
+    JumpIfTrue { cond, target },
+    JumpIfFalse { cond, target },
+    JumpIfNullish { cond, target },
+    JumpIfNotNullish { cond, target },
+    Break { target, try_depth },
+    Continue { target, try_depth },
+    SwitchString { value, table, default },
+    GetVar { dst, name },
+    TryGetVar { dst, name },
+    SetVar { name, src },
+    SetFunctionVar { name, src },
+    DeclareVar { name, init, mutable },
+    DeclareVarHoisted { name, init },
+    DeclareUninitialized { name },
+    GetGlobal { dst, name },
+    SetGlobal { name, src },
+    CreateObject { dst },
+    CreateArray { dst, start, count },
+    GetProperty { dst, obj, key },
+    GetPropertyConst { dst, obj, key },
+    SetProperty { obj, key, value },
+    SetPropertyConst { obj, key, value },
+    DeleteProperty { dst, obj, key },
+    DeletePropertyConst { dst, obj, key },
+    DefineProperty { obj, key, value, flags },
+    Call { dst, callee, this, args_start, argc },
+    CallSpread { dst, callee, this, args_start, argc },
+    DirectEval { dst, arg },
+    CallMethod { dst, obj, method, args_start, argc },
+    Construct { dst, callee, args_start, argc },
+    ConstructSpread { dst, callee, args_start, argc },
+    Return { value },
+    ReturnUndefined,
+    CreateClosure { dst, chunk_idx },
+    CreateArrow { dst, chunk_idx },
+    CreateGenerator { dst, chunk_idx },
+    CreateAsync { dst, chunk_idx },
+    CreateAsyncGenerator { dst, chunk_idx },
+    Throw { value },
+    PushTry { catch_target, finally_target },
+    PopTry,
+    FinallyEnd,
+    GetException { dst },
+    PushIterTry { iterator, catch_target },
+    PopIterTry,
+    Rethrow,
+    Await { dst, promise },
+    Yield { dst, value },
+    YieldStar { dst, iterable },
+    PushScope,
+    PopScope,
+    GetIterator { dst, obj },
+    GetKeysIterator { dst, obj },
+    GetAsyncIterator { dst, obj },
+    IteratorNext { dst, iterator },
+    IteratorDone { result, target },
+    IteratorValue { dst, result },
+    IteratorClose { iterator },
+    IteratorCloseOnThrow { iterator },
+    CreateClass { dst, constructor, super_class },
+    DefineMethod { class, name, method, is_static },
+    DefineAccessor { class, name, getter, setter, is_static, enumerable },
+    DefineMethodComputed { class, key, method, is_static },
+    DefineAccessorComputed { class, key, getter, setter, is_static, enumerable },
+    SuperCall { dst, args_start, argc },
+    SuperCallSpread { dst, args_array },
+    SuperGet { dst, key },
+    SuperGetConst { dst, key },
+    SuperSet { key, value },
+    SuperSetConst { key, value },
+    ApplyClassDecorator { class, decorator, class_name, initializers },
+    RunClassInitializers { class, initializers },
+    ApplyMethodDecorator { method, decorator, name, kind, is_static, is_private },
+    ApplyParameterDecorator { target, decorator, method_name, param_name, param_index, is_static },
+    ApplyFieldDecorator { dst, decorator, name, is_static, is_private, is_accessor },
+    StoreFieldInitializer { class, name, initializer },
+    GetFieldInitializer { dst, class, name },
+    ApplyFieldInitializer { value, initializer },
+    DefineAutoAccessor { class, name, init_value, target_dst, is_static },
+    StoreAutoAccessor { class, name, accessor_obj, is_static },
+    ApplyAutoAccessorDecorator { target, decorator, name, is_static },
+    SpreadArray { dst, src },
+    CreateRestArray { dst, start_index },
+    CreateObjectRest { dst, src, excluded_keys },
+    SpreadObject { dst, src },
+    TemplateConcat { dst, start, count },
+    TaggedTemplate { dst, tag, this, template, exprs_start, exprs_count },
+    GetPrivateField { dst, obj, class_brand, field_name },
+    SetPrivateField { obj, class_brand, field_name, value },
+    DefinePrivateField { obj, class_brand, field_name, value },
+    DefinePrivateMethod { class, class_brand, method_name, method, is_static },
+    InstallPrivateMethod { class_brand, method_name },
+    SetFunctionName { func, name },
+    SetFunctionNameFromKey { func, key },
+    Nop,
+    Halt,
+    Debugger,
+    Pop,
+    Dup { dst, src },
+    LoadThis { dst },
+    LoadArguments { dst },
+    LoadNewTarget { dst },
+    ExportBinding { export_name, binding_name, value },
+    ExportNamespace { export_name, module_specifier },
+    ReExport { export_name, source_module, source_key },
+}
+
+fn instruction_set_fingerprint() -> u64 {
+    let mut hasher = FxHasher::default();
+    OP_LAYOUT.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// An instruction operand
+trait Operand: Sized {
+    fn write(self, w: &mut Writer);
+    fn read(r: &mut Reader<'_>) -> Result<Self, CheckpointError>;
+}
+
+impl Operand for u8 {
+    fn write(self, w: &mut Writer) {
+        w.u8(self);
+    }
+
+    fn read(r: &mut Reader<'_>) -> Result<Self, CheckpointError> {
+        r.u8()
+    }
+}
+
+impl Operand for u16 {
+    fn write(self, w: &mut Writer) {
+        w.uint(u64::from(self));
+    }
+
+    fn read(r: &mut Reader<'_>) -> Result<Self, CheckpointError> {
+        u16::try_from(r.uint()?).map_err(|_| corrupt("integer out of range"))
+    }
+}
+
+impl Operand for u32 {
+    fn write(self, w: &mut Writer) {
+        w.uint(u64::from(self));
+    }
+
+    fn read(r: &mut Reader<'_>) -> Result<Self, CheckpointError> {
+        r.u32()
+    }
+}
+
+impl Operand for i32 {
+    fn write(self, w: &mut Writer) {
+        w.uint(u64::from(self as u32));
+    }
+
+    fn read(r: &mut Reader<'_>) -> Result<Self, CheckpointError> {
+        Ok(r.u32()? as i32)
+    }
+}
+
+impl Operand for bool {
+    fn write(self, w: &mut Writer) {
+        w.bool(self);
+    }
+
+    fn read(r: &mut Reader<'_>) -> Result<Self, CheckpointError> {
+        r.bool()
+    }
+}
+
+/// Writes chunks, with each program text shared by function sources written once
+#[derive(Default)]
+struct ChunkWriter {
+    sources: FxHashMap<usize, usize>,
+}
+
+impl ChunkWriter {
+    fn chunk(&mut self, w: &mut Writer, chunk: &BytecodeChunk) {
+        let BytecodeChunk {
+            code,
+            constants,
+            source_map,
+            operand_names,
+            register_count,
+            function_info,
+            source_file,
+            back_edges,
+        } = chunk;
+
+        w.usize(code.len());
+        for op in code.iter() {
+            write_op(w, *op);
+        }
+        w.usize(constants.len());
+        for constant in constants {
+            self.constant(w, constant);
+        }
+        w.usize(source_map.len());
+        for entry in source_map {
+            w.usize(entry.bytecode_offset);
+            let Span {
+                start,
+                end,
+                line,
+                column,
+            } = entry.span;
+            w.usize(start);
+            w.usize(end);
+            w.uint(u64::from(line));
+            w.uint(u64::from(column));
+        }
+        w.usize(operand_names.len());
+        for operand in operand_names {
+            w.usize(operand.bytecode_offset);
+            w.str(operand.name.as_str());
+        }
+        w.u8(*register_count);
+        match function_info {
+            Some(info) => {
+                w.u8(1);
+                self.function_info(w, info);
+            }
+            None => w.u8(0),
+        }
+        w.opt_str(source_file.as_deref());
+        w.usize(back_edges.len());
+        for offset in back_edges {
+            w.uint(u64::from(*offset));
+        }
+    }
+
+    fn constant(&mut self, w: &mut Writer, constant: &Constant) {
+        match constant {
+            Constant::String(s) => {
+                w.u8(C_STRING);
+                w.str(s.as_str());
+            }
+            Constant::Number(n) => {
+                w.u8(C_NUMBER);
+                w.f64(*n);
+            }
+            Constant::Chunk(chunk) => {
+                w.u8(C_CHUNK);
+                self.chunk(w, chunk);
+            }
+            Constant::RegExp { pattern, flags } => {
+                w.u8(C_REGEXP);
+                w.str(pattern.as_str());
+                w.str(flags.as_str());
+            }
+            Constant::TemplateStrings { cooked, raw } => {
+                w.u8(C_TEMPLATE_STRINGS);
+                write_strings(w, cooked);
+                write_strings(w, raw);
+            }
+            Constant::ExcludedKeys(keys) => {
+                w.u8(C_EXCLUDED_KEYS);
+                write_strings(w, keys);
+            }
+            Constant::SwitchTable(table) => {
+                w.u8(C_SWITCH_TABLE);
+                let mut cases: Vec<(&JsString, &u32)> = table.iter().collect();
+                cases.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+                w.usize(cases.len());
+                for (case, target) in cases {
+                    w.str(case.as_str());
+                    w.uint(u64::from(*target));
+                }
+            }
+        }
+    }
+
+    fn function_info(&mut self, w: &mut Writer, info: &FunctionInfo) {
+        let FunctionInfo {
+            name,
+            param_count,
+            is_generator,
+            is_async,
+            is_arrow,
+            uses_arguments,
+            uses_this,
+            creates_closures,
+            param_names,
+            rest_param,
+            local_names,
+            binding_count,
+            source_text,
+        } = info;
+
+        w.opt_str(name.as_ref().map(|name| name.as_str()));
+        w.usize(*param_count);
+        for flag in [
+            is_generator,
+            is_async,
+            is_arrow,
+            uses_arguments,
+            uses_this,
+            creates_closures,
+        ] {
+            w.bool(*flag);
+        }
+        write_strings(w, param_names);
+        match rest_param {
+            Some(index) => {
+                w.u8(1);
+                w.usize(*index);
+            }
+            None => w.u8(0),
+        }
+        write_strings(w, local_names);
+        w.usize(*binding_count);
+        match source_text {
+            Some(source) => {
+                w.u8(1);
+                self.function_source(w, source);
+            }
+            None => w.u8(0),
+        }
+    }
+
+    fn function_source(&mut self, w: &mut Writer, source: &FunctionSource) {
+        let program = source.program_source();
+        let key = Rc::as_ptr(program) as *const u8 as usize;
+        let next = self.sources.len();
+        match self.sources.get(&key) {
+            Some(&index) => w.usize(index),
+            None => {
+                self.sources.insert(key, next);
+                w.usize(next);
+                w.str(program);
+            }
+        }
+        let (start, end) = source.range();
+        w.usize(start);
+        w.usize(end);
+        w.opt_str(source.path());
+    }
+}
+
+fn write_strings(w: &mut Writer, strings: &[JsString]) {
+    w.usize(strings.len());
+    for s in strings {
+        w.str(s.as_str());
+    }
+}
+
+/// Reads chunks written by [`ChunkWriter`]
+#[derive(Default)]
+struct ChunkReader {
+    sources: Vec<Rc<str>>,
+}
+
+impl ChunkReader {
+    fn chunk(
+        &mut self,
+        r: &mut Reader<'_>,
+        depth: usize,
+    ) -> Result<BytecodeChunk, CheckpointError> {
+        if depth > MAX_NESTING {
+            return Err(corrupt("chunks nested too deeply"));
+        }
+        let mut chunk = BytecodeChunk::new();
+
+        let len = r.len()?;
+        let mut code = Vec::with_capacity(len);
+        for _ in 0..len {
+            code.push(read_op(r)?);
+        }
+        chunk.code = Rc::from(code);
+        for _ in 0..r.len()? {
+            let constant = self.constant(r, depth)?;
+            chunk.constants.push(constant);
+        }
+        for _ in 0..r.len()? {
+            let bytecode_offset = r.usize()?;
+            let span = Span::new(r.usize()?, r.usize()?, r.u32()?, r.u32()?);
+            chunk.source_map.push(SourceMapEntry {
+                bytecode_offset,
+                span,
+            });
+        }
+        for _ in 0..r.len()? {
+            let bytecode_offset = r.usize()?;
+            let name = JsString::from(r.str()?);
+            chunk.operand_names.push(OperandName {
+                bytecode_offset,
+                name,
+            });
+        }
+        chunk.register_count = r.u8()?;
+        chunk.function_info = match r.u8()? {
+            0 => None,
+            _ => Some(self.function_info(r)?),
+        };
+        chunk.source_file = r.opt_str()?.map(str::to_string);
+        for _ in 0..r.len()? {
+            chunk.back_edges.push(r.u32()?);
+        }
+        Ok(chunk)
+    }
+
+    fn constant(&mut self, r: &mut Reader<'_>, depth: usize) -> Result<Constant, CheckpointError> {
+        Ok(match r.u8()? {
+            C_STRING => Constant::String(JsString::from(r.str()?)),
+            C_NUMBER => Constant::Number(r.f64()?),
+            C_CHUNK => Constant::Chunk(Rc::new(self.chunk(r, depth + 1)?)),
+            C_REGEXP => Constant::RegExp {
+                pattern: JsString::from(r.str()?),
+                flags: JsString::from(r.str()?),
+            },
+            C_TEMPLATE_STRINGS => Constant::TemplateStrings {
+                cooked: read_strings(r)?,
+                raw: read_strings(r)?,
+            },
+            C_EXCLUDED_KEYS => Constant::ExcludedKeys(read_strings(r)?),
+            C_SWITCH_TABLE => {
+                let mut table = FxHashMap::default();
+                for _ in 0..r.len()? {
+                    let case = JsString::from(r.str()?);
+                    table.insert(case, r.u32()?);
+                }
+                Constant::SwitchTable(table)
+            }
+            _ => return Err(corrupt("unknown constant")),
+        })
+    }
+
+    fn function_info(&mut self, r: &mut Reader<'_>) -> Result<FunctionInfo, CheckpointError> {
+        let name = r.opt_str()?.map(JsString::from);
+        let param_count = r.usize()?;
+        let is_generator = r.bool()?;
+        let is_async = r.bool()?;
+        let is_arrow = r.bool()?;
+        let uses_arguments = r.bool()?;
+        let uses_this = r.bool()?;
+        let creates_closures = r.bool()?;
+        let param_names = read_strings(r)?;
+        let rest_param = match r.u8()? {
+            0 => None,
+            _ => Some(r.usize()?),
+        };
+        let local_names = read_strings(r)?;
+        let binding_count = r.usize()?;
+        let source_text = match r.u8()? {
+            0 => None,
+            _ => Some(self.function_source(r)?),
+        };
+        Ok(FunctionInfo {
+            name,
+            param_count,
+            is_generator,
+            is_async,
+            is_arrow,
+            uses_arguments,
+            uses_this,
+            creates_closures,
+            param_names,
+            rest_param,
+            local_names,
+            binding_count,
+            source_text,
+        })
+    }
+
+    fn function_source(&mut self, r: &mut Reader<'_>) -> Result<FunctionSource, CheckpointError> {
+        let index = r.usize()?;
+        let program = match self.sources.get(index) {
+            Some(program) => Rc::clone(program),
+            None if index == self.sources.len() => {
+                let program: Rc<str> = Rc::from(r.str()?);
+                self.sources.push(Rc::clone(&program));
+                program
+            }
+            None => return Err(corrupt("function source out of range")),
+        };
+        let start = r.usize()?;
+        let end = r.usize()?;
+        let path = r.opt_str()?;
+        FunctionSource::new(program, start, end)
+            .map(|source| source.with_path(path))
+            .ok_or_else(|| corrupt("function source range out of bounds"))
+    }
+}
+
+fn read_strings(r: &mut Reader<'_>) -> Result<Vec<JsString>, CheckpointError> {
+    let len = r.len()?;
+    let mut strings = Vec::with_capacity(len);
+    for _ in 0..len {
+        strings.push(JsString::from(r.str()?));
+    }
+    Ok(strings)
+}
@@ -0,0 +1,211 @@
+//! Watch expressions sampled whenever a run suspends or completes.
+//!
+//! [`Interpreter::add_probe`] compiles an expression once, after checking
+//! that it only reads: identifiers, literals, property access and operators,
+//! but no calls, assignments or function literals. Each time `step()` is
+//! about to return `Suspended` or `Complete`, every probe is evaluated in the
+//! main module's top-level scope and its value, or the error it threw, is
+//! kept until the host calls [`Interpreter::take_probe_samples`].
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use super::env_scope::EnvScope;
+use crate::StepResult;
+use crate::ast::{
+    ArrayElement, Expression, MemberProperty, ObjectProperty, ObjectPropertyKey, PropertyKind,
+    Statement, UnaryOp,
+};
+use crate::compiler::{BytecodeChunk, Compiler};
+use crate::error::JsError;
+use crate::gc::Gc;
+use crate::lexer::Span;
+use crate::value::{CheapClone, JsObject, JsValue};
+
+/// Identifies a probe added with [`Interpreter::add_probe`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProbeId(pub u32);
+
+/// The value of a probe at one suspension or completion of the run
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProbeSample {
+    pub probe: ProbeId,
+    /// Name the probe was added under
+    pub name: String,
+    /// Index of the `step()` call that returned, counting from 0
+    pub step_index: u64,
+    /// The value as JSON, or the message of the error evaluating it threw
+    pub value_json: Result<serde_json::Value, String>,
+}
+
+/// A compiled probe expression
+pub(crate) struct Probe {
+    id: ProbeId,
+    name: Rc<str>,
+    chunk: Rc<BytecodeChunk>,
+}
+
+impl Interpreter {
+    /// Add a probe sampling `expression` each time `step()` returns
+    /// `Suspended` or `Complete`.
+    ///
+    /// The expression is parsed and compiled now, and must only read:
+    /// function calls, `new`, assignments, updates, `delete`, `await`,
+    /// spreads and function or class literals fail with a syntax error.
+    /// Getters and `toString` methods it reaches still run.
+    pub fn add_probe(&mut self, name: &str, expression: &str) -> Result<ProbeId, JsError> {
+        let program = self.parse_program(expression)?;
+        let expr = match &*program.body {
+            [Statement::Expression(stmt)] => stmt.expression.cheap_clone(),
+            _ => {
+                return Err(JsError::syntax_error_simple(format!(
+                    "Probe '{}' must be a single expression",
+                    name
+                )));
+            }
+        };
+        check_read_only(&expr)?;
+
+        let mut chunk = Compiler::compile_program_for_eval(&program, self.compile_limits)?;
+        self.intern_chunk(&mut chunk);
+        let id = ProbeId(self.probes.len() as u32);
+        self.probes.push(Probe {
+            id,
+            name: Rc::from(name),
+            chunk,
+        });
+        Ok(id)
+    }
+
+    /// The samples recorded since the last call, oldest first.
+    ///
+    /// Samples accumulate until taken, so hosts with probes should take
+    /// them regularly.
+    pub fn take_probe_samples(&mut self) -> Vec<ProbeSample> {
+        mem::take(&mut self.probe_samples)
+    }
+
+    /// The scope probes read from: the main module's top-level environment,
+    /// or the global one for scripts run without a module path
+    pub(crate) fn probe_scope(&self) -> Gc<JsObject> {
+        self.active_module_env
+            .as_ref()
+            .unwrap_or(&self.global_env)
+            .cheap_clone()
+    }
+
+    /// Evaluate every probe in `scope` if `result` suspends or completes the run
+    pub(crate) fn sample_probes(&mut self, result: &StepResult, scope: Gc<JsObject>) {
+        if !matches!(
+            result,
+            StepResult::Suspended { .. } | StepResult::Complete(_)
+        ) {
+            return;
+        }
+        // Probes run instructions, which is no progress of the script
+        let progress = self.stall_progress;
+        let probes: Vec<(ProbeId, Rc<str>, Rc<BytecodeChunk>)> = self
+            .probes
+            .iter()
+            .map(|probe| (probe.id, probe.name.clone(), probe.chunk.clone()))
+            .collect();
+        for (probe, name, chunk) in probes {
+            let guard = self.heap.create_guard();
+            guard.guard(scope.cheap_clone());
+            let value = {
+                let mut env = EnvScope::enter(self, scope.cheap_clone(), guard);
+                env.run_bytecode_with_this(chunk, JsValue::Undefined)
+            };
+            let value_json = value
+                .and_then(|value| crate::js_value_to_json(&value.value))
+                .map_err(|err| err.to_string());
+            self.probe_samples.push(ProbeSample {
+                probe,
+                name: name.to_string(),
+                step_index: self.steps_taken,
+                value_json,
+            });
+        }
+        self.stall_progress = progress;
+    }
+}
+
+/// Fail unless `expr` only reads: identifiers, literals, property access and
+/// operators that don't assign
+fn check_read_only(expr: &Expression) -> Result<(), JsError> {
+    let rejected = |what: &str, span: &Span| {
+        Err(JsError::syntax_error_at(
+            format!("Probe expressions can only read, {} are not allowed", what),
+            span,
+        ))
+    };
+    match expr {
+        Expression::Literal(_) | Expression::Identifier(_) | Expression::This(_) => Ok(()),
+        Expression::Array(array) => {
+            for element in array.elements.iter().flatten() {
+                match element {
+                    ArrayElement::Expression(e) => check_read_only(e)?,
+                    ArrayElement::Spread(spread) => return rejected("spreads", &spread.span),
+                }
+            }
+            Ok(())
+        }
+        Expression::Object(object) => {
+            for property in &object.properties {
+                let property = match property {
+                    ObjectProperty::Property(property) => property,
+                    ObjectProperty::Spread(spread) => return rejected("spreads", &spread.span),
+                };
+                if property.method || property.kind != PropertyKind::Init {
+                    return rejected("methods", &property.span);
+                }
+                if let ObjectPropertyKey::Computed(key) = &property.key {
+                    check_read_only(key)?;
+                }
+                check_read_only(&property.value)?;
+            }
+            Ok(())
+        }
+        Expression::Template(template) => template.expressions.iter().try_for_each(check_read_only),
+        Expression::Unary(unary) if unary.operator == UnaryOp::Delete => {
+            rejected("deletes", &unary.span)
+        }
+        Expression::Unary(unary) => check_read_only(&unary.argument),
+        Expression::Binary(binary) => {
+            check_read_only(&binary.left)?;
+            check_read_only(&binary.right)
+        }
+        Expression::Logical(logical) => {
+            check_read_only(&logical.left)?;
+            check_read_only(&logical.right)
+        }
+        Expression::Conditional(cond) => {
+            check_read_only(&cond.test)?;
+            check_read_only(&cond.consequent)?;
+            check_read_only(&cond.alternate)
+        }
+        Expression::Sequence(seq) => seq.expressions.iter().try_for_each(check_read_only),
+        Expression::Member(member) => {
+            check_read_only(&member.object)?;
+            match &member.property {
+                MemberProperty::Expression(key) => check_read_only(key),
+                MemberProperty::Identifier(_) | MemberProperty::PrivateIdentifier(_) => Ok(()),
+            }
+        }
+        Expression::OptionalChain(chain) => check_read_only(&chain.base),
+        Expression::TypeAssertion(assertion) => check_read_only(&assertion.expression),
+        Expression::NonNull(non_null) => check_read_only(&non_null.expression),
+        Expression::Parenthesized(inner, _) => check_read_only(inner),
+        Expression::Call(call) => rejected("function calls", &call.span),
+        Expression::New(new) => rejected("function calls", &new.span),
+        Expression::TaggedTemplate(tagged) => rejected("function calls", &tagged.span),
+        Expression::Assignment(assign) => rejected("assignments", &assign.span),
+        Expression::Update(update) => rejected("assignments", &update.span),
+        Expression::Function(_) | Expression::ArrowFunction(_) | Expression::Class(_) => {
+            rejected("function literals", &expr.span())
+        }
+        Expression::Spread(spread) => rejected("spreads", &spread.span),
+        Expression::Await(_) | Expression::Yield(_) => rejected("await and yield", &expr.span()),
+        Expression::Super(span) => rejected("super", span),
+    }
+}
@@ -0,0 +1,278 @@
+//! Where the objects of a value were built.
+//!
+//! With [`crate::InterpreterConfig::track_provenance`] on, every object and
+//! array literal records its creation site (file, line and column) and the
+//! innermost function that returned it, in a side table keyed by object id.
+//! [`Interpreter::explain`] renders a value tree annotated with these sites
+//! and [`Interpreter::provenance`] returns them as [`ProvenanceEntry`]s.
+//! With the mode off nothing is recorded and objects carry no extra field.
+//!
+//! Tracked objects stay rooted for the life of the interpreter so their ids
+//! are never reused, which makes the mode meant for debugging runs only.
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use super::purity::member_path;
+use crate::RuntimeValue;
+use crate::gc::{Gc, Guard};
+use crate::value::{CheapClone, ExoticObject, JsObject, JsValue, PropertyKey, number_to_string};
+
+/// Where an object was created, see [`Interpreter::provenance`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreationSite {
+    /// Module path of the literal, `None` for scripts run without one
+    pub file: Option<String>,
+    pub line: u32,
+    pub column: u32,
+    /// Innermost function that returned the object, `None` if none did
+    pub returned_by: Option<String>,
+}
+
+/// One object or array of an explained value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProvenanceEntry {
+    /// Member path from the explained value, e.g. `spec.ports[0]`; empty for
+    /// the value itself
+    pub path: String,
+    /// `None` for objects not created by a literal while tracking was on
+    pub site: Option<CreationSite>,
+}
+
+/// Creation sites recorded while [`crate::InterpreterConfig::track_provenance`] is on
+pub(crate) struct ProvenanceTable {
+    sites: FxHashMap<usize, CreationSite>,
+    /// Roots the tracked objects so their ids can't be reused by new allocations
+    guard: Guard<JsObject>,
+}
+
+impl ProvenanceTable {
+    pub(crate) fn new(guard: Guard<JsObject>) -> Self {
+        Self {
+            sites: FxHashMap::default(),
+            guard,
+        }
+    }
+
+    /// Record `obj` as created at `file:line:column`
+    pub(crate) fn record(
+        &mut self,
+        obj: &Gc<JsObject>,
+        file: Option<String>,
+        line: u32,
+        column: u32,
+    ) {
+        self.guard.guard(obj.cheap_clone());
+        self.sites.insert(
+            obj.id(),
+            CreationSite {
+                file,
+                line,
+                column,
+                returned_by: None,
+            },
+        );
+    }
+
+    /// Note that `function` returned `obj`, unless a function it called did first
+    pub(crate) fn note_returned(&mut self, obj: &Gc<JsObject>, function: &str) {
+        if let Some(site) = self.sites.get_mut(&obj.id())
+            && site.returned_by.is_none()
+        {
+            site.returned_by = Some(function.to_string());
+        }
+    }
+}
+
+impl Interpreter {
+    /// Render `value` as a tree annotated with where each object and array
+    /// was created and which function returned it.
+    ///
+    /// Objects nested deeper than `max_depth` are summarized as `[Object]` or
+    /// `[Array]`. Without [`crate::InterpreterConfig::track_provenance`] the
+    /// tree is rendered without annotations.
+    pub fn explain(&self, value: &RuntimeValue, max_depth: usize) -> String {
+        let mut walk = Walk::new(self, max_depth);
+        let annotation = walk.value(value.value(), "", 0);
+        push_annotation(&mut walk.out, annotation);
+        walk.out
+    }
+
+    /// The creation site of every object and array in `value`, down to
+    /// `max_depth` levels of nesting, in the order [`Self::explain`] shows them
+    pub fn provenance(&self, value: &RuntimeValue, max_depth: usize) -> Vec<ProvenanceEntry> {
+        let mut walk = Walk::new(self, max_depth);
+        walk.value(value.value(), "", 0);
+        walk.entries
+    }
+}
+
+/// Depth-first rendering of a value tree for `explain` and `provenance`
+struct Walk<'a> {
+    sites: Option<&'a FxHashMap<usize, CreationSite>>,
+    max_depth: usize,
+    /// Ids of the objects being rendered, to show cycles as `[Circular]`
+    ancestors: Vec<usize>,
+    out: String,
+    entries: Vec<ProvenanceEntry>,
+}
+
+impl<'a> Walk<'a> {
+    fn new(interp: &'a Interpreter, max_depth: usize) -> Self {
+        Self {
+            sites: interp.provenance.as_ref().map(|table| &table.sites),
+            max_depth,
+            ancestors: Vec::new(),
+            out: String::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Render `value`, returning the annotation of an object written on one
+    /// line, for the caller to place after any trailing comma
+    fn value(&mut self, value: &JsValue, path: &str, depth: usize) -> Option<String> {
+        match value {
+            JsValue::Undefined => self.out.push_str("undefined"),
+            JsValue::Null => self.out.push_str("null"),
+            JsValue::Boolean(b) => self.out.push_str(if *b { "true" } else { "false" }),
+            JsValue::Number(n) => self.out.push_str(&number_to_string(*n)),
+            JsValue::String(s) => self
+                .out
+                .push_str(&serde_json::Value::String(s.to_string()).to_string()),
+            JsValue::Symbol(symbol) => {
+                let desc = symbol.description.as_ref().map_or("", |d| d.as_str());
+                self.out.push_str(&format!("Symbol({})", desc));
+            }
+            JsValue::Object(obj) => return self.object(obj, path, depth),
+        }
+        None
+    }
+
+    fn object(&mut self, obj: &Gc<JsObject>, path: &str, depth: usize) -> Option<String> {
+        if let ExoticObject::Function(func) = &obj.borrow().exotic {
+            match func.name() {
+                Some(name) if !name.is_empty() => {
+                    self.out.push_str(&format!("[Function {}]", name))
+                }
+                _ => self.out.push_str("[Function]"),
+            }
+            return None;
+        }
+        if self.ancestors.contains(&obj.id()) {
+            self.out.push_str("[Circular]");
+            return None;
+        }
+
+        let site = self.sites.and_then(|sites| sites.get(&obj.id())).cloned();
+        let annotation = site.as_ref().map(describe_site);
+        self.entries.push(ProvenanceEntry {
+            path: path.to_string(),
+            site,
+        });
+
+        let is_array = obj.borrow().is_array();
+        if depth >= self.max_depth {
+            self.out
+                .push_str(if is_array { "[Array]" } else { "[Object]" });
+            return annotation;
+        }
+
+        // (path, label, value); accessors are shown without being called
+        let mut children: Vec<(String, String, Option<JsValue>)> = Vec::new();
+        {
+            let obj_ref = obj.borrow();
+            if let Some(elements) = obj_ref.array_elements() {
+                for (i, element) in elements.iter().enumerate() {
+                    children.push((
+                        format!("{}[{}]", path, i),
+                        String::new(),
+                        Some(element.clone()),
+                    ));
+                }
+            }
+            for (key, prop) in obj_ref.properties.iter() {
+                if !prop.enumerable() || matches!(key, PropertyKey::Symbol(_)) {
+                    continue;
+                }
+                let value = (!prop.is_accessor()).then(|| prop.value.clone());
+                children.push((member_path(path, key), property_label(key), value));
+            }
+        }
+
+        let (open, close) = if is_array { ('[', ']') } else { ('{', '}') };
+        self.out.push(open);
+        if children.is_empty() {
+            self.out.push(close);
+            return annotation;
+        }
+        push_annotation(&mut self.out, annotation);
+
+        self.ancestors.push(obj.id());
+        let indent = "  ".repeat(depth + 1);
+        for (child_path, label, value) in children {
+            self.out.push('\n');
+            self.out.push_str(&indent);
+            if !label.is_empty() {
+                self.out.push_str(&label);
+                self.out.push_str(": ");
+            }
+            let annotation = match value {
+                Some(value) => self.value(&value, &child_path, depth + 1),
+                None => {
+                    self.out.push_str("[Accessor]");
+                    None
+                }
+            };
+            self.out.push(',');
+            push_annotation(&mut self.out, annotation);
+        }
+        self.ancestors.pop();
+
+        self.out.push('\n');
+        self.out.push_str(&"  ".repeat(depth));
+        self.out.push(close);
+        None
+    }
+}
+
+/// `file:line:column`, and the function that returned the object
+fn describe_site(site: &CreationSite) -> String {
+    let mut text = match &site.file {
+        Some(file) => format!("created at {}:{}:{}", file, site.line, site.column),
+        None => format!("created at {}:{}", site.line, site.column),
+    };
+    if let Some(function) = &site.returned_by {
+        text.push_str(", returned by ");
+        text.push_str(function);
+    }
+    text
+}
+
+fn push_annotation(out: &mut String, annotation: Option<String>) {
+    if let Some(annotation) = annotation {
+        out.push_str("  // ");
+        out.push_str(&annotation);
+    }
+}
+
+/// An object key as written in a literal: quoted unless it is an identifier
+fn property_label(key: &PropertyKey) -> String {
+    match key {
+        PropertyKey::String(name) => {
+            let name = name.as_str();
+            let is_identifier = name
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+                && name
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+            if is_identifier {
+                name.to_string()
+            } else {
+                serde_json::Value::String(name.to_string()).to_string()
+            }
+        }
+        key => key.to_string(),
+    }
+}
@@ -0,0 +1,310 @@
+//! Side-effect detection for modules that must be pure.
+//!
+//! [`Interpreter::eval_pure`] evaluates a module with a write barrier on every
+//! object the module did not create itself: the global object, the intrinsics
+//! reachable from it, and everything reachable from the namespaces it imports.
+//! The protected objects are collected up front (imports as they are bound),
+//! and the property write paths, plus assignments to global bindings, consult
+//! [`Interpreter::check_write`] before changing them.
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use crate::error::{JsError, StackFrame};
+use crate::gc::{Gc, Guard};
+use crate::value::{CheapClone, ExoticObject, JsFunction, JsObject, JsValue, PropertyKey, VarKey};
+use crate::{PurityMode, PurityViolation, PurityViolationKind, StepResult};
+
+/// Where a protected object came from
+struct Origin {
+    kind: PurityViolationKind,
+    /// Specifier of the imported module, for `ImportWrite`
+    module: Option<Rc<str>>,
+    /// Path the object was first reached by; empty for the global object or
+    /// a namespace itself
+    path: String,
+}
+
+/// Write-barrier state of a running [`Interpreter::eval_pure`]
+pub(crate) struct PurityCheck {
+    mode: PurityMode,
+    /// Protected objects by id
+    protected: FxHashMap<usize, Origin>,
+    /// Roots the protected objects so their ids can't be reused by new allocations
+    guard: Guard<JsObject>,
+    violations: Vec<PurityViolation>,
+    /// Innermost script frame at the last instrumented operation
+    pub(crate) location: Option<StackFrame>,
+}
+
+impl PurityCheck {
+    /// Protect `root` and every object reachable from it that isn't protected yet
+    fn protect(&mut self, root: &Gc<JsObject>, kind: PurityViolationKind, module: Option<Rc<str>>) {
+        let mut queue: VecDeque<(Gc<JsObject>, String)> = VecDeque::new();
+        queue.push_back((root.cheap_clone(), String::new()));
+
+        while let Some((obj, path)) = queue.pop_front() {
+            if self.protected.contains_key(&obj.id()) {
+                continue;
+            }
+            self.guard.guard(obj.cheap_clone());
+
+            let mut children: Vec<(Gc<JsObject>, String)> = Vec::new();
+            {
+                let obj_ref = obj.borrow();
+                for (key, prop) in obj_ref.properties.iter() {
+                    let value = match prop.getter() {
+                        Some(getter) => export_value(getter),
+                        None if prop.is_accessor() => None,
+                        None => Some(prop.value.clone()),
+                    };
+                    if let Some(JsValue::Object(child)) = value {
+                        children.push((child, member_path(&path, key)));
+                    }
+                }
+                if let Some(env) = obj_ref.as_environment() {
+                    for (name, binding) in env.bindings.iter() {
+                        if let JsValue::Object(child) = &binding.value {
+                            let key = PropertyKey::String(name.0.cheap_clone());
+                            children.push((child.cheap_clone(), member_path(&path, &key)));
+                        }
+                    }
+                }
+                if let Some(elements) = obj_ref.array_elements() {
+                    for (i, element) in elements.iter().enumerate() {
+                        if let JsValue::Object(child) = element {
+                            children.push((child.cheap_clone(), format!("{}[{}]", path, i)));
+                        }
+                    }
+                }
+                if let Some(proto) = &obj_ref.prototype {
+                    let name = if path.is_empty() { "globalThis" } else { &path };
+                    children.push((proto.cheap_clone(), format!("{}.__proto__", name)));
+                }
+            }
+
+            self.protected.insert(
+                obj.id(),
+                Origin {
+                    kind,
+                    module: module.clone(),
+                    path,
+                },
+            );
+            queue.extend(children);
+        }
+    }
+}
+
+/// Value behind a module namespace getter, read without running any code
+fn export_value(getter: &Gc<JsObject>) -> Option<JsValue> {
+    let getter_ref = getter.borrow();
+    match &getter_ref.exotic {
+        ExoticObject::Function(JsFunction::ModuleExportGetter {
+            module_env,
+            binding_name,
+        }) => module_env
+            .borrow()
+            .as_environment()
+            .and_then(|env| env.bindings.get(&VarKey(binding_name.cheap_clone())))
+            .filter(|binding| binding.import_binding.is_none())
+            .map(|binding| binding.value.clone()),
+        ExoticObject::Function(JsFunction::ModuleReExportGetter {
+            source_module,
+            source_key,
+        }) => {
+            let source = source_module.borrow();
+            let prop = source.properties.get(source_key)?;
+            match prop.getter() {
+                Some(inner) => export_value(inner),
+                None => Some(prop.value.clone()),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// `base.key`, `base[0]` or `base[Symbol.iterator]`; a bare name for top-level keys
+pub(crate) fn member_path(base: &str, key: &PropertyKey) -> String {
+    match key {
+        PropertyKey::String(name) => {
+            let name = name.as_str();
+            let is_identifier = name
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+                && name
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+            match (base.is_empty(), is_identifier) {
+                (true, _) => name.to_string(),
+                (false, true) => format!("{}.{}", base, name),
+                (false, false) => format!("{}[{:?}]", base, name),
+            }
+        }
+        PropertyKey::Index(i) => format!("{}[{}]", base, i),
+        PropertyKey::Symbol(symbol) => {
+            let desc = symbol.description.as_ref().map_or("", |d| d.as_str());
+            format!("{}[{}]", base, desc)
+        }
+    }
+}
+
+impl Interpreter {
+    /// Evaluate a module while checking that it has no side effects.
+    ///
+    /// Every object the module did not create is write-protected: the global
+    /// object and the intrinsics reachable from it, and everything reachable
+    /// from the namespaces the module imports. Setting, defining or deleting
+    /// a property of one of them, changing its prototype, or mutating it
+    /// through a builtin such as `Array.prototype.push` or `Map.prototype.set`
+    /// is a violation, and so is issuing an order.
+    ///
+    /// With `PurityMode::Raise` the first violation stops evaluation. An order
+    /// stops it in either mode, since the host is never asked to fulfill it.
+    /// Only internal and shared modules can be imported.
+    ///
+    /// Returns the violations in the order they happened, so an empty list
+    /// means the module is pure. Errors thrown by the module are returned as
+    /// they would be from `eval`.
+    pub fn eval_pure(
+        &mut self,
+        source: &str,
+        module_path: Option<crate::ModulePath>,
+        mode: PurityMode,
+    ) -> Result<Vec<PurityViolation>, JsError> {
+        if self.purity.is_some() {
+            return Err(JsError::internal_error("eval_pure is already running"));
+        }
+
+        let guard = self.heap.create_guard();
+        guard.set_label("purity check");
+        let mut check = PurityCheck {
+            mode,
+            protected: FxHashMap::default(),
+            guard,
+            violations: Vec::new(),
+            location: None,
+        };
+        // Lazy builtins built later would escape protection
+        self.materialize_builtins();
+        // Builtins are bindings of the global environment, not only global properties
+        let global_env = self.global_env.cheap_clone();
+        check.protect(&global_env, PurityViolationKind::GlobalWrite, None);
+        let global = self.global.cheap_clone();
+        check.protect(&global, PurityViolationKind::GlobalWrite, None);
+        self.purity = Some(Box::new(check));
+
+        let result = self.eval(source, module_path);
+        let violations = self
+            .purity
+            .take()
+            .map(|check| check.violations)
+            .unwrap_or_default();
+
+        match result {
+            Ok(StepResult::Complete(_)) => Ok(violations),
+            Err(JsError::Terminated {
+                reason: crate::TerminationReason::PurityViolation,
+            }) => Ok(violations),
+            Ok(StepResult::NeedImports(requests)) => {
+                self.pending_program = None;
+                self.clear_import_requests();
+                let specifier = requests
+                    .first()
+                    .map(|req| req.specifier.clone())
+                    .unwrap_or_default();
+                Err(JsError::module_error(format!(
+                    "eval_pure can only import internal modules, but '{}' is not one",
+                    specifier
+                )))
+            }
+            Ok(_) => Err(JsError::internal_error(
+                "eval_pure module did not complete without the host",
+            )),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Protect an imported namespace and the objects reachable from it
+    pub(crate) fn protect_import(&mut self, specifier: &str, namespace: &Gc<JsObject>) {
+        if let Some(check) = self.purity.as_mut() {
+            check.protect(
+                namespace,
+                PurityViolationKind::ImportWrite,
+                Some(Rc::from(specifier)),
+            );
+        }
+    }
+
+    /// Check a write to `obj` against a running `eval_pure`.
+    ///
+    /// `key` is the property being written, or `None` when the object as a
+    /// whole changes (its prototype, array elements or collection entries).
+    #[inline]
+    pub(crate) fn check_write(
+        &mut self,
+        obj: &Gc<JsObject>,
+        key: Option<&PropertyKey>,
+    ) -> Result<(), JsError> {
+        match &self.purity {
+            Some(check) if check.protected.contains_key(&obj.id()) => {
+                self.record_write_violation(obj, key)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    #[cold]
+    fn record_write_violation(
+        &mut self,
+        obj: &Gc<JsObject>,
+        key: Option<&PropertyKey>,
+    ) -> Result<(), JsError> {
+        let Some(check) = self.purity.as_mut() else {
+            return Ok(());
+        };
+        let Some(origin) = check.protected.get(&obj.id()) else {
+            return Ok(());
+        };
+        let target = match key {
+            Some(key) => member_path(&origin.path, key),
+            None if !origin.path.is_empty() => origin.path.clone(),
+            None => match &origin.module {
+                Some(module) => module.to_string(),
+                None => "globalThis".to_string(),
+            },
+        };
+        let violation = PurityViolation {
+            kind: origin.kind,
+            target,
+            module: origin.module.as_deref().map(String::from),
+            location: check.location.clone(),
+        };
+        check.violations.push(violation);
+        match check.mode {
+            PurityMode::Record => Ok(()),
+            PurityMode::Raise => Err(purity_terminated()),
+        }
+    }
+
+    /// Check an order against a running `eval_pure`; orders always stop it
+    pub(crate) fn check_order(&mut self) -> Result<(), JsError> {
+        let Some(check) = self.purity.as_mut() else {
+            return Ok(());
+        };
+        check.violations.push(PurityViolation {
+            kind: PurityViolationKind::Order,
+            target: "order".to_string(),
+            module: None,
+            location: check.location.clone(),
+        });
+        Err(purity_terminated())
+    }
+}
+
+fn purity_terminated() -> JsError {
+    JsError::Terminated {
+        reason: crate::TerminationReason::PurityViolation,
+    }
+}
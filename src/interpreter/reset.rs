@@ -0,0 +1,212 @@
+//! Reusing one interpreter for unrelated runs.
+//!
+//! [`Interpreter::reset`] drops what a run left behind: loaded modules, orders,
+//! the main module and the globals scripts defined or replaced. The global
+//! object and global scope are put back from a baseline recorded when the
+//! first run after creation (or after the previous reset) was prepared, so
+//! globals the host set up before that survive. Internal modules marked with
+//! [`InternalModule::retain_across_reset`](crate::InternalModule::retain_across_reset)
+//! keep their evaluated namespace, rooted by a guard that resets leave alone.
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use super::provenance::ProvenanceTable;
+use crate::gc::Gc;
+use crate::value::{
+    Binding, CheapClone, ExoticObject, JsObject, JsValue, Property, PropertyKey, VarKey,
+};
+
+/// The global object and global scope as a run found them
+pub(crate) struct GlobalBaseline {
+    properties: Vec<(PropertyKey, Property)>,
+    bindings: FxHashMap<VarKey, Binding>,
+    extensible: bool,
+    sealed: bool,
+    frozen: bool,
+    /// Recorded objects added to the root guard, since scripts may overwrite
+    /// them. Rooting them there rather than on a guard of their own keeps
+    /// `gc_retainers` crediting builtins to the root guard.
+    rooted: Vec<Gc<JsObject>>,
+}
+
+/// Objects a global property holds: its value and accessors
+fn property_objects(prop: &Property) -> impl Iterator<Item = Gc<JsObject>> + '_ {
+    let value = match &prop.value {
+        JsValue::Object(obj) => Some(obj.cheap_clone()),
+        _ => None,
+    };
+    value.into_iter().chain(
+        prop.getter()
+            .into_iter()
+            .chain(prop.setter())
+            .map(|accessor| accessor.cheap_clone()),
+    )
+}
+
+impl Interpreter {
+    /// Record the globals a run starts from, unless a run since the last
+    /// reset already did
+    pub(crate) fn record_global_baseline(&mut self) {
+        if self.global_baseline.is_some() {
+            return;
+        }
+        let global = self.global.borrow();
+        let mut baseline = GlobalBaseline {
+            properties: global
+                .properties
+                .iter()
+                .map(|(key, prop)| (key.clone(), prop.clone()))
+                .collect(),
+            bindings: match &self.global_env.borrow().exotic {
+                ExoticObject::Environment(env) => env.bindings.clone(),
+                _ => FxHashMap::default(),
+            },
+            extensible: global.extensible,
+            sealed: global.sealed,
+            frozen: global.frozen,
+            rooted: Vec::new(),
+        };
+        drop(global);
+        baseline.rooted = baseline
+            .properties
+            .iter()
+            .flat_map(|(_, prop)| property_objects(prop))
+            .collect();
+        for binding in baseline.bindings.values() {
+            if let JsValue::Object(obj) = &binding.value {
+                baseline.rooted.push(obj.cheap_clone());
+            }
+        }
+        for obj in &baseline.rooted {
+            self.root_guard.guard(obj.cheap_clone());
+        }
+        self.global_baseline = Some(baseline);
+    }
+
+    /// Record builtins built after the baseline as part of it
+    pub(crate) fn update_global_baseline(&mut self, keys: &[PropertyKey]) {
+        let Some(baseline) = self.global_baseline.as_mut() else {
+            return;
+        };
+        for key in keys {
+            let Some(prop) = self.global.borrow().properties.get(key).cloned() else {
+                continue;
+            };
+            for obj in property_objects(&prop) {
+                self.root_guard.guard(obj.cheap_clone());
+                baseline.rooted.push(obj);
+            }
+            match baseline.properties.iter_mut().find(|(k, _)| k == key) {
+                Some((_, slot)) => *slot = prop,
+                None => baseline.properties.push((key.clone(), prop)),
+            }
+        }
+    }
+
+    /// Forget the current run so the interpreter can start an unrelated one.
+    ///
+    /// Abandons a run in progress and drops loaded modules, outstanding
+    /// orders and import requests, and the main module, so the next
+    /// `prepare` starts like the first. Global properties and global scope
+    /// bindings go back to how they were when the first run since creation
+    /// (or the previous reset) was prepared: globals scripts added are
+    /// removed, and ones they replaced are restored.
+    ///
+    /// Configuration, registered internal modules, host classes and builtins
+    /// are kept. So are the namespaces of internal modules marked with
+    /// [`InternalModule::retain_across_reset`](crate::InternalModule::retain_across_reset),
+    /// which are not evaluated again. Changes scripts made to builtin objects,
+    /// such as a method added to `Array.prototype`, are not undone.
+    pub fn reset(&mut self) {
+        // The run in progress
+        self.active_vm = None;
+        self.active_task = None;
+        self.active_module_path = None;
+        self.active_saved_env = None;
+        self.active_module_env = None;
+        self.active_context_eval = None;
+        self.retained_main_scope = None;
+        self.incremental = None;
+        self.driven_generator = None;
+        self.env = self.global_env.cheap_clone();
+        self.env_guards.clear();
+        self.call_stack.clear();
+        self.join_stack.clear();
+        self.wait_graph = super::WaitGraph::new();
+        self.thenable_jobs.clear();
+        self.finalization_jobs.clear();
+        self.deferred_completion = None;
+        self.held_completion = None;
+        self.suspended_for_order = None;
+        self.last_throw = None;
+
+        // Orders
+        self.pending_orders.clear();
+        self.order_responses.clear();
+        self.cancelled_orders.clear();
+        self.outstanding_orders.clear();
+        self.order_batch_depth = 0;
+        self.eager_order_promises.clear();
+        #[cfg(feature = "testing")]
+        self.timer_orders.clear();
+        self.last_fulfilled.clear();
+
+        // Modules, keeping the retained internal ones
+        self.pending_program = None;
+        self.pending_module_sources.clear();
+        self.clear_import_requests();
+        self.loaded_modules.clear();
+        self.module_errors.clear();
+        self.failed_module_namespaces.clear();
+        self.main_module_path = None;
+        self.current_module_path = None;
+        self.exports.clear();
+        self.internal_modules_loading.clear();
+        let internal_modules = &self.internal_modules;
+        let retained = |specifier: &String| {
+            internal_modules
+                .get(specifier)
+                .is_some_and(|module| module.retain_across_reset)
+        };
+        self.internal_module_cache
+            .retain(|specifier, _| retained(specifier));
+        self.internal_function_modules
+            .retain(|_, specifier| retained(specifier));
+        self.shared_module_objects.clear();
+        self.module_guard = self.heap.create_guard();
+        self.module_guard.set_label("module cache");
+
+        self.console_timers.clear();
+        self.console_counters.clear();
+        if self.provenance.is_some() {
+            let guard = self.heap.create_guard();
+            self.provenance = Some(Box::new(ProvenanceTable::new(guard)));
+        }
+
+        self.restore_global_baseline();
+    }
+
+    /// Put the global object and global scope back as the baseline recorded them
+    fn restore_global_baseline(&mut self) {
+        let Some(baseline) = self.global_baseline.take() else {
+            return;
+        };
+        for obj in &baseline.rooted {
+            self.root_guard.unguard(obj);
+        }
+        {
+            let mut global = self.global.borrow_mut();
+            global.properties.clear();
+            for (key, prop) in baseline.properties {
+                global.properties.insert(key, prop);
+            }
+            global.extensible = baseline.extensible;
+            global.sealed = baseline.sealed;
+            global.frozen = baseline.frozen;
+        }
+        if let ExoticObject::Environment(env) = &mut self.global_env.borrow_mut().exotic {
+            env.bindings = baseline.bindings;
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Isolated evaluation of snippets for `evalInSandbox` in `tsrun:host`.
+//!
+//! Each call runs the snippet in a fresh interpreter with no internal
+//! modules, so it can neither import anything nor create orders, and sees
+//! nothing of the caller: its globals are the builtins plus the bindings
+//! handed in, defined as constants holding copies made with structured
+//! clone rules and deep-frozen.
+//! `globalThis` is removed, so reaching for it is a `ReferenceError`. The
+//! completion value is copied back into the caller the same way.
+//!
+//! The snippet's fuel is capped by what the caller has left and charged to
+//! the caller afterwards, so a snippet can't extend its caller's budget.
+//! Exhausting the snippet's own `fuel` or `timeoutMs` throws a `RangeError`
+//! the caller can catch. Errors the snippet throws are rethrown in the
+//! caller with `line` and `column` properties locating them in the snippet.
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use super::builtins;
+use crate::error::JsError;
+use crate::gc::Gc;
+use crate::value::{CheapClone, Guarded, JsObject, JsValue, PropertyKey};
+use crate::{RuntimeValue, StepResult, TerminationReason};
+
+/// Instructions between clock reads while a snippet has a timeout
+const TIMEOUT_CHECK_INTERVAL: u32 = 256;
+
+/// Limits of one `evalInSandbox` call
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct SandboxLimits {
+    /// Instructions the snippet may run, capped by the caller's fuel
+    pub fuel: Option<u64>,
+    pub timeout_ms: Option<u64>,
+}
+
+/// Why a snippet produced no value
+enum SandboxFailure {
+    /// An error of the snippet's own making, rethrown as an error object
+    Script(JsError),
+    /// A limit or restriction of the sandbox, thrown as-is
+    Sandbox(JsError),
+}
+
+impl Interpreter {
+    /// Evaluate `code` in a fresh, isolated interpreter and copy its
+    /// completion value back
+    pub(crate) fn eval_in_sandbox(
+        &mut self,
+        code: &str,
+        bindings: Option<&Gc<JsObject>>,
+        limits: SandboxLimits,
+    ) -> Result<Guarded, JsError> {
+        let mut sandbox = Interpreter::new();
+        sandbox.internal_modules.clear();
+        let global_this = sandbox.property_key("globalThis");
+        sandbox.global.borrow_mut().properties.remove(&global_this);
+        if let Some(bindings) = bindings {
+            sandbox.define_sandbox_bindings(self, bindings)?;
+        }
+
+        let fuel = match (limits.fuel, self.fuel) {
+            (Some(own), Some(parent)) => Some(own.min(parent)),
+            (own, parent) => own.or(parent),
+        };
+        sandbox.set_fuel(fuel);
+        let outcome = self.run_sandbox(&mut sandbox, code, limits);
+        if let (Some(given), Some(parent)) = (fuel, self.fuel.as_mut()) {
+            let used = given.saturating_sub(sandbox.fuel.unwrap_or(0));
+            *parent = parent.saturating_sub(used);
+        }
+
+        match outcome {
+            Ok(value) => {
+                let copy = self.import_value(&sandbox, &value)?;
+                Ok(Guarded::from_value(copy.value().clone(), &self.heap))
+            }
+            Err(SandboxFailure::Sandbox(error)) => Err(error),
+            Err(SandboxFailure::Script(error)) => Err(self.rethrow_sandbox_error(&error)),
+        }
+    }
+
+    /// Copy the own properties of the caller's `bindings` in as constants
+    /// holding frozen values
+    fn define_sandbox_bindings(
+        &mut self,
+        caller: &Interpreter,
+        bindings: &Gc<JsObject>,
+    ) -> Result<(), JsError> {
+        let source = RuntimeValue::unguarded(JsValue::Object(bindings.cheap_clone()));
+        let copy = self.import_value(caller, &source)?;
+        builtins::object::deep_freeze(copy.value(), false)?;
+        let JsValue::Object(copy_obj) = copy.value() else {
+            return Ok(());
+        };
+        let copy_ref = copy_obj.borrow();
+        for key in copy_ref.own_keys() {
+            let PropertyKey::String(name) = &key else {
+                continue;
+            };
+            let value = copy_ref.get_property(&key).unwrap_or(JsValue::Undefined);
+            // The global environment keeps the copies alive once `copy` is dropped
+            self.env_define(name.cheap_clone(), value, false);
+        }
+        Ok(())
+    }
+
+    /// Step `sandbox` through `code` to its completion value
+    fn run_sandbox(
+        &mut self,
+        sandbox: &mut Interpreter,
+        code: &str,
+        limits: SandboxLimits,
+    ) -> Result<RuntimeValue, SandboxFailure> {
+        let deadline = limits
+            .timeout_ms
+            .map(|ms| self.time_provider.now_millis().saturating_add(ms as i64));
+        let mut result = sandbox.prepare(code, None);
+        let mut steps: u32 = 0;
+        loop {
+            match result {
+                Ok(StepResult::Continue) => {}
+                Ok(StepResult::Complete(value)) => return Ok(value),
+                Ok(StepResult::Done) => return Ok(RuntimeValue::unguarded(JsValue::Undefined)),
+                Ok(StepResult::NeedImports(_)) => {
+                    return Err(SandboxFailure::Sandbox(JsError::type_error(
+                        "evalInSandbox: snippets cannot import modules",
+                    )));
+                }
+                Ok(StepResult::Suspended { .. } | StepResult::Orders(_)) => {
+                    return Err(SandboxFailure::Sandbox(JsError::type_error(
+                        "evalInSandbox: the snippet awaited a promise that never settles",
+                    )));
+                }
+                Err(JsError::Terminated {
+                    reason: TerminationReason::FuelExhausted,
+                }) => {
+                    return Err(SandboxFailure::Sandbox(JsError::range_error(
+                        "evalInSandbox: fuel exhausted",
+                    )));
+                }
+                Err(error) if error.is_termination() => {
+                    return Err(SandboxFailure::Sandbox(error));
+                }
+                Err(error) => return Err(SandboxFailure::Script(error)),
+            }
+
+            // The caller's interrupt stops the snippet too
+            if self.interrupt.is_interrupted() {
+                return Err(SandboxFailure::Sandbox(JsError::Terminated {
+                    reason: TerminationReason::Interrupted,
+                }));
+            }
+            steps = steps.wrapping_add(1);
+            if let Some(deadline) = deadline
+                && steps.is_multiple_of(TIMEOUT_CHECK_INTERVAL)
+                && self.time_provider.now_millis() >= deadline
+            {
+                return Err(SandboxFailure::Sandbox(JsError::range_error(format!(
+                    "evalInSandbox: timed out after {}ms",
+                    limits.timeout_ms.unwrap_or(0)
+                ))));
+            }
+            result = sandbox.step();
+        }
+    }
+
+    /// The caller-side error object for an error the snippet threw, with the
+    /// snippet line and column it came from
+    fn rethrow_sandbox_error(&mut self, error: &JsError) -> JsError {
+        let position = match error {
+            JsError::RuntimeError { stack, .. } => {
+                stack.first().map(|frame| (frame.line, frame.column))
+            }
+            other => other
+                .location()
+                .map(|location| (location.line, location.column)),
+        };
+        let (value, guard) = builtins::error::create_error_object(self, error);
+        if let (JsValue::Object(obj), Some((line, column))) = (&value, position) {
+            let line_key = self.property_key("line");
+            let column_key = self.property_key("column");
+            let mut obj_ref = obj.borrow_mut();
+            obj_ref.set_property(line_key, JsValue::Number(line as f64));
+            obj_ref.set_property(column_key, JsValue::Number(column as f64));
+        }
+        JsError::thrown(Guarded { value, guard })
+    }
+}
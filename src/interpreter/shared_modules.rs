@@ -0,0 +1,420 @@
+//! Module namespaces evaluated once and shared by many interpreters.
+//!
+//! A [`SharedModuleSet`] runs each module in a throwaway donor interpreter and
+//! snapshots its exports as a plain value graph that holds no GC pointers, so
+//! the set is `Send + Sync` and can sit behind an `Arc` shared across threads.
+//! Interpreters configured with the set copy a module into their own heap on
+//! first import, as frozen objects, without parsing or compiling anything.
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use crate::error::JsError;
+use crate::gc::{Gc, Guard};
+use crate::value::{CheapClone, ExoticObject, JsObject, JsString, JsValue, Property, PropertyKey};
+
+/// An exported value with objects replaced by indices into [`SharedModuleSet::objects`]
+#[derive(Debug, Clone)]
+enum SharedValue {
+    Undefined,
+    Null,
+    Boolean(bool),
+    Number(f64),
+    String(Box<str>),
+    Object(usize),
+}
+
+/// A captured object; the index-based layout keeps shared references and cycles intact
+#[derive(Debug)]
+enum SharedObject {
+    Plain(Vec<(Box<str>, SharedValue)>),
+    Array(Vec<SharedValue>),
+}
+
+/// The captured exports of one module, in namespace order
+#[derive(Debug)]
+struct SharedModule {
+    specifier: String,
+    exports: Vec<(Box<str>, SharedValue)>,
+}
+
+/// Immutable module namespaces that any number of interpreters can import.
+///
+/// Exports may only contain primitives, plain objects and arrays, since
+/// anything else (functions, class instances, `Map`, `Date`, ...) would tie
+/// the value to the donor interpreter. Importers see deeply frozen copies.
+///
+/// Functions are not shared: they close over the donor's module environment,
+/// and compiled code is not `Send`. Keep them in an ordinary module (an
+/// [`InternalModule`](crate::InternalModule) or a file) that imports its data
+/// from the set, so only the functions are compiled per interpreter.
+///
+/// ```
+/// use std::sync::Arc;
+/// use tsrun::{Interpreter, InterpreterConfig, SharedModuleSet};
+///
+/// let shared = Arc::new(
+///     SharedModuleSet::build([("app:limits", "export const limits = { rps: 100 };")]).unwrap(),
+/// );
+/// let interp = Interpreter::with_config(InterpreterConfig {
+///     shared_modules: Some(shared.clone()),
+///     ..Default::default()
+/// });
+/// // Now code can: import { limits } from "app:limits";
+/// ```
+#[derive(Debug)]
+pub struct SharedModuleSet {
+    modules: Vec<SharedModule>,
+    objects: Vec<SharedObject>,
+}
+
+impl SharedModuleSet {
+    /// Evaluate `(specifier, source)` modules in order and capture their exports.
+    ///
+    /// Modules may import earlier modules in the list by specifier. Fails with
+    /// the module's own error if it throws, or with a `TypeError` naming the
+    /// export if a value can't be shared.
+    pub fn build<'a>(
+        modules: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<Self, JsError> {
+        let mut donor = Interpreter::new();
+        let mut set = Self {
+            modules: Vec::new(),
+            objects: Vec::new(),
+        };
+        // Donor object id -> index in `objects`, so shared references stay shared
+        let mut captured: FxHashMap<usize, usize> = FxHashMap::default();
+
+        let modules: Vec<_> = modules.into_iter().collect();
+        for (specifier, source) in &modules {
+            donor.register_internal_module(crate::InternalModule::source(*specifier, *source));
+        }
+        for (specifier, _) in modules {
+            // Thrown values point into the donor heap, which is about to go away
+            let namespace = donor
+                .resolve_internal_module(specifier)
+                .map_err(|e| donor.materialize_thrown_error(e))?
+                .ok_or_else(|| JsError::internal_error("Shared module was not registered"))?;
+
+            let entries: Vec<(PropertyKey, Property)> = namespace
+                .borrow()
+                .properties
+                .iter()
+                .map(|(key, prop)| (key.clone(), prop.clone()))
+                .collect();
+
+            let mut exports = Vec::with_capacity(entries.len());
+            for (key, prop) in entries {
+                let name = key.to_string();
+                let value = match prop.getter() {
+                    Some(getter) => {
+                        let getter = JsValue::Object(getter.cheap_clone());
+                        donor
+                            .call_function(getter, JsValue::Undefined, &[])
+                            .map_err(|e| donor.materialize_thrown_error(e))?
+                            .value
+                    }
+                    None => prop.value.clone(),
+                };
+                let location = format!("{}:{}", specifier, name);
+                let value = set.capture(&donor, &value, location, &mut captured)?;
+                exports.push((name.into_boxed_str(), value));
+            }
+
+            set.modules.push(SharedModule {
+                specifier: specifier.to_string(),
+                exports,
+            });
+        }
+
+        Ok(set)
+    }
+
+    /// Whether the set provides a module for `specifier`
+    pub fn contains(&self, specifier: &str) -> bool {
+        self.module(specifier).is_some()
+    }
+
+    /// Specifiers of the modules in the set, in build order
+    pub fn specifiers(&self) -> impl Iterator<Item = &str> {
+        self.modules.iter().map(|m| m.specifier.as_str())
+    }
+
+    fn module(&self, specifier: &str) -> Option<&SharedModule> {
+        self.modules.iter().find(|m| m.specifier == specifier)
+    }
+
+    /// Snapshot a donor value, rejecting anything that isn't plain data.
+    ///
+    /// Objects are captured from a worklist rather than by recursion, so
+    /// deeply nested exports can't overflow the Rust stack.
+    fn capture(
+        &mut self,
+        donor: &Interpreter,
+        value: &JsValue,
+        location: String,
+        captured: &mut FxHashMap<usize, usize>,
+    ) -> Result<SharedValue, JsError> {
+        let mut walk = CaptureWalk {
+            captured,
+            pending: Vec::new(),
+            paths: Vec::new(),
+        };
+        let root = self.capture_value(&mut walk, value, None, location)?;
+        while let Some((obj, index, path)) = walk.pending.pop() {
+            let object = self.capture_object(donor, &mut walk, &obj, path)?;
+            if let Some(slot) = self.objects.get_mut(index) {
+                *slot = object;
+            }
+        }
+        Ok(root)
+    }
+
+    /// Snapshot a primitive, or reserve a slot for an object and queue it
+    fn capture_value(
+        &mut self,
+        walk: &mut CaptureWalk<'_>,
+        value: &JsValue,
+        parent: Option<usize>,
+        segment: String,
+    ) -> Result<SharedValue, JsError> {
+        let obj = match value {
+            JsValue::Undefined => return Ok(SharedValue::Undefined),
+            JsValue::Null => return Ok(SharedValue::Null),
+            JsValue::Boolean(b) => return Ok(SharedValue::Boolean(*b)),
+            JsValue::Number(n) => return Ok(SharedValue::Number(*n)),
+            JsValue::String(s) => return Ok(SharedValue::String(s.as_str().into())),
+            JsValue::Symbol(_) => {
+                let path = walk.add_path(parent, segment);
+                return Err(unshareable(&walk.location(path), "a symbol"));
+            }
+            JsValue::Object(obj) => obj,
+        };
+
+        if let Some(&index) = walk.captured.get(&obj.id()) {
+            return Ok(SharedValue::Object(index));
+        }
+        let index = self.objects.len();
+        walk.captured.insert(obj.id(), index);
+        // Placeholder until the object is taken off the worklist
+        self.objects.push(SharedObject::Array(Vec::new()));
+        let path = walk.add_path(parent, segment);
+        walk.pending.push((obj.cheap_clone(), index, path));
+        Ok(SharedValue::Object(index))
+    }
+
+    /// Snapshot the contents of an array or plain object
+    fn capture_object(
+        &mut self,
+        donor: &Interpreter,
+        walk: &mut CaptureWalk<'_>,
+        obj: &Gc<JsObject>,
+        path: usize,
+    ) -> Result<SharedObject, JsError> {
+        let is_array = obj.borrow().array_elements().is_some();
+        if is_array {
+            let elements: Vec<JsValue> = obj
+                .borrow()
+                .array_elements()
+                .map(<[JsValue]>::to_vec)
+                .unwrap_or_default();
+            let mut shared = Vec::with_capacity(elements.len());
+            for (i, element) in elements.iter().enumerate() {
+                let segment = format!("[{}]", i);
+                shared.push(self.capture_value(walk, element, Some(path), segment)?);
+            }
+            return Ok(SharedObject::Array(shared));
+        }
+
+        let entries = {
+            let obj_ref = obj.borrow();
+            let is_plain = matches!(obj_ref.exotic, ExoticObject::Ordinary)
+                && match &obj_ref.prototype {
+                    Some(proto) => proto.id() == donor.object_prototype.id(),
+                    None => true,
+                };
+            if !is_plain {
+                let kind = if matches!(obj_ref.exotic, ExoticObject::Function(_)) {
+                    "a function"
+                } else {
+                    "an object that is not a plain object or array"
+                };
+                return Err(unshareable(&walk.location(path), kind));
+            }
+            let mut entries = Vec::with_capacity(obj_ref.properties.len());
+            for (key, prop) in obj_ref.properties.iter() {
+                if matches!(key, PropertyKey::Symbol(_)) {
+                    return Err(unshareable(&walk.location(path), "a symbol-keyed property"));
+                }
+                if prop.is_accessor() {
+                    return Err(unshareable(&walk.location(path), "an accessor property"));
+                }
+                entries.push((key.to_string(), prop.value.clone()));
+            }
+            entries
+        };
+        let mut shared = Vec::with_capacity(entries.len());
+        for (key, value) in entries {
+            let segment = format!(".{}", key);
+            let value = self.capture_value(walk, &value, Some(path), segment)?;
+            shared.push((key.into_boxed_str(), value));
+        }
+        Ok(SharedObject::Plain(shared))
+    }
+}
+
+/// State of one export's capture: objects still to snapshot, and where each
+/// queued object sits, kept as parent links so paths are only joined for errors
+struct CaptureWalk<'a> {
+    captured: &'a mut FxHashMap<usize, usize>,
+    pending: Vec<(Gc<JsObject>, usize, usize)>,
+    paths: Vec<(Option<usize>, String)>,
+}
+
+impl CaptureWalk<'_> {
+    fn add_path(&mut self, parent: Option<usize>, segment: String) -> usize {
+        self.paths.push((parent, segment));
+        self.paths.len() - 1
+    }
+
+    /// The export path of `path`, such as `app:config:limits.tiers[0]`
+    fn location(&self, path: usize) -> String {
+        let mut segments = Vec::new();
+        let mut next = Some(path);
+        while let Some((parent, segment)) = next.and_then(|path| self.paths.get(path)) {
+            segments.push(segment.as_str());
+            next = *parent;
+        }
+        segments.reverse();
+        segments.concat()
+    }
+}
+
+fn unshareable(location: &str, kind: &str) -> JsError {
+    JsError::type_error(format!(
+        "Shared module export '{}' is {}, which cannot be shared",
+        location, kind
+    ))
+}
+
+impl Interpreter {
+    /// Copy a shared module's namespace into this heap, if the set provides it.
+    ///
+    /// Objects reachable from several shared modules are copied once per
+    /// interpreter, so identity between modules is preserved.
+    pub(crate) fn attach_shared_module(&mut self, specifier: &str) -> Option<Gc<JsObject>> {
+        let shared = self.shared_modules.clone()?;
+        let module = shared.module(specifier)?;
+
+        let guard = self.heap.create_guard();
+        let namespace = self.create_object(&guard);
+        for (name, value) in &module.exports {
+            let value = self.attach_shared_value(&guard, &shared, value);
+            let key = PropertyKey::String(self.intern(name));
+            namespace
+                .borrow_mut()
+                .properties
+                .insert(key, Property::with_attributes(value, false, true, false));
+        }
+        self.seal_module_namespace(&namespace);
+        freeze(&namespace);
+
+        self.module_guard.guard(namespace.cheap_clone());
+        Some(namespace)
+    }
+
+    /// Copy a shared value into this heap, filling objects from a worklist
+    /// so deeply nested values can't overflow the Rust stack
+    fn attach_shared_value(
+        &mut self,
+        guard: &Guard<JsObject>,
+        shared: &SharedModuleSet,
+        value: &SharedValue,
+    ) -> JsValue {
+        let mut pending = Vec::new();
+        let root = self.attach_shared_slot(guard, shared, value, &mut pending);
+        while let Some(index) = pending.pop() {
+            let (Some(Some(obj)), Some(object)) = (
+                self.shared_module_objects.get(index).cloned(),
+                shared.objects.get(index),
+            ) else {
+                continue;
+            };
+            match object {
+                SharedObject::Array(elements) => {
+                    let values: Vec<JsValue> = elements
+                        .iter()
+                        .map(|element| {
+                            self.attach_shared_slot(guard, shared, element, &mut pending)
+                        })
+                        .collect();
+                    if let Some(slot) = obj.borrow_mut().array_elements_mut() {
+                        *slot = values;
+                    }
+                }
+                SharedObject::Plain(entries) => {
+                    for (key, value) in entries {
+                        let value = self.attach_shared_slot(guard, shared, value, &mut pending);
+                        let key = self.property_key(key);
+                        obj.borrow_mut()
+                            .properties
+                            .insert(key, Property::with_attributes(value, false, true, false));
+                    }
+                }
+            }
+            freeze(&obj);
+        }
+        root
+    }
+
+    /// Convert a primitive, or find the copy of a shared object, creating it
+    /// empty and queueing its contents the first time it is seen
+    fn attach_shared_slot(
+        &mut self,
+        guard: &Guard<JsObject>,
+        shared: &SharedModuleSet,
+        value: &SharedValue,
+        pending: &mut Vec<usize>,
+    ) -> JsValue {
+        let index = match value {
+            SharedValue::Undefined => return JsValue::Undefined,
+            SharedValue::Null => return JsValue::Null,
+            SharedValue::Boolean(b) => return JsValue::Boolean(*b),
+            SharedValue::Number(n) => return JsValue::Number(*n),
+            SharedValue::String(s) => return JsValue::String(JsString::from(&**s)),
+            SharedValue::Object(index) => *index,
+        };
+
+        if let Some(Some(obj)) = self.shared_module_objects.get(index) {
+            return JsValue::Object(obj.cheap_clone());
+        }
+        let Some(object) = shared.objects.get(index) else {
+            return JsValue::Undefined;
+        };
+
+        let obj = match object {
+            SharedObject::Array(_) => self.create_array_from(guard, Vec::new()),
+            SharedObject::Plain(_) => self.create_object(guard),
+        };
+        if self.shared_module_objects.len() <= index {
+            self.shared_module_objects.resize(index + 1, None);
+        }
+        if let Some(slot) = self.shared_module_objects.get_mut(index) {
+            *slot = Some(obj.cheap_clone());
+        }
+        pending.push(index);
+
+        JsValue::Object(obj)
+    }
+}
+
+/// Mark an attached object frozen, as `Object.freeze` would
+fn freeze(obj: &Gc<JsObject>) {
+    let mut obj_mut = obj.borrow_mut();
+    obj_mut.frozen = true;
+    obj_mut.extensible = false;
+    for (_, prop) in obj_mut.properties.iter_mut() {
+        prop.set_writable(false);
+        prop.set_configurable(false);
+    }
+}
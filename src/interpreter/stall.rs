@@ -0,0 +1,90 @@
+//! Detection of hosts stepping a script that can no longer make progress.
+//!
+//! Fulfilling an order under the wrong id, or resolving a promise nothing
+//! awaits, leaves the script suspended for good: every `step()` returns an
+//! empty `Suspended` and a host stepping in a loop spins. After
+//! [`crate::InterpreterConfig::stall_threshold`] such suspensions in a row,
+//! with no instruction executed and no order fulfilled in between, `step()`
+//! fails with [`JsError::Stalled`] describing what the script still waits on.
+//!
+//! A host that waits on purpose, for a long poll or a timer, steps again only
+//! once it has something to deliver, so it never sees consecutive idle
+//! suspensions.
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use crate::error::JsError;
+use crate::{OrderId, PendingPromiseInfo, StepResult};
+
+/// What a stalled script was waiting on, carried by [`JsError::Stalled`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StallReport {
+    /// Orders created by the script that are neither fulfilled nor cancelled
+    pub outstanding: Vec<OrderId>,
+    /// The order the suspended script is blocked on inside `order()`
+    pub blocked_on_order: Option<OrderId>,
+    /// The promises suspended async contexts are awaiting, oldest context first
+    pub awaited: Vec<PendingPromiseInfo>,
+    /// The ids passed to the latest `fulfill_orders` call, including any
+    /// that matched no outstanding order
+    pub last_fulfilled: Vec<OrderId>,
+}
+
+impl fmt::Display for StallReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "outstanding orders [{}]", join_ids(&self.outstanding))?;
+        if let Some(id) = self.blocked_on_order {
+            write!(f, ", blocked on order {}", id.0)?;
+        }
+        write!(f, ", {} awaited promise(s)", self.awaited.len())?;
+        write!(f, ", last fulfilled [{}]", join_ids(&self.last_fulfilled))
+    }
+}
+
+fn join_ids(ids: &[OrderId]) -> String {
+    ids.iter()
+        .map(|id| id.0.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Interpreter {
+    /// Count `result` towards a stall and fail once there are
+    /// `stall_threshold` idle suspensions in a row
+    pub(crate) fn check_stall(&mut self, result: &StepResult) -> Result<(), JsError> {
+        let StepResult::Suspended { pending, cancelled } = result else {
+            return Ok(());
+        };
+        let progressed = mem::take(&mut self.stall_progress);
+        if progressed || !pending.is_empty() || !cancelled.is_empty() {
+            self.idle_suspensions = 0;
+            return Ok(());
+        }
+        self.idle_suspensions += 1;
+        if self.idle_suspensions < self.stall_threshold {
+            return Ok(());
+        }
+        self.idle_suspensions = 0;
+        Err(JsError::Stalled {
+            report: Box::new(self.stall_report()),
+        })
+    }
+
+    fn stall_report(&self) -> StallReport {
+        let mut contexts: Vec<_> = self.wait_graph.contexts.values().collect();
+        contexts.sort_by_key(|ctx| ctx.id.0);
+        StallReport {
+            outstanding: self.outstanding_orders.clone(),
+            blocked_on_order: self
+                .suspended_for_order
+                .as_ref()
+                .map(|suspension| suspension.order_id),
+            awaited: contexts
+                .iter()
+                .map(|ctx| self.promise_info(&ctx.waiting_on))
+                .collect(),
+            last_fulfilled: self.last_fulfilled.clone(),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Did-you-mean suggestions for misspelled names.
+//!
+//! A name is compared with each candidate by edit distance (Levenshtein,
+//! counted in characters), and the closest candidate within
+//! [`MAX_DISTANCE`] edits is suggested, the earliest winning a tie. Only the
+//! first [`MAX_CANDIDATES`] candidates are compared, so a lookup on an
+//! object with a huge key list stays cheap.
+
+use crate::prelude::*;
+
+/// Most edits between a name and the suggestion for it
+pub(crate) const MAX_DISTANCE: usize = 2;
+
+/// Most candidates compared for one suggestion
+pub(crate) const MAX_CANDIDATES: usize = 1000;
+
+/// The candidate `name` is most likely a misspelling of, if any is close.
+///
+/// A candidate is never suggested for a name shorter than the edits it
+/// takes, so `"a"` does not suggest `"bc"`.
+pub(crate) fn did_you_mean<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let name: Vec<char> = name.chars().collect();
+    let mut best: Option<(usize, &'a str)> = None;
+    let mut candidate_chars = Vec::new();
+    for candidate in candidates.into_iter().take(MAX_CANDIDATES) {
+        candidate_chars.clear();
+        candidate_chars.extend(candidate.chars());
+        if candidate_chars.len().abs_diff(name.len()) > MAX_DISTANCE {
+            continue;
+        }
+        let distance = edit_distance(&name, &candidate_chars);
+        if distance == 0 || distance > MAX_DISTANCE || distance >= name.len() {
+            continue;
+        }
+        if best.is_none_or(|(closest, _)| distance < closest) {
+            best = Some((distance, candidate));
+        }
+    }
+    best.map(|(_, candidate)| candidate)
+}
+
+/// Single-character insertions, deletions and substitutions turning `a` into `b`
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    // Distances from a prefix of `a` to each prefix of `b`, one row at a time
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut row = Vec::with_capacity(b.len() + 1);
+    for (i, ca) in a.iter().enumerate() {
+        row.clear();
+        row.push(i + 1);
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = previous
+                .get(j)
+                .map_or(usize::MAX, |d| d + usize::from(ca != cb));
+            let insert = row.get(j).map_or(usize::MAX, |d| d + 1);
+            let delete = previous.get(j + 1).map_or(usize::MAX, |d| d + 1);
+            row.push(substitute.min(insert).min(delete));
+        }
+        mem::swap(&mut previous, &mut row);
+    }
+    previous.last().copied().unwrap_or(0)
+}
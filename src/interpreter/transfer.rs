@@ -0,0 +1,318 @@
+//! Deep copies of values from one interpreter into another.
+//!
+//! [`Interpreter::import_value`] follows structured clone rules: plain
+//! objects, arrays, Map, Set, Date, RegExp, primitive wrappers and errors are
+//! copied with their own enumerable properties, shared references and cycles
+//! keep their shape, and functions, proxies, symbols and other values tied to
+//! the source are rejected. Every object of the copy is allocated in the
+//! destination heap, and strings are copied too, so the result stays valid
+//! after the source interpreter is dropped.
+
+use crate::prelude::*;
+
+use super::Interpreter;
+use crate::RuntimeValue;
+use crate::error::JsError;
+use crate::gc::{Gc, Guard};
+use crate::value::{
+    CheapClone, ExoticObject, JsMapKey, JsObject, JsString, JsValue, Property, PropertyKey,
+};
+
+impl Interpreter {
+    /// Deep-copy `value`, owned by `source`, into this interpreter.
+    ///
+    /// The copy is allocated under a single guard held by the returned value
+    /// and shares nothing with `source`, which may be dropped right after.
+    /// Values the structured clone algorithm cannot copy fail with a
+    /// TypeError, as do accessor properties, whose getters would have to run
+    /// in `source`. Objects keep no prototype beyond the built-in one for
+    /// their kind; errors keep their error type, `cause` and AggregateError
+    /// `errors`.
+    pub fn import_value(
+        &mut self,
+        source: &Interpreter,
+        value: &RuntimeValue,
+    ) -> Result<RuntimeValue, JsError> {
+        let guard = self.heap.create_guard();
+        let error_prototypes = [
+            (&source.error_prototype, self.error_prototype.cheap_clone()),
+            (
+                &source.type_error_prototype,
+                self.type_error_prototype.cheap_clone(),
+            ),
+            (
+                &source.reference_error_prototype,
+                self.reference_error_prototype.cheap_clone(),
+            ),
+            (
+                &source.range_error_prototype,
+                self.range_error_prototype.cheap_clone(),
+            ),
+            (
+                &source.syntax_error_prototype,
+                self.syntax_error_prototype.cheap_clone(),
+            ),
+            (
+                &source.eval_error_prototype,
+                self.eval_error_prototype.cheap_clone(),
+            ),
+            (
+                &source.uri_error_prototype,
+                self.uri_error_prototype.cheap_clone(),
+            ),
+            (
+                &source.aggregate_error_prototype,
+                self.aggregate_error_prototype.cheap_clone(),
+            ),
+            (
+                &source.suppressed_error_prototype,
+                self.suppressed_error_prototype.cheap_clone(),
+            ),
+        ]
+        .into_iter()
+        .map(|(from, to)| (from.id(), to))
+        .collect();
+
+        let mut transfer = Transfer {
+            dest: self,
+            guard: &guard,
+            error_prototypes,
+            copies: FxHashMap::default(),
+            pending: Vec::new(),
+        };
+        let copy = transfer.value(value.value())?;
+        // A worklist rather than recursion, so deep nesting cannot exhaust the stack
+        while let Some((from, to)) = transfer.pending.pop() {
+            transfer.fill(&from, &to)?;
+        }
+        Ok(RuntimeValue::with_guard(copy, guard))
+    }
+}
+
+struct Transfer<'a> {
+    dest: &'a mut Interpreter,
+    guard: &'a Guard<JsObject>,
+    /// Source error prototype ids and the destination prototype each maps to
+    error_prototypes: FxHashMap<usize, Gc<JsObject>>,
+    /// Source object id to its copy, so shared references and cycles survive
+    copies: FxHashMap<usize, Gc<JsObject>>,
+    /// Copies created but not yet filled in, with their source object
+    pending: Vec<(Gc<JsObject>, Gc<JsObject>)>,
+}
+
+impl Transfer<'_> {
+    fn value(&mut self, value: &JsValue) -> Result<JsValue, JsError> {
+        Ok(match value {
+            JsValue::Undefined => JsValue::Undefined,
+            JsValue::Null => JsValue::Null,
+            JsValue::Boolean(b) => JsValue::Boolean(*b),
+            JsValue::Number(n) => JsValue::Number(*n),
+            JsValue::String(s) => JsValue::String(JsString::from(s.as_str())),
+            JsValue::Symbol(_) => return Err(not_transferable("Symbol")),
+            JsValue::Object(obj) => JsValue::Object(self.object(obj)?),
+        })
+    }
+
+    /// The copy of `obj`, created empty on first sight and queued to be filled
+    fn object(&mut self, obj: &Gc<JsObject>) -> Result<Gc<JsObject>, JsError> {
+        if let Some(copy) = self.copies.get(&obj.id()) {
+            return Ok(copy.cheap_clone());
+        }
+
+        let dest = &mut *self.dest;
+        // Their prototypes are filled in when the builtin is built
+        let builtin = match &obj.borrow().exotic {
+            ExoticObject::Map { .. } => Some("Map"),
+            ExoticObject::Set { .. } => Some("Set"),
+            ExoticObject::Date { .. } => Some("Date"),
+            _ => None,
+        };
+        if let Some(name) = builtin {
+            dest.install_builtin(name);
+        }
+        let (prototype, exotic) = match &obj.borrow().exotic {
+            ExoticObject::Ordinary => {
+                let error_prototype = obj
+                    .borrow()
+                    .prototype
+                    .as_ref()
+                    .and_then(|proto| self.error_prototypes.get(&proto.id()))
+                    .map(|proto| proto.cheap_clone());
+                (
+                    Some(error_prototype.unwrap_or_else(|| dest.object_prototype.cheap_clone())),
+                    ExoticObject::Ordinary,
+                )
+            }
+            // Elements and entries are copied by `fill`
+            ExoticObject::Array { .. } => (
+                Some(dest.array_prototype.cheap_clone()),
+                ExoticObject::Array {
+                    elements: Vec::new(),
+                },
+            ),
+            ExoticObject::Map { .. } => (
+                Some(dest.map_prototype.cheap_clone()),
+                ExoticObject::Map {
+                    entries: Default::default(),
+                },
+            ),
+            ExoticObject::Set { .. } => (
+                Some(dest.set_prototype.cheap_clone()),
+                ExoticObject::Set {
+                    entries: Default::default(),
+                },
+            ),
+            ExoticObject::Date { timestamp } => (
+                Some(dest.date_prototype.cheap_clone()),
+                ExoticObject::Date {
+                    timestamp: *timestamp,
+                },
+            ),
+            ExoticObject::RegExp { pattern, flags, .. } => (
+                Some(dest.regexp_prototype.cheap_clone()),
+                ExoticObject::RegExp {
+                    pattern: pattern.clone(),
+                    flags: flags.clone(),
+                    compiled: None,
+                },
+            ),
+            ExoticObject::Boolean(b) => (
+                Some(dest.boolean_prototype.cheap_clone()),
+                ExoticObject::Boolean(*b),
+            ),
+            ExoticObject::Number(n) => (
+                Some(dest.number_prototype.cheap_clone()),
+                ExoticObject::Number(*n),
+            ),
+            ExoticObject::StringObj(s) => (
+                Some(dest.string_prototype.cheap_clone()),
+                ExoticObject::StringObj(JsString::from(s.as_str())),
+            ),
+            ExoticObject::RawJSON(raw) => {
+                (None, ExoticObject::RawJSON(JsString::from(raw.as_str())))
+            }
+            ExoticObject::Function(_) => return Err(not_transferable("Function")),
+            ExoticObject::Proxy(_) => return Err(not_transferable("Proxy")),
+            ExoticObject::Symbol(_) => return Err(not_transferable("Symbol")),
+            ExoticObject::Generator(_) | ExoticObject::BytecodeGenerator(_) => {
+                return Err(not_transferable("Generator"));
+            }
+            ExoticObject::Promise(_) => return Err(not_transferable("Promise")),
+            ExoticObject::Environment(_) => return Err(not_transferable("Environment")),
+            ExoticObject::Enum(_) => return Err(not_transferable("Enum")),
+            ExoticObject::Url(_) | ExoticObject::UrlSearchParams(_) => {
+                return Err(not_transferable("URL object"));
+            }
+            ExoticObject::WeakRef(_) | ExoticObject::FinalizationRegistry(_) => {
+                return Err(not_transferable("Weak reference"));
+            }
+            ExoticObject::DisposableStack(_) => return Err(not_transferable("DisposableStack")),
+            ExoticObject::PendingOrder { .. } => return Err(not_transferable("PendingOrder")),
+            ExoticObject::Host(_) => return Err(not_transferable("Host object")),
+        };
+
+        let copy = dest.create_object(self.guard);
+        {
+            let mut copy_ref = copy.borrow_mut();
+            copy_ref.null_prototype = prototype.is_none();
+            copy_ref.prototype = prototype;
+            copy_ref.exotic = exotic;
+        }
+        self.copies.insert(obj.id(), copy.cheap_clone());
+        self.pending.push((obj.cheap_clone(), copy.cheap_clone()));
+        Ok(copy)
+    }
+
+    /// Copy the properties, elements and entries of `from` into `to`
+    fn fill(&mut self, from: &Gc<JsObject>, to: &Gc<JsObject>) -> Result<(), JsError> {
+        let (properties, elements, entries) = {
+            let from_ref = from.borrow();
+            // Errors also keep their non-enumerable `cause` and AggregateError `errors`
+            let is_error = from_ref
+                .prototype
+                .as_ref()
+                .is_some_and(|proto| self.error_prototypes.contains_key(&proto.id()));
+            let mut properties = Vec::new();
+            for (key, prop) in from_ref.properties.iter() {
+                let error_field = is_error
+                    && matches!(key, PropertyKey::String(name)
+                        if name.as_str() == "cause" || name.as_str() == "errors");
+                if !(prop.enumerable() || error_field) || matches!(key, PropertyKey::Symbol(_)) {
+                    continue;
+                }
+                if prop.is_accessor() {
+                    return Err(JsError::type_error(format!(
+                        "Accessor property '{}' cannot be transferred between interpreters",
+                        key
+                    )));
+                }
+                properties.push((key.clone(), prop.value.clone(), prop.enumerable()));
+            }
+            let (elements, entries) = match &from_ref.exotic {
+                ExoticObject::Array { elements } => (elements.clone(), Vec::new()),
+                ExoticObject::Map { entries } => (
+                    Vec::new(),
+                    entries
+                        .iter()
+                        .map(|(key, value)| (key.0.clone(), Some(value.clone())))
+                        .collect(),
+                ),
+                ExoticObject::Set { entries } => (
+                    Vec::new(),
+                    entries.iter().map(|key| (key.0.clone(), None)).collect(),
+                ),
+                _ => (Vec::new(), Vec::new()),
+            };
+            (properties, elements, entries)
+        };
+
+        for (key, value, enumerable) in properties {
+            let key = match key {
+                PropertyKey::String(name) => self.dest.property_key(name.as_str()),
+                key => key,
+            };
+            let value = self.value(&value)?;
+            to.borrow_mut().define_property(
+                key,
+                Property::with_attributes(value, true, enumerable, true),
+            );
+        }
+
+        let mut copied_elements = Vec::with_capacity(elements.len());
+        for element in &elements {
+            copied_elements.push(self.value(element)?);
+        }
+        let mut copied_entries = Vec::with_capacity(entries.len());
+        for (key, value) in &entries {
+            let value = match value {
+                Some(value) => Some(self.value(value)?),
+                None => None,
+            };
+            copied_entries.push((self.value(key)?, value));
+        }
+
+        let mut to_ref = to.borrow_mut();
+        match &mut to_ref.exotic {
+            ExoticObject::Array { elements } => *elements = copied_elements,
+            ExoticObject::Map { entries } => {
+                for (key, value) in copied_entries {
+                    entries.insert(JsMapKey(key), value.unwrap_or(JsValue::Undefined));
+                }
+            }
+            ExoticObject::Set { entries } => {
+                for (key, _) in copied_entries {
+                    entries.insert(JsMapKey(key));
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+fn not_transferable(kind: &str) -> JsError {
+    JsError::type_error(format!(
+        "{} cannot be transferred between interpreters",
+        kind
+    ))
+}
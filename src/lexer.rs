@@ -4,7 +4,7 @@
 
 use crate::prelude::*;
 use crate::string_dict::StringDict;
-use crate::value::JsString;
+use crate::value::{JsString, Utf16Collector};
 
 /// Source span information
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -37,6 +37,22 @@ impl Default for Span {
     }
 }
 
+/// A source range as 1-based lines and columns, for reporting to hosts.
+///
+/// Columns count characters, and the end position is just past the last
+/// character, so a one-line range spans `end_column - start_column` chars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceSpan {
+    /// Byte offset of the first character
+    pub start: usize,
+    /// Byte offset just past the last character
+    pub end: usize,
+    pub start_line: u32,
+    pub start_column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
 /// Token types for JavaScript/TypeScript
 #[derive(Debug, Clone, PartialEq)]
 pub enum TokenKind {
@@ -244,7 +260,7 @@ pub struct Lexer<'a> {
 
 impl<'a> Lexer<'a> {
     pub fn new(source: &'a str, string_dict: &'a mut StringDict) -> Self {
-        Self {
+        let mut lexer = Self {
             source,
             chars: source.char_indices().peekable(),
             chars_base_offset: 0,
@@ -256,7 +272,35 @@ impl<'a> Lexer<'a> {
             start_column: 1,
             saw_newline: false,
             string_dict,
+        };
+        lexer.skip_source_prefix();
+        lexer
+    }
+
+    /// Skip a leading byte order mark and a `#!` hashbang line.
+    ///
+    /// Both are only recognized at the very start of the source. Byte offsets
+    /// stay absolute, so spans still index into the original text; the BOM is
+    /// invisible in editors and doesn't count as a column.
+    fn skip_source_prefix(&mut self) {
+        if self.peek() == Some('\u{FEFF}') {
+            self.advance();
+            self.column = 1;
         }
+        if self.peek() == Some('#') && self.peek_next() == Some('!') {
+            // The line terminator is left for the regular whitespace skipping
+            while let Some(ch) = self.peek() {
+                if ch == '\n' || ch == '\u{2028}' || ch == '\u{2029}' {
+                    break;
+                }
+                self.advance();
+            }
+        }
+    }
+
+    /// Get the full source text being tokenized
+    pub fn source(&self) -> &'a str {
+        self.source
     }
 
     /// Get mutable reference to the string dictionary for interning
@@ -457,6 +501,10 @@ impl<'a> Lexer<'a> {
                     self.saw_newline = true;
                     self.advance();
                 }
+                // Other Unicode space separators (Zs), e.g. U+2003 em space
+                Some(c) if is_space_separator(c) => {
+                    self.advance();
+                }
                 Some('/') => {
                     let next = self.peek_next();
                     if next == Some('/') {
@@ -471,19 +519,14 @@ impl<'a> Lexer<'a> {
                             self.advance();
                         }
                     } else if next == Some('*') {
-                        // Multi-line comment
+                        // Multi-line comment; these don't nest, the first */ ends it
                         self.advance(); // /
                         self.advance(); // *
-                        let mut depth = 1;
-                        while depth > 0 {
+                        loop {
                             match self.advance() {
                                 Some((_, '*')) if self.peek() == Some('/') => {
                                     self.advance();
-                                    depth -= 1;
-                                }
-                                Some((_, '/')) if self.peek() == Some('*') => {
-                                    self.advance();
-                                    depth += 1;
+                                    break;
                                 }
                                 // ECMAScript line terminators: LF, LS (U+2028), PS (U+2029)
                                 Some((_, '\n' | '\u{2028}' | '\u{2029}')) => {
@@ -743,7 +786,7 @@ impl<'a> Lexer<'a> {
     }
 
     fn scan_string(&mut self, quote: char) -> TokenKind {
-        let mut value = String::new();
+        let mut value = Utf16Collector::default();
 
         loop {
             match self.advance() {
@@ -788,10 +831,8 @@ impl<'a> Lexer<'a> {
                         }
                         Some((_, 'x')) => {
                             // Hex escape \xNN
-                            if let Some(hex) = self.scan_hex_escape(2)
-                                && let Some(ch) = char::from_u32(hex)
-                            {
-                                value.push(ch);
+                            if let Some(hex) = self.scan_hex_escape(2) {
+                                value.push_code_point(hex);
                             }
                         }
                         Some((_, 'u')) => {
@@ -811,15 +852,11 @@ impl<'a> Lexer<'a> {
                                         break;
                                     }
                                 }
-                                if let Ok(code) = u32::from_str_radix(&hex_str, 16)
-                                    && let Some(ch) = char::from_u32(code)
-                                {
-                                    value.push(ch);
+                                if let Ok(code) = u32::from_str_radix(&hex_str, 16) {
+                                    value.push_code_point(code);
                                 }
-                            } else if let Some(hex) = self.scan_hex_escape(4)
-                                && let Some(ch) = char::from_u32(hex)
-                            {
-                                value.push(ch);
+                            } else if let Some(hex) = self.scan_hex_escape(4) {
+                                value.push_code_point(hex);
                             }
                         }
                         Some((_, '\n')) => {
@@ -838,7 +875,7 @@ impl<'a> Lexer<'a> {
             }
         }
 
-        TokenKind::String(self.string_dict.get_or_insert(&value))
+        TokenKind::String(self.string_dict.get_or_insert(&value.finish()))
     }
 
     fn scan_hex_escape(&mut self, count: usize) -> Option<u32> {
@@ -897,17 +934,21 @@ impl<'a> Lexer<'a> {
     }
 
     fn scan_template_literal(&mut self) -> TokenKind {
-        let mut value = String::new();
+        let mut value = Utf16Collector::default();
 
         loop {
             match self.advance() {
                 Some((_, '`')) => {
                     // End of template
-                    return TokenKind::TemplateNoSub(self.string_dict.get_or_insert(&value));
+                    return TokenKind::TemplateNoSub(
+                        self.string_dict.get_or_insert(&value.finish()),
+                    );
                 }
                 Some((_, '$')) if self.peek() == Some('{') => {
                     self.advance(); // consume {
-                    return TokenKind::TemplateHead(self.string_dict.get_or_insert(&value));
+                    return TokenKind::TemplateHead(
+                        self.string_dict.get_or_insert(&value.finish()),
+                    );
                 }
                 Some((_, '\\')) => {
                     // Escape sequence (same as strings)
@@ -933,11 +974,7 @@ impl<'a> Lexer<'a> {
                         Some((_, 'x')) => {
                             // Hex escape \xNN - must be exactly 2 hex digits
                             if let Some(hex) = self.scan_hex_escape(2) {
-                                if let Some(ch) = char::from_u32(hex) {
-                                    value.push(ch);
-                                } else {
-                                    return TokenKind::Invalid('x');
-                                }
+                                value.push_code_point(hex);
                             } else {
                                 return TokenKind::Invalid('x');
                             }
@@ -964,21 +1001,13 @@ impl<'a> Lexer<'a> {
                                 if !found_close || hex_str.is_empty() {
                                     return TokenKind::Invalid('u');
                                 }
-                                if let Ok(code) = u32::from_str_radix(&hex_str, 16) {
-                                    if let Some(ch) = char::from_u32(code) {
-                                        value.push(ch);
-                                    } else {
-                                        return TokenKind::Invalid('u');
-                                    }
-                                } else {
+                                // Too many digits to parse is past U+10FFFF too
+                                let code = u32::from_str_radix(&hex_str, 16).unwrap_or(u32::MAX);
+                                if !value.push_code_point(code) {
                                     return TokenKind::Invalid('u');
                                 }
                             } else if let Some(hex) = self.scan_hex_escape(4) {
-                                if let Some(ch) = char::from_u32(hex) {
-                                    value.push(ch);
-                                } else {
-                                    return TokenKind::Invalid('u');
-                                }
+                                value.push_code_point(hex);
                             } else {
                                 return TokenKind::Invalid('u');
                             }
@@ -993,21 +1022,25 @@ impl<'a> Lexer<'a> {
         }
 
         // Unterminated template
-        TokenKind::TemplateNoSub(self.string_dict.get_or_insert(&value))
+        TokenKind::TemplateNoSub(self.string_dict.get_or_insert(&value.finish()))
     }
 
     /// Continue scanning a template literal after an expression
     pub fn scan_template_continuation(&mut self) -> TokenKind {
-        let mut value = String::new();
+        let mut value = Utf16Collector::default();
 
         loop {
             match self.advance() {
                 Some((_, '`')) => {
-                    return TokenKind::TemplateTail(self.string_dict.get_or_insert(&value));
+                    return TokenKind::TemplateTail(
+                        self.string_dict.get_or_insert(&value.finish()),
+                    );
                 }
                 Some((_, '$')) if self.peek() == Some('{') => {
                     self.advance();
-                    return TokenKind::TemplateMiddle(self.string_dict.get_or_insert(&value));
+                    return TokenKind::TemplateMiddle(
+                        self.string_dict.get_or_insert(&value.finish()),
+                    );
                 }
                 Some((_, '\\')) => match self.advance() {
                     Some((_, 'n')) => value.push('\n'),
@@ -1031,11 +1064,7 @@ impl<'a> Lexer<'a> {
                     Some((_, 'x')) => {
                         // Hex escape \xNN - must be exactly 2 hex digits
                         if let Some(hex) = self.scan_hex_escape(2) {
-                            if let Some(ch) = char::from_u32(hex) {
-                                value.push(ch);
-                            } else {
-                                return TokenKind::Invalid('x');
-                            }
+                            value.push_code_point(hex);
                         } else {
                             return TokenKind::Invalid('x');
                         }
@@ -1062,21 +1091,13 @@ impl<'a> Lexer<'a> {
                             if !found_close || hex_str.is_empty() {
                                 return TokenKind::Invalid('u');
                             }
-                            if let Ok(code) = u32::from_str_radix(&hex_str, 16) {
-                                if let Some(ch) = char::from_u32(code) {
-                                    value.push(ch);
-                                } else {
-                                    return TokenKind::Invalid('u');
-                                }
-                            } else {
+                            // Too many digits to parse is past U+10FFFF too
+                            let code = u32::from_str_radix(&hex_str, 16).unwrap_or(u32::MAX);
+                            if !value.push_code_point(code) {
                                 return TokenKind::Invalid('u');
                             }
                         } else if let Some(hex) = self.scan_hex_escape(4) {
-                            if let Some(ch) = char::from_u32(hex) {
-                                value.push(ch);
-                            } else {
-                                return TokenKind::Invalid('u');
-                            }
+                            value.push_code_point(hex);
                         } else {
                             return TokenKind::Invalid('u');
                         }
@@ -1089,7 +1110,7 @@ impl<'a> Lexer<'a> {
             }
         }
 
-        TokenKind::TemplateTail(self.string_dict.get_or_insert(&value))
+        TokenKind::TemplateTail(self.string_dict.get_or_insert(&value.finish()))
     }
 
     /// Rescan template continuation from a given span position (the } token)
@@ -1352,114 +1373,146 @@ impl<'a> Lexer<'a> {
             return TokenKind::Identifier(self.string_dict.get_or_insert(&name));
         }
 
-        // Length-prefixed keyword dispatch for faster matching
-        // First dispatch on length, then compare only keywords of that length
-        match name.len() {
-            2 => match name.as_str() {
-                "if" => TokenKind::If,
-                "in" => TokenKind::In,
-                "do" => TokenKind::Do,
-                "as" => TokenKind::As,
-                "of" => TokenKind::Of,
-                "is" => TokenKind::Is,
-                _ => TokenKind::Identifier(self.string_dict.get_or_insert(&name)),
-            },
-            3 => match name.as_str() {
-                "let" => TokenKind::Let,
-                "var" => TokenKind::Var,
-                "for" => TokenKind::For,
-                "new" => TokenKind::New,
-                "try" => TokenKind::Try,
-                "any" => TokenKind::Any,
-                _ => TokenKind::Identifier(self.string_dict.get_or_insert(&name)),
-            },
-            4 => match name.as_str() {
-                "true" => TokenKind::True,
-                "null" => TokenKind::Null,
-                "else" => TokenKind::Else,
-                "case" => TokenKind::Case,
-                "this" => TokenKind::This,
-                "void" => TokenKind::Void,
-                "enum" => TokenKind::Enum,
-                "type" => TokenKind::Type,
-                "from" => TokenKind::From,
-                _ => TokenKind::Identifier(self.string_dict.get_or_insert(&name)),
-            },
-            5 => match name.as_str() {
-                "false" => TokenKind::False,
-                "const" => TokenKind::Const,
-                "while" => TokenKind::While,
-                "break" => TokenKind::Break,
-                "class" => TokenKind::Class,
-                "super" => TokenKind::Super,
-                "throw" => TokenKind::Throw,
-                "await" => TokenKind::Await,
-                "async" => TokenKind::Async,
-                "yield" => TokenKind::Yield,
-                "infer" => TokenKind::Infer,
-                "never" => TokenKind::Never,
-                "catch" => TokenKind::Catch,
-                "keyof" => TokenKind::Keyof,
-                _ => TokenKind::Identifier(self.string_dict.get_or_insert(&name)),
-            },
-            6 => match name.as_str() {
-                "return" => TokenKind::Return,
-                "switch" => TokenKind::Switch,
-                "static" => TokenKind::Static,
-                "import" => TokenKind::Import,
-                "export" => TokenKind::Export,
-                "typeof" => TokenKind::Typeof,
-                "delete" => TokenKind::Delete,
-                "public" => TokenKind::Public,
-                "module" => TokenKind::Module,
-                _ => TokenKind::Identifier(self.string_dict.get_or_insert(&name)),
-            },
-            7 => match name.as_str() {
-                "default" => TokenKind::Default,
-                "finally" => TokenKind::Finally,
-                "extends" => TokenKind::Extends,
-                "declare" => TokenKind::Declare,
-                "private" => TokenKind::Private,
-                "unknown" => TokenKind::Unknown,
-                "asserts" => TokenKind::Asserts,
-                _ => TokenKind::Identifier(self.string_dict.get_or_insert(&name)),
-            },
-            8 => match name.as_str() {
-                "function" => TokenKind::Function,
-                "continue" => TokenKind::Continue,
-                "debugger" => TokenKind::Debugger,
-                "readonly" => TokenKind::Readonly,
-                "accessor" => TokenKind::Accessor,
-                "abstract" => TokenKind::Abstract,
-                _ => TokenKind::Identifier(self.string_dict.get_or_insert(&name)),
-            },
-            9 => match name.as_str() {
-                "protected" => TokenKind::Protected,
-                "namespace" => TokenKind::Namespace,
-                "interface" => TokenKind::Interface,
-                _ => TokenKind::Identifier(self.string_dict.get_or_insert(&name)),
-            },
-            10 => match name.as_str() {
-                "instanceof" => TokenKind::Instanceof,
-                "implements" => TokenKind::Implements,
-                _ => TokenKind::Identifier(self.string_dict.get_or_insert(&name)),
-            },
-            _ => TokenKind::Identifier(self.string_dict.get_or_insert(&name)),
-        }
+        keyword(&name)
+            .unwrap_or_else(|| TokenKind::Identifier(self.string_dict.get_or_insert(&name)))
     }
 }
 
+/// The keyword token spelled by an unescaped identifier, if any.
+///
+/// Dispatches on length first, then compares only keywords of that length.
+pub(crate) fn keyword(name: &str) -> Option<TokenKind> {
+    let kind = match name.len() {
+        2 => match name {
+            "if" => TokenKind::If,
+            "in" => TokenKind::In,
+            "do" => TokenKind::Do,
+            "as" => TokenKind::As,
+            "of" => TokenKind::Of,
+            "is" => TokenKind::Is,
+            _ => return None,
+        },
+        3 => match name {
+            "let" => TokenKind::Let,
+            "var" => TokenKind::Var,
+            "for" => TokenKind::For,
+            "new" => TokenKind::New,
+            "try" => TokenKind::Try,
+            "any" => TokenKind::Any,
+            _ => return None,
+        },
+        4 => match name {
+            "true" => TokenKind::True,
+            "null" => TokenKind::Null,
+            "else" => TokenKind::Else,
+            "case" => TokenKind::Case,
+            "this" => TokenKind::This,
+            "void" => TokenKind::Void,
+            "enum" => TokenKind::Enum,
+            "type" => TokenKind::Type,
+            "from" => TokenKind::From,
+            _ => return None,
+        },
+        5 => match name {
+            "false" => TokenKind::False,
+            "const" => TokenKind::Const,
+            "while" => TokenKind::While,
+            "break" => TokenKind::Break,
+            "class" => TokenKind::Class,
+            "super" => TokenKind::Super,
+            "throw" => TokenKind::Throw,
+            "await" => TokenKind::Await,
+            "async" => TokenKind::Async,
+            "yield" => TokenKind::Yield,
+            "infer" => TokenKind::Infer,
+            "never" => TokenKind::Never,
+            "catch" => TokenKind::Catch,
+            "keyof" => TokenKind::Keyof,
+            _ => return None,
+        },
+        6 => match name {
+            "return" => TokenKind::Return,
+            "switch" => TokenKind::Switch,
+            "static" => TokenKind::Static,
+            "import" => TokenKind::Import,
+            "export" => TokenKind::Export,
+            "typeof" => TokenKind::Typeof,
+            "delete" => TokenKind::Delete,
+            "public" => TokenKind::Public,
+            "module" => TokenKind::Module,
+            _ => return None,
+        },
+        7 => match name {
+            "default" => TokenKind::Default,
+            "finally" => TokenKind::Finally,
+            "extends" => TokenKind::Extends,
+            "declare" => TokenKind::Declare,
+            "private" => TokenKind::Private,
+            "unknown" => TokenKind::Unknown,
+            "asserts" => TokenKind::Asserts,
+            _ => return None,
+        },
+        8 => match name {
+            "function" => TokenKind::Function,
+            "continue" => TokenKind::Continue,
+            "debugger" => TokenKind::Debugger,
+            "readonly" => TokenKind::Readonly,
+            "accessor" => TokenKind::Accessor,
+            "abstract" => TokenKind::Abstract,
+            _ => return None,
+        },
+        9 => match name {
+            "protected" => TokenKind::Protected,
+            "namespace" => TokenKind::Namespace,
+            "interface" => TokenKind::Interface,
+            _ => return None,
+        },
+        10 => match name {
+            "instanceof" => TokenKind::Instanceof,
+            "implements" => TokenKind::Implements,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(kind)
+}
+
 /// Check if a character can start an identifier (including unicode escape sequence)
 fn is_id_start(ch: char) -> bool {
-    ch == '_' || ch == '$' || ch == '\\' || ch.is_ascii_alphabetic()
+    ch == '\\' || is_id_start_char(ch)
+}
+
+/// Check if a decoded character is valid as identifier start (without escape check).
+/// Beyond ASCII, letters by the Unicode Alphabetic property, which is close
+/// to the ID_Start the spec names.
+pub(crate) fn is_id_start_char(ch: char) -> bool {
+    ch == '_' || ch == '$' || ch.is_ascii_alphabetic() || (!ch.is_ascii() && ch.is_alphabetic())
 }
 
-/// Check if a decoded character is valid as identifier start (without escape check)
-fn is_id_start_char(ch: char) -> bool {
-    ch == '_' || ch == '$' || ch.is_ascii_alphabetic()
+/// Check if a decoded character is valid as identifier continue (without escape check).
+/// Beyond ASCII, letters and digits as well as combining marks (`é` written
+/// as `e` + U+0301) and the zero-width joiners the spec allows.
+pub(crate) fn is_id_continue_char(ch: char) -> bool {
+    if ch.is_ascii() {
+        return ch == '_' || ch == '$' || ch.is_ascii_alphanumeric();
+    }
+    ch.is_alphanumeric()
+        || matches!(
+            ch,
+            '\u{200c}'
+                | '\u{200d}'
+                | '\u{300}'..='\u{36f}'
+                | '\u{1ab0}'..='\u{1aff}'
+                | '\u{1dc0}'..='\u{1dff}'
+                | '\u{20d0}'..='\u{20ff}'
+                | '\u{fe20}'..='\u{fe2f}'
+        )
 }
 
-/// Check if a decoded character is valid as identifier continue (without escape check)
-fn is_id_continue_char(ch: char) -> bool {
-    ch == '_' || ch == '$' || ch.is_ascii_alphanumeric()
+/// Unicode space separators beyond those matched by name in `skip_whitespace_and_comments`
+pub(crate) fn is_space_separator(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{1680}' | '\u{2000}'..='\u{200a}' | '\u{202f}' | '\u{205f}' | '\u{3000}'
+    )
 }
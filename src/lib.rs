@@ -41,6 +41,11 @@
 //! }
 //! ```
 //!
+//! # Stable API
+//!
+//! The [`embed`] module gathers the items hosts can rely on across minor
+//! releases; the rest of the public surface may change in any release.
+//!
 //! # Execution Model
 //!
 //! The interpreter uses step-based execution, giving hosts full control:
@@ -49,6 +54,7 @@
 //! - [`Interpreter::step`] - Executes one instruction, returns [`StepResult`]
 //! - [`StepResult::NeedImports`] - Execution paused, waiting for ES modules
 //! - [`StepResult::Suspended`] - Execution paused, waiting for async operations
+//! - [`StepResult::Orders`] - New async operations while still running ([`OrderDelivery::Eager`])
 //!
 //! # Working with Values
 //!
@@ -458,7 +464,9 @@ use prelude::ToString;
 
 pub mod api;
 pub mod ast;
+#[doc(hidden)]
 pub mod compiler;
+pub mod embed;
 pub mod error;
 pub mod gc;
 pub(crate) mod interpreter;
@@ -466,6 +474,10 @@ pub mod lexer;
 pub mod parser;
 pub mod platform;
 pub mod string_dict;
+// Deterministic time and randomness for tests
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod token_stream;
 pub mod value;
 
 // C FFI module (only when c-api feature is enabled)
@@ -477,14 +489,36 @@ pub mod ffi;
 #[cfg(all(target_arch = "wasm32", feature = "wasm"))]
 pub mod wasm;
 
-use prelude::{Rc, String, Vec, format};
+use prelude::{Arc, Box, Rc, String, Vec, format};
 
-pub use error::JsError;
-pub use gc::{Gc, GcStats, Guard, Heap, Reset};
+pub use compiler::FunctionSource;
+pub use error::{JsError, SourceText};
+pub use gc::{CollectOutcome, Gc, GcStats, Guard, Heap, Reset, Retainer};
 pub use interpreter::Interpreter;
+pub use interpreter::checkpoint::{CheckpointError, ModuleSources};
+pub use interpreter::chunk_cache::ChunkCacheStats;
+pub use interpreter::flight_recorder::{FLIGHT_LOG_TAIL, FlightEvent, FlightEventKind};
+pub use interpreter::generator_drive::GeneratorStep;
+pub use interpreter::heap_budget::{HeapBudget, HeapUsage};
+pub use interpreter::host_class::{
+    HostCallFn, HostClassDef, HostClassId, HostDisposeFn, HostGetFn, HostHasFn, HostObjectData,
+    HostOwnKeysFn, HostSetFn,
+};
+pub use interpreter::host_driver::{Host, HostAction, ResponseFactory, RunPhase};
+pub use interpreter::metrics::ModuleMetrics;
+pub use interpreter::module_map::ModuleCandidate;
+pub use interpreter::order_log::{OrderLogEntry, OrderLogResponse};
+pub use interpreter::pending_promises::PendingPromiseInfo;
+pub use interpreter::probes::{ProbeId, ProbeSample};
+pub use interpreter::provenance::{CreationSite, ProvenanceEntry};
+pub use interpreter::shared_modules::SharedModuleSet;
+pub use interpreter::stall::StallReport;
+pub use lexer::SourceSpan;
 pub use string_dict::StringDict;
 pub use value::CheapClone;
+#[doc(hidden)]
 pub use value::EnvRef;
+pub use value::GcCensus;
 pub use value::Guarded;
 pub use value::JsObject;
 pub use value::JsString;
@@ -492,21 +526,27 @@ pub use value::JsValue;
 
 // Re-export serde conversion functions for JsValue <-> serde_json::Value
 pub use interpreter::builtins::json::{
-    js_value_to_json, json_to_js_value_with_guard, json_to_js_value_with_interp,
+    ErrorsAs, MapsAs, NonFinite, ToJsonOptions, UndefinedInArrays, WrappersAs, js_value_to_json,
+    js_value_to_json_opts, js_value_to_json_with, json_to_js_value_with_guard,
+    json_to_js_value_with_interp,
 };
+pub use interpreter::builtins::performance::{PerfEntry, PerfEntryType};
+pub use interpreter::inspect::{MAX_SUSPENSION_LOCALS, SuspendedFrame, SuspensionInfo};
 
 // Re-export internal module builder for the order system
+pub use interpreter::builtins::assert::create_assert_internal_module;
 pub use interpreter::builtins::internal::create_eval_internal_module;
+pub use interpreter::builtins::quantity::create_quantity_internal_module;
 
 // Re-export order system types
-// Note: Order, OrderId, OrderResponse, ModulePath, ImportRequest, StepResult are defined in this module
+// Note: Order, OrderId, OrderOrigin, OrderResponse, ModulePath, ImportKind, ImportRequest, StepResult are defined in this module
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // Order System Types
 // ═══════════════════════════════════════════════════════════════════════════════
 
 /// Unique identifier for an order
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct OrderId(pub u64);
 
 /// An order is a request for an external effect.
@@ -519,6 +559,34 @@ pub struct Order {
     /// The JS value describing what operation to perform.
     /// Wrapped in RuntimeValue to keep it alive until the order is processed.
     pub payload: RuntimeValue,
+    /// Time after which the script stops waiting for this order, in
+    /// milliseconds since the Unix epoch on the interpreter's clock (`Date.now()`).
+    /// Set by `request(..., { timeoutMs })` from `tsrun:host`.
+    pub deadline_ms: Option<i64>,
+    /// Where in the script the order was created
+    pub origin: OrderOrigin,
+    /// Position of the order among those handed to the host with it, in
+    /// creation order
+    pub batch_seq: u32,
+}
+
+/// The script location that created an order, for audit logs and error
+/// attribution.
+///
+/// Calls made from inside tsrun's own modules (such as `request` in
+/// `tsrun:host`) are attributed to the script code that called into them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OrderOrigin {
+    /// Module containing the call; `None` for an entry program prepared
+    /// without a path, or when the order was created outside script code
+    pub module: Option<ModulePath>,
+    /// 1-based line of the call, 0 when unknown
+    pub line: u32,
+    /// 1-based column of the call, 0 when unknown
+    pub column: u32,
+    /// Name of the function the call is in; `None` at module top level and
+    /// in anonymous functions
+    pub function_name: Option<String>,
 }
 
 /// Response to fulfill an order from the host
@@ -531,10 +599,183 @@ pub struct OrderResponse {
     pub result: Result<RuntimeValue, JsError>,
 }
 
+impl OrderResponse {
+    /// Reject the order with `value` as the rejection reason, as if the
+    /// script had thrown it. Use this to hand the script a structured error,
+    /// such as an object with a `code` field.
+    ///
+    /// Errors passed as `result: Err(..)` reach the script as error objects
+    /// of the matching type, e.g. a `TypeError` for [`JsError::type_error`].
+    pub fn reject_with_value(id: OrderId, value: RuntimeValue) -> Self {
+        Self {
+            id,
+            result: Err(JsError::thrown(Guarded {
+                value: value.value,
+                guard: value._guard,
+            })),
+        }
+    }
+}
+
+/// Controls when orders created by the script are handed to the host.
+///
+/// Set with [`Interpreter::set_order_delivery`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OrderDelivery {
+    /// `order()` suspends the script and orders are reported through
+    /// `StepResult::Suspended` (default).
+    #[default]
+    Batched,
+
+    /// `order()` returns a Promise immediately and `step()` reports new orders
+    /// through `StepResult::Orders` while the script keeps running.
+    ///
+    /// Responses passed to `fulfill_orders()` settle the order's Promise right
+    /// away, so a script that awaits already-fulfilled orders never suspends.
+    Eager,
+}
+
+/// How much a single [`Interpreter::step`] executes.
+///
+/// Set with [`InterpreterConfig::step_granularity`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StepGranularity {
+    /// Every `step()` executes one instruction (default).
+    #[default]
+    Instruction,
+
+    /// Every `step()` runs until the next checkpoint: a loop back-edge, a
+    /// call into or return from a script function, a new order, or a
+    /// suspension. Interruption latency stays bounded by one basic block
+    /// while hosts that step in a loop cross into the VM far less often.
+    Checkpoint,
+}
+
+/// What happens when the script creates an order past
+/// [`InterpreterConfig::max_pending_orders`].
+///
+/// Set with [`InterpreterConfig::pending_order_overflow`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PendingOrderOverflow {
+    /// `order()` throws a catchable `RangeError: too many pending host
+    /// requests` (default).
+    #[default]
+    Throw,
+
+    /// The order is created and `step()` then returns
+    /// `StepResult::Suspended` with the current batch, without running the
+    /// script, until fulfilled or cancelled orders bring the count back
+    /// under the limit. Orders created by callbacks a builtin runs are only
+    /// stopped once the builtin returns.
+    Suspend,
+}
+
+/// What a provided module that fails to parse or compile does to the run.
+///
+/// Set with [`InterpreterConfig::module_error_policy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ModuleErrorPolicy {
+    /// `provide_module` fails with the error, and so does execution if the
+    /// module was compiled when its importers were ready to run (default).
+    #[default]
+    FailFast,
+
+    /// The error is recorded for [`Interpreter::module_errors`] and the module
+    /// loads as an empty namespace whose every property read throws
+    /// `TypeError: Module '...' failed to load: ...`. Importers still run, so
+    /// parts of the graph that don't touch it complete. The entry module
+    /// failing still fails the run.
+    Collect,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Access Audit
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Kind of access recorded by the audit log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuditEventKind {
+    /// A global binding was read (identifier lookup or `globalThis.name`)
+    GlobalRead,
+    /// An export of an internal module was imported
+    ModuleAccess,
+    /// A native function was called
+    NativeCall,
+}
+
+/// Aggregated access record returned by [`Interpreter::take_audit_log`].
+///
+/// Repeated accesses of the same member are merged and counted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEvent {
+    /// What kind of access this is
+    pub kind: AuditEventKind,
+    /// Global, export, or function name
+    pub name: String,
+    /// Internal module specifier for module exports and their functions
+    pub module: Option<String>,
+    /// Number of times the access happened
+    pub count: usize,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Purity Check
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// What [`Interpreter::eval_pure`] does when a module breaks purity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PurityMode {
+    /// Record the violation and let the write go through
+    #[default]
+    Record,
+    /// Record the violation and stop evaluation with `JsError::Terminated`
+    Raise,
+}
+
+/// Kind of side effect found by [`Interpreter::eval_pure`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PurityViolationKind {
+    /// A property of the global object or an intrinsic was written
+    GlobalWrite,
+    /// An object reachable from an imported module namespace was written
+    ImportWrite,
+    /// The module issued an order
+    Order,
+}
+
+/// A side effect found by [`Interpreter::eval_pure`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PurityViolation {
+    /// What kind of side effect this is
+    pub kind: PurityViolationKind,
+    /// What was written, as the script would name it, e.g. `cache`,
+    /// `Array.prototype.last` or `limits.rps`; `order` for orders
+    pub target: String,
+    /// Specifier of the imported module for `ImportWrite`
+    pub module: Option<String>,
+    /// Innermost script frame when the violation happened
+    pub location: Option<error::StackFrame>,
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // Runtime Value
 // ═══════════════════════════════════════════════════════════════════════════════
 
+/// Identity of a heap object, from [`RuntimeValue::object_id`].
+///
+/// Unique within an interpreter for as long as the object lives: a heap slot
+/// reused for a new object after collection gives it a new id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ObjectId(u64);
+
+impl ObjectId {
+    /// The id as a nonzero integer: the heap slot plus one in the high 32
+    /// bits and the times the slot was reused in the low 32
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
 /// A JS value with an attached guard that keeps it alive until dropped.
 ///
 /// This struct ensures that GC-managed objects remain valid for as long as
@@ -581,6 +822,9 @@ pub struct RuntimeValue {
 impl RuntimeValue {
     /// Create a RuntimeValue from an internal Guarded value
     pub(crate) fn from_guarded(guarded: Guarded) -> Self {
+        if let Some(guard) = &guarded.guard {
+            guard.mark_host();
+        }
         Self {
             value: guarded.value,
             _guard: guarded.guard,
@@ -589,6 +833,7 @@ impl RuntimeValue {
 
     /// Create a RuntimeValue with an explicit guard
     pub(crate) fn with_guard(value: JsValue, guard: Guard<JsObject>) -> Self {
+        guard.mark_host();
         Self {
             value,
             _guard: Some(guard),
@@ -609,6 +854,39 @@ impl RuntimeValue {
         &self.value
     }
 
+    /// Whether the value is an object whose [`Interpreter`] has been dropped.
+    ///
+    /// Reading properties of such an object panics; primitives stay usable.
+    pub fn is_detached(&self) -> bool {
+        self.value
+            .as_object()
+            .is_some_and(|object| !object.is_heap_alive())
+    }
+
+    /// The value, or an error if it [is detached](Self::is_detached)
+    pub fn try_value(&self) -> Result<&JsValue, JsError> {
+        if self.is_detached() {
+            return Err(JsError::detached_value());
+        }
+        Ok(&self.value)
+    }
+
+    /// Identity of the object this value refers to, `None` for primitives
+    /// and [detached](Self::is_detached) values.
+    ///
+    /// Values reaching the host separately, say in the payloads of two
+    /// orders, report the same id when they are the same object, so hosts
+    /// can cache and diff shared subtrees that JSON conversion duplicates.
+    pub fn object_id(&self) -> Option<ObjectId> {
+        let (slot, generation) = self.value.as_object()?.slot_generation()?;
+        Some(ObjectId(((slot as u64 + 1) << 32) | u64::from(generation)))
+    }
+
+    /// Convert the value to JSON as `options` say; see [`ToJsonOptions`]
+    pub fn to_json(&self, options: &ToJsonOptions) -> Result<serde_json::Value, JsError> {
+        js_value_to_json_opts(self.try_value()?, options)
+    }
+
     // NOTE: Do NOT add `into_value(self) -> JsValue` or similar methods that
     // extract the value without the guard. The guard must stay alive as long
     // as the value is in use. If you need to pass the value somewhere, pass
@@ -672,11 +950,75 @@ impl RuntimeValue {
         self.value.as_str()
     }
 
+    /// See [`JsValue::as_i64`]
+    pub fn as_i64(&self) -> Option<i64> {
+        self.value.as_i64()
+    }
+
+    /// See [`JsValue::as_u64`]
+    pub fn as_u64(&self) -> Option<u64> {
+        self.value.as_u64()
+    }
+
+    /// See [`JsValue::as_u32`]
+    pub fn as_u32(&self) -> Option<u32> {
+        self.value.as_u32()
+    }
+
+    /// See [`JsValue::as_usize`]
+    pub fn as_usize(&self) -> Option<usize> {
+        self.value.as_usize()
+    }
+
+    /// See [`JsValue::as_f32`]
+    pub fn as_f32(&self) -> Option<f32> {
+        self.value.as_f32()
+    }
+
+    /// See [`JsValue::try_i64`]
+    pub fn try_i64(&self) -> Result<i64, JsError> {
+        self.value.try_i64()
+    }
+
+    /// See [`JsValue::try_u64`]
+    pub fn try_u64(&self) -> Result<u64, JsError> {
+        self.value.try_u64()
+    }
+
+    /// See [`JsValue::try_u32`]
+    pub fn try_u32(&self) -> Result<u32, JsError> {
+        self.value.try_u32()
+    }
+
+    /// See [`JsValue::try_usize`]
+    pub fn try_usize(&self) -> Result<usize, JsError> {
+        self.value.try_usize()
+    }
+
+    /// See [`JsValue::try_f32`]
+    pub fn try_f32(&self) -> Result<f32, JsError> {
+        self.value.try_f32()
+    }
+
+    /// See [`JsValue::as_string_lossy`]
+    pub fn as_string_lossy(&self) -> Option<String> {
+        self.value.as_string_lossy()
+    }
+
     /// Returns a string describing the type of this value
     pub fn type_name(&self) -> &'static str {
         self.value.type_name()
     }
 
+    /// Returns the source of a script function: its text, file and span.
+    ///
+    /// Covers declarations, expressions, arrows, methods, classes, async
+    /// functions and generators. Native and bound functions return `None`, as
+    /// does everything when [`InterpreterConfig::retain_function_source`] is off.
+    pub fn function_source(&self) -> Option<FunctionSource> {
+        interpreter::builtins::function::function_source(&self.value)
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════════
     // Array Inspection Methods (primitives only - complex values go through Runtime)
     // ═══════════════════════════════════════════════════════════════════════════════
@@ -721,6 +1063,19 @@ impl RuntimeValue {
         }
     }
 
+    /// Check whether this array holds `value`, comparing as `includes` does
+    /// (SameValueZero: NaN matches NaN, -0 matches +0, objects by identity).
+    ///
+    /// Returns `None` if this is not an array.
+    pub fn array_contains(&self, value: &JsValue) -> Option<bool> {
+        let obj = self.value.as_object()?;
+        let borrowed = obj.borrow();
+        let value::ExoticObject::Array { elements } = &borrowed.exotic else {
+            return None;
+        };
+        Some(elements.iter().any(|elem| elem.same_value_zero(value)))
+    }
+
     /// Get all property keys of an object.
     ///
     /// Returns an empty vector if this is not an object.
@@ -748,6 +1103,79 @@ impl RuntimeValue {
             Vec::new()
         }
     }
+
+    /// Get the entries of a Map, in insertion order.
+    ///
+    /// Each key and value is rooted on its own, so the entries stay valid
+    /// after this value is dropped. Returns `None` if this is not a Map.
+    pub fn as_map_entries(
+        &self,
+        interp: &Interpreter,
+    ) -> Option<Vec<(RuntimeValue, RuntimeValue)>> {
+        let obj = self.value.as_object()?;
+        let borrowed = obj.borrow();
+        let value::ExoticObject::Map { entries } = &borrowed.exotic else {
+            return None;
+        };
+        Some(
+            entries
+                .iter()
+                .map(|(key, value)| {
+                    (
+                        RuntimeValue::from_guarded(Guarded::from_value(
+                            key.0.clone(),
+                            &interp.heap,
+                        )),
+                        RuntimeValue::from_guarded(Guarded::from_value(
+                            value.clone(),
+                            &interp.heap,
+                        )),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Get the values of a Set, in insertion order.
+    ///
+    /// Returns `None` if this is not a Set.
+    pub fn as_set_values(&self, interp: &Interpreter) -> Option<Vec<RuntimeValue>> {
+        let obj = self.value.as_object()?;
+        let borrowed = obj.borrow();
+        let value::ExoticObject::Set { entries } = &borrowed.exotic else {
+            return None;
+        };
+        Some(
+            entries
+                .iter()
+                .map(|value| {
+                    RuntimeValue::from_guarded(Guarded::from_value(value.0.clone(), &interp.heap))
+                })
+                .collect(),
+        )
+    }
+
+    /// Get the symbol keys of an object's own properties, in insertion order.
+    ///
+    /// Each symbol carries its id and description; wrap it in
+    /// `JsValue::Symbol` to address the property with
+    /// [`Interpreter::get_property_by_symbol`]. Returns an empty vector if
+    /// this is not an object.
+    pub fn symbol_keys(&self) -> Vec<value::JsSymbol> {
+        if let Some(obj) = self.value.as_object() {
+            let borrowed = obj.borrow();
+            borrowed
+                .properties
+                .keys()
+                .filter_map(|k| match k {
+                    value::PropertyKey::Symbol(sym) => Some((**sym).clone()),
+                    _ => None,
+                })
+                .collect()
+        } else {
+            Vec::new()
+        }
+    }
 }
 
 impl core::ops::Deref for RuntimeValue {
@@ -758,6 +1186,22 @@ impl core::ops::Deref for RuntimeValue {
     }
 }
 
+macro_rules! impl_try_from_runtime_value {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl TryFrom<&RuntimeValue> for $ty {
+                type Error = JsError;
+
+                fn try_from(value: &RuntimeValue) -> Result<Self, JsError> {
+                    <$ty>::try_from(&value.value)
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_runtime_value!(i64, u64, u32, usize, f32, f64, bool, String);
+
 impl core::fmt::Debug for RuntimeValue {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("RuntimeValue")
@@ -767,9 +1211,16 @@ impl core::fmt::Debug for RuntimeValue {
     }
 }
 
+/// Shows objects as `{ key: value, ... }` and arrays as `[a, b, ...]`,
+/// [`value::DEFAULT_DISPLAY_DEPTH`] levels deep and at most
+/// [`value::DEFAULT_DISPLAY_ITEMS`] items each, without running any script.
+/// Use [`JsValue::display_opts`] for other limits.
 impl core::fmt::Display for RuntimeValue {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        core::fmt::Display::fmt(&self.value, f)
+        let display = self
+            .value
+            .display_opts(value::DEFAULT_DISPLAY_DEPTH, value::DEFAULT_DISPLAY_ITEMS);
+        core::fmt::Display::fmt(&display, f)
     }
 }
 
@@ -785,6 +1236,69 @@ impl PartialEq<RuntimeValue> for JsValue {
     }
 }
 
+/// The well-known symbols, for hosts reading or installing protocol methods.
+///
+/// ```
+/// use tsrun::{Interpreter, WellKnownSymbol};
+///
+/// let mut interp = Interpreter::new();
+/// let iterator = interp.get_well_known(WellKnownSymbol::Iterator);
+/// assert_eq!(iterator.to_string(), "Symbol(Symbol.iterator)");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WellKnownSymbol {
+    /// `Symbol.iterator`
+    Iterator,
+    /// `Symbol.asyncIterator`
+    AsyncIterator,
+    /// `Symbol.hasInstance`
+    HasInstance,
+    /// `Symbol.isConcatSpreadable`
+    IsConcatSpreadable,
+    /// `Symbol.match`
+    Match,
+    /// `Symbol.replace`
+    Replace,
+    /// `Symbol.search`
+    Search,
+    /// `Symbol.species`
+    Species,
+    /// `Symbol.split`
+    Split,
+    /// `Symbol.toPrimitive`
+    ToPrimitive,
+    /// `Symbol.toStringTag`
+    ToStringTag,
+    /// `Symbol.unscopables`
+    Unscopables,
+    /// `Symbol.dispose`
+    Dispose,
+    /// `Symbol.asyncDispose`
+    AsyncDispose,
+}
+
+impl WellKnownSymbol {
+    /// The symbol's description, e.g. `"Symbol.iterator"`
+    pub fn description(self) -> &'static str {
+        match self {
+            WellKnownSymbol::Iterator => "Symbol.iterator",
+            WellKnownSymbol::AsyncIterator => "Symbol.asyncIterator",
+            WellKnownSymbol::HasInstance => "Symbol.hasInstance",
+            WellKnownSymbol::IsConcatSpreadable => "Symbol.isConcatSpreadable",
+            WellKnownSymbol::Match => "Symbol.match",
+            WellKnownSymbol::Replace => "Symbol.replace",
+            WellKnownSymbol::Search => "Symbol.search",
+            WellKnownSymbol::Species => "Symbol.species",
+            WellKnownSymbol::Split => "Symbol.split",
+            WellKnownSymbol::ToPrimitive => "Symbol.toPrimitive",
+            WellKnownSymbol::ToStringTag => "Symbol.toStringTag",
+            WellKnownSymbol::Unscopables => "Symbol.unscopables",
+            WellKnownSymbol::Dispose => "Symbol.dispose",
+            WellKnownSymbol::AsyncDispose => "Symbol.asyncDispose",
+        }
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // Module Path System
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -797,6 +1311,16 @@ impl PartialEq<RuntimeValue> for JsValue {
 /// - No trailing slashes
 /// - Absolute (starts with `/` or is a bare specifier like `lodash`)
 ///
+/// Three shapes of absolute path are recognized, each with a root that `..`
+/// never climbs above:
+/// - POSIX: `/src/main.ts`, rooted at `/`
+/// - Windows: `C:\src\main.ts` or `\\server\share\main.ts`, stored with
+///   forward slashes (`C:/src/main.ts`, `//server/share/main.ts`) and rooted
+///   at the drive or share. Drive letters compare case-insensitively.
+/// - URL: `https://cdn.example.com/lib/mod.ts`, rooted at `scheme://authority`,
+///   with the scheme and authority lowercased and any `#fragment` dropped.
+///   A `?query` is kept unless [`UrlQuery::Strip`] says otherwise.
+///
 /// # Resolution Examples
 ///
 /// ```
@@ -818,10 +1342,30 @@ impl PartialEq<RuntimeValue> for JsValue {
 /// // Absolute paths are just normalized
 /// let resolved = ModulePath::resolve("/lib/../src/index.ts", None);
 /// assert_eq!(resolved.as_str(), "/src/index.ts");
+///
+/// // Windows and URL bases keep their root
+/// let base = ModulePath::entry(r"C:\project\src\main.ts");
+/// let resolved = ModulePath::resolve(r"..\lib\util.ts", Some(&base));
+/// assert_eq!(resolved.as_str(), "C:/project/lib/util.ts");
+///
+/// let base = ModulePath::entry("https://cdn.example.com/lib/mod.ts");
+/// let resolved = ModulePath::resolve("../../../dep.ts", Some(&base));
+/// assert_eq!(resolved.as_str(), "https://cdn.example.com/dep.ts");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone)]
 pub struct ModulePath(String);
 
+/// What [`ModulePath::resolve_with`] does with the `?query` of a URL
+/// specifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UrlQuery {
+    /// Keep it, so `mod.ts?v=1` and `mod.ts?v=2` are different modules
+    #[default]
+    Keep,
+    /// Drop it, so both load `mod.ts` once
+    Strip,
+}
+
 impl ModulePath {
     /// Create a ModulePath from an already-normalized absolute path.
     /// Use `resolve` for relative paths.
@@ -836,76 +1380,251 @@ impl ModulePath {
 
     /// Get the directory portion of this path (everything before the last `/`)
     pub fn parent(&self) -> Option<&str> {
-        self.0.rfind('/').and_then(|idx| self.0.get(..idx))
+        // A URL's query may contain slashes of its own
+        let path = match url_root_len(&self.0) {
+            Some(root) => {
+                let end = self
+                    .0
+                    .get(root..)
+                    .and_then(|rest| rest.find(['?', '#']))
+                    .map_or(self.0.len(), |pos| root + pos);
+                self.0.get(..end)?
+            }
+            None => &self.0,
+        };
+        path.rfind('/').and_then(|idx| path.get(..idx))
     }
 
     /// Check if this is a relative specifier (starts with `.` or `..`)
     pub fn is_relative(specifier: &str) -> bool {
-        specifier.starts_with("./") || specifier.starts_with("../")
+        specifier.starts_with("./")
+            || specifier.starts_with("../")
+            || specifier.starts_with(".\\")
+            || specifier.starts_with("..\\")
     }
 
     /// Check if this is a bare specifier (not relative, not absolute)
     /// e.g., "lodash", "react", "tsrun:host"
     pub fn is_bare(specifier: &str) -> bool {
-        !specifier.starts_with('/') && !Self::is_relative(specifier)
+        !Self::is_absolute(specifier) && !Self::is_relative(specifier)
+    }
+
+    /// Classify a specifier as relative, absolute or bare
+    pub fn kind(specifier: &str) -> ImportKind {
+        if Self::is_relative(specifier) {
+            ImportKind::Relative
+        } else if Self::is_absolute(specifier) {
+            ImportKind::Absolute
+        } else {
+            ImportKind::Bare
+        }
+    }
+
+    /// A POSIX, Windows or URL absolute path
+    fn is_absolute(specifier: &str) -> bool {
+        specifier.starts_with('/')
+            || specifier.starts_with("\\\\")
+            || drive_letter(specifier).is_some()
+            || url_root_len(specifier).is_some()
+    }
+
+    /// Normalize the path of an entry module given to `prepare`/`eval`.
+    ///
+    /// A path without a leading `/` (`main.ts`, `./src/main.ts`) is taken to be
+    /// relative to an implicit root, so `main.ts` becomes `/main.ts` and its
+    /// relative imports resolve to absolute paths like `/lib/util` instead of
+    /// names that would collide with bare specifiers. Windows paths
+    /// (`C:\src\main.ts`) and URLs are absolute already.
+    pub fn entry(path: impl Into<String>) -> ModulePath {
+        let path = path.into();
+        if Self::is_absolute(&path) {
+            ModulePath(Self::normalize_path(&path, UrlQuery::Keep))
+        } else {
+            ModulePath(Self::normalize_path(&format!("/{}", path), UrlQuery::Keep))
+        }
     }
 
     /// Resolve a specifier relative to a base path.
     ///
     /// - Relative specifiers (`./foo`, `../bar`) are resolved against the base's directory
-    /// - Absolute specifiers (`/foo/bar`) are normalized and returned as-is
+    /// - Absolute specifiers (`/foo/bar`, `C:\foo`, `https://host/foo`) are
+    ///   normalized; `/foo` keeps the drive, share or origin of the base
     /// - Bare specifiers (`lodash`) are returned as-is (for the host to resolve)
     pub fn resolve(specifier: &str, base: Option<&ModulePath>) -> ModulePath {
+        Self::resolve_with(specifier, base, UrlQuery::Keep)
+    }
+
+    /// [`Self::resolve`] with the query of URL paths handled as `query` says
+    pub fn resolve_with(specifier: &str, base: Option<&ModulePath>, query: UrlQuery) -> ModulePath {
         if Self::is_bare(specifier) {
             // Bare specifier - return as-is for host resolution
             return ModulePath(specifier.to_string());
         }
 
-        if specifier.starts_with('/') {
-            // Absolute path - just normalize
-            return ModulePath(Self::normalize_path(specifier));
-        }
-
-        // Relative path - resolve against base
-        let base_dir = base.and_then(|b| b.parent()).unwrap_or("");
-
-        let combined = if base_dir.is_empty() {
+        let base_root = base.and_then(|b| b.0.get(..root_len(&b.0))).unwrap_or("");
+        let combined = if url_root_len(specifier).is_some()
+            || drive_letter(specifier).is_some()
+            || specifier.starts_with("\\\\")
+        {
             specifier.to_string()
+        } else if let Some(rest) = specifier.strip_prefix("//") {
+            // Protocol-relative against a URL base, a share otherwise
+            match base_root.split_once("://") {
+                Some((scheme, _)) => format!("{}://{}", scheme, rest),
+                None => specifier.to_string(),
+            }
+        } else if specifier.starts_with('/') {
+            // Root-relative: keep the drive, share or origin of the base
+            format!("{}{}", base_root, specifier)
         } else {
-            format!("{}/{}", base_dir, specifier)
+            // Relative path - resolve against base (a base at the root has parent "")
+            let specifier = if base_root.is_empty() || url_root_len(base_root).is_some() {
+                specifier.to_string()
+            } else {
+                specifier.replace('\\', "/")
+            };
+            match base.and_then(|b| b.parent()) {
+                Some(base_dir) => format!("{}/{}", base_dir, specifier),
+                None => specifier,
+            }
         };
 
-        ModulePath(Self::normalize_path(&combined))
+        ModulePath(Self::normalize_path(&combined, query))
     }
 
-    /// Normalize a path by resolving `.` and `..` segments
-    fn normalize_path(path: &str) -> String {
-        let mut segments: Vec<&str> = Vec::new();
-
-        for segment in path.split('/') {
-            match segment {
-                "" | "." => {
-                    // Skip empty segments and current directory markers
-                }
-                ".." => {
-                    // Go up one directory
-                    segments.pop();
-                }
-                s => {
-                    segments.push(s);
-                }
+    /// Normalize a path by resolving `.` and `..` segments below its root
+    fn normalize_path(path: &str, query: UrlQuery) -> String {
+        if let Some(root) = url_root_len(path) {
+            let (prefix, rest) = path.split_at(root);
+            let (rest, _fragment) = rest.split_once('#').unwrap_or((rest, ""));
+            let (rest, search) = match rest.split_once('?') {
+                Some((rest, search)) if query == UrlQuery::Keep => (rest, Some(search)),
+                Some((rest, _)) => (rest, None),
+                None => (rest, None),
+            };
+            let mut normalized = format!("{}/{}", prefix.to_lowercase(), join_segments(rest));
+            if let Some(search) = search {
+                normalized.push('?');
+                normalized.push_str(search);
             }
+            return normalized;
+        }
+
+        // Backslashes separate segments only in Windows paths
+        let is_windows = drive_letter(path).is_some() || path.starts_with("\\\\");
+        let path = if is_windows {
+            path.replace('\\', "/")
+        } else {
+            path.to_string()
+        };
+        let root = root_len(&path);
+        let (prefix, rest) = path.split_at(root);
+        if !prefix.is_empty() {
+            return format!("{}/{}", prefix, join_segments(rest));
         }
 
         // Reconstruct path
         if path.starts_with('/') {
-            format!("/{}", segments.join("/"))
+            format!("/{}", join_segments(&path))
         } else {
-            segments.join("/")
+            join_segments(&path)
+        }
+    }
+
+    /// The drive letter, lowercased, and the rest of the path, so `C:/a`
+    /// and `c:/a` are the same module
+    fn identity(&self) -> (Option<char>, &str) {
+        match drive_letter(&self.0) {
+            Some(drive) => (
+                Some(drive.to_ascii_lowercase()),
+                self.0.get(1..).unwrap_or(""),
+            ),
+            None => (None, &self.0),
         }
     }
 }
 
+/// `path`'s segments with `.` and `..` resolved, joined by `/`; `..` at the
+/// top is dropped
+fn join_segments(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            // Skip empty segments and current directory markers
+            "" | "." => {}
+            // Go up one directory, never above the root
+            ".." => {
+                segments.pop();
+            }
+            s => segments.push(s),
+        }
+    }
+    segments.join("/")
+}
+
+/// The drive letter of a Windows absolute path such as `C:\src` or `c:/src`
+fn drive_letter(path: &str) -> Option<char> {
+    let mut chars = path.chars();
+    let drive = chars.next().filter(char::is_ascii_alphabetic)?;
+    (chars.next() == Some(':') && matches!(chars.next(), Some('/' | '\\'))).then_some(drive)
+}
+
+/// Length of the `scheme://authority` root of a URL path
+fn url_root_len(path: &str) -> Option<usize> {
+    let scheme_end = path.find("://")?;
+    let scheme = path.get(..scheme_end)?;
+    // One letter before `:` is a drive, not a scheme
+    let is_scheme = scheme.len() > 1
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    if !is_scheme {
+        return None;
+    }
+    let authority_start = scheme_end + 3;
+    let authority_len = path
+        .get(authority_start..)?
+        .find(['/', '?', '#'])
+        .unwrap_or(path.len() - authority_start);
+    Some(authority_start + authority_len)
+}
+
+/// Length of the root `..` stops at in a normalized path: a URL's
+/// `scheme://authority`, a drive (`C:`) or a share (`//server/share`), or 0
+/// for POSIX and relative paths
+fn root_len(path: &str) -> usize {
+    if let Some(root) = url_root_len(path) {
+        return root;
+    }
+    if drive_letter(path).is_some() {
+        return 2;
+    }
+    if let Some(rest) = path.strip_prefix("//") {
+        // Server and share name
+        let mut end = 2;
+        for (i, part) in rest.splitn(3, '/').take(2).enumerate() {
+            end += part.len() + usize::from(i > 0);
+        }
+        return end;
+    }
+    0
+}
+
+impl PartialEq for ModulePath {
+    fn eq(&self, other: &Self) -> bool {
+        self.identity() == other.identity()
+    }
+}
+
+impl Eq for ModulePath {}
+
+impl core::hash::Hash for ModulePath {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.identity().hash(state);
+    }
+}
+
 impl core::fmt::Display for ModulePath {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.0)
@@ -924,6 +1643,53 @@ impl From<String> for ModulePath {
     }
 }
 
+/// How an import specifier is written, which determines how it was resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImportKind {
+    /// `./foo` or `../foo`, resolved against the importing module's directory
+    Relative,
+    /// `/foo`, normalized independently of the importer
+    Absolute,
+    /// `lodash`, `@scope/pkg`: passed through unchanged for the host to resolve
+    Bare,
+}
+
+/// The language a module's source is parsed as.
+///
+/// JavaScript mode turns off TypeScript-only syntax: type annotations,
+/// angle-bracket assertions, `as` expressions, non-null `!`, enums and
+/// namespaces are syntax errors, `type`, `interface`, `namespace` and the
+/// other TypeScript contextual keywords are ordinary identifiers, and `<`
+/// never starts type arguments, so `a < b > (c)` is two comparisons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Lang {
+    /// TypeScript, a superset of JavaScript
+    #[default]
+    TypeScript,
+    /// Plain JavaScript
+    JavaScript,
+}
+
+impl Lang {
+    /// The language of `path` by its extension: `.js`, `.mjs`, `.cjs` and
+    /// `.jsx` are JavaScript, `.ts`, `.mts`, `.cts` and `.tsx` TypeScript.
+    /// Other paths, and no path, are `default`.
+    pub fn infer(path: Option<&ModulePath>, default: Lang) -> Lang {
+        let Some(path) = path else {
+            return default;
+        };
+        let path = path.as_str();
+        // A URL's query and fragment aren't part of the file name
+        let path = path.split(['?', '#']).next().unwrap_or(path);
+        let file = path.rsplit('/').next().unwrap_or(path);
+        match file.rsplit_once('.').map(|(_, ext)| ext) {
+            Some("js" | "mjs" | "cjs" | "jsx") => Lang::JavaScript,
+            Some("ts" | "mts" | "cts" | "tsx") => Lang::TypeScript,
+            _ => default,
+        }
+    }
+}
+
 /// A pending import request with context about where it was requested from.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ImportRequest {
@@ -933,6 +1699,23 @@ pub struct ImportRequest {
     pub resolved_path: ModulePath,
     /// The module that requested this import (None for main module)
     pub importer: Option<ModulePath>,
+    /// The module that first requested `resolved_path` (None for main
+    /// module). Differs from `importer` when a path is surfaced again after
+    /// its `provide_module` failed, for a different importer.
+    pub first_requested_by: Option<ModulePath>,
+    /// How `specifier` is written
+    pub kind: ImportKind,
+    /// The SRI integrity the module must have, e.g. `sha256-<base64>`, from
+    /// a `with { integrity: "..." }` import attribute or the manifest set
+    /// with [`Interpreter::set_integrity_manifest`]
+    pub integrity: Option<String>,
+    /// The import attributes as written, e.g. `("type", "json")` for
+    /// `with { type: "json" }`. A `type: "json"` import is loaded as a JSON
+    /// module; other keys are host metadata.
+    pub attributes: Vec<(String, String)>,
+    /// The language the module's source will be parsed as, inferred from
+    /// `resolved_path` with [`Lang::infer`]
+    pub lang: Lang,
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -956,7 +1739,7 @@ pub struct ImportRequest {
 ///             StepResult::Continue => continue,
 ///             StepResult::Complete(val) => return val.as_number(),
 ///             StepResult::NeedImports(_) => return None, // would need module loading
-///             StepResult::Suspended { .. } => return None, // would need async handling
+///             StepResult::Suspended { .. } | StepResult::Orders(_) => return None, // would need async handling
 ///             StepResult::Done => return None,
 ///         }
 ///     }
@@ -972,27 +1755,71 @@ pub enum StepResult {
     /// Call `step()` again to continue.
     Continue,
 
-    /// Execution completed with a final value.
+    /// Execution completed with a final value: the value of the last
+    /// expression statement the entry program ran, script or module, and
+    /// `undefined` if it ran none. Imported modules don't contribute.
     Complete(RuntimeValue),
 
     /// Need these modules before execution can continue.
     /// Call `provide_module()` for each import, then call `step()` again.
+    ///
+    /// Requests come in the order their importers were requested, each
+    /// importer's in source order, so the same program asks in the same
+    /// order every run. A path is reported once until it is provided, even
+    /// when several modules import it; it is only reported again after its
+    /// `provide_module()` failed, or when `step()` is called with nothing new
+    /// to ask for while it is still awaited.
     NeedImports(Vec<ImportRequest>),
 
     /// Execution suspended waiting for orders to be fulfilled.
     /// Call `fulfill_orders()` with responses, then call `step()` again.
+    ///
+    /// The host may answer only some of the outstanding orders: `step()` then
+    /// runs everything those answers unblock, such as an async function
+    /// that awaited one of them, and suspends again while the rest are
+    /// pending. An async function awaiting an order doesn't hold up its
+    /// caller, so independent async calls wait side by side.
+    ///
+    /// Also returned, with no pending orders, to report cancellations just
+    /// before the script completes.
     Suspended {
-        /// Orders waiting for fulfillment
+        /// Orders created since the last `Suspended` or `Orders` result,
+        /// empty when the script is still waiting on earlier ones; see
+        /// `Interpreter::outstanding_orders` for every order not yet
+        /// fulfilled
         pending: Vec<Order>,
         /// Orders that were cancelled (e.g., Promise.race loser)
         cancelled: Vec<OrderId>,
     },
 
+    /// Orders were created and execution is still running
+    /// (only with `OrderDelivery::Eager`).
+    /// Start the work, call `fulfill_orders()` whenever responses are ready,
+    /// and keep calling `step()`.
+    Orders(Vec<Order>),
+
     /// No active execution to step.
     /// Call `prepare()` first to start execution.
     Done,
 }
 
+/// Progress of an incremental prepare started with `Interpreter::prepare_begin`
+#[derive(Debug)]
+pub enum PrepareProgress {
+    /// Out of budget before the source was ready to run.
+    /// Call `prepare_continue()` again, e.g. after yielding to the event loop.
+    Pending {
+        /// Source bytes parsed so far
+        parsed_bytes: usize,
+        /// Total source bytes
+        total_bytes: usize,
+    },
+
+    /// The source is parsed and compiled; this is what `prepare()` would
+    /// have returned. Continue with `step()`.
+    Ready(StepResult),
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // Internal Module System
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -1013,13 +1840,26 @@ pub enum InternalExport {
     Value(JsValue),
 }
 
+/// Resolver of a lazy internal module: the value of the named export, or
+/// `None` if the module has no such export
+pub type LazyExportFn = dyn Fn(&mut Interpreter, &str) -> Result<Option<Guarded>, JsError>;
+
+/// Export names of a lazy internal module, listed without resolving them
+pub type LazyKeysFn = dyn Fn() -> Vec<String>;
+
 /// How an internal module is defined
 #[derive(Clone)]
 pub enum InternalModuleKind {
     /// Native module with Rust functions
     Native(Vec<(String, InternalExport)>),
-    /// Source module (TypeScript code that may import from other internal modules)
+    /// Source module (TypeScript code that may import other internal modules
+    /// and modules the host provides)
     Source(String),
+    /// Native module whose exports are resolved by Rust on first access
+    Lazy {
+        resolver: Rc<LazyExportFn>,
+        own_keys: Option<Rc<LazyKeysFn>>,
+    },
 }
 
 /// Definition of an internal module that can be imported from JavaScript.
@@ -1061,6 +1901,9 @@ pub struct InternalModule {
     pub specifier: String,
     /// How the module is implemented
     pub kind: InternalModuleKind,
+    /// Keep the evaluated namespace across [`Interpreter::reset`], see
+    /// [`InternalModule::retain_across_reset`]
+    pub retain_across_reset: bool,
 }
 
 impl InternalModule {
@@ -1077,6 +1920,63 @@ impl InternalModule {
         Self {
             specifier: specifier.into(),
             kind: InternalModuleKind::Source(source.into()),
+            retain_across_reset: false,
+        }
+    }
+
+    /// Create a lazy module builder.
+    ///
+    /// `resolver` is called with an export's name the first time a script
+    /// reads it, through an import binding or the namespace object; the
+    /// value is kept for later reads. An error it returns is thrown at the
+    /// read, where the script can catch it.
+    pub fn lazy(specifier: impl Into<String>, resolver: Box<LazyExportFn>) -> LazyModuleBuilder {
+        LazyModuleBuilder {
+            specifier: specifier.into(),
+            resolver: Rc::from(resolver),
+            own_keys: None,
+        }
+    }
+
+    /// Keep the module's namespace across [`Interpreter::reset`].
+    ///
+    /// The module is evaluated the first time a run imports it and never
+    /// again; later runs share its namespace. The namespace itself is sealed,
+    /// but changes scripts make to the objects it exports, and to the
+    /// module's own `let` bindings through its functions, persist across
+    /// resets. Export frozen values to keep runs independent.
+    pub fn retain_across_reset(mut self, retain: bool) -> Self {
+        self.retain_across_reset = retain;
+        self
+    }
+}
+
+/// Builder for creating lazy internal modules, see [`InternalModule::lazy`]
+pub struct LazyModuleBuilder {
+    specifier: String,
+    resolver: Rc<LazyExportFn>,
+    own_keys: Option<Rc<LazyKeysFn>>,
+}
+
+impl LazyModuleBuilder {
+    /// List the module's exports for namespace enumeration (`Object.keys`,
+    /// `for-in`, `in`) without resolving their values.
+    ///
+    /// Without it the namespace only lists exports already read.
+    pub fn with_own_keys(mut self, own_keys: Box<LazyKeysFn>) -> Self {
+        self.own_keys = Some(Rc::from(own_keys));
+        self
+    }
+
+    /// Build the internal module
+    pub fn build(self) -> InternalModule {
+        InternalModule {
+            specifier: self.specifier,
+            kind: InternalModuleKind::Lazy {
+                resolver: self.resolver,
+                own_keys: self.own_keys,
+            },
+            retain_across_reset: false,
         }
     }
 }
@@ -1113,6 +2013,7 @@ impl NativeModuleBuilder {
         InternalModule {
             specifier: self.specifier,
             kind: InternalModuleKind::Native(self.exports),
+            retain_across_reset: false,
         }
     }
 }
@@ -1122,7 +2023,6 @@ impl NativeModuleBuilder {
 // ═══════════════════════════════════════════════════════════════════════════════
 
 /// Configuration for creating an Interpreter
-#[derive(Default)]
 pub struct InterpreterConfig {
     /// Internal modules available for import
     pub internal_modules: Vec<InternalModule>,
@@ -1133,4 +2033,484 @@ pub struct InterpreterConfig {
     /// - `FancyRegexProvider` when `regex` feature is enabled
     /// - `NoOpRegExpProvider` otherwise
     pub regexp_provider: Option<Rc<dyn platform::RegExpProvider>>,
+
+    /// Keep the source text of compiled functions so `Function.prototype.toString`
+    /// can return it (default: `true`).
+    ///
+    /// When disabled, program source is dropped after parsing and user functions
+    /// stringify in the `function name() { [native code] }` form.
+    pub retain_function_source: bool,
+
+    /// Maximum length of a string in UTF-8 bytes (default: 256 MiB).
+    ///
+    /// Builtins such as `repeat`, `padStart`, `join`, `JSON.stringify` and the
+    /// `+` operator throw `RangeError: Invalid string length` instead of
+    /// allocating past this limit.
+    pub max_string_len: usize,
+
+    /// Maximum array length (default: 2^25 elements).
+    ///
+    /// `new Array(n)`, `fill` and element writes that would grow an array past
+    /// this limit throw `RangeError: Invalid array length`.
+    pub max_array_len: usize,
+
+    /// Most arguments a single call may pass (default: 65535).
+    ///
+    /// `fn(...values)`, `new C(...values)`, `super(...values)`,
+    /// `Function.prototype.apply`, `Reflect.apply` and `Reflect.construct`
+    /// throw `RangeError: Too many arguments in function call` instead of
+    /// passing more.
+    pub max_call_arguments: usize,
+
+    /// Deepest call nesting before calls throw `RangeError: Maximum call
+    /// stack size exceeded` (default: 10 000).
+    ///
+    /// Counts every function frame plus every level of native recursion:
+    /// getters, setters, proxy traps, `toString`/`valueOf` coercion and other
+    /// callbacks run by builtins, and builtins walking nested values such as
+    /// `flat` and `structuredClone`. Native levels also use the Rust stack,
+    /// so at most [`MAX_NATIVE_CALL_DEPTH`] of them are allowed whatever this
+    /// limit; see [`Interpreter::call_depth`].
+    pub max_call_depth: usize,
+
+    /// Most instructions the compiler emits for one function, or for the top
+    /// level of one module or script (default: 500 000).
+    ///
+    /// Compiling stops with [`JsError::CompileLimit`] once a function passes
+    /// it, so a generated function with hundreds of thousands of statements
+    /// fails in `prepare` instead of producing a multi-megabyte chunk.
+    pub max_chunk_instructions: usize,
+
+    /// Most constant pool entries of one function (default and upper bound:
+    /// 65535): strings, numbers and nested functions it uses.
+    pub max_chunk_constants: usize,
+
+    /// Deepest nesting of functions, classes and arrows inside one another
+    /// the compiler accepts (default: 128).
+    pub max_function_depth: usize,
+
+    /// Most instructions one compile may emit across a module or script and
+    /// all the functions in it (default: 4 000 000).
+    pub max_program_instructions: usize,
+
+    /// Memory the interpreter may use, `None` for unlimited (default: `None`).
+    ///
+    /// GC objects, the strings they hold and compiled bytecode are counted
+    /// against the budget, coarsely; see [`HeapBudget`] for what is counted
+    /// when. Passing the limit, or building a string larger than what is
+    /// left, fails with [`TerminationReason::HeapBudgetExceeded`], which
+    /// scripts cannot catch. Keep a clone of the budget to watch usage.
+    pub heap_budget: Option<HeapBudget>,
+
+    /// Longest console message in UTF-8 bytes (default: 64 KiB).
+    ///
+    /// Longer messages are cut short and end with `… (N more characters)`.
+    /// Objects and arrays stop being formatted once this budget is spent, so a
+    /// huge value is never stringified in full just to be truncated.
+    pub max_console_message_len: usize,
+
+    /// Total console output in UTF-8 bytes, `None` for unlimited (default: `None`).
+    ///
+    /// Once reached, a single `console output limit reached` warning is written
+    /// and later messages are dropped; see [`Interpreter::console_dropped_messages`].
+    pub max_console_output: Option<usize>,
+
+    /// Keep the main module's top-level scope after completion (default: `false`).
+    ///
+    /// Required for `Interpreter::eval_in_context`. The retained scope keeps
+    /// every value reachable from top-level bindings alive.
+    pub retain_main_scope: bool,
+
+    /// Development mode: reading a property that module namespaces and host
+    /// context objects don't have throws a `TypeError` instead of giving
+    /// `undefined` (default: `false`).
+    ///
+    /// Catches typos such as `cfg.replcias` where they happen, with the
+    /// closest own key suggested: `Property 'replcias' does not exist on
+    /// module '/lib/config'; did you mean 'replicas'?`. Host context objects
+    /// are those frozen with [`Interpreter::deep_freeze`] or created by
+    /// [`Interpreter::create_from_json_cached`]. Only string keys are checked,
+    /// not array indices or symbols, and other objects keep normal semantics.
+    pub strict_property_access: bool,
+
+    /// Pre-evaluated modules to import without parsing, compiling or
+    /// `NeedImports` (default: `None`).
+    ///
+    /// Registered internal modules with the same specifier take precedence.
+    pub shared_modules: Option<Arc<SharedModuleSet>>,
+
+    /// Let scripts compile code from strings at runtime (default: `false`).
+    ///
+    /// Gates the `Function` constructor and indirect eval (`(0, eval)(src)`),
+    /// which throw `EvalError` while disabled. Direct `eval(src)` calls are
+    /// not affected.
+    pub allow_dynamic_code: bool,
+
+    /// Write the disassembly of every chunk compiled for execution to the
+    /// console provider at `Debug` level (default: `false`).
+    ///
+    /// Meant for checking what the compiler emitted in embeddings without a
+    /// debugger; see [`Interpreter::disassemble`] to compile without running.
+    pub dump_bytecode: bool,
+
+    /// Compile reads of imported constants as the constants themselves
+    /// (default: `false`).
+    ///
+    /// Each module is compiled after the modules it imports have run, so a
+    /// read of an imported `const` primitive, or a chain of static property
+    /// names from an import through frozen objects and arrays to a primitive
+    /// (`limits.cpu.default`), loads the value directly instead of looking
+    /// it up on every evaluation. Anything that could read differently later,
+    /// such as `let` exports, objects that aren't frozen and computed keys,
+    /// is compiled as usual. Best with [`Interpreter::prepare_with_modules`],
+    /// which knows every module before the run starts: if any of them
+    /// mentions `eval` or `Function`, the run folds nothing. A run that
+    /// folded an import cannot be checkpointed.
+    pub fold_constant_imports: bool,
+
+    /// Callbacks reporting progress through the module graph (default: none)
+    pub module_hooks: ModuleHooks,
+
+    /// Most arrays and objects kept by [`Interpreter::create_from_json_cached`]
+    /// for reuse (default: 65536). Past this, the least recently used quarter
+    /// is evicted; 0 disables caching.
+    pub json_cache_capacity: usize,
+
+    /// How much each [`Interpreter::step`] executes (default:
+    /// [`StepGranularity::Instruction`]).
+    pub step_granularity: StepGranularity,
+
+    /// Give entry scripts run without a module path browser-style global
+    /// scope (default: `false`).
+    ///
+    /// Top-level `var` and function declarations become non-configurable
+    /// properties of `globalThis`, with functions created before the script
+    /// runs. `let`, `const` and `class` stay global lexical bindings. Both
+    /// persist across runs, and a later script declaring a name that clashes
+    /// with them fails with `SyntaxError: Identifier 'x' has already been
+    /// declared` before any of it runs.
+    pub script_globals: bool,
+
+    /// Record where every object and array literal was evaluated and which
+    /// function returned it, for [`Interpreter::explain`] (default: `false`).
+    ///
+    /// Meant for debugging how a result was assembled: the sites live in a
+    /// side table that keeps every tracked object alive.
+    pub track_provenance: bool,
+
+    /// Keep a ring buffer of recent VM activity and attach its tail to
+    /// script errors leaving `step()` or `eval` (default: `false`).
+    ///
+    /// Records function calls and returns, property reads that found
+    /// nothing, orders created and fulfilled, and modules starting and
+    /// finishing; see [`JsError::flight_log`].
+    pub flight_recorder: bool,
+
+    /// Most events the flight recorder keeps (default: 256)
+    pub flight_recorder_capacity: usize,
+
+    /// Most orders the script may have outstanding, neither fulfilled nor
+    /// cancelled, before creating another fails (default: 10 000).
+    ///
+    /// Stops a script firing requests in a loop without awaiting them from
+    /// exhausting memory; see [`Interpreter::pending_order_count`].
+    pub max_pending_orders: usize,
+
+    /// What creating an order past [`Self::max_pending_orders`] does
+    /// (default: [`PendingOrderOverflow::Throw`]).
+    pub pending_order_overflow: PendingOrderOverflow,
+
+    /// What a provided module that fails to parse or compile does (default:
+    /// [`ModuleErrorPolicy::FailFast`]).
+    pub module_error_policy: ModuleErrorPolicy,
+
+    /// Idle suspensions in a row after which [`Interpreter::step`] fails with
+    /// [`JsError::Stalled`] (default: 2); 0 turns detection off.
+    ///
+    /// A suspension is idle when `step()` returns `Suspended` with no new or
+    /// cancelled orders and neither ran an instruction nor saw an order
+    /// fulfilled since the previous one: the host is stepping a script that
+    /// no longer waits on anything it will deliver, for instance after
+    /// fulfilling the wrong `OrderId`.
+    pub stall_threshold: usize,
+
+    /// What resolving an import does with the `?query` of a URL module path
+    /// (default: [`UrlQuery::Keep`]); the `#fragment` is always dropped.
+    pub url_query: UrlQuery,
+
+    /// The language of modules whose path doesn't tell, and of code without
+    /// a path (default: [`Lang::TypeScript`]). A `.js` path is parsed as
+    /// JavaScript and a `.ts` path as TypeScript whatever this says; see
+    /// [`Lang::infer`].
+    pub default_lang: Lang,
+
+    /// Build host JSON values with null prototypes all the way down (default:
+    /// false), as [`api::create_from_json_detached`] does: applies to
+    /// [`api::create_from_json`], [`api::create_response_object`] and the
+    /// responses of [`ResponseFactory::json`], so context and order responses
+    /// carry no path to `Object.prototype` or `Function`.
+    pub detach_host_json: bool,
+
+    /// Rewrite module sources before they are parsed (default: none).
+    ///
+    /// Sees the entry source given to `eval`, `prepare`, `parse` or an
+    /// incremental prepare (an entry without a path as `<main>`), every
+    /// module the host provides, including through the module source map,
+    /// and internal source modules. Each module is transformed once, when it
+    /// is parsed; re-running a prepared program does not transform it again.
+    /// Integrity checks apply to the source as provided. An `Err` fails with
+    /// [`JsError::TransformError`].
+    ///
+    /// Error positions and `Function.prototype.toString` refer to the
+    /// transformed text, so a transform that keeps lines in place (blanking
+    /// a pragma rather than removing its line) keeps positions meaningful.
+    pub source_transform: Option<SourceTransform>,
+
+    /// Choose the order provided modules run in (default: none).
+    ///
+    /// Modules run once all their imports have: by default, those that
+    /// become ready together run in the order they were first imported,
+    /// after the modules they depend on. With a callback, each time a set
+    /// of modules is ready it is called with them in that default order and
+    /// returns the ones to run now, in the order to run them. Modules it
+    /// leaves out stay ready and are offered again in the next round, along
+    /// with those the chosen ones unblock.
+    ///
+    /// Returning a path that is not among those offered, or the same path
+    /// twice, or none at all, fails with a [`JsError::ModuleError`].
+    pub module_execution_order: Option<ModuleExecutionOrder>,
+}
+
+/// Default for [`InterpreterConfig::max_string_len`]
+pub const DEFAULT_MAX_STRING_LEN: usize = 256 * 1024 * 1024;
+
+/// Default for [`InterpreterConfig::max_array_len`]
+pub const DEFAULT_MAX_ARRAY_LEN: usize = 1 << 25;
+
+/// Default for [`InterpreterConfig::max_call_arguments`]
+pub const DEFAULT_MAX_CALL_ARGUMENTS: usize = 65535;
+
+/// Default for [`InterpreterConfig::max_call_depth`]
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 10_000;
+
+/// Default for [`InterpreterConfig::max_chunk_instructions`]
+pub const DEFAULT_MAX_CHUNK_INSTRUCTIONS: usize = 500_000;
+
+/// Default for [`InterpreterConfig::max_chunk_constants`]
+pub const DEFAULT_MAX_CHUNK_CONSTANTS: usize = 65535;
+
+/// Default for [`InterpreterConfig::max_function_depth`]
+pub const DEFAULT_MAX_FUNCTION_DEPTH: usize = 128;
+
+/// Default for [`InterpreterConfig::max_program_instructions`]
+pub const DEFAULT_MAX_PROGRAM_INSTRUCTIONS: usize = 4_000_000;
+
+/// Most levels of native recursion in progress at once, each of which takes
+/// Rust stack; see [`InterpreterConfig::max_call_depth`]
+pub const MAX_NATIVE_CALL_DEPTH: usize = 256;
+
+/// Default for [`InterpreterConfig::max_console_message_len`]
+pub const DEFAULT_MAX_CONSOLE_MESSAGE_LEN: usize = 64 * 1024;
+
+/// Default for [`InterpreterConfig::max_pending_orders`]
+pub const DEFAULT_MAX_PENDING_ORDERS: usize = 10_000;
+
+/// Default for [`InterpreterConfig::stall_threshold`]
+pub const DEFAULT_STALL_THRESHOLD: usize = 2;
+
+/// Default for [`InterpreterConfig::flight_recorder_capacity`]
+pub const DEFAULT_FLIGHT_RECORDER_CAPACITY: usize = 256;
+
+/// Default for [`InterpreterConfig::json_cache_capacity`]
+pub const DEFAULT_JSON_CACHE_CAPACITY: usize = 64 * 1024;
+
+impl Default for InterpreterConfig {
+    fn default() -> Self {
+        Self {
+            internal_modules: Vec::new(),
+            regexp_provider: None,
+            retain_function_source: true,
+            max_string_len: DEFAULT_MAX_STRING_LEN,
+            max_array_len: DEFAULT_MAX_ARRAY_LEN,
+            max_call_arguments: DEFAULT_MAX_CALL_ARGUMENTS,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            max_chunk_instructions: DEFAULT_MAX_CHUNK_INSTRUCTIONS,
+            max_chunk_constants: DEFAULT_MAX_CHUNK_CONSTANTS,
+            max_function_depth: DEFAULT_MAX_FUNCTION_DEPTH,
+            max_program_instructions: DEFAULT_MAX_PROGRAM_INSTRUCTIONS,
+            heap_budget: None,
+            max_console_message_len: DEFAULT_MAX_CONSOLE_MESSAGE_LEN,
+            max_console_output: None,
+            retain_main_scope: false,
+            strict_property_access: false,
+            shared_modules: None,
+            allow_dynamic_code: false,
+            dump_bytecode: false,
+            fold_constant_imports: false,
+            module_hooks: ModuleHooks::default(),
+            json_cache_capacity: DEFAULT_JSON_CACHE_CAPACITY,
+            step_granularity: StepGranularity::Instruction,
+            script_globals: false,
+            track_provenance: false,
+            flight_recorder: false,
+            flight_recorder_capacity: DEFAULT_FLIGHT_RECORDER_CAPACITY,
+            max_pending_orders: DEFAULT_MAX_PENDING_ORDERS,
+            pending_order_overflow: PendingOrderOverflow::Throw,
+            module_error_policy: ModuleErrorPolicy::FailFast,
+            stall_threshold: DEFAULT_STALL_THRESHOLD,
+            url_query: UrlQuery::Keep,
+            default_lang: Lang::TypeScript,
+            detach_host_json: false,
+            source_transform: None,
+            module_execution_order: None,
+        }
+    }
+}
+
+/// [`InterpreterConfig::source_transform`] callback: `(path, source)` to the
+/// source to parse, or a message for [`JsError::TransformError`]
+pub type SourceTransform = Box<dyn Fn(&ModulePath, &str) -> Result<String, String>>;
+
+/// [`InterpreterConfig::module_execution_order`] callback: the ready modules
+/// to the ones to run now, in order
+pub type ModuleExecutionOrder = Box<dyn Fn(&[ModulePath]) -> Vec<ModulePath>>;
+
+/// [`ModuleHooks::on_module_start`] callback: `(path)`
+pub type ModuleStartHook = Box<dyn Fn(&ModulePath)>;
+
+/// [`ModuleHooks::on_module_loaded`] callback: `(path, parse_ms)`
+pub type ModuleLoadedHook = Box<dyn Fn(&ModulePath, u64)>;
+
+/// [`ModuleHooks::on_module_executed`] callback: `(path, exec_ms, export_count)`
+pub type ModuleExecutedHook = Box<dyn Fn(&ModulePath, u64, usize)>;
+
+/// [`ModuleHooks::on_import_requested`] callback
+pub type ImportRequestedHook = Box<dyn Fn(&ImportRequest)>;
+
+/// Optional callbacks that let the host follow module loading, e.g. to show
+/// progress while a large graph is fed in through repeated `NeedImports`.
+///
+/// Hooks only observe: they get no access to the interpreter and so cannot
+/// re-enter evaluation. Timings are milliseconds measured with the
+/// interpreter's `TimeProvider`, and nothing is timed for hooks left unset.
+///
+/// ```
+/// use tsrun::{Interpreter, InterpreterConfig, ModuleHooks};
+///
+/// let interp = Interpreter::with_config(InterpreterConfig {
+///     module_hooks: ModuleHooks {
+///         on_module_executed: Some(Box::new(|path, ms, exports| {
+///             println!("{} ran in {}ms with {} exports", path, ms, exports);
+///         })),
+///         ..Default::default()
+///     },
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Default)]
+pub struct ModuleHooks {
+    /// Called when the host provides a module, before it is parsed
+    pub on_module_start: Option<ModuleStartHook>,
+
+    /// Called once a provided module has parsed, with the parse time
+    pub on_module_loaded: Option<ModuleLoadedHook>,
+
+    /// Called after a module's body has run, with the execution time and
+    /// the number of exports in its namespace
+    pub on_module_executed: Option<ModuleExecutedHook>,
+
+    /// Called the first time an import is handed to the host in `NeedImports`
+    pub on_import_requested: Option<ImportRequestedHook>,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Execution Limits
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Why the interpreter stopped a script that had not finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminationReason {
+    /// The budget set with `Interpreter::set_fuel` ran out
+    FuelExhausted,
+    /// `InterruptHandle::interrupt` was called
+    Interrupted,
+    /// A module checked with `PurityMode::Raise` had a side effect
+    PurityViolation,
+    /// Memory use passed [`InterpreterConfig::heap_budget`]
+    HeapBudgetExceeded,
+}
+
+/// Which compile-time limit a [`JsError::CompileLimit`] passed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompileLimitKind {
+    /// [`InterpreterConfig::max_chunk_instructions`]
+    ChunkInstructions,
+    /// [`InterpreterConfig::max_chunk_constants`]
+    ChunkConstants,
+    /// [`InterpreterConfig::max_function_depth`]
+    FunctionDepth,
+    /// [`InterpreterConfig::max_program_instructions`]
+    ProgramInstructions,
+}
+
+/// Thread-safe flag that stops a running interpreter.
+///
+/// Obtained from `Interpreter::interrupt_handle`. While the flag is raised,
+/// the next VM instruction or builtin loop iteration fails with
+/// `JsError::Terminated`, so a watchdog thread can enforce a wall-clock
+/// timeout even while a single builtin call is running.
+///
+/// The handle also carries the softer cancellation request of
+/// `Interpreter::request_cancellation`, which scripts observe at their own
+/// `checkpoint()` calls.
+#[derive(Debug, Clone, Default)]
+pub struct InterruptHandle(Arc<InterruptFlags>);
+
+#[derive(Debug, Default)]
+struct InterruptFlags {
+    interrupted: core::sync::atomic::AtomicBool,
+    cancellation: core::sync::atomic::AtomicBool,
+}
+
+impl InterruptHandle {
+    /// Ask the interpreter to stop at its next check
+    pub fn interrupt(&self) {
+        self.0
+            .interrupted
+            .store(true, core::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Lower the flag so the interpreter can run again
+    pub fn reset(&self) {
+        self.0
+            .interrupted
+            .store(false, core::sync::atomic::Ordering::Relaxed);
+    }
+
+    pub fn is_interrupted(&self) -> bool {
+        self.0
+            .interrupted
+            .load(core::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Ask the script to wind down: its next `checkpoint()` throws a
+    /// `CancellationError` without a reason. Scripts that never call
+    /// `checkpoint()` keep running.
+    pub fn request_cancellation(&self) {
+        self.0
+            .cancellation
+            .store(true, core::sync::atomic::Ordering::Relaxed);
+    }
+
+    pub fn is_cancellation_requested(&self) -> bool {
+        self.0
+            .cancellation
+            .load(core::sync::atomic::Ordering::Relaxed)
+    }
+
+    pub(crate) fn clear_cancellation(&self) {
+        self.0
+            .cancellation
+            .store(false, core::sync::atomic::Ordering::Relaxed);
+    }
 }
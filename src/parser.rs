@@ -2,13 +2,25 @@
 //!
 //! Uses recursive descent with Pratt parsing for expressions.
 
+use crate::Lang;
 use crate::ast::*;
 use crate::error::JsError;
-use crate::lexer::{Lexer, Span, Token, TokenKind};
+use crate::lexer::{Lexer, LexerCheckpoint, Span, Token, TokenKind};
 use crate::prelude::*;
 use crate::string_dict::StringDict;
 use crate::value::JsString;
 
+/// Where a top-level parse stopped; see [`Parser::checkpoint`]
+#[derive(Clone)]
+pub struct ParseCheckpoint {
+    lexer: LexerCheckpoint,
+    current: Token,
+    previous: Token,
+}
+
+/// Hint for JSX, which needs a compile step before tsrun can run it
+const JSX_HINT: &str = "compile JSX to JavaScript (e.g. with tsc or esbuild) before running it";
+
 /// Parser for TypeScript source code
 pub struct Parser<'a> {
     lexer: Lexer<'a>,
@@ -18,6 +30,8 @@ pub struct Parser<'a> {
     /// This is used in for-loop init expressions where 'in' separates
     /// the variable from the iterable (for x in obj).
     no_in: bool,
+    /// The language parsed; JavaScript turns TypeScript-only syntax off
+    lang: Lang,
 }
 
 impl<'a> Parser<'a> {
@@ -29,27 +43,99 @@ impl<'a> Parser<'a> {
             current,
             previous: Token::eof(0, 1, 1),
             no_in: false,
+            lang: Lang::TypeScript,
         }
     }
 
+    /// Parse the source as `lang` (TypeScript by default)
+    pub fn with_lang(mut self, lang: Lang) -> Self {
+        self.lang = lang;
+        let current = mem::replace(&mut self.current, Token::eof(0, 1, 1));
+        self.current = self.lang_token(current);
+        self
+    }
+
     /// Helper to intern a string in the dictionary
     #[inline]
     fn intern(&mut self, s: &str) -> JsString {
         self.lexer.string_dict().get_or_insert(s)
     }
 
-    /// Parse a complete program
-    pub fn parse_program(&mut self) -> Result<Program, JsError> {
-        let mut body = Vec::new();
+    /// Resume a top-level parse of `source` where `checkpoint` was taken
+    pub fn resume(
+        source: &'a str,
+        string_dict: &'a mut StringDict,
+        checkpoint: ParseCheckpoint,
+    ) -> Self {
+        let mut lexer = Lexer::new(source, string_dict);
+        lexer.restore(checkpoint.lexer);
+        Self {
+            lexer,
+            current: checkpoint.current,
+            previous: checkpoint.previous,
+            no_in: false,
+            lang: Lang::TypeScript,
+        }
+    }
+
+    /// Parser state between two top-level statements, for [`Parser::resume`]
+    pub fn checkpoint(&self) -> ParseCheckpoint {
+        ParseCheckpoint {
+            lexer: self.lexer.checkpoint(),
+            current: self.current.clone(),
+            previous: self.previous.clone(),
+        }
+    }
 
+    /// Parse top-level statements into `body` until the source ends or the
+    /// parser has moved at least `budget` bytes past where it started.
+    ///
+    /// Statements are never split, so a single large statement can exceed the
+    /// budget. Returns true once the whole source has been parsed.
+    pub fn parse_statements(
+        &mut self,
+        body: &mut Vec<Statement>,
+        budget: usize,
+    ) -> Result<bool, JsError> {
+        let start = self.current.span.start;
         while !self.is_at_end() {
             body.push(self.parse_statement()?);
+            if self.current.span.start.saturating_sub(start) >= budget {
+                break;
+            }
         }
+        Ok(self.is_at_end())
+    }
+
+    /// Byte offset of the next token to parse
+    pub fn position(&self) -> usize {
+        self.current.span.start
+    }
+
+    /// Parse a complete program
+    pub fn parse_program(&mut self) -> Result<Program, JsError> {
+        let mut body = Vec::new();
+        self.parse_statements(&mut body, usize::MAX)?;
+        Ok(self.finish_program(body))
+    }
 
-        Ok(Program {
+    /// Build the program from top-level statements parsed from this source
+    pub fn finish_program(&self, body: Vec<Statement>) -> Program {
+        // Any import/export declaration makes this module code (implicitly strict)
+        let source_type = if body
+            .iter()
+            .any(|stmt| matches!(stmt, Statement::Import(_) | Statement::Export(_)))
+        {
+            SourceType::Module
+        } else {
+            SourceType::Script
+        };
+
+        Program {
             body: body.into(),
-            source_type: SourceType::Script,
-        })
+            source_type,
+            source: Some(Rc::from(self.lexer.source())),
+        }
     }
 
     // ============ DECORATORS ============
@@ -110,10 +196,9 @@ impl<'a> Parser<'a> {
                 }
                 return Ok(Statement::Export(Box::new(export_decl)));
             } else {
-                return Err(JsError::syntax_error(
+                return Err(JsError::syntax_error_at(
                     "Decorators can only be applied to class declarations".to_string(),
-                    self.current.span.line,
-                    self.current.span.column,
+                    &self.current.span,
                 ));
             }
         }
@@ -146,9 +231,10 @@ impl<'a> Parser<'a> {
             ))),
             TokenKind::Async => {
                 // async function declaration
+                let start = self.current.span;
                 self.advance(); // consume 'async'
                 self.require_token(&TokenKind::Function)?;
-                let mut func = self.parse_function_declaration_inner()?;
+                let mut func = self.parse_function_declaration_inner(start)?;
                 func.async_ = true;
                 Ok(Statement::FunctionDeclaration(Box::new(func)))
             }
@@ -325,13 +411,12 @@ impl<'a> Parser<'a> {
     fn validate_binding_identifier(&self, id: &Identifier) -> Result<(), JsError> {
         let name = id.name.as_ref();
         if name == "eval" || name == "arguments" {
-            return Err(JsError::syntax_error(
+            return Err(JsError::syntax_error_at(
                 format!(
                     "'{}' cannot be used as a binding identifier in strict mode",
                     name
                 ),
-                id.span.line,
-                id.span.column,
+                &id.span,
             ));
         }
         Ok(())
@@ -480,15 +565,19 @@ impl<'a> Parser<'a> {
         &mut self,
         is_async: bool,
     ) -> Result<FunctionDeclaration, JsError> {
+        let start = self.current.span;
         self.require_token(&TokenKind::Function)?;
-        let mut func = self.parse_function_declaration_inner()?;
+        let mut func = self.parse_function_declaration_inner(start)?;
         func.async_ = is_async;
         Ok(func)
     }
 
-    fn parse_function_declaration_inner(&mut self) -> Result<FunctionDeclaration, JsError> {
-        let start = self.current.span;
-
+    /// Parse the rest of a function declaration after `function`.
+    /// `start` is the span of the first token (`async` or `function`).
+    fn parse_function_declaration_inner(
+        &mut self,
+        start: Span,
+    ) -> Result<FunctionDeclaration, JsError> {
         let generator = self.match_token(&TokenKind::Star);
         let id = if self.check_identifier() {
             Some(self.parse_identifier()?)
@@ -546,7 +635,7 @@ impl<'a> Parser<'a> {
             // Check for duplicate parameter names in strict mode
             Self::check_duplicate_params(&pattern, &mut seen_names)?;
 
-            let optional = self.match_token(&TokenKind::Question);
+            let optional = self.match_ts_token(&TokenKind::Question);
 
             let type_annotation = if self.match_token(&TokenKind::Colon) {
                 Some(Box::new(self.parse_type_annotation()?))
@@ -596,10 +685,9 @@ impl<'a> Parser<'a> {
         match pattern {
             Pattern::Identifier(id) => {
                 if !seen.insert(id.name.clone()) {
-                    return Err(JsError::syntax_error(
+                    return Err(JsError::syntax_error_at(
                         format!("Duplicate parameter name '{}' not allowed", id.name),
-                        id.span.line,
-                        id.span.column,
+                        &id.span,
                     ));
                 }
             }
@@ -715,6 +803,28 @@ impl<'a> Parser<'a> {
         let readonly = self.match_token(&TokenKind::Readonly);
         let accessor = self.match_token(&TokenKind::Accessor);
 
+        // TypeScript index signature: [key: string]: T
+        if self.check(&TokenKind::LBracket) && self.is_index_signature_start() {
+            self.advance(); // consume [
+            let key = self.parse_identifier()?;
+            self.require_token(&TokenKind::Colon)?;
+            let key_type = Box::new(self.parse_type_annotation()?);
+            self.require_token(&TokenKind::RBracket)?;
+            self.require_token(&TokenKind::Colon)?;
+            let value_type = Box::new(self.parse_type_annotation()?);
+            self.expect_semicolon()?;
+            return Ok(ClassMember::IndexSignature(IndexSignature {
+                key,
+                key_type,
+                value_type,
+                readonly,
+                span: self.span_from(start),
+            }));
+        }
+
+        // Method source text (for toString) starts after decorators and modifiers
+        let method_start = self.current.span;
+
         // Check for async method
         let is_async = self.match_token(&TokenKind::Async);
 
@@ -775,7 +885,7 @@ impl<'a> Parser<'a> {
                 body,
                 generator: is_generator,
                 async_: is_async,
-                span: self.span_from(start),
+                span: self.span_from(method_start),
             };
 
             let span = self.span_from(start);
@@ -791,7 +901,8 @@ impl<'a> Parser<'a> {
             })))
         } else {
             // Property
-            let optional = self.match_token(&TokenKind::Question);
+            let optional = self.match_ts_token(&TokenKind::Question);
+            let definite = !optional && self.match_ts_token(&TokenKind::Bang);
             let type_annotation = if self.match_token(&TokenKind::Colon) {
                 Some(Box::new(self.parse_type_annotation()?))
             } else {
@@ -815,6 +926,7 @@ impl<'a> Parser<'a> {
                 static_,
                 readonly,
                 optional,
+                definite,
                 accessor,
                 accessibility,
                 decorators,
@@ -823,6 +935,24 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Whether the `[` at the current token opens an index signature
+    /// (`[key: T]`) rather than a computed member name
+    fn is_index_signature_start(&mut self) -> bool {
+        if !self.is_typescript() {
+            return false;
+        }
+        let saved_current = self.current.clone();
+        let checkpoint = self.lexer.checkpoint();
+        self.advance(); // consume [
+        let is_signature = self.check_identifier() && {
+            self.advance();
+            self.check(&TokenKind::Colon)
+        };
+        self.current = saved_current;
+        self.lexer.restore(checkpoint);
+        is_signature
+    }
+
     fn parse_accessibility(&mut self) -> Option<Accessibility> {
         match &self.current.kind {
             TokenKind::Public => {
@@ -1349,6 +1479,9 @@ impl<'a> Parser<'a> {
 
     fn parse_enum(&mut self) -> Result<EnumDeclaration, JsError> {
         let start = self.current.span;
+        if !self.is_typescript() {
+            return Err(self.error("Enums can only be used in TypeScript files"));
+        }
         let const_ = self.match_token(&TokenKind::Const);
         self.require_token(&TokenKind::Enum)?;
         let id = self.parse_identifier()?;
@@ -1440,13 +1573,12 @@ impl<'a> Parser<'a> {
                 self.parse_ambient_global_declaration()?;
             }
             _ => {
-                return Err(JsError::syntax_error(
+                return Err(JsError::syntax_error_at(
                     format!(
                         "Expected const, let, var, function, class, namespace, module, enum, or global after 'declare', got {:?}",
                         self.current.kind
                     ),
-                    self.current.span.line,
-                    self.current.span.column,
+                    &self.current.span,
                 ));
             }
         }
@@ -1774,11 +1906,13 @@ impl<'a> Parser<'a> {
                 span: self.current.span,
             };
             self.advance();
+            let attributes = self.parse_import_attributes()?;
             self.expect_semicolon()?;
             let span = self.span_from(start);
             return Ok(ImportDeclaration {
                 specifiers,
                 source,
+                attributes,
                 type_only,
                 span,
             });
@@ -1797,11 +1931,13 @@ impl<'a> Parser<'a> {
             } else {
                 self.require_token(&TokenKind::From)?;
                 let source = self.parse_string_literal()?;
+                let attributes = self.parse_import_attributes()?;
                 self.expect_semicolon()?;
                 let span = self.span_from(start);
                 return Ok(ImportDeclaration {
                     specifiers,
                     source,
+                    attributes,
                     type_only,
                     span,
                 });
@@ -1841,17 +1977,48 @@ impl<'a> Parser<'a> {
 
         self.require_token(&TokenKind::From)?;
         let source = self.parse_string_literal()?;
+        let attributes = self.parse_import_attributes()?;
         self.expect_semicolon()?;
 
         let span = self.span_from(start);
         Ok(ImportDeclaration {
             specifiers,
             source,
+            attributes,
             type_only,
             span,
         })
     }
 
+    /// Parse import attributes after a module specifier: `with { type: "json" }`,
+    /// or the older `assert { ... }`. They are host metadata; only `integrity`
+    /// and `type: "json"` change how tsrun loads a module.
+    fn parse_import_attributes(&mut self) -> Result<Vec<ImportAttribute>, JsError> {
+        let mut attributes = Vec::new();
+        let is_attributes = self.check_keyword("with")
+            || (self.check_keyword("assert") && !self.lexer.had_newline_before());
+        if !is_attributes || !self.peek_is(&TokenKind::LBrace) {
+            return Ok(attributes);
+        }
+        self.advance();
+        self.require_token(&TokenKind::LBrace)?;
+        while !self.check(&TokenKind::RBrace) && !self.is_at_end() {
+            let key = if matches!(self.current.kind, TokenKind::String(_)) {
+                self.parse_string_literal()?.value
+            } else {
+                self.parse_identifier_name()?.name
+            };
+            self.require_token(&TokenKind::Colon)?;
+            let value = self.parse_string_literal()?;
+            attributes.push(ImportAttribute { key, value });
+            if !self.match_token(&TokenKind::Comma) {
+                break;
+            }
+        }
+        self.require_token(&TokenKind::RBrace)?;
+        Ok(attributes)
+    }
+
     fn parse_export(&mut self) -> Result<ExportDeclaration, JsError> {
         let start = self.current.span;
         self.require_token(&TokenKind::Export)?;
@@ -1878,6 +2045,7 @@ impl<'a> Parser<'a> {
                     declaration: Some(Box::new(Statement::TypeAlias(Box::new(type_alias)))),
                     specifiers: vec![],
                     source: None,
+                    attributes: Vec::new(),
                     namespace_export: None,
                     default: false,
                     type_only: false,
@@ -1902,17 +2070,17 @@ impl<'a> Parser<'a> {
                     class_decl.decorators = all_decorators;
                     Some(Box::new(Statement::ClassDeclaration(Box::new(class_decl))))
                 } else {
-                    return Err(JsError::syntax_error(
+                    return Err(JsError::syntax_error_at(
                         "Decorators can only be applied to class declarations".to_string(),
-                        self.current.span.line,
-                        self.current.span.column,
+                        &self.current.span,
                     ));
                 }
             } else if self.check(&TokenKind::Async) {
                 // export default async function
+                let start = self.current.span;
                 self.advance(); // consume 'async'
                 self.require_token(&TokenKind::Function)?;
-                let mut func = self.parse_function_declaration_inner()?;
+                let mut func = self.parse_function_declaration_inner(start)?;
                 func.async_ = true;
                 Some(Box::new(Statement::FunctionDeclaration(Box::new(func))))
             } else if self.check(&TokenKind::Function) {
@@ -1938,6 +2106,7 @@ impl<'a> Parser<'a> {
                 declaration,
                 specifiers: vec![],
                 source: None,
+                attributes: Vec::new(),
                 namespace_export: None,
                 default: true,
                 type_only,
@@ -1973,10 +2142,11 @@ impl<'a> Parser<'a> {
 
             self.require_token(&TokenKind::RBrace)?;
 
-            let source = if self.match_token(&TokenKind::From) {
-                Some(self.parse_string_literal()?)
+            let (source, attributes) = if self.match_token(&TokenKind::From) {
+                let source = self.parse_string_literal()?;
+                (Some(source), self.parse_import_attributes()?)
             } else {
-                None
+                (None, Vec::new())
             };
 
             self.expect_semicolon()?;
@@ -1986,6 +2156,7 @@ impl<'a> Parser<'a> {
                 declaration: None,
                 specifiers,
                 source,
+                attributes,
                 namespace_export: None,
                 default: false,
                 type_only,
@@ -2004,6 +2175,7 @@ impl<'a> Parser<'a> {
 
             self.require_token(&TokenKind::From)?;
             let source = Some(self.parse_string_literal()?);
+            let attributes = self.parse_import_attributes()?;
             self.expect_semicolon()?;
 
             let span = self.span_from(start);
@@ -2011,6 +2183,7 @@ impl<'a> Parser<'a> {
                 declaration: None,
                 specifiers: vec![],
                 source,
+                attributes,
                 namespace_export,
                 default: false,
                 type_only,
@@ -2025,9 +2198,10 @@ impl<'a> Parser<'a> {
             )),
             TokenKind::Async => {
                 // export async function
+                let start = self.current.span;
                 self.advance(); // consume 'async'
                 self.require_token(&TokenKind::Function)?;
-                let mut func = self.parse_function_declaration_inner()?;
+                let mut func = self.parse_function_declaration_inner(start)?;
                 func.async_ = true;
                 Some(Box::new(Statement::FunctionDeclaration(Box::new(func))))
             }
@@ -2059,6 +2233,7 @@ impl<'a> Parser<'a> {
             declaration,
             specifiers: vec![],
             source: None,
+            attributes: Vec::new(),
             namespace_export: None,
             default: false,
             type_only,
@@ -2239,13 +2414,12 @@ impl<'a> Parser<'a> {
             if op == UnaryOp::Delete
                 && let Expression::Identifier(id) = argument.as_ref()
             {
-                return Err(JsError::syntax_error(
+                return Err(JsError::syntax_error_at(
                     format!(
                         "Delete of an unqualified identifier '{}' in strict mode",
                         id.name
                     ),
-                    id.span.line,
-                    id.span.column,
+                    &id.span,
                 ));
             }
 
@@ -2273,7 +2447,7 @@ impl<'a> Parser<'a> {
 
         // TypeScript angle bracket type assertion: <Type>value
         // Must check for < and distinguish from comparison or generics
-        if self.check(&TokenKind::Lt) {
+        if self.is_typescript() && self.check(&TokenKind::Lt) {
             // Try to parse as type assertion <Type>expr
             if let Some(expr) = self.try_parse_angle_bracket_assertion()? {
                 return Ok(expr);
@@ -2302,6 +2476,10 @@ impl<'a> Parser<'a> {
                 if self.match_token(&TokenKind::Gt) {
                     // Parse the expression being asserted
                     let expr = self.parse_unary_expression()?;
+                    // `<div>text</div>` reads as an assertion up to the closing tag
+                    if self.check(&TokenKind::Lt) && self.peek_is(&TokenKind::Slash) {
+                        return Err(JsError::unsupported_syntax("JSX", JSX_HINT, &start));
+                    }
                     let span = self.span_from(start);
                     return Ok(Some(Expression::TypeAssertion(TypeAssertionExpression {
                         expression: Rc::new(expr),
@@ -2350,12 +2528,13 @@ impl<'a> Parser<'a> {
         let mut expr = if self.match_token(&TokenKind::New) {
             let callee = Rc::new(self.parse_member_expression()?);
             // Check for type arguments (<T>) or arguments (()
-            let (arguments, type_arguments) =
-                if self.check(&TokenKind::LParen) || self.check(&TokenKind::Lt) {
-                    self.parse_call_arguments()?
-                } else {
-                    (vec![], None)
-                };
+            let (arguments, type_arguments) = if self.check(&TokenKind::LParen)
+                || (self.is_typescript() && self.check(&TokenKind::Lt))
+            {
+                self.parse_call_arguments()?
+            } else {
+                (vec![], None)
+            };
             let span = self.span_from(start);
             Expression::New(Box::new(NewExpression {
                 callee,
@@ -2374,7 +2553,9 @@ impl<'a> Parser<'a> {
 
         loop {
             // Check for call with either ( or < (type arguments)
-            if self.check(&TokenKind::LParen) || self.check(&TokenKind::Lt) {
+            if self.check(&TokenKind::LParen)
+                || (self.is_typescript() && self.check(&TokenKind::Lt))
+            {
                 // Try to parse as call with type arguments
                 // Use checkpoint in case < is actually a comparison
                 if self.check(&TokenKind::Lt) {
@@ -2509,7 +2690,10 @@ impl<'a> Parser<'a> {
                         span,
                     }));
                 }
-            } else if self.check(&TokenKind::Bang) && !self.lexer.had_newline_before() {
+            } else if self.is_typescript()
+                && self.check(&TokenKind::Bang)
+                && !self.lexer.had_newline_before()
+            {
                 // TypeScript non-null assertion (!)
                 // The lexer tokenizes != and !== as single tokens (BangEq, BangEqEq)
                 // so if we see Bang, it's a standalone !
@@ -2534,8 +2718,12 @@ impl<'a> Parser<'a> {
             });
         }
 
-        // TypeScript type assertion (as)
-        if self.match_token(&TokenKind::As) {
+        // TypeScript type assertion (as), which can't follow a line break:
+        // `x\nas = 1` is two statements
+        if self.is_typescript()
+            && !self.lexer.had_newline_before()
+            && self.match_token(&TokenKind::As)
+        {
             // Handle "as const" - const assertion (TypeScript 3.4+)
             // This is a compile-time feature; at runtime we just return the value unchanged
             if self.match_token(&TokenKind::Const) {
@@ -2743,12 +2931,8 @@ impl<'a> Parser<'a> {
                         span: decl.span,
                     })))
                 } else {
-                    Err(JsError::syntax_error(
-                        "Decorators can only be applied to class expressions in expression position"
-                            .to_string(),
-                        self.current.span.line,
-                        self.current.span.column,
-                    ))
+                    Err(JsError::syntax_error_at("Decorators can only be applied to class expressions in expression position"
+                            .to_string(), &self.current.span))
                 }
             }
 
@@ -2781,7 +2965,7 @@ impl<'a> Parser<'a> {
                 // Rescan from the current token's position as a regexp.
                 let token = self.lexer.rescan_as_regexp(self.current.span);
                 if let TokenKind::RegExp(pattern, flags) = token.kind {
-                    self.current = self.lexer.next_token();
+                    self.current = self.next_token();
                     Ok(Expression::Literal(Box::new(Literal {
                         value: LiteralValue::RegExp { pattern, flags },
                         span: self.span_from(start),
@@ -2791,10 +2975,47 @@ impl<'a> Parser<'a> {
                 }
             }
 
-            _ => Err(self.unexpected_token("expression")),
+            _ => Err(self
+                .unsupported_expression()
+                .unwrap_or_else(|| self.unexpected_token("expression"))),
         }
     }
 
+    /// Recognize syntax from proposals and dialects tsrun doesn't implement
+    /// where an expression was expected, so the error can name it
+    fn unsupported_expression(&mut self) -> Option<JsError> {
+        let span = self.current.span;
+        let next = self.peek_kind();
+        let (construct, hint, span) = match (&self.current.kind, next) {
+            (TokenKind::Lt, TokenKind::Gt) => ("JSX fragment syntax", JSX_HINT, span),
+            (TokenKind::Lt, TokenKind::Identifier(_)) => ("JSX", JSX_HINT, span),
+            (TokenKind::Do, TokenKind::LBrace) => (
+                "a do expression",
+                "wrap the block in an immediately invoked arrow function: (() => { ... })()",
+                span,
+            ),
+            // `|>` lexes as `|` then `>`; point at both
+            (TokenKind::Gt, _)
+                if self.previous.kind == TokenKind::Pipe
+                    && self.previous.span.end == span.start =>
+            {
+                let pipe = self.previous.span;
+                (
+                    "the pipeline operator `|>`",
+                    "call the function directly instead: f(x)",
+                    Span::new(pipe.start, span.end, pipe.line, pipe.column),
+                )
+            }
+            (TokenKind::Hash, TokenKind::Bang) => (
+                "a `#!` hashbang after the first line",
+                "move it to the very first line of the file or remove it",
+                span,
+            ),
+            _ => return None,
+        };
+        Some(JsError::unsupported_syntax(construct, hint, &span))
+    }
+
     fn parse_array_literal(&mut self) -> Result<Expression, JsError> {
         let start = self.current.span;
         self.require_token(&TokenKind::LBracket)?;
@@ -2863,6 +3084,14 @@ impl<'a> Parser<'a> {
     fn parse_property(&mut self) -> Result<Property, JsError> {
         let start = self.current.span;
 
+        if self.check(&TokenKind::At) {
+            return Err(JsError::unsupported_syntax(
+                "a decorator on an object literal member",
+                "decorators apply only to classes and class members; move the method into a class",
+                &start,
+            ));
+        }
+
         // Check for generator method (*)
         let is_generator = self.match_token(&TokenKind::Star);
 
@@ -3159,7 +3388,7 @@ impl<'a> Parser<'a> {
                 self.parse_binding_pattern()?
             };
 
-            let optional = self.match_token(&TokenKind::Question);
+            let optional = self.match_ts_token(&TokenKind::Question);
 
             let type_annotation = if self.match_token(&TokenKind::Colon) {
                 Some(Box::new(self.parse_type_annotation()?))
@@ -3248,7 +3477,7 @@ impl<'a> Parser<'a> {
 
         // async function - async function expression
         if self.check(&TokenKind::Function) {
-            return self.parse_function_expression(true);
+            return self.parse_function_expression_from(start, true);
         }
 
         // async () => or async (params) =>
@@ -3312,7 +3541,7 @@ impl<'a> Parser<'a> {
         // Parse parameter decorators (e.g., @inject param)
         let decorators = self.parse_decorators()?;
         let pattern = self.parse_binding_pattern()?;
-        let optional = self.match_token(&TokenKind::Question);
+        let optional = self.match_ts_token(&TokenKind::Question);
         let type_annotation = if self.match_token(&TokenKind::Colon) {
             Some(Box::new(self.parse_type_annotation()?))
         } else {
@@ -3337,6 +3566,16 @@ impl<'a> Parser<'a> {
 
     fn parse_function_expression(&mut self, is_async: bool) -> Result<Expression, JsError> {
         let start = self.current.span;
+        self.parse_function_expression_from(start, is_async)
+    }
+
+    /// Parse a function expression starting at `function`.
+    /// `start` is the span of the first token (`async` or `function`).
+    fn parse_function_expression_from(
+        &mut self,
+        start: Span,
+        is_async: bool,
+    ) -> Result<Expression, JsError> {
         self.require_token(&TokenKind::Function)?;
 
         let generator = self.match_token(&TokenKind::Star);
@@ -3394,13 +3633,12 @@ impl<'a> Parser<'a> {
             expressions.push(self.parse_expression()?);
             // Check for closing brace but don't advance - let scan_template_continuation handle it
             if !self.check(&TokenKind::RBrace) {
-                return Err(JsError::syntax_error(
+                return Err(JsError::syntax_error_at(
                     format!(
                         "Expected '}}' in template literal, found {:?}",
                         self.current.kind
                     ),
-                    self.current.span.line,
-                    self.current.span.column,
+                    &self.current.span,
                 ));
             }
 
@@ -3424,14 +3662,14 @@ impl<'a> Parser<'a> {
                     });
                     // After TemplateMiddle, we need to parse another expression
                     // The lexer is now positioned after ${, so get the next token
-                    self.current = self.lexer.next_token();
+                    self.current = self.next_token();
                 }
                 _ => break,
             }
         }
 
         // After template literal parsing, advance to get the next token
-        self.current = self.lexer.next_token();
+        self.current = self.next_token();
 
         let span = self.span_from(start);
         Ok(Expression::Template(Box::new(TemplateLiteral {
@@ -3550,6 +3788,9 @@ impl<'a> Parser<'a> {
     // ============ TYPE ANNOTATIONS ============
 
     fn parse_type_annotation(&mut self) -> Result<TypeAnnotation, JsError> {
+        if !self.is_typescript() {
+            return Err(self.error("Type annotations can only be used in TypeScript files"));
+        }
         self.parse_conditional_type()
     }
 
@@ -3982,13 +4223,13 @@ impl<'a> Parser<'a> {
                         TokenKind::TemplateTail(tail) => {
                             quasis.push(tail);
                             // Advance past the tail token
-                            self.current = self.lexer.next_token();
+                            self.current = self.next_token();
                             break;
                         }
                         TokenKind::TemplateMiddle(middle) => {
                             quasis.push(middle);
                             // Advance past the middle token to continue parsing
-                            self.current = self.lexer.next_token();
+                            self.current = self.next_token();
                         }
                         _ => return Err(self.unexpected_token("template middle or tail")),
                     }
@@ -4330,7 +4571,7 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_optional_type_parameters(&mut self) -> Result<Option<TypeParameters>, JsError> {
-        if !self.check(&TokenKind::Lt) {
+        if !self.is_typescript() || !self.check(&TokenKind::Lt) {
             return Ok(None);
         }
 
@@ -4375,7 +4616,7 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_optional_type_arguments(&mut self) -> Result<Option<TypeArguments>, JsError> {
-        if !self.check(&TokenKind::Lt) {
+        if !self.is_typescript() || !self.check(&TokenKind::Lt) {
             return Ok(None);
         }
 
@@ -4404,7 +4645,7 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_optional_return_type(&mut self) -> Result<Option<Box<TypeAnnotation>>, JsError> {
-        if self.match_token(&TokenKind::Colon) {
+        if self.match_ts_token(&TokenKind::Colon) {
             // Check for assertion predicate: asserts param or asserts param is Type
             if self.check(&TokenKind::Asserts) {
                 let start = self.current.span;
@@ -4628,7 +4869,58 @@ impl<'a> Parser<'a> {
     }
 
     fn advance(&mut self) {
-        self.previous = mem::replace(&mut self.current, self.lexer.next_token());
+        let next = self.next_token();
+        self.previous = mem::replace(&mut self.current, next);
+    }
+
+    /// The next token from the lexer, as the language reads it
+    fn next_token(&mut self) -> Token {
+        let token = self.lexer.next_token();
+        self.lang_token(token)
+    }
+
+    /// `token`, with TypeScript-only keywords turned into identifiers when
+    /// parsing JavaScript
+    fn lang_token(&mut self, token: Token) -> Token {
+        if self.lang == Lang::TypeScript {
+            return token;
+        }
+        let name = match token.kind {
+            TokenKind::Type => "type",
+            TokenKind::Interface => "interface",
+            TokenKind::Namespace => "namespace",
+            TokenKind::Module => "module",
+            TokenKind::Declare => "declare",
+            TokenKind::Abstract => "abstract",
+            TokenKind::Readonly => "readonly",
+            TokenKind::Public => "public",
+            TokenKind::Private => "private",
+            TokenKind::Protected => "protected",
+            TokenKind::Implements => "implements",
+            TokenKind::Any => "any",
+            TokenKind::Unknown => "unknown",
+            TokenKind::Never => "never",
+            TokenKind::Keyof => "keyof",
+            TokenKind::Infer => "infer",
+            TokenKind::Is => "is",
+            TokenKind::Asserts => "asserts",
+            _ => return token,
+        };
+        Token {
+            kind: TokenKind::Identifier(self.intern(name)),
+            span: token.span,
+        }
+    }
+
+    /// Whether TypeScript-only syntax is allowed
+    fn is_typescript(&self) -> bool {
+        self.lang == Lang::TypeScript
+    }
+
+    /// Like [`Self::match_token`], for a token that only TypeScript gives a
+    /// meaning to in this position
+    fn match_ts_token(&mut self, kind: &TokenKind) -> bool {
+        self.is_typescript() && self.match_token(kind)
     }
 
     fn require_token(&mut self, kind: &TokenKind) -> Result<(), JsError> {
@@ -4718,10 +5010,15 @@ impl<'a> Parser<'a> {
 
     /// Check if the next token (after current) is of the given kind
     fn peek_is(&mut self, kind: &TokenKind) -> bool {
+        mem::discriminant(&self.peek_kind()) == mem::discriminant(kind)
+    }
+
+    /// Kind of the next token (after current)
+    fn peek_kind(&mut self) -> TokenKind {
         let checkpoint = self.lexer.checkpoint();
-        let next = self.lexer.next_token();
+        let next = self.next_token();
         self.lexer.restore(checkpoint);
-        mem::discriminant(&next.kind) == mem::discriminant(kind)
+        next.kind
     }
 
     fn check_identifier(&self) -> bool {
@@ -4884,7 +5181,7 @@ impl<'a> Parser<'a> {
         // Peek at the next token to determine if it's a property name
         // Used for distinguishing `get: ...` (property named "get") from `get x() {}` (getter)
         let checkpoint = self.lexer.checkpoint();
-        let next = self.lexer.next_token();
+        let next = self.next_token();
         self.lexer.restore(checkpoint);
 
         // If next token is `:` or `,` or `}`, current is the property name itself
@@ -4974,14 +5271,13 @@ impl<'a> Parser<'a> {
     }
 
     fn error(&self, message: &str) -> JsError {
-        JsError::syntax_error(message, self.current.span.line, self.current.span.column)
+        JsError::syntax_error_at(message, &self.current.span)
     }
 
     fn unexpected_token(&self, expected: &str) -> JsError {
-        JsError::syntax_error(
+        JsError::syntax_error_at(
             format!("Unexpected {:?}, expected {}", self.current.kind, expected),
-            self.current.span.line,
-            self.current.span.column,
+            &self.current.span,
         )
     }
 
@@ -5127,10 +5423,9 @@ impl<'a> Parser<'a> {
                     AssignmentTarget::Identifier(id) => Pattern::Identifier(id.clone()),
                     AssignmentTarget::Pattern(p) => p.clone(),
                     AssignmentTarget::Member(_) => {
-                        return Err(JsError::syntax_error(
+                        return Err(JsError::syntax_error_at(
                             "Invalid destructuring target",
-                            assign.span.line,
-                            assign.span.column,
+                            &assign.span,
                         ));
                     }
                 };
@@ -5141,10 +5436,9 @@ impl<'a> Parser<'a> {
                     span: assign.span,
                 }))
             }
-            _ => Err(JsError::syntax_error(
+            _ => Err(JsError::syntax_error_at(
                 "Invalid destructuring target",
-                expr.span().line,
-                expr.span().column,
+                &expr.span(),
             )),
         }
     }
@@ -5160,10 +5454,9 @@ impl<'a> Parser<'a> {
                 let pattern = self.expression_to_pattern(expr)?;
                 Ok(AssignmentTarget::Pattern(pattern))
             }
-            _ => Err(JsError::syntax_error(
+            _ => Err(JsError::syntax_error_at(
                 "Invalid assignment target",
-                expr.span().line,
-                expr.span().column,
+                &expr.span(),
             )),
         }
     }
@@ -5179,11 +5472,7 @@ impl<'a> Parser<'a> {
                 AssignmentTarget::Identifier(id) => Pattern::Identifier(id.clone()),
                 AssignmentTarget::Pattern(p) => p.clone(),
                 _ => {
-                    return Err(JsError::syntax_error(
-                        "Invalid parameter",
-                        span.line,
-                        span.column,
-                    ));
+                    return Err(JsError::syntax_error_at("Invalid parameter", &span));
                 }
             };
 
@@ -31,6 +31,12 @@ pub trait TimeProvider {
     /// Start a timer and return an opaque handle.
     /// The handle can be passed to `elapsed_millis` to get the elapsed time.
     fn start_timer(&self) -> u64;
+
+    /// Like `elapsed_millis`, with sub-millisecond precision where available.
+    /// Used for `performance.now()`.
+    fn elapsed_precise_millis(&self, start: u64) -> f64 {
+        self.elapsed_millis(start) as f64
+    }
 }
 
 /// Trait for providing random number generation.
@@ -47,6 +47,11 @@ impl TimeProvider for StdTimeProvider {
     fn start_timer(&self) -> u64 {
         self.epoch.elapsed().as_millis() as u64
     }
+
+    fn elapsed_precise_millis(&self, start: u64) -> f64 {
+        let now = self.epoch.elapsed().as_secs_f64() * 1000.0;
+        (now - start as f64).max(0.0)
+    }
 }
 
 /// Random provider using a simple xorshift64 PRNG.
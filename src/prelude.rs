@@ -28,6 +28,7 @@ pub use std::{
     format,
     rc::{Rc, Weak},
     string::{String, ToString},
+    sync::Arc,
     vec,
     vec::Vec,
 };
@@ -39,6 +40,7 @@ pub use alloc::{
     format,
     rc::{Rc, Weak},
     string::{String, ToString},
+    sync::Arc,
     vec,
     vec::Vec,
 };
@@ -179,6 +181,18 @@ pub mod math {
         libm::pow(base, exp)
     }
 
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn powi(base: f64, exp: i32) -> f64 {
+        base.powi(exp)
+    }
+
+    #[cfg(not(feature = "std"))]
+    #[inline]
+    pub fn powi(base: f64, exp: i32) -> f64 {
+        libm::pow(base, f64::from(exp))
+    }
+
     #[cfg(feature = "std")]
     #[inline]
     pub fn sqrt(x: f64) -> f64 {
@@ -431,18 +445,6 @@ pub mod math {
         libm::log1p(x)
     }
 
-    #[cfg(feature = "std")]
-    #[inline]
-    pub fn powi(base: f64, exp: i32) -> f64 {
-        base.powi(exp)
-    }
-
-    #[cfg(not(feature = "std"))]
-    #[inline]
-    pub fn powi(base: f64, exp: i32) -> f64 {
-        libm::pow(base, exp as f64)
-    }
-
     /// Euclidean remainder (modulo) - always returns positive result
     #[cfg(feature = "std")]
     #[inline]
@@ -0,0 +1,253 @@
+//! Deterministic time and randomness for testing scripts.
+//!
+//! Enabled with the `testing` feature. [`MockClock`] is a [`TimeProvider`]
+//! that only moves when told to, and [`SeededRandom`] a [`RandomProvider`]
+//! that gives the same numbers for the same seed, so scripts using
+//! `Date.now()`, `performance.now()` or `Math.random()` behave the same on
+//! every run.
+//!
+//! With a clock installed through [`Interpreter::set_mock_clock`],
+//! [`Interpreter::advance_time`] moves it forward and answers the timer
+//! orders that come due on the way: orders with payload
+//! `{ type: "timeout" }` and an [`Order::deadline_ms`], the kind
+//! `request()`'s `timeoutMs` creates. A script that waits 30 seconds before
+//! retrying is then tested without waiting:
+//!
+//! ```
+//! use tsrun::testing::MockClock;
+//! use tsrun::{Interpreter, OrderDelivery, StepResult};
+//!
+//! let mut interp = Interpreter::new();
+//! interp.set_order_delivery(OrderDelivery::Eager);
+//! interp.set_mock_clock(MockClock::new(1_700_000_000_000));
+//! interp
+//!     .prepare(
+//!         r#"
+//!         import { order } from "tsrun:host";
+//!         const start = Date.now();
+//!         await order({ type: "timeout", delayMs: 30000 }, { deadlineMs: start + 30000 });
+//!         Date.now() - start
+//!         "#,
+//!         Some("/main.ts".into()),
+//!     )
+//!     .unwrap();
+//! while let StepResult::Continue | StepResult::Orders(_) = interp.step().unwrap() {}
+//!
+//! let StepResult::Complete(waited) = interp.advance_time(30_000).unwrap() else {
+//!     panic!("expected the script to finish");
+//! };
+//! assert_eq!(waited.as_number(), Some(30000.0));
+//! ```
+
+use core::cell::Cell;
+
+use crate::platform::{RandomProvider, TimeProvider};
+use crate::prelude::*;
+use crate::value::{JsString, JsValue, PropertyKey};
+use crate::{Interpreter, JsError, Order, OrderId, OrderResponse, RuntimeValue, StepResult};
+
+#[derive(Debug)]
+struct ClockState {
+    /// Milliseconds since the Unix epoch, for `Date.now()`
+    epoch_ms: Cell<i64>,
+    /// Milliseconds since the clock was created, for timers and
+    /// `performance.now()`; never goes back
+    monotonic_ms: Cell<u64>,
+}
+
+/// A clock that stands still until it is set or advanced.
+///
+/// Clones share the same time, so the host keeps one to move the clock
+/// while the interpreter reads another.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    state: Rc<ClockState>,
+}
+
+impl MockClock {
+    /// A clock showing `epoch_ms` milliseconds since the Unix epoch
+    pub fn new(epoch_ms: i64) -> Self {
+        Self {
+            state: Rc::new(ClockState {
+                epoch_ms: Cell::new(epoch_ms),
+                monotonic_ms: Cell::new(0),
+            }),
+        }
+    }
+
+    /// The time `Date.now()` reads
+    pub fn now(&self) -> i64 {
+        self.state.epoch_ms.get()
+    }
+
+    /// Move the clock forward by `ms`, for `Date.now()` and timers alike
+    pub fn advance(&self, ms: u64) {
+        let state = &self.state;
+        state
+            .epoch_ms
+            .set(state.epoch_ms.get().saturating_add_unsigned(ms));
+        state
+            .monotonic_ms
+            .set(state.monotonic_ms.get().saturating_add(ms));
+    }
+
+    /// Set the time `Date.now()` reads, forward or back. Timers don't see
+    /// the jump, as with a wall clock corrected mid-run.
+    pub fn set(&self, epoch_ms: i64) {
+        self.state.epoch_ms.set(epoch_ms);
+    }
+}
+
+impl TimeProvider for MockClock {
+    fn now_millis(&self) -> i64 {
+        self.now()
+    }
+
+    fn elapsed_millis(&self, start: u64) -> u64 {
+        self.state.monotonic_ms.get().saturating_sub(start)
+    }
+
+    fn start_timer(&self) -> u64 {
+        self.state.monotonic_ms.get()
+    }
+}
+
+/// `Math.random()` numbers from a fixed seed: the same seed gives the same
+/// sequence on every run and platform
+#[derive(Debug, Clone)]
+pub struct SeededRandom {
+    state: u64,
+}
+
+impl SeededRandom {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl RandomProvider for SeededRandom {
+    fn random(&mut self) -> f64 {
+        // splitmix64, which is fine with any seed including 0
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        // The upper 53 bits, as a fraction in [0, 1)
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl Interpreter {
+    /// Use `clock` for `Date.now()` and timers, and for
+    /// [`Interpreter::advance_time`]
+    pub fn set_mock_clock(&mut self, clock: MockClock) {
+        self.set_time_provider(Box::new(clock.clone()));
+        self.mock_clock = Some(clock);
+    }
+
+    /// Move the mock clock forward by `ms`, answering each timer order that
+    /// comes due on the way, earliest deadline first, and running the script
+    /// until it waits again.
+    ///
+    /// Returns what the run stopped at: `Suspended` with the orders it
+    /// created meanwhile, other than the timers answered here, or
+    /// `Complete` if it finished. When no timer comes due the clock just
+    /// moves and the result is an empty `Suspended`.
+    pub fn advance_time(&mut self, ms: u64) -> Result<StepResult, JsError> {
+        let Some(clock) = self.mock_clock.clone() else {
+            return Err(JsError::type_error(
+                "advance_time needs a clock installed with set_mock_clock",
+            ));
+        };
+        let target = clock.now().saturating_add_unsigned(ms);
+        let mut fired = Vec::new();
+        let mut pending: Vec<Order> = Vec::new();
+        let mut cancelled: Vec<OrderId> = Vec::new();
+
+        while let Some((deadline, id)) = self.next_due_timer(target) {
+            if deadline > clock.now() {
+                clock.advance(deadline.abs_diff(clock.now()));
+            }
+            fired.push(id);
+            self.fulfill_orders(vec![OrderResponse {
+                id,
+                result: Ok(RuntimeValue::unguarded(JsValue::Undefined)),
+            }]);
+            match self.run_until_waiting(&mut pending, &mut cancelled)? {
+                StepResult::Suspended { .. } => {}
+                finished => {
+                    clock.advance(target.abs_diff(clock.now()));
+                    return Ok(finished);
+                }
+            }
+        }
+
+        if target > clock.now() {
+            clock.advance(target.abs_diff(clock.now()));
+        }
+        pending.retain(|order| !fired.contains(&order.id));
+        Ok(StepResult::Suspended { pending, cancelled })
+    }
+
+    /// Note order `id` as a timer for `advance_time` if it is one
+    pub(crate) fn note_timer_order(&mut self, payload: &JsValue, id: OrderId, deadline_ms: i64) {
+        let JsValue::Object(obj) = payload else {
+            return;
+        };
+        let type_key = PropertyKey::String(JsString::from("type"));
+        let is_timer = matches!(
+            obj.borrow().get_property(&type_key),
+            Some(JsValue::String(kind)) if kind.as_str() == "timeout"
+        );
+        if is_timer {
+            self.timer_orders.push((deadline_ms, id));
+        }
+    }
+
+    /// The outstanding timer with the earliest deadline up to `target`,
+    /// taken off the list; the first created wins a tie
+    fn next_due_timer(&mut self, target: i64) -> Option<(i64, OrderId)> {
+        let outstanding = &self.outstanding_orders;
+        self.timer_orders.retain(|(_, id)| outstanding.contains(id));
+        let mut due: Option<(usize, i64)> = None;
+        for (index, &(deadline, _)) in self.timer_orders.iter().enumerate() {
+            if deadline <= target && due.is_none_or(|(_, earliest)| deadline < earliest) {
+                due = Some((index, deadline));
+            }
+        }
+        due.map(|(index, _)| self.timer_orders.remove(index))
+    }
+
+    /// Step until the script waits or finishes, collecting the orders eager
+    /// delivery hands out and the cancellations reported on the way
+    fn run_until_waiting(
+        &mut self,
+        pending: &mut Vec<Order>,
+        cancelled: &mut Vec<OrderId>,
+    ) -> Result<StepResult, JsError> {
+        loop {
+            match self.step()? {
+                StepResult::Continue => {}
+                StepResult::Orders(orders) => pending.extend(orders),
+                // A timer settling a race reports the orders it cancelled;
+                // the script runs on after the report
+                StepResult::Suspended {
+                    pending: more,
+                    cancelled: more_cancelled,
+                } if !more_cancelled.is_empty() => {
+                    pending.extend(more);
+                    cancelled.extend(more_cancelled);
+                }
+                StepResult::Suspended { pending: more, .. } => {
+                    pending.extend(more);
+                    return Ok(StepResult::Suspended {
+                        pending: Vec::new(),
+                        cancelled: Vec::new(),
+                    });
+                }
+                other => return Ok(other),
+            }
+        }
+    }
+}
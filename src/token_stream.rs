@@ -0,0 +1,438 @@
+//! Token stream for syntax highlighting.
+//!
+//! [`Lexer::tokens`] splits source text into tokens that cover it completely,
+//! whitespace and comments included, so an editor can color code exactly the
+//! way tsrun reads it. Unlike [`Lexer::next_token`] it needs no
+//! [`StringDict`] and decodes no literal values: a token is a kind and a span
+//! into the source. Malformed input doesn't end the stream; an unterminated
+//! string or a stray character is flagged as invalid and scanning resumes
+//! right after it.
+//!
+//! Template literals are split at their substitutions like the parser sees
+//! them, so `` `a${b}c` `` is a [`SyntaxKind::TemplateHead`], the tokens of
+//! `b`, and a [`SyntaxKind::TemplateTail`] starting at the `}`.
+//!
+//! Whether a `/` starts a regular expression depends on the grammar. Without
+//! a parser the stream decides from the previous token, as editors do: after
+//! an operand (`a / b`) it is division, after an operator or keyword
+//! (`x = /b/g`, `return /b/`) a regex.
+
+use crate::lexer::{
+    Lexer, Span, TokenKind, is_id_continue_char, is_id_start_char, is_space_separator, keyword,
+};
+use crate::prelude::*;
+use crate::string_dict::StringDict;
+
+/// What a [`SyntaxToken`] is, at the granularity a highlighter colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyntaxKind {
+    /// A run of spaces, tabs and other non-line-terminator whitespace
+    Whitespace,
+    /// One line terminator; significant for automatic semicolon insertion
+    Newline,
+    /// `// ...` up to the line end, or a `#!` line at the very start
+    LineComment,
+    /// `/* ... */`
+    BlockComment,
+    /// A reserved or contextual keyword, including `true`, `false` and `null`
+    Keyword,
+    Identifier,
+    Number,
+    String,
+    RegExp,
+    /// `` `...` `` without substitutions
+    TemplateNoSub,
+    /// `` `...${ ``
+    TemplateHead,
+    /// `}...${` between two substitutions
+    TemplateMiddle,
+    /// `` }...` `` after the last substitution
+    TemplateTail,
+    /// Operators, brackets and other punctuators
+    Punctuation,
+    /// A character that starts no token
+    Error,
+}
+
+/// A token of the highlighting stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyntaxToken {
+    pub kind: SyntaxKind,
+    pub span: Span,
+    /// Set for tokens tsrun would reject: unterminated strings, templates,
+    /// regexes and comments, malformed escapes, legacy octal literals, and
+    /// every [`SyntaxKind::Error`]
+    pub invalid: bool,
+}
+
+/// Iterator behind [`Lexer::tokens`]
+pub struct TokenStream<'a> {
+    source: &'a str,
+    pos: usize,
+    line: u32,
+    column: u32,
+    /// Whether a `/` here would start a regex rather than divide
+    regex_allowed: bool,
+    /// One entry per open `{` or `${`, true for template substitutions
+    braces: Vec<bool>,
+    /// Handed to the [`Lexer`] that scans punctuators and numbers, which
+    /// intern nothing, so it stays empty
+    dict: StringDict,
+}
+
+impl<'a> Lexer<'a> {
+    /// Tokenize `source` for syntax highlighting.
+    ///
+    /// The tokens cover the source without gaps or overlap and never stop
+    /// at an error; see the [`token_stream`](crate::token_stream) module.
+    pub fn tokens(source: &'a str) -> TokenStream<'a> {
+        TokenStream {
+            source,
+            pos: 0,
+            line: 1,
+            column: 1,
+            regex_allowed: true,
+            braces: Vec::new(),
+            dict: StringDict::new(),
+        }
+    }
+}
+
+impl Iterator for TokenStream<'_> {
+    type Item = SyntaxToken;
+
+    fn next(&mut self) -> Option<SyntaxToken> {
+        let ch = self.peek()?;
+        let (start, line, column) = (self.pos, self.line, self.column);
+        let (kind, invalid) = self.scan(ch);
+        let text = self.source.get(start..self.pos).unwrap_or("");
+        match kind {
+            SyntaxKind::Whitespace
+            | SyntaxKind::Newline
+            | SyntaxKind::LineComment
+            | SyntaxKind::BlockComment
+            | SyntaxKind::Error => {}
+            SyntaxKind::Keyword => {
+                self.regex_allowed = !matches!(text, "this" | "super" | "true" | "false" | "null");
+            }
+            SyntaxKind::Punctuation => {
+                self.regex_allowed = !matches!(text, ")" | "]" | "}" | "++" | "--");
+            }
+            SyntaxKind::TemplateHead | SyntaxKind::TemplateMiddle => self.regex_allowed = true,
+            _ => self.regex_allowed = false,
+        }
+        Some(SyntaxToken {
+            kind,
+            span: Span::new(start, self.pos, line, column),
+            invalid,
+        })
+    }
+}
+
+impl TokenStream<'_> {
+    fn scan(&mut self, ch: char) -> (SyntaxKind, bool) {
+        match ch {
+            c if is_line_terminator(c) => {
+                self.advance();
+                (SyntaxKind::Newline, false)
+            }
+            // A leading byte order mark doesn't count as a column
+            '\u{FEFF}' if self.pos == 0 => {
+                self.advance();
+                self.column = 1;
+                (SyntaxKind::Whitespace, false)
+            }
+            c if is_whitespace(c) => {
+                self.advance_while(is_whitespace);
+                (SyntaxKind::Whitespace, false)
+            }
+            '#' if self.at_source_start() && self.peek_second() == Some('!') => {
+                self.advance_while(|c| !is_line_terminator(c));
+                (SyntaxKind::LineComment, false)
+            }
+            '/' => match self.peek_second() {
+                Some('/') => {
+                    self.advance_while(|c| !is_line_terminator(c));
+                    (SyntaxKind::LineComment, false)
+                }
+                Some('*') => self.scan_block_comment(),
+                _ if self.regex_allowed => self.scan_regexp(),
+                _ => self.scan_with_lexer(),
+            },
+            '"' | '\'' => self.scan_string(ch),
+            '`' => {
+                self.advance();
+                self.scan_template(true)
+            }
+            '{' => {
+                self.advance();
+                self.braces.push(false);
+                (SyntaxKind::Punctuation, false)
+            }
+            '}' => {
+                self.advance();
+                if self.braces.pop() == Some(true) {
+                    self.scan_template(false)
+                } else {
+                    (SyntaxKind::Punctuation, false)
+                }
+            }
+            // Scanned here so a `#!` past the start isn't taken for a hashbang
+            '#' => {
+                self.advance();
+                (SyntaxKind::Punctuation, false)
+            }
+            c if c == '\\' || is_id_start_char(c) => self.scan_identifier(),
+            _ => self.scan_with_lexer(),
+        }
+    }
+
+    /// Scan a punctuator or number with the parser's own lexer, so operator
+    /// and numeric literal rules can't drift from what tsrun accepts
+    fn scan_with_lexer(&mut self) -> (SyntaxKind, bool) {
+        let rest = self.rest();
+        let token = Lexer::new(rest, &mut self.dict).next_token();
+        let text = rest.get(..token.span.end).unwrap_or("");
+        if text.is_empty() {
+            self.advance();
+            return (SyntaxKind::Error, true);
+        }
+        for _ in text.chars() {
+            self.advance();
+        }
+        let numeric = text.starts_with(|c: char| c.is_ascii_digit());
+        match token.kind {
+            TokenKind::Number(_) | TokenKind::BigInt(_) => (SyntaxKind::Number, false),
+            // Legacy octal literals like `0777`
+            TokenKind::Invalid(_) if numeric => (SyntaxKind::Number, true),
+            TokenKind::Invalid(_) => (SyntaxKind::Error, true),
+            _ => (SyntaxKind::Punctuation, false),
+        }
+    }
+
+    fn scan_identifier(&mut self) -> (SyntaxKind, bool) {
+        let start = self.pos;
+        let mut escaped = false;
+        let mut invalid = false;
+        while let Some(ch) = self.peek() {
+            if ch == '\\' {
+                self.advance();
+                escaped = true;
+                let first = self.pos == start + 1;
+                let valid = match self.scan_unicode_escape() {
+                    Some(c) if first => is_id_start_char(c),
+                    Some(c) => is_id_continue_char(c),
+                    None => false,
+                };
+                invalid |= !valid;
+            } else if is_id_continue_char(ch) {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+        let name = self.source.get(start..self.pos).unwrap_or("");
+        if !escaped && keyword(name).is_some() {
+            (SyntaxKind::Keyword, false)
+        } else {
+            (SyntaxKind::Identifier, invalid)
+        }
+    }
+
+    fn scan_string(&mut self, quote: char) -> (SyntaxKind, bool) {
+        self.advance();
+        let mut invalid = false;
+        loop {
+            match self.peek() {
+                // The line feed is left for its own Newline token
+                None | Some('\n') => return (SyntaxKind::String, true),
+                Some(c) if c == quote => {
+                    self.advance();
+                    return (SyntaxKind::String, invalid);
+                }
+                Some('\\') => {
+                    self.advance();
+                    // Octal escapes are rejected in strict mode
+                    match self.advance() {
+                        Some('0') if matches!(self.peek(), Some('0'..='7')) => invalid = true,
+                        Some('1'..='7') => invalid = true,
+                        _ => {}
+                    }
+                }
+                Some(_) => {
+                    self.advance();
+                }
+            }
+        }
+    }
+
+    /// Scan template text after the opening backtick (`head`) or after the
+    /// `}` closing a substitution
+    fn scan_template(&mut self, head: bool) -> (SyntaxKind, bool) {
+        let (complete, open) = if head {
+            (SyntaxKind::TemplateNoSub, SyntaxKind::TemplateHead)
+        } else {
+            (SyntaxKind::TemplateTail, SyntaxKind::TemplateMiddle)
+        };
+        let mut invalid = false;
+        loop {
+            match self.advance() {
+                Some('`') => return (complete, invalid),
+                Some('$') if self.peek() == Some('{') => {
+                    self.advance();
+                    self.braces.push(true);
+                    return (open, invalid);
+                }
+                Some('\\') => {
+                    let valid = match self.advance() {
+                        Some('0') => !matches!(self.peek(), Some('0'..='9')),
+                        Some('x') => self.scan_hex_digits(2).is_some(),
+                        Some('u') => self.scan_unicode_escape_body().is_some(),
+                        _ => true,
+                    };
+                    invalid |= !valid;
+                }
+                Some(_) => {}
+                None => return (complete, true),
+            }
+        }
+    }
+
+    fn scan_regexp(&mut self) -> (SyntaxKind, bool) {
+        self.advance();
+        let mut in_class = false;
+        loop {
+            match self.peek() {
+                // A regex can't span lines; end it here rather than
+                // swallowing the rest of the source
+                None => return (SyntaxKind::RegExp, true),
+                Some(c) if is_line_terminator(c) => return (SyntaxKind::RegExp, true),
+                Some('/') if !in_class => {
+                    self.advance();
+                    break;
+                }
+                Some('\\') => {
+                    self.advance();
+                    if self.peek().is_some_and(|c| !is_line_terminator(c)) {
+                        self.advance();
+                    }
+                }
+                Some(c) => {
+                    match c {
+                        '[' => in_class = true,
+                        ']' => in_class = false,
+                        _ => {}
+                    }
+                    self.advance();
+                }
+            }
+        }
+        self.advance_while(|c| c.is_ascii_alphabetic());
+        (SyntaxKind::RegExp, false)
+    }
+
+    fn scan_block_comment(&mut self) -> (SyntaxKind, bool) {
+        self.advance();
+        self.advance();
+        loop {
+            match self.advance() {
+                Some('*') if self.peek() == Some('/') => {
+                    self.advance();
+                    return (SyntaxKind::BlockComment, false);
+                }
+                Some(_) => {}
+                None => return (SyntaxKind::BlockComment, true),
+            }
+        }
+    }
+
+    /// Decode a `\u` escape after its backslash
+    fn scan_unicode_escape(&mut self) -> Option<char> {
+        if self.peek() != Some('u') {
+            return None;
+        }
+        self.advance();
+        self.scan_unicode_escape_body()
+    }
+
+    /// Decode `XXXX` or `{X...}` after `\u`
+    fn scan_unicode_escape_body(&mut self) -> Option<char> {
+        if self.peek() != Some('{') {
+            return char::from_u32(self.scan_hex_digits(4)?);
+        }
+        self.advance();
+        let mut code: u32 = 0;
+        let mut digits = 0;
+        while let Some(digit) = self.peek().and_then(|c| c.to_digit(16)) {
+            self.advance();
+            code = code.saturating_mul(16).saturating_add(digit);
+            digits += 1;
+        }
+        if digits == 0 || self.peek() != Some('}') {
+            return None;
+        }
+        self.advance();
+        char::from_u32(code)
+    }
+
+    fn scan_hex_digits(&mut self, count: usize) -> Option<u32> {
+        let mut code = 0;
+        for _ in 0..count {
+            let digit = self.peek()?.to_digit(16)?;
+            self.advance();
+            code = code * 16 + digit;
+        }
+        Some(code)
+    }
+}
+
+impl<'a> TokenStream<'a> {
+    fn rest(&self) -> &'a str {
+        self.source.get(self.pos..).unwrap_or("")
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        self.rest().chars().nth(1)
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += ch.len_utf8();
+        if is_line_terminator(ch) {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(ch)
+    }
+
+    fn advance_while(&mut self, pred: impl Fn(char) -> bool) {
+        while self.peek().is_some_and(&pred) {
+            self.advance();
+        }
+    }
+
+    /// Whether nothing but a byte order mark comes before the position
+    fn at_source_start(&self) -> bool {
+        self.pos == 0 || (self.pos == '\u{FEFF}'.len_utf8() && self.source.starts_with('\u{FEFF}'))
+    }
+}
+
+/// ECMAScript line terminators: LF, LS (U+2028), PS (U+2029). A lone CR is
+/// whitespace, as in the lexer.
+fn is_line_terminator(ch: char) -> bool {
+    matches!(ch, '\n' | '\u{2028}' | '\u{2029}')
+}
+
+/// Whitespace the lexer skips between tokens, line terminators aside
+fn is_whitespace(ch: char) -> bool {
+    matches!(
+        ch,
+        ' ' | '\t' | '\r' | '\u{000B}' | '\u{000C}' | '\u{00A0}' | '\u{FEFF}'
+    ) || is_space_separator(ch)
+}
@@ -47,14 +47,16 @@
 
 use crate::platform::CompiledRegex;
 use crate::prelude::*;
+use core::cell::OnceCell;
 
 /// Convert a JavaScript number to its canonical string representation.
 ///
-/// According to ECMAScript spec (7.1.12.1 NumberToString):
-/// - Very small numbers (< 1e-6) use exponential notation
-/// - Very large numbers (>= 1e21) use exponential notation
-/// - Otherwise use decimal notation
-/// - Integer values are printed without decimal point
+/// Implements ECMAScript Number::toString (6.1.6.1.20) for radix 10: the
+/// shortest digits that round-trip, written in decimal when the exponent
+/// puts the value in `1e-7 < |n| < 1e21`, exponential (`1e+21`, `1e-7`)
+/// otherwise. Both zeros print as `"0"`. Every number-to-string path
+/// (`String()`, template literals, `toString()`, `JSON.stringify`) formats
+/// through this function.
 pub fn number_to_string(n: f64) -> String {
     if n.is_nan() {
         return "NaN".to_string();
@@ -70,66 +72,72 @@ pub fn number_to_string(n: f64) -> String {
         return "0".to_string();
     }
 
-    let abs_n = n.abs();
-
-    // Check if it's an integer that can be represented exactly
-    if math::trunc(n) == n && abs_n < 1e21 {
-        // Format as integer (no decimal point)
-        return format!("{:.0}", n);
-    }
-
-    // Very small numbers (absolute value < 1e-6) use exponential notation
-    // Very large numbers (absolute value >= 1e21) use exponential notation
-    if !(1e-6..1e21).contains(&abs_n) {
-        // Use exponential notation
-        format_exponential(n)
+    // `{:e}` gives the shortest round-trip digits, e.g. "1.2345e-7"
+    let scientific = format!("{:e}", n.abs());
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let k = digits.len() as i32;
+    // The value is 0.digits * 10^point
+    let point = exponent.parse::<i32>().unwrap_or(0) + 1;
+
+    let mut out = String::new();
+    if n < 0.0 {
+        out.push('-');
+    }
+    if k <= point && point <= 21 {
+        out.push_str(&digits);
+        out.extend(core::iter::repeat_n('0', (point - k) as usize));
+    } else if 0 < point && point <= 21 {
+        let (int_part, frac_part) = digits.split_at(point as usize);
+        out.push_str(int_part);
+        out.push('.');
+        out.push_str(frac_part);
+    } else if -6 < point && point <= 0 {
+        out.push_str("0.");
+        out.extend(core::iter::repeat_n('0', (-point) as usize));
+        out.push_str(&digits);
     } else {
-        // Use decimal notation
-        // We need to produce the shortest representation that round-trips
-        format_decimal(n)
+        let (first, rest) = digits.split_at(1);
+        out.push_str(first);
+        if !rest.is_empty() {
+            out.push('.');
+            out.push_str(rest);
+        }
+        out.push('e');
+        out.push(if point > 0 { '+' } else { '-' });
+        out.push_str(&(point - 1).abs().to_string());
     }
+    out
 }
 
-/// Format a number in exponential notation matching JavaScript's output
-fn format_exponential(n: f64) -> String {
-    // Get the exponent
-    let abs_n = n.abs();
-    let exponent = math::floor(math::log10(abs_n)) as i32;
-    let mantissa = n / math::powi(10_f64, exponent);
+/// Order two strings by UTF-16 code units, as JavaScript string comparison does.
+///
+/// This differs from `str` ordering (code points) when one side has a
+/// character above U+FFFF and the other one in U+E000..=U+FFFF.
+pub fn compare_code_units(a: &str, b: &str) -> core::cmp::Ordering {
+    a.encode_utf16().cmp(b.encode_utf16())
+}
 
-    // Format mantissa - remove trailing zeros after decimal point
-    let mantissa_str = if math::trunc(mantissa) == mantissa {
-        format!("{:.0}", mantissa)
+/// ECMAScript ToUint32: NaN and infinities map to 0, everything else is
+/// truncated and wrapped modulo 2^32.
+pub fn to_uint32(n: f64) -> u32 {
+    const TWO_32: f64 = 4294967296.0;
+    if !n.is_finite() {
+        return 0;
+    }
+    let wrapped = math::trunc(n) % TWO_32;
+    let wrapped = if wrapped < 0.0 {
+        wrapped + TWO_32
     } else {
-        let s = format!("{}", mantissa);
-        // Remove trailing zeros but keep at least one digit after decimal
-        s.trim_end_matches('0').to_string()
+        wrapped
     };
-
-    // Format exponent with sign
-    if exponent >= 0 {
-        format!("{}e+{}", mantissa_str, exponent)
-    } else {
-        format!("{}e{}", mantissa_str, exponent)
-    }
+    wrapped as u32
 }
 
-/// Format a number in decimal notation matching JavaScript's output
-fn format_decimal(n: f64) -> String {
-    // Use Rust's default formatting which handles most cases
-    let s = format!("{}", n);
-
-    // Remove trailing zeros after decimal point (but keep at least one digit)
-    if s.contains('.') {
-        let trimmed = s.trim_end_matches('0');
-        if trimmed.ends_with('.') {
-            format!("{}0", trimmed)
-        } else {
-            trimmed.to_string()
-        }
-    } else {
-        s
-    }
+/// ECMAScript ToInt32: ToUint32 reinterpreted as a two's complement integer,
+/// so `2 ** 31` becomes `-(2 ** 31)` rather than saturating.
+pub fn to_int32(n: f64) -> i32 {
+    to_uint32(n) as i32
 }
 
 /// Convert a JavaScript string to a number according to ECMAScript ToNumber.
@@ -230,7 +238,7 @@ pub fn string_to_number(s: &str) -> f64 {
 /// - ASCII whitespace: space, tab, LF, CR, form feed, vertical tab
 /// - Unicode: no-break space (00A0), BOM (FEFF), line separator (2028), paragraph separator (2029)
 /// - And other Unicode space separators
-fn trim_js_whitespace(s: &str) -> &str {
+pub(crate) fn trim_js_whitespace(s: &str) -> &str {
     fn is_js_whitespace(c: char) -> bool {
         matches!(
             c,
@@ -266,7 +274,11 @@ fn trim_js_whitespace(s: &str) -> &str {
 
 use crate::ast::{BlockStatement, FunctionParam};
 use crate::error::JsError;
+
+// Bounded formatting for logs and console output
+pub(crate) mod display;
 use crate::gc::{Gc, GcPtr, Guard, Heap, Reset, Traceable};
+pub use display::{DEFAULT_DISPLAY_DEPTH, DEFAULT_DISPLAY_ITEMS, ValueDisplay};
 
 /// Trait for types that have cheap (O(1), reference-counted) clones.
 ///
@@ -431,9 +443,14 @@ impl JsValue {
     /// Check if this value is callable (a function)
     pub fn is_callable(&self) -> bool {
         match self {
-            JsValue::Object(obj) => {
-                matches!(obj.borrow().exotic, ExoticObject::Function(_))
-            }
+            JsValue::Object(obj) => matches!(
+                obj.borrow().exotic,
+                ExoticObject::Function(_)
+                    | ExoticObject::Host(crate::interpreter::host_class::HostObjectData {
+                        callable: true,
+                        ..
+                    })
+            ),
             _ => false,
         }
     }
@@ -518,6 +535,17 @@ impl JsValue {
         }
     }
 
+    /// The string a String wrapper object boxes, if this is one
+    pub(crate) fn string_wrapper_value(&self) -> Option<JsString> {
+        match self {
+            JsValue::Object(obj) => match &obj.borrow().exotic {
+                ExoticObject::StringObj(s) => Some(s.cheap_clone()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     /// Returns a reference to the object if this is an Object, otherwise None
     pub fn as_object(&self) -> Option<&Gc<JsObject>> {
         match self {
@@ -534,6 +562,118 @@ impl JsValue {
         }
     }
 
+    // ═══════════════════════════════════════════════════════════════════════════════
+    // Checked Number Conversions
+    // ═══════════════════════════════════════════════════════════════════════════════
+
+    /// The number as an `i64` if it is a safe integer, otherwise None.
+    ///
+    /// Safe integers are those `Number.isSafeInteger` accepts: integral and
+    /// within ±(2^53 − 1). Past that a number may not be the integer the
+    /// script computed, so it is refused rather than rounded. `-0` is 0.
+    pub fn as_i64(&self) -> Option<i64> {
+        self.try_i64().ok()
+    }
+
+    /// The number as a `u64` if it is a non-negative safe integer, otherwise None
+    pub fn as_u64(&self) -> Option<u64> {
+        self.try_u64().ok()
+    }
+
+    /// The number as a `u32` if it is an integer in `0..=u32::MAX`, otherwise None
+    pub fn as_u32(&self) -> Option<u32> {
+        self.try_u32().ok()
+    }
+
+    /// The number as a `usize` if it is a non-negative safe integer that
+    /// fits, otherwise None
+    pub fn as_usize(&self) -> Option<usize> {
+        self.try_usize().ok()
+    }
+
+    /// The number as the nearest `f32` if it is finite and within `f32`
+    /// range, otherwise None
+    pub fn as_f32(&self) -> Option<f32> {
+        self.try_f32().ok()
+    }
+
+    /// Like [`Self::as_i64`], failing with a TypeError for non-numbers and a
+    /// RangeError saying why for numbers that don't convert
+    pub fn try_i64(&self) -> Result<i64, JsError> {
+        self.try_integer("i64", -MAX_SAFE_INTEGER, MAX_SAFE_INTEGER)
+            .map(|n| n as i64)
+    }
+
+    /// Like [`Self::as_u64`], with the errors of [`Self::try_i64`]
+    pub fn try_u64(&self) -> Result<u64, JsError> {
+        self.try_integer("u64", 0.0, MAX_SAFE_INTEGER)
+            .map(|n| n as u64)
+    }
+
+    /// Like [`Self::as_u32`], with the errors of [`Self::try_i64`]
+    pub fn try_u32(&self) -> Result<u32, JsError> {
+        self.try_integer("u32", 0.0, f64::from(u32::MAX))
+            .map(|n| n as u32)
+    }
+
+    /// Like [`Self::as_usize`], with the errors of [`Self::try_i64`]
+    pub fn try_usize(&self) -> Result<usize, JsError> {
+        self.try_integer("usize", 0.0, MAX_SAFE_INTEGER.min(usize::MAX as f64))
+            .map(|n| n as usize)
+    }
+
+    /// Like [`Self::as_f32`], with the errors of [`Self::try_i64`]
+    pub fn try_f32(&self) -> Result<f32, JsError> {
+        let n = self.try_number("f32")?;
+        if !n.is_finite() || n.abs() > f64::from(f32::MAX) {
+            return Err(JsError::range_error(format!(
+                "{} is out of range for f32",
+                number_to_string(n)
+            )));
+        }
+        Ok(n as f32)
+    }
+
+    /// The number, or a TypeError naming the Rust type it was wanted as
+    fn try_number(&self, target: &str) -> Result<f64, JsError> {
+        self.as_number().ok_or_else(|| {
+            JsError::type_error(format!(
+                "Expected a number for {}, got {}",
+                target,
+                self.type_name()
+            ))
+        })
+    }
+
+    /// The number if it is an integer in `min..=max`
+    fn try_integer(&self, target: &str, min: f64, max: f64) -> Result<f64, JsError> {
+        let n = self.try_number(target)?;
+        if !n.is_finite() || math::fract(n) != 0.0 {
+            return Err(JsError::range_error(format!(
+                "Expected an integer for {}, got {}",
+                target,
+                number_to_string(n)
+            )));
+        }
+        if n < min || n > max {
+            return Err(JsError::range_error(format!(
+                "{} is out of range for {}",
+                number_to_string(n),
+                target
+            )));
+        }
+        Ok(n)
+    }
+
+    /// The primitive converted as `String(value)` converts it, or None for
+    /// objects, whose conversion runs script code
+    pub fn as_string_lossy(&self) -> Option<String> {
+        match self {
+            JsValue::Object(_) => None,
+            primitive => Some(primitive.to_string()),
+        }
+    }
+
     /// If this value is an object, add it to the guard.
     /// This keeps the object alive as long as the guard exists.
     pub fn guard_by(&self, guard: &Guard<JsObject>) {
@@ -543,6 +683,9 @@ impl JsValue {
     }
 
     /// Convert to boolean (ToBoolean)
+    ///
+    /// Every object is truthy, including wrappers like `new Boolean(false)`
+    /// and `new String("")`; only primitives can be falsy.
     pub fn to_boolean(&self) -> bool {
         match self {
             JsValue::Undefined | JsValue::Null => false,
@@ -641,6 +784,29 @@ impl JsValue {
         }
     }
 
+    /// SameValue (used by Object.is): like `===`, except NaN equals NaN
+    /// and +0 does not equal -0
+    pub fn same_value(&self, other: &JsValue) -> bool {
+        match (self, other) {
+            (JsValue::Number(a), JsValue::Number(b)) => {
+                if a.is_nan() && b.is_nan() {
+                    return true;
+                }
+                a == b && a.is_sign_negative() == b.is_sign_negative()
+            }
+            _ => self.strict_equals(other),
+        }
+    }
+
+    /// SameValueZero (used by `includes`, Map and Set): like `===`, except
+    /// NaN equals NaN
+    pub fn same_value_zero(&self, other: &JsValue) -> bool {
+        match (self, other) {
+            (JsValue::Number(a), JsValue::Number(b)) if a.is_nan() && b.is_nan() => true,
+            _ => self.strict_equals(other),
+        }
+    }
+
     /// Strict equality (===)
     pub fn strict_equals(&self, other: &JsValue) -> bool {
         match (self, other) {
@@ -707,10 +873,9 @@ impl fmt::Debug for JsValue {
             JsValue::Boolean(b) => write!(f, "{}", b),
             JsValue::Number(n) => write!(f, "{}", n),
             JsValue::String(s) => write!(f, "\"{}\"", s.as_ref()),
-            JsValue::Symbol(s) => match &s.description {
-                Some(desc) => write!(f, "Symbol({})", desc.as_str()),
-                None => write!(f, "Symbol()"),
-            },
+            JsValue::Symbol(s) => write!(f, "{:?}", s),
+            // Its interpreter is gone, so there is nothing left to show
+            JsValue::Object(obj) if !obj.is_heap_alive() => write!(f, "[detached object]"),
             JsValue::Object(obj) => {
                 let obj = obj.borrow();
                 match &obj.exotic {
@@ -765,14 +930,22 @@ impl fmt::Debug for JsValue {
                         }
                     }
                     ExoticObject::Boolean(b) => write!(f, "[Boolean: {}]", b),
-                    ExoticObject::Number(n) => write!(f, "[Number: {}]", n),
+                    ExoticObject::Number(n) => write!(f, "[Number: {}]", number_to_string(*n)),
                     ExoticObject::StringObj(s) => write!(f, "[String: \"{}\"]", s),
                     ExoticObject::Symbol(sym) => match &sym.description {
                         Some(desc) => write!(f, "[Symbol: Symbol({})]", desc.as_str()),
                         None => write!(f, "[Symbol: Symbol()]"),
                     },
                     ExoticObject::RawJSON(raw) => write!(f, "[RawJSON: {}]", raw),
+                    ExoticObject::Url(data) => write!(f, "URL({})", data.record.borrow().href()),
+                    ExoticObject::UrlSearchParams(data) => {
+                        write!(f, "URLSearchParams({})", data.borrow().list.len())
+                    }
+                    ExoticObject::WeakRef(_) => write!(f, "WeakRef"),
+                    ExoticObject::FinalizationRegistry(_) => write!(f, "FinalizationRegistry"),
+                    ExoticObject::DisposableStack(_) => write!(f, "DisposableStack"),
                     ExoticObject::PendingOrder { id, .. } => write!(f, "[PendingOrder: {}]", id),
+                    ExoticObject::Host(_) => write!(f, "[HostObject]"),
                 }
             }
         }
@@ -787,6 +960,59 @@ impl PartialEq for JsValue {
 
 // Conversions from Rust types
 
+/// Largest integer a number holds exactly, `Number.MAX_SAFE_INTEGER`
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+macro_rules! impl_try_from_js_value {
+    ($($ty:ty => $method:ident),* $(,)?) => {
+        $(
+            impl TryFrom<&JsValue> for $ty {
+                type Error = JsError;
+
+                fn try_from(value: &JsValue) -> Result<Self, JsError> {
+                    value.$method()
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_js_value!(
+    i64 => try_i64,
+    u64 => try_u64,
+    u32 => try_u32,
+    usize => try_usize,
+    f32 => try_f32,
+);
+
+impl TryFrom<&JsValue> for f64 {
+    type Error = JsError;
+
+    fn try_from(value: &JsValue) -> Result<Self, JsError> {
+        value.try_number("f64")
+    }
+}
+
+impl TryFrom<&JsValue> for bool {
+    type Error = JsError;
+
+    fn try_from(value: &JsValue) -> Result<Self, JsError> {
+        value.as_bool().ok_or_else(|| {
+            JsError::type_error(format!("Expected a boolean, got {}", value.type_name()))
+        })
+    }
+}
+
+impl TryFrom<&JsValue> for String {
+    type Error = JsError;
+
+    fn try_from(value: &JsValue) -> Result<Self, JsError> {
+        value.as_str().map(String::from).ok_or_else(|| {
+            JsError::type_error(format!("Expected a string, got {}", value.type_name()))
+        })
+    }
+}
+
 impl From<bool> for JsValue {
     fn from(b: bool) -> Self {
         JsValue::Boolean(b)
@@ -869,8 +1095,150 @@ impl JsValue {
 }
 
 /// Reference-counted string for efficient string handling
-#[derive(Clone, PartialEq, Eq, Hash)]
-pub struct JsString(Rc<str>);
+///
+/// Long concatenations build a rope: `a + b` allocates a small node pointing at
+/// both halves instead of copying them, and the node is flattened on first
+/// access to its contents. Building a string with `+=` in a loop is therefore
+/// linear rather than quadratic. Interned strings are always flat.
+///
+/// Strings hold Unicode scalar values, so they are always valid UTF-8.
+/// Everything that builds a string from UTF-16 code units (escapes,
+/// `String.fromCharCode`, `JSON.parse`, [`JsString::from_utf16_lossy`])
+/// joins surrogate pairs and replaces lone surrogates with U+FFFD.
+#[derive(Clone)]
+pub struct JsString(StrRepr);
+
+/// Builds a string from chars and UTF-16 code units, joining surrogate
+/// pairs and replacing lone surrogates with U+FFFD
+#[derive(Default)]
+pub(crate) struct Utf16Collector {
+    out: String,
+    /// A high surrogate waiting for its low half
+    high: Option<u16>,
+}
+
+impl Utf16Collector {
+    pub(crate) fn push(&mut self, c: char) {
+        self.flush_high();
+        self.out.push(c);
+    }
+
+    pub(crate) fn push_unit(&mut self, unit: u16) {
+        match unit {
+            0xD800..=0xDBFF => {
+                self.flush_high();
+                self.high = Some(unit);
+            }
+            0xDC00..=0xDFFF => match self.high.take() {
+                Some(high) => {
+                    let code =
+                        0x10000 + ((u32::from(high) - 0xD800) << 10) + (u32::from(unit) - 0xDC00);
+                    self.out
+                        .push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                }
+                None => self.out.push(char::REPLACEMENT_CHARACTER),
+            },
+            _ => self.push(char::from_u32(u32::from(unit)).unwrap_or(char::REPLACEMENT_CHARACTER)),
+        }
+    }
+
+    /// Push a code point, which may be one half of a surrogate pair; false
+    /// if it is past U+10FFFF
+    pub(crate) fn push_code_point(&mut self, code: u32) -> bool {
+        if let Ok(unit) = u16::try_from(code) {
+            self.push_unit(unit);
+            return true;
+        }
+        match char::from_u32(code) {
+            Some(c) => {
+                self.push(c);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub(crate) fn finish(mut self) -> String {
+        self.flush_high();
+        self.out
+    }
+
+    fn flush_high(&mut self) {
+        if self.high.take().is_some() {
+            self.out.push(char::REPLACEMENT_CHARACTER);
+        }
+    }
+}
+
+#[derive(Clone)]
+enum StrRepr {
+    Flat(Rc<str>),
+    Rope(Rc<RopeNode>),
+}
+
+/// Concatenations shorter than this are copied eagerly; below it a rope
+/// node costs more than the copy it saves.
+const ROPE_MIN_LEN: usize = 256;
+
+/// Interior node of a rope string.
+struct RopeNode {
+    /// Total length in bytes.
+    len: usize,
+    /// Total length in chars, so `.length` never forces a flatten.
+    chars: usize,
+    /// Flattened contents, filled on first access.
+    flat: OnceCell<Rc<str>>,
+    /// Left and right halves; cleared once flattened.
+    parts: RefCell<Option<(JsString, JsString)>>,
+}
+
+impl RopeNode {
+    fn flatten(&self) -> Rc<str> {
+        let mut out = String::with_capacity(self.len);
+        // Iterative in-order walk: ropes built by `+=` are as deep as the
+        // number of appends, far beyond what recursion could handle.
+        let mut stack: Vec<JsString> = Vec::new();
+        if let Some((left, right)) = self.parts.borrow_mut().take() {
+            stack.push(right);
+            stack.push(left);
+        }
+        while let Some(part) = stack.pop() {
+            match &part.0 {
+                StrRepr::Flat(s) => out.push_str(s),
+                StrRepr::Rope(node) => {
+                    if let Some(flat) = node.flat.get() {
+                        out.push_str(flat);
+                    } else if let Some((left, right)) = node.parts.borrow().as_ref() {
+                        stack.push(right.cheap_clone());
+                        stack.push(left.cheap_clone());
+                    }
+                }
+            }
+        }
+        out.into()
+    }
+}
+
+impl Drop for RopeNode {
+    fn drop(&mut self) {
+        // Unlink uniquely owned children iteratively so dropping a deep rope
+        // cannot overflow the stack.
+        let mut stack: Vec<JsString> = Vec::new();
+        if let Some((left, right)) = self.parts.get_mut().take() {
+            stack.push(left);
+            stack.push(right);
+        }
+        while let Some(part) = stack.pop() {
+            if let StrRepr::Rope(node) = part.0
+                && let Some(mut node) = Rc::into_inner(node)
+                && let Some((left, right)) = node.parts.get_mut().take()
+            {
+                stack.push(left);
+                stack.push(right);
+            }
+        }
+    }
+}
 
 /// A key for variable lookups that uses pointer-based hashing.
 ///
@@ -887,16 +1255,14 @@ pub struct VarKey(pub JsString);
 impl Hash for VarKey {
     fn hash<H: Hasher>(&self, state: &mut H) {
         // Hash the pointer address, not the content
-        // Use the data pointer from the fat pointer (Rc<str> is a fat pointer)
-        let ptr = Rc::as_ptr(&self.0.0) as *const () as usize;
-        ptr.hash(state);
+        self.0.0.ptr_addr().hash(state);
     }
 }
 
 impl PartialEq for VarKey {
     fn eq(&self, other: &Self) -> bool {
         // Compare pointer addresses, not content
-        Rc::ptr_eq(&self.0.0, &other.0.0)
+        self.0.0.ptr_addr() == other.0.0.ptr_addr()
     }
 }
 
@@ -948,72 +1314,168 @@ impl PrivateFieldKey {
     }
 }
 
-// JsString wraps Rc<str>, so clone is cheap (just reference count increment)
+// JsString wraps an Rc, so clone is cheap (just reference count increment)
 impl CheapClone for JsString {}
 
 impl JsString {
+    /// Build a string from UTF-16 code units, joining surrogate pairs and
+    /// replacing lone surrogates with U+FFFD
+    pub fn from_utf16_lossy(units: &[u16]) -> JsString {
+        let mut collector = Utf16Collector::default();
+        for &unit in units {
+            collector.push_unit(unit);
+        }
+        JsString::from(collector.finish())
+    }
+
+    /// The string as UTF-16 code units
+    pub fn to_utf16(&self) -> Vec<u16> {
+        self.as_str().encode_utf16().collect()
+    }
+
+    /// The string's contents. Exact, never lossy: lone surrogates were
+    /// already replaced when the string was built.
     pub fn as_str(&self) -> &str {
-        &self.0
+        match &self.0 {
+            StrRepr::Flat(s) => s,
+            StrRepr::Rope(node) => node.flat.get_or_init(|| node.flatten()),
+        }
     }
 
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.len() == 0
     }
 
+    /// Length in bytes (does not flatten ropes)
     pub fn len(&self) -> usize {
-        self.0.len()
+        match &self.0 {
+            StrRepr::Flat(s) => s.len(),
+            StrRepr::Rope(node) => node.len,
+        }
+    }
+
+    /// Length in chars (does not flatten ropes)
+    pub fn char_count(&self) -> usize {
+        match &self.0 {
+            StrRepr::Flat(s) => s.chars().count(),
+            StrRepr::Rope(node) => node.chars,
+        }
     }
 
     pub fn parse<F: core::str::FromStr>(&self) -> Result<F, F::Err> {
-        self.0.parse()
+        self.as_str().parse()
+    }
+
+    /// Concatenate two strings, sharing both halves when the result is long.
+    fn concat(self, other: &JsString) -> JsString {
+        if other.is_empty() {
+            return self;
+        }
+        if self.is_empty() {
+            return other.cheap_clone();
+        }
+        let len = self.len() + other.len();
+        if len < ROPE_MIN_LEN {
+            let mut s = String::with_capacity(len);
+            s.push_str(self.as_str());
+            s.push_str(other.as_str());
+            return JsString::from(s);
+        }
+
+        // Appending a short piece to a rope whose right half is also short:
+        // merge the two pieces so `+=` loops don't create a node per append.
+        if let StrRepr::Rope(node) = &self.0
+            && node.flat.get().is_none()
+            && let Some((left, right)) = node.parts.borrow().as_ref()
+            && right.len() + other.len() < ROPE_MIN_LEN
+        {
+            let merged = right.cheap_clone().concat(other);
+            return JsString::rope(left.cheap_clone(), merged);
+        }
+
+        JsString::rope(self, other.cheap_clone())
+    }
+
+    fn rope(left: JsString, right: JsString) -> JsString {
+        JsString(StrRepr::Rope(Rc::new(RopeNode {
+            len: left.len() + right.len(),
+            chars: left.char_count() + right.char_count(),
+            flat: OnceCell::new(),
+            parts: RefCell::new(Some((left, right))),
+        })))
+    }
+}
+
+impl StrRepr {
+    /// Address of the shared allocation, used for identity comparisons
+    fn ptr_addr(&self) -> usize {
+        match self {
+            StrRepr::Flat(s) => Rc::as_ptr(s) as *const () as usize,
+            StrRepr::Rope(node) => Rc::as_ptr(node) as *const () as usize,
+        }
+    }
+}
+
+impl PartialEq for JsString {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.ptr_addr() == other.0.ptr_addr()
+            || (self.len() == other.len() && self.as_str() == other.as_str())
+    }
+}
+
+impl Eq for JsString {}
+
+impl Hash for JsString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
     }
 }
 
 impl AsRef<str> for JsString {
     fn as_ref(&self) -> &str {
-        &self.0
+        self.as_str()
     }
 }
 
 impl core::borrow::Borrow<str> for JsString {
     fn borrow(&self) -> &str {
-        &self.0
+        self.as_str()
     }
 }
 
 impl PartialEq<str> for JsString {
     fn eq(&self, other: &str) -> bool {
-        self.0.as_ref() == other
+        self.as_str() == other
     }
 }
 
 impl PartialEq<&str> for JsString {
     fn eq(&self, other: &&str) -> bool {
-        self.0.as_ref() == *other
+        self.as_str() == *other
     }
 }
 
 impl From<&str> for JsString {
     fn from(s: &str) -> Self {
-        JsString(s.into())
+        JsString(StrRepr::Flat(s.into()))
     }
 }
 
 impl From<String> for JsString {
     fn from(s: String) -> Self {
-        JsString(s.into())
+        JsString(StrRepr::Flat(s.into()))
     }
 }
 
 impl fmt::Debug for JsString {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "\"{}\"", self.0)
+        write!(f, "\"{}\"", self.as_str())
     }
 }
 
 impl fmt::Display for JsString {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.as_str())
     }
 }
 
@@ -1021,9 +1483,13 @@ impl core::ops::Add<&str> for JsString {
     type Output = JsString;
 
     fn add(self, other: &str) -> JsString {
-        let mut s = String::from(&*self.0);
-        s.push_str(other);
-        JsString::from(s)
+        if self.len() + other.len() < ROPE_MIN_LEN {
+            let mut s = String::with_capacity(self.len() + other.len());
+            s.push_str(self.as_str());
+            s.push_str(other);
+            return JsString::from(s);
+        }
+        self.concat(&JsString::from(other))
     }
 }
 
@@ -1031,15 +1497,13 @@ impl core::ops::Add<&JsString> for JsString {
     type Output = JsString;
 
     fn add(self, other: &JsString) -> JsString {
-        let mut s = String::from(&*self.0);
-        s.push_str(&other.0);
-        JsString::from(s)
+        self.concat(other)
     }
 }
 
 /// JavaScript Symbol primitive
 /// Symbols are unique identifiers, optionally with a description
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct JsSymbol {
     /// Unique identifier for this symbol
     id: u64,
@@ -1059,6 +1523,17 @@ impl JsSymbol {
     }
 }
 
+/// `Symbol(description)`, or `Symbol()#id` without a description: ids are
+/// handed out in creation order, so the same script prints the same ids
+impl fmt::Debug for JsSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.description {
+            Some(desc) => write!(f, "Symbol({})", desc.as_str()),
+            None => write!(f, "Symbol()#{}", self.id),
+        }
+    }
+}
+
 impl PartialEq for JsSymbol {
     fn eq(&self, other: &Self) -> bool {
         self.id == other.id
@@ -1091,12 +1566,16 @@ impl Reset for JsObject {
         self.prototype = None;
         self.extensible = true;
         self.frozen = false;
+        self.deep_frozen = false;
         self.sealed = false;
         self.null_prototype = false;
+        self.strict_reads = false;
         // clear() preserves capacity, avoiding reallocation for reused objects
         self.properties.clear();
         self.exotic = ExoticObject::Ordinary;
         self.private_fields = None;
+        self.enumerable_keys = None;
+        self.enumerations = 0;
     }
 }
 
@@ -1138,7 +1617,8 @@ impl Traceable for JsObject {
                             }
                         }
                     }
-                    JsFunction::PromiseResolve(promise) | JsFunction::PromiseReject(promise) => {
+                    JsFunction::PromiseResolve { promise, .. }
+                    | JsFunction::PromiseReject { promise, .. } => {
                         visitor(promise.copy_ref());
                     }
                     JsFunction::PromiseAllFulfill { state, .. } => {
@@ -1188,6 +1668,10 @@ impl Traceable for JsObject {
                         // Trace the associated proxy object
                         visitor(proxy.copy_ref());
                     }
+                    JsFunction::AsyncGeneratorResume { generator, .. } => {
+                        // Trace the generator waiting on the awaited promise
+                        visitor(generator.copy_ref());
+                    }
                     JsFunction::Native(_)
                     | JsFunction::AccessorGetter
                     | JsFunction::AccessorSetter => {}
@@ -1243,7 +1727,9 @@ impl Traceable for JsObject {
             ExoticObject::BytecodeGenerator(state) => {
                 let state = state.borrow();
                 // Trace closure environment
-                visitor(state.closure.copy_ref());
+                if let Some(closure) = &state.closure {
+                    visitor(closure.copy_ref());
+                }
                 // Trace arguments
                 for arg in &state.args {
                     if let JsValue::Object(obj) = arg {
@@ -1272,6 +1758,9 @@ impl Traceable for JsObject {
                 if let Some(env) = &state.current_env {
                     visitor(env.copy_ref());
                 }
+                for env in &state.saved_env_stack {
+                    visitor(env.copy_ref());
+                }
                 // Trace delegated iterator for yield*
                 if let Some((iter_obj, next_method)) = &state.delegated_iterator {
                     visitor(iter_obj.copy_ref());
@@ -1283,6 +1772,22 @@ impl Traceable for JsObject {
                 if let Some(JsValue::Object(obj)) = &state.throw_value {
                     visitor(obj.copy_ref());
                 }
+                // Trace return value for generator.return()
+                if let Some(JsValue::Object(obj)) = &state.return_value {
+                    visitor(obj.copy_ref());
+                }
+                // Trace queued async generator requests
+                for request in &state.queue {
+                    visitor(request.promise.copy_ref());
+                    if let JsValue::Object(obj) = &request.value {
+                        visitor(obj.copy_ref());
+                    }
+                }
+                // Trace the environment and promise of a pending await
+                if let Some(awaiting) = &state.suspended_await {
+                    visitor(awaiting.env.copy_ref());
+                    visitor(awaiting.waiting_on.copy_ref());
+                }
             }
             ExoticObject::Environment(env_data) => {
                 // Trace all bindings in the environment
@@ -1313,9 +1818,35 @@ impl Traceable for JsObject {
             | ExoticObject::StringObj(_)
             | ExoticObject::Symbol(_)
             | ExoticObject::RawJSON(_)
-            | ExoticObject::PendingOrder { .. } => {
+            | ExoticObject::UrlSearchParams(_)
+            | ExoticObject::WeakRef(_)
+            | ExoticObject::PendingOrder { .. }
+            | ExoticObject::Host(_) => {
                 // These exotic types don't contain object references that need tracing
             }
+            ExoticObject::FinalizationRegistry(data) => {
+                // Targets and unregister tokens are weak; only trace what cleanup needs
+                let data = data.borrow();
+                if let JsValue::Object(obj) = &data.cleanup {
+                    visitor(obj.copy_ref());
+                }
+                for cell in &data.cells {
+                    if let JsValue::Object(obj) = &cell.held_value {
+                        visitor(obj.copy_ref());
+                    }
+                }
+            }
+            ExoticObject::DisposableStack(data) => {
+                let data = data.borrow();
+                for resource in data.resources.iter().chain(&data.pending_error) {
+                    if let JsValue::Object(obj) = resource {
+                        visitor(obj.copy_ref());
+                    }
+                }
+            }
+            ExoticObject::Url(data) => {
+                visitor(data.search_params.copy_ref());
+            }
             ExoticObject::Proxy(proxy_data) => {
                 // Trace target and handler objects
                 visitor(proxy_data.target.copy_ref());
@@ -1334,6 +1865,96 @@ impl Traceable for JsObject {
     }
 }
 
+/// Reachable objects grouped by kind, as returned by
+/// [`Interpreter::gc_census`](crate::Interpreter::gc_census)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GcCensus {
+    /// Plain objects, including module namespaces
+    pub ordinary: usize,
+    pub arrays: usize,
+    /// Functions of every kind: bytecode, native and bound
+    pub functions: usize,
+    pub promises: usize,
+    pub maps: usize,
+    pub sets: usize,
+    /// Scope environments, including the global and module environments
+    pub environments: usize,
+    /// Generator objects, both interpreted and bytecode
+    pub generators: usize,
+    pub proxies: usize,
+    /// Everything else: dates, regexps, boxed primitives, enums, URLs, ...
+    pub other: usize,
+    /// Own properties, array elements and environment bindings across all objects
+    pub properties: usize,
+    /// UTF-8 bytes of the strings those slots hold, counting property keys.
+    /// A string stored in several places is counted once per place.
+    pub string_bytes: usize,
+}
+
+impl GcCensus {
+    /// Total number of objects counted
+    pub fn objects(&self) -> usize {
+        self.ordinary
+            + self.arrays
+            + self.functions
+            + self.promises
+            + self.maps
+            + self.sets
+            + self.environments
+            + self.generators
+            + self.proxies
+            + self.other
+    }
+
+    pub(crate) fn record(&mut self, obj: &JsObject) {
+        let count = match &obj.exotic {
+            ExoticObject::Ordinary => &mut self.ordinary,
+            ExoticObject::Array { .. } => &mut self.arrays,
+            ExoticObject::Function(_) => &mut self.functions,
+            ExoticObject::Promise(_) => &mut self.promises,
+            ExoticObject::Map { .. } => &mut self.maps,
+            ExoticObject::Set { .. } => &mut self.sets,
+            ExoticObject::Environment(_) => &mut self.environments,
+            ExoticObject::Generator(_) | ExoticObject::BytecodeGenerator(_) => &mut self.generators,
+            ExoticObject::Proxy(_) => &mut self.proxies,
+            _ => &mut self.other,
+        };
+        *count += 1;
+
+        for (key, prop) in obj.properties.iter() {
+            self.properties += 1;
+            if let PropertyKey::String(s) = key {
+                self.string_bytes += s.len();
+            }
+            self.record_value(&prop.value);
+        }
+        match &obj.exotic {
+            ExoticObject::Array { elements } => {
+                self.properties += elements.len();
+                for value in elements {
+                    self.record_value(value);
+                }
+            }
+            ExoticObject::Environment(env) => {
+                self.properties += env.bindings.len();
+                for binding in env.bindings.values() {
+                    self.record_value(&binding.value);
+                }
+            }
+            ExoticObject::StringObj(s) | ExoticObject::RawJSON(s) => {
+                self.string_bytes += s.len();
+            }
+            _ => {}
+        }
+    }
+
+    fn record_value(&mut self, value: &JsValue) {
+        if let JsValue::String(s) = value {
+            self.string_bytes += s.len();
+        }
+    }
+}
+
 /// A JavaScript object
 #[derive(Debug)]
 pub struct JsObject {
@@ -1343,10 +1964,17 @@ pub struct JsObject {
     pub extensible: bool,
     /// Whether the object is frozen (no modifications allowed)
     pub frozen: bool,
+    /// Set by deep freeze: everything reachable from the object is frozen
+    /// too, and Map/Set entries can no longer change
+    pub deep_frozen: bool,
     /// Whether the object is sealed (no new properties, but existing can be modified)
     pub sealed: bool,
     /// Whether this object was explicitly created with null prototype (Object.create(null))
     pub null_prototype: bool,
+    /// Reading a missing property throws under
+    /// `InterpreterConfig::strict_property_access`: set on module namespaces
+    /// and on objects the host deep-freezes
+    pub strict_reads: bool,
     /// Object properties (optimized for small objects)
     pub properties: PropertyStorage,
     /// Exotic object behavior
@@ -1354,6 +1982,22 @@ pub struct JsObject {
     /// Private fields storage (only used by instances of classes with private members)
     /// Key is (ClassBrandId, field_name), value is the private field/method value
     pub private_fields: Option<FxHashMap<PrivateFieldKey, JsValue>>,
+    /// Own enumerable keys kept once the object is enumerated often enough.
+    /// Holds keys only, so the GC never needs to trace it.
+    enumerable_keys: Option<Box<CachedKeys>>,
+    /// Uncached enumerations so far, counting up to [`KEY_CACHE_THRESHOLD`]
+    enumerations: u8,
+}
+
+/// Enumerations an object goes through before its key list is cached, so
+/// objects that are only enumerated once or twice don't pay for a cache
+const KEY_CACHE_THRESHOLD: u8 = 3;
+
+/// An own enumerable key list and the property generation it was built from
+#[derive(Debug)]
+struct CachedKeys {
+    generation: u32,
+    keys: Vec<PropertyKey>,
 }
 
 impl JsObject {
@@ -1363,11 +2007,15 @@ impl JsObject {
             prototype: None,
             extensible: true,
             frozen: false,
+            deep_frozen: false,
             sealed: false,
             null_prototype: false,
+            strict_reads: false,
             properties: PropertyStorage::new(),
             exotic: ExoticObject::Ordinary,
             private_fields: None,
+            enumerable_keys: None,
+            enumerations: 0,
         }
     }
 
@@ -1377,11 +2025,15 @@ impl JsObject {
             prototype: None,
             extensible: true,
             frozen: false,
+            deep_frozen: false,
             sealed: false,
             null_prototype: false,
+            strict_reads: false,
             properties: PropertyStorage::with_capacity(capacity),
             exotic: ExoticObject::Ordinary,
             private_fields: None,
+            enumerable_keys: None,
+            enumerations: 0,
         }
     }
 
@@ -1391,11 +2043,15 @@ impl JsObject {
             prototype: Some(prototype),
             extensible: true,
             frozen: false,
+            deep_frozen: false,
             sealed: false,
             null_prototype: false,
+            strict_reads: false,
             properties: PropertyStorage::new(),
             exotic: ExoticObject::Ordinary,
             private_fields: None,
+            enumerable_keys: None,
+            enumerations: 0,
         }
     }
 
@@ -1426,6 +2082,7 @@ impl JsObject {
                 // A proxy is callable if its target is callable
                 !data.revoked && data.target.borrow().is_callable()
             }
+            ExoticObject::Host(data) => data.callable,
             _ => false,
         }
     }
@@ -1435,6 +2092,16 @@ impl JsObject {
         self.properties.get(key)
     }
 
+    /// The character a string wrapper exposes at index `key`
+    fn string_wrapper_char(&self, key: &PropertyKey) -> Option<char> {
+        match (&self.exotic, key) {
+            (ExoticObject::StringObj(s), PropertyKey::Index(idx)) => {
+                s.as_str().chars().nth(*idx as usize)
+            }
+            _ => None,
+        }
+    }
+
     /// Get a property, searching the prototype chain
     pub fn get_property(&self, key: &PropertyKey) -> Option<JsValue> {
         // For arrays, handle index access and length from elements Vec
@@ -1450,6 +2117,11 @@ impl JsObject {
             }
         }
 
+        // String wrappers expose their characters as indices
+        if let Some(c) = self.string_wrapper_char(key) {
+            return Some(JsValue::String(JsString::from(c.to_string())));
+        }
+
         // For functions, handle name and length properties
         if let ExoticObject::Function(ref func) = self.exotic
             && let PropertyKey::String(s) = key
@@ -1585,13 +2257,28 @@ impl JsObject {
             match key {
                 PropertyKey::Index(idx) => {
                     if let Some(val) = elements.get(*idx as usize) {
-                        return Some((Property::data(val.clone()), false));
+                        return Some((
+                            Property::with_attributes(
+                                val.clone(),
+                                !self.frozen,
+                                true,
+                                !self.frozen,
+                            ),
+                            false,
+                        ));
                     }
                     // Index out of bounds - return None (falls through to prototype)
                 }
+                // `length` is non-enumerable and non-configurable, and only
+                // becomes read-only once the array is frozen
                 PropertyKey::String(s) if s.as_str() == "length" => {
                     return Some((
-                        Property::data(JsValue::Number(elements.len() as f64)),
+                        Property::with_attributes(
+                            JsValue::Number(elements.len() as f64),
+                            !self.frozen,
+                            false,
+                            false,
+                        ),
                         false,
                     ));
                 }
@@ -1599,6 +2286,12 @@ impl JsObject {
             }
         }
 
+        // String wrapper characters are read-only and enumerable
+        if let Some(c) = self.string_wrapper_char(key) {
+            let value = JsValue::String(JsString::from(c.to_string()));
+            return Some((Property::with_attributes(value, false, true, false), false));
+        }
+
         // For Maps, compute size from entries
         if let ExoticObject::Map { ref entries } = self.exotic
             && let PropertyKey::String(s) = key
@@ -1615,9 +2308,11 @@ impl JsObject {
             return Some((Property::data(JsValue::Number(entries.len() as f64)), false));
         }
 
-        // For functions, handle name and length properties
+        // For functions, handle name and length properties; an own property
+        // (SetFunctionName, Object.defineProperty) takes precedence
         if let ExoticObject::Function(ref func) = self.exotic
             && let PropertyKey::String(s) = key
+            && !self.properties.contains_key(key)
         {
             match s.as_str() {
                 "name" => {
@@ -1800,12 +2495,10 @@ impl JsObject {
                 // Also update reverse mapping if value is numeric
                 if let JsValue::Number(n) = &value {
                     // Find and update the reverse mapping entry
-                    let reverse_key =
-                        if math::fract(*n) == 0.0 && *n >= 0.0 && *n <= u32::MAX as f64 {
-                            PropertyKey::Index(*n as u32)
-                        } else {
-                            PropertyKey::String(JsString::from(ToString::to_string(n)))
-                        };
+                    let reverse_key = match PropertyKey::array_index_from_number(*n) {
+                        Some(idx) => PropertyKey::Index(idx),
+                        None => PropertyKey::String(JsString::from(ToString::to_string(n))),
+                    };
                     self.properties.insert(
                         reverse_key,
                         Property::data(JsValue::String(s.cheap_clone())),
@@ -1816,12 +2509,11 @@ impl JsObject {
             // If not an existing member, allow adding new properties
         }
 
-        if let Some(prop) = self.properties.get_mut(&key) {
-            // Only set if writable
-            if prop.writable() {
-                prop.value = value;
-            }
-        } else if self.extensible && !self.sealed {
+        // Only set if writable
+        if let Err(value) = self.properties.write_value(&key, value)
+            && self.extensible
+            && !self.sealed
+        {
             // Sealed objects cannot have new properties added
             self.properties.insert(key, Property::data(value));
         }
@@ -1832,6 +2524,16 @@ impl JsObject {
         self.properties.insert(key, prop);
     }
 
+    /// Whether `key` is an own property that `delete` must refuse to remove
+    pub fn is_non_configurable_own(&self, key: &PropertyKey) -> bool {
+        if matches!(self.exotic, ExoticObject::Array { .. }) && key.eq_str("length") {
+            return true;
+        }
+        self.properties
+            .get(key)
+            .is_some_and(|prop| !prop.configurable())
+    }
+
     /// Check if object has own property
     pub fn has_own_property(&self, key: &PropertyKey) -> bool {
         self.properties.contains_key(key)
@@ -1881,6 +2583,90 @@ impl JsObject {
     pub fn is_array(&self) -> bool {
         matches!(self.exotic, ExoticObject::Array { .. })
     }
+
+    /// Own property keys in spec order: integer indices ascending, then string
+    /// keys in insertion order, then symbol keys in insertion order.
+    ///
+    /// Array elements and the characters of string wrappers are included as
+    /// index keys. When `enumerable_only` is set, non-enumerable properties
+    /// are skipped.
+    pub fn ordered_own_keys(&self, enumerable_only: bool) -> Vec<PropertyKey> {
+        let element_count = match &self.exotic {
+            ExoticObject::Array { elements } => elements.len() as u32,
+            ExoticObject::StringObj(s) => s.char_count() as u32,
+            _ => 0,
+        };
+        let mut indices: Vec<u32> = (0..element_count).collect();
+        let mut strings = Vec::new();
+        let mut symbols = Vec::new();
+
+        for (key, prop) in self.properties.iter() {
+            if enumerable_only && !prop.enumerable() {
+                continue;
+            }
+            match key {
+                PropertyKey::Index(idx) if *idx < element_count => {}
+                PropertyKey::Index(idx) => indices.push(*idx),
+                PropertyKey::String(_) => strings.push(key.clone()),
+                PropertyKey::Symbol(_) => symbols.push(key.clone()),
+            }
+        }
+
+        indices.sort_unstable();
+        let mut keys: Vec<PropertyKey> = indices.into_iter().map(PropertyKey::Index).collect();
+        keys.extend(strings);
+        keys.extend(symbols);
+        keys
+    }
+
+    /// All own keys in spec order, as [[OwnPropertyKeys]] lists them.
+    ///
+    /// Like `ordered_own_keys(false)`, with the `length` of arrays and string
+    /// wrappers first among the string keys, where creation order puts it.
+    pub fn own_property_keys(&self) -> Vec<PropertyKey> {
+        let mut keys = self.ordered_own_keys(false);
+        if matches!(
+            self.exotic,
+            ExoticObject::Array { .. } | ExoticObject::StringObj(_)
+        ) {
+            let length = PropertyKey::String(JsString::from("length"));
+            keys.retain(|key| *key != length);
+            let first_string = keys
+                .iter()
+                .position(|key| !matches!(key, PropertyKey::Index(_)))
+                .unwrap_or(keys.len());
+            keys.insert(first_string, length);
+        }
+        keys
+    }
+
+    /// Own enumerable keys in spec order, the same as `ordered_own_keys(true)`.
+    ///
+    /// Ordinary objects that keep getting enumerated (stringified, spread,
+    /// passed to `Object.keys`, ...) reuse the list until their keys or
+    /// attributes change.
+    pub fn enumerable_own_keys(&mut self) -> Vec<PropertyKey> {
+        if !matches!(self.exotic, ExoticObject::Ordinary) {
+            return self.ordered_own_keys(true);
+        }
+        let generation = self.properties.generation();
+        if let Some(cached) = &self.enumerable_keys
+            && cached.generation == generation
+        {
+            return cached.keys.clone();
+        }
+
+        let keys = self.ordered_own_keys(true);
+        if self.enumerations < KEY_CACHE_THRESHOLD {
+            self.enumerations += 1;
+        } else {
+            self.enumerable_keys = Some(Box::new(CachedKeys {
+                generation,
+                keys: keys.clone(),
+            }));
+        }
+        keys
+    }
 }
 
 impl Default for JsObject {
@@ -1900,25 +2686,36 @@ pub enum PropertyKey {
 }
 
 impl PropertyKey {
+    /// Largest array index; 2^32 - 1 itself is an ordinary property name
+    pub const MAX_ARRAY_INDEX: u32 = u32::MAX - 1;
+
+    /// The array index named by `s`: a canonical decimal integer in [0, 2^32 - 2]
+    pub fn array_index(s: &str) -> Option<u32> {
+        let first = s.bytes().next()?;
+        if !first.is_ascii_digit() || (first == b'0' && s.len() > 1) {
+            return None;
+        }
+        s.parse::<u32>()
+            .ok()
+            .filter(|idx| *idx <= Self::MAX_ARRAY_INDEX)
+    }
+
+    /// The array index equal to `n`, if it is an integer in [0, 2^32 - 2]
+    pub fn array_index_from_number(n: f64) -> Option<u32> {
+        let idx = n as u32;
+        (idx as f64 == n && n >= 0.0 && idx <= Self::MAX_ARRAY_INDEX).then_some(idx)
+    }
+
     pub fn from_value(value: &JsValue) -> Self {
         match value {
-            JsValue::Number(n) => {
-                let idx = *n as u32;
-                if idx as f64 == *n && *n >= 0.0 {
-                    PropertyKey::Index(idx)
-                } else {
-                    PropertyKey::String(value.to_js_string())
-                }
-            }
-            JsValue::String(s) => {
-                // Check if it's a valid array index
-                if let Ok(idx) = s.parse::<u32>()
-                    && idx.to_string() == s.as_str()
-                {
-                    return PropertyKey::Index(idx);
-                }
-                PropertyKey::String(s.cheap_clone())
-            }
+            JsValue::Number(n) => match Self::array_index_from_number(*n) {
+                Some(idx) => PropertyKey::Index(idx),
+                None => PropertyKey::String(value.to_js_string()),
+            },
+            JsValue::String(s) => match Self::array_index(s.as_str()) {
+                Some(idx) => PropertyKey::Index(idx),
+                None => PropertyKey::String(s.cheap_clone()),
+            },
             JsValue::Symbol(s) => PropertyKey::Symbol(s.clone()),
             _ => PropertyKey::String(value.to_js_string()),
         }
@@ -2132,17 +2929,27 @@ const INLINE_PROPERTY_CAPACITY: usize = 2;
 ///
 /// Most JavaScript objects have only a few properties. By storing up to 2 properties
 /// inline (without heap allocation), we avoid the overhead of a HashMap for common cases.
-/// When the object grows beyond 2 properties, we transparently switch to a HashMap.
+/// When the object grows beyond 2 properties, we transparently switch to an IndexMap.
+/// Both representations preserve insertion order, which property enumeration relies on.
+///
+/// A generation counter changes whenever the set of keys or their enumerability
+/// may have changed, so key lists derived from the storage can be cached.
+#[derive(Debug)]
+pub struct PropertyStorage {
+    entries: PropertyEntries,
+    generation: u32,
+}
+
 #[derive(Debug)]
-pub enum PropertyStorage {
+enum PropertyEntries {
     /// Inline storage for small objects (≤2 properties).
     /// Uses a fixed-size array with a length counter.
     Inline {
         len: u8,
         entries: [(PropertyKey, Property); INLINE_PROPERTY_CAPACITY],
     },
-    /// HashMap storage for larger objects.
-    Map(FxHashMap<PropertyKey, Property>),
+    /// IndexMap storage for larger objects.
+    Map(IndexMap<PropertyKey, Property>),
 }
 
 impl Default for PropertyStorage {
@@ -2155,32 +2962,35 @@ impl PropertyStorage {
     /// Create empty inline storage.
     #[inline]
     pub fn new() -> Self {
-        PropertyStorage::Inline {
-            len: 0,
-            entries: core::array::from_fn(|_| {
-                (PropertyKey::Index(0), Property::data(JsValue::Undefined))
-            }),
+        Self {
+            entries: PropertyEntries::Inline {
+                len: 0,
+                entries: core::array::from_fn(|_| {
+                    (PropertyKey::Index(0), Property::data(JsValue::Undefined))
+                }),
+            },
+            generation: 0,
         }
     }
 
     /// Create storage with pre-allocated capacity.
-    /// If capacity > INLINE_PROPERTY_CAPACITY, creates a HashMap.
+    /// If capacity > INLINE_PROPERTY_CAPACITY, creates an IndexMap.
     #[inline]
     pub fn with_capacity(capacity: usize) -> Self {
         if capacity <= INLINE_PROPERTY_CAPACITY {
             Self::new()
         } else {
-            PropertyStorage::Map(FxHashMap::with_capacity_and_hasher(
-                capacity,
-                Default::default(),
-            ))
+            Self {
+                entries: PropertyEntries::Map(index_map_with_capacity(capacity)),
+                generation: 0,
+            }
         }
     }
 
     /// Reserve capacity. Only meaningful for Map variant.
     #[inline]
     pub fn reserve(&mut self, additional: usize) {
-        if let PropertyStorage::Map(map) = self {
+        if let PropertyEntries::Map(map) = &mut self.entries {
             map.reserve(additional);
         }
         // For Inline, we'll convert to Map when needed during insert
@@ -2189,8 +2999,8 @@ impl PropertyStorage {
     /// Get a property by key.
     #[inline]
     pub fn get(&self, key: &PropertyKey) -> Option<&Property> {
-        match self {
-            PropertyStorage::Inline { len, entries } => {
+        match &self.entries {
+            PropertyEntries::Inline { len, entries } => {
                 for entry in entries.get(..(*len as usize)).unwrap_or_default() {
                     if &entry.0 == key {
                         return Some(&entry.1);
@@ -2198,15 +3008,41 @@ impl PropertyStorage {
                 }
                 None
             }
-            PropertyStorage::Map(map) => map.get(key),
+            PropertyEntries::Map(map) => map.get(key),
         }
     }
 
     /// Get a mutable reference to a property by key.
+    ///
+    /// The caller may change the property's attributes, so this counts as a
+    /// change to the key list; use [`Self::write_value`] to only update a value.
     #[inline]
     pub fn get_mut(&mut self, key: &PropertyKey) -> Option<&mut Property> {
-        match self {
-            PropertyStorage::Inline { len, entries } => {
+        self.generation = self.generation.wrapping_add(1);
+        self.find_mut(key)
+    }
+
+    /// Update an existing property's value, leaving its attributes alone.
+    ///
+    /// Read-only properties keep their value. Hands `value` back if there is no
+    /// property with this key.
+    #[inline]
+    pub fn write_value(&mut self, key: &PropertyKey, value: JsValue) -> Result<(), JsValue> {
+        match self.find_mut(key) {
+            Some(prop) => {
+                if prop.writable() {
+                    prop.value = value;
+                }
+                Ok(())
+            }
+            None => Err(value),
+        }
+    }
+
+    #[inline]
+    fn find_mut(&mut self, key: &PropertyKey) -> Option<&mut Property> {
+        match &mut self.entries {
+            PropertyEntries::Inline { len, entries } => {
                 for entry in entries.get_mut(..(*len as usize)).unwrap_or_default() {
                     if &entry.0 == key {
                         return Some(&mut entry.1);
@@ -2214,14 +3050,26 @@ impl PropertyStorage {
                 }
                 None
             }
-            PropertyStorage::Map(map) => map.get_mut(key),
+            PropertyEntries::Map(map) => map.get_mut(key),
         }
     }
 
     /// Insert or update a property. Returns the old value if the key existed.
     pub fn insert(&mut self, key: PropertyKey, value: Property) -> Option<Property> {
-        match self {
-            PropertyStorage::Inline { len, entries } => {
+        let enumerable = value.enumerable();
+        let old = self.insert_entry(key, value);
+        if old
+            .as_ref()
+            .is_none_or(|old| old.enumerable() != enumerable)
+        {
+            self.generation = self.generation.wrapping_add(1);
+        }
+        old
+    }
+
+    fn insert_entry(&mut self, key: PropertyKey, value: Property) -> Option<Property> {
+        match &mut self.entries {
+            PropertyEntries::Inline { len, entries } => {
                 let current_len = *len as usize;
 
                 // Check if key already exists
@@ -2240,10 +3088,7 @@ impl PropertyStorage {
                 }
 
                 // Need to convert to Map (current_len == INLINE_PROPERTY_CAPACITY)
-                let mut map = FxHashMap::with_capacity_and_hasher(
-                    INLINE_PROPERTY_CAPACITY + 1,
-                    Default::default(),
-                );
+                let mut map = index_map_with_capacity(INLINE_PROPERTY_CAPACITY + 1);
                 for entry in entries.iter_mut() {
                     let (k, v) = mem::replace(
                         entry,
@@ -2252,18 +3097,18 @@ impl PropertyStorage {
                     map.insert(k, v);
                 }
                 map.insert(key, value);
-                *self = PropertyStorage::Map(map);
+                self.entries = PropertyEntries::Map(map);
                 None
             }
-            PropertyStorage::Map(map) => map.insert(key, value),
+            PropertyEntries::Map(map) => map.insert(key, value),
         }
     }
 
     /// Check if a key exists.
     #[inline]
     pub fn contains_key(&self, key: &PropertyKey) -> bool {
-        match self {
-            PropertyStorage::Inline { len, entries } => {
+        match &self.entries {
+            PropertyEntries::Inline { len, entries } => {
                 for entry in entries.get(..(*len as usize)).unwrap_or_default() {
                     if &entry.0 == key {
                         return true;
@@ -2271,14 +3116,22 @@ impl PropertyStorage {
                 }
                 false
             }
-            PropertyStorage::Map(map) => map.contains_key(key),
+            PropertyEntries::Map(map) => map.contains_key(key),
         }
     }
 
     /// Remove a property by key. Returns the removed value if it existed.
     pub fn remove(&mut self, key: &PropertyKey) -> Option<Property> {
-        match self {
-            PropertyStorage::Inline { len, entries } => {
+        let removed = self.remove_entry(key);
+        if removed.is_some() {
+            self.generation = self.generation.wrapping_add(1);
+        }
+        removed
+    }
+
+    fn remove_entry(&mut self, key: &PropertyKey) -> Option<Property> {
+        match &mut self.entries {
+            PropertyEntries::Inline { len, entries } => {
                 let current_len = *len as usize;
                 let mut found_idx = None;
                 for (i, entry) in entries
@@ -2293,7 +3146,7 @@ impl PropertyStorage {
                     }
                 }
                 if let Some(i) = found_idx {
-                    // Swap with last element and decrement len
+                    // Shift later entries down to keep insertion order, then decrement len
                     let removed = if let Some(entry) = entries.get_mut(i) {
                         mem::replace(
                             entry,
@@ -2302,8 +3155,8 @@ impl PropertyStorage {
                     } else {
                         return None;
                     };
-                    if i < current_len - 1 {
-                        entries.swap(i, current_len - 1);
+                    if let Some(tail) = entries.get_mut(i..current_len) {
+                        tail.rotate_left(1);
                     }
                     *len -= 1;
                     Some(removed.1)
@@ -2311,34 +3164,42 @@ impl PropertyStorage {
                     None
                 }
             }
-            PropertyStorage::Map(map) => map.remove(key),
+            PropertyEntries::Map(map) => map.shift_remove(key),
         }
     }
 
     /// Clear all properties.
     #[inline]
     pub fn clear(&mut self) {
-        match self {
-            PropertyStorage::Inline { len, entries } => {
+        self.generation = self.generation.wrapping_add(1);
+        match &mut self.entries {
+            PropertyEntries::Inline { len, entries } => {
                 // Reset entries to avoid holding references
                 for entry in entries.get_mut(..(*len as usize)).unwrap_or_default() {
                     *entry = (PropertyKey::Index(0), Property::data(JsValue::Undefined));
                 }
                 *len = 0;
             }
-            PropertyStorage::Map(map) => map.clear(),
+            PropertyEntries::Map(map) => map.clear(),
         }
     }
 
     /// Get the number of properties.
     #[inline]
     pub fn len(&self) -> usize {
-        match self {
-            PropertyStorage::Inline { len, .. } => *len as usize,
-            PropertyStorage::Map(map) => map.len(),
+        match &self.entries {
+            PropertyEntries::Inline { len, .. } => *len as usize,
+            PropertyEntries::Map(map) => map.len(),
         }
     }
 
+    /// Counter that changes whenever keys are added or removed or attributes
+    /// may have changed. Value writes through [`Self::write_value`] keep it.
+    #[inline]
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
     /// Check if empty.
     #[inline]
     pub fn is_empty(&self) -> bool {
@@ -2347,26 +3208,29 @@ impl PropertyStorage {
 
     /// Iterate over all (key, value) pairs.
     pub fn iter(&self) -> PropertyStorageIter<'_> {
-        match self {
-            PropertyStorage::Inline { len, entries } => PropertyStorageIter::Inline {
+        match &self.entries {
+            PropertyEntries::Inline { len, entries } => PropertyStorageIter::Inline {
                 entries,
                 index: 0,
                 len: *len as usize,
             },
-            PropertyStorage::Map(map) => PropertyStorageIter::Map(map.iter()),
+            PropertyEntries::Map(map) => PropertyStorageIter::Map(map.iter()),
         }
     }
 
     /// Iterate over all (key, value) pairs mutably.
+    ///
+    /// Like [`Self::get_mut`], this counts as a change to the key list.
     pub fn iter_mut(&mut self) -> PropertyStorageIterMut<'_> {
-        match self {
-            PropertyStorage::Inline { len, entries } => {
+        self.generation = self.generation.wrapping_add(1);
+        match &mut self.entries {
+            PropertyEntries::Inline { len, entries } => {
                 let len = *len as usize;
                 PropertyStorageIterMut::Inline {
                     entries: entries.get_mut(..len).unwrap_or_default(),
                 }
             }
-            PropertyStorage::Map(map) => PropertyStorageIterMut::Map(map.iter_mut()),
+            PropertyEntries::Map(map) => PropertyStorageIterMut::Map(map.iter_mut()),
         }
     }
 
@@ -2388,10 +3252,7 @@ pub enum PropertyStorageIter<'a> {
         index: usize,
         len: usize,
     },
-    #[cfg(feature = "std")]
-    Map(std::collections::hash_map::Iter<'a, PropertyKey, Property>),
-    #[cfg(not(feature = "std"))]
-    Map(hashbrown::hash_map::Iter<'a, PropertyKey, Property>),
+    Map(indexmap::map::Iter<'a, PropertyKey, Property>),
 }
 
 impl<'a> Iterator for PropertyStorageIter<'a> {
@@ -2422,10 +3283,7 @@ pub enum PropertyStorageIterMut<'a> {
     Inline {
         entries: &'a mut [(PropertyKey, Property)],
     },
-    #[cfg(feature = "std")]
-    Map(std::collections::hash_map::IterMut<'a, PropertyKey, Property>),
-    #[cfg(not(feature = "std"))]
-    Map(hashbrown::hash_map::IterMut<'a, PropertyKey, Property>),
+    Map(indexmap::map::IterMut<'a, PropertyKey, Property>),
 }
 
 impl<'a> Iterator for PropertyStorageIterMut<'a> {
@@ -2506,30 +3364,18 @@ impl Default for EnvironmentData {
 pub struct JsMapKey(pub JsValue);
 
 impl JsMapKey {
-    /// Check SameValueZero equality (used by Map/Set for key comparison)
-    fn same_value_zero(a: &JsValue, b: &JsValue) -> bool {
-        match (a, b) {
-            (JsValue::Number(x), JsValue::Number(y)) => {
-                // NaN equals NaN, -0 equals +0
-                if x.is_nan() && y.is_nan() {
-                    return true;
-                }
-                x == y
-            }
-            (JsValue::String(x), JsValue::String(y)) => x == y,
-            (JsValue::Boolean(x), JsValue::Boolean(y)) => x == y,
-            (JsValue::Null, JsValue::Null) => true,
-            (JsValue::Undefined, JsValue::Undefined) => true,
-            (JsValue::Object(x), JsValue::Object(y)) => x.id() == y.id(),
-            (JsValue::Symbol(x), JsValue::Symbol(y)) => x.id == y.id,
-            _ => false,
+    /// Key for storing `value`, with -0 normalized to +0 as Map.set and Set.add do
+    pub fn canonical(value: JsValue) -> Self {
+        match value {
+            JsValue::Number(0.0) => JsMapKey(JsValue::Number(0.0)),
+            value => JsMapKey(value),
         }
     }
 }
 
 impl PartialEq for JsMapKey {
     fn eq(&self, other: &Self) -> bool {
-        Self::same_value_zero(&self.0, &other.0)
+        self.0.same_value_zero(&other.0)
     }
 }
 
@@ -2609,8 +3455,22 @@ pub enum ExoticObject {
     Enum(EnumData),
     /// Proxy exotic object - wraps target with handler traps
     Proxy(ProxyData),
+    /// Host-defined exotic object - dispatches to the hooks of a registered host class
+    Host(crate::interpreter::host_class::HostObjectData),
     /// Raw JSON exotic object - stores a JSON string for literal insertion in JSON.stringify
     RawJSON(JsString),
+    /// URL exotic object - stores the parsed URL and its linked searchParams object
+    Url(Box<crate::interpreter::builtins::url::UrlData>),
+    /// URLSearchParams exotic object - stores name/value pairs preserving insertion order
+    UrlSearchParams(Rc<RefCell<crate::interpreter::builtins::url::SearchParamsData>>),
+    /// WeakRef exotic object - refers to its target without keeping it alive
+    WeakRef(crate::gc::WeakGc<JsObject>),
+    /// FinalizationRegistry exotic object - stores the cleanup callback and registrations
+    FinalizationRegistry(
+        Rc<RefCell<crate::interpreter::builtins::weakref::FinalizationRegistryData>>,
+    ),
+    /// DisposableStack or AsyncDisposableStack exotic object - stores the resources to dispose
+    DisposableStack(Rc<RefCell<crate::interpreter::builtins::disposable::DisposableStackData>>),
     /// Pending order marker - triggers immediate VM suspension
     /// The id is the OrderId that will be used to match the response from host
     /// When detected, VM suspends and waits for host to provide a value via fulfill_orders()
@@ -2855,8 +3715,8 @@ pub enum GeneratorStatus {
 pub struct BytecodeGeneratorState {
     /// The bytecode chunk for the generator function
     pub chunk: Rc<crate::compiler::BytecodeChunk>,
-    /// The captured closure environment
-    pub closure: JsObjectRef,
+    /// The captured closure environment, released once the generator completes
+    pub closure: Option<JsObjectRef>,
     /// Arguments passed to the generator function
     pub args: Vec<JsValue>,
     /// The `this` value passed when the generator function was called
@@ -2877,6 +3737,8 @@ pub struct BytecodeGeneratorState {
     pub saved_call_stack: Vec<crate::interpreter::bytecode_vm::CallFrame>,
     /// Saved try stack (for resumption)
     pub saved_try_stack: Vec<crate::interpreter::bytecode_vm::TryHandler>,
+    /// Environments saved by the scopes open at the yield (for resumption)
+    pub saved_env_stack: Vec<JsObjectRef>,
     /// Register to store the result of yield
     pub yield_result_register: Option<u8>,
     /// The function environment (created on first call, reused on subsequent calls)
@@ -2889,6 +3751,73 @@ pub struct BytecodeGeneratorState {
     pub is_async: bool,
     /// Exception to throw when resuming (for generator.throw())
     pub throw_value: Option<JsValue>,
+    /// Value to return when resuming (for generator.return())
+    pub return_value: Option<JsValue>,
+    /// Pending next/throw/return calls on an async generator, oldest first
+    pub queue: VecDeque<AsyncGeneratorRequest>,
+    /// Whether the oldest queued request is executing or awaiting
+    pub running: bool,
+    /// Body of an async generator parked on an `await`
+    pub suspended_await: Option<Box<AsyncGeneratorAwait>>,
+}
+
+/// Which generator method queued an async generator request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsyncGeneratorRequestKind {
+    Next,
+    Throw,
+    Return,
+}
+
+/// A next/throw/return call waiting for its turn on an async generator
+pub struct AsyncGeneratorRequest {
+    pub kind: AsyncGeneratorRequestKind,
+    /// Argument of the call
+    pub value: JsValue,
+    /// Promise returned to the caller, settled with the request's outcome
+    pub promise: JsObjectRef,
+}
+
+/// VM state of an async generator body suspended on an `await`
+pub struct AsyncGeneratorAwait {
+    /// Saved VM state, including any async calls the body is waiting in
+    pub state: crate::interpreter::bytecode_vm::SavedVmState,
+    /// Register receiving the awaited value
+    pub resume_register: u8,
+    /// Environment active at the `await`
+    pub env: JsObjectRef,
+    /// Promise the body is waiting on
+    pub waiting_on: JsObjectRef,
+}
+
+impl BytecodeGeneratorState {
+    /// Mark the generator completed and drop what only a resumption needs:
+    /// saved registers and stacks, environments, the closure and any
+    /// delegate. A spent generator object kept alive then holds no scope.
+    /// Queued async generator requests stay to be answered.
+    pub fn complete(&mut self) {
+        self.status = GeneratorStatus::Completed;
+        self.closure = None;
+        self.args = Vec::new();
+        self.this_value = JsValue::Undefined;
+        self.sent_value = JsValue::Undefined;
+        self.saved_ip = 0;
+        self.saved_registers = Vec::new();
+        self.saved_call_stack = Vec::new();
+        self.saved_try_stack = Vec::new();
+        self.saved_env_stack = Vec::new();
+        self.yield_result_register = None;
+        self.func_env = None;
+        self.current_env = None;
+        self.delegated_iterator = None;
+        self.throw_value = None;
+        self.return_value = None;
+        self.suspended_await = None;
+        // Other holders of the chunk, such as the generator function, keep it alive
+        if Rc::strong_count(&self.chunk) == 1 {
+            self.chunk = Rc::default();
+        }
+    }
 }
 
 impl fmt::Debug for BytecodeGeneratorState {
@@ -2917,10 +3846,18 @@ pub enum JsFunction {
     Native(NativeFunction),
     /// Bound function (created by Function.prototype.bind)
     Bound(Box<BoundFunctionData>),
-    /// Promise resolve function (has internal [[Promise]] slot)
-    PromiseResolve(JsObjectRef),
-    /// Promise reject function (has internal [[Promise]] slot)
-    PromiseReject(JsObjectRef),
+    /// Promise resolve function (has internal [[Promise]] and [[AlreadyResolved]] slots)
+    PromiseResolve {
+        promise: JsObjectRef,
+        /// Shared with the paired reject function: only the first call of either counts
+        already_resolved: Rc<Cell<bool>>,
+    },
+    /// Promise reject function (has internal [[Promise]] and [[AlreadyResolved]] slots)
+    PromiseReject {
+        promise: JsObjectRef,
+        /// Shared with the paired resolve function
+        already_resolved: Rc<Cell<bool>>,
+    },
     /// Promise.all fulfill handler (tracks shared state for aggregation)
     /// Contains (shared_state, index) - index is which promise slot to fill
     PromiseAllFulfill {
@@ -2956,6 +3893,12 @@ pub enum JsFunction {
     /// Proxy revoke function - revokes the associated proxy
     /// Contains the proxy object reference
     ProxyRevoke(JsObjectRef),
+    /// Reaction resuming an async generator whose body awaits a promise
+    AsyncGeneratorResume {
+        generator: JsObjectRef,
+        /// true = on_fulfilled handler, false = on_rejected handler
+        is_fulfill: bool,
+    },
 }
 
 /// Shared state for Promise.all tracking
@@ -3009,8 +3952,8 @@ impl JsFunction {
                 .map(|s| s.as_str()),
             JsFunction::Native(f) => Some(f.name.as_ref()),
             JsFunction::Bound(_) => Some("bound"),
-            JsFunction::PromiseResolve(_) => Some("resolve"),
-            JsFunction::PromiseReject(_) => Some("reject"),
+            JsFunction::PromiseResolve { .. } => Some("resolve"),
+            JsFunction::PromiseReject { .. } => Some("reject"),
             JsFunction::PromiseAllFulfill { .. } => Some("promiseAllFulfill"),
             JsFunction::PromiseAllReject(_) => Some("promiseAllReject"),
             JsFunction::PromiseRaceSettle { .. } => Some("promiseRaceSettle"),
@@ -3019,6 +3962,7 @@ impl JsFunction {
             JsFunction::ModuleExportGetter { .. } => Some("get"),
             JsFunction::ModuleReExportGetter { .. } => Some("get"),
             JsFunction::ProxyRevoke(_) => Some("revoke"),
+            JsFunction::AsyncGeneratorResume { .. } => Some("asyncGeneratorResume"),
         }
     }
 }
@@ -3263,6 +4207,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_js_string_concat_builds_rope() {
+        let piece = "x".repeat(300);
+        let mut s = JsString::from("");
+        for _ in 0..10_000 {
+            s = s + piece.as_str();
+        }
+        assert!(matches!(s.0, StrRepr::Rope(_)));
+        assert_eq!(s.len(), 3_000_000);
+        assert_eq!(s.char_count(), 3_000_000);
+        assert_eq!(s, JsString::from(piece.repeat(10_000)));
+        assert_eq!(s.as_str().len(), 3_000_000);
+    }
+
+    #[test]
+    fn test_js_string_short_concat_stays_flat() {
+        let s = JsString::from("foo") + "bar";
+        assert!(matches!(s.0, StrRepr::Flat(_)));
+        assert_eq!(s, "foobar");
+
+        // Short appends onto a rope merge into its right half
+        let long = JsString::from("a".repeat(ROPE_MIN_LEN));
+        let s = long + "b" + "c";
+        let StrRepr::Rope(node) = &s.0 else {
+            panic!("expected rope");
+        };
+        let parts = node.parts.borrow();
+        let (_, right) = parts.as_ref().unwrap();
+        assert_eq!(right.as_str(), "bc");
+    }
+
+    #[test]
+    fn test_js_string_deep_rope_drop() {
+        // Dropping a never-flattened rope must not recurse per node
+        let mut s = JsString::from("y".repeat(ROPE_MIN_LEN));
+        let chunk = JsString::from("z".repeat(ROPE_MIN_LEN));
+        for _ in 0..200_000 {
+            s = s + &chunk;
+        }
+        assert_eq!(s.len(), ROPE_MIN_LEN * 200_001);
+        drop(s);
+    }
+
     #[test]
     fn test_strict_equals() {
         assert!(JsValue::Undefined.strict_equals(&JsValue::Undefined));
@@ -0,0 +1,314 @@
+//! Bounded, cycle-safe formatting of values, shared by `console.log` and
+//! the `Display` of [`crate::RuntimeValue`].
+//!
+//! Formatting reads property maps directly and never calls into script:
+//! accessors show as `[Getter]`, `[Setter]` or `[Getter/Setter]`, and
+//! proxies as `Proxy {}`. It needs no interpreter, so a host can log any
+//! value it holds. Objects seen on the current path print as `[Circular]`,
+//! objects nested deeper than the depth limit as `{...}`, `[Array(n)]` or
+//! `[Function]`, and containers past the item limit end with a count of
+//! what was left out.
+
+use crate::prelude::*;
+
+use super::{ExoticObject, JsObject, JsValue, PropertyKey, number_to_string};
+use crate::gc::Gc;
+use core::fmt;
+
+/// Nesting levels `Display` of a `RuntimeValue` expands
+pub const DEFAULT_DISPLAY_DEPTH: usize = 4;
+
+/// Items, entries or properties `Display` of a `RuntimeValue` shows per object
+pub const DEFAULT_DISPLAY_ITEMS: usize = 100;
+
+/// Nested strings longer than this, in chars, are cut short
+const MAX_DISPLAY_STRING_LEN: usize = 1000;
+
+/// How far a value is formatted
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DisplayLimits {
+    /// Nesting levels expanded
+    pub depth: usize,
+    /// Items, entries or properties shown per object
+    pub max_items: usize,
+    /// Longest nested string shown in full, in chars
+    pub max_string_len: usize,
+    /// Quote nested strings; `console.log` leaves them bare
+    pub quote_strings: bool,
+}
+
+/// A value formatted with bounded depth and item counts, from
+/// [`JsValue::display_opts`]
+pub struct ValueDisplay<'a> {
+    value: &'a JsValue,
+    limits: DisplayLimits,
+}
+
+impl fmt::Display for ValueDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut budget = usize::MAX;
+        f.write_str(&format_value(self.value, &self.limits, &mut budget))
+    }
+}
+
+impl JsValue {
+    /// Format this value for logs, expanding objects and arrays `depth`
+    /// levels deep and showing at most `max_items` items of each.
+    ///
+    /// Top-level strings print bare, nested ones quoted. Accessors are not
+    /// called and cycles print as `[Circular]`, so any value can be shown.
+    ///
+    /// ```
+    /// use tsrun::{Interpreter, StepResult};
+    ///
+    /// let mut interp = Interpreter::new();
+    /// let source = "({ name: 'app', ports: [80, 443, 8080] })";
+    /// let StepResult::Complete(value) = interp.eval(source, None)? else {
+    ///     panic!("expected a value");
+    /// };
+    /// assert_eq!(
+    ///     value.display_opts(1, 2).to_string(),
+    ///     "{ name: 'app', ports: [Array(3)] }"
+    /// );
+    /// assert_eq!(
+    ///     value.display_opts(2, 2).to_string(),
+    ///     "{ name: 'app', ports: [80, 443, ... 1 more items] }"
+    /// );
+    /// # Ok::<(), tsrun::JsError>(())
+    /// ```
+    pub fn display_opts(&self, depth: usize, max_items: usize) -> ValueDisplay<'_> {
+        ValueDisplay {
+            value: self,
+            limits: DisplayLimits {
+                depth,
+                max_items,
+                max_string_len: MAX_DISPLAY_STRING_LEN,
+                quote_strings: true,
+            },
+        }
+    }
+}
+
+/// Format `value` within `limits`.
+///
+/// Output is charged against `budget`; containers stop adding items once it
+/// reaches zero.
+pub(crate) fn format_value(value: &JsValue, limits: &DisplayLimits, budget: &mut usize) -> String {
+    Formatter {
+        limits,
+        seen: Vec::new(),
+        budget,
+    }
+    .value(value, 0)
+}
+
+struct Formatter<'a> {
+    limits: &'a DisplayLimits,
+    /// Ids of the objects on the current path
+    seen: Vec<usize>,
+    budget: &'a mut usize,
+}
+
+impl Formatter<'_> {
+    fn value(&mut self, value: &JsValue, depth: usize) -> String {
+        let text = match value {
+            JsValue::Undefined => String::from("undefined"),
+            JsValue::Null => String::from("null"),
+            JsValue::Boolean(b) => b.to_string(),
+            JsValue::Number(n) => number_to_string(*n),
+            JsValue::String(s) if depth > 0 && self.limits.quote_strings => self.quoted(s.as_str()),
+            JsValue::String(s) => s.to_string(),
+            JsValue::Symbol(sym) => match &sym.description {
+                Some(desc) => format!("Symbol({})", desc),
+                None => String::from("Symbol()"),
+            },
+            // Its interpreter is gone, so there is nothing left to show
+            JsValue::Object(obj) if !obj.is_heap_alive() => String::from("[detached object]"),
+            JsValue::Object(obj) => {
+                let obj_id = obj.id();
+                if self.seen.contains(&obj_id) {
+                    return String::from("[Circular]");
+                }
+
+                if depth >= self.limits.depth {
+                    let obj_ref = obj.borrow();
+                    return match &obj_ref.exotic {
+                        ExoticObject::Array { elements } => format!("[Array({})]", elements.len()),
+                        ExoticObject::Function(_) => String::from("[Function]"),
+                        _ => String::from("{...}"),
+                    };
+                }
+
+                self.seen.push(obj_id);
+                let result = self.object(obj, depth);
+                self.seen.pop();
+                return result;
+            }
+        };
+        *self.budget = self.budget.saturating_sub(text.len());
+        text
+    }
+
+    /// `s` in single quotes, cut short past the string limit
+    fn quoted(&self, s: &str) -> String {
+        let mut out = String::from("'");
+        let mut chars = s.chars();
+        for c in chars.by_ref().take(self.limits.max_string_len) {
+            match c {
+                '\'' => out.push_str("\\'"),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                c => out.push(c),
+            }
+        }
+        out.push('\'');
+        let rest = chars.count();
+        if rest > 0 {
+            out.push_str(&format!("... {} more characters", rest));
+        }
+        out
+    }
+
+    /// The contents of an object or array
+    fn object(&mut self, obj: &Gc<JsObject>, depth: usize) -> String {
+        let obj_ref = obj.borrow();
+        let max_items = self.limits.max_items;
+
+        match &obj_ref.exotic {
+            ExoticObject::Array { elements } => {
+                let mut items = Vec::new();
+                for elem in elements.iter().take(max_items) {
+                    if *self.budget == 0 {
+                        break;
+                    }
+                    items.push(self.value(elem, depth + 1));
+                }
+
+                let shown = items.len();
+                if elements.len() > shown {
+                    items.push(format!("... {} more items", elements.len() - shown));
+                }
+
+                format!("[{}]", items.join(", "))
+            }
+            ExoticObject::Function(func_info) => {
+                let name = func_info.name().unwrap_or("anonymous");
+                format!("[Function: {}]", name)
+            }
+            ExoticObject::Date { timestamp } => format!("Date({})", timestamp),
+            ExoticObject::RegExp { pattern, flags, .. } => format!("/{}/{}", pattern, flags),
+            ExoticObject::Map { entries } => {
+                let mut items = Vec::new();
+                for (k, v) in entries.iter().take(max_items) {
+                    if *self.budget == 0 {
+                        break;
+                    }
+                    let key_str = self.value(&k.0, depth + 1);
+                    let val_str = self.value(v, depth + 1);
+                    items.push(format!("{} => {}", key_str, val_str));
+                }
+
+                let shown = items.len();
+                if entries.len() > shown {
+                    items.push(format!("... {} more entries", entries.len() - shown));
+                }
+
+                format!("Map({}){}", entries.len(), braced(&items))
+            }
+            ExoticObject::Set { entries } => {
+                let mut items = Vec::new();
+                for v in entries.iter().take(max_items) {
+                    if *self.budget == 0 {
+                        break;
+                    }
+                    items.push(self.value(&v.0, depth + 1));
+                }
+
+                let shown = items.len();
+                if entries.len() > shown {
+                    items.push(format!("... {} more items", entries.len() - shown));
+                }
+
+                format!("Set({}){}", entries.len(), braced(&items))
+            }
+            ExoticObject::Promise(_) => String::from("Promise { <pending> }"),
+            ExoticObject::Generator(_) | ExoticObject::BytecodeGenerator(_) => {
+                String::from("Generator { <suspended> }")
+            }
+            ExoticObject::Proxy(_) => String::from("Proxy {}"),
+            ExoticObject::Boolean(b) => format!("[Boolean: {}]", b),
+            ExoticObject::Number(n) => format!("[Number: {}]", number_to_string(*n)),
+            ExoticObject::StringObj(s) => format!("[String: \"{}\"]", s),
+            ExoticObject::Symbol(sym) => match &sym.description {
+                Some(desc) => format!("[Symbol: Symbol({})]", desc),
+                None => String::from("[Symbol: Symbol()]"),
+            },
+            ExoticObject::Environment(_) => String::from("[Environment]"),
+            ExoticObject::Enum(_) => String::from("[Enum]"),
+            ExoticObject::RawJSON(s) => s.to_string(),
+            ExoticObject::Url(data) => format!("URL {{ {} }}", data.record.borrow().href()),
+            ExoticObject::UrlSearchParams(data) => {
+                let items: Vec<String> = data
+                    .borrow()
+                    .list
+                    .iter()
+                    .map(|(name, value)| format!("{} => {}", name, value))
+                    .collect();
+                format!("URLSearchParams {}", braced(&items))
+            }
+            ExoticObject::WeakRef(_) => String::from("WeakRef {}"),
+            ExoticObject::FinalizationRegistry(_) => String::from("FinalizationRegistry {}"),
+            ExoticObject::DisposableStack(data) if data.borrow().is_async => {
+                String::from("AsyncDisposableStack {}")
+            }
+            ExoticObject::DisposableStack(_) => String::from("DisposableStack {}"),
+            ExoticObject::PendingOrder { id } => format!("[PendingOrder: {}]", id),
+            ExoticObject::Host(_) => String::from("[HostObject]"),
+            ExoticObject::Ordinary => {
+                let mut items = Vec::new();
+                let mut count = 0;
+
+                for (key, prop) in obj_ref.properties.iter() {
+                    if count >= max_items || *self.budget == 0 {
+                        break;
+                    }
+                    let key_str = match key {
+                        PropertyKey::String(s) => s.to_string(),
+                        // Symbol keys are internal as often as not
+                        PropertyKey::Symbol(_) => continue,
+                        PropertyKey::Index(i) => i.to_string(),
+                    };
+                    *self.budget = self.budget.saturating_sub(key_str.len());
+                    let val_str = match (prop.getter(), prop.setter()) {
+                        _ if !prop.is_accessor() => self.value(&prop.value, depth + 1),
+                        (Some(_), Some(_)) => String::from("[Getter/Setter]"),
+                        (Some(_), None) => String::from("[Getter]"),
+                        _ => String::from("[Setter]"),
+                    };
+                    items.push(format!("{}: {}", key_str, val_str));
+                    count += 1;
+                }
+
+                let total = obj_ref.properties.len();
+                if total > max_items || (*self.budget == 0 && total > count) {
+                    items.push(format!("... {} more properties", total - count));
+                }
+
+                if items.is_empty() {
+                    String::from("{}")
+                } else {
+                    format!("{{ {} }}", items.join(", "))
+                }
+            }
+        }
+    }
+}
+
+/// `{ a, b }`, or `{}` for no items
+fn braced(items: &[String]) -> String {
+    if items.is_empty() {
+        String::from("{}")
+    } else {
+        format!("{{ {} }}", items.join(", "))
+    }
+}
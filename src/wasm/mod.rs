@@ -46,6 +46,7 @@ use alloc::boxed::Box;
 use core::alloc::Layout;
 use core::ffi::c_void;
 
+use crate::CollectOutcome;
 use crate::platform::{ConsoleLevel, ConsoleProvider, RandomProvider, TimeProvider};
 
 use crate::ffi::{TsRunContext, console::FfiConsoleProvider};
@@ -243,3 +244,29 @@ pub extern "C" fn tsrun_wasm_new_with_callback_console() -> *mut TsRunContext {
 
     ctx_ptr
 }
+
+// ============================================================================
+// Idle-Time GC
+// ============================================================================
+
+/// Collect garbage if worthwhile and likely to fit in `budget_ms`.
+///
+/// Intended for idle gaps between user actions. The cycle is timed with the
+/// host timer imports. Returns the cycle's duration in milliseconds, `-1.0`
+/// if there was too little garbage to bother, `-2.0` if past cycles suggest
+/// it would overrun the budget, or `-3.0` if `ctx` is NULL.
+///
+/// # Safety
+/// `ctx` must be a context returned by `tsrun_wasm_new` (or NULL).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tsrun_maybe_collect(ctx: *mut TsRunContext, budget_ms: f64) -> f64 {
+    // SAFETY: the caller passes a live context or NULL
+    let Some(ctx) = (unsafe { ctx.as_ref() }) else {
+        return -3.0;
+    };
+    match ctx.interp.maybe_collect(budget_ms, &WasmRawTimeProvider) {
+        CollectOutcome::Collected { duration_ms, .. } => duration_ms,
+        CollectOutcome::NothingToDo => -1.0,
+        CollectOutcome::OverBudget { .. } => -2.0,
+    }
+}
@@ -0,0 +1,125 @@
+//! Tests for the AST visitor and the built-in static analyses
+
+use tsrun::ast::visit::{
+    ImportKind, Visitor, collect_call_targets, collect_imports, walk_function_declaration,
+};
+use tsrun::ast::{FunctionDeclaration, Identifier, Program};
+use tsrun::parser::Parser;
+use tsrun::string_dict::StringDict;
+
+const FIXTURE: &str = include_str!("fixtures/imports.ts");
+
+#[allow(clippy::unwrap_used)]
+fn parse(source: &str) -> Program {
+    let mut dict = StringDict::new();
+    Parser::new(source, &mut dict).parse_program().unwrap()
+}
+
+#[test]
+fn test_collect_imports_covers_every_form() {
+    let imports = collect_imports(&parse(FIXTURE));
+    let found: Vec<(&str, ImportKind, bool)> = imports
+        .iter()
+        .map(|info| (info.specifier.as_str(), info.kind, info.type_only))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("./side-effect", ImportKind::Static, false),
+            ("./default", ImportKind::Static, false),
+            ("./namespace", ImportKind::Static, false),
+            ("./named", ImportKind::Static, false),
+            ("./mixed", ImportKind::Static, false),
+            ("./types", ImportKind::Static, true),
+            ("./reexport-named", ImportKind::ReExport, false),
+            ("./reexport-all", ImportKind::ReExport, false),
+            ("./reexport-namespace", ImportKind::ReExport, false),
+            ("./reexport-types", ImportKind::ReExport, true),
+            ("./dynamic", ImportKind::Dynamic, false),
+            ("./dynamic-template", ImportKind::Dynamic, false),
+            ("./in-class-field", ImportKind::Dynamic, false),
+        ]
+    );
+}
+
+#[test]
+fn test_collect_imports_spans_point_at_source() {
+    let imports = collect_imports(&parse(FIXTURE));
+    for info in &imports {
+        let text = FIXTURE
+            .get(info.span.start..info.span.end)
+            .unwrap_or_default();
+        assert!(text.contains(&info.specifier), "{:?} -> {:?}", info, text);
+    }
+}
+
+#[test]
+fn test_collect_call_targets() {
+    let program = parse(FIXTURE);
+    let calls: Vec<&str> = collect_call_targets(&program, "console.log")
+        .iter()
+        .map(|span| FIXTURE.get(span.start..span.end).unwrap_or_default())
+        .collect();
+    assert_eq!(
+        calls,
+        [
+            "console.log(new Loader(), console.log.length)",
+            "console.log(\"nested\", [1, 2].map(n => console.log(n)))",
+            "console.log(n)",
+            "console?.log(\"optional\")",
+        ]
+    );
+    assert_eq!(collect_call_targets(&program, "import").len(), 4);
+    assert_eq!(collect_call_targets(&program, "Math.random").len(), 1);
+    assert_eq!(
+        collect_call_targets(&program, "logger.console.log").len(),
+        1
+    );
+    assert!(collect_call_targets(&program, "log").is_empty());
+}
+
+#[test]
+fn test_custom_visitor_distinguishes_bindings_from_references() {
+    #[derive(Default)]
+    struct Names {
+        bindings: Vec<String>,
+        references: Vec<String>,
+        functions: usize,
+    }
+
+    impl Visitor for Names {
+        fn visit_function_declaration(&mut self, function: &FunctionDeclaration) {
+            self.functions += 1;
+            walk_function_declaration(self, function);
+        }
+
+        fn visit_binding_identifier(&mut self, id: &Identifier) {
+            self.bindings.push(id.name.to_string());
+        }
+
+        fn visit_identifier(&mut self, id: &Identifier) {
+            self.references.push(id.name.to_string());
+        }
+    }
+
+    let program = parse(
+        r#"
+        function f({ a, b: [c = d] }: Opts, ...rest: number[]) {
+            const obj = { key: a, [c]: rest };
+            try { g(obj.key); } catch (err) { label: for (const x of e) break label; }
+            return class K extends Base { m() { return this.y ?? z; } };
+        }
+        "#,
+    );
+    let mut names = Names::default();
+    names.visit_program(&program);
+    assert_eq!(names.functions, 1);
+    assert_eq!(
+        names.bindings,
+        ["f", "a", "c", "rest", "obj", "err", "x", "K"]
+    );
+    assert_eq!(
+        names.references,
+        ["d", "a", "c", "rest", "g", "obj", "e", "Base", "z"]
+    );
+}
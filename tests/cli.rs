@@ -0,0 +1,125 @@
+//! Tests for the tsrun-cli binary.
+//!
+//! Each test runs the built binary inside a project under
+//! `tests/fixtures/cli`, checking its output and exit code.
+//!
+//! Run with `cargo test --features cli`.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Run tsrun-cli with `args` from the fixture project `project`
+#[allow(clippy::unwrap_used)]
+fn tsrun_cli(project: &str, args: &[&str]) -> Output {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/cli")
+        .join(project);
+    Command::new(env!("CARGO_BIN_EXE_tsrun-cli"))
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[allow(clippy::unwrap_used)]
+fn stdout_json(output: &Output) -> serde_json::Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn test_cli_prints_completion_as_json() {
+    let output = tsrun_cli("project", &["main.ts"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert_eq!(
+        stdout_json(&output),
+        serde_json::json!({ "region": "local", "items": [2, 4, 6], "label": "deploy:local" })
+    );
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn test_cli_context_and_yaml_output() {
+    let output = tsrun_cli(
+        "project",
+        &["--yaml", "--context", "context.json", "main.ts"],
+    );
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "region: eu-west\nitems:\n  - 2\n  - 4\n  - 6\nlabel: deploy:eu-west\n"
+    );
+}
+
+#[test]
+fn test_cli_order_handled_by_shell_script() {
+    let output = tsrun_cli(
+        "orders",
+        &["--order-handler", "price.get=exec:sh price.sh", "main.ts"],
+    );
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert_eq!(
+        stdout_json(&output),
+        serde_json::json!({
+            "total": 42,
+            "sku": "A-1",
+            "failure": "No handler for order type 'stock.get'"
+        })
+    );
+}
+
+#[test]
+fn test_cli_script_error_renders_snippet() {
+    let output = tsrun_cli("broken", &["main.ts"]);
+    assert_eq!(output.status.code(), Some(1));
+    let err = stderr(&output);
+    assert!(err.starts_with("Error: negative input: -1\n"), "{}", err);
+    assert!(err.contains("check.ts:3:"), "{}", err);
+    assert!(
+        err.contains("3 |         throw new Error(\"negative input: \" + n);"),
+        "{}",
+        err
+    );
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn test_cli_limits_stop_runaway_script() {
+    let output = tsrun_cli("broken", &["--fuel", "10000", "spin.ts"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        stderr(&output).contains("fuel exhausted"),
+        "{}",
+        stderr(&output)
+    );
+
+    let output = tsrun_cli("broken", &["--timeout", "100", "spin.ts"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output), "Execution timed out after 100ms\n");
+}
+
+#[test]
+fn test_cli_usage_errors_exit_2() {
+    for args in [
+        &["--bogus", "main.ts"][..],
+        &[][..],
+        &["missing.ts"][..],
+        &["--order-handler", "price.get", "main.ts"][..],
+    ] {
+        let output = tsrun_cli("project", args);
+        assert_eq!(
+            output.status.code(),
+            Some(2),
+            "{:?}: {}",
+            args,
+            stderr(&output)
+        );
+    }
+}
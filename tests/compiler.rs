@@ -3,9 +3,14 @@
 //! These tests verify that the compiler correctly generates bytecode
 //! from AST nodes.
 
-use tsrun::compiler::{BytecodeChunk, Compiler, Op};
+use tsrun::compiler::{BytecodeChunk, CompileLimits, Compiler, Constant, Op};
 use tsrun::parser::Parser;
+use tsrun::platform::{ConsoleLevel, ConsoleProvider};
 use tsrun::string_dict::StringDict;
+use tsrun::{Interpreter, InterpreterConfig, JsError, JsValue, ModulePath, StepResult};
+
+use std::cell::RefCell;
+use std::rc::Rc;
 
 /// Parse source and compile to bytecode
 #[allow(clippy::expect_used)]
@@ -13,7 +18,8 @@ fn compile(source: &str) -> BytecodeChunk {
     let mut dict = StringDict::new();
     let mut parser = Parser::new(source, &mut dict);
     let program = parser.parse_program().expect("parse failed");
-    let chunk = Compiler::compile_program(&program).expect("compile failed");
+    let chunk =
+        Compiler::compile_program(&program, CompileLimits::default()).expect("compile failed");
     (*chunk).clone()
 }
 
@@ -531,6 +537,64 @@ fn test_register_count() {
     );
 }
 
+#[test]
+fn test_call_argument_registers_are_reused() {
+    // Each statement's argument, array and template registers are freed
+    // once it is done, so a long run of them stays within the 255 limit
+    let statement = "f(1, 2, 3); [a, b, c]; `${a}${b}`; g`${a}`;\n";
+    let chunk = compile(&statement.repeat(1000));
+    assert!(
+        chunk.register_count < 16,
+        "Expected registers to be reused, got {}",
+        chunk.register_count
+    );
+}
+
+/// A chain of `terms` ternaries joined with `+`, as generated config logic
+/// tends to look
+fn ternary_chain(terms: usize) -> String {
+    (0..terms)
+        .map(|i| format!("(x > {i} ? {i} : -{i})"))
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+#[test]
+fn test_chained_expression_registers_are_reused() {
+    // The running result of a left-associative chain stays in one register,
+    // where it used to take a register per term and overflow past 255
+    let chunk = compile(&format!("const x = 7; const r = {};", ternary_chain(2000)));
+    assert!(
+        chunk.register_count <= 4,
+        "Expected a chained expression to reuse registers, got {}",
+        chunk.register_count
+    );
+}
+
+#[test]
+fn test_generator_with_many_temporaries_has_small_register_file() {
+    // A suspended generator keeps its whole register file, so a long
+    // expression must not inflate it
+    let source = format!(
+        "function* g(x) {{ const a = {}; yield a; yield a + {}; }}",
+        ternary_chain(300),
+        ternary_chain(300)
+    );
+    let chunk = compile(&source);
+    let generator = chunk.constants.iter().find_map(|constant| match constant {
+        Constant::Chunk(chunk) => Some(chunk),
+        _ => None,
+    });
+    let Some(generator) = generator else {
+        panic!("Expected the generator's chunk among the constants");
+    };
+    assert!(
+        generator.register_count <= 6,
+        "Expected a small generator register file, got {}",
+        generator.register_count
+    );
+}
+
 #[test]
 fn test_compile_nullish_coalescing() {
     let chunk = compile("null ?? 'default'");
@@ -560,3 +624,145 @@ fn test_compile_nullish_coalescing() {
     });
     assert!(has_valid_jump, "JumpIfNotNullish has invalid target");
 }
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Disassembler
+// ═══════════════════════════════════════════════════════════════════════════
+
+const DISASSEMBLY_FIXTURE: &str = r#"
+function pick(flag) {
+    if (flag) {
+        return "yes";
+    }
+    return "no";
+}
+const counter = () => {
+    let n = 0;
+    return () => ++n;
+};
+try {
+    pick(true);
+} catch (e) {
+    counter();
+}
+"#;
+
+#[test]
+fn test_disassemble_snapshot() {
+    let listing = compile(DISASSEMBLY_FIXTURE).disassemble();
+    assert_eq!(
+        listing,
+        r#"== <main> (registers: 4) ==
+   0  LoadUndefined           dst=r0
+   1  CreateClosure           dst=r0, chunk_idx=<function pick>
+   2  DeclareVarHoisted       name="pick", init=r0
+   3  CreateArrow             dst=r0, chunk_idx=<function counter>
+   4  DeclareVar              name="counter", init=r0, mutable=false
+   5  PushTry                 catch_target=L14, finally_target=none
+   6  PushScope
+   7  GetVar                  dst=r1, name="pick"
+   8  LoadUndefined           dst=r2
+   9  LoadBool                dst=r3, value=true
+  10  Call                    dst=r0, callee=r1, this=r2, args_start=r3, argc=1
+  11  PopScope
+  12  PopTry
+  13  Jump                    target=L22
+L14:
+  14  PushScope
+  15  GetException            dst=r0
+  16  DeclareVar              name="e", init=r0, mutable=true
+  17  GetVar                  dst=r1, name="counter"
+  18  LoadUndefined           dst=r2
+  19  Call                    dst=r0, callee=r1, this=r2, args_start=r0, argc=0
+  20  PopScope
+  21  Jump                    target=L22
+L22:
+  22  Halt
+
+  == function pick(flag) (registers: 2) ==
+     0  DeclareVar              name="flag", init=r0, mutable=true
+     1  LoadUndefined           dst=r1
+     2  GetVar                  dst=r1, name="flag"
+     3  JumpIfFalse             cond=r1, target=L8
+     4  PushScope
+     5  LoadConst               dst=r1, idx="yes"
+     6  Return                  value=r1
+     7  PopScope
+  L8:
+     8  LoadConst               dst=r1, idx="no"
+     9  Return                  value=r1
+    10  LoadUndefined           dst=r1
+    11  Return                  value=r1
+
+  == arrow counter() (registers: 1) ==
+     0  LoadUndefined           dst=r0
+     1  LoadInt                 dst=r0, value=0
+     2  DeclareVar              name="n", init=r0, mutable=true
+     3  CreateArrow             dst=r0, chunk_idx=<function>
+     4  Return                  value=r0
+     5  LoadUndefined           dst=r0
+     6  Return                  value=r0
+
+    == arrow() (registers: 2) ==
+       0  GetVar                  dst=r0, name="n"
+       1  LoadInt                 dst=r1, value=1
+       2  Add                     dst=r0, left=r0, right=r1
+       3  SetVar                  name="n", src=r0
+       4  Return                  value=r0
+"#
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_interpreter_disassemble_compiles_without_running() {
+    let mut interp = Interpreter::new();
+    let listing = interp
+        .disassemble(
+            r#"import { missing } from "./missing";
+            throw new Error(missing);"#,
+            Some(ModulePath::new("/app/main.ts")),
+        )
+        .unwrap();
+    assert!(listing.contains("Throw"), "{}", listing);
+    assert!(listing.contains(r#"name="missing""#), "{}", listing);
+
+    let err = interp.disassemble("let = ;", None).unwrap_err();
+    assert!(matches!(err, JsError::SyntaxError { .. }), "{:?}", err);
+}
+
+/// Console provider that records everything written to it
+struct RecordingConsole(Rc<RefCell<Vec<(ConsoleLevel, String)>>>);
+
+impl ConsoleProvider for RecordingConsole {
+    fn write(&self, level: ConsoleLevel, message: &str) {
+        self.0.borrow_mut().push((level, message.to_string()));
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::indexing_slicing)]
+fn test_dump_bytecode_writes_each_chunk_to_console() {
+    let lines = Rc::new(RefCell::new(Vec::new()));
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        dump_bytecode: true,
+        allow_dynamic_code: true,
+        ..Default::default()
+    });
+    interp.set_console(Box::new(RecordingConsole(lines.clone())));
+
+    let result = interp
+        .eval("const f = new Function('a', 'return a * 2'); f(21)", None)
+        .unwrap();
+    assert!(matches!(result, StepResult::Complete(ref rv) if *rv == JsValue::Number(42.0)));
+
+    let lines = lines.borrow();
+    assert_eq!(lines.len(), 2, "{:?}", lines);
+    assert!(lines.iter().all(|(level, _)| *level == ConsoleLevel::Debug));
+    assert!(lines[0].1.starts_with("== <main>"), "{}", lines[0].1);
+    assert!(
+        lines[1].1.starts_with("== function anonymous(a)"),
+        "{}",
+        lines[1].1
+    );
+}
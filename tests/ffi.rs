@@ -0,0 +1,618 @@
+//! Tests for the C API, exercised through the exported `extern "C"` functions.
+//!
+//! Run with `cargo test --features c-api`.
+
+#![cfg(feature = "c-api")]
+#![allow(clippy::unwrap_used, clippy::panic)]
+
+use std::ffi::{CStr, CString, c_char, c_void};
+use std::ptr;
+
+use tsrun::ffi::{
+    TSRUN_RETAINER_LABEL_LEN, TsRunBytes, TsRunConsoleFn, TsRunConsoleLevel, TsRunContext,
+    TsRunFunctionSource, TsRunGcCensus, TsRunGcRetainer, TsRunOrderHandlerFn, TsRunPrepareProgress,
+    TsRunPrepareStatus, TsRunResult, TsRunStepResult, TsRunStepStatus, TsRunValue,
+    TsRunValueResult, TsRunWellKnownSymbol, tsrun_alloc_string, tsrun_free_bytes,
+    tsrun_free_string, tsrun_free_values,
+};
+
+// Context functions live in a private module; bind them by symbol like a C host would.
+// TsRunContext is only ever handled through a pointer, as in the C header.
+#[allow(improper_ctypes)]
+unsafe extern "C" {
+    fn tsrun_new() -> *mut TsRunContext;
+    fn tsrun_free(ctx: *mut TsRunContext);
+    fn tsrun_eval_json(
+        ctx: *mut TsRunContext,
+        source: *const c_char,
+        path: *const c_char,
+        order_handler: Option<TsRunOrderHandlerFn>,
+        userdata: *mut c_void,
+        out_json: *mut *mut c_char,
+        out_err: *mut *mut c_char,
+    ) -> bool;
+    fn tsrun_object_new(ctx: *mut TsRunContext) -> TsRunValueResult;
+    fn tsrun_number(ctx: *mut TsRunContext, n: f64) -> *mut TsRunValue;
+    fn tsrun_get_number(val: *const TsRunValue) -> f64;
+    fn tsrun_is_undefined(val: *const TsRunValue) -> bool;
+    fn tsrun_is_array(val: *const TsRunValue) -> bool;
+    fn tsrun_value_object_id(val: *const TsRunValue) -> u64;
+    fn tsrun_get(
+        ctx: *mut TsRunContext,
+        obj: *mut TsRunValue,
+        key: *const c_char,
+    ) -> TsRunValueResult;
+    fn tsrun_set(
+        ctx: *mut TsRunContext,
+        obj: *mut TsRunValue,
+        key: *const c_char,
+        val: *mut TsRunValue,
+    ) -> TsRunResult;
+    fn tsrun_value_free(val: *mut TsRunValue);
+    fn tsrun_well_known_symbol(
+        ctx: *mut TsRunContext,
+        symbol: TsRunWellKnownSymbol,
+    ) -> *mut TsRunValue;
+    fn tsrun_symbol_new(ctx: *mut TsRunContext, description: *const c_char) -> *mut TsRunValue;
+    fn tsrun_symbol_description(val: *const TsRunValue) -> *mut c_char;
+    fn tsrun_get_symbol(
+        ctx: *mut TsRunContext,
+        obj: *mut TsRunValue,
+        symbol: *mut TsRunValue,
+    ) -> TsRunValueResult;
+    fn tsrun_set_symbol(
+        ctx: *mut TsRunContext,
+        obj: *mut TsRunValue,
+        symbol: *mut TsRunValue,
+        val: *mut TsRunValue,
+    ) -> TsRunResult;
+    fn tsrun_symbol_keys(
+        ctx: *mut TsRunContext,
+        obj: *mut TsRunValue,
+        count_out: *mut usize,
+    ) -> *mut *mut TsRunValue;
+    fn tsrun_set_console(
+        ctx: *mut TsRunContext,
+        func: Option<TsRunConsoleFn>,
+        userdata: *mut c_void,
+    ) -> TsRunResult;
+    fn tsrun_set_console_limits(
+        ctx: *mut TsRunContext,
+        max_message_len: usize,
+        max_output: usize,
+    ) -> TsRunResult;
+    fn tsrun_console_dropped_count(ctx: *const TsRunContext) -> usize;
+    fn tsrun_get_global(ctx: *mut TsRunContext, name: *const c_char) -> TsRunValueResult;
+    fn tsrun_value_function_source(val: *const TsRunValue, out: *mut TsRunFunctionSource) -> bool;
+    fn tsrun_function_source_free(source: *mut TsRunFunctionSource);
+    fn tsrun_prepare(
+        ctx: *mut TsRunContext,
+        code: *const c_char,
+        path: *const c_char,
+    ) -> TsRunResult;
+    fn tsrun_prepare_begin(ctx: *mut TsRunContext, path: *const c_char) -> TsRunResult;
+    fn tsrun_prepare_chunk(ctx: *mut TsRunContext, data: *const u8, len: usize) -> TsRunResult;
+    fn tsrun_prepare_finish(ctx: *mut TsRunContext, budget: usize) -> TsRunPrepareProgress;
+    fn tsrun_prepare_continue(ctx: *mut TsRunContext, budget: usize) -> TsRunPrepareProgress;
+    fn tsrun_export_prepared(ctx: *mut TsRunContext) -> TsRunBytes;
+    fn tsrun_import_prepared(ctx: *mut TsRunContext, data: *const u8, len: usize) -> TsRunResult;
+    fn tsrun_run(out: *mut TsRunStepResult, ctx: *mut TsRunContext);
+    fn tsrun_step_result_free(result: *mut TsRunStepResult);
+    fn tsrun_get_string(val: *const TsRunValue) -> *const c_char;
+    fn tsrun_gc_census(ctx: *mut TsRunContext) -> TsRunGcCensus;
+    fn tsrun_gc_retainers(
+        ctx: *mut TsRunContext,
+        out: *mut TsRunGcRetainer,
+        capacity: usize,
+    ) -> usize;
+}
+
+/// Order handler that answers every order with its own payload and counts calls.
+extern "C" fn echo_handler(
+    _ctx: *mut TsRunContext,
+    order_payload_json: *const c_char,
+    userdata: *mut c_void,
+    out_response_json: *mut *mut c_char,
+    out_is_error: *mut bool,
+) {
+    unsafe {
+        *(userdata as *mut usize) += 1;
+        let payload = CStr::from_ptr(order_payload_json);
+        if payload.to_bytes().starts_with(b"{\"fail\"") {
+            *out_response_json = tsrun_alloc_string(c"rejected by host".as_ptr());
+            *out_is_error = true;
+        } else {
+            *out_response_json = tsrun_alloc_string(order_payload_json);
+        }
+    }
+}
+
+/// Evaluate `source` as a module with `handler`, returning the JSON or error string.
+fn eval_json(
+    source: &str,
+    handler: Option<TsRunOrderHandlerFn>,
+    calls: &mut usize,
+) -> Result<String, String> {
+    let ctx = unsafe { tsrun_new() };
+    let source = CString::new(source).unwrap();
+    let mut out_json: *mut c_char = ptr::null_mut();
+    let mut out_err: *mut c_char = ptr::null_mut();
+
+    let ok = unsafe {
+        tsrun_eval_json(
+            ctx,
+            source.as_ptr(),
+            c"/main.ts".as_ptr(),
+            handler,
+            calls as *mut usize as *mut c_void,
+            &mut out_json,
+            &mut out_err,
+        )
+    };
+
+    let take = |s: *mut c_char| {
+        let owned = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string();
+        unsafe { tsrun_free_string(s) };
+        owned
+    };
+    let result = if ok {
+        assert!(out_err.is_null());
+        Ok(take(out_json))
+    } else {
+        assert!(out_json.is_null());
+        Err(take(out_err))
+    };
+    unsafe { tsrun_free(ctx) };
+    result
+}
+
+#[test]
+fn test_eval_json_echo_order_handler() {
+    let mut calls = 0;
+    let json = eval_json(
+        r#"
+        import { order } from "tsrun:host";
+        const a: any = order({ kind: "echo", n: 20 });
+        const b: any = order([1, 2, "three"]);
+        let message = "";
+        try { order({ fail: true }); } catch (e) { message = String(e); }
+        ({ n: a.n + 1, kind: a.kind, items: b, message })
+        "#,
+        Some(echo_handler),
+        &mut calls,
+    );
+
+    assert_eq!(
+        json.as_deref(),
+        Ok(
+            r#"{"n":21,"kind":"echo","items":[1,2,"three"],"message":"TypeError: rejected by host"}"#
+        )
+    );
+    assert_eq!(calls, 3);
+}
+
+#[test]
+fn test_eval_json_reports_errors() {
+    let mut calls = 0;
+    let missing_handler = eval_json(
+        r#"import { order } from "tsrun:host"; order({ kind: "echo" });"#,
+        None,
+        &mut calls,
+    );
+    assert!(missing_handler.unwrap_err().contains("no order handler"));
+
+    let missing_module = eval_json(
+        r#"import { x } from "./dep"; x"#,
+        Some(echo_handler),
+        &mut calls,
+    );
+    assert!(
+        missing_module
+            .unwrap_err()
+            .contains("Missing modules: /dep")
+    );
+
+    let thrown = eval_json(r#"throw new Error("boom")"#, None, &mut calls);
+    assert!(thrown.unwrap_err().contains("boom"));
+    assert_eq!(calls, 0);
+}
+
+#[test]
+fn test_symbol_keyed_properties() {
+    unsafe {
+        let ctx = tsrun_new();
+        let obj = tsrun_object_new(ctx).value;
+        let custom = tsrun_symbol_new(ctx, c"meta".as_ptr());
+        let iterator = tsrun_well_known_symbol(ctx, TsRunWellKnownSymbol::Iterator);
+
+        let one = tsrun_number(ctx, 1.0);
+        assert!(tsrun_set_symbol(ctx, obj, custom, one).ok);
+        let read = tsrun_get_symbol(ctx, obj, custom).value;
+        assert_eq!(tsrun_get_number(read), 1.0);
+        let missing = tsrun_get_symbol(ctx, obj, iterator).value;
+        assert!(tsrun_is_undefined(missing));
+        let not_symbol = tsrun_get_symbol(ctx, obj, one);
+        assert!(not_symbol.value.is_null());
+        assert!(!not_symbol.error.is_null());
+
+        let mut count = 0;
+        let keys = tsrun_symbol_keys(ctx, obj, &mut count);
+        assert_eq!(count, 1);
+        let description = tsrun_symbol_description(*keys);
+        assert_eq!(CStr::from_ptr(description).to_str().unwrap(), "meta");
+        tsrun_free_string(description);
+        tsrun_free_values(keys, count);
+
+        let description = tsrun_symbol_description(iterator);
+        assert_eq!(
+            CStr::from_ptr(description).to_str().unwrap(),
+            "Symbol.iterator"
+        );
+        tsrun_free_string(description);
+        assert!(tsrun_symbol_description(one).is_null());
+
+        for val in [obj, custom, iterator, one, read, missing] {
+            tsrun_value_free(val);
+        }
+        tsrun_free(ctx);
+    }
+}
+
+#[test]
+fn test_value_object_id() {
+    unsafe {
+        let ctx = tsrun_new();
+        let obj = tsrun_object_new(ctx).value;
+        let other = tsrun_object_new(ctx).value;
+        assert!(tsrun_set(ctx, obj, c"self".as_ptr(), obj).ok);
+        let read = tsrun_get(ctx, obj, c"self".as_ptr()).value;
+
+        let id = tsrun_value_object_id(obj);
+        assert_ne!(id, 0);
+        assert_eq!(tsrun_value_object_id(read), id);
+        assert_ne!(tsrun_value_object_id(other), id);
+        let one = tsrun_number(ctx, 1.0);
+        assert_eq!(tsrun_value_object_id(one), 0);
+        assert_eq!(tsrun_value_object_id(ptr::null()), 0);
+
+        for val in [obj, other, read, one] {
+            tsrun_value_free(val);
+        }
+        tsrun_free(ctx);
+    }
+}
+
+/// Console callback that records each message it receives.
+extern "C" fn record_console(
+    level: TsRunConsoleLevel,
+    message: *const c_char,
+    message_len: usize,
+    userdata: *mut c_void,
+) {
+    unsafe {
+        let bytes = std::slice::from_raw_parts(message as *const u8, message_len);
+        let messages = &mut *(userdata as *mut Vec<(TsRunConsoleLevel, String)>);
+        messages.push((level, String::from_utf8_lossy(bytes).into_owned()));
+    }
+}
+
+#[test]
+fn test_console_limits_truncate_and_count_dropped_messages() {
+    let mut messages: Vec<(TsRunConsoleLevel, String)> = Vec::new();
+    unsafe {
+        let ctx = tsrun_new();
+        let userdata = &mut messages as *mut Vec<(TsRunConsoleLevel, String)> as *mut c_void;
+        assert!(tsrun_set_console(ctx, Some(record_console), userdata).ok);
+        assert!(tsrun_set_console_limits(ctx, 32, 100).ok);
+
+        let source = CString::new(
+            "console.log('x'.repeat(1000)); for (let i = 0; i < 10; i++) console.log('line ' + i);",
+        )
+        .unwrap();
+        let mut out_err: *mut c_char = ptr::null_mut();
+        let ok = tsrun_eval_json(
+            ctx,
+            source.as_ptr(),
+            c"/main.ts".as_ptr(),
+            None,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            &mut out_err,
+        );
+        assert!(ok);
+
+        // 32 kept bytes plus the note, then seven "line N" messages fit in 100 bytes
+        let first = &messages.first().unwrap().1;
+        assert_eq!(first, &format!("{}… (968 more characters)", "x".repeat(32)));
+        let (level, notice) = messages.last().unwrap();
+        assert_eq!(*level, TsRunConsoleLevel::Warn);
+        assert_eq!(notice, "console output limit reached");
+        assert_eq!(messages.len(), 1 + 7 + 1);
+        assert_eq!(tsrun_console_dropped_count(ctx), 3);
+        tsrun_free(ctx);
+    }
+}
+
+#[test]
+fn test_gc_census_and_retainers() {
+    unsafe {
+        let ctx = tsrun_new();
+        let baseline = tsrun_gc_census(ctx);
+
+        let source = CString::new(
+            "const kept: number[][] = []; for (let i = 0; i < 10; i++) kept.push([i]); \
+             (globalThis as any).kept = kept;",
+        )
+        .unwrap();
+        let mut out_err: *mut c_char = ptr::null_mut();
+        let ok = tsrun_eval_json(
+            ctx,
+            source.as_ptr(),
+            c"/main.ts".as_ptr(),
+            None,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            &mut out_err,
+        );
+        assert!(ok);
+
+        let census = tsrun_gc_census(ctx);
+        assert_eq!(census.arrays - baseline.arrays, 11);
+
+        let mut retainers: Vec<TsRunGcRetainer> = (0..4)
+            .map(|_| TsRunGcRetainer {
+                label: [0; TSRUN_RETAINER_LABEL_LEN],
+                roots: 0,
+                retained: 0,
+            })
+            .collect();
+        let written = tsrun_gc_retainers(ctx, retainers.as_mut_ptr(), retainers.len());
+        assert!(written >= 1 && written <= retainers.len());
+        let top = retainers.first().unwrap();
+        assert_eq!(CStr::from_ptr(top.label.as_ptr()).to_str().unwrap(), "root");
+        // The global object holds the kept arrays
+        assert!(top.retained >= 11);
+        assert!(top.roots > 0);
+
+        tsrun_free(ctx);
+    }
+}
+
+#[test]
+fn test_value_function_source() {
+    unsafe {
+        let ctx = tsrun_new();
+        let source = CString::new(
+            "const makeStep = () => (n: number) => n + 1;\n\
+             (globalThis as any).step = makeStep();\n\
+             (globalThis as any).native = Math.max;",
+        )
+        .unwrap();
+        let mut out_err: *mut c_char = ptr::null_mut();
+        let ok = tsrun_eval_json(
+            ctx,
+            source.as_ptr(),
+            c"/jobs/steps.ts".as_ptr(),
+            None,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            &mut out_err,
+        );
+        assert!(ok);
+
+        let step = tsrun_get_global(ctx, c"step".as_ptr());
+        assert!(step.error.is_null());
+        let mut info = TsRunFunctionSource {
+            text: ptr::null_mut(),
+            path: ptr::null_mut(),
+            start_line: 0,
+            start_column: 0,
+            end_line: 0,
+            end_column: 0,
+        };
+        assert!(tsrun_value_function_source(step.value, &mut info));
+        assert_eq!(
+            CStr::from_ptr(info.text).to_str().unwrap(),
+            "(n: number) => n + 1"
+        );
+        assert_eq!(
+            CStr::from_ptr(info.path).to_str().unwrap(),
+            "/jobs/steps.ts"
+        );
+        assert_eq!(
+            (
+                info.start_line,
+                info.start_column,
+                info.end_line,
+                info.end_column
+            ),
+            (1, 24, 1, 44)
+        );
+        tsrun_function_source_free(&mut info);
+        assert!(info.text.is_null() && info.path.is_null());
+        tsrun_value_free(step.value);
+
+        let native = tsrun_get_global(ctx, c"native".as_ptr());
+        assert!(!tsrun_value_function_source(native.value, &mut info));
+        tsrun_value_free(native.value);
+
+        tsrun_free(ctx);
+    }
+}
+
+#[test]
+fn test_incremental_prepare() {
+    unsafe {
+        let ctx = tsrun_new();
+        let mut source = String::from("const parts: string[] = [];\n");
+        for i in 0..200 {
+            source.push_str(&format!("parts[{i}] = \"ü{i}\";\n"));
+        }
+        source.push_str("parts.join(\"\")\n");
+
+        assert!(tsrun_prepare_begin(ctx, c"/bundle.ts".as_ptr()).ok);
+        // Odd-sized chunks split the two-byte characters
+        for chunk in source.as_bytes().chunks(7) {
+            assert!(tsrun_prepare_chunk(ctx, chunk.as_ptr(), chunk.len()).ok);
+        }
+
+        let mut progress = tsrun_prepare_finish(ctx, 256);
+        let mut pending = 0;
+        while progress.status == TsRunPrepareStatus::Pending {
+            assert_eq!(progress.total_bytes, source.len());
+            pending += 1;
+            progress = tsrun_prepare_continue(ctx, 256);
+        }
+        assert_eq!(progress.status, TsRunPrepareStatus::Ready);
+        assert!(progress.error.is_null());
+        assert!(pending > 4);
+
+        let mut result: TsRunStepResult = std::mem::zeroed();
+        tsrun_run(&mut result, ctx);
+        assert_eq!(result.status, TsRunStepStatus::Complete);
+        let expected: String = (0..200).map(|i| format!("ü{i}")).collect();
+        assert_eq!(
+            CStr::from_ptr(tsrun_get_string(result.value))
+                .to_str()
+                .unwrap(),
+            expected
+        );
+        tsrun_value_free(result.value);
+        tsrun_step_result_free(&mut result);
+
+        // Without a pending prepare, continuing is an error
+        let progress = tsrun_prepare_continue(ctx, 256);
+        assert_eq!(progress.status, TsRunPrepareStatus::Error);
+        assert!(!progress.error.is_null());
+
+        tsrun_free(ctx);
+    }
+}
+
+#[test]
+fn test_pending_order_origin() {
+    unsafe {
+        let ctx = tsrun_new();
+        let source = c"import { order } from \"tsrun:host\";
+function fetchUser() {
+    return order({ kind: \"user\" });
+}
+fetchUser();
+";
+        assert!(tsrun_prepare(ctx, source.as_ptr(), c"/app/main.ts".as_ptr()).ok);
+
+        let mut result: TsRunStepResult = std::mem::zeroed();
+        tsrun_run(&mut result, ctx);
+        assert_eq!(result.status, TsRunStepStatus::Suspended);
+        assert_eq!(result.pending_count, 1);
+        let order = &*result.pending_orders;
+        assert_eq!(
+            CStr::from_ptr(order.origin_module).to_str().unwrap(),
+            "/app/main.ts"
+        );
+        assert_eq!(
+            CStr::from_ptr(order.origin_function).to_str().unwrap(),
+            "fetchUser"
+        );
+        assert_eq!((order.origin_line, order.origin_column), (3, 12));
+        tsrun_value_free(order.payload);
+        tsrun_step_result_free(&mut result);
+
+        tsrun_free(ctx);
+    }
+}
+
+#[test]
+fn test_export_and_import_prepared() {
+    unsafe {
+        let source = c"const parts = ['a', 'b', 'c'];
+parts.map((p) => p.toUpperCase()).join('-');
+";
+        let ctx = tsrun_new();
+        assert!(tsrun_prepare(ctx, source.as_ptr(), c"/app/main.ts".as_ptr()).ok);
+        let exported = tsrun_export_prepared(ctx);
+        assert!(exported.error.is_null());
+        assert!(!exported.data.is_null());
+        tsrun_free(ctx);
+
+        let ctx = tsrun_new();
+        assert!(tsrun_import_prepared(ctx, exported.data, exported.len).ok);
+        tsrun_free_bytes(exported.data, exported.len);
+
+        let mut result: TsRunStepResult = std::mem::zeroed();
+        tsrun_run(&mut result, ctx);
+        assert_eq!(result.status, TsRunStepStatus::Complete);
+        assert_eq!(
+            CStr::from_ptr(tsrun_get_string(result.value))
+                .to_str()
+                .unwrap(),
+            "A-B-C"
+        );
+        tsrun_value_free(result.value);
+        tsrun_step_result_free(&mut result);
+
+        // The run has finished, so there is nothing left to export
+        let exported = tsrun_export_prepared(ctx);
+        assert!(exported.data.is_null());
+        assert!(!exported.error.is_null());
+
+        let garbage = [0u8; 4];
+        assert!(!tsrun_import_prepared(ctx, garbage.as_ptr(), garbage.len()).ok);
+
+        tsrun_free(ctx);
+    }
+}
+
+#[test]
+fn test_values_freed_after_context() {
+    unsafe {
+        let ctx = tsrun_new();
+        let source = CString::new(
+            "(globalThis as any).list = [1, 2]; (globalThis as any).greeting = \"hi\";",
+        )
+        .unwrap();
+        let mut out_err: *mut c_char = ptr::null_mut();
+        assert!(tsrun_eval_json(
+            ctx,
+            source.as_ptr(),
+            ptr::null(),
+            None,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            &mut out_err,
+        ));
+        let list = tsrun_get_global(ctx, c"list".as_ptr()).value;
+        let greeting = tsrun_get_global(ctx, c"greeting".as_ptr()).value;
+        assert!(tsrun_is_array(list));
+
+        tsrun_free(ctx);
+
+        // The array's memory is gone, so it reads as undefined; strings survive
+        assert!(tsrun_is_undefined(list));
+        assert!(!tsrun_is_array(list));
+        let mut source_out = TsRunFunctionSource {
+            text: ptr::null_mut(),
+            path: ptr::null_mut(),
+            start_line: 0,
+            start_column: 0,
+            end_line: 0,
+            end_column: 0,
+        };
+        assert!(!tsrun_value_function_source(list, &mut source_out));
+        let text = tsrun_get_string(greeting) as *mut c_char;
+        assert_eq!(CStr::from_ptr(text).to_str().unwrap(), "hi");
+        tsrun_free_string(text);
+
+        // Used with another context, the stale object is refused
+        let other = tsrun_new();
+        let symbol = tsrun_symbol_new(other, c"tag".as_ptr());
+        let result = tsrun_get_symbol(other, list, symbol);
+        assert!(result.value.is_null());
+        assert!(!result.error.is_null());
+
+        tsrun_value_free(list);
+        tsrun_value_free(greeting);
+        tsrun_value_free(symbol);
+        tsrun_free(other);
+    }
+}
@@ -0,0 +1,5 @@
+export function check(n: number): void {
+    if (n < 0) {
+        throw new Error("negative input: " + n);
+    }
+}
@@ -0,0 +1,10 @@
+import { request } from "tsrun:host";
+
+const quote = await request("price.get", { sku: "A-1" });
+let failure = "";
+try {
+    await request("stock.get", { sku: "A-1" });
+} catch (e) {
+    failure = e.message;
+}
+({ total: quote.price * 2, sku: quote.request.sku, failure })
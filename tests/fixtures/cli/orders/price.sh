@@ -0,0 +1,4 @@
+#!/bin/sh
+# Answers price.get orders: echoes the request next to a fixed price
+payload=$(cat)
+printf '{"price": 21, "request": %s}\n' "$payload"
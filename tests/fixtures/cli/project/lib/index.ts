@@ -0,0 +1,5 @@
+import { prefix } from "./prefix";
+
+export function describe(name: string): string {
+    return prefix + name;
+}
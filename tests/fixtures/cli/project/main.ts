@@ -0,0 +1,6 @@
+import { context } from "tsrun:env";
+import { describe } from "./lib";
+import { double } from "./util";
+
+const region: string = context.region ?? "local";
+({ region, items: [1, 2, 3].map(double), label: describe(region) })
@@ -0,0 +1,39 @@
+// Every import form recognised by collect_imports
+import "./side-effect";
+import def from "./default";
+import * as ns from "./namespace";
+import { a, b as bee } from "./named";
+import def2, { c } from "./mixed";
+import type { Shape } from "./types";
+
+export { x } from "./reexport-named";
+export * from "./reexport-all";
+export * as utils from "./reexport-namespace";
+export type { Config } from "./reexport-types";
+
+// Local exports are not imports
+const local = 1;
+export { local };
+
+export async function load(name: string) {
+    const eager = await import("./dynamic");
+    const tpl = await import(`./dynamic-template`);
+    // Computed specifiers can't be resolved statically
+    const computed = await import("./dynamic-" + name);
+    return [eager, tpl, computed, def, ns, a, bee, def2, c];
+}
+
+class Loader {
+    lazy = () => import("./in-class-field");
+}
+
+console.log(new Loader(), console.log.length);
+(function () {
+    if (Math.random() > 0.5) {
+        console.log("nested", [1, 2].map(n => console.log(n)));
+    }
+})();
+console?.log("optional");
+console["log"]("computed");
+const logger = { console };
+logger.console.log("deeper path");
@@ -0,0 +1,96 @@
+//! Tests for `InterpreterConfig::heap_budget`.
+//!
+//! These use only what builds without the standard library, so they also run
+//! with `cargo test --no-default-features --test heap_budget`.
+
+use tsrun::{HeapBudget, Interpreter, InterpreterConfig, JsError, StepResult, TerminationReason};
+
+/// Budget used by the tests: a few times what a fresh interpreter takes
+const LIMIT: usize = 1024 * 1024;
+
+/// Interpreter limited to [`LIMIT`] bytes, with the budget to watch it
+fn budgeted_interp() -> (Interpreter, HeapBudget) {
+    let budget = HeapBudget::new(LIMIT);
+    let config = InterpreterConfig {
+        heap_budget: Some(budget.clone()),
+        ..Default::default()
+    };
+    (Interpreter::with_config(config), budget)
+}
+
+/// Run `source` as a script until it completes or fails
+fn run(interp: &mut Interpreter, source: &str) -> Result<StepResult, JsError> {
+    interp.prepare(source, None)?;
+    loop {
+        match interp.step()? {
+            StepResult::Continue => continue,
+            result => return Ok(result),
+        }
+    }
+}
+
+fn is_budget_exceeded(result: &Result<StepResult, JsError>) -> bool {
+    matches!(
+        result,
+        Err(JsError::Terminated {
+            reason: TerminationReason::HeapBudgetExceeded
+        })
+    )
+}
+
+#[test]
+fn test_string_building_loop_exceeds_budget() {
+    let (mut interp, budget) = budgeted_interp();
+    let result = run(
+        &mut interp,
+        r#"
+        let s = "";
+        try {
+            while (true) s += "abcdefghijklmnop";
+        } catch (e) {
+            s = "caught";
+        }
+        s.length
+    "#,
+    );
+    assert!(is_budget_exceeded(&result), "{:?}", result);
+    assert!(budget.used() <= budget.limit());
+    assert!(budget.peak() > 0);
+}
+
+#[test]
+fn test_object_growth_exceeds_budget() {
+    let (mut interp, budget) = budgeted_interp();
+    let result = run(
+        &mut interp,
+        "const xs = []; while (true) xs.push({ n: xs.length });",
+    );
+    assert!(is_budget_exceeded(&result), "{:?}", result);
+    assert!(budget.is_exceeded());
+}
+
+#[test]
+fn test_normal_script_runs_within_budget() {
+    let (mut interp, budget) = budgeted_interp();
+    let result = run(
+        &mut interp,
+        r#"
+        function fib(n) { return n < 2 ? n : fib(n - 1) + fib(n - 2); }
+        let total = 0;
+        for (let i = 0; i < 2000; i++) {
+            const item = { id: i, label: "item " + i, tags: [i % 3, i % 5] };
+            total += item.tags[0] + item.label.length;
+        }
+        total + fib(15)
+    "#,
+    );
+    assert!(
+        matches!(&result, Ok(StepResult::Complete(value)) if value.as_number() == Some(19499.0)),
+        "{:?}",
+        result
+    );
+    let usage = interp.heap_usage().unwrap_or_default();
+    assert!(usage.object_bytes > 0 && usage.string_bytes > 0 && usage.bytecode_bytes > 0);
+    assert_eq!(usage.total(), budget.used());
+    assert!(budget.peak() < budget.limit());
+}
@@ -1,7 +1,7 @@
 //! Tests for the public API ergonomics
 
-use super::{create_test_runtime, run};
-use tsrun::{JsValue, StepResult, api};
+use super::{create_test_runtime, eval, run};
+use tsrun::{JsValue, RuntimeValue, StepResult, WellKnownSymbol, api};
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // JsValue Type Check Tests
@@ -114,6 +114,195 @@ fn test_as_js_string() {
     assert!(JsValue::Number(42.0).as_js_string().is_none());
 }
 
+// ═══════════════════════════════════════════════════════════════════════════════
+// Checked Number Conversion Tests
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// 2^53, the first integer past `Number.MAX_SAFE_INTEGER`
+const TWO_POW_53: f64 = 9_007_199_254_740_992.0;
+
+#[test]
+fn test_as_i64_boundaries() {
+    let num = |n: f64| JsValue::Number(n).as_i64();
+    assert_eq!(num(0.0), Some(0));
+    assert_eq!(num(-0.0), Some(0));
+    assert_eq!(num(42.0), Some(42));
+    assert_eq!(num(-42.0), Some(-42));
+    assert_eq!(num(TWO_POW_53 - 1.0), Some(9_007_199_254_740_991));
+    assert_eq!(num(-(TWO_POW_53 - 1.0)), Some(-9_007_199_254_740_991));
+    assert_eq!(num(TWO_POW_53), None);
+    assert_eq!(num(-TWO_POW_53), None);
+    assert_eq!(num(4.7), None);
+    assert_eq!(num(-0.5), None);
+    assert_eq!(num(f64::NAN), None);
+    assert_eq!(num(f64::INFINITY), None);
+    assert_eq!(num(f64::NEG_INFINITY), None);
+    assert_eq!(JsValue::from("42").as_i64(), None);
+    assert_eq!(JsValue::Undefined.as_i64(), None);
+}
+
+#[test]
+fn test_as_u64_and_usize_boundaries() {
+    for (n, expected) in [
+        (0.0, Some(0u64)),
+        (-0.0, Some(0)),
+        (1.0, Some(1)),
+        (TWO_POW_53 - 1.0, Some(9_007_199_254_740_991)),
+        (TWO_POW_53, None),
+        (-1.0, None),
+        (0.25, None),
+        (f64::NAN, None),
+        (f64::INFINITY, None),
+        (f64::NEG_INFINITY, None),
+    ] {
+        let value = JsValue::Number(n);
+        assert_eq!(value.as_u64(), expected, "as_u64({})", n);
+        assert_eq!(
+            value.as_usize(),
+            expected.map(|n| n as usize),
+            "as_usize({})",
+            n
+        );
+    }
+    assert_eq!(JsValue::Boolean(true).as_u64(), None);
+    assert_eq!(JsValue::Null.as_usize(), None);
+}
+
+#[test]
+fn test_as_u32_boundaries() {
+    let num = |n: f64| JsValue::Number(n).as_u32();
+    assert_eq!(num(0.0), Some(0));
+    assert_eq!(num(-0.0), Some(0));
+    assert_eq!(num(4_294_967_295.0), Some(u32::MAX));
+    assert_eq!(num(4_294_967_296.0), None);
+    assert_eq!(num(-1.0), None);
+    assert_eq!(num(4.7), None);
+    assert_eq!(num(TWO_POW_53), None);
+    assert_eq!(num(f64::NAN), None);
+    assert_eq!(num(f64::INFINITY), None);
+    assert_eq!(JsValue::from("7").as_u32(), None);
+}
+
+#[test]
+fn test_as_f32_boundaries() {
+    let num = |n: f64| JsValue::Number(n).as_f32();
+    assert_eq!(num(1.5), Some(1.5));
+    assert_eq!(num(-0.0).map(f32::is_sign_negative), Some(true));
+    assert_eq!(num(0.1), Some(0.1f32));
+    assert_eq!(num(TWO_POW_53), Some(9_007_199_254_740_992.0f32));
+    assert_eq!(num(f64::from(f32::MAX)), Some(f32::MAX));
+    assert_eq!(num(f64::from(f32::MAX) * 2.0), None);
+    assert_eq!(num(-f64::from(f32::MAX) * 2.0), None);
+    assert_eq!(num(f64::NAN), None);
+    assert_eq!(num(f64::INFINITY), None);
+    assert_eq!(num(f64::NEG_INFINITY), None);
+    assert_eq!(JsValue::from("1.5").as_f32(), None);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_try_conversions_explain_failures() {
+    assert_eq!(JsValue::Number(7.0).try_u32().unwrap(), 7);
+    let message = |result: Result<u32, tsrun::JsError>| result.unwrap_err().to_string();
+    assert_eq!(
+        message(JsValue::Number(4.7).try_u32()),
+        "RangeError: Expected an integer for u32, got 4.7"
+    );
+    assert_eq!(
+        message(JsValue::Number(-1.0).try_u32()),
+        "RangeError: -1 is out of range for u32"
+    );
+    assert_eq!(
+        message(JsValue::Number(f64::NAN).try_u32()),
+        "RangeError: Expected an integer for u32, got NaN"
+    );
+    assert_eq!(
+        message(JsValue::from("7").try_u32()),
+        "TypeError: Expected a number for u32, got string"
+    );
+    assert_eq!(
+        JsValue::Number(TWO_POW_53)
+            .try_i64()
+            .unwrap_err()
+            .to_string(),
+        "RangeError: 9007199254740992 is out of range for i64"
+    );
+    assert_eq!(
+        JsValue::Number(f64::INFINITY)
+            .try_f32()
+            .unwrap_err()
+            .to_string(),
+        "RangeError: Infinity is out of range for f32"
+    );
+}
+
+#[test]
+fn test_as_string_lossy() {
+    assert_eq!(
+        JsValue::Undefined.as_string_lossy().as_deref(),
+        Some("undefined")
+    );
+    assert_eq!(JsValue::Null.as_string_lossy().as_deref(), Some("null"));
+    assert_eq!(
+        JsValue::Boolean(false).as_string_lossy().as_deref(),
+        Some("false")
+    );
+    assert_eq!(
+        JsValue::Number(-0.0).as_string_lossy().as_deref(),
+        Some("0")
+    );
+    assert_eq!(
+        JsValue::Number(1e21).as_string_lossy().as_deref(),
+        Some("1e+21")
+    );
+    assert_eq!(
+        JsValue::Number(f64::NEG_INFINITY)
+            .as_string_lossy()
+            .as_deref(),
+        Some("-Infinity")
+    );
+    assert_eq!(JsValue::from("hi").as_string_lossy().as_deref(), Some("hi"));
+    // String(value) matches for primitives produced by script
+    assert_eq!(
+        eval("String(Symbol('tag'))").as_str().map(String::from),
+        eval("Symbol('tag')").as_string_lossy()
+    );
+    // Objects would need their toString run
+    assert_eq!(eval("({})").as_string_lossy(), None);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_try_from_runtime_value() {
+    let count = eval("[1, 2, 3].length");
+    let n: u32 = (&count).try_into().unwrap();
+    assert_eq!(n, 3);
+    let n: i64 = (&count).try_into().unwrap();
+    assert_eq!(n, 3);
+    let n: usize = (&count).try_into().unwrap();
+    assert_eq!(n, 3);
+    let n: f64 = (&count).try_into().unwrap();
+    assert_eq!(n, 3.0);
+    assert_eq!(count.as_u64(), Some(3));
+    assert_eq!(count.as_f32(), Some(3.0));
+
+    let ratio = eval("7 / 2");
+    assert!(u32::try_from(&ratio).is_err());
+    assert!(ratio.try_i64().is_err());
+    assert_eq!(f32::try_from(&ratio).unwrap(), 3.5);
+
+    let flag = eval("1 < 2");
+    assert!(bool::try_from(&flag).unwrap());
+    assert!(String::try_from(&flag).is_err());
+
+    let name: String = (&eval("'tsrun'")).try_into().unwrap();
+    assert_eq!(name, "tsrun");
+    assert_eq!(
+        u64::try_from(&eval("'3'")).unwrap_err().to_string(),
+        "TypeError: Expected a number for u64, got string"
+    );
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // From/Into Trait Tests
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -523,8 +712,90 @@ fn test_runtime_value_display() {
     check("42", "42");
     check("3.14", "3.14");
     check("'hello'", "hello");
-    check("({})", "[object Object]"); // Parentheses force object literal
-    check("[]", "[object Object]");
+    check("({})", "{}"); // Parentheses force object literal
+    check("[]", "[]");
+    check(
+        "({ name: 'app', tags: ['a', 'b'], get port() { return 80; } })",
+        "{ name: 'app', tags: ['a', 'b'], port: [Getter] }",
+    );
+    check(
+        "[function build() {}, () => 1]",
+        "[[Function: build], [Function: anonymous]]",
+    );
+}
+
+#[test]
+fn test_runtime_value_display_of_cycle_terminates() {
+    let mut runtime = create_test_runtime();
+    let result = run(
+        &mut runtime,
+        "const a: any = { name: 'a' }; a.self = a; a.list = [a, { back: a }]; a",
+        None,
+    )
+    .unwrap();
+    let StepResult::Complete(rv) = result else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(
+        rv.to_string(),
+        "{ name: 'a', self: [Circular], list: [[Circular], { back: [Circular] }] }"
+    );
+}
+
+#[test]
+fn test_runtime_value_display_truncates() {
+    let mut runtime = create_test_runtime();
+    let result = run(
+        &mut runtime,
+        "({ items: Array.from({ length: 10000 }, (_, i) => i), text: 'x'.repeat(5000) })",
+        None,
+    )
+    .unwrap();
+    let StepResult::Complete(rv) = result else {
+        panic!("Expected Complete");
+    };
+    let text = rv.to_string();
+    assert!(text.contains("98, 99, ... 9900 more items]"), "{}", text);
+    assert!(text.contains("... 4000 more characters"), "{}", text);
+    assert!(text.len() < 2000, "{}", text.len());
+
+    let short = rv.display_opts(2, 3).to_string();
+    assert!(
+        short.starts_with("{ items: [0, 1, 2, ... 9997 more items], text: 'xxx"),
+        "{}",
+        short
+    );
+    assert_eq!(rv.display_opts(0, 3).to_string(), "{...}");
+}
+
+#[test]
+fn test_runtime_value_display_is_stable() {
+    let manifest = r#"({
+        name: "svc",
+        deploy: { replicas: 3, regions: ["eu", "us"], limits: { cpu: "500m", memory: { max: "1Gi" } } },
+        env: new Map([["MODE", "prod"]]),
+        flags: new Set(["a"]),
+    })"#;
+    let display = || {
+        let mut runtime = create_test_runtime();
+        let result = run(&mut runtime, manifest, None).unwrap();
+        let StepResult::Complete(rv) = result else {
+            panic!("Expected Complete");
+        };
+        (rv.to_string(), rv.display_opts(2, 100).to_string())
+    };
+    let (full, shallow) = display();
+    assert_eq!(
+        full,
+        "{ name: 'svc', deploy: { replicas: 3, regions: ['eu', 'us'], limits: { cpu: '500m', \
+         memory: { max: '1Gi' } } }, env: Map(1){ 'MODE' => 'prod' }, flags: Set(1){ 'a' } }"
+    );
+    assert_eq!(
+        shallow,
+        "{ name: 'svc', deploy: { replicas: 3, regions: [Array(2)], limits: {...} }, \
+         env: Map(1){ 'MODE' => 'prod' }, flags: Set(1){ 'a' } }"
+    );
+    assert_eq!(display(), (full, shallow));
 }
 
 #[test]
@@ -1287,6 +1558,42 @@ fn test_api_is_empty() {
     assert_eq!(api::is_empty(&non_empty), Some(false));
 }
 
+#[test]
+fn test_runtime_value_array_contains() {
+    let mut runtime = create_test_runtime();
+    let result = run(
+        &mut runtime,
+        "const shared = {}; [[NaN, -0, 'a', shared, undefined], shared, {}]",
+        None,
+    )
+    .unwrap();
+    let StepResult::Complete(values) = result else {
+        panic!("Expected Complete");
+    };
+    let arr = RuntimeValue::unguarded(api::get_index(values.value(), 0).unwrap());
+    let shared = api::get_index(values.value(), 1).unwrap();
+    let other = api::get_index(values.value(), 2).unwrap();
+
+    let cases = [
+        (JsValue::Number(f64::NAN), true),
+        (JsValue::Number(0.0), true),
+        (JsValue::from("a"), true),
+        (JsValue::from("b"), false),
+        (JsValue::Undefined, true),
+        (JsValue::Null, false),
+        (shared, true),
+        (other, false),
+    ];
+    for (value, expected) in cases {
+        assert_eq!(arr.array_contains(&value), Some(expected), "{:?}", value);
+    }
+    assert_eq!(values.array_contains(&JsValue::Null), Some(false));
+    assert_eq!(
+        RuntimeValue::unguarded(JsValue::from("a")).array_contains(&JsValue::from("a")),
+        None
+    );
+}
+
 #[test]
 fn test_api_is_array() {
     let mut runtime = create_test_runtime();
@@ -1855,3 +2162,729 @@ fn test_get_export_live_binding() {
         _ => panic!("Expected Complete"),
     }
 }
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Symbol-Keyed Property Tests
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_symbol_keys_and_lookup_by_symbol() {
+    let mut runtime = create_test_runtime();
+    let result = run(
+        &mut runtime,
+        r#"
+        const tag = Symbol("tag");
+        ({ a: 1, [tag]: "custom", [Symbol.toStringTag]: "Meta" })
+    "#,
+        None,
+    )
+    .unwrap();
+    let StepResult::Complete(obj) = result else {
+        panic!("Expected Complete");
+    };
+
+    assert_eq!(obj.keys(), vec!["a".to_string()]);
+    let symbols = obj.symbol_keys();
+    let descriptions: Vec<String> = symbols
+        .iter()
+        .map(|sym| sym.description.as_ref().unwrap().to_string())
+        .collect();
+    assert_eq!(descriptions, vec!["tag", "Symbol.toStringTag"]);
+
+    let tag = RuntimeValue::unguarded(JsValue::Symbol(Box::new(symbols[0].clone())));
+    let custom = runtime.get_property_by_symbol(&obj, &tag).unwrap();
+    assert_eq!(custom.as_str(), Some("custom"));
+
+    let to_string_tag = runtime.get_well_known(WellKnownSymbol::ToStringTag);
+    let meta = runtime
+        .get_property_by_symbol(&obj, &to_string_tag)
+        .unwrap();
+    assert_eq!(meta.as_str(), Some("Meta"));
+
+    // Missing symbol properties read as undefined
+    let iterator = runtime.get_well_known(WellKnownSymbol::Iterator);
+    let missing = runtime.get_property_by_symbol(&obj, &iterator).unwrap();
+    assert!(missing.is_undefined());
+}
+
+#[test]
+fn test_host_created_symbols_are_unique() {
+    let mut runtime = create_test_runtime();
+    let guard = api::create_guard(&runtime);
+    let obj = RuntimeValue::unguarded(api::create_object(&mut runtime, &guard).unwrap());
+
+    let first = runtime.create_symbol(Some("id"));
+    let second = runtime.create_symbol(Some("id"));
+    assert_ne!(first.value(), second.value());
+    assert!(runtime.create_symbol(None).value().is_symbol());
+
+    runtime
+        .set_property_by_symbol(&obj, &first, &RuntimeValue::unguarded(JsValue::from(1)))
+        .unwrap();
+    runtime
+        .set_property_by_symbol(&obj, &second, &RuntimeValue::unguarded(JsValue::from(2)))
+        .unwrap();
+    assert_eq!(
+        runtime
+            .get_property_by_symbol(&obj, &first)
+            .unwrap()
+            .as_number(),
+        Some(1.0)
+    );
+    assert_eq!(
+        runtime
+            .get_property_by_symbol(&obj, &second)
+            .unwrap()
+            .as_number(),
+        Some(2.0)
+    );
+    assert_eq!(obj.symbol_keys().len(), 2);
+    assert!(obj.keys().is_empty());
+}
+
+#[test]
+fn test_symbol_property_access_rejects_bad_operands() {
+    let mut runtime = create_test_runtime();
+    let guard = api::create_guard(&runtime);
+    let obj = RuntimeValue::unguarded(api::create_object(&mut runtime, &guard).unwrap());
+    let symbol = runtime.get_well_known(WellKnownSymbol::Iterator);
+    let not_symbol = RuntimeValue::unguarded(JsValue::from("iterator"));
+    let not_object = RuntimeValue::unguarded(JsValue::from(1));
+
+    assert!(runtime.get_property_by_symbol(&obj, &not_symbol).is_err());
+    assert!(
+        runtime
+            .get_property_by_symbol(&not_object, &symbol)
+            .is_err()
+    );
+    assert!(
+        runtime
+            .set_property_by_symbol(&not_object, &symbol, &not_symbol)
+            .is_err()
+    );
+    assert!(not_object.symbol_keys().is_empty());
+}
+
+#[test]
+fn test_deep_freeze_host_value() {
+    let mut runtime = create_test_runtime();
+    let guard = api::create_guard(&runtime);
+    let v = api::create_from_json(
+        &mut runtime,
+        &guard,
+        &serde_json::json!({ "user": { "tags": ["a", "b"] }, "limits": { "max": 3 } }),
+    )
+    .unwrap();
+    let v = RuntimeValue::unguarded(v);
+
+    // The root, user, tags and limits
+    assert_eq!(runtime.deep_freeze(&v).unwrap(), 4);
+    assert_eq!(runtime.deep_freeze(&v).unwrap(), 0);
+    assert_eq!(
+        runtime
+            .deep_freeze(&RuntimeValue::unguarded(JsValue::from(1)))
+            .unwrap(),
+        0
+    );
+
+    let user = api::get_property(v.value(), "user").unwrap();
+    let tags = api::get_property(&user, "tags").unwrap();
+    assert!(api::as_object(&tags).is_some_and(|tags| tags.borrow().frozen));
+    let _ = api::set_property(&user, "name", JsValue::from("Mallory"));
+    assert!(api::get_property(&user, "name").unwrap().is_undefined());
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Cached JSON Conversion Tests
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// A JSON document of about 1MB with records that share some subtrees
+fn large_json_document() -> serde_json::Value {
+    let records: Vec<serde_json::Value> = (0..4000)
+        .map(|i| {
+            serde_json::json!({
+                "id": i,
+                "name": format!("user-{}", i),
+                "roles": ["reader", "writer"],
+                "profile": { "bio": "b".repeat(200), "age": i % 90 },
+            })
+        })
+        .collect();
+    serde_json::json!({ "version": 3, "records": records })
+}
+
+fn allocations(interp: &tsrun::Interpreter) -> usize {
+    interp.gc_stats().allocations
+}
+
+#[test]
+fn test_json_cache_reuses_identical_document() {
+    let mut interp = tsrun::Interpreter::new();
+    let doc = large_json_document();
+    assert!(serde_json::to_string(&doc).unwrap().len() > 1_000_000);
+
+    let before = allocations(&interp);
+    let first = interp.create_from_json_cached(&doc).unwrap();
+    let after_first = allocations(&interp);
+    let second = interp.create_from_json_cached(&doc).unwrap();
+    let after_second = allocations(&interp);
+
+    assert!(after_first - before > 4000);
+    assert_eq!(after_second - after_first, 0);
+    assert_eq!(tsrun::js_value_to_json(first.value()).unwrap(), doc);
+    assert_eq!(tsrun::js_value_to_json(second.value()).unwrap(), doc);
+    assert!(api::as_object(second.value()).is_some_and(|obj| obj.borrow().deep_frozen));
+}
+
+#[test]
+fn test_json_cache_shares_only_common_parts() {
+    let mut interp = tsrun::Interpreter::new();
+    let shared = serde_json::json!({ "limits": { "max": 3 }, "tags": ["a", "b"] });
+    let a = interp
+        .create_from_json_cached(&serde_json::json!({ "shared": shared, "x": 1 }))
+        .unwrap();
+    let before = allocations(&interp);
+    let b = interp
+        .create_from_json_cached(&serde_json::json!({ "shared": shared, "x": 2 }))
+        .unwrap();
+
+    // Only the differing root is new
+    assert_eq!(allocations(&interp) - before, 1);
+    let shared_a = api::as_object(&api::get_property(a.value(), "shared").unwrap()).unwrap();
+    let shared_b = api::as_object(&api::get_property(b.value(), "shared").unwrap()).unwrap();
+    assert!(tsrun::Gc::ptr_eq(&shared_a, &shared_b));
+    assert!(!tsrun::Gc::ptr_eq(
+        &api::as_object(a.value()).unwrap(),
+        &api::as_object(b.value()).unwrap()
+    ));
+    assert_eq!(api::get_property(b.value(), "x").unwrap(), JsValue::from(2));
+
+    // Frozen, so neither document can see a change made through the other
+    let _ = api::set_property(&JsValue::Object(shared_a), "extra", JsValue::from(1));
+    assert!(
+        api::get_property(&JsValue::Object(shared_b), "extra")
+            .unwrap()
+            .is_undefined()
+    );
+}
+
+#[test]
+fn test_json_cache_survives_gc_and_evicts_least_recent() {
+    let mut interp = tsrun::Interpreter::with_config(tsrun::InterpreterConfig {
+        json_cache_capacity: 4,
+        ..Default::default()
+    });
+    let doc = |n: i32| serde_json::json!({ "n": n });
+
+    // Rooted by the cache after the caller's value is dropped
+    drop(interp.create_from_json_cached(&doc(0)).unwrap());
+    interp.collect();
+    let before = allocations(&interp);
+    let again = interp.create_from_json_cached(&doc(0)).unwrap();
+    assert_eq!(allocations(&interp) - before, 0);
+    assert_eq!(tsrun::js_value_to_json(again.value()).unwrap(), doc(0));
+
+    for n in 1..=4 {
+        drop(interp.create_from_json_cached(&doc(n)).unwrap());
+    }
+    assert!(interp.json_cache_len() <= 4);
+
+    // The oldest document was evicted, the newest is still shared
+    let before = allocations(&interp);
+    drop(interp.create_from_json_cached(&doc(4)).unwrap());
+    assert_eq!(allocations(&interp) - before, 0);
+    drop(interp.create_from_json_cached(&doc(1)).unwrap());
+    assert_eq!(allocations(&interp) - before, 1);
+
+    interp.clear_json_cache();
+    assert_eq!(interp.json_cache_len(), 0);
+    let before = allocations(&interp);
+    drop(interp.create_from_json_cached(&doc(4)).unwrap());
+    assert_eq!(allocations(&interp) - before, 1);
+}
+
+#[test]
+fn test_create_map_round_trip() {
+    let mut runtime = create_test_runtime();
+    let entries = vec![
+        (
+            RuntimeValue::unguarded(JsValue::from(2)),
+            RuntimeValue::unguarded(JsValue::from("two")),
+        ),
+        (
+            RuntimeValue::unguarded(JsValue::from(1)),
+            RuntimeValue::unguarded(JsValue::from("one")),
+        ),
+        (
+            RuntimeValue::unguarded(JsValue::from("1")),
+            RuntimeValue::unguarded(JsValue::from("string one")),
+        ),
+    ];
+    let map = runtime.create_map(entries);
+
+    let result = run(
+        &mut runtime,
+        r#"
+        (map: Map<any, string>) => {
+            const seen: string[] = [];
+            for (const [key, value] of map) seen.push(typeof key + ":" + value);
+            const out = new Map(map);
+            out.set(3, seen.join());
+            out.delete("1");
+            return out;
+        }
+        "#,
+        None,
+    )
+    .unwrap();
+    let StepResult::Complete(transform) = result else {
+        panic!("Expected Complete");
+    };
+    let guard = api::create_guard(&runtime);
+    let out = api::call_function(
+        &mut runtime,
+        &guard,
+        transform.value(),
+        None,
+        &[map.value().clone()],
+    )
+    .unwrap();
+    let out = RuntimeValue::unguarded(out);
+
+    let entries = out.as_map_entries(&runtime).unwrap();
+    let entries: Vec<(JsValue, JsValue)> = entries
+        .iter()
+        .map(|(k, v)| (k.value().clone(), v.value().clone()))
+        .collect();
+    assert_eq!(
+        entries,
+        vec![
+            (JsValue::from(2), JsValue::from("two")),
+            (JsValue::from(1), JsValue::from("one")),
+            (
+                JsValue::from(3),
+                JsValue::from("number:two,number:one,string:string one")
+            ),
+        ]
+    );
+    assert!(map.as_set_values(&runtime).is_none());
+    assert!(
+        RuntimeValue::unguarded(JsValue::from(1))
+            .as_map_entries(&runtime)
+            .is_none()
+    );
+}
+
+#[test]
+fn test_create_set_round_trip() {
+    let mut runtime = create_test_runtime();
+    let set = runtime.create_set(vec![
+        RuntimeValue::unguarded(JsValue::from(1)),
+        RuntimeValue::unguarded(JsValue::from("a")),
+        RuntimeValue::unguarded(JsValue::from(1)),
+    ]);
+
+    let result = run(
+        &mut runtime,
+        "(s: Set<any>) => s.has(1) && s.size === 2",
+        None,
+    )
+    .unwrap();
+    let StepResult::Complete(check) = result else {
+        panic!("Expected Complete");
+    };
+    let guard = api::create_guard(&runtime);
+    let ok = api::call_function(
+        &mut runtime,
+        &guard,
+        check.value(),
+        None,
+        &[set.value().clone()],
+    )
+    .unwrap();
+    assert_eq!(ok, JsValue::Boolean(true));
+
+    let values: Vec<JsValue> = set
+        .as_set_values(&runtime)
+        .unwrap()
+        .iter()
+        .map(|v| v.value().clone())
+        .collect();
+    assert_eq!(values, vec![JsValue::from(1), JsValue::from("a")]);
+}
+
+#[test]
+fn test_to_json_maps_as() {
+    use tsrun::{MapsAs, js_value_to_json, js_value_to_json_with};
+
+    let mut runtime = create_test_runtime();
+    let result = run(
+        &mut runtime,
+        r#"({ m: new Map<any, any>([[1, "a"], ["k", new Set([true])]]) })"#,
+        None,
+    )
+    .unwrap();
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete");
+    };
+
+    assert_eq!(
+        js_value_to_json_with(value.value(), MapsAs::Object).unwrap(),
+        serde_json::json!({ "m": { "1": "a", "k": [true] } })
+    );
+    assert_eq!(
+        js_value_to_json_with(value.value(), MapsAs::EntriesArray).unwrap(),
+        serde_json::json!({ "m": [[1, "a"], ["k", [true]]] })
+    );
+    let err = js_value_to_json_with(value.value(), MapsAs::Error).unwrap_err();
+    assert!(
+        err.to_string().contains("Cannot convert a Map to JSON"),
+        "{}",
+        err
+    );
+
+    // The plain conversion keeps JSON.stringify's behavior
+    assert_eq!(
+        js_value_to_json(value.value()).unwrap(),
+        serde_json::json!({ "m": null })
+    );
+
+    // Object keys have no JSON object form
+    let result = run(&mut runtime, "new Map([[{}, 1]])", None).unwrap();
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete");
+    };
+    assert!(js_value_to_json_with(value.value(), MapsAs::Object).is_err());
+    assert_eq!(
+        js_value_to_json_with(value.value(), MapsAs::EntriesArray).unwrap(),
+        serde_json::json!([[{}, 1]])
+    );
+}
+
+#[test]
+fn test_to_json_options() {
+    use tsrun::{NonFinite, ToJsonOptions, UndefinedInArrays};
+
+    let mut runtime = create_test_runtime();
+    let result = run(
+        &mut runtime,
+        r#"({ spec: { template: { replicas: NaN, limits: [1, -Infinity] } }, tags: ["a", undefined, "b"] })"#,
+        None,
+    )
+    .unwrap();
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete");
+    };
+
+    // The default matches JSON.stringify
+    let json = value.to_json(&ToJsonOptions::default()).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "spec": { "template": { "replicas": null, "limits": [1, null] } },
+            "tags": ["a", null, "b"]
+        })
+    );
+    assert_eq!(json, tsrun::js_value_to_json(value.value()).unwrap());
+
+    let as_strings = ToJsonOptions {
+        non_finite: NonFinite::String,
+        undefined_in_arrays: UndefinedInArrays::Skip,
+        ..ToJsonOptions::default()
+    };
+    assert_eq!(
+        value.to_json(&as_strings).unwrap(),
+        serde_json::json!({
+            "spec": { "template": { "replicas": "NaN", "limits": [1, "-Infinity"] } },
+            "tags": ["a", "b"]
+        })
+    );
+
+    let strict = ToJsonOptions {
+        non_finite: NonFinite::Error,
+        ..ToJsonOptions::default()
+    };
+    let err = value.to_json(&strict).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "TypeError: Cannot convert non-finite number NaN to JSON at /spec/template/replicas"
+    );
+
+    let no_holes = ToJsonOptions {
+        undefined_in_arrays: UndefinedInArrays::Error,
+        ..ToJsonOptions::default()
+    };
+    let err = value.to_json(&no_holes).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "TypeError: Cannot convert undefined array element to JSON at /tags/1"
+    );
+}
+
+#[test]
+fn test_to_json_primitive_wrappers() {
+    use tsrun::{ToJsonOptions, WrappersAs};
+
+    let mut runtime = create_test_runtime();
+    let result = run(
+        &mut runtime,
+        r#"({ name: new String("web"), replicas: new Number(3), spec: { enabled: [new Boolean(true)] } })"#,
+        None,
+    )
+    .unwrap();
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete");
+    };
+
+    let json = value.to_json(&ToJsonOptions::default()).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({ "name": "web", "replicas": 3, "spec": { "enabled": [true] } })
+    );
+    assert_eq!(json, tsrun::js_value_to_json(value.value()).unwrap());
+
+    let strict = ToJsonOptions {
+        wrappers: WrappersAs::Error,
+        ..ToJsonOptions::default()
+    };
+    let err = value.to_json(&strict).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "TypeError: Cannot convert String wrapper object to JSON at /name"
+    );
+    let err = tsrun::js_value_to_json_opts(value.value(), &strict).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "TypeError: Cannot convert String wrapper object to JSON at /name"
+    );
+}
+
+#[test]
+fn test_to_json_max_depth() {
+    use tsrun::ToJsonOptions;
+
+    let mut runtime = create_test_runtime();
+    let result = run(
+        &mut runtime,
+        r#"({ name: "web", spec: { "a/b": { deep: [{ x: 1 }] } } })"#,
+        None,
+    )
+    .unwrap();
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete");
+    };
+
+    let depth = |max_depth| ToJsonOptions {
+        max_depth: Some(max_depth),
+        ..ToJsonOptions::default()
+    };
+    assert!(value.to_json(&depth(5)).is_ok());
+
+    // Path keys are escaped as in a JSON Pointer
+    let err = value.to_json(&depth(3)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "RangeError: JSON nesting exceeds the maximum depth of 3 at /spec/a~1b/deep"
+    );
+    let err = value.to_json(&depth(4)).unwrap_err();
+    assert!(err.to_string().ends_with("at /spec/a~1b/deep/0"), "{}", err);
+    let err = value.to_json(&depth(0)).unwrap_err();
+    assert!(err.to_string().ends_with("at /"), "{}", err);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Values Outliving the Interpreter
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_runtime_value_after_interpreter_drop() {
+    let mut runtime = create_test_runtime();
+    let result = run(&mut runtime, r#"({ name: "config" })"#, None).unwrap();
+    let StepResult::Complete(object) = result else {
+        panic!("Expected Complete");
+    };
+    let result = run(&mut runtime, r#""text""#, None).unwrap();
+    let StepResult::Complete(text) = result else {
+        panic!("Expected Complete");
+    };
+    assert!(!object.is_detached());
+    assert_eq!(runtime.heap.host_guard_count(), 1);
+
+    drop(runtime);
+
+    assert!(object.is_detached());
+    let err = object.try_value().unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("after its Interpreter was dropped"),
+        "{}",
+        err
+    );
+    let err = api::get_property(object.value(), "name").unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("after its Interpreter was dropped"),
+        "{}",
+        err
+    );
+    assert_eq!(api::len(object.value()), None);
+    assert!(api::keys(object.value()).is_empty());
+
+    // Borrowing the object directly panics instead of reading freed memory
+    let JsValue::Object(obj) = object.value() else {
+        panic!("Expected an object");
+    };
+    let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        obj.borrow().properties.len()
+    }))
+    .unwrap_err();
+    let message = panic.downcast_ref::<&str>().copied().unwrap_or_default();
+    assert!(
+        message.contains("after its Heap was dropped"),
+        "{}",
+        message
+    );
+
+    // Primitives don't depend on the heap
+    assert!(!text.is_detached());
+    assert_eq!(text.try_value().unwrap().as_str(), Some("text"));
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Detached Host Values
+// ═══════════════════════════════════════════════════════════════════════════════
+
+const DETACHED_CONTEXT: &str =
+    r#"{"user": {"name": "Ada", "tags": ["a", "b"]}, "items": [{"n": 1}, {"n": 2}]}"#;
+
+/// Answers every order with `DETACHED_CONTEXT`, detached
+struct DetachedContextHost;
+
+impl tsrun::Host for DetachedContextHost {
+    fn load_module(&mut self, request: &tsrun::ImportRequest) -> Result<String, String> {
+        Err(format!("no module {}", request.specifier))
+    }
+
+    fn handle_order(
+        &mut self,
+        _order: &tsrun::Order,
+        responses: &mut tsrun::ResponseFactory<'_>,
+    ) -> tsrun::HostAction {
+        let json = serde_json::from_str(DETACHED_CONTEXT)
+            .map_err(|e| tsrun::JsError::type_error(e.to_string()));
+        tsrun::HostAction::Fulfill(
+            json.and_then(|json| {
+                api::create_response_object_detached(responses.interpreter(), &json)
+            }),
+        )
+    }
+}
+
+/// Run `body` with `ctx` bound to the detached context
+#[allow(clippy::unwrap_used)]
+fn with_detached_context(body: &str) -> RuntimeValue {
+    let source = format!(
+        r#"
+        import {{ order }} from "tsrun:host";
+        const ctx: any = await order({{}});
+        {}
+        "#,
+        body
+    );
+    tsrun::Interpreter::new()
+        .run(&source, None, &mut DetachedContextHost)
+        .unwrap()
+}
+
+#[test]
+fn test_detached_context_reaches_no_intrinsics() {
+    let result = with_detached_context(
+        r#"
+        const graph = [ctx, ctx.user, ctx.user.tags, ctx.items, ctx.items[1]];
+        [
+            graph.every((o) => Object.getPrototypeOf(o) === null),
+            graph.every((o) => o.constructor === undefined),
+            graph.every((o) => !("hasOwnProperty" in o) && !("__proto__" in o)),
+            Array.isArray(ctx.items),
+        ].join()
+        "#,
+    );
+    assert_eq!(result.as_str(), Some("true,true,true,true"));
+}
+
+#[test]
+fn test_detached_context_property_access() {
+    let result = with_detached_context(
+        r#"`${ctx.user.name}:${ctx["items"][1].n}:${ctx.items.length}:${ctx.user?.missing}`"#,
+    );
+    assert_eq!(result.as_str(), Some("Ada:2:2:undefined"));
+}
+
+#[test]
+fn test_detached_context_iteration() {
+    let result = with_detached_context(
+        r#"
+        let sum = 0;
+        for (const item of ctx.items) sum += item.n;
+        const keys: string[] = [];
+        for (const key in ctx.user) keys.push(key);
+        `${sum}|${keys.join()}|${Object.entries(ctx.items[0]).join()}`
+        "#,
+    );
+    assert_eq!(result.as_str(), Some("3|name,tags|n,1"));
+}
+
+#[test]
+fn test_detached_context_spread() {
+    let result = with_detached_context(
+        r#"
+        const tags = [...ctx.user.tags, "c"];
+        const user = { ...ctx.user, id: 1 };
+        `${tags.join("")}|${Object.keys(user).join()}|${Object.getPrototypeOf(user) === Object.prototype}`
+        "#,
+    );
+    assert_eq!(result.as_str(), Some("abc|name,tags,id|true"));
+}
+
+#[test]
+fn test_detached_context_json_stringify() {
+    let result = with_detached_context("JSON.stringify(ctx)");
+    let expected: serde_json::Value = serde_json::from_str(DETACHED_CONTEXT).unwrap_or_default();
+    assert_eq!(result.as_str(), Some(expected.to_string().as_str()));
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_detach_host_json_config() {
+    let mut interp = tsrun::Interpreter::with_config(tsrun::InterpreterConfig {
+        detach_host_json: true,
+        ..Default::default()
+    });
+    let guard = api::create_guard(&interp);
+    let value = api::create_from_json(
+        &mut interp,
+        &guard,
+        &serde_json::json!({ "a": [{ "b": {} }] }),
+    )
+    .unwrap();
+    let list = api::get_property(&value, "a").unwrap();
+    let item = api::get_index(&list, 0).unwrap();
+    let inner = api::get_property(&item, "b").unwrap();
+    for object in [&value, &list, &item, &inner] {
+        assert_eq!(
+            api::get_property(object, "constructor").unwrap(),
+            JsValue::Undefined
+        );
+    }
+
+    // Off by default
+    let mut interp = tsrun::Interpreter::new();
+    let guard = api::create_guard(&interp);
+    let value = api::create_from_json(&mut interp, &guard, &serde_json::json!({})).unwrap();
+    assert!(
+        api::get_property(&value, "constructor")
+            .unwrap()
+            .is_object()
+    );
+}
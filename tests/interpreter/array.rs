@@ -520,6 +520,84 @@ fn test_array_includes_from_index() {
     assert_eq!(eval("[1, 2, 3].includes(1, 1)"), JsValue::Boolean(false));
 }
 
+#[test]
+fn test_array_search_comparisons_and_from_index() {
+    let cases = [
+        // SameValueZero for includes, strict equality for indexOf and lastIndexOf
+        ("[NaN].includes(NaN)", JsValue::Boolean(true)),
+        ("[NaN].indexOf(NaN)", JsValue::Number(-1.0)),
+        ("[NaN].lastIndexOf(NaN)", JsValue::Number(-1.0)),
+        ("[-0].includes(0)", JsValue::Boolean(true)),
+        ("[0].includes(-0)", JsValue::Boolean(true)),
+        ("[-0].indexOf(0)", JsValue::Number(0.0)),
+        ("[1, 0].lastIndexOf(-0)", JsValue::Number(1.0)),
+        // Objects by identity
+        ("const o = {}; [{}, o].indexOf(o)", JsValue::Number(1.0)),
+        ("[{}].includes({})", JsValue::Boolean(false)),
+        (
+            "const o = {}; [o, {}, o].lastIndexOf(o)",
+            JsValue::Number(2.0),
+        ),
+        // Holes are skipped by indexOf and read as undefined by includes
+        (
+            "Array.prototype.indexOf.call({ length: 2, 1: 1 }, undefined)",
+            JsValue::Number(-1.0),
+        ),
+        (
+            "Array.prototype.includes.call({ length: 2, 1: 1 }, undefined)",
+            JsValue::Boolean(true),
+        ),
+        // fromIndex at or beyond length
+        ("[1, 2, 3].includes(3, 3)", JsValue::Boolean(false)),
+        ("[1, 2, 3].indexOf(1, 10)", JsValue::Number(-1.0)),
+        ("[1, 2, 3].indexOf(3, Infinity)", JsValue::Number(-1.0)),
+        ("[1, 2, 3].lastIndexOf(3, 10)", JsValue::Number(2.0)),
+        // Negative fromIndex counts from the end, clamped to the start
+        ("[1, 2, 3].indexOf(1, -2)", JsValue::Number(-1.0)),
+        ("[1, 2, 3].indexOf(2, -2)", JsValue::Number(1.0)),
+        ("[1, 2, 3].includes(1, -100)", JsValue::Boolean(true)),
+        ("[1, 2, 3].indexOf(1, -Infinity)", JsValue::Number(0.0)),
+        ("[1, 2, 3, 2].lastIndexOf(2, -2)", JsValue::Number(1.0)),
+        ("[1, 2, 3].lastIndexOf(1, -4)", JsValue::Number(-1.0)),
+        ("[1, 2, 3].lastIndexOf(3, -Infinity)", JsValue::Number(-1.0)),
+        // fromIndex is converted to an integer
+        ("[1, 2, 3].indexOf(2, 1.9)", JsValue::Number(1.0)),
+        ("[1, 2, 3].includes(1, NaN)", JsValue::Boolean(true)),
+        ("[1, 2, 3].indexOf(3, '2')", JsValue::Number(2.0)),
+        // Generic receivers
+        (
+            "Array.prototype.includes.call('abc', 'b')",
+            JsValue::Boolean(true),
+        ),
+        (
+            "Array.prototype.indexOf.call('abcb', 'b', 2)",
+            JsValue::Number(3.0),
+        ),
+        (
+            "Array.prototype.lastIndexOf.call('abcb', 'b')",
+            JsValue::Number(3.0),
+        ),
+        (
+            "Array.prototype.includes.call({ length: 2, 0: NaN, 1: 'x' }, NaN)",
+            JsValue::Boolean(true),
+        ),
+        (
+            "Array.prototype.lastIndexOf.call({ length: 3, 0: 'a', 2: 'a' }, 'a', 1)",
+            JsValue::Number(0.0),
+        ),
+    ];
+    for (source, expected) in cases {
+        assert_eq!(eval(source), expected, "{}", source);
+    }
+
+    assert_eq!(
+        eval(
+            "let caught = ''; try { Array.prototype.includes.call(null, 1); } catch (e) { caught = e.message; } caught"
+        ),
+        JsValue::from("Array.prototype.includes called on null or undefined")
+    );
+}
+
 // Array.prototype.slice tests
 #[test]
 fn test_array_slice_basic() {
@@ -647,6 +725,79 @@ fn test_array_join_empty() {
     );
 }
 
+#[test]
+fn test_array_join_calls_element_to_string() {
+    assert_eq!(
+        eval(
+            r#"
+            const x = { toString() { return "x"; } };
+            [x, null, undefined, 1].join(",") + "|" + `${[x, x]}` + "|" + String([x])
+            "#
+        ),
+        JsValue::String(JsString::from("x,,,1|x,x|x"))
+    );
+}
+
+#[test]
+fn test_array_join_separator_to_string() {
+    assert_eq!(
+        eval(r#"[1, 2].join({ toString() { return "+"; } } as any)"#),
+        JsValue::String(JsString::from("1+2"))
+    );
+}
+
+#[test]
+fn test_array_join_nested_arrays() {
+    assert_eq!(
+        eval(r#"[1, [2, [3, null]], []].join(";") + "|" + `${[[1, 2], [3]]}`"#),
+        JsValue::String(JsString::from("1;2,3,;|1,2,3"))
+    );
+}
+
+#[test]
+fn test_array_join_cyclic() {
+    assert_eq!(
+        eval(
+            r#"
+            const a: any[] = [1];
+            a.push(a);
+            a.push([a, 2]);
+            a.join("-") + "|" + `${a}` + "|" + String([a])
+            "#
+        ),
+        JsValue::String(JsString::from("1--,2|1,,,2|1,,,2"))
+    );
+}
+
+#[test]
+fn test_array_join_to_string_throws() {
+    let result = eval_result(r#"[1, { toString() { throw new Error("boom"); } }].join()"#);
+    let err = result.expect_err("join should rethrow");
+    assert!(err.to_string().contains("boom"), "{}", err);
+}
+
+#[test]
+fn test_array_join_array_like() {
+    assert_eq!(
+        eval(r#"Array.prototype.join.call({ length: 3, 0: "a", 2: "c" } as any, "/")"#),
+        JsValue::String(JsString::from("a//c"))
+    );
+}
+
+#[test]
+fn test_array_to_string_calls_join() {
+    assert_eq!(
+        eval(
+            r#"
+            const b: any = [1, 2];
+            b.join = () => "own";
+            `${b}` + "|" + Array.prototype.toString.call({} as any)
+            "#
+        ),
+        JsValue::String(JsString::from("own|[object Object]"))
+    );
+}
+
 // Array.prototype.every tests
 #[test]
 fn test_array_every_all_pass() {
@@ -826,6 +977,15 @@ fn test_array_from() {
         eval("Array.from([1, 2, 3], x => x * 2)[1]"),
         JsValue::Number(4.0)
     );
+    // Array-likes are read up to their length
+    assert_eq!(
+        eval(r#"Array.from({ length: 3, 0: "a", 2: "c" }).join("-")"#),
+        JsValue::from("a--c")
+    );
+    assert_eq!(
+        eval("Array.from({ length: 2 }, (_, i) => i * 2).join()"),
+        JsValue::from("0,2")
+    );
 }
 
 // Array.prototype.at tests
@@ -1948,3 +2108,113 @@ fn test_array_length_coercion_negative() {
         JsValue::Number(0.0)
     );
 }
+
+// Array index keys stop at 2^32 - 2; `length` is validated on assignment.
+
+#[test]
+fn test_array_write_at_max_index_is_capped_and_keeps_length() {
+    // Index 2^32 - 2 would make length 2^32 - 1, beyond the dense storage cap
+    assert_eq!(
+        eval(
+            r#"
+            const a: any = [1, 2, 3];
+            let error = "";
+            try { a[4294967294] = "x"; } catch (e) { error = (e as any).name; }
+            error + ":" + a.length
+            "#
+        ),
+        JsValue::from("RangeError:3")
+    );
+}
+
+#[test]
+fn test_array_index_2_pow_32_minus_1_is_ordinary_key() {
+    assert_eq!(
+        eval(
+            r#"
+            const a: any = [1, 2, 3];
+            a[4294967295] = "x";
+            a["4294967295"] + ":" + a.length + ":" + Object.keys(a).join()
+            "#
+        ),
+        JsValue::from("x:3:0,1,2,4294967295")
+    );
+}
+
+#[test]
+fn test_array_length_assignment_truncates() {
+    assert_eq!(
+        eval(
+            r#"
+            const a: any = [1, 2, 3, 4, 5];
+            a.length = 2;
+            a.length + ":" + a.join() + ":" + (3 in a) + ":" + a[3]
+            "#
+        ),
+        JsValue::from("2:1,2:false:undefined")
+    );
+}
+
+#[test]
+fn test_array_length_assignment_coerces_value() {
+    assert_eq!(
+        eval(
+            r#"
+            const a: any = [1, 2, 3];
+            a.length = "2";
+            const b: any = [1, 2, 3];
+            b.length = { valueOf() { return 1; } };
+            a.length + ":" + b.length
+            "#
+        ),
+        JsValue::from("2:1")
+    );
+}
+
+#[test]
+fn test_array_length_assignment_rejects_invalid_lengths() {
+    assert_eq!(
+        eval(
+            r#"
+            const results: string[] = [];
+            for (const len of [-1, 1.5, 4294967296, NaN]) {
+                const a: any = [1, 2, 3];
+                try { a.length = len; results.push("ok"); } catch (e) { results.push((e as any).name); }
+                results.push(String(a.length));
+            }
+            results.join()
+            "#
+        ),
+        JsValue::from("RangeError,3,RangeError,3,RangeError,3,RangeError,3")
+    );
+}
+
+#[test]
+fn test_array_length_descriptor_and_define_property() {
+    assert_eq!(
+        eval(
+            r#"
+            const a: any = [1, 2, 3];
+            const d = Object.getOwnPropertyDescriptor(a, "length")!;
+            Object.defineProperty(a, "length", { value: 1 });
+            [d.writable, d.enumerable, d.configurable, a.length, Object.getOwnPropertyNames(a).join()].join()
+            "#
+        ),
+        JsValue::from("true,false,false,1,0,length")
+    );
+}
+
+#[test]
+fn test_array_length_cannot_be_deleted() {
+    assert_eq!(
+        eval(
+            r#"
+            const a: any = [1, 2, 3];
+            let error = "";
+            try { delete a.length; } catch (e) { error = (e as any).name; }
+            error + ":" + a.length
+            "#
+        ),
+        JsValue::from("TypeError:3")
+    );
+}
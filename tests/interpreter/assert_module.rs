@@ -0,0 +1,153 @@
+//! Tests for the tsrun:assert internal module
+
+use super::{eval, run};
+use std::cell::Cell;
+use std::rc::Rc;
+use tsrun::platform::{CompiledRegex, FancyRegexProvider, RegExpProvider};
+use tsrun::{Interpreter, InterpreterConfig, JsValue, StepResult};
+
+#[test]
+fn test_assert_passes_and_throws_message() {
+    assert_eq!(
+        eval(
+            r#"
+            import { assert } from "tsrun:assert";
+            assert(1 + 1 === 2, "math works");
+            let caught = "";
+            try { assert(false, "replicas must be positive"); } catch (e) { caught = e.name + ": " + e.message; }
+            caught
+        "#
+        ),
+        JsValue::from("Error: replicas must be positive")
+    );
+}
+
+#[test]
+fn test_check_shape_reports_every_nested_violation() {
+    assert_eq!(
+        eval(
+            r#"
+            import { checkShape } from "tsrun:assert";
+            const shape = {
+                type: "object",
+                required: ["name", "replicas", "owner"],
+                properties: {
+                    name: { type: "string", minLength: 3 },
+                    replicas: { type: "number", min: 1 },
+                    ports: { type: "array", items: { type: "integer", max: 65535 } },
+                    meta: {
+                        type: "object",
+                        properties: { tier: { enum: ["web", "worker"] } },
+                        additionalProperties: false,
+                    },
+                },
+            };
+            const violations = checkShape({
+                name: "ab",
+                replicas: 0,
+                ports: [80, 70000, "443"],
+                meta: { tier: "db", extra: true },
+            }, shape);
+            violations.map((v: any) => v.path + ": " + v.message).join("\n")
+        "#
+        ),
+        JsValue::from(
+            "$: missing required property 'owner'\n\
+             $.name: expected length at least 3, got 2\n\
+             $.replicas: expected at least 1, got 0\n\
+             $.ports[1]: expected at most 65535, got 70000\n\
+             $.ports[2]: expected integer, got string\n\
+             $.meta: unexpected property 'extra'\n\
+             $.meta.tier: expected one of [\"web\", \"worker\"], got \"db\""
+        )
+    );
+}
+
+#[test]
+fn test_assert_shape_throws_aggregated_error() {
+    assert_eq!(
+        eval(
+            r#"
+            import { assertShape } from "tsrun:assert";
+            const shape = {
+                type: "object",
+                properties: { a: { type: "string" }, b: { type: "object", required: ["c"] } },
+            };
+            assertShape({ a: "ok", b: { c: 1 } }, shape);
+            let result = "";
+            try {
+                assertShape({ a: 1, b: {} }, shape);
+            } catch (e) {
+                result = e.message + "|" + e.violations.length;
+            }
+            result
+        "#
+        ),
+        JsValue::from(
+            "Shape validation failed with 2 violation(s):\n  $.a: expected string, got number\n  $.b: missing required property 'c'|2"
+        )
+    );
+}
+
+#[test]
+fn test_check_shape_deep_document() {
+    // Deep nesting is walked iteratively
+    assert_eq!(
+        eval(
+            r#"
+            import { checkShape } from "tsrun:assert";
+            let doc: any = { value: "leaf" };
+            let shape: any = { type: "object", properties: { value: { type: "number" } } };
+            for (let i = 0; i < 1000; i++) {
+                doc = { child: doc };
+                shape = { type: "object", properties: { child: shape } };
+            }
+            const violations = checkShape(doc, shape);
+            violations.length + ":" + violations[0].path.endsWith(".child.value")
+        "#
+        ),
+        JsValue::from("1:true")
+    );
+}
+
+/// Delegates to the default provider while counting compiled patterns
+struct CountingProvider {
+    compiled: Rc<Cell<usize>>,
+}
+
+impl RegExpProvider for CountingProvider {
+    fn compile(&self, pattern: &str, flags: &str) -> Result<Rc<dyn CompiledRegex>, String> {
+        self.compiled.set(self.compiled.get() + 1);
+        FancyRegexProvider::new().compile(pattern, flags)
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_shape_pattern_uses_regexp_provider() {
+    let compiled = Rc::new(Cell::new(0));
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        regexp_provider: Some(Rc::new(CountingProvider {
+            compiled: compiled.clone(),
+        })),
+        ..Default::default()
+    });
+
+    let result = run(
+        &mut interp,
+        r#"
+        import { checkShape } from "tsrun:assert";
+        const shape = { type: "array", items: { type: "string", pattern: "^[a-z]+$" } };
+        checkShape(["web", "Worker", "db", "cache-1"], shape).map((v: any) => v.path).join(",")
+    "#,
+        None,
+    )
+    .unwrap();
+
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(*value, JsValue::from("$[1],$[3]"));
+    // The pattern is compiled once per validation, not once per element
+    assert_eq!(compiled.get(), 1);
+}
@@ -1212,6 +1212,144 @@ fn test_async_generator_throw_method() {
     assert_eq!(result, JsValue::Number(100.0));
 }
 
+// ═══════════════════════════════════════════════════════════════════════════
+// Request queue: next/throw/return issued while the body awaits
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_async_generator_queues_concurrent_next() {
+    // The body awaits a pending promise; both next() calls must wait for it
+    let result = eval(
+        r#"
+        let release: (v: number) => void = () => {};
+        const gate = new Promise<number>(resolve => { release = resolve; });
+        const steps: string[] = [];
+        async function* gen(): AsyncGenerator<number> {
+            steps.push("start");
+            const base = await gate;
+            yield base;
+            yield base + 1;
+        }
+        const g = gen();
+        const p1 = g.next();
+        const p2 = g.next();
+        steps.push("issued");
+        release(5);
+        const r1 = await p1;
+        const r2 = await p2;
+        const r3 = await g.next();
+        [steps.join(), r1.value, r2.value, r3.done].join("|")
+    "#,
+    );
+    assert_eq!(result, JsValue::from("start,issued|5|6|true"));
+}
+
+#[test]
+fn test_async_generator_requests_settle_in_order() {
+    let result = eval(
+        r#"
+        let release: () => void = () => {};
+        const gate = new Promise<void>(resolve => { release = resolve; });
+        async function* gen(): AsyncGenerator<string> {
+            await gate;
+            yield "a";
+            yield "b";
+        }
+        const g = gen();
+        const settled: string[] = [];
+        const all = [g.next(), g.next(), g.next()].map((p, i) =>
+            p.then(r => { settled.push(i + ":" + r.value + ":" + r.done); }));
+        release();
+        await Promise.all(all);
+        settled.join(" ")
+    "#,
+    );
+    assert_eq!(
+        result,
+        JsValue::from("0:a:false 1:b:false 2:undefined:true")
+    );
+}
+
+#[test]
+fn test_async_generator_return_runs_finally() {
+    let result = eval(
+        r#"
+        const log: string[] = [];
+        async function* gen(): AsyncGenerator<number> {
+            try {
+                yield 1;
+                yield 2;
+            } finally {
+                await Promise.resolve();
+                log.push("cleanup");
+            }
+        }
+        const g = gen();
+        await g.next();
+        const r = await g.return(42);
+        const after = await g.next();
+        [r.value, r.done, after.done, log.join()].join(",")
+    "#,
+    );
+    assert_eq!(result, JsValue::from("42,true,true,cleanup"));
+}
+
+#[test]
+fn test_async_generator_return_queued_behind_pending_next() {
+    let result = eval(
+        r#"
+        let release: () => void = () => {};
+        const gate = new Promise<void>(resolve => { release = resolve; });
+        const log: string[] = [];
+        async function* gen(): AsyncGenerator<number> {
+            try {
+                await gate;
+                yield 1;
+                yield 2;
+            } finally {
+                log.push("cleanup");
+            }
+        }
+        const g = gen();
+        const next = g.next();
+        const ret = g.return(9);
+        log.push("queued");
+        release();
+        const r1 = await next;
+        const r2 = await ret;
+        [r1.value, r1.done, r2.value, r2.done, log.join()].join(",")
+    "#,
+    );
+    assert_eq!(result, JsValue::from("1,false,9,true,queued,cleanup"));
+}
+
+#[test]
+fn test_async_generator_rejected_await_rejects_request() {
+    let result = eval(
+        r#"
+        let fail: (e: Error) => void = () => {};
+        const gate = new Promise<void>((_, reject) => { fail = reject; });
+        async function* gen(): AsyncGenerator<number> {
+            await gate;
+            yield 1;
+        }
+        const g = gen();
+        const p1 = g.next();
+        const p2 = g.next();
+        fail(new Error("broken"));
+        let message = "";
+        try {
+            await p1;
+        } catch (e) {
+            message = (e as Error).message;
+        }
+        const r2 = await p2;
+        message + "," + r2.done
+    "#,
+    );
+    assert_eq!(result, JsValue::from("broken,true"));
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // Top-level for await
 // ═══════════════════════════════════════════════════════════════════════════
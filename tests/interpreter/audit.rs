@@ -0,0 +1,134 @@
+//! Tests for the access audit log
+
+use super::run;
+use tsrun::{
+    AuditEvent, AuditEventKind, Guarded, InternalModule, Interpreter, InterpreterConfig, JsError,
+    JsValue, StepResult,
+};
+
+fn exec_fn(
+    _interp: &mut Interpreter,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    Ok(Guarded::unguarded(
+        args.first().cloned().unwrap_or(JsValue::Undefined),
+    ))
+}
+
+fn create_audit_interp() -> Interpreter {
+    let config = InterpreterConfig {
+        internal_modules: vec![
+            InternalModule::native("tsrun:shell")
+                .with_function("exec", exec_fn, 1)
+                .with_function("spawn", exec_fn, 1)
+                .build(),
+        ],
+        ..Default::default()
+    };
+    let interp = Interpreter::with_config(config);
+
+    // Set aggressive GC for testing
+    let gc_threshold = std::env::var("GC_THRESHOLD")
+        .ok()
+        .and_then(|s| s.parse::<usize>().ok())
+        .unwrap_or(1);
+    interp.set_gc_threshold(gc_threshold);
+
+    interp
+}
+
+fn event(kind: AuditEventKind, name: &str, module: Option<&str>, count: usize) -> AuditEvent {
+    AuditEvent {
+        kind,
+        name: name.to_string(),
+        module: module.map(String::from),
+        count,
+    }
+}
+
+#[test]
+fn test_audit_globals_modules_and_native_calls() {
+    let mut interp = create_audit_interp();
+    interp.enable_audit();
+
+    let result = run(
+        &mut interp,
+        r#"
+        import { exec } from "tsrun:shell";
+        const r = Math.random();
+        exec("ls");
+        exec("pwd");
+        r < 1;
+    "#,
+        None,
+    )
+    .unwrap();
+    assert!(matches!(result, StepResult::Complete(_)));
+
+    assert_eq!(
+        interp.take_audit_log(),
+        vec![
+            event(AuditEventKind::ModuleAccess, "exec", Some("tsrun:shell"), 1),
+            event(AuditEventKind::GlobalRead, "Math", None, 1),
+            event(AuditEventKind::NativeCall, "random", None, 1),
+            event(AuditEventKind::NativeCall, "exec", Some("tsrun:shell"), 2),
+        ]
+    );
+}
+
+#[test]
+fn test_audit_global_this_property_reads() {
+    let mut interp = create_audit_interp();
+    interp.enable_audit();
+
+    run(
+        &mut interp,
+        r#"
+        const F = globalThis.Function;
+        typeof F;
+    "#,
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(
+        interp.take_audit_log(),
+        vec![
+            event(AuditEventKind::GlobalRead, "globalThis", None, 1),
+            event(AuditEventKind::GlobalRead, "Function", None, 1),
+        ]
+    );
+}
+
+#[test]
+fn test_audit_namespace_import_and_take_resets() {
+    let mut interp = create_audit_interp();
+    interp.enable_audit();
+
+    run(
+        &mut interp,
+        r#"
+        import * as shell from "tsrun:shell";
+        shell.spawn("a");
+    "#,
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(
+        interp.take_audit_log(),
+        vec![
+            event(AuditEventKind::ModuleAccess, "*", Some("tsrun:shell"), 1),
+            event(AuditEventKind::NativeCall, "spawn", Some("tsrun:shell"), 1),
+        ]
+    );
+    assert!(interp.take_audit_log().is_empty());
+}
+
+#[test]
+fn test_audit_disabled_by_default() {
+    let mut interp = create_audit_interp();
+    run(&mut interp, "Math.max(1, 2)", None).unwrap();
+    assert!(interp.take_audit_log().is_empty());
+}
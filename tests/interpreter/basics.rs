@@ -106,6 +106,146 @@ fn test_comparison() {
     );
 }
 
+#[test]
+fn test_relational_comparison_table() {
+    let cases: &[(&str, bool)] = &[
+        // Numbers, including NaN, infinities and signed zeros
+        ("1 < 2", true),
+        ("2 < 1", false),
+        ("1 <= 1", true),
+        ("1 >= 1", true),
+        ("1 > 1", false),
+        ("NaN < 1", false),
+        ("NaN > 1", false),
+        ("NaN <= NaN", false),
+        ("NaN >= NaN", false),
+        ("1 <= NaN", false),
+        ("-0 < 0", false),
+        ("-0 <= 0", true),
+        ("0 >= -0", true),
+        ("-Infinity < Infinity", true),
+        ("Infinity <= Infinity", true),
+        ("Infinity > Number.MAX_VALUE", true),
+        // Strings compare lexicographically
+        ("'a' < 'b'", true),
+        ("'b' < 'a'", false),
+        ("'a' < 'ab'", true),
+        ("'ab' <= 'ab'", true),
+        ("'' < 'a'", true),
+        ("'B' < 'a'", true),
+        ("'10' < '9'", true),
+        ("'10' > '9'", false),
+        ("'abc' >= 'abd'", false),
+        // By UTF-16 code units: the surrogate 0xD83D sorts below 0xFF61
+        ("'😀' < '｡'", true),
+        ("'｡' < '😀'", false),
+        // Mixed string and number compare numerically
+        ("'10' < 9", false),
+        ("'10' > 9", true),
+        ("10 < '9'", false),
+        ("'' < 1", true),
+        ("' 2 ' < 3", true),
+        ("'0x10' > 15", true),
+        ("'abc' < 1", false),
+        ("'abc' >= 1", false),
+        // null and undefined
+        ("null >= 0", true),
+        ("null <= 0", true),
+        ("null > 0", false),
+        ("null < 0", false),
+        ("null == 0", false),
+        ("null < 1", true),
+        ("undefined < 1", false),
+        ("undefined >= 0", false),
+        ("undefined <= undefined", false),
+        ("null <= null", true),
+        ("undefined == null", true),
+        // Booleans
+        ("false < true", true),
+        ("true <= 1", true),
+        ("true > 0", true),
+        ("false >= '0'", true),
+        // Objects go through ToPrimitive with the number hint
+        ("[2] < 10", true),
+        ("[10] < [9]", true),
+        ("[10] < 9", false),
+        ("[] < 1", true),
+        ("[1, 2] < 3", false),
+        ("({}) < 1", false),
+        ("({ valueOf() { return 5 } }) > 4", true),
+        ("({ toString() { return 'b' } }) > 'a'", true),
+        ("new Date(0) < new Date(1)", true),
+        ("new Number(3) >= 3", true),
+        ("new String('a') < 'b'", true),
+        // Loose equality with strings uses full ToNumber
+        ("'' == 0", true),
+        ("' 12 ' == 12", true),
+        ("'0x10' == 16", true),
+    ];
+    for (source, expected) in cases {
+        assert_eq!(
+            eval(source),
+            JsValue::Boolean(*expected),
+            "{} should be {}",
+            source,
+            expected
+        );
+    }
+}
+
+#[test]
+fn test_relational_comparison_to_primitive_order() {
+    // Operands are converted left then right, even when the spec swaps them
+    assert_eq!(
+        eval(
+            r#"
+            const log: string[] = [];
+            const a = { valueOf() { log.push("a"); return 1; } };
+            const b = { valueOf() { log.push("b"); return 2; } };
+            [a < b, a > b, a <= b, a >= b, log.join("")].join(",")
+        "#
+        ),
+        JsValue::from("true,false,true,false,abababab")
+    );
+}
+
+#[test]
+fn test_relational_comparison_errors() {
+    assert!(throws_error("Symbol() < 1", "TypeError"));
+    assert!(throws_error("1 >= Symbol('x')", "TypeError"));
+    assert!(throws_error(
+        "({ valueOf() { throw new RangeError('boom') } }) < 1",
+        "boom"
+    ));
+    // A symbol still throws when the other side is NaN
+    assert!(throws_error("NaN < Symbol()", "TypeError"));
+}
+
+#[test]
+fn test_string_sort_uses_code_units() {
+    assert_eq!(
+        eval("['｡', '😀', 'a'].sort().join(',')"),
+        JsValue::from("a,😀,｡")
+    );
+}
+
+#[test]
+fn test_object_is_same_value() {
+    let cases: &[(&str, bool)] = &[
+        ("Object.is(NaN, NaN)", true),
+        ("Object.is(0, -0)", false),
+        ("Object.is(-0, -0)", true),
+        ("Object.is(1, 1)", true),
+        ("Object.is('a', 'a')", true),
+        ("Object.is(null, undefined)", false),
+        ("Object.is({}, {})", false),
+        ("const o = {}; Object.is(o, o)", true),
+    ];
+    for (source, expected) in cases {
+        assert_eq!(eval(source), JsValue::Boolean(*expected), "{}", source);
+    }
+}
+
 #[test]
 fn test_variables() {
     assert_eq!(eval("let x: number = 5; x"), JsValue::Number(5.0));
@@ -232,6 +372,69 @@ fn test_sequence_expression() {
     );
 }
 
+/// Operators evaluated as a statement, as a call argument and inside a
+/// template interpolation, each compared as a string
+#[test]
+fn test_unary_and_sequence_operators_in_each_position() {
+    let setup =
+        "let n: number = 0; const o: any = { a: { b: 1 }, c: 2 }; const u: any = undefined;";
+    let cases: &[(&str, &str)] = &[
+        ("typeof undeclaredVar", "undefined"),
+        ("typeof (undeclaredVar)", "undefined"),
+        ("typeof typeof undeclaredVar", "string"),
+        ("typeof window === 'undefined'", "true"),
+        ("typeof o.a", "object"),
+        ("void 0", "undefined"),
+        ("void (n = 5)", "undefined"),
+        ("(void n++, n)", "1"),
+        ("(1, 2, 3)", "3"),
+        ("(n++, n++, n)", "2"),
+        ("(o.c = 7, o.c * 2)", "14"),
+        ("delete o?.a.b", "true"),
+        ("(delete o?.a.b, 'b' in o.a)", "false"),
+        (
+            "(delete o.a?.['b'], JSON.stringify(o))",
+            "{\"a\":{},\"c\":2}",
+        ),
+        ("(delete (o.c), 'c' in o)", "false"),
+        ("delete u?.a", "true"),
+        ("delete u?.a.b.c", "true"),
+        ("delete u?.[n++]", "true"),
+        ("(delete u?.[n++], n)", "0"),
+    ];
+    for (expr, expected) in cases {
+        for program in [
+            format!("{} String({})", setup, expr),
+            format!("{} ((v: any) => String(v))({})", setup, expr),
+            format!("{} `${{{}}}`", setup, expr),
+        ] {
+            assert_eq!(
+                eval(&program),
+                JsValue::from(*expected),
+                "{} should be {}",
+                program,
+                expected
+            );
+        }
+    }
+}
+
+#[test]
+fn test_comma_in_for_loop_update() {
+    assert_eq!(
+        eval(
+            "const seen: string[] = []; \
+             for (let i = 0, j = 10; i < 3; i++, j--) seen.push(i + ':' + j); \
+             seen.join()"
+        ),
+        JsValue::from("0:10,1:9,2:8")
+    );
+    assert_eq!(
+        eval("let a = 0, b = 0; for (; a < 3; a++, b += 2) {} a + ',' + b"),
+        JsValue::from("3,6")
+    );
+}
+
 // BigInt literals (parsed and converted to Number for now)
 #[test]
 fn test_bigint_literal() {
@@ -747,6 +950,89 @@ fn test_default_param_not_evaluated_when_arg_provided() {
     );
 }
 
+#[test]
+fn test_default_param_chained_through_destructuring() {
+    assert_eq!(
+        eval(
+            r#"
+            function f(a: number, b: number = a * 2, { c = b }: { c?: number } = {}) {
+                return [a, b, c].join(",");
+            }
+            const arrow = (x: number, y = x + 1, [z = y * 2]: number[] = []) => `${x}${y}${z}`;
+            const obj = { m(p: string, q = p + "!") { return q; } };
+            [f(1), f(1, undefined, { c: 7 }), f(1, 5), arrow(1), obj.m("hi")].join("|")
+        "#
+        ),
+        JsValue::from("1,2,2|1,2,7|1,5,5|124|hi!")
+    );
+}
+
+#[test]
+fn test_default_param_later_reference_is_tdz_not_outer() {
+    // The later parameter shadows the outer binding while it is uninitialized
+    assert!(throws_error(
+        r#"
+        const b = 10;
+        function f(a = b, b = 1) { return a; }
+        f()
+        "#,
+        "Cannot access 'b' before initialization"
+    ));
+    assert!(throws_error(
+        "const g = ({ a = b, b }: any) => a; g({ b: 1 })",
+        "ReferenceError"
+    ));
+    assert_eq!(
+        eval("const b = 10; function f(a = b * 2, b = 1) { return a; } f(3)"),
+        JsValue::Number(3.0)
+    );
+}
+
+#[test]
+fn test_default_param_closure_captures_parameter() {
+    assert_eq!(
+        eval(
+            r#"
+            function f(a: number, get = () => a, c = get() + 1) {
+                a = 5;
+                return [get(), c, get.name].join(",");
+            }
+            class Point {
+                constructor(public x: number, public y: number = x + 1, ...rest: number[]) {
+                    this.rest = rest.length;
+                }
+                rest: number;
+            }
+            const p = new Point(1, undefined, 7, 8);
+            `${f(1)}|${p.x},${p.y},${p.rest}`
+        "#
+        ),
+        JsValue::from("5,2,get|1,2,2")
+    );
+}
+
+#[test]
+fn test_body_var_keeps_parameter_value() {
+    assert_eq!(
+        eval(
+            "function f(a = 1, b?: number) { var a; var b; return [a, b].join(); } f(undefined, 2)"
+        ),
+        JsValue::from("1,2")
+    );
+}
+
+#[test]
+fn test_let_destructuring_defaults_and_tdz() {
+    assert_eq!(
+        eval("let [p = 1, q = p + 1] = []; const { r = q * 2 }: any = {}; `${p}${q}${r}`"),
+        JsValue::from("124")
+    );
+    assert!(throws_error(
+        "const x = 1; { let [a = x, x] = []; }",
+        "Cannot access 'x' before initialization"
+    ));
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // Var Hoisting Tests
 // ═══════════════════════════════════════════════════════════════════════════
@@ -1043,6 +1329,190 @@ fn test_var_hoisting_labeled_statement() {
     );
 }
 
+#[test]
+fn test_var_hoisting_in_catch_and_for_head_of_function() {
+    // vars in a catch block or a for-loop head belong to the function scope
+    assert_eq!(
+        eval(
+            r#"
+            function f(): string {
+                const before = [typeof a, typeof i, typeof k].join();
+                try {
+                    throw new Error("x");
+                } catch (e) {
+                    var a: number = 1;
+                }
+                for (var i: number = 0; i < 2; i++) {}
+                for (var k in { p: 1 }) {}
+                return before + "|" + [a, i, k].join();
+            }
+            f()
+            "#
+        ),
+        JsValue::from("undefined,undefined,undefined|1,2,p")
+    );
+}
+
+#[test]
+fn test_var_in_catch_assigns_catch_parameter() {
+    // `var e` inside `catch (e)` writes the parameter; the var stays undefined
+    assert_eq!(
+        eval(
+            r#"
+            function f(): string {
+                const seen: string[] = [];
+                try {
+                    throw "thrown";
+                } catch (e) {
+                    var e: any = "assigned";
+                    seen.push(e);
+                }
+                seen.push(String(e));
+                return seen.join();
+            }
+            f()
+            "#
+        ),
+        JsValue::from("assigned,undefined")
+    );
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Function Hoisting Tests
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_function_called_before_declaration() {
+    assert_eq!(
+        eval(
+            r#"
+            function outer(): number {
+                return inner() * 2;
+                function inner(): number { return 21; }
+            }
+            const early = [typeof later, outer()].join();
+            function later() {}
+            early
+            "#
+        ),
+        JsValue::from("function,42")
+    );
+}
+
+#[test]
+fn test_function_hoisted_within_block() {
+    assert_eq!(
+        eval(
+            r#"
+            let result = "";
+            {
+                result = helper();
+                function helper(): string { return "from block"; }
+            }
+            result
+            "#
+        ),
+        JsValue::from("from block")
+    );
+}
+
+#[test]
+fn test_duplicate_function_declarations_last_wins() {
+    assert_eq!(
+        eval(
+            r#"
+            const early = pick();
+            function pick(): string { return "first"; }
+            function pick(): string { return "second"; }
+            early + "," + pick()
+            "#
+        ),
+        JsValue::from("second,second")
+    );
+}
+
+#[test]
+fn test_block_function_is_block_scoped_in_module_code() {
+    assert_eq!(
+        eval(
+            r#"
+            export {};
+            const seen: string[] = [];
+            {
+                seen.push(typeof local);
+                function local() {}
+            }
+            seen.push(typeof local);
+            function f(): string {
+                "use strict";
+                { function g() {} }
+                return typeof g;
+            }
+            seen.push(f());
+            seen.join()
+            "#
+        ),
+        JsValue::from("function,undefined,undefined")
+    );
+}
+
+#[test]
+fn test_block_function_in_sloppy_script_gets_var_binding() {
+    // Annex B.3.3: the var is undefined until the declaration is evaluated
+    assert_eq!(
+        eval(
+            r#"
+            const seen: string[] = [typeof g];
+            {
+                function g() { return "block"; }
+            }
+            seen.push(typeof g);
+            function shadowed(): string {
+                let h = 1;
+                { function h() {} }
+                return typeof h;
+            }
+            seen.push(shadowed());
+            function replaced() { return "outer"; }
+            { function replaced() { return "inner"; } }
+            seen.push(replaced());
+            seen.join()
+            "#
+        ),
+        JsValue::from("undefined,function,number,inner")
+    );
+}
+
+#[test]
+fn test_hoisted_functions_in_generator_and_async_bodies() {
+    assert_eq!(
+        eval(
+            r#"
+            function* gen(): Generator<string> {
+                yield typeof step;
+                {
+                    yield step();
+                    function step(): string { return "step"; }
+                }
+                yield typeof step;
+            }
+            async function run(): Promise<string> {
+                await null;
+                const value = late();
+                function late(): string { return "late"; }
+                return value;
+            }
+            let out = "";
+            run().then((value) => { out = [...gen()].join() + "," + value; });
+            await null;
+            await null;
+            out
+            "#
+        ),
+        JsValue::from("undefined,step,function,late")
+    );
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // Bitwise operations
 // ═══════════════════════════════════════════════════════════════════════════
@@ -1059,6 +1529,88 @@ fn test_bitwise_operations() {
     assert_eq!(eval("-20 >>> 2"), JsValue::Number(1073741819.0));
 }
 
+#[test]
+fn test_bitwise_int32_coercion() {
+    // Operands go through ToInt32/ToUint32: wrap modulo 2^32, non-finite is 0
+    let cases: &[(&str, f64)] = &[
+        ("0.5 | 0", 0.0),
+        ("-0.5 | 0", 0.0),
+        ("-0 | 0", 0.0),
+        ("1.9 | 0", 1.0),
+        ("-1.9 | 0", -1.0),
+        ("NaN | 0", 0.0),
+        ("Infinity | 0", 0.0),
+        ("-Infinity | 0", 0.0),
+        ("2147483647 | 0", 2147483647.0),
+        ("2147483648 | 0", -2147483648.0),
+        ("-2147483649 | 0", 2147483647.0),
+        ("4294967295 | 0", -1.0),
+        ("4294967296 | 0", 0.0),
+        ("4294967297 | 0", 1.0),
+        ("2 ** 53 | 0", 0.0),
+        ("(2 ** 53 + 2) | 0", 2.0),
+        ("1e21 | 0", -559939584.0),
+        ("-1e21 | 0", 559939584.0),
+        ("'12.7' | 0", 12.0),
+        ("'0x10' | 0", 16.0),
+        ("'abc' | 0", 0.0),
+        ("null | 0", 0.0),
+        ("undefined | 0", 0.0),
+        ("true | 0", 1.0),
+        ("({ valueOf() { return 7.5; } }) | 0", 7.0),
+        ("[5] | 0", 5.0),
+        ("4294967295 & 255", 255.0),
+        ("2 ** 32 + 5 & 7", 5.0),
+        ("-1 & 2 ** 31", -2147483648.0),
+        ("2 ** 31 ^ 1", -2147483647.0),
+        ("({ valueOf() { return 6; } }) ^ 3", 5.0),
+        ("~2147483648", 2147483647.0),
+        ("~4294967295", 0.0),
+        ("~NaN", -1.0),
+        ("~-0.5", -1.0),
+        ("~({ valueOf() { return 1; } })", -2.0),
+        ("1 << 31", -2147483648.0),
+        ("1 << 32", 1.0),
+        ("1 << 33", 2.0),
+        ("1 << -1", -2147483648.0),
+        ("1 << 4294967297", 2.0),
+        ("2147483648 << 1", 0.0),
+        ("4294967295 << 1", -2.0),
+        ("1 << NaN", 1.0),
+        ("-1 >> 31", -1.0),
+        ("2147483648 >> 1", -1073741824.0),
+        ("4294967296 >> 0", 0.0),
+        ("16 >> 36", 1.0),
+        ("-1 >>> 0", 4294967295.0),
+        ("-1.5 >>> 0", 4294967295.0),
+        ("4294967296 >>> 0", 0.0),
+        ("2 ** 53 >>> 0", 0.0),
+        ("-4294967297 >>> 0", 4294967295.0),
+        ("1e21 >>> 0", 3735027712.0),
+        ("-1 >>> 32", 4294967295.0),
+        ("-1 >>> -1", 1.0),
+        ("Infinity >>> 0", 0.0),
+        ("({ valueOf() { return -2; } }) >>> 30", 3.0),
+        ("let x = 2 ** 32 + 3; x |= 0; x", 3.0),
+        ("let x = -1; x >>>= 1; x", 2147483647.0),
+    ];
+    for (expr, expected) in cases {
+        let value = eval(expr);
+        let JsValue::Number(actual) = *value else {
+            panic!("{} returned {:?}, expected a number", expr, *value);
+        };
+        // Bit comparison so a -0 result is caught
+        assert_eq!(
+            actual.to_bits(),
+            expected.to_bits(),
+            "{} returned {:?}, expected {:?}",
+            expr,
+            actual,
+            expected
+        );
+    }
+}
+
 #[test]
 fn test_array_function_call() {
     // Test storing function via push
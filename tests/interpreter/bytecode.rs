@@ -3231,3 +3231,79 @@ fn test_bytecode_args_not_evaluated_when_callee_throws() {
     );
     assert_eq!(result, JsValue::Boolean(false));
 }
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Register Reuse
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_bytecode_long_chained_expression() {
+    // 2000 terms, far more than the 255 registers a frame can have
+    let terms = (0..2000)
+        .map(|i| format!("(x > {i} ? {i} : -{i})"))
+        .collect::<Vec<_>>()
+        .join(" + ");
+    let result = eval_bytecode(&format!("const x = 7; {}", terms));
+    assert_eq!(
+        result,
+        JsValue::Number(21.0 - (7..2000).sum::<i32>() as f64)
+    );
+}
+
+#[test]
+fn test_bytecode_long_chained_expression_in_generator() {
+    let terms = (0..1000)
+        .map(|i| format!("(n > {i} ? 1 : 0)"))
+        .collect::<Vec<_>>()
+        .join(" + ");
+    let result = eval_bytecode(&format!(
+        "function* g(n: number) {{ const a = {terms}; const b = (yield a) + {terms}; return b; }}
+        const it = g(500);
+        const first = it.next().value;
+        [first, it.next(first).value].join()"
+    ));
+    assert_eq!(result, JsValue::String("500,1000".into()));
+}
+
+/// Run `test` on a thread whose stack fits compiling deeply nested
+/// expressions in debug builds
+#[allow(clippy::unwrap_used)]
+fn with_large_stack(test: impl FnOnce() + Send + 'static) {
+    std::thread::Builder::new()
+        .stack_size(256 * 1024 * 1024)
+        .spawn(test)
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn test_bytecode_deeply_nested_operands_spill() {
+    // Each level holds its left operand until the right one is done, so
+    // past the register limit the left operands move out of registers
+    with_large_stack(|| {
+        let mut nested = "x".to_string();
+        for i in 0..400 {
+            nested = format!("({i} * x - {nested})");
+        }
+        let mut expected = 1.0;
+        for i in 0..400 {
+            expected = f64::from(i) - expected;
+        }
+        let source = format!(
+            "const x = 1;
+            function* g() {{ return (yield 0) + {nested}; }}
+            const it = g(); it.next();
+            [{nested}, it.next(1).value].join()"
+        );
+        assert_eq!(
+            eval_bytecode(&source),
+            JsValue::String(format!("{},{}", expected, expected + 1.0).into())
+        );
+
+        let thrown = format!(
+            "const x = 1; let caught = 0; try {{ {nested} + (() => {{ throw 1; }})(); }} catch {{ caught = x + {nested}; }} caught"
+        );
+        assert_eq!(eval_bytecode(&thrown), JsValue::Number(1.0 + expected));
+    });
+}
@@ -0,0 +1,141 @@
+//! Tests for checkpointing runs suspended on an order and restoring them
+
+use super::run_to_completion;
+use serde_json::json;
+use tsrun::{
+    CheckpointError, Interpreter, InterpreterConfig, JsValue, ModulePath, ModuleSources,
+    OrderResponse, StepResult, api, create_eval_internal_module,
+};
+
+const MAIN: &str = r#"
+import { order } from "tsrun:host";
+import { Counter, label } from "./util";
+
+const seen = new Map<string, number>();
+
+async function bump(counter: Counter): Promise<number> {
+    const response: any = await order({ kind: "bump" });
+    counter.add(response.by);
+    seen.set("after", counter.value);
+    return counter.value;
+}
+
+const counter = new Counter(10);
+seen.set("before", counter.value);
+const value = await bump(counter);
+`${label}:${value}:${seen.get("before")}:${seen.get("after")}`
+"#;
+
+const UTIL: &str = r#"
+export const label = "counter";
+
+export class Counter {
+    #count: number;
+    constructor(start: number) {
+        this.#count = start;
+    }
+    add(by: number) {
+        this.#count += by;
+    }
+    get value(): number {
+        return this.#count;
+    }
+}
+"#;
+
+fn config() -> InterpreterConfig {
+    InterpreterConfig {
+        internal_modules: vec![create_eval_internal_module()],
+        ..Default::default()
+    }
+}
+
+fn sources() -> ModuleSources {
+    ModuleSources::new(MAIN).with_module(ModulePath::new("/app/util"), UTIL)
+}
+
+/// Run MAIN until it suspends on its order
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn run_until_order() -> Interpreter {
+    let mut interp = Interpreter::with_config(config());
+    interp
+        .prepare(MAIN, Some(ModulePath::new("/app/main.ts")))
+        .unwrap();
+    loop {
+        match run_to_completion(&mut interp).unwrap() {
+            StepResult::NeedImports(imports) => {
+                for import in imports {
+                    interp.provide_module(import.resolved_path, UTIL).unwrap();
+                }
+            }
+            StepResult::Suspended { pending, .. } => {
+                assert_eq!(pending.len(), 1);
+                return interp;
+            }
+            other => panic!("Expected an order, got {:?}", other),
+        }
+    }
+}
+
+/// Fulfill the outstanding order and return the completed value
+#[allow(clippy::unwrap_used, clippy::panic, clippy::indexing_slicing)]
+fn finish(interp: &mut Interpreter) -> String {
+    let ids: Vec<_> = interp.outstanding_orders();
+    assert_eq!(ids.len(), 1);
+    let response = api::create_response_object(interp, &json!({ "by": 5 })).unwrap();
+    interp.fulfill_orders(vec![OrderResponse {
+        id: ids[0],
+        result: Ok(response),
+    }]);
+    match run_to_completion(interp).unwrap() {
+        StepResult::Complete(value) => match value.value() {
+            JsValue::String(s) => s.to_string(),
+            other => panic!("Expected a string, got {:?}", other),
+        },
+        other => panic!("Expected completion, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_checkpoint_restore_resumes_run() {
+    let expected = finish(&mut run_until_order());
+    assert_eq!(expected, "counter:15:10:15");
+
+    let interp = run_until_order();
+    let bytes = interp.checkpoint().unwrap();
+    let mut restored = Interpreter::restore_with_config(config(), &bytes, &sources()).unwrap();
+    assert_eq!(restored.outstanding_orders(), interp.outstanding_orders());
+
+    assert_eq!(finish(&mut restored), expected);
+}
+
+#[test]
+fn test_checkpoint_requires_suspended_order() {
+    let interp = Interpreter::with_config(config());
+    assert!(matches!(
+        interp.checkpoint(),
+        Err(CheckpointError::InvalidState(_))
+    ));
+}
+
+#[test]
+fn test_restore_rejects_changed_source() {
+    let bytes = run_until_order().checkpoint().unwrap();
+    let changed = ModuleSources::new(MAIN).with_module(
+        ModulePath::new("/app/util"),
+        UTIL.replace("this.#count += by", "this.#count += by * 2"),
+    );
+    match Interpreter::restore_with_config(config(), &bytes, &changed) {
+        Err(CheckpointError::SourceMismatch { path }) => assert_eq!(path, "/app/util"),
+        other => panic!("Expected a source mismatch, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn test_restore_rejects_corrupt_bytes() {
+    let bytes = run_until_order().checkpoint().unwrap();
+    assert!(matches!(
+        Interpreter::restore_with_config(config(), &bytes[..bytes.len() / 2], &sources()),
+        Err(CheckpointError::Corrupt(_))
+    ));
+}
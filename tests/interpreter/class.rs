@@ -1305,8 +1305,6 @@ fn test_class_method_not_enumerable() {
 #[test]
 fn test_class_static_method_not_enumerable() {
     // Static methods should not be enumerable
-    // Note: 'length' and 'name' are function properties that are currently enumerable
-    // (which is a separate issue), so we filter those out too
     assert_eq!(
         eval(
             r#"
@@ -1329,3 +1327,93 @@ fn test_class_static_method_not_enumerable() {
         JsValue::Number(0.0) // foo and bar should not be enumerable
     );
 }
+
+#[test]
+fn test_class_member_descriptors() {
+    assert_eq!(
+        eval(
+            r#"
+            class Base { greet() { return "hi"; } }
+            class C extends Base {
+                field = 1;
+                static count = 2;
+                method() {}
+                get value() { return 1; }
+                set value(v) {}
+                static make() {}
+                static get shared() { return 2; }
+                ["computed"]() {}
+                get ["dyn"]() { return 3; }
+            }
+            const describe = (obj, key) => {
+                const d = Object.getOwnPropertyDescriptor(obj, key);
+                const flags = [d.enumerable, d.configurable];
+                if ("writable" in d) flags.push(d.writable);
+                return key + ":" + flags.map(f => (f ? 1 : 0)).join("");
+            };
+            [
+                describe(C.prototype, "method"),
+                describe(C.prototype, "value"),
+                describe(C.prototype, "computed"),
+                describe(C.prototype, "dyn"),
+                describe(C.prototype, "constructor"),
+                describe(C, "make"),
+                describe(C, "shared"),
+                describe(C, "count"),
+                describe(C, "prototype"),
+                describe(C, "name"),
+                describe(C, "length"),
+                describe(new C(), "field"),
+            ].join(" ")
+        "#
+        ),
+        JsValue::from(
+            "method:011 value:01 computed:011 dyn:01 constructor:011 make:011 shared:01 \
+             count:111 prototype:000 name:010 length:010 field:111"
+        )
+    );
+}
+
+#[test]
+fn test_class_keys_list_only_fields() {
+    assert_eq!(
+        eval(
+            r#"
+            class Base { x = 1; base() {} get total() { return 0; } }
+            class Point extends Base {
+                y = 2;
+                move() { return super.base(); }
+                get norm() { return 0; }
+                static origin() {}
+            }
+            const p = new Point();
+            const seen = [];
+            for (const key in p) seen.push(key);
+            [
+                JSON.stringify(p),
+                seen.join(),
+                Object.keys(Point.prototype).length,
+                Object.keys(Point).length,
+                Object.keys(p.move).length,
+                Object.keys({ ...p }).join(),
+                Object.keys(Object.assign({}, p)).join(),
+            ].join("|")
+        "#
+        ),
+        JsValue::from(r#"{"x":1,"y":2}|x,y|0|0|0|x,y|x,y"#)
+    );
+}
+
+#[test]
+fn test_object_literal_accessors_stay_enumerable() {
+    assert_eq!(
+        eval(
+            r#"
+            const key = "dyn";
+            const o = { get a() { return 1; }, set b(v) {}, get [key]() { return 2; } };
+            Object.keys(o).join()
+        "#
+        ),
+        JsValue::from("a,b,dyn")
+    );
+}
@@ -0,0 +1,276 @@
+//! Loose equality, truthiness and ToNumber conformance
+//!
+//! Expected values follow the ECMAScript tables (IsLooselyEqual, ToBoolean,
+//! StringToNumber), cross-checked against V8.
+
+use super::{eval, throws_error};
+use tsrun::JsValue;
+
+/// `==` cases from the IsLooselyEqual table
+const LOOSE_EQUALS: &[(&str, bool)] = &[
+    (r#"undefined == null"#, true),
+    (r#"null == undefined"#, true),
+    (r#"undefined == undefined"#, true),
+    (r#"null == null"#, true),
+    (r#"undefined == 0"#, false),
+    (r#"null == 0"#, false),
+    (r#"undefined == """#, false),
+    (r#"null == """#, false),
+    (r#"undefined == false"#, false),
+    (r#"null == false"#, false),
+    (r#"undefined == NaN"#, false),
+    (r#"null == []"#, false),
+    (r#"undefined == {}"#, false),
+    (r#"NaN == NaN"#, false),
+    (r#"0 == -0"#, true),
+    (r#"1 == 1"#, true),
+    (r#"0 == """#, true),
+    (r#"0 == " \n""#, true),
+    (r#"0 == "0""#, true),
+    (r#"1 == "1""#, true),
+    (r#"1 == " 1 ""#, true),
+    (r#"1 == "1.0""#, true),
+    (r#"16 == "0x10""#, true),
+    (r#"0 == "abc""#, false),
+    (r#"NaN == "abc""#, false),
+    (r#"Infinity == "Infinity""#, true),
+    (r#""" == "0""#, false),
+    (r#""1" == "1.0""#, false),
+    (r#""abc" == "abc""#, true),
+    (r#"true == 1"#, true),
+    (r#"true == "1""#, true),
+    (r#"true == 2"#, false),
+    (r#"true == "true""#, false),
+    (r#"false == 0"#, true),
+    (r#"false == """#, true),
+    (r#"false == " \n""#, true),
+    (r#"false == "0""#, true),
+    (r#"false == "false""#, false),
+    (r#"true == true"#, true),
+    (r#"false == NaN"#, false),
+    (r#"[] == false"#, true),
+    (r#"[] == true"#, false),
+    (r#"[0] == false"#, true),
+    (r#"[1] == true"#, true),
+    (r#"[2] == true"#, false),
+    (r#"["1"] == true"#, true),
+    (r#"[[]] == false"#, true),
+    (r#"[null] == false"#, true),
+    (r#"[undefined] == false"#, true),
+    (r#"[1,2] == false"#, false),
+    (r#"[0] == true"#, false),
+    (r#"[] == 0"#, true),
+    (r#"[] == """#, true),
+    (r#"[0] == 0"#, true),
+    (r#"[0] == """#, false),
+    (r#"[1] == 1"#, true),
+    (r#"["1"] == 1"#, true),
+    (r#"[1,2] == "1,2""#, true),
+    (r#"[[]] == 0"#, true),
+    (r#"[[1]] == 1"#, true),
+    (r#"[] == "0""#, false),
+    (r#"[] == []"#, false),
+    (r#"[] == ![]"#, true),
+    (r#"({ a: 1 }) == "[object Object]""#, true),
+    (r#"({}) == false"#, false),
+    (r#"({}) == true"#, false),
+    (r#"({}) == ({})"#, false),
+    (r#"new Boolean(false) == false"#, true),
+    (r#"new Boolean(false) == true"#, false),
+    (r#"new Boolean(true) == 1"#, true),
+    (r#"new Number(0) == 0"#, true),
+    (r#"new Number(0) == false"#, true),
+    (r#"new Number(1) == "1""#, true),
+    (r#"new String("") == """#, true),
+    (r#"new String("") == false"#, true),
+    (r#"new String("a") == new String("a")"#, false),
+    (r#"({ valueOf() { return 1; } }) == 1"#, true),
+    (r#"({ valueOf() { return 1; } }) == true"#, true),
+    (r#"({ valueOf() { return "2"; } }) == 2"#, true),
+    (r#"({ toString() { return "x"; } }) == "x""#, true),
+    (
+        r#"({ [Symbol.toPrimitive]() { return 0; } }) == false"#,
+        true,
+    ),
+    (r#"new Date(0) == new Date(0).toString()"#, true),
+    (r#"Symbol.iterator == Symbol.iterator"#, true),
+    (r#"Object(Symbol.iterator) == Symbol.iterator"#, true),
+    (r#"Symbol.iterator == "Symbol(Symbol.iterator)""#, false),
+    (r#"null == {}"#, false),
+    (r#"undefined == []"#, false),
+];
+
+/// `!=` is the negation of `==`
+const LOOSE_NOT_EQUALS: &[(&str, bool)] = &[
+    (r#"null != undefined"#, false),
+    (r#"null != 0"#, true),
+    (r#"undefined != false"#, true),
+    (r#""" != 0"#, false),
+    (r#"" \n" != 0"#, false),
+    (r#""abc" != NaN"#, true),
+    (r#"[] != false"#, false),
+    (r#"[0] != false"#, false),
+    (r#"[1] != false"#, true),
+    (r#"[] != []"#, true),
+    (r#"({}) != "[object Object]""#, false),
+    (r#"new Boolean(false) != false"#, false),
+    (r#"NaN != NaN"#, true),
+    (r#"0 != -0"#, false),
+    (r#""1" != 1"#, false),
+    (r#"true != "true""#, true),
+    (r#"false != "false""#, true),
+    (r#"[null] != """#, false),
+    (r#"1 != "1e0""#, false),
+    (r#"0 != "-0""#, false),
+];
+
+/// ToBoolean: only primitives can be falsy, wrapper objects are truthy
+const TRUTHINESS: &[(&str, bool)] = &[
+    (r#"!!undefined"#, false),
+    (r#"!!null"#, false),
+    (r#"!!0"#, false),
+    (r#"!!-0"#, false),
+    (r#"!!NaN"#, false),
+    (r#"!!"""#, false),
+    (r#"!!" ""#, true),
+    (r#"!!"0""#, true),
+    (r#"!!"false""#, true),
+    (r#"!!1"#, true),
+    (r#"!!-1"#, true),
+    (r#"!!Infinity"#, true),
+    (r#"!![]"#, true),
+    (r#"!!{}"#, true),
+    (r#"!![0]"#, true),
+    (r#"!!new Boolean(false)"#, true),
+    (r#"!!new Number(0)"#, true),
+    (r#"!!new String("")"#, true),
+    (r#"!!Object(false)"#, true),
+    (r#"!!Symbol()"#, true),
+    (r#"!!function () {}"#, true),
+    (r#"!!new Date(NaN)"#, true),
+];
+
+/// ToNumber through `Number()`, unary plus, arithmetic and builtins taking numbers
+const TO_NUMBER: &[(&str, f64)] = &[
+    (r#"Number("")"#, 0.0),
+    (r#"Number(" \n")"#, 0.0),
+    (r#"Number("\t12\t")"#, 12.0),
+    (r#"Number("abc")"#, f64::NAN),
+    (r#"Number("0x10")"#, 16.0),
+    (r#"Number("1e3")"#, 1000.0),
+    (r#"Number(" -5 ")"#, -5.0),
+    (r#"Number("1_000")"#, f64::NAN),
+    (r#"Number([])"#, 0.0),
+    (r#"Number([0])"#, 0.0),
+    (r#"Number(["7"])"#, 7.0),
+    (r#"Number([1, 2])"#, f64::NAN),
+    (r#"Number({ valueOf() { return 3; } })"#, 3.0),
+    (r#"Number(new Date(5))"#, 5.0),
+    (r#"Number(null)"#, 0.0),
+    (r#"Number(undefined)"#, f64::NAN),
+    (r#"Number(true)"#, 1.0),
+    (r#"+"""#, 0.0),
+    (r#"+" ""#, 0.0),
+    (r#"+[]"#, 0.0),
+    (r#"Math.abs(["-3"])"#, 3.0),
+    (r#"new Number([]).valueOf()"#, 0.0),
+    (r#""" - 1"#, -1.0),
+    (r#"" \n" * 2"#, 0.0),
+];
+
+/// Predicates that convert their argument with ToNumber
+const NUMBER_PREDICATES: &[(&str, bool)] = &[
+    (r#"isNaN([])"#, false),
+    (r#"isNaN("abc")"#, true),
+    (r#"isFinite(" 4 ")"#, true),
+];
+
+fn check_booleans(cases: &[(&str, bool)]) {
+    let failures: Vec<String> = cases
+        .iter()
+        .filter_map(|(source, expected)| {
+            let actual = eval(source);
+            (actual != JsValue::Boolean(*expected))
+                .then(|| format!("{}: expected {}, got {:?}", source, expected, actual))
+        })
+        .collect();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn test_loose_equality_table() {
+    check_booleans(LOOSE_EQUALS);
+}
+
+#[test]
+fn test_loose_inequality_table() {
+    check_booleans(LOOSE_NOT_EQUALS);
+}
+
+#[test]
+fn test_truthiness_table() {
+    check_booleans(TRUTHINESS);
+    assert_eq!(
+        eval(r#"let taken = "no"; if (new Boolean(false)) { taken = "yes"; } taken"#),
+        JsValue::from("yes")
+    );
+}
+
+#[test]
+fn test_to_number_table() {
+    let failures: Vec<String> = TO_NUMBER
+        .iter()
+        .filter_map(|(source, expected)| {
+            let actual = eval(source);
+            let matches = match *actual {
+                JsValue::Number(n) if expected.is_nan() => n.is_nan(),
+                JsValue::Number(n) => n == *expected,
+                _ => false,
+            };
+            (!matches).then(|| format!("{}: expected {}, got {:?}", source, expected, actual))
+        })
+        .collect();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+    check_booleans(NUMBER_PREDICATES);
+}
+
+#[test]
+fn test_loose_equality_propagates_to_primitive_errors() {
+    assert!(throws_error(
+        r#"({ valueOf() { throw new Error("no primitive"); } }) == 1"#,
+        "no primitive"
+    ));
+    assert!(throws_error(
+        r#"Number({ valueOf() { throw new Error("no number"); } })"#,
+        "no number"
+    ));
+    assert!(throws_error(r#"Math.floor(Symbol())"#, "Symbol"));
+}
+
+#[test]
+fn test_symbol_to_primitive_receives_hint() {
+    assert_eq!(
+        eval(
+            r#"
+            const hints: string[] = [];
+            const value = {
+                [Symbol.toPrimitive](hint: string) {
+                    hints.push(hint);
+                    return hint === "number" ? 7 : "seven";
+                },
+            };
+            const results = [+value, `${value}`, value + "", value == "seven", value * 2];
+            results.join(",") + "|" + hints.join(",")
+        "#
+        ),
+        JsValue::from("7,seven,seven,true,14|number,string,default,default,number")
+    );
+    assert!(throws_error(
+        r#"({ [Symbol.toPrimitive]() { return {}; } }) == 1"#,
+        "Cannot convert object to primitive value"
+    ));
+    assert!(throws_error(
+        r#"+({ [Symbol.toPrimitive]: 1 })"#,
+        "Symbol.toPrimitive is not a function"
+    ));
+}
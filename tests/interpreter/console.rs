@@ -1,7 +1,11 @@
 //! Console-related tests
 
-use super::eval;
-use tsrun::JsValue;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::{eval, run};
+use tsrun::platform::{ConsoleLevel, ConsoleProvider};
+use tsrun::{Interpreter, InterpreterConfig, JsValue};
 
 #[test]
 fn test_console_log() {
@@ -84,3 +88,93 @@ fn test_console_group_end() {
     // groupEnd returns undefined
     assert_eq!(eval("console.groupEnd()"), JsValue::Undefined);
 }
+
+// Output limits
+
+type ConsoleLines = Rc<RefCell<Vec<(ConsoleLevel, String)>>>;
+
+struct RecordingConsole(ConsoleLines);
+
+impl ConsoleProvider for RecordingConsole {
+    fn write(&self, level: ConsoleLevel, message: &str) {
+        self.0.borrow_mut().push((level, message.to_string()));
+    }
+}
+
+fn recording_interpreter(config: InterpreterConfig) -> (Interpreter, ConsoleLines) {
+    let lines = Rc::new(RefCell::new(Vec::new()));
+    let mut interp = Interpreter::with_config(config);
+    interp.set_console(Box::new(RecordingConsole(lines.clone())));
+    (interp, lines)
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_console_log_huge_array_is_bounded() {
+    let (mut interp, lines) = recording_interpreter(InterpreterConfig {
+        max_console_message_len: 2500,
+        ..Default::default()
+    });
+    run(
+        &mut interp,
+        "console.log(new Array(50).fill('y'.repeat(1000)))",
+        None,
+    )
+    .unwrap();
+
+    // Formatting stops after the third element spends the budget, so only
+    // the short "more items" tail is built past the limit and then cut
+    let lines = lines.borrow();
+    let (_, message) = lines.first().unwrap();
+    let y = "y".repeat(1000);
+    let expected = format!("[{}, {}, {}", y, y, "y".repeat(495));
+    assert_eq!(message, &format!("{}… (525 more characters)", expected));
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_console_output_limit_drops_and_counts_messages() {
+    let (mut interp, lines) = recording_interpreter(InterpreterConfig {
+        max_console_output: Some(20),
+        ..Default::default()
+    });
+    run(
+        &mut interp,
+        "for (let i = 0; i < 10; i++) console.log('msg ' + i);",
+        None,
+    )
+    .unwrap();
+
+    let lines = lines.borrow();
+    let messages: Vec<&str> = lines.iter().map(|(_, m)| m.as_str()).collect();
+    assert_eq!(
+        messages,
+        [
+            "msg 0",
+            "msg 1",
+            "msg 2",
+            "msg 3",
+            "console output limit reached"
+        ]
+    );
+    assert_eq!(interp.console_dropped_messages(), 6);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_console_log_shows_primitive_wrappers() {
+    let (mut interp, lines) = recording_interpreter(InterpreterConfig::default());
+    run(
+        &mut interp,
+        r#"console.log(new String("ab"), new Number(1e21), new Boolean(false), { s: new String("x") })"#,
+        None,
+    )
+    .unwrap();
+
+    let lines = lines.borrow();
+    let (_, message) = lines.first().unwrap();
+    assert_eq!(
+        message,
+        r#"[String: "ab"] [Number: 1e+21] [Boolean: false] { s: [String: "x"] }"#
+    );
+}
@@ -0,0 +1,307 @@
+//! Tests for folding imported constants (`InterpreterConfig::fold_constant_imports`)
+//!
+//! Every module graph runs with folding on and off and must produce the
+//! same output either way.
+
+use super::run_to_completion;
+use std::cell::RefCell;
+use std::rc::Rc;
+use tsrun::platform::{ConsoleLevel, ConsoleProvider};
+use tsrun::{Interpreter, InterpreterConfig, JsValue, ModulePath, StepResult};
+
+const CONFIG: &str = r#"
+export const limits = Object.freeze({
+    cpu: Object.freeze({ default: 250, max: 4000 }),
+    memory: Object.freeze({ default: "512Mi" }),
+    zones: Object.freeze(["a", "b", "c"]),
+});
+export const name = "payments";
+export const negZero = -0;
+export let replicas = 2;
+export function scale(n: number) { replicas = n; }
+export const mutable = { level: 1 };
+export const shallow = Object.freeze({ inner: { level: 1 } });
+let reads = 0;
+export const counted = Object.freeze({ get value() { return ++reads; } });
+export default Object.freeze({ region: "eu-west-1" });
+"#;
+
+/// Module graphs run with folding on and off: entry source and modules
+const CORPUS: &[(&str, &[(&str, &str)])] = &[
+    // Nested frozen constants read in a hot loop
+    (
+        r#"import { limits } from "./config.ts";
+        let total = 0;
+        for (let i = 0; i < 1000; i++) total += limits.cpu.default;
+        JSON.stringify([total, limits.cpu.max, limits.memory.default, limits.zones.length]);"#,
+        &[("/app/config.ts", CONFIG)],
+    ),
+    // Namespace and default imports
+    (
+        r#"import * as config from "./config.ts";
+        import defaults from "./config.ts";
+        JSON.stringify([config.limits.cpu.default, config.name, defaults.region, config.name.length]);"#,
+        &[("/app/config.ts", CONFIG)],
+    ),
+    // Named, renamed and star re-exports
+    (
+        r#"import { cpu, limits as l, name } from "./reexport.ts";
+        import * as all from "./star.ts";
+        JSON.stringify([cpu, l.cpu.max, name, all.limits.memory.default]);"#,
+        &[
+            ("/app/config.ts", CONFIG),
+            (
+                "/app/reexport.ts",
+                r#"import { limits } from "./config.ts";
+                export const cpu = limits.cpu.default;
+                export { limits, name } from "./config.ts";"#,
+            ),
+            ("/app/star.ts", r#"export { limits } from "./reexport.ts";"#),
+        ],
+    ),
+    // Live bindings and objects that change keep reading the current value
+    (
+        r#"import { replicas, scale, mutable, shallow } from "./config.ts";
+        const before = [replicas, mutable.level, shallow.inner.level];
+        scale(5);
+        mutable.level = 2;
+        shallow.inner.level = 3;
+        JSON.stringify([before, replicas, mutable.level, shallow.inner.level]);"#,
+        &[("/app/config.ts", CONFIG)],
+    ),
+    // Getters run on every read
+    (
+        r#"import { counted } from "./config.ts";
+        JSON.stringify([counted.value, counted.value, counted.value]);"#,
+        &[("/app/config.ts", CONFIG)],
+    ),
+    // Names declared again in the module read their own binding
+    (
+        r#"import { limits, name } from "./config.ts";
+        function pick(limits: any) { return limits.cpu.default; }
+        const inner = (() => { const name = "shadow"; return name; })();
+        JSON.stringify([pick({ cpu: { default: 1 } }), inner, name, limits.cpu.default]);"#,
+        &[("/app/config.ts", CONFIG)],
+    ),
+    // Values folding can't tell apart from others
+    (
+        r#"import { negZero, limits } from "./config.ts";
+        JSON.stringify([Object.is(negZero, -0), 1 / negZero, typeof limits.cpu.missing,
+            limits?.cpu.default, limits.cpu["default"], limits.cpu.default.toFixed(1)]);"#,
+        &[("/app/config.ts", CONFIG)],
+    ),
+    // Writes still throw
+    (
+        r#"import { limits } from "./config.ts";
+        import * as config from "./config.ts";
+        const errors = [];
+        try { limits.cpu.default = 1; } catch (e) { errors.push(e.constructor.name); }
+        try { limits.cpu.default++; } catch (e) { errors.push(e.constructor.name); }
+        try { (config as any).name = "x"; } catch (e) { errors.push(e.constructor.name); }
+        JSON.stringify([errors, limits.cpu.default, config.name]);"#,
+        &[("/app/config.ts", CONFIG)],
+    ),
+    // Imports read in functions, classes and generators
+    (
+        r#"import { limits, name } from "./config.ts";
+        class Service { cpu() { return limits.cpu.default; } }
+        function* zones() { for (let i = 0; i < limits.zones.length; i++) yield name + i; }
+        const arrow = () => limits.memory.default;
+        JSON.stringify([new Service().cpu(), [...zones()], arrow()]);"#,
+        &[("/app/config.ts", CONFIG)],
+    ),
+    // Renamed imports and a primitive default export
+    (
+        r#"import { name as service } from "./config.ts";
+        import port from "./port.ts";
+        JSON.stringify([service, port, `${service}:${port}`]);"#,
+        &[
+            ("/app/config.ts", CONFIG),
+            ("/app/port.ts", "const port = 8080;\nexport default port;"),
+        ],
+    ),
+    // Dynamic code turns folding off for the whole run
+    (
+        r#"import { limits } from "./config.ts";
+        import { read } from "./dynamic.ts";
+        JSON.stringify([limits.cpu.default, read()]);"#,
+        &[
+            ("/app/config.ts", CONFIG),
+            (
+                "/app/dynamic.ts",
+                r#"import { limits } from "./config.ts";
+                export function read() { return eval("limits.cpu.max"); }"#,
+            ),
+        ],
+    ),
+];
+
+/// Run a module graph and describe its outcome
+#[allow(clippy::unwrap_used)]
+fn run_graph(entry: &str, modules: &[(&str, &str)], fold: bool) -> String {
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        fold_constant_imports: fold,
+        ..Default::default()
+    });
+    let result = interp
+        .prepare_with_modules(
+            entry,
+            Some(ModulePath::new("/app/main.ts")),
+            modules
+                .iter()
+                .map(|(path, source)| (ModulePath::new(*path), source.to_string())),
+        )
+        .and_then(|_| run_to_completion(&mut interp));
+    match result {
+        Ok(StepResult::Complete(value)) => match value.value() {
+            JsValue::String(s) => s.to_string(),
+            other => format!("{:?}", other),
+        },
+        Ok(other) => format!("{:?}", other),
+        Err(err) => format!("error: {}", err),
+    }
+}
+
+#[test]
+fn test_constant_imports_same_output_with_and_without_folding() {
+    for (entry, modules) in CORPUS {
+        let folded = run_graph(entry, modules, true);
+        let unfolded = run_graph(entry, modules, false);
+        assert_eq!(folded, unfolded, "entry:\n{}", entry);
+        assert!(!folded.starts_with("error"), "{}", folded);
+    }
+}
+
+#[test]
+fn test_constant_imports_corpus_values() {
+    let outputs: Vec<String> = CORPUS
+        .iter()
+        .map(|(entry, modules)| run_graph(entry, modules, true))
+        .collect();
+    let expected = [
+        r#"[250000,4000,"512Mi",3]"#,
+        r#"[250,"payments","eu-west-1",8]"#,
+        r#"[250,4000,"payments","512Mi"]"#,
+        r#"[[2,1,1],5,2,3]"#,
+        r#"[1,2,3]"#,
+        r#"[1,"shadow","payments",250]"#,
+        r#"[true,null,"undefined",250,250,"250.0"]"#,
+        r#"[["TypeError","TypeError","TypeError"],250,"payments"]"#,
+        r#"[250,["payments0","payments1","payments2"],"512Mi"]"#,
+        r#"["payments",8080,"payments:8080"]"#,
+        r#"[250,4000]"#,
+    ];
+    assert_eq!(outputs.len(), expected.len());
+    for (output, expected) in outputs.iter().zip(expected) {
+        assert_eq!(output, expected);
+    }
+}
+
+struct RecordingConsole(Rc<RefCell<Vec<String>>>);
+
+impl ConsoleProvider for RecordingConsole {
+    fn write(&self, level: ConsoleLevel, message: &str) {
+        if level == ConsoleLevel::Debug {
+            self.0.borrow_mut().push(message.to_string());
+        }
+    }
+}
+
+/// Disassembly of the entry chunk, compiled after its imports
+#[allow(clippy::unwrap_used)]
+fn entry_bytecode(entry: &str, modules: &[(&str, &str)], fold: bool) -> String {
+    let chunks = Rc::new(RefCell::new(Vec::new()));
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        fold_constant_imports: fold,
+        dump_bytecode: true,
+        allow_dynamic_code: true,
+        ..Default::default()
+    });
+    interp.set_console(Box::new(RecordingConsole(chunks.clone())));
+    interp
+        .prepare_with_modules(
+            entry,
+            Some(ModulePath::new("/app/main.ts")),
+            modules
+                .iter()
+                .map(|(path, source)| (ModulePath::new(*path), source.to_string())),
+        )
+        .unwrap();
+    run_to_completion(&mut interp).unwrap();
+    let chunks = chunks.borrow();
+    chunks
+        .iter()
+        .rev()
+        .find(|chunk| chunk.starts_with("== <main>"))
+        .cloned()
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_constant_imports_fold_property_chains() {
+    let entry = r#"import { limits, replicas } from "./config.ts";
+        limits.cpu.default + replicas;"#;
+    let modules: &[(&str, &str)] = &[("/app/config.ts", CONFIG)];
+
+    let folded = entry_bytecode(entry, modules, true);
+    assert!(!folded.contains("GetPropertyConst"), "{}", folded);
+    assert!(folded.contains("250"), "{}", folded);
+    // The `let` export is still read through its binding
+    assert!(folded.contains("GetVar"), "{}", folded);
+
+    let unfolded = entry_bytecode(entry, modules, false);
+    assert!(unfolded.contains("GetPropertyConst"), "{}", unfolded);
+
+    // Through a namespace import and a re-export
+    let entry = r#"import * as config from "./reexport.ts";
+        config.limits.memory.default;"#;
+    let modules: &[(&str, &str)] = &[
+        ("/app/config.ts", CONFIG),
+        (
+            "/app/reexport.ts",
+            r#"export { limits } from "./config.ts";"#,
+        ),
+    ];
+    let folded = entry_bytecode(entry, modules, true);
+    assert!(!folded.contains("GetPropertyConst"), "{}", folded);
+    assert!(folded.contains("\"512Mi\""), "{}", folded);
+}
+
+#[test]
+fn test_constant_imports_off_when_any_module_uses_eval() {
+    let entry = r#"import { limits } from "./config.ts";
+        import "./dynamic.ts";
+        limits.cpu.default;"#;
+    let modules: &[(&str, &str)] = &[
+        ("/app/config.ts", CONFIG),
+        ("/app/dynamic.ts", r#"export const x = eval("1");"#),
+    ];
+    let bytecode = entry_bytecode(entry, modules, true);
+    assert!(bytecode.contains("GetPropertyConst"), "{}", bytecode);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_constant_imports_run_cannot_checkpoint() {
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        fold_constant_imports: true,
+        ..Default::default()
+    });
+    let result = interp
+        .prepare_with_modules(
+            r#"import { limits } from "./config.ts";
+            import { order } from "tsrun:host";
+            await order({ cpu: limits.cpu.default });"#,
+            Some(ModulePath::new("/app/main.ts")),
+            [(ModulePath::new("/app/config.ts"), CONFIG.to_string())],
+        )
+        .and_then(|_| run_to_completion(&mut interp))
+        .unwrap();
+    assert!(
+        matches!(result, StepResult::Suspended { .. }),
+        "{:?}",
+        result
+    );
+    let err = interp.checkpoint().unwrap_err();
+    assert!(err.to_string().contains("folded"), "{}", err);
+}
@@ -400,6 +400,83 @@ fn test_switch_no_matching_case_no_default() {
     );
 }
 
+#[test]
+fn test_switch_string_table_matches_comparison_chain() {
+    // Four or more string cases dispatch through a table; fall-through,
+    // default placement and repeated cases behave as the chain does
+    assert_eq!(
+        eval(
+            r#"
+            function route(kind: any): string {
+                let out = "";
+                switch (kind) {
+                    case "Deployment": out += "deploy";
+                    case "Service": out += "svc"; break;
+                    default: out += "other";
+                    case "ConfigMap": out += "cm"; break;
+                    case "Secret":
+                    case "Token": out += "secret"; break;
+                    case "Deployment": out += "unreachable"; break;
+                }
+                return out;
+            }
+            ["Deployment", "Service", "ConfigMap", "Secret", "Token", "Job", 1, "Deploy" + "ment"]
+                .map(route)
+                .join()
+        "#
+        ),
+        JsValue::from("deploysvc,svc,cm,secret,secret,othercm,othercm,deploysvc")
+    );
+    assert_eq!(
+        eval(
+            r#"
+            let hits = 0;
+            for (const kind of ["a", "b", "c", "d", "e"]) {
+                switch (kind) {
+                    case "a": hits += 1; break;
+                    case "b": hits += 10; break;
+                    case "c": hits += 100; break;
+                    case "d": hits += 1000; break;
+                }
+            }
+            hits
+        "#
+        ),
+        JsValue::Number(1111.0)
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_switch_string_table_threshold() {
+    let mut interp = tsrun::Interpreter::new();
+    let four = interp
+        .disassemble(
+            r#"switch (k) { case "a": 1; case "b": 2; case "c": 3; case "d": 4; }"#,
+            None,
+        )
+        .unwrap();
+    assert!(four.contains("SwitchString"), "{}", four);
+    assert!(!four.contains("StrictEq"), "{}", four);
+
+    let three = interp
+        .disassemble(
+            r#"switch (k) { case "a": 1; case "b": 2; case "c": 3; }"#,
+            None,
+        )
+        .unwrap();
+    assert!(!three.contains("SwitchString"), "{}", three);
+
+    // Any non-literal case keeps the comparison chain
+    let mixed = interp
+        .disassemble(
+            r#"switch (k) { case "a": 1; case "b": 2; case "c": 3; case d: 4; }"#,
+            None,
+        )
+        .unwrap();
+    assert!(!mixed.contains("SwitchString"), "{}", mixed);
+}
+
 // -----------------------------------------------------------------------------
 // While Loops
 // -----------------------------------------------------------------------------
@@ -1625,6 +1702,78 @@ fn test_rethrow_in_catch() {
     );
 }
 
+#[test]
+fn test_optional_catch_binding() {
+    // `catch {}` binds nothing, so an outer `e` is the one assigned
+    assert_eq!(
+        eval(
+            r#"
+            let e: string = "outer";
+            try {
+                throw new Error("ignored");
+            } catch {
+                e = e + "-caught";
+            }
+            e
+        "#
+        ),
+        JsValue::String("outer-caught".into())
+    );
+}
+
+#[test]
+fn test_catch_destructuring() {
+    assert_eq!(
+        eval(
+            r#"
+            const seen: string[] = [];
+            try {
+                throw { message: "rejected", code: 409, details: { retry: 30 } };
+            } catch ({ message, code, details: { retry } }: any) {
+                seen.push(`${message}:${code}:${retry}`);
+            }
+            try {
+                throw ["first", "second", "third"];
+            } catch ([head, ...rest]: any) {
+                seen.push(`${head}:${rest.join(",")}`);
+            }
+            // Defaults apply when the thrown object lacks the key; building
+            // them allocates while the thrown value is still being read
+            try {
+                throw { message: "partial" };
+            } catch ({ message, code = 500, tags = ["a", "b"].map(t => t + "!") }: any) {
+                seen.push(`${message}:${code}:${tags.join(",")}`);
+            }
+            seen.join("|")
+        "#
+        ),
+        JsValue::String("rejected:409:30|first:second,third|partial:500:a!,b!".into())
+    );
+}
+
+#[test]
+fn test_rethrow_from_destructuring_catch_keeps_identity() {
+    assert_eq!(
+        eval(
+            r#"
+            const original = new Error("boom");
+            let same = false;
+            try {
+                try {
+                    throw original;
+                } catch ({ message }: any) {
+                    if (message === "boom") throw original;
+                }
+            } catch (e) {
+                same = e === original;
+            }
+            same
+        "#
+        ),
+        JsValue::Boolean(true)
+    );
+}
+
 // =============================================================================
 // PHASE 4: Error Handling for Invalid Control Flow
 // =============================================================================
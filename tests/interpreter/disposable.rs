@@ -0,0 +1,258 @@
+//! Tests for DisposableStack, AsyncDisposableStack and SuppressedError
+
+use std::any::Any;
+use std::cell::Cell;
+use std::rc::Rc;
+
+use super::{eval, run_to_completion};
+use serde_json::json;
+use tsrun::{
+    HostClassDef, Interpreter, InterpreterConfig, JsError, JsValue, ModulePath, OrderResponse,
+    StepResult, api, create_eval_internal_module,
+};
+
+#[test]
+fn test_disposable_stack_disposes_in_reverse_order() {
+    assert_eq!(
+        eval(
+            r#"
+            const log: string[] = [];
+            const resource = (name: string) => ({ [Symbol.dispose]() { log.push(name); } });
+            const stack = new DisposableStack();
+            stack.use(resource("a"));
+            stack.use(resource("b"));
+            stack.use(resource("c"));
+            const before = stack.disposed;
+            stack.dispose();
+            stack.dispose();
+            [before, stack.disposed, log.join(",")].join(" ")
+            "#
+        ),
+        JsValue::from("false true c,b,a")
+    );
+}
+
+#[test]
+fn test_disposable_stack_adopt_defer_and_move() {
+    assert_eq!(
+        eval(
+            r#"
+            const log: string[] = [];
+            const stack = new DisposableStack();
+            stack.adopt(7, (value: number) => log.push("adopt " + value));
+            stack.defer(() => log.push("defer"));
+            stack.use(null);
+            const moved = stack.move();
+            let error = "";
+            try { stack.defer(() => {}); } catch (e) { error = e.name; }
+            moved[Symbol.dispose]();
+            [stack.disposed, error, log.join(","), Object.prototype.toString.call(moved)].join(" ")
+            "#
+        ),
+        JsValue::from("true ReferenceError defer,adopt 7 [object DisposableStack]")
+    );
+}
+
+#[test]
+fn test_disposable_stack_use_rejects_non_disposable() {
+    assert_eq!(
+        eval(
+            r#"
+            const stack = new DisposableStack();
+            const errors: string[] = [];
+            for (const value of [{}, 1]) {
+                try { stack.use(value); } catch (e) { errors.push(e.name); }
+            }
+            errors.join(",")
+            "#
+        ),
+        JsValue::from("TypeError,TypeError")
+    );
+}
+
+#[test]
+fn test_disposal_error_still_disposes_the_rest() {
+    assert_eq!(
+        eval(
+            r#"
+            const log: string[] = [];
+            const stack = new DisposableStack();
+            stack.defer(() => { log.push("a"); throw new Error("a failed"); });
+            stack.defer(() => log.push("b"));
+            stack.defer(() => { log.push("c"); throw new Error("c failed"); });
+            let result = "";
+            try {
+                stack.dispose();
+            } catch (e) {
+                result = [e instanceof SuppressedError, e.error.message, e.suppressed.message].join(" ");
+            }
+            log.join(",") + " " + result
+            "#
+        ),
+        JsValue::from("c,b,a true a failed c failed")
+    );
+}
+
+#[test]
+fn test_suppressed_error_constructor() {
+    assert_eq!(
+        eval(
+            r#"
+            const e = new SuppressedError("new", "old", "while closing");
+            [e.name, e.message, e.error, e.suppressed, e instanceof Error,
+             Object.keys(e).includes("error"), String(Symbol.dispose)].join(" ")
+            "#
+        ),
+        JsValue::from("SuppressedError while closing new old true false Symbol(Symbol.dispose)")
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic, clippy::indexing_slicing)]
+fn test_async_dispose_awaits_order() {
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        internal_modules: vec![create_eval_internal_module()],
+        ..Default::default()
+    });
+    interp
+        .prepare(
+            r#"
+            import { order } from "tsrun:host";
+            const log: string[] = [];
+            const stack = new AsyncDisposableStack();
+            stack.use({ [Symbol.dispose]() { log.push("sync"); } });
+            stack.use({
+                async [Symbol.asyncDispose]() {
+                    const reply: any = await order({ close: "db" });
+                    log.push("closed " + reply.status);
+                },
+            });
+            stack.defer(() => log.push("deferred"));
+            await stack.disposeAsync();
+            log.push(String(stack.disposed));
+            log.join(",")
+            "#,
+            Some(ModulePath::new("/main.ts")),
+        )
+        .unwrap();
+
+    let StepResult::Suspended { pending, .. } = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected disposal to wait on its order");
+    };
+    assert_eq!(pending.len(), 1);
+    assert_eq!(
+        api::get_property(&pending[0].payload, "close").unwrap(),
+        JsValue::from("db")
+    );
+    let response = api::create_response_object(&mut interp, &json!({ "status": "ok" })).unwrap();
+    interp.fulfill_orders(vec![OrderResponse {
+        id: pending[0].id,
+        result: Ok(response),
+    }]);
+    let StepResult::Complete(value) = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected the script to complete");
+    };
+    assert_eq!(
+        *value.value(),
+        JsValue::from("deferred,closed ok,sync,true")
+    );
+}
+
+#[test]
+fn test_async_dispose_rejects_with_suppressed_error() {
+    assert_eq!(
+        eval(
+            r#"
+            const stack = new AsyncDisposableStack();
+            stack.defer(async () => { throw new Error("first"); });
+            stack.defer(() => { throw new Error("second"); });
+            let result = "";
+            stack.disposeAsync().catch((e: any) => {
+                result = e.name + " " + e.error.message + " " + e.suppressed.message;
+            });
+            result
+            "#
+        ),
+        JsValue::from("SuppressedError first second")
+    );
+}
+
+/// Counts how often the script disposed it
+struct Handle(Rc<Cell<u32>>);
+
+fn handle_dispose(_interp: &mut Interpreter, data: &dyn Any) -> Result<(), JsError> {
+    if let Some(handle) = data.downcast_ref::<Handle>() {
+        handle.0.set(handle.0.get() + 1);
+    }
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_host_object_dispose_hook_runs_once() {
+    let mut interp = Interpreter::new();
+    let class = interp.register_host_class(HostClassDef {
+        name: "Handle".to_string(),
+        dispose: Some(handle_dispose),
+        ..Default::default()
+    });
+    let disposals = Rc::new(Cell::new(0));
+    let handle = interp
+        .create_host_object(class, Box::new(Handle(disposals.clone())))
+        .unwrap();
+    let global = JsValue::Object(interp.global.clone());
+    api::set_property(&global, "handle", handle.value().clone()).unwrap();
+    interp
+        .eval(
+            r#"
+            const stack = new DisposableStack();
+            stack.use(handle);
+            stack.dispose();
+            handle[Symbol.dispose]();
+            "#,
+            None,
+        )
+        .unwrap();
+    assert_eq!(disposals.get(), 1);
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_host_function_dispose_hook() {
+    let mut interp = Interpreter::new();
+    let disposals = Rc::new(Cell::new(0));
+    let counter = disposals.clone();
+    let read = interp.create_disposable_function_value(
+        "read",
+        0,
+        |_, _, _| Ok(tsrun::Guarded::unguarded(JsValue::from("line"))),
+        move |_| {
+            counter.set(counter.get() + 1);
+            Ok(())
+        },
+    );
+    let plain = interp.create_function_value("plain", 0, |_, _, _| {
+        Ok(tsrun::Guarded::unguarded(JsValue::Undefined))
+    });
+    let global = JsValue::Object(interp.global.clone());
+    api::set_property(&global, "read", read.value().clone()).unwrap();
+    api::set_property(&global, "plain", plain.value().clone()).unwrap();
+    let result = interp
+        .eval(
+            r#"
+            const stack = new DisposableStack();
+            const line = stack.use(read)();
+            stack.dispose();
+            let error = "";
+            try { new DisposableStack().use(plain); } catch (e) { error = e.name; }
+            line + " " + error
+            "#,
+            None,
+        )
+        .unwrap();
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete, got {:?}", result);
+    };
+    assert_eq!(*value.value(), JsValue::from("line TypeError"));
+    assert_eq!(disposals.get(), 1);
+}
@@ -0,0 +1,206 @@
+//! Tests for the duration helpers of tsrun:host
+
+use super::eval;
+use tsrun::{Interpreter, InterpreterConfig, JsValue, RuntimeValue, create_eval_internal_module};
+
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn eval_with_host(source: &str) -> RuntimeValue {
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        internal_modules: vec![create_eval_internal_module()],
+        ..Default::default()
+    });
+    match super::run(&mut interp, source, None).unwrap() {
+        tsrun::StepResult::Complete(value) => value,
+        other => panic!("Expected completion, got {:?}", other),
+    }
+}
+
+fn parse(input: &str) -> RuntimeValue {
+    eval_with_host(&format!(
+        r#"import {{ parseDuration }} from "tsrun:host"; parseDuration({:?})"#,
+        input
+    ))
+}
+
+fn format(args: &str) -> RuntimeValue {
+    eval_with_host(&format!(
+        r#"import {{ formatDuration }} from "tsrun:host"; formatDuration({})"#,
+        args
+    ))
+}
+
+fn error_message(call: &str) -> RuntimeValue {
+    eval_with_host(&format!(
+        r#"
+        import {{ parseDuration, formatDuration }} from "tsrun:host";
+        let caught = "";
+        try {{ {}; }} catch (e) {{ caught = e.name + ": " + e.message; }}
+        caught
+    "#,
+        call
+    ))
+}
+
+#[test]
+fn test_parse_duration_table() {
+    let cases: &[(&str, f64)] = &[
+        ("0", 0.0),
+        ("0ms", 0.0),
+        ("0s", 0.0),
+        ("1ms", 1.0),
+        ("250ms", 250.0),
+        ("1s", 1_000.0),
+        ("90s", 90_000.0),
+        ("1m", 60_000.0),
+        ("1h", 3_600_000.0),
+        ("1d", 86_400_000.0),
+        ("1h30m", 5_400_000.0),
+        ("1d2h3m4s5ms", 93_784_005.0),
+        ("2m30s", 150_000.0),
+        ("1.5h", 5_400_000.0),
+        ("0.5s", 500.0),
+        ("1.25s", 1_250.0),
+        (".5m", 30_000.0),
+        ("+10s", 10_000.0),
+        (" 3s ", 3_000.0),
+        ("104249991d", 9_007_199_222_400_000.0),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(
+            parse(input),
+            JsValue::Number(*expected),
+            "parsing {}",
+            input
+        );
+    }
+}
+
+#[test]
+fn test_format_duration_table() {
+    let cases: &[(&str, &str)] = &[
+        ("0", "0ms"),
+        ("1", "1ms"),
+        ("999", "999ms"),
+        ("1000", "1s"),
+        ("90000", "1m 30s"),
+        ("5400000", "1h 30m"),
+        ("5400000, { compact: true }", "1h30m"),
+        ("86400000", "1d"),
+        ("93784005", "1d 2h 3m 4s 5ms"),
+        ("93784005, { compact: true }", "1d2h3m4s5ms"),
+        ("93784005, { maxUnits: 2 }", "1d 2h"),
+        ("93784005, { maxUnits: 1 }", "1d"),
+        // Units are counted from the largest present, zero ones included
+        ("3605000, { maxUnits: 2 }", "1h"),
+        ("3605000", "1h 5s"),
+        ("5430000, { maxUnits: 2, compact: true }", "1h30m"),
+        ("93784005, { maxUnits: 10 }", "1d 2h 3m 4s 5ms"),
+    ];
+    for (args, expected) in cases {
+        assert_eq!(
+            format(args),
+            JsValue::from(*expected),
+            "formatting {}",
+            args
+        );
+    }
+}
+
+#[test]
+fn test_duration_round_trips() {
+    assert_eq!(
+        eval_with_host(
+            r#"
+            import { parseDuration, formatDuration } from "tsrun:host";
+            ["1h30m", "250ms", "1d2h3m4s5ms", "45s", "2d"]
+                .map(d => formatDuration(parseDuration(d), { compact: true }) === d)
+                .every(ok => ok)
+        "#
+        ),
+        JsValue::Boolean(true)
+    );
+}
+
+#[test]
+fn test_duration_errors_name_offending_input() {
+    let cases: &[(&str, &str)] = &[
+        (
+            r#"parseDuration("5x")"#,
+            "RangeError: Invalid duration '5x': unknown unit at 'x'",
+        ),
+        (
+            r#"parseDuration("10s!")"#,
+            "RangeError: Invalid duration '10s!': expected a number at '!'",
+        ),
+        (
+            r#"parseDuration("10")"#,
+            "RangeError: Invalid duration '10': expected a unit at ''",
+        ),
+        (
+            r#"parseDuration("")"#,
+            "RangeError: Invalid duration '': expected a number at ''",
+        ),
+        (
+            r#"parseDuration("30m1h")"#,
+            "RangeError: Invalid duration '30m1h': units must go from largest to smallest at 'h'",
+        ),
+        (
+            r#"parseDuration("1s1s")"#,
+            "RangeError: Invalid duration '1s1s': units must go from largest to smallest at 's'",
+        ),
+        (
+            r#"parseDuration("1.5ms")"#,
+            "RangeError: Invalid duration '1.5ms': durations are whole milliseconds",
+        ),
+        (
+            r#"parseDuration("1.0001s")"#,
+            "RangeError: Invalid duration '1.0001s': durations are whole milliseconds",
+        ),
+        (
+            r#"parseDuration("-5s")"#,
+            "RangeError: Invalid duration '-5s': durations cannot be negative",
+        ),
+        (
+            r#"parseDuration("104249992d")"#,
+            "RangeError: Duration '104249992d' exceeds Number.MAX_SAFE_INTEGER milliseconds",
+        ),
+        (
+            r#"parseDuration(90)"#,
+            "TypeError: parseDuration requires a string",
+        ),
+        (
+            r#"formatDuration(-1)"#,
+            "RangeError: Cannot format -1 as a duration: durations cannot be negative",
+        ),
+        (
+            r#"formatDuration(0.5)"#,
+            "RangeError: Cannot format 0.5 as a duration: durations are whole milliseconds",
+        ),
+        (
+            r#"formatDuration(Infinity)"#,
+            "RangeError: Cannot format Infinity as a duration: not a finite number",
+        ),
+        (
+            r#"formatDuration(2 ** 60)"#,
+            "RangeError: Cannot format 1152921504606847000 as a duration: exceeds Number.MAX_SAFE_INTEGER",
+        ),
+        (
+            r#"formatDuration(1000, { maxUnits: 0 })"#,
+            "RangeError: formatDuration maxUnits must be a positive integer",
+        ),
+    ];
+    for (call, expected) in cases {
+        assert_eq!(
+            error_message(call),
+            JsValue::from(*expected),
+            "calling {}",
+            call
+        );
+    }
+}
+
+#[test]
+fn test_duration_helpers_need_host_module() {
+    // Not globals: only available through the tsrun:host import
+    assert_eq!(eval("typeof parseDuration"), JsValue::from("undefined"));
+}
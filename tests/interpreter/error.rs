@@ -55,6 +55,36 @@ fn test_rangeerror() {
     );
 }
 
+#[test]
+fn test_error_cause_option() {
+    assert_eq!(
+        eval(
+            r#"
+            const root = new Error("root");
+            const e = new RangeError("outer", { cause: root });
+            [e.cause === root, Object.keys(e).includes("cause"), "cause" in new Error("x", {})].join()
+        "#
+        ),
+        JsValue::from("true,false,false")
+    );
+}
+
+#[test]
+fn test_aggregate_error() {
+    assert_eq!(
+        eval(
+            r#"
+            const e = new AggregateError(new Set([new Error("a"), 2]), "many", { cause: "why" });
+            [
+                e instanceof AggregateError, e instanceof Error, e.name, e.message,
+                e.errors.length, e.errors[0].message, e.cause, Object.keys(e).includes("errors"),
+            ].join()
+        "#
+        ),
+        JsValue::from("true,true,AggregateError,many,2,a,why,false")
+    );
+}
+
 // Error.prototype.toString tests
 #[test]
 fn test_error_tostring_basic() {
@@ -1078,3 +1108,246 @@ fn test_try_catch_native_error_through_callback() {
         JsValue::from("caught")
     );
 }
+
+// ============================================================================
+// Named TypeErrors for property access and calls
+// ============================================================================
+
+/// The message of the TypeError thrown by `code`
+fn type_error_message(code: &str) -> tsrun::RuntimeValue {
+    eval(&format!(
+        r#"
+        let message: string = "no error";
+        try {{
+            {}
+        }} catch (e) {{
+            message = e instanceof TypeError ? e.message : "not a TypeError";
+        }}
+        message
+    "#,
+        code
+    ))
+}
+
+#[test]
+fn test_read_property_of_undefined_names_object_chain() {
+    assert_eq!(
+        type_error_message("const config: any = { spec: undefined }; config.spec.replicas;"),
+        JsValue::from("Cannot read properties of undefined (reading 'replicas' of 'config.spec')")
+    );
+}
+
+#[test]
+fn test_read_property_of_null_names_identifier() {
+    assert_eq!(
+        type_error_message("const user: any = null; user.name;"),
+        JsValue::from("Cannot read properties of null (reading 'name' of 'user')")
+    );
+}
+
+#[test]
+fn test_read_computed_property_of_undefined() {
+    assert_eq!(
+        type_error_message("const rows: any = {}; const i = 2; rows.items[i];"),
+        JsValue::from("Cannot read properties of undefined (reading '2' of 'rows.items')")
+    );
+}
+
+#[test]
+fn test_read_property_of_unnamed_expression() {
+    assert_eq!(
+        type_error_message("function get(): any { return undefined; } get().value;"),
+        JsValue::from("Cannot read properties of undefined (reading 'value')")
+    );
+}
+
+#[test]
+fn test_set_property_of_undefined_names_object_chain() {
+    assert_eq!(
+        type_error_message("const state: any = {}; state.session.user = 'ann';"),
+        JsValue::from("Cannot set properties of undefined (setting 'user' of 'state.session')")
+    );
+}
+
+#[test]
+fn test_compound_assignment_reads_named_object() {
+    assert_eq!(
+        type_error_message("const stats: any = {}; stats.totals.count += 1;"),
+        JsValue::from("Cannot read properties of undefined (reading 'count' of 'stats.totals')")
+    );
+}
+
+#[test]
+fn test_method_on_undefined_names_receiver() {
+    assert_eq!(
+        type_error_message("const config: any = {}; config.spec.render();"),
+        JsValue::from("Cannot read properties of undefined (reading 'render' of 'config.spec')")
+    );
+}
+
+#[test]
+fn test_call_missing_method_names_callee() {
+    assert_eq!(
+        type_error_message("const config: any = {}; config.render();"),
+        JsValue::from("config.render is not a function")
+    );
+}
+
+#[test]
+fn test_call_non_function_identifier_names_callee() {
+    assert_eq!(
+        type_error_message("const handler: any = 42; handler(1, ...[2]);"),
+        JsValue::from("handler is not a function")
+    );
+}
+
+#[test]
+fn test_call_this_member_names_callee() {
+    assert_eq!(
+        type_error_message(
+            "class Widget { draw() { (this as any).paint(); } } new Widget().draw();"
+        ),
+        JsValue::from("this.paint is not a function")
+    );
+}
+
+#[test]
+fn test_call_unnamed_expression_keeps_generic_message() {
+    assert_eq!(
+        type_error_message("const fns: any = [1]; fns[0]();"),
+        JsValue::from("Not a function")
+    );
+}
+
+#[test]
+#[allow(clippy::panic)]
+fn test_uncaught_throw_reports_throw_site() {
+    let err = super::eval_result(
+        "function check(n: number) {\n    if (n < 0) throw new Error('negative');\n}\ncheck(-1);",
+    )
+    .err();
+    let Some(tsrun::JsError::RuntimeError { message, stack, .. }) = err else {
+        panic!("Expected RuntimeError, got {:?}", err);
+    };
+    assert_eq!(message, "negative");
+    let sites: Vec<_> = stack
+        .iter()
+        .map(|frame| (frame.function_name.as_deref(), frame.line))
+        .collect();
+    assert_eq!(sites, vec![(Some("check"), 2), (None, 4)]);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Errors carried across the host boundary
+// ═══════════════════════════════════════════════════════════════════════════
+
+const DB_ERROR: &str = r#"
+class DbError extends Error {
+    code = "E_DB_DOWN";
+    retry = { after: 30 };
+    constructor(message: string) {
+        super(message);
+        this.name = "DbError";
+    }
+}
+function connect() { throw new DbError("db unavailable"); }
+connect();
+"#;
+
+#[test]
+#[allow(clippy::panic)]
+fn test_materialized_error_keeps_own_properties() {
+    let err = super::eval_result(DB_ERROR).err();
+    let Some(tsrun::JsError::RuntimeError {
+        kind,
+        message,
+        properties,
+        ..
+    }) = err
+    else {
+        panic!("Expected RuntimeError, got {:?}", err);
+    };
+    assert_eq!(
+        (kind.as_str(), message.as_str()),
+        ("DbError", "db unavailable")
+    );
+    let properties = properties.map(|p| serde_json::Value::Object(*p));
+    assert_eq!(
+        properties,
+        Some(serde_json::json!({ "code": "E_DB_DOWN", "retry": { "after": 30 } }))
+    );
+}
+
+#[test]
+#[allow(clippy::panic, clippy::unwrap_used, clippy::indexing_slicing)]
+fn test_error_to_value_round_trips_into_order_rejection() {
+    let err = super::eval_result(DB_ERROR).unwrap_err();
+
+    // A second run sees the error as the rejection of its order
+    let mut interp = super::create_test_runtime();
+    let result = super::run(
+        &mut interp,
+        r#"
+        import { order } from "tsrun:host";
+        let seen = "";
+        try {
+            await order({ type: "query" });
+        } catch (e: any) {
+            seen = [
+                e.name, e.message, e.code, e.retry.after, e instanceof Error,
+                e.stack.includes("at connect"),
+            ].join("|");
+        }
+        seen
+    "#,
+        None,
+    )
+    .unwrap();
+    let tsrun::StepResult::Suspended { pending, .. } = result else {
+        panic!("Expected Suspended, got {:?}", result);
+    };
+    let rejection = interp.error_to_value(&err);
+    interp.fulfill_orders(vec![tsrun::OrderResponse::reject_with_value(
+        pending[0].id,
+        rejection,
+    )]);
+    let result = super::run_to_completion(&mut interp).unwrap();
+    let tsrun::StepResult::Complete(value) = result else {
+        panic!("Expected Complete, got {:?}", result);
+    };
+    assert_eq!(
+        value.value().as_str(),
+        Some("DbError|db unavailable|E_DB_DOWN|30|true|true")
+    );
+}
+
+#[test]
+#[allow(clippy::panic, clippy::unwrap_used)]
+fn test_error_to_value_picks_prototype_and_host_data() {
+    let mut interp = super::create_test_runtime();
+    let describe = r#"
+        (typeError: any, mismatch: any) => [
+            typeError instanceof TypeError, typeError.message,
+            mismatch instanceof Error, mismatch.hostData.path, mismatch.hostData.actual,
+        ].join("|")
+    "#;
+    let Ok(tsrun::StepResult::Complete(describe)) = super::run(&mut interp, describe, None) else {
+        panic!("Expected a function");
+    };
+    let type_error = interp.error_to_value(&tsrun::JsError::type_error("bad input"));
+    let mismatch = interp.error_to_value(&tsrun::JsError::IntegrityMismatch {
+        path: tsrun::ModulePath::new("/lib.ts"),
+        expected: "sha256-a".to_string(),
+        actual: "sha256-b".to_string(),
+    });
+    let guard = tsrun::api::create_guard(&interp);
+    let value = tsrun::api::call_function(
+        &mut interp,
+        &guard,
+        describe.value(),
+        None,
+        &[type_error.value().clone(), mismatch.value().clone()],
+    )
+    .unwrap();
+    assert_eq!(value.as_str(), Some("true|bad input|true|/lib.ts|sha256-b"));
+}
@@ -0,0 +1,137 @@
+//! Tests for the flight recorder attached to script errors
+
+use super::run_to_completion;
+use serde_json::json;
+use tsrun::{
+    FlightEventKind, Interpreter, InterpreterConfig, JsError, ModulePath, OrderResponse,
+    StepResult, api, create_eval_internal_module,
+};
+
+const LIB: &str = r#"
+export function parse(text: string): string[] { return text.split(","); }
+export function count(items: string[]): number { return items.length; }
+"#;
+
+const MAIN: &str = r#"
+import { order } from "tsrun:host";
+import { parse, count } from "./lib.ts";
+
+function check(n: number): number { return n * 2; }
+
+const config: any = {};
+const items = parse("a,b,c");
+const total = check(count(items));
+const missing = config.retries;
+const reply: any = await order({ total });
+throw new Error(`failed after ${reply.status}`);
+"#;
+
+/// Run MAIN to the error it throws once its order is answered
+#[allow(clippy::unwrap_used, clippy::panic, clippy::indexing_slicing)]
+fn run_failing(flight_recorder: bool) -> JsError {
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        internal_modules: vec![create_eval_internal_module()],
+        flight_recorder,
+        ..Default::default()
+    });
+    interp
+        .prepare_with_modules(
+            MAIN,
+            Some(ModulePath::new("/main.ts")),
+            [(ModulePath::new("/lib.ts"), LIB.to_string())],
+        )
+        .unwrap();
+    let StepResult::Suspended { pending, .. } = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected the script to wait on its order");
+    };
+    let id = pending[0].id;
+    let response = api::create_response_object(&mut interp, &json!({ "status": 503 })).unwrap();
+    interp.fulfill_orders(vec![OrderResponse {
+        id,
+        result: Ok(response),
+    }]);
+    run_to_completion(&mut interp).unwrap_err()
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_flight_log_records_events_leading_to_error() {
+    let err = run_failing(true);
+    assert!(err.to_string().contains("failed after 503"), "{}", err);
+
+    let events: Vec<(FlightEventKind, &str)> = err
+        .flight_events()
+        .unwrap()
+        .iter()
+        .map(|event| (event.kind, event.subject.as_str()))
+        .collect();
+    assert_eq!(
+        events,
+        [
+            (FlightEventKind::ModuleStart, "/lib.ts"),
+            (FlightEventKind::ModuleEnd, "/lib.ts"),
+            (FlightEventKind::ModuleStart, "/main.ts"),
+            (FlightEventKind::Call, "parse"),
+            (FlightEventKind::Return, "parse"),
+            (FlightEventKind::Call, "count"),
+            (FlightEventKind::Return, "count"),
+            (FlightEventKind::Call, "check"),
+            (FlightEventKind::Return, "check"),
+            (FlightEventKind::MissingProperty, "retries"),
+            (FlightEventKind::OrderCreated, "1"),
+            (FlightEventKind::OrderFulfilled, "1"),
+        ]
+    );
+
+    let log = err.flight_log().unwrap();
+    assert!(log.contains("call parse at /main.ts:8:"), "{}", log);
+    assert!(
+        log.contains("missing property retries at /main.ts:10:"),
+        "{}",
+        log
+    );
+    assert!(log.contains("order created 1 at /main.ts:11:"), "{}", log);
+}
+
+#[test]
+fn test_flight_log_absent_when_disabled() {
+    let err = run_failing(false);
+    assert!(err.flight_events().is_none());
+    assert!(err.flight_log().is_none());
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_flight_recorder_keeps_last_events() {
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        flight_recorder: true,
+        flight_recorder_capacity: 4,
+        ..Default::default()
+    });
+    let err = interp
+        .eval(
+            r#"
+            function step(n: number): number { return n; }
+            for (let i = 0; i < 10; i++) step(i);
+            throw new Error("done");
+            "#,
+            None,
+        )
+        .unwrap_err();
+
+    let kinds: Vec<_> = err
+        .flight_events()
+        .unwrap()
+        .iter()
+        .map(|event| event.kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            FlightEventKind::Call,
+            FlightEventKind::Return,
+            FlightEventKind::Call,
+            FlightEventKind::Return,
+        ]
+    );
+}
@@ -0,0 +1,257 @@
+//! Tests for fuel limits and interrupts inside long-running builtins, and
+//! cooperative cancellation through `checkpoint()`
+
+use super::{run, run_to_completion};
+use tsrun::{
+    Interpreter, InterpreterConfig, JsError, JsValue, OrderResponse, RuntimeValue, StepResult,
+    TerminationReason, create_eval_internal_module,
+};
+
+/// Builds `globalThis.items` (5k numbers) and `globalThis.text` (its JSON) without limits
+#[allow(clippy::unwrap_used)]
+fn interp_with_data() -> Interpreter {
+    let mut interp = Interpreter::new();
+    run(
+        &mut interp,
+        r#"
+        const items: number[] = [];
+        for (let i = 0; i < 5000; i++) items.push((i * 7919) % 5000);
+        globalThis.items = items;
+        globalThis.text = JSON.stringify(items);
+        globalThis.entries = new Map(items.map(n => [String(n), n]));
+        "#,
+        None,
+    )
+    .unwrap();
+    interp
+}
+
+/// Run `expr` under a small fuel budget, wrapped in try/catch/finally that
+/// must not observe the termination
+fn run_with_fuel(interp: &mut Interpreter, expr: &str) -> Result<StepResult, JsError> {
+    interp.set_fuel(Some(2000));
+    let source = format!(
+        r#"
+        let out = "unset";
+        try {{ {expr}; out = "finished"; }} catch (e) {{ out = "caught"; }} finally {{ out = "finally"; }}
+        out
+    "#
+    );
+    run(interp, &source, None)
+}
+
+#[track_caller]
+#[allow(clippy::panic)]
+fn assert_fuel_exhausted(result: Result<StepResult, JsError>) {
+    match result {
+        Err(JsError::Terminated { reason }) => assert_eq!(reason, TerminationReason::FuelExhausted),
+        Err(other) => panic!("Expected fuel exhaustion, got error {}", other),
+        Ok(_) => panic!("Expected fuel exhaustion, script finished"),
+    }
+}
+
+#[test]
+fn test_fuel_stops_plain_loop_uncatchably() {
+    let mut interp = Interpreter::new();
+    interp.set_fuel(Some(10_000));
+    let result = run(
+        &mut interp,
+        "let n = 0; try { while (true) n++; } catch (e) {} finally { n = -1; } n",
+        None,
+    );
+    assert_fuel_exhausted(result);
+    assert_eq!(interp.fuel_remaining(), Some(0));
+}
+
+#[test]
+fn test_fuel_stops_long_running_builtins() {
+    let cases = [
+        "items.slice().sort()",
+        "items.slice().sort((a, b) => a - b)",
+        "items.toSorted((a, b) => a - b)",
+        "JSON.stringify(items)",
+        "JSON.parse(text)",
+        r#""ab".repeat(1000000)"#,
+        r#""x".padStart(1000000, "ab")"#,
+        r#""x".padEnd(1000000, "ab")"#,
+        "Object.fromEntries(entries)",
+    ];
+    for expr in cases {
+        let mut interp = interp_with_data();
+        let result = run_with_fuel(&mut interp, expr);
+        assert!(
+            matches!(
+                result,
+                Err(JsError::Terminated {
+                    reason: TerminationReason::FuelExhausted
+                })
+            ),
+            "{}: {:?}",
+            expr,
+            result.map(|_| ()).map_err(|e| e.to_string())
+        );
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_small_builtin_calls_fit_in_budget() {
+    let mut interp = Interpreter::new();
+    interp.set_fuel(Some(2000));
+    let result = run(
+        &mut interp,
+        r#"[3, 1, 2].sort().join("") + JSON.stringify({ a: [1] }) + "ab".repeat(3)"#,
+        None,
+    )
+    .unwrap();
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete result");
+    };
+    assert_eq!(value.as_str(), Some("123{\"a\":[1]}ababab"));
+    assert!(interp.fuel_remaining().is_some_and(|fuel| fuel > 0));
+}
+
+#[test]
+fn test_termination_is_not_a_promise_rejection() {
+    let mut interp = Interpreter::new();
+    interp.set_fuel(Some(5000));
+    let result = run(
+        &mut interp,
+        r#"
+        async function spin() { while (true) {} }
+        let rejected = false;
+        spin().catch(() => { rejected = true; });
+        new Promise(() => { while (true) {} });
+        rejected
+        "#,
+        None,
+    );
+    assert_fuel_exhausted(result);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_interrupt_handle_stops_builtin_until_reset() {
+    let mut interp = interp_with_data();
+    let handle = interp.interrupt_handle();
+    handle.interrupt();
+    let result = run(&mut interp, "JSON.stringify(items).length", None);
+    assert!(matches!(
+        result,
+        Err(JsError::Terminated {
+            reason: TerminationReason::Interrupted
+        })
+    ));
+    assert_eq!(
+        result.err().map(|e| e.to_string()).as_deref(),
+        Some("Execution terminated: interrupted")
+    );
+
+    handle.reset();
+    assert!(!interp.interrupt_handle().is_interrupted());
+    let result = run(&mut interp, "[1, 2, 3].length", None).unwrap();
+    assert!(matches!(result, StepResult::Complete(_)));
+}
+
+fn host_interp() -> Interpreter {
+    Interpreter::with_config(InterpreterConfig {
+        internal_modules: vec![create_eval_internal_module()],
+        ..Default::default()
+    })
+}
+
+/// Renders 10 documents, awaiting the host after the third; cancellation
+/// is caught to return what was rendered so far
+const RENDER_SOURCE: &str = r#"
+    import { order, checkpoint, isCancellationRequested, CancellationError } from "tsrun:host";
+    const rendered: string[] = [];
+    let outcome = "done";
+    try {
+        for (let i = 0; i < 10; i++) {
+            checkpoint();
+            rendered.push("doc" + i);
+            if (i === 2) await order({ type: "flush" });
+        }
+    } catch (e) {
+        outcome = e instanceof CancellationError && e.name === "CancellationError"
+            ? "cancelled (" + e.reason + ") " + isCancellationRequested()
+            : "other";
+    }
+    outcome + ": " + rendered.join(",")
+"#;
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_checkpoint_throws_once_cancellation_is_requested() {
+    let mut interp = host_interp();
+    let result = run(&mut interp, RENDER_SOURCE, None).unwrap();
+    let StepResult::Suspended { pending, .. } = result else {
+        panic!("Expected Suspended, got {:?}", result);
+    };
+
+    // Between steps, the host asks the script to wind down
+    interp.request_cancellation("deadline");
+    interp.fulfill_orders(vec![OrderResponse {
+        id: pending[0].id,
+        result: Ok(RuntimeValue::unguarded(JsValue::Undefined)),
+    }]);
+    match run_to_completion(&mut interp).unwrap() {
+        StepResult::Complete(value) => assert_eq!(
+            value,
+            JsValue::from("cancelled (deadline) true: doc0,doc1,doc2")
+        ),
+        other => panic!("Expected Complete, got {:?}", other),
+    }
+
+    // Once withdrawn, checkpoints pass again
+    interp.clear_cancellation();
+    let result = run(
+        &mut interp,
+        r#"import { checkpoint, isCancellationRequested } from "tsrun:host";
+        checkpoint(); isCancellationRequested()"#,
+        None,
+    )
+    .unwrap();
+    assert!(matches!(result, StepResult::Complete(v) if v == JsValue::Boolean(false)));
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_interrupt_handle_requests_cancellation() {
+    let mut interp = host_interp();
+    let handle = interp.interrupt_handle();
+    handle.request_cancellation();
+    assert!(handle.is_cancellation_requested());
+    let result = run(
+        &mut interp,
+        r#"import { checkpoint } from "tsrun:host";
+        let message = "";
+        try { checkpoint(); } catch (e) { message = e.message + " " + e.reason; }
+        message"#,
+        None,
+    )
+    .unwrap();
+    assert!(
+        matches!(&result, StepResult::Complete(v) if *v == JsValue::from("The run was cancelled undefined")),
+        "{:?}",
+        result
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_cancellation_leaves_uncooperative_script_running() {
+    let source = r#"
+        let total = 0;
+        for (let i = 0; i < 2000; i++) total += i;
+        total
+    "#;
+    let mut interp = host_interp();
+    interp.request_cancellation("deadline");
+    let result = run(&mut interp, source, None).unwrap();
+    assert!(matches!(result, StepResult::Complete(v) if v == JsValue::Number(1999000.0)));
+
+    // Only the hard limits stop it
+    interp.set_fuel(Some(2000));
+    assert_fuel_exhausted(run(&mut interp, source, None));
+}
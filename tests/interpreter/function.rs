@@ -2,7 +2,7 @@
 
 use super::{eval, run};
 use tsrun::value::JsString;
-use tsrun::{Interpreter, JsValue, StepResult};
+use tsrun::{Interpreter, InterpreterConfig, JsError, JsValue, StepResult, TerminationReason};
 
 #[test]
 fn test_function_name_property() {
@@ -809,6 +809,147 @@ fn test_function_prototype_constructor() {
     );
 }
 
+// ============================================================
+// Dynamic code capability
+// ============================================================
+
+fn dynamic_code_interpreter(allow_dynamic_code: bool) -> Interpreter {
+    Interpreter::with_config(InterpreterConfig {
+        allow_dynamic_code,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_function_constructor_adder_when_dynamic_code_allowed() {
+    let mut interp = dynamic_code_interpreter(true);
+    let result = run(
+        &mut interp,
+        r#"
+        const add = new Function("a", "b", "return a + b");
+        const indirect = (0, eval)("var generated = 40; generated + 2");
+        [add(2, 3), indirect, add.name, typeof add]
+    "#,
+        None,
+    )
+    .unwrap();
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete");
+    };
+    let JsValue::Object(arr) = &*value else {
+        panic!("Expected an array");
+    };
+    let elements: Vec<JsValue> = arr.borrow().array_elements().unwrap().to_vec();
+    assert_eq!(
+        elements,
+        vec![
+            JsValue::Number(5.0),
+            JsValue::Number(42.0),
+            JsValue::from("anonymous"),
+            JsValue::from("function"),
+        ]
+    );
+}
+
+#[test]
+fn test_dynamic_code_disabled_by_default_throws_eval_error() {
+    let mut interp = Interpreter::new();
+    let result = run(
+        &mut interp,
+        r#"
+        const outcomes: string[] = [];
+        for (const attempt of [
+            () => new Function("a", "b", "return a + b"),
+            () => Function("return 1"),
+            () => (0, eval)("1 + 1"),
+        ]) {
+            try {
+                attempt();
+                outcomes.push("compiled");
+            } catch (e) {
+                outcomes.push((e instanceof EvalError) + ": " + e.message);
+            }
+        }
+        outcomes.join("\n")
+    "#,
+        None,
+    )
+    .unwrap();
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(
+        *value,
+        JsValue::from(
+            "true: The Function constructor is not allowed: code generation from strings is disabled\n\
+             true: The Function constructor is not allowed: code generation from strings is disabled\n\
+             true: Indirect eval is not allowed: code generation from strings is disabled"
+        )
+    );
+}
+
+#[test]
+fn test_dynamic_code_disabled_leaves_direct_eval_and_non_strings() {
+    let mut interp = Interpreter::new();
+    let result = run(&mut interp, "eval('20 + 1') + (0, eval)(21)", None).unwrap();
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(*value, JsValue::Number(42.0));
+}
+
+#[test]
+fn test_dynamic_function_error_stack_names_dynamic_source() {
+    let mut interp = dynamic_code_interpreter(true);
+    let err = run(
+        &mut interp,
+        r#"
+        const pick = new Function("config", "return config.spec.replicas");
+        function render(config: any) {
+            return pick(config);
+        }
+        render({});
+    "#,
+        None,
+    )
+    .unwrap_err();
+    let message = err.to_string();
+    let mut lines = message.lines();
+    assert_eq!(
+        lines.next(),
+        Some(
+            "TypeError: Cannot read properties of undefined (reading 'replicas' of 'config.spec')"
+        )
+    );
+    assert_eq!(lines.next(), Some("    at anonymous (<dynamic>:1:38)"));
+    assert_eq!(lines.next(), Some("    at render (<eval>:4:20)"));
+}
+
+#[test]
+fn test_dynamic_function_respects_fuel() {
+    let mut interp = dynamic_code_interpreter(true);
+    interp.set_fuel(Some(10_000));
+    let err = run(
+        &mut interp,
+        r#"
+        const spin = new Function("let i = 0; while (true) { i++; }");
+        try { spin(); } catch (e) {}
+    "#,
+        None,
+    )
+    .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            JsError::Terminated {
+                reason: TerminationReason::FuelExhausted
+            }
+        ),
+        "{}",
+        err
+    );
+}
+
 // ============================================================
 // Function.prototype methods on proxied functions
 // ============================================================
@@ -1033,6 +1174,22 @@ fn test_arrow_function_length() {
     );
 }
 
+#[test]
+fn test_function_length_stops_at_default_or_rest() {
+    // Only parameters before the first default or rest parameter count
+    assert_eq!(
+        eval(
+            r#"
+            function f(a: number, b = 1, c?: number) {}
+            const g = (a: number, ...rest: number[]) => a;
+            class C { constructor(a: string, b = 0) {} }
+            [f.length, g.length, C.length].join()
+        "#
+        ),
+        JsValue::from("1,1,1")
+    );
+}
+
 // NOTE: Method shorthand name inference is not yet implemented
 // `{ myMethod() {} }` should give myMethod.name = "myMethod" but currently is ""
 
@@ -1737,3 +1894,500 @@ fn test_call_bind_get_own_property_descriptor() {
         JsValue::Number(1.0)
     );
 }
+
+// ============================================================
+// Function.prototype.toString
+// ============================================================
+
+#[test]
+fn test_function_to_string_declarations() {
+    assert_eq!(
+        eval("function add(a: number, b: number): number { return a + b; } add.toString()"),
+        JsValue::from("function add(a: number, b: number): number { return a + b; }")
+    );
+    assert_eq!(
+        eval("async function load() { await 1; } String(load)"),
+        JsValue::from("async function load() { await 1; }")
+    );
+    assert_eq!(
+        eval("function* gen() { yield 1; } `${gen}`"),
+        JsValue::from("function* gen() { yield 1; }")
+    );
+}
+
+#[test]
+fn test_function_to_string_expressions_and_arrows() {
+    assert_eq!(
+        eval("const f = function named(x) { return x; }; f.toString()"),
+        JsValue::from("function named(x) { return x; }")
+    );
+    assert_eq!(
+        eval("((a, b) => a + b).toString()"),
+        JsValue::from("(a, b) => a + b")
+    );
+    assert_eq!(
+        eval("(async x => { await x; }).toString()"),
+        JsValue::from("async x => { await x; }")
+    );
+}
+
+#[test]
+fn test_function_to_string_methods() {
+    assert_eq!(
+        eval(
+            r#"
+            const o = { m(a) { return a; }, async *ag() {}, get x() { return 1; } };
+            [o.m, o.ag, Object.getOwnPropertyDescriptor(o, "x").get].map(String).join(" | ")
+        "#
+        ),
+        JsValue::from("m(a) { return a; } | async *ag() {} | get x() { return 1; }")
+    );
+    assert_eq!(
+        eval(
+            r#"
+            class K { m() { return 1; } static s() {} private async p() {} }
+            [K.prototype.m, K.s, (K.prototype as any).p].map(String).join(" | ")
+        "#
+        ),
+        JsValue::from("m() { return 1; } | s() {} | async p() {}")
+    );
+}
+
+#[test]
+fn test_function_to_string_classes() {
+    assert_eq!(
+        eval("class Point { constructor(public x: number) {} } Point.toString()"),
+        JsValue::from("class Point { constructor(public x: number) {} }")
+    );
+    assert_eq!(
+        eval("const C = class {}; C.toString()"),
+        JsValue::from("class {}")
+    );
+}
+
+#[test]
+fn test_function_to_string_native_code() {
+    assert_eq!(
+        eval("Math.max.toString()"),
+        JsValue::from("function max() { [native code] }")
+    );
+    assert_eq!(
+        eval("function add() {} add.bind(null).toString()"),
+        JsValue::from("function bound add() { [native code] }")
+    );
+    assert_eq!(
+        eval(r#"new Function("a", "return a").toString()"#),
+        JsValue::from("function anonymous(a) { return a }")
+    );
+    assert_eq!(
+        eval(
+            r#"
+            let msg = "";
+            try { Function.prototype.toString.call({}); } catch (e) { msg = e.name; }
+            msg
+        "#
+        ),
+        JsValue::from("TypeError")
+    );
+}
+
+#[test]
+fn test_function_to_string_without_retained_source() {
+    let config = tsrun::InterpreterConfig {
+        retain_function_source: false,
+        ..Default::default()
+    };
+    let mut interp = Interpreter::with_config(config);
+    let result = run(
+        &mut interp,
+        "function add(a, b) { return a + b; } add.toString()",
+        None,
+    )
+    .unwrap();
+    match result {
+        StepResult::Complete(value) => {
+            assert_eq!(value, JsValue::from("function add() { [native code] }"));
+        }
+        _ => panic!("Expected Complete"),
+    }
+}
+
+/// Read a property of an object value, as the host sees it
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn payload_prop(payload: &JsValue, key: &str) -> tsrun::RuntimeValue {
+    let JsValue::Object(obj) = payload else {
+        panic!("Expected an object payload, got {:?}", payload);
+    };
+    let value = obj
+        .borrow()
+        .get_property(&tsrun::value::PropertyKey::String(JsString::from(key)))
+        .unwrap();
+    tsrun::RuntimeValue::unguarded(value)
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_function_source_of_callbacks_in_order_payload() {
+    let source = r#"import { order } from "tsrun:host";
+const label = "déploy";
+await order({
+    steps: () => {
+        console.log(label);
+    },
+    methods: { build(x: number) { return x * 2; } },
+    later: async function later() { return 1; },
+    native: Math.max,
+    bound: ((a: number) => a).bind(null),
+});
+"#;
+    let mut interp = Interpreter::new();
+    let result = run(&mut interp, source, Some("/app/deploy.ts")).unwrap();
+    let StepResult::Suspended { pending, .. } = result else {
+        panic!("Expected Suspended, got {:?}", result);
+    };
+    let payload = pending[0].payload.value();
+
+    let steps = payload_prop(payload, "steps").function_source().unwrap();
+    assert_eq!(steps.text(), "() => {\n        console.log(label);\n    }");
+    assert_eq!(steps.path(), Some("/app/deploy.ts"));
+    let span = steps.span();
+    assert_eq!(
+        (
+            span.start_line,
+            span.start_column,
+            span.end_line,
+            span.end_column
+        ),
+        (4, 12, 6, 6)
+    );
+    assert_eq!(source.get(span.start..span.end), Some(steps.text()));
+
+    let methods = payload_prop(payload, "methods");
+    let JsValue::Object(methods) = methods.value() else {
+        panic!("Expected methods object");
+    };
+    let build = methods
+        .borrow()
+        .get_property(&tsrun::value::PropertyKey::String(JsString::from("build")))
+        .unwrap();
+    let build = tsrun::RuntimeValue::unguarded(build)
+        .function_source()
+        .unwrap();
+    assert_eq!(build.text(), "build(x: number) { return x * 2; }");
+    assert_eq!(build.span().start_line, 7);
+    assert_eq!(build.span().start_column, 16);
+
+    let later = payload_prop(payload, "later").function_source().unwrap();
+    assert_eq!(later.text(), "async function later() { return 1; }");
+    assert_eq!(
+        (later.span().start_line, later.span().start_column),
+        (8, 12)
+    );
+
+    assert!(payload_prop(payload, "native").function_source().is_none());
+    assert!(payload_prop(payload, "bound").function_source().is_none());
+    assert!(payload_prop(payload, "methods").function_source().is_none());
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_function_source_requires_retained_source() {
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        retain_function_source: false,
+        ..Default::default()
+    });
+    let result = run(&mut interp, "(x => x + 1)", None).unwrap();
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete");
+    };
+    assert!(value.function_source().is_none());
+}
+
+#[test]
+fn test_recursion_returning_from_inside_blocks() {
+    // Returning from inside block scopes must leave no scope behind, even when the
+    // function's environment is reused by the next call
+    assert_eq!(
+        eval(
+            r#"
+            function depth(n: number): number {
+                if (n > 0) {
+                    const inner = n;
+                    for (const k of [1]) {
+                        return depth(inner - k) + 1;
+                    }
+                }
+                return 0;
+            }
+            let total = 0;
+            for (let i = 0; i < 20; i++) total += depth(i);
+            total
+        "#
+        ),
+        JsValue::Number(190.0)
+    );
+}
+
+#[test]
+fn test_nested_call_rest_and_arguments() {
+    // Callbacks invoked by builtins use the nested call path
+    assert_eq!(
+        eval(
+            r#"
+            function tail(first: number, ...rest: number[]) { return first + ":" + rest.join("|"); }
+            function count() { return arguments.length; }
+            const a = [[1, 2, 3], [4], [5, 6]].map(xs => tail(...xs)).join(",");
+            const b = [1, 2].map(count).join(",");
+            a + " " + b
+        "#
+        ),
+        JsValue::from("1:2|3,4:,5:6 3,3")
+    );
+}
+
+#[test]
+fn test_recursion_recovers_from_throw() {
+    // Frames unwound by an exception hand their environments back too
+    assert_eq!(
+        eval(
+            r#"
+            function down(n: number): number {
+                if (n === 0) throw new Error("bottom");
+                return down(n - 1) + 1;
+            }
+            function sum(n: number): number { return n === 0 ? 0 : n + sum(n - 1); }
+            let caught = 0;
+            for (let i = 1; i <= 10; i++) {
+                try { down(i); } catch (e) { caught++; }
+            }
+            caught + sum(100)
+        "#
+        ),
+        JsValue::Number(5060.0)
+    );
+}
+
+#[test]
+fn test_named_evaluation_table() {
+    // Each form gives its anonymous function a name and a length that stops
+    // at the first default or rest parameter
+    let cases = [
+        ("const f = (x, y) => x;", "f", "f", 2.0),
+        ("const f = function (a, b = 1, c) {};", "f", "f", 1.0),
+        ("let f; f = (...rest) => rest;", "f", "f", 0.0),
+        ("let f = null; f ??= function (a) {};", "f", "f", 1.0),
+        ("let f = 0; f ||= (a, b) => a;", "f", "f", 2.0),
+        ("const o = { m(a) {} };", "o.m", "m", 1.0),
+        ("const o = { p: (a, b) => a };", "o.p", "p", 2.0),
+        (
+            "const o = { 'two words': function () {} };",
+            "o['two words']",
+            "two words",
+            0.0,
+        ),
+        ("const o = { 42: () => 0 };", "o[42]", "42", 0.0),
+        (
+            "const o = { ['ca' + 'lc']: (a) => a };",
+            "o.calc",
+            "calc",
+            1.0,
+        ),
+        (
+            "const o = { [Symbol.iterator]: function () {} };",
+            "o[Symbol.iterator]",
+            "[Symbol.iterator]",
+            0.0,
+        ),
+        (
+            "const o = { [Symbol()]: () => 0 }; const s = Object.getOwnPropertySymbols(o)[0];",
+            "o[s]",
+            "",
+            0.0,
+        ),
+        (
+            "const o = { get size() { return 1; } };",
+            "Object.getOwnPropertyDescriptor(o, 'size').get",
+            "get size",
+            0.0,
+        ),
+        (
+            "const o = { set size(v) {} };",
+            "Object.getOwnPropertyDescriptor(o, 'size').set",
+            "set size",
+            1.0,
+        ),
+        (
+            "const o = { get [Symbol.toStringTag]() { return 'O'; } };",
+            "Object.getOwnPropertyDescriptor(o, Symbol.toStringTag).get",
+            "get [Symbol.toStringTag]",
+            0.0,
+        ),
+        ("class C { run(a, b) {} }", "C.prototype.run", "run", 2.0),
+        (
+            "class C { get total() { return 0; } }",
+            "Object.getOwnPropertyDescriptor(C.prototype, 'total').get",
+            "get total",
+            0.0,
+        ),
+        (
+            "class C { static set level(v) {} }",
+            "Object.getOwnPropertyDescriptor(C, 'level').set",
+            "set level",
+            1.0,
+        ),
+        (
+            "class C { *[Symbol.iterator]() {} }",
+            "C.prototype[Symbol.iterator]",
+            "[Symbol.iterator]",
+            0.0,
+        ),
+        (
+            "class C { handler = (e) => e; }",
+            "new C().handler",
+            "handler",
+            1.0,
+        ),
+        (
+            "class C { static make = function (a, b) {}; }",
+            "C.make",
+            "make",
+            2.0,
+        ),
+        (
+            "class C { #check = () => 0; read() { return this.#check; } }",
+            "new C().read()",
+            "#check",
+            0.0,
+        ),
+        ("const { f = (a) => a } = {};", "f", "f", 1.0),
+        ("const [f = function () {}] = [];", "f", "f", 0.0),
+        ("const K = class {};", "K", "K", 0.0),
+        ("function f(a, { b }, c = 2, d) {}", "f", "f", 2.0),
+        ("const f = ((a) => a).bind(null);", "f", "bound ", 1.0),
+        (
+            "const g = (a, b, c) => a; const f = g.bind(null, 1);",
+            "f",
+            "bound g",
+            2.0,
+        ),
+        ("const f = (0, function () {});", "f", "", 0.0),
+    ];
+
+    for (setup, target, name, length) in cases {
+        assert_eq!(
+            eval(&format!("{} {}.name", setup, target)),
+            JsValue::from(name),
+            "name of {} in `{}`",
+            target,
+            setup
+        );
+        assert_eq!(
+            eval(&format!("{} {}.length", setup, target)),
+            JsValue::Number(length),
+            "length of {} in `{}`",
+            target,
+            setup
+        );
+    }
+}
+
+#[test]
+fn test_own_name_overrides_inferred_name() {
+    // A class's static `name` member and Object.defineProperty win over inference
+    assert_eq!(
+        eval(
+            r#"
+            const o = { ["k"]: class { static name() {} } };
+            const f = function () {};
+            Object.defineProperty(f, "name", { value: "custom" });
+            [typeof o.k.name, f.name].join("|")
+        "#
+        ),
+        JsValue::from("function|custom")
+    );
+}
+
+#[test]
+fn test_error_stack_shows_inferred_arrow_names() {
+    let mut interp = Interpreter::new();
+    let err = run(
+        &mut interp,
+        r#"
+        const render = () => { throw new Error("boom"); };
+        let handler;
+        handler = () => render();
+        const api = { load: () => handler() };
+        api.load();
+    "#,
+        None,
+    )
+    .unwrap_err();
+    let message = err.to_string();
+    let frames: Vec<&str> = message
+        .lines()
+        .skip(1)
+        .map(|line| line.trim_start().split(' ').nth(1).unwrap_or(""))
+        .collect();
+    assert_eq!(frames, ["render", "handler", "load", "<anonymous>"]);
+}
+
+#[test]
+fn test_apply_accepts_array_likes() {
+    assert_eq!(
+        eval(
+            r#"
+            function join(...a: any[]): string { return a.join(","); }
+            function forward(): string { return join.apply(null, arguments as any); }
+            [
+                join.apply(null, { length: 3, 0: "x", 2: "z" } as any),
+                forward(1, 2),
+                join.apply(null, new String("ab") as any),
+                join.apply(null, undefined),
+                Reflect.apply(join, null, { length: 1, 0: 9 }),
+                Reflect.construct(Array, { length: 2, 0: "p", 1: "q" }).join("+"),
+            ].join("|")
+        "#
+        ),
+        JsValue::from("x,,z|1,2|a,b||9|p+q")
+    );
+    assert_eq!(
+        eval(
+            r#"
+            const errors: string[] = [];
+            function f() {}
+            for (const run of [
+                () => f.apply(null, "ab" as any),
+                () => Reflect.apply(f, null, undefined as any),
+                () => Reflect.construct(Object, 1 as any),
+            ]) {
+                try { run(); } catch (e) { errors.push(e.name); }
+            }
+            errors.join(",")
+        "#
+        ),
+        JsValue::from("TypeError,TypeError,TypeError")
+    );
+}
+
+#[test]
+fn test_spread_arguments_from_iterables() {
+    assert_eq!(
+        eval(
+            r#"
+            function join(...a: any[]): string { return a.join(","); }
+            class Pair { v: string; constructor(a: any, b: any) { this.v = a + "-" + b; } }
+            class Named extends Pair { constructor(...a: any[]) { super(...a); } }
+            function* gen() { yield 5; yield 6; }
+            [
+                join(...new Set([1, 2])),
+                join(...gen(), 7),
+                join(..."ok"),
+                String.fromCharCode(...[104, 105]),
+                new Pair(...new Map([["k", 1]]).keys(), "v").v,
+                new Named(...gen()).v,
+            ].join("|")
+        "#
+        ),
+        JsValue::from("1,2|5,6,7|o,k|hi|k-v|5-6")
+    );
+}
@@ -1,7 +1,11 @@
 //! Tests for garbage collection of JavaScript objects
 
 use super::run;
-use tsrun::{GcStats, Interpreter, JsString, JsValue, RuntimeValue, StepResult};
+use tsrun::platform::TimeProvider;
+use tsrun::{
+    CollectOutcome, GcStats, Interpreter, JsString, JsValue, OrderDelivery, RuntimeValue,
+    StepResult,
+};
 
 /// Get baseline object count (builtins only, no user code)
 fn get_baseline_live_count() -> usize {
@@ -37,10 +41,10 @@ fn test_gc_stats_available() {
 fn test_baseline_object_count() {
     let baseline = get_baseline_live_count();
     println!("Baseline live count (builtins only): {}", baseline);
-    // Builtins include: global, prototypes, constructors, Math, JSON, console, Boolean, etc.
-    // This should be stable and typically around 100-300
+    // Builtins include: global, prototypes, constructors, Math, JSON, console, Boolean, URL, etc.
+    // This should be stable and typically around 100-400
     assert!(baseline > 50, "Should have some builtins");
-    assert!(baseline < 350, "Baseline should be bounded");
+    assert!(baseline < 450, "Baseline should be bounded");
 }
 
 #[test]
@@ -1232,6 +1236,240 @@ fn test_gc_threshold_1_try_catch_with_throw() {
     assert_eq!(result, JsValue::String(JsString::from("error message")));
 }
 
+// ═══════════════════════════════════════════════════════════════════════════════
+// Array callbacks that mutate the array under gc_threshold=1
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Allocates enough garbage to force several collections at threshold 1
+const CHURN: &str = r#"
+    function churn() {
+        const junk: any[] = [];
+        for (let i = 0; i < 10; i++) junk.push({ i });
+    }
+"#;
+
+fn eval_churning(source: &str) -> RuntimeValue {
+    eval_with_threshold_1(&format!("{}{}", CHURN, source))
+}
+
+#[test]
+fn test_gc_threshold_1_sort_comparator_truncates_array() {
+    let result = eval_churning(
+        r#"
+        const arr = [{ v: 3 }, { v: 1 }, { v: 2 }, { v: 5 }, { v: 4 }];
+        arr.sort((a, b) => {
+            arr.length = 0;
+            churn();
+            return a.v - b.v;
+        });
+        arr.map(o => o.v).join()
+    "#,
+    );
+    assert_eq!(result, JsValue::String(JsString::from("1,2,3,4,5")));
+}
+
+#[test]
+fn test_gc_threshold_1_to_sorted_comparator_truncates_source() {
+    let result = eval_churning(
+        r#"
+        const arr = [{ v: 3 }, { v: 1 }, { v: 2 }];
+        const sorted = arr.toSorted((a, b) => {
+            arr.length = 0;
+            churn();
+            return a.v - b.v;
+        });
+        arr.length + ":" + sorted.map(o => o.v).join()
+    "#,
+    );
+    assert_eq!(result, JsValue::String(JsString::from("0:1,2,3")));
+}
+
+#[test]
+fn test_gc_threshold_1_map_callback_pushes() {
+    let result = eval_churning(
+        r#"
+        const arr = [1, 2, 3];
+        const out = arr.map((x, i, a) => {
+            if (i === 0) a.push(4);
+            churn();
+            return { x };
+        });
+        out.length + ":" + out.map(o => o.x).join()
+    "#,
+    );
+    assert_eq!(result, JsValue::String(JsString::from("3:1,2,3")));
+}
+
+#[test]
+fn test_gc_threshold_1_filter_callback_splices() {
+    let result = eval_churning(
+        r#"
+        const arr = [{ n: 1 }, { n: 2 }, { n: 3 }, { n: 4 }];
+        arr.filter((o, i, a) => {
+            if (i === 0) a.splice(1, 1);
+            churn();
+            return true;
+        }).map(o => o.n).join()
+    "#,
+    );
+    assert_eq!(result, JsValue::String(JsString::from("1,3,4")));
+}
+
+#[test]
+fn test_gc_threshold_1_foreach_callback_pops_and_pushes() {
+    let result = eval_churning(
+        r#"
+        const seen: any[] = [];
+        [1, 2, 3].forEach((x, i, a) => {
+            seen.push({ x });
+            if (i === 0) {
+                a.pop();
+                a.push(9, 10);
+            }
+            churn();
+        });
+        seen.map(o => o.x).join()
+    "#,
+    );
+    assert_eq!(result, JsValue::String(JsString::from("1,2,9")));
+}
+
+#[test]
+fn test_gc_threshold_1_reduce_fresh_accumulator_while_truncating() {
+    let result = eval_churning(
+        r#"
+        [1, 2, 3, 4].reduce((acc: any, x, i, a) => {
+            if (i === 1) a.length = 3;
+            churn();
+            return { sum: acc.sum + x };
+        }, { sum: 0 }).sum
+    "#,
+    );
+    assert_eq!(result, JsValue::Number(6.0));
+}
+
+#[test]
+fn test_gc_threshold_1_reduce_right_callback_shifts() {
+    let result = eval_churning(
+        r#"
+        [1, 2, 3, 4].reduceRight((acc: any, x, i, a) => {
+            if (i === 3) a.shift();
+            churn();
+            return { s: acc.s + x };
+        }, { s: 0 }).s
+    "#,
+    );
+    assert_eq!(result, JsValue::Number(13.0));
+}
+
+#[test]
+fn test_gc_threshold_1_some_and_every_mutate() {
+    let result = eval_churning(
+        r#"
+        const arr = [1, 2, 3];
+        const some = arr.some((x, i, a) => {
+            if (i === 0) a.length = 1;
+            churn();
+            return x > 1;
+        });
+        const b: any[] = [1, 2];
+        const every = b.every((x, i, a) => {
+            a.push({ x });
+            churn();
+            return true;
+        });
+        some + ":" + every + ":" + b.length
+    "#,
+    );
+    assert_eq!(result, JsValue::String(JsString::from("false:true:4")));
+}
+
+#[test]
+fn test_gc_threshold_1_find_returns_removed_element() {
+    let result = eval_churning(
+        r#"
+        const arr = [{ id: 1 }, { id: 2 }, { id: 3 }];
+        const visited: any[] = [];
+        const found = arr.find((o, i, a) => {
+            visited.push(o === undefined ? "u" : o.id);
+            if (i === 1) {
+                a.length = 0;
+                churn();
+                return true;
+            }
+            return false;
+        });
+        found.id + ":" + visited.join()
+    "#,
+    );
+    assert_eq!(result, JsValue::String(JsString::from("2:1,2")));
+}
+
+#[test]
+fn test_gc_threshold_1_find_last_returns_removed_element() {
+    let result = eval_churning(
+        r#"
+        const arr = [{ id: 1 }, { id: 2 }, { id: 3 }];
+        const found = arr.findLast((o, i, a) => {
+            if (i === 2) {
+                a.length = 1;
+                churn();
+            }
+            return o !== undefined;
+        });
+        found.id + ":" + arr.length
+    "#,
+    );
+    assert_eq!(result, JsValue::String(JsString::from("3:1")));
+}
+
+#[test]
+fn test_gc_threshold_1_flat_map_callback_pushes() {
+    let result = eval_churning(
+        r#"
+        [1, 2].flatMap((x, i, a) => {
+            if (i === 0) a.push(3);
+            churn();
+            return [{ x }, { x: x * 10 }];
+        }).map(o => o.x).join()
+    "#,
+    );
+    assert_eq!(result, JsValue::String(JsString::from("1,10,2,20")));
+}
+
+#[test]
+fn test_gc_threshold_1_join_element_to_string_truncates() {
+    let result = eval_churning(
+        r#"
+        const arr: any[] = [1, {
+            toString() {
+                arr.length = 1;
+                churn();
+                return "x";
+            }
+        }, 3];
+        arr.join("-")
+    "#,
+    );
+    assert_eq!(result, JsValue::String(JsString::from("1-x-")));
+}
+
+#[test]
+fn test_gc_threshold_1_array_from_map_fn_pushes() {
+    let result = eval_churning(
+        r#"
+        const src = [1, 2];
+        const out = Array.from(src, (x, i) => {
+            if (i === 0) src.push(3);
+            churn();
+            return { x };
+        });
+        out.map(o => o.x).join()
+    "#,
+    );
+    assert_eq!(result, JsValue::String(JsString::from("1,2,3")));
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // Test for loop environment leak
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -1578,6 +1816,90 @@ fn test_exception_unwind_registers_cleaned_up() {
     );
 }
 
+// ═══════════════════════════════════════════════════════════════════════════════
+// Call pool tests
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Count heap allocations made while running `source` on a fresh interpreter
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn allocations_during(source: &str) -> (JsValue, usize) {
+    let mut interp = Interpreter::new();
+    let before = interp.gc_stats().allocations;
+    let result = match run(&mut interp, source, None).unwrap() {
+        StepResult::Complete(rv) => rv.value().clone(),
+        other => panic!("Expected Complete, got {:?}", other),
+    };
+    (result, interp.gc_stats().allocations - before)
+}
+
+#[test]
+fn test_recursive_calls_reuse_environments() {
+    // fib(20) makes 21891 calls. Without environment reuse each one allocates an
+    // environment object; with it, only the first few frames of the deepest
+    // chain do.
+    let (result, allocations) = allocations_during(
+        r#"
+        function fib(n: number): number {
+            if (n < 2) return n;
+            return fib(n - 1) + fib(n - 2);
+        }
+        fib(20)
+    "#,
+    );
+    assert_eq!(result, JsValue::Number(6765.0));
+    assert!(
+        allocations < 1000,
+        "Recursion allocated {} objects; function environments aren't being reused",
+        allocations
+    );
+}
+
+#[test]
+fn test_nested_calls_reuse_environments() {
+    // Calls from builtins (here, Array.prototype.map) run on a nested VM
+    let (result, allocations) = allocations_during(
+        r#"
+        function square(x: number): number { return x * x; }
+        const xs: number[] = [];
+        for (var i = 0; i < 2000; i++) xs.push(i);
+        let total = 0;
+        for (var round = 0; round < 5; round++) {
+            total += xs.map(square)[1999];
+        }
+        total
+    "#,
+    );
+    assert_eq!(result, JsValue::Number(5.0 * 1999.0 * 1999.0));
+    assert!(
+        allocations < 2000,
+        "10000 nested calls allocated {} objects; function environments aren't being reused",
+        allocations
+    );
+}
+
+#[test]
+fn test_closures_keep_their_environment() {
+    // Functions that create closures must not hand their environment back
+    let result = eval_with_gc_stats(
+        r#"
+        function counter(start: number) {
+            let n = start;
+            return () => ++n;
+        }
+        function plain(x: number) { const y = x * 2; return y; }
+        const a = counter(10);
+        const b = counter(100);
+        // Plain calls in between would reuse a recycled environment
+        let junk = 0;
+        for (let i = 0; i < 50; i++) junk += plain(i);
+        a(); a(); b();
+        [a(), b(), junk].join(",")
+    "#,
+    )
+    .0;
+    assert_eq!(result, JsValue::from("13,102,2450"));
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // Size checks for memory optimization
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -1610,3 +1932,356 @@ fn test_type_sizes() {
         "PropertyStorage too large"
     );
 }
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_gc_census_counts_arrays_and_promises() {
+    let baseline = {
+        let interp = Interpreter::new();
+        interp.gc_census()
+    };
+
+    let mut interp = Interpreter::new();
+    run(
+        &mut interp,
+        r#"
+        const arrays: number[][] = [];
+        for (let i = 0; i < 50; i++) arrays.push([i]);
+        const promises: Promise<void>[] = [];
+        for (let i = 0; i < 20; i++) promises.push(new Promise(() => {}));
+        (globalThis as any).kept = { arrays, promises };
+        "#,
+        None,
+    )
+    .unwrap();
+    interp.collect();
+
+    let census = interp.gc_census();
+    // The 50 element arrays plus the two arrays holding them
+    assert_eq!(census.arrays - baseline.arrays, 52);
+    assert_eq!(census.promises - baseline.promises, 20);
+    assert!(census.properties > baseline.properties);
+    assert_eq!(census.objects(), interp.gc_stats().live_objects);
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_gc_retainers_report_unfulfilled_order_payload() {
+    let mut interp = Interpreter::new();
+    interp.set_order_delivery(OrderDelivery::Eager);
+    interp
+        .prepare(
+            r#"
+            import { order } from "tsrun:host";
+            function makeItems() {
+                const items = [];
+                for (let i = 0; i < 200; i++) items.push({ i });
+                return items;
+            }
+            order({ items: makeItems() });
+            "done";
+            "#,
+            None,
+        )
+        .unwrap();
+
+    // Never fulfill the order, as a host that lost track of it would
+    let mut orders = Vec::new();
+    let result = loop {
+        match interp.step().unwrap() {
+            StepResult::Continue => continue,
+            StepResult::Orders(new_orders) => orders.extend(new_orders),
+            result => break result,
+        }
+    };
+    assert!(matches!(result, StepResult::Suspended { .. }));
+    assert_eq!(orders.len(), 1);
+
+    let retainers = interp.gc_retainers(3);
+    let payload = retainers
+        .iter()
+        .find(|r| r.label == Some("order payload"))
+        .unwrap();
+    // The items array, its 200 elements and the payload object itself
+    assert_eq!(payload.retained, 202);
+
+    // Once the host drops the order, the payload is no longer retained
+    drop(orders);
+    assert!(
+        interp
+            .gc_retainers(usize::MAX)
+            .iter()
+            .all(|r| r.label != Some("order payload"))
+    );
+}
+
+/// Time provider where every measured interval takes 3ms
+struct FixedElapsed;
+
+impl TimeProvider for FixedElapsed {
+    fn now_millis(&self) -> i64 {
+        0
+    }
+
+    fn elapsed_millis(&self, _start: u64) -> u64 {
+        3
+    }
+
+    fn start_timer(&self) -> u64 {
+        0
+    }
+}
+
+/// Interpreter without automatic collection, holding `n` dead objects
+#[allow(clippy::unwrap_used)]
+fn interp_with_garbage(n: usize) -> Interpreter {
+    let mut interp = Interpreter::new();
+    interp.set_gc_threshold(0);
+    interp.collect();
+    let source = format!("for (let i = 0; i < {n}; i++) {{ const o = {{ i }}; }}");
+    run(&mut interp, &source, None).unwrap();
+    interp
+}
+
+#[test]
+fn test_maybe_collect_after_heavy_allocation() {
+    let interp = interp_with_garbage(5000);
+    let before = interp.gc_stats();
+    assert!(before.allocations_since_collect >= 5000);
+
+    let outcome = interp.maybe_collect(2.0, &FixedElapsed);
+    assert!(
+        matches!(outcome, CollectOutcome::Collected { duration_ms, freed } if duration_ms == 3.0 && freed >= 5000),
+        "{:?}",
+        outcome
+    );
+
+    let after = interp.gc_stats();
+    assert_eq!(after.collections, before.collections + 1);
+    assert_eq!(after.allocations_since_collect, 0);
+    assert_eq!(after.last_collect_ms, 3.0);
+    assert_eq!(after.total_collect_ms, 3.0);
+
+    // Nothing was allocated since, so a second call has nothing to do
+    assert_eq!(
+        interp.maybe_collect(2.0, &FixedElapsed),
+        CollectOutcome::NothingToDo
+    );
+    assert_eq!(interp.gc_stats().collections, after.collections);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_maybe_collect_skips_cycles_over_budget() {
+    let mut interp = interp_with_garbage(5000);
+    interp.maybe_collect(10.0, &FixedElapsed);
+
+    // The recorded 3ms cycle predicts the next one won't fit in 1ms
+    run(
+        &mut interp,
+        "for (let i = 0; i < 5000; i++) { const o = { i }; }",
+        None,
+    )
+    .unwrap();
+    let collections = interp.gc_stats().collections;
+    assert!(matches!(
+        interp.maybe_collect(1.0, &FixedElapsed),
+        CollectOutcome::OverBudget { estimated_ms } if estimated_ms > 1.0
+    ));
+    assert_eq!(interp.gc_stats().collections, collections);
+
+    assert!(matches!(
+        interp.maybe_collect(10.0, &FixedElapsed),
+        CollectOutcome::Collected { .. }
+    ));
+}
+
+#[test]
+fn test_promise_reactions_survive_gc_while_earlier_ones_run() {
+    // The second reaction is only reachable from the settled promise's
+    // handler list while the first one runs and allocates
+    let source = r#"
+        let resolve;
+        const shared = new Promise(r => { resolve = r; });
+        const a = shared.then(v => ({ v }));
+        const b = shared.then(v => [v, v + 1]);
+        resolve(1);
+        const [x, y] = await Promise.all([a, b]);
+        x.v + y[1]
+    "#;
+
+    let mut interp = Interpreter::new();
+    interp.set_gc_threshold(1);
+
+    let result_step = run(&mut interp, source, None).unwrap();
+    let result = if let StepResult::Complete(rv) = result_step {
+        rv
+    } else {
+        panic!("Expected Complete, got {:?}", result_step);
+    };
+    assert_eq!(result, JsValue::Number(3.0));
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Environment guard balance
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Scripts that leave a construct by throwing, or by suspending inside a scope
+const UNBALANCING_CONSTRUCTS: &[&str] = &[
+    // Top-level block and for-of over a Map
+    r#"{ const marker = "block"; throw new Error("block"); }"#,
+    r#"for (const [marker] of new Map([["loop", 1]])) { let z = marker; throw new Error("loop"); }"#,
+    // Class static blocks
+    r#"class A { static { const marker = "static"; throw new Error("static"); } }"#,
+    // Throwing from finally, inside a function and at top level
+    r#"function f() { try { let a = 1; return a; } finally { const marker = "fin"; throw 3; } } f()"#,
+    r#"try { try { let a = 1; throw 1; } finally { let b = 2; throw 2; } } catch (e) {} throw 4"#,
+    // Callbacks and eval
+    r#"[1].map(x => { const marker = "cb"; throw x; })"#,
+    r#"eval('const marker = "eval"; throw 2')"#,
+    // Generators left suspended inside a block, then thrown into
+    r#"
+        function* g() { let x = 1; { const marker = "gen"; yield x; } yield x; }
+        function h() { const it = g(); it.next(); return 1; }
+        h(); h();
+        const it = g(); it.next(); it.throw(new Error("gen"));
+    "#,
+    // Async functions rejecting after an await inside a block
+    r#"
+        async function f() { { const marker = "async"; await null; throw 4; } }
+        await f();
+    "#,
+];
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_throwing_constructs_leave_env_stack_balanced() {
+    for source in UNBALANCING_CONSTRUCTS {
+        let mut interp = Interpreter::new();
+        interp.set_gc_threshold(1);
+        run(&mut interp, r#"globalThis.marker = "global";"#, None).unwrap();
+
+        assert!(run(&mut interp, source, None).is_err(), "{}", source);
+        assert_eq!(interp.env_depth(), 0, "{}", source);
+
+        // A follow-up run resolves names in the global scope again
+        let StepResult::Complete(value) = run(
+            &mut interp,
+            "marker + ':' + typeof Math + ':' + [1, 2].map(x => x * 2).join()",
+            None,
+        )
+        .unwrap() else {
+            panic!("Expected Complete after {}", source);
+        };
+        assert_eq!(value, JsValue::from("global:object:2,4"), "{}", source);
+        assert_eq!(interp.env_depth(), 0, "{}", source);
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_suspended_generator_scopes_do_not_accumulate() {
+    // Each generator left parked at a yield inside a block used to keep its
+    // environments on the guard stack, and with them everything they reach
+    let source = r#"
+        function* g() { { const big = new Array(1000).fill(0); yield big.length; } }
+        let total = 0;
+        for (let i = 0; i < 50; i++) {
+            const it = g();
+            total += it.next().value;
+        }
+        total
+    "#;
+    let mut interp = Interpreter::new();
+    let StepResult::Complete(value) = run(&mut interp, source, None).unwrap() else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(value, JsValue::Number(50_000.0));
+    assert_eq!(interp.env_depth(), 0);
+
+    drop(value);
+    interp.collect();
+    let live = interp.gc_stats().live_objects;
+    assert!(
+        live < get_baseline_live_count() + 200,
+        "parked generators kept {} objects alive",
+        live
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_completed_generators_release_captured_scope() {
+    // Spent generators kept in an array must not keep the scope their
+    // function closed over, whether they finished, threw or returned
+    let source = r#"
+        function make(): any[] {
+            const big = Array.from({ length: 100000 }, (_, i) => ({ i }));
+            function* g() { yield big.length; }
+            function* h() { try { yield big[0]; } finally { big.length; } }
+            async function* a() { yield big.length; }
+            return [g(), h(), h(), a()];
+        }
+        const [done, thrown, returned, asyncDone] = make();
+        for (const _ of done) {}
+        thrown.next();
+        try { thrown.throw(new Error("stop")); } catch {}
+        returned.next();
+        returned.return(0);
+        while (!(await asyncDone.next()).done) {}
+        [done, thrown, returned, asyncDone]
+    "#;
+    let mut interp = Interpreter::new();
+    let StepResult::Complete(generators) = run(&mut interp, source, None).unwrap() else {
+        panic!("Expected Complete");
+    };
+
+    // The generator objects stay reachable from the result
+    interp.collect();
+    let live = interp.gc_stats().live_objects;
+    assert!(
+        live < get_baseline_live_count() + 1000,
+        "spent generators kept {} objects alive",
+        live
+    );
+    drop(generators);
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_object_id_not_reused_with_slot() {
+    let mut interp = Interpreter::new();
+    let object_ids = |interp: &mut Interpreter, source: &str| -> Vec<u64> {
+        let StepResult::Complete(objects) = run(interp, source, None).unwrap() else {
+            panic!("Expected Complete");
+        };
+        tsrun::api::get_elements(objects.value())
+            .unwrap()
+            .into_iter()
+            .map(|object| {
+                RuntimeValue::unguarded(object)
+                    .object_id()
+                    .unwrap()
+                    .as_u64()
+            })
+            .collect()
+    };
+
+    let first = object_ids(&mut interp, "Array.from({ length: 50 }, (_, i) => ({ i }))");
+    // The next run releases the first completion value
+    run(&mut interp, "0", None).unwrap();
+    interp.collect();
+    let second = object_ids(
+        &mut interp,
+        "Array.from({ length: 200 }, (_, i) => ({ i }))",
+    );
+
+    // Slots of the collected objects went to new objects, under new ids
+    let slot = |id: &u64| id >> 32;
+    assert!(
+        second
+            .iter()
+            .any(|id| first.iter().any(|old| slot(old) == slot(id)))
+    );
+    assert!(second.iter().all(|id| !first.contains(id)));
+}
@@ -276,6 +276,31 @@ fn test_generator_return_done() {
     );
 }
 
+#[test]
+fn test_generator_return_runs_finally() {
+    assert_eq!(
+        eval(
+            r#"
+            const log: string[] = [];
+            function* gen(): Generator<number> {
+                try {
+                    yield 1;
+                    yield 2;
+                } finally {
+                    log.push("cleanup");
+                }
+            }
+            const g = gen();
+            g.next();
+            const r = g['return'](7);
+            const after = g.next();
+            [r.value, r.done, after.done, log.join()].join(",")
+        "#
+        ),
+        JsValue::from("7,true,true,cleanup")
+    );
+}
+
 // Generator.prototype.throw() - simplified test
 // Note: Full throw() implementation with try-catch integration is complex
 #[test]
@@ -12,11 +12,73 @@ fn test_parseint() {
     assert_eq!(eval("parseInt('101', 2)"), JsValue::Number(5.0));
 }
 
+#[test]
+fn test_parseint_radix_and_prefix_table() {
+    let cases: &[(&str, &str)] = &[
+        // No radix: decimal, except for a 0x prefix; a leading 0 is not octal
+        ("parseInt('08')", "8"),
+        ("parseInt('0x1f')", "31"),
+        ("parseInt('-0X1F')", "-31"),
+        ("parseInt('0x')", "NaN"),
+        ("parseInt('0b11')", "0"),
+        // Whitespace skipped, the valid prefix kept
+        ("parseInt(' 42abc')", "42"),
+        ("parseInt('\\t\\n\\uFEFF 7')", "7"),
+        ("parseInt('\\u0085 7')", "NaN"),
+        ("parseInt('+12px')", "12"),
+        ("parseInt('1e3')", "1"),
+        ("parseInt('abc')", "NaN"),
+        ("parseInt('')", "NaN"),
+        ("parseInt('-')", "NaN"),
+        // Explicit radix
+        ("parseInt('0x1f', 16)", "31"),
+        ("parseInt('0x1f', 10)", "0"),
+        ("parseInt('z', 36)", "35"),
+        ("parseInt('ZZ', 36)", "1295"),
+        ("parseInt('2', 2)", "NaN"),
+        ("parseInt('777', 8)", "511"),
+        ("parseInt('10', 37)", "NaN"),
+        ("parseInt('10', 1)", "NaN"),
+        ("parseInt('10', 0)", "10"),
+        ("parseInt('10', 4294967312)", "16"),
+        ("parseInt('11', '2')", "3"),
+        // Precision beyond 2^53 rounds like the decimal literal
+        ("parseInt('9007199254740993')", "9007199254740992"),
+        (
+            "parseInt('123456789012345678901234567890')",
+            "1.2345678901234568e+29",
+        ),
+    ];
+    for (source, expected) in cases {
+        assert_eq!(
+            eval(&format!("String({})", source)),
+            JsValue::from(*expected),
+            "{}",
+            source
+        );
+    }
+    assert_eq!(
+        eval("Object.is(parseInt('-0'), -0)"),
+        JsValue::Boolean(true)
+    );
+}
+
 #[test]
 fn test_parsefloat() {
     assert_eq!(eval("parseFloat('3.15')"), JsValue::Number(3.15));
     assert_eq!(eval("parseFloat('  3.15  ')"), JsValue::Number(3.15));
     assert_eq!(eval("parseFloat('3.15abc')"), JsValue::Number(3.15));
+    assert_eq!(
+        eval("[parseFloat('Infinityx'), parseFloat('-Infinity'), parseFloat('e5')].join()"),
+        JsValue::from("Infinity,-Infinity,NaN")
+    );
+    // An exponent without digits is not part of the number
+    assert_eq!(
+        eval(
+            "[parseFloat('1e'), parseFloat('1e+'), parseFloat('.5.'), parseFloat('  -.5e-1x')].join()"
+        ),
+        JsValue::from("1,1,0.5,-0.05")
+    );
 }
 
 #[test]
@@ -85,6 +147,67 @@ fn test_decodeuricomponent() {
     );
 }
 
+#[test]
+fn test_uri_multibyte_utf8() {
+    assert_eq!(eval("encodeURIComponent('€')"), JsValue::from("%E2%82%AC"));
+    assert_eq!(
+        eval("encodeURIComponent('😀')"),
+        JsValue::from("%F0%9F%98%80")
+    );
+    assert_eq!(eval("decodeURIComponent('%E2%82%AC')"), JsValue::from("€"));
+    assert_eq!(eval("decodeURI('%F0%9F%98%80')"), JsValue::from("😀"));
+    assert_eq!(
+        eval("decodeURIComponent(encodeURIComponent('naïve café ✓'))"),
+        JsValue::from("naïve café ✓")
+    );
+}
+
+#[test]
+fn test_decodeuri_preserves_reserved_escapes() {
+    assert_eq!(
+        eval("decodeURI('%3Fa%2fb%20c%23d')"),
+        JsValue::from("%3Fa%2fb c%23d")
+    );
+    assert_eq!(
+        eval("decodeURI('https://x.org/?q=%E2%82%AC%26')"),
+        JsValue::from("https://x.org/?q=€%26")
+    );
+}
+
+#[test]
+fn test_decodeuri_malformed_throws_uri_error() {
+    assert_eq!(
+        eval(
+            r#"
+            const names: string[] = [];
+            for (const input of ["%", "%E2%82", "%ZZ", "%C0%80", "%80"]) {
+                try { decodeURIComponent(input); names.push("ok"); }
+                catch (e) { names.push(e.name); }
+            }
+            names.join()
+        "#
+        ),
+        JsValue::from("URIError,URIError,URIError,URIError,URIError")
+    );
+}
+
+#[test]
+fn test_decodeuri_malformed_error_is_uri_error_instance() {
+    assert_eq!(
+        eval(
+            r#"
+            const results: boolean[] = [];
+            for (const decode of [decodeURI, decodeURIComponent]) {
+                try { decode("%"); results.push(false); }
+                catch (e) { results.push(e instanceof URIError && e instanceof Error); }
+            }
+            results.join()
+        "#
+        ),
+        JsValue::from("true,true")
+    );
+}
+
 // btoa tests (base64 encode)
 #[test]
 fn test_btoa_basic() {
@@ -335,3 +458,199 @@ fn test_structured_clone_error_types() {
         JsValue::from("type error")
     );
 }
+
+#[test]
+fn test_structured_clone_error_cause_chain() {
+    assert_eq!(
+        eval(
+            r#"
+            const root = new Error("disk full");
+            const mid = new RangeError("write failed", { cause: root });
+            const top: any = new TypeError("save failed", { cause: mid });
+            top.code = "E_SAVE";
+            const clone: any = structuredClone(top);
+            [
+                Object.getPrototypeOf(clone) === TypeError.prototype,
+                clone.name, clone.message, clone.code, clone.stack === top.stack,
+                clone.cause instanceof RangeError, clone.cause !== mid, clone.cause.message,
+                clone.cause.cause instanceof Error, clone.cause.cause.message,
+                Object.keys(clone).includes("cause"),
+            ].join()
+        "#
+        ),
+        JsValue::from(
+            "true,TypeError,save failed,E_SAVE,true,true,true,write failed,true,disk full,false"
+        )
+    );
+}
+
+#[test]
+fn test_structured_clone_aggregate_error() {
+    assert_eq!(
+        eval(
+            r#"
+            const errors = [new Error("a"), new TypeError("b"), new SyntaxError("c")];
+            const clone: any = structuredClone(new AggregateError(errors, "all failed"));
+            [
+                clone instanceof AggregateError, clone.message, clone.errors.length,
+                clone.errors[0] !== errors[0],
+                clone.errors.map((e: Error) => `${e.constructor.name}:${e.message}`).join("/"),
+            ].join()
+        "#
+        ),
+        JsValue::from("true,all failed,3,true,Error:a/TypeError:b/SyntaxError:c")
+    );
+}
+
+/// Interpreter whose entry scripts use `script_globals`
+fn script_globals_runtime() -> tsrun::Interpreter {
+    tsrun::Interpreter::with_config(tsrun::InterpreterConfig {
+        script_globals: true,
+        ..Default::default()
+    })
+}
+
+/// Run one script in `interp` and return its value
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn eval_script(interp: &mut tsrun::Interpreter, source: &str) -> JsValue {
+    match super::run(interp, source, None).unwrap() {
+        tsrun::StepResult::Complete(value) => value.value().clone(),
+        other => panic!("Expected completion, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_script_globals_declarations_persist() {
+    let mut interp = script_globals_runtime();
+    eval_script(
+        &mut interp,
+        "var x = 1; function f() { return x * 10; } let y = 2; const z = 3; class C {}",
+    );
+    assert_eq!(
+        eval_script(
+            &mut interp,
+            "[globalThis.x, typeof globalThis.f, 'y' in globalThis, 'C' in globalThis, f(), y + z, new C() instanceof C].join()"
+        ),
+        JsValue::from("1,function,false,false,10,5,true")
+    );
+
+    // Function declarations exist before the script's first statement runs
+    assert_eq!(
+        eval_script(
+            &mut interp,
+            "const early = g(); function g() { return 'hoisted'; } early"
+        ),
+        JsValue::from("hoisted")
+    );
+
+    // Assignments, from scripts and from functions, update the global property
+    assert_eq!(
+        eval_script(&mut interp, "x = 5; globalThis.f()"),
+        JsValue::Number(50.0)
+    );
+    assert_eq!(
+        eval_script(&mut interp, "function bump() { x++; } bump(); globalThis.x"),
+        JsValue::Number(6.0)
+    );
+    assert_eq!(eval_script(&mut interp, "var x; x"), JsValue::Number(6.0));
+    assert_eq!(
+        eval_script(
+            &mut interp,
+            "[JSON.stringify(Object.getOwnPropertyDescriptor(globalThis, 'x')), (() => { try { delete globalThis.f; return 'deleted'; } catch (e) { return e.name; } })()].join()"
+        ),
+        JsValue::from(
+            r#"{"value":6,"writable":true,"enumerable":true,"configurable":false},TypeError"#
+        )
+    );
+}
+
+#[test]
+#[allow(clippy::panic)]
+fn test_script_globals_redeclaration_conflicts() {
+    let mut interp = script_globals_runtime();
+    eval_script(&mut interp, "var a = 1; let b = 2; function h() {}");
+
+    let cases = [
+        ("let a = 0", "a"),
+        ("const a = 1", "a"),
+        ("var b", "b"),
+        ("function b() {}", "b"),
+        ("class h {}", "h"),
+        ("let fresh = 1; let a = 2", "a"),
+        ("var q; let q = 1", "q"),
+    ];
+    for (source, name) in cases {
+        match interp.prepare(source, None) {
+            Err(tsrun::JsError::SyntaxError { message, .. }) => assert_eq!(
+                message,
+                format!("Identifier '{}' has already been declared", name),
+                "running {}",
+                source
+            ),
+            Err(other) => panic!("Expected SyntaxError running {}, got {}", source, other),
+            Ok(_) => panic!("Expected SyntaxError running {}", source),
+        }
+    }
+
+    // Rejected scripts declare nothing, and the earlier bindings are intact
+    assert_eq!(
+        eval_script(
+            &mut interp,
+            "[typeof fresh, typeof q, a, b, typeof h].join()"
+        ),
+        JsValue::from("undefined,undefined,1,2,function")
+    );
+}
+
+#[test]
+fn test_lazy_builtins_keep_construction_small() {
+    let mut interp = tsrun::Interpreter::new();
+    let initial = interp.gc_stats().total_objects;
+    assert!(
+        initial < 300,
+        "Interpreter::new allocated {} objects",
+        initial
+    );
+    interp.materialize_builtins();
+    assert!(interp.gc_stats().total_objects > initial);
+}
+
+#[test]
+fn test_lazy_builtins_are_built_on_first_use() {
+    assert_eq!(eval("typeof Map"), JsValue::from("function"));
+    assert_eq!(eval("new Date(0).getTime()"), JsValue::Number(0.0));
+    assert_eq!(eval("(() => Math.max(1, 3))()"), JsValue::Number(3.0));
+    assert_eq!(
+        eval("Reflect.ownKeys(new Proxy({ a: 1 }, {})).join()"),
+        JsValue::from("a")
+    );
+    assert_eq!(
+        eval("Map.groupBy([1, 2, 3], x => x % 2).size"),
+        JsValue::Number(2.0)
+    );
+}
+
+#[test]
+fn test_lazy_builtins_are_visible_through_global_object() {
+    assert_eq!(
+        eval(
+            "const names = Object.getOwnPropertyNames(globalThis);
+             names.includes('Map') && names.includes('Reflect') &&
+             names.every(n => n === 'undefined' || globalThis[n] !== undefined)"
+        ),
+        JsValue::Boolean(true)
+    );
+    assert_eq!(
+        eval("Object.getOwnPropertyDescriptor(globalThis, 'JSON').value === JSON"),
+        JsValue::Boolean(true)
+    );
+    assert_eq!(
+        eval("typeof Object.getOwnPropertyDescriptor(this, 'Set').value"),
+        JsValue::from("function")
+    );
+}
+
+#[test]
+fn test_lazy_builtins_can_be_shadowed() {
+    assert_eq!(eval("let Set = 1; Set"), JsValue::Number(1.0));
+}
@@ -0,0 +1,255 @@
+//! Tests for host-defined exotic objects: Rust hooks behind property access,
+//! enumeration and calls
+
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use super::run;
+use tsrun::{
+    Guarded, HostClassDef, Interpreter, JsError, JsValue, RuntimeValue, StepResult,
+    value::PropertyKey,
+};
+
+/// A config branch whose leaves are computed when read
+struct LazyConfig {
+    scale: f64,
+    reads: Cell<usize>,
+    writes: RefCell<Vec<String>>,
+}
+
+const LEAVES: [&str; 3] = ["cpu", "memory", "disk"];
+
+fn leaf_index(key: &PropertyKey) -> Option<usize> {
+    match key {
+        PropertyKey::String(s) => LEAVES.iter().position(|leaf| *leaf == s.as_str()),
+        _ => None,
+    }
+}
+
+fn config(data: &dyn Any) -> Result<&LazyConfig, JsError> {
+    data.downcast_ref::<LazyConfig>()
+        .ok_or_else(|| JsError::type_error("not a config"))
+}
+
+fn config_get(
+    _interp: &mut Interpreter,
+    data: &dyn Any,
+    key: &PropertyKey,
+) -> Result<Option<Guarded>, JsError> {
+    let config = config(data)?;
+    Ok(leaf_index(key).map(|index| {
+        config.reads.set(config.reads.get() + 1);
+        Guarded::unguarded(JsValue::Number((index as f64 + 1.0) * config.scale))
+    }))
+}
+
+fn config_set(
+    _interp: &mut Interpreter,
+    data: &dyn Any,
+    key: &PropertyKey,
+    _value: JsValue,
+) -> Result<bool, JsError> {
+    let config = config(data)?;
+    if leaf_index(key).is_none() {
+        return Ok(false);
+    }
+    config.writes.borrow_mut().push(key.to_string());
+    Ok(true)
+}
+
+fn config_has(
+    _interp: &mut Interpreter,
+    _data: &dyn Any,
+    key: &PropertyKey,
+) -> Result<bool, JsError> {
+    Ok(leaf_index(key).is_some())
+}
+
+fn config_own_keys(interp: &mut Interpreter, _data: &dyn Any) -> Result<Vec<PropertyKey>, JsError> {
+    Ok(LEAVES
+        .iter()
+        .map(|leaf| interp.property_key(leaf))
+        .collect())
+}
+
+fn config_call(
+    _interp: &mut Interpreter,
+    data: &dyn Any,
+    _this: JsValue,
+    args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let config = config(data)?;
+    let sum: f64 = args.iter().filter_map(JsValue::as_number).sum();
+    Ok(Guarded::unguarded(JsValue::Number(sum * config.scale)))
+}
+
+fn config_class() -> HostClassDef {
+    HostClassDef {
+        name: "Config".to_string(),
+        get: Some(config_get),
+        set: Some(config_set),
+        has: Some(config_has),
+        own_keys: Some(config_own_keys),
+        call: Some(config_call),
+        dispose: None,
+    }
+}
+
+/// Interpreter with a `LazyConfig` of `scale` as the global `cfg`
+#[allow(clippy::unwrap_used)]
+fn interp_with_config(scale: f64) -> (Interpreter, RuntimeValue) {
+    let mut interp = Interpreter::new();
+    let class = interp.register_host_class(config_class());
+    let cfg = interp
+        .create_host_object(
+            class,
+            Box::new(LazyConfig {
+                scale,
+                reads: Cell::new(0),
+                writes: RefCell::new(Vec::new()),
+            }),
+        )
+        .unwrap();
+    let global = JsValue::Object(interp.global.clone());
+    tsrun::api::set_property(&global, "cfg", cfg.value().clone()).unwrap();
+    (interp, cfg)
+}
+
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn eval_with_config(scale: f64, source: &str) -> JsValue {
+    let (mut interp, _cfg) = interp_with_config(scale);
+    match run(&mut interp, source, None).unwrap() {
+        StepResult::Complete(value) => value.value().clone(),
+        other => panic!("Expected Complete, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_host_object_get_hook_computes_values() {
+    assert_eq!(
+        eval_with_config(10.0, "[cfg.cpu, cfg.memory, cfg['disk'], cfg.other].join()"),
+        JsValue::from("10,20,30,")
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_host_object_hooks_receive_host_data() {
+    let (mut interp, cfg) = interp_with_config(1.0);
+    run(
+        &mut interp,
+        "cfg.cpu; cfg.disk; cfg.cpu = 4; cfg.label = 'x'; cfg.label",
+        None,
+    )
+    .unwrap();
+    let obj = cfg.value().as_object().unwrap();
+    let tsrun::value::ExoticObject::Host(host) = &obj.borrow().exotic else {
+        panic!("Expected a host object");
+    };
+    let config = host.data.downcast_ref::<LazyConfig>().unwrap();
+    assert_eq!(config.reads.get(), 2);
+    assert_eq!(*config.writes.borrow(), vec!["cpu".to_string()]);
+}
+
+#[test]
+fn test_host_object_unhandled_write_is_ordinary_property() {
+    assert_eq!(
+        eval_with_config(1.0, "cfg.label = 'web'; [cfg.label, cfg.cpu].join()"),
+        JsValue::from("web,1")
+    );
+}
+
+#[test]
+fn test_host_object_enumerates_own_keys() {
+    assert_eq!(
+        eval_with_config(
+            2.0,
+            r#"
+            const seen = [];
+            for (const key in cfg) seen.push(key + "=" + cfg[key]);
+            [seen.join(), Object.keys(cfg).join(), JSON.stringify(Object.entries(cfg))].join("|")
+        "#
+        ),
+        JsValue::from(
+            "cpu=2,memory=4,disk=6|cpu,memory,disk|[[\"cpu\",2],[\"memory\",4],[\"disk\",6]]"
+        )
+    );
+}
+
+#[test]
+fn test_host_object_has_hook() {
+    assert_eq!(
+        eval_with_config(1.0, r#"["cpu" in cfg, "gpu" in cfg].join()"#),
+        JsValue::from("true,false")
+    );
+}
+
+#[test]
+fn test_host_object_call_hook() {
+    assert_eq!(
+        eval_with_config(
+            3.0,
+            "[typeof cfg, cfg(1, 2), [1, 2, 3].map(cfg).join(' '), Object.prototype.toString.call(cfg)].join()"
+        ),
+        JsValue::from("function,9,3 9 15,[object Config]")
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_host_object_without_call_hook_is_not_callable() {
+    let mut interp = Interpreter::new();
+    let class = interp.register_host_class(HostClassDef {
+        name: "Plain".to_string(),
+        ..Default::default()
+    });
+    let obj = interp.create_host_object(class, Box::new(())).unwrap();
+    let global = JsValue::Object(interp.global.clone());
+    tsrun::api::set_property(&global, "plain", obj.value().clone()).unwrap();
+    let result = run(
+        &mut interp,
+        r#"
+        let message = "";
+        try { plain(); } catch (e) { message = e.name + ": " + e.message; }
+        [typeof plain, String(plain), message].join("|")
+    "#,
+        None,
+    )
+    .unwrap();
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete, got {:?}", result);
+    };
+    assert_eq!(
+        *value.value(),
+        JsValue::from("object|[object Plain]|TypeError: plain is not a function")
+    );
+}
+
+/// Host data that reports when it is dropped
+struct DropFlag(Rc<Cell<bool>>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.set(true);
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_host_data_dropped_when_object_collected() {
+    let mut interp = Interpreter::new();
+    let class = interp.register_host_class(HostClassDef {
+        name: "Handle".to_string(),
+        ..Default::default()
+    });
+    let dropped = Rc::new(Cell::new(false));
+    let obj = interp
+        .create_host_object(class, Box::new(DropFlag(dropped.clone())))
+        .unwrap();
+    interp.collect();
+    assert!(!dropped.get(), "dropped while still guarded");
+    drop(obj);
+    interp.collect();
+    assert!(dropped.get(), "host data outlived its object");
+}
@@ -0,0 +1,162 @@
+//! Tests for Interpreter::run driving a script against a Host
+
+use tsrun::platform::ConsoleLevel;
+use tsrun::{
+    Host, HostAction, ImportRequest, JsError, Order, OrderId, ResponseFactory, RunPhase,
+    RuntimeValue,
+};
+
+/// Modules from an in-memory file map; orders answered with their payload,
+/// or deferred when the payload has `defer: true`
+#[derive(Default)]
+struct MapHost {
+    files: Vec<(&'static str, &'static str)>,
+    loaded: Vec<String>,
+    console: Vec<(ConsoleLevel, String)>,
+    phases: Vec<RunPhase>,
+    deferred: Vec<OrderId>,
+    resolved: Vec<OrderId>,
+}
+
+impl Host for MapHost {
+    fn load_module(&mut self, request: &ImportRequest) -> Result<String, String> {
+        let path = request.resolved_path.as_str();
+        self.loaded.push(path.to_string());
+        self.files
+            .iter()
+            .find(|(file, _)| *file == path)
+            .map(|(_, source)| source.to_string())
+            .ok_or_else(|| "not found".to_string())
+    }
+
+    fn handle_order(&mut self, order: &Order, responses: &mut ResponseFactory<'_>) -> HostAction {
+        let defer = tsrun::api::get_property(order.payload.value(), "defer")
+            .is_ok_and(|value| value.to_boolean());
+        if defer {
+            self.deferred.push(order.id);
+            return HostAction::Defer;
+        }
+        let echo = order
+            .payload
+            .to_json(&tsrun::ToJsonOptions::default())
+            .and_then(|json| responses.json(&json));
+        HostAction::Fulfill(echo)
+    }
+
+    fn resolve_deferred(
+        &mut self,
+        id: OrderId,
+        responses: &mut ResponseFactory<'_>,
+    ) -> Result<RuntimeValue, JsError> {
+        self.resolved.push(id);
+        responses.json(&serde_json::json!({ "late": id.0 }))
+    }
+
+    fn on_console(&mut self, level: ConsoleLevel, message: &str) {
+        self.console.push((level, message.to_string()));
+    }
+
+    fn on_phase(&mut self, phase: RunPhase) {
+        self.phases.push(phase);
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_run_multi_module_async_script_in_one_call() {
+    let mut host = MapHost {
+        files: vec![
+            (
+                "/lib/fetch.ts",
+                r#"
+                import { order } from "tsrun:host";
+                import { tag } from "./tag.ts";
+                export async function fetchValue(n: number): Promise<string> {
+                    const reply: any = await order({ n });
+                    return tag(reply.n * 2);
+                }
+                "#,
+            ),
+            (
+                "/lib/tag.ts",
+                r#"export function tag(n: number): string { return `#${n}`; }"#,
+            ),
+        ],
+        ..MapHost::default()
+    };
+
+    let source = r#"
+        import { fetchValue } from "./lib/fetch.ts";
+        const values = await Promise.all([fetchValue(1), fetchValue(2)]);
+        console.log("got", values.length);
+        console.warn("done");
+        values.join(",")
+    "#;
+    let mut interp = tsrun::Interpreter::new();
+    let value = interp
+        .run(source, Some(tsrun::ModulePath::new("/main.ts")), &mut host)
+        .unwrap();
+
+    assert_eq!(value.as_str(), Some("#2,#4"));
+    assert_eq!(host.loaded, ["/lib/fetch.ts", "/lib/tag.ts"]);
+    assert_eq!(
+        host.console,
+        [
+            (ConsoleLevel::Log, "got 2".to_string()),
+            (ConsoleLevel::Warn, "done".to_string()),
+        ]
+    );
+    assert_eq!(
+        host.phases,
+        [
+            RunPhase::Executing,
+            RunPhase::LoadingModules,
+            RunPhase::Executing,
+            RunPhase::LoadingModules,
+            RunPhase::Executing,
+        ]
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_run_resolves_deferred_orders_once_script_waits() {
+    let mut host = MapHost::default();
+    let source = r#"
+        import { order } from "tsrun:host";
+        const later: any = order({ defer: true });
+        const now: any = await order({ value: 1 });
+        const reply: any = await later;
+        `${now.value}:${reply.late}`
+    "#;
+    let mut interp = tsrun::Interpreter::new();
+    let value = interp.run(source, None, &mut host).unwrap();
+
+    let [id] = host.deferred.as_slice() else {
+        panic!("Expected one deferred order, got {}", host.deferred.len());
+    };
+    assert_eq!(host.resolved, [*id]);
+    assert_eq!(value.as_str(), Some(format!("1:{}", id.0).as_str()));
+    assert_eq!(host.phases.last(), Some(&RunPhase::Executing));
+    assert!(host.phases.contains(&RunPhase::AwaitingOrders));
+}
+
+#[test]
+fn test_run_fails_on_missing_module() {
+    let mut host = MapHost::default();
+    let mut interp = tsrun::Interpreter::new();
+    let err = interp
+        .run(
+            r#"import { x } from "./missing.ts"; x"#,
+            Some(tsrun::ModulePath::new("/main.ts")),
+            &mut host,
+        )
+        .unwrap_err();
+
+    assert!(
+        err.to_string()
+            .contains("Cannot load module '/missing.ts': not found"),
+        "{}",
+        err
+    );
+}
@@ -0,0 +1,130 @@
+//! Tests for inspecting a run suspended on an order
+
+use super::run_to_completion;
+use serde_json::json;
+use tsrun::{
+    Interpreter, InterpreterConfig, JsValue, ModulePath, OrderResponse, StepResult, api,
+    create_eval_internal_module,
+};
+
+const MAIN: &str = r#"
+import { order } from "tsrun:host";
+
+async function fetchUser(id: number): Promise<string> {
+    const url = `/users/${id}`;
+    const attempt = 1;
+    const response: any = await order({ kind: "fetch", url });
+    return `${response.name}#${attempt}`;
+}
+
+async function loadProfile(name: string) {
+    const userId = name.length;
+    const tags = ["admin", "ops"];
+    const user = await fetchUser(userId);
+    return `${user}:${tags.join()}`;
+}
+
+const started = "main";
+await loadProfile("alice")
+"#;
+
+/// Run `source` until it suspends on an order
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn run_until_order(source: &str) -> Interpreter {
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        internal_modules: vec![create_eval_internal_module()],
+        ..Default::default()
+    });
+    interp
+        .prepare(source, Some(ModulePath::new("/app/main.ts")))
+        .unwrap();
+    match run_to_completion(&mut interp).unwrap() {
+        StepResult::Suspended { pending, .. } => assert_eq!(pending.len(), 1),
+        other => panic!("Expected an order, got {:?}", other),
+    }
+    interp
+}
+
+/// Names and values of a frame's locals, rendered for comparison
+fn locals(frame: &tsrun::SuspendedFrame) -> Vec<(String, JsValue)> {
+    frame
+        .locals
+        .iter()
+        .map(|(name, value)| (name.clone(), value.value().clone()))
+        .collect()
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::indexing_slicing)]
+fn test_suspension_info_reports_nested_frames() {
+    let mut interp = run_until_order(MAIN);
+    let info = interp.suspension_info().unwrap();
+    assert_eq!(info.order_id, interp.outstanding_orders()[0]);
+
+    let names: Vec<_> = info
+        .frames
+        .iter()
+        .map(|frame| frame.function_name.as_deref())
+        .collect();
+    assert_eq!(names, vec![Some("fetchUser"), Some("loadProfile"), None]);
+
+    let at = info.await_frame().unwrap();
+    assert!(at.is_async);
+    assert_eq!(at.file.as_deref(), Some("/app/main.ts"));
+    assert_eq!(at.line, 7);
+    assert_eq!(
+        locals(at),
+        vec![
+            ("id".to_string(), JsValue::Number(5.0)),
+            ("url".to_string(), JsValue::from("/users/5")),
+            ("attempt".to_string(), JsValue::Number(1.0)),
+        ]
+    );
+
+    let outer = &info.frames[1];
+    assert_eq!(outer.line, 14);
+    let outer_locals = locals(outer);
+    let outer_names: Vec<_> = outer_locals.iter().map(|(name, _)| name.as_str()).collect();
+    // `user` is still in its temporal dead zone
+    assert_eq!(outer_names, vec!["name", "userId", "tags"]);
+    assert_eq!(outer_locals[0].1, JsValue::from("alice"));
+    assert!(matches!(outer_locals[2].1, JsValue::Object(_)));
+
+    let script = locals(&info.frames[2]);
+    assert!(script.contains(&("started".to_string(), JsValue::from("main"))));
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic, clippy::indexing_slicing)]
+fn test_suspension_info_values_outlive_resume() {
+    let mut interp = run_until_order(MAIN);
+    assert!(interp.suspension_info().is_some());
+    let info = interp.suspension_info().unwrap();
+
+    let id = interp.outstanding_orders()[0];
+    let response = api::create_response_object(&mut interp, &json!({ "name": "Alice" })).unwrap();
+    interp.fulfill_orders(vec![OrderResponse {
+        id,
+        result: Ok(response),
+    }]);
+    match run_to_completion(&mut interp).unwrap() {
+        StepResult::Complete(value) => {
+            assert_eq!(value.value(), &JsValue::from("Alice#1:admin,ops"))
+        }
+        other => panic!("Expected completion, got {:?}", other),
+    }
+    assert!(interp.suspension_info().is_none());
+
+    interp.collect();
+    let tags = &info.frames[1].locals[2].1;
+    assert_eq!(
+        api::get_index(tags.value(), 1).unwrap(),
+        JsValue::from("ops")
+    );
+}
+
+#[test]
+fn test_suspension_info_none_without_order() {
+    let mut interp = Interpreter::new();
+    assert!(interp.suspension_info().is_none());
+}
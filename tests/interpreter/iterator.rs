@@ -0,0 +1,153 @@
+//! Tests for Iterator.prototype helpers
+
+use super::eval;
+use tsrun::JsValue;
+
+#[test]
+fn test_iterator_helpers_chain_over_infinite_generator() {
+    assert_eq!(
+        eval(
+            r#"
+            function* naturals() { let n = 0; while (true) yield n++; }
+            naturals()
+                .filter(n => n % 2 === 1)
+                .map(n => n * n)
+                .take(5)
+                .toArray()
+                .join(",")
+        "#
+        ),
+        JsValue::from("1,9,25,49,81")
+    );
+}
+
+#[test]
+fn test_iterator_helpers_are_lazy() {
+    assert_eq!(
+        eval(
+            r#"
+            let pulled = 0;
+            function* counted() { while (true) { pulled++; yield pulled; } }
+            const it = counted().map(n => n * 10).drop(2).take(3);
+            const before = pulled;
+            const first = it.next().value;
+            const afterFirst = pulled;
+            const rest = it.toArray();
+            [before, first, afterFirst, rest.join(","), pulled].join("|")
+        "#
+        ),
+        JsValue::from("0|30|3|40,50|5")
+    );
+}
+
+#[test]
+fn test_iterator_helper_break_closes_source() {
+    assert_eq!(
+        eval(
+            r#"
+            const log: string[] = [];
+            const source = {
+                n: 0,
+                next() { this.n++; return { value: this.n, done: false }; },
+                return() { log.push("closed at " + this.n); return { done: true }; },
+                __proto__: Iterator.prototype,
+            };
+            for (const v of source.map(x => x * 2).filter(x => x > 2)) {
+                log.push("got " + v);
+                if (v >= 6) break;
+            }
+            log.join(", ")
+        "#
+        ),
+        JsValue::from("got 4, got 6, closed at 3")
+    );
+}
+
+#[test]
+fn test_iterator_helper_throwing_callback_closes_source() {
+    assert_eq!(
+        eval(
+            r#"
+            let closed = false;
+            const source = {
+                n: 0,
+                next() { this.n++; return { value: this.n, done: this.n > 3 }; },
+                return() { closed = true; return { done: true }; },
+                __proto__: Iterator.prototype,
+            };
+            let message = "";
+            try {
+                source.map(x => { if (x === 2) throw new Error("bad " + x); return x; }).toArray();
+            } catch (e) {
+                message = e.message;
+            }
+            function* gen() { yield 1; yield 2; }
+            const it = gen();
+            const someResult = it.some(x => x === 1);
+            message + "|" + closed + "|" + someResult + "|" + it.next().done
+        "#
+        ),
+        JsValue::from("bad 2|true|true|true")
+    );
+}
+
+#[test]
+fn test_iterator_consumers() {
+    assert_eq!(
+        eval(
+            r#"
+            function* nums() { yield 1; yield 2; yield 3; yield 4; }
+            const seen: number[] = [];
+            nums().forEach((v, i) => seen.push(v * 10 + i));
+            [
+                nums().reduce((a, b) => a + b),
+                nums().reduce((a, b) => a + b, 100),
+                nums().every(n => n > 0),
+                nums().some(n => n > 3),
+                nums().find(n => n % 2 === 0),
+                nums().flatMap(n => [n, -n]).take(4).toArray().join(" "),
+                seen.join(" "),
+            ].join(",")
+        "#
+        ),
+        JsValue::from("10,110,true,true,2,1 -1 2 -2,10 21 32 43")
+    );
+}
+
+#[test]
+fn test_builtin_iterators_inherit_iterator_prototype() {
+    assert_eq!(
+        eval(
+            r#"
+            const m = new Map([["a", 1], ["b", 2]]);
+            const s = new Set([1, 2, 3]);
+            [
+                m.entries().map(([k, v]) => k + v).toArray().join(","),
+                s.values().filter(x => x !== 2).toArray().join(","),
+                [5, 6, 7].values().drop(1).toArray().join(","),
+                (function* () {})() instanceof Iterator && s.values().map(x => x) instanceof Iterator,
+            ].join("|")
+        "#
+        ),
+        JsValue::from("a1,b2|1,3|6,7|true")
+    );
+}
+
+#[test]
+fn test_iterator_helper_argument_errors() {
+    assert_eq!(
+        eval(
+            r#"
+            function* g() { yield 1; }
+            const errors: string[] = [];
+            try { g().take(-1); } catch (e) { errors.push(e.name); }
+            try { g().drop(NaN); } catch (e) { errors.push(e.name); }
+            try { g().map(42 as any); } catch (e) { errors.push(e.name); }
+            try { g().flatMap(x => x as any).toArray(); } catch (e) { errors.push(e.name); }
+            try { new (Iterator as any)(); } catch (e) { errors.push(e.name); }
+            errors.join(",")
+        "#
+        ),
+        JsValue::from("RangeError,RangeError,TypeError,TypeError,TypeError")
+    );
+}
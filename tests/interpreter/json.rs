@@ -1,7 +1,7 @@
 //! Tests for JSON object (parse and stringify)
 
-use super::eval;
-use tsrun::JsValue;
+use super::{eval, run};
+use tsrun::{Interpreter, JsValue, StepResult};
 
 #[test]
 fn test_json_stringify_simple_object() {
@@ -51,6 +51,15 @@ fn test_json_parse_array() {
     assert_eq!(eval(r#"JSON.parse('[1,2,3]')[1]"#), JsValue::Number(2.0));
 }
 
+#[test]
+fn test_json_parse_keeps_member_order() {
+    assert_eq!(
+        eval(r#"JSON.stringify(JSON.parse('{"b":1,"a":2,"2":3,"1":4}'))"#),
+        JsValue::String(r#"{"1":4,"2":3,"b":1,"a":2}"#.into())
+    );
+    assert_eq!(eval(r#"JSON.parse('{"2":7}')[2]"#), JsValue::Number(7.0));
+}
+
 #[test]
 fn test_json_round_trip() {
     assert_eq!(
@@ -207,3 +216,204 @@ fn test_json_raw_json_null_prototype() {
         JsValue::Null
     );
 }
+
+// Canonical JSON (RFC 8785)
+
+#[test]
+fn test_json_canonicalize_ignores_property_order() {
+    assert_eq!(
+        eval(
+            r#"
+            const a = { b: [1, { y: 2, x: 1 }], a: "s", c: null };
+            const b = { c: null, a: "s", b: [1, { x: 1, y: 2 }] };
+            JSON.canonicalize(a) === JSON.canonicalize(b) && JSON.canonicalize(a)
+        "#
+        ),
+        JsValue::from(r#"{"a":"s","b":[1,{"x":1,"y":2}],"c":null}"#)
+    );
+}
+
+#[test]
+fn test_json_canonicalize_rfc8785_vectors() {
+    // Sorting vector from RFC 8785 section 3.2.3: keys compare by UTF-16
+    // code units, so the surrogate pair sorts before U+FB33
+    assert_eq!(
+        eval(
+            r#"JSON.canonicalize({
+                "\u20ac": "Euro Sign", "\r": "Carriage Return", "\ufb33": "Hebrew Letter Dalet With Dagesh",
+                "1": "One", "\u{1f600}": "Emoji: Grinning Face", "\u0080": "Control",
+                "\u00f6": "Latin Small Letter O With Diaeresis"
+            })"#
+        ),
+        JsValue::from(
+            "{\"\\r\":\"Carriage Return\",\"1\":\"One\",\"\u{80}\":\"Control\",\
+             \"\u{f6}\":\"Latin Small Letter O With Diaeresis\",\"\u{20ac}\":\"Euro Sign\",\
+             \"\u{1f600}\":\"Emoji: Grinning Face\",\
+             \"\u{fb33}\":\"Hebrew Letter Dalet With Dagesh\"}"
+        )
+    );
+    // Number and literal vector from RFC 8785 section 3.2.2
+    assert_eq!(
+        eval(
+            r#"JSON.canonicalize({
+                numbers: [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+                string: "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+                literals: [null, true, false]
+            })"#
+        ),
+        JsValue::from(
+            r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+        )
+    );
+}
+
+#[test]
+fn test_json_canonicalize_pretty() {
+    assert_eq!(
+        eval(r#"JSON.canonicalize({ b: [1], a: {} }, { pretty: true })"#),
+        JsValue::from("{\n  \"a\": {},\n  \"b\": [\n    1\n  ]\n}")
+    );
+}
+
+#[test]
+fn test_json_canonicalize_cycle_throws() {
+    assert_eq!(
+        eval(
+            r#"
+            const o: any = { a: 1 };
+            o.self = o;
+            try { JSON.canonicalize(o); "no error" } catch (e) { e.name }
+        "#
+        ),
+        JsValue::from("TypeError")
+    );
+}
+
+#[test]
+fn test_json_canonicalize_errors_option() {
+    assert_eq!(
+        eval(
+            r#"
+            const e: any = new TypeError("save failed", { cause: new Error("disk full") });
+            e.code = "E_SAVE";
+            JSON.canonicalize({ failures: [e] }, { errors: "withoutStack" })
+        "#
+        ),
+        JsValue::from(
+            r#"{"failures":[{"cause":{"message":"disk full","name":"Error"},"code":"E_SAVE","message":"save failed","name":"TypeError"}]}"#
+        )
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_to_canonical_json_host_api() {
+    let mut interp = Interpreter::new();
+    let result = run(&mut interp, r#"({ z: [true], a: 1.5 })"#, None).unwrap();
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete");
+    };
+
+    assert_eq!(
+        interp.to_canonical_json(&value).unwrap(),
+        r#"{"a":1.5,"z":[true]}"#
+    );
+    assert_eq!(
+        interp.to_canonical_json_pretty(&value).unwrap(),
+        "{\n  \"a\": 1.5,\n  \"z\": [\n    true\n  ]\n}"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_to_canonical_json_with_errors_keeps_stack() {
+    let mut interp = Interpreter::new();
+    let result = run(&mut interp, r#"new Error("boom", { cause: 1 })"#, None).unwrap();
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete");
+    };
+
+    assert_eq!(
+        interp
+            .to_canonical_json_with_errors(&value, tsrun::ErrorsAs::WithStack, false)
+            .unwrap(),
+        r#"{"cause":1,"message":"boom","name":"Error","stack":"Error: boom"}"#
+    );
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Primitive wrapper objects
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_primitive_wrappers_own_keys() {
+    assert_eq!(
+        eval(
+            r#"
+            const s = new String("ab");
+            [
+                Object.keys(s),
+                Object.getOwnPropertyNames(s),
+                Object.keys(new Number(5)),
+                Object.keys(new Boolean(true)),
+            ].map(keys => JSON.stringify(keys)).join(" ")
+            "#
+        ),
+        JsValue::from(r#"["0","1"] ["0","1","length"] [] []"#)
+    );
+}
+
+#[test]
+fn test_json_stringify_unwraps_primitive_wrappers() {
+    assert_eq!(
+        eval(
+            r#"
+            [
+                JSON.stringify(new String("ab")),
+                JSON.stringify(new Number(5)),
+                JSON.stringify(new Boolean(false)),
+                JSON.stringify({ s: new String("x"), n: [new Number(1.5)] }),
+            ].join(" ")
+            "#
+        ),
+        JsValue::from(r#""ab" 5 false {"s":"x","n":[1.5]}"#)
+    );
+}
+
+#[test]
+fn test_spread_of_primitive_wrappers() {
+    assert_eq!(
+        eval(
+            r#"
+            const s = new String("ab");
+            const { 0: first, ...rest } = new String("xyz") as any;
+            [
+                JSON.stringify({ ...s }),
+                JSON.stringify([...s]),
+                JSON.stringify({ ...new Number(5), ...new Boolean(true) }),
+                JSON.stringify({ ..."cd" }),
+                JSON.stringify({ ...[1, 2] }),
+                first + JSON.stringify(rest),
+                Array.from(s).join("-"),
+            ].join(" ")
+            "#
+        ),
+        JsValue::from(
+            r#"{"0":"a","1":"b"} ["a","b"] {} {"0":"c","1":"d"} {"0":1,"1":2} x{"1":"y","2":"z"} a-b"#
+        )
+    );
+}
+
+#[test]
+fn test_for_of_string_wrapper() {
+    assert_eq!(
+        eval(
+            r#"
+            const chars: string[] = [];
+            for (const c of new String("hi")) chars.push(c);
+            chars.join(",")
+            "#
+        ),
+        JsValue::from("h,i")
+    );
+}
@@ -0,0 +1,265 @@
+//! Tests for the lenient JSON parsers: parseJSONC and parseJSON5 from
+//! tsrun:host, and Interpreter::create_from_jsonc
+
+use tsrun::{
+    Interpreter, InterpreterConfig, JsError, JsValue, RuntimeValue, create_eval_internal_module,
+    js_value_to_json,
+};
+
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn eval_with_host(source: &str) -> RuntimeValue {
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        internal_modules: vec![create_eval_internal_module()],
+        ..Default::default()
+    });
+    match super::run(&mut interp, source, None).unwrap() {
+        tsrun::StepResult::Complete(value) => value,
+        other => panic!("Expected completion, got {:?}", other),
+    }
+}
+
+/// JSON.stringify of `parse<dialect>(text)`
+fn parsed(dialect: &str, text: &str) -> RuntimeValue {
+    eval_with_host(&format!(
+        r#"import {{ parse{dialect} }} from "tsrun:host"; JSON.stringify(parse{dialect}({text:?}))"#
+    ))
+}
+
+/// `name: message` of the error `parse<dialect>(text)` throws
+fn parse_error(dialect: &str, text: &str) -> RuntimeValue {
+    eval_with_host(&format!(
+        r#"
+        import {{ parse{dialect} }} from "tsrun:host";
+        let caught = "";
+        try {{ parse{dialect}({text:?}); }} catch (e) {{ caught = e.name + ": " + e.message; }}
+        caught
+    "#
+    ))
+}
+
+const JSONC_FIXTURE: &str = r#"// Editor settings
+{
+    /* Indentation */
+    "tabSize": 4, // spaces
+    "rulers": [80, 120,],
+    "files.exclude": {
+        "**/.git": true,
+    },
+    "url": "http://example.com/*not-a-comment*/",
+}
+"#;
+
+#[test]
+fn test_jsonc_comments_and_trailing_commas() {
+    assert_eq!(
+        parsed("JSONC", JSONC_FIXTURE),
+        JsValue::from(
+            r#"{"tabSize":4,"rulers":[80,120],"files.exclude":{"**/.git":true},"url":"http://example.com/*not-a-comment*/"}"#
+        )
+    );
+}
+
+#[test]
+fn test_jsonc_matches_json_parse() {
+    let text = r#"{"a": [1, -2.5e3, 0, true, null, "é\n"], "b": {"": {}}, "0": []}"#;
+    assert_eq!(
+        parsed("JSONC", text),
+        eval_with_host(&format!("JSON.stringify(JSON.parse({:?}))", text))
+            .value()
+            .clone()
+    );
+}
+
+#[test]
+fn test_jsonc_rejects_json5_syntax() {
+    let cases: &[(&str, &str)] = &[
+        ("{a: 1}", "expected property name at line 1 column 2"),
+        ("['x']", "unexpected character '\\'' at line 1 column 2"),
+        ("0x1F", "unexpected character 'x' at line 1 column 2"),
+        ("[.5]", "unexpected character '.' at line 1 column 2"),
+        ("[+1]", "unexpected character '+' at line 1 column 2"),
+        ("NaN", "unexpected character 'N' at line 1 column 1"),
+        (r#""\v""#, "invalid escape sequence at line 1 column 2"),
+    ];
+    for (text, expected) in cases {
+        assert_eq!(
+            parse_error("JSONC", text),
+            JsValue::from(format!("SyntaxError: JSONC parse error: {}", expected)),
+            "parsing {}",
+            text
+        );
+    }
+}
+
+const JSON5_FIXTURE: &str = r#"// JSON5 sample
+{
+    unquoted: 'and you can quote me on that',
+    singleQuotes: 'I can use "double quotes" here',
+    lineBreaks: "Look, Mom! \
+No \\n's!",
+    hexadecimal: 0xdecaf,
+    leadingDecimalPoint: .8675309, andTrailing: 8675309.,
+    positiveSign: +1,
+    trailingComma: 'in objects', andIn: ['arrays',],
+    "backwardsCompatible": "with JSON",
+    $_ident1: '\x41\0',
+}
+"#;
+
+#[test]
+fn test_json5_fixture() {
+    assert_eq!(
+        parsed("JSON5", JSON5_FIXTURE),
+        JsValue::from(concat!(
+            r#"{"unquoted":"and you can quote me on that","#,
+            r#""singleQuotes":"I can use \"double quotes\" here","#,
+            r#""lineBreaks":"Look, Mom! No \\n's!","hexadecimal":912559,"#,
+            r#""leadingDecimalPoint":0.8675309,"andTrailing":8675309,"positiveSign":1,"#,
+            r#""trailingComma":"in objects","andIn":["arrays"],"#,
+            r#""backwardsCompatible":"with JSON","$_ident1":"A\u0000"}"#
+        ))
+    );
+}
+
+#[test]
+fn test_json5_special_numbers() {
+    assert_eq!(
+        eval_with_host(
+            r#"
+            import { parseJSON5 } from "tsrun:host";
+            const v = parseJSON5("[Infinity, -Infinity, NaN, -0x10, 1e3, -.5]");
+            [v[0] === Infinity, v[1] === -Infinity, Number.isNaN(v[2]), v[3], v[4], v[5]].join()
+        "#
+        ),
+        JsValue::from("true,true,true,-16,1000,-0.5")
+    );
+}
+
+#[test]
+fn test_malformed_input_positions() {
+    let cases: &[(&str, &str, &str)] = &[
+        (
+            "JSONC",
+            "{\n  \"a\": 1\n  \"b\": 2\n}",
+            "expected ',' or '}' after property value at line 3 column 3",
+        ),
+        (
+            "JSONC",
+            "[1, 2",
+            "unexpected end of input at line 1 column 6",
+        ),
+        (
+            "JSONC",
+            "{\"a\" 1}",
+            "expected ':' after property name at line 1 column 6",
+        ),
+        (
+            "JSONC",
+            "[1] [2]",
+            "unexpected character '[' at line 1 column 5",
+        ),
+        (
+            "JSONC",
+            "[01]",
+            "leading zeros are not allowed at line 1 column 2",
+        ),
+        ("JSONC", "[1.]", "invalid number at line 1 column 2"),
+        (
+            "JSONC",
+            "/* open\n[1]",
+            "unterminated comment at line 1 column 1",
+        ),
+        (
+            "JSONC",
+            "{\"a\": \"x\ty\"}",
+            "unescaped control character in string at line 1 column 9",
+        ),
+        (
+            "JSONC",
+            "[,]",
+            "unexpected character ',' at line 1 column 2",
+        ),
+        (
+            "JSON5",
+            "{\n  a: 'b\n'}",
+            "unescaped line break in string at line 2 column 8",
+        ),
+        (
+            "JSON5",
+            "{ key: 1, 2bad: 3 }",
+            "expected property name at line 1 column 11",
+        ),
+        (
+            "JSON5",
+            "[0x]",
+            "invalid hexadecimal number at line 1 column 2",
+        ),
+        (
+            "JSON5",
+            "['\\1']",
+            "invalid escape sequence at line 1 column 3",
+        ),
+        (
+            "JSON5",
+            "// only a comment",
+            "unexpected end of input at line 1 column 18",
+        ),
+    ];
+    for (dialect, text, expected) in cases {
+        assert_eq!(
+            parse_error(dialect, text),
+            JsValue::from(format!(
+                "SyntaxError: {} parse error: {}",
+                dialect, expected
+            )),
+            "parsing {} {:?}",
+            dialect,
+            text
+        );
+    }
+}
+
+#[test]
+fn test_parse_requires_string() {
+    assert_eq!(
+        eval_with_host(
+            r#"
+            import { parseJSON5 } from "tsrun:host";
+            let caught = "";
+            try { parseJSON5(42 as any); } catch (e) { caught = e.name + ": " + e.message; }
+            caught
+        "#
+        ),
+        JsValue::from("TypeError: parseJSON5 requires a string")
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_create_from_jsonc() {
+    let mut interp = Interpreter::new();
+    let value = interp.create_from_jsonc(JSONC_FIXTURE).unwrap();
+    assert_eq!(
+        js_value_to_json(value.value()).unwrap(),
+        serde_json::json!({
+            "tabSize": 4,
+            "rulers": [80, 120],
+            "files.exclude": { "**/.git": true },
+            "url": "http://example.com/*not-a-comment*/",
+        })
+    );
+
+    match interp.create_from_jsonc("{\n  // fine\n  \"a\": tru\n}") {
+        Err(JsError::SyntaxError { message, location }) => {
+            assert_eq!(
+                message,
+                "JSONC parse error: unexpected character 't' at line 3 column 8"
+            );
+            assert_eq!((location.line, location.column), (3, 8));
+        }
+        other => panic!(
+            "Expected SyntaxError, got {:?}",
+            other.map(|v| v.value().clone())
+        ),
+    }
+}
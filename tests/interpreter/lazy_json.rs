@@ -0,0 +1,142 @@
+//! Tests for `create_lazy_json_array`: host JSON converted as it is iterated
+
+use std::any::Any;
+use std::cell::RefCell;
+
+use super::run;
+use tsrun::{Guarded, HostClassDef, Interpreter, JsError, JsValue, StepResult, api};
+
+const FILTER_SCRIPT: &str = r#"
+    const kept = [];
+    let seen = 0;
+    for (const item of items) {
+        if (item.id % 100 === 0) kept.push(item.name);
+        if (++seen % 100000 === 0) sample();
+    }
+    [seen, kept.length, kept.slice(0, 3).join(), kept[kept.length - 1]].join("|")
+"#;
+
+fn element(i: usize) -> serde_json::Value {
+    serde_json::json!({ "id": i, "name": format!("item-{i}"), "tags": ["a", "b"] })
+}
+
+/// Live object counts recorded by the script's `sample()` calls
+struct Samples(RefCell<Vec<usize>>);
+
+fn record_sample(
+    interp: &mut Interpreter,
+    data: &dyn Any,
+    _this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    if let Some(samples) = data.downcast_ref::<Samples>() {
+        samples.0.borrow_mut().push(interp.gc_stats().live_objects);
+    }
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+/// Run `source` with `items` and `sample` as globals, returning the
+/// completion value and the samples taken
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn run_with_items(interp: &mut Interpreter, items: JsValue, source: &str) -> (JsValue, Vec<usize>) {
+    let class = interp.register_host_class(HostClassDef {
+        name: "Sampler".to_string(),
+        call: Some(record_sample),
+        ..Default::default()
+    });
+    let sample = interp
+        .create_host_object(class, Box::new(Samples(RefCell::new(Vec::new()))))
+        .unwrap();
+    let global = JsValue::Object(interp.global.clone());
+    api::set_property(&global, "items", items).unwrap();
+    api::set_property(&global, "sample", sample.value().clone()).unwrap();
+    let value = match run(interp, source, None).unwrap() {
+        StepResult::Complete(value) => value.value().clone(),
+        other => panic!("Expected Complete, got {:?}", other),
+    };
+    let obj = sample.value().as_object().unwrap();
+    let tsrun::value::ExoticObject::Host(host) = &obj.borrow().exotic else {
+        panic!("Expected a host object");
+    };
+    let samples = host
+        .data
+        .downcast_ref::<Samples>()
+        .unwrap()
+        .0
+        .borrow()
+        .clone();
+    (value, samples)
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_lazy_json_array_iteration_keeps_heap_bounded() {
+    let mut interp = Interpreter::new();
+    let baseline = interp.gc_stats().live_objects;
+    let items = interp
+        .create_lazy_json_array((0..1_000_000).map(element))
+        .unwrap();
+    let (value, samples) = run_with_items(&mut interp, items.value().clone(), FILTER_SCRIPT);
+    assert_eq!(
+        value,
+        JsValue::from("1000000|10000|item-0,item-100,item-200|item-999900")
+    );
+    assert_eq!(samples.len(), 10);
+    // Three objects per element would be three million if all were kept
+    for live in samples {
+        assert!(
+            live < baseline + 10_000,
+            "{} live objects mid-iteration",
+            live
+        );
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_lazy_json_array_matches_eager_conversion() {
+    let script = FILTER_SCRIPT.replace("seen % 100000", "seen % 1000");
+    let elements: Vec<serde_json::Value> = (0..5_000).map(element).collect();
+
+    let mut interp = Interpreter::new();
+    let guard = api::create_guard(&interp);
+    let eager = api::create_from_json(
+        &mut interp,
+        &guard,
+        &serde_json::Value::Array(elements.clone()),
+    )
+    .unwrap();
+    let (eager_value, _) = run_with_items(&mut interp, eager, &script);
+
+    let mut interp = Interpreter::new();
+    let lazy = interp.create_lazy_json_array(elements).unwrap();
+    let (lazy_value, _) = run_with_items(&mut interp, lazy.value().clone(), &script);
+
+    assert_eq!(lazy_value, eager_value);
+    assert_eq!(
+        lazy_value,
+        JsValue::from("5000|50|item-0,item-100,item-200|item-4900")
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_lazy_json_array_is_single_pass_without_random_access() {
+    let mut interp = Interpreter::new();
+    let items = interp
+        .create_lazy_json_array((1..=5).map(serde_json::Value::from))
+        .unwrap();
+    let (value, _) = run_with_items(
+        &mut interp,
+        items.value().clone(),
+        r#"
+        const errors = [];
+        try { items.length; } catch (e) { errors.push(e.name); }
+        try { items[0]; } catch (e) { errors.push(e.name); }
+        const firstTwo = [items.next().value, items.next().value];
+        const rest = items.filter(n => n % 2 === 1).toArray();
+        [firstTwo.join(), rest.join(), [...items].length, errors.join()].join("|")
+    "#,
+    );
+    assert_eq!(value, JsValue::from("1,2|3,5|0|TypeError,TypeError"));
+}
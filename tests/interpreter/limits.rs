@@ -0,0 +1,397 @@
+//! Tests for the string and array length limits, the call argument limit,
+//! the call depth limit and the limits on compiled code size
+
+use super::{eval, run};
+use std::time::{Duration, Instant};
+use tsrun::{
+    CompileLimitKind, Interpreter, InterpreterConfig, JsError, JsValue, ModulePath, StepResult,
+};
+
+/// Interpreter with small limits so tests don't need large allocations
+fn limited_interp() -> Interpreter {
+    let config = InterpreterConfig {
+        max_string_len: 1024,
+        max_array_len: 1000,
+        ..Default::default()
+    };
+    Interpreter::with_config(config)
+}
+
+/// Run `body` inside try/catch and return "<name>: <message>", or "ok" if nothing was thrown
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn caught(interp: &mut Interpreter, body: &str) -> String {
+    let source = format!(
+        r#"
+        let out = "ok";
+        try {{ {body} }} catch (e) {{ out = e.name + ": " + e.message; }}
+        out
+    "#
+    );
+    match run(interp, &source, None).unwrap() {
+        StepResult::Complete(value) => value.as_str().unwrap().to_string(),
+        _ => panic!("Expected Complete result"),
+    }
+}
+
+#[test]
+fn test_default_limits_reject_huge_repeat_and_array() {
+    assert_eq!(
+        eval(r#"try { "x".repeat(2 ** 30); "ok" } catch (e) { e.name + ": " + e.message }"#),
+        JsValue::from("RangeError: Invalid string length")
+    );
+    assert_eq!(
+        eval(
+            r#"try { new Array(2 ** 32 - 2).join("ab"); "ok" } catch (e) { e.name + ": " + e.message }"#
+        ),
+        JsValue::from("RangeError: Invalid array length")
+    );
+}
+
+#[test]
+fn test_string_limit_builtins() {
+    let mut interp = limited_interp();
+    let string_err = "RangeError: Invalid string length";
+
+    assert_eq!(caught(&mut interp, r#""ab".repeat(600);"#), string_err);
+    assert_eq!(caught(&mut interp, r#""ab".repeat(500);"#), "ok");
+    assert_eq!(
+        caught(&mut interp, r#""x".padStart(2000, "-");"#),
+        string_err
+    );
+    assert_eq!(caught(&mut interp, r#""x".padEnd(2000);"#), string_err);
+    assert_eq!(
+        caught(&mut interp, r#"new Array(600).fill("x").join("ab");"#),
+        string_err
+    );
+    assert_eq!(
+        caught(&mut interp, r#""a".repeat(600).concat("b".repeat(600));"#),
+        string_err
+    );
+}
+
+#[test]
+fn test_string_limit_concatenation() {
+    let mut interp = limited_interp();
+    let string_err = "RangeError: Invalid string length";
+
+    assert_eq!(
+        caught(
+            &mut interp,
+            r#"let s = "0123456789"; while (true) { s = s + s; }"#
+        ),
+        string_err
+    );
+    assert_eq!(
+        caught(
+            &mut interp,
+            r#"let s = ""; for (let i = 0; i < 2000; i++) { s += "x"; }"#
+        ),
+        string_err
+    );
+    assert_eq!(
+        caught(&mut interp, r#"const s = "x".repeat(600); `${s}${s}`;"#),
+        string_err
+    );
+}
+
+#[test]
+fn test_string_limit_json_stringify() {
+    let mut interp = limited_interp();
+
+    assert_eq!(
+        caught(
+            &mut interp,
+            r#"JSON.stringify({ items: new Array(500).fill("abc") });"#
+        ),
+        "RangeError: Invalid string length"
+    );
+    // Shared subtrees grow exponentially in the output
+    assert_eq!(
+        caught(
+            &mut interp,
+            r#"let v: any = [1]; for (let i = 0; i < 40; i++) { v = [v, v]; } JSON.stringify(v);"#
+        ),
+        "RangeError: Invalid string length"
+    );
+    assert_eq!(
+        caught(&mut interp, r#"JSON.stringify({ a: [1, 2, 3] });"#),
+        "ok"
+    );
+}
+
+#[test]
+fn test_array_limit() {
+    let mut interp = limited_interp();
+    let array_err = "RangeError: Invalid array length";
+
+    assert_eq!(caught(&mut interp, "new Array(1001);"), array_err);
+    assert_eq!(caught(&mut interp, "new Array(1000).fill(0);"), "ok");
+    assert_eq!(
+        caught(&mut interp, "const a: number[] = []; a[5000] = 1;"),
+        array_err
+    );
+    assert_eq!(
+        caught(&mut interp, "const a: number[] = []; a.length = 5000;"),
+        array_err
+    );
+    assert_eq!(
+        caught(&mut interp, "const a = [1, 2]; a.length = 10; a.fill(7);"),
+        "ok"
+    );
+    assert_eq!(caught(&mut interp, "new Array(-1);"), array_err);
+    assert_eq!(caught(&mut interp, "new Array(1.5);"), array_err);
+}
+
+#[test]
+fn test_limits_keep_heap_bounded() {
+    let mut interp = limited_interp();
+    interp.collect();
+    let baseline = interp.gc_stats().total_objects;
+
+    for _ in 0..20 {
+        caught(&mut interp, r#""x".repeat(1e9);"#);
+        caught(&mut interp, "new Array(1e9);");
+        caught(&mut interp, "const a: number[] = []; a[1e9] = 1;");
+    }
+    interp.collect();
+
+    // A rejected allocation never materialized its contents
+    assert!(interp.gc_stats().total_objects < baseline + 1000);
+}
+
+/// Run `test` on a thread whose stack fits 50 levels of native recursion in
+/// debug builds
+#[allow(clippy::unwrap_used)]
+fn with_large_stack(test: impl FnOnce() + Send + 'static) {
+    std::thread::Builder::new()
+        .stack_size(64 * 1024 * 1024)
+        .spawn(test)
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn test_call_argument_limit() {
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        max_call_arguments: 100,
+        ..Default::default()
+    });
+    let args_err = "RangeError: Too many arguments in function call";
+    let setup = "function f(...a: any[]) { return a.length; } class C { constructor(...a: any[]) {} } class D extends C { constructor(...a: any[]) { super(...a); } }";
+
+    for call in [
+        "f(...new Array(101).fill(0));",
+        "new C(...new Array(101).fill(0));",
+        "new D(...new Array(101).fill(0));",
+        "f.apply(null, { length: 101 });",
+        "Reflect.apply(f, null, new Array(101));",
+        "Reflect.construct(C, { length: 1e9 });",
+    ] {
+        assert_eq!(
+            caught(&mut interp, &format!("{setup} {call}")),
+            args_err,
+            "{}",
+            call
+        );
+    }
+    assert_eq!(
+        caught(
+            &mut interp,
+            &format!(
+                "{setup} if (f(...new Array(100)) + f.apply(null, new Array(100)) !== 200) throw 1;"
+            )
+        ),
+        "ok"
+    );
+}
+
+#[test]
+fn test_call_depth_limit_covers_native_recursion() {
+    with_large_stack(|| {
+        let mut interp = Interpreter::with_config(InterpreterConfig {
+            max_call_depth: 50,
+            ..Default::default()
+        });
+        let overflow = "RangeError: Maximum call stack size exceeded";
+        let cases = [
+            // A get trap reading through its own proxy
+            "const p: any = new Proxy({}, { get(t, k) { return p[k]; } }); p.x;",
+            // Proxies forwarding to proxies without calling any script
+            "let p: any = {}; for (let i = 0; i < 1000; i++) p = new Proxy(p, {}); p.x;",
+            // Mutually recursive getters
+            "const o: any = { get a() { return this.b; }, get b() { return this.a; } }; o.a;",
+            // Coercion calling toString, which coerces again
+            "const s: any = { toString() { return `${s}`; } }; String(s);",
+            "const a: any[] = []; a.push(a); a.flat(Infinity);",
+            "function f(n: number): number { return f(n + 1); } f(0);",
+            "class A { constructor() { new A(); } } new A();",
+        ];
+        for body in cases {
+            assert_eq!(caught(&mut interp, body), overflow, "running {}", body);
+            assert_eq!(interp.call_depth(), 0, "depth leaked by {}", body);
+        }
+
+        assert_eq!(
+            caught(
+                &mut interp,
+                "function f(n: number): number { return n ? f(n - 1) : 0; } f(40);"
+            ),
+            "ok"
+        );
+    });
+}
+
+/// A function of `statements` statements, the shapes generated manifests
+/// repeat: vars, branches, object literals and small arrows
+fn generated_function(statements: usize) -> String {
+    let mut source = String::from("function big(v: any) {\n");
+    for i in 0..statements {
+        let n = i % 100;
+        source.push_str(&match i % 4 {
+            0 => format!("var a{} = v;\n", i % 10_000),
+            1 => format!("if (v > {n}) {{ v = v - 1; }}\n"),
+            2 => format!("v = {{ k: v, n: {n} }}.k;\n"),
+            _ => format!("v = ((x: any) => x + {i})(v);\n"),
+        });
+    }
+    source.push_str("return v;\n}\nbig(1);\n");
+    source
+}
+
+#[test]
+#[allow(clippy::panic)]
+fn test_compile_limit_stops_huge_function() {
+    let source = generated_function(200_000);
+    let mut interp = Interpreter::new();
+    let start = Instant::now();
+    let err = interp
+        .prepare(&source, Some(ModulePath::new("/manifest.ts")))
+        .unwrap_err();
+    assert!(start.elapsed() < Duration::from_secs(60));
+
+    let JsError::CompileLimit {
+        limit,
+        max,
+        function,
+        location,
+    } = &err
+    else {
+        panic!("Expected a compile limit error, got {}", err);
+    };
+    assert_eq!(*limit, CompileLimitKind::ChunkInstructions);
+    assert_eq!(*max, tsrun::DEFAULT_MAX_CHUNK_INSTRUCTIONS);
+    assert_eq!(function.as_deref(), Some("big"));
+    assert!(
+        location
+            .as_ref()
+            .is_some_and(|loc| loc.file.as_deref() == Some("/manifest.ts") && loc.line > 1)
+    );
+    assert!(
+        err.to_string()
+            .starts_with("RangeError: Compiled code too large: more than 500000 instructions in function big at /manifest.ts:"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_large_function_compiles_in_linear_time() {
+    let source = generated_function(50_000);
+    let mut interp = Interpreter::new();
+    let start = Instant::now();
+    interp
+        .prepare(&source, Some(ModulePath::new("/manifest.ts")))
+        .unwrap();
+    assert!(start.elapsed() < Duration::from_secs(10));
+
+    let metrics = &interp.module_metrics()[0];
+    assert!(metrics.compile_ms < 10_000, "{:?}", metrics);
+    assert!(metrics.instruction_count < 50_000 * 12, "{:?}", metrics);
+    assert_eq!(metrics.function_count, 50_000 / 4 + 1);
+    // Each arrow is its own chunk, and none of them is mistaken for another
+    let cache = interp.chunk_cache_stats();
+    assert_eq!(cache.chunks_shared, 0);
+    assert_eq!(cache.live_chunks, metrics.function_count);
+}
+
+#[test]
+#[allow(clippy::panic)]
+fn test_compile_limits_from_config() {
+    let compile_error = |config: InterpreterConfig, source: &str| {
+        let mut interp = Interpreter::with_config(config);
+        match interp.prepare(source, None) {
+            Err(JsError::CompileLimit {
+                limit, function, ..
+            }) => (limit, function),
+            other => panic!("Expected a compile limit error, got {:?}", other.err()),
+        }
+    };
+
+    let nested = "const f = () => () => () => () => 1;";
+    assert_eq!(
+        compile_error(
+            InterpreterConfig {
+                max_function_depth: 3,
+                ..Default::default()
+            },
+            nested
+        ),
+        (CompileLimitKind::FunctionDepth, Some(String::new()))
+    );
+    assert!(
+        Interpreter::with_config(InterpreterConfig {
+            max_function_depth: 4,
+            ..Default::default()
+        })
+        .prepare(nested, None)
+        .is_ok()
+    );
+
+    let strings: String = (0..20).map(|i| format!("\"s{i}\";")).collect();
+    assert_eq!(
+        compile_error(
+            InterpreterConfig {
+                max_chunk_constants: 10,
+                ..Default::default()
+            },
+            &format!("function consts() {{ {strings} }}")
+        ),
+        (CompileLimitKind::ChunkConstants, Some("consts".to_string()))
+    );
+
+    let functions: String = (0..50)
+        .map(|i| format!("function f{i}(x: number) {{ return x * {i} + 1; }}\n"))
+        .collect();
+    assert_eq!(
+        compile_error(
+            InterpreterConfig {
+                max_program_instructions: 100,
+                ..Default::default()
+            },
+            &functions
+        )
+        .0,
+        CompileLimitKind::ProgramInstructions
+    );
+}
+
+#[test]
+fn test_compile_limit_in_eval_is_range_error() {
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        max_chunk_instructions: 1000,
+        ..Default::default()
+    });
+    let result = caught(
+        &mut interp,
+        "eval('let v = 0;' + 'v = v + 1;'.repeat(1000));",
+    );
+    assert!(
+        result.starts_with(
+            "RangeError: Compiled code too large: more than 1000 instructions in top-level code"
+        ),
+        "{}",
+        result
+    );
+}
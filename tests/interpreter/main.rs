@@ -16,25 +16,41 @@
 
 mod api;
 mod array;
+mod assert_module;
 mod async_await;
 mod async_iter;
+mod audit;
 mod basics;
 mod boolean;
 mod bytecode;
+mod checkpoint;
 mod class;
+mod coercion;
 mod console;
+mod constant_imports;
 mod control_flow;
 mod cycle_leak;
 mod date;
 mod decorator;
+mod disposable;
+mod duration;
 mod enum_test;
 mod error;
 mod eval;
+mod flight_recorder;
+mod fuel;
 mod function;
 mod gc;
 mod generator;
 mod global;
+mod host_class;
+mod host_driver;
+mod inspect;
+mod iterator;
 mod json;
+mod jsonc;
+mod lazy_json;
+mod limits;
 mod map;
 mod math;
 mod modules;
@@ -42,22 +58,40 @@ mod namespace;
 mod number;
 mod object;
 mod orders;
+mod pending_promises;
+mod performance;
+mod prepared;
+mod probes;
 mod promise;
+mod provenance;
 mod proxy;
+mod purity;
+mod quantity;
 mod regexp;
+mod sandbox;
 mod set;
+mod source_text;
 mod step;
 mod strict;
 mod string;
 mod symbol;
+mod transfer;
 mod typescript;
+mod url;
+mod weakref;
+#[cfg(feature = "yaml")]
+mod yaml;
 
-use tsrun::{Interpreter, JsError, JsValue, RuntimeValue, StepResult};
+use tsrun::{Interpreter, InterpreterConfig, JsError, JsValue, RuntimeValue, StepResult};
 
 /// Create a new interpreter with aggressive defaults for testing:
 /// - GC_THRESHOLD=1 (GC on every allocation) to catch GC bugs
+/// - dynamic code allowed, so `Function` and indirect eval can be exercised
 pub fn create_test_runtime() -> Interpreter {
-    let interp = Interpreter::new();
+    let interp = Interpreter::with_config(InterpreterConfig {
+        allow_dynamic_code: true,
+        ..Default::default()
+    });
 
     // Default to GC_THRESHOLD=1 (most aggressive) to catch GC bugs early
     // Override via environment variable if needed:
@@ -118,7 +152,7 @@ pub fn eval_result(source: &str) -> Result<RuntimeValue, JsError> {
             "Missing imports in test: {:?}",
             specifiers
         ))),
-        StepResult::Suspended { pending, .. } => {
+        StepResult::Suspended { pending, .. } | StepResult::Orders(pending) => {
             // For tests without external dependencies, this shouldn't happen
             // If it does, treat as error
             Err(JsError::type_error(format!(
@@ -274,6 +274,99 @@ fn test_map_groupby_returns_map_instance() {
     );
 }
 
+#[test]
+fn test_map_groupby_string_field() {
+    assert_eq!(
+        eval(
+            r#"
+            const services = [
+                { name: "api", ns: "prod" },
+                { name: "db", ns: "infra" },
+                { name: "web", ns: "prod" },
+            ];
+            const grouped = Map.groupBy(services, (s) => s.ns);
+            [[...grouped.keys()].join(), grouped.get("prod").map((s) => s.name).join(),
+             grouped.size, Array.isArray(grouped.get("infra"))].join(" ")
+        "#
+        ),
+        JsValue::from("prod,infra api,web 2 true")
+    );
+}
+
+#[test]
+fn test_map_groupby_object_keys_same_value_zero() {
+    assert_eq!(
+        eval(
+            r#"
+            const east = { region: "east" };
+            const west = { region: "west" };
+            const hosts = [
+                { host: "a", dc: east },
+                { host: "b", dc: west },
+                { host: "c", dc: east },
+            ];
+            const byDc = Map.groupBy(hosts, (h) => h.dc);
+            const zeros = Map.groupBy([0, -0, NaN, NaN], (n) => n);
+            [byDc.get(east).length, byDc.get(west).length, byDc.get({ region: "east" }),
+             zeros.size, zeros.get(0).length, Object.is([...zeros.keys()][0], 0),
+             zeros.get(NaN).length].join()
+        "#
+        ),
+        JsValue::from("2,1,,2,2,true,2")
+    );
+}
+
+#[test]
+fn test_map_groupby_iterables() {
+    assert_eq!(
+        eval(
+            r#"
+            function* gen() { yield "x1"; yield "y1"; yield "x2"; }
+            const grouped = Map.groupBy(gen(), (s, i) => s[0] + (i > 0 ? "" : "!"));
+            JSON.stringify([...grouped])
+        "#
+        ),
+        JsValue::from(r#"[["x!",["x1"]],["y",["y1"]],["x",["x2"]]]"#)
+    );
+}
+
+#[test]
+fn test_map_groupby_closes_iterator_on_callback_error() {
+    assert_eq!(
+        eval(
+            r#"
+            function countdown(n: number) {
+                const state = { closed: false };
+                const source = {
+                    state,
+                    [Symbol.iterator]() { return this; },
+                    next() {
+                        return n > 0 ? { value: n--, done: false } : { value: undefined, done: true };
+                    },
+                    return() {
+                        state.closed = true;
+                        return { value: undefined, done: true };
+                    },
+                };
+                return source;
+            }
+            const source = countdown(5);
+            let caught = "";
+            try {
+                Map.groupBy(source, (n: number) => {
+                    if (n === 3) throw new TypeError("bad key");
+                    return n;
+                });
+            } catch (e) {
+                caught = e.name + ": " + e.message;
+            }
+            caught + " " + source.state.closed
+        "#
+        ),
+        JsValue::from("TypeError: bad key true")
+    );
+}
+
 // =============================================================================
 // Map Iteration Tests (for...of, spread, Symbol.iterator)
 // =============================================================================
@@ -411,3 +504,61 @@ fn test_map_iterator_entries_equivalence() {
         JsValue::from("true")
     );
 }
+
+#[test]
+fn test_map_constructor_copies_another_map() {
+    assert_eq!(
+        eval(
+            r#"
+            const a = new Map([["x", 1], ["y", 2]]);
+            const b = new Map(a);
+            b.set("z", 3);
+            [a.size, b.size, b.get("y")].join(',')
+            "#
+        ),
+        JsValue::from("2,3,2")
+    );
+}
+
+#[test]
+fn test_map_keys_values_are_iterators() {
+    assert_eq!(
+        eval(
+            r#"
+            const m = new Map([["a", 1], ["b", 2]]);
+            const keys = m.keys();
+            const first = keys.next();
+            [first.value, first.done, [...keys].join(''), [...m.values()].join('')].join(',')
+            "#
+        ),
+        JsValue::from("a,false,b,12")
+    );
+}
+
+#[test]
+fn test_map_get_or_insert() {
+    assert_eq!(
+        eval(
+            r#"
+            const groups = new Map<string, number[]>();
+            groups.getOrInsert("odd", []).push(1);
+            groups.getOrInsert("odd", []).push(3);
+            groups.getOrInsertComputed("even", () => []).push(2);
+            let calls = 0;
+            groups.getOrInsertComputed("even", () => { calls++; return []; });
+            [groups.get("odd").join(''), groups.get("even").join(''), groups.size, calls].join(',')
+            "#
+        ),
+        JsValue::from("13,2,2,0")
+    );
+    assert_eq!(
+        eval("const m = new Map(); m.getOrInsert(-0, 'z'); Object.is([...m.keys()][0], 0)"),
+        JsValue::Boolean(true)
+    );
+    assert_eq!(
+        eval(
+            "let name = ''; try { new Map().getOrInsertComputed('k', 1 as any); } catch (e) { name = e.name; } name"
+        ),
+        JsValue::from("TypeError")
+    );
+}
@@ -191,3 +191,257 @@ fn test_math_method_descriptors() {
         JsValue::from("true,false,true")
     );
 }
+
+// ============================================================================
+// Spec edge cases (±0, ±Infinity, NaN)
+// ============================================================================
+
+/// Evaluate each `(expression, expected)` pair, comparing like `Object.is`
+#[track_caller]
+#[allow(clippy::panic)]
+fn assert_number_cases(cases: &[(&str, f64)]) {
+    for (expr, expected) in cases {
+        let value = eval(expr);
+        let JsValue::Number(actual) = *value else {
+            panic!("{} returned {:?}, expected a number", expr, *value);
+        };
+        let same = if expected.is_nan() {
+            actual.is_nan()
+        } else {
+            actual.to_bits() == expected.to_bits()
+        };
+        assert!(
+            same,
+            "{} returned {:?}, expected {:?}",
+            expr, actual, expected
+        );
+    }
+}
+
+#[test]
+fn test_math_hypot_edge_cases() {
+    assert_number_cases(&[
+        ("Math.hypot()", 0.0),
+        ("Math.hypot(-0)", 0.0),
+        ("Math.hypot(-0, -0)", 0.0),
+        ("Math.hypot(-3)", 3.0),
+        ("Math.hypot(3, 4)", 5.0),
+        ("Math.hypot(NaN, Infinity)", f64::INFINITY),
+        ("Math.hypot(-Infinity, NaN)", f64::INFINITY),
+        ("Math.hypot(NaN, 1)", f64::NAN),
+        ("Math.hypot(1e200, 1e200)", 1e200 * std::f64::consts::SQRT_2),
+        ("Math.hypot(3e-200, 4e-200)", 5e-200),
+        ("Math.hypot(1, 1, 1, 1)", 2.0),
+    ]);
+}
+
+#[test]
+fn test_math_clz32_edge_cases() {
+    assert_number_cases(&[
+        ("Math.clz32()", 32.0),
+        ("Math.clz32(0)", 32.0),
+        ("Math.clz32(-0)", 32.0),
+        ("Math.clz32(NaN)", 32.0),
+        ("Math.clz32(Infinity)", 32.0),
+        ("Math.clz32(-Infinity)", 32.0),
+        ("Math.clz32(1)", 31.0),
+        ("Math.clz32(1000)", 22.0),
+        ("Math.clz32(-1)", 0.0),
+        ("Math.clz32(0.5)", 32.0),
+        ("Math.clz32(2 ** 32)", 32.0),
+        ("Math.clz32(2 ** 32 + 1)", 31.0),
+        ("Math.clz32('8')", 28.0),
+    ]);
+}
+
+#[test]
+fn test_math_imul() {
+    assert_number_cases(&[
+        ("Math.imul(2, 4)", 8.0),
+        ("Math.imul(-1, 8)", -8.0),
+        ("Math.imul(-2, -2)", 4.0),
+        ("Math.imul(0xffffffff, 5)", -5.0),
+        ("Math.imul(0xfffffffe, 5)", -10.0),
+        ("Math.imul(2 ** 31, 2)", 0.0),
+        ("Math.imul(65536, 65536)", 0.0),
+        ("Math.imul(123456789, 987654321)", -67153019.0),
+        ("Math.imul(1.9, 2.9)", 2.0),
+        ("Math.imul(-0, 5)", 0.0),
+        ("Math.imul(NaN, 5)", 0.0),
+        ("Math.imul(Infinity, 5)", 0.0),
+        ("Math.imul(3)", 0.0),
+        ("Math.imul()", 0.0),
+        ("Math.imul('3', { valueOf() { return 4; } })", 12.0),
+        ("Math.imul.length", 2.0),
+    ]);
+}
+
+#[test]
+fn test_math_fround_edge_cases() {
+    assert_number_cases(&[
+        ("Math.fround(0)", 0.0),
+        ("Math.fround(-0)", -0.0),
+        ("Math.fround(NaN)", f64::NAN),
+        ("Math.fround(Infinity)", f64::INFINITY),
+        ("Math.fround(-Infinity)", f64::NEG_INFINITY),
+        ("Math.fround(1.5)", 1.5),
+        ("Math.fround(5.5)", 5.5),
+        ("Math.fround(5.05)", 5.050000190734863),
+        ("Math.fround(2 ** 128)", f64::INFINITY),
+        ("Math.fround(1e-50)", 0.0),
+    ]);
+}
+
+#[test]
+fn test_math_cbrt_edge_cases() {
+    assert_number_cases(&[
+        ("Math.cbrt(0)", 0.0),
+        ("Math.cbrt(-0)", -0.0),
+        ("Math.cbrt(NaN)", f64::NAN),
+        ("Math.cbrt(Infinity)", f64::INFINITY),
+        ("Math.cbrt(-Infinity)", f64::NEG_INFINITY),
+        ("Math.cbrt(27)", 3.0),
+        ("Math.cbrt(-8)", -2.0),
+    ]);
+}
+
+#[test]
+fn test_math_sign_edge_cases() {
+    assert_number_cases(&[
+        ("Math.sign(0)", 0.0),
+        ("Math.sign(-0)", -0.0),
+        ("Math.sign(NaN)", f64::NAN),
+        ("Math.sign(Infinity)", 1.0),
+        ("Math.sign(-Infinity)", -1.0),
+        ("Math.sign(-3)", -1.0),
+        ("Math.sign('7')", 1.0),
+    ]);
+}
+
+#[test]
+fn test_math_trunc_floor_round_negatives() {
+    assert_number_cases(&[
+        ("Math.trunc(-0.5)", -0.0),
+        ("Math.trunc(-4.7)", -4.0),
+        ("Math.trunc(-Infinity)", f64::NEG_INFINITY),
+        ("Math.trunc(NaN)", f64::NAN),
+        ("Math.floor(-0.5)", -1.0),
+        ("Math.floor(-0)", -0.0),
+        ("Math.floor(-4.7)", -5.0),
+        ("Math.ceil(-0.5)", -0.0),
+        ("Math.round(-0)", -0.0),
+        ("Math.round(-0.4)", -0.0),
+        ("Math.round(-0.5)", -0.0),
+        ("Math.round(-2.5)", -2.0),
+        ("Math.round(-2.6)", -3.0),
+        ("Math.round(2.5)", 3.0),
+        ("Math.round(0.49999999999999994)", 0.0),
+        ("Math.round(Infinity)", f64::INFINITY),
+        ("Math.round(NaN)", f64::NAN),
+    ]);
+}
+
+#[test]
+fn test_math_min_max_edge_cases() {
+    assert_number_cases(&[
+        ("Math.min()", f64::INFINITY),
+        ("Math.max()", f64::NEG_INFINITY),
+        ("Math.min(0, -0)", -0.0),
+        ("Math.min(-0, 0)", -0.0),
+        ("Math.max(0, -0)", 0.0),
+        ("Math.max(-0, 0)", 0.0),
+        ("Math.max(-0, -0)", -0.0),
+        ("Math.min(1, NaN, 0)", f64::NAN),
+        ("Math.max(NaN, 1)", f64::NAN),
+        ("Math.max(1, Infinity)", f64::INFINITY),
+        ("Math.min(-Infinity, 1)", f64::NEG_INFINITY),
+    ]);
+}
+
+#[test]
+fn test_math_min_coerces_every_argument_after_nan() {
+    assert_eq!(
+        eval(
+            r#"
+            let seen = 0;
+            const counted = { valueOf() { seen++; return 1; } };
+            const result = Math.min(NaN, counted, counted);
+            [String(result), seen].join(",")
+        "#
+        ),
+        JsValue::from("NaN,2")
+    );
+}
+
+#[test]
+fn test_math_hypot_coerces_arguments() {
+    assert_eq!(
+        eval("Math.hypot({ valueOf() { return 3; } }, '4')"),
+        JsValue::Number(5.0)
+    );
+}
+
+#[test]
+fn test_math_sum_precise_avoids_drift() {
+    assert_eq!(
+        eval(
+            r#"
+            const costs = [0.1, 0.2, 0.3];
+            const naive = costs.reduce((a, b) => a + b, 0);
+            [naive, Math.sumPrecise(costs), Math.sumPrecise([1e20, 0.1, -1e20])].join()
+        "#
+        ),
+        JsValue::from("0.6000000000000001,0.6,0.1")
+    );
+    // Intermediate sums past the largest double still come out finite
+    assert_eq!(
+        eval("Math.sumPrecise([Number.MAX_VALUE, Number.MAX_VALUE, -Number.MAX_VALUE])"),
+        JsValue::Number(f64::MAX)
+    );
+}
+
+#[test]
+fn test_math_sum_precise_special_values() {
+    assert_eq!(
+        eval(
+            r#"
+            [
+                Object.is(Math.sumPrecise([]), -0),
+                Object.is(Math.sumPrecise([-0, -0]), -0),
+                Object.is(Math.sumPrecise([-0, 0]), 0),
+                Math.sumPrecise([1, Infinity]),
+                Math.sumPrecise([Infinity, -Infinity]),
+                Math.sumPrecise([1, NaN]),
+                Math.sumPrecise(new Set([1, 2, 3])),
+            ].join()
+        "#
+        ),
+        JsValue::from("true,true,true,Infinity,NaN,NaN,6")
+    );
+}
+
+#[test]
+fn test_math_sum_precise_rejects_non_numbers() {
+    assert_eq!(
+        eval(
+            r#"
+            let closed = false;
+            const iterable = {
+                [Symbol.iterator]() {
+                    let i = 0;
+                    return {
+                        next: () => ({ value: i++ === 0 ? 1 : "2", done: false }),
+                        return() { closed = true; return {}; },
+                    };
+                },
+            };
+            const errors = [];
+            for (const input of [iterable, 5, [1, null]]) {
+                try { Math.sumPrecise(input); } catch (e) { errors.push(e.name); }
+            }
+            [errors.join(" "), closed].join()
+        "#
+        ),
+        JsValue::from("TypeError TypeError TypeError,true")
+    );
+}
@@ -1,9 +1,13 @@
 //! Tests for the module system and order API
 
 use super::{run, run_to_completion};
+use std::cell::RefCell;
+use std::rc::Rc;
+use tsrun::platform::TimeProvider;
 use tsrun::{
-    Guarded, InternalModule, Interpreter, InterpreterConfig, JsError, JsValue, ModulePath,
-    RuntimeValue, StepResult, value::PropertyKey,
+    GeneratorStep, Guarded, ImportKind, InternalModule, Interpreter, InterpreterConfig, JsError,
+    JsValue, Lang, ModuleHooks, ModuleMetrics, ModulePath, RuntimeValue, SharedModuleSet,
+    StepResult, UrlQuery, value::PropertyKey,
 };
 
 #[test]
@@ -77,7 +81,7 @@ fn test_provide_module() {
                 StepResult::NeedImports(_) => {
                     panic!("Should not need more imports after providing module");
                 }
-                StepResult::Suspended { .. } => {
+                StepResult::Suspended { .. } | StepResult::Orders(_) => {
                     panic!("Unexpected suspended state");
                 }
                 StepResult::Continue => {
@@ -88,7 +92,7 @@ fn test_provide_module() {
         StepResult::Complete(_) | StepResult::Done => {
             panic!("Expected NeedImports, got Complete/Done");
         }
-        StepResult::Suspended { .. } => {
+        StepResult::Suspended { .. } | StepResult::Orders(_) => {
             panic!("Expected NeedImports, got Suspended");
         }
         StepResult::Continue => {
@@ -97,6 +101,432 @@ fn test_provide_module() {
     }
 }
 
+#[test]
+fn test_provide_module_typo_reports_requested_paths() {
+    let mut interp = Interpreter::new();
+    let result = run(
+        &mut interp,
+        r#"
+        import { add } from "./math";
+        add(2, 3);
+    "#,
+        Some("/project/main.ts"),
+    )
+    .unwrap();
+
+    let StepResult::NeedImports(imports) = result else {
+        panic!("Expected NeedImports result");
+    };
+    assert_eq!(imports[0].resolved_path.as_str(), "/project/math");
+
+    // Passing the specifier instead of the resolved path is a mistake
+    let err = interp
+        .provide_module(ModulePath::new("./math"), "export const add = 1;")
+        .unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("'./math' was not requested"), "{message}");
+    assert!(message.contains("awaiting '/project/math'"), "{message}");
+    assert!(
+        message.contains("Did you mean '/project/math'"),
+        "{message}"
+    );
+
+    // The correct path is still accepted afterwards
+    interp
+        .provide_module(
+            imports[0].resolved_path.clone(),
+            "export function add(a: number, b: number): number { return a + b; }",
+        )
+        .unwrap();
+    let result = run_to_completion(&mut interp).unwrap();
+    assert!(matches!(result, StepResult::Complete(ref v) if *v == JsValue::Number(5.0)));
+}
+
+/// Leaves shared by the fan-out modules, not in alphabetical order
+const FAN_OUT_LEAVES: [&str; 3] = ["/leaf/c", "/leaf/a", "/leaf/b"];
+
+/// Source of fan-out module `i`, importing two of the shared leaves
+#[allow(clippy::indexing_slicing)]
+fn fan_out_module(i: usize) -> String {
+    let first = FAN_OUT_LEAVES[i % 3];
+    let second = FAN_OUT_LEAVES[(i + 2) % 3];
+    format!(
+        "import {{ v as x }} from \"{first}\";\nimport {{ v as y }} from \"{second}\";\nexport const v = x + y + {i};"
+    )
+}
+
+/// `NeedImports` batches as resolved paths, and each request's path with
+/// the module that first requested it
+type ImportBatches = (Vec<Vec<String>>, Vec<(String, Option<String>)>);
+
+/// Run a main module importing ten fan-out modules, providing every batch
+/// of `NeedImports` in full
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn run_fan_out() -> ImportBatches {
+    let main: String = (0..10)
+        .map(|i| format!("import {{ v as m{i} }} from \"/mods/m{i}\";\n"))
+        .chain(std::iter::once(
+            (0..10)
+                .map(|i| format!("m{i}"))
+                .collect::<Vec<_>>()
+                .join(" + ")
+                + ";",
+        ))
+        .collect();
+    let mut interp = Interpreter::new();
+    let mut batches = Vec::new();
+    let mut first_requested = Vec::new();
+    let mut result = run(&mut interp, &main, Some("/main.ts")).unwrap();
+    loop {
+        match result {
+            StepResult::NeedImports(imports) => {
+                batches.push(
+                    imports
+                        .iter()
+                        .map(|req| req.resolved_path.to_string())
+                        .collect(),
+                );
+                for req in imports {
+                    first_requested.push((
+                        req.resolved_path.to_string(),
+                        req.first_requested_by.map(|path| path.to_string()),
+                    ));
+                    let path = req.resolved_path.as_str();
+                    let source = match path.strip_prefix("/mods/m") {
+                        Some(i) => fan_out_module(i.parse().unwrap()),
+                        None => "export const v = 100;".to_string(),
+                    };
+                    interp.provide_module(req.resolved_path, &source).unwrap();
+                }
+                result = run_to_completion(&mut interp).unwrap();
+            }
+            StepResult::Complete(value) => {
+                // 10 modules each adding two leaves and their index
+                assert_eq!(value, JsValue::Number(10.0 * 200.0 + 45.0));
+                return (batches, first_requested);
+            }
+            other => panic!("Expected NeedImports or Complete, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_need_imports_fan_out_surfaces_each_leaf_once_in_order() {
+    let (batches, first_requested) = run_fan_out();
+    let modules: Vec<String> = (0..10).map(|i| format!("/mods/m{i}")).collect();
+    // m0 imports c then b, m1 imports a
+    let leaves = ["/leaf/c", "/leaf/b", "/leaf/a"];
+    assert_eq!(
+        batches,
+        vec![modules, leaves.iter().map(|l| l.to_string()).collect()]
+    );
+    assert_eq!(
+        first_requested
+            .iter()
+            .filter(|(path, _)| path.starts_with("/leaf/"))
+            .map(|(path, by)| (path.as_str(), by.as_deref()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("/leaf/c", Some("/mods/m0")),
+            ("/leaf/b", Some("/mods/m0")),
+            ("/leaf/a", Some("/mods/m1")),
+        ]
+    );
+
+    // The same program asks in the same order every run
+    for _ in 0..5 {
+        assert_eq!(run_fan_out().0, batches);
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_need_imports_not_repeated_while_awaited() {
+    let mut interp = Interpreter::new();
+    let result = run(
+        &mut interp,
+        r#"
+        import { a } from "/lib/a";
+        import { b } from "/lib/b";
+        a + b;
+    "#,
+        None,
+    )
+    .unwrap();
+    let StepResult::NeedImports(imports) = result else {
+        panic!("Expected NeedImports, got {:?}", result);
+    };
+    assert_eq!(imports.len(), 2);
+
+    // /lib/b is still awaited, so only what /lib/a imports is new
+    interp
+        .provide_module(
+            ModulePath::new("/lib/a"),
+            r#"import { b } from "/lib/b"; import { c } from "/lib/c"; export const a = b + c;"#,
+        )
+        .unwrap();
+    let StepResult::NeedImports(imports) = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected NeedImports");
+    };
+    let paths: Vec<&str> = imports
+        .iter()
+        .map(|req| req.resolved_path.as_str())
+        .collect();
+    assert_eq!(paths, vec!["/lib/c"]);
+
+    // A module that fails to parse is asked for again
+    let err = interp.provide_module(ModulePath::new("/lib/c"), "export const c = ;");
+    assert!(err.is_err());
+    interp
+        .provide_module(ModulePath::new("/lib/b"), "export const b = 2;")
+        .unwrap();
+    let StepResult::NeedImports(imports) = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected NeedImports");
+    };
+    let paths: Vec<&str> = imports
+        .iter()
+        .map(|req| req.resolved_path.as_str())
+        .collect();
+    assert_eq!(paths, vec!["/lib/c"]);
+    assert_eq!(
+        imports[0].first_requested_by,
+        Some(ModulePath::new("/lib/a"))
+    );
+
+    interp
+        .provide_module(ModulePath::new("/lib/c"), "export const c = 3;")
+        .unwrap();
+    let result = run_to_completion(&mut interp).unwrap();
+    assert!(matches!(result, StepResult::Complete(ref v) if *v == JsValue::Number(7.0)));
+}
+
+#[test]
+fn test_provide_module_after_completion_errors() {
+    let mut interp = Interpreter::new();
+    let result = run(&mut interp, "1 + 2", None).unwrap();
+    assert!(matches!(result, StepResult::Complete(_)));
+
+    let err = interp
+        .provide_module(ModulePath::new("/lib/late"), "export const x = 1;")
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("not waiting for imports"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_provide_module_unrequested_preseeds() {
+    let mut interp = Interpreter::new();
+    interp
+        .provide_module_unrequested(
+            ModulePath::new("/lib/math"),
+            "export function double(x: number): number { return x * 2; }",
+        )
+        .unwrap();
+
+    // The pre-seeded module satisfies the import without a NeedImports round trip
+    let result = run(
+        &mut interp,
+        r#"
+        import { double } from "/lib/math";
+        double(21);
+    "#,
+        None,
+    )
+    .unwrap();
+    assert!(matches!(result, StepResult::Complete(ref v) if *v == JsValue::Number(42.0)));
+}
+
+/// Module `i` of a 200-module graph, importing the two modules before it
+fn graph_module(i: usize) -> (ModulePath, String) {
+    let source = match i {
+        0 => "export const value = 1;".to_string(),
+        1 => "import { value as a } from \"./m0.ts\";\nexport const value = a + 1;".to_string(),
+        _ => format!(
+            "import {{ value as a }} from \"./m{}.ts\";\n\
+             import {{ value as b }} from \"./m{}.ts\";\n\
+             export const value = (a + b) % 1000;",
+            i - 1,
+            i - 2
+        ),
+    };
+    (ModulePath::new(format!("/graph/m{}.ts", i)), source)
+}
+
+#[test]
+fn test_prepare_with_modules_runs_graph_without_need_imports() {
+    const MODULES: usize = 200;
+    let mut interp = Interpreter::new();
+    let scans_before = interp.module_import_scans();
+
+    let result = interp
+        .prepare_with_modules(
+            "import { value } from \"./m199.ts\";\nvalue;",
+            Some(ModulePath::new("/graph/main.ts")),
+            (0..MODULES).map(graph_module),
+        )
+        .unwrap();
+    assert!(matches!(result, StepResult::Continue));
+
+    let result = run_to_completion(&mut interp).unwrap();
+    let mut expected = [1u64, 2];
+    for _ in 2..MODULES {
+        expected = [expected[1], (expected[0] + expected[1]) % 1000];
+    }
+    assert!(
+        matches!(result, StepResult::Complete(ref v) if *v == JsValue::Number(expected[1] as f64))
+    );
+
+    // Each module's imports are collected once, not once per module that runs
+    let scans = interp.module_import_scans() - scans_before;
+    assert!(scans <= 2 * MODULES, "{} import scans", scans);
+}
+
+#[test]
+fn test_prepare_with_modules_reports_only_unknown_imports() {
+    let mut interp = Interpreter::new();
+    let result = interp
+        .prepare_with_modules(
+            "import { a } from \"./a.ts\";\na;",
+            Some(ModulePath::new("/app/main.ts")),
+            [(
+                ModulePath::new("/app/a.ts"),
+                "import { b } from \"./missing.ts\";\nexport const a = b + 1;".to_string(),
+            )],
+        )
+        .unwrap();
+    let StepResult::NeedImports(requests) = result else {
+        panic!("expected NeedImports");
+    };
+    let paths: Vec<&str> = requests
+        .iter()
+        .map(|req| req.resolved_path.as_str())
+        .collect();
+    assert_eq!(paths, ["/app/missing.ts"]);
+
+    interp
+        .provide_module(ModulePath::new("/app/missing.ts"), "export const b = 41;")
+        .unwrap();
+    let result = run_to_completion(&mut interp).unwrap();
+    assert!(matches!(result, StepResult::Complete(ref v) if *v == JsValue::Number(42.0)));
+}
+
+/// Two plugins registering into a shared registry, imported by main in the
+/// order plugin-b, plugin-a
+fn plugin_modules() -> [(ModulePath, String); 3] {
+    let plugin = |name: &str| {
+        format!(
+            "import {{ registry }} from \"./registry.ts\";\nregistry.push(\"{}\");",
+            name
+        )
+    };
+    [
+        (
+            ModulePath::new("/app/registry.ts"),
+            "export const registry: string[] = [];".to_string(),
+        ),
+        (ModulePath::new("/app/plugin-a.ts"), plugin("a")),
+        (ModulePath::new("/app/plugin-b.ts"), plugin("b")),
+    ]
+}
+
+const PLUGIN_MAIN: &str = r#"
+import "./plugin-b.ts";
+import "./plugin-a.ts";
+import { registry } from "./registry.ts";
+registry.join(",");
+"#;
+
+/// Run PLUGIN_MAIN with `order` choosing the module execution order
+fn run_plugins(order: Option<tsrun::ModuleExecutionOrder>) -> Result<JsValue, JsError> {
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        module_execution_order: order,
+        ..Default::default()
+    });
+    interp.prepare_with_modules(
+        PLUGIN_MAIN,
+        Some(ModulePath::new("/app/main.ts")),
+        plugin_modules(),
+    )?;
+    match run_to_completion(&mut interp)? {
+        StepResult::Complete(value) => Ok(value.value().clone()),
+        other => Err(JsError::internal_error(format!("{:?}", other))),
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_ready_modules_run_in_discovery_order() {
+    for _ in 0..5 {
+        assert_eq!(run_plugins(None).unwrap(), JsValue::from("b,a"));
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_module_execution_order_hook() {
+    let rounds: Rc<RefCell<Vec<Vec<String>>>> = Rc::new(RefCell::new(Vec::new()));
+    let seen = rounds.clone();
+    let reversed = run_plugins(Some(Box::new(move |ready: &[ModulePath]| {
+        seen.borrow_mut()
+            .push(ready.iter().map(|path| path.to_string()).collect());
+        ready.iter().rev().cloned().collect()
+    })))
+    .unwrap();
+    assert_eq!(reversed, JsValue::from("a,b"));
+    assert_eq!(
+        *rounds.borrow(),
+        [
+            vec!["/app/registry.ts"],
+            vec!["/app/plugin-b.ts", "/app/plugin-a.ts"]
+        ]
+    );
+
+    // Modules left out are offered again in the next round
+    rounds.borrow_mut().clear();
+    let seen = rounds.clone();
+    let one_at_a_time = run_plugins(Some(Box::new(move |ready: &[ModulePath]| {
+        seen.borrow_mut()
+            .push(ready.iter().map(|path| path.to_string()).collect());
+        ready.iter().skip(ready.len() - 1).cloned().collect()
+    })))
+    .unwrap();
+    assert_eq!(one_at_a_time, JsValue::from("a,b"));
+    assert_eq!(
+        *rounds.borrow(),
+        [
+            vec!["/app/registry.ts"],
+            vec!["/app/plugin-b.ts", "/app/plugin-a.ts"],
+            vec!["/app/plugin-b.ts"]
+        ]
+    );
+}
+
+#[test]
+fn test_module_execution_order_hook_misuse() {
+    let err = run_plugins(Some(Box::new(|_: &[ModulePath]| {
+        vec![ModulePath::new("/app/other.ts")]
+    })))
+    .unwrap_err();
+    assert!(matches!(err, JsError::ModuleError { .. }), "{:?}", err);
+    assert_eq!(
+        err.to_string(),
+        "ModuleError: module_execution_order returned /app/other.ts, \
+         which is not one of the ready modules [/app/registry.ts]"
+    );
+
+    let err = run_plugins(Some(Box::new(|ready: &[ModulePath]| {
+        ready.iter().chain(ready).cloned().collect()
+    })))
+    .unwrap_err();
+    assert!(err.to_string().contains("more than once"), "{}", err);
+
+    let err = run_plugins(Some(Box::new(|_: &[ModulePath]| Vec::new()))).unwrap_err();
+    assert!(err.to_string().contains("chose none"), "{}", err);
+}
+
 #[test]
 fn test_internal_module_registered() {
     // Create a native internal module
@@ -256,6 +686,182 @@ fn test_source_internal_module() {
     }
 }
 
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_import_with_attributes_runs() {
+    let settings = InternalModule::source("eval:settings", "export const retries = 3;");
+    let config = InterpreterConfig {
+        internal_modules: vec![settings],
+        ..Default::default()
+    };
+    let mut interp = Interpreter::with_config(config);
+
+    let result = run(
+        &mut interp,
+        r#"
+        import { retries } from "eval:settings" with { type: "json" };
+        import * as all from "eval:settings" assert { type: "json" };
+        retries + all.retries;
+    "#,
+        None,
+    )
+    .unwrap();
+
+    match result {
+        StepResult::Complete(value) => assert_eq!(value, JsValue::Number(6.0)),
+        other => panic!("Expected Complete, got {:?}", other),
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_internal_module_imports_internal_module() {
+    let units = InternalModule::source(
+        "eval:units",
+        r#"
+        (globalThis as any).loaded = [...((globalThis as any).loaded ?? []), "units"];
+        export const KB = 1024;
+    "#,
+    );
+    let sizes = InternalModule::source(
+        "eval:sizes",
+        r#"
+        import { KB } from "eval:units";
+        (globalThis as any).loaded = [...((globalThis as any).loaded ?? []), "sizes"];
+        export function kilobytes(n: number): number {
+            return n * KB;
+        }
+    "#,
+    );
+    let config = InterpreterConfig {
+        // Registered dependents first, so the dependency can't have been instantiated yet
+        internal_modules: vec![sizes, units],
+        ..Default::default()
+    };
+    let mut interp = Interpreter::with_config(config);
+
+    let result = run(
+        &mut interp,
+        r#"
+        import { kilobytes } from "eval:sizes";
+        (globalThis as any).loaded.join(",") + ":" + kilobytes(2);
+    "#,
+        None,
+    )
+    .unwrap();
+
+    match result {
+        StepResult::Complete(value) => {
+            assert_eq!(value, JsValue::String("units,sizes:2048".into()))
+        }
+        other => panic!("Expected Complete, got {:?}", other),
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_internal_module_imports_host_module() {
+    let db = InternalModule::source(
+        "eval:db",
+        r#"
+        import { table } from "/lib/schema.ts";
+        export function query(id: number): string {
+            return `select * from ${table} where id = ${id}`;
+        }
+    "#,
+    );
+    let config = InterpreterConfig {
+        internal_modules: vec![db],
+        ..Default::default()
+    };
+    let mut interp = Interpreter::with_config(config);
+
+    let result = run(
+        &mut interp,
+        r#"
+        import { query } from "eval:db";
+        query(7);
+    "#,
+        Some("/main.ts"),
+    )
+    .unwrap();
+
+    // The internal module's dependency is requested like the program's own
+    let StepResult::NeedImports(requests) = result else {
+        panic!("Expected NeedImports, got {:?}", result);
+    };
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].resolved_path.as_str(), "/lib/schema.ts");
+    assert_eq!(
+        requests[0].importer.as_ref().map(|p| p.as_str()),
+        Some("eval:db")
+    );
+
+    interp
+        .provide_module(
+            requests[0].resolved_path.clone(),
+            r#"export const table = "users";"#,
+        )
+        .unwrap();
+    match run_to_completion(&mut interp).unwrap() {
+        StepResult::Complete(value) => assert_eq!(
+            value,
+            JsValue::String("select * from users where id = 7".into())
+        ),
+        other => panic!("Expected Complete, got {:?}", other),
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_internal_module_cycle_is_named() {
+    let first = InternalModule::source(
+        "eval:first",
+        r#"
+        import { second } from "eval:second";
+        export const first = () => second;
+    "#,
+    );
+    let second = InternalModule::source(
+        "eval:second",
+        r#"
+        import { first } from "eval:first";
+        export const second = () => first;
+    "#,
+    );
+    let config = InterpreterConfig {
+        internal_modules: vec![first, second],
+        ..Default::default()
+    };
+    let mut interp = Interpreter::with_config(config);
+
+    let err = run(
+        &mut interp,
+        r#"
+        import { first } from "eval:first";
+        first;
+    "#,
+        None,
+    )
+    .unwrap_err();
+
+    let message = err.to_string();
+    assert!(
+        message.contains("eval:first → eval:second → eval:first"),
+        "{}",
+        message
+    );
+
+    // Instantiating without collecting imports first stops at the cycle too
+    let err = interp.resolve_module("eval:second").unwrap_err();
+    let message = err.to_string();
+    assert!(
+        message.contains("eval:second → eval:first → eval:second"),
+        "{}",
+        message
+    );
+}
+
 #[test]
 fn test_import_namespace() {
     // Test namespace import: import * as foo from "module"
@@ -917,66 +1523,625 @@ fn test_export_class() {
     }
 }
 
-// ═══════════════════════════════════════════════════════════════════════════════
-// Module Path Resolution Tests
-// ═══════════════════════════════════════════════════════════════════════════════
-
-#[test]
-fn test_module_path_normalize() {
-    // Test that paths are normalized correctly
-    assert_eq!(ModulePath::resolve("./utils", None).as_str(), "utils");
-    assert_eq!(ModulePath::resolve("./foo/bar", None).as_str(), "foo/bar");
-    assert_eq!(ModulePath::resolve("./a/b/../c", None).as_str(), "a/c");
-    assert_eq!(ModulePath::resolve("./a/./b/./c", None).as_str(), "a/b/c");
+/// Run `main` against a provided `/lib/util` module and return the error message
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn missing_export_error(main: &str) -> String {
+    let mut interp = Interpreter::new();
+    let request = match run(&mut interp, main, None).unwrap() {
+        StepResult::NeedImports(mut imports) => imports.remove(0),
+        other => panic!("Expected NeedImports, got {:?}", other),
+    };
+    interp
+        .provide_module(
+            request.resolved_path,
+            "export function format(s: string): string { return s; }",
+        )
+        .unwrap();
+    match run_to_completion(&mut interp) {
+        Err(err) => err
+            .to_string()
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+        Ok(other) => panic!("Expected an error, got {:?}", other),
+    }
 }
 
 #[test]
-fn test_module_path_resolve_with_base() {
-    let base = ModulePath::new("/project/src/main.ts");
-
-    // Relative import from main module
-    assert_eq!(
-        ModulePath::resolve("./utils", Some(&base)).as_str(),
-        "/project/src/utils"
-    );
-
-    // Parent directory
-    assert_eq!(
-        ModulePath::resolve("../shared/lib", Some(&base)).as_str(),
-        "/project/shared/lib"
+fn test_call_missing_named_import_names_module() {
+    let message = missing_export_error(
+        r#"
+        import { render } from "/lib/util";
+        render("x");
+    "#,
     );
-
-    // Multiple parent levels
     assert_eq!(
-        ModulePath::resolve("../../config", Some(&base)).as_str(),
-        "/config"
+        message,
+        "TypeError: Module '/lib/util' has no export 'render'"
     );
 }
 
 #[test]
-fn test_module_path_bare_specifier() {
-    // Bare specifiers (npm packages) should pass through unchanged
-    assert_eq!(ModulePath::resolve("lodash", None).as_str(), "lodash");
-    assert_eq!(
-        ModulePath::resolve("@scope/package", None).as_str(),
-        "@scope/package"
+fn test_call_missing_namespace_member_names_module() {
+    let message = missing_export_error(
+        r#"
+        import * as util from "/lib/util";
+        util.format("ok");
+        util.render("x");
+    "#,
     );
-
-    // Bare specifiers should ignore base path
-    let base = ModulePath::new("/project/src/main.ts");
     assert_eq!(
-        ModulePath::resolve("lodash", Some(&base)).as_str(),
-        "lodash"
+        message,
+        "TypeError: Module '/lib/util' has no export 'render'"
     );
 }
 
 #[test]
-fn test_module_path_absolute() {
+fn test_call_non_function_export_keeps_callee_name() {
+    let message = missing_export_error(
+        r#"
+        import { format } from "/lib/util";
+        const helpers: any = { format };
+        helpers.render("x");
+    "#,
+    );
+    assert_eq!(message, "TypeError: helpers.render is not a function");
+}
+
+/// Run `main` against a provided `/lib/config` module, with
+/// `strict_property_access` set to `strict`
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn run_with_config_module(main: &str, strict: bool) -> Result<JsValue, String> {
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        strict_property_access: strict,
+        ..Default::default()
+    });
+    let request = match run(&mut interp, main, None).unwrap() {
+        StepResult::NeedImports(mut imports) => imports.remove(0),
+        other => panic!("Expected NeedImports, got {:?}", other),
+    };
+    interp
+        .provide_module(
+            request.resolved_path,
+            "export const replicas = 3; export const region = 'eu';",
+        )
+        .unwrap();
+    match run_to_completion(&mut interp) {
+        Ok(StepResult::Complete(value)) => Ok(value.value().clone()),
+        Ok(other) => panic!("Expected Complete, got {:?}", other),
+        Err(err) => Err(err
+            .to_string()
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string()),
+    }
+}
+
+#[test]
+fn test_strict_property_access_suggests_export() {
+    assert_eq!(
+        run_with_config_module(
+            r#"
+            import * as cfg from "/lib/config";
+            cfg.replcias;
+        "#,
+            true
+        ),
+        Err(
+            "TypeError: Property 'replcias' does not exist on module '/lib/config'; did you mean 'replicas'?"
+                .to_string()
+        )
+    );
+    // Nothing close enough to suggest
+    assert_eq!(
+        run_with_config_module(
+            r#"
+            import * as cfg from "/lib/config";
+            cfg.timeout;
+        "#,
+            true
+        ),
+        Err("TypeError: Property 'timeout' does not exist on module '/lib/config'".to_string())
+    );
+}
+
+#[test]
+fn test_strict_property_access_allows_exports_and_plain_objects() {
+    assert_eq!(
+        run_with_config_module(
+            r#"
+            import * as cfg from "/lib/config";
+            const local: any = { replicas: 1 };
+            [cfg.replicas, cfg.region, "replicas" in cfg, local.replcias].join()
+        "#,
+            true
+        ),
+        Ok(JsValue::from("3,eu,true,"))
+    );
+}
+
+// Native function for testing: returns deep-frozen host context
+fn test_get_context(
+    interp: &mut Interpreter,
+    _this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let guard = tsrun::api::create_guard(interp);
+    let context = tsrun::api::create_from_json(
+        interp,
+        &guard,
+        &serde_json::json!({ "user": { "name": "Ada" }, "locale": "en" }),
+    )?;
+    interp.deep_freeze(&RuntimeValue::unguarded(context.clone()))?;
+    Ok(Guarded::with_guard(context, guard))
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_strict_property_access_on_host_context() {
+    let context_module = InternalModule::native("eval:context")
+        .with_function("getContext", test_get_context, 0)
+        .build();
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        internal_modules: vec![context_module],
+        strict_property_access: true,
+        ..Default::default()
+    });
+    let result = run(
+        &mut interp,
+        r#"
+        import { getContext } from "eval:context";
+        const ctx = getContext();
+        let message = "";
+        try { ctx.user.nmae; } catch (e) { message = e.message; }
+        [ctx.user.name, ctx.locale, ctx.hasOwnProperty("locale"), message].join("|")
+    "#,
+        None,
+    )
+    .unwrap();
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete, got {:?}", result);
+    };
+    assert_eq!(
+        value,
+        JsValue::from(
+            "Ada|en|true|Property 'nmae' does not exist on the host object; did you mean 'name'?"
+        )
+    );
+}
+
+#[test]
+fn test_strict_property_access_off_reads_undefined() {
+    assert_eq!(
+        run_with_config_module(
+            r#"
+            import * as cfg from "/lib/config";
+            cfg.replcias === undefined
+        "#,
+            false
+        ),
+        Ok(JsValue::Boolean(true))
+    );
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Module Path Resolution Tests
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_module_path_normalize() {
+    // Test that paths are normalized correctly
+    assert_eq!(ModulePath::resolve("./utils", None).as_str(), "utils");
+    assert_eq!(ModulePath::resolve("./foo/bar", None).as_str(), "foo/bar");
+    assert_eq!(ModulePath::resolve("./a/b/../c", None).as_str(), "a/c");
+    assert_eq!(ModulePath::resolve("./a/./b/./c", None).as_str(), "a/b/c");
+}
+
+#[test]
+fn test_module_path_resolve_with_base() {
+    let base = ModulePath::new("/project/src/main.ts");
+
+    // Relative import from main module
+    assert_eq!(
+        ModulePath::resolve("./utils", Some(&base)).as_str(),
+        "/project/src/utils"
+    );
+
+    // Parent directory
+    assert_eq!(
+        ModulePath::resolve("../shared/lib", Some(&base)).as_str(),
+        "/project/shared/lib"
+    );
+
+    // Multiple parent levels
+    assert_eq!(
+        ModulePath::resolve("../../config", Some(&base)).as_str(),
+        "/config"
+    );
+}
+
+#[test]
+fn test_module_path_bare_specifier() {
+    // Bare specifiers (npm packages) should pass through unchanged
+    assert_eq!(ModulePath::resolve("lodash", None).as_str(), "lodash");
+    assert_eq!(
+        ModulePath::resolve("@scope/package", None).as_str(),
+        "@scope/package"
+    );
+
+    // Bare specifiers should ignore base path
+    let base = ModulePath::new("/project/src/main.ts");
+    assert_eq!(
+        ModulePath::resolve("lodash", Some(&base)).as_str(),
+        "lodash"
+    );
+}
+
+#[test]
+fn test_module_path_absolute() {
     // Absolute paths should just be normalized
     assert_eq!(ModulePath::resolve("/foo/bar", None).as_str(), "/foo/bar");
     assert_eq!(ModulePath::resolve("/foo/../bar", None).as_str(), "/bar");
 }
 
+#[test]
+fn test_module_path_kind() {
+    assert_eq!(ModulePath::kind("./utils"), ImportKind::Relative);
+    assert_eq!(ModulePath::kind("../utils"), ImportKind::Relative);
+    assert_eq!(ModulePath::kind("/lib/utils"), ImportKind::Absolute);
+    assert_eq!(ModulePath::kind("lodash"), ImportKind::Bare);
+    assert_eq!(ModulePath::kind("@scope/pkg"), ImportKind::Bare);
+    assert_eq!(ModulePath::kind("tsrun:host"), ImportKind::Bare);
+}
+
+#[test]
+fn test_module_path_entry() {
+    assert_eq!(ModulePath::entry("main.ts").as_str(), "/main.ts");
+    assert_eq!(ModulePath::entry("./src/main.ts").as_str(), "/src/main.ts");
+    assert_eq!(ModulePath::entry("src/../main.ts").as_str(), "/main.ts");
+    assert_eq!(ModulePath::entry("/app/main.ts").as_str(), "/app/main.ts");
+    assert_eq!(
+        ModulePath::entry("C:/app/main.ts").as_str(),
+        "C:/app/main.ts"
+    );
+}
+
+#[test]
+fn test_module_path_resolution_by_root_shape() {
+    // (base, specifier, expected resolved path)
+    let cases: &[(&str, &str, &str)] = &[
+        // POSIX
+        ("/app/src/main.ts", "./util.ts", "/app/src/util.ts"),
+        ("/app/src/main.ts", "../lib/util.ts", "/app/lib/util.ts"),
+        ("/app/src/main.ts", "../../../../util.ts", "/util.ts"),
+        ("/app/src/main.ts", "/lib/util.ts", "/lib/util.ts"),
+        ("/app/src/main.ts", "C:\\lib\\util.ts", "C:/lib/util.ts"),
+        (
+            "/app/src/main.ts",
+            "https://cdn.example.com/a/../util.ts",
+            "https://cdn.example.com/util.ts",
+        ),
+        // Windows drive
+        ("C:\\app\\src\\main.ts", "./util.ts", "C:/app/src/util.ts"),
+        ("C:\\app\\src\\main.ts", ".\\util.ts", "C:/app/src/util.ts"),
+        (
+            "C:\\app\\src\\main.ts",
+            "..\\lib\\util.ts",
+            "C:/app/lib/util.ts",
+        ),
+        ("C:\\app\\src\\main.ts", "../../../../util.ts", "C:/util.ts"),
+        ("C:\\app\\src\\main.ts", "/lib/util.ts", "C:/lib/util.ts"),
+        (
+            "C:\\app\\src\\main.ts",
+            "D:\\lib\\..\\util.ts",
+            "D:/util.ts",
+        ),
+        (
+            "C:\\app\\src\\main.ts",
+            "https://cdn.example.com/util.ts",
+            "https://cdn.example.com/util.ts",
+        ),
+        // Windows share
+        (
+            "\\\\server\\share\\app\\main.ts",
+            "..\\lib\\util.ts",
+            "//server/share/lib/util.ts",
+        ),
+        (
+            "\\\\server\\share\\app\\main.ts",
+            "../../../util.ts",
+            "//server/share/util.ts",
+        ),
+        (
+            "\\\\server\\share\\app\\main.ts",
+            "/lib/util.ts",
+            "//server/share/lib/util.ts",
+        ),
+        // URL
+        (
+            "https://cdn.example.com/pkg/src/mod.ts",
+            "./util.ts",
+            "https://cdn.example.com/pkg/src/util.ts",
+        ),
+        (
+            "https://cdn.example.com/pkg/src/mod.ts",
+            "../lib/util.ts",
+            "https://cdn.example.com/pkg/lib/util.ts",
+        ),
+        (
+            "https://cdn.example.com/pkg/src/mod.ts",
+            "../../../../util.ts",
+            "https://cdn.example.com/util.ts",
+        ),
+        (
+            "https://cdn.example.com/pkg/src/mod.ts",
+            "/lib/util.ts",
+            "https://cdn.example.com/lib/util.ts",
+        ),
+        (
+            "https://cdn.example.com/pkg/src/mod.ts",
+            "//other.example.com/util.ts",
+            "https://other.example.com/util.ts",
+        ),
+        (
+            "https://cdn.example.com/pkg/src/mod.ts",
+            "./util.ts?v=2#top",
+            "https://cdn.example.com/pkg/src/util.ts?v=2",
+        ),
+        (
+            "https://cdn.example.com/pkg/mod.ts?from=/a/b",
+            "./util.ts",
+            "https://cdn.example.com/pkg/util.ts",
+        ),
+        (
+            "https://cdn.example.com/pkg/src/mod.ts",
+            "HTTPS://CDN.Example.com/Lib/Util.ts",
+            "https://cdn.example.com/Lib/Util.ts",
+        ),
+        (
+            "https://cdn.example.com",
+            "./util.ts",
+            "https://cdn.example.com/util.ts",
+        ),
+    ];
+
+    for (base, specifier, expected) in cases {
+        let base = ModulePath::entry(*base);
+        assert_eq!(
+            ModulePath::resolve(specifier, Some(&base)).as_str(),
+            *expected,
+            "{:?} from {}",
+            specifier,
+            base
+        );
+        // Bare specifiers pass through whatever the base
+        assert_eq!(
+            ModulePath::resolve("lodash", Some(&base)).as_str(),
+            "lodash"
+        );
+    }
+}
+
+#[test]
+fn test_module_path_kind_of_windows_and_url_specifiers() {
+    assert_eq!(ModulePath::kind("C:\\lib\\util.ts"), ImportKind::Absolute);
+    assert_eq!(ModulePath::kind("c:/lib/util.ts"), ImportKind::Absolute);
+    assert_eq!(
+        ModulePath::kind("\\\\server\\share\\a.ts"),
+        ImportKind::Absolute
+    );
+    assert_eq!(
+        ModulePath::kind("https://cdn.example.com/a.ts"),
+        ImportKind::Absolute
+    );
+    assert_eq!(ModulePath::kind(".\\util.ts"), ImportKind::Relative);
+    assert_eq!(ModulePath::kind("tsrun:host"), ImportKind::Bare);
+    assert_eq!(ModulePath::kind("node:fs"), ImportKind::Bare);
+    assert_eq!(ModulePath::kind("C:"), ImportKind::Bare);
+    assert!(!ModulePath::is_bare("https://cdn.example.com/a.ts"));
+    assert!(!ModulePath::is_bare("C:\\a.ts"));
+}
+
+#[test]
+fn test_module_path_drive_letter_compares_case_insensitively() {
+    use std::collections::HashSet;
+
+    let upper = ModulePath::entry("C:\\app\\main.ts");
+    let lower = ModulePath::entry("c:/app/main.ts");
+    assert_eq!(upper, lower);
+    assert_eq!(HashSet::from([upper, lower]).len(), 1);
+    // The rest of the path keeps its case
+    assert_ne!(
+        ModulePath::entry("C:/App/main.ts"),
+        ModulePath::entry("C:/app/main.ts")
+    );
+}
+
+#[test]
+fn test_module_path_url_query_policy() {
+    let base = ModulePath::entry("https://cdn.example.com/pkg/mod.ts");
+    assert_eq!(
+        ModulePath::resolve_with("./util.ts?v=1#x", Some(&base), UrlQuery::Strip).as_str(),
+        "https://cdn.example.com/pkg/util.ts"
+    );
+
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        url_query: UrlQuery::Strip,
+        ..Default::default()
+    });
+    let source = r#"import { x } from "./util.ts?v=1";"#;
+    let result = run(
+        &mut interp,
+        source,
+        Some("https://cdn.example.com/pkg/main.ts"),
+    );
+    let Ok(StepResult::NeedImports(imports)) = result else {
+        panic!("Expected NeedImports");
+    };
+    assert_eq!(
+        imports
+            .iter()
+            .map(|i| i.resolved_path.as_str())
+            .collect::<Vec<_>>(),
+        ["https://cdn.example.com/pkg/util.ts"]
+    );
+}
+
+/// The first NeedImports request of a main module importing `specifier`
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn main_import_request(main_path: Option<&str>, specifier: &str) -> tsrun::ImportRequest {
+    let mut interp = Interpreter::new();
+    let source = format!("import {{ x }} from \"{}\";", specifier);
+    let result = run(&mut interp, &source, main_path).unwrap();
+    let StepResult::NeedImports(mut imports) = result else {
+        panic!(
+            "Expected NeedImports for {:?} from {:?}",
+            specifier, main_path
+        );
+    };
+    assert_eq!(imports.len(), 1);
+    imports.remove(0)
+}
+
+#[test]
+fn test_import_resolution_by_main_path_form() {
+    // (main path, specifier, expected resolved path, expected kind)
+    let cases: &[(Option<&str>, &str, &str, ImportKind)] = &[
+        // No main path: relative imports stay rootless
+        (None, "./lib/util", "lib/util", ImportKind::Relative),
+        (None, "../lib/util", "lib/util", ImportKind::Relative),
+        (None, "/lib/util", "/lib/util", ImportKind::Absolute),
+        (None, "lodash", "lodash", ImportKind::Bare),
+        // Bare main name: rooted at an implicit /
+        (
+            Some("main.ts"),
+            "./lib/util",
+            "/lib/util",
+            ImportKind::Relative,
+        ),
+        (
+            Some("main.ts"),
+            "../lib/util",
+            "/lib/util",
+            ImportKind::Relative,
+        ),
+        (
+            Some("main.ts"),
+            "/lib/util",
+            "/lib/util",
+            ImportKind::Absolute,
+        ),
+        (Some("main.ts"), "lodash", "lodash", ImportKind::Bare),
+        // Relative-looking main name
+        (
+            Some("./src/main.ts"),
+            "./lib/util",
+            "/src/lib/util",
+            ImportKind::Relative,
+        ),
+        (
+            Some("./src/main.ts"),
+            "../lib/util",
+            "/lib/util",
+            ImportKind::Relative,
+        ),
+        (
+            Some("./src/main.ts"),
+            "/lib/util",
+            "/lib/util",
+            ImportKind::Absolute,
+        ),
+        (Some("./src/main.ts"), "lodash", "lodash", ImportKind::Bare),
+        // Absolute main path
+        (
+            Some("/app/src/main.ts"),
+            "./lib/util",
+            "/app/src/lib/util",
+            ImportKind::Relative,
+        ),
+        (
+            Some("/app/src/main.ts"),
+            "../lib/util",
+            "/app/lib/util",
+            ImportKind::Relative,
+        ),
+        (
+            Some("/app/src/main.ts"),
+            "/lib/util",
+            "/lib/util",
+            ImportKind::Absolute,
+        ),
+        (
+            Some("/app/src/main.ts"),
+            "lodash",
+            "lodash",
+            ImportKind::Bare,
+        ),
+    ];
+    for (main_path, specifier, resolved, kind) in cases {
+        let req = main_import_request(*main_path, specifier);
+        assert_eq!(
+            req.resolved_path.as_str(),
+            *resolved,
+            "{} from {:?}",
+            specifier,
+            main_path
+        );
+        assert_eq!(req.kind, *kind, "{} from {:?}", specifier, main_path);
+        assert_eq!(req.specifier, *specifier);
+        assert_eq!(req.importer, None);
+    }
+}
+
+#[test]
+fn test_bare_main_name_relative_import_runs() {
+    let mut interp = Interpreter::new();
+    let result = run(
+        &mut interp,
+        r#"
+        import { util } from "./util";
+        import { other } from "util";
+        util + other
+    "#,
+        Some("main.ts"),
+    )
+    .unwrap();
+    let StepResult::NeedImports(imports) = result else {
+        panic!("Expected NeedImports");
+    };
+    // The relative and the bare import stay distinct
+    let paths: Vec<&str> = imports.iter().map(|r| r.resolved_path.as_str()).collect();
+    assert_eq!(paths, vec!["/util", "util"]);
+
+    interp
+        .provide_module(ModulePath::new("/util"), "export const util = 1;")
+        .unwrap();
+    interp
+        .provide_module(ModulePath::new("util"), "export const other = 2;")
+        .unwrap();
+    let result = run_to_completion(&mut interp).unwrap();
+    assert!(matches!(result, StepResult::Complete(ref v) if *v == JsValue::Number(3.0)));
+}
+
+#[test]
+fn test_rootless_relative_import_colliding_with_bare_is_error() {
+    let mut interp = Interpreter::new();
+    let err = run(
+        &mut interp,
+        r#"
+        import { a } from "./util";
+        import { b } from "util";
+    "#,
+        None,
+    )
+    .unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("Ambiguous import"), "{message}");
+    assert!(message.contains("'./util' resolves to 'util'"), "{message}");
+}
+
 #[test]
 fn test_eval_with_path_resolves_imports() {
     let mut interp = Interpreter::new();
@@ -1940,3 +3105,2342 @@ fn test_live_binding_imported_value_is_readonly() {
         _ => panic!("Expected NeedImports"),
     }
 }
+
+#[test]
+fn test_object_assign_materializes_module_namespace() {
+    let mut interp = Interpreter::new();
+
+    let result = run(
+        &mut interp,
+        r#"
+        import * as config from "./config";
+        const plain: any = Object.assign({}, config);
+        const desc = Object.getOwnPropertyDescriptor(plain, "name");
+        typeof desc.get + ":" + plain.name + ":" + plain.replicas + ":" + Object.keys(plain).length;
+    "#,
+        None,
+    )
+    .unwrap();
+
+    match result {
+        StepResult::NeedImports(imports) => {
+            interp
+                .provide_module(
+                    imports[0].resolved_path.clone(),
+                    r#"
+                export const name = "web";
+                export const replicas = 3;
+            "#,
+                )
+                .unwrap();
+
+            match run_to_completion(&mut interp).unwrap() {
+                StepResult::Complete(value) => {
+                    assert_eq!(value, JsValue::String("undefined:web:3:2".into()));
+                }
+                _ => panic!("Expected Complete"),
+            }
+        }
+        _ => panic!("Expected NeedImports"),
+    }
+}
+
+#[test]
+fn test_module_top_level_this_is_undefined() {
+    let mut interp = Interpreter::new();
+
+    let result = run(
+        &mut interp,
+        r#"
+        import { tag } from "./tag";
+        function who() { return this === undefined ? "undefined" : "bound"; }
+        [typeof this, (() => typeof this)(), who(), tag()].join(",");
+    "#,
+        None,
+    )
+    .unwrap();
+
+    match result {
+        StepResult::NeedImports(imports) => {
+            interp
+                .provide_module(
+                    imports[0].resolved_path.clone(),
+                    r#"
+                const self = this;
+                export function tag() { return typeof self; }
+            "#,
+                )
+                .unwrap();
+
+            match run_to_completion(&mut interp).unwrap() {
+                StepResult::Complete(value) => {
+                    assert_eq!(
+                        value,
+                        JsValue::String("undefined,undefined,undefined,undefined".into())
+                    );
+                }
+                _ => panic!("Expected Complete"),
+            }
+        }
+        _ => panic!("Expected NeedImports"),
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Post-mortem Query Tests (eval_in_context)
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn retaining_interp() -> Interpreter {
+    Interpreter::with_config(InterpreterConfig {
+        retain_main_scope: true,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_eval_in_context_reads_top_level_bindings() {
+    let mut interp = retaining_interp();
+    let result = run(
+        &mut interp,
+        r#"
+        const config = { name: "api", replicas: 3 };
+        let status = "ok";
+        function validate(c: { replicas: number }): boolean { return c.replicas > 0; }
+        "done";
+    "#,
+        Some("/app/main.ts"),
+    )
+    .unwrap();
+    assert!(matches!(result, StepResult::Complete(_)));
+
+    let StepResult::Complete(value) = interp.eval_in_context("config.replicas").unwrap() else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(*value, JsValue::Number(3.0));
+
+    let StepResult::Complete(value) = interp
+        .eval_in_context("validate(config) && status === 'ok'")
+        .unwrap()
+    else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(*value, JsValue::Boolean(true));
+
+    // Declarations made by a query stay local to it
+    interp.eval_in_context("const extra = 1; extra").unwrap();
+    let StepResult::Complete(value) = interp.eval_in_context("typeof extra").unwrap() else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(*value, JsValue::from("undefined"));
+}
+
+#[test]
+fn test_eval_in_context_sees_imported_bindings() {
+    let mut interp = retaining_interp();
+    let result = run(
+        &mut interp,
+        r#"
+        import { double } from "./math";
+        const base = 21;
+    "#,
+        Some("/app/main.ts"),
+    )
+    .unwrap();
+    let StepResult::NeedImports(imports) = result else {
+        panic!("Expected NeedImports");
+    };
+    interp
+        .provide_module(
+            imports[0].resolved_path.clone(),
+            "export function double(x: number): number { return x * 2; }",
+        )
+        .unwrap();
+    assert!(matches!(
+        run_to_completion(&mut interp).unwrap(),
+        StepResult::Complete(_)
+    ));
+
+    let StepResult::Complete(value) = interp.eval_in_context("double(base)").unwrap() else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(*value, JsValue::Number(42.0));
+}
+
+#[test]
+fn test_eval_in_context_requires_retained_scope() {
+    let mut interp = Interpreter::new();
+    run(&mut interp, "const x = 1;", None).unwrap();
+
+    let err = interp.eval_in_context("x").unwrap_err();
+    assert!(err.to_string().contains("retain_main_scope"), "{}", err);
+}
+
+#[test]
+fn test_eval_in_context_awaits_orders() {
+    use tsrun::{OrderResponse, create_eval_internal_module};
+
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        internal_modules: vec![create_eval_internal_module()],
+        retain_main_scope: true,
+        ..Default::default()
+    });
+    let result = run(
+        &mut interp,
+        r#"
+        import { order } from "tsrun:host";
+        async function lookup(key: string): Promise<number> {
+            return (await order({ key })) * 2;
+        }
+    "#,
+        Some("/app/main.ts"),
+    )
+    .unwrap();
+    assert!(matches!(result, StepResult::Complete(_)));
+
+    let StepResult::Suspended { pending, .. } =
+        interp.eval_in_context("await lookup('replicas')").unwrap()
+    else {
+        panic!("Expected Suspended");
+    };
+    assert_eq!(pending.len(), 1);
+
+    interp.fulfill_orders(vec![OrderResponse {
+        id: pending[0].id,
+        result: Ok(RuntimeValue::unguarded(JsValue::Number(21.0))),
+    }]);
+    let StepResult::Complete(value) = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(*value, JsValue::Number(42.0));
+
+    // The scope is restored so further queries work
+    let StepResult::Complete(value) = interp.eval_in_context("typeof lookup").unwrap() else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(*value, JsValue::from("function"));
+}
+
+/// Run a module exporting `source` to completion and return the export `name`
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn exported(interp: &mut Interpreter, source: &str, name: &str) -> RuntimeValue {
+    let result = run(interp, source, Some("/app/plugin.ts")).unwrap();
+    assert!(matches!(result, StepResult::Complete(_)));
+    RuntimeValue::unguarded(interp.get_export(name).unwrap())
+}
+
+#[allow(clippy::panic)]
+fn completed(result: Result<StepResult, JsError>) -> RuntimeValue {
+    match result {
+        Ok(StepResult::Complete(value)) => value,
+        Ok(other) => panic!("Expected Complete, got {:?}", other),
+        Err(err) => panic!("Expected Complete, got error {}", err),
+    }
+}
+
+#[test]
+fn test_construct_exported_class_and_call_method() {
+    let mut interp = Interpreter::new();
+    let class = exported(
+        &mut interp,
+        r#"
+        export class Deployment {
+            kind = "Deployment";
+            constructor(public name: string, public replicas: number) {}
+            render(suffix: string) {
+                return `${this.kind}/${this.name} x${this.replicas}${suffix}`;
+            }
+        }
+    "#,
+        "Deployment",
+    );
+
+    let instance = completed(interp.construct(
+        &class,
+        &[
+            RuntimeValue::unguarded(JsValue::from("web")),
+            RuntimeValue::unguarded(JsValue::Number(3.0)),
+        ],
+    ));
+    let rendered = completed(interp.call_method(
+        &instance,
+        "render",
+        &[RuntimeValue::unguarded(JsValue::from("!"))],
+    ));
+    assert_eq!(*rendered, JsValue::from("Deployment/web x3!"));
+
+    let err = interp.call_method(&instance, "missing", &[]).unwrap_err();
+    assert!(
+        err.to_string().contains("missing is not a function"),
+        "{}",
+        err
+    );
+    let err = interp
+        .construct(&RuntimeValue::unguarded(JsValue::Number(1.0)), &[])
+        .unwrap_err();
+    assert!(err.to_string().contains("not a constructor"), "{}", err);
+}
+
+#[test]
+fn test_construct_derived_class_runs_base_constructor() {
+    let mut interp = Interpreter::new();
+    let class = exported(
+        &mut interp,
+        r#"
+        const log: string[] = [];
+        class Base {
+            baseField = "base";
+            constructor(public id: number) {
+                log.push("base:" + this.constructor.name);
+            }
+        }
+        export class Service extends Base {
+            port = 8080;
+            constructor(id: number) {
+                super(id * 10);
+                log.push("service");
+            }
+            describe() {
+                return [this.baseField, this.id, this.port, this instanceof Base, log.join(",")].join("|");
+            }
+        }
+    "#,
+        "Service",
+    );
+
+    let instance =
+        completed(interp.construct(&class, &[RuntimeValue::unguarded(JsValue::Number(4.0))]));
+    let described = completed(interp.call_method(&instance, "describe", &[]));
+    assert_eq!(
+        *described,
+        JsValue::from("base|40|8080|true|base:Service,service")
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_construct_and_call_method_suspend_on_orders() {
+    use tsrun::{OrderResponse, create_eval_internal_module};
+
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        internal_modules: vec![create_eval_internal_module()],
+        ..Default::default()
+    });
+    let class = exported(
+        &mut interp,
+        r#"
+        import { order } from "tsrun:host";
+        export class Client {
+            base: number;
+            constructor() {
+                this.base = order({ op: "base" });
+            }
+            async fetch(n: number) {
+                return this.base + (await order({ op: "fetch", n }));
+            }
+        }
+    "#,
+        "Client",
+    );
+
+    let StepResult::Suspended { pending, .. } = interp.construct(&class, &[]).unwrap() else {
+        panic!("Expected constructor to suspend");
+    };
+    interp.fulfill_orders(vec![OrderResponse {
+        id: pending[0].id,
+        result: Ok(RuntimeValue::unguarded(JsValue::Number(100.0))),
+    }]);
+    let instance = completed(run_to_completion(&mut interp));
+
+    let StepResult::Suspended { pending, .. } = interp
+        .call_method(
+            &instance,
+            "fetch",
+            &[RuntimeValue::unguarded(JsValue::Number(2.0))],
+        )
+        .unwrap()
+    else {
+        panic!("Expected method to suspend");
+    };
+    assert_eq!(pending.len(), 1);
+    interp.fulfill_orders(vec![OrderResponse {
+        id: pending[0].id,
+        result: Ok(RuntimeValue::unguarded(JsValue::Number(5.0))),
+    }]);
+    let value = completed(run_to_completion(&mut interp));
+    assert_eq!(*value, JsValue::Number(105.0));
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Host-Driven Generator Tests
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// The value of a `Yielded` step
+#[allow(clippy::panic)]
+fn yielded(step: Result<GeneratorStep, JsError>) -> JsValue {
+    match step {
+        Ok(GeneratorStep::Yielded(value)) => value.value().clone(),
+        Ok(other) => panic!("Expected Yielded, got {:?}", other),
+        Err(err) => panic!("Expected Yielded, got error {}", err),
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_generator_next_passes_values_into_sync_generator() {
+    let mut interp = Interpreter::new();
+    let generator = exported(
+        &mut interp,
+        r#"
+        function* totals(): Generator<number, string, number> {
+            let total = 0;
+            while (total < 10) {
+                total += yield total;
+            }
+            return `total ${total}`;
+        }
+        export const running = totals();
+    "#,
+        "running",
+    );
+
+    assert_eq!(
+        yielded(interp.generator_next(&generator, None)),
+        JsValue::Number(0.0)
+    );
+    let four = Some(RuntimeValue::unguarded(JsValue::Number(4.0)));
+    assert_eq!(
+        yielded(interp.generator_next(&generator, four)),
+        JsValue::Number(4.0)
+    );
+    let seven = Some(RuntimeValue::unguarded(JsValue::Number(7.0)));
+    let Ok(GeneratorStep::Done(value)) = interp.generator_next(&generator, seven) else {
+        panic!("Expected the generator to finish");
+    };
+    assert_eq!(*value, JsValue::from("total 11"));
+    let Ok(GeneratorStep::Done(value)) = interp.generator_next(&generator, None) else {
+        panic!("Expected the generator to stay finished");
+    };
+    assert_eq!(*value, JsValue::Undefined);
+
+    let err = interp
+        .generator_next(&RuntimeValue::unguarded(JsValue::Number(1.0)), None)
+        .unwrap_err();
+    assert!(err.to_string().contains("not a generator"), "{}", err);
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_generator_next_suspends_async_generator_on_orders() {
+    use tsrun::{OrderResponse, create_eval_internal_module};
+
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        internal_modules: vec![create_eval_internal_module()],
+        ..Default::default()
+    });
+    let generator = exported(
+        &mut interp,
+        r#"
+        import { order } from "tsrun:host";
+        async function* pages() {
+            let cursor = 0;
+            while (cursor < 2) {
+                const page: number = await order({ op: "page", cursor });
+                cursor += 1;
+                yield page;
+            }
+        }
+        export const stream = pages();
+    "#,
+        "stream",
+    );
+
+    for page in [10.0, 20.0] {
+        let Ok(GeneratorStep::Suspended { pending, .. }) = interp.generator_next(&generator, None)
+        else {
+            panic!("Expected the generator to wait on its order");
+        };
+        assert_eq!(pending.len(), 1);
+        interp.fulfill_orders(vec![OrderResponse {
+            id: pending[0].id,
+            result: Ok(RuntimeValue::unguarded(JsValue::Number(page))),
+        }]);
+        // The fulfilled order lets the generator continue without an argument
+        assert_eq!(
+            yielded(interp.generator_next(&generator, None)),
+            JsValue::Number(page)
+        );
+    }
+    let Ok(GeneratorStep::Done(_)) = interp.generator_next(&generator, None) else {
+        panic!("Expected the generator to finish");
+    };
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_generator_return_and_throw_run_finally() {
+    let mut interp = Interpreter::new();
+    run(
+        &mut interp,
+        r#"
+            export const log: string[] = [];
+            function* steps() {
+                try {
+                    yield "a";
+                    yield "b";
+                } catch (e) {
+                    log.push("caught " + e);
+                    yield "recovered";
+                } finally {
+                    log.push("cleanup");
+                }
+            }
+            export const first = steps();
+            export const second = steps();
+        "#,
+        Some("/app/steps.ts"),
+    )
+    .unwrap();
+    let first = RuntimeValue::unguarded(interp.get_export("first").unwrap());
+    let second = RuntimeValue::unguarded(interp.get_export("second").unwrap());
+
+    assert_eq!(
+        yielded(interp.generator_next(&first, None)),
+        JsValue::from("a")
+    );
+    let Ok(GeneratorStep::Done(value)) =
+        interp.generator_return(&first, RuntimeValue::unguarded(JsValue::from("stop")))
+    else {
+        panic!("Expected return to finish the generator");
+    };
+    assert_eq!(*value, JsValue::from("stop"));
+
+    assert_eq!(
+        yielded(interp.generator_next(&second, None)),
+        JsValue::from("a")
+    );
+    let thrown = interp.generator_throw(&second, RuntimeValue::unguarded(JsValue::from("boom")));
+    assert_eq!(yielded(thrown), JsValue::from("recovered"));
+    let err = interp
+        .generator_throw(&second, RuntimeValue::unguarded(JsValue::from("again")))
+        .unwrap_err();
+    assert!(err.to_string().contains("again"), "{}", err);
+
+    let log = interp.get_export("log").unwrap();
+    let log = completed(interp.call_method(&RuntimeValue::unguarded(log), "join", &[]));
+    assert_eq!(*log, JsValue::from("cleanup,caught boom,cleanup"));
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Shared Module Tests
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn shared_interp(shared: &std::sync::Arc<SharedModuleSet>) -> Interpreter {
+    Interpreter::with_config(InterpreterConfig {
+        shared_modules: Some(shared.clone()),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_shared_modules_imported_by_two_interpreters() {
+    let shared = std::sync::Arc::new(
+        SharedModuleSet::build([
+            (
+                "app:limits",
+                r#"
+                const tiers = ["free", "pro"];
+                export const limits = { rps: 100, burst: 20 * 5, tiers };
+                export const byTier = { free: tiers, pro: tiers };
+                export const name = "limits";
+            "#,
+            ),
+            (
+                "app:routes",
+                r#"
+                import { limits } from "app:limits";
+                export const routes = [{ path: "/api", rps: limits.rps, tiers: limits.tiers }];
+            "#,
+            ),
+        ])
+        .unwrap(),
+    );
+    assert!(shared.contains("app:routes"));
+    assert_eq!(
+        shared.specifiers().collect::<Vec<_>>(),
+        vec!["app:limits", "app:routes"]
+    );
+
+    let source = r#"
+        import { limits, byTier, name } from "app:limits";
+        import { routes } from "app:routes";
+        const same = byTier.free === byTier.pro && byTier.free === limits.tiers
+            && routes[0].tiers === limits.tiers;
+        `${name}:${limits.rps}:${limits.burst}:${routes[0].path}:${limits.tiers.join(",")}:${same}`
+    "#;
+    for _ in 0..2 {
+        let mut interp = shared_interp(&shared);
+        let value = completed(run(&mut interp, source, Some("/app/main.ts")));
+        assert_eq!(*value, JsValue::from("limits:100:100:/api:free,pro:true"));
+        // Only the main program is parsed; the shared modules are copied in
+        assert_eq!(interp.parsed_programs(), 1);
+    }
+}
+
+#[test]
+fn test_shared_module_exports_are_frozen() {
+    let shared = std::sync::Arc::new(
+        SharedModuleSet::build([(
+            "app:config",
+            "export const config = { retries: 3, hosts: ['a', 'b'] };",
+        )])
+        .unwrap(),
+    );
+
+    let mut first = shared_interp(&shared);
+    let value = completed(run(
+        &mut first,
+        r#"
+        import { config } from "app:config";
+        let errors = 0;
+        try { (config as any).retries = 9; } catch (e) { errors++; }
+        try { (config.hosts as any)[0] = "z"; } catch (e) {}
+        try { (config as any).extra = 1; } catch (e) { errors++; }
+        `${errors}:${Object.isFrozen(config)}:${Object.isFrozen(config.hosts)}:${config.retries}:${config.hosts[0]}`
+    "#,
+        Some("/app/main.ts"),
+    ));
+    assert_eq!(*value, JsValue::from("2:true:true:3:a"));
+
+    let mut second = shared_interp(&shared);
+    let value = completed(run(
+        &mut second,
+        r#"import { config } from "app:config"; `${config.retries}:${config.hosts.length}`"#,
+        Some("/app/main.ts"),
+    ));
+    assert_eq!(*value, JsValue::from("3:2"));
+}
+
+#[test]
+fn test_shared_module_set_rejects_unshareable_exports() {
+    let err = SharedModuleSet::build([(
+        "app:handlers",
+        "export const handlers = { ok: 1, run() { return 1; } };",
+    )])
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("'app:handlers:handlers.run' is a function"),
+        "{}",
+        err
+    );
+
+    let err = SharedModuleSet::build([("app:map", "export const m = new Map();")]).unwrap_err();
+    assert!(err.to_string().contains("'app:map:m'"), "{}", err);
+
+    let err = SharedModuleSet::build([("app:broken", "throw new Error('boom');")]).unwrap_err();
+    assert!(err.to_string().contains("boom"), "{}", err);
+}
+
+#[test]
+fn test_shared_module_deeply_nested_export() {
+    // Deeper than the Rust stack would allow if capture and attach recursed
+    let shared = std::sync::Arc::new(
+        SharedModuleSet::build([(
+            "app:deep",
+            r#"
+            let node: any = { depth: 0 };
+            for (let i = 1; i <= 10000; i++) node = { depth: i, next: node, list: [node] };
+            export const deep = node;
+        "#,
+        )])
+        .unwrap(),
+    );
+
+    let mut interp = shared_interp(&shared);
+    let value = completed(run(
+        &mut interp,
+        r#"
+        import { deep } from "app:deep";
+        let node: any = deep;
+        let steps = 0;
+        while (node.next) { if (node.list[0] !== node.next) break; node = node.next; steps++; }
+        `${deep.depth}:${steps}:${node.depth}:${Object.isFrozen(node)}`
+    "#,
+        Some("/app/main.ts"),
+    ));
+    assert_eq!(*value, JsValue::from("10000:10000:0:true"));
+}
+
+#[test]
+fn test_shared_module_data_used_by_ordinary_module_functions() {
+    // Functions live in an ordinary module and import their data from the set
+    let shared = std::sync::Arc::new(
+        SharedModuleSet::build([("app:rates", "export const rates = { eur: 0.9, gbp: 0.8 };")])
+            .unwrap(),
+    );
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        shared_modules: Some(shared.clone()),
+        internal_modules: vec![InternalModule::source(
+            "app:convert",
+            r#"
+            import { rates } from "app:rates";
+            export function convert(amount: number, currency: "eur" | "gbp") {
+                return amount * rates[currency];
+            }
+        "#,
+        )],
+        ..Default::default()
+    });
+    let value = completed(run(
+        &mut interp,
+        r#"import { convert } from "app:convert"; convert(10, "gbp")"#,
+        Some("/app/main.ts"),
+    ));
+    assert_eq!(*value, JsValue::Number(8.0));
+}
+
+#[test]
+fn test_shared_module_set_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<SharedModuleSet>();
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Module Hook Tests
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Time provider where every measured interval takes 7ms
+struct FixedElapsed;
+
+impl TimeProvider for FixedElapsed {
+    fn now_millis(&self) -> i64 {
+        0
+    }
+
+    fn elapsed_millis(&self, _start: u64) -> u64 {
+        7
+    }
+
+    fn start_timer(&self) -> u64 {
+        0
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_module_hooks_report_graph_progress_in_dependency_order() {
+    let events: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+    let log = |events: &Rc<RefCell<Vec<String>>>| {
+        let events = events.clone();
+        move |event: String| events.borrow_mut().push(event)
+    };
+
+    let (start, loaded, executed, requested) =
+        (log(&events), log(&events), log(&events), log(&events));
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        module_hooks: ModuleHooks {
+            on_module_start: Some(Box::new(move |path| start(format!("start {}", path)))),
+            on_module_loaded: Some(Box::new(move |path, ms| {
+                loaded(format!("loaded {} {}ms", path, ms))
+            })),
+            on_module_executed: Some(Box::new(move |path, ms, exports| {
+                executed(format!("executed {} {}ms {} exports", path, ms, exports))
+            })),
+            on_import_requested: Some(Box::new(move |req| {
+                requested(format!("requested {}", req.resolved_path))
+            })),
+        },
+        ..Default::default()
+    });
+    interp.set_time_provider(Box::new(FixedElapsed));
+
+    let sources = [
+        (
+            "/project/app",
+            r#"import { double } from "./util";
+            export const answer = double(21);
+            export const label = "app";
+            export function run() { return answer; }"#,
+        ),
+        (
+            "/project/util",
+            r#"import { factor } from "./config";
+            export function double(n: number) { return n * factor; }"#,
+        ),
+        ("/project/config", "export const factor = 2, unused = 0;"),
+    ];
+
+    let mut result = run(
+        &mut interp,
+        r#"import { run } from "./app"; run()"#,
+        Some("/project/main.ts"),
+    )
+    .unwrap();
+    while let StepResult::NeedImports(imports) = result {
+        for req in imports {
+            let (_, source) = sources
+                .iter()
+                .find(|(path, _)| *path == req.resolved_path.as_str())
+                .unwrap();
+            interp.provide_module(req.resolved_path, source).unwrap();
+        }
+        result = run_to_completion(&mut interp).unwrap();
+    }
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete, got {:?}", result);
+    };
+    assert_eq!(*value, JsValue::Number(42.0));
+
+    assert_eq!(
+        *events.borrow(),
+        [
+            "requested /project/app",
+            "start /project/app",
+            "loaded /project/app 7ms",
+            "requested /project/util",
+            "start /project/util",
+            "loaded /project/util 7ms",
+            "requested /project/config",
+            "start /project/config",
+            "loaded /project/config 7ms",
+            "executed /project/config 7ms 2 exports",
+            "executed /project/util 7ms 1 exports",
+            "executed /project/app 7ms 3 exports",
+        ]
+    );
+}
+
+/// Number of instructions in a disassembler listing
+fn listed_instructions(listing: &str) -> usize {
+    listing
+        .lines()
+        .filter(|line| line.trim_start().starts_with(|c: char| c.is_ascii_digit()))
+        .count()
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_module_metrics_report_each_module() {
+    let small = "export const one = 1;";
+    let mut large = String::new();
+    for i in 0..40 {
+        large.push_str(&format!(
+            "export function f{i}(n: number) {{ const s = \"f{i}\"; return n * {i} + s.length; }}\n"
+        ));
+    }
+    let main = r#"import { one } from "./small"; import { f7 } from "./large"; f7(one)"#;
+
+    let mut interp = Interpreter::new();
+    interp.set_time_provider(Box::new(FixedElapsed));
+    let mut result = run(&mut interp, main, Some("/project/main.ts")).unwrap();
+    while let StepResult::NeedImports(imports) = result {
+        for req in imports {
+            let source = if req.specifier == "./small" {
+                small
+            } else {
+                &large
+            };
+            interp.provide_module(req.resolved_path, source).unwrap();
+        }
+        result = run_to_completion(&mut interp).unwrap();
+    }
+    assert!(matches!(result, StepResult::Complete(_)));
+
+    let metrics = interp.module_metrics();
+    let paths: Vec<&str> = metrics.iter().map(|m| m.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        ["/project/main.ts", "/project/small", "/project/large"]
+    );
+    for m in metrics {
+        assert_eq!((m.parse_ms, m.compile_ms), (7, 7), "{}", m.path);
+    }
+
+    let by_path = |path: &str| metrics.iter().find(|m| m.path == path).unwrap();
+    let (small_metrics, large_metrics) = (by_path("/project/small"), by_path("/project/large"));
+    assert_eq!(small_metrics.function_count, 0);
+    assert_eq!(large_metrics.function_count, 40);
+    assert!(large_metrics.instruction_count > 10 * small_metrics.instruction_count);
+    assert!(large_metrics.constant_count > small_metrics.constant_count);
+
+    // Instruction counts agree with the disassembler's listing, which
+    // compiles each source as an entry program: imported modules lack the
+    // instruction clearing the completion value
+    let mut listing = Interpreter::new();
+    for (path, source, entry_only) in [
+        ("/project/main.ts", main, 0),
+        ("/project/small", small, 1),
+        ("/project/large", &large, 1),
+    ] {
+        let text = listing
+            .disassemble(source, Some(ModulePath::new(path)))
+            .unwrap();
+        assert_eq!(
+            by_path(path).instruction_count + entry_only,
+            listed_instructions(&text),
+            "{}",
+            path
+        );
+    }
+
+    // Metrics stay until reset
+    interp.reset_module_metrics();
+    assert!(interp.module_metrics().is_empty());
+}
+
+#[test]
+fn test_module_metrics_summary_sorts_by_total_time() {
+    let metrics = |path: &str, parse_ms, compile_ms| ModuleMetrics {
+        path: path.to_string(),
+        parse_ms,
+        compile_ms,
+        instruction_count: 12,
+        constant_count: 3,
+        function_count: 1,
+    };
+    let summary = ModuleMetrics::summary(&[
+        metrics("/a", 1, 2),
+        metrics("/b", 10, 5),
+        metrics("<main>", 2, 2),
+    ]);
+    assert_eq!(
+        summary,
+        "/b: 15ms (parse 10ms, compile 5ms), 12 instructions, 3 constants, 1 functions\n\
+         <main>: 4ms (parse 2ms, compile 2ms), 12 instructions, 3 constants, 1 functions\n\
+         /a: 3ms (parse 1ms, compile 2ms), 12 instructions, 3 constants, 1 functions\n"
+    );
+}
+
+/// Run `main` against an `eval:counter` module and return the completion
+/// value or the first line of the error
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn run_against_counter(main: &str) -> Result<JsValue, String> {
+    let counter = InternalModule::source(
+        "eval:counter",
+        "export let count = 1; export function bump() { count++; }",
+    );
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        internal_modules: vec![counter],
+        ..Default::default()
+    });
+    match run(&mut interp, main, None) {
+        Ok(StepResult::Complete(value)) => Ok(value.value().clone()),
+        Ok(other) => panic!("Expected Complete, got {:?}", other),
+        Err(err) => Err(err
+            .to_string()
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string()),
+    }
+}
+
+#[test]
+fn test_assign_to_import_is_syntax_error() {
+    for (main, name) in [
+        (
+            r#"import { count } from "eval:counter"; count = 5;"#,
+            "count",
+        ),
+        (r#"import { count as n } from "eval:counter"; n++;"#, "n"),
+        (r#"import * as all from "eval:counter"; all = {};"#, "all"),
+        (
+            r#"import { count } from "eval:counter"; function f() { count += 1; }"#,
+            "count",
+        ),
+        (
+            r#"import { count } from "eval:counter"; [count] = [2];"#,
+            "count",
+        ),
+        (
+            r#"import { count } from "eval:counter"; for (count of [1]) {}"#,
+            "count",
+        ),
+    ] {
+        let message = run_against_counter(main).unwrap_err();
+        assert!(
+            message.starts_with(&format!(
+                "SyntaxError: Cannot assign to imported binding '{}'",
+                name
+            )),
+            "{}: {}",
+            main,
+            message
+        );
+    }
+}
+
+#[test]
+fn test_shadowed_import_name_is_assignable() {
+    assert_eq!(
+        run_against_counter(
+            r#"
+            import { count } from "eval:counter";
+            function f(count: number) { count = count * 10; return count; }
+            f(2);
+            "#
+        ),
+        Ok(JsValue::Number(20.0))
+    );
+}
+
+#[test]
+fn test_namespace_import_is_sealed() {
+    assert_eq!(
+        run_against_counter(
+            r#"
+            import * as ns from "eval:counter";
+            const desc: any = Object.getOwnPropertyDescriptor(ns, "count");
+            [Object.isExtensible(ns), Object.isSealed(ns), desc.configurable, desc.writable].join()
+            "#
+        ),
+        Ok(JsValue::from("false,true,false,true"))
+    );
+}
+
+#[test]
+fn test_namespace_import_rejects_writes() {
+    for (main, expected) in [
+        (
+            r#"import * as ns from "eval:counter"; ns.count = 5;"#,
+            "TypeError: Cannot set property 'count' which has only a getter",
+        ),
+        (
+            r#"import * as ns from "eval:counter"; ns["count"] = 5;"#,
+            "TypeError: Cannot set property 'count' which has only a getter",
+        ),
+        (
+            r#"import * as ns from "eval:counter"; ns.extra = 1;"#,
+            "TypeError: Cannot add property 'extra' to non-extensible object",
+        ),
+        (
+            r#"import * as ns from "eval:counter"; Object.assign(ns, { count: 3 });"#,
+            "TypeError: Cannot assign to read only property 'count'",
+        ),
+        (
+            r#"import * as ns from "eval:counter"; delete ns.count;"#,
+            "TypeError: Cannot delete property 'count' of object",
+        ),
+    ] {
+        assert_eq!(run_against_counter(main).unwrap_err(), expected, "{}", main);
+    }
+}
+
+#[test]
+fn test_namespace_live_binding_survives_rejected_write() {
+    assert_eq!(
+        run_against_counter(
+            r#"
+            import * as ns from "eval:counter";
+            try { ns.count = 100; } catch {}
+            ns.bump();
+            ns.count
+            "#
+        ),
+        Ok(JsValue::Number(2.0))
+    );
+}
+
+/// Run `main` against an `eval:unsorted` module whose exports are declared
+/// out of order
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn run_against_unsorted(main: &str) -> JsValue {
+    let unsorted = InternalModule::source(
+        "eval:unsorted",
+        r#"
+        export let zeta = 1;
+        export const alpha = 2;
+        export function bump() { zeta++; }
+        export const Beta = 3;
+        export { alpha as _alias };
+        "#,
+    );
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        internal_modules: vec![unsorted],
+        ..Default::default()
+    });
+    match run(&mut interp, main, None) {
+        Ok(StepResult::Complete(value)) => value.value().clone(),
+        other => panic!("Expected Complete, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_namespace_to_string_tag() {
+    assert_eq!(
+        run_against_unsorted(
+            r#"
+            import * as ns from "eval:unsorted";
+            const desc: any = Object.getOwnPropertyDescriptor(ns, Symbol.toStringTag);
+            [Object.prototype.toString.call(ns), desc.value, desc.writable, desc.enumerable,
+                desc.configurable].join()
+            "#
+        ),
+        JsValue::from("[object Module],Module,false,false,false")
+    );
+}
+
+#[test]
+fn test_namespace_keys_sorted_by_code_unit() {
+    assert_eq!(
+        run_against_unsorted(
+            r#"
+            import * as ns from "eval:unsorted";
+            const names: string[] = [];
+            for (const name in ns) names.push(name);
+            [Object.keys(ns).join(), names.join(), Reflect.ownKeys(ns).length].join(" | ")
+            "#
+        ),
+        JsValue::from("Beta,_alias,alpha,bump,zeta | Beta,_alias,alpha,bump,zeta | 6")
+    );
+}
+
+#[test]
+fn test_namespace_null_prototype_and_missing_exports() {
+    assert_eq!(
+        run_against_unsorted(
+            r#"
+            import * as ns from "eval:unsorted";
+            const any: any = ns;
+            [Object.getPrototypeOf(ns) === null, Object.isExtensible(ns), any.missing,
+                "missing" in ns, "zeta" in ns]
+                .map(String).join()
+            "#
+        ),
+        JsValue::from("true,false,undefined,false,true")
+    );
+}
+
+#[test]
+fn test_namespace_descriptor_follows_live_binding() {
+    assert_eq!(
+        run_against_unsorted(
+            r#"
+            import * as ns from "eval:unsorted";
+            const before = Object.getOwnPropertyDescriptor(ns, "zeta");
+            ns.bump();
+            const after = Object.getOwnPropertyDescriptor(ns, "zeta");
+            const all: any = Object.getOwnPropertyDescriptors(ns);
+            [JSON.stringify(before), JSON.stringify(after), all.zeta.value,
+                Reflect.getOwnPropertyDescriptor(ns, "zeta")!.value].join(" | ")
+            "#
+        ),
+        JsValue::from(
+            r#"{"value":1,"writable":true,"enumerable":true,"configurable":false} | {"value":2,"writable":true,"enumerable":true,"configurable":false} | 2 | 2"#
+        )
+    );
+}
+
+/// Run a pre-parsed entry program, answering its one import with `module`
+#[allow(clippy::unwrap_used, clippy::panic, clippy::indexing_slicing)]
+fn run_ast(
+    interp: &mut Interpreter,
+    main: &tsrun::ast::Program,
+    module: &tsrun::ast::Program,
+) -> JsValue {
+    let result = match interp.prepare_ast(main.clone(), None).unwrap() {
+        StepResult::Continue => run_to_completion(interp).unwrap(),
+        other => other,
+    };
+    let StepResult::NeedImports(imports) = result else {
+        panic!("Expected NeedImports, got {:?}", result);
+    };
+    interp
+        .provide_module_ast(imports[0].resolved_path.clone(), module.clone())
+        .unwrap();
+    match run_to_completion(interp).unwrap() {
+        StepResult::Complete(value) => value.value().clone(),
+        other => panic!("Expected completion, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parsed_ast_runs_in_several_interpreters() {
+    // Parsed by an interpreter that never runs it, so every name comes from
+    // a string dictionary the others don't share
+    let mut parser = Interpreter::new();
+    let main = parser
+        .parse(
+            r#"
+            import { bump, count as total } from "./counter";
+            import * as counter from "./counter";
+            bump(); bump();
+            `${total}/${counter.count}/${typeof bump}`
+            "#,
+            None,
+        )
+        .unwrap();
+    let module = parser
+        .parse(
+            "export let count = 0; export function bump() { count++; }",
+            Some(ModulePath::new("./counter")),
+        )
+        .unwrap();
+
+    let mut first = Interpreter::new();
+    let mut second = Interpreter::new();
+    assert_eq!(
+        run_ast(&mut first, &main, &module),
+        JsValue::from("2/2/function")
+    );
+    // The second interpreter gets its own module instance
+    assert_eq!(
+        run_ast(&mut second, &main, &module),
+        JsValue::from("2/2/function")
+    );
+    assert_eq!(parser.parsed_programs(), 2);
+    assert_eq!(first.parsed_programs(), 0);
+}
+
+#[test]
+fn test_provide_module_ast_requires_request() {
+    let mut interp = Interpreter::new();
+    let module = interp.parse("export const x = 1;", None).unwrap();
+    let err = interp
+        .provide_module_ast(ModulePath::new("./x"), module)
+        .unwrap_err();
+    assert!(err.to_string().contains("./x"), "{}", err);
+}
+
+/// An in-memory project: an extensionless import and a directory import
+/// whose index imports a sibling of its own
+fn module_source_map_project() -> Interpreter {
+    let mut interp = Interpreter::new();
+    interp.set_module_source_map([
+        (
+            "/src/utils.ts",
+            "export function greet(name: string) { return `hi ${name}`; }",
+        ),
+        (
+            "/src/lib/index.ts",
+            r#"import { helper } from "./helper"; export const VERSION = "1.0"; export function describe() { return helper(); }"#,
+        ),
+        ("/src/lib/helper.ts", "export function helper() { return 'helped'; }"),
+        ("/src/view.tsx", "export const view = 1;"),
+    ]);
+    interp
+}
+
+#[test]
+fn test_module_source_map_serves_imports() {
+    let mut interp = module_source_map_project();
+    let result = run(
+        &mut interp,
+        r#"
+        import { greet } from "./utils";
+        import { VERSION, describe } from "./lib";
+        `${greet("x")} ${VERSION} ${describe()}`
+        "#,
+        Some("/src/main.ts"),
+    )
+    .unwrap();
+    match result {
+        StepResult::Complete(value) => assert_eq!(value, JsValue::from("hi x 1.0 helped")),
+        other => panic!("Expected Complete, got {:?}", other),
+    }
+
+    // `eval` runs served imports without a step loop
+    let mut interp = module_source_map_project();
+    let result = interp
+        .eval(
+            r#"import { greet } from "./utils"; greet("y")"#,
+            Some(ModulePath::new("/src/main.ts")),
+        )
+        .unwrap();
+    match result {
+        StepResult::Complete(value) => assert_eq!(value, JsValue::from("hi y")),
+        other => panic!("Expected Complete, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_module_source_map_leaves_absent_imports_to_host() {
+    let mut interp = module_source_map_project();
+    let result = run(
+        &mut interp,
+        r#"
+        import { greet } from "./utils";
+        import { extra } from "./extra";
+        greet(extra)
+        "#,
+        Some("/src/main.ts"),
+    )
+    .unwrap();
+    let StepResult::NeedImports(imports) = result else {
+        panic!("Expected NeedImports, got {:?}", result);
+    };
+    let paths: Vec<&str> = imports
+        .iter()
+        .map(|req| req.resolved_path.as_str())
+        .collect();
+    assert_eq!(paths, ["/src/extra"]);
+
+    interp
+        .provide_module(
+            imports[0].resolved_path.clone(),
+            "export const extra = 'host';",
+        )
+        .unwrap();
+    match run_to_completion(&mut interp).unwrap() {
+        StepResult::Complete(value) => assert_eq!(value, JsValue::from("hi host")),
+        other => panic!("Expected Complete, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_module_source_map_candidates() {
+    let mut interp = module_source_map_project();
+    let err = run(
+        &mut interp,
+        r#"import { view } from "./view";"#,
+        Some("/src/main.ts"),
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "Cannot import './view': found '/src/view.tsx', but TSX modules are not supported"
+        ),
+        "{}",
+        err
+    );
+
+    // With only exact paths, an extensionless import goes to the host
+    let mut interp = module_source_map_project();
+    interp.set_module_candidates(vec![tsrun::ModuleCandidate::Exact]);
+    let result = run(
+        &mut interp,
+        r#"import { greet } from "./utils";"#,
+        Some("/src/main.ts"),
+    )
+    .unwrap();
+    assert!(matches!(result, StepResult::NeedImports(_)), "{:?}", result);
+}
+
+/// A module as a code generator would emit it: the same helpers, different data
+fn generated_module(index: usize) -> String {
+    format!(
+        r#"function clamp(value: number, min: number, max: number) {{
+    return Math.min(Math.max(value, min), max);
+}}
+export function replicas(requested: number) {{
+    const bounded = clamp(requested, 1, 10);
+    return [1, 2, 3].map((n) => n * bounded);
+}}
+export const name = "service-{index}";
+export const total = replicas({index}).reduce((a, b) => a + b, 0);
+"#
+    )
+}
+
+#[test]
+fn test_generated_modules_share_compiled_code() {
+    let count = 100;
+    let mut interp = Interpreter::new();
+    interp.set_module_source_map(
+        (0..count).map(|i| (format!("/gen/service{}.ts", i), generated_module(i))),
+    );
+    let imports: String = (0..count)
+        .map(|i| format!("import {{ total as t{i} }} from \"./gen/service{i}\";\n"))
+        .collect();
+    let sum: Vec<String> = (0..count).map(|i| format!("t{}", i)).collect();
+    let result = run(
+        &mut interp,
+        &format!("{}{}", imports, sum.join(" + ")),
+        Some("/main.ts"),
+    )
+    .unwrap();
+    let expected: usize = (0..count).map(|i| 6 * i.clamp(1, 10)).sum();
+    assert!(
+        matches!(&result, StepResult::Complete(v) if *v == JsValue::from(expected as f64)),
+        "{:?}",
+        result
+    );
+
+    // Each module attaches itself and four functions, yet the code that stays
+    // alive is bounded by the template, not the module count
+    let stats = interp.chunk_cache_stats();
+    assert!(stats.chunks_attached > 5 * count, "{:?}", stats);
+    assert!(stats.code_shared >= 3 * (count - 1), "{:?}", stats);
+    assert!(stats.live_code <= 10, "{:?}", stats);
+}
+
+#[test]
+fn test_identical_function_chunks_are_shared() {
+    let mut interp = Interpreter::new();
+    let source = "function twice(x: number) { return x * 2; } twice(21)";
+    for _ in 0..3 {
+        let result = run(&mut interp, source, None).unwrap();
+        assert!(
+            matches!(&result, StepResult::Complete(v) if *v == JsValue::Number(42.0)),
+            "{:?}",
+            result
+        );
+    }
+    let stats = interp.chunk_cache_stats();
+    assert_eq!(stats.chunks_shared, 2, "{:?}", stats);
+    assert_eq!(stats.live_chunks, 1, "{:?}", stats);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Module Integrity Tests
+// ═══════════════════════════════════════════════════════════════════════════════
+
+const SCHEMA_SOURCE: &str = r#"export const table = "users";
+export const columns = ["id", "name", "email", "created_at", "updated_at", "deleted_at"];
+export const primaryKey = "id";
+"#;
+const SCHEMA_INTEGRITY: &str = "sha256-pJIQ5Sn5kw7pHpbBKuldj2A5VJQPHIILrNhmnL/ZdYE=";
+const TAMPERED_SCHEMA: &str = r#"export const table = "admins";"#;
+
+/// Prepare a main module importing `/lib/schema.ts` with `import_line`,
+/// returning its one import request
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn request_schema(interp: &mut Interpreter, import_line: &str) -> tsrun::ImportRequest {
+    let source = format!("{}\ntable + ':' + columns.length;", import_line);
+    let result = run(interp, &source, Some("/main.ts")).unwrap();
+    let StepResult::NeedImports(mut requests) = result else {
+        panic!("Expected NeedImports, got {:?}", result);
+    };
+    assert_eq!(requests.len(), 1);
+    requests.remove(0)
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_provide_module_with_matching_integrity() {
+    let mut interp = Interpreter::new();
+    let request = request_schema(
+        &mut interp,
+        r#"import { table, columns } from "/lib/schema.ts";"#,
+    );
+    assert_eq!(request.integrity, None);
+
+    interp
+        .provide_module_with_integrity(request.resolved_path, SCHEMA_SOURCE, SCHEMA_INTEGRITY)
+        .unwrap();
+    match run_to_completion(&mut interp).unwrap() {
+        StepResult::Complete(value) => assert_eq!(value, JsValue::String("users:6".into())),
+        other => panic!("Expected Complete, got {:?}", other),
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_module_integrity_mismatch_is_refused() {
+    let mut interp = Interpreter::new();
+    let request = request_schema(
+        &mut interp,
+        r#"import { table, columns } from "/lib/schema.ts";"#,
+    );
+
+    let err = interp
+        .provide_module_with_integrity(
+            request.resolved_path.clone(),
+            TAMPERED_SCHEMA,
+            SCHEMA_INTEGRITY,
+        )
+        .unwrap_err();
+    match err {
+        JsError::IntegrityMismatch {
+            path,
+            expected,
+            actual,
+        } => {
+            assert_eq!(path.as_str(), "/lib/schema.ts");
+            assert_eq!(expected, SCHEMA_INTEGRITY);
+            assert_eq!(
+                actual,
+                "sha256-9LN+bfXKqUNFPhrwgkouR4AVzNtN9WNS1pm8d1rdEh8="
+            );
+        }
+        other => panic!("Expected IntegrityMismatch, got {:?}", other),
+    }
+
+    // An integrity import attribute applies to a plain provide_module too
+    let mut interp = Interpreter::new();
+    let import_line = format!(
+        r#"import {{ table, columns }} from "/lib/schema.ts" with {{ integrity: "{}" }};"#,
+        SCHEMA_INTEGRITY
+    );
+    let request = request_schema(&mut interp, &import_line);
+    assert_eq!(request.integrity.as_deref(), Some(SCHEMA_INTEGRITY));
+    let err = interp
+        .provide_module(request.resolved_path.clone(), TAMPERED_SCHEMA)
+        .unwrap_err();
+    assert!(
+        matches!(err, JsError::IntegrityMismatch { .. }),
+        "{:?}",
+        err
+    );
+
+    // The import is still outstanding and the genuine source loads
+    interp
+        .provide_module(request.resolved_path, SCHEMA_SOURCE)
+        .unwrap();
+    match run_to_completion(&mut interp).unwrap() {
+        StepResult::Complete(value) => assert_eq!(value, JsValue::String("users:6".into())),
+        other => panic!("Expected Complete, got {:?}", other),
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_integrity_manifest_refuses_unexpected_module() {
+    let mut interp = Interpreter::new();
+    interp.set_integrity_manifest([(
+        ModulePath::new("/lib/schema.ts"),
+        SCHEMA_INTEGRITY.to_string(),
+    )]);
+    let request = request_schema(
+        &mut interp,
+        r#"import { table, columns } from "/lib/schema.ts";"#,
+    );
+    assert_eq!(request.integrity.as_deref(), Some(SCHEMA_INTEGRITY));
+
+    // The host passes no hash, but the manifest still applies
+    let err = interp
+        .provide_module(request.resolved_path.clone(), TAMPERED_SCHEMA)
+        .unwrap_err();
+    assert!(
+        matches!(&err, JsError::IntegrityMismatch { path, .. } if path.as_str() == "/lib/schema.ts"),
+        "{:?}",
+        err
+    );
+    assert!(
+        err.to_string().contains("Integrity check failed"),
+        "{}",
+        err
+    );
+
+    // Sources from the module source map are checked as well
+    let mut interp = Interpreter::new();
+    interp.set_integrity_manifest([(
+        ModulePath::new("/lib/schema.ts"),
+        SCHEMA_INTEGRITY.to_string(),
+    )]);
+    interp.set_module_source_map([("/lib/schema.ts", TAMPERED_SCHEMA)]);
+    let err = run(
+        &mut interp,
+        r#"import { table } from "/lib/schema.ts"; table;"#,
+        Some("/main.ts"),
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, JsError::IntegrityMismatch { .. }),
+        "{:?}",
+        err
+    );
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// JSON Module Tests
+// ═══════════════════════════════════════════════════════════════════════════════
+
+const LIMITS_JSON: &str = r#"{ "retries": 3, "hosts": ["a", "b"] }"#;
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_json_module_is_shared_and_frozen() {
+    let mut interp = Interpreter::new();
+    let result = run(
+        &mut interp,
+        r#"
+        import limits from "./limits.json" with { type: "json" };
+        import { sameLimits } from "./check.ts";
+        [sameLimits === limits, limits.retries, limits.hosts.join(),
+            Object.isFrozen(limits), Object.isFrozen(limits.hosts)].join()
+        "#,
+        Some("/main.ts"),
+    )
+    .unwrap();
+    let StepResult::NeedImports(requests) = result else {
+        panic!("Expected NeedImports, got {:?}", result);
+    };
+    let json = requests
+        .iter()
+        .find(|req| req.specifier == "./limits.json")
+        .unwrap();
+    assert!(json.is_json());
+    interp
+        .provide_module(json.resolved_path.clone(), LIMITS_JSON)
+        .unwrap();
+    interp
+        .provide_module(
+            ModulePath::new("/check.ts"),
+            r#"import data from "./limits.json" with { type: "json" };
+            export const sameLimits = data;"#,
+        )
+        .unwrap();
+
+    match run_to_completion(&mut interp).unwrap() {
+        StepResult::Complete(value) => {
+            assert_eq!(value, JsValue::from("true,3,a,b,true,true"))
+        }
+        other => panic!("Expected Complete, got {:?}", other),
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_json_module_namespace_has_only_default() {
+    let mut interp = Interpreter::new();
+    interp.set_module_source_map([("/data/limits.json", LIMITS_JSON)]);
+    let result = run(
+        &mut interp,
+        r#"
+        import * as ns from "/data/limits.json" with { type: "json" };
+        Object.keys(ns).join() + ":" + ns.default.retries
+        "#,
+        Some("/main.ts"),
+    )
+    .unwrap();
+    match result {
+        StepResult::Complete(value) => assert_eq!(value, JsValue::from("default:3")),
+        other => panic!("Expected Complete, got {:?}", other),
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_malformed_json_module_reports_position() {
+    let mut interp = Interpreter::new();
+    let result = run(
+        &mut interp,
+        r#"import limits from "./limits.json" with { type: "json" }; limits;"#,
+        Some("/main.ts"),
+    )
+    .unwrap();
+    let StepResult::NeedImports(requests) = result else {
+        panic!("Expected NeedImports, got {:?}", result);
+    };
+    let path = requests[0].resolved_path.clone();
+    let err = interp
+        .provide_module(path.clone(), "{\n  \"retries\": 3,\n  hosts: []\n}")
+        .unwrap_err();
+    let message = err.to_string();
+    assert!(
+        message.contains("/limits.json") && message.contains("not valid JSON"),
+        "{}",
+        message
+    );
+    assert!(message.contains("line 3 column 3"), "{}", message);
+
+    // The import stays outstanding, and code is refused for it
+    let module = interp.parse("export default 1;", None).unwrap();
+    let err = interp.provide_module_ast(path.clone(), module).unwrap_err();
+    assert!(err.to_string().contains("type: \"json\""), "{}", err);
+    interp.provide_module(path, LIMITS_JSON).unwrap();
+    match run_to_completion(&mut interp).unwrap() {
+        StepResult::Complete(value) => assert!(matches!(value.value(), JsValue::Object(_))),
+        other => panic!("Expected Complete, got {:?}", other),
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_import_attributes_round_trip_on_request() {
+    let mut interp = Interpreter::new();
+    let request = request_schema(
+        &mut interp,
+        r#"import { table, columns } from "/lib/schema.ts" with { type: "ts", "x-cache": "no" };"#,
+    );
+    assert_eq!(
+        request.attributes,
+        vec![
+            ("type".to_string(), "ts".to_string()),
+            ("x-cache".to_string(), "no".to_string()),
+        ]
+    );
+    assert!(!request.is_json());
+
+    let request = main_import_request(Some("/main.ts"), "./x");
+    assert!(request.attributes.is_empty());
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Source Transform Tests
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// A config whose source transform uppercases `debug` and logs each path it sees
+fn create_transforming_interp(seen: &Rc<RefCell<Vec<String>>>) -> Interpreter {
+    let seen = Rc::clone(seen);
+    Interpreter::with_config(InterpreterConfig {
+        internal_modules: vec![InternalModule::source(
+            "eval:settings",
+            r#"export const level = "debug";"#,
+        )],
+        source_transform: Some(Box::new(move |path, source| {
+            seen.borrow_mut().push(path.as_str().to_string());
+            if source.contains("@@bad") {
+                return Err("unknown pragma @@bad".to_string());
+            }
+            Ok(source.replace("debug", "DEBUG"))
+        })),
+        ..Default::default()
+    })
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_source_transform_rewrites_every_module_once() {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let mut interp = create_transforming_interp(&seen);
+
+    let result = run(
+        &mut interp,
+        r#"
+        import { level } from "eval:settings";
+        import { mode } from "./mode.ts";
+        [level, mode, "debug"].join(",");
+    "#,
+        Some("/main.ts"),
+    )
+    .unwrap();
+    let StepResult::NeedImports(requests) = result else {
+        panic!("Expected NeedImports, got {:?}", result);
+    };
+    let request = requests.first().unwrap();
+    assert_eq!(request.resolved_path.as_str(), "/mode.ts");
+    interp
+        .provide_module(
+            request.resolved_path.clone(),
+            r#"export const mode = "debug";"#,
+        )
+        .unwrap();
+
+    match run_to_completion(&mut interp).unwrap() {
+        StepResult::Complete(value) => {
+            assert_eq!(value, JsValue::String("DEBUG,DEBUG,DEBUG".into()))
+        }
+        other => panic!("Expected Complete, got {:?}", other),
+    }
+    assert_eq!(
+        *seen.borrow(),
+        vec!["/main.ts", "eval:settings", "/mode.ts"]
+    );
+
+    // A later import of the instantiated internal module is not transformed again
+    seen.borrow_mut().clear();
+    let result = run(
+        &mut interp,
+        r#"import { level } from "eval:settings"; level;"#,
+        None,
+    )
+    .unwrap();
+    assert!(matches!(result, StepResult::Complete(ref value) if *value == JsValue::from("DEBUG")));
+    assert_eq!(*seen.borrow(), vec!["<main>"]);
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_source_transform_error_names_module() {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let mut interp = create_transforming_interp(&seen);
+
+    let result = run(
+        &mut interp,
+        r#"import { mode } from "/lib/mode.ts"; mode;"#,
+        Some("/main.ts"),
+    )
+    .unwrap();
+    let StepResult::NeedImports(requests) = result else {
+        panic!("Expected NeedImports, got {:?}", result);
+    };
+    let request = requests.first().unwrap();
+    let err = interp
+        .provide_module(
+            request.resolved_path.clone(),
+            "// @@bad\nexport const mode = 1;",
+        )
+        .unwrap_err();
+    match &err {
+        JsError::TransformError { path, message } => {
+            assert_eq!(path.as_str(), "/lib/mode.ts");
+            assert_eq!(message, "unknown pragma @@bad");
+        }
+        other => panic!("Expected TransformError, got {:?}", other),
+    }
+    assert_eq!(
+        err.to_string(),
+        "ModuleError: Source transform failed for '/lib/mode.ts': unknown pragma @@bad"
+    );
+
+    // The entry source is transformed too
+    let err = run(&mut interp, "// @@bad\n1", None).unwrap_err();
+    assert!(
+        matches!(&err, JsError::TransformError { path, .. } if path.as_str() == "<main>"),
+        "{:?}",
+        err
+    );
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Reset Tests
+// ═══════════════════════════════════════════════════════════════════════════════
+
+thread_local! {
+    /// Module bodies evaluated on this test's thread
+    static EVALUATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+fn count_evaluation(
+    _interp: &mut Interpreter,
+    _this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    EVALUATIONS.with(|count| count.set(count.get() + 1));
+    Ok(Guarded::unguarded(JsValue::Undefined))
+}
+
+const COUNTED_MODULE: &str = r#"
+    import { countEvaluation } from "test:count";
+    countEvaluation();
+    let calls = 0;
+    export function next() { return ++calls; }
+"#;
+
+/// Run `main` three times with a reset before each later run, and return
+/// what each run completed with and how often the module body was evaluated
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn run_across_resets(retain: bool, main: &str) -> (Vec<JsValue>, usize) {
+    EVALUATIONS.with(|count| count.set(0));
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        internal_modules: vec![
+            InternalModule::native("test:count")
+                .with_function("countEvaluation", count_evaluation, 0)
+                .build(),
+            InternalModule::source("test:counted", COUNTED_MODULE).retain_across_reset(retain),
+        ],
+        ..Default::default()
+    });
+    let mut results = Vec::new();
+    for run in 0..3 {
+        if run > 0 {
+            interp.reset();
+        }
+        interp
+            .prepare(main, Some(ModulePath::new("/main.ts")))
+            .unwrap();
+        match run_to_completion(&mut interp).unwrap() {
+            StepResult::Complete(value) => results.push(value.value().clone()),
+            other => panic!("Unexpected step result {:?}", other),
+        }
+        interp.collect();
+    }
+    (results, EVALUATIONS.with(|count| count.get()))
+}
+
+#[test]
+fn test_reset_keeps_retained_module_evaluated_once() {
+    let (results, evaluations) =
+        run_across_resets(true, r#"import { next } from "test:counted"; next();"#);
+    assert_eq!(evaluations, 1);
+    // The module's own state carries over between runs
+    assert_eq!(results, [1.0, 2.0, 3.0].map(JsValue::Number).to_vec());
+}
+
+#[test]
+fn test_reset_evaluates_other_modules_again() {
+    let (results, evaluations) =
+        run_across_resets(false, r#"import { next } from "test:counted"; next();"#);
+    assert_eq!(evaluations, 3);
+    assert_eq!(results, vec![JsValue::Number(1.0); 3]);
+}
+
+#[test]
+fn test_reset_restores_globals() {
+    let main = r#"
+        const seen = typeof (globalThis as any).leaked + "," + typeof (globalThis as any).Math.max;
+        (globalThis as any).leaked = new Map([[1, 2]]);
+        (globalThis as any).Math = undefined;
+        seen;
+    "#;
+    let (results, _) = run_across_resets(true, main);
+    assert_eq!(results, vec![JsValue::from("undefined,function"); 3]);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_reset_drops_loaded_modules_and_pending_run() {
+    let mut interp = Interpreter::new();
+    let result = interp
+        .prepare(
+            r#"import { value } from "./lib.ts"; value;"#,
+            Some(ModulePath::new("/main.ts")),
+        )
+        .unwrap();
+    assert!(matches!(result, StepResult::NeedImports(_)));
+    interp.reset();
+
+    // The next run starts over and asks for the module again
+    let result = interp
+        .prepare(
+            r#"import { value } from "./lib.ts"; value;"#,
+            Some(ModulePath::new("/main.ts")),
+        )
+        .unwrap();
+    assert!(matches!(result, StepResult::NeedImports(ref imports) if imports.len() == 1));
+}
+
+/// Run `main` under `config`, providing each requested module from `sources`
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn run_module_graph(
+    config: InterpreterConfig,
+    main: &str,
+    sources: &[(&str, &str)],
+) -> (Interpreter, Result<StepResult, JsError>) {
+    let mut interp = Interpreter::with_config(config);
+    let mut result = run(&mut interp, main, Some("/main.ts"));
+    while let Ok(StepResult::NeedImports(imports)) = result {
+        for req in imports {
+            let source = sources
+                .iter()
+                .find(|(path, _)| *path == req.resolved_path.as_str())
+                .map(|(_, source)| *source)
+                .unwrap();
+            if let Err(err) = interp.provide_module(req.resolved_path, source) {
+                return (interp, Err(err));
+            }
+        }
+        result = run_to_completion(&mut interp);
+    }
+    (interp, result)
+}
+
+const BROKEN_LEAF_MAIN: &str = r#"
+    import { area } from "./a.ts";
+    import { total } from "./b.ts";
+    let message = "";
+    try { area(); } catch (e) { message = e.name + ": " + e.message; }
+    [total, message].join("|")
+"#;
+
+const BROKEN_LEAF_SOURCES: [(&str, &str); 3] = [
+    (
+        "/a.ts",
+        r#"import * as shapes from "./bad.ts"; export function area() { return shapes.square(2); }"#,
+    ),
+    (
+        "/b.ts",
+        "export const total = [1, 2, 3].reduce((a, b) => a + b);",
+    ),
+    ("/bad.ts", "export function square(n) { return n * ; }"),
+];
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_module_error_policy_collect_runs_unaffected_branches() {
+    let config = InterpreterConfig {
+        module_error_policy: tsrun::ModuleErrorPolicy::Collect,
+        ..Default::default()
+    };
+    let (interp, result) = run_module_graph(config, BROKEN_LEAF_MAIN, &BROKEN_LEAF_SOURCES);
+    let Ok(StepResult::Complete(value)) = result else {
+        panic!("Expected Complete, got {:?}", result);
+    };
+    let JsValue::String(s) = value.value() else {
+        panic!("Expected a string, got {:?}", value);
+    };
+    assert!(
+        s.as_str()
+            .starts_with("6|TypeError: Module '/bad.ts' failed to load:"),
+        "{}",
+        s
+    );
+
+    let errors = interp.module_errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0.as_str(), "/bad.ts");
+    assert!(
+        matches!(errors[0].1, JsError::SyntaxError { .. }),
+        "{:?}",
+        errors[0].1
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_module_error_policy_collect_poisons_named_imports() {
+    let config = InterpreterConfig {
+        module_error_policy: tsrun::ModuleErrorPolicy::Collect,
+        ..Default::default()
+    };
+    let (interp, result) = run_module_graph(
+        config,
+        r#"import { square } from "./bad.ts"; square(2);"#,
+        &BROKEN_LEAF_SOURCES,
+    );
+    let err = result.unwrap_err();
+    assert!(
+        err.to_string().contains("Module '/bad.ts' failed to load"),
+        "{}",
+        err
+    );
+    assert_eq!(interp.module_errors().len(), 1);
+}
+
+#[test]
+fn test_module_error_policy_fail_fast_by_default() {
+    let (interp, result) = run_module_graph(
+        InterpreterConfig::default(),
+        BROKEN_LEAF_MAIN,
+        &BROKEN_LEAF_SOURCES,
+    );
+    assert!(
+        matches!(result, Err(JsError::SyntaxError { .. })),
+        "{:?}",
+        result
+    );
+    assert!(interp.module_errors().is_empty());
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_provide_module_file_resolves_imports_from_file() {
+    let mut interp = Interpreter::new();
+    let mut result = run(
+        &mut interp,
+        r#"import { label } from "./lib"; label"#,
+        Some("/app/main.ts"),
+    )
+    .unwrap();
+    let mut requested = Vec::new();
+    while let StepResult::NeedImports(imports) = result {
+        for req in imports {
+            requested.push(req.resolved_path.to_string());
+            match req.resolved_path.as_str() {
+                "/app/lib" => interp
+                    .provide_module_file(
+                        req.resolved_path,
+                        ModulePath::new("/app/lib/index.ts"),
+                        r#"import { name } from "./name.ts"; export const label = "lib:" + name;"#,
+                    )
+                    .unwrap(),
+                _ => interp
+                    .provide_module(req.resolved_path, r#"export const name = "inner";"#)
+                    .unwrap(),
+            }
+        }
+        result = run_to_completion(&mut interp).unwrap();
+    }
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete, got {:?}", result);
+    };
+    assert_eq!(*value.value(), JsValue::from("lib:inner"));
+    assert_eq!(requested, vec!["/app/lib", "/app/lib/name.ts"]);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Lazy Internal Module Tests
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// A lazy "host:catalog" module exporting `services` and `regions`, whose
+/// resolver logs each name it resolves and fails for `broken`
+fn lazy_catalog(resolved: &Rc<RefCell<Vec<String>>>, with_keys: bool) -> InternalModule {
+    let log = Rc::clone(resolved);
+    let module = InternalModule::lazy(
+        "host:catalog",
+        Box::new(move |_interp, name| {
+            log.borrow_mut().push(name.to_string());
+            match name {
+                "services" => Ok(Some(Guarded::unguarded(JsValue::from(50_000.0)))),
+                "regions" => Ok(Some(Guarded::unguarded(JsValue::from("eu,us")))),
+                "broken" => Err(JsError::type_error("catalog backend unavailable")),
+                _ => Ok(None),
+            }
+        }),
+    );
+    let module = if with_keys {
+        module.with_own_keys(Box::new(|| {
+            vec!["services".to_string(), "regions".to_string()]
+        }))
+    } else {
+        module
+    };
+    module.build()
+}
+
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn run_with_catalog(module: InternalModule, source: &str) -> JsValue {
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        internal_modules: vec![module],
+        ..Default::default()
+    });
+    match run(&mut interp, source, None).unwrap() {
+        StepResult::Complete(value) => value.value().clone(),
+        other => panic!("Expected Complete, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_lazy_module_resolves_only_accessed_exports() {
+    let resolved = Rc::new(RefCell::new(Vec::new()));
+    let value = run_with_catalog(
+        lazy_catalog(&resolved, true),
+        r#"
+        import { services, regions } from "host:catalog";
+        services + services
+    "#,
+    );
+    assert_eq!(value, JsValue::from(100_000.0));
+    // `regions` is imported but never read; `services` resolves once
+    assert_eq!(*resolved.borrow(), vec!["services".to_string()]);
+}
+
+#[test]
+fn test_lazy_module_namespace_lists_keys_without_resolving() {
+    let resolved = Rc::new(RefCell::new(Vec::new()));
+    let value = run_with_catalog(
+        lazy_catalog(&resolved, true),
+        r#"
+        import * as catalog from "host:catalog";
+        const names: string[] = [];
+        for (const name in catalog) names.push(name);
+        [Object.keys(catalog).join(), names.join(), "regions" in catalog].join(" | ")
+    "#,
+    );
+    assert_eq!(
+        value,
+        JsValue::from("services,regions | services,regions | true")
+    );
+    assert!(resolved.borrow().is_empty(), "{:?}", resolved.borrow());
+
+    let value = run_with_catalog(
+        lazy_catalog(&resolved, false),
+        r#"
+        import * as catalog from "host:catalog";
+        [catalog.regions, catalog.missing, Object.keys(catalog).join()].join(" | ")
+    "#,
+    );
+    assert_eq!(value, JsValue::from("eu,us |  | regions"));
+}
+
+#[test]
+fn test_lazy_module_resolver_error_is_catchable_at_access() {
+    let resolved = Rc::new(RefCell::new(Vec::new()));
+    let value = run_with_catalog(
+        lazy_catalog(&resolved, true),
+        r#"
+        import { broken, services } from "host:catalog";
+        let message = "";
+        try {
+            broken;
+        } catch (e) {
+            message = e.name + ": " + e.message;
+        }
+        message + " | " + services
+    "#,
+    );
+    assert_eq!(
+        value,
+        JsValue::from("TypeError: catalog backend unavailable | 50000")
+    );
+}
+
+#[test]
+fn test_anonymous_default_exports_are_named_default() {
+    let mut interp = Interpreter::new();
+    let result = run(
+        &mut interp,
+        r#"
+        import f from "./f";
+        import C from "./c";
+        import a from "./a";
+        [f.name, C.name, a.name, a.length].join("|")
+    "#,
+        None,
+    )
+    .unwrap();
+
+    let StepResult::NeedImports(imports) = result else {
+        panic!("Expected NeedImports");
+    };
+    let sources = [
+        ("./f", "export default function () {}"),
+        ("./c", "export default class {}"),
+        ("./a", "export default (x, y = 1) => x;"),
+    ];
+    for import in imports {
+        let (_, source) = sources
+            .iter()
+            .find(|(specifier, _)| *specifier == import.specifier)
+            .unwrap();
+        interp
+            .provide_module(import.resolved_path.clone(), source)
+            .unwrap();
+    }
+
+    match run_to_completion(&mut interp).unwrap() {
+        StepResult::Complete(value) => {
+            assert_eq!(value, JsValue::from("default|default|default|1"));
+        }
+        _ => panic!("Expected Complete"),
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_javascript_module_uses_typescript_keywords_as_names() {
+    let mut interp = Interpreter::new();
+    let source = r#"
+        import { describe } from "./lib/kind.js";
+        import { twice } from "./lib/twice.ts";
+        describe(twice(2))
+    "#;
+    let StepResult::NeedImports(imports) = run(&mut interp, source, Some("/main.ts")).unwrap()
+    else {
+        panic!("Expected NeedImports");
+    };
+    let langs: Vec<_> = imports
+        .iter()
+        .map(|req| (req.resolved_path.as_str(), req.lang))
+        .collect();
+    assert_eq!(
+        langs,
+        [
+            ("/lib/kind.js", Lang::JavaScript),
+            ("/lib/twice.ts", Lang::TypeScript)
+        ]
+    );
+
+    interp
+        .provide_module(
+            ModulePath::new("/lib/kind.js"),
+            r#"
+            export function describe(n) {
+                const type = typeof n, interface = "number";
+                let as = 1
+                as = 2
+                return type === interface && as < n > (0) ? "big" : "small";
+            }
+            "#,
+        )
+        .unwrap();
+    interp
+        .provide_module(
+            ModulePath::new("/lib/twice.ts"),
+            "export const twice = (n: number): number => n * 2;",
+        )
+        .unwrap();
+
+    let StepResult::Complete(value) = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(value.as_str(), Some("big"));
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_lang_override_and_default() {
+    // A .js path is parsed as TypeScript when asked to
+    let mut interp = Interpreter::new();
+    let result = interp.prepare_with_lang(
+        "const n: number = 1; n",
+        Some(ModulePath::new("/main.js")),
+        Lang::TypeScript,
+    );
+    assert!(result.is_ok(), "{:?}", result.err());
+    let mut interp = Interpreter::new();
+    let result = interp.prepare("const n: number = 1; n", Some(ModulePath::new("/main.js")));
+    assert!(matches!(result, Err(JsError::SyntaxError { .. })));
+
+    // Paths without an extension use the configured default
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        default_lang: Lang::JavaScript,
+        ..Default::default()
+    });
+    let result = interp.prepare("let type = 2; type < 1 > (0)", None);
+    assert!(result.is_ok(), "{:?}", result.err());
+    let result = interp.prepare("enum E { A }", None);
+    assert!(matches!(result, Err(JsError::SyntaxError { .. })));
+
+    // A provided module can be parsed as another language than its request says
+    let mut interp = Interpreter::new();
+    let source = r#"import { n } from "./lib/n.ts"; n"#;
+    run(&mut interp, source, Some("/main.ts")).unwrap();
+    interp
+        .provide_module_with_lang(
+            ModulePath::new("/lib/n.ts"),
+            "const a = 2, b = 1; export const n = a < b > (0);",
+            Lang::JavaScript,
+        )
+        .unwrap();
+    let result = run_to_completion(&mut interp).unwrap();
+    assert!(matches!(result, StepResult::Complete(v) if v.as_bool() == Some(false)));
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Entry Module Completion Value Tests
+// ═══════════════════════════════════════════════════════════════════════════════
+
+const COMPLETION_LIB: &str = "export const x = 21; x + 1000;";
+
+/// Run `main` as /main.ts importing COMPLETION_LIB as /lib.ts, answering each
+/// order with `{ n: 20 }`, and return what it completes with
+#[allow(clippy::unwrap_used, clippy::panic, clippy::indexing_slicing)]
+fn entry_completion(main: &str) -> JsValue {
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        internal_modules: vec![tsrun::create_eval_internal_module()],
+        ..Default::default()
+    });
+    interp
+        .prepare_with_modules(
+            main,
+            Some(ModulePath::new("/main.ts")),
+            [(ModulePath::new("/lib.ts"), COMPLETION_LIB.to_string())],
+        )
+        .unwrap();
+    loop {
+        match run_to_completion(&mut interp).unwrap() {
+            StepResult::Suspended { pending, .. } => {
+                let response = tsrun::api::create_response_object(
+                    &mut interp,
+                    &serde_json::json!({ "n": 20 }),
+                )
+                .unwrap();
+                interp.fulfill_orders(vec![tsrun::OrderResponse {
+                    id: pending[0].id,
+                    result: Ok(response),
+                }]);
+            }
+            StepResult::Complete(value) => return value.value().clone(),
+            other => panic!("Unexpected step result {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_entry_module_completes_with_last_expression() {
+    let main = r#"
+        import { x } from "./lib.ts";
+        function twice(n: number): number { return n * 2; }
+        const label = "x";
+        twice(x);
+        const unused = 1;
+    "#;
+    assert_eq!(entry_completion(main), JsValue::Number(42.0));
+}
+
+#[test]
+fn test_entry_module_completion_survives_awaited_order() {
+    let main = r#"
+        import { order } from "tsrun:host";
+        import { x } from "./lib.ts";
+        const reply: any = await order({ x });
+        let offset = 1;
+        for (const k of [1, 2]) offset += k;
+        x + reply.n + offset;
+        const finished = true;
+    "#;
+    assert_eq!(entry_completion(main), JsValue::Number(45.0));
+}
+
+#[test]
+fn test_entry_module_ending_in_declaration_completes_undefined() {
+    let main = r#"
+        import { x } from "./lib.ts";
+        export const doubled = x * 2;
+        function unused() { return doubled; }
+    "#;
+    assert_eq!(entry_completion(main), JsValue::Undefined);
+}
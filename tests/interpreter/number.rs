@@ -374,3 +374,180 @@ fn test_decimal_with_exponent_no_fraction() {
     assert_eq!(eval("0.e0"), JsValue::Number(0.0));
     assert_eq!(eval("1.e0"), JsValue::Number(1.0));
 }
+
+#[test]
+fn test_number_constants_exact_values() {
+    assert_eq!(eval("Number.EPSILON === 2 ** -52"), JsValue::Boolean(true));
+    assert_eq!(
+        eval("Number.MAX_SAFE_INTEGER === 2 ** 53 - 1"),
+        JsValue::Boolean(true)
+    );
+    assert_eq!(
+        eval("Number.MIN_SAFE_INTEGER === -(2 ** 53 - 1)"),
+        JsValue::Boolean(true)
+    );
+    // MIN_VALUE is the smallest subnormal, so halving it rounds to zero
+    assert_eq!(eval("Number.MIN_VALUE > 0"), JsValue::Boolean(true));
+    assert_eq!(eval("Number.MIN_VALUE / 2"), JsValue::Number(0.0));
+    assert_eq!(
+        eval("Number.MIN_VALUE === 2 ** -1074"),
+        JsValue::Boolean(true)
+    );
+}
+
+#[test]
+fn test_number_predicates_edge_cases() {
+    let cases = [
+        ("Number.isSafeInteger(2 ** 53 - 1)", true),
+        ("Number.isSafeInteger(2 ** 53)", false),
+        ("Number.isSafeInteger(-(2 ** 53 - 1))", true),
+        ("Number.isSafeInteger(-0)", true),
+        ("Number.isSafeInteger(1.5)", false),
+        ("Number.isSafeInteger(Infinity)", false),
+        ("Number.isSafeInteger(NaN)", false),
+        ("Number.isSafeInteger('1')", false),
+        ("Number.isInteger(-0)", true),
+        ("Number.isInteger(2 ** 60)", true),
+        ("Number.isInteger(-Infinity)", false),
+        ("Number.isInteger(NaN)", false),
+        ("Number.isInteger(5e-324)", false),
+        ("Number.isFinite(-0)", true),
+        ("Number.isFinite(-Infinity)", false),
+        ("Number.isFinite(NaN)", false),
+        ("Number.isFinite(null)", false),
+        ("isFinite(null)", true),
+        ("isFinite('12')", true),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(eval(expr), JsValue::Boolean(expected), "{}", expr);
+    }
+}
+
+#[test]
+fn test_number_formatting_is_consistent_across_paths() {
+    let cases = [
+        ("0", "0"),
+        ("-0", "0"),
+        ("1", "1"),
+        ("-1", "-1"),
+        ("100", "100"),
+        ("1.5", "1.5"),
+        ("-1.5", "-1.5"),
+        ("0.1", "0.1"),
+        ("0.1 + 0.2", "0.30000000000000004"),
+        ("1 / 3", "0.3333333333333333"),
+        ("123.456", "123.456"),
+        ("2 ** 31", "2147483648"),
+        ("2 ** 53", "9007199254740992"),
+        ("2 ** 53 + 2", "9007199254740994"),
+        ("2 ** 60", "1152921504606847000"),
+        ("2 ** 64", "18446744073709552000"),
+        ("1e20", "100000000000000000000"),
+        ("123456789012345680000", "123456789012345680000"),
+        ("999999999999999900000", "999999999999999900000"),
+        ("1e21", "1e+21"),
+        ("-1e21", "-1e+21"),
+        ("1.5e21", "1.5e+21"),
+        ("1e100", "1e+100"),
+        ("-1.5e300", "-1.5e+300"),
+        ("Number.MAX_VALUE", "1.7976931348623157e+308"),
+        ("Number.MAX_SAFE_INTEGER", "9007199254740991"),
+        ("Number.EPSILON", "2.220446049250313e-16"),
+        ("0.000001", "0.000001"),
+        ("0.0000015", "0.0000015"),
+        ("1e-6", "0.000001"),
+        ("1e-7", "1e-7"),
+        ("1.5e-7", "1.5e-7"),
+        ("-1e-7", "-1e-7"),
+        ("123e-20", "1.23e-18"),
+        ("Number.MIN_VALUE", "5e-324"),
+        ("2.2250738585072014e-308", "2.2250738585072014e-308"),
+        ("1e-323", "1e-323"),
+        ("4.35", "4.35"),
+        ("0.5e-6", "5e-7"),
+        ("25 / 1e25", "2.5e-24"),
+    ];
+    for (expr, expected) in cases {
+        let source = format!(
+            r#"
+            const n: number = {expr};
+            [
+                String(n), `${{n}}`, "" + n, n.toString(), n.toString(10), [n].join(),
+                JSON.stringify(n), JSON.stringify([n]).slice(1, -1),
+                JSON.stringify({{ n }}, null, 1).split(": ")[1].split("\n")[0],
+            ].join("|")
+        "#
+        );
+        let expected_all = [expected; 9].join("|");
+        assert_eq!(
+            eval(&source),
+            JsValue::from(expected_all),
+            "formatting {}",
+            expr
+        );
+    }
+}
+
+#[test]
+fn test_negative_zero_keeps_its_sign() {
+    assert_eq!(
+        eval(
+            r#"
+            const z = -0;
+            [
+                Object.is(z, -0), Object.is(z, 0), 1 / z, Object.is(JSON.parse("-0"), -0),
+                Object.is([z][0], -0), Object.is(Number("-0"), -0), z.toFixed(1),
+                JSON.stringify({ z }), Object.is(Math.sign(z), -0),
+            ].join()
+        "#
+        ),
+        JsValue::from("true,false,-Infinity,true,true,true,0.0,{\"z\":0},true")
+    );
+    assert_eq!(
+        eval("[String(NaN), `${0 / 0}`, JSON.stringify([NaN, -Infinity])].join()"),
+        JsValue::from("NaN,NaN,[null,null]")
+    );
+}
+
+#[test]
+fn test_number_formatting_matches_exact_value() {
+    assert_eq!(
+        eval(
+            r#"
+            [
+                (1e21).toFixed(2), (1.005).toFixed(2), (2.5).toFixed(0), (-1.55).toFixed(1),
+                (123.456).toExponential(), (0).toExponential(), (1.45).toExponential(1),
+                (123.456).toPrecision(4), (0.00001234).toPrecision(2), (123456).toPrecision(2),
+            ].join("|")
+        "#
+        ),
+        JsValue::from("1e+21|1.00|3|-1.6|1.23456e+2|0e+0|1.4e+0|123.5|0.000012|1.2e+5")
+    );
+}
+
+#[test]
+fn test_number_to_string_radix_fractions() {
+    assert_eq!(
+        eval("[(0.1).toString(2), (255.5).toString(16), (-0.5).toString(3)].join('|')"),
+        JsValue::from(
+            "0.0001100110011001100110011001100110011001100110011001101|ff.8|-0.1111111111111111111111111111111112"
+        )
+    );
+}
+
+#[test]
+fn test_number_parse_matches_globals() {
+    assert_eq!(
+        eval(r#"[Number.parseFloat("3.5e"), Number.parseInt("0x1f")].join()"#),
+        JsValue::from("3.5,31")
+    );
+    assert_eq!(
+        eval(
+            r#"
+            const desc = Object.getOwnPropertyDescriptor(Number, "parseInt");
+            [Number.parseInt === parseInt, Number.parseFloat === parseFloat, desc.enumerable].join()
+        "#
+        ),
+        JsValue::from("true,true,false")
+    );
+}
@@ -1,8 +1,8 @@
 //! Object-related tests
 
-use super::eval;
-use tsrun::JsValue;
+use super::{create_test_runtime, eval, eval_result, run, throws_error};
 use tsrun::value::JsString;
+use tsrun::{JsValue, StepResult};
 
 #[test]
 fn test_object_string_numeric_keys() {
@@ -25,6 +25,52 @@ fn test_object_string_numeric_keys() {
     );
 }
 
+#[test]
+fn test_object_numeric_keys_normalize_canonically() {
+    // Only canonical numeric strings name the same property as the number
+    assert_eq!(
+        eval(
+            r#"
+            const o: any = {};
+            o["01"] = "a"; o["1e3"] = "b"; o["+5"] = "c"; o["-0"] = "d";
+            o[1e3] = "e"; o[-0] = "f"; o[1.50] = "g"; o["4294967295"] = "h";
+            JSON.stringify(Object.keys(o)) + " " + o[1] + " " + o["1000"] + " " + o["0"] + " " + o[5]
+            "#
+        ),
+        JsValue::String(JsString::from(
+            r#"["0","1000","01","1e3","+5","-0","1.5","4294967295"] undefined e f undefined"#
+        ))
+    );
+    assert_eq!(
+        eval(
+            r#"
+            const o: any = { get 1e21() { return 1; }, 0.0000001: 2, 1.50() { return 3; } };
+            Object.keys(o).join("|")
+            "#
+        ),
+        JsValue::String(JsString::from("1e+21|1e-7|1.5"))
+    );
+}
+
+#[test]
+fn test_object_leading_zero_key_distinct_from_index() {
+    assert_eq!(
+        eval(
+            r#"
+            const o: any = {};
+            o["01"] = "padded";
+            o[1] = "index";
+            const p: any = { "01": "x", 1: "y" };
+            [o["01"], o[1], o["1"], o[0o1], JSON.stringify(Object.keys(o)), JSON.stringify(o),
+             p[1], p["01"]].join(" ")
+            "#
+        ),
+        JsValue::String(JsString::from(
+            r#"padded index index index ["1","01"] {"1":"index","01":"padded"} y x"#
+        ))
+    );
+}
+
 #[test]
 fn test_object() {
     assert_eq!(
@@ -1030,17 +1076,133 @@ fn test_proto_set() {
 
 #[test]
 fn test_proto_null() {
-    // Setting __proto__ to null should work
-    // After setting __proto__ to null, accessing it returns null
+    // Setting __proto__ to null detaches the object from Object.prototype,
+    // so the inherited accessor is gone and __proto__ reads as undefined
     assert_eq!(
         eval(
             r#"
             const obj: any = {};
             obj.__proto__ = null;
-            obj.__proto__
+            [Object.getPrototypeOf(obj), obj.__proto__].join(",")
         "#
         ),
-        JsValue::Null
+        JsValue::from(",")
+    );
+}
+
+#[test]
+fn test_proto_accessor_annex_b() {
+    assert_eq!(
+        eval(
+            r#"
+            const d = Object.getOwnPropertyDescriptor(Object.prototype, "__proto__")!;
+            const obj: any = {};
+            obj.__proto__ = 5;
+            const dict: any = Object.create(null);
+            dict.__proto__ = { x: 1 };
+            [
+                typeof d.get, typeof d.set, d.enumerable, d.configurable,
+                Object.getPrototypeOf(obj) === Object.prototype,
+                (1 as any).__proto__ === Number.prototype,
+                dict.x, Object.getPrototypeOf(dict), Object.keys(dict).join(),
+                "__proto__" in {},
+            ].join(",")
+        "#
+        ),
+        JsValue::from("function,function,false,true,true,true,,,__proto__,true")
+    );
+}
+
+#[test]
+fn test_manual_three_level_prototype_chain() {
+    assert_eq!(
+        eval(
+            r#"
+            const basePlugin = {
+                describe() { return this.name() + "@" + this.version; },
+                name() { return "base"; },
+                version: 1,
+            };
+            const loggingPlugin = Object.create(basePlugin, {
+                name: { value() { return "logging"; } },
+            });
+            const plugin = Object.create(loggingPlugin, {
+                version: { value: 3, enumerable: true },
+            });
+            [
+                plugin.describe(),
+                Object.getPrototypeOf(plugin) === loggingPlugin,
+                Object.getPrototypeOf(loggingPlugin) === basePlugin,
+                basePlugin.isPrototypeOf(plugin),
+                Object.keys(plugin).join(),
+            ].join(",")
+        "#
+        ),
+        JsValue::from("logging@3,true,true,true,version")
+    );
+}
+
+#[test]
+fn test_set_prototype_of_cycle_throws() {
+    assert_eq!(
+        eval(
+            r#"
+            const a: any = {};
+            const b = Object.create(a);
+            const c = Object.create(b);
+            const errors: string[] = [];
+            for (const attempt of [
+                () => Object.setPrototypeOf(a, a),
+                () => Object.setPrototypeOf(a, c),
+                () => { a.__proto__ = c; },
+                () => Object.setPrototypeOf(Object.preventExtensions({}), a),
+            ]) {
+                try { attempt(); errors.push("none"); } catch (e) { errors.push((e as Error).message); }
+            }
+            errors.push(String(Object.getPrototypeOf(a) === Object.prototype));
+            errors.join("|")
+        "#
+        ),
+        JsValue::from(
+            "Cyclic __proto__ value|Cyclic __proto__ value|Cyclic __proto__ value|Object is not extensible|true"
+        )
+    );
+}
+
+#[test]
+fn test_prototype_primitives() {
+    assert_eq!(
+        eval(
+            r#"
+            [
+                Object.getPrototypeOf(1) === Number.prototype,
+                Object.getPrototypeOf("s") === String.prototype,
+                Object.setPrototypeOf(1, null),
+            ].join(",")
+        "#
+        ),
+        JsValue::from("true,true,1")
+    );
+}
+
+#[test]
+fn test_prototype_swap_changes_found_method() {
+    assert_eq!(
+        eval(
+            r#"
+            const A = { f() { return "A"; } };
+            const B = { f() { return "B"; } };
+            const o = Object.create(A);
+            const seen: string[] = [];
+            for (let i = 0; i < 3; i++) seen.push(o.f());
+            Object.setPrototypeOf(o, B);
+            for (let i = 0; i < 3; i++) seen.push(o.f());
+            (o as any).__proto__ = A;
+            seen.push(o.f());
+            seen.join("")
+        "#
+        ),
+        JsValue::from("AAABBBA")
     );
 }
 
@@ -1152,6 +1314,96 @@ fn test_object_entries_inner_arrays_have_methods() {
     );
 }
 
+#[test]
+fn test_object_entries_key_order() {
+    // Integer keys ascending first, then string keys in insertion order
+    assert_eq!(
+        eval(
+            r#"
+            const obj: any = { z: 1, b: 2, 10: 3, a: 4, 2: 5 };
+            Object.entries(obj).map(([k, v]) => k + "=" + v).join(",")
+        "#
+        ),
+        JsValue::String(JsString::from("2=5,10=3,z=1,b=2,a=4"))
+    );
+}
+
+#[test]
+fn test_object_values_invokes_getters_and_skips_non_enumerable() {
+    assert_eq!(
+        eval(
+            r#"
+            const obj: any = { a: 1, get b() { return this.a + 1; } };
+            Object.defineProperty(obj, "hidden", { value: 99, enumerable: false });
+            Object.values(obj).join(",")
+        "#
+        ),
+        JsValue::String(JsString::from("1,2"))
+    );
+}
+
+#[test]
+fn test_object_entries_primitive_and_null() {
+    assert_eq!(eval(r#"Object.values(42).length"#), JsValue::Number(0.0));
+    assert!(eval_result("Object.entries(null)").is_err());
+}
+
+#[test]
+fn test_object_assign_invokes_getters_and_setters() {
+    assert_eq!(
+        eval(
+            r#"
+            let log: string[] = [];
+            const src = { get x() { log.push("get"); return 42; } };
+            const target = { set x(v: number) { log.push("set " + v); } };
+            const result = Object.assign(target, src);
+            (result === target) + ":" + log.join(",")
+        "#
+        ),
+        JsValue::String(JsString::from("true:get,set 42"))
+    );
+}
+
+#[test]
+fn test_object_assign_copies_symbols() {
+    assert_eq!(
+        eval(
+            r#"
+            const sym = Symbol("s");
+            const src: any = { a: 1, [sym]: "symbol value" };
+            const out: any = Object.assign({}, src, null, undefined);
+            out.a + ":" + out[sym]
+        "#
+        ),
+        JsValue::String(JsString::from("1:symbol value"))
+    );
+}
+
+#[test]
+fn test_object_from_entries_iterables() {
+    assert_eq!(
+        eval(
+            r#"
+            const m = new Map<any, number>([["a", 1], [2, 2]]);
+            const obj: any = Object.fromEntries(m);
+            obj.a + obj["2"]
+        "#
+        ),
+        JsValue::Number(3.0)
+    );
+    assert_eq!(
+        eval(
+            r#"
+            function* pairs() { yield ["x", 10]; yield ["y", 20]; }
+            const obj: any = Object.fromEntries(pairs());
+            obj.x + obj.y
+        "#
+        ),
+        JsValue::Number(30.0)
+    );
+    assert!(eval_result("Object.fromEntries([1])").is_err());
+}
+
 // Object.is tests
 #[test]
 fn test_object_is_basic() {
@@ -1753,6 +2005,153 @@ fn test_object_literal_mixed_properties() {
     );
 }
 
+// ═══════════════════════════════════════════════════════════════════════════════
+// Own-property checks and null-prototype dictionaries
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_has_own_property_call_on_null_prototype() {
+    assert_eq!(
+        eval(
+            r#"
+            const dict = Object.create(null);
+            dict.toString = "t";
+            dict.constructor = "c";
+            const has = Object.prototype.hasOwnProperty;
+            [
+                has.call(dict, "toString"),
+                has.call(dict, "constructor"),
+                has.call(dict, "valueOf"),
+                has.apply(dict, ["constructor"]),
+                Object.hasOwn(dict, "toString"),
+            ].join(",")
+        "#
+        ),
+        JsValue::from("true,true,false,true,true")
+    );
+}
+
+#[test]
+fn test_has_own_property_exotic_receivers() {
+    assert_eq!(
+        eval(
+            r#"
+            const has = Object.prototype.hasOwnProperty;
+            const str = new String("ab");
+            [
+                [1, 2].hasOwnProperty(1),
+                [1, 2].hasOwnProperty("1"),
+                [1, 2].hasOwnProperty(2),
+                [1, 2].hasOwnProperty("length"),
+                str.hasOwnProperty(1),
+                str.hasOwnProperty(2),
+                str.hasOwnProperty("length"),
+                has.call("ab", 0),
+                has.call({ [Symbol.iterator]: 1 }, Symbol.iterator),
+            ].join(",")
+        "#
+        ),
+        JsValue::from("true,true,false,true,true,false,true,true,true")
+    );
+    assert!(throws_error(
+        "Object.prototype.hasOwnProperty.call(null, 'x')",
+        "TypeError"
+    ));
+}
+
+#[test]
+fn test_property_is_enumerable() {
+    assert_eq!(
+        eval(
+            r#"
+            const obj: any = { a: 1 };
+            Object.defineProperty(obj, "hidden", { value: 2, enumerable: false });
+            const dict = Object.create(null);
+            dict.toString = 1;
+            const proto = { inherited: 1 };
+            const child = Object.create(proto);
+            const str = new String("ab");
+            [
+                obj.propertyIsEnumerable("a"),
+                obj.propertyIsEnumerable("hidden"),
+                obj.propertyIsEnumerable("missing"),
+                child.propertyIsEnumerable("inherited"),
+                [1].propertyIsEnumerable(0),
+                [1].propertyIsEnumerable("length"),
+                str.propertyIsEnumerable(0),
+                str.propertyIsEnumerable("length"),
+                Object.prototype.propertyIsEnumerable.call(dict, "toString"),
+                Object.prototype.propertyIsEnumerable.call(dict, "valueOf"),
+                Object.prototype.propertyIsEnumerable("toString"),
+            ].join(",")
+        "#
+        ),
+        JsValue::from("true,false,false,false,true,false,true,false,true,false,false")
+    );
+}
+
+#[test]
+fn test_is_prototype_of_null_prototype() {
+    assert_eq!(
+        eval(
+            r#"
+            const dict = Object.create(null);
+            const child = Object.create(dict);
+            [
+                Object.prototype.isPrototypeOf.call(Object.prototype, dict),
+                Object.prototype.isPrototypeOf.call(dict, child),
+                Object.prototype.isPrototypeOf.call(child, dict),
+            ].join(",")
+        "#
+        ),
+        JsValue::from("false,true,false")
+    );
+    assert!(throws_error(
+        "Object.prototype.isPrototypeOf.call(undefined, {})",
+        "TypeError"
+    ));
+}
+
+#[test]
+fn test_null_prototype_dictionary_is_plain_data() {
+    // Keys that shadow Object.prototype methods are ordinary entries
+    assert_eq!(
+        eval(
+            r#"
+            const dict = Object.create(null);
+            dict.toString = "t";
+            dict.constructor = "c";
+            const keys: string[] = [];
+            for (const k in dict) keys.push(k);
+            [
+                keys.join("|"),
+                Object.keys(dict).join("|"),
+                dict.toString,
+                dict.constructor,
+                typeof dict.valueOf,
+                "toString" in dict,
+                "hasOwnProperty" in dict,
+                Object.getPrototypeOf(dict) === null,
+            ].join(",")
+        "#
+        ),
+        JsValue::from("toString|constructor,toString|constructor,t,c,undefined,true,false,true")
+    );
+}
+
+#[test]
+fn test_null_prototype_string_conversion_throws() {
+    // No inherited toString/valueOf to fall back on
+    assert!(throws_error("String(Object.create(null))", "TypeError"));
+    assert!(throws_error("`${Object.create(null)}`", "TypeError"));
+    assert!(throws_error("'' + Object.create(null)", "TypeError"));
+    // A dictionary whose toString key holds data is not callable either
+    assert!(throws_error(
+        "const d = Object.create(null); d.toString = 'x'; `${d}`",
+        "TypeError"
+    ));
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // Delete operator tests
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -2714,3 +3113,392 @@ fn test_object_groupby_coerces_key_to_string() {
         JsValue::String("string".into())
     );
 }
+
+#[test]
+fn test_object_groupby_string_field_arrays_are_real_arrays() {
+    assert_eq!(
+        eval(
+            r#"
+            const services = [
+                { name: "api", ns: "prod" },
+                { name: "db", ns: "infra" },
+                { name: "web", ns: "prod" },
+            ];
+            const grouped = Object.groupBy(services, (s) => s.ns);
+            const prod = grouped.prod!;
+            [Object.keys(grouped).join(), prod.map((s) => s.name).join(),
+             Array.isArray(prod), Object.getPrototypeOf(prod) === Array.prototype].join(" ")
+        "#
+        ),
+        JsValue::from("prod,infra api,web true true")
+    );
+}
+
+#[test]
+fn test_object_groupby_iterables() {
+    assert_eq!(
+        eval(
+            r#"
+            function* gen() { yield 1; yield 2; yield 3; yield 4; }
+            const fromGen = Object.groupBy(gen(), (n) => (n % 2 ? "odd" : "even"));
+            const fromSet = Object.groupBy(new Set(["aa", "b", "cc"]), (s) => s.length);
+            const fromString = Object.groupBy("abca", (c) => c);
+            [fromGen, fromSet, fromString]
+                .map((g) => Object.entries(g).map(([k, v]) => k + ":" + v.join("")).join(" "))
+                .join(" | ")
+        "#
+        ),
+        JsValue::from("odd:13 even:24 | 1:b 2:aacc | a:aa b:b c:c")
+    );
+}
+
+#[test]
+fn test_object_groupby_symbol_and_object_keys() {
+    assert_eq!(
+        eval(
+            r#"
+            const sym = Symbol("s");
+            const key = { toString() { return "custom"; } };
+            const grouped = Object.groupBy([1, 2, 3], (n) => (n === 1 ? sym : key));
+            [grouped[sym].length, grouped.custom.join()].join(" ")
+        "#
+        ),
+        JsValue::from("1 2,3")
+    );
+}
+
+#[test]
+fn test_object_groupby_errors() {
+    assert!(throws_error(
+        "Object.groupBy([1], 'nope' as any)",
+        "not a function"
+    ));
+    assert!(throws_error(
+        "Object.groupBy(null as any, (x) => x)",
+        "null or undefined"
+    ));
+    assert!(throws_error(
+        "Object.groupBy(42 as any, (x) => x)",
+        "not iterable"
+    ));
+}
+
+#[test]
+fn test_object_groupby_closes_iterator_on_callback_error() {
+    assert_eq!(
+        eval(
+            r#"
+            let closed = 0;
+            let pulled = 0;
+            const iterable = {
+                [Symbol.iterator]() {
+                    return {
+                        next() { pulled++; return { value: pulled, done: pulled > 10 }; },
+                        return() { closed++; return { done: true }; },
+                    };
+                },
+            };
+            let message = "";
+            try {
+                Object.groupBy(iterable as any, (n: number) => {
+                    if (n === 2) throw new Error("boom");
+                    return "k";
+                });
+            } catch (e) {
+                message = e.message;
+            }
+            [message, pulled, closed].join()
+        "#
+        ),
+        JsValue::from("boom,2,1")
+    );
+}
+
+// =============================================================================
+// Enumeration Key Cache Tests
+// =============================================================================
+
+// Objects enumerated more than a few times serve their keys from a cache, so
+// each test enumerates well past that point before and after changing keys.
+
+#[test]
+fn test_enumeration_cache_sees_added_and_deleted_keys() {
+    assert_eq!(
+        eval(
+            r#"
+            const obj: any = { a: 1, b: 2 };
+            const seen: string[] = [];
+            for (let i = 0; i < 6; i++) seen.push(Object.keys(obj).join(""));
+            obj.c = 3;
+            for (let i = 0; i < 6; i++) seen.push(Object.keys(obj).join(""));
+            delete obj.a;
+            for (let i = 0; i < 6; i++) seen.push(Object.keys(obj).join(""));
+            obj[1] = "x";
+            seen.push(Object.keys(obj).join(""));
+            [seen[5], seen[11], seen[17], seen[18]].join()
+        "#
+        ),
+        JsValue::from("ab,abc,bc,1bc")
+    );
+}
+
+#[test]
+fn test_enumeration_cache_keeps_keys_across_value_writes() {
+    assert_eq!(
+        eval(
+            r#"
+            const obj: any = { a: 1, b: 2 };
+            let out = "";
+            for (let i = 0; i < 6; i++) {
+                obj.a = i;
+                out = JSON.stringify(obj);
+            }
+            out + " " + Object.values(obj).join()
+        "#
+        ),
+        JsValue::from(r#"{"a":5,"b":2} 5,2"#)
+    );
+}
+
+#[test]
+fn test_enumeration_cache_sees_enumerability_changes() {
+    assert_eq!(
+        eval(
+            r#"
+            const obj: any = { a: 1, b: 2, c: 3 };
+            for (let i = 0; i < 6; i++) Object.keys(obj);
+            Object.defineProperty(obj, "b", { enumerable: false });
+            const hidden = Object.keys(obj).join("");
+            for (let i = 0; i < 6; i++) Object.keys(obj);
+            Object.defineProperty(obj, "b", { enumerable: true });
+            hidden + "," + Object.keys(obj).join("")
+        "#
+        ),
+        JsValue::from("ac,abc")
+    );
+}
+
+#[test]
+fn test_enumeration_cache_shared_by_for_in_spread_and_json() {
+    assert_eq!(
+        eval(
+            r#"
+            const obj: any = { x: 1, y: 2 };
+            const rounds: string[] = [];
+            for (let round = 0; round < 2; round++) {
+                let last = "";
+                for (let i = 0; i < 6; i++) {
+                    const forIn: string[] = [];
+                    for (const k in obj) forIn.push(k);
+                    const { x, ...rest } = obj;
+                    last = [
+                        forIn.join(""),
+                        Object.keys({ ...obj }).join(""),
+                        Object.keys(rest).join(""),
+                        Object.entries(obj).map(([k, v]) => k + v).join(""),
+                        JSON.stringify(obj),
+                    ].join(" ");
+                }
+                rounds.push(last);
+                delete obj.y;
+                obj.z = 3;
+            }
+            rounds.join(" | ")
+        "#
+        ),
+        JsValue::from(r#"xy xy y x1y2 {"x":1,"y":2} | xz xz z x1z3 {"x":1,"z":3}"#)
+    );
+}
+
+#[test]
+fn test_enumeration_cache_sees_freeze_and_integer_keys_order() {
+    assert_eq!(
+        eval(
+            r#"
+            const obj: any = { b: 1, 2: "two" };
+            for (let i = 0; i < 6; i++) Object.keys(obj);
+            obj[0] = "zero";
+            const keys = Object.keys(obj).join();
+            Object.freeze(obj);
+            for (let i = 0; i < 6; i++) Object.keys(obj);
+            try { obj.extra = 1; } catch {}
+            keys + " " + Object.keys(obj).join()
+        "#
+        ),
+        JsValue::from("0,2,b 0,2,b")
+    );
+}
+
+#[test]
+fn test_object_deep_freeze_cyclic_graph() {
+    assert_eq!(
+        eval(
+            r#"
+            "use strict";
+            const root: any = {
+                child: { list: [1, { leaf: true }] },
+                map: new Map([["k", { v: 1 }]]),
+                set: new Set([{ s: 1 }]),
+            };
+            root.child.parent = root;
+            root.child.list.push(root);
+            root.map.set(root, root.child);
+            const result = Object.deepFreeze(root);
+            const attempts = [
+                () => { root.x = 1; },
+                () => { root.child = null; },
+                () => { delete root.map; },
+                () => { root.child.y = 2; },
+                () => { root.child.parent.x = 1; },
+                () => { root.child.list[0] = 5; },
+                () => { root.child.list.push(3); },
+                () => { root.child.list[1].leaf = false; },
+                () => { root.map.set("k2", 1); },
+                () => { root.map.get("k").v = 2; },
+                () => { root.map.delete("k"); },
+                () => { root.map.clear(); },
+                () => { root.map.getOrInsert("k3", 1); },
+                () => { root.set.add(1); },
+                () => { root.set.delete(1); },
+                () => { root.set.clear(); },
+                () => { [...root.set][0].s = 2; },
+            ];
+            const outcomes = attempts.map(f => {
+                try { f(); return "ok"; } catch (e) { return e instanceof TypeError ? "T" : String(e); }
+            });
+            [
+                result === root,
+                Object.isFrozen(root.child.list[1]),
+                root.map.size,
+                root.map.get("k").v,
+                root.map.getOrInsert("k", 0).v,
+                outcomes.join(""),
+            ].join(",")
+        "#
+        ),
+        JsValue::from("true,true,2,1,1,TTTTTTTTTTTTTTTTT")
+    );
+}
+
+#[test]
+fn test_object_deep_freeze_skips_closures_and_prototypes() {
+    assert_eq!(
+        eval(
+            r#"
+            const captured = { n: 0 };
+            const proto = { shared: 1 };
+            const obj: any = Object.create(proto);
+            obj.read = () => captured;
+            Object.deepFreeze(obj);
+            captured.n = 1;
+            proto.shared = 2;
+            [Object.isFrozen(obj.read), Object.isFrozen(captured), Object.isFrozen(proto),
+             obj.read().n, obj.shared].join(",")
+        "#
+        ),
+        JsValue::from("true,false,false,1,2")
+    );
+    // Primitives are returned unchanged and a plain Object.freeze leaves Maps usable
+    assert_eq!(
+        eval(
+            r#"
+            const m = Object.freeze(new Map());
+            m.set(1, 2);
+            [Object.deepFreeze(5), Object.deepFreeze(null), m.get(1)].join(",")
+        "#
+        ),
+        JsValue::from("5,,2")
+    );
+    assert!(throws_error(
+        "Object.deepFreeze({ inner: new Proxy({}, {}) })",
+        "Cannot deep-freeze a Proxy"
+    ));
+}
+
+#[test]
+fn test_object_deep_freeze_deep_chain() {
+    // Iterative traversal: a 100k-node chain must not overflow the stack.
+    // Collecting rarely keeps building the chain fast.
+    let mut interp = create_test_runtime();
+    interp.set_gc_threshold(100_000);
+    let result = run(
+        &mut interp,
+        r#"
+        const head: any = {};
+        let tail = head;
+        for (let i = 0; i < 100000; i++) {
+            tail.next = { i };
+            tail = tail.next;
+        }
+        Object.deepFreeze(head);
+        let rejected = false;
+        try { tail.i = -1; } catch (e) { rejected = e instanceof TypeError; }
+        [Object.isFrozen(tail), tail.i, rejected].join(",")
+    "#,
+        None,
+    )
+    .unwrap();
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete, got {:?}", result);
+    };
+    assert_eq!(*value, JsValue::from("true,99999,true"));
+}
+
+#[test]
+fn test_own_keys_spec_order_across_apis() {
+    let result = eval(
+        r#"
+        const s1 = Symbol("s1"), s2 = Symbol("s2");
+        const obj: any = { z: 1, 2: 1, [s2]: 1, a: 1, 1: 1 };
+        Object.defineProperty(obj, "hidden", { value: 1, enumerable: false });
+        Object.defineProperty(obj, s1, { value: 1, enumerable: false });
+        obj["10"] = 1;
+        obj.b = 1;
+        [
+            Object.keys(obj).join(),
+            Object.getOwnPropertyNames(obj).join(),
+            Object.getOwnPropertySymbols(obj).map(String).join(),
+            Reflect.ownKeys(obj).map(String).join(),
+        ].join(" | ")
+    "#,
+    );
+    assert_eq!(
+        result,
+        JsValue::from(
+            "1,2,10,z,a,b | 1,2,10,z,a,hidden,b | Symbol(s2),Symbol(s1) \
+             | 1,2,10,z,a,hidden,b,Symbol(s2),Symbol(s1)"
+        )
+    );
+}
+
+#[test]
+fn test_own_keys_of_exotic_objects_and_proxies() {
+    let result = eval(
+        r#"
+        const arr: any = [1, 2];
+        arr.extra = 1;
+        function f(a: number, b: number) {}
+        const wrapper = new String("ab");
+        const sym = Symbol("k");
+        const proxy = new Proxy({}, { ownKeys: () => ["x", sym, "y"] });
+        [
+            Reflect.ownKeys(arr).join(),
+            Object.getOwnPropertyNames(arr).join(),
+            Object.getOwnPropertyNames(f).join(),
+            Reflect.ownKeys(wrapper).join(),
+            Object.getOwnPropertyNames(Object("ab")).join(),
+            Object.keys(wrapper).join(),
+            Object.getOwnPropertyNames(proxy).join(),
+            Object.getOwnPropertySymbols(proxy).map(String).join(),
+            Reflect.ownKeys(proxy).map(String).join(),
+        ].join(" | ")
+    "#,
+    );
+    assert_eq!(
+        result,
+        JsValue::from(
+            "0,1,length,extra | 0,1,length,extra | length,name,prototype \
+             | 0,1,length | 0,1,length | 0,1 | x,y | Symbol(k) | x,Symbol(k),y"
+        )
+    );
+}
@@ -7,8 +7,9 @@
 use super::{run, run_to_completion};
 use serde_json::json;
 use tsrun::{
-    InternalModule, Interpreter, InterpreterConfig, JsString, JsValue, OrderId, OrderResponse,
-    RuntimeValue, StepResult, api, create_eval_internal_module, value::PropertyKey,
+    Guarded, InternalModule, Interpreter, InterpreterConfig, JsError, JsString, JsValue,
+    OrderDelivery, OrderId, OrderResponse, PendingOrderOverflow, RuntimeValue, StepResult,
+    WellKnownSymbol, api, create_eval_internal_module, value::PropertyKey,
 };
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -557,6 +558,61 @@ fn test_sleep_basic() {
     assert_eq!(*value, JsValue::String("after".into()));
 }
 
+#[test]
+fn test_slow_thenable_settles_across_order_suspensions() {
+    let mut interp = create_test_interp();
+
+    // The thenable settles when a promise the host hands out is resolved later
+    let result = run_with_globals(
+        &mut interp,
+        r#"
+        import { order } from "tsrun:host";
+
+        const hostPromise = order({ type: "getPromise" });
+        let thenCalls = 0;
+        const slow = {
+            then(resolve: any, reject: any) {
+                thenCalls++;
+                hostPromise.then((v: string) => { resolve(v); reject("late"); });
+            },
+        };
+        const value = await new Promise((resolve) => resolve(slow));
+        value + ":" + thenCalls;
+    "#,
+    );
+
+    let StepResult::Suspended { pending, .. } = result else {
+        panic!("Expected Suspended for the order");
+    };
+    assert_eq!(pending.len(), 1);
+    let promise = api::create_promise(&mut interp);
+    interp.fulfill_orders(vec![OrderResponse {
+        id: pending[0].id,
+        result: Ok(RuntimeValue::unguarded(promise.value().clone())),
+    }]);
+
+    // The thenable's `then` has run, but its promise is still pending
+    let result = run_to_completion(&mut interp).unwrap();
+    let StepResult::Suspended { pending, .. } = result else {
+        panic!(
+            "Expected Suspended waiting for the thenable, got {:?}",
+            result
+        );
+    };
+    assert!(pending.is_empty());
+
+    api::resolve_promise(
+        &mut interp,
+        &promise,
+        RuntimeValue::unguarded(JsValue::from("slow")),
+    )
+    .unwrap();
+    let StepResult::Complete(value) = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected Complete after the host resolved its promise");
+    };
+    assert_eq!(*value, JsValue::String("slow:1".into()));
+}
+
 #[test]
 fn test_sleep_sequential() {
     let mut interp = create_test_interp();
@@ -792,6 +848,63 @@ fn test_fetch_parallel() {
     assert_eq!(*value, JsValue::String("John has 3 posts".into()));
 }
 
+/// `[Symbol.iterator]` the host installs on responses: iterates `this.items`
+fn iterate_items(
+    interp: &mut Interpreter,
+    this: JsValue,
+    _args: &[JsValue],
+) -> Result<Guarded, JsError> {
+    let items = api::get_property(&this, "items")?;
+    let iterator = interp.get_well_known(WellKnownSymbol::Iterator);
+    let method =
+        interp.get_property_by_symbol(&RuntimeValue::unguarded(items.clone()), &iterator)?;
+    interp.call_function(method.value().clone(), items, &[])
+}
+
+#[test]
+fn test_fetch_response_made_iterable_by_host() {
+    let mut interp = create_test_interp();
+
+    let result = run_with_globals(
+        &mut interp,
+        r#"
+        const page = await fetch("/users");
+        const names: string[] = [];
+        for (const user of page) names.push(user.name);
+        names.join(",") + " of " + page.total;
+    "#,
+    );
+
+    let StepResult::Suspended { pending, .. } = result else {
+        panic!("Expected Suspended for fetch");
+    };
+    let response = api::create_response_object(
+        &mut interp,
+        &json!({ "items": [{ "name": "Ann" }, { "name": "Bo" }], "total": 2 }),
+    )
+    .unwrap();
+    let guard = api::create_guard(&interp);
+    let func = interp.create_native_fn(&guard, "[Symbol.iterator]", iterate_items, 0);
+    let iterator = interp.get_well_known(WellKnownSymbol::Iterator);
+    interp
+        .set_property_by_symbol(
+            &response,
+            &iterator,
+            &RuntimeValue::unguarded(JsValue::Object(func)),
+        )
+        .unwrap();
+    assert_eq!(response.symbol_keys().len(), 1);
+
+    interp.fulfill_orders(vec![OrderResponse {
+        id: pending[0].id,
+        result: Ok(response),
+    }]);
+    let StepResult::Complete(value) = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected Complete after fulfillment");
+    };
+    assert_eq!(*value, JsValue::String("Ann,Bo of 2".into()));
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // File System Tests
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -2193,3 +2306,1871 @@ fn test_three_way_race_cancels_two_losers() {
         cancelled
     );
 }
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Eager Order Delivery Tests
+// With OrderDelivery::Eager, order() returns a Promise and step() reports new
+// orders via StepResult::Orders while the script keeps running.
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Step until a terminal result, fulfilling every order as soon as it is reported.
+/// Returns the final result and the number of suspensions seen along the way.
+#[allow(clippy::unwrap_used)]
+fn run_eager_fulfilling(
+    interp: &mut Interpreter,
+    respond: impl Fn(&mut Interpreter, &JsValue) -> RuntimeValue,
+) -> (StepResult, usize) {
+    let mut suspensions = 0;
+    loop {
+        match interp.step().unwrap() {
+            StepResult::Continue => continue,
+            StepResult::Orders(orders) => {
+                let responses = orders
+                    .iter()
+                    .map(|order| OrderResponse {
+                        id: order.id,
+                        result: Ok(respond(interp, order.payload.value())),
+                    })
+                    .collect();
+                interp.fulfill_orders(responses);
+            }
+            StepResult::Suspended { pending, .. } if !pending.is_empty() => {
+                suspensions += 1;
+                let responses = pending
+                    .iter()
+                    .map(|order| OrderResponse {
+                        id: order.id,
+                        result: Ok(respond(interp, order.payload.value())),
+                    })
+                    .collect();
+                interp.fulfill_orders(responses);
+            }
+            result => return (result, suspensions),
+        }
+    }
+}
+
+#[test]
+fn test_eager_orders_promise_all_without_suspension() {
+    let mut interp = create_test_interp();
+    interp.set_order_delivery(OrderDelivery::Eager);
+    interp
+        .prepare(
+            r#"
+            import { order } from "tsrun:host";
+            const a = order({ id: 1 });
+            const b = order({ id: 2 });
+            const c = order({ id: 3 });
+            const [x, y, z] = await Promise.all([a, b, c]);
+            x + y + z;
+        "#,
+            None,
+        )
+        .unwrap();
+
+    let mut order_batches = Vec::new();
+    let mut suspensions = 0;
+    let final_result = loop {
+        match interp.step().unwrap() {
+            StepResult::Continue => continue,
+            StepResult::Orders(orders) => {
+                order_batches.push(orders.len());
+                let responses = orders
+                    .iter()
+                    .map(|order| {
+                        let id = get_number_prop(order.payload.value(), "id").unwrap();
+                        OrderResponse {
+                            id: order.id,
+                            result: Ok(RuntimeValue::unguarded(JsValue::Number(id * 10.0))),
+                        }
+                    })
+                    .collect();
+                interp.fulfill_orders(responses);
+            }
+            StepResult::Suspended { .. } => {
+                suspensions += 1;
+                break None;
+            }
+            result => break Some(result),
+        }
+    };
+
+    // Each order is reported as soon as it is created
+    assert_eq!(order_batches, vec![1, 1, 1]);
+    assert_eq!(suspensions, 0);
+    let Some(StepResult::Complete(value)) = final_result else {
+        panic!("Expected Complete, got {:?}", final_result);
+    };
+    assert_eq!(*value, JsValue::Number(60.0));
+}
+
+#[test]
+fn test_eager_orders_through_async_functions() {
+    let mut interp = create_test_interp();
+    interp.set_order_delivery(OrderDelivery::Eager);
+
+    let script = r#"import "eval:globals";
+        const results = await Promise.all([fetch("/a"), fetch("/b"), fetch("/c")]);
+        results.map(r => r.url).join(",");
+    "#;
+    interp.prepare(script, None).unwrap();
+
+    let (result, suspensions) = run_eager_fulfilling(&mut interp, |interp, payload| {
+        let url = get_string_prop(payload, "url").unwrap();
+        api::create_response_object(interp, &json!({ "url": url })).unwrap()
+    });
+
+    assert!(suspensions <= 1, "suspensions: {}", suspensions);
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete, got {:?}", result);
+    };
+    assert_eq!(*value, JsValue::String("/a,/b,/c".into()));
+}
+
+#[test]
+fn test_eager_order_fulfilled_later_resumes_await() {
+    let mut interp = create_test_interp();
+    interp.set_order_delivery(OrderDelivery::Eager);
+    interp
+        .prepare(
+            r#"
+            import { order } from "tsrun:host";
+            const p = order({ type: "slow" });
+            "before:" + (await p);
+        "#,
+            None,
+        )
+        .unwrap();
+
+    // Order arrives while the script is still running
+    let StepResult::Orders(orders) = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected Orders");
+    };
+    assert_eq!(orders.len(), 1);
+    let order_id = orders[0].id;
+
+    // Host holds the response; script suspends on the await with nothing new pending
+    let StepResult::Suspended { pending, .. } = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected Suspended on await");
+    };
+    assert!(pending.is_empty());
+
+    interp.fulfill_orders(vec![OrderResponse {
+        id: order_id,
+        result: Ok(RuntimeValue::unguarded(JsValue::String("done".into()))),
+    }]);
+    let StepResult::Complete(value) = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected Complete after fulfillment");
+    };
+    assert_eq!(*value, JsValue::String("before:done".into()));
+}
+
+#[test]
+fn test_eager_order_error_rejects_promise() {
+    let mut interp = create_test_interp();
+    interp.set_order_delivery(OrderDelivery::Eager);
+    interp
+        .prepare(
+            r#"
+            import { order } from "tsrun:host";
+            const p = order({ type: "fail" });
+            let message = "none";
+            try {
+                await p;
+            } catch (e) {
+                message = "caught: " + e;
+            }
+            message;
+        "#,
+            None,
+        )
+        .unwrap();
+
+    let StepResult::Orders(orders) = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected Orders");
+    };
+    interp.fulfill_orders(vec![OrderResponse {
+        id: orders[0].id,
+        result: Err(tsrun::JsError::type_error("boom")),
+    }]);
+
+    let result = run_to_completion(&mut interp).unwrap();
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete, got {:?}", result);
+    };
+    let JsValue::String(s) = &*value else {
+        panic!("Expected string");
+    };
+    assert!(s.to_string().contains("boom"), "got {}", s);
+}
+
+#[test]
+fn test_order_rejection_is_catchable_error() {
+    let mut interp = create_test_interp();
+    let result = run(
+        &mut interp,
+        r#"
+        import { order } from "tsrun:host";
+        let outcome = "none";
+        try {
+            await order({ type: "query" });
+        } catch (e) {
+            outcome = [e instanceof TypeError, e instanceof Error, e.name, e.message].join("|");
+        }
+        outcome
+    "#,
+        None,
+    )
+    .unwrap();
+
+    let StepResult::Suspended { pending, .. } = result else {
+        panic!("Expected Suspended, got {:?}", result);
+    };
+    interp.fulfill_orders(vec![OrderResponse {
+        id: pending[0].id,
+        result: Err(JsError::type_error("db unavailable")),
+    }]);
+
+    let StepResult::Complete(value) = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(
+        value.value().as_str(),
+        Some("true|true|TypeError|db unavailable")
+    );
+}
+
+#[test]
+fn test_order_reject_with_value() {
+    let mut interp = create_test_interp();
+    interp.set_order_delivery(OrderDelivery::Eager);
+    interp
+        .prepare(
+            r#"
+            import { order } from "tsrun:host";
+            let outcome = "none";
+            try {
+                await order({ type: "query" });
+            } catch (e) {
+                outcome = e.code + ":" + e.reason + ":" + (e instanceof Error);
+            }
+            outcome;
+        "#,
+            None,
+        )
+        .unwrap();
+
+    let StepResult::Orders(orders) = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected Orders");
+    };
+    let reason =
+        api::create_response_object(&mut interp, &json!({ "code": 503, "reason": "busy" }))
+            .unwrap();
+    interp.fulfill_orders(vec![OrderResponse::reject_with_value(orders[0].id, reason)]);
+
+    let StepResult::Complete(value) = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(value.value().as_str(), Some("503:busy:false"));
+}
+
+#[test]
+fn test_uncaught_order_rejection_keeps_error_kind() {
+    let mut interp = create_test_interp();
+    let result = run(
+        &mut interp,
+        r#"
+        import { order } from "tsrun:host";
+        async function load() {
+            return await order({ type: "query" });
+        }
+        await load().then((rows) => rows.length);
+    "#,
+        None,
+    )
+    .unwrap();
+
+    let StepResult::Suspended { pending, .. } = result else {
+        panic!("Expected Suspended, got {:?}", result);
+    };
+    interp.fulfill_orders(vec![OrderResponse {
+        id: pending[0].id,
+        result: Err(JsError::range_error("quota exceeded")),
+    }]);
+
+    // The host receives the error object the script saw, not a string
+    match run_to_completion(&mut interp) {
+        Err(JsError::ThrownValue { guarded }) => {
+            assert_eq!(
+                get_string_prop(&guarded.value, "name").as_deref(),
+                Some("RangeError")
+            );
+            assert_eq!(
+                get_string_prop(&guarded.value, "message").as_deref(),
+                Some("quota exceeded")
+            );
+        }
+        other => panic!("Expected a thrown RangeError, got {:?}", other),
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Transitive Cancellation Tests
+// When a combinator settles, eager orders that only feed its losers are cancelled.
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Step until a terminal result, collecting eagerly delivered orders by their `n`
+/// payload field and every cancelled order ID.
+#[allow(clippy::unwrap_used)]
+fn step_collecting(
+    interp: &mut Interpreter,
+    orders: &mut Vec<(f64, OrderId)>,
+    cancelled: &mut Vec<OrderId>,
+) -> StepResult {
+    loop {
+        match interp.step().unwrap() {
+            StepResult::Continue => continue,
+            StepResult::Orders(new_orders) => {
+                for order in new_orders {
+                    let n = get_number_prop(order.payload.value(), "n").unwrap();
+                    orders.push((n, order.id));
+                }
+            }
+            StepResult::Suspended {
+                pending,
+                cancelled: newly_cancelled,
+            } => {
+                assert!(pending.is_empty(), "eager mode reports orders via Orders");
+                // Cancellations may be reported on their own right before completion
+                if !newly_cancelled.is_empty() {
+                    cancelled.extend(newly_cancelled);
+                    continue;
+                }
+                return StepResult::Suspended {
+                    pending,
+                    cancelled: Vec::new(),
+                };
+            }
+            result => return result,
+        }
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+fn order_id_for(orders: &[(f64, OrderId)], n: f64) -> OrderId {
+    orders.iter().find(|(m, _)| *m == n).unwrap().1
+}
+
+fn fulfill_number(interp: &mut Interpreter, id: OrderId, n: f64) {
+    interp.fulfill_orders(vec![OrderResponse {
+        id,
+        result: Ok(RuntimeValue::unguarded(JsValue::Number(n))),
+    }]);
+}
+
+#[test]
+fn test_race_cancels_loser_chain_of_two_orders() {
+    let mut interp = create_test_interp();
+    interp.set_order_delivery(OrderDelivery::Eager);
+    interp
+        .prepare(
+            r#"
+            import { order } from "tsrun:host";
+            const loser = Promise.all([order({ n: 1 }), order({ n: 2 })])
+                .then(([a, b]) => a + b)
+                .then(sum => sum * 2);
+            const winner = order({ n: 3 });
+            "winner:" + (await Promise.race([winner, loser]));
+        "#,
+            None,
+        )
+        .unwrap();
+
+    let mut orders = Vec::new();
+    let mut cancelled = Vec::new();
+    let result = step_collecting(&mut interp, &mut orders, &mut cancelled);
+    assert!(matches!(result, StepResult::Suspended { .. }));
+    assert_eq!(orders.len(), 3);
+    assert_eq!(interp.outstanding_orders().len(), 3);
+
+    fulfill_number(&mut interp, order_id_for(&orders, 3.0), 30.0);
+
+    let result = step_collecting(&mut interp, &mut orders, &mut cancelled);
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete, got {:?}", result);
+    };
+    assert_eq!(*value, JsValue::String("winner:30".into()));
+
+    // Both orders of the losing chain are reported as cancelled
+    assert!(
+        cancelled.contains(&order_id_for(&orders, 1.0)),
+        "cancelled: {:?}",
+        cancelled
+    );
+    assert!(
+        cancelled.contains(&order_id_for(&orders, 2.0)),
+        "cancelled: {:?}",
+        cancelled
+    );
+    assert!(!cancelled.contains(&order_id_for(&orders, 3.0)));
+    assert!(interp.outstanding_orders().is_empty());
+
+    // Late fulfillment of a cancelled order is a silent no-op
+    fulfill_number(&mut interp, order_id_for(&orders, 1.0), 10.0);
+    assert!(interp.outstanding_orders().is_empty());
+    assert!(matches!(interp.step().unwrap(), StepResult::Done));
+}
+
+#[test]
+fn test_race_cancel_stops_follow_up_orders() {
+    let mut interp = create_test_interp();
+    interp.set_order_delivery(OrderDelivery::Eager);
+    interp
+        .prepare(
+            r#"
+            import { order } from "tsrun:host";
+            const loser = order({ n: 1 }).then(() => order({ n: 2 }));
+            const winner = order({ n: 3 });
+            await Promise.race([winner, loser]);
+        "#,
+            None,
+        )
+        .unwrap();
+
+    let mut orders = Vec::new();
+    let mut cancelled = Vec::new();
+    step_collecting(&mut interp, &mut orders, &mut cancelled);
+    let ids: Vec<f64> = orders.iter().map(|(n, _)| *n).collect();
+    assert_eq!(ids, vec![1.0, 3.0]);
+    let loser_id = orders[0].1;
+    let winner_id = orders[1].1;
+
+    fulfill_number(&mut interp, winner_id, 3.0);
+    let result = step_collecting(&mut interp, &mut orders, &mut cancelled);
+    assert!(
+        matches!(result, StepResult::Complete(_)),
+        "got {:?}",
+        result
+    );
+    assert_eq!(cancelled, vec![loser_id]);
+
+    // The cancelled order's .then never runs, so no follow-up order is created
+    fulfill_number(&mut interp, loser_id, 1.0);
+    let result = step_collecting(&mut interp, &mut orders, &mut cancelled);
+    assert!(matches!(result, StepResult::Done), "got {:?}", result);
+    assert_eq!(orders.len(), 2);
+    assert!(interp.outstanding_orders().is_empty());
+}
+
+#[test]
+fn test_race_keeps_orders_still_awaited_elsewhere() {
+    let mut interp = create_test_interp();
+    interp.set_order_delivery(OrderDelivery::Eager);
+    interp
+        .prepare(
+            r#"
+            import { order } from "tsrun:host";
+            const shared = order({ n: 1 });
+            const both = Promise.all([shared.then(v => v), shared.then(v => v + 1)]);
+            const raced = Promise.race([order({ n: 2 }), shared.then(v => v * 10)]);
+            const first = await raced;
+            const [a, b] = await both;
+            first + a + b;
+        "#,
+            None,
+        )
+        .unwrap();
+
+    let mut orders = Vec::new();
+    let mut cancelled = Vec::new();
+    step_collecting(&mut interp, &mut orders, &mut cancelled);
+
+    fulfill_number(&mut interp, order_id_for(&orders, 2.0), 100.0);
+    let result = step_collecting(&mut interp, &mut orders, &mut cancelled);
+    assert!(matches!(result, StepResult::Suspended { .. }));
+    assert!(cancelled.is_empty(), "shared order still feeds Promise.all");
+    assert_eq!(
+        interp.outstanding_orders(),
+        vec![order_id_for(&orders, 1.0)]
+    );
+
+    fulfill_number(&mut interp, order_id_for(&orders, 1.0), 1.0);
+    let result = step_collecting(&mut interp, &mut orders, &mut cancelled);
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete, got {:?}", result);
+    };
+    assert_eq!(*value, JsValue::Number(103.0));
+    assert!(interp.outstanding_orders().is_empty());
+}
+
+const RACE_LOSER_WITH_TWO_ORDERS: &str = r#"
+    import { order } from "tsrun:host";
+    let release = () => {};
+    const gate = new Promise<void>((resolve) => { release = resolve; });
+    let second = "not reached";
+    async function* loser() {
+        const a = await order({ n: 1 });
+        await gate;
+        const b = await order({ n: 2 });
+        second = String(b);
+        yield a + b;
+    }
+    const winner = order({ n: 3 });
+    const first = await Promise.race([winner, loser().next().then((r) => r.value)]);
+    release();
+    "winner:" + first + ", second:" + second;
+"#;
+
+#[test]
+fn test_race_loser_resumed_after_settling_creates_cancelled_orders() {
+    let mut interp = create_test_interp();
+    interp.set_order_delivery(OrderDelivery::Eager);
+    interp.prepare(RACE_LOSER_WITH_TWO_ORDERS, None).unwrap();
+
+    let mut orders = Vec::new();
+    let mut cancelled = Vec::new();
+    step_collecting(&mut interp, &mut orders, &mut cancelled);
+
+    // The loser resumes and waits on the gate before the race settles
+    fulfill_number(&mut interp, order_id_for(&orders, 1.0), 1.0);
+    let result = step_collecting(&mut interp, &mut orders, &mut cancelled);
+    assert!(matches!(result, StepResult::Suspended { .. }));
+
+    // Releasing the gate after the race resumes a branch nothing observes:
+    // its next order is cancelled up front and resolves to undefined
+    fulfill_number(&mut interp, order_id_for(&orders, 3.0), 3.0);
+    let result = step_collecting(&mut interp, &mut orders, &mut cancelled);
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete, got {:?}", result);
+    };
+    assert_eq!(*value, JsValue::String("winner:3, second:undefined".into()));
+
+    assert_eq!(orders.len(), 2, "the second order never reaches the host");
+    assert_eq!(cancelled.len(), 1, "cancelled: {:?}", cancelled);
+    assert!(!orders.iter().any(|(_, id)| cancelled.contains(id)));
+    assert!(interp.outstanding_orders().is_empty());
+    assert_eq!(interp.pending_order_count(), 0);
+}
+
+#[test]
+fn test_race_loser_response_arriving_with_winner_cancels_next_order() {
+    let mut interp = create_test_interp();
+    interp.set_order_delivery(OrderDelivery::Eager);
+    interp
+        .prepare(
+            r#"
+            import { order } from "tsrun:host";
+            async function* loser() {
+                const a = await order({ n: 1 });
+                const b = await order({ n: 2 });
+                yield a + b;
+            }
+            const winner = order({ n: 3 });
+            "winner:" + (await Promise.race([winner, loser().next().then((r) => r.value)]));
+        "#,
+            None,
+        )
+        .unwrap();
+
+    let mut orders = Vec::new();
+    let mut cancelled = Vec::new();
+    step_collecting(&mut interp, &mut orders, &mut cancelled);
+
+    interp.fulfill_orders(vec![
+        OrderResponse {
+            id: order_id_for(&orders, 1.0),
+            result: Ok(RuntimeValue::unguarded(JsValue::Number(1.0))),
+        },
+        OrderResponse {
+            id: order_id_for(&orders, 3.0),
+            result: Ok(RuntimeValue::unguarded(JsValue::Number(3.0))),
+        },
+    ]);
+    let result = step_collecting(&mut interp, &mut orders, &mut cancelled);
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete, got {:?}", result);
+    };
+    assert_eq!(*value, JsValue::String("winner:3".into()));
+
+    // The order the loser made on resuming is only ever reported as cancelled
+    assert_eq!(orders.len(), 2);
+    assert_eq!(cancelled.len(), 1, "cancelled: {:?}", cancelled);
+    assert!(!orders.iter().any(|(_, id)| cancelled.contains(id)));
+    assert!(interp.outstanding_orders().is_empty());
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Request Helper Tests
+// request(), requestAll() and defineClient() from tsrun:host.
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Step an eager interpreter until it reports new orders
+#[allow(clippy::panic, clippy::unwrap_used)]
+fn next_orders(interp: &mut Interpreter) -> Vec<tsrun::Order> {
+    match run_to_completion(interp).unwrap() {
+        StepResult::Orders(orders) => orders,
+        other => panic!("Expected Orders, got {:?}", other),
+    }
+}
+
+#[allow(clippy::panic)]
+fn order_of_type<'a>(orders: &'a [tsrun::Order], kind: &str) -> &'a tsrun::Order {
+    orders
+        .iter()
+        .find(|order| get_string_prop(order.payload.value(), "type").as_deref() == Some(kind))
+        .unwrap_or_else(|| panic!("no {} order", kind))
+}
+
+const REQUEST_SCRIPT: &str = r#"
+    import { request, TimeoutError } from "tsrun:host";
+    let outcome: string;
+    try {
+        const user: any = await request("getUser", { id: 7 }, { timeoutMs: 250 });
+        outcome = "ok:" + user.name;
+    } catch (e) {
+        outcome = (e instanceof TimeoutError ? "timeout:" : "error:") + e.message;
+    }
+    outcome
+"#;
+
+#[test]
+fn test_request_times_out_when_host_fires_timer() {
+    let mut interp = create_test_interp();
+    interp.set_order_delivery(OrderDelivery::Eager);
+    interp.prepare(REQUEST_SCRIPT, None).unwrap();
+
+    let mut orders = next_orders(&mut interp);
+    orders.extend(next_orders(&mut interp));
+    let work = order_of_type(&orders, "getUser");
+    let timer = order_of_type(&orders, "timeout");
+    assert_eq!(get_number_prop(work.payload.value(), "id"), Some(7.0));
+    assert_eq!(
+        get_number_prop(timer.payload.value(), "orderId"),
+        Some(work.id.0 as f64)
+    );
+    assert_eq!(
+        get_number_prop(timer.payload.value(), "delayMs"),
+        Some(250.0)
+    );
+    assert!(work.deadline_ms.is_some());
+    assert_eq!(work.deadline_ms, timer.deadline_ms);
+    let (work_id, timer_id) = (work.id, timer.id);
+
+    // The host never answers getUser; the deadline passes and it fires the timer
+    interp.fulfill_orders(vec![OrderResponse {
+        id: timer_id,
+        result: Ok(RuntimeValue::unguarded(JsValue::Undefined)),
+    }]);
+    let result = run_to_completion(&mut interp).unwrap();
+    let StepResult::Suspended { pending, cancelled } = result else {
+        panic!("Expected cancellation report, got {:?}", result);
+    };
+    assert!(pending.is_empty());
+    assert!(cancelled.contains(&work_id), "cancelled: {:?}", cancelled);
+
+    let StepResult::Complete(value) = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(
+        *value,
+        JsValue::from(format!("timeout:Order {} timed out after 250ms", work_id.0).as_str())
+    );
+    assert!(interp.outstanding_orders().is_empty());
+}
+
+#[test]
+fn test_request_fulfilled_in_time_resolves() {
+    let mut interp = create_test_interp();
+    interp.set_order_delivery(OrderDelivery::Eager);
+    interp.prepare(REQUEST_SCRIPT, None).unwrap();
+
+    let mut orders = next_orders(&mut interp);
+    orders.extend(next_orders(&mut interp));
+    let work_id = order_of_type(&orders, "getUser").id;
+    let timer_id = order_of_type(&orders, "timeout").id;
+
+    let user = api::create_response_object(&mut interp, &json!({ "name": "ada" })).unwrap();
+    interp.fulfill_orders(vec![OrderResponse {
+        id: work_id,
+        result: Ok(user),
+    }]);
+
+    let mut cancelled = Vec::new();
+    let value = loop {
+        match run_to_completion(&mut interp).unwrap() {
+            StepResult::Suspended {
+                pending,
+                cancelled: newly_cancelled,
+            } => {
+                assert!(pending.is_empty());
+                cancelled.extend(newly_cancelled);
+            }
+            StepResult::Complete(value) => break value,
+            other => panic!("Expected Complete, got {:?}", other),
+        }
+    };
+    assert_eq!(*value, JsValue::from("ok:ada"));
+    assert!(cancelled.contains(&timer_id), "cancelled: {:?}", cancelled);
+    assert!(interp.outstanding_orders().is_empty());
+}
+
+#[test]
+fn test_request_without_timeout_batched() {
+    let mut interp = create_test_interp();
+    let result = run(
+        &mut interp,
+        r#"
+        import { request, requestAll, defineClient } from "tsrun:host";
+        const one: any = await request("echo", { n: 1 });
+        const all: any[] = await requestAll([
+            { type: "echo", params: { n: 2 } },
+            { type: "echo", params: { n: 3 } },
+        ]);
+        const api = defineClient({ echo: "echo", slow: { type: "echo", timeoutMs: 1000 } });
+        const four: any = await api.echo({ n: 4 });
+        [one.type, one.n, all.map((r: any) => r.n).join("+"), four.n, typeof api.slow].join(",")
+    "#,
+        None,
+    )
+    .unwrap();
+
+    // Batched delivery: every order suspends and the host answers with its payload
+    let mut result = result;
+    let mut deadlines = Vec::new();
+    let value = loop {
+        match result {
+            StepResult::Suspended { pending, .. } if !pending.is_empty() => {
+                let responses = pending
+                    .iter()
+                    .map(|order| {
+                        deadlines.push(order.deadline_ms);
+                        let echo = json!({
+                            "type": get_string_prop(order.payload.value(), "type"),
+                            "n": get_number_prop(order.payload.value(), "n"),
+                        });
+                        OrderResponse {
+                            id: order.id,
+                            result: Ok(api::create_response_object(&mut interp, &echo).unwrap()),
+                        }
+                    })
+                    .collect();
+                interp.fulfill_orders(responses);
+                result = run_to_completion(&mut interp).unwrap();
+            }
+            StepResult::Complete(value) => break value,
+            other => panic!("Expected Complete, got {:?}", other),
+        }
+    };
+    assert_eq!(*value, JsValue::from("echo,1,2+3,4,function"));
+    assert_eq!(deadlines, vec![None; 4]);
+}
+
+const BATCH_SCRIPT: &str = r#"
+    import { request, batch } from "tsrun:host";
+    async function reqA() { return request("a"); }
+    async function reqB() {
+        await Promise.resolve();
+        // A thenable settles in a job of its own, after the await suspends
+        await { then(resolve: (v: number) => void) { resolve(0); } };
+        return request("b");
+    }
+    async function reqC() { return request("c"); }
+    const results: any[] = await batch(() => Promise.all([reqA(), reqB(), reqC()]));
+    results.map((r: any) => r.type).join(",")
+"#;
+
+/// Run BATCH_SCRIPT, answering each order with its payload; returns the
+/// `(type, batch_seq)` of the orders of every non-empty Suspended batch
+#[allow(clippy::panic, clippy::unwrap_used)]
+fn run_batch_script() -> Vec<Vec<(String, u32)>> {
+    let mut interp = create_test_interp();
+    let mut result = run(&mut interp, BATCH_SCRIPT, None).unwrap();
+    let mut batches = Vec::new();
+    let value = loop {
+        match result {
+            StepResult::Suspended { pending, .. } if !pending.is_empty() => {
+                batches.push(
+                    pending
+                        .iter()
+                        .map(|order| {
+                            let kind = get_string_prop(order.payload.value(), "type").unwrap();
+                            (kind, order.batch_seq)
+                        })
+                        .collect(),
+                );
+                let responses = pending
+                    .iter()
+                    .map(|order| OrderResponse {
+                        id: order.id,
+                        result: Ok(RuntimeValue::unguarded(order.payload.value().clone())),
+                    })
+                    .collect();
+                interp.fulfill_orders(responses);
+                result = run_to_completion(&mut interp).unwrap();
+            }
+            StepResult::Complete(value) => break value,
+            other => panic!("Expected Complete, got {:?}", other),
+        }
+    };
+    assert_eq!(*value, JsValue::from("a,b,c"));
+    assert!(interp.outstanding_orders().is_empty());
+    batches
+}
+
+#[test]
+fn test_batch_delivers_promise_all_orders_in_one_suspension() {
+    // reqC runs while reqB waits on its awaits, so its order comes first
+    assert_eq!(
+        run_batch_script(),
+        vec![vec![
+            ("a".to_string(), 0),
+            ("c".to_string(), 1),
+            ("b".to_string(), 2),
+        ]]
+    );
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Async generators awaiting orders
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_async_generator_queued_next_across_suspensions() {
+    for delivery in [OrderDelivery::Batched, OrderDelivery::Eager] {
+        let mut interp = create_test_interp();
+        interp.set_order_delivery(delivery);
+        let mut result = run(
+            &mut interp,
+            r#"
+            import { order } from "tsrun:host";
+            const log: string[] = [];
+            async function* readings() {
+                try {
+                    const a: any = await order({ n: 1 });
+                    yield a.v * 10;
+                    const b: any = await order({ n: 2 });
+                    yield b.v * 10;
+                } finally {
+                    log.push("closed");
+                }
+            }
+            const gen = readings();
+            // Both requests are issued before the body has produced anything
+            const first = gen.next();
+            const second = gen.next();
+            const r1 = await first;
+            const r2 = await second;
+            const r3 = await gen.next();
+            [r1.value, r1.done, r2.value, r2.done, r3.done, log.join()].join(",")
+        "#,
+            None,
+        )
+        .unwrap();
+
+        // Each order arrives in its own cycle: the second next() waits its turn
+        let mut batches = Vec::new();
+        let value = loop {
+            match result {
+                StepResult::Suspended { pending, .. } | StepResult::Orders(pending)
+                    if !pending.is_empty() =>
+                {
+                    let mut batch = Vec::new();
+                    let responses = pending
+                        .iter()
+                        .map(|order| {
+                            let n = get_number_prop(order.payload.value(), "n").unwrap();
+                            batch.push(n);
+                            let response = json!({ "v": n });
+                            OrderResponse {
+                                id: order.id,
+                                result: Ok(
+                                    api::create_response_object(&mut interp, &response).unwrap()
+                                ),
+                            }
+                        })
+                        .collect();
+                    batches.push(batch);
+                    interp.fulfill_orders(responses);
+                    result = run_to_completion(&mut interp).unwrap();
+                }
+                StepResult::Complete(value) => break value,
+                other => panic!("Expected Complete, got {:?}", other),
+            }
+        };
+        assert_eq!(*value, JsValue::from("10,false,20,false,true,closed"));
+        assert_eq!(batches, vec![vec![1.0], vec![2.0]], "{:?}", delivery);
+        assert!(interp.outstanding_orders().is_empty());
+    }
+}
+
+#[test]
+fn test_async_generator_rejected_order_rejects_request() {
+    let mut interp = create_test_interp();
+    let result = run(
+        &mut interp,
+        r#"
+        import { order } from "tsrun:host";
+        async function* source() {
+            yield await order({ n: 1 });
+        }
+        const gen = source();
+        let outcome: string;
+        try {
+            await gen.next();
+            outcome = "resolved";
+        } catch (e) {
+            outcome = "rejected:" + e;
+        }
+        const after = await gen.next();
+        outcome + "," + after.done
+    "#,
+        None,
+    )
+    .unwrap();
+
+    let StepResult::Suspended { pending, .. } = result else {
+        panic!("Expected Suspended, got {:?}", result);
+    };
+    assert_eq!(pending.len(), 1);
+    interp.fulfill_orders(vec![OrderResponse {
+        id: pending[0].id,
+        result: Err(tsrun::JsError::type_error("offline")),
+    }]);
+
+    match run_to_completion(&mut interp).unwrap() {
+        StepResult::Complete(value) => {
+            let text = value.value().as_str().unwrap_or_default().to_string();
+            assert!(text.starts_with("rejected:"), "{}", text);
+            assert!(text.contains("offline"), "{}", text);
+            assert!(text.ends_with(",true"), "{}", text);
+        }
+        other => panic!("Expected Complete, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_order_origin_per_module() {
+    let config = InterpreterConfig {
+        internal_modules: vec![
+            create_eval_internal_module(),
+            InternalModule::source(
+                "eval:users",
+                r#"import { order } from "tsrun:host";
+export function loadUser(id: number): any {
+    return order({ type: "user", id });
+}
+"#,
+            ),
+            InternalModule::source(
+                "eval:billing",
+                r#"import { request } from "tsrun:host";
+
+export async function charge(amount: number): Promise<any> {
+    return await request("charge", { amount });
+}
+"#,
+            ),
+        ],
+        ..Default::default()
+    };
+    let mut interp = Interpreter::with_config(config);
+
+    let result = run(
+        &mut interp,
+        r#"import { loadUser } from "eval:users";
+import { charge } from "eval:billing";
+const user = loadUser(1);
+await charge(5);
+"#,
+        Some("/app/main.ts"),
+    )
+    .unwrap();
+
+    let StepResult::Suspended { pending, .. } = result else {
+        panic!("Expected Suspended, got {:?}", result);
+    };
+    assert_eq!(pending.len(), 1);
+    let origin = &pending[0].origin;
+    assert_eq!(
+        origin.module.as_ref().map(|m| m.as_str()),
+        Some("eval:users")
+    );
+    assert_eq!(origin.function_name.as_deref(), Some("loadUser"));
+    assert_eq!((origin.line, origin.column), (3, 12));
+    interp.fulfill_orders(vec![OrderResponse {
+        id: pending[0].id,
+        result: Ok(RuntimeValue::unguarded(JsValue::Null)),
+    }]);
+
+    // request() is attributed to its caller, not to tsrun:host
+    let StepResult::Suspended { pending, .. } = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected Suspended for charge");
+    };
+    assert_eq!(pending.len(), 1);
+    let origin = &pending[0].origin;
+    assert_eq!(
+        origin.module.as_ref().map(|m| m.as_str()),
+        Some("eval:billing")
+    );
+    assert_eq!(origin.function_name.as_deref(), Some("charge"));
+    assert_eq!((origin.line, origin.column), (4, 18));
+    interp.fulfill_orders(vec![OrderResponse {
+        id: pending[0].id,
+        result: Ok(RuntimeValue::unguarded(JsValue::Null)),
+    }]);
+    assert!(matches!(
+        run_to_completion(&mut interp).unwrap(),
+        StepResult::Complete(_)
+    ));
+}
+
+#[test]
+fn test_order_origin_entry_top_level() {
+    let mut interp = create_test_interp();
+    let result = run(
+        &mut interp,
+        r#"import { order } from "tsrun:host";
+order({ type: "ping" });
+"#,
+        Some("/app/main.ts"),
+    )
+    .unwrap();
+
+    let StepResult::Suspended { pending, .. } = result else {
+        panic!("Expected Suspended, got {:?}", result);
+    };
+    let origin = &pending[0].origin;
+    assert_eq!(
+        origin.module.as_ref().map(|m| m.as_str()),
+        Some("/app/main.ts")
+    );
+    assert_eq!(origin.function_name, None);
+    assert_eq!(origin.line, 2);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Pending Order Limit Tests
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// An eager-mode interpreter allowing `max` outstanding orders
+fn create_limited_interp(max: usize, overflow: PendingOrderOverflow) -> Interpreter {
+    let config = InterpreterConfig {
+        internal_modules: vec![create_eval_internal_module()],
+        max_pending_orders: max,
+        pending_order_overflow: overflow,
+        ..Default::default()
+    };
+    let mut interp = Interpreter::with_config(config);
+    interp.set_order_delivery(OrderDelivery::Eager);
+    interp
+}
+
+/// Step until the script completes or suspends, collecting the orders handed out
+#[allow(clippy::unwrap_used)]
+fn step_collecting_orders(interp: &mut Interpreter, orders: &mut Vec<OrderId>) -> StepResult {
+    loop {
+        match interp.step().unwrap() {
+            StepResult::Continue => {}
+            StepResult::Orders(batch) => orders.extend(batch.iter().map(|o| o.id)),
+            StepResult::Suspended { pending, cancelled } => {
+                orders.extend(pending.iter().map(|o| o.id));
+                return StepResult::Suspended { pending, cancelled };
+            }
+            result => return result,
+        }
+    }
+}
+
+fn fulfill_all(interp: &mut Interpreter, orders: &mut Vec<OrderId>) {
+    let responses = orders
+        .drain(..)
+        .map(|id| OrderResponse {
+            id,
+            result: Ok(RuntimeValue::unguarded(JsValue::Number(id.0 as f64))),
+        })
+        .collect();
+    interp.fulfill_orders(responses);
+}
+
+#[test]
+fn test_unbounded_orders_throw_range_error() {
+    let mut interp = create_limited_interp(5, PendingOrderOverflow::Throw);
+    interp
+        .prepare(
+            r#"
+            import { order } from "tsrun:host";
+            const counts: string[] = [];
+            for (let round = 0; round < 2; round++) {
+                const fired: Promise<unknown>[] = [];
+                try {
+                    while (true) {
+                        fired.push(order({ type: "spam" }));
+                    }
+                } catch (e) {
+                    counts.push((e instanceof RangeError) + ":" + e.message + ":" + fired.length);
+                }
+                await Promise.all(fired);
+            }
+            counts.join(",");
+        "#,
+            None,
+        )
+        .unwrap();
+
+    let mut orders = Vec::new();
+    let result = step_collecting_orders(&mut interp, &mut orders);
+    assert!(matches!(result, StepResult::Suspended { .. }));
+    assert_eq!(orders.len(), 5);
+    assert_eq!(interp.pending_order_count(), 5);
+
+    // The limit counts outstanding orders, so draining lets the script fire again
+    fulfill_all(&mut interp, &mut orders);
+    assert_eq!(interp.pending_order_count(), 0);
+    let result = step_collecting_orders(&mut interp, &mut orders);
+    assert!(matches!(result, StepResult::Suspended { .. }));
+    assert_eq!(orders.len(), 5);
+
+    fulfill_all(&mut interp, &mut orders);
+    let StepResult::Complete(value) = step_collecting_orders(&mut interp, &mut orders) else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(
+        value.value().as_str(),
+        Some(
+            "true:too many pending host requests:5,\
+             true:too many pending host requests:5"
+        )
+    );
+}
+
+#[test]
+fn test_unbounded_orders_suspend_until_drained() {
+    let mut interp = create_limited_interp(3, PendingOrderOverflow::Suspend);
+    interp
+        .prepare(
+            r#"
+            import { order } from "tsrun:host";
+            const fired: Promise<unknown>[] = [];
+            while (fired.length < 7) {
+                fired.push(order({ type: "spam" }));
+            }
+            (await Promise.all(fired)).length;
+        "#,
+            None,
+        )
+        .unwrap();
+
+    let mut orders = Vec::new();
+    for _ in 0..2 {
+        let result = step_collecting_orders(&mut interp, &mut orders);
+        assert!(matches!(result, StepResult::Suspended { .. }));
+        assert_eq!(orders.len(), 3);
+        assert_eq!(interp.pending_order_count(), 3);
+
+        // Held back without progress until the host drains the batch
+        assert!(matches!(
+            interp.step().unwrap(),
+            StepResult::Suspended { .. }
+        ));
+        fulfill_all(&mut interp, &mut orders);
+    }
+
+    // The last order leaves the script under the limit, awaiting the batch
+    let result = step_collecting_orders(&mut interp, &mut orders);
+    assert!(matches!(result, StepResult::Suspended { .. }));
+    assert_eq!(orders.len(), 1);
+    fulfill_all(&mut interp, &mut orders);
+
+    let StepResult::Complete(value) = step_collecting_orders(&mut interp, &mut orders) else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(value.value().as_number(), Some(7.0));
+}
+
+#[test]
+fn test_order_batch_under_limit_unaffected() {
+    for overflow in [PendingOrderOverflow::Throw, PendingOrderOverflow::Suspend] {
+        let mut interp = create_limited_interp(20, overflow);
+        interp
+            .prepare(
+                r#"
+                import { order } from "tsrun:host";
+                const fired: Promise<number>[] = [];
+                for (let i = 0; i < 19; i++) {
+                    fired.push(order({ type: "item", i }) as Promise<number>);
+                }
+                const results = await Promise.all(fired);
+                results.length;
+            "#,
+                None,
+            )
+            .unwrap();
+
+        let mut orders = Vec::new();
+        let result = step_collecting_orders(&mut interp, &mut orders);
+        assert!(matches!(result, StepResult::Suspended { .. }));
+        assert_eq!(orders.len(), 19);
+        fulfill_all(&mut interp, &mut orders);
+
+        let StepResult::Complete(value) = step_collecting_orders(&mut interp, &mut orders) else {
+            panic!("Expected Complete");
+        };
+        assert_eq!(value.value().as_number(), Some(19.0));
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Stall Detection Tests
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+#[allow(clippy::panic, clippy::unwrap_used)]
+fn test_fulfilling_wrong_order_id_reports_stall() {
+    let mut interp = create_test_interp();
+    let result = run(
+        &mut interp,
+        r#"
+        import { order } from "tsrun:host";
+        const user: any = await order({ type: "getUser" });
+        user.name
+    "#,
+        None,
+    )
+    .unwrap();
+    let StepResult::Suspended { pending, .. } = result else {
+        panic!("Expected Suspended, got {:?}", result);
+    };
+    let real_id = pending[0].id;
+    let wrong_id = OrderId(real_id.0 + 100);
+
+    interp.fulfill_orders(vec![OrderResponse {
+        id: wrong_id,
+        result: Ok(RuntimeValue::unguarded(JsValue::Undefined)),
+    }]);
+    // The first idle suspension is tolerated, the second is a stall
+    let result = run_to_completion(&mut interp);
+    assert!(
+        matches!(&result, Ok(StepResult::Suspended { pending, .. }) if pending.is_empty()),
+        "{:?}",
+        result
+    );
+    let Err(JsError::Stalled { report }) = run_to_completion(&mut interp) else {
+        panic!("Expected Stalled");
+    };
+    assert_eq!(report.outstanding, vec![real_id]);
+    assert_eq!(report.blocked_on_order, Some(real_id));
+    assert_eq!(report.last_fulfilled, vec![wrong_id]);
+    assert_eq!(
+        JsError::Stalled { report }.to_string(),
+        format!(
+            "Execution stalled: outstanding orders [{}], blocked on order {}, 0 awaited promise(s), last fulfilled [{}]",
+            real_id.0, real_id.0, wrong_id.0
+        )
+    );
+
+    // The right id gets the script going again
+    let user = api::create_response_object(&mut interp, &json!({ "name": "ada" })).unwrap();
+    interp.fulfill_orders(vec![OrderResponse {
+        id: real_id,
+        result: Ok(user),
+    }]);
+    let StepResult::Complete(value) = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(*value, JsValue::from("ada"));
+}
+
+#[test]
+#[allow(clippy::panic, clippy::unwrap_used)]
+fn test_stall_detection_reports_awaited_promise_and_can_be_disabled() {
+    for threshold in [2, 0] {
+        let mut interp = Interpreter::with_config(InterpreterConfig {
+            stall_threshold: threshold,
+            ..Default::default()
+        });
+        let promise = api::create_promise(&mut interp);
+        let global = JsValue::Object(interp.global.clone());
+        api::set_property(&global, "hostPromise", promise.value().clone()).unwrap();
+        let result = run(&mut interp, "await hostPromise;", None).unwrap();
+        assert!(
+            matches!(result, StepResult::Suspended { .. }),
+            "{:?}",
+            result
+        );
+
+        let mut outcomes = Vec::new();
+        for _ in 0..3 {
+            outcomes.push(match run_to_completion(&mut interp) {
+                Ok(StepResult::Suspended { .. }) => "suspended".to_string(),
+                Err(JsError::Stalled { report }) => format!("stalled:{}", report.awaited.len()),
+                other => panic!("Unexpected {:?}", other),
+            });
+        }
+        let expected = if threshold == 0 {
+            ["suspended", "suspended", "suspended"]
+        } else {
+            ["suspended", "stalled:1", "suspended"]
+        };
+        assert_eq!(outcomes, expected);
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Order Record/Replay Tests
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Two dependent orders: the second payload uses the first response
+fn replay_script(sku: &str) -> String {
+    format!(
+        r#"
+        import {{ order }} from "tsrun:host";
+        const price: any = await order({{ type: "price.get", sku: "{sku}" }});
+        const stock: any = await order({{ type: "stock.get", sku: "{sku}", min: price.amount }});
+        ({{ total: price.amount * stock.count, warehouse: stock.warehouse }})
+    "#
+    )
+}
+
+#[test]
+#[allow(clippy::panic, clippy::unwrap_used)]
+fn test_recorded_orders_replay_without_host() {
+    let mut interp = create_test_interp();
+    interp.set_order_recording(true);
+    let mut result = run(&mut interp, &replay_script("A-1"), None).unwrap();
+    let final_value = loop {
+        match result {
+            StepResult::Suspended { pending, .. } => {
+                let responses = pending
+                    .iter()
+                    .map(|order| {
+                        let kind = get_string_prop(order.payload.value(), "type").unwrap();
+                        let reply = if kind == "price.get" {
+                            json!({ "amount": 7 })
+                        } else {
+                            json!({ "count": 6, "warehouse": "north" })
+                        };
+                        OrderResponse {
+                            id: order.id,
+                            result: Ok(api::create_response_object(&mut interp, &reply).unwrap()),
+                        }
+                    })
+                    .collect();
+                interp.fulfill_orders(responses);
+                result = run_to_completion(&mut interp).unwrap();
+            }
+            StepResult::Complete(value) => break interp.to_canonical_json(&value).unwrap(),
+            other => panic!("Unexpected {:?}", other),
+        }
+    };
+    assert_eq!(final_value, r#"{"total":42,"warehouse":"north"}"#);
+
+    let log = interp.take_order_log();
+    assert_eq!(log.len(), 2);
+    assert_eq!(
+        log[1].payload_json,
+        json!({ "type": "stock.get", "sku": "A-1", "min": 7 })
+    );
+    assert_eq!(
+        log.iter()
+            .map(|entry| entry.batch_index)
+            .collect::<Vec<_>>(),
+        vec![0, 1]
+    );
+    // The log survives a round trip through JSON
+    let saved = serde_json::to_string(&log).unwrap();
+    let log: Vec<tsrun::OrderLogEntry> = serde_json::from_str(&saved).unwrap();
+
+    // A fresh interpreter with no host handling finishes on its own
+    let mut replay = create_test_interp();
+    replay.set_order_replay(log);
+    let StepResult::Complete(value) = run(&mut replay, &replay_script("A-1"), None).unwrap() else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(replay.to_canonical_json(&value).unwrap(), final_value);
+}
+
+#[test]
+#[allow(clippy::panic, clippy::unwrap_used)]
+fn test_recorded_payload_with_lone_surrogate() {
+    let mut interp = create_test_interp();
+    interp.set_order_recording(true);
+    let script = r#"
+        import { order } from "tsrun:host";
+        const name = "x" + String.fromCharCode(0xD83D) + "\uD83D\uDCA9";
+        await order({ name });
+    "#;
+    let StepResult::Suspended { pending, .. } = run(&mut interp, script, None).unwrap() else {
+        panic!("Expected Suspended");
+    };
+    assert_eq!(pending.len(), 1);
+    assert_eq!(
+        get_string_prop(pending[0].payload.value(), "name").unwrap(),
+        "x\u{FFFD}💩"
+    );
+    let log = interp.take_order_log();
+    assert_eq!(log[0].payload_json, json!({ "name": "x\u{FFFD}💩" }));
+}
+
+#[test]
+#[allow(clippy::panic, clippy::unwrap_used)]
+fn test_replay_mismatch_names_differing_field() {
+    let log = vec![tsrun::OrderLogEntry {
+        id: OrderId(1),
+        payload_json: json!({ "type": "price.get", "sku": "A-1" }),
+        response: Some(tsrun::OrderLogResponse::Value(json!({ "amount": 7 }))),
+        batch_index: 0,
+    }];
+
+    let mut interp = create_test_interp();
+    interp.set_order_replay(log.clone());
+    let err = run(&mut interp, &replay_script("B-2"), None).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        r#"Order replay mismatch at log entry 0: payload.sku: logged "A-1", got "B-2""#
+    );
+
+    // Past the end of the log
+    let mut interp = create_test_interp();
+    interp.set_order_replay(log);
+    let Err(JsError::ReplayMismatch { position, diff }) =
+        run(&mut interp, &replay_script("A-1"), None)
+    else {
+        panic!("Expected ReplayMismatch");
+    };
+    assert_eq!(position, 1);
+    assert!(diff.starts_with("the log ends here"), "{}", diff);
+}
+
+#[test]
+#[allow(clippy::panic, clippy::unwrap_used)]
+fn test_replayed_errors_reach_script() {
+    let log = vec![
+        tsrun::OrderLogEntry {
+            id: OrderId(5),
+            payload_json: json!({ "step": 1 }),
+            response: Some(tsrun::OrderLogResponse::Error {
+                name: "TypeError".to_string(),
+                message: "bad input".to_string(),
+            }),
+            batch_index: 0,
+        },
+        tsrun::OrderLogEntry {
+            id: OrderId(6),
+            payload_json: json!({ "step": 2 }),
+            response: Some(tsrun::OrderLogResponse::Rejected(json!({ "code": 404 }))),
+            batch_index: 1,
+        },
+    ];
+    let mut interp = create_test_interp();
+    interp.set_order_delivery(OrderDelivery::Eager);
+    interp.set_order_replay(log);
+    let result = run(
+        &mut interp,
+        r#"
+        import { order } from "tsrun:host";
+        const seen: string[] = [];
+        try { await order({ step: 1 }); } catch (e) { seen.push(e.name + ": " + e.message); }
+        try { await order({ step: 2 }); } catch (e) { seen.push("code " + e.code); }
+        seen.join(" | ")
+    "#,
+        None,
+    )
+    .unwrap();
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete, got {:?}", result);
+    };
+    assert_eq!(*value, JsValue::from("TypeError: bad input | code 404"));
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Orders started while an object is under construction
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Fulfill every order in `result` with `{ value: <type> }`, collecting
+/// garbage before handing the responses over
+#[allow(clippy::unwrap_used)]
+fn fulfill_after_collect(interp: &mut Interpreter, result: StepResult) -> StepResult {
+    let mut result = result;
+    loop {
+        let StepResult::Suspended { pending, .. } = result else {
+            return result;
+        };
+        interp.collect();
+        let responses = pending
+            .iter()
+            .map(|order| {
+                let kind = get_string_prop(order.payload.value(), "type").unwrap_or_default();
+                OrderResponse {
+                    id: order.id,
+                    result: Ok(
+                        api::create_response_object(interp, &json!({ "value": kind })).unwrap(),
+                    ),
+                }
+            })
+            .collect();
+        interp.fulfill_orders(responses);
+        interp.collect();
+        result = run_to_completion(interp).unwrap();
+    }
+}
+
+#[test]
+#[allow(clippy::panic, clippy::unwrap_used)]
+fn test_order_started_in_constructor_keeps_instance_intact() {
+    let mut interp = create_test_interp();
+    let result = run(
+        &mut interp,
+        r#"
+        import { order } from "tsrun:host";
+        async function init(kind: string) {
+            const reply: any = await order({ type: kind });
+            return reply.value;
+        }
+        class Base {
+            tag = "base";
+            pending = init("base");
+        }
+        class Widget extends Base {
+            label = { text: "w" };
+            ready: Promise<string>;
+            constructor(public name: string) {
+                super();
+                this.ready = init(name);
+            }
+            describe() { return this.name + "/" + this.label.text; }
+        }
+        const widget = new Widget("ctor");
+        const fromField = new (class {
+            loaded = init("field-init");
+            extra = [1, 2, 3];
+        })();
+        [
+            widget.describe(), await widget.ready, widget instanceof Widget,
+            widget.tag, await widget.pending, await fromField.loaded, fromField.extra.length,
+        ].join("|")
+    "#,
+        None,
+    )
+    .unwrap();
+
+    let StepResult::Complete(value) = fulfill_after_collect(&mut interp, result) else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(
+        value.value().as_str(),
+        Some("ctor/w|ctor|true|base|base|field-init|3")
+    );
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Responses carrying host functions
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Fulfill the single pending order of `result` with `value`
+#[allow(clippy::panic, clippy::indexing_slicing)]
+fn fulfill_only(interp: &mut Interpreter, result: StepResult, value: RuntimeValue) -> StepResult {
+    let StepResult::Suspended { pending, .. } = result else {
+        panic!("Expected Suspended, got {:?}", result);
+    };
+    assert_eq!(pending.len(), 1);
+    let id = pending[0].id;
+    interp.fulfill_orders(vec![OrderResponse {
+        id,
+        result: Ok(value),
+    }]);
+    #[allow(clippy::unwrap_used)]
+    run_to_completion(interp).unwrap()
+}
+
+/// A resource handle for an "open" order: `read(n)` answers directly,
+/// `fetch(path)` asks the host with another order
+#[allow(clippy::unwrap_used)]
+fn resource_handle(interp: &mut Interpreter) -> RuntimeValue {
+    let handle = api::create_response_object(interp, &json!({ "kind": "file" })).unwrap();
+    let read = interp.create_function_value("read", 1, |_, this, args| {
+        let kind = get_string_prop(&this, "kind").unwrap_or_default();
+        let n = args.first().and_then(JsValue::as_number).unwrap_or(0.0);
+        Ok(Guarded::unguarded(JsValue::from(format!("{kind}:{n}"))))
+    });
+    let fetch = interp.create_function_value("fetch", 1, |interp, _, args| {
+        let path = args.first().cloned().unwrap_or(JsValue::Undefined);
+        let payload = api::create_response_object(interp, &json!({ "type": "fetch" }))?;
+        api::set_property(payload.value(), "path", path)?;
+        let promise = interp.issue_order(payload.value().clone())?;
+        Ok(Guarded::unguarded(promise.value().clone()))
+    });
+    api::set_property(handle.value(), "read", read.value().clone()).unwrap();
+    api::set_property(handle.value(), "fetch", fetch.value().clone()).unwrap();
+    handle
+}
+
+#[test]
+#[allow(clippy::panic, clippy::unwrap_used)]
+fn test_order_response_with_host_method_returning_value() {
+    let mut interp = create_test_interp();
+    let result = run(
+        &mut interp,
+        r#"
+        import { order } from "tsrun:host";
+        const handle: any = await order({ type: "open" });
+        [typeof handle.read, handle.read.name, handle.read.length, handle.read(3)].join()
+    "#,
+        None,
+    )
+    .unwrap();
+    let handle = resource_handle(&mut interp);
+    interp.collect();
+
+    let StepResult::Complete(value) = fulfill_only(&mut interp, result, handle) else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(value.value().as_str(), Some("function,read,1,file:3"));
+}
+
+#[test]
+#[allow(clippy::panic, clippy::unwrap_used, clippy::indexing_slicing)]
+fn test_order_response_with_host_method_issuing_order() {
+    let mut interp = create_test_interp();
+    let result = run(
+        &mut interp,
+        r#"
+        import { order } from "tsrun:host";
+        const handle: any = await order({ type: "open" });
+        const body = await handle.fetch("/etc/hosts");
+        "got " + body
+    "#,
+        None,
+    )
+    .unwrap();
+    let handle = resource_handle(&mut interp);
+    let result = fulfill_only(&mut interp, result, handle);
+
+    let StepResult::Suspended { pending, .. } = &result else {
+        panic!("Expected the fetch order, got {:?}", result);
+    };
+    assert_eq!(pending.len(), 1);
+    let payload = pending[0].payload.value();
+    assert_eq!(get_string_prop(payload, "type").as_deref(), Some("fetch"));
+    assert_eq!(
+        get_string_prop(payload, "path").as_deref(),
+        Some("/etc/hosts")
+    );
+    interp.collect();
+
+    let body = RuntimeValue::unguarded(JsValue::from("127.0.0.1"));
+    let StepResult::Complete(value) = fulfill_only(&mut interp, result, body) else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(value.value().as_str(), Some("got 127.0.0.1"));
+}
+
+#[test]
+fn test_host_function_closure_dropped_with_interpreter() {
+    let captured = std::rc::Rc::new(());
+    let mut interp = create_test_interp();
+    let held = std::rc::Rc::clone(&captured);
+    let function = interp.create_function_value("noop", 0, move |_, _, _| {
+        let _ = &held;
+        Ok(Guarded::unguarded(JsValue::Undefined))
+    });
+    assert_eq!(std::rc::Rc::strong_count(&captured), 2);
+    drop(function);
+    drop(interp);
+    assert_eq!(std::rc::Rc::strong_count(&captured), 1);
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_object_id_matches_across_payloads() {
+    let mut interp = create_test_interp();
+    let mut result = run(
+        &mut interp,
+        r#"
+        import { order } from "tsrun:host";
+        const shared = { retries: 3 };
+        await order({ doc: "a", config: shared });
+        await order({ doc: "b", config: shared });
+        "#,
+        Some("/main.ts"),
+    )
+    .unwrap();
+
+    let mut payloads = Vec::new();
+    while let StepResult::Suspended { pending, .. } = result {
+        let responses = pending
+            .iter()
+            .map(|order| OrderResponse {
+                id: order.id,
+                result: Ok(RuntimeValue::unguarded(JsValue::Undefined)),
+            })
+            .collect();
+        payloads.extend(pending.into_iter().map(|order| order.payload));
+        interp.fulfill_orders(responses);
+        result = run_to_completion(&mut interp).unwrap();
+    }
+    let StepResult::Complete(_) = result else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(payloads.len(), 2);
+
+    // Both payloads stay alive, and with them the shared object
+    let configs: Vec<RuntimeValue> = payloads
+        .iter()
+        .map(|payload| {
+            RuntimeValue::unguarded(api::get_property(payload.value(), "config").unwrap())
+        })
+        .collect();
+    let config_id = configs[0].object_id().unwrap();
+    assert_eq!(configs[1].object_id(), Some(config_id));
+    assert!(interp.values_same(&configs[0], &configs[1]));
+
+    assert_ne!(payloads[0].object_id(), payloads[1].object_id());
+    assert_ne!(payloads[0].object_id(), Some(config_id));
+    assert!(!interp.values_same(&payloads[0], &payloads[1]));
+
+    let nan = RuntimeValue::unguarded(JsValue::Number(f64::NAN));
+    assert_eq!(nan.object_id(), None);
+    assert!(interp.values_same(&nan, &nan));
+    assert!(!interp.values_same(
+        &RuntimeValue::unguarded(JsValue::Number(0.0)),
+        &RuntimeValue::unguarded(JsValue::Number(-0.0))
+    ));
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Partial Fulfillment Tests
+// An async function awaiting an order doesn't hold up its caller, so the host
+// can answer some orders and step() runs whatever those answers unblock.
+// ═══════════════════════════════════════════════════════════════════════════════
+
+type ConsoleLines = std::rc::Rc<std::cell::RefCell<Vec<String>>>;
+
+struct RecordingConsole(ConsoleLines);
+
+impl tsrun::platform::ConsoleProvider for RecordingConsole {
+    fn write(&self, _level: tsrun::platform::ConsoleLevel, message: &str) {
+        self.0.borrow_mut().push(message.to_string());
+    }
+}
+
+/// A test interpreter whose console output is recorded
+fn create_recording_interp() -> (Interpreter, ConsoleLines) {
+    let lines = ConsoleLines::default();
+    let mut interp = create_test_interp();
+    interp.set_console(Box::new(RecordingConsole(lines.clone())));
+    (interp, lines)
+}
+
+/// Answer order `id` with `value`
+fn answer(interp: &mut Interpreter, id: OrderId, value: &str) {
+    interp.fulfill_orders(vec![OrderResponse {
+        id,
+        result: Ok(RuntimeValue::unguarded(JsValue::from(value))),
+    }]);
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic, clippy::indexing_slicing)]
+fn test_partial_fulfillment_resumes_independent_await() {
+    let (mut interp, lines) = create_recording_interp();
+    let result = run(
+        &mut interp,
+        r#"
+        import { order } from "tsrun:host";
+        async function fetchFast() {
+            const value = await order({ name: "fast" });
+            console.log("fast done: " + value);
+            return value;
+        }
+        async function fetchSlow() {
+            const value = await order({ name: "slow" });
+            console.log("slow done: " + value);
+            return value;
+        }
+        const results = await Promise.all([fetchFast(), fetchSlow()]);
+        results.join(",")
+        "#,
+        Some("/main.ts"),
+    )
+    .unwrap();
+    let StepResult::Suspended { pending, .. } = result else {
+        panic!("Expected Suspended, got {:?}", result);
+    };
+    let names: Vec<_> = pending
+        .iter()
+        .map(|order| get_string_prop(order.payload.value(), "name").unwrap())
+        .collect();
+    assert_eq!(names, ["fast", "slow"]);
+    let (fast, slow) = (pending[0].id, pending[1].id);
+
+    // Only the fast order is answered: its continuation runs, the slow one waits
+    answer(&mut interp, fast, "a");
+    let result = run_to_completion(&mut interp).unwrap();
+    let StepResult::Suspended { pending, .. } = result else {
+        panic!("Expected Suspended, got {:?}", result);
+    };
+    assert!(pending.is_empty());
+    assert_eq!(*lines.borrow(), ["fast done: a"]);
+    assert_eq!(interp.outstanding_orders(), [slow]);
+
+    answer(&mut interp, slow, "b");
+    let StepResult::Complete(value) = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(*value, JsValue::from("a,b"));
+    assert_eq!(*lines.borrow(), ["fast done: a", "slow done: b"]);
+    assert!(interp.outstanding_orders().is_empty());
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic, clippy::indexing_slicing)]
+fn test_awaiting_async_call_returns_to_caller() {
+    let (mut interp, lines) = create_recording_interp();
+    let result = run(
+        &mut interp,
+        r#"
+        import { order } from "tsrun:host";
+        async function load(name: string) {
+            const value = await order({ name });
+            console.log(name + " loaded");
+            return value;
+        }
+        const first = load("first");
+        console.log("after first call");
+        const second = Promise.resolve().then(async () => {
+            const value = await load("second");
+            console.log("second continuation");
+            return value;
+        });
+        console.log("after second call");
+        (await first) + (await second)
+        "#,
+        Some("/main.ts"),
+    )
+    .unwrap();
+    let StepResult::Suspended { pending, .. } = result else {
+        panic!("Expected Suspended, got {:?}", result);
+    };
+    assert_eq!(pending.len(), 2);
+    assert_eq!(*lines.borrow(), ["after first call", "after second call"]);
+
+    // Answer in reverse: the callback's await finishes while `first` waits
+    answer(&mut interp, pending[1].id, "2");
+    let result = run_to_completion(&mut interp).unwrap();
+    assert!(
+        matches!(result, StepResult::Suspended { .. }),
+        "{:?}",
+        result
+    );
+    assert_eq!(
+        lines.borrow()[2..],
+        ["second loaded", "second continuation"]
+    );
+
+    answer(&mut interp, pending[0].id, "1");
+    let StepResult::Complete(value) = run_to_completion(&mut interp).unwrap() else {
+        panic!("Expected Complete");
+    };
+    assert_eq!(*value, JsValue::from("12"));
+    assert_eq!(lines.borrow()[4..], ["first loaded"]);
+}
@@ -0,0 +1,84 @@
+//! Tests for Interpreter::pending_promises
+
+use super::run;
+use tsrun::{CreationSite, Interpreter, InterpreterConfig, PendingPromiseInfo, StepResult};
+
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn run_main(source: &str, track_provenance: bool) -> Interpreter {
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        track_provenance,
+        ..Default::default()
+    });
+    match run(&mut interp, source, Some("/main.ts")).unwrap() {
+        StepResult::Complete(_) => interp,
+        other => panic!("Expected completion, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_unawaited_async_call_leaves_pending_promise() {
+    let interp = run_main(
+        r#"async function load(): Promise<string> {
+    return new Promise<string>(() => {});
+}
+load();
+"done"
+"#,
+        true,
+    );
+    assert_eq!(interp.pending_promise_count(), 1);
+    assert_eq!(
+        interp.pending_promises(),
+        vec![PendingPromiseInfo {
+            created_at: Some(CreationSite {
+                file: Some("/main.ts".to_string()),
+                line: 2,
+                column: 16,
+                returned_by: None,
+            }),
+            has_reactions: false,
+        }]
+    );
+}
+
+#[test]
+fn test_fully_awaited_script_has_no_pending_promises() {
+    let interp = run_main(
+        r#"async function load(): Promise<string> {
+    return new Promise<string>((resolve) => resolve("loaded"));
+}
+const first = await load();
+const second = await load().then((v) => v + "!");
+first + second
+"#,
+        true,
+    );
+    assert_eq!(interp.pending_promise_count(), 0);
+    assert_eq!(interp.pending_promises(), vec![]);
+}
+
+#[test]
+fn test_pending_promise_without_provenance_has_no_site() {
+    let interp = run_main(
+        r#"let settle: (v: number) => void = () => {};
+const p = new Promise<number>((resolve) => { settle = resolve; });
+p.then((v) => v * 2);
+"done"
+"#,
+        false,
+    );
+    // The promise created by `then` is pending too
+    assert_eq!(
+        interp.pending_promises(),
+        vec![
+            PendingPromiseInfo {
+                created_at: None,
+                has_reactions: true,
+            },
+            PendingPromiseInfo {
+                created_at: None,
+                has_reactions: false,
+            },
+        ]
+    );
+}
@@ -0,0 +1,155 @@
+//! performance.now(), mark() and measure() tests
+
+use super::{create_test_runtime, eval, run, throws_error};
+use std::cell::Cell;
+use std::rc::Rc;
+use tsrun::platform::TimeProvider;
+use tsrun::{Interpreter, JsValue, PerfEntry, PerfEntryType, StepResult};
+
+/// Time provider whose clock only moves when the test advances it
+struct ManualClock(Rc<Cell<f64>>);
+
+impl TimeProvider for ManualClock {
+    fn now_millis(&self) -> i64 {
+        0
+    }
+
+    fn elapsed_millis(&self, start: u64) -> u64 {
+        (self.0.get() as u64).saturating_sub(start)
+    }
+
+    fn start_timer(&self) -> u64 {
+        self.0.get() as u64
+    }
+
+    fn elapsed_precise_millis(&self, start: u64) -> f64 {
+        self.0.get() - start as f64
+    }
+}
+
+fn manual_clock_runtime() -> (Interpreter, Rc<Cell<f64>>) {
+    let clock = Rc::new(Cell::new(1000.0));
+    let mut interp = create_test_runtime();
+    interp.set_time_provider(Box::new(ManualClock(clock.clone())));
+    (interp, clock)
+}
+
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn run_value(interp: &mut Interpreter, source: &str) -> JsValue {
+    match run(interp, source, None).unwrap() {
+        StepResult::Complete(value) => (*value).clone(),
+        other => panic!("Expected Complete, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_performance_now_is_relative_to_origin() {
+    let (mut interp, clock) = manual_clock_runtime();
+    assert_eq!(
+        run_value(&mut interp, "performance.now()"),
+        JsValue::Number(0.0)
+    );
+    clock.set(1012.25);
+    assert_eq!(
+        run_value(&mut interp, "performance.now()"),
+        JsValue::Number(12.25)
+    );
+}
+
+#[test]
+fn test_performance_now_never_goes_backwards() {
+    let (mut interp, clock) = manual_clock_runtime();
+    clock.set(1005.0);
+    run_value(&mut interp, "globalThis.first = performance.now()");
+    clock.set(1001.0);
+    assert_eq!(
+        run_value(&mut interp, "performance.now() >= first"),
+        JsValue::Boolean(true)
+    );
+}
+
+#[test]
+fn test_performance_mark_and_measure() {
+    let (mut interp, clock) = manual_clock_runtime();
+    clock.set(1002.0);
+    run_value(&mut interp, "performance.mark('render-start')");
+    clock.set(1009.5);
+    let measure = run_value(
+        &mut interp,
+        r#"
+        const m = performance.measure("render", "render-start");
+        [m.name, m.entryType, m.startTime, m.duration].join()
+        "#,
+    );
+    assert_eq!(measure, JsValue::from("render,measure,2,7.5"));
+
+    assert_eq!(
+        interp.performance_entries(),
+        vec![
+            PerfEntry {
+                name: "render-start".into(),
+                entry_type: PerfEntryType::Mark,
+                start: 2.0,
+                duration: 0.0,
+            },
+            PerfEntry {
+                name: "render".into(),
+                entry_type: PerfEntryType::Measure,
+                start: 2.0,
+                duration: 7.5,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_performance_measure_between_marks_and_options() {
+    let (mut interp, clock) = manual_clock_runtime();
+    clock.set(1001.0);
+    run_value(&mut interp, "performance.mark('a')");
+    clock.set(1004.0);
+    run_value(&mut interp, "performance.mark('b')");
+    clock.set(1010.0);
+    assert_eq!(
+        run_value(
+            &mut interp,
+            r#"
+            [
+                performance.measure("ab", "a", "b").duration,
+                performance.measure("opts", { start: "a", end: 9 }).duration,
+                performance.measure("all").duration,
+            ].join()
+            "#
+        ),
+        JsValue::from("3,8,10")
+    );
+}
+
+#[test]
+fn test_performance_get_and_clear_entries() {
+    assert_eq!(
+        eval(
+            r#"
+            performance.mark("x", { startTime: 5 });
+            performance.mark("y", { startTime: 1 });
+            performance.measure("xy", "y", "x");
+            const byType = performance.getEntriesByType("mark").map(e => e.name).join();
+            const byName = performance.getEntriesByName("xy").map(e => e.duration).join();
+            const all = performance.getEntries().length;
+            performance.clearMarks("x");
+            const afterClearX = performance.getEntriesByType("mark").map(e => e.name).join();
+            performance.clearMeasures();
+            [byType, byName, all, afterClearX, performance.getEntries().length].join("|")
+            "#
+        ),
+        JsValue::from("y,x|4|3|y|1")
+    );
+}
+
+#[test]
+fn test_performance_measure_unknown_mark() {
+    assert!(throws_error(
+        "performance.measure('m', 'missing')",
+        "The mark 'missing' does not exist"
+    ));
+}
@@ -0,0 +1,202 @@
+//! Tests for Interpreter::export_prepared and Interpreter::import_prepared
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::{run, run_to_completion};
+use tsrun::platform::{ConsoleLevel, ConsoleProvider};
+use tsrun::{CheckpointError, Interpreter, ModulePath, StepResult};
+
+type ConsoleLines = Rc<RefCell<Vec<(ConsoleLevel, String)>>>;
+
+struct RecordingConsole(ConsoleLines);
+
+impl ConsoleProvider for RecordingConsole {
+    fn write(&self, level: ConsoleLevel, message: &str) {
+        self.0.borrow_mut().push((level, message.to_string()));
+    }
+}
+
+fn recording_interpreter() -> (Interpreter, ConsoleLines) {
+    let lines = Rc::new(RefCell::new(Vec::new()));
+    let mut interp = Interpreter::new();
+    interp.set_console(Box::new(RecordingConsole(lines.clone())));
+    (interp, lines)
+}
+
+/// Run `interp` to completion and return the result as JSON with the console output
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn finish(interp: &mut Interpreter, lines: &ConsoleLines) -> (serde_json::Value, Vec<String>) {
+    let value = match run_to_completion(interp).unwrap() {
+        StepResult::Complete(value) => tsrun::js_value_to_json(value.value()).unwrap(),
+        other => panic!("Expected completion, got {:?}", other),
+    };
+    let output = lines
+        .borrow()
+        .iter()
+        .map(|(level, message)| format!("{:?} {}", level, message))
+        .collect();
+    (value, output)
+}
+
+const PROGRAM: &str = r#"
+class Counter {
+    #count = 0;
+    constructor(public readonly label: string) {}
+    bump(by = 1) { this.#count += by; return this; }
+    get count() { return this.#count; }
+}
+function* ids(prefix: string) { let i = 0; while (true) yield `${prefix}-${i++}`; }
+function tag(strings: TemplateStringsArray, ...values: unknown[]) {
+    return strings.raw.join("|") + ":" + values.length;
+}
+function kind(s: string) {
+    switch (s) { case "a": return "first"; case "b": return "second"; default: return "other"; }
+}
+async function load(n: number) { await null; return n * 2; }
+
+const counter = new Counter("hits").bump().bump(4);
+const gen = ids("job");
+const { label, ...rest } = { label: "x", keep: 1, also: 2 };
+const matches = "a1 b22 c333".match(/[a-z](\d+)/g);
+console.log("counter", counter.label, counter.count);
+console.warn([gen.next().value, gen.next().value].join(","));
+console.log(tag`one${1}two\n${2}`, kind("b"), kind("z"), Object.keys(rest).join());
+const doubled = await load(21);
+let stack = "";
+try { null!.toString(); } catch (e) { stack = String((e as Error).message); }
+({
+    count: counter.count,
+    matches,
+    doubled,
+    label,
+    source: kind.toString().slice(0, 17),
+    stack,
+})
+"#;
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_imported_run_matches_prepared_run() {
+    let (mut original, original_lines) = recording_interpreter();
+    original
+        .prepare(PROGRAM, Some(ModulePath::new("/main.ts")))
+        .unwrap();
+    let expected = finish(&mut original, &original_lines);
+
+    let mut exporter = Interpreter::new();
+    exporter
+        .prepare(PROGRAM, Some(ModulePath::new("/main.ts")))
+        .unwrap();
+    let bytes = exporter.export_prepared().unwrap();
+    drop(exporter);
+
+    let (mut imported, imported_lines) = recording_interpreter();
+    assert!(matches!(
+        imported.import_prepared(&bytes).unwrap(),
+        StepResult::Continue
+    ));
+    let actual = finish(&mut imported, &imported_lines);
+
+    assert_eq!(actual, expected);
+    assert_eq!(expected.0["count"], 5);
+    assert_eq!(expected.0["source"], "function kind(s: ");
+    assert_eq!(expected.1.len(), 3);
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_imported_run_keeps_script_this_and_module_map() {
+    let mut exporter = Interpreter::new();
+    exporter.set_module_source_map([(
+        "/lib/greet.ts",
+        "export const greet = (n: string) => `hi ${n}`;",
+    )]);
+    exporter
+        .prepare("var seen = this === globalThis; seen", None)
+        .unwrap();
+    let bytes = exporter.export_prepared().unwrap();
+
+    let (mut imported, lines) = recording_interpreter();
+    imported.import_prepared(&bytes).unwrap();
+    let (value, _) = finish(&mut imported, &lines);
+    assert_eq!(value, serde_json::json!(true));
+
+    // Later runs of the importing interpreter are served from the module map
+    match run(
+        &mut imported,
+        "import { greet } from './lib/greet'; greet('again')",
+        Some("/main.ts"),
+    )
+    .unwrap()
+    {
+        StepResult::Complete(value) => assert_eq!(value, tsrun::JsValue::from("hi again")),
+        other => panic!("Expected completion, got {:?}", other),
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_export_prepared_requires_unstarted_run_without_imports() {
+    let mut interp = Interpreter::new();
+    match interp.export_prepared() {
+        Err(CheckpointError::InvalidState(_)) => {}
+        other => panic!("Expected InvalidState before prepare, got {:?}", other),
+    }
+
+    interp.prepare("let x = 1; x + 1", None).unwrap();
+    interp.step().unwrap();
+    match interp.export_prepared() {
+        Err(CheckpointError::InvalidState(reason)) => {
+            assert_eq!(reason, "the prepared run has already started")
+        }
+        other => panic!("Expected InvalidState after step, got {:?}", other),
+    }
+
+    let mut interp = Interpreter::new();
+    interp
+        .prepare(
+            "import { x } from './dep'; x",
+            Some(ModulePath::new("/main.ts")),
+        )
+        .unwrap();
+    match interp.export_prepared() {
+        Err(CheckpointError::InvalidState(reason)) => assert!(reason.contains("imports")),
+        other => panic!("Expected InvalidState with imports, got {:?}", other),
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_import_prepared_refuses_other_builds() {
+    let mut exporter = Interpreter::new();
+    exporter.prepare("1 + 1", None).unwrap();
+    let bytes = exporter.export_prepared().unwrap();
+
+    // Magic, format version, then the crate version and the instruction set fingerprint
+    let version_len = usize::from(bytes[5]);
+    let mut other_version = bytes.clone();
+    other_version[6] = b'9';
+    let mut other_ops = bytes.clone();
+    other_ops[6 + version_len] ^= 0xff;
+    let mut other_format = bytes.clone();
+    other_format[4] = 2;
+
+    for tampered in [other_version, other_ops, other_format] {
+        let mut interp = Interpreter::new();
+        match interp.import_prepared(&tampered) {
+            Err(CheckpointError::IncompatibleRuntime(_)) => {}
+            other => panic!("Expected IncompatibleRuntime, got {:?}", other),
+        }
+    }
+
+    let mut interp = Interpreter::new();
+    match interp.import_prepared(&bytes[..bytes.len() - 1]) {
+        Err(CheckpointError::Corrupt(_)) => {}
+        other => panic!("Expected Corrupt for truncated bytes, got {:?}", other),
+    }
+    assert!(matches!(
+        run(&mut interp, "40 + 2", None).unwrap(),
+        StepResult::Complete(value) if value == tsrun::JsValue::from(42)
+    ));
+}
@@ -0,0 +1,129 @@
+//! Tests for probes sampled at suspensions
+
+use super::run_to_completion;
+use serde_json::json;
+use tsrun::{
+    Interpreter, InterpreterConfig, ModulePath, OrderResponse, StepResult, api,
+    create_eval_internal_module,
+};
+
+const COUNTER: &str = r#"
+import { order } from "tsrun:host";
+
+let count = 0;
+const queue: number[] = [];
+for (let i = 0; i < 3; i++) {
+    count++;
+    queue.push(i);
+    await order({ i });
+}
+count
+"#;
+
+fn create_interp() -> Interpreter {
+    Interpreter::with_config(InterpreterConfig {
+        internal_modules: vec![create_eval_internal_module()],
+        ..Default::default()
+    })
+}
+
+/// Step `interp` to completion, answering each order with an empty object
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn answer_orders(interp: &mut Interpreter) -> usize {
+    let mut suspensions = 0;
+    loop {
+        match run_to_completion(interp).unwrap() {
+            StepResult::Suspended { pending, .. } => {
+                suspensions += 1;
+                let responses = pending
+                    .iter()
+                    .map(|order| OrderResponse {
+                        id: order.id,
+                        result: api::create_response_object(interp, &json!({})),
+                    })
+                    .collect();
+                interp.fulfill_orders(responses);
+            }
+            StepResult::Complete(_) => return suspensions,
+            other => panic!("Unexpected step result {:?}", other),
+        }
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_probe_samples_top_level_counter_at_each_suspension() {
+    let mut interp = create_interp();
+    let count = interp.add_probe("count", "count").unwrap();
+    let queue = interp.add_probe("queue", "queue.length * 10").unwrap();
+    interp
+        .prepare(COUNTER, Some(ModulePath::new("/main.ts")))
+        .unwrap();
+    assert_eq!(answer_orders(&mut interp), 3);
+
+    let samples = interp.take_probe_samples();
+    let values = |id| {
+        samples
+            .iter()
+            .filter(|sample| sample.probe == id)
+            .map(|sample| sample.value_json.clone().unwrap())
+            .collect::<Vec<_>>()
+    };
+    // Three suspensions, then completion
+    assert_eq!(values(count), [json!(1), json!(2), json!(3), json!(3)]);
+    assert_eq!(values(queue), [json!(10), json!(20), json!(30), json!(30)]);
+    assert!(samples.iter().all(|sample| match sample.probe {
+        id if id == count => sample.name == "count",
+        _ => sample.name == "queue",
+    }));
+    assert!(
+        samples
+            .windows(2)
+            .all(|pair| pair[0].step_index <= pair[1].step_index)
+    );
+    assert!(interp.take_probe_samples().is_empty());
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_probe_error_is_recorded_without_failing_the_run() {
+    let mut interp = create_interp();
+    interp.add_probe("missing", "config.limit").unwrap();
+    interp
+        .prepare(COUNTER, Some(ModulePath::new("/main.ts")))
+        .unwrap();
+    assert_eq!(answer_orders(&mut interp), 3);
+
+    let samples = interp.take_probe_samples();
+    assert_eq!(samples.len(), 4);
+    let err = samples[0].value_json.clone().unwrap_err();
+    assert!(err.contains("config"), "{}", err);
+}
+
+#[test]
+fn test_probe_rejects_expressions_that_write() {
+    let mut interp = create_interp();
+    for expression in [
+        "queue.push(1)",
+        "count++",
+        "count = 1",
+        "new Map()",
+        "delete globalThis.count",
+        "(() => 1)",
+        "[...queue]",
+    ] {
+        let err = interp.add_probe("bad", expression).unwrap_err();
+        assert!(
+            err.to_string().contains("Probe expressions can only read"),
+            "{}: {}",
+            expression,
+            err
+        );
+    }
+    assert!(interp.add_probe("bad", "let x = 1").is_err());
+    assert!(
+        interp
+            .add_probe("ok", "count > 1 ? queue[0] : `${count}`")
+            .is_ok()
+    );
+}
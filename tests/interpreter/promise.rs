@@ -597,3 +597,106 @@ fn test_promise_any_first_success() {
     );
     assert_eq!(result, JsValue::Number(42.0));
 }
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Thenable adoption
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_thenable_calling_resolve_twice_settles_once() {
+    let result = eval(
+        r#"
+        let fulfilled = 0;
+        const p = new Promise((resolve) => {
+            resolve({ then(res: any, rej: any) { res(1); res(2); rej(3); } });
+        });
+        p.then(() => { fulfilled++; }, () => { fulfilled += 100; });
+        const value = await p;
+        [value, fulfilled].join()
+    "#,
+    );
+    assert_eq!(result, JsValue::from("1,1"));
+}
+
+#[test]
+fn test_thenable_throwing_after_resolve_is_ignored() {
+    let result = eval(
+        r#"
+        const p = Promise.resolve().then(() => ({
+            then(res: any) { res("ok"); throw new Error("late"); },
+        }));
+        await p
+    "#,
+    );
+    assert_eq!(result, JsValue::from("ok"));
+}
+
+#[test]
+fn test_thenable_then_getter_and_call_errors_reject() {
+    let result = eval(
+        r#"
+        const reasons: string[] = [];
+        const badGetter = { get then(): any { throw new RangeError("getter"); } };
+        const badThen = { then() { throw "thrown"; } };
+        for (const value of [badGetter, badThen]) {
+            try {
+                await new Promise((resolve) => resolve(value));
+            } catch (e) {
+                reasons.push(e instanceof RangeError ? "RangeError " + e.message : String(e));
+            }
+        }
+        reasons.join()
+    "#,
+    );
+    assert_eq!(result, JsValue::from("RangeError getter,thrown"));
+}
+
+#[test]
+fn test_thenable_adoption_runs_as_a_job() {
+    let result = eval(
+        r#"
+        const log: string[] = [];
+        const p = new Promise((resolve) => {
+            resolve({ then(res: any) { log.push("then"); res("done"); } });
+            log.push("resolved");
+        });
+        log.push("sync");
+        log.push(await p);
+        log.join()
+    "#,
+    );
+    assert_eq!(result, JsValue::from("resolved,sync,then,done"));
+}
+
+#[test]
+fn test_chained_custom_thenables_settle_outer_promise() {
+    let result = eval(
+        r#"
+        function wrap(value: any) {
+            return { then(res: any) { res(value); } };
+        }
+        const direct = await wrap(wrap(wrap("inner")));
+        const chained = await Promise.resolve(1).then(() => wrap(wrap(Promise.resolve(2))));
+        direct + ":" + chained
+    "#,
+    );
+    assert_eq!(result, JsValue::from("inner:2"));
+}
+
+#[test]
+fn test_promise_resolved_with_itself_rejects_with_type_error() {
+    let result = eval(
+        r#"
+        let resolve: any;
+        const p = new Promise((r) => { resolve = r; });
+        resolve(p);
+        let caught = "";
+        try { await p; } catch (e) { caught = (e instanceof TypeError) + " " + e.message; }
+        caught
+    "#,
+    );
+    assert_eq!(
+        result,
+        JsValue::from("true Chaining cycle detected for promise #<Promise>")
+    );
+}
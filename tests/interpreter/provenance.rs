@@ -0,0 +1,118 @@
+//! Tests for Interpreter::explain and Interpreter::provenance
+
+use super::run;
+use tsrun::{CreationSite, Interpreter, InterpreterConfig, RuntimeValue, StepResult};
+
+const LIB: &str = r#"export function ports(extra: number) {
+    return [80, extra];
+}
+export function container(name: string) {
+    const c = { name, ports: ports(8080) };
+    return c;
+}
+"#;
+
+const MAIN: &str = r#"import { container } from "./lib";
+const manifest = {
+    kind: "Deployment",
+    spec: { containers: [container("web")] },
+};
+manifest
+"#;
+
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn run_manifest(track_provenance: bool) -> (Interpreter, RuntimeValue) {
+    let mut interp = Interpreter::with_config(InterpreterConfig {
+        track_provenance,
+        ..Default::default()
+    });
+    interp.set_module_source_map([("/lib.ts", LIB)]);
+    match run(&mut interp, MAIN, Some("/main.ts")).unwrap() {
+        StepResult::Complete(value) => (interp, value),
+        other => panic!("Expected completion, got {:?}", other),
+    }
+}
+
+fn site(file: &str, line: u32, column: u32, returned_by: Option<&str>) -> Option<CreationSite> {
+    Some(CreationSite {
+        file: Some(file.to_string()),
+        line,
+        column,
+        returned_by: returned_by.map(str::to_string),
+    })
+}
+
+#[test]
+fn test_explain_shows_sites_across_modules() {
+    let (interp, value) = run_manifest(true);
+    assert_eq!(
+        interp.explain(&value, 8),
+        r#"{  // created at /main.ts:2:18
+  kind: "Deployment",
+  spec: {  // created at /main.ts:4:11
+    containers: [  // created at /main.ts:4:25
+      {  // created at /lib.ts:5:15, returned by container
+        name: "web",
+        ports: [  // created at /lib.ts:2:12, returned by ports
+          80,
+          8080,
+        ],
+      },
+    ],
+  },
+}"#
+    );
+    assert_eq!(
+        interp.explain(&value, 1),
+        r#"{  // created at /main.ts:2:18
+  kind: "Deployment",
+  spec: [Object],  // created at /main.ts:4:11
+}"#
+    );
+
+    let entries: Vec<_> = interp
+        .provenance(&value, 8)
+        .into_iter()
+        .map(|entry| (entry.path, entry.site))
+        .collect();
+    assert_eq!(
+        entries,
+        vec![
+            (String::new(), site("/main.ts", 2, 18, None)),
+            ("spec".to_string(), site("/main.ts", 4, 11, None)),
+            ("spec.containers".to_string(), site("/main.ts", 4, 25, None)),
+            (
+                "spec.containers[0]".to_string(),
+                site("/lib.ts", 5, 15, Some("container"))
+            ),
+            (
+                "spec.containers[0].ports".to_string(),
+                site("/lib.ts", 2, 12, Some("ports"))
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_explain_without_tracking() {
+    let (interp, value) = run_manifest(false);
+    assert!(
+        interp
+            .provenance(&value, 8)
+            .iter()
+            .all(|entry| entry.site.is_none())
+    );
+    assert_eq!(
+        interp.explain(&value, 2),
+        r#"{
+  kind: "Deployment",
+  spec: {
+    containers: [Array],
+  },
+}"#
+    );
+}
+
+// Tracking lives in a side table: objects are the same size either way
+#[cfg(target_pointer_width = "64")]
+const _: () = assert!(core::mem::size_of::<tsrun::value::JsObject>() == 272);
@@ -0,0 +1,198 @@
+//! Tests for side-effect detection with `Interpreter::eval_pure`
+
+use super::run;
+use tsrun::{
+    InternalModule, Interpreter, InterpreterConfig, JsValue, ModulePath, PurityMode,
+    PurityViolation, PurityViolationKind, StepResult, create_eval_internal_module,
+};
+
+fn create_purity_interp() -> Interpreter {
+    let config = InterpreterConfig {
+        internal_modules: vec![
+            create_eval_internal_module(),
+            InternalModule::source(
+                "app:config",
+                r#"
+                export const limits = { rps: 100, regions: ["eu", "us"] };
+                export const registry = new Map<string, number>();
+                export class Rule {}
+                "#,
+            ),
+        ],
+        ..Default::default()
+    };
+    let interp = Interpreter::with_config(config);
+
+    // Set aggressive GC for testing
+    let gc_threshold = std::env::var("GC_THRESHOLD")
+        .ok()
+        .and_then(|s| s.parse::<usize>().ok())
+        .unwrap_or(1);
+    interp.set_gc_threshold(gc_threshold);
+
+    interp
+}
+
+#[allow(clippy::unwrap_used)]
+fn check(interp: &mut Interpreter, source: &str, mode: PurityMode) -> Vec<PurityViolation> {
+    interp
+        .eval_pure(source, Some(ModulePath::new("/manifest.ts")), mode)
+        .unwrap()
+}
+
+fn summary(violations: &[PurityViolation]) -> Vec<(PurityViolationKind, &str, u32)> {
+    violations
+        .iter()
+        .map(|v| {
+            let line = v.location.as_ref().map_or(0, |loc| loc.line);
+            (v.kind, v.target.as_str(), line)
+        })
+        .collect()
+}
+
+#[test]
+fn test_pure_module_has_no_violations() {
+    let mut interp = create_purity_interp();
+    let violations = check(
+        &mut interp,
+        r#"
+        import { limits, registry, Rule } from "app:config";
+        const regions = [...limits.regions, "ap"];
+        regions.push("sa");
+        const seen = new Map<string, number>();
+        seen.set("eu", 1);
+        const copy = { ...limits, rps: limits.rps * 2 };
+        copy.rps += 1;
+        class StrictRule extends Rule {}
+        let total = 0;
+        for (const r of regions) total += r.length;
+        export const manifest = { regions, copy, total, size: registry.size, rule: new StrictRule() };
+        "#,
+        PurityMode::Record,
+    );
+    assert_eq!(violations, vec![]);
+}
+
+#[test]
+fn test_global_and_intrinsic_writes() {
+    let mut interp = create_purity_interp();
+    let violations = check(
+        &mut interp,
+        r#"globalThis.leak = 1;
+Array.prototype.last = function () { return this[this.length - 1]; };
+Object.defineProperty(Math, "tau", { value: 6.28 });
+delete (JSON as any).stringify;
+Object.setPrototypeOf(globalThis, null);
+"#,
+        PurityMode::Record,
+    );
+    assert_eq!(
+        summary(&violations),
+        vec![
+            (PurityViolationKind::GlobalWrite, "leak", 1),
+            (PurityViolationKind::GlobalWrite, "Array.prototype.last", 2),
+            (PurityViolationKind::GlobalWrite, "Math.tau", 3),
+            (PurityViolationKind::GlobalWrite, "JSON.stringify", 4),
+            (PurityViolationKind::GlobalWrite, "globalThis", 5),
+        ]
+    );
+    assert!(violations.iter().all(|v| v.module.is_none()));
+    let location = violations.first().and_then(|v| v.location.as_ref());
+    assert_eq!(
+        location.and_then(|l| l.file.as_deref()),
+        Some("/manifest.ts")
+    );
+}
+
+#[test]
+fn test_writes_to_imported_objects() {
+    let mut interp = create_purity_interp();
+    let violations = check(
+        &mut interp,
+        r#"import { limits, registry, Rule } from "app:config";
+limits.rps = 5;
+limits.regions.push("ap");
+registry.set("checked", 1);
+Rule.prototype.strict = true;
+function tweak(target: { rps: number }) { target.rps++; }
+tweak(limits);
+"#,
+        PurityMode::Record,
+    );
+    assert_eq!(
+        summary(&violations),
+        vec![
+            (PurityViolationKind::ImportWrite, "limits.rps", 2),
+            (PurityViolationKind::ImportWrite, "limits.regions", 3),
+            (PurityViolationKind::ImportWrite, "registry", 4),
+            (PurityViolationKind::ImportWrite, "Rule.prototype.strict", 5),
+            (PurityViolationKind::ImportWrite, "limits.rps", 6),
+        ]
+    );
+    assert!(
+        violations
+            .iter()
+            .all(|v| v.module.as_deref() == Some("app:config"))
+    );
+    let function_name = violations
+        .last()
+        .and_then(|v| v.location.as_ref())
+        .and_then(|l| l.function_name.as_deref());
+    assert_eq!(function_name, Some("tweak"));
+}
+
+#[test]
+fn test_order_stops_evaluation() {
+    let mut interp = create_purity_interp();
+    let violations = check(
+        &mut interp,
+        r#"import { order } from "tsrun:host";
+const config = await order({ type: "fetchConfig" });
+globalThis.config = config;
+"#,
+        PurityMode::Record,
+    );
+    assert_eq!(
+        summary(&violations),
+        vec![(PurityViolationKind::Order, "order", 2)]
+    );
+    assert!(interp.outstanding_orders().is_empty());
+}
+
+#[test]
+fn test_raise_mode_stops_at_first_violation() {
+    let mut interp = create_purity_interp();
+    let violations = check(
+        &mut interp,
+        r#"try {
+    globalThis.first = 1;
+} catch {}
+globalThis.second = 2;
+"#,
+        PurityMode::Raise,
+    );
+    assert_eq!(
+        summary(&violations),
+        vec![(PurityViolationKind::GlobalWrite, "first", 2)]
+    );
+
+    // The blocked write never happened and the interpreter stays usable
+    let result = run(&mut interp, "typeof (globalThis as any).first", None);
+    assert!(matches!(
+        result,
+        Ok(StepResult::Complete(ref v)) if *v.value() == JsValue::from("undefined")
+    ));
+}
+
+#[test]
+fn test_record_mode_lets_writes_through() {
+    let mut interp = create_purity_interp();
+    let violations = check(&mut interp, "globalThis.flag = 42;", PurityMode::Record);
+    assert_eq!(violations.len(), 1);
+
+    let result = run(&mut interp, "(globalThis as any).flag", None);
+    assert!(matches!(
+        result,
+        Ok(StepResult::Complete(ref v)) if *v.value() == JsValue::Number(42.0)
+    ));
+}
@@ -0,0 +1,150 @@
+//! Tests for the tsrun:quantity internal module
+
+use super::eval;
+use tsrun::{JsValue, RuntimeValue};
+
+fn parse(input: &str) -> RuntimeValue {
+    eval(&format!(
+        r#"import {{ parseQuantity }} from "tsrun:quantity"; parseQuantity({:?})"#,
+        input
+    ))
+}
+
+fn format(expr: &str) -> RuntimeValue {
+    eval(&format!(
+        r#"import {{ formatQuantity }} from "tsrun:quantity"; formatQuantity({})"#,
+        expr
+    ))
+}
+
+fn error_message(call: &str) -> RuntimeValue {
+    eval(&format!(
+        r#"
+        import {{ parseQuantity, formatQuantity }} from "tsrun:quantity";
+        let caught = "";
+        try {{ {}; }} catch (e) {{ caught = e.name + ": " + e.message; }}
+        caught
+    "#,
+        call
+    ))
+}
+
+#[test]
+fn test_parse_quantity_table() {
+    let cases: &[(&str, f64)] = &[
+        ("0", 0.0),
+        ("1", 1.0),
+        ("100m", 0.1),
+        ("250m", 0.25),
+        ("1500m", 1.5),
+        ("-2", -2.0),
+        ("+3k", 3000.0),
+        ("1.5", 1.5),
+        ("1k", 1e3),
+        ("2M", 2e6),
+        ("3G", 3e9),
+        ("4T", 4e12),
+        ("5P", 5e15),
+        ("1Ki", 1024.0),
+        ("512Mi", 536_870_912.0),
+        ("1Gi", 1_073_741_824.0),
+        ("1.5Gi", 1_610_612_736.0),
+        ("0.5Gi", 536_870_912.0),
+        ("2Ti", 2_199_023_255_552.0),
+        ("1e3", 1000.0),
+        ("1E3", 1000.0),
+        ("1.5e3m", 1.5),
+        ("100n", 1e-7),
+        ("10u", 1e-5),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(
+            parse(input),
+            JsValue::Number(*expected),
+            "parsing {}",
+            input
+        );
+    }
+}
+
+#[test]
+fn test_format_quantity_table() {
+    let cases: &[(&str, &str)] = &[
+        ("0", "0"),
+        ("0.1", "100m"),
+        ("1.5", "1500m"),
+        ("2", "2"),
+        ("1000", "1k"),
+        ("1500", "1500"),
+        ("1500, { precision: 1 }", "1.5k"),
+        ("3e9", "3G"),
+        ("-2000000", "-2M"),
+        ("1024, { binary: true }", "1Ki"),
+        ("1000, { binary: true }", "1000"),
+        ("1610612736, { binary: true }", "1536Mi"),
+        ("1610612736, { binary: true, precision: 1 }", "1.5Gi"),
+        ("2 ** 40, { binary: true }", "1Ti"),
+        ("2 ** 50, { binary: true }", "1Pi"),
+        // Suffixed forms past 2^53 wouldn't parse back, so the plain number is used
+        ("2 ** 60, { binary: true }", "1152921504606847000"),
+        ("0.5, { binary: true }", "500m"),
+        ("1234567, { binary: true, precision: 2 }", "1.18Mi"),
+        ("1e-7", "100n"),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(
+            format(expr),
+            JsValue::from(*expected),
+            "formatting {}",
+            expr
+        );
+    }
+}
+
+#[test]
+fn test_quantity_round_trips() {
+    assert_eq!(
+        eval(
+            r#"
+            import { parseQuantity, formatQuantity } from "tsrun:quantity";
+            ["100m", "1536Mi", "3G", "7Ki", "42", "5n", "9007199254740991"]
+                .map(q => formatQuantity(parseQuantity(q), { binary: q.endsWith("i") }) === q)
+                .every(ok => ok)
+        "#
+        ),
+        JsValue::Boolean(true)
+    );
+}
+
+#[test]
+fn test_quantity_errors_name_offending_input() {
+    assert_eq!(
+        error_message(r#"parseQuantity("12Gx")"#),
+        JsValue::from("RangeError: Invalid quantity '12Gx': unknown suffix at 'Gx'")
+    );
+    assert_eq!(
+        error_message(r#"parseQuantity("abc")"#),
+        JsValue::from("RangeError: Invalid quantity 'abc': expected a number at 'abc'")
+    );
+    assert_eq!(
+        error_message(r#"parseQuantity("1.2.3")"#),
+        JsValue::from("RangeError: Invalid quantity '1.2.3': unknown suffix at '.3'")
+    );
+    assert_eq!(
+        error_message(r#"parseQuantity("")"#),
+        JsValue::from("RangeError: Invalid quantity '': expected a number at ''")
+    );
+    // Beyond 2^53 the result can no longer be represented exactly
+    assert_eq!(
+        error_message(r#"parseQuantity("8Pi")"#),
+        JsValue::from("RangeError: Quantity '8Pi' exceeds Number.MAX_SAFE_INTEGER")
+    );
+    assert_eq!(
+        error_message(r#"parseQuantity("10E")"#),
+        JsValue::from("RangeError: Quantity '10E' exceeds Number.MAX_SAFE_INTEGER")
+    );
+    assert_eq!(
+        error_message(r#"formatQuantity(Infinity)"#),
+        JsValue::from("RangeError: Cannot format Infinity as a quantity")
+    );
+}
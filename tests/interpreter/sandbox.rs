@@ -0,0 +1,100 @@
+//! Tests for evalInSandbox of tsrun:host
+
+use tsrun::{Interpreter, InterpreterConfig, JsValue, RuntimeValue, create_eval_internal_module};
+
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn eval_with_host(interp: &mut Interpreter, source: &str) -> RuntimeValue {
+    match super::run(interp, source, None).unwrap() {
+        tsrun::StepResult::Complete(value) => value,
+        other => panic!("Expected completion, got {:?}", other),
+    }
+}
+
+fn host_interp() -> Interpreter {
+    Interpreter::with_config(InterpreterConfig {
+        internal_modules: vec![create_eval_internal_module()],
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_sandbox_reads_bindings_and_returns_copy() {
+    let mut interp = host_interp();
+    let result = eval_with_host(
+        &mut interp,
+        r#"
+        import { evalInSandbox } from "tsrun:host";
+        const rates = { base: 3, items: [1, 2, 3] };
+        const result = evalInSandbox(
+            "({ total: rates.base * rates.items.reduce((a, b) => a + b, 0) })",
+            { rates },
+        );
+        let frozen = "";
+        try { evalInSandbox("rates.base = 9", { rates }); } catch (e) { frozen = e.name; }
+        let constant = "";
+        try { evalInSandbox("rates = 1", { rates }); } catch (e) { constant = e.name; }
+        [result.total, Object.isFrozen(result), rates.base, frozen, constant].join(",")
+    "#,
+    );
+    assert_eq!(result, JsValue::from("18,false,3,TypeError,TypeError"));
+}
+
+#[test]
+fn test_sandbox_hides_caller_and_global_this() {
+    let mut interp = host_interp();
+    let result = eval_with_host(
+        &mut interp,
+        r#"
+        import { evalInSandbox } from "tsrun:host";
+        const secret = 42;
+        const caught: string[] = [];
+        for (const code of ["1 +\n  globalThis.x", "secret", "import { order } from 'tsrun:host'; 1"]) {
+            try {
+                evalInSandbox(code);
+            } catch (e) {
+                caught.push(e.name + " " + e.message + (e.line ? " @" + e.line + ":" + e.column : ""));
+            }
+        }
+        caught.join("|")
+    "#,
+    );
+    assert_eq!(
+        result,
+        JsValue::from(
+            "ReferenceError globalThis is not defined @2:3\
+             |ReferenceError secret is not defined @1:1\
+             |TypeError evalInSandbox: snippets cannot import modules"
+        )
+    );
+}
+
+#[test]
+fn test_sandbox_fuel_limit_leaves_caller_running() {
+    let mut interp = host_interp();
+    interp.set_fuel(Some(1_000_000));
+    let result = eval_with_host(
+        &mut interp,
+        r#"
+        import { evalInSandbox } from "tsrun:host";
+        let message = "";
+        try {
+            evalInSandbox("let i = 0;\nwhile (true) { i++; }", {}, { fuel: 10000 });
+        } catch (e) {
+            message = e.name + ": " + e.message;
+        }
+        let sum = 0;
+        for (let i = 0; i < 100; i++) sum += i;
+        message + " / " + sum
+    "#,
+    );
+    assert_eq!(
+        result,
+        JsValue::from("RangeError: evalInSandbox: fuel exhausted / 4950")
+    );
+    // The snippet's instructions were charged to the caller
+    assert!(
+        interp
+            .fuel_remaining()
+            .is_some_and(|fuel| fuel < 1_000_000 - 10_000)
+    );
+}
@@ -114,3 +114,155 @@ fn test_set_entries() {
         JsValue::from("1:1,2:2")
     );
 }
+
+/// A set-like view over a Map's keys, so the set methods can't use the Set fast path
+const MAP_KEYS_LIKE: &str = r#"
+    const m = new Map([[2, "b"], [3, "c"], [4, "d"]]);
+    const like = { size: m.size, has: (k: number) => m.has(k), keys: () => m.keys() };
+    const s = new Set([1, 2, 3]);
+"#;
+
+#[test]
+fn test_set_algebra_with_set_like() {
+    let cases = [
+        ("[...s.union(like)].join(',')", "1,2,3,4"),
+        ("[...s.intersection(like)].join(',')", "2,3"),
+        ("[...s.difference(like)].join(',')", "1"),
+        ("[...s.symmetricDifference(like)].join(',')", "1,4"),
+        ("String(s.isSubsetOf(like))", "false"),
+        ("String(new Set([2, 3]).isSubsetOf(like))", "true"),
+        ("String(s.isSupersetOf(like))", "false"),
+        ("String(new Set([1, 2, 3, 4]).isSupersetOf(like))", "true"),
+        ("String(s.isDisjointFrom(like))", "false"),
+        ("String(new Set([1, 9]).isDisjointFrom(like))", "true"),
+        // The result is a new Set; neither side is modified
+        (
+            "s.union(like) !== s && s.size === 3 && m.size === 3 ? 'ok' : 'bad'",
+            "ok",
+        ),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(
+            eval(&format!("{MAP_KEYS_LIKE} {expr}")),
+            JsValue::from(expected),
+            "{expr}"
+        );
+    }
+}
+
+#[test]
+fn test_set_algebra_iterates_the_smaller_side() {
+    // With a larger `this`, intersection walks other.keys() and keeps its order
+    assert_eq!(
+        eval("[...new Set([1, 2, 3, 4, 5, 6]).intersection(new Set([6, 1]))].join(',')"),
+        JsValue::from("6,1")
+    );
+    // With a smaller `this`, only has() is consulted
+    assert_eq!(
+        eval(
+            r#"
+            let calls = 0;
+            const other = {
+                size: 100,
+                has: (x: number) => { calls++; return x > 1; },
+                keys: () => { throw new Error("keys should not be called"); },
+            };
+            const r = new Set([1, 2, 3]).intersection(other);
+            [...r].join(',') + ' ' + calls
+            "#
+        ),
+        JsValue::from("2,3 3")
+    );
+    // isSupersetOf stops, and closes the iterator, at the first missing key
+    assert_eq!(
+        eval(
+            r#"
+            let closed = false;
+            const other = {
+                size: 2,
+                has: () => true,
+                keys() {
+                    let i = 0;
+                    return {
+                        next: () => ({ done: i >= 2, value: i++ === 0 ? 9 : 1 }),
+                        return() { closed = true; return {}; },
+                    };
+                },
+            };
+            new Set([1, 2]).isSupersetOf(other) + ' ' + closed
+            "#
+        ),
+        JsValue::from("false true")
+    );
+}
+
+#[test]
+fn test_set_algebra_empty_sets() {
+    assert_eq!(
+        eval(
+            r#"
+            const e = new Set();
+            const a = new Set([1, 2]);
+            [
+                e.union(a).size, e.intersection(a).size, a.intersection(e).size,
+                a.difference(e).size, e.symmetricDifference(e).size,
+                e.isSubsetOf(a), e.isSubsetOf(e), a.isSupersetOf(e),
+                e.isDisjointFrom(e), a.isSubsetOf(e),
+            ].join(',')
+            "#
+        ),
+        JsValue::from("2,0,0,2,0,true,true,true,true,false")
+    );
+}
+
+#[test]
+fn test_set_algebra_same_value_zero() {
+    assert_eq!(
+        eval(
+            r#"
+            const z = new Set([-0, NaN]);
+            [
+                z.has(0),
+                z.intersection(new Set([0, NaN])).size,
+                Object.is([...new Set([1]).union(new Set([-0]))][1], 0),
+                Object.is([...new Set([-0])][0], 0),
+                new Set([NaN]).isSubsetOf(new Set([NaN])),
+            ].join(',')
+            "#
+        ),
+        JsValue::from("true,2,true,true,true")
+    );
+}
+
+#[test]
+fn test_set_algebra_rejects_invalid_set_likes() {
+    let cases = [
+        ("s.union([1])", "TypeError"),
+        ("s.union({ size: NaN, has() {}, keys() {} })", "TypeError"),
+        ("s.union({ size: -1, has() {}, keys() {} })", "RangeError"),
+        ("s.union({ size: 1, keys() {} })", "TypeError"),
+        ("s.union({ size: 1, has() {} })", "TypeError"),
+        (
+            "Set.prototype.union.call(new Map(), new Set())",
+            "TypeError",
+        ),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(
+            eval(&format!(
+                "const s = new Set([1]); let name = 'none'; try {{ {expr}; }} catch (e) {{ name = e.name; }} name"
+            )),
+            JsValue::from(expected),
+            "{expr}"
+        );
+    }
+}
+
+#[test]
+fn test_set_constructor_accepts_iterables() {
+    assert_eq!(eval("new Set('abca').size"), JsValue::Number(3.0));
+    assert_eq!(
+        eval("[...new Set(new Map([[1, 'a'], [2, 'b']]).keys())].join(',')"),
+        JsValue::from("1,2")
+    );
+}
@@ -0,0 +1,90 @@
+//! Tests for SourceText offset mapping and rendered error snippets
+
+use tsrun::{Interpreter, JsError, SourceText};
+
+#[allow(clippy::panic)]
+fn parse_error(source: &str) -> JsError {
+    match Interpreter::new().prepare(source, None) {
+        Err(err) => err,
+        Ok(_) => panic!("Expected a parse error for {:?}", source),
+    }
+}
+
+#[test]
+fn test_offset_to_line_col() {
+    let text = SourceText::new("ab\ncdé\n\nfg");
+    assert_eq!(text.line_count(), 4);
+    assert_eq!(text.offset_to_line_col(0), (1, 1));
+    assert_eq!(text.offset_to_line_col(2), (1, 3));
+    assert_eq!(text.offset_to_line_col(3), (2, 1));
+    // 'é' is two bytes but one column
+    assert_eq!(text.offset_to_line_col(7), (2, 4));
+    assert_eq!(text.offset_to_line_col(9), (4, 1));
+    assert_eq!(text.offset_to_line_col(100), (4, 3));
+    assert_eq!(text.line_text(2), Some("cdé"));
+    assert_eq!(text.line_text(3), Some(""));
+    assert_eq!(text.line_text(4), Some("fg"));
+    assert_eq!(text.line_text(5), None);
+    assert_eq!(SourceText::new("a\r\nb").line_text(1), Some("a"));
+}
+
+#[test]
+fn test_render_error_on_first_line() {
+    let source = "let = 1;\nconst ok = 2;";
+    let err = parse_error(source);
+    assert_eq!(
+        err.render(&SourceText::new(source)),
+        "SyntaxError: Unexpected Eq, expected binding pattern\n --> 1:5\n  |\n1 | let = 1;\n  |     ^"
+    );
+}
+
+#[test]
+fn test_render_error_on_last_line() {
+    let mut source = String::new();
+    for i in 0..11 {
+        source.push_str(&format!("const v{} = {};\n", i, i));
+    }
+    source.push_str("const broken = (1 + ;");
+    let err = parse_error(&source);
+    let rendered = err.render(&SourceText::new(&source));
+    assert!(rendered.contains("\n  --> 12:21\n"), "{}", rendered);
+    assert!(
+        rendered.ends_with("\n   |\n12 | const broken = (1 + ;\n   |                     ^"),
+        "{}",
+        rendered
+    );
+}
+
+#[test]
+fn test_render_caret_aligns_after_tabs_and_multibyte_chars() {
+    let source = "function f() {\n\tconst s = \"héllo wörld\"; let = 3;\n}";
+    let err = parse_error(source);
+    let rendered = err.render(&SourceText::new(source));
+    assert!(
+        rendered.ends_with(
+            "2 | \tconst s = \"héllo wörld\"; let = 3;\n  | \t                             ^"
+        ),
+        "{}",
+        rendered
+    );
+}
+
+#[test]
+fn test_render_caret_covers_span() {
+    let source = "class A {}\n@dec const x = 1;";
+    let err = parse_error(source);
+    let rendered = err.render(&SourceText::new(source));
+    assert!(rendered.ends_with("  |      ^^^^^"), "{}", rendered);
+}
+
+#[test]
+fn test_prepare_attaches_snippet_to_display() {
+    let source = "let x = 1;\nlet y = ;";
+    let err = parse_error(source);
+    let display = err.to_string();
+    assert!(
+        display.ends_with("\n --> 2:9\n  |\n2 | let y = ;\n  |         ^"),
+        "{}",
+        display
+    );
+}
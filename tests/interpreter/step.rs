@@ -6,7 +6,11 @@
 //! - Query call depth with call_depth()
 //! - Enforce time limits, step limits, or depth limits as needed
 
-use tsrun::{Interpreter, StepResult};
+use super::run_to_completion;
+use tsrun::{
+    Interpreter, InterpreterConfig, JsValue, ModulePath, OrderResponse, PrepareProgress,
+    RuntimeValue, StepGranularity, StepResult, create_eval_internal_module,
+};
 
 #[test]
 fn test_step_basic_execution() {
@@ -446,3 +450,239 @@ fn test_call_depth_with_nested_user_functions() {
     // Should have reached depth 4 (level1 -> level2 -> level3 -> level4)
     assert!(max_depth >= 4, "max_depth was {}", max_depth);
 }
+
+/// A generated bundle of a few hundred KB: thousands of top-level statements
+/// defining functions and classes, folded into one string at the end
+fn synthetic_bundle() -> String {
+    let mut source = String::from("const table: Record<string, (x: number) => string> = {};\n");
+    for i in 0..2000 {
+        source.push_str(&format!(
+            "table.f{i} = function (x: number): string {{\n\
+             \x20   class C{i} {{ value = x * {i} + {m}; get label() {{ return `c{i}:${{this.value}}`; }} }}\n\
+             \x20   return new C{i}().label;\n\
+             }};\n",
+            m = i % 7
+        ));
+    }
+    source.push_str(
+        "const results: string[] = [];\n\
+         for (const key in table) results.push(table[key](results.length));\n\
+         `${results.length}:${results.join(',')}`\n",
+    );
+    source
+}
+
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn completion_string(interp: &mut Interpreter) -> String {
+    match run_to_completion(interp).unwrap() {
+        StepResult::Complete(value) => value.as_str().unwrap().to_string(),
+        other => panic!("Unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+#[allow(clippy::panic)]
+fn test_incremental_prepare_matches_one_shot_prepare() {
+    let source = synthetic_bundle();
+
+    // Not a GC stress test; collecting on every allocation makes this slow
+    let mut one_shot = Interpreter::new();
+    one_shot.prepare(&source, None).unwrap();
+    let expected = completion_string(&mut one_shot);
+
+    let mut interp = Interpreter::new();
+    interp.prepare_begin(None);
+    for chunk in source.as_bytes().chunks(4096) {
+        interp
+            .prepare_chunk(std::str::from_utf8(chunk).unwrap())
+            .unwrap();
+    }
+
+    let budget = 16 * 1024;
+    let mut pending = 0;
+    let mut last_parsed = 0;
+    let mut progress = interp.prepare_finish(budget).unwrap();
+    let result = loop {
+        match progress {
+            PrepareProgress::Pending {
+                parsed_bytes,
+                total_bytes,
+            } => {
+                assert_eq!(total_bytes, source.len());
+                assert!(parsed_bytes > last_parsed || parsed_bytes == total_bytes);
+                last_parsed = parsed_bytes;
+                pending += 1;
+            }
+            PrepareProgress::Ready(result) => break result,
+        }
+        progress = interp.prepare_continue(budget).unwrap();
+    };
+    assert!(matches!(result, StepResult::Continue));
+    assert!(
+        pending >= source.len() / budget,
+        "only {} pending steps",
+        pending
+    );
+
+    assert_eq!(completion_string(&mut interp), expected);
+}
+
+#[test]
+fn test_incremental_prepare_reports_errors_like_prepare() {
+    let source = "const a = 1;\nconst b = ;\n";
+    let expected = Interpreter::new().prepare(source, None).unwrap_err();
+
+    let mut interp = Interpreter::new();
+    interp.prepare_begin(None);
+    interp.prepare_chunk(source).unwrap();
+    let mut progress = interp.prepare_finish(1);
+    while let Ok(PrepareProgress::Pending { .. }) = progress {
+        progress = interp.prepare_continue(1);
+    }
+    let err = progress.unwrap_err();
+    assert_eq!(err.to_string(), expected.to_string());
+
+    // The failed prepare is abandoned
+    assert!(interp.prepare_continue(1).is_err());
+    assert!(interp.prepare_chunk("1").is_err());
+}
+
+#[test]
+#[allow(clippy::panic)]
+fn test_incremental_prepare_requests_imports() {
+    let mut interp = Interpreter::new();
+    interp.prepare_begin(Some(ModulePath::new("/app/main.ts")));
+    interp
+        .prepare_chunk("import { x } from './dep';\n")
+        .unwrap();
+    interp.prepare_chunk("x * 2").unwrap();
+
+    let mut progress = interp.prepare_finish(usize::MAX).unwrap();
+    while let PrepareProgress::Pending { .. } = progress {
+        progress = interp.prepare_continue(usize::MAX).unwrap();
+    }
+    let PrepareProgress::Ready(StepResult::NeedImports(requests)) = progress else {
+        panic!("expected import requests, got {:?}", progress);
+    };
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].resolved_path.as_str(), "/app/dep");
+}
+
+fn interp_with_granularity(step_granularity: StepGranularity) -> Interpreter {
+    Interpreter::with_config(InterpreterConfig {
+        internal_modules: vec![create_eval_internal_module()],
+        step_granularity,
+        ..Default::default()
+    })
+}
+
+/// Step `source` to completion, returning the number of `Continue` results,
+/// the other results in order, rendered, and the completion value.
+///
+/// Imports are answered with `MODULE` and orders with their payload's `n`.
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn step_all(granularity: StepGranularity, source: &str) -> (usize, Vec<String>, JsValue) {
+    const MODULE: &str = "export function double(n: number) { return n * 2; }";
+    let mut interp = interp_with_granularity(granularity);
+    let mut continues = 0;
+    let mut events = Vec::new();
+    let mut result = interp.prepare(source, Some(ModulePath::new("/main.ts")));
+    loop {
+        match result.unwrap() {
+            StepResult::Continue => continues += 1,
+            StepResult::Complete(value) => return (continues, events, value.value().clone()),
+            StepResult::NeedImports(imports) => {
+                for import in imports {
+                    events.push(format!("import {}", import.specifier));
+                    interp.provide_module(import.resolved_path, MODULE).unwrap();
+                }
+            }
+            StepResult::Suspended { pending, .. } => {
+                let responses = pending
+                    .into_iter()
+                    .map(|order| {
+                        let n = tsrun::api::get_property(order.payload.value(), "n").unwrap();
+                        events.push(format!("order {:?}", n));
+                        OrderResponse {
+                            id: order.id,
+                            result: Ok(RuntimeValue::unguarded(n)),
+                        }
+                    })
+                    .collect();
+                interp.fulfill_orders(responses);
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+        result = interp.step();
+    }
+}
+
+#[test]
+fn test_checkpoint_granularity_steps_far_less() {
+    let source = r#"
+        function add(a: number, b: number) { return a + b; }
+        let total = 0;
+        for (let i = 0; i < 1000; i++) {
+            total = add(total, i % 7);
+        }
+        total
+    "#;
+    let (per_instruction, _, expected) = step_all(StepGranularity::Instruction, source);
+    let (per_checkpoint, _, value) = step_all(StepGranularity::Checkpoint, source);
+    assert_eq!(value, expected);
+    assert_eq!(value, JsValue::Number(2997.0));
+    // Each iteration still stops at the call, the return and the back-edge
+    assert!(
+        per_checkpoint * 3 < per_instruction,
+        "{} checkpoints vs {} instructions",
+        per_checkpoint,
+        per_instruction
+    );
+
+    let tight_loop = "let n = 0; while (n < 1000) { n = n + 1; } n";
+    let (per_instruction, _, _) = step_all(StepGranularity::Instruction, tight_loop);
+    let (per_checkpoint, _, value) = step_all(StepGranularity::Checkpoint, tight_loop);
+    assert_eq!(value, JsValue::Number(1000.0));
+    assert!(
+        per_checkpoint * 5 < per_instruction,
+        "{} checkpoints vs {} instructions",
+        per_checkpoint,
+        per_instruction
+    );
+}
+
+#[test]
+fn test_checkpoint_granularity_keeps_orders_and_imports() {
+    let source = r#"
+        import { order } from "tsrun:host";
+        import { double } from "./math";
+        async function fetchN(n: number): Promise<number> {
+            const got: any = await order({ n });
+            return double(got);
+        }
+        let sum = 0;
+        for (let i = 1; i <= 3; i++) {
+            sum += await fetchN(i);
+        }
+        sum
+    "#;
+    let instruction = step_all(StepGranularity::Instruction, source);
+    let checkpoint = step_all(StepGranularity::Checkpoint, source);
+    assert_eq!(checkpoint.1, instruction.1);
+    assert_eq!(
+        checkpoint.1,
+        vec!["import ./math", "order 1", "order 2", "order 3"]
+    );
+    assert_eq!(checkpoint.2, instruction.2);
+    assert_eq!(checkpoint.2, JsValue::Number(12.0));
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_checkpoint_granularity_can_stop_infinite_loop() {
+    let mut interp = interp_with_granularity(StepGranularity::Checkpoint);
+    interp.prepare("while (true) {}", None).unwrap();
+    for _ in 0..100 {
+        assert!(matches!(interp.step().unwrap(), StepResult::Continue));
+    }
+}
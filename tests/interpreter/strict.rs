@@ -312,3 +312,121 @@ fn test_delete_super_property_with_type_cast() {
         err
     );
 }
+
+// ============================================================================
+// `this` binding for the different call forms
+// ============================================================================
+
+/// Prelude defining a function that reports the `this` it was called with
+const WHO: &str = r#"
+    function who() {
+        return this === undefined ? "undefined"
+            : this === globalThis ? "global"
+            : this.tag;
+    }
+    const obj: any = { tag: "obj", who, inner: { tag: "inner", who } };
+"#;
+
+fn eval_who(code: &str) -> JsValue {
+    eval(&format!("{}\n{}", WHO, code)).value().clone()
+}
+
+#[test]
+fn test_this_plain_and_extracted_calls() {
+    assert_eq!(eval_who("who()"), JsValue::from("undefined"));
+    assert_eq!(
+        eval_who("const f = obj.who; f()"),
+        JsValue::from("undefined")
+    );
+    assert_eq!(eval_who("(0, obj.who)()"), JsValue::from("undefined"));
+}
+
+#[test]
+fn test_this_method_call_forms() {
+    assert_eq!(eval_who("obj.who()"), JsValue::from("obj"));
+    assert_eq!(eval_who(r#"obj["who"]()"#), JsValue::from("obj"));
+    assert_eq!(eval_who("(obj.who)()"), JsValue::from("obj"));
+    assert_eq!(eval_who("obj?.who()"), JsValue::from("obj"));
+    assert_eq!(eval_who("obj.who?.()"), JsValue::from("obj"));
+    assert_eq!(eval_who(r#"obj?.["who"]()"#), JsValue::from("obj"));
+    assert_eq!(eval_who("obj?.inner.who()"), JsValue::from("inner"));
+    assert_eq!(eval_who("obj.who`tpl`"), JsValue::from("obj"));
+}
+
+#[test]
+fn test_this_receiver_evaluated_once() {
+    assert_eq!(
+        eval_who(
+            r#"
+            let count = 0;
+            const get = () => { count++; return obj; };
+            const key = () => { count++; return "who"; };
+            get().who() + get()[key()]() + get()?.who() + ":" + count
+        "#
+        ),
+        JsValue::from("objobjobj:4")
+    );
+}
+
+#[test]
+fn test_this_callback_builtins() {
+    // Without thisArg, callbacks see undefined
+    assert_eq!(
+        eval_who(
+            r#"
+            const seen: string[] = [];
+            [1].forEach(function () { seen.push(who.call(this)); });
+            [1].map(function () { seen.push(who.call(this)); });
+            new Map([[1, 1]]).forEach(function () { seen.push(who.call(this)); });
+            new Set([1]).forEach(function () { seen.push(who.call(this)); });
+            Array.from([1], function () { seen.push(who.call(this)); });
+            seen.join(",")
+        "#
+        ),
+        JsValue::from("undefined,undefined,undefined,undefined,undefined")
+    );
+    // With thisArg, callbacks see it
+    assert_eq!(
+        eval_who(
+            r#"
+            const seen: string[] = [];
+            const cb = function () { seen.push(who.call(this)); return true; };
+            [1].forEach(cb, obj);
+            [1].map(cb, obj);
+            [1].filter(cb, obj);
+            [1].some(cb, obj);
+            [1].every(cb, obj);
+            [1].find(cb, obj);
+            [1].findIndex(cb, obj);
+            new Map([[1, 1]]).forEach(cb, obj);
+            new Set([1]).forEach(cb, obj);
+            Array.from([1], cb, obj);
+            seen.join(",")
+        "#
+        ),
+        JsValue::from("obj,obj,obj,obj,obj,obj,obj,obj,obj,obj")
+    );
+}
+
+#[test]
+fn test_this_arrow_keeps_lexical_this_when_extracted() {
+    assert_eq!(
+        eval_who(
+            r#"
+            const holder: any = { tag: "holder", make() { return () => who.call(this); } };
+            const arrow = holder.make();
+            const other: any = { tag: "other", arrow };
+            arrow() + "," + other.arrow() + "," + arrow.call(obj)
+        "#
+        ),
+        JsValue::from("holder,holder,holder")
+    );
+}
+
+#[test]
+fn test_this_script_top_level_is_global() {
+    assert_eq!(
+        eval("this === globalThis && (() => this)() === globalThis"),
+        JsValue::Boolean(true)
+    );
+}
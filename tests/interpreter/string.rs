@@ -87,6 +87,32 @@ fn test_string_touppercase() {
     );
 }
 
+#[test]
+fn test_string_case_special_casing() {
+    // Multi-character and context-sensitive mappings from SpecialCasing
+    assert_eq!(eval("'straße'.toUpperCase()"), JsValue::from("STRASSE"));
+    assert_eq!(eval("'ﬁ'.toUpperCase()"), JsValue::from("FI"));
+    assert_eq!(eval("'İ'.toLowerCase()"), JsValue::from("i\u{307}"));
+    assert_eq!(eval("'ΟΔΟΣ'.toLowerCase()"), JsValue::from("οδος"));
+    assert_eq!(eval("'ǅ'.toLowerCase()"), JsValue::from("ǆ"));
+    // The default mapping, not the Turkish one
+    assert_eq!(eval("'ı'.toUpperCase()"), JsValue::from("I"));
+    assert_eq!(eval("'i'.toUpperCase()"), JsValue::from("I"));
+}
+
+#[test]
+fn test_string_to_locale_case() {
+    assert_eq!(
+        eval("'Straße'.toLocaleUpperCase('tr')"),
+        JsValue::from("STRASSE")
+    );
+    assert_eq!(eval("'ÉCOLE'.toLocaleLowerCase()"), JsValue::from("école"));
+    assert_eq!(
+        eval("String.prototype.toLocaleUpperCase.length"),
+        JsValue::Number(0.0)
+    );
+}
+
 #[test]
 fn test_string_trim() {
     assert_eq!(
@@ -166,6 +192,19 @@ fn test_string_charat_index() {
     assert_eq!(eval("'hello'.charCodeAt(1)"), JsValue::Number(101.0));
 }
 
+#[test]
+fn test_string_primitive_index_keys() {
+    assert_eq!(
+        eval(
+            r#"
+            const s = "abc";
+            [s[1], s["1"], s["01"], s[1.5], s[-1], s[-0], s[3], s.length].join(",")
+            "#
+        ),
+        JsValue::String(JsString::from("b,b,,,,a,,3"))
+    );
+}
+
 #[test]
 fn test_string_fromcharcode() {
     assert_eq!(
@@ -478,6 +517,81 @@ fn test_string_locale_compare_empty() {
     assert_eq!(eval("''.localeCompare('a')"), JsValue::Number(-1.0));
 }
 
+#[test]
+fn test_string_locale_compare_case_before_code_point() {
+    // Letters first, then case: "a" < "B" although 'B' < 'a' in code points
+    assert_eq!(eval("'a'.localeCompare('B')"), JsValue::Number(-1.0));
+    assert_eq!(eval("'a'.localeCompare('A')"), JsValue::Number(-1.0));
+    assert_eq!(eval("'A'.localeCompare('a')"), JsValue::Number(1.0));
+    assert_eq!(
+        eval("['b', 'B', 'a', 'A', 'á'].sort((x, y) => x.localeCompare(y)).join()"),
+        JsValue::from("a,A,á,b,B")
+    );
+}
+
+#[test]
+fn test_string_locale_compare_numeric() {
+    assert_eq!(
+        eval("'item2'.localeCompare('item10')"),
+        JsValue::Number(1.0)
+    );
+    assert_eq!(
+        eval("'item2'.localeCompare('item10', undefined, { numeric: true })"),
+        JsValue::Number(-1.0)
+    );
+    assert_eq!(
+        eval("'item007'.localeCompare('item7', 'en', { numeric: true })"),
+        JsValue::Number(0.0)
+    );
+    assert_eq!(
+        eval(
+            r#"
+            ['item10', 'item2', 'item1', 'item20b', 'item20a', 'item3']
+                .sort((a, b) => a.localeCompare(b, undefined, { numeric: true }))
+                .join()
+            "#
+        ),
+        JsValue::from("item1,item2,item3,item10,item20a,item20b")
+    );
+}
+
+#[test]
+fn test_string_locale_compare_sensitivity() {
+    let compare = |a: &str, b: &str, sensitivity: &str| {
+        eval(&format!(
+            "'{}'.localeCompare('{}', undefined, {{ sensitivity: '{}' }})",
+            a, b, sensitivity
+        ))
+    };
+    assert_eq!(compare("résumé", "resume", "base"), JsValue::Number(0.0));
+    assert_eq!(compare("Résumé", "resume", "base"), JsValue::Number(0.0));
+    assert_eq!(
+        compare("re\u{301}sume\u{301}", "resume", "base"),
+        JsValue::Number(0.0)
+    );
+    assert_eq!(compare("a", "b", "base"), JsValue::Number(-1.0));
+
+    assert_eq!(compare("résumé", "resume", "accent"), JsValue::Number(1.0));
+    assert_eq!(compare("RESUME", "resume", "accent"), JsValue::Number(0.0));
+
+    assert_eq!(compare("résumé", "resume", "case"), JsValue::Number(0.0));
+    assert_eq!(compare("Resume", "resume", "case"), JsValue::Number(1.0));
+
+    assert_eq!(compare("résumé", "resume", "variant"), JsValue::Number(1.0));
+    assert_eq!(
+        eval("'résumé'.localeCompare('resume')"),
+        JsValue::Number(1.0)
+    );
+}
+
+#[test]
+fn test_string_locale_compare_invalid_sensitivity() {
+    let err = eval_result("'a'.localeCompare('b', undefined, { sensitivity: 'loose' })");
+    assert!(err.is_err_and(|e| e.to_string().contains(
+        "RangeError: Value loose out of range for Intl.Collator options property sensitivity"
+    )));
+}
+
 // substr tests (deprecated but still needs support)
 #[test]
 fn test_string_substr_basic() {
@@ -1156,3 +1270,115 @@ fn test_template_invalid_unicode_brace_escape() {
 // This is slightly stricter than the spec but prevents common errors.
 // TODO: Support invalid escapes in tagged templates for full ES2018+ compliance.
 // TODO: raw values are currently the same as cooked values - should preserve escapes
+
+#[test]
+fn test_string_concat_in_loop_preserves_semantics() {
+    assert_eq!(
+        eval(
+            r#"
+            let out = "";
+            let rev = "";
+            for (let i = 0; i < 2000; i++) {
+                out += "line " + i + "\n";
+                rev = i + "," + rev;
+            }
+            const lines = out.split("\n");
+            const m = new Map([[out, "found"]]);
+            let copy = "";
+            for (const line of lines.slice(0, -1)) copy += line + "\n";
+            [
+                out.length,
+                lines[1999],
+                out.slice(0, 13),
+                rev.startsWith("1999,1998,"),
+                out === copy,
+                m.get(copy),
+                out.charCodeAt(out.length - 1),
+            ].join("|")
+        "#
+        ),
+        JsValue::from("18890|line 1999|line 0\nline 1|true|true|found|10")
+    );
+}
+
+#[test]
+fn test_string_positions_count_characters() {
+    assert_eq!(
+        eval(
+            r#"
+            [
+                "héllo wörld".indexOf("w"), "héllo wörld".lastIndexOf("ö"), "éé".at(-1),
+                "aéb".includes("b", 2), "aéb".startsWith("b", 2), "aéb".endsWith("é", 2),
+                "aé€x".slice(1, 3), "aé€x".substring(3, 1), "".indexOf(""), "ab".indexOf("", 5),
+            ].join("|")
+        "#
+        ),
+        JsValue::from("6|7|é|true|true|true|é€|é€|0|2")
+    );
+}
+
+#[test]
+fn test_string_pad_with_multibyte_filler() {
+    assert_eq!(
+        eval(r#"["x".padStart(4, "é€"), "x".padEnd(3, "€")].join("|")"#),
+        JsValue::from("é€éx|x€€")
+    );
+}
+
+#[test]
+fn test_string_surrogate_escapes_join_into_one_char() {
+    assert_eq!(
+        eval(
+            r#"
+            const a = "💩", b = `💩`, c = String.fromCharCode(0xD83D, 0xDCA9);
+            [a === "💩", b === "💩", c === "💩", a.length, a.codePointAt(0)].join("|")
+        "#
+        ),
+        JsValue::from("true|true|true|1|128169")
+    );
+}
+
+#[test]
+fn test_string_lone_surrogates_become_replacement_char() {
+    assert_eq!(
+        eval(
+            r#"
+            const lone = [String.fromCharCode(0xD83D), "\uDCA9", `\uD800x`, String.fromCodePoint(0xDC00)];
+            const key: any = {};
+            key[String.fromCharCode(0xD800)] = 1;
+            [
+                lone.every(s => s.startsWith("�")), lone[2].length,
+                lone[0].isWellFormed(), lone[0].toWellFormed() === lone[0],
+                key["�"], Object.keys(key)[0] === "�",
+            ].join("|")
+        "#
+        ),
+        JsValue::from("true|2|true|true|1|true")
+    );
+}
+
+#[test]
+fn test_json_round_trips_surrogates() {
+    assert_eq!(
+        eval(
+            r#"
+            const text = JSON.stringify({ s: "💩" + String.fromCharCode(0xD83D) });
+            const parsed = JSON.parse(text);
+            [
+                parsed.s === "💩�", JSON.parse('"\\ud83d\\udca9"') === "💩",
+                JSON.parse('"\\ud83d"') === "�", JSON.parse('["a\\udca9b"]')[0].length,
+            ].join("|")
+        "#
+        ),
+        JsValue::from("true|true|true|3")
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_js_string_utf16_conversion() {
+    let units = [0x61, 0xD83D, 0xDCA9, 0xDC00, 0x62];
+    let s = JsString::from_utf16_lossy(&units);
+    assert_eq!(s.as_str(), "a💩\u{FFFD}b");
+    assert_eq!(s.to_utf16(), vec![0x61, 0xD83D, 0xDCA9, 0xFFFD, 0x62]);
+}
@@ -1,7 +1,7 @@
 //! Tests for Symbol primitive
 
-use super::eval;
-use tsrun::JsValue;
+use super::{create_test_runtime, eval, run};
+use tsrun::{JsValue, StepResult};
 
 #[test]
 fn test_symbol_typeof() {
@@ -353,6 +353,111 @@ fn test_iterator_close_not_called_on_normal_completion() {
     );
 }
 
+#[test]
+fn test_iterator_close_runs_generator_finally_on_break() {
+    assert_eq!(
+        eval(
+            r#"
+            const log: string[] = [];
+            function* gen(name: string) {
+                try { yield 1; yield 2; yield 3; } finally { log.push("close " + name); }
+            }
+            for (const x of gen("a")) {
+                if (x === 2) break;
+            }
+            outer: for (const a of gen("outer")) {
+                for (const b of gen("inner")) {
+                    continue outer;
+                }
+            }
+            log.join()
+        "#
+        ),
+        JsValue::from("close a,close inner,close inner,close inner,close outer")
+    );
+}
+
+#[test]
+fn test_iterator_close_on_throw_keeps_original_error() {
+    // A throwing return() must not replace the exception that left the loop
+    assert_eq!(
+        eval(
+            r#"
+            let calls = 0;
+            const iter = {
+                [Symbol.iterator]() {
+                    return {
+                        next() { return { value: 1, done: false }; },
+                        return() { calls++; throw new Error("from return"); }
+                    };
+                }
+            };
+            let message = "";
+            try {
+                for (const x of iter) {
+                    throw new Error("original");
+                }
+            } catch (e) {
+                message = (e as Error).message;
+            }
+            message + " " + calls
+        "#
+        ),
+        JsValue::from("original 1")
+    );
+}
+
+#[test]
+fn test_iterator_close_error_on_break_propagates_once() {
+    assert_eq!(
+        eval(
+            r#"
+            let calls = 0;
+            const iter = {
+                [Symbol.iterator]() {
+                    return {
+                        next() { return { value: 1, done: false }; },
+                        return() { calls++; return 5; }
+                    };
+                }
+            };
+            let name = "";
+            try {
+                for (const x of iter) {
+                    break;
+                }
+            } catch (e) {
+                name = (e as Error).constructor.name;
+            }
+            name + " " + calls
+        "#
+        ),
+        JsValue::from("TypeError 1")
+    );
+}
+
+#[test]
+fn test_for_of_not_iterable_message() {
+    assert_eq!(
+        eval(
+            r#"
+            const messages: string[] = [];
+            for (const value of [5, null, undefined, {}]) {
+                try {
+                    for (const x of (value as any)) {}
+                } catch (e) {
+                    messages.push((e as Error).message);
+                }
+            }
+            messages.join("; ")
+        "#
+        ),
+        JsValue::from(
+            "number 5 is not iterable; null is not iterable; undefined is not iterable; object is not iterable"
+        )
+    );
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // Symbol.species Tests
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -515,3 +620,75 @@ fn test_destructuring_empty_pattern_calls_close() {
         JsValue::Boolean(true)
     );
 }
+
+/// Symbol keys enumerated every way a script can, and the canonical JSON
+/// of the object carrying them, from a fresh interpreter
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn symbol_key_run() -> (String, String) {
+    let source = r#"
+        const names = ["zeta", "alpha", "mid", "beta", "omega", "gamma", "delta"];
+        const target: any = { plain: 1 };
+        for (const name of names) {
+            target[Symbol.for(name)] = name;
+            target[Symbol(name)] = name.length;
+        }
+        target[Symbol()] = "anonymous";
+        delete target[Symbol.for("mid")];
+        target[Symbol.for("mid")] = "again";
+        const copy = { ...target };
+        const assigned = Object.assign({}, target);
+        const describe = (keys: any[]) =>
+            keys.map((k) => typeof k === "symbol" ? String(Symbol.keyFor(k)) + ":" + k.toString() : k).join(",");
+        const order = [
+            describe(Object.getOwnPropertySymbols(target)),
+            describe(Reflect.ownKeys(target)),
+            describe(Object.getOwnPropertySymbols(copy)),
+            describe(Object.getOwnPropertySymbols(assigned)),
+        ].join("|");
+        ({ order, target })
+    "#;
+    let mut interp = create_test_runtime();
+    let StepResult::Complete(value) = run(&mut interp, source, None).unwrap() else {
+        panic!("Expected Complete");
+    };
+    let order = tsrun::api::get_property(value.value(), "order").unwrap();
+    let target = tsrun::api::get_property(value.value(), "target").unwrap();
+    let target = tsrun::RuntimeValue::unguarded(target);
+    (
+        order.as_str().unwrap_or_default().to_string(),
+        interp.to_canonical_json(&target).unwrap(),
+    )
+}
+
+#[test]
+fn test_symbol_key_enumeration_is_identical_across_runs() {
+    let (order, json) = symbol_key_run();
+    for _ in 0..3 {
+        assert_eq!(symbol_key_run(), (order.clone(), json.clone()));
+    }
+
+    // Insertion order, with the re-added key last
+    let symbols = order.split('|').next().unwrap_or_default();
+    assert!(
+        symbols.starts_with("zeta:Symbol(zeta),undefined:Symbol(zeta),alpha:Symbol(alpha)"),
+        "{}",
+        symbols
+    );
+    assert!(
+        symbols.ends_with("undefined:Symbol(),mid:Symbol(mid)"),
+        "{}",
+        symbols
+    );
+    assert!(order.split('|').all(|keys| keys.ends_with(symbols)));
+    // Symbols are skipped
+    assert_eq!(json, r#"{"plain":1}"#);
+}
+
+#[test]
+fn test_symbol_debug_shows_id_without_description() {
+    let anonymous = eval("Symbol()");
+    let debug = format!("{:?}", *anonymous);
+    assert!(debug.starts_with("Symbol()#"), "{}", debug);
+    assert_eq!(format!("{:?}", *eval("Symbol()")), debug);
+    assert_eq!(format!("{:?}", *eval("Symbol('tag')")), "Symbol(tag)");
+}
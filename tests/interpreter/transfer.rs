@@ -0,0 +1,206 @@
+//! Tests for Interpreter::import_value, copying values between interpreters
+
+use super::run;
+use tsrun::{Interpreter, JsValue, RuntimeValue, StepResult};
+
+/// Run `source` to completion in `interp` and return its value
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn eval_in(interp: &mut Interpreter, source: &str) -> RuntimeValue {
+    match run(interp, source, None).unwrap() {
+        StepResult::Complete(value) => value,
+        other => panic!("Expected completion, got {:?}", other),
+    }
+}
+
+/// Call the script function `name` of `interp` with `value`
+#[allow(clippy::unwrap_used)]
+fn call_global(interp: &mut Interpreter, name: &str, value: &RuntimeValue) -> RuntimeValue {
+    let global = JsValue::Object(interp.global.clone());
+    tsrun::api::set_property(&global, "__imported", value.value().clone()).unwrap();
+    eval_in(interp, &format!("{}(__imported)", name))
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_import_cyclic_value_survives_source_drop() {
+    let mut source = Interpreter::new();
+    let value = eval_in(
+        &mut source,
+        r#"
+        const shared = { label: "shared" };
+        const root: any = {
+            name: "worker",
+            when: new Date(86400000),
+            index: new Map<any, any>([["a", shared], [shared, [1, 2]]]),
+            tags: new Set(["x", "y"]),
+            list: [shared, shared],
+        };
+        root.self = root;
+        root.index.set("root", root);
+        root
+    "#,
+    );
+
+    let mut dest = Interpreter::new();
+    let copy = dest.import_value(&source, &value).unwrap();
+    drop(value);
+    drop(source);
+
+    eval_in(
+        &mut dest,
+        r#"
+        function inspect(r: any) {
+            const shared = r.index.get("a");
+            return [
+                r.self === r,
+                r.index.get("root") === r,
+                r.list[0] === r.list[1] && r.list[0] === shared,
+                r.index.get(shared).join("+"),
+                r.when instanceof Date && r.when.toISOString(),
+                r.index instanceof Map && r.tags instanceof Set,
+                [...r.tags].join(""),
+                shared.label,
+            ].join();
+        }
+        function mutate(r: any) {
+            r.name = "aggregated";
+            r.index.get("a").label = "changed";
+            r.tags.add("z");
+            r.when.setTime(0);
+            return [r.name, r.list[0].label, r.tags.size, r.when.getTime()].join();
+        }
+    "#,
+    );
+    assert_eq!(
+        call_global(&mut dest, "inspect", &copy),
+        JsValue::from("true,true,true,1+2,1970-01-02T00:00:00.000Z,true,xy,shared")
+    );
+    assert_eq!(
+        call_global(&mut dest, "mutate", &copy),
+        JsValue::from("aggregated,changed,3,0")
+    );
+
+    // Collecting in the destination keeps the guarded copy intact
+    dest.collect();
+    assert_eq!(
+        call_global(&mut dest, "inspect", &copy),
+        JsValue::from("true,true,true,1+2,1970-01-01T00:00:00.000Z,true,xyz,changed")
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_import_copy_is_independent_of_source() {
+    let mut source = Interpreter::new();
+    eval_in(
+        &mut source,
+        "var config: any = { retries: 3, hosts: ['a'] }; function bump() { config.retries++; config.hosts.push('b'); }",
+    );
+    let value = eval_in(&mut source, "config");
+
+    let mut dest = Interpreter::new();
+    let copy = dest.import_value(&source, &value).unwrap();
+    eval_in(&mut source, "bump()");
+
+    let json = tsrun::js_value_to_json(copy.value()).unwrap();
+    assert_eq!(json, serde_json::json!({ "retries": 3, "hosts": ["a"] }));
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_import_regexp_and_error() {
+    let mut source = Interpreter::new();
+    let value = eval_in(
+        &mut source,
+        "[/ab+c/gi, new TypeError('bad input'), new Boolean(false), 'plain']",
+    );
+
+    let mut dest = Interpreter::new();
+    let copy = dest.import_value(&source, &value).unwrap();
+    drop(source);
+
+    eval_in(
+        &mut dest,
+        r#"
+        function describe(v: any) {
+            const [re, err, flag, s] = v;
+            return [
+                re.test("xABBC"), re.flags, err instanceof TypeError, err.message,
+                typeof flag, flag.valueOf(), s,
+            ].join();
+        }
+    "#,
+    );
+    assert_eq!(
+        call_global(&mut dest, "describe", &copy),
+        JsValue::from("true,gi,true,bad input,object,false,plain")
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_import_error_cause_and_aggregate_errors() {
+    let mut source = Interpreter::new();
+    let value = eval_in(
+        &mut source,
+        r#"new AggregateError([new RangeError("a")], "both", { cause: new TypeError("root") })"#,
+    );
+
+    let mut dest = Interpreter::new();
+    let copy = dest.import_value(&source, &value).unwrap();
+    drop(source);
+
+    eval_in(
+        &mut dest,
+        r#"
+        function describe(e: any) {
+            return [
+                e instanceof AggregateError, e.cause instanceof TypeError, e.cause.message,
+                e.errors[0] instanceof RangeError, Object.keys(e).includes("cause"),
+            ].join();
+        }
+    "#,
+    );
+    assert_eq!(
+        call_global(&mut dest, "describe", &copy),
+        JsValue::from("true,true,root,true,false")
+    );
+}
+
+#[test]
+#[allow(clippy::panic)]
+fn test_import_rejects_functions_and_proxies() {
+    let mut source = Interpreter::new();
+    let mut dest = Interpreter::new();
+    let cases = [
+        (
+            "({ run() {} })",
+            "Function cannot be transferred between interpreters",
+        ),
+        (
+            "[new Proxy({}, {})]",
+            "Proxy cannot be transferred between interpreters",
+        ),
+        (
+            "({ s: Symbol('x') })",
+            "Symbol cannot be transferred between interpreters",
+        ),
+        (
+            "({ get x() { return 1; } })",
+            "Accessor property 'x' cannot be transferred between interpreters",
+        ),
+    ];
+    for (source_text, expected) in cases {
+        let value = eval_in(&mut source, source_text);
+        match dest.import_value(&source, &value) {
+            Err(tsrun::JsError::TypeError { message, .. }) => {
+                assert_eq!(message, expected, "importing {}", source_text)
+            }
+            Err(other) => panic!(
+                "Expected TypeError importing {}, got {}",
+                source_text, other
+            ),
+            Ok(_) => panic!("Expected TypeError importing {}", source_text),
+        }
+    }
+}
@@ -726,6 +726,111 @@ fn test_class_parameter_properties_with_enum_default() {
     );
 }
 
+#[test]
+fn test_class_parameter_properties_all_modifiers() {
+    assert_eq!(
+        eval(
+            r#"
+            class Account {
+                constructor(
+                    public owner: string,
+                    private balance: number = 100,
+                    protected currency = "EUR",
+                    readonly id: number = 7,
+                    note: string = "",
+                ) {}
+            }
+
+            const a: any = new Account("ann");
+            [a.owner, a.balance, a.currency, a.id, "note" in a].join()
+        "#
+        ),
+        JsValue::from("ann,100,EUR,7,false")
+    );
+}
+
+#[test]
+fn test_class_parameter_properties_after_super() {
+    // A derived constructor assigns its parameter properties and fields once
+    // super() returns, so the base constructor sees none of them
+    assert_eq!(
+        eval(
+            r#"
+            class Base {
+                constructor(public x: number) {
+                    (this as any).seen = Object.keys(this).join("|");
+                }
+            }
+            class Derived extends Base {
+                tag = "d";
+                constructor(public y: number, x: number) {
+                    super(x);
+                }
+            }
+
+            const d: any = new Derived(1, 2);
+            [d.seen, d.x, d.y, d.tag].join()
+        "#
+        ),
+        JsValue::from("x,2,1,d")
+    );
+}
+
+#[test]
+fn test_class_parameter_properties_super_in_arrow() {
+    assert_eq!(
+        eval(
+            r#"
+            class Base {}
+            class Derived extends Base {
+                constructor(public y: number) {
+                    const init = () => super();
+                    init();
+                }
+            }
+            new Derived(5).y
+        "#
+        ),
+        JsValue::Number(5.0)
+    );
+}
+
+#[test]
+fn test_class_definite_assignment_assertion() {
+    assert_eq!(
+        eval(
+            r#"
+            class Lazy {
+                value!: number;
+                init() { this.value = 42; return this; }
+            }
+            const l = new Lazy();
+            ("value" in l) + ":" + l.init().value
+        "#
+        ),
+        JsValue::from("true:42")
+    );
+}
+
+#[test]
+fn test_class_index_signature() {
+    assert_eq!(
+        eval(
+            r#"
+            class Bag {
+                [key: string]: any;
+                readonly [index: number]: string;
+                size = 1;
+            }
+            const b = new Bag();
+            b.extra = 2;
+            b.size + b.extra
+        "#
+        ),
+        JsValue::Number(3.0)
+    );
+}
+
 #[test]
 fn test_class_static_types() {
     assert_eq!(
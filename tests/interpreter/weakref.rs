@@ -0,0 +1,131 @@
+//! WeakRef and FinalizationRegistry tests
+
+use super::{create_test_runtime, eval, run, throws_error};
+use tsrun::{Interpreter, JsValue, StepResult};
+
+/// Run `source` in `interp` and return its completion value
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn run_value(interp: &mut Interpreter, source: &str) -> JsValue {
+    match run(interp, source, None).unwrap() {
+        StepResult::Complete(value) => (*value).clone(),
+        other => panic!("Expected Complete, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_weakref_deref_live_target() {
+    assert_eq!(
+        eval(
+            r#"
+            const target = { name: "conn" };
+            const ref = new WeakRef(target);
+            ref.deref() === target && ref.deref().name === "conn"
+        "#
+        ),
+        JsValue::Boolean(true)
+    );
+    assert_eq!(
+        eval("Object.prototype.toString.call(new WeakRef({}))"),
+        JsValue::from("[object WeakRef]")
+    );
+}
+
+#[test]
+fn test_weakref_deref_after_collection() {
+    let mut interp = create_test_runtime();
+    run_value(
+        &mut interp,
+        r#"
+        globalThis.anchor = { name: "kept" };
+        globalThis.kept = new WeakRef(anchor);
+        globalThis.lost = new WeakRef({ name: "dropped" });
+        "#,
+    );
+    interp.collect();
+    assert_eq!(
+        run_value(
+            &mut interp,
+            "[lost.deref() === undefined, kept.deref() === anchor].join()"
+        ),
+        JsValue::from("true,true")
+    );
+}
+
+#[test]
+fn test_weakref_requires_object_target() {
+    assert!(throws_error("new WeakRef(1)", "TypeError"));
+    assert!(throws_error(
+        "WeakRef.prototype.deref.call({})",
+        "TypeError"
+    ));
+}
+
+#[test]
+fn test_finalization_callback_runs_on_step() {
+    let mut interp = create_test_runtime();
+    run_value(
+        &mut interp,
+        r#"
+        globalThis.log = [];
+        globalThis.registry = new FinalizationRegistry(held => log.push(held));
+        globalThis.keep = {};
+        registry.register({ socket: 3 }, "conn-1");
+        registry.register(keep, "conn-2");
+        "#,
+    );
+    interp.collect();
+
+    // Queued by the collection, but not run by it
+    assert_eq!(interp.pending_finalizations(), 1);
+    assert!(matches!(interp.step(), Ok(StepResult::Continue)));
+    assert_eq!(interp.pending_finalizations(), 0);
+    assert!(matches!(interp.step(), Ok(StepResult::Done)));
+
+    assert_eq!(
+        run_value(&mut interp, "log.join()"),
+        JsValue::from("conn-1")
+    );
+}
+
+#[test]
+fn test_finalization_unregister() {
+    let mut interp = create_test_runtime();
+    assert_eq!(
+        run_value(
+            &mut interp,
+            r#"
+            globalThis.log = [];
+            globalThis.registry = new FinalizationRegistry(held => log.push(held));
+            globalThis.token = {};
+            registry.register({}, "unregistered", token);
+            registry.register({}, "collected");
+            [registry.unregister(token), registry.unregister(token)].join()
+            "#,
+        ),
+        JsValue::from("true,false")
+    );
+    interp.collect();
+    assert_eq!(interp.pending_finalizations(), 1);
+    assert!(matches!(interp.step(), Ok(StepResult::Continue)));
+    assert_eq!(
+        run_value(&mut interp, "log.join()"),
+        JsValue::from("collected")
+    );
+}
+
+#[test]
+fn test_finalization_register_validates_arguments() {
+    assert!(throws_error("new FinalizationRegistry(1)", "TypeError"));
+    assert!(throws_error(
+        "new FinalizationRegistry(() => {}).register(1, 'held')",
+        "TypeError"
+    ));
+    assert!(throws_error(
+        "const o = {}; new FinalizationRegistry(() => {}).register(o, o)",
+        "TypeError"
+    ));
+    assert!(throws_error(
+        "new FinalizationRegistry(() => {}).register({}, 'held', 'token')",
+        "TypeError"
+    ));
+}
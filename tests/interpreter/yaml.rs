@@ -0,0 +1,192 @@
+//! Tests for YAML.stringify and Interpreter::to_yaml
+
+use super::{eval, run};
+use tsrun::{Interpreter, JsValue, StepResult};
+
+#[test]
+fn test_yaml_stringify_two_documents() {
+    assert_eq!(
+        eval(
+            r#"
+            const deployment = {
+                apiVersion: "apps/v1",
+                kind: "Deployment",
+                metadata: { name: "web", labels: { app: "web" } },
+                spec: {
+                    replicas: 3,
+                    containers: [
+                        { name: "web", image: "nginx:1.25", ports: [{ containerPort: 80 }] },
+                    ],
+                },
+            };
+            const service = {
+                apiVersion: "v1",
+                kind: "Service",
+                metadata: { name: "web" },
+                spec: { selector: { app: "web" }, ports: [80, 443] },
+            };
+            YAML.stringify([deployment, service])
+        "#
+        ),
+        JsValue::from(
+            "apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+  labels:
+    app: web
+spec:
+  replicas: 3
+  containers:
+    - name: web
+      image: nginx:1.25
+      ports:
+        - containerPort: 80
+---
+apiVersion: v1
+kind: Service
+metadata:
+  name: web
+spec:
+  selector:
+    app: web
+  ports:
+    - 80
+    - 443
+"
+        )
+    );
+}
+
+#[test]
+fn test_yaml_stringify_block_scalars() {
+    assert_eq!(
+        eval(
+            r#"
+            YAML.stringify({
+                script: "set -e\nmake build\n",
+                message: "line one\nline two",
+                padded: "keep\n\n",
+                single: "one line",
+            })
+        "#
+        ),
+        JsValue::from(
+            "script: |
+  set -e
+  make build
+message: |-
+  line one
+  line two
+padded: |+
+  keep
+
+single: one line
+"
+        )
+    );
+}
+
+#[test]
+fn test_yaml_stringify_quotes_ambiguous_scalars() {
+    assert_eq!(
+        eval(
+            r#"
+            YAML.stringify({
+                "8080": "http",
+                name: "api",
+                version: "1.0",
+                enabled: "true",
+                empty: "",
+                note: "a: b",
+                quote: 'say "hi"',
+                count: 2,
+            })
+        "#
+        ),
+        JsValue::from(
+            "\"8080\": http
+name: api
+version: \"1.0\"
+enabled: \"true\"
+empty: \"\"
+note: \"a: b\"
+quote: say \"hi\"
+count: 2
+"
+        )
+    );
+}
+
+#[test]
+fn test_yaml_stringify_json_semantics() {
+    assert_eq!(
+        eval(
+            r#"
+            YAML.stringify({
+                skipped: undefined,
+                fn: () => 1,
+                when: new Date(0),
+                list: [undefined, null, NaN],
+                nested: { empty: {}, none: [] },
+            }, { documents: false })
+        "#
+        ),
+        JsValue::from(
+            "when: \"1970-01-01T00:00:00.000Z\"
+list:
+  - null
+  - null
+  - null
+nested:
+  empty: {}
+  none: []
+"
+        )
+    );
+    assert_eq!(
+        eval(r#"YAML.stringify([[1, 2], [3]], { documents: false })"#),
+        JsValue::from("- - 1\n  - 2\n- - 3\n")
+    );
+    assert_eq!(
+        eval(
+            r#"let caught = ""; const a: any = {}; a.self = a; try { YAML.stringify(a); } catch (e) { caught = e.message; } caught"#
+        ),
+        JsValue::from("Converting circular structure to YAML")
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_to_yaml_host_api() {
+    let mut interp = Interpreter::new();
+    let result = run(
+        &mut interp,
+        r#"[{ kind: "ConfigMap", data: { "app.yaml": "port: 80\n" } }, { kind: "Secret" }]"#,
+        None,
+    )
+    .unwrap();
+    let StepResult::Complete(value) = result else {
+        panic!("Expected Complete");
+    };
+
+    assert_eq!(
+        interp.to_yaml(&value).unwrap(),
+        "kind: ConfigMap\ndata:\n  app.yaml: |\n    port: 80\n---\nkind: Secret\n"
+    );
+}
+
+#[test]
+fn test_yaml_stringify_errors_option() {
+    assert_eq!(
+        eval(
+            r#"
+            const e = new AggregateError([new Error("a")], "all", { cause: "why" });
+            YAML.stringify({ error: e }, { errors: "withoutStack" })
+        "#
+        ),
+        JsValue::from(
+            "error:\n  name: AggregateError\n  message: all\n  cause: why\n  errors:\n    - name: Error\n      message: a\n"
+        )
+    );
+}
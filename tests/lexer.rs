@@ -5,6 +5,7 @@
 use tsrun::JsString;
 use tsrun::lexer::{Lexer, TokenKind};
 use tsrun::string_dict::StringDict;
+use tsrun::token_stream::SyntaxKind;
 
 /// Helper to create JsString from &str in tests
 fn s(value: &str) -> JsString {
@@ -133,6 +134,18 @@ fn test_identifiers() {
     );
 }
 
+#[test]
+fn test_non_ascii_identifiers() {
+    assert_eq!(
+        lex("café π e\u{301}x"),
+        vec![
+            TokenKind::Identifier(JsString::from("café")),
+            TokenKind::Identifier(JsString::from("π")),
+            TokenKind::Identifier(JsString::from("e\u{301}x")),
+        ]
+    );
+}
+
 #[test]
 fn test_comments() {
     assert_eq!(
@@ -143,6 +156,20 @@ fn test_comments() {
         lex("1 /* comment */ 2"),
         vec![TokenKind::Number(1.0), TokenKind::Number(2.0)]
     );
+    // Block comments don't nest
+    assert_eq!(
+        lex("1 /* /* */ 2"),
+        vec![TokenKind::Number(1.0), TokenKind::Number(2.0)]
+    );
+    // Unicode space separators are whitespace
+    assert_eq!(
+        lex("1\u{2003}2\u{3000}3"),
+        vec![
+            TokenKind::Number(1.0),
+            TokenKind::Number(2.0),
+            TokenKind::Number(3.0)
+        ]
+    );
 }
 
 #[test]
@@ -514,3 +541,215 @@ fn test_unicode_escape_id_continue_middle_dot() {
         vec![TokenKind::Identifier(JsString::from("a·"))]
     );
 }
+
+#[test]
+fn test_leading_bom_is_skipped() {
+    assert_eq!(lex("\u{FEFF}"), vec![]);
+    assert_eq!(
+        lex("\u{FEFF}x"),
+        vec![TokenKind::Identifier(JsString::from("x"))]
+    );
+}
+
+#[test]
+fn test_hashbang_is_comment() {
+    assert_eq!(lex("#!/usr/bin/env tsrun"), vec![]);
+    assert_eq!(
+        lex("#!/usr/bin/env tsrun\n42"),
+        vec![TokenKind::Number(42.0)]
+    );
+    assert_eq!(
+        lex("\u{FEFF}#!/usr/bin/env tsrun\r\n42"),
+        vec![TokenKind::Number(42.0)]
+    );
+}
+
+#[test]
+fn test_hashbang_only_at_start() {
+    // Anywhere else `#!` lexes as ordinary tokens, which the parser rejects
+    assert_eq!(
+        lex(" #!x"),
+        vec![
+            TokenKind::Hash,
+            TokenKind::Bang,
+            TokenKind::Identifier(JsString::from("x"))
+        ]
+    );
+    assert_eq!(
+        lex("1\n#!x"),
+        vec![
+            TokenKind::Number(1.0),
+            TokenKind::Hash,
+            TokenKind::Bang,
+            TokenKind::Identifier(JsString::from("x"))
+        ]
+    );
+}
+
+#[test]
+fn test_source_prefix_keeps_positions() {
+    let mut dict = StringDict::new();
+    let source = "\u{FEFF}#!/usr/bin/env tsrun\n  answer";
+    let mut lexer = Lexer::new(source, &mut dict);
+    let token = lexer.next_token();
+    assert_eq!(token.kind, TokenKind::Identifier(JsString::from("answer")));
+    assert_eq!((token.span.line, token.span.column), (2, 3));
+    // Offsets index into the original source, BOM included
+    assert_eq!(source.get(token.span.start..token.span.end), Some("answer"));
+
+    let mut dict = StringDict::new();
+    let mut lexer = Lexer::new("\u{FEFF}answer", &mut dict);
+    let token = lexer.next_token();
+    assert_eq!((token.span.line, token.span.column), (1, 1));
+    assert_eq!(token.span.start, '\u{FEFF}'.len_utf8());
+}
+
+/// Highlighting tokens as (kind, source text, invalid), whitespace and
+/// newlines left out
+fn highlight(source: &str) -> Vec<(SyntaxKind, &str, bool)> {
+    Lexer::tokens(source)
+        .filter(|t| !matches!(t.kind, SyntaxKind::Whitespace | SyntaxKind::Newline))
+        .map(|t| {
+            (
+                t.kind,
+                source.get(t.span.start..t.span.end).unwrap_or(""),
+                t.invalid,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_token_stream_regex_vs_division() {
+    use SyntaxKind::*;
+    assert_eq!(
+        highlight("a / b / c; x = /b/g; return /[/]+/.test(s) / 2"),
+        vec![
+            (Identifier, "a", false),
+            (Punctuation, "/", false),
+            (Identifier, "b", false),
+            (Punctuation, "/", false),
+            (Identifier, "c", false),
+            (Punctuation, ";", false),
+            (Identifier, "x", false),
+            (Punctuation, "=", false),
+            (RegExp, "/b/g", false),
+            (Punctuation, ";", false),
+            (Keyword, "return", false),
+            (RegExp, "/[/]+/", false),
+            (Punctuation, ".", false),
+            (Identifier, "test", false),
+            (Punctuation, "(", false),
+            (Identifier, "s", false),
+            (Punctuation, ")", false),
+            (Punctuation, "/", false),
+            (Number, "2", false),
+        ]
+    );
+    assert_eq!(
+        highlight("n /= 2 // half\n/* a */ (1)/2"),
+        vec![
+            (Identifier, "n", false),
+            (Punctuation, "/=", false),
+            (Number, "2", false),
+            (LineComment, "// half", false),
+            (BlockComment, "/* a */", false),
+            (Punctuation, "(", false),
+            (Number, "1", false),
+            (Punctuation, ")", false),
+            (Punctuation, "/", false),
+            (Number, "2", false),
+        ]
+    );
+}
+
+#[test]
+fn test_token_stream_nested_templates() {
+    use SyntaxKind::*;
+    assert_eq!(
+        highlight("`a${ {k: `in${x}`}.k }b${y}c`"),
+        vec![
+            (TemplateHead, "`a${", false),
+            (Punctuation, "{", false),
+            (Identifier, "k", false),
+            (Punctuation, ":", false),
+            (TemplateHead, "`in${", false),
+            (Identifier, "x", false),
+            (TemplateTail, "}`", false),
+            (Punctuation, "}", false),
+            (Punctuation, ".", false),
+            (Identifier, "k", false),
+            (TemplateMiddle, "}b${", false),
+            (Identifier, "y", false),
+            (TemplateTail, "}c`", false),
+        ]
+    );
+    assert_eq!(
+        highlight(r"`ok\u{1F600}` `bad\x4`"),
+        vec![
+            (TemplateNoSub, r"`ok\u{1F600}`", false),
+            (TemplateNoSub, r"`bad\x4`", true),
+        ]
+    );
+}
+
+#[test]
+fn test_token_stream_continues_after_errors() {
+    use SyntaxKind::*;
+    let source = "const s = \"open\nlet n = 0777 ¤ 1n;\n/* never closed";
+    assert_eq!(
+        highlight(source),
+        vec![
+            (Keyword, "const", false),
+            (Identifier, "s", false),
+            (Punctuation, "=", false),
+            (String, "\"open", true),
+            (Keyword, "let", false),
+            (Identifier, "n", false),
+            (Punctuation, "=", false),
+            (Number, "0777", true),
+            (Error, "¤", true),
+            (Number, "1n", false),
+            (Punctuation, ";", false),
+            (BlockComment, "/* never closed", true),
+        ]
+    );
+
+    // The tokens cover the source exactly, with lines and columns
+    let tokens: Vec<_> = Lexer::tokens(source).collect();
+    let mut end = 0;
+    for token in &tokens {
+        assert_eq!(token.span.start, end);
+        end = token.span.end;
+    }
+    assert_eq!(end, source.len());
+    let newline = tokens.iter().position(|t| t.kind == Newline).unwrap();
+    assert_eq!(tokens[newline].span.start, 15);
+    let let_token = &tokens[newline + 1];
+    assert_eq!((let_token.span.line, let_token.span.column), (2, 1));
+}
+
+#[test]
+fn test_token_stream_keywords_and_prefix() {
+    use SyntaxKind::*;
+    assert_eq!(
+        highlight("#!/usr/bin/env tsrun\nif (this) y\\u0061 = null; class C { #p }"),
+        vec![
+            (LineComment, "#!/usr/bin/env tsrun", false),
+            (Keyword, "if", false),
+            (Punctuation, "(", false),
+            (Keyword, "this", false),
+            (Punctuation, ")", false),
+            (Identifier, "y\\u0061", false),
+            (Punctuation, "=", false),
+            (Keyword, "null", false),
+            (Punctuation, ";", false),
+            (Keyword, "class", false),
+            (Identifier, "C", false),
+            (Punctuation, "{", false),
+            (Punctuation, "#", false),
+            (Identifier, "p", false),
+            (Punctuation, "}", false),
+        ]
+    );
+}
@@ -3,10 +3,12 @@
 //! These tests verify that the parser correctly parses TypeScript/JavaScript source into AST.
 
 use tsrun::ast::{
-    ClassMember, Expression, MemberProperty, MethodKind, ObjectPropertyKey, Program, Statement,
+    BinaryOp, ClassMember, Expression, MemberProperty, MethodKind, ObjectPropertyKey, Program,
+    Statement,
 };
 use tsrun::parser::Parser;
 use tsrun::string_dict::StringDict;
+use tsrun::{JsError, Lang};
 
 #[allow(clippy::unwrap_used)]
 fn parse(source: &str) -> Program {
@@ -1741,3 +1743,225 @@ fn test_object_with_multiple_contextual_keywords() {
     );
     assert_eq!(prog.body.len(), 1);
 }
+
+#[test]
+fn test_hashbang_and_bom_prefix() {
+    assert_eq!(parse("#!/usr/bin/env tsrun").body.len(), 0);
+    assert_eq!(
+        parse("\u{FEFF}#!/usr/bin/env tsrun\nlet x = 1;").body.len(),
+        1
+    );
+    assert_eq!(parse("\u{FEFF}let x = 1;").body.len(), 1);
+}
+
+#[test]
+fn test_hashbang_not_at_start_is_error() {
+    let mut dict = StringDict::new();
+    let result = Parser::new("let x = 1;\n#!/usr/bin/env tsrun", &mut dict).parse_program();
+    assert!(result.is_err());
+    let mut dict = StringDict::new();
+    let result = Parser::new(" #!/usr/bin/env tsrun", &mut dict).parse_program();
+    assert!(result.is_err());
+}
+
+/// Parse `source`, expecting an unsupported-syntax error, and return its construct
+#[allow(clippy::panic)]
+fn unsupported_construct(source: &str) -> String {
+    let mut dict = StringDict::new();
+    match Parser::new(source, &mut dict).parse_program() {
+        Err(JsError::UnsupportedSyntax { construct, .. }) => construct.to_string(),
+        other => panic!(
+            "Expected UnsupportedSyntax for {:?}, got {:?}",
+            source, other
+        ),
+    }
+}
+
+#[test]
+fn test_unsupported_jsx_element() {
+    assert_eq!(
+        unsupported_construct(r#"const a = <Button label="ok" />;"#),
+        "JSX"
+    );
+    assert_eq!(unsupported_construct("const a = <div>hello</div>;"), "JSX");
+}
+
+#[test]
+fn test_unsupported_jsx_fragment() {
+    assert_eq!(
+        unsupported_construct("const a = <>hello</>;"),
+        "JSX fragment syntax"
+    );
+}
+
+#[test]
+fn test_unsupported_do_expression() {
+    assert_eq!(
+        unsupported_construct("const x = do { 1 };"),
+        "a do expression"
+    );
+}
+
+#[test]
+fn test_unsupported_pipeline_operator() {
+    assert_eq!(
+        unsupported_construct("const y = value |> double;"),
+        "the pipeline operator `|>`"
+    );
+}
+
+#[test]
+fn test_unsupported_hashbang_mid_file() {
+    assert_eq!(
+        unsupported_construct("let x = 1;\n#!/usr/bin/env tsrun"),
+        "a `#!` hashbang after the first line"
+    );
+}
+
+#[test]
+fn test_unsupported_object_literal_decorator() {
+    assert_eq!(
+        unsupported_construct("const o = { @logged run() {} };"),
+        "a decorator on an object literal member"
+    );
+}
+
+#[test]
+#[allow(clippy::panic)]
+fn test_unknown_syntax_keeps_generic_error() {
+    let mut dict = StringDict::new();
+    let result = Parser::new("let z = 1 +;", &mut dict).parse_program();
+    assert!(
+        matches!(result, Err(JsError::SyntaxError { .. })),
+        "{:?}",
+        result
+    );
+}
+
+#[test]
+fn test_import_attributes_are_ignored() {
+    let prog = parse(
+        r#"
+        import config from "./config.json" with { type: "json" };
+        import legacy from "./legacy.json" assert { type: "json" };
+        import "./side-effect.css" with { type: "css" };
+        export { a } from "./a.json" with { "type": "json" };
+        export * from "./b.json" with { type: "json" };
+        "#,
+    );
+    assert_eq!(prog.body.len(), 5);
+}
+
+fn parse_as(source: &str, lang: Lang) -> Result<Program, JsError> {
+    let mut dict = StringDict::new();
+    Parser::new(source, &mut dict)
+        .with_lang(lang)
+        .parse_program()
+}
+
+#[allow(clippy::panic)]
+fn only_expression(prog: &Program) -> &Expression {
+    let [Statement::Expression(stmt)] = &*prog.body else {
+        panic!("Expected one expression statement, got {:?}", prog.body);
+    };
+    &stmt.expression
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::panic)]
+fn test_angle_brackets_by_lang() {
+    // TypeScript: a call with type arguments
+    let prog = parse_as("a < b > (c);", Lang::TypeScript).unwrap();
+    let Expression::Call(call) = only_expression(&prog) else {
+        panic!("Expected a call in TypeScript");
+    };
+    assert!(call.type_arguments.is_some());
+
+    // JavaScript: (a < b) > c
+    let prog = parse_as("a < b > (c);", Lang::JavaScript).unwrap();
+    let Expression::Binary(outer) = only_expression(&prog) else {
+        panic!("Expected a comparison in JavaScript");
+    };
+    assert_eq!(outer.operator, BinaryOp::Gt);
+    let Expression::Binary(inner) = outer.left.as_ref() else {
+        panic!("Expected a nested comparison");
+    };
+    assert_eq!(inner.operator, BinaryOp::Lt);
+
+    // No angle-bracket assertion either
+    let prog = parse_as("x = <y>z;", Lang::TypeScript).unwrap();
+    assert_eq!(prog.body.len(), 1);
+    assert!(parse_as("x = <y>z;", Lang::JavaScript).is_err());
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_typescript_contextual_keywords_are_identifiers_in_javascript() {
+    let source = r#"
+        let type = 1, interface = 2, namespace = 3, declare = 4, readonly = 5;
+        let as = type + interface;
+        let abstract = { is: 1, keyof: 2, infer: 3 };
+        class C { public = 1; private() {} }
+        import type from "./t.js";
+    "#;
+    let prog = parse_as(source, Lang::JavaScript).unwrap();
+    assert_eq!(prog.body.len(), 5);
+}
+
+#[test]
+fn test_typescript_only_syntax_is_rejected_in_javascript() {
+    for source in [
+        "let x: number = 1;",
+        "function f(a: string) {}",
+        "function f(): void {}",
+        "function f(a?) {}",
+        "function f<T>(a) {}",
+        "const f = (a: number) => a;",
+        "enum Color { Red }",
+        "const enum Color { Red }",
+        "namespace N { }",
+        "type T = number;",
+        "interface I { }",
+        "let y = x as any;",
+        "let y = x!;",
+        "class C<T> { }",
+        "class C { x!: number; }",
+        "class C { private x = 1; }",
+        "class C implements I { }",
+    ] {
+        assert!(
+            parse_as(source, Lang::TypeScript).is_ok(),
+            "TypeScript should accept {:?}",
+            source
+        );
+        assert!(
+            matches!(
+                parse_as(source, Lang::JavaScript),
+                Err(JsError::SyntaxError { .. })
+            ),
+            "JavaScript should reject {:?}",
+            source
+        );
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_as_after_line_break_starts_a_new_statement() {
+    let source = "let as = 1;\nlet x = as\nas = 2";
+    for lang in [Lang::TypeScript, Lang::JavaScript] {
+        let prog = parse_as(source, lang).unwrap();
+        assert_eq!(prog.body.len(), 3, "{:?}", lang);
+    }
+    // `x \n as T` isn't an assertion in TypeScript either
+    let prog = parse_as("let x = y\nas\nz", Lang::TypeScript).unwrap();
+    assert_eq!(prog.body.len(), 3);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_conditional_with_parenthesized_consequent_in_javascript() {
+    // `(b) :` isn't an arrow return type in JavaScript
+    let prog = parse_as("let r = a ? (b) : c => c;", Lang::JavaScript).unwrap();
+    assert_eq!(prog.body.len(), 1);
+}
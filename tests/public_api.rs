@@ -0,0 +1,315 @@
+//! Snapshot of the public API.
+//!
+//! Lists the items the crate root exports and, for everything the stable
+//! `tsrun::embed` module re-exports, their fields, variants and methods. The
+//! listing is compared with `tests/fixtures/public_api.txt`, so a change to
+//! that surface fails here until the snapshot is updated on purpose:
+//!
+//! ```text
+//! UPDATE_PUBLIC_API=1 cargo test --test public_api
+//! ```
+//!
+//! The listing is read from the source with rustfmt's layout in mind, not
+//! from rustdoc, so it needs no extra tooling.
+
+#![allow(clippy::panic, clippy::indexing_slicing)]
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SNAPSHOT: &str = "tests/fixtures/public_api.txt";
+
+/// One `pub` declaration, with the attributes that change its visibility
+struct Item {
+    /// Type the item belongs to, for fields, variants and methods
+    owner: Option<String>,
+    kind: &'static str,
+    name: String,
+    signature: String,
+}
+
+fn root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn read(path: &Path) -> String {
+    fs::read_to_string(path).unwrap_or_else(|e| panic!("read {}: {}", path.display(), e))
+}
+
+fn collapse(text: &str) -> String {
+    let joined = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    joined
+        .replace("( ", "(")
+        .replace(" )", ")")
+        .replace(", )", ")")
+        .replace(",)", ")")
+}
+
+/// The identifier that follows `keyword` in `decl`
+fn name_after(decl: &str, keyword: &str) -> String {
+    decl.split_once(keyword)
+        .map(|(_, rest)| rest)
+        .unwrap_or_default()
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect()
+}
+
+/// Read a declaration starting at `lines[start]` up to the first line ending
+/// in one of `terminators`
+fn declaration(lines: &[&str], start: usize, terminators: &[char]) -> (String, usize) {
+    let mut text = String::new();
+    let mut end = start;
+    for (i, line) in lines.iter().enumerate().skip(start) {
+        end = i;
+        let trimmed = line.trim_end();
+        text.push_str(trimmed);
+        text.push(' ');
+        if trimmed.ends_with(terminators) {
+            break;
+        }
+    }
+    let text = collapse(&text);
+    let text = text
+        .trim_end_matches(['{', ';', ','])
+        .trim_end()
+        .to_string();
+    (text, end)
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Collect the `pub` items of one source file, tracking which struct, enum
+/// or inherent impl each member one indentation level in belongs to
+fn scan(source: &str) -> Vec<Item> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut items = Vec::new();
+    let mut attrs: Vec<String> = Vec::new();
+    // Kind and name of the top-level block being read
+    let mut block: Option<(&'static str, String)> = None;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+        let level = indent(line);
+        i += 1;
+        if level > 4 || trimmed.is_empty() || trimmed.starts_with("//") {
+            if level > 4 {
+                attrs.clear();
+            }
+            continue;
+        }
+        if trimmed.starts_with("#[doc(hidden)]") || trimmed.starts_with("#[cfg(") {
+            attrs.push(trimmed.to_string());
+            continue;
+        }
+        if trimmed.starts_with("#[") {
+            continue;
+        }
+        let prefix = if attrs.is_empty() {
+            String::new()
+        } else {
+            format!("{} ", attrs.join(" "))
+        };
+        attrs.clear();
+
+        if level == 0 {
+            block = None;
+            if trimmed.starts_with('}') {
+                continue;
+            }
+            let terminators: &[char] = if trimmed.starts_with("pub use ") {
+                &[';']
+            } else {
+                &['{', ';']
+            };
+            let (decl, end) = declaration(&lines, i - 1, terminators);
+            i = end + 1;
+            if let Some(rest) = decl.strip_prefix("impl ") {
+                if !rest.contains(" for ") {
+                    block = Some(("impl", name_after(&format!(" {}", rest), " ")));
+                }
+                continue;
+            }
+            for (keyword, kind) in [
+                ("pub struct ", "struct"),
+                ("pub enum ", "enum"),
+                ("pub trait ", "trait"),
+                ("pub type ", "type"),
+                ("pub const ", "const"),
+                ("pub fn ", "fn"),
+                ("pub mod ", "mod"),
+                ("pub use ", "use"),
+            ] {
+                if !decl.starts_with(keyword) {
+                    continue;
+                }
+                let name = name_after(&decl, keyword);
+                if (kind == "struct" || kind == "enum") && lines[end].trim_end().ends_with('{') {
+                    block = Some((kind, name.clone()));
+                }
+                items.push(Item {
+                    owner: None,
+                    kind,
+                    name,
+                    signature: format!("{}{}", prefix, decl),
+                });
+                break;
+            }
+            continue;
+        }
+
+        let Some((block_kind, owner)) = &block else {
+            continue;
+        };
+        let member = match *block_kind {
+            "struct" if trimmed.starts_with("pub ") => {
+                let (decl, end) = declaration(&lines, i - 1, &[',']);
+                i = end + 1;
+                Some(("field", name_after(&decl, "pub "), decl))
+            }
+            "enum" if trimmed.starts_with(|c: char| c.is_ascii_uppercase()) => {
+                let (decl, end) = declaration(&lines, i - 1, &[',', '{']);
+                i = end + 1;
+                Some(("variant", name_after(&format!(" {}", decl), " "), decl))
+            }
+            "impl" if trimmed.starts_with("pub fn ") || trimmed.starts_with("pub const fn ") => {
+                let (decl, end) = declaration(&lines, i - 1, &['{', ';']);
+                i = end + 1;
+                Some(("method", name_after(&decl, "fn "), decl))
+            }
+            _ => None,
+        };
+        if let Some((kind, name, decl)) = member {
+            items.push(Item {
+                owner: Some(owner.clone()),
+                kind,
+                name,
+                signature: format!("{}{}", prefix, decl),
+            });
+        }
+    }
+    items
+}
+
+fn rust_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            rust_files(&path, out);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            out.push(path);
+        }
+    }
+}
+
+/// The names `tsrun::embed` re-exports
+fn embed_names() -> BTreeSet<String> {
+    scan(&read(&root().join("src/embed.rs")))
+        .into_iter()
+        .filter(|item| item.kind == "use")
+        .map(|item| {
+            item.signature
+                .rsplit("::")
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        })
+        .collect()
+}
+
+fn listing() -> String {
+    let embed = embed_names();
+    let mut lines = BTreeSet::new();
+
+    for item in scan(&read(&root().join("src/lib.rs"))) {
+        if item.owner.is_none() {
+            lines.insert(format!("root {}", item.signature));
+        }
+    }
+    for name in &embed {
+        lines.insert(format!("embed {}", name));
+    }
+
+    // Members of the embedded types, wherever they are declared
+    let mut files = vec![
+        root().join("src/lib.rs"),
+        root().join("src/api.rs"),
+        root().join("src/error.rs"),
+        root().join("src/value.rs"),
+    ];
+    rust_files(&root().join("src/interpreter"), &mut files);
+    for file in &files {
+        for item in scan(&read(file)) {
+            let listed = match &item.owner {
+                Some(owner) => embed.contains(owner),
+                None => item.kind != "use" && embed.contains(&item.name),
+            };
+            if listed {
+                let owner = item.owner.as_deref().unwrap_or("-");
+                lines.insert(format!("{} {} {}", item.kind, owner, item.signature));
+            }
+        }
+    }
+
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+#[test]
+fn test_public_api_matches_snapshot() {
+    let actual = listing();
+    let path = root().join(SNAPSHOT);
+    if std::env::var_os("UPDATE_PUBLIC_API").is_some() {
+        if let Err(e) = fs::write(&path, &actual) {
+            panic!("write {}: {}", path.display(), e);
+        }
+        return;
+    }
+
+    let expected = read(&path);
+    let expected_lines: BTreeSet<&str> = expected.lines().collect();
+    let actual_lines: BTreeSet<&str> = actual.lines().collect();
+    let mut diff = String::new();
+    for line in expected_lines.difference(&actual_lines) {
+        diff.push_str(&format!("- {}\n", line));
+    }
+    for line in actual_lines.difference(&expected_lines) {
+        diff.push_str(&format!("+ {}\n", line));
+    }
+    if !diff.is_empty() {
+        panic!(
+            "The public API changed:\n{}\nIf this is intended, run \
+             `UPDATE_PUBLIC_API=1 cargo test --test public_api` and commit {}",
+            diff, SNAPSHOT
+        );
+    }
+}
+
+#[test]
+fn test_embed_reexports_stable_items() {
+    let embed = embed_names();
+    for name in [
+        "Interpreter",
+        "InterpreterConfig",
+        "RuntimeValue",
+        "StepResult",
+        "Order",
+        "OrderResponse",
+        "ModulePath",
+        "InternalModule",
+        "JsError",
+    ] {
+        assert!(embed.contains(name), "tsrun::embed is missing {}", name);
+    }
+}
@@ -0,0 +1,86 @@
+// Minimal test262 harness, baked into examples/test262-runner.rs.
+// Provides the same assertion API as test262's harness/assert.js.
+
+function assert(mustBeTrue, message) {
+  if (mustBeTrue === true) {
+    return;
+  }
+  if (message === undefined) {
+    message = "Expected true but got " + assert._toString(mustBeTrue);
+  }
+  throw new Test262Error(message);
+}
+
+assert._isSameValue = function (a, b) {
+  if (a === b) {
+    // Distinguish +0 from -0
+    return a !== 0 || 1 / a === 1 / b;
+  }
+  // NaN is the only value not equal to itself
+  return a !== a && b !== b;
+};
+
+assert._toString = function (value) {
+  try {
+    if (value === 0 && 1 / value === -Infinity) {
+      return "-0";
+    }
+    return String(value);
+  } catch (err) {
+    if (err.name === "TypeError") {
+      return Object.prototype.toString.call(value);
+    }
+    throw err;
+  }
+};
+
+assert.sameValue = function (actual, expected, message) {
+  try {
+    if (assert._isSameValue(actual, expected)) {
+      return;
+    }
+  } catch (error) {
+    throw new Test262Error(message + " (_isSameValue operation threw) " + error);
+  }
+  message = message === undefined ? "" : message + " ";
+  message += "Expected SameValue(«" + assert._toString(actual) + "», «" +
+    assert._toString(expected) + "») to be true";
+  throw new Test262Error(message);
+};
+
+assert.notSameValue = function (actual, unexpected, message) {
+  if (!assert._isSameValue(actual, unexpected)) {
+    return;
+  }
+  message = message === undefined ? "" : message + " ";
+  message += "Expected SameValue(«" + assert._toString(actual) + "», «" +
+    assert._toString(unexpected) + "») to be false";
+  throw new Test262Error(message);
+};
+
+assert.throws = function (expectedErrorConstructor, func, message) {
+  message = message === undefined ? "" : message + " ";
+  if (typeof func !== "function") {
+    throw new Test262Error("assert.throws requires two arguments: the error constructor " +
+      "and a function to run");
+  }
+  try {
+    func();
+  } catch (thrown) {
+    if (typeof thrown !== "object" || thrown === null) {
+      throw new Test262Error(message + "Thrown value was not an object!");
+    }
+    if (thrown.constructor !== expectedErrorConstructor) {
+      var expectedName = expectedErrorConstructor.name;
+      var actualName = thrown.constructor && thrown.constructor.name;
+      if (expectedName === actualName) {
+        throw new Test262Error(message + "Expected a " + expectedName +
+          " but got a different error constructor with the same name");
+      }
+      throw new Test262Error(message + "Expected a " + expectedName + " but got a " + actualName);
+    }
+    return;
+  }
+  throw new Test262Error(message + "Expected a " + expectedErrorConstructor.name +
+    " to be thrown but no exception was thrown at all");
+};
@@ -0,0 +1,14 @@
+// Minimal test262 harness, baked into examples/test262-runner.rs.
+// $DONE for async tests; the runner reads the printed protocol lines.
+
+function $DONE(error) {
+  if (error) {
+    if (typeof error === "object" && error !== null && "name" in error) {
+      console.log("Test262:AsyncTestFailure:" + error.name + ": " + error.message);
+    } else {
+      console.log("Test262:AsyncTestFailure:Test262Error: " + String(error));
+    }
+  } else {
+    console.log("Test262:AsyncTestComplete");
+  }
+}
@@ -0,0 +1,6 @@
+/*---
+description: An async failure listed in known-failures.txt
+flags: [async]
+---*/
+
+Promise.reject(new Test262Error("known to fail")).then($DONE, $DONE);
@@ -0,0 +1,6 @@
+/*---
+description: An async test that never calls $DONE, listed in known-failures.txt
+flags: [async]
+---*/
+
+Promise.resolve();
@@ -0,0 +1,16 @@
+/*---
+description: Async tests report completion through $DONE
+flags: [async]
+---*/
+
+var log = [];
+Promise.resolve(1)
+  .then(function (v) {
+    log.push(v);
+    return Promise.resolve(2);
+  })
+  .then(function (v) {
+    log.push(v);
+    assert.sameValue(log.join(","), "1,2");
+  })
+  .then($DONE, $DONE);
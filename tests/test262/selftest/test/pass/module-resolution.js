@@ -0,0 +1,11 @@
+/*---
+description: Missing module files fail in the resolution phase
+flags: [module]
+negative:
+  phase: resolution
+  type: SyntaxError
+---*/
+
+$DONOTEVALUATE();
+
+import "./does-not-exist_FIXTURE.js";
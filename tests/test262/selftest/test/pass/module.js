@@ -0,0 +1,9 @@
+/*---
+description: Module tests load fixtures from the test directory
+flags: [module]
+---*/
+
+import { value, double } from "./module_FIXTURE.js";
+
+assert.sameValue(value, 21);
+assert.sameValue(double(value), 42);
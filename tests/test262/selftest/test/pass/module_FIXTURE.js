@@ -0,0 +1,4 @@
+export const value = 21;
+export function double(n) {
+  return n * 2;
+}
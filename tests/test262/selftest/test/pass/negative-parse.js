@@ -0,0 +1,10 @@
+/*---
+description: Parse errors satisfy a negative parse expectation
+negative:
+  phase: parse
+  type: SyntaxError
+---*/
+
+$DONOTEVALUATE();
+
+var = ;
@@ -0,0 +1,8 @@
+/*---
+description: Thrown errors satisfy a negative runtime expectation
+negative:
+  phase: runtime
+  type: TypeError
+---*/
+
+throw new TypeError("expected");
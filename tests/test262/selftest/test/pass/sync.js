@@ -0,0 +1,9 @@
+/*---
+description: Synchronous assertions pass
+---*/
+
+assert.sameValue(1 + 1, 2);
+assert.notSameValue(0, -0);
+assert.throws(TypeError, function () {
+  null.x;
+});
@@ -0,0 +1,206 @@
+//! Tests for `tsrun::testing`: the mock clock, seeded randomness and
+//! `Interpreter::advance_time`.
+//!
+//! Run with `cargo test --features testing`.
+
+#![cfg(feature = "testing")]
+#![allow(clippy::unwrap_used, clippy::panic, clippy::indexing_slicing)]
+
+use tsrun::testing::{MockClock, SeededRandom};
+use tsrun::value::{JsString, PropertyKey};
+use tsrun::{Interpreter, JsValue, Order, OrderDelivery, OrderResponse, RuntimeValue, StepResult};
+
+const EPOCH: i64 = 1_700_000_000_000;
+
+/// `setTimeout` the way a host might provide it, as a timer order
+const SET_TIMEOUT: &str = r#"
+    import { order } from "tsrun:host";
+    function setTimeout(callback: () => void, ms: number) {
+        order({ type: "timeout", delayMs: ms }, { deadlineMs: Date.now() + ms }).then(callback);
+    }
+"#;
+
+fn interpreter_with_clock() -> (Interpreter, MockClock) {
+    let mut interp = Interpreter::new();
+    interp.set_order_delivery(OrderDelivery::Eager);
+    let clock = MockClock::new(EPOCH);
+    interp.set_mock_clock(clock.clone());
+    (interp, clock)
+}
+
+/// Prepare `source` and run it until it waits for the host
+fn start(interp: &mut Interpreter, source: &str) -> StepResult {
+    interp.prepare(source, Some("/main.ts".into())).unwrap();
+    loop {
+        match interp.step().unwrap() {
+            StepResult::Continue | StepResult::Orders(_) => {}
+            other => return other,
+        }
+    }
+}
+
+fn global_string(interp: &mut Interpreter, source: &str) -> String {
+    match start(interp, source) {
+        StepResult::Complete(value) => value.as_str().unwrap().to_string(),
+        other => panic!("expected a string, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_mock_clock_drives_date_now() {
+    let (mut interp, clock) = interpreter_with_clock();
+    let StepResult::Complete(value) = start(&mut interp, "Date.now()") else {
+        panic!("expected Date.now() to complete");
+    };
+    assert_eq!(value.as_number(), Some(EPOCH as f64));
+
+    clock.advance(1500);
+    let StepResult::Complete(value) = start(&mut interp, "Date.now()") else {
+        panic!("expected Date.now() to complete");
+    };
+    assert_eq!(value.as_number(), Some((EPOCH + 1500) as f64));
+
+    clock.set(0);
+    let StepResult::Complete(value) = start(&mut interp, "Date.now()") else {
+        panic!("expected Date.now() to complete");
+    };
+    assert_eq!(value.as_number(), Some(0.0));
+}
+
+/// The `entry` of each `log` order among `orders`
+fn log_entries(orders: &[Order]) -> Vec<String> {
+    let entry_key = PropertyKey::String(JsString::from("entry"));
+    orders
+        .iter()
+        .filter_map(|order| match order.payload.value() {
+            JsValue::Object(payload) => match payload.borrow().get_property(&entry_key) {
+                Some(JsValue::String(entry)) => Some(entry.to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_advance_time_fires_timers_in_deadline_order() {
+    let (mut interp, _clock) = interpreter_with_clock();
+    let source = format!(
+        r#"{SET_TIMEOUT}
+        const start = Date.now();
+        const log = (name: string) =>
+            order({{ type: "log", entry: name + "@" + (Date.now() - start) }});
+        setTimeout(() => log("b"), 200);
+        setTimeout(() => {{
+            log("a");
+            setTimeout(() => log("c"), 150);
+        }}, 100);
+        setTimeout(() => log("d"), 200);
+        "#
+    );
+    let StepResult::Suspended { .. } = start(&mut interp, &source) else {
+        panic!("expected the script to wait for its timers");
+    };
+
+    let advance = |interp: &mut Interpreter, ms| match interp.advance_time(ms).unwrap() {
+        StepResult::Suspended { pending, .. } => log_entries(&pending),
+        other => panic!("expected the script to stay suspended, got {:?}", other),
+    };
+    // Nothing is due yet
+    assert!(advance(&mut interp, 50).is_empty());
+    // a fires at 100 and schedules c for 250; b and d fire at 200, in the
+    // order they were created
+    assert_eq!(advance(&mut interp, 170), ["a@100", "b@200", "d@200"]);
+    assert_eq!(advance(&mut interp, 1000), ["c@250"]);
+    assert!(advance(&mut interp, 1000).is_empty());
+}
+
+#[test]
+fn test_advance_time_finishes_the_script() {
+    let (mut interp, _clock) = interpreter_with_clock();
+    let source = format!(
+        r#"{SET_TIMEOUT}
+        const start = Date.now();
+        await new Promise<void>(resolve => setTimeout(resolve, 30000));
+        Date.now() - start
+        "#
+    );
+    let StepResult::Suspended { .. } = start(&mut interp, &source) else {
+        panic!("expected the script to wait for its timer");
+    };
+    let StepResult::Complete(waited) = interp.advance_time(60000).unwrap() else {
+        panic!("expected the script to finish");
+    };
+    assert_eq!(waited.as_number(), Some(30000.0));
+}
+
+#[test]
+fn test_advance_time_returns_other_orders() {
+    let (mut interp, _clock) = interpreter_with_clock();
+    let source = format!(
+        r#"{SET_TIMEOUT}
+        setTimeout(() => order({{ type: "fetch" }}), 10);
+        "#
+    );
+    start(&mut interp, &source);
+
+    let StepResult::Suspended { pending, .. } = interp.advance_time(10).unwrap() else {
+        panic!("expected the script to wait for the fetch");
+    };
+    assert_eq!(pending.len(), 1);
+    let JsValue::Object(payload) = pending[0].payload.value() else {
+        panic!("expected an object payload");
+    };
+    let kind = payload
+        .borrow()
+        .get_property(&PropertyKey::String(JsString::from("type")));
+    assert_eq!(kind, Some(JsValue::String(JsString::from("fetch"))));
+
+    interp.fulfill_orders(vec![OrderResponse {
+        id: pending[0].id,
+        result: Ok(RuntimeValue::unguarded(JsValue::Undefined)),
+    }]);
+}
+
+#[test]
+fn test_request_timeout_fires_under_advance_time() {
+    let (mut interp, _clock) = interpreter_with_clock();
+    let source = r#"
+        import { request } from "tsrun:host";
+        try {
+            await request("fetch", { url: "/slow" }, { timeoutMs: 5000 });
+            "answered"
+        } catch (e) {
+            e.name
+        }
+    "#;
+    start(&mut interp, source);
+    let StepResult::Complete(value) = interp.advance_time(5000).unwrap() else {
+        panic!("expected the request to time out");
+    };
+    assert_eq!(value.as_str(), Some("TimeoutError"));
+}
+
+#[test]
+fn test_advance_time_needs_a_mock_clock() {
+    let mut interp = Interpreter::new();
+    assert!(interp.advance_time(10).is_err());
+}
+
+#[test]
+fn test_seeded_random_is_reproducible() {
+    let source = "Array.from({ length: 5 }, () => Math.random()).join()";
+    let run = |seed| {
+        let mut interp = Interpreter::new();
+        interp.set_random_provider(Box::new(SeededRandom::new(seed)));
+        global_string(&mut interp, source)
+    };
+
+    let first = run(42);
+    assert_eq!(first, run(42));
+    assert_ne!(first, run(7));
+    for n in first.split(',') {
+        let n: f64 = n.parse().unwrap();
+        assert!((0.0..1.0).contains(&n));
+    }
+}